@@ -0,0 +1,173 @@
+/// Criterion benchmarks for the compiler, run against synthetic worlds
+/// from `urd_compiler::test_support`.
+///
+/// Two end-to-end compiles (a medium single-file world and a large
+/// hundred-file world) and one benchmark each for PARSE, LINK, VALIDATE
+/// and EMIT in isolation, plus EMIT streamed through `emit_to_writer()`.
+/// The per-phase benchmarks do their setup outside the timed section.
+/// VALIDATE runs on a world of many small sections, where the per-file
+/// section scans dominate: as `validate()` chooses, with the worker steps
+/// on scoped threads, and with every step on one thread. On a single
+/// core the threaded run gains nothing and pays for the spawns, which is
+/// why `validate()` only starts threads when there is more than one.
+///
+/// Criterion measures time, not memory. For peak memory, compare the
+/// maximum resident set size of `urd world.urd.md > out.json` (the world
+/// built as one string) against a manifest `out` or `urd watch -o`
+/// (streamed), e.g. with `/usr/bin/time -v`. On an 80,000-choice world
+/// (`synthetic_world(4_000, 5, 4, 200)`, 56 MB of JSON) streaming took
+/// the peak from about 940 MB to 790 MB; what remains is mostly the ASTs,
+/// FactSet and symbol table, and the dialogue block, which is still built
+/// whole.
+///
+/// Usage: cargo bench [-- <filter>]
+
+use std::collections::HashMap;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+
+use urd_compiler::diagnostics::DiagnosticCollector;
+use urd_compiler::graph::CompilationUnit;
+use urd_compiler::test_support::{synthetic_world, MemoryFileReader, ENTRY_FILE};
+use urd_compiler::{compile_source_with_reader, emit, import, link, parse, validate};
+
+/// The most a single source file may hold (`MAX_FILE_SIZE`).
+const ONE_MEGABYTE: usize = 1_048_576;
+
+fn medium_world() -> HashMap<String, String> {
+    synthetic_world(60, 3, 4, 1)
+}
+
+fn large_world() -> HashMap<String, String> {
+    synthetic_world(1_000, 3, 4, 100)
+}
+
+/// A single-file world as close to one megabyte as whole locations allow.
+fn one_megabyte_source() -> String {
+    let sample = synthetic_world(100, 3, 4, 1).remove(ENTRY_FILE).unwrap();
+    let mut locations = 100 * ONE_MEGABYTE / sample.len();
+    loop {
+        let source = synthetic_world(locations, 3, 4, 1).remove(ENTRY_FILE).unwrap();
+        if source.len() <= ONE_MEGABYTE {
+            return source;
+        }
+        locations -= locations / 100 + 1;
+    }
+}
+
+/// PARSE and IMPORT a world, ready for LINK.
+fn compilation_unit(reader: &MemoryFileReader) -> CompilationUnit {
+    let mut diagnostics = DiagnosticCollector::new();
+    let source = reader.get(ENTRY_FILE).unwrap();
+    let ast = parse::parse(&ENTRY_FILE.to_string(), source, &mut diagnostics).unwrap();
+    import::resolve_imports_with_reader(ast, "", &mut diagnostics, reader)
+}
+
+fn bench_compile(c: &mut Criterion) {
+    let worlds = [
+        ("compile/medium_1_file", medium_world(), 20),
+        ("compile/large_100_files", large_world(), 10),
+    ];
+    for (name, files, samples) in worlds {
+        let bytes: usize = files.values().map(String::len).sum();
+        let reader = MemoryFileReader::new(files);
+        let entry = reader.get(ENTRY_FILE).unwrap().to_string();
+        assert!(compile_source_with_reader(ENTRY_FILE, &entry, &reader).success);
+
+        let mut group = c.benchmark_group(name);
+        group.throughput(Throughput::Bytes(bytes as u64));
+        group.sample_size(samples);
+        group.bench_function("full", |b| {
+            b.iter(|| compile_source_with_reader(ENTRY_FILE, &entry, &reader))
+        });
+        group.finish();
+    }
+}
+
+fn bench_parse(c: &mut Criterion) {
+    let source = one_megabyte_source();
+    let path = ENTRY_FILE.to_string();
+    let mut group = c.benchmark_group("parse");
+    group.throughput(Throughput::Bytes(source.len() as u64));
+    group.sample_size(20);
+    group.bench_function("one_megabyte", |b| {
+        b.iter(|| {
+            let mut diagnostics = DiagnosticCollector::new();
+            parse::parse(&path, &source, &mut diagnostics)
+        })
+    });
+    group.finish();
+}
+
+fn bench_link(c: &mut Criterion) {
+    let reader = MemoryFileReader::new(large_world());
+    let mut group = c.benchmark_group("link");
+    group.sample_size(20);
+    group.bench_function("large_100_files", |b| {
+        b.iter_batched(
+            || compilation_unit(&reader),
+            |unit| {
+                let mut diagnostics = DiagnosticCollector::new();
+                link::link(unit, &mut diagnostics)
+            },
+            BatchSize::LargeInput,
+        )
+    });
+    group.finish();
+}
+
+fn bench_validate(c: &mut Criterion) {
+    let reader = MemoryFileReader::new(synthetic_world(500, 6, 1, 5));
+    let mut diagnostics = DiagnosticCollector::new();
+    let linked = link::link(compilation_unit(&reader), &mut diagnostics);
+    assert_eq!(linked.symbol_table.sections.len(), 3_000);
+
+    let mut group = c.benchmark_group("validate");
+    group.sample_size(20);
+    group.bench_function("sections_3000", |b| {
+        b.iter(|| {
+            let mut diagnostics = DiagnosticCollector::new();
+            validate::validate(&linked.graph, &linked.symbol_table, &mut diagnostics)
+        })
+    });
+    group.bench_function("sections_3000_parallel", |b| {
+        b.iter(|| {
+            let mut diagnostics = DiagnosticCollector::new();
+            validate::validate_parallel(&linked.graph, &linked.symbol_table, &mut diagnostics)
+        })
+    });
+    group.bench_function("sections_3000_sequential", |b| {
+        b.iter(|| {
+            let mut diagnostics = DiagnosticCollector::new();
+            validate::validate_sequential(&linked.graph, &linked.symbol_table, &mut diagnostics)
+        })
+    });
+    group.finish();
+}
+
+fn bench_emit(c: &mut Criterion) {
+    let reader = MemoryFileReader::new(large_world());
+    let mut diagnostics = DiagnosticCollector::new();
+    let linked = link::link(compilation_unit(&reader), &mut diagnostics);
+    validate::validate(&linked.graph, &linked.symbol_table, &mut diagnostics);
+    assert!(!diagnostics.has_errors());
+
+    let mut group = c.benchmark_group("emit");
+    group.sample_size(20);
+    group.bench_function("large_100_files", |b| {
+        b.iter(|| {
+            let mut diagnostics = DiagnosticCollector::new();
+            emit::emit(&linked.graph, &linked.symbol_table, &mut diagnostics)
+        })
+    });
+    group.bench_function("large_100_files_streamed", |b| {
+        b.iter(|| {
+            let options = emit::EmitOptions::default();
+            emit::emit_to_writer(&linked.graph, &linked.symbol_table, &options, &mut std::io::sink())
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_compile, bench_parse, bench_link, bench_validate, bench_emit);
+criterion_main!(benches);
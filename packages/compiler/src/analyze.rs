@@ -9,9 +9,12 @@
 /// |--------------------|---------------|
 /// | ANALYZE (FactSet)  | URD600–URD699 |
 
+use std::collections::{HashSet, VecDeque};
+
 use crate::diagnostics::{Diagnostic, RelatedInfo, Severity};
 use crate::facts::{
-    CompareOp, FactSet, LiteralKind, PropertyDependencyIndex, WriteOp,
+    CompareOp, EndFact, FactSet, JumpTarget, LiteralKind, Placement, PropertyDependencyIndex, PropertyKey,
+    WriteOp,
 };
 
 /// Run all FactSet-derived diagnostics.
@@ -26,6 +29,15 @@ pub fn analyze(fact_set: &FactSet, index: &PropertyDependencyIndex) -> Vec<Diagn
     diagnostics.extend(check_enum_variant_untested(fact_set, &index));
     diagnostics.extend(check_unreachable_threshold(fact_set, &index));
     diagnostics.extend(check_circular_dependency(fact_set, &index));
+    diagnostics.extend(check_uninvoked_templates(fact_set));
+    diagnostics.extend(check_phases_after_end(fact_set));
+    diagnostics.extend(check_stalled_advance(fact_set, index));
+    diagnostics.extend(check_unplaced_entities(fact_set));
+    diagnostics.extend(check_ambient_exit_effects(fact_set));
+    diagnostics.extend(check_self_toggling_exits(fact_set));
+    diagnostics.extend(check_empty_opening(fact_set));
+    diagnostics.extend(check_unreached_endings(fact_set));
+    diagnostics.extend(check_stranded_endings(fact_set));
 
     diagnostics
 }
@@ -70,6 +82,7 @@ fn check_read_never_written(
             ),
             span: first.span.clone(),
             suggestion: None,
+            fix: None,
             related,
         });
     }
@@ -117,6 +130,7 @@ fn check_written_never_read(
             ),
             span: first.span.clone(),
             suggestion: None,
+            fix: None,
             related,
         });
     }
@@ -184,6 +198,7 @@ fn check_enum_variant_untested(
                 ),
                 span: write.span.clone(),
                 suggestion: None,
+                fix: None,
                 related: vec![],
             });
         }
@@ -271,6 +286,7 @@ fn check_unreachable_threshold(
                 ),
                 span: read.span.clone(),
                 suggestion: None,
+                fix: None,
                 related: vec![],
             });
         }
@@ -339,6 +355,7 @@ fn check_circular_dependency(
                 ),
                 span: first.span.clone(),
                 suggestion: None,
+                fix: None,
                 related,
             });
         }
@@ -347,6 +364,530 @@ fn check_circular_dependency(
     diagnostics
 }
 
+/// D6: Parameterised section never invoked — URD606
+///
+/// No jump passes an argument to the section, so EMIT produces no instance
+/// of it and its content is absent from the compiled world.
+fn check_uninvoked_templates(fact_set: &FactSet) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for template in fact_set.section_templates() {
+        let invoked = fact_set.jumps().iter().any(|j| {
+            j.argument.is_some() && matches!(&j.target, JumpTarget::Section(id) if *id == template.section)
+        });
+        if !invoked {
+            diagnostics.push(Diagnostic {
+                severity: Severity::Info,
+                code: "URD606".to_string(),
+                message: format!(
+                    "Parameterised section '{}' is never invoked. \
+                     No jump passes it an '@{}: {}' argument, so it emits nothing.",
+                    template.section, template.parameter, template.parameter_type
+                ),
+                span: template.span.clone(),
+                suggestion: None,
+                fix: None,
+                related: Vec::new(),
+            });
+        }
+    }
+
+    diagnostics
+}
+
+/// D7: Phases after an `end` phase — URD607
+///
+/// A phase with `advance: end` finishes its sequence. Later phases of the
+/// same sequence can never run.
+fn check_phases_after_end(fact_set: &FactSet) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let phases = fact_set.phases();
+
+    for (i, phase) in phases.iter().enumerate() {
+        if phase.advance != "end" {
+            continue;
+        }
+        let later: Vec<&str> = phases[i + 1..]
+            .iter()
+            .take_while(|p| p.sequence == phase.sequence)
+            .map(|p| p.phase.as_str())
+            .collect();
+        if later.is_empty() {
+            continue;
+        }
+        let related: Vec<RelatedInfo> = phases[i + 1..i + 1 + later.len()]
+            .iter()
+            .map(|p| RelatedInfo {
+                message: format!("Phase '{}' can never run", p.phase),
+                span: p.span.clone(),
+            })
+            .collect();
+        diagnostics.push(Diagnostic {
+            severity: Severity::Warning,
+            code: "URD607".to_string(),
+            message: format!(
+                "Phase '{}' ends sequence '{}', but {} after it. \
+                 Unreachable phases: {}.",
+                phase.phase,
+                phase.sequence,
+                if later.len() == 1 { "a phase follows" } else { "phases follow" },
+                later.join(", ")
+            ),
+            span: phase.span.clone(),
+            suggestion: None,
+            fix: None,
+            related,
+        });
+    }
+
+    diagnostics
+}
+
+/// D8: Advance condition on a property nothing writes — URD608
+///
+/// An `on_condition` phase waits for its expression to become true. If no
+/// effect anywhere writes a property the expression reads, the property
+/// keeps its initial value and the sequence stalls. Writers are matched
+/// world-wide, not per phase, so this is an over-approximation.
+fn check_stalled_advance(
+    fact_set: &FactSet,
+    index: &PropertyDependencyIndex,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for phase in fact_set.phases() {
+        for key in &phase.advance_reads {
+            if !index.writes_of(key).is_empty() {
+                continue;
+            }
+            diagnostics.push(Diagnostic {
+                severity: Severity::Warning,
+                code: "URD608".to_string(),
+                message: format!(
+                    "Phase '{}' in sequence '{}' advances on a condition that reads '{}.{}', \
+                     but no effect writes that property. The sequence will stall in this phase.",
+                    phase.phase, phase.sequence, key.entity_type, key.property
+                ),
+                span: phase.span.clone(),
+                suggestion: None,
+                fix: None,
+                related: Vec::new(),
+            });
+        }
+    }
+
+    diagnostics
+}
+
+/// Traits whose entities need no starting place. The world block's
+/// `unplaced_traits:` list adds to these.
+pub const UNPLACED_TRAITS: &[&str] = &["abstract", "conceptual"];
+
+/// D9: Entity placed nowhere — URD609
+///
+/// An entity in no location's presence line and not in the start
+/// inventory exists nowhere when the game begins. Silent for the player,
+/// for entities some `> move` effect brings into the world, and for types
+/// with a trait in `UNPLACED_TRAITS` or the world's `unplaced_traits:`.
+/// Silent throughout a dialogue world, which has nowhere to place them.
+fn check_unplaced_entities(fact_set: &FactSet) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    if fact_set.dialogue_mode() {
+        return diagnostics;
+    }
+
+    for entity in fact_set.entities() {
+        if !entity.placements.is_empty() || entity.entity == "player" {
+            continue;
+        }
+        if fact_set.moves().iter().any(|m| m.entity == entity.entity) {
+            continue;
+        }
+        let exempt = entity.traits.iter().any(|t| {
+            UNPLACED_TRAITS.contains(&t.as_str()) || fact_set.unplaced_traits().contains(t)
+        });
+        if exempt {
+            continue;
+        }
+        diagnostics.push(Diagnostic {
+            severity: Severity::Info,
+            code: "URD609".to_string(),
+            message: format!(
+                "Entity '@{}' is not placed in any location or the start inventory, \
+                 and no effect moves it, so it exists nowhere when the game begins.",
+                entity.entity
+            ),
+            span: entity.span.clone(),
+            suggestion: Some(format!(
+                "Place '@{}' in a location's entity list or world.start_inventory. \
+                 If the runtime spawns it later, give its type a trait named in the \
+                 world block's `unplaced_traits:` list.",
+                entity.entity
+            )),
+            fix: None,
+            related: Vec::new(),
+        });
+    }
+
+    diagnostics
+}
+
+/// D10: Exit effects with no gate — URD610
+///
+/// An exit with effects but no condition and no blocked message applies
+/// them every time anyone walks through it. That is rarely what the
+/// author meant: usually a condition or message was forgotten.
+fn check_ambient_exit_effects(fact_set: &FactSet) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for exit in fact_set.exits() {
+        if !exit.has_effects || exit.is_conditional || exit.has_blocked_message {
+            continue;
+        }
+        diagnostics.push(Diagnostic {
+            severity: Severity::Info,
+            code: "URD610".to_string(),
+            message: format!(
+                "Exit '{}' from '{}' has effects but no condition or blocked message, \
+                 so they run on every traversal.",
+                exit.exit_name, exit.from_location
+            ),
+            span: exit.span.clone(),
+            suggestion: Some(
+                "If the effects should happen once, guard the exit with a condition \
+                 the effects change. If they are meant to run every time, a location \
+                 hook (`on exit:`) says so more plainly."
+                    .to_string(),
+            ),
+            fix: None,
+            related: Vec::new(),
+        });
+    }
+
+    diagnostics
+}
+
+/// D11: Exit effect flips its own condition — URD611
+///
+/// The exit sets the same entity's property that its condition reads. If
+/// the new value fails the condition, the exit blocks itself after one
+/// traversal. If it is exactly the value the condition requires, the
+/// effect does nothing on traversal and can only matter on a blocked
+/// attempt, where a runtime that fires effects early would unblock the
+/// exit. Only `=` writes of a literal are judged.
+fn check_self_toggling_exits(fact_set: &FactSet) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for exit in fact_set.exits() {
+        for &write_idx in &exit.effect_writes {
+            let w = &fact_set.writes()[write_idx];
+            if w.operator != WriteOp::Set || w.entity.is_none() {
+                continue;
+            }
+            let guard = exit.guard_reads.iter().map(|&i| &fact_set.reads()[i]).find(|r| {
+                r.entity == w.entity && r.key() == w.key()
+            });
+            let Some(r) = guard else {
+                continue;
+            };
+            let target = format!("@{}.{}", w.entity.as_deref().unwrap_or(""), w.property);
+            let outcome = match literal_satisfies(&w.value_expr, &r.operator, &r.value_literal) {
+                Some(false) => "blocks the exit after its first traversal",
+                Some(true) if r.operator == CompareOp::Eq => {
+                    "changes nothing on traversal, and would only unblock the exit \
+                     if a runtime applied it on a blocked attempt"
+                }
+                _ => continue,
+            };
+            diagnostics.push(Diagnostic {
+                severity: Severity::Warning,
+                code: "URD611".to_string(),
+                message: format!(
+                    "Exit '{}' from '{}' sets '{}' to {}, which its own condition reads. \
+                     The effect {}.",
+                    exit.exit_name, exit.from_location, target, w.value_expr, outcome
+                ),
+                span: w.span.clone(),
+                suggestion: None,
+                fix: None,
+                related: vec![RelatedInfo {
+                    message: format!("Condition reading '{}'", target),
+                    span: r.span.clone(),
+                }],
+            });
+        }
+    }
+
+    diagnostics
+}
+
+/// D12: Start location with nothing to do — URD612
+///
+/// The player begins at `world.start`. With no exits leading out, no
+/// entities present, and no sections after its heading, there is nothing
+/// to do there. A warning when the location has no description either;
+/// info when the description is all there is. Silent when `world.start`
+/// is missing or unresolved, which VALIDATE reports.
+fn check_empty_opening(fact_set: &FactSet) -> Vec<Diagnostic> {
+    let Some(start) = fact_set.world_start() else {
+        return Vec::new();
+    };
+    let Some(location) = fact_set.locations().iter().find(|l| l.location == start) else {
+        return Vec::new();
+    };
+    let has_exits = fact_set.exits().iter().any(|e| e.from_location == start);
+    let has_entities = fact_set
+        .entities()
+        .iter()
+        .any(|e| e.placements.contains(&Placement::Location(start.to_string())));
+    if has_exits || has_entities || !location.sections.is_empty() {
+        return Vec::new();
+    }
+
+    let (severity, message) = if location.has_description {
+        (
+            Severity::Info,
+            format!(
+                "The start location '{}' has a description but no exits, entities, or \
+                 sections, so the player can read it and do nothing else.",
+                start
+            ),
+        )
+    } else {
+        (
+            Severity::Warning,
+            format!(
+                "The start location '{}' has no description, exits, entities, or sections. \
+                 The player will begin there with nothing to see or do.",
+                start
+            ),
+        )
+    };
+    vec![Diagnostic {
+        severity,
+        code: "URD612".to_string(),
+        message,
+        span: location.span.clone(),
+        suggestion: Some(
+            "Give the location an exit (`-> north: Hall`), an entity list (`[@lamp]`), \
+             or a section (`== look_around`) with choices."
+                .to_string(),
+        ),
+        fix: None,
+        related: Vec::new(),
+    }]
+}
+
+/// D13: Ending never reached — URD613
+///
+/// A declared ending that no `> end` effect names. The world can never
+/// finish there.
+fn check_unreached_endings(fact_set: &FactSet) -> Vec<Diagnostic> {
+    fact_set
+        .endings()
+        .iter()
+        .filter(|ending| !fact_set.ends().iter().any(|e| e.ending == ending.ending))
+        .map(|ending| Diagnostic {
+            severity: Severity::Warning,
+            code: "URD613".to_string(),
+            message: format!(
+                "Ending '{}' is declared but no `> end {}` effect reaches it.",
+                ending.ending, ending.ending
+            ),
+            span: ending.span.clone(),
+            suggestion: Some(format!(
+                "Add `> end {}` to a choice, exit, rule, or hook, or remove the ending.",
+                ending.ending
+            )),
+            fix: None,
+            related: Vec::new(),
+        })
+        .collect()
+}
+
+/// D14: Ending reached only from unreachable locations — URD614
+///
+/// Every `> end` effect naming the ending sits in a location that no path
+/// of exits from the start location reaches, the same test as URD430. An
+/// effect in a rule, or outside any location, counts as reachable. Silent
+/// without a resolved `world.start`.
+fn check_stranded_endings(fact_set: &FactSet) -> Vec<Diagnostic> {
+    let Some(start) = fact_set.world_start() else {
+        return Vec::new();
+    };
+    if !fact_set.locations().iter().any(|l| l.location == start) {
+        return Vec::new();
+    }
+
+    let mut reachable: HashSet<&str> = HashSet::from([start]);
+    let mut queue: VecDeque<&str> = VecDeque::from([start]);
+    while let Some(current) = queue.pop_front() {
+        for exit in fact_set.exits().iter().filter(|e| e.from_location == current) {
+            if reachable.insert(exit.to_location.as_str()) {
+                queue.push_back(exit.to_location.as_str());
+            }
+        }
+    }
+
+    let mut diagnostics = Vec::new();
+    for ending in fact_set.endings() {
+        let ends: Vec<_> = fact_set.ends().iter().filter(|e| e.ending == ending.ending).collect();
+        let stranded = |e: &&EndFact| e.location.as_deref().is_some_and(|l| !reachable.contains(l));
+        if ends.is_empty() || !ends.iter().all(stranded) {
+            continue;
+        }
+        let mut locations: Vec<&str> = Vec::new();
+        for location in ends.iter().filter_map(|e| e.location.as_deref()) {
+            if !locations.contains(&location) {
+                locations.push(location);
+            }
+        }
+        diagnostics.push(Diagnostic {
+            severity: Severity::Info,
+            code: "URD614".to_string(),
+            message: format!(
+                "Ending '{}' is only reached from unreachable locations ({}), so no playthrough can end there.",
+                ending.ending,
+                locations.iter().map(|l| format!("'{}'", l)).collect::<Vec<_>>().join(", ")
+            ),
+            span: ending.span.clone(),
+            suggestion: None,
+            fix: None,
+            related: ends
+                .iter()
+                .map(|e| RelatedInfo {
+                    message: format!("Reached here: {}:{}", e.span.file, e.span.start_line),
+                    span: e.span.clone(),
+                })
+                .collect(),
+        });
+    }
+    diagnostics
+}
+
+/// Static answer to "can this property ever hold this value?".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueReachability {
+    /// At least one `=` effect assigns exactly the target value.
+    DirectlySettable,
+    /// No effect assigns the target, but it is numeric, within the declared
+    /// range, and `+`/`-` effects exist that could accumulate to it.
+    ViaArithmetic,
+    /// No writer can produce the target value.
+    Unreachable,
+}
+
+/// Classification of a target value plus the write sites supporting it.
+///
+/// Indices refer to `FactSet::writes()`. Together the three lists cover
+/// every write to the property.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValueReachabilityReport {
+    pub verdict: ValueReachability,
+    /// `=` writes whose literal equals the target.
+    pub exact_writes: Vec<usize>,
+    /// `+`/`-` writes. Only relevant to numeric targets.
+    pub arithmetic_writes: Vec<usize>,
+    /// Remaining writes, none of which produce the target directly.
+    pub other_writes: Vec<usize>,
+    /// True when the target is numeric and lies outside `[min, max]`.
+    pub out_of_range: bool,
+}
+
+/// Classify whether any effect in the world can make `key` hold `target`.
+///
+/// This is an over-approximation: guards on the write sites are not
+/// evaluated, and writes are matched per type rather than per entity.
+/// `Unreachable` is therefore reliable; the other verdicts mean "possibly".
+/// `min` and `max` are the property's declared bounds, if any — the
+/// FactSet does not carry declarations, so the caller supplies them.
+pub fn classify_value_reachability(
+    fact_set: &FactSet,
+    index: &PropertyDependencyIndex,
+    key: &PropertyKey,
+    target: &str,
+    min: Option<f64>,
+    max: Option<f64>,
+) -> ValueReachabilityReport {
+    let target = unquote(target);
+    let numeric_target: Option<f64> = target.parse().ok();
+    let out_of_range = numeric_target
+        .map(|v| min.is_some_and(|m| v < m) || max.is_some_and(|m| v > m))
+        .unwrap_or(false);
+
+    let mut exact_writes = Vec::new();
+    let mut arithmetic_writes = Vec::new();
+    let mut other_writes = Vec::new();
+
+    for &i in index.writes_of(key) {
+        let w = &fact_set.writes()[i];
+        match w.operator {
+            WriteOp::Set if literal_equals(&w.value_expr, target, numeric_target) => {
+                exact_writes.push(i)
+            }
+            WriteOp::Add | WriteOp::Sub => arithmetic_writes.push(i),
+            WriteOp::Set => other_writes.push(i),
+        }
+    }
+
+    let verdict = if out_of_range {
+        ValueReachability::Unreachable
+    } else if !exact_writes.is_empty() {
+        ValueReachability::DirectlySettable
+    } else if numeric_target.is_some() && !arithmetic_writes.is_empty() {
+        ValueReachability::ViaArithmetic
+    } else {
+        ValueReachability::Unreachable
+    };
+
+    ValueReachabilityReport {
+        verdict,
+        exact_writes,
+        arithmetic_writes,
+        other_writes,
+        out_of_range,
+    }
+}
+
+/// Compare a written literal with the target. Numbers compare by value so
+/// that `5` and `5.0` agree; everything else compares as unquoted text.
+fn literal_equals(value_expr: &str, target: &str, numeric_target: Option<f64>) -> bool {
+    let value = unquote(value_expr);
+    match (value.parse::<f64>(), numeric_target) {
+        (Ok(v), Some(t)) => v == t,
+        _ => value == target,
+    }
+}
+
+fn unquote(s: &str) -> &str {
+    let s = s.trim();
+    s.strip_prefix('"')
+        .and_then(|rest| rest.strip_suffix('"'))
+        .unwrap_or(s)
+}
+
+/// Whether the literal `value_expr` satisfies `value <op> literal`.
+/// Numbers compare by value; other literals support only `==` and `!=`.
+/// `None` when the answer is unknown.
+pub(crate) fn literal_satisfies(value_expr: &str, op: &CompareOp, literal: &str) -> Option<bool> {
+    let (value, literal) = (unquote(value_expr), unquote(literal));
+    if let (Ok(v), Ok(l)) = (value.parse::<f64>(), literal.parse::<f64>()) {
+        return Some(match op {
+            CompareOp::Lt => v < l,
+            CompareOp::Gt => v > l,
+            CompareOp::Le => v <= l,
+            CompareOp::Ge => v >= l,
+            CompareOp::Eq => v == l,
+            CompareOp::Ne => v != l,
+        });
+    }
+    match op {
+        CompareOp::Eq => Some(value == literal),
+        CompareOp::Ne => Some(value != literal),
+        _ => None,
+    }
+}
+
 /// Returns true if `value` satisfies the comparison `value <op> threshold`.
 fn satisfies_comparison(value: i64, op: &CompareOp, threshold: i64) -> bool {
     match op {
@@ -0,0 +1,171 @@
+/// Anonymised copies of a project, for sharing reproduction cases.
+///
+/// `urd anonymize` compiles a project and rewrites each file in its
+/// dependency graph with [`anonymize_source()`]: the text of every prose
+/// line, speech line, stage direction, and blocked message becomes
+/// [`placeholder()`] words of about the same length. Everything the
+/// compiler derives structure from is kept as written: frontmatter,
+/// headings, section labels, choice labels (choice IDs are slugified from
+/// them), conditions, effects, jumps, exits, entity references, and
+/// comments. The copy compiles to a world with the same IDs and the same
+/// diagnostics, differing only in text.
+///
+/// Placeholders are `lorem-<hash>` words hashed from the text they
+/// replace, so the output is the same on every run and equal texts stay
+/// equal. Like `fix`, this module does no file I/O: the CLI reads the
+/// originals and writes the copies.
+
+use crate::ast::{ContentNode, FileAst};
+use crate::fix::{apply_edits, TextEdit};
+use crate::hash::sha256_hex;
+
+/// Rewrite the player-visible text of `source`, the file `ast` was
+/// parsed from.
+pub fn anonymize_source(ast: &FileAst, source: &str) -> String {
+    // PARSE strips a byte order mark; line numbers count from after it.
+    let (bom, body) = match source.strip_prefix('\u{feff}') {
+        Some(rest) => ("\u{feff}", rest),
+        None => ("", source),
+    };
+    let lines = Lines::new(body);
+    let mut edits = Vec::new();
+    for node in &ast.content {
+        visit(node, &lines, &mut edits);
+    }
+    format!("{}{}", bom, apply_edits(body, &edits))
+}
+
+/// `lorem-<hash>` words, separated by spaces, about as long as `text`.
+/// Always at least one word.
+pub fn placeholder(text: &str) -> String {
+    let target = text.chars().count();
+    let mut hash = sha256_hex(text.as_bytes());
+    let mut offset = 0;
+    let mut out = String::new();
+    loop {
+        if offset + 4 > hash.len() {
+            hash = sha256_hex(hash.as_bytes());
+            offset = 0;
+        }
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str("lorem-");
+        out.push_str(&hash[offset..offset + 4]);
+        offset += 4;
+        if out.len() >= target {
+            return out;
+        }
+    }
+}
+
+/// Where a node's text starts on its first line, after the indent.
+#[derive(Clone, Copy)]
+enum Prefix {
+    /// Prose: the whole line.
+    None,
+    /// `@name: text`
+    Speech,
+    /// `"text"`, a quoted line: the quotes stay, on whichever lines
+    /// they fall.
+    Quoted,
+    /// `@name text`
+    Direction,
+    /// `! text`
+    Blocked,
+}
+
+fn visit(node: &ContentNode, lines: &Lines, edits: &mut Vec<TextEdit>) {
+    let (span, prefix) = match node {
+        ContentNode::Prose(p) => (&p.span, Prefix::None),
+        ContentNode::EntitySpeech(s) if s.inherits_speaker() => (&s.span, Prefix::Quoted),
+        ContentNode::EntitySpeech(s) => (&s.span, Prefix::Speech),
+        ContentNode::StageDirection(s) => (&s.span, Prefix::Direction),
+        ContentNode::BlockedMessage(b) => (&b.span, Prefix::Blocked),
+        ContentNode::Choice(choice) => {
+            for child in &choice.content {
+                visit(child, lines, edits);
+            }
+            return;
+        }
+        ContentNode::ExitDeclaration(exit) => {
+            for child in &exit.children {
+                visit(child, lines, edits);
+            }
+            return;
+        }
+        ContentNode::LocationHook(hook) => {
+            for child in &hook.children {
+                visit(child, lines, edits);
+            }
+            return;
+        }
+        _ => return,
+    };
+
+    // Continuation lines hold plain text after their indent.
+    for line in span.start_line..=span.end_line {
+        let prefix = match prefix {
+            Prefix::Quoted => Prefix::Quoted,
+            _ if line == span.start_line => prefix,
+            _ => Prefix::None,
+        };
+        if let Some(edit) = lines.text_edit(line, prefix) {
+            edits.push(edit);
+        }
+    }
+}
+
+/// Byte ranges of the source's lines, without their line endings.
+struct Lines<'a> {
+    source: &'a str,
+    ranges: Vec<(usize, usize)>,
+}
+
+impl<'a> Lines<'a> {
+    fn new(source: &'a str) -> Self {
+        let mut ranges = Vec::new();
+        let mut start = 0;
+        for line in source.split_inclusive('\n') {
+            let text = line.trim_end_matches('\n').trim_end_matches('\r');
+            ranges.push((start, start + text.len()));
+            start += line.len();
+        }
+        Lines { source, ranges }
+    }
+
+    /// An edit replacing the text on 1-indexed `line`. `None` when the
+    /// line holds no text, such as a lone `\` paragraph break.
+    fn text_edit(&self, line: u32, prefix: Prefix) -> Option<TextEdit> {
+        let &(line_start, line_end) = self.ranges.get((line as usize).checked_sub(1)?)?;
+        let full = &self.source[line_start..line_end];
+        let indent = full.len() - full.trim_start_matches(' ').len();
+        let body = &full[indent..];
+
+        let skip = match prefix {
+            Prefix::None => 0,
+            Prefix::Speech => body.find(':')? + 1,
+            Prefix::Direction => body.find(' ').map_or(body.len(), |pos| pos + 1),
+            Prefix::Quoted => usize::from(body.starts_with('"')),
+            Prefix::Blocked => 1,
+        };
+        // The same inline comment rule as PARSE.
+        let after = &body[skip..];
+        let before_comment = after.find(" //").map_or(after, |pos| &after[..pos]);
+        let mut text = before_comment.trim();
+        if let Prefix::Quoted = prefix {
+            text = text.strip_suffix('"').unwrap_or(text).trim_end();
+        }
+        if text.is_empty() || text == "\\" {
+            return None;
+        }
+
+        let start = line_start + indent + skip + (before_comment.len() - before_comment.trim_start().len());
+        Some(TextEdit {
+            start,
+            end: start + text.len(),
+            replacement: placeholder(text),
+            line,
+        })
+    }
+}
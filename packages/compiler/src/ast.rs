@@ -8,6 +8,10 @@
 /// - Annotatable: nodes carry optional annotation slots that LINK fills in.
 /// - Span-tracked: every node records its exact source position.
 
+use std::collections::BTreeSet;
+
+use crate::diagnostics::Diagnostic;
+use crate::slugify::slugify;
 use crate::span::Span;
 
 // ── File-level nodes ──
@@ -19,6 +23,10 @@ pub struct FileAst {
     pub frontmatter: Option<Frontmatter>,
     pub content: Vec<ContentNode>,
     pub span: Span,
+    /// SHA-256 of the source text the file was parsed from. `None` for
+    /// ASTs not built by PARSE, such as stubs for missing imports. Used by
+    /// [`crate::link::LinkCache`] to recognise unchanged files.
+    pub source_hash: Option<String>,
 }
 
 /// The `---`-delimited frontmatter block.
@@ -45,8 +53,11 @@ pub enum FrontmatterValue {
     InlineObject(Vec<FrontmatterEntry>),
     EntityDecl(EntityDecl),
     TypeDef(TypeDef),
+    EndingDecl(EndingDecl),
     ImportDecl(ImportDecl),
     WorldBlock(WorldBlock),
+    /// Top-level entries inside a `?if` guard. The entry's key is `?if`.
+    Conditional(ConditionalBlock<FrontmatterEntry>),
 }
 
 /// A frontmatter value (primitives, lists, entity references).
@@ -82,6 +93,8 @@ pub struct TypeDef {
     pub name: String,
     pub traits: Vec<String>,
     pub properties: Vec<PropertyDef>,
+    /// Text of the `///` doc comment lines directly above the declaration.
+    pub doc: Option<String>,
     pub span: Span,
 }
 
@@ -110,6 +123,16 @@ pub struct EntityDecl {
     pub type_name: String,
     pub property_overrides: Vec<(String, Scalar)>,
     pub annotation: Option<Annotation>,
+    /// Text of the `///` doc comment lines directly above the declaration.
+    pub doc: Option<String>,
+    pub span: Span,
+}
+
+/// An ending in the `endings:` block: `escaped: You slip away.`
+#[derive(Debug, Clone)]
+pub struct EndingDecl {
+    pub id: String,
+    pub description: Option<String>,
     pub span: Span,
 }
 
@@ -118,6 +141,7 @@ pub struct EntityDecl {
 /// A node in the narrative content region of a `.urd.md` file.
 #[derive(Debug, Clone)]
 pub enum ContentNode {
+    RegionHeading(RegionHeading),
     LocationHeading(LocationHeading),
     SequenceHeading(SequenceHeading),
     PhaseHeading(PhaseHeading),
@@ -133,18 +157,51 @@ pub enum ContentNode {
     Jump(Jump),
     ExitDeclaration(ExitDeclaration),
     BlockedMessage(BlockedMessage),
+    LocationHook(LocationHook),
     RuleBlock(RuleBlock),
     Comment(Comment),
     ErrorNode(ErrorNode),
+    ConditionalBlock(ConditionalBlock<ContentNode>),
+}
+
+/// `# Region: Display Name` — a region heading. The locations after it in
+/// the same file, up to the next region heading, belong to the region.
+/// Like the start of a file, it ends the previous location.
+#[derive(Debug, Clone)]
+pub struct RegionHeading {
+    pub display_name: String,
+    pub span: Span,
+}
+
+impl RegionHeading {
+    /// The region ID: the slugified display name.
+    pub fn id(&self) -> String {
+        slugify(&self.display_name)
+    }
 }
 
-/// `# Display Name` — a location heading.
+/// `# Display Name` — a location heading. `# Display Name {#id}` sets an
+/// explicit ID instead of deriving one from the display name.
 #[derive(Debug, Clone)]
 pub struct LocationHeading {
     pub display_name: String,
+    pub explicit_id: Option<String>,
+    /// Text of the `///` doc comment lines directly above the heading.
+    pub doc: Option<String>,
     pub span: Span,
 }
 
+impl LocationHeading {
+    /// The location ID: the explicit override if present, otherwise the
+    /// slugified display name. LINK validates explicit IDs.
+    pub fn id(&self) -> String {
+        match &self.explicit_id {
+            Some(id) => id.clone(),
+            None => slugify(&self.display_name),
+        }
+    }
+}
+
 /// `## Display Name` — a sequence heading.
 #[derive(Debug, Clone)]
 pub struct SequenceHeading {
@@ -152,38 +209,96 @@ pub struct SequenceHeading {
     pub span: Span,
 }
 
-/// `### Name (auto)` — a phase heading.
+/// `### Name (auto)` — a phase heading, optionally followed directly by a
+/// `section: name` line naming the dialogue section the phase opens.
 #[derive(Debug, Clone)]
 pub struct PhaseHeading {
     pub display_name: String,
     pub auto: bool,
+    pub section: Option<PhaseSection>,
     pub span: Span,
 }
 
-/// `== name` — a section label.
+/// `section: name` under a phase heading. `name` is a local section name
+/// or a compiled section ID (`file_stem/name`); LINK resolves it.
+#[derive(Debug, Clone)]
+pub struct PhaseSection {
+    pub name: String,
+    pub span: Span,
+}
+
+/// `== name` — a section label. `== name {#id}` sets an explicit local ID
+/// instead of using the name. `== name(@param: Type)` declares a
+/// parameterised section.
 #[derive(Debug, Clone)]
 pub struct SectionLabel {
     pub name: String,
+    pub explicit_id: Option<String>,
+    pub param: Option<SectionParam>,
+    /// Text of the `///` doc comment lines directly above the label.
+    pub doc: Option<String>,
     pub span: Span,
 }
 
-/// `[@arina, @barrel]` — entity presence in a location.
+/// `@merchant: Merchant` — the entity parameter of a parameterised section.
+/// `name` is written without the `@`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionParam {
+    pub name: String,
+    pub type_name: String,
+}
+
+impl SectionLabel {
+    /// The file-local section ID: the explicit override if present,
+    /// otherwise the name. The compiled ID is `file_stem/local_id`.
+    pub fn local_id(&self) -> &str {
+        self.explicit_id.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// `[@arina, @barrel]` — entity presence in a location. An entry may
+/// carry initial property overrides: `[@cell_door (locked: false)]`.
 #[derive(Debug, Clone)]
 pub struct EntityPresence {
     pub entity_refs: Vec<String>,
     pub annotations: Vec<Option<Annotation>>,
+    /// One per entry of `entity_refs`: its overrides, in source order.
+    /// LINK merges them into the entity's `property_overrides`.
+    pub property_overrides: Vec<Vec<(String, Scalar)>>,
     pub span: Span,
 }
 
-/// `@arina: What'll it be?` — entity speech.
+/// `@arina: What'll it be?` — entity speech. Inside a choice, or after a
+/// section's choices, a quoted line (`"Aye, it was."`) is speech too,
+/// with an empty `entity_ref`: it inherits the section prompt's speaker.
 #[derive(Debug, Clone)]
 pub struct EntitySpeech {
     pub entity_ref: String,
     pub text: String,
+    /// Alternatives to `text` from the `| ...` lines directly below it;
+    /// the runtime picks one. Empty for a plain line.
+    pub variants: Vec<String>,
     pub annotation: Option<Annotation>,
     pub span: Span,
 }
 
+impl EntitySpeech {
+    /// Whether this is a quoted line with no speaker of its own.
+    pub fn inherits_speaker(&self) -> bool {
+        self.entity_ref.is_empty()
+    }
+
+    /// The speaker as written, or for a quoted line the entity LINK
+    /// resolved the prompt's speaker to. Empty when that did not resolve.
+    pub fn speaker(&self) -> &str {
+        if self.inherits_speaker() {
+            self.annotation.as_ref().and_then(|a| a.resolved_entity.as_deref()).unwrap_or("")
+        } else {
+            &self.entity_ref
+        }
+    }
+}
+
 /// `@arina leans in close.` — stage direction.
 #[derive(Debug, Clone)]
 pub struct StageDirection {
@@ -197,6 +312,8 @@ pub struct StageDirection {
 #[derive(Debug, Clone)]
 pub struct Prose {
     pub text: String,
+    /// Alternatives to `text` from `| ...` lines, as for [`EntitySpeech`].
+    pub variants: Vec<String>,
     pub span: Span,
 }
 
@@ -213,6 +330,16 @@ pub struct Choice {
     pub span: Span,
 }
 
+impl Choice {
+    /// Whether the choice carries a `? once` condition among its direct
+    /// children.
+    pub fn is_once(&self) -> bool {
+        self.content.iter().any(|node| {
+            matches!(node, ContentNode::Condition(c) if matches!(c.expr, ConditionExpr::Once(_)))
+        })
+    }
+}
+
 /// `? expression` — a condition.
 #[derive(Debug, Clone)]
 pub struct Condition {
@@ -238,11 +365,14 @@ pub struct Effect {
     pub span: Span,
 }
 
-/// `-> name` or `-> exit:name` — a jump.
+/// `-> name`, `-> exit:name`, or `-> name(@entity)` — a jump. The last
+/// form invokes a parameterised section; `argument` is the entity
+/// reference without the `@`.
 #[derive(Debug, Clone)]
 pub struct Jump {
     pub target: String,
     pub is_exit_qualified: bool,
+    pub argument: Option<String>,
     pub indent_level: usize,
     pub annotation: Option<Annotation>,
     pub span: Span,
@@ -266,6 +396,65 @@ pub struct BlockedMessage {
     pub span: Span,
 }
 
+/// Which location transition a hook block fires on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HookKind {
+    /// `on enter:` — fires when an entity enters the location.
+    Enter,
+    /// `on exit:` — fires when an entity leaves the location.
+    Exit,
+}
+
+impl HookKind {
+    /// The JSON key this hook is emitted under (`on_enter` / `on_exit`).
+    pub fn json_key(&self) -> &'static str {
+        match self {
+            HookKind::Enter => "on_enter",
+            HookKind::Exit => "on_exit",
+        }
+    }
+
+    /// The header as written in source (`on enter` / `on exit`).
+    pub fn header(&self) -> &'static str {
+        match self {
+            HookKind::Enter => "on enter",
+            HookKind::Exit => "on exit",
+        }
+    }
+}
+
+/// `on enter:` / `on exit:` — a location hook block.
+/// Children are Condition, OrConditionBlock, and Effect nodes.
+#[derive(Debug, Clone)]
+pub struct LocationHook {
+    pub kind: HookKind,
+    pub children: Vec<ContentNode>,
+    pub span: Span,
+}
+
+/// `?if flag:` … `?endif` — lines kept only when the compile sets `flag`,
+/// or only when it does not for `?if !flag:`. Guards start at column 0 and
+/// nest one level. IMPORT splices the children of a kept block in its
+/// place and drops the rest, so LINK and later phases never see one.
+#[derive(Debug, Clone)]
+pub struct ConditionalBlock<T> {
+    pub flag: String,
+    pub negated: bool,
+    pub children: Vec<T>,
+    /// Diagnostics raised while parsing the children. IMPORT reports them
+    /// only if the block is kept.
+    pub diagnostics: Vec<Diagnostic>,
+    /// From the `?if` line to the `?endif` line.
+    pub span: Span,
+}
+
+impl<T> ConditionalBlock<T> {
+    /// Whether the block is kept under `flags`.
+    pub fn is_active(&self, flags: &BTreeSet<String>) -> bool {
+        flags.contains(&self.flag) != self.negated
+    }
+}
+
 /// `// text` — a comment, retained for potential LSP use.
 #[derive(Debug, Clone)]
 pub struct Comment {
@@ -292,6 +481,9 @@ pub struct RuleBlock {
     pub select: Option<SelectClause>,
     pub where_clauses: Vec<ConditionExpr>,
     pub effects: Vec<Effect>,
+    /// Indent of the `rule` line. An indented rule under a location
+    /// heading applies only while the player is in that location.
+    pub indent_level: usize,
     pub span: Span,
 }
 
@@ -300,6 +492,8 @@ pub struct RuleBlock {
 pub struct SelectClause {
     pub variable: String,
     pub entity_refs: Vec<String>,
+    /// One per entry of `entity_refs`, set by LINK when the entry resolves.
+    pub annotations: Vec<Option<Annotation>>,
     pub where_clauses: Vec<ConditionExpr>,
     pub span: Span,
 }
@@ -313,19 +507,107 @@ pub enum ConditionExpr {
     PropertyComparison(PropertyComparison),
     ContainmentCheck(ContainmentCheck),
     ExhaustionCheck(ExhaustionCheck),
+    VisitedCheck(VisitedCheck),
+    Once(OnceCondition),
 }
 
-/// `@entity.property op value`
+/// `@entity.property op value`, `not @entity.property op value`, or a bare
+/// boolean read `@entity.property` / `!@entity.property`.
+///
+/// `operator` and `value` are as written; a bare read stores `== true`.
+/// Use [`PropertyComparison::lowered()`] for the comparison the condition
+/// actually makes.
 #[derive(Debug, Clone)]
 pub struct PropertyComparison {
     pub entity_ref: String,
     pub property: String,
     pub operator: String,
     pub value: String,
+    /// Written with a `not` prefix, or `!` on a bare read.
+    pub negated: bool,
+    /// Written without an operator or value.
+    pub bare: bool,
     pub annotation: Option<Annotation>,
+    /// Where the entity, property, operator, and value are written.
+    pub spans: OperandSpans,
     pub span: Span,
 }
 
+impl PropertyComparison {
+    /// The operator and value the condition tests, with negation applied:
+    /// `!@door.locked` tests `== false`, and `not @x.n < 3` tests `>= 3`.
+    pub fn lowered(&self) -> (&str, &str) {
+        match (self.negated, self.bare) {
+            (false, _) => (&self.operator, &self.value),
+            (true, true) => ("==", "false"),
+            (true, false) => (negate_operator(&self.operator), &self.value),
+        }
+    }
+}
+
+/// The parts of a `@entity.property op value` comparison or set effect,
+/// each as the `(start_col, end_col)` it is written at on the line of the
+/// condition's or effect's span, so diagnostics can point at the one at
+/// fault. The `*_span()` methods build the part's span from that span.
+///
+/// A bare read has no operator or value; both are the property's columns.
+/// Where PARSE cannot place a part (rule bodies, whose select variable is
+/// rewritten before parsing), its columns are `(0, 0)` and its span is the
+/// whole condition or effect.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct OperandSpans {
+    /// The entity reference, sigil included: `@guard`, `$door`, `target`.
+    pub entity: (u32, u32),
+    pub property: (u32, u32),
+    pub operator: (u32, u32),
+    pub value: (u32, u32),
+}
+
+impl OperandSpans {
+    /// Every part on the whole condition or effect.
+    pub fn whole() -> Self {
+        Self::default()
+    }
+
+    pub fn entity_span(&self, span: &Span) -> Span {
+        place(self.entity, span)
+    }
+
+    pub fn property_span(&self, span: &Span) -> Span {
+        place(self.property, span)
+    }
+
+    pub fn operator_span(&self, span: &Span) -> Span {
+        place(self.operator, span)
+    }
+
+    pub fn value_span(&self, span: &Span) -> Span {
+        place(self.value, span)
+    }
+}
+
+/// `columns` on `span`'s first line, or `span` itself when unplaced.
+fn place(columns: (u32, u32), span: &Span) -> Span {
+    match columns {
+        (0, 0) => span.clone(),
+        (start, end) => Span::new(span.file.clone(), span.start_line, start, span.start_line, end),
+    }
+}
+
+/// The comparison operator that holds exactly when `operator` does not.
+/// Unknown operators are returned unchanged.
+pub fn negate_operator(operator: &str) -> &str {
+    match operator {
+        "==" => "!=",
+        "!=" => "==",
+        "<" => ">=",
+        ">=" => "<",
+        ">" => "<=",
+        "<=" => ">",
+        other => other,
+    }
+}
+
 /// `@entity in container` or `@entity not in container`
 #[derive(Debug, Clone)]
 pub struct ContainmentCheck {
@@ -344,6 +626,22 @@ pub struct ExhaustionCheck {
     pub span: Span,
 }
 
+/// `visited location` or `!visited location`
+#[derive(Debug, Clone)]
+pub struct VisitedCheck {
+    pub location_ref: String,
+    pub negated: bool,
+    pub annotation: Option<Annotation>,
+    pub span: Span,
+}
+
+/// `once` — the choice is offered only the first time its section
+/// presents choices. Valid only as a direct condition of a choice.
+#[derive(Debug, Clone)]
+pub struct OnceCondition {
+    pub span: Span,
+}
+
 // ── Effect subtypes ──
 
 /// Discriminated effect types.
@@ -354,6 +652,8 @@ pub enum EffectType {
         target_prop: String,
         operator: String,
         value_expr: String,
+        /// Where the entity, property, operator, and value are written.
+        spans: OperandSpans,
     },
     /// `> move @entity -> container`
     Move {
@@ -368,6 +668,10 @@ pub enum EffectType {
     Destroy {
         entity_ref: String,
     },
+    /// `> end ending_id`
+    End {
+        ending_ref: String,
+    },
 }
 
 // ── Annotations ──
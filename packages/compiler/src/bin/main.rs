@@ -1,93 +1,652 @@
-/// Urd compiler CLI — compile, diff, and snapshot `.urd.md` files.
+/// Urd compiler CLI — compile, watch, diff, snapshot, measure, fix,
+/// anonymize, document, graph, export as a playable page, and extract
+/// strings and symbols from `.urd.md` files, plan their imports, locate a
+/// line's output, compile snippets, explain diagnostic codes, and export
+/// the world JSON Schema.
 ///
 /// Usage:
-///   urd <file.urd.md>                         Compile and emit .urd.json to stdout
+///   urd <file.urd.md> [-o|--output [<path>]] [--root <dir>] [--emit-keys] [--include-docs] [--numeric-ids] [--split-output <dir>] [--cache-dir <dir>] [--timings] [--text-lint] [--no-generator] [--define <flag>] [--meta [<path>]] [--reproducible] [--diagnostics-format text|json]  Compile and emit .urd.json (bare -o: beside the source)
+///   urd [--profile <name>] [OPTIONS]           Compile the project in the nearest urd.toml
+///   urd watch <file.urd.md> [-o output] [--root <dir>] [--emit-keys] [--include-docs] [--numeric-ids] [--cache-dir <dir>]  Recompile on change
 ///   urd diff <a> <b> [--format json|summary]  Compare two files and report changes
 ///   urd snapshot <file.urd.md> [-o output]    Create a .urd.snapshot.json
+///   urd stats <file.urd.md> [--format table|json]  Report world complexity metrics
+///   urd strings <file.urd.md> [-o output] [--format json|csv|po]  Export player-visible text
+///   urd symbols <file.urd.md> [-o output] [--root <dir>]  Export the symbol table
+///   urd fix <file.urd.md> [--dry-run] [--root <dir>]  Apply unambiguous "did you mean" fixes
+///   urd anonymize <file.urd.md> [-o dir] [--root <dir>]  Copy the project with its text replaced
+///   urd doc <file.urd.md> [-o dir] [--format markdown|html] [--date]  Write reference pages
+///   urd flow <file.urd.md> [--format dot|mermaid] [--section <id>] [-o output]  Graph the dialogue flow
+///   urd play <file.urd.md> [-o output] [--root <dir>]  Export a click-through HTML page
+///   urd imports <file.urd.md> [--full]        List the files a compile would read
+///   urd locate <file.urd.md> --line <n> [--file <path>] [--root <dir>]  Print the JSON a line produced
+///   urd fragment --kind section|location <file> [-o output]  Compile one section or location
+///   urd explain <code> | --list                Explain a diagnostic code
+///   urd schema [-o output]                     Print the .urd.json JSON Schema
+///   urd --help [<command>]                     Print help for every command, or one
 ///
-/// Diagnostics are printed to stderr. Exit code 0 on success (or no changes),
-/// 1 on errors (or changes detected by diff).
+/// Diagnostics are printed to stderr; `--quiet` keeps only errors and
+/// `--verbose` adds per-phase progress. `--pretty-diagnostics` (the
+/// default on a terminal) shows each one's source line, and
+/// `--plain-diagnostics` keeps them to one line; `--diagnostics-format
+/// json` prints them as one JSON array instead. Each command returns an
+/// [`ExitStatus`]: 0 on success (or no changes), 1 on errors (or changes
+/// detected by diff), 2 on usage or I/O failures. The contract and help
+/// text live in `urd_compiler::cli`.
 
+use urd_compiler::cache::CacheStore;
+use urd_compiler::cli::{self, CliError, DiagnosticStyle, ExitStatus, Verbosity};
+use urd_compiler::diagnostics::{catalog, render, Diagnostic, DiagnosticCollector, Severity};
 use urd_compiler::diff::{DiffSnapshot, DiffError};
-use urd_compiler::import::OsFileReader;
-
-fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    match args.get(1).map(|s| s.as_str()) {
-        Some("--help" | "-h" | "help") => { print_help(); std::process::exit(0); }
-        Some("--version" | "-V") => { print_version(); std::process::exit(0); }
-        Some("diff") => run_diff(&args[2..]),
-        Some("snapshot") => run_snapshot(&args[2..]),
-        Some(path) if !path.starts_with('-') => run_compile(path),
-        _ => { print_help(); std::process::exit(1); }
-    }
-}
-
-fn print_version() {
-    println!("urd {}", env!("CARGO_PKG_VERSION"));
-}
-
-fn print_help() {
-    eprintln!("urd {} — Schema Markdown compiler", env!("CARGO_PKG_VERSION"));
-    eprintln!();
-    eprintln!("Compiles .urd.md files through a five-phase pipeline");
-    eprintln!("(PARSE → IMPORT → LINK → VALIDATE → EMIT) to produce .urd.json.");
-    eprintln!();
-    eprintln!("USAGE:");
-    eprintln!("  urd <file.urd.md>");
-    eprintln!("  urd diff <a> <b> [OPTIONS]");
-    eprintln!("  urd snapshot <file.urd.md> [OPTIONS]");
-    eprintln!("  urd --help | -h");
-    eprintln!("  urd --version | -V");
-    eprintln!();
-    eprintln!("COMMANDS:");
-    eprintln!("  <file.urd.md>    Compile a .urd.md file and emit .urd.json to stdout.");
-    eprintln!("                   Diagnostics are printed to stderr.");
-    eprintln!("                   Exit code 0 on success, 1 on errors.");
-    eprintln!();
-    eprintln!("  diff <a> <b>     Compare two compilations and report changes.");
-    eprintln!("                   Each argument can be a .urd.md file (compiled on the");
-    eprintln!("                   fly) or a .urd.snapshot.json file.");
-    eprintln!("                   Exit code 0 if no changes, 1 if changes detected.");
-    eprintln!();
-    eprintln!("      --format <FORMAT>   Output format: json (default) or summary.");
-    eprintln!();
-    eprintln!("  snapshot <file>  Create a .urd.snapshot.json from a .urd.md file.");
-    eprintln!("                   Snapshots capture entities, locations, exits, sections,");
-    eprintln!("                   choices, rules, properties, and diagnostics for use");
-    eprintln!("                   with the diff command.");
-    eprintln!();
-    eprintln!("      -o <path>           Output path. Defaults to <file>.urd.snapshot.json.");
-    eprintln!();
-    eprintln!("OPTIONS:");
-    eprintln!("  -h, --help       Print this help message and exit.");
-    eprintln!("  -V, --version    Print the compiler version and exit.");
+use urd_compiler::docgen::{DocFormat, DocOptions};
+use urd_compiler::emit::{EmitOptions, Format};
+use urd_compiler::fragment::FragmentKind;
+use urd_compiler::metadata::MetadataOptions;
+use std::cell::{Cell, RefCell};
+use std::io::IsTerminal;
+use std::process::ExitCode;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use urd_compiler::import::{self, OsFileReader, PlanMode};
+use urd_compiler::observer::{CompileObserver, Phase};
+use urd_compiler::playable::PlayError;
+use urd_compiler::project::{self, ProjectOptions};
+use urd_compiler::CompileOptions;
+
+/// Set once in `main`, before any command runs.
+static DIAGNOSTIC_STYLE: OnceLock<DiagnosticStyle> = OnceLock::new();
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let status = cli::take_verbosity(&args)
+        .and_then(|(verbosity, args)| {
+            let (style, args) = cli::take_diagnostic_style(&args, std::io::stderr().is_terminal())?;
+            DIAGNOSTIC_STYLE.get_or_init(|| style);
+            run(&args, verbosity)
+        })
+        .unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            ExitStatus::Error
+        });
+    ExitCode::from(status.code())
+}
+
+/// Dispatch to the subcommand `args` name. `--quiet` and `--verbose` have
+/// already been taken out.
+fn run(args: &[String], verbosity: Verbosity) -> Result<ExitStatus, CliError> {
+    match args.first().map(|s| s.as_str()) {
+        Some("--help" | "-h" | "help") => run_help(&args[1..]),
+        Some("--version" | "-V") => run_version(&args[1..]),
+        Some("watch") => run_watch(&args[1..], verbosity),
+        Some("diff") => run_diff(&args[1..], verbosity),
+        Some("snapshot") => run_snapshot(&args[1..], verbosity),
+        Some("stats") => run_stats(&args[1..], verbosity),
+        Some("strings") => run_strings(&args[1..], verbosity),
+        Some("symbols") => run_symbols(&args[1..], verbosity),
+        Some("fix") => run_fix(&args[1..], verbosity),
+        Some("anonymize") => run_anonymize(&args[1..], verbosity),
+        Some("doc") => run_doc(&args[1..], verbosity),
+        Some("flow") => run_flow(&args[1..], verbosity),
+        Some("play") => run_play(&args[1..], verbosity),
+        Some("imports") => run_imports(&args[1..]),
+        Some("locate") => run_locate(&args[1..], verbosity),
+        Some("fragment") => run_fragment(&args[1..], verbosity),
+        Some("explain") => run_explain(&args[1..]),
+        Some("schema") => run_schema(&args[1..]),
+        Some(path) if !path.starts_with('-') => run_compile(args, verbosity),
+        _ => run_project(args, verbosity),
+    }
+}
+
+fn run_version(args: &[String]) -> Result<ExitStatus, CliError> {
+    match args {
+        [] => println!("{}", urd_compiler::version::to_text()),
+        [flag] if flag == "--json" => println!("{}", urd_compiler::version::to_json()),
+        _ => return Err(CliError::new("Usage: urd --version [--json]")),
+    }
+    Ok(ExitStatus::Success)
+}
+
+fn run_help(args: &[String]) -> Result<ExitStatus, CliError> {
+    let text = match args {
+        [] => cli::help(None)?,
+        [command] => cli::help(Some(command))?,
+        _ => return Err(CliError::new("Usage: urd --help [<command>]")),
+    };
+    eprint!("{}", text);
+    Ok(ExitStatus::Success)
 }
 
 // ── Compile (default command) ──
 
-fn run_compile(path: &str) {
-    let source = std::fs::read_to_string(path).unwrap_or_else(|e| {
-        eprintln!("Cannot read '{}': {}", path, e);
-        std::process::exit(1);
-    });
+fn run_compile(args: &[String], verbosity: Verbosity) -> Result<ExitStatus, CliError> {
+    let (flags, run) = parse_compile_flags(&args[1..])?;
+    compile_file(&args[0], &flags, &run, verbosity)
+}
 
-    let result = urd_compiler::compile_source_with_reader(path, &source, &OsFileReader);
-    print_diagnostics(&result);
+/// Compile flags that apply to one run, which a manifest cannot set.
+#[derive(Debug, Default)]
+struct RunFlags {
+    /// `-o/--output [<path>]`: write the world there rather than to
+    /// stdout. `Some(None)` when the flag is given bare, for the
+    /// `.urd.json` beside the source.
+    out: Option<Option<String>>,
+    /// `--meta [<path>]`: write build metadata. `Some(None)` when the
+    /// flag is given bare, for the path beside the world.
+    meta: Option<Option<String>>,
+    /// `--reproducible`: leave timings out of the metadata.
+    reproducible: bool,
+}
 
-    if let Some(json) = result.world {
-        println!("{}", json);
-    } else {
-        std::process::exit(1);
+/// Parse --root, --emit-keys, --include-docs, --numeric-ids,
+/// --split-output, --cache-dir, --timings, --text-lint, --no-generator
+/// and --define flags. Only flags given are set, so a manifest can supply the rest.
+/// -o/--output, --meta and --reproducible go into the [`RunFlags`].
+fn parse_compile_flags(args: &[String]) -> Result<(ProjectOptions, RunFlags), CliError> {
+    let mut flags = ProjectOptions::default();
+    let mut run = RunFlags::default();
+    let mut i = 0;
+    while i < args.len() {
+        if args[i] == "-o" || args[i] == "--output" {
+            match args.get(i + 1).filter(|next| !next.starts_with('-')) {
+                Some(path) => {
+                    run.out = Some(Some(path.clone()));
+                    i += 2;
+                }
+                None => {
+                    run.out = Some(None);
+                    i += 1;
+                }
+            }
+        } else if args[i] == "--meta" {
+            match args.get(i + 1).filter(|next| !next.starts_with('-')) {
+                Some(path) => {
+                    run.meta = Some(Some(path.clone()));
+                    i += 2;
+                }
+                None => {
+                    run.meta = Some(None);
+                    i += 1;
+                }
+            }
+        } else if args[i] == "--reproducible" {
+            run.reproducible = true;
+            i += 1;
+        } else if args[i] == "--root" && i + 1 < args.len() {
+            flags.root = Some(args[i + 1].clone());
+            i += 2;
+        } else if args[i] == "--split-output" && i + 1 < args.len() {
+            flags.split_output = Some(args[i + 1].clone());
+            i += 2;
+        } else if args[i] == "--cache-dir" && i + 1 < args.len() {
+            flags.cache_dir = Some(args[i + 1].clone());
+            i += 2;
+        } else if args[i] == "--define" && i + 1 < args.len() {
+            flags.define.get_or_insert_with(Vec::new).push(args[i + 1].clone());
+            i += 2;
+        } else if args[i] == "--emit-keys" {
+            flags.emit_keys = Some(true);
+            i += 1;
+        } else if args[i] == "--include-docs" {
+            flags.include_docs = Some(true);
+            i += 1;
+        } else if args[i] == "--numeric-ids" {
+            flags.numeric_ids = Some(true);
+            i += 1;
+        } else if args[i] == "--timings" {
+            flags.timings = Some(true);
+            i += 1;
+        } else if args[i] == "--text-lint" {
+            flags.text_lint = Some(true);
+            i += 1;
+        } else if args[i] == "--no-generator" {
+            flags.generator = Some(false);
+            i += 1;
+        } else {
+            return Err(CliError::unknown_argument(&args[i]));
+        }
+    }
+    Ok((flags, run))
+}
+
+/// Compile `path` with `flags`, printing diagnostics. The world goes to
+/// `run.out`, or stdout when it is `None`, and the build metadata to
+/// `run.meta`, whether or not the compile succeeds.
+fn compile_file(
+    path: &str,
+    flags: &ProjectOptions,
+    run: &RunFlags,
+    verbosity: Verbosity,
+) -> Result<ExitStatus, CliError> {
+    let out = match &run.out {
+        None => None,
+        Some(Some(out)) => Some(out.clone()),
+        Some(None) => Some(urd_compiler::watch::default_output_path(path)),
+    };
+    let out = out.as_deref();
+    if let Some(out) = out {
+        refuse_input_as_output(path, out)?;
+    }
+    let meta = match &run.meta {
+        None => None,
+        Some(Some(meta)) => Some(meta.clone()),
+        Some(None) => match out {
+            Some(out) => Some(metadata_path(out)),
+            None => return Err(CliError::new("--meta needs a path when the world is not written with -o.")),
+        },
+    };
+    let root = flags.root.as_deref();
+    let split_dir = flags.split_output.as_deref();
+    // Metadata describes a full compile: a cache hit has no sources or stats.
+    let cache_dir = flags.cache_dir.as_deref().filter(|_| meta.is_none());
+    let show_timings = flags.timings.unwrap_or(false);
+    let emit = EmitOptions {
+        split: split_dir.is_some(),
+        externalize_strings: flags.emit_keys.unwrap_or(false),
+        include_docs: flags.include_docs.unwrap_or(false),
+        numeric_ids: flags.numeric_ids.unwrap_or(false),
+        build_output_map: false,
+        include_generator: flags.generator.unwrap_or(true),
+        format: Format::V1,
+    };
+
+    let source = read(path)?;
+
+    let observer = CliObserver::new(verbosity);
+    let (path, mut options) = compile_options(path, root, emit)?;
+    if show_timings || verbosity.progress() || (meta.is_some() && !run.reproducible) {
+        options.observer = Some(&observer);
+    }
+    options.keep_sources |= meta.is_some();
+    options.text_lints = flags.text_lint.unwrap_or(false);
+    options.defines = flags.define.clone().unwrap_or_default();
+    // Without split output or a cache, a world bound for a file is
+    // streamed there rather than built as one string first.
+    options.skip_world = out.is_some() && split_dir.is_none() && cache_dir.is_none();
+    let result = match cache_dir {
+        Some(dir) => {
+            let store = DirCacheStore { dir: std::path::PathBuf::from(dir) };
+            let cached =
+                urd_compiler::cache::compile_cached(&path, &source, &OsFileReader, &options, &store);
+            if let Some(e) = &cached.store_error {
+                eprintln!("Cannot write cache to '{}': {}", dir, e);
+            }
+            cached.result
+        }
+        None => urd_compiler::compile_source_with_options(&path, &source, &OsFileReader, &options),
+    };
+    print_diagnostics(&result, verbosity);
+    if show_timings {
+        eprint!("{}", observer.table());
+    }
+    if let Some(meta) = &meta {
+        let phases = observer.phases.borrow();
+        let metadata_options = MetadataOptions {
+            compile: &options,
+            timings: (!run.reproducible).then_some(phases.as_slice()),
+        };
+        let sources = result.sources.clone().unwrap_or_default();
+        let document = urd_compiler::metadata::build_metadata(&result, &sources, &metadata_options);
+        if let Some(parent) = std::path::Path::new(meta).parent() {
+            std::fs::create_dir_all(parent).map_err(|e| CliError::io("create", parent.display(), e))?;
+        }
+        std::fs::write(meta, document).map_err(|e| CliError::io("write", meta, e))?;
+        eprintln!("Metadata written to {}", meta);
+    }
+
+    if !result.success {
+        return Ok(ExitStatus::Failure);
+    }
+    let (Some(dir), Some(parts)) = (split_dir, &result.parts) else {
+        match (out, &result.world) {
+            (Some(out), _) => {
+                let parent = std::path::Path::new(out).parent().filter(|p| !p.as_os_str().is_empty());
+                if let Some(parent) = parent {
+                    std::fs::create_dir_all(parent).map_err(|e| CliError::io("create", parent.display(), e))?;
+                }
+                let bytes = write_world(out, &result, &options.emit).map_err(|e| match e.kind() {
+                    std::io::ErrorKind::PermissionDenied => CliError::new(format!(
+                        "Cannot write '{}': the directory '{}' is not writable.",
+                        out,
+                        parent.map_or(".".into(), |p| p.display().to_string()),
+                    )),
+                    _ => CliError::io("write", out, e),
+                })?;
+                eprintln!("Wrote {} bytes to {}", bytes, out);
+            }
+            (None, Some(json)) => println!("{}", json),
+            (None, None) => unreachable!("the world is only skipped when it goes to a file"),
+        }
+        return Ok(ExitStatus::Success);
+    };
+
+    std::fs::create_dir_all(dir).map_err(|e| CliError::io("create", dir, e))?;
+    for (file, document) in parts {
+        let output = std::path::Path::new(dir).join(file);
+        std::fs::write(&output, document).map_err(|e| CliError::io("write", output.display(), e))?;
+    }
+    eprintln!("{} files written to {}", parts.len(), dir);
+    Ok(ExitStatus::Success)
+}
+
+// ── Project manifest ──
+
+/// Compile the entry named by the nearest `urd.toml`. Bare `urd` with no
+/// manifest in reach prints the help text, as it always has.
+fn run_project(args: &[String], verbosity: Verbosity) -> Result<ExitStatus, CliError> {
+    // Take --profile; the rest are compile flags, which win over the
+    // manifest and the profile.
+    let mut profile: Option<&str> = None;
+    let mut rest = Vec::new();
+    let mut i = 0;
+    while i < args.len() {
+        if args[i] == "--profile" && i + 1 < args.len() {
+            profile = Some(&args[i + 1]);
+            i += 2;
+        } else {
+            rest.push(args[i].clone());
+            i += 1;
+        }
+    }
+    let (flags, mut run) = parse_compile_flags(&rest)?;
+
+    let cwd = current_dir()?;
+    let Some(manifest_path) = project::find_manifest(&cwd, |p| p.is_file()) else {
+        if args.is_empty() {
+            eprint!("{}", cli::help(None)?);
+            return Ok(ExitStatus::Error);
+        }
+        return Err(CliError::new(format!(
+            "No {} found in '{}' or any parent directory. Pass a .urd.md file to compile it directly.",
+            project::MANIFEST_NAME,
+            cwd.display(),
+        )));
+    };
+    let shown = manifest_path.display();
+
+    let source = std::fs::read_to_string(&manifest_path).map_err(|e| CliError::io("read", &shown, e))?;
+    let (mut manifest, warnings) = project::parse_manifest(&source)
+        .map_err(|e| CliError::new(format!("{}:{}: error: {}", shown, e.line, e.message)))?;
+    if verbosity.shows(Severity::Warning) {
+        for warning in &warnings {
+            eprintln!("{}:{}: warning: {}", shown, warning.line, warning.message);
+        }
+    }
+    if let Some(dir) = manifest_path.parent() {
+        manifest.rebase(dir);
+    }
+
+    let options = manifest
+        .options_for(profile)
+        .map_err(|e| CliError::new(format!("{}: error: {}", shown, e)))?;
+    let Some(entry) = &manifest.entry else {
+        return Err(CliError::new(format!(
+            "{}: error: No 'entry' file. Add a line such as: entry = \"src/main.urd.md\"",
+            shown,
+        )));
+    };
+    run.out = run.out.or_else(|| manifest.out.clone().map(Some));
+    compile_file(entry, &options.overlay(&flags), &run, verbosity)
+}
+
+/// Refuse to write the world over its own source. Paths are compared once
+/// resolved, so `./world.urd.md` and `world.urd.md` are the same file; an
+/// output that does not exist yet cannot be the input.
+fn refuse_input_as_output(input: &str, out: &str) -> Result<(), CliError> {
+    match (std::fs::canonicalize(input), std::fs::canonicalize(out)) {
+        (Ok(input), Ok(out_path)) if input == out_path => Err(CliError::new(format!(
+            "Refusing to write the world to '{}': it is the input file.",
+            out,
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// The metadata path beside a world written to `out`:
+/// `world.urd.json` gives `world.urd.meta.json`.
+fn metadata_path(out: &str) -> String {
+    format!("{}.meta.json", out.strip_suffix(".json").unwrap_or(out))
+}
+
+/// Observes a compile: records phase durations and the file count for
+/// `--timings`, and prints progress lines under `--verbose`.
+#[derive(Default)]
+struct CliObserver {
+    progress: bool,
+    phases: RefCell<Vec<(Phase, Duration)>>,
+    files: Cell<usize>,
+}
+
+impl CliObserver {
+    fn new(verbosity: Verbosity) -> Self {
+        CliObserver { progress: verbosity.progress(), ..CliObserver::default() }
+    }
+}
+
+impl CompileObserver for CliObserver {
+    fn phase_started(&self, phase: Phase) {
+        if self.progress {
+            eprintln!("{}...", phase.name().to_uppercase());
+        }
+    }
+
+    fn phase_finished(&self, phase: Phase, elapsed: Duration) {
+        if self.progress {
+            eprintln!("{} done in {:.2} ms", phase.name().to_uppercase(), elapsed.as_secs_f64() * 1000.0);
+        }
+        self.phases.borrow_mut().push((phase, elapsed));
+    }
+
+    fn file_parsed(&self, path: &str, node_count: usize) {
+        if self.progress {
+            eprintln!("  parsed {} ({} nodes)", path, node_count);
+        }
+        self.files.set(self.files.get() + 1);
+    }
+}
+
+impl CliObserver {
+    /// An aligned table: one row per phase that ran, then the total and the
+    /// file count. A cache hit runs no phases.
+    fn table(&self) -> String {
+        let phases = self.phases.borrow();
+        if phases.is_empty() {
+            return "No phases ran (cached result).\n".to_string();
+        }
+        let ms = |d: Duration| format!("{:.2} ms", d.as_secs_f64() * 1000.0);
+        let mut rows: Vec<(String, String)> =
+            phases.iter().map(|(phase, elapsed)| (phase.name().to_uppercase(), ms(*elapsed))).collect();
+        rows.push(("Total".into(), ms(phases.iter().map(|(_, d)| *d).sum())));
+        rows.push(("Files parsed".into(), self.files.get().to_string()));
+
+        let label_width = rows.iter().map(|(l, _)| l.len()).max().unwrap_or(0);
+        let value_width = rows.iter().map(|(_, v)| v.len()).max().unwrap_or(0);
+        rows.iter()
+            .map(|(label, value)| format!("{:<lw$}  {:>vw$}\n", label, value, lw = label_width, vw = value_width))
+            .collect()
+    }
+}
+
+// ── Watch command ──
+
+fn run_watch(args: &[String], verbosity: Verbosity) -> Result<ExitStatus, CliError> {
+    if args.is_empty() {
+        return Err(CliError::usage("watch"));
+    }
+
+    let path = &args[0];
+
+    // Parse -o, --root, --emit-keys, --include-docs, --numeric-ids,
+    // --no-generator and --cache-dir flags.
+    let mut output_path: Option<String> = None;
+    let mut root: Option<&str> = None;
+    let mut cache_dir: Option<&str> = None;
+    let mut emit = EmitOptions { include_generator: true, ..EmitOptions::default() };
+    let mut i = 1;
+    while i < args.len() {
+        if args[i] == "-o" && i + 1 < args.len() {
+            output_path = Some(args[i + 1].clone());
+            i += 2;
+        } else if args[i] == "--root" && i + 1 < args.len() {
+            root = Some(&args[i + 1]);
+            i += 2;
+        } else if args[i] == "--cache-dir" && i + 1 < args.len() {
+            cache_dir = Some(&args[i + 1]);
+            i += 2;
+        } else if args[i] == "--emit-keys" {
+            emit.externalize_strings = true;
+            i += 1;
+        } else if args[i] == "--include-docs" {
+            emit.include_docs = true;
+            i += 1;
+        } else if args[i] == "--numeric-ids" {
+            emit.numeric_ids = true;
+            i += 1;
+        } else if args[i] == "--no-generator" {
+            emit.include_generator = false;
+            i += 1;
+        } else {
+            return Err(CliError::unknown_argument(&args[i]));
+        }
+    }
+
+    let output = output_path.unwrap_or_else(|| urd_compiler::watch::default_output_path(path));
+    let (path, mut options) = compile_options(path, root, emit)?;
+    // Cached results carry no tables to stream from.
+    options.skip_world = cache_dir.is_none();
+    if verbosity.progress() {
+        // Watching lasts until the process ends, so its observer does too.
+        options.observer = Some(Box::leak(Box::new(CliObserver::new(verbosity))));
+    }
+    // Graph paths are relative to the project root, or else to the entry
+    // file's directory.
+    let base_dir = match &options.project_root {
+        Some(root) => root.clone(),
+        None => path.rfind('/').map(|pos| path[..pos + 1].to_string()).unwrap_or_default(),
+    };
+
+    let mut host = WatchCli {
+        path: path.clone(),
+        options,
+        output,
+        cache: cache_dir.map(|dir| DirCacheStore { dir: std::path::PathBuf::from(dir) }),
+        watching: 1,
+        verbosity,
+    };
+    // Ctrl-C ends the process. Output is written to a temporary file and
+    // renamed into place, so an interrupted write never leaves a partial
+    // world behind.
+    let stop = std::sync::atomic::AtomicBool::new(false);
+    urd_compiler::watch::watch(&mut host, &path, &base_dir, &stop);
+    Ok(ExitStatus::Success)
+}
+
+/// The file-system host for `urd watch`.
+struct WatchCli {
+    path: String,
+    options: CompileOptions<'static>,
+    output: String,
+    cache: Option<DirCacheStore>,
+    /// Files watched after the previous compile, for the status line.
+    watching: usize,
+    verbosity: Verbosity,
+}
+
+impl urd_compiler::watch::WatchHost for WatchCli {
+    fn modified(&self, path: &str) -> Option<std::time::SystemTime> {
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+
+    fn compile(&mut self) -> urd_compiler::CompilationResult {
+        let source = match std::fs::read_to_string(&self.path) {
+            Ok(source) => source,
+            // Reports the unreadable entry file as URD100.
+            Err(_) => return urd_compiler::compile_with_options(&self.path, &self.options),
+        };
+        let result = match &self.cache {
+            Some(store) => {
+                let cached = urd_compiler::cache::compile_cached(
+                    &self.path, &source, &OsFileReader, &self.options, store,
+                );
+                if let Some(e) = &cached.store_error {
+                    eprintln!("Cannot write cache to '{}': {}", store.dir.display(), e);
+                }
+                cached.result
+            }
+            None => urd_compiler::compile_source_with_options(&self.path, &source, &OsFileReader, &self.options),
+        };
+        if let Some(graph) = &result.graph {
+            self.watching = graph.nodes.len();
+        }
+        result
     }
+
+    fn compiled(&mut self, run: urd_compiler::watch::WatchRun, result: &urd_compiler::CompilationResult) {
+        // Clear the screen and move the cursor home.
+        eprint!("\x1b[2J\x1b[H");
+        print_diagnostics(result, self.verbosity);
+        let ms = run.elapsed.as_secs_f64() * 1000.0;
+        let status = if result.success {
+            match write_world_atomically(&self.output, result, &self.options.emit) {
+                Ok(()) => format!("Wrote {}.", self.output),
+                Err(e) => format!("Cannot write '{}': {}", self.output, e),
+            }
+        } else {
+            let errors = result
+                .diagnostics
+                .all()
+                .iter()
+                .filter(|d| d.severity == Severity::Error)
+                .count();
+            format!(
+                "{} error{}; {} left unchanged.",
+                errors,
+                if errors == 1 { "" } else { "s" },
+                self.output,
+            )
+        };
+        eprintln!("[#{}] Compiled in {:.2} ms. {}", run.count, ms, status);
+        eprintln!(
+            "Watching {} file{}. Press Ctrl-C to stop.",
+            self.watching,
+            if self.watching == 1 { "" } else { "s" },
+        );
+    }
+}
+
+/// Write `contents` beside `path`, then rename it over `path`.
+fn write_atomically(path: &str, contents: &str) -> Result<(), String> {
+    let temp = format!("{}.{}.tmp", path, std::process::id());
+    std::fs::write(&temp, contents).map_err(|e| e.to_string())?;
+    std::fs::rename(&temp, path).map_err(|e| {
+        let _ = std::fs::remove_file(&temp);
+        e.to_string()
+    })
+}
+
+/// [`write_world()`] by way of a temporary file, like [`write_atomically()`].
+fn write_world_atomically(
+    path: &str,
+    result: &urd_compiler::CompilationResult,
+    emit: &EmitOptions,
+) -> Result<(), String> {
+    let temp = format!("{}.{}.tmp", path, std::process::id());
+    write_world(&temp, result, emit).map_err(|e| {
+        let _ = std::fs::remove_file(&temp);
+        e.to_string()
+    })?;
+    std::fs::rename(&temp, path).map_err(|e| {
+        let _ = std::fs::remove_file(&temp);
+        e.to_string()
+    })
 }
 
 // ── Diff command ──
 
-fn run_diff(args: &[String]) {
+fn run_diff(args: &[String], verbosity: Verbosity) -> Result<ExitStatus, CliError> {
     if args.len() < 2 {
-        eprintln!("Usage: urd diff <file_a> <file_b> [--format json|summary]");
-        std::process::exit(1);
+        return Err(CliError::usage("diff"));
     }
 
     let path_a = &args[0];
@@ -102,19 +661,17 @@ fn run_diff(args: &[String]) {
                 "json" => "json",
                 "summary" => "summary",
                 other => {
-                    eprintln!("Unknown format '{}'. Use 'json' or 'summary'.", other);
-                    std::process::exit(1);
+                    return Err(CliError::new(format!("Unknown format '{}'. Use 'json' or 'summary'.", other)));
                 }
             };
             i += 2;
         } else {
-            eprintln!("Unknown argument '{}'", args[i]);
-            std::process::exit(1);
+            return Err(CliError::unknown_argument(&args[i]));
         }
     }
 
-    let snap_a = load_snapshot(path_a);
-    let snap_b = load_snapshot(path_b);
+    let snap_a = load_snapshot(path_a, verbosity)?;
+    let snap_b = load_snapshot(path_b, verbosity)?;
     let report = urd_compiler::diff::diff(&snap_a, &snap_b);
 
     match format {
@@ -128,112 +685,956 @@ fn run_diff(args: &[String]) {
     }
 
     if report.changes.is_empty() {
-        std::process::exit(0);
+        Ok(ExitStatus::Success)
     } else {
-        std::process::exit(1);
+        Ok(ExitStatus::Failure)
     }
 }
 
 // ── Snapshot command ──
 
-fn run_snapshot(args: &[String]) {
+fn run_snapshot(args: &[String], verbosity: Verbosity) -> Result<ExitStatus, CliError> {
     if args.is_empty() {
-        eprintln!("Usage: urd snapshot <file.urd.md> [-o output.snapshot.json]");
-        std::process::exit(1);
+        return Err(CliError::usage("snapshot"));
     }
 
     let path = &args[0];
 
-    // Parse -o flag.
+    // Parse -o and --root flags.
     let mut output_path: Option<String> = None;
+    let mut root: Option<&str> = None;
     let mut i = 1;
     while i < args.len() {
         if args[i] == "-o" && i + 1 < args.len() {
             output_path = Some(args[i + 1].clone());
             i += 2;
+        } else if args[i] == "--root" && i + 1 < args.len() {
+            root = Some(&args[i + 1]);
+            i += 2;
         } else {
-            eprintln!("Unknown argument '{}'", args[i]);
-            std::process::exit(1);
+            return Err(CliError::unknown_argument(&args[i]));
         }
     }
 
-    let output = output_path.unwrap_or_else(|| {
-        let stem = path
-            .strip_suffix(".urd.md")
-            .unwrap_or(path);
-        format!("{}.urd.snapshot.json", stem)
-    });
+    let output = output_path.unwrap_or_else(|| urd_compiler::diff::default_snapshot_path(path));
 
-    let source = std::fs::read_to_string(path).unwrap_or_else(|e| {
-        eprintln!("Cannot read '{}': {}", path, e);
-        std::process::exit(1);
-    });
+    let source = read(path)?;
 
-    let result = urd_compiler::compile_source_with_reader(path, &source, &OsFileReader);
-    print_diagnostics(&result);
+    let result = compile_with_root(path, &source, root, EmitOptions::default(), verbosity)?;
+    print_diagnostics(&result, verbosity);
 
     if result.diagnostics.has_errors() && result.fact_set.is_none() {
         eprintln!("Compilation failed; cannot create snapshot.");
-        std::process::exit(1);
+        return Ok(ExitStatus::Failure);
     }
 
-    // Extract world name from compiled JSON.
-    let world_name = result
-        .world
-        .as_deref()
-        .and_then(|w| serde_json::from_str::<serde_json::Value>(w).ok())
-        .and_then(|v| v.get("world")?.get("name")?.as_str().map(|s| s.to_string()))
-        .unwrap_or_default();
-
+    let world_name = urd_compiler::diff::world_name(&result);
     let snapshot = DiffSnapshot::from_compilation(&result);
     let json = serde_json::to_string_pretty(&snapshot.to_json(&world_name)).unwrap();
 
-    std::fs::write(&output, format!("{}\n", json)).unwrap_or_else(|e| {
-        eprintln!("Cannot write '{}': {}", output, e);
-        std::process::exit(1);
-    });
+    write(&output, &format!("{}\n", json))?;
 
     eprintln!("Snapshot written to {}", output);
+    Ok(ExitStatus::from_diagnostics(&result.diagnostics))
 }
 
-// ── Helpers ──
+// ── Stats command ──
 
-/// Load a DiffSnapshot from either a .urd.md source or a .urd.snapshot.json file.
-fn load_snapshot(path: &str) -> DiffSnapshot {
-    if path.ends_with(".urd.snapshot.json") {
-        let content = std::fs::read_to_string(path).unwrap_or_else(|e| {
-            eprintln!("Cannot read '{}': {}", path, e);
-            std::process::exit(1);
-        });
-        match DiffSnapshot::from_json(&content) {
-            Ok(snap) => snap,
-            Err(DiffError::UnsupportedSnapshotVersion) => {
-                eprintln!("Unsupported snapshot version in '{}'. Regenerate with current compiler.", path);
-                std::process::exit(1);
+fn run_stats(args: &[String], verbosity: Verbosity) -> Result<ExitStatus, CliError> {
+    if args.is_empty() {
+        return Err(CliError::usage("stats"));
+    }
+
+    let path = &args[0];
+
+    // Parse --format and --root flags.
+    let mut format = "table";
+    let mut root: Option<&str> = None;
+    let mut i = 1;
+    while i < args.len() {
+        if args[i] == "--format" && i + 1 < args.len() {
+            format = match args[i + 1].as_str() {
+                "table" => "table",
+                "json" => "json",
+                other => {
+                    return Err(CliError::new(format!("Unknown format '{}'. Use 'table' or 'json'.", other)));
+                }
+            };
+            i += 2;
+        } else if args[i] == "--root" && i + 1 < args.len() {
+            root = Some(&args[i + 1]);
+            i += 2;
+        } else {
+            return Err(CliError::unknown_argument(&args[i]));
+        }
+    }
+
+    let source = read(path)?;
+
+    let result = compile_with_root(path, &source, root, EmitOptions::default(), verbosity)?;
+    print_diagnostics(&result, verbosity);
+
+    let stats = match urd_compiler::stats::from_compilation(&result) {
+        Some(s) => s,
+        None => {
+            eprintln!("Compilation failed; cannot compute stats.");
+            return Ok(ExitStatus::Failure);
+        }
+    };
+
+    match format {
+        "json" => {
+            let json = serde_json::to_string_pretty(&stats.to_json()).unwrap();
+            println!("{}", json);
+        }
+        _ => print!("{}", stats.table()),
+    }
+    Ok(ExitStatus::from_diagnostics(&result.diagnostics))
+}
+
+// ── Explain command ──
+
+fn run_explain(args: &[String]) -> Result<ExitStatus, CliError> {
+    let code = match args {
+        [flag] if flag == "--list" => {
+            for info in catalog::CATALOG {
+                println!("{}  {}", info.code, info.title);
             }
-            Err(DiffError::ParseError(msg)) => {
-                eprintln!("Failed to parse snapshot '{}': {}", path, msg);
-                std::process::exit(1);
+            return Ok(ExitStatus::Success);
+        }
+        [code] => code.to_ascii_uppercase(),
+        _ => return Err(CliError::usage("explain")),
+    };
+
+    let info = catalog::lookup(&code).ok_or_else(|| {
+        CliError::new(format!("Unknown diagnostic code '{}'. Run 'urd explain --list' for every code.", code))
+    })?;
+    println!("{}: {}", info.code, info.title);
+    println!();
+    println!("{}", info.explanation);
+    println!();
+    println!("{}", info.docs_url());
+    Ok(ExitStatus::Success)
+}
+
+// ── Schema command ──
+
+fn run_schema(args: &[String]) -> Result<ExitStatus, CliError> {
+    let schema = urd_compiler::schema::world_schema();
+    match args {
+        [] => print!("{}", schema),
+        [flag, output] if flag == "-o" => {
+            write(output, schema)?;
+            eprintln!("Schema written to {}", output);
+        }
+        _ => return Err(CliError::usage("schema")),
+    }
+    Ok(ExitStatus::Success)
+}
+
+// ── Strings command ──
+
+fn run_strings(args: &[String], verbosity: Verbosity) -> Result<ExitStatus, CliError> {
+    if args.is_empty() {
+        return Err(CliError::usage("strings"));
+    }
+
+    let path = &args[0];
+
+    // Parse -o, --format, and --root flags.
+    let mut output_path: Option<String> = None;
+    let mut format = "json";
+    let mut root: Option<&str> = None;
+    let mut i = 1;
+    while i < args.len() {
+        if args[i] == "-o" && i + 1 < args.len() {
+            output_path = Some(args[i + 1].clone());
+            i += 2;
+        } else if args[i] == "--format" && i + 1 < args.len() {
+            format = match args[i + 1].as_str() {
+                "json" => "json",
+                "csv" => "csv",
+                "po" => "po",
+                other => {
+                    return Err(CliError::new(format!("Unknown format '{}'. Use 'json', 'csv', or 'po'.", other)));
+                }
+            };
+            i += 2;
+        } else if args[i] == "--root" && i + 1 < args.len() {
+            root = Some(&args[i + 1]);
+            i += 2;
+        } else {
+            return Err(CliError::unknown_argument(&args[i]));
+        }
+    }
+
+    let source = read(path)?;
+
+    let result = compile_with_root(path, &source, root, EmitOptions::default(), verbosity)?;
+    print_diagnostics(&result, verbosity);
+
+    let table = match urd_compiler::strings::from_compilation(&result) {
+        Some(t) => t,
+        None => {
+            eprintln!("Compilation failed; cannot extract strings.");
+            return Ok(ExitStatus::Failure);
+        }
+    };
+
+    let rendered = match format {
+        "csv" => table.to_csv(),
+        "po" => table.to_po(),
+        _ => format!("{}\n", serde_json::to_string_pretty(&table.to_json()).unwrap()),
+    };
+
+    match output_path {
+        Some(output) => {
+            write(&output, &rendered)?;
+            eprintln!("{} strings written to {}", table.len(), output);
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(ExitStatus::from_diagnostics(&result.diagnostics))
+}
+
+// ── Symbols command ──
+
+fn run_symbols(args: &[String], verbosity: Verbosity) -> Result<ExitStatus, CliError> {
+    if args.is_empty() {
+        return Err(CliError::usage("symbols"));
+    }
+
+    let path = &args[0];
+
+    // Parse -o and --root flags.
+    let mut output_path: Option<String> = None;
+    let mut root: Option<&str> = None;
+    let mut i = 1;
+    while i < args.len() {
+        if args[i] == "-o" && i + 1 < args.len() {
+            output_path = Some(args[i + 1].clone());
+            i += 2;
+        } else if args[i] == "--root" && i + 1 < args.len() {
+            root = Some(&args[i + 1]);
+            i += 2;
+        } else {
+            return Err(CliError::unknown_argument(&args[i]));
+        }
+    }
+
+    let source = read(path)?;
+
+    let result = compile_with_root(path, &source, root, EmitOptions::default(), verbosity)?;
+    print_diagnostics(&result, verbosity);
+
+    let Some(symbol_table) = &result.symbol_table else {
+        eprintln!("Compilation failed; cannot export symbols.");
+        return Ok(ExitStatus::Failure);
+    };
+    let json = serde_json::to_string_pretty(&symbol_table.export().to_json()).unwrap();
+
+    match output_path {
+        Some(output) => {
+            write(&output, &format!("{}\n", json))?;
+            eprintln!("Symbols written to {}", output);
+        }
+        None => println!("{}", json),
+    }
+    Ok(ExitStatus::from_diagnostics(&result.diagnostics))
+}
+
+// ── Fix command ──
+
+fn run_fix(args: &[String], verbosity: Verbosity) -> Result<ExitStatus, CliError> {
+    if args.is_empty() {
+        return Err(CliError::usage("fix"));
+    }
+
+    let path = &args[0];
+
+    // Parse --dry-run and --root flags.
+    let mut dry_run = false;
+    let mut root: Option<&str> = None;
+    let mut i = 1;
+    while i < args.len() {
+        if args[i] == "--dry-run" {
+            dry_run = true;
+            i += 1;
+        } else if args[i] == "--root" && i + 1 < args.len() {
+            root = Some(&args[i + 1]);
+            i += 2;
+        } else {
+            return Err(CliError::unknown_argument(&args[i]));
+        }
+    }
+
+    let source = read(path)?;
+    let observer = CliObserver::new(verbosity);
+    let (path, mut options) = compile_options(path, root, EmitOptions::default())?;
+    if verbosity.progress() {
+        options.observer = Some(&observer);
+    }
+    let before = urd_compiler::compile_source_with_options(&path, &source, &OsFileReader, &options);
+
+    let normalised = path.replace('\\', "/");
+    let Some(base_dir) = base_dir(&normalised, &options) else {
+        print_diagnostics(&before, verbosity);
+        return Ok(ExitStatus::Failure);
+    };
+
+    let fixes = urd_compiler::fix::fixes_by_file(&before.diagnostics);
+    let mut patched: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+    let mut applied = 0;
+    for (file, fixes) in &fixes {
+        let fs_path = format!("{}{}", base_dir, file);
+        let original = match std::fs::read_to_string(&fs_path) {
+            Ok(original) => original,
+            Err(e) => {
+                eprintln!("Cannot read '{}': {}. Skipping it.", fs_path, e);
+                continue;
+            }
+        };
+        let plan = match urd_compiler::fix::plan_edits(&original, fixes) {
+            Ok(plan) => plan,
+            Err(conflict) => {
+                eprintln!("{} in '{}'. Skipping it.", conflict, fs_path);
+                continue;
             }
+        };
+        for fix in &plan.unmatched {
+            eprintln!("Cannot find '{}' at {}. Skipping this fix.", fix.original, fix.span);
+        }
+        if plan.edits.is_empty() {
+            continue;
+        }
+        applied += plan.edits.len();
+        let fixed = urd_compiler::fix::apply_edits(&original, &plan.edits);
+        if dry_run {
+            print!("{}", urd_compiler::fix::unified_diff(file, &original, &fixed));
+        } else {
+            write_atomically(&fs_path, &fixed).map_err(|e| CliError::io("write", &fs_path, e))?;
         }
+        patched.insert(fs_path, fixed);
+    }
+
+    // Recompile against the fixed text, which a dry run holds only in memory.
+    let entry = patched.get(&normalised).cloned().unwrap_or(source);
+    let reader = PatchedReader { patched: &patched };
+    let after = urd_compiler::compile_source_with_options(&path, &entry, &reader, &options);
+    print_diagnostics(&after, verbosity);
+
+    let plural = |n: usize| if n == 1 { "" } else { "s" };
+    eprintln!(
+        "{} {} fix{} in {} file{}. Errors: {} before, {} after.",
+        if dry_run { "Would apply" } else { "Applied" },
+        applied,
+        if applied == 1 { "" } else { "es" },
+        patched.len(),
+        plural(patched.len()),
+        error_count(&before),
+        error_count(&after),
+    );
+    Ok(ExitStatus::from_diagnostics(&after.diagnostics))
+}
+
+/// Serves fixed sources in place of the files on disk.
+struct PatchedReader<'a> {
+    patched: &'a std::collections::HashMap<String, String>,
+}
+
+impl urd_compiler::import::FileReader for PatchedReader<'_> {
+    fn read_file(&self, fs_path: &str) -> Result<String, urd_compiler::import::FileReadError> {
+        match self.patched.get(fs_path) {
+            Some(source) => Ok(source.clone()),
+            None => OsFileReader.read_file(fs_path),
+        }
+    }
+
+    fn canonical_filename(&self, dir: &str, filename: &str) -> Option<String> {
+        OsFileReader.canonical_filename(dir, filename)
+    }
+}
+
+// ── Anonymize command ──
+
+fn run_anonymize(args: &[String], verbosity: Verbosity) -> Result<ExitStatus, CliError> {
+    if args.is_empty() {
+        return Err(CliError::usage("anonymize"));
+    }
+
+    let path = &args[0];
+
+    // Parse -o and --root flags.
+    let mut output_dir = "anonymized".to_string();
+    let mut root: Option<&str> = None;
+    let mut i = 1;
+    while i < args.len() {
+        if args[i] == "-o" && i + 1 < args.len() {
+            output_dir = args[i + 1].clone();
+            i += 2;
+        } else if args[i] == "--root" && i + 1 < args.len() {
+            root = Some(&args[i + 1]);
+            i += 2;
+        } else {
+            return Err(CliError::unknown_argument(&args[i]));
+        }
+    }
+
+    let source = read(path)?;
+    let observer = CliObserver::new(verbosity);
+    let (path, mut options) = compile_options(path, root, EmitOptions::default())?;
+    if verbosity.progress() {
+        options.observer = Some(&observer);
+    }
+    let result = urd_compiler::compile_source_with_options(&path, &source, &OsFileReader, &options);
+    print_diagnostics(&result, verbosity);
+
+    let normalised = path.replace('\\', "/");
+    let (Some(graph), Some(base_dir)) = (&result.graph, base_dir(&normalised, &options)) else {
+        eprintln!("Compilation stopped before LINK; nothing to anonymize.");
+        return Ok(ExitStatus::Failure);
+    };
+
+    let mut written = 0;
+    for (file, node) in &graph.nodes {
+        // Stubs for missing imports have no file behind them.
+        if node.ast.source_hash.is_none() {
+            continue;
+        }
+        let original = read(&format!("{}{}", base_dir, file))?;
+        let output = std::path::Path::new(&output_dir).join(file);
+        if let Some(parent) = output.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| CliError::io("create", parent.display(), e))?;
+        }
+        let anonymized = urd_compiler::anonymize::anonymize_source(&node.ast, &original);
+        std::fs::write(&output, anonymized).map_err(|e| CliError::io("write", output.display(), e))?;
+        written += 1;
+    }
+    eprintln!("{} file{} written to {}", written, if written == 1 { "" } else { "s" }, output_dir);
+    Ok(ExitStatus::from_diagnostics(&result.diagnostics))
+}
+
+// ── Doc command ──
+
+fn run_doc(args: &[String], verbosity: Verbosity) -> Result<ExitStatus, CliError> {
+    if args.is_empty() {
+        return Err(CliError::usage("doc"));
+    }
+
+    let path = &args[0];
+
+    // Parse -o, --format, --date, and --root flags.
+    let mut output_dir = "doc".to_string();
+    let mut options = DocOptions::default();
+    let mut root: Option<&str> = None;
+    let mut i = 1;
+    while i < args.len() {
+        if args[i] == "-o" && i + 1 < args.len() {
+            output_dir = args[i + 1].clone();
+            i += 2;
+        } else if args[i] == "--format" && i + 1 < args.len() {
+            options.format = match args[i + 1].as_str() {
+                "markdown" => DocFormat::Markdown,
+                "html" => DocFormat::Html,
+                other => {
+                    return Err(CliError::new(format!("Unknown format '{}'. Use 'markdown' or 'html'.", other)));
+                }
+            };
+            i += 2;
+        } else if args[i] == "--date" {
+            options.date = Some(today());
+            i += 1;
+        } else if args[i] == "--root" && i + 1 < args.len() {
+            root = Some(&args[i + 1]);
+            i += 2;
+        } else {
+            return Err(CliError::unknown_argument(&args[i]));
+        }
+    }
+
+    let source = read(path)?;
+    let emit = EmitOptions {
+        include_docs: true,
+        ..EmitOptions::default()
+    };
+    let result = compile_with_root(path, &source, root, emit, verbosity)?;
+    print_diagnostics(&result, verbosity);
+
+    let Some(pages) = urd_compiler::docgen::from_compilation(&result, &options) else {
+        eprintln!("Compilation failed; cannot document the world.");
+        return Ok(ExitStatus::Failure);
+    };
+
+    std::fs::create_dir_all(&output_dir).map_err(|e| CliError::io("create", &output_dir, e))?;
+    for page in &pages {
+        let output = std::path::Path::new(&output_dir).join(&page.file);
+        std::fs::write(&output, &page.contents).map_err(|e| CliError::io("write", output.display(), e))?;
+    }
+    eprintln!("{} pages written to {}", pages.len(), output_dir);
+    Ok(ExitStatus::from_diagnostics(&result.diagnostics))
+}
+
+/// Today's UTC date as `YYYY-MM-DD`.
+fn today() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    // Days since 1970-01-01 to a civil date (Howard Hinnant's algorithm).
+    let z = (secs / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+// ── Flow command ──
+
+fn run_flow(args: &[String], verbosity: Verbosity) -> Result<ExitStatus, CliError> {
+    if args.is_empty() {
+        return Err(CliError::usage("flow"));
+    }
+
+    let path = &args[0];
+
+    // Parse -o, --format, --section, and --root flags.
+    let mut output_path: Option<String> = None;
+    let mut format = "dot";
+    let mut section: Option<&str> = None;
+    let mut root: Option<&str> = None;
+    let mut i = 1;
+    while i < args.len() {
+        if args[i] == "-o" && i + 1 < args.len() {
+            output_path = Some(args[i + 1].clone());
+            i += 2;
+        } else if args[i] == "--format" && i + 1 < args.len() {
+            format = match args[i + 1].as_str() {
+                "dot" => "dot",
+                "mermaid" => "mermaid",
+                other => {
+                    return Err(CliError::new(format!("Unknown format '{}'. Use 'dot' or 'mermaid'.", other)));
+                }
+            };
+            i += 2;
+        } else if args[i] == "--section" && i + 1 < args.len() {
+            section = Some(&args[i + 1]);
+            i += 2;
+        } else if args[i] == "--root" && i + 1 < args.len() {
+            root = Some(&args[i + 1]);
+            i += 2;
+        } else {
+            return Err(CliError::unknown_argument(&args[i]));
+        }
+    }
+
+    let source = read(path)?;
+
+    let result = compile_with_root(path, &source, root, EmitOptions::default(), verbosity)?;
+    print_diagnostics(&result, verbosity);
+
+    let Some(mut graph) = urd_compiler::flowgraph::from_compilation(&result) else {
+        eprintln!("Compilation failed; cannot graph the dialogue flow.");
+        return Ok(ExitStatus::Failure);
+    };
+    if let Some(section) = section {
+        graph = graph.component(section).ok_or_else(|| {
+            CliError::new(format!("Unknown section '{}'. Use a compiled ID such as 'file/section'.", section))
+        })?;
+    }
+
+    let rendered = match format {
+        "mermaid" => graph.to_mermaid(),
+        _ => graph.to_dot(),
+    };
+
+    match output_path {
+        Some(output) => {
+            write(&output, &rendered)?;
+            eprintln!("Flow graph written to {}", output);
+        }
+        None => print!("{}", rendered),
+    }
+    Ok(ExitStatus::from_diagnostics(&result.diagnostics))
+}
+
+// ── Play command ──
+
+fn run_play(args: &[String], verbosity: Verbosity) -> Result<ExitStatus, CliError> {
+    if args.is_empty() {
+        return Err(CliError::usage("play"));
+    }
+
+    let path = &args[0];
+
+    // Parse -o and --root flags.
+    let mut output_path: Option<String> = None;
+    let mut root: Option<&str> = None;
+    let mut i = 1;
+    while i < args.len() {
+        if args[i] == "-o" && i + 1 < args.len() {
+            output_path = Some(args[i + 1].clone());
+            i += 2;
+        } else if args[i] == "--root" && i + 1 < args.len() {
+            root = Some(&args[i + 1]);
+            i += 2;
+        } else {
+            return Err(CliError::unknown_argument(&args[i]));
+        }
+    }
+
+    let source = read(path)?;
+    let result = compile_with_root(path, &source, root, EmitOptions::default(), verbosity)?;
+    print_diagnostics(&result, verbosity);
+
+    let page = match urd_compiler::playable::from_compilation(&result) {
+        Ok(page) => page,
+        Err(PlayError::CompilationFailed) => {
+            eprintln!("{}", PlayError::CompilationFailed);
+            return Ok(ExitStatus::Failure);
+        }
+        Err(unsupported) => return Err(CliError::new(unsupported.to_string())),
+    };
+
+    match output_path {
+        Some(output) => {
+            write(&output, &page)?;
+            eprintln!("Playable world written to {}", output);
+        }
+        None => print!("{}", page),
+    }
+    Ok(ExitStatus::from_diagnostics(&result.diagnostics))
+}
+
+// ── Imports command ──
+
+fn run_imports(args: &[String]) -> Result<ExitStatus, CliError> {
+    let (path, mode) = match args {
+        [path] => (path, PlanMode::Shallow),
+        [path, flag] if flag == "--full" => (path, PlanMode::Full),
+        [_, other, ..] => return Err(CliError::unknown_argument(other)),
+        [] => return Err(CliError::usage("imports")),
+    };
+
+    let source = read(path)?;
+    let plan = import::resolve_imports_dry_run(path, &source, &OsFileReader, mode);
+    if !plan.entry_parsed {
+        eprintln!("{} does not parse; compile it to see why.", path);
+        return Ok(ExitStatus::Failure);
+    }
+
+    print!("{}", plan.to_text());
+    Ok(if plan.has_errors() { ExitStatus::Failure } else { ExitStatus::Success })
+}
+
+// ── Locate command ──
+
+fn run_locate(args: &[String], verbosity: Verbosity) -> Result<ExitStatus, CliError> {
+    if args.is_empty() || args[0].starts_with('-') {
+        return Err(CliError::usage("locate"));
+    }
+    let path = &args[0];
+
+    // Parse --line, --file, and --root flags.
+    let mut line: Option<u32> = None;
+    let mut file: Option<&str> = None;
+    let mut root: Option<&str> = None;
+    let mut i = 1;
+    while i < args.len() {
+        if args[i] == "--line" && i + 1 < args.len() {
+            line = Some(
+                args[i + 1]
+                    .parse()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| CliError::new(format!("Invalid line '{}'. Lines count from 1.", args[i + 1])))?,
+            );
+            i += 2;
+        } else if args[i] == "--file" && i + 1 < args.len() {
+            file = Some(&args[i + 1]);
+            i += 2;
+        } else if args[i] == "--root" && i + 1 < args.len() {
+            root = Some(&args[i + 1]);
+            i += 2;
+        } else {
+            return Err(CliError::unknown_argument(&args[i]));
+        }
+    }
+    let Some(line) = line else {
+        return Err(CliError::usage("locate"));
+    };
+
+    let source = read(path)?;
+    let emit = EmitOptions { build_output_map: true, ..EmitOptions::default() };
+    let result = compile_with_root(path, &source, root, emit, verbosity)?;
+    print_diagnostics(&result, verbosity);
+
+    let (Some(map), Some(graph)) = (&result.output_map, &result.graph) else {
+        eprintln!("Compilation failed; there is no output to locate.");
+        return Ok(ExitStatus::Failure);
+    };
+    let file = match file {
+        Some(file) => file.replace('\\', "/"),
+        None => graph.entry_path.clone().unwrap_or_default(),
+    };
+    if !graph.nodes.contains_key(&file) {
+        return Err(CliError::new(format!("'{}' is not part of this compile. Name it as diagnostics do.", file)));
+    }
+
+    let paths = map.paths_at(&file, line);
+    if paths.is_empty() {
+        eprintln!("{}:{} produces no output.", file, line);
+        return Ok(ExitStatus::Failure);
+    }
+    for path in paths {
+        println!("{}", path.pointer());
+    }
+    Ok(ExitStatus::Success)
+}
+
+// ── Fragment command ──
+
+fn run_fragment(args: &[String], verbosity: Verbosity) -> Result<ExitStatus, CliError> {
+    // Parse --kind and -o flags around the snippet path.
+    let mut kind: Option<FragmentKind> = None;
+    let mut path: Option<&str> = None;
+    let mut output_path: Option<String> = None;
+    let mut i = 0;
+    while i < args.len() {
+        if args[i] == "--kind" && i + 1 < args.len() {
+            kind = Some(FragmentKind::from_name(&args[i + 1]).ok_or_else(|| {
+                CliError::new(format!("Unknown kind '{}'. Use 'section' or 'location'.", args[i + 1]))
+            })?);
+            i += 2;
+        } else if args[i] == "-o" && i + 1 < args.len() {
+            output_path = Some(args[i + 1].clone());
+            i += 2;
+        } else if path.is_none() && !args[i].starts_with('-') {
+            path = Some(&args[i]);
+            i += 1;
+        } else {
+            return Err(CliError::unknown_argument(&args[i]));
+        }
+    }
+    let (Some(kind), Some(path)) = (kind, path) else {
+        return Err(CliError::usage("fragment"));
+    };
+
+    let source = read(path)?;
+    let result = urd_compiler::fragment::compile_fragment(kind, &source);
+    // Spans are on the snippet's lines; name the snippet's file.
+    let mut renamed = DiagnosticCollector::new();
+    for d in result.diagnostics.all() {
+        let mut d = d.clone();
+        d.span.file = path.to_string();
+        renamed.emit(d);
+    }
+    if json_diagnostics() {
+        eprintln!("{}", renamed.to_json());
     } else {
-        let source = std::fs::read_to_string(path).unwrap_or_else(|e| {
-            eprintln!("Cannot read '{}': {}", path, e);
-            std::process::exit(1);
-        });
-        let result = urd_compiler::compile_source_with_reader(path, &source, &OsFileReader);
-        print_diagnostics(&result);
-        DiffSnapshot::from_compilation(&result)
+        for d in renamed.sorted() {
+            if verbosity.shows(d.severity) {
+                print_diagnostic(d, Some(&source));
+            }
+        }
+    }
+
+    let Some(json) = &result.json else {
+        if result.success {
+            eprintln!("The snippet declares no {}.", if kind == FragmentKind::Section { "section" } else { "location" });
+        } else {
+            eprintln!("Compilation failed; no fragment output.");
+        }
+        return Ok(ExitStatus::Failure);
+    };
+    let rendered = serde_json::to_string_pretty(json).unwrap();
+    match output_path {
+        Some(output) => {
+            write(&output, &format!("{}\n", rendered))?;
+            eprintln!("Fragment written to {}", output);
+        }
+        None => println!("{}", rendered),
     }
+    Ok(ExitStatus::from_diagnostics(&result.diagnostics))
 }
 
-fn print_diagnostics(result: &urd_compiler::CompilationResult) {
-    for d in result.diagnostics.sorted() {
-        let severity = match d.severity {
-            urd_compiler::diagnostics::Severity::Error => "error",
-            urd_compiler::diagnostics::Severity::Warning => "warning",
-            urd_compiler::diagnostics::Severity::Info => "info",
+// ── Helpers ──
+
+fn read(path: &str) -> Result<String, CliError> {
+    std::fs::read_to_string(path).map_err(|e| CliError::io("read", path, e))
+}
+
+fn write(path: &str, contents: &str) -> Result<(), CliError> {
+    std::fs::write(path, contents).map_err(|e| CliError::io("write", path, e))
+}
+
+/// Write a successful compile's world to `path`, with the trailing blank
+/// line stdout's `println!` gives it, returning the bytes written. A
+/// skipped world is streamed from the graph and symbol table.
+fn write_world(path: &str, result: &urd_compiler::CompilationResult, emit: &EmitOptions) -> std::io::Result<u64> {
+    use std::io::Write;
+
+    let mut file = std::io::BufWriter::new(std::fs::File::create(path)?);
+    match (&result.world, &result.graph, &result.symbol_table) {
+        (Some(json), _, _) => file.write_all(json.as_bytes())?,
+        (None, Some(graph), Some(symbol_table)) => {
+            urd_compiler::emit::emit_to_writer(graph, symbol_table, emit, &mut file)?
+        }
+        _ => unreachable!("a successful compile has a world or the tables to emit it"),
+    }
+    file.write_all(b"\n")?;
+    file.flush()?;
+    Ok(file.get_ref().metadata()?.len())
+}
+
+/// The directory graph and diagnostic paths are relative to: the project
+/// root, or else the entry file's directory. IMPORT reads each file at
+/// this prefix. `None` when the entry lies outside the root.
+fn base_dir(normalised: &str, options: &CompileOptions) -> Option<String> {
+    match &options.project_root {
+        Some(root) => urd_compiler::import::anchor_to_project_root(root, normalised).map(|(dir, _)| dir),
+        None => Some(normalised.rfind('/').map(|pos| normalised[..pos + 1].to_string()).unwrap_or_default()),
+    }
+}
+
+fn current_dir() -> Result<std::path::PathBuf, CliError> {
+    std::env::current_dir().map_err(|e| CliError::new(format!("Cannot determine working directory: {}", e)))
+}
+
+/// Compile a source file, optionally anchored to a project root.
+///
+/// When a root is given, both it and the entry path are made absolute
+/// against the working directory so that relative forms such as
+/// `--root ..` compare correctly.
+fn compile_with_root(
+    path: &str,
+    source: &str,
+    root: Option<&str>,
+    emit: EmitOptions,
+    verbosity: Verbosity,
+) -> Result<urd_compiler::CompilationResult, CliError> {
+    let observer = CliObserver::new(verbosity);
+    let (path, mut options) = compile_options(path, root, emit)?;
+    if verbosity.progress() {
+        options.observer = Some(&observer);
+    }
+    Ok(urd_compiler::compile_source_with_options(&path, source, &OsFileReader, &options))
+}
+
+/// The entry path and options for a compile. With `--root`, both the
+/// entry path and the root are made absolute.
+fn compile_options(
+    path: &str,
+    root: Option<&str>,
+    emit: EmitOptions,
+) -> Result<(String, CompileOptions<'static>), CliError> {
+    let Some(root) = root else {
+        let options = CompileOptions {
+            emit,
+            keep_sources: pretty_diagnostics(),
+            ..CompileOptions::default()
         };
-        eprintln!("[{}] {}: {} ({})", severity, d.span, d.message, d.code);
+        return Ok((path.to_string(), options));
+    };
+
+    let cwd = current_dir()?;
+    let absolute = |p: &str| cwd.join(p).to_string_lossy().to_string();
+
+    let options = CompileOptions {
+        project_root: Some(absolute(root)),
+        emit,
+        keep_sources: pretty_diagnostics(),
+        ..CompileOptions::default()
+    };
+    Ok((absolute(path), options))
+}
+
+/// Cache entries stored as `<key>.json` files in a directory.
+struct DirCacheStore {
+    dir: std::path::PathBuf,
+}
+
+impl CacheStore for DirCacheStore {
+    fn load(&self, key: &str) -> Option<String> {
+        std::fs::read_to_string(self.dir.join(format!("{}.json", key))).ok()
+    }
+
+    fn store(&self, key: &str, contents: &str) -> Result<(), String> {
+        std::fs::create_dir_all(&self.dir).map_err(|e| e.to_string())?;
+        // Write beside the entry, then rename over it, so a concurrent
+        // run never reads a partial entry.
+        let temp = self.dir.join(format!("{}.{}.tmp", key, std::process::id()));
+        std::fs::write(&temp, contents).map_err(|e| e.to_string())?;
+        std::fs::rename(&temp, self.dir.join(format!("{}.json", key))).map_err(|e| {
+            let _ = std::fs::remove_file(&temp);
+            e.to_string()
+        })
+    }
+}
+
+/// Load a DiffSnapshot from either a .urd.md source or a .urd.snapshot.json file.
+fn load_snapshot(path: &str, verbosity: Verbosity) -> Result<DiffSnapshot, CliError> {
+    if path.ends_with(".urd.snapshot.json") {
+        let content = read(path)?;
+        DiffSnapshot::from_json(&content).map_err(|e| match e {
+            DiffError::UnsupportedSnapshotVersion => CliError::new(format!(
+                "Unsupported snapshot version in '{}'. Regenerate with current compiler.",
+                path,
+            )),
+            DiffError::ParseError(msg) => CliError::new(format!("Failed to parse snapshot '{}': {}", path, msg)),
+        })
+    } else {
+        let source = read(path)?;
+        let result = compile_with_root(path, &source, None, EmitOptions::default(), verbosity)?;
+        print_diagnostics(&result, verbosity);
+        Ok(DiffSnapshot::from_compilation(&result))
+    }
+}
+
+fn error_count(result: &urd_compiler::CompilationResult) -> usize {
+    result
+        .diagnostics
+        .all()
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count()
+}
+
+/// Print the result's diagnostics in the chosen style. JSON diagnostics
+/// are one array of all of them, whatever the verbosity.
+fn print_diagnostics(result: &urd_compiler::CompilationResult, verbosity: Verbosity) {
+    if json_diagnostics() {
+        eprintln!("{}", result.diagnostics.to_json());
+        return;
+    }
+    for d in result.diagnostics.sorted() {
+        if !verbosity.shows(d.severity) {
+            continue;
+        }
+        let source = result.sources.as_ref().and_then(|sources| sources.get(&d.span.file));
+        print_diagnostic(d, source.map(String::as_str));
+    }
+}
+
+/// Whether diagnostics are printed with their source lines.
+fn pretty_diagnostics() -> bool {
+    DIAGNOSTIC_STYLE.get() == Some(&DiagnosticStyle::Pretty)
+}
+
+/// Whether diagnostics are printed as a JSON array
+/// (`--diagnostics-format json`).
+fn json_diagnostics() -> bool {
+    DIAGNOSTIC_STYLE.get() == Some(&DiagnosticStyle::Json)
+}
+
+/// Print one diagnostic in the chosen style. Pretty diagnostics are
+/// followed by a blank line; without `source` they fall back to one line.
+fn print_diagnostic(d: &Diagnostic, source: Option<&str>) {
+    match source.filter(|_| pretty_diagnostics()) {
+        Some(source) => eprintln!("{}\n", render::pretty(d, Some(source))),
+        None => eprintln!("{}", render::plain(d)),
     }
 }
@@ -0,0 +1,332 @@
+/// On-disk compilation cache keyed by content hashes.
+///
+/// A cache entry records the compiled output, the diagnostics, the
+/// compiler version, and a manifest: the SHA-256 of the entry source and
+/// of every file IMPORT read. A later compile of the same entry with the
+/// same options re-hashes the manifest files; if every hash and the
+/// version match, the cached output and diagnostics are returned without
+/// running any phase. Anything else is a normal compile that replaces the
+/// entry.
+///
+/// Storage is abstracted by [`CacheStore`] so this module does no file
+/// I/O itself. The CLI provides a directory-backed store; other hosts
+/// supply their own.
+///
+/// Only successful compilations under [`MissingImportPolicy::Error`] are
+/// stored. Under the other policies a missing import compiles, and the
+/// manifest cannot record a file that did not exist.
+
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use serde_json::{json, Map, Value as Json};
+
+use crate::diagnostics::{Diagnostic, DiagnosticCollector, Fix, RelatedInfo, Severity};
+use crate::emit::CompiledWorld;
+use crate::hash::sha256_hex;
+use crate::import::{FileReadError, FileReader, MissingImportPolicy};
+use crate::span::Span;
+use crate::{compile_source_with_options, CompilationResult, CompileOptions};
+
+/// The compiler version recorded in every entry. An entry from another
+/// version is never a hit.
+pub const COMPILER_VERSION: &str = crate::version::VERSION;
+
+/// Key-value storage for cache entries.
+pub trait CacheStore {
+    /// The entry stored under `key`, if any.
+    fn load(&self, key: &str) -> Option<String>;
+
+    /// Replace the entry stored under `key`. A reader must never observe
+    /// a partly written entry: file-backed stores write to a temporary
+    /// file and rename it into place.
+    fn store(&self, key: &str, contents: &str) -> Result<(), String>;
+}
+
+/// How a cached compilation was answered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheStatus {
+    /// The stored entry matched; no phase ran.
+    Hit,
+    /// An entry existed but a file hash or the compiler version differed.
+    Stale,
+    /// No usable entry existed.
+    Miss,
+}
+
+/// The result of [`compile_cached()`].
+pub struct CachedCompilation {
+    /// On a hit, only `success`, `world`, `parts`, and `diagnostics` are
+    /// populated.
+    pub result: CompilationResult,
+    pub status: CacheStatus,
+    /// Set when the refreshed entry could not be stored. The compilation
+    /// itself is unaffected.
+    pub store_error: Option<String>,
+}
+
+/// The store key for an entry file compiled with `options`. Every option
+/// that can change the output is part of the key, so differently
+/// configured builds of the same world keep separate entries.
+pub fn cache_key(filename: &str, options: &CompileOptions) -> String {
+    // The observer only watches and the link cache only saves work;
+    // neither is part of the identity.
+    let options = CompileOptions { observer: None, link_cache: None, ..options.clone() };
+    let identity = format!("{}\0{:?}", filename.replace('\\', "/"), options);
+    sha256_hex(identity.as_bytes())
+}
+
+/// Compile through the cache.
+///
+/// `filename` and `source` are as for [`compile_source_with_options()`];
+/// `reader` resolves imports and is also used to re-hash manifest files.
+pub fn compile_cached(
+    filename: &str,
+    source: &str,
+    reader: &dyn FileReader,
+    options: &CompileOptions,
+    store: &dyn CacheStore,
+) -> CachedCompilation {
+    let key = cache_key(filename, options);
+
+    let status = match store.load(&key).as_deref().and_then(Entry::parse) {
+        Some(entry) if entry.is_current(filename, source, reader) => {
+            return CachedCompilation {
+                result: entry.into_result(),
+                status: CacheStatus::Hit,
+                store_error: None,
+            };
+        }
+        Some(_) => CacheStatus::Stale,
+        None => CacheStatus::Miss,
+    };
+
+    let recorder = RecordingReader { inner: reader, hashes: RefCell::new(BTreeMap::new()) };
+    let result = compile_source_with_options(filename, source, &recorder, options);
+
+    let mut store_error = None;
+    if result.success && !options.skip_world && options.missing_import_policy == MissingImportPolicy::Error {
+        let mut files = recorder.hashes.into_inner();
+        files.insert(filename.to_string(), sha256_hex(source.as_bytes()));
+        let entry = Entry::from_result(files, &result);
+        store_error = store.store(&key, &entry.to_json()).err();
+    }
+
+    CachedCompilation { result, status, store_error }
+}
+
+// ── Recording reader ──
+
+/// Passes reads through to the wrapped reader, hashing each file read.
+struct RecordingReader<'a> {
+    inner: &'a dyn FileReader,
+    hashes: RefCell<BTreeMap<String, String>>,
+}
+
+impl FileReader for RecordingReader<'_> {
+    fn read_file(&self, fs_path: &str) -> Result<String, FileReadError> {
+        let contents = self.inner.read_file(fs_path)?;
+        self.hashes
+            .borrow_mut()
+            .insert(fs_path.to_string(), sha256_hex(contents.as_bytes()));
+        Ok(contents)
+    }
+
+    fn canonical_filename(&self, dir: &str, filename: &str) -> Option<String> {
+        self.inner.canonical_filename(dir, filename)
+    }
+}
+
+// ── Entry format ──
+
+/// One stored compilation.
+///
+/// ```json
+/// {
+///   "version": "0.1.14",
+///   "files": { "main.urd.md": "<sha256>", "lib/types.urd.md": "<sha256>" },
+///   "world": "<compiled .urd.json>",
+///   "parts": { "world.json": "..." },
+///   "diagnostics": [ { "severity": "warning", "code": "URD433", ... } ]
+/// }
+/// ```
+///
+/// `files` maps each path as passed to the reader (the entry file as
+/// passed to [`compile_cached()`]) to the hash of its contents. `parts`
+/// is present only for split output.
+struct Entry {
+    version: String,
+    files: BTreeMap<String, String>,
+    world: String,
+    parts: Option<BTreeMap<String, String>>,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Entry {
+    fn from_result(files: BTreeMap<String, String>, result: &CompilationResult) -> Self {
+        Entry {
+            version: COMPILER_VERSION.to_string(),
+            files,
+            world: result.world.clone().unwrap_or_default(),
+            parts: result.parts.clone(),
+            diagnostics: result.diagnostics.all().to_vec(),
+        }
+    }
+
+    /// Whether the entry still describes the given sources.
+    fn is_current(&self, filename: &str, source: &str, reader: &dyn FileReader) -> bool {
+        if self.version != COMPILER_VERSION {
+            return false;
+        }
+        self.files.iter().all(|(path, hash)| {
+            if path == filename {
+                return &sha256_hex(source.as_bytes()) == hash;
+            }
+            match reader.read_file(path) {
+                Ok(contents) => &sha256_hex(contents.as_bytes()) == hash,
+                Err(_) => false,
+            }
+        })
+    }
+
+    fn into_result(self) -> CompilationResult {
+        let mut diagnostics = DiagnosticCollector::new();
+        for diagnostic in self.diagnostics {
+            diagnostics.emit(diagnostic);
+        }
+        CompilationResult {
+            success: true,
+            world_value: CompiledWorld::parse(&self.world),
+            world: Some(self.world),
+            diagnostics,
+            fact_set: None,
+            property_index: None,
+            definition_index: None,
+            symbol_table: None,
+            symbols: None,
+            graph: None,
+            parts: self.parts,
+            sources: None,
+            output_map: None,
+        }
+    }
+
+    fn to_json(&self) -> String {
+        let mut obj = Map::new();
+        obj.insert("version".to_string(), json!(self.version));
+        obj.insert("files".to_string(), json!(self.files));
+        obj.insert("world".to_string(), json!(self.world));
+        if let Some(parts) = &self.parts {
+            obj.insert("parts".to_string(), json!(parts));
+        }
+        let diagnostics: Vec<Json> = self.diagnostics.iter().map(diagnostic_to_json).collect();
+        obj.insert("diagnostics".to_string(), Json::Array(diagnostics));
+        Json::Object(obj).to_string()
+    }
+
+    /// `None` for anything that is not a well-formed entry.
+    fn parse(text: &str) -> Option<Self> {
+        let root: Json = serde_json::from_str(text).ok()?;
+        Some(Entry {
+            version: root.get("version")?.as_str()?.to_string(),
+            files: string_map(root.get("files")?)?,
+            world: root.get("world")?.as_str()?.to_string(),
+            parts: match root.get("parts") {
+                Some(parts) => Some(string_map(parts)?),
+                None => None,
+            },
+            diagnostics: root
+                .get("diagnostics")?
+                .as_array()?
+                .iter()
+                .map(diagnostic_from_json)
+                .collect::<Option<Vec<_>>>()?,
+        })
+    }
+}
+
+fn string_map(value: &Json) -> Option<BTreeMap<String, String>> {
+    value
+        .as_object()?
+        .iter()
+        .map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+        .collect()
+}
+
+fn diagnostic_to_json(d: &Diagnostic) -> Json {
+    let severity = match d.severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "info",
+    };
+    let related: Vec<Json> = d
+        .related
+        .iter()
+        .map(|r| json!({ "message": r.message, "span": span_to_json(&r.span) }))
+        .collect();
+    json!({
+        "severity": severity,
+        "code": d.code,
+        "message": d.message,
+        "span": span_to_json(&d.span),
+        "suggestion": d.suggestion,
+        "fix": d.fix.as_ref().map(|f| json!({
+            "span": span_to_json(&f.span),
+            "original": f.original,
+            "replacement": f.replacement,
+        })),
+        "related": related,
+    })
+}
+
+fn diagnostic_from_json(value: &Json) -> Option<Diagnostic> {
+    let severity = match value.get("severity")?.as_str()? {
+        "error" => Severity::Error,
+        "warning" => Severity::Warning,
+        "info" => Severity::Info,
+        _ => return None,
+    };
+    let related = value
+        .get("related")?
+        .as_array()?
+        .iter()
+        .map(|r| {
+            Some(RelatedInfo {
+                message: r.get("message")?.as_str()?.to_string(),
+                span: span_from_json(r.get("span")?)?,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    Some(Diagnostic {
+        severity,
+        code: value.get("code")?.as_str()?.to_string(),
+        message: value.get("message")?.as_str()?.to_string(),
+        span: span_from_json(value.get("span")?)?,
+        suggestion: value.get("suggestion")?.as_str().map(str::to_string),
+        fix: match value.get("fix") {
+            None | Some(Json::Null) => None,
+            Some(f) => Some(Fix {
+                span: span_from_json(f.get("span")?)?,
+                original: f.get("original")?.as_str()?.to_string(),
+                replacement: f.get("replacement")?.as_str()?.to_string(),
+            }),
+        },
+        related,
+    })
+}
+
+fn span_to_json(span: &Span) -> Json {
+    json!([span.file, span.start_line, span.start_col, span.end_line, span.end_col])
+}
+
+fn span_from_json(value: &Json) -> Option<Span> {
+    let parts = value.as_array()?;
+    let number = |i: usize| -> Option<u32> { u32::try_from(parts.get(i)?.as_u64()?).ok() };
+    Some(Span::new(
+        parts.first()?.as_str()?.to_string(),
+        number(1)?,
+        number(2)?,
+        number(3)?,
+        number(4)?,
+    ))
+}
@@ -0,0 +1,551 @@
+/// The `urd` command-line contract: exit statuses, diagnostic verbosity,
+/// and help text.
+///
+/// The binary does the file I/O. This module holds what scripts depend
+/// on, so each part is defined once and tested here.
+///
+/// Every subcommand ends with one of three exit codes:
+///
+/// | Code | Meaning |
+/// |------|---------|
+/// | 0 | Success, or no changes found. |
+/// | 1 | The diagnostics include errors, or changes were found. |
+/// | 2 | The command could not run: bad arguments, or a file could not be read or written. |
+///
+/// `--quiet` and `--verbose` apply to every subcommand. Quiet prints only
+/// error diagnostics. Verbose prints a progress line as each phase starts
+/// and finishes and as each file is parsed.
+///
+/// `--pretty-diagnostics` and `--plain-diagnostics` also apply to every
+/// subcommand. Pretty diagnostics show the source line under each one
+/// (see [`crate::diagnostics::render`]); they are the default when stderr
+/// is a terminal. `--diagnostics-format json` prints them instead as one
+/// JSON array per compile, on one line of stderr, in the shape of
+/// [`DiagnosticCollector::to_json()`]. The array holds every diagnostic,
+/// whatever the verbosity.
+
+use std::fmt;
+
+use crate::diagnostics::{DiagnosticCollector, Severity};
+
+/// How a command ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitStatus {
+    /// Exit code 0.
+    Success,
+    /// Exit code 1: errors in the diagnostics, or changes found.
+    Failure,
+    /// Exit code 2: a usage or I/O failure.
+    Error,
+}
+
+impl ExitStatus {
+    pub fn code(self) -> u8 {
+        match self {
+            ExitStatus::Success => 0,
+            ExitStatus::Failure => 1,
+            ExitStatus::Error => 2,
+        }
+    }
+
+    /// `Failure` if any diagnostic is an error, else `Success`.
+    pub fn from_diagnostics(diagnostics: &DiagnosticCollector) -> Self {
+        if diagnostics.has_errors() {
+            ExitStatus::Failure
+        } else {
+            ExitStatus::Success
+        }
+    }
+}
+
+/// A command that could not run. The message is printed to stderr and
+/// the process exits with code 2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CliError {
+    pub message: String,
+}
+
+impl CliError {
+    pub fn new(message: impl Into<String>) -> Self {
+        CliError { message: message.into() }
+    }
+
+    /// `Usage: ...` for `command`, from its help entry.
+    pub fn usage(command: &str) -> Self {
+        let usage = find(command).map_or("urd --help", |help| help.usage);
+        CliError::new(format!("Usage: {}", usage))
+    }
+
+    pub fn unknown_argument(argument: &str) -> Self {
+        CliError::new(format!("Unknown argument '{}'", argument))
+    }
+
+    /// `Cannot <verb> '<path>': <error>`.
+    pub fn io(verb: &str, path: impl fmt::Display, error: impl fmt::Display) -> Self {
+        CliError::new(format!("Cannot {} '{}': {}", verb, path, error))
+    }
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// How much a command prints to stderr.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Verbosity {
+    /// Error diagnostics only.
+    Quiet,
+    #[default]
+    Normal,
+    /// Every diagnostic, and per-phase progress lines.
+    Verbose,
+}
+
+impl Verbosity {
+    /// Whether a diagnostic of `severity` is printed.
+    pub fn shows(self, severity: Severity) -> bool {
+        self != Verbosity::Quiet || severity == Severity::Error
+    }
+
+    /// Whether per-phase progress lines are printed.
+    pub fn progress(self) -> bool {
+        self == Verbosity::Verbose
+    }
+}
+
+/// Remove `--quiet` and `--verbose` from `args`, wherever they appear,
+/// and return the verbosity they set with the remaining arguments.
+pub fn take_verbosity(args: &[String]) -> Result<(Verbosity, Vec<String>), CliError> {
+    let quiet = args.iter().any(|a| a == "--quiet");
+    let verbose = args.iter().any(|a| a == "--verbose");
+    let verbosity = match (quiet, verbose) {
+        (true, true) => return Err(CliError::new("Use either --quiet or --verbose, not both.")),
+        (true, false) => Verbosity::Quiet,
+        (false, true) => Verbosity::Verbose,
+        (false, false) => Verbosity::Normal,
+    };
+    let rest = args.iter().filter(|a| *a != "--quiet" && *a != "--verbose").cloned().collect();
+    Ok((verbosity, rest))
+}
+
+/// How diagnostics are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticStyle {
+    /// One line each: `[error] file:12:5: message (URD301)`.
+    Plain,
+    /// With the source line underlined and the suggestion after it.
+    Pretty,
+    /// One JSON array of every diagnostic, for tools
+    /// (`--diagnostics-format json`).
+    Json,
+}
+
+/// Remove `--pretty-diagnostics`, `--plain-diagnostics`, and
+/// `--diagnostics-format <text|json>` from `args` and return the style
+/// they set with the remaining arguments. With none of them, or with
+/// `text` alone, the style is pretty when `terminal` (stderr is a
+/// terminal).
+pub fn take_diagnostic_style(args: &[String], terminal: bool) -> Result<(DiagnosticStyle, Vec<String>), CliError> {
+    let mut json = false;
+    let mut rest = Vec::new();
+    let mut i = 0;
+    while i < args.len() {
+        if args[i] == "--diagnostics-format" {
+            json = match args.get(i + 1).map(String::as_str) {
+                Some("json") => true,
+                Some("text") => false,
+                _ => return Err(CliError::new("--diagnostics-format takes 'text' or 'json'.")),
+            };
+            i += 2;
+        } else {
+            rest.push(args[i].clone());
+            i += 1;
+        }
+    }
+    let pretty = rest.iter().any(|a| a == "--pretty-diagnostics");
+    let plain = rest.iter().any(|a| a == "--plain-diagnostics");
+    let style = match (pretty, plain) {
+        (true, true) => {
+            return Err(CliError::new("Use either --pretty-diagnostics or --plain-diagnostics, not both."))
+        }
+        (true, false) | (false, true) if json => {
+            return Err(CliError::new("--pretty-diagnostics and --plain-diagnostics do not apply to JSON diagnostics."))
+        }
+        (false, false) if json => DiagnosticStyle::Json,
+        (true, false) => DiagnosticStyle::Pretty,
+        (false, true) => DiagnosticStyle::Plain,
+        (false, false) if terminal => DiagnosticStyle::Pretty,
+        (false, false) => DiagnosticStyle::Plain,
+    };
+    rest.retain(|a| a != "--pretty-diagnostics" && a != "--plain-diagnostics");
+    Ok((style, rest))
+}
+
+// ── Help ──
+
+/// The help entry for one subcommand.
+#[derive(Debug)]
+pub struct CommandHelp {
+    /// The name `urd --help <name>` takes.
+    pub name: &'static str,
+    /// One-line synopsis, without the `Usage: ` prefix.
+    pub usage: &'static str,
+    /// The description and options, indented as printed.
+    pub details: &'static str,
+}
+
+/// Every subcommand, in the order `urd --help` lists them.
+pub const COMMANDS: &[CommandHelp] = &[
+    CommandHelp {
+        name: "compile",
+        usage: "urd <file.urd.md> [-o|--output [output.urd.json]] [--root <dir>] [--emit-keys] [--include-docs] [--numeric-ids] [--split-output <dir>] [--cache-dir <dir>] [--timings] [--text-lint] [--no-generator] [--define <flag>] [--meta [<path>]] [--reproducible]",
+        details: "  <file.urd.md>    Compile a .urd.md file and emit .urd.json to stdout.
+                   Diagnostics are printed to stderr.
+                   Exit code 0 on success, 1 on errors.
+
+      -o, --output [<path>]  Write the world to <path> instead of stdout,
+                          creating its directory if needed. Bare, it goes
+                          beside the source (world.urd.md gives
+                          world.urd.json). Never overwrites the input.
+
+      --root <dir>        Project root. Imports may reach anywhere inside
+                          it; paths in diagnostics are relative to it.
+                          Defaults to the entry file's directory.
+      --emit-keys         Replace player-visible text with string table
+                          keys (see the strings command).
+      --include-docs      Add /// doc comments as \"doc\" fields on types,
+                          entities, locations, and sections.
+      --numeric-ids       Add an \"idx\" integer to every type, entity,
+                          location, rule, action, sequence, phase, section,
+                          and choice, and an \"index\" block mapping string
+                          IDs to them.
+      --split-output <dir>  Write one JSON file per block group (world,
+                          locations, rules, actions, sequences, dialogue)
+                          plus manifest.json to <dir> instead of stdout.
+      --cache-dir <dir>   Reuse the previous result from <dir> when the
+                          entry file, its imports, the options, and the
+                          compiler version are unchanged.
+      --timings           Print the time spent in each phase and the
+                          number of files parsed to stderr.
+      --text-lint         Check player-visible text for doubled words,
+                          repeated spaces, and unmatched brackets or
+                          quotes (URD701-URD703, info).
+      --no-generator      Leave out the world block's \"generator\" field,
+                          which names the compiler version, so the output
+                          does not change when only the compiler does.
+      --define <flag>     Set a flag for ?if guards, besides the world
+                          block's flags. Repeat for several flags.
+      --meta [<path>]     Write build metadata to <path>: diagnostics,
+                          source file hashes, options, stats, and phase
+                          timings, as JSON. Bare, it goes beside the -o
+                          output (world.urd.json gives world.urd.meta.json).
+                          Written even when compilation fails. Bypasses
+                          --cache-dir.
+      --reproducible      Leave the timings out of the metadata, so it is
+                          the same for the same sources and options.
+",
+    },
+    CommandHelp {
+        name: "project",
+        usage: "urd [--profile <name>] [OPTIONS]",
+        details: "  (no file)        Compile the project described by the nearest urd.toml,
+                   in the current directory or a parent. The manifest
+                   names the entry file (entry = \"...\"), the output
+                   file (out = \"...\", default stdout), and default
+                   values for the compile options above, keyed by flag
+                   name without the dashes. Flags given on the command
+                   line win. Paths are relative to the manifest.
+                   Exit code 0 on success, 1 on errors, 2 if no
+                   manifest is found or it cannot be read.
+
+      --profile <name>    Apply the [profile.<name>] table over the
+                          manifest's top-level options.
+",
+    },
+    CommandHelp {
+        name: "watch",
+        usage: "urd watch <file.urd.md> [-o output.urd.json] [--root <dir>] [--emit-keys] [--include-docs] [--numeric-ids] [--no-generator] [--cache-dir <dir>]",
+        details: "  watch <file>     Compile, then recompile whenever the file or anything
+                   it imports changes. Each compile clears the screen and
+                   reprints the diagnostics. The output file is written
+                   only when a compile succeeds. Ctrl-C stops.
+
+      -o <path>           Output path. Defaults to <file>.urd.json.
+      --root <dir>        Project root (as for compile).
+      --emit-keys         As for compile.
+      --include-docs      As for compile.
+      --numeric-ids       As for compile.
+      --no-generator      As for compile.
+      --cache-dir <dir>   As for compile.
+",
+    },
+    CommandHelp {
+        name: "diff",
+        usage: "urd diff <file_a> <file_b> [--format json|summary]",
+        details: "  diff <a> <b>     Compare two compilations and report changes.
+                   Each argument can be a .urd.md file (compiled on the
+                   fly) or a .urd.snapshot.json file.
+                   Exit code 0 if no changes, 1 if changes detected,
+                   2 if a file cannot be read.
+
+      --format <FORMAT>   Output format: json (default) or summary.
+",
+    },
+    CommandHelp {
+        name: "snapshot",
+        usage: "urd snapshot <file.urd.md> [-o output.snapshot.json] [--root <dir>]",
+        details: "  snapshot <file>  Create a .urd.snapshot.json from a .urd.md file.
+                   Snapshots capture entities, locations, exits, sections,
+                   choices, rules, properties, and diagnostics for use
+                   with the diff command.
+                   Exit code 0 on success, 1 on errors.
+
+      -o <path>           Output path. Defaults to <file>.urd.snapshot.json.
+      --root <dir>        Project root (as for compile).
+",
+    },
+    CommandHelp {
+        name: "stats",
+        usage: "urd stats <file.urd.md> [--format table|json] [--root <dir>]",
+        details: "  stats <file>     Report world complexity metrics: locations, exits,
+                   entities per type, sections, choices, word counts,
+                   nesting depth, conditions, effects, and analysis counts.
+                   Exit code 0 on success, 1 on errors.
+
+      --format <FORMAT>   Output format: table (default) or json.
+      --root <dir>        Project root (as for compile).
+",
+    },
+    CommandHelp {
+        name: "strings",
+        usage: "urd strings <file.urd.md> [-o output] [--format json|csv|po] [--root <dir>]",
+        details: "  strings <file>   Export every player-visible string (descriptions,
+                   prompts, speech, choice labels, responses, blocked
+                   messages, on_exhausted text) with stable keys, source
+                   file, and line. Exit code 0 on success, 1 on errors.
+
+      -o <path>           Output path. Defaults to stdout.
+      --format <FORMAT>   Output format: json (default), csv, or po.
+      --root <dir>        Project root (as for compile).
+",
+    },
+    CommandHelp {
+        name: "symbols",
+        usage: "urd symbols <file.urd.md> [-o output] [--root <dir>]",
+        details: "  symbols <file>   Export the symbol table as JSON: types and their
+                   properties, entities, locations with exits and
+                   contents, regions, sections with choices, actions,
+                   rules, sequences, and duplicate declarations, each
+                   with its declaration span. Written even when
+                   VALIDATE reports errors. Exit code 0 on success,
+                   1 on errors.
+
+      -o <path>           Output path. Defaults to stdout.
+      --root <dir>        Project root (as for compile).
+",
+    },
+    CommandHelp {
+        name: "fix",
+        usage: "urd fix <file.urd.md> [--dry-run] [--root <dir>]",
+        details: "  fix <file>       Apply the fixes the compiler is sure of: a misspelt
+                   reference with exactly one close match (URD301) and
+                   import filename casing (URD206). Then recompile and
+                   report the error counts before and after. A file
+                   with overlapping fixes is left unchanged.
+                   Exit code 0 if no errors remain, 1 otherwise.
+
+      --dry-run           Print a unified diff instead of writing files.
+      --root <dir>        Project root (as for compile).
+",
+    },
+    CommandHelp {
+        name: "anonymize",
+        usage: "urd anonymize <file.urd.md> [-o <dir>] [--root <dir>]",
+        details: "  anonymize <file> Copy every file of the project with its prose,
+                   speech, stage directions, and blocked messages
+                   replaced by lorem-<hash> placeholders, for sharing
+                   a reproduction case. IDs, choice labels, frontmatter,
+                   conditions, and effects are kept, so the copy
+                   compiles to the same structure and diagnostics.
+                   Exit code 0 on success, 1 on errors.
+
+      -o <dir>            Output directory. Defaults to ./anonymized.
+      --root <dir>        Project root (as for compile).
+",
+    },
+    CommandHelp {
+        name: "doc",
+        usage: "urd doc <file.urd.md> [-o <dir>] [--format markdown|html] [--date] [--root <dir>]",
+        details: "  doc <file>       Write a reference of the world for readers who do not
+                   read .urd.md: an index plus one page each for types,
+                   entities, locations, and dialogue, with /// doc
+                   comments and an outline of where each choice leads.
+                   Anchors come from compiled IDs, so links survive
+                   regeneration. Exit code 0 on success, 1 on errors.
+
+      -o <dir>            Output directory. Defaults to ./doc.
+      --format <FORMAT>   Output format: markdown (default) or html.
+      --date              Print today's date on the index. Off by default
+                          so unchanged worlds regenerate identically.
+      --root <dir>        Project root (as for compile).
+",
+    },
+    CommandHelp {
+        name: "flow",
+        usage: "urd flow <file.urd.md> [--format dot|mermaid] [--section <id>] [-o output] [--root <dir>]",
+        details: "  flow <file>      Graph the dialogue flow: sections as nodes, and choice
+                   jumps, on_exhausted jumps, and phase sections as
+                   edges. Edges carry the choice label, marked * for
+                   one-shot and + for sticky choices. Node order is
+                   fixed, so regenerated graphs diff cleanly.
+                   Exit code 0 on success, 1 on errors.
+
+      --format <FORMAT>   Output format: dot (default) or mermaid.
+      --section <id>      Keep only the sections connected to this one,
+                          by compiled ID.
+      -o <path>           Output path. Defaults to stdout.
+      --root <dir>        Project root (as for compile).
+",
+    },
+    CommandHelp {
+        name: "play",
+        usage: "urd play <file.urd.md> [-o output] [--root <dir>]",
+        details: "  play <file>      Export the world as one self-contained HTML page
+                   that clicks through it: locations, exits, and dialogue,
+                   with set and move effects applied as you go. Not a
+                   runtime: a world with rules, sequences, location
+                   hooks, or other effects and conditions is refused
+                   with a list of what it uses.
+                   Exit code 0 on success, 1 on errors, 2 when the world
+                   cannot be played.
+
+      -o <path>           Output path. Defaults to stdout.
+      --root <dir>        Project root (as for compile).
+",
+    },
+    CommandHelp {
+        name: "imports",
+        usage: "urd imports <file.urd.md> [--full]",
+        details: "  imports <file>   List the files a compile would read, without
+                   compiling. Each import shows the file it resolves to,
+                   or the diagnostic code that would reject it. Only the
+                   entry file's own imports are planned, and nothing else
+                   is read, unless --full is given.
+                   Exit code 0 if every import resolves, 1 otherwise.
+
+      --full              Read each imported file's frontmatter and plan
+                          its imports too.
+",
+    },
+    CommandHelp {
+        name: "locate",
+        usage: "urd locate <file.urd.md> --line <n> [--file <path>] [--root <dir>]",
+        details: "  locate <file>    Compile, then print the JSON pointer of each object
+                   the line produced, innermost first, so an effect in a
+                   rule comes before the rule. Prose, text, and
+                   conditions are not located.
+                   Exit code 0 if the line produced output, 1 if it
+                   produced none or compilation failed.
+
+      --line <n>          The line, counting from 1. Required.
+      --file <path>       A file of the compile other than the entry, by
+                          the path its diagnostics show.
+      --root <dir>        Project root (as for compile).
+",
+    },
+    CommandHelp {
+        name: "fragment",
+        usage: "urd fragment --kind section|location <file> [-o output]",
+        details: "  fragment <file>  Compile a snippet holding one section or location
+                   without a whole world, and print its emitted JSON.
+                   The snippet may open with frontmatter declaring
+                   types and entities; the world block is supplied.
+                   Undeclared @entity references are warnings.
+                   Diagnostics use the snippet's own line numbers.
+                   Exit code 0 on success, 1 on errors.
+
+      --kind <KIND>       What the snippet holds: section or location.
+                          Required.
+      -o <path>           Output path. Defaults to stdout.
+",
+    },
+    CommandHelp {
+        name: "explain",
+        usage: "urd explain <code> | --list",
+        details: "  explain <code>   Print what a diagnostic code such as URD433 means,
+                   and a link to its documentation.
+                   Exit code 2 for an unknown code.
+
+      --list              List every code with its title.
+",
+    },
+    CommandHelp {
+        name: "schema",
+        usage: "urd schema [-o output]",
+        details: "  schema           Print the JSON Schema (draft 2020-12) that compiled
+                   .urd.json worlds conform to.
+
+      -o <path>           Output path. Defaults to stdout.
+",
+    },
+];
+
+const OPTIONS: &str = "OPTIONS:
+  -h, --help [<command>]
+                   Print this help message, or one command's, and exit.
+  -V, --version [--json]
+                   Print the compiler version and the world format
+                   versions it emits, and exit. --json prints
+                   {name, version, formats, features}.
+      --quiet      Print only error diagnostics. Any command.
+      --verbose    Print a line as each phase starts and finishes and as
+                   each file is parsed. Any command.
+      --pretty-diagnostics
+                   Show each diagnostic's source line, underlined, and its
+                   suggestion. The default when stderr is a terminal.
+      --plain-diagnostics
+                   Print each diagnostic on one line. The default otherwise.
+      --diagnostics-format text|json
+                   json prints every diagnostic as one JSON array on one
+                   line of stderr: severity, code, message, span (file,
+                   start_line, start_col, end_line, end_col), and
+                   suggestion when there is one. Any command.
+
+EXIT CODES:
+  0  Success, or no changes found.
+  1  The diagnostics include errors, or changes were found.
+  2  Bad arguments, or a file could not be read or written.
+";
+
+/// The help entry for `name`.
+pub fn find(name: &str) -> Option<&'static CommandHelp> {
+    COMMANDS.iter().find(|help| help.name == name)
+}
+
+/// Help text: every command when `command` is `None`, else that one
+/// command's usage and details.
+pub fn help(command: Option<&str>) -> Result<String, CliError> {
+    let Some(name) = command else {
+        let mut out = format!("urd {} — Schema Markdown compiler\n\n", env!("CARGO_PKG_VERSION"));
+        out.push_str("Compiles .urd.md files through a six-phase pipeline\n");
+        out.push_str("(PARSE → IMPORT → LINK → ANALYZE → VALIDATE → EMIT) to produce .urd.json.\n\n");
+        out.push_str("USAGE:\n");
+        for help in COMMANDS {
+            out.push_str(&format!("  {}\n", help.usage));
+        }
+        out.push_str("  urd --help [<command>]\n  urd --version | -V [--json]\n\nCOMMANDS:\n");
+        for help in COMMANDS {
+            out.push_str(help.details);
+            out.push('\n');
+        }
+        out.push_str(OPTIONS);
+        return Ok(out);
+    };
+    let help = find(name).ok_or_else(|| {
+        let names: Vec<&str> = COMMANDS.iter().map(|help| help.name).collect();
+        CliError::new(format!("Unknown command '{}'. Commands: {}.", name, names.join(", ")))
+    })?;
+    Ok(format!("Usage: {}\n\n{}\n{}", help.usage, help.details, OPTIONS))
+}
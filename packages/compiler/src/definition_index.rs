@@ -27,10 +27,14 @@ pub enum DefinitionKind {
     },
     Location {
         display_name: String,
+        /// Description paragraphs, as EMIT writes them.
+        description: Vec<String>,
     },
     Exit {
         from_location: String,
+        /// The resolved location ID, or the destination as written.
         destination: String,
+        resolved: bool,
     },
     Choice {
         section_id: String,
@@ -44,6 +48,8 @@ pub enum DefinitionKind {
 pub struct DefinitionEntry {
     pub span: Span,
     pub kind: DefinitionKind,
+    /// Author `///` documentation (types, entities, sections, locations).
+    pub doc: Option<String>,
 }
 
 /// Index mapping namespace-prefixed keys to their declaration sites.
@@ -69,6 +75,7 @@ impl DefinitionIndex {
                 format!("type:{}", name),
                 DefinitionEntry {
                     span: sym.declared_in.clone(),
+                    doc: sym.doc.clone(),
                     kind: DefinitionKind::Type,
                 },
             );
@@ -80,6 +87,7 @@ impl DefinitionIndex {
                     format!("prop:{}.{}", name, prop_name),
                     DefinitionEntry {
                         span: prop_sym.declared_in.clone(),
+                        doc: None,
                         kind: DefinitionKind::Property {
                             type_name: name.clone(),
                             property_type: prop_sym.raw_type_string.clone(),
@@ -96,6 +104,7 @@ impl DefinitionIndex {
                 format!("entity:@{}", id),
                 DefinitionEntry {
                     span: sym.declared_in.clone(),
+                    doc: sym.doc.clone(),
                     kind: DefinitionKind::Entity {
                         type_name: sym.type_name.clone(),
                     },
@@ -109,6 +118,7 @@ impl DefinitionIndex {
                 format!("section:{}", compiled_id),
                 DefinitionEntry {
                     span: sym.declared_in.clone(),
+                    doc: sym.doc.clone(),
                     kind: DefinitionKind::Section {
                         local_name: sym.local_name.clone(),
                         file_stem: sym.file_stem.clone(),
@@ -122,6 +132,7 @@ impl DefinitionIndex {
                     format!("choice:{}", choice_sym.compiled_id),
                     DefinitionEntry {
                         span: choice_sym.declared_in.clone(),
+                        doc: None,
                         kind: DefinitionKind::Choice {
                             section_id: compiled_id.clone(),
                             label: choice_sym.label.clone(),
@@ -137,8 +148,10 @@ impl DefinitionIndex {
                 format!("location:{}", slug),
                 DefinitionEntry {
                     span: sym.declared_in.clone(),
+                    doc: sym.doc.clone(),
                     kind: DefinitionKind::Location {
                         display_name: sym.display_name.clone(),
+                        description: sym.description.iter().map(|p| p.text.clone()).collect(),
                     },
                 },
             );
@@ -153,9 +166,11 @@ impl DefinitionIndex {
                     format!("exit:{}/{}", slug, direction),
                     DefinitionEntry {
                         span: exit_sym.declared_in.clone(),
+                        doc: None,
                         kind: DefinitionKind::Exit {
                             from_location: slug.clone(),
                             destination: destination.to_string(),
+                            resolved: exit_sym.resolved_destination.is_some(),
                         },
                     },
                 );
@@ -168,6 +183,7 @@ impl DefinitionIndex {
                 format!("rule:{}", name),
                 DefinitionEntry {
                     span: sym.declared_in.clone(),
+                    doc: None,
                     kind: DefinitionKind::Rule,
                 },
             );
@@ -230,16 +246,23 @@ impl DefinitionIndex {
                         "local_name": local_name,
                         "file_stem": file_stem,
                     }),
-                    DefinitionKind::Location { display_name } => {
-                        serde_json::json!({ "kind": "location", "display_name": display_name })
-                    }
+                    DefinitionKind::Location {
+                        display_name,
+                        description,
+                    } => serde_json::json!({
+                        "kind": "location",
+                        "display_name": display_name,
+                        "description": description,
+                    }),
                     DefinitionKind::Exit {
                         from_location,
                         destination,
+                        resolved,
                     } => serde_json::json!({
                         "kind": "exit",
                         "from_location": from_location,
                         "destination": destination,
+                        "resolved": resolved,
                     }),
                     DefinitionKind::Choice { section_id, label } => serde_json::json!({
                         "kind": "choice",
@@ -249,7 +272,7 @@ impl DefinitionIndex {
                     DefinitionKind::Rule => serde_json::json!({ "kind": "rule" }),
                 };
 
-                serde_json::json!({
+                let mut json = serde_json::json!({
                     "key": key,
                     "span": {
                         "file": entry.span.file,
@@ -259,7 +282,11 @@ impl DefinitionIndex {
                         "end_col": entry.span.end_col,
                     },
                     "definition": kind_json,
-                })
+                });
+                if let Some(doc) = &entry.doc {
+                    json["doc"] = serde_json::json!(doc);
+                }
+                json
             })
             .collect();
 
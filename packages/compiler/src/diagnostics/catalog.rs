@@ -0,0 +1,909 @@
+/// Diagnostic code catalog: a title, an explanation, and a documentation
+/// link for every code the compiler emits.
+///
+/// The LSP attaches each code's link to the diagnostics it publishes, and
+/// `urd explain` prints the explanation. Entries mirror the diagnostic code
+/// reference (`docs/urd-diagnostic-codes.md`). A test checks that every
+/// code literal in the compiler source has an entry here and a row there.
+
+/// The reference page. Each code has an anchor on it named by its slug.
+pub const DOCS_URL: &str = "https://urd.dev/documents/diagnostic-codes";
+
+/// A diagnostic code and what it means.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodeInfo {
+    pub code: &'static str,
+    /// Short title, as in the reference table without its gate tag.
+    pub title: &'static str,
+    /// What triggers the code, in a sentence or three.
+    pub explanation: &'static str,
+    /// Anchor on the reference page: the code in lower case (`urd433`).
+    /// Stable once published.
+    pub slug: &'static str,
+}
+
+impl CodeInfo {
+    /// Link to this code's entry on the reference page.
+    pub fn docs_url(&self) -> String {
+        format!("{}#{}", DOCS_URL, self.slug)
+    }
+}
+
+/// Every diagnostic code, in code order.
+pub const CATALOG: &[CodeInfo] = &[
+    CodeInfo {
+        code: "URD100",
+        title: "File cannot be read",
+        explanation: "The entry file passed to the compiler could not be read: it is missing, unreadable, or not valid UTF-8. Compilation stops before PARSE.",
+        slug: "urd100",
+    },
+    CodeInfo {
+        code: "URD101",
+        title: "Unclosed frontmatter block",
+        explanation: "Opening `---` found but no closing `---` before end of file.",
+        slug: "urd101",
+    },
+    CodeInfo {
+        code: "URD102",
+        title: "Tab character in source",
+        explanation: "A line contains one or more tab characters. Urd requires spaces for indentation. Emitted per tab found.",
+        slug: "urd102",
+    },
+    CodeInfo {
+        code: "URD103",
+        title: "File exceeds size limit",
+        explanation: "Source file is larger than 1 MB (1,048,576 bytes). Also emitted during IMPORT for imported files.",
+        slug: "urd103",
+    },
+    CodeInfo {
+        code: "URD104",
+        title: "Frontmatter nesting too deep",
+        explanation: "A frontmatter entry exceeds 8 levels of indentation nesting.",
+        slug: "urd104",
+    },
+    CodeInfo {
+        code: "URD105",
+        title: "YAML anchor rejected",
+        explanation: "An `&identifier` anchor pattern was detected in frontmatter. Urd does not support YAML anchors.",
+        slug: "urd105",
+    },
+    CodeInfo {
+        code: "URD106",
+        title: "YAML alias rejected",
+        explanation: "A `*identifier` alias pattern was detected in frontmatter. Urd does not support YAML aliases.",
+        slug: "urd106",
+    },
+    CodeInfo {
+        code: "URD107",
+        title: "YAML merge key rejected",
+        explanation: "A `<<:` merge key was detected in frontmatter. Urd does not support YAML merge keys.",
+        slug: "urd107",
+    },
+    CodeInfo {
+        code: "URD108",
+        title: "YAML custom tag rejected",
+        explanation: "A `!!type` custom tag was detected in frontmatter. Urd does not support YAML custom tags.",
+        slug: "urd108",
+    },
+    CodeInfo {
+        code: "URD109",
+        title: "Block-style list rejected",
+        explanation: "A `- item` block-style list was detected in frontmatter. Use flow-style `[item1, item2]` instead.",
+        slug: "urd109",
+    },
+    CodeInfo {
+        code: "URD111",
+        title: "Unrecognised frontmatter syntax",
+        explanation: "A frontmatter line could not be parsed as any valid entry pattern.",
+        slug: "urd111",
+    },
+    CodeInfo {
+        code: "URD112",
+        title: "Unrecognised content syntax",
+        explanation: "A content line could not be parsed as any valid block type (location, section, choice, condition, effect, entity speech, jump, etc.). Fallback after all grammar rules fail.",
+        slug: "urd112",
+    },
+    CodeInfo {
+        code: "URD113",
+        title: "Unbalanced or malformed guard",
+        explanation: "A `?if flag:` line has no matching `?endif`, an `?endif` has no `?if`, a `?if` line is not `?if flag:` or `?if !flag:`, or a guard is nested two levels deep. Reported at the guard line whatever the compile's flags.",
+        slug: "urd113",
+    },
+    CodeInfo {
+        code: "URD114",
+        title: "Odd indentation",
+        explanation: "A content line is indented by an odd number of spaces. Indent levels are two spaces each, so the stray space is dropped: three spaces read as level 1. Reported at the last indent space.",
+        slug: "urd114",
+    },
+    CodeInfo {
+        code: "URD115",
+        title: "Indent jumps more than one level",
+        explanation: "A content line is indented two or more levels deeper than the line above it, or than its parent choice. It keeps the level it is written at, which can nest a choice or its conditions and effects differently from how the author meant.",
+        slug: "urd115",
+    },
+    CodeInfo {
+        code: "URD116",
+        title: "Variant line with nothing to vary",
+        explanation: "A `| ...` line does not directly follow a speech line, a line of prose, or another variant at the same indent. Variants attach only to the line above them, with no blank line between. The line is kept as prose, including its `|`.",
+        slug: "urd116",
+    },
+    CodeInfo {
+        code: "URD201",
+        title: "Imported file not found",
+        explanation: "The file referenced by an `import:` declaration does not exist on disk. A warning when `CompileOptions::missing_import_policy` is `WarnAndSkip` (file omitted) or `Stub` (empty file substituted); references into the file then surface as URD301.",
+        slug: "urd201",
+    },
+    CodeInfo {
+        code: "URD202",
+        title: "Circular import detected",
+        explanation: "A cycle was found in the import graph. The full cycle path is reported (e.g. `a → b → c → a`).",
+        slug: "urd202",
+    },
+    CodeInfo {
+        code: "URD203",
+        title: "File stem collision",
+        explanation: "Two or more files in the compilation unit produce the same stem after stripping the `.urd.md` extension (e.g. `foo/bar.urd.md` and `baz/bar.urd.md`). Section IDs would collide.",
+        slug: "urd203",
+    },
+    CodeInfo {
+        code: "URD204",
+        title: "Import depth limit exceeded",
+        explanation: "The import chain exceeds 64 levels deep.",
+        slug: "urd204",
+    },
+    CodeInfo {
+        code: "URD205",
+        title: "Compilation unit too large",
+        explanation: "More than 256 files discovered in the compilation unit.",
+        slug: "urd205",
+    },
+    CodeInfo {
+        code: "URD206",
+        title: "Filename casing mismatch",
+        explanation: "The import path differs in casing from the file discovered on disk (case-insensitive filesystem). The compiler uses the discovered casing. An error when another file in the same directory differs from the discovered one only in case, since the import could load either.",
+        slug: "urd206",
+    },
+    CodeInfo {
+        code: "URD207",
+        title: "Self-import",
+        explanation: "A file's `import:` declaration references itself.",
+        slug: "urd207",
+    },
+    CodeInfo {
+        code: "URD208",
+        title: "Import escapes project root",
+        explanation: "After resolving `..` segments, the import path would resolve outside the project root directory. The project root is the entry file's directory unless set explicitly (`--root`, `CompileOptions.project_root`, or the LSP workspace folder). Also emitted when the entry file itself lies outside an explicit root.",
+        slug: "urd208",
+    },
+    CodeInfo {
+        code: "URD209",
+        title: "Absolute import path",
+        explanation: "The import path is absolute (starts with `/` or a drive letter like `C:`). Imports must be relative.",
+        slug: "urd209",
+    },
+    CodeInfo {
+        code: "URD210",
+        title: "Missing .urd.md extension",
+        explanation: "The import path does not end with `.urd.md`.",
+        slug: "urd210",
+    },
+    CodeInfo {
+        code: "URD211",
+        title: "Empty import path",
+        explanation: "The `import:` declaration has an empty path string.",
+        slug: "urd211",
+    },
+    CodeInfo {
+        code: "URD212",
+        title: "Invalid UTF-8",
+        explanation: "The imported file contains invalid UTF-8 byte sequences.",
+        slug: "urd212",
+    },
+    CodeInfo {
+        code: "URD213",
+        title: "Permission denied",
+        explanation: "The compiler cannot read the imported file due to OS-level permissions.",
+        slug: "urd213",
+    },
+    CodeInfo {
+        code: "URD214",
+        title: "I/O error",
+        explanation: "A generic I/O error occurred while reading the imported file.",
+        slug: "urd214",
+    },
+    CodeInfo {
+        code: "URD215",
+        title: "Case-insensitive stem collision",
+        explanation: "Two file stems differ only in letter case (compared with Unicode case folding, e.g. `Café` and `café`). On the case-insensitive file systems macOS and Windows use by default, such files cannot share a directory and one shadows the other. An error when `CompileOptions::strict_filename_case` is set.",
+        slug: "urd215",
+    },
+    CodeInfo {
+        code: "URD301",
+        title: "Unresolved reference",
+        explanation: "An `@entity`, type name, location, or property access could not be resolved. Includes scope violations (declared in a file that is not imported). Suggestions offered via edit distance when available.",
+        slug: "urd301",
+    },
+    CodeInfo {
+        code: "URD302",
+        title: "Duplicate entity, rule, or ending ID",
+        explanation: "Two entities, two rules, or two endings share the same ID across the compilation unit. Both declaration sites are reported.",
+        slug: "urd302",
+    },
+    CodeInfo {
+        code: "URD303",
+        title: "Duplicate type name",
+        explanation: "Two type definitions share the same name across the compilation unit. Both declaration sites are reported.",
+        slug: "urd303",
+    },
+    CodeInfo {
+        code: "URD304",
+        title: "Duplicate location ID",
+        explanation: "Two `# Location` headings produce the same location ID, whether slugified or set explicitly with `{#id}`. Both display names and the colliding ID are reported.",
+        slug: "urd304",
+    },
+    CodeInfo {
+        code: "URD305",
+        title: "Duplicate section name",
+        explanation: "Two `== section` labels share the same name (or explicit `{#id}`) within a single file. Section names must be unique per file.",
+        slug: "urd305",
+    },
+    CodeInfo {
+        code: "URD306",
+        title: "Duplicate choice ID",
+        explanation: "Two choices within the same section produce the same slugified ID. Both labels and the colliding slug are reported.",
+        slug: "urd306",
+    },
+    CodeInfo {
+        code: "URD307",
+        title: "Unknown entity type",
+        explanation: "An `@entity: TypeName` declaration, a section parameter `== name(@param: TypeName)`, or a choice target `-> any TypeName` references a type that does not exist in the symbol table. Edit distance suggestions offered.",
+        slug: "urd307",
+    },
+    CodeInfo {
+        code: "URD308",
+        title: "Unknown property on type",
+        explanation: "A property override or property access references a property that does not exist on the entity's declared type. Suggests the type's closest property, or lists up to five of its properties when none is close.",
+        slug: "urd308",
+    },
+    CodeInfo {
+        code: "URD309",
+        title: "Unresolved jump target or section",
+        explanation: "A `->` jump target, exhaustion check, or phase `section:` line references a section or exit name that does not exist in the current scope. When the name is the label of a section with an explicit `{#id}`, the ID is suggested; an exhaustion check otherwise suggests the closest section in the current file. A file-qualified name (`-> tavern/topics`) must name a section in the current file or a direct import; otherwise the import hint or the closest compiled section ID is suggested.",
+        slug: "urd309",
+    },
+    CodeInfo {
+        code: "URD310",
+        title: "Section shadows exit",
+        explanation: "A section name matches an exit direction name in the same location. Jumps to that name will target the section, not the exit. Use `-> exit:name` for the exit.",
+        slug: "urd310",
+    },
+    CodeInfo {
+        code: "URD311",
+        title: "Unresolved exit-qualified jump",
+        explanation: "A `-> exit:name` jump references an exit direction that does not exist in the current location.",
+        slug: "urd311",
+    },
+    CodeInfo {
+        code: "URD312",
+        title: "Unresolved exit destination",
+        explanation: "An exit declaration's destination does not resolve to any known location. Includes scope violations. When the destination is the derived form of a heading with an explicit `{#id}`, the ID is suggested.",
+        slug: "urd312",
+    },
+    CodeInfo {
+        code: "URD313",
+        title: "Empty slugified ID",
+        explanation: "A heading or declaration produces an empty string after slugification.",
+        slug: "urd313",
+    },
+    CodeInfo {
+        code: "URD314",
+        title: "Construct outside location context",
+        explanation: "An exit declaration, entity presence list, location hook, or exit-qualified jump appears before any `# Location` heading.",
+        slug: "urd314",
+    },
+    CodeInfo {
+        code: "URD315",
+        title: "Duplicate location hook",
+        explanation: "A location declares more than one `on enter:` block, or more than one `on exit:` block.",
+        slug: "urd315",
+    },
+    CodeInfo {
+        code: "URD316",
+        title: "Invalid explicit ID",
+        explanation: "A `{#id}` override is malformed. Location IDs must be lowercase ASCII letters and digits joined by single hyphens (`{#cafe-bar}`). Section IDs follow section name rules: a lowercase letter, then lowercase letters, digits, or underscores (`{#intro_topics}`).",
+        slug: "urd316",
+    },
+    CodeInfo {
+        code: "URD317",
+        title: "Parameterised section entered without argument",
+        explanation: "A jump, exhaustion check, or phase `-> section` names a parameterised section without passing an entity. Use `-> name(@entity)`. A bare `-> name` or `? name.exhausted` inside the section's own body is allowed and refers to the current instance.",
+        slug: "urd317",
+    },
+    CodeInfo {
+        code: "URD318",
+        title: "Unexpected jump argument",
+        explanation: "A jump passes `(@entity)` to a target that takes no parameter: an unparameterised section, `end`, or an exit.",
+        slug: "urd318",
+    },
+    CodeInfo {
+        code: "URD319",
+        title: "Nested parameterised invocation",
+        explanation: "A jump inside a parameterised section passes an argument to another parameterised section. Parameterised sections cannot invoke parameterised sections.",
+        slug: "urd319",
+    },
+    CodeInfo {
+        code: "URD320",
+        title: "Duplicate phase ID",
+        explanation: "Two `### Phase` headings in the same sequence produce the same slugified ID. The first phase is kept; the colliding display name and the first phase's line are reported.",
+        slug: "urd320",
+    },
+    CodeInfo {
+        code: "URD321",
+        title: "Unresolved location in visited check",
+        explanation: "A `? visited` or `? !visited` condition names a location that does not exist or is declared in a file that is not imported. Suggests the location's explicit ID when the name is its derived form, otherwise the closest location ID within edit distance 2.",
+        slug: "urd321",
+    },
+    CodeInfo {
+        code: "URD322",
+        title: "Choice named like its section",
+        explanation: "A choice's slugified label equals the local name of its enclosing section, so its ID repeats the name: `* Topics` under `== topics` becomes `tavern/topics/topics`. Tools that read the last segment of an ID may take the choice for the section.",
+        slug: "urd322",
+    },
+    CodeInfo {
+        code: "URD323",
+        title: "Choice named like exit",
+        explanation: "A choice's slugified label equals an exit direction of the enclosing location. The choice counterpart of URD310: input matched against the name may pick the choice instead of the exit.",
+        slug: "urd323",
+    },
+    CodeInfo {
+        code: "URD324",
+        title: "Unbound select variable",
+        explanation: "A `$name` reference in a condition or effect that no select clause binds: it is used outside a rule, in a rule without a `selects` line, or under a name other than the rule's select variable. The suggestion names the variable when the rule has one.",
+        slug: "urd324",
+    },
+    CodeInfo {
+        code: "URD325",
+        title: "Select candidates of different types",
+        explanation: "A rule reads or writes a declared property through its select variable, but the `from` list holds entities of different types, so there is no one type to check the property against. Implicit properties such as `container`, moves and destroys need no type and are allowed.",
+        slug: "urd325",
+    },
+    CodeInfo {
+        code: "URD326",
+        title: "Unresolved ending",
+        explanation: "An `> end` effect names no ending declared in an `endings:` block, or names one declared in a file that is not imported. Suggests the closest ending within edit distance 2, with a fix when exactly one is closest.",
+        slug: "urd326",
+    },
+    CodeInfo {
+        code: "URD327",
+        title: "Presence override replaces declaration override",
+        explanation: "A presence line overrides a property the entity's declaration also sets: `[@cell_door (locked: false)]` with `@cell_door: Door { locked: true }`. The presence line's value is used.",
+        slug: "urd327",
+    },
+    CodeInfo {
+        code: "URD328",
+        title: "Property overridden on two presence lines",
+        explanation: "Two presence lines override the same property of the same entity, in one location or two. The entity exists once, so only one line may set it. The first line's value is kept and reported as related information.",
+        slug: "urd328",
+    },
+    CodeInfo {
+        code: "URD329",
+        title: "No speaker to inherit",
+        explanation: "A quoted line inside a choice, or after a section's choices, takes its speaker from the first speech line of the section's prompt. This one is outside any section, or in a section whose prompt has no speech line, so it has no speaker. Name one with '@name: ...', or give the prompt a speech line.",
+        slug: "urd329",
+    },
+    CodeInfo {
+        code: "URD330",
+        title: "Reserved property name",
+        explanation: "A type declares a property named `container` or `exhausted`. Conditions and effects give these names a built-in meaning (`@entity.container` is where the entity is; `name.exhausted` tests a section), so the property could never be read or written. Rename the property.",
+        slug: "urd330",
+    },
+    CodeInfo {
+        code: "URD331",
+        title: "Reserved or malformed entity ID",
+        explanation: "An entity ID is one of `here`, `end`, `exit`, `world`, or `not`, or contains `.`, `/`, or whitespace. Lowered conditions and effects name entities bare, so such an ID reads as grammar. `@player` is the player entity and is not reserved.",
+        slug: "urd331",
+    },
+    CodeInfo {
+        code: "URD332",
+        title: "Type named like a property type",
+        explanation: "A type's name equals a property type keyword (`string`, `ref`, `list`, and the rest), ignoring case, so a declaration such as `ref(String)` reads as either. Rename the type.",
+        slug: "urd332",
+    },
+    CodeInfo {
+        code: "URD333",
+        title: "Location ID matches an entity ID",
+        explanation: "A location has the same ID as an entity. Lowered conditions and effects name both bare (`player.container == hall`), so a runtime cannot tell which is meant. Give the location an explicit ID or rename the entity.",
+        slug: "urd333",
+    },
+    CodeInfo {
+        code: "URD334",
+        title: "Reference resolves only transitively",
+        explanation: "Under the transitive scope policy (`scope: transitive` in the world block, or `CompileOptions::scope_policy`), a reference resolved to a declaration in a file this one reaches only through another import. The message names the declaring file. Import it directly to make the dependency explicit; the world compiles either way.",
+        slug: "urd334",
+    },
+    CodeInfo {
+        code: "URD401",
+        title: "Type mismatch (condition/effect)",
+        explanation: "A property comparison value or set-effect value does not match the property's declared type. Used in conditions and effects.",
+        slug: "urd401",
+    },
+    CodeInfo {
+        code: "URD402",
+        title: "Invalid enum override",
+        explanation: "An entity's property override specifies an enum value that is not in the type's declared values list.",
+        slug: "urd402",
+    },
+    CodeInfo {
+        code: "URD404",
+        title: "Invalid world.start",
+        explanation: "The `world.start` value matches no declared location ID, either as written or slugified (`Cell` names `cell`). When it names a heading that has an explicit `{#id}`, the ID is suggested; otherwise the closest location ID, with the display name it comes from when the value was written as a name.",
+        slug: "urd404",
+    },
+    CodeInfo {
+        code: "URD405",
+        title: "Invalid world.entry",
+        explanation: "The `world.entry` value matches no declared sequence ID, either as written or slugified. The closest sequence ID is suggested.",
+        slug: "urd405",
+    },
+    CodeInfo {
+        code: "URD406",
+        title: "Mutual exclusion: target + target_type",
+        explanation: "A choice declares both a `target` (entity/section) and a `target_type` (type selector). Only one is allowed. Related information points at the target's and the type's declarations.",
+        slug: "urd406",
+    },
+    CodeInfo {
+        code: "URD407",
+        title: "Unknown action in phase",
+        explanation: "A sequence phase references an action ID that does not exist.",
+        slug: "urd407",
+    },
+    CodeInfo {
+        code: "URD408",
+        title: "Unknown rule in phase",
+        explanation: "A sequence phase references a rule name that does not exist.",
+        slug: "urd408",
+    },
+    CodeInfo {
+        code: "URD409",
+        title: "Invalid advance mode",
+        explanation: "A sequence phase declares an advance mode that is not one of: `on_action`, `on_rule`, `on_condition`, `end`, `auto`, `manual`.",
+        slug: "urd409",
+    },
+    CodeInfo {
+        code: "URD410",
+        title: "Choice nesting depth",
+        explanation: "A choice is nested too deeply. Warning at depth 3, error at depth 4+.",
+        slug: "urd410",
+    },
+    CodeInfo {
+        code: "URD411",
+        title: "Author set `urd` field",
+        explanation: "The author explicitly set the `urd:` field in the world block. This field is injected automatically and the author's value will be overridden.",
+        slug: "urd411",
+    },
+    CodeInfo {
+        code: "URD412",
+        title: "Player entity missing traits",
+        explanation: "The `@player` entity's type is missing required `mobile` and/or `container` traits.",
+        slug: "urd412",
+    },
+    CodeInfo {
+        code: "URD413",
+        title: "Invalid property default",
+        explanation: "A type definition's property default value does not match the property's declared type, or is a number that is not finite (`inf`, `NaN`), which JSON cannot hold. For an enum, the message lists the declared values.",
+        slug: "urd413",
+    },
+    CodeInfo {
+        code: "URD414",
+        title: "Empty enum values list",
+        explanation: "An enum property declares `enum()` with no values.",
+        slug: "urd414",
+    },
+    CodeInfo {
+        code: "URD415",
+        title: "Unknown ref target type",
+        explanation: "A `ref(TypeName)` property references a type name that does not exist. A close match among the declared types is suggested.",
+        slug: "urd415",
+    },
+    CodeInfo {
+        code: "URD416",
+        title: "Invalid range: min > max",
+        explanation: "A numeric property declares a minimum value greater than its maximum.",
+        slug: "urd416",
+    },
+    CodeInfo {
+        code: "URD417",
+        title: "Range on non-numeric type",
+        explanation: "Range constraints (`min`/`max`) are declared on a property that is not integer or number. The range is ignored and left out of the compiled world.",
+        slug: "urd417",
+    },
+    CodeInfo {
+        code: "URD418",
+        title: "Value outside declared range",
+        explanation: "A numeric value (in override, condition, or effect) is outside the property's declared `[min, max]` range.",
+        slug: "urd418",
+    },
+    CodeInfo {
+        code: "URD419",
+        title: "Ref type mismatch",
+        explanation: "A `ref(TypeName)` property references an entity whose type does not match the declared ref constraint.",
+        slug: "urd419",
+    },
+    CodeInfo {
+        code: "URD420",
+        title: "Invalid comparison operator",
+        explanation: "A comparison uses an operator its property type does not allow. Integers and numbers allow `==`, `!=`, `<`, `>`, `<=`, `>=`; every other type allows only `==` and `!=`. The message lists the allowed operators.",
+        slug: "urd420",
+    },
+    CodeInfo {
+        code: "URD422",
+        title: "Missing container trait",
+        explanation: "An entity is used as a container (in a containment check or move destination) but its type does not have the `container` trait.",
+        slug: "urd422",
+    },
+    CodeInfo {
+        code: "URD423",
+        title: "Cross-file exhaustion check",
+        explanation: "An exhaustion check references a section that is not declared in the current file. Unqualified exhaustion checks are file-local; use the file-qualified form (`? tavern/topics.exhausted`) for a section in an imported file.",
+        slug: "urd423",
+    },
+    CodeInfo {
+        code: "URD424",
+        title: "Arithmetic on non-numeric property",
+        explanation: "An arithmetic effect operator (`+` or `-`) is used on a property that is not integer or number.",
+        slug: "urd424",
+    },
+    CodeInfo {
+        code: "URD425",
+        title: "Move without portable trait",
+        explanation: "A `move` effect targets an entity whose type does not have the `portable` trait.",
+        slug: "urd425",
+    },
+    CodeInfo {
+        code: "URD426",
+        title: "Reveal on non-hidden property",
+        explanation: "A `reveal` effect targets a property that is not marked as hidden. The reveal has no effect.",
+        slug: "urd426",
+    },
+    CodeInfo {
+        code: "URD427",
+        title: "Auto phase with player actions",
+        explanation: "A sequence phase is marked `auto` but also declares player actions. The actions will never be available.",
+        slug: "urd427",
+    },
+    CodeInfo {
+        code: "URD428",
+        title: "Empty sequence",
+        explanation: "A sequence declares no phases.",
+        slug: "urd428",
+    },
+    CodeInfo {
+        code: "URD429",
+        title: "Unrecognised property type",
+        explanation: "A property's type string is not one of the recognised types (`bool`, `int`, `num`, `str`, `enum`, `ref`, `list` or long forms). Treated as `string`.",
+        slug: "urd429",
+    },
+    CodeInfo {
+        code: "URD430",
+        title: "Unreachable location",
+        explanation: "A location has no path from `world.start` via exits. Silent in a dialogue world, which reports its locations as URD461. Also used in PARSE for unparseable type definitions.",
+        slug: "urd430",
+    },
+    CodeInfo {
+        code: "URD431",
+        title: "Section shadows built-in jump",
+        explanation: "A section named `end` shadows the built-in `-> end` terminal. Jumps will always end the conversation, not jump to the section.",
+        slug: "urd431",
+    },
+    CodeInfo {
+        code: "URD432",
+        title: "Orphaned choice",
+        explanation: "A choice's condition requires an enum value that is not in the type's declared values list, meaning the choice can never be available. Also used in PARSE for unparseable entity declarations.",
+        slug: "urd432",
+    },
+    CodeInfo {
+        code: "URD433",
+        title: "Missing fallthrough",
+        explanation: "A section contains only one-shot choices with no terminal jump or fallthrough text. It will exhaust to an empty state.",
+        slug: "urd433",
+    },
+    CodeInfo {
+        code: "URD434",
+        title: "Section-exit shadowing",
+        explanation: "A section label in a location shares a name with an exit direction. Jumps will target the section. Use `-> exit:name` for the exit.",
+        slug: "urd434",
+    },
+    CodeInfo {
+        code: "URD435",
+        title: "Condition outside declared range",
+        explanation: "A numeric property comparison can never be true given the property's declared `min`/`max` (e.g. `> 150` when `max: 100`). The message names the range. Not emitted when the property declares no range.",
+        slug: "urd435",
+    },
+    CodeInfo {
+        code: "URD436",
+        title: "Condition always satisfied",
+        explanation: "A numeric property comparison is true for every value in the property's declared range (e.g. `>= 0` when `min: 0`).",
+        slug: "urd436",
+    },
+    CodeInfo {
+        code: "URD437",
+        title: "World field has wrong type",
+        explanation: "`world.seed` is not an integer, `world.start`, `world.entry`, or `world.version` is not a string, `world.mode` is not `world` or `dialogue`, `world.scope` is not `direct` or `transitive`, or `world.start_inventory` is not a list of `@entity` references. The message names the field and the expected type.",
+        slug: "urd437",
+    },
+    CodeInfo {
+        code: "URD438",
+        title: "Start inventory entity not portable",
+        explanation: "An entity listed in `world.start_inventory` has a type without the `portable` trait. The player holds it anyway, but runtimes cannot move it out again.",
+        slug: "urd438",
+    },
+    CodeInfo {
+        code: "URD439",
+        title: "Start inventory entity also placed",
+        explanation: "An entity in `world.start_inventory` is also listed in a location's entity presence line. An entity starts in exactly one place. Related information points at the presence line.",
+        slug: "urd439",
+    },
+    CodeInfo {
+        code: "URD440",
+        title: "Redundant override",
+        explanation: "An entity property override equals the type's default value (compared by value, so `1` matches `1.0`). The override has no effect and can be removed. Only reported for overrides that pass type checking.",
+        slug: "urd440",
+    },
+    CodeInfo {
+        code: "URD441",
+        title: "Unknown trait",
+        explanation: "A type declares a trait that is not built in (`container`, `portable`, `mobile`, `interactable`). Suggests the closest built-in trait within edit distance 2. Reported as info instead when the world block sets `allow_custom_traits: true`.",
+        slug: "urd441",
+    },
+    CodeInfo {
+        code: "URD442",
+        title: "Section argument type mismatch",
+        explanation: "A jump `-> name(@entity)` passes an entity whose type differs from the declared parameter type of the parameterised section.",
+        slug: "urd442",
+    },
+    CodeInfo {
+        code: "URD443",
+        title: "Text dropped from output",
+        explanation: "A prose, speech or stage direction line sits where EMIT places no text: prose after a location's description has ended, text between a section's choices, a second speech line after the last choice, or prose inside a choice. The message says where the text would be kept.",
+        slug: "urd443",
+    },
+    CodeInfo {
+        code: "URD444",
+        title: "Once on a sticky choice",
+        explanation: "A `+` choice has a `? once` condition. A sticky choice is never consumed and a once choice is offered only once, so the two contradict. Use `*` for a one-shot choice.",
+        slug: "urd444",
+    },
+    CodeInfo {
+        code: "URD445",
+        title: "Once outside a choice",
+        explanation: "`? once` appears somewhere other than directly under a choice: at section level, in a `? any:` block, on an exit, or in a location hook.",
+        slug: "urd445",
+    },
+    CodeInfo {
+        code: "URD446",
+        title: "Property collides with group",
+        explanation: "A type declares a property whose name is also the prefix of a dotted property, such as `appearance` alongside `appearance.height`. EMIT nests dotted properties into a group object of that name, so the scalar has nowhere to go. Related information points at the group member.",
+        slug: "urd446",
+    },
+    CodeInfo {
+        code: "URD447",
+        title: "Unknown exit direction",
+        explanation: "An exit's direction is not in the movement vocabulary: the eight compass directions, `up`, `down`, `in` and `out`, plus any listed in the world block's `directions:` field. Suggests the closest known direction within edit distance 2. A world with its own movement verbs, such as `port` and `starboard`, lists them in `directions:`.",
+        slug: "urd447",
+    },
+    CodeInfo {
+        code: "URD448",
+        title: "Reciprocal exits share a direction",
+        explanation: "Two locations lead to each other through exits with the same direction, such as `north` both ways. The return exit usually goes the opposite way. Only directions with an opposite are checked. Related information points at the other exit.",
+        slug: "urd448",
+    },
+    CodeInfo {
+        code: "URD449",
+        title: "Speaker has no display name",
+        explanation: "An entity speaks in dialogue but has no display name: no `name` override, and its type has no string `name` property with a default. Runtimes show it by its ID. Give the type a `name: string` property or set `name` on the entity.",
+        slug: "urd449",
+    },
+    CodeInfo {
+        code: "URD450",
+        title: "Entity placed in more than one location",
+        explanation: "An entity is listed in the entity presence lines of two or more locations, so it would start in several places at once. The message names every location, and related information points at each listing. Keep it in one list and use `> move` if it should travel. `@player` is exempt (see URD452).",
+        slug: "urd450",
+    },
+    CodeInfo {
+        code: "URD451",
+        title: "Entity listed twice in one location",
+        explanation: "An entity appears more than once in the same location's presence lines. It is placed there once and the repeat is ignored. Related information points at the first listing.",
+        slug: "urd451",
+    },
+    CodeInfo {
+        code: "URD452",
+        title: "Player listed in a location",
+        explanation: "`@player` appears in a location's entity presence line. The player's starting position comes from `world.start`, so the listing has no effect on where the game begins. Remove it.",
+        slug: "urd452",
+    },
+    CodeInfo {
+        code: "URD453",
+        title: "Bare condition on a non-boolean property",
+        explanation: "A condition names a property without comparing it, as in `? @guard.mood` or `? !@guard.mood`, but the property is not boolean. Only a boolean property can be tested bare, meaning `== true` (or `== false` with `!`). Write an explicit comparison such as `? @guard.mood == angry`.",
+        slug: "urd453",
+    },
+    CodeInfo {
+        code: "URD454",
+        title: "Unknown rule trigger",
+        explanation: "A rule's trigger is not one the runtime fires, so the rule would never run. The known triggers are `phase_is <phase>`, `action <action>`, `enter <location>`, `state_change <entity.property>`, and `always`, which takes no argument. A trigger kind the runtime adds can be listed in the world block's `triggers:` field.",
+        slug: "urd454",
+    },
+    CodeInfo {
+        code: "URD455",
+        title: "Too many conditions",
+        explanation: "A `? any:` block, a run of adjacent `?` lines, or a rule's `where` clauses holds so many conditions that it is slow to compile and evaluate and hard to read. Warning at 32 conditions, error at 256. Track the outcome in a property that an effect sets, and test that property instead.",
+        slug: "urd455",
+    },
+    CodeInfo {
+        code: "URD456",
+        title: "Player destroyed",
+        explanation: "A `destroy` effect targets `@player`. The player must exist for the world to run; finish the game with an ending (`> end <ending>`) instead.",
+        slug: "urd456",
+    },
+    CodeInfo {
+        code: "URD457",
+        title: "Effect after destroy",
+        explanation: "An effect refers to an entity that an earlier `destroy` in the same choice, exit, hook, or rule already destroyed. The message names both effect positions, counting effects from 1; related information points at the destroy. Unresolved effects take no part.",
+        slug: "urd457",
+    },
+    CodeInfo {
+        code: "URD458",
+        title: "Ref compared with a non-entity",
+        explanation: "A condition compares a `ref` property with a value that is not an entity reference (`@name`), or with one that names no declared entity. A ref of the wrong type is URD419.",
+        slug: "urd458",
+    },
+    CodeInfo {
+        code: "URD459",
+        title: "Duplicate enum value",
+        explanation: "An enum property lists the same value more than once. Each repeated value is named once in the message.",
+        slug: "urd459",
+    },
+    CodeInfo {
+        code: "URD460",
+        title: "Target type has no entities",
+        explanation: "A choice targets `-> any TypeName`, but no entity in the world has that type, so the action never has anything to bind to and can never be taken.",
+        slug: "urd460",
+    },
+    CodeInfo {
+        code: "URD461",
+        title: "Location in a dialogue world",
+        explanation: "The world block sets `mode: dialogue`, but the world declares a location heading. The message gives the location's exit count. A dialogue world has no locations or exits.",
+        slug: "urd461",
+    },
+    CodeInfo {
+        code: "URD501",
+        title: "Text variants dropped",
+        explanation: "A speech line or prose has `| ...` variants, but the world is emitted in format 1, which has no way to carry them. Only the line as written is emitted. Emitting format 2 (`EmitOptions::format`) writes the text as `{\"variants\": [...]}` instead. The string table lists every variant either way.",
+        slug: "urd501",
+    },
+    CodeInfo {
+        code: "URD601",
+        title: "Property read but never written",
+        explanation: "A property is tested in conditions, but no effect anywhere writes it. Every condition on it sees the declared default.",
+        slug: "urd601",
+    },
+    CodeInfo {
+        code: "URD602",
+        title: "Property written but never read",
+        explanation: "Effects change a property that no condition tests. The writes have no observable consequence.",
+        slug: "urd602",
+    },
+    CodeInfo {
+        code: "URD603",
+        title: "Enum variant never tested",
+        explanation: "An effect sets an enum property to a variant that no condition compares against. Nothing reacts to the value.",
+        slug: "urd603",
+    },
+    CodeInfo {
+        code: "URD604",
+        title: "Unreachable threshold",
+        explanation: "A condition compares a numeric property against a value that no `=` effect produces. Skipped when `+` or `-` effects write the property, since they could accumulate to any value.",
+        slug: "urd604",
+    },
+    CodeInfo {
+        code: "URD605",
+        title: "Circular property dependency",
+        explanation: "Every write to a property is guarded by a condition that reads the same property, and no unguarded write exists. The property can only change once it has already changed.",
+        slug: "urd605",
+    },
+    CodeInfo {
+        code: "URD606",
+        title: "Parameterised section never invoked",
+        explanation: "A section declared `== name(@param: Type)` is the target of no `-> name(@entity)` jump, so EMIT produces no instance of it.",
+        slug: "urd606",
+    },
+    CodeInfo {
+        code: "URD607",
+        title: "Phases after an end phase",
+        explanation: "A phase whose advance mode is `end` is followed by more phases in the same sequence. The later phases can never run; each one is listed.",
+        slug: "urd607",
+    },
+    CodeInfo {
+        code: "URD608",
+        title: "Stalled phase condition",
+        explanation: "A phase advancing `on_condition` reads a property that no effect writes. The condition never changes, so the sequence stays in that phase.",
+        slug: "urd608",
+    },
+    CodeInfo {
+        code: "URD609",
+        title: "Entity placed nowhere",
+        explanation: "An entity is in no location's entity list and not in `world.start_inventory`, so it exists nowhere when the game begins. Silent for `@player`, for entities a `> move` effect brings into the world, and for types with an `abstract` or `conceptual` trait or a trait named in the world block's `unplaced_traits:` list. Silent in a dialogue world (`mode: dialogue`).",
+        slug: "urd609",
+    },
+    CodeInfo {
+        code: "URD610",
+        title: "Exit effects with no gate",
+        explanation: "An exit has effects but no condition and no blocked message, so the effects run on every traversal. Usually a condition or message is missing. Effects meant to run every time the player leaves read more plainly as an `on exit:` hook.",
+        slug: "urd610",
+    },
+    CodeInfo {
+        code: "URD611",
+        title: "Exit effect flips its own condition",
+        explanation: "An exit's `=` effect sets the same entity's property that the exit's condition reads. If the new value fails the condition, the exit blocks itself after one traversal. If it is exactly the value the condition requires, the effect changes nothing on traversal, and would only unblock the exit on a runtime that applied effects on a blocked attempt. Exit effects run only when the exit is traversed.",
+        slug: "urd611",
+    },
+    CodeInfo {
+        code: "URD612",
+        title: "Empty opening",
+        explanation: "The `world.start` location has no exits, no entities in its entity list, and no sections after its heading, so the player begins somewhere with nothing to do. A warning when it has no description either; info when the description is all it has. Silent when `world.start` is missing or unresolved.",
+        slug: "urd612",
+    },
+    CodeInfo {
+        code: "URD613",
+        title: "Ending never reached",
+        explanation: "A declared ending is named by no `> end` effect, so no playthrough can finish there.",
+        slug: "urd613",
+    },
+    CodeInfo {
+        code: "URD614",
+        title: "Ending reached only from unreachable locations",
+        explanation: "Every `> end` effect naming the ending sits in a location that URD430 reports unreachable: no path of exits from the start location leads there. An effect in a rule counts as reachable. Silent when `world.start` is missing or unresolved.",
+        slug: "urd614",
+    },
+    CodeInfo {
+        code: "URD615",
+        title: "Too many choices in one section",
+        explanation: "A section has more than 20 choices directly under it. Choices nested inside other choices do not count. A list this long is hard to read at once; splitting the section or grouping choices under a few that lead on usually helps. The limit is `CompileOptions::size_limits`, and `lints: [URD615]` in the world block turns the check off.",
+        slug: "urd615",
+    },
+    CodeInfo {
+        code: "URD616",
+        title: "Long prose before the first choice",
+        explanation: "A section has more than 500 words of prose before its first choice, all of which becomes its description. A run this long often means a section label is missing. Reported at the label; the message gives the line where the prose ends. The limit is `CompileOptions::size_limits`, and `lints: [URD616]` in the world block turns the check off.",
+        slug: "urd616",
+    },
+    CodeInfo {
+        code: "URD617",
+        title: "Too many exits from one location",
+        explanation: "A location has more than 10 exits. The limit is `CompileOptions::size_limits`, and `lints: [URD617]` in the world block turns the check off.",
+        slug: "urd617",
+    },
+    CodeInfo {
+        code: "URD701",
+        title: "Doubled word",
+        explanation: "Player-visible text has the same word twice in a row with only whitespace between, ignoring case (`the the`). Reported by text lint, which runs only when asked for (`--text-lint`, or in Forge).",
+        slug: "urd701",
+    },
+    CodeInfo {
+        code: "URD702",
+        title: "Consecutive spaces",
+        explanation: "Player-visible text has two or more spaces in a row. Often a trailing space before a line break. Reported by text lint.",
+        slug: "urd702",
+    },
+    CodeInfo {
+        code: "URD703",
+        title: "Unmatched bracket or quote",
+        explanation: "Player-visible text has a `(`, `[`, `{`, or curly quote with no partner, or an odd number of straight double quotes. Single quotes are not checked, since they double as apostrophes. Reported by text lint.",
+        slug: "urd703",
+    },
+];
+
+/// Look up a code, such as `"URD433"`.
+pub fn lookup(code: &str) -> Option<&'static CodeInfo> {
+    CATALOG.iter().find(|c| c.code == code)
+}
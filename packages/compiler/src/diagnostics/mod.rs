@@ -15,8 +15,13 @@
 /// | EMIT     | URD500–URD599 |
 /// | ANALYZE (FactSet) | URD600–URD699 |
 
+use serde_json::{json, Value as Json};
+
 use crate::span::Span;
 
+pub mod catalog;
+pub mod render;
+
 /// Diagnostic severity levels.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Severity {
@@ -33,9 +38,25 @@ pub struct Diagnostic {
     pub message: String,
     pub span: Span,
     pub suggestion: Option<String>,
+    /// A correction safe to apply without asking: set only when the
+    /// suggestion names a single replacement token and no other candidate
+    /// is as close. Applied by `urd fix` (see [`crate::fix`]).
+    pub fix: Option<Fix>,
     pub related: Vec<RelatedInfo>,
 }
 
+/// A machine-applicable correction: replace the token `original` with
+/// `replacement` wherever it occurs within `span`.
+///
+/// Spans cover whole nodes (a condition line, an import declaration), so
+/// the token is located inside the span when the fix is applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fix {
+    pub span: Span,
+    pub original: String,
+    pub replacement: String,
+}
+
 /// Additional context for a diagnostic (e.g. "first declared here").
 #[derive(Debug, Clone)]
 pub struct RelatedInfo {
@@ -62,6 +83,7 @@ impl DiagnosticCollector {
             message: message.into(),
             span,
             suggestion: None,
+            fix: None,
             related: Vec::new(),
         });
     }
@@ -74,6 +96,7 @@ impl DiagnosticCollector {
             message: message.into(),
             span,
             suggestion: None,
+            fix: None,
             related: Vec::new(),
         });
     }
@@ -86,6 +109,7 @@ impl DiagnosticCollector {
             message: message.into(),
             span,
             suggestion: None,
+            fix: None,
             related: Vec::new(),
         });
     }
@@ -95,6 +119,27 @@ impl DiagnosticCollector {
         self.diagnostics.push(diagnostic);
     }
 
+    /// Record `diagnostics` after everything already recorded, in their
+    /// order, as though each had been emitted here in turn.
+    pub fn merge(&mut self, diagnostics: Vec<Diagnostic>) {
+        self.diagnostics.extend(diagnostics);
+    }
+
+    /// Consume the collector, returning its diagnostics in the order they
+    /// were recorded.
+    pub fn into_vec(self) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+
+    /// Record the errors `matches` selects as warnings instead.
+    pub(crate) fn downgrade_errors(&mut self, matches: impl Fn(&Diagnostic) -> bool) {
+        for d in &mut self.diagnostics {
+            if d.severity == Severity::Error && matches(d) {
+                d.severity = Severity::Warning;
+            }
+        }
+    }
+
     /// Returns `true` if any Error-severity diagnostic has been recorded.
     pub fn has_errors(&self) -> bool {
         self.diagnostics.iter().any(|d| d.severity == Severity::Error)
@@ -129,4 +174,42 @@ impl DiagnosticCollector {
     pub fn all(&self) -> &[Diagnostic] {
         &self.diagnostics
     }
+
+    /// Every diagnostic as a JSON array, in [`Self::sorted()`] order. Each
+    /// element is:
+    /// ```json
+    /// { "severity": "error", "code": "URD301", "message": "...",
+    ///   "span": { "file": "main.urd.md", "start_line": 12, "start_col": 5,
+    ///             "end_line": 12, "end_col": 11 },
+    ///   "suggestion": "..." }
+    /// ```
+    /// `severity` is `error`, `warning`, or `info`, and `suggestion` is
+    /// present only when the diagnostic has one. Keys come in this order.
+    /// The CLI's `--diagnostics-format json` and the WASM payloads print
+    /// this shape, so hosts can parse one format.
+    pub fn to_json(&self) -> Json {
+        let items = self
+            .sorted()
+            .into_iter()
+            .map(|d| {
+                let mut item = json!({
+                    "severity": render::severity_name(d.severity),
+                    "code": d.code,
+                    "message": d.message,
+                    "span": {
+                        "file": d.span.file,
+                        "start_line": d.span.start_line,
+                        "start_col": d.span.start_col,
+                        "end_line": d.span.end_line,
+                        "end_col": d.span.end_col,
+                    },
+                });
+                if let Some(suggestion) = &d.suggestion {
+                    item["suggestion"] = Json::String(suggestion.clone());
+                }
+                item
+            })
+            .collect();
+        Json::Array(items)
+    }
 }
@@ -0,0 +1,132 @@
+/// Text rendering of diagnostics, for terminals and text views.
+///
+/// [`plain()`] is the one-line form the CLI has always printed:
+///
+/// ```text
+/// [error] tavern.urd.md:12:5: Unresolved entity reference '@ghost'. (URD301)
+/// ```
+///
+/// [`pretty()`] adds the source under a header naming the code, with the
+/// span underlined and the suggestion after it:
+///
+/// ```text
+/// error[URD301]: Unresolved entity reference '@ghost'.
+///   --> tavern.urd.md:12:5
+///    |
+/// 12 |     @ghost: Hello.
+///    |     ^^^^^^
+///    = help: Did you mean '@host'?
+/// ```
+///
+/// A span over several lines shows its first and last lines with `...`
+/// between them. Tabs are expanded to the next multiple of four columns
+/// in both the line and the underline, so the carets stay aligned. When
+/// the source is unavailable, or the span does not fall inside it, the
+/// pretty form falls back to the plain one.
+
+use super::{Diagnostic, Severity};
+
+const TAB_WIDTH: usize = 4;
+
+/// The severity as printed: `error`, `warning`, or `info`.
+pub fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "info",
+    }
+}
+
+/// `[severity] file:line:col: message (CODE)`.
+pub fn plain(diagnostic: &Diagnostic) -> String {
+    format!(
+        "[{}] {}: {} ({})",
+        severity_name(diagnostic.severity),
+        diagnostic.span,
+        diagnostic.message,
+        diagnostic.code
+    )
+}
+
+/// The diagnostic with its source lines underlined. `source` is the full
+/// text of `diagnostic.span.file`. Lines are joined with `\n`, with no
+/// newline after the last, as for [`plain()`].
+pub fn pretty(diagnostic: &Diagnostic, source: Option<&str>) -> String {
+    let span = &diagnostic.span;
+    let lines: Vec<&str> = match source {
+        Some(source) => source.lines().collect(),
+        None => return plain(diagnostic),
+    };
+    let (first, last) = (span.start_line as usize, span.end_line.max(span.start_line) as usize);
+    if first == 0 || last > lines.len() {
+        return plain(diagnostic);
+    }
+
+    let gutter = last.to_string().len();
+    let mut out = vec![
+        format!("{}[{}]: {}", severity_name(diagnostic.severity), diagnostic.code, diagnostic.message),
+        format!("{}--> {}", " ".repeat(gutter), span),
+        format!("{} |", " ".repeat(gutter)),
+    ];
+
+    let start = span.start_col.max(1) as usize - 1;
+    if first == last {
+        let end = (span.end_col.max(1) as usize - 1).max(start + 1);
+        push_line(&mut out, gutter, first, lines[first - 1], start, end);
+    } else {
+        let first_text = lines[first - 1];
+        push_line(&mut out, gutter, first, first_text, start, first_text.len().max(start + 1));
+        if last > first + 1 {
+            out.push("...".to_string());
+        }
+        let last_text = lines[last - 1];
+        let indent = last_text.len() - last_text.trim_start().len();
+        let end = (span.end_col.max(1) as usize - 1).max(indent + 1);
+        push_line(&mut out, gutter, last, last_text, indent, end);
+    }
+
+    if let Some(suggestion) = &diagnostic.suggestion {
+        out.push(format!("{} = help: {}", " ".repeat(gutter), suggestion));
+    }
+    out.join("\n")
+}
+
+/// One numbered source line and its underline from byte `start` to byte
+/// `end` (exclusive), both clamped to the line.
+fn push_line(out: &mut Vec<String>, gutter: usize, number: usize, text: &str, start: usize, end: usize) {
+    let start = floor_boundary(text, start.min(text.len()));
+    let end = floor_boundary(text, end.min(text.len()));
+    let lead = display_width(&text[..start]);
+    let width = (display_width(&text[..end]) - lead).max(1);
+    out.push(format!("{:>gutter$} | {}", number, expand_tabs(text), gutter = gutter));
+    out.push(format!("{} | {}{}", " ".repeat(gutter), " ".repeat(lead), "^".repeat(width)));
+}
+
+/// Columns `text` takes with tabs expanded.
+fn display_width(text: &str) -> usize {
+    text.chars().fold(0, |col, c| if c == '\t' { col + TAB_WIDTH - col % TAB_WIDTH } else { col + 1 })
+}
+
+fn expand_tabs(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut col = 0;
+    for c in text.chars() {
+        if c == '\t' {
+            let pad = TAB_WIDTH - col % TAB_WIDTH;
+            out.push_str(&" ".repeat(pad));
+            col += pad;
+        } else {
+            out.push(c);
+            col += 1;
+        }
+    }
+    out
+}
+
+/// The largest char boundary of `text` at or before `index`.
+fn floor_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
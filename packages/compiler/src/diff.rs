@@ -1,7 +1,8 @@
 /// Semantic diff engine: compare two compiled worlds at the structural level.
 ///
-/// Produces a typed change report over six categories: entity, location/exit,
-/// dialogue (section/choice), property dependency, rule, and reachability.
+/// Produces a typed change report over eight categories: entity,
+/// location/exit, dialogue (section/choice), property dependency, rule,
+/// ending, reachability, and the `requires` block a runtime checks on load.
 ///
 /// The diff operates on `DiffSnapshot` values — normalised, comparable
 /// representations of compiled output. Snapshots can be built from a live
@@ -13,6 +14,7 @@ use serde_json::Value as Json;
 
 use crate::CompilationResult;
 use crate::facts::{JumpTarget, PropertyKey};
+use crate::property_groups;
 
 // ── Snapshot structs ──
 
@@ -41,6 +43,8 @@ pub struct ExitSnapshot {
 pub struct SectionSnapshot {
     pub choice_ids: Vec<String>,
     pub jump_targets: Vec<String>,
+    /// Phases (`sequence_id/phase_id`) that open this section.
+    pub phases: Vec<String>,
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -58,6 +62,13 @@ pub struct RuleSnapshot {
     pub effect_count: usize,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndingSnapshot {
+    pub description: Option<String>,
+    /// Resolved `> end` effects naming the ending.
+    pub end_count: usize,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct PropertySnapshot {
     pub read_count: usize,
@@ -79,8 +90,15 @@ pub struct DiffSnapshot {
     pub sections: IndexMap<String, SectionSnapshot>,
     pub choices: IndexMap<String, ChoiceSnapshot>,
     pub rules: IndexMap<String, RuleSnapshot>,
+    pub endings: IndexMap<String, EndingSnapshot>,
     pub properties: IndexMap<String, PropertySnapshot>,
     pub diagnostic_keys: BTreeSet<DiagnosticKey>,
+    /// The emitted `requires` block. `None` when EMIT did not run, or the
+    /// snapshot was written before the block existed.
+    pub requires: Option<Json>,
+    /// The version of the compiler that built the snapshot. `None` for a
+    /// snapshot written before it was recorded. Never compared.
+    pub compiler_version: Option<String>,
 }
 
 #[derive(Debug)]
@@ -111,15 +129,24 @@ impl DiffSnapshot {
         let mut sections: IndexMap<String, SectionSnapshot> = IndexMap::new();
         let mut choices = IndexMap::new();
         let mut rules = IndexMap::new();
+        let mut endings: IndexMap<String, EndingSnapshot> = IndexMap::new();
         let mut properties = IndexMap::new();
         let mut diagnostic_keys = BTreeSet::new();
+        let mut requires = None;
 
         // Build a reverse map: entity_id (without @) → location_id.
         let mut entity_container: IndexMap<String, String> = IndexMap::new();
+        // (section_id, "sequence_id/phase_id") for phases that open a section.
+        let mut phase_sections: Vec<(String, String)> = Vec::new();
 
         // Parse world JSON for entities and locations.
         if let Some(ref world_json) = result.world {
-            if let Ok(world) = serde_json::from_str::<Json>(world_json) {
+            if let Ok(mut world) = serde_json::from_str::<Json>(world_json) {
+                // Snapshots key properties by their dotted names.
+                property_groups::flatten_world(&mut world);
+                // Numeric IDs depend on emission order; snapshots compare by string ID.
+                crate::emit::numeric_ids::strip(&mut world);
+                requires = world.get("requires").cloned();
                 // Entities
                 if let Some(ents) = world.get("entities").and_then(|v| v.as_object()) {
                     for (id, val) in ents {
@@ -174,6 +201,32 @@ impl DiffSnapshot {
                         );
                     }
                 }
+
+                // Endings
+                if let Some(ends) = world.get("endings").and_then(|v| v.as_object()) {
+                    for (id, val) in ends {
+                        let description = val
+                            .get("description")
+                            .and_then(|v| v.as_str())
+                            .map(|s| s.to_string());
+                        endings.insert(id.clone(), EndingSnapshot { description, end_count: 0 });
+                    }
+                }
+
+                // Phase → section references
+                if let Some(seqs) = world.get("sequences").and_then(|v| v.as_object()) {
+                    for (seq_id, seq) in seqs {
+                        let phases = seq.get("phases").and_then(|v| v.as_array());
+                        for phase in phases.into_iter().flatten() {
+                            let phase_id = phase.get("id").and_then(|v| v.as_str());
+                            let section = phase.get("section").and_then(|v| v.as_str());
+                            if let (Some(phase_id), Some(section)) = (phase_id, section) {
+                                phase_sections
+                                    .push((section.to_string(), format!("{}/{}", seq_id, phase_id)));
+                            }
+                        }
+                    }
+                }
             }
         }
 
@@ -233,6 +286,7 @@ impl DiffSnapshot {
                     .or_insert_with(|| SectionSnapshot {
                         choice_ids: Vec::new(),
                         jump_targets: Vec::new(),
+                        phases: Vec::new(),
                     })
                     .choice_ids
                     .push(choice.choice_id.clone());
@@ -246,12 +300,20 @@ impl DiffSnapshot {
                         .or_insert_with(|| SectionSnapshot {
                             choice_ids: Vec::new(),
                             jump_targets: Vec::new(),
+                            phases: Vec::new(),
                         })
                         .jump_targets
                         .push(jump_target_string(&jump.target));
                 }
             }
 
+            // End effects per ending
+            for end in fs.ends() {
+                if let Some(snap) = endings.get_mut(&end.ending) {
+                    snap.end_count += 1;
+                }
+            }
+
             // Rules
             for rule in fs.rules() {
                 rules.insert(
@@ -264,6 +326,18 @@ impl DiffSnapshot {
             }
         }
 
+        for (section, phase) in phase_sections {
+            sections
+                .entry(section)
+                .or_insert_with(|| SectionSnapshot {
+                    choice_ids: Vec::new(),
+                    jump_targets: Vec::new(),
+                    phases: Vec::new(),
+                })
+                .phases
+                .push(phase);
+        }
+
         // Properties from PropertyDependencyIndex.
         if let Some(ref idx) = result.property_index {
             let read_never_written: BTreeSet<(String, String)> = idx
@@ -341,8 +415,11 @@ impl DiffSnapshot {
             sections,
             choices,
             rules,
+            endings,
             properties,
             diagnostic_keys,
+            requires,
+            compiler_version: Some(crate::version::VERSION.to_string()),
         }
     }
 }
@@ -367,6 +444,24 @@ fn json_value_to_string(v: &Json) -> String {
     }
 }
 
+/// The world name from a compilation's emitted JSON, or `""` if EMIT did
+/// not run or the world is unnamed. Used as the snapshot's `world` field.
+pub fn world_name(result: &CompilationResult) -> String {
+    result
+        .world
+        .as_deref()
+        .and_then(|w| serde_json::from_str::<Json>(w).ok())
+        .and_then(|v| v.get("world")?.get("name")?.as_str().map(|s| s.to_string()))
+        .unwrap_or_default()
+}
+
+/// Default snapshot path for a source file: `<stem>.urd.snapshot.json`
+/// alongside it.
+pub fn default_snapshot_path(source_path: &str) -> String {
+    let stem = source_path.strip_suffix(".urd.md").unwrap_or(source_path);
+    format!("{}.urd.snapshot.json", stem)
+}
+
 /// Extract location slug from URD430 message.
 /// Pattern: "Location '...' is unreachable"
 pub fn extract_urd430_target(message: &str) -> Option<String> {
@@ -449,6 +544,7 @@ impl DiffSnapshot {
                     serde_json::json!({
                         "choice_ids": snap.choice_ids,
                         "jump_targets": snap.jump_targets,
+                        "phases": snap.phases,
                     }),
                 )
             })
@@ -485,6 +581,20 @@ impl DiffSnapshot {
             })
             .collect();
 
+        let endings: serde_json::Map<String, Json> = self
+            .endings
+            .iter()
+            .map(|(id, snap)| {
+                (
+                    id.clone(),
+                    serde_json::json!({
+                        "description": snap.description,
+                        "end_count": snap.end_count,
+                    }),
+                )
+            })
+            .collect();
+
         let properties: serde_json::Map<String, Json> = self
             .properties
             .iter()
@@ -513,6 +623,7 @@ impl DiffSnapshot {
 
         serde_json::json!({
             "urd_snapshot": "1",
+            "compiler_version": self.compiler_version,
             "world_name": world_name,
             "entities": Json::Object(entities),
             "locations": Json::Object(locations),
@@ -520,8 +631,10 @@ impl DiffSnapshot {
             "sections": Json::Object(sections),
             "choices": Json::Object(choices),
             "rules": Json::Object(rules),
+            "endings": Json::Object(endings),
             "properties": Json::Object(properties),
             "diagnostic_keys": diag_keys,
+            "requires": self.requires,
         })
     }
 
@@ -545,8 +658,11 @@ impl DiffSnapshot {
         let sections = parse_sections(&root)?;
         let choices = parse_choices(&root)?;
         let rules = parse_rules(&root)?;
+        let endings = parse_endings(&root)?;
         let properties = parse_properties(&root)?;
         let diagnostic_keys = parse_diagnostic_keys(&root)?;
+        let requires = root.get("requires").filter(|v| v.is_object()).cloned();
+        let compiler_version = root.get("compiler_version").and_then(|v| v.as_str()).map(str::to_string);
 
         Ok(DiffSnapshot {
             entities,
@@ -555,8 +671,11 @@ impl DiffSnapshot {
             sections,
             choices,
             rules,
+            endings,
             properties,
             diagnostic_keys,
+            requires,
+            compiler_version,
         })
     }
 }
@@ -676,11 +795,21 @@ fn parse_sections(root: &Json) -> Result<IndexMap<String, SectionSnapshot>, Diff
                     }
                 }
             }
+            // Absent in snapshots written before phases could open sections.
+            let mut phases = Vec::new();
+            if let Some(arr) = val.get("phases").and_then(|v| v.as_array()) {
+                for e in arr {
+                    if let Some(s) = e.as_str() {
+                        phases.push(s.to_string());
+                    }
+                }
+            }
             map.insert(
                 id.clone(),
                 SectionSnapshot {
                     choice_ids,
                     jump_targets,
+                    phases,
                 },
             );
         }
@@ -750,6 +879,28 @@ fn parse_rules(root: &Json) -> Result<IndexMap<String, RuleSnapshot>, DiffError>
     Ok(map)
 }
 
+fn parse_endings(root: &Json) -> Result<IndexMap<String, EndingSnapshot>, DiffError> {
+    let mut map = IndexMap::new();
+    if let Some(obj) = root.get("endings").and_then(|v| v.as_object()) {
+        for (id, val) in obj {
+            map.insert(
+                id.clone(),
+                EndingSnapshot {
+                    description: val
+                        .get("description")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string()),
+                    end_count: val
+                        .get("end_count")
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(0) as usize,
+                },
+            );
+        }
+    }
+    Ok(map)
+}
+
 fn parse_properties(root: &Json) -> Result<IndexMap<String, PropertySnapshot>, DiffError> {
     let mut map = IndexMap::new();
     if let Some(obj) = root.get("properties").and_then(|v| v.as_object()) {
@@ -817,6 +968,17 @@ impl ChangeEntry {
             detail,
         }
     }
+
+    /// Serialise as `{category, kind, id, detail}`, the per-change shape
+    /// used in `DiffReport::to_json()`.
+    pub fn to_json(&self) -> Json {
+        serde_json::json!({
+            "category": self.category,
+            "kind": self.kind,
+            "id": self.id,
+            "detail": self.detail,
+        })
+    }
 }
 
 /// The result of comparing two DiffSnapshots.
@@ -834,9 +996,11 @@ fn category_order(cat: &str) -> usize {
         "section" => 3,
         "choice" => 4,
         "rule" => 5,
-        "property_dependency" => 6,
-        "reachability" => 7,
-        _ => 8,
+        "ending" => 6,
+        "property_dependency" => 7,
+        "reachability" => 8,
+        "requires" => 9,
+        _ => 10,
     }
 }
 
@@ -871,7 +1035,10 @@ pub fn diff(a: &DiffSnapshot, b: &DiffSnapshot) -> DiffReport {
     // 6. Rule changes
     diff_maps(&a.rules, &b.rules, "rule", &mut changes, compare_rule);
 
-    // 7. Property dependency changes
+    // 7. Ending changes
+    diff_maps(&a.endings, &b.endings, "ending", &mut changes, compare_ending);
+
+    // 8. Property dependency changes
     diff_maps(
         &a.properties,
         &b.properties,
@@ -880,9 +1047,14 @@ pub fn diff(a: &DiffSnapshot, b: &DiffSnapshot) -> DiffReport {
         compare_property,
     );
 
-    // 8. Reachability changes (from diagnostic keys)
+    // 9. Reachability changes (from diagnostic keys)
     diff_diagnostic_keys(&a.diagnostic_keys, &b.diagnostic_keys, &mut changes);
 
+    // 10. Capability changes
+    if let (Some(before), Some(after)) = (&a.requires, &b.requires) {
+        diff_requires(before, after, &mut changes);
+    }
+
     // Sort deterministically.
     changes.sort_by(|a, b| {
         category_order(&a.category)
@@ -1034,6 +1206,17 @@ fn compare_section(
             }),
         ));
     }
+    if a.phases != b.phases {
+        changes.push(ChangeEntry::new(
+            "section",
+            "phases_changed",
+            id,
+            serde_json::json!({
+                "before": a.phases,
+                "after": b.phases,
+            }),
+        ));
+    }
     changes
 }
 
@@ -1138,6 +1321,38 @@ fn compare_rule(
     changes
 }
 
+fn compare_ending(
+    _category: &str,
+    id: &str,
+    a: &EndingSnapshot,
+    b: &EndingSnapshot,
+) -> Vec<ChangeEntry> {
+    let mut changes = Vec::new();
+    if a.description != b.description {
+        changes.push(ChangeEntry::new(
+            "ending",
+            "description_changed",
+            id,
+            serde_json::json!({
+                "before": a.description,
+                "after": b.description,
+            }),
+        ));
+    }
+    if a.end_count != b.end_count {
+        changes.push(ChangeEntry::new(
+            "ending",
+            "reach_changed",
+            id,
+            serde_json::json!({
+                "before": { "end_count": a.end_count },
+                "after": { "end_count": b.end_count },
+            }),
+        ));
+    }
+    changes
+}
+
 fn compare_property(
     _category: &str,
     id: &str,
@@ -1248,31 +1463,50 @@ fn diff_diagnostic_keys(
     }
 }
 
+/// Compare two `requires` blocks key by key. Each differing key is one
+/// `changed` entry, so a new trait or effect kind shows up in review.
+fn diff_requires(a: &Json, b: &Json, changes: &mut Vec<ChangeEntry>) {
+    let (Some(a), Some(b)) = (a.as_object(), b.as_object()) else {
+        return;
+    };
+    let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+    for key in keys {
+        let (before, after) = (a.get(key).unwrap_or(&Json::Null), b.get(key).unwrap_or(&Json::Null));
+        if before != after {
+            changes.push(ChangeEntry::new(
+                "requires",
+                "changed",
+                key,
+                serde_json::json!({
+                    "before": before,
+                    "after": after,
+                }),
+            ));
+        }
+    }
+}
+
 // ── DiffReport JSON output ──
 
 impl DiffReport {
+    /// Group changes by category, preserving report order (categories in
+    /// their canonical order, entries sorted within each).
+    pub fn by_category(&self) -> IndexMap<String, Vec<&ChangeEntry>> {
+        let mut groups: IndexMap<String, Vec<&ChangeEntry>> = IndexMap::new();
+        for c in &self.changes {
+            groups.entry(c.category.clone()).or_default().push(c);
+        }
+        groups
+    }
+
     /// Serialise the report to structured JSON.
     pub fn to_json(&self) -> serde_json::Value {
-        let changes: Vec<Json> = self
-            .changes
-            .iter()
-            .map(|c| {
-                serde_json::json!({
-                    "category": c.category,
-                    "kind": c.kind,
-                    "id": c.id,
-                    "detail": c.detail,
-                })
-            })
-            .collect();
+        let changes: Vec<Json> = self.changes.iter().map(|c| c.to_json()).collect();
 
-        let mut by_category: IndexMap<String, usize> = IndexMap::new();
-        for c in &self.changes {
-            *by_category.entry(c.category.clone()).or_insert(0) += 1;
-        }
-        let by_cat_json: serde_json::Map<String, Json> = by_category
+        let by_cat_json: serde_json::Map<String, Json> = self
+            .by_category()
             .iter()
-            .map(|(k, v)| (k.clone(), Json::Number((*v as u64).into())))
+            .map(|(k, v)| (k.clone(), Json::Number((v.len() as u64).into())))
             .collect();
 
         serde_json::json!({
@@ -1290,14 +1524,10 @@ impl DiffReport {
             return "No changes detected.".to_string();
         }
 
-        let mut by_category: IndexMap<String, usize> = IndexMap::new();
-        for c in &self.changes {
-            *by_category.entry(c.category.clone()).or_insert(0) += 1;
-        }
-
-        let parts: Vec<String> = by_category
+        let parts: Vec<String> = self
+            .by_category()
             .iter()
-            .map(|(cat, count)| format!("{} {}", count, cat))
+            .map(|(cat, entries)| format!("{} {}", entries.len(), cat))
             .collect();
 
         format!(
@@ -0,0 +1,787 @@
+/// Reference pages for a compiled world, for collaborators who do not read
+/// `.urd.md`.
+///
+/// `urd doc` renders an index and one page per category:
+///
+/// - `types`: each type's traits, properties, and entities
+/// - `entities`: each entity's type, location, and property overrides
+/// - `locations`: each location's description, exits, and contents
+/// - `dialogue`: each section's choices, outlined with where they lead
+///
+/// Pages are Markdown or HTML. Every entry carries an anchor derived from
+/// its compiled ID (`entity-rusty_key`, `section-tavern-topics`), so links
+/// into the pages survive regeneration. Everything is read from the
+/// emitted world, plus location names from the symbol table; `///` doc
+/// comments appear when the world was emitted with
+/// `EmitOptions::include_docs`. The output is the same on every run: the
+/// only date is the one in `DocOptions::date`. Like `strings`, this module
+/// does no file I/O.
+
+use std::collections::{HashMap, HashSet};
+
+use serde_json::{Map, Value as Json};
+
+use crate::symbol_table::SymbolTable;
+use crate::CompilationResult;
+
+/// Output format of the pages.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DocFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+impl DocFormat {
+    /// File extension of each page, without the dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            DocFormat::Markdown => "md",
+            DocFormat::Html => "html",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DocOptions {
+    pub format: DocFormat,
+    /// Printed on the index as "Generated on {date}." Omitted by default
+    /// so regenerating an unchanged world changes no file.
+    pub date: Option<String>,
+}
+
+/// One generated page.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocPage {
+    /// File name, such as `types.md`.
+    pub file: String,
+    pub contents: String,
+}
+
+// ── Construction ──
+
+/// Generate the pages for a compilation result, index first.
+///
+/// Returns `None` when compilation reported errors, since EMIT would not
+/// have run and there is no world to document.
+pub fn from_compilation(result: &CompilationResult, options: &DocOptions) -> Option<Vec<DocPage>> {
+    if !result.success {
+        return None;
+    }
+    let world = result.world_value.as_ref()?.to_json();
+    Some(generate(&world, result.symbol_table.as_ref()?, options))
+}
+
+/// Generate the pages for an emitted world, index first.
+pub fn generate(world: &Json, symbol_table: &SymbolTable, options: &DocOptions) -> Vec<DocPage> {
+    let docs = Docs {
+        world,
+        symbol_table,
+        anchors: Anchors::new(world),
+        format: options.format,
+    };
+    Page::ALL
+        .iter()
+        .map(|&page| {
+            let blocks = match page {
+                Page::Index => docs.index(options.date.as_deref()),
+                Page::Types => docs.types(),
+                Page::Entities => docs.entities(),
+                Page::Locations => docs.locations(),
+                Page::Dialogue => docs.dialogue(),
+            };
+            let contents = match options.format {
+                DocFormat::Markdown => render_markdown(&blocks),
+                DocFormat::Html => render_html(&format!("{} — {}", page.title(), docs.world_name()), &blocks),
+            };
+            DocPage {
+                file: format!("{}.{}", page.stem(), options.format.extension()),
+                contents,
+            }
+        })
+        .collect()
+}
+
+/// The anchor of an entry: its kind, then its compiled ID with every
+/// character other than ASCII letters, digits, `-`, and `_` replaced by
+/// `-`. `anchor("section", "tavern/topics")` is `section-tavern-topics`.
+pub fn anchor(kind: &str, id: &str) -> String {
+    let id: String = id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' { c } else { '-' })
+        .collect();
+    format!("{}-{}", kind, id)
+}
+
+// ── Pages ──
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Page {
+    Index,
+    Types,
+    Entities,
+    Locations,
+    Dialogue,
+}
+
+impl Page {
+    const ALL: [Page; 5] = [Page::Index, Page::Types, Page::Entities, Page::Locations, Page::Dialogue];
+
+    fn stem(self) -> &'static str {
+        match self {
+            Page::Index => "index",
+            Page::Types => "types",
+            Page::Entities => "entities",
+            Page::Locations => "locations",
+            Page::Dialogue => "dialogue",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            Page::Index => "Index",
+            Page::Types => "Types",
+            Page::Entities => "Entities",
+            Page::Locations => "Locations",
+            Page::Dialogue => "Dialogue",
+        }
+    }
+
+    /// The world block each page documents, and the anchor kind of its
+    /// entries.
+    fn block(self) -> Option<(&'static str, &'static str)> {
+        match self {
+            Page::Index => None,
+            Page::Types => Some(("types", "type")),
+            Page::Entities => Some(("entities", "entity")),
+            Page::Locations => Some(("locations", "location")),
+            Page::Dialogue => Some(("dialogue", "section")),
+        }
+    }
+}
+
+/// The anchor of every entry, keyed by page and compiled ID. Two IDs that
+/// map to the same anchor get `-2`, `-3`, and so on, in world order.
+struct Anchors {
+    by_id: HashMap<(Page, String), String>,
+}
+
+impl Anchors {
+    fn new(world: &Json) -> Self {
+        let mut by_id = HashMap::new();
+        let mut taken = HashSet::new();
+        for page in Page::ALL {
+            let Some((block, kind)) = page.block() else { continue };
+            for id in entries(world, block).map(|(id, _)| id) {
+                let base = anchor(kind, id);
+                let mut unique = base.clone();
+                let mut n = 2;
+                while !taken.insert(unique.clone()) {
+                    unique = format!("{}-{}", base, n);
+                    n += 1;
+                }
+                by_id.insert((page, id.clone()), unique);
+            }
+        }
+        Anchors { by_id }
+    }
+
+    fn get(&self, page: Page, id: &str) -> Option<&str> {
+        self.by_id.get(&(page, id.to_string())).map(String::as_str)
+    }
+}
+
+/// The entries of a top-level world block, in emitted order.
+fn entries<'a>(world: &'a Json, block: &str) -> impl Iterator<Item = (&'a String, &'a Json)> {
+    world.get(block).and_then(Json::as_object).into_iter().flat_map(Map::iter)
+}
+
+struct Docs<'a> {
+    world: &'a Json,
+    symbol_table: &'a SymbolTable,
+    anchors: Anchors,
+    format: DocFormat,
+}
+
+impl Docs<'_> {
+    fn world_name(&self) -> &str {
+        self.world["world"]["name"].as_str().unwrap_or("")
+    }
+
+    /// The heading and index link that open every category page.
+    fn page_header(&self, page: Page) -> Vec<Block> {
+        vec![
+            Block::Heading(1, None, vec![Inline::text(page.title())]),
+            Block::Paragraph(vec![Inline::Text("Back to the ".to_string()), self.page_link(Page::Index), Inline::text(".")]),
+        ]
+    }
+
+    fn page_link(&self, page: Page) -> Inline {
+        Inline::Link {
+            text: Box::new(Inline::text(if page == Page::Index { "index" } else { page.title() })),
+            href: format!("{}.{}", page.stem(), self.format.extension()),
+        }
+    }
+
+    /// A link to an entry, or its bare text when the page has no entry
+    /// for `id`.
+    fn link(&self, page: Page, id: &str, text: Inline) -> Inline {
+        match self.anchors.get(page, id) {
+            Some(anchor) => Inline::Link {
+                text: Box::new(text),
+                href: format!("{}.{}#{}", page.stem(), self.format.extension(), anchor),
+            },
+            None => text,
+        }
+    }
+
+    /// An entity is shown under its display name, or else as `@id`.
+    fn entity_label(&self, id: &str) -> Inline {
+        match self.world["display_names"][id].as_str() {
+            Some(name) => Inline::text(name),
+            None => Inline::Code(format!("@{}", id)),
+        }
+    }
+
+    fn entity_link(&self, id: &str) -> Inline {
+        self.link(Page::Entities, id, self.entity_label(id))
+    }
+
+    fn location_name<'b>(&'b self, id: &'b str) -> &'b str {
+        self.symbol_table.locations.get(id).map_or(id, |loc| loc.display_name.as_str())
+    }
+
+    fn location_link(&self, id: &str) -> Inline {
+        self.link(Page::Locations, id, Inline::text(self.location_name(id)))
+    }
+
+    fn type_link(&self, name: &str) -> Inline {
+        self.link(Page::Types, name, Inline::text(name))
+    }
+
+    fn section_link(&self, id: &str) -> Inline {
+        self.link(Page::Dialogue, id, Inline::Code(id.to_string()))
+    }
+
+    /// The heading of an entry, carrying its anchor.
+    fn entry_heading(&self, page: Page, id: &str, text: Inline) -> Block {
+        Block::Heading(2, self.anchors.get(page, id).map(str::to_string), vec![text])
+    }
+
+    fn doc(&self, entry: &Json, blocks: &mut Vec<Block>) {
+        if let Some(doc) = entry["doc"].as_str() {
+            blocks.push(Block::Paragraph(vec![Inline::text(doc)]));
+        }
+    }
+
+    fn index(&self, date: Option<&str>) -> Vec<Block> {
+        let mut blocks = vec![Block::Heading(1, None, vec![Inline::text(self.world_name())])];
+        if let Some(description) = self.world["world"]["description"].as_str() {
+            blocks.push(Block::Paragraph(vec![Inline::text(description)]));
+        }
+        if let Some(start) = self.world["world"]["start"].as_str() {
+            blocks.push(Block::Paragraph(vec![Inline::text("The player starts in "), self.location_link(start), Inline::text(".")]));
+        }
+        if let Some(date) = date {
+            blocks.push(Block::Paragraph(vec![Inline::Text(format!("Generated on {}.", date))]));
+        }
+
+        let items = Page::ALL
+            .iter()
+            .filter_map(|&page| {
+                let (block, _) = page.block()?;
+                let count = entries(self.world, block).count();
+                Some(ListItem::new(vec![self.page_link(page), Inline::Text(format!(" ({})", count))]))
+            })
+            .collect();
+        blocks.push(Block::List(items));
+        blocks
+    }
+
+    fn types(&self) -> Vec<Block> {
+        let mut blocks = self.page_header(Page::Types);
+        if entries(self.world, "types").next().is_none() {
+            blocks.push(Block::Paragraph(vec![Inline::text("No types are declared.")]));
+        }
+        for (name, ty) in entries(self.world, "types") {
+            blocks.push(self.entry_heading(Page::Types, name, Inline::text(name)));
+            self.doc(ty, &mut blocks);
+            let traits = strings(&ty["traits"]);
+            if !traits.is_empty() {
+                blocks.push(Block::Paragraph(vec![Inline::Text(format!("Traits: {}.", traits.join(", ")))]));
+            }
+
+            let rows: Vec<Vec<Vec<Inline>>> = ty["properties"]
+                .as_object()
+                .into_iter()
+                .flatten()
+                .map(|(prop_name, prop)| {
+                    vec![
+                        vec![Inline::Code(prop_name.clone())],
+                        vec![Inline::Code(property_type(prop))],
+                        prop.get("default").map(value_text).map(Inline::Text).into_iter().collect(),
+                        vec![Inline::Text(property_notes(prop))],
+                    ]
+                })
+                .collect();
+            if !rows.is_empty() {
+                blocks.push(Block::Table(vec!["Property", "Type", "Default", "Notes"], rows));
+            }
+
+            let members: Vec<&String> = entries(self.world, "entities")
+                .filter(|(_, entity)| entity["type"].as_str() == Some(name))
+                .map(|(id, _)| id)
+                .collect();
+            let mut line = vec![Inline::text("Entities: ")];
+            if members.is_empty() {
+                line.push(Inline::text("none"));
+            }
+            for (i, id) in members.iter().enumerate() {
+                if i > 0 {
+                    line.push(Inline::text(", "));
+                }
+                line.push(self.entity_link(id));
+            }
+            line.push(Inline::text("."));
+            blocks.push(Block::Paragraph(line));
+        }
+        blocks
+    }
+
+    fn entities(&self) -> Vec<Block> {
+        let mut blocks = self.page_header(Page::Entities);
+        if entries(self.world, "entities").next().is_none() {
+            blocks.push(Block::Paragraph(vec![Inline::text("No entities are declared.")]));
+        }
+        let start_inventory = strings(&self.world["world"]["start_inventory"]);
+        for (id, entity) in entries(self.world, "entities") {
+            blocks.push(self.entry_heading(Page::Entities, id, self.entity_label(id)));
+
+            let mut line = vec![Inline::text("ID: "), Inline::Code(format!("@{}", id)), Inline::text(". Type: ")];
+            line.push(self.type_link(entity["type"].as_str().unwrap_or("")));
+            line.push(Inline::text("."));
+            let locations: Vec<&String> = entries(self.world, "locations")
+                .filter(|(_, loc)| strings(&loc["contains"]).contains(&id.as_str()))
+                .map(|(loc_id, _)| loc_id)
+                .collect();
+            for loc_id in locations {
+                line.push(Inline::text(" In: "));
+                line.push(self.location_link(loc_id));
+                line.push(Inline::text("."));
+            }
+            if start_inventory.contains(&id.as_str()) {
+                line.push(Inline::text(" Starts in the player's inventory."));
+            }
+            blocks.push(Block::Paragraph(line));
+            self.doc(entity, &mut blocks);
+
+            let rows: Vec<Vec<Vec<Inline>>> = entity["properties"]
+                .as_object()
+                .into_iter()
+                .flatten()
+                .map(|(prop_name, value)| vec![vec![Inline::Code(prop_name.clone())], vec![Inline::Text(value_text(value))]])
+                .collect();
+            if !rows.is_empty() {
+                blocks.push(Block::Table(vec!["Property", "Value"], rows));
+            }
+        }
+        blocks
+    }
+
+    fn locations(&self) -> Vec<Block> {
+        let mut blocks = self.page_header(Page::Locations);
+        if entries(self.world, "locations").next().is_none() {
+            blocks.push(Block::Paragraph(vec![Inline::text("No locations are declared.")]));
+        }
+        let start = self.world["world"]["start"].as_str();
+        for (id, loc) in entries(self.world, "locations") {
+            blocks.push(self.entry_heading(Page::Locations, id, Inline::text(self.location_name(id))));
+
+            let mut line = vec![Inline::text("ID: "), Inline::Code(id.clone()), Inline::text(".")];
+            if let Some(region) = loc["region"].as_str() {
+                let name = self.world["regions"][region]["name"].as_str().unwrap_or(region);
+                line.push(Inline::Text(format!(" Region: {}.", name)));
+            }
+            if start == Some(id.as_str()) {
+                line.push(Inline::text(" The player starts here."));
+            }
+            blocks.push(Block::Paragraph(line));
+            self.doc(loc, &mut blocks);
+            if let Some(description) = loc["description"].as_str() {
+                blocks.push(Block::Quote(description.split("\n\n").map(str::to_string).collect()));
+            }
+
+            let rows: Vec<Vec<Vec<Inline>>> = loc["exits"]
+                .as_object()
+                .into_iter()
+                .flatten()
+                .map(|(direction, exit)| {
+                    vec![
+                        vec![Inline::Code(direction.clone())],
+                        vec![self.location_link(exit["to"].as_str().unwrap_or(""))],
+                        exit["condition"].as_str().map(|c| Inline::Code(c.to_string())).into_iter().collect(),
+                        exit["blocked_message"].as_str().map(Inline::text).into_iter().collect(),
+                    ]
+                })
+                .collect();
+            if !rows.is_empty() {
+                blocks.push(Block::Table(vec!["Exit", "To", "Condition", "Blocked message"], rows));
+            }
+
+            let contains = strings(&loc["contains"]);
+            if !contains.is_empty() {
+                let mut line = vec![Inline::text("Contains: ")];
+                for (i, entity) in contains.iter().enumerate() {
+                    if i > 0 {
+                        line.push(Inline::text(", "));
+                    }
+                    line.push(self.entity_link(entity));
+                }
+                line.push(Inline::text("."));
+                blocks.push(Block::Paragraph(line));
+            }
+        }
+        blocks
+    }
+
+    fn dialogue(&self) -> Vec<Block> {
+        let mut blocks = self.page_header(Page::Dialogue);
+        if entries(self.world, "dialogue").next().is_none() {
+            blocks.push(Block::Paragraph(vec![Inline::text("No dialogue sections are declared.")]));
+        }
+        for (id, section) in entries(self.world, "dialogue") {
+            blocks.push(self.entry_heading(Page::Dialogue, id, Inline::Code(id.clone())));
+            self.doc(section, &mut blocks);
+
+            // EMIT also lists nested choices at the section's top level;
+            // the outline shows each one under its parent only.
+            let mut nested = HashSet::new();
+            nested_choice_ids(&section["choices"], false, &mut nested);
+            let mut items: Vec<ListItem> = self
+                .choice_outline(&section["choices"])
+                .into_iter()
+                .zip(section["choices"].as_array().into_iter().flatten())
+                .filter(|(_, choice)| !choice["id"].as_str().is_some_and(|id| nested.contains(id)))
+                .map(|(item, _)| item)
+                .collect();
+            let mut exhausted = vec![Inline::text("When no choices remain")];
+            self.jump(&section["on_exhausted"], &mut exhausted);
+            if exhausted.len() > 1 {
+                items.push(ListItem::new(exhausted));
+            }
+            if items.is_empty() {
+                blocks.push(Block::Paragraph(vec![Inline::text("No choices.")]));
+            } else {
+                blocks.push(Block::List(items));
+            }
+        }
+        blocks
+    }
+
+    /// One item per choice: its label, whether it is sticky, and where it
+    /// leads, with nested choices beneath.
+    fn choice_outline(&self, choices: &Json) -> Vec<ListItem> {
+        choices
+            .as_array()
+            .into_iter()
+            .flatten()
+            .map(|choice| {
+                let mut item = vec![Inline::text(choice["label"].as_str().unwrap_or(""))];
+                if choice["sticky"].as_bool() == Some(true) {
+                    item.push(Inline::text(" (sticky)"));
+                }
+                self.jump(choice, &mut item);
+                ListItem {
+                    content: item,
+                    children: self.choice_outline(&choice["choices"]),
+                }
+            })
+            .collect()
+    }
+
+    fn jump(&self, node: &Json, item: &mut Vec<Inline>) {
+        if let Some(target) = node["goto"].as_str() {
+            item.push(Inline::text(" → "));
+            item.push(if target == "end" { Inline::text("end") } else { self.section_link(target) });
+        } else if let Some(direction) = node["exit"].as_str() {
+            item.push(Inline::text(" → exit "));
+            item.push(Inline::Code(direction.to_string()));
+        }
+    }
+}
+
+/// IDs of the choices nested under another choice.
+fn nested_choice_ids<'a>(choices: &'a Json, nested: bool, ids: &mut HashSet<&'a str>) {
+    for choice in choices.as_array().into_iter().flatten() {
+        if nested {
+            ids.extend(choice["id"].as_str());
+        }
+        nested_choice_ids(&choice["choices"], true, ids);
+    }
+}
+
+/// The strings of a JSON array, skipping anything else.
+fn strings(value: &Json) -> Vec<&str> {
+    value.as_array().into_iter().flatten().filter_map(Json::as_str).collect()
+}
+
+/// A value as a reader would write it: strings bare, lists comma-separated.
+fn value_text(value: &Json) -> String {
+    match value {
+        Json::String(s) => s.clone(),
+        Json::Array(items) => items.iter().map(value_text).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
+
+/// A range bound without a trailing `.0`: EMIT writes bounds as numbers.
+fn number_text(value: &Json) -> String {
+    match value.as_f64() {
+        Some(n) if n.fract() == 0.0 && n.abs() < 1e15 => format!("{}", n as i64),
+        _ => value.to_string(),
+    }
+}
+
+/// `enum(a, b)` and `ref(Type)` spell out their constraint; other types
+/// are their name.
+fn property_type(prop: &Json) -> String {
+    let name = prop["type"].as_str().unwrap_or("");
+    match name {
+        "enum" => format!("enum({})", strings(&prop["values"]).join(", ")),
+        "ref" => format!("ref({})", prop["ref_type"].as_str().unwrap_or("")),
+        _ => name.to_string(),
+    }
+}
+
+/// The description, the range, and whether the property is hidden.
+fn property_notes(prop: &Json) -> String {
+    let mut notes = Vec::new();
+    if let Some(description) = prop["description"].as_str() {
+        notes.push(description.to_string());
+    }
+    match (prop.get("min").map(number_text), prop.get("max").map(number_text)) {
+        (Some(min), Some(max)) => notes.push(format!("From {} to {}.", min, max)),
+        (Some(min), None) => notes.push(format!("At least {}.", min)),
+        (None, Some(max)) => notes.push(format!("At most {}.", max)),
+        (None, None) => {}
+    }
+    if prop["visibility"].as_str() == Some("hidden") {
+        notes.push("Hidden from the player.".to_string());
+    }
+    notes.join(" ")
+}
+
+// ── Rendering ──
+
+enum Block {
+    /// Level, anchor, and text.
+    Heading(u8, Option<String>, Vec<Inline>),
+    Paragraph(Vec<Inline>),
+    /// Player-visible text, one string per paragraph.
+    Quote(Vec<String>),
+    List(Vec<ListItem>),
+    /// Column headers and rows of cells.
+    Table(Vec<&'static str>, Vec<Vec<Vec<Inline>>>),
+}
+
+struct ListItem {
+    content: Vec<Inline>,
+    children: Vec<ListItem>,
+}
+
+impl ListItem {
+    fn new(content: Vec<Inline>) -> Self {
+        ListItem { content, children: Vec::new() }
+    }
+}
+
+enum Inline {
+    Text(String),
+    Code(String),
+    Link { text: Box<Inline>, href: String },
+}
+
+impl Inline {
+    fn text(s: &str) -> Self {
+        Inline::Text(s.to_string())
+    }
+}
+
+fn render_markdown(blocks: &[Block]) -> String {
+    let mut out = String::new();
+    for block in blocks {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        match block {
+            Block::Heading(level, anchor, text) => {
+                out.push_str(&"#".repeat(*level as usize));
+                out.push(' ');
+                if let Some(anchor) = anchor {
+                    out.push_str(&format!("<a id=\"{}\"></a>", anchor));
+                }
+                out.push_str(&markdown_inlines(text, false));
+                out.push('\n');
+            }
+            Block::Paragraph(text) => {
+                out.push_str(&markdown_inlines(text, false));
+                out.push('\n');
+            }
+            Block::Quote(paragraphs) => {
+                for (i, paragraph) in paragraphs.iter().enumerate() {
+                    if i > 0 {
+                        out.push_str(">\n");
+                    }
+                    for line in markdown_text(paragraph, false, true).lines() {
+                        out.push_str("> ");
+                        out.push_str(line);
+                        out.push('\n');
+                    }
+                }
+            }
+            Block::List(items) => markdown_list(items, 0, &mut out),
+            Block::Table(headers, rows) => {
+                out.push_str(&format!("| {} |\n", headers.join(" | ")));
+                out.push_str(&format!("|{}\n", "---|".repeat(headers.len())));
+                for row in rows {
+                    out.push('|');
+                    for cell in row {
+                        match markdown_inlines(cell, true) {
+                            text if text.is_empty() => out.push_str(" |"),
+                            text => out.push_str(&format!(" {} |", text)),
+                        }
+                    }
+                    out.push('\n');
+                }
+            }
+        }
+    }
+    out
+}
+
+fn markdown_list(items: &[ListItem], depth: usize, out: &mut String) {
+    for item in items {
+        out.push_str(&"  ".repeat(depth));
+        out.push_str("- ");
+        out.push_str(&markdown_inlines(&item.content, false));
+        out.push('\n');
+        markdown_list(&item.children, depth + 1, out);
+    }
+}
+
+fn markdown_inlines(inlines: &[Inline], in_table: bool) -> String {
+    inlines
+        .iter()
+        .enumerate()
+        .map(|(i, inline)| markdown_inline(inline, in_table, i == 0 && !in_table))
+        .collect()
+}
+
+fn markdown_inline(inline: &Inline, in_table: bool, line_start: bool) -> String {
+    match inline {
+        Inline::Text(text) => markdown_text(text, in_table, line_start),
+        Inline::Code(code) => {
+            // A fence one backtick longer than any run inside the code.
+            let longest = code.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+            let fence = "`".repeat(longest + 1);
+            let pad = if longest > 0 { " " } else { "" };
+            let code = if in_table { code.replace('|', "\\|") } else { code.clone() };
+            format!("{}{}{}{}{}", fence, pad, code, pad, fence)
+        }
+        Inline::Link { text, href } => format!("[{}]({})", markdown_inline(text, in_table, false), href),
+    }
+}
+
+/// Escape the characters Markdown would read as formatting. Text that
+/// starts a line has a leading block marker escaped too; a table cell has
+/// no line breaks.
+fn markdown_text(text: &str, in_table: bool, line_start: bool) -> String {
+    let mut out = String::new();
+    for (i, line) in text.split('\n').enumerate() {
+        if i > 0 {
+            out.push_str(if in_table { " " } else { "\n" });
+        }
+        let line = if line_start || (i > 0 && !in_table) { line.trim_start() } else { line };
+        if (line_start || (i > 0 && !in_table)) && line.starts_with(['#', '-', '+', '=']) {
+            out.push('\\');
+        }
+        for c in line.chars() {
+            if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '|') {
+                out.push('\\');
+            }
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn render_html(title: &str, blocks: &[Block]) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n",
+        html_escape(title)
+    );
+    for block in blocks {
+        match block {
+            Block::Heading(level, anchor, text) => {
+                let id = anchor.as_ref().map(|a| format!(" id=\"{}\"", a)).unwrap_or_default();
+                out.push_str(&format!("<h{}{}>{}</h{}>\n", level, id, html_inlines(text), level));
+            }
+            Block::Paragraph(text) => out.push_str(&format!("<p>{}</p>\n", html_inlines(text))),
+            Block::Quote(paragraphs) => {
+                out.push_str("<blockquote>\n");
+                for paragraph in paragraphs {
+                    out.push_str(&format!("<p>{}</p>\n", html_escape(paragraph)));
+                }
+                out.push_str("</blockquote>\n");
+            }
+            Block::List(items) => html_list(items, &mut out),
+            Block::Table(headers, rows) => {
+                out.push_str("<table>\n<thead>\n<tr>");
+                for header in headers {
+                    out.push_str(&format!("<th>{}</th>", html_escape(header)));
+                }
+                out.push_str("</tr>\n</thead>\n<tbody>\n");
+                for row in rows {
+                    out.push_str("<tr>");
+                    for cell in row {
+                        out.push_str(&format!("<td>{}</td>", html_inlines(cell)));
+                    }
+                    out.push_str("</tr>\n");
+                }
+                out.push_str("</tbody>\n</table>\n");
+            }
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn html_list(items: &[ListItem], out: &mut String) {
+    out.push_str("<ul>\n");
+    for item in items {
+        out.push_str("<li>");
+        out.push_str(&html_inlines(&item.content));
+        if !item.children.is_empty() {
+            out.push('\n');
+            html_list(&item.children, out);
+        }
+        out.push_str("</li>\n");
+    }
+    out.push_str("</ul>\n");
+}
+
+fn html_inlines(inlines: &[Inline]) -> String {
+    inlines.iter().map(html_inline).collect()
+}
+
+fn html_inline(inline: &Inline) -> String {
+    match inline {
+        Inline::Text(text) => html_escape(text),
+        Inline::Code(code) => format!("<code>{}</code>", html_escape(code)),
+        Inline::Link { text, href } => format!("<a href=\"{}\">{}</a>", html_escape(href), html_inline(text)),
+    }
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -0,0 +1,140 @@
+/// The compiled world as a typed value: one field per top-level block.
+///
+/// EMIT builds a [`CompiledWorld`] and serialises it, so Rust hosts can
+/// read the blocks without parsing the JSON they were just given. Each
+/// block is the object the `.urd.json` document holds under that key,
+/// with its entries in emission order. A block EMIT leaves out is `None`.
+
+use std::io::{self, Write};
+
+use serde_json::{Map, Value as Json};
+
+/// A compiled world, block by block. [`Self::to_document()`] writes the
+/// `.urd.json` text, byte-identical to [`super::emit_with_options()`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CompiledWorld {
+    pub world: Map<String, Json>,
+    pub requires: Map<String, Json>,
+    pub types: Option<Map<String, Json>>,
+    pub entities: Option<Map<String, Json>>,
+    pub display_names: Option<Map<String, Json>>,
+    pub locations: Option<Map<String, Json>>,
+    pub regions: Option<Map<String, Json>>,
+    pub rules: Option<Map<String, Json>>,
+    pub actions: Option<Map<String, Json>>,
+    pub sequences: Option<Map<String, Json>>,
+    pub endings: Option<Map<String, Json>>,
+    pub dialogue: Option<Map<String, Json>>,
+    /// Set only with `EmitOptions::numeric_ids`.
+    pub index: Option<Map<String, Json>>,
+}
+
+impl CompiledWorld {
+    /// The blocks present, in the order the document holds them.
+    pub fn blocks(&self) -> impl Iterator<Item = (&'static str, &Map<String, Json>)> {
+        [
+            ("world", Some(&self.world)),
+            ("requires", Some(&self.requires)),
+            ("types", self.types.as_ref()),
+            ("entities", self.entities.as_ref()),
+            ("display_names", self.display_names.as_ref()),
+            ("locations", self.locations.as_ref()),
+            ("regions", self.regions.as_ref()),
+            ("rules", self.rules.as_ref()),
+            ("actions", self.actions.as_ref()),
+            ("sequences", self.sequences.as_ref()),
+            ("endings", self.endings.as_ref()),
+            ("dialogue", self.dialogue.as_ref()),
+            ("index", self.index.as_ref()),
+        ]
+        .into_iter()
+        .filter_map(|(key, block)| Some((key, block?)))
+    }
+
+    /// The block under `key`, as the document names it.
+    pub fn block(&self, key: &str) -> Option<&Map<String, Json>> {
+        self.blocks().find_map(|(k, block)| (k == key).then_some(block))
+    }
+
+    fn optional_slot(&mut self, key: &str) -> Option<&mut Option<Map<String, Json>>> {
+        Some(match key {
+            "types" => &mut self.types,
+            "entities" => &mut self.entities,
+            "display_names" => &mut self.display_names,
+            "locations" => &mut self.locations,
+            "regions" => &mut self.regions,
+            "rules" => &mut self.rules,
+            "actions" => &mut self.actions,
+            "sequences" => &mut self.sequences,
+            "endings" => &mut self.endings,
+            "dialogue" => &mut self.dialogue,
+            "index" => &mut self.index,
+            _ => return None,
+        })
+    }
+
+    /// Set the block under `key`. `false`, and nothing set, for a key no
+    /// document has.
+    pub(super) fn insert(&mut self, key: &str, block: Map<String, Json>) -> bool {
+        match key {
+            "world" => self.world = block,
+            "requires" => self.requires = block,
+            _ => match self.optional_slot(key) {
+                Some(slot) => *slot = Some(block),
+                None => return false,
+            },
+        }
+        true
+    }
+
+    /// Read a compiled world back from the object [`Self::to_json()`]
+    /// returns. `None` for anything else: a block that is not an object,
+    /// a top-level key no world has, or a missing `world` or `requires`
+    /// block.
+    pub fn from_json(value: Json) -> Option<Self> {
+        let Json::Object(root) = value else {
+            return None;
+        };
+        let mut compiled = CompiledWorld::default();
+        let (mut world, mut requires) = (false, false);
+        for (key, block) in root {
+            world |= key == "world";
+            requires |= key == "requires";
+            let Json::Object(block) = block else {
+                return None;
+            };
+            if !compiled.insert(&key, block) {
+                return None;
+            }
+        }
+        (world && requires).then_some(compiled)
+    }
+
+    /// Parse `.urd.json` text (see [`Self::from_json()`]). `None` for
+    /// text that is not JSON.
+    pub fn parse(text: &str) -> Option<Self> {
+        Self::from_json(serde_json::from_str(text).ok()?)
+    }
+
+    /// The whole world as one JSON object, blocks in document order.
+    pub fn to_json(&self) -> Json {
+        Json::Object(self.blocks().map(|(key, block)| (key.to_string(), Json::Object(block.clone()))).collect())
+    }
+
+    /// The `.urd.json` text: pretty printed with a trailing newline, as
+    /// every EMIT output is written.
+    pub fn to_document(&self) -> String {
+        let mut out = Vec::new();
+        self.write_to(&mut out).expect("writing to a Vec cannot fail");
+        String::from_utf8(out).expect("serde_json writes UTF-8")
+    }
+
+    /// Write the `.urd.json` text to `writer`, one block at a time.
+    pub fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        writer.write_all(b"{")?;
+        for (i, (key, block)) in self.blocks().enumerate() {
+            super::write_block(writer, i == 0, key, block)?;
+        }
+        writer.write_all(b"\n}\n")
+    }
+}
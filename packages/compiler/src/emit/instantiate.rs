@@ -0,0 +1,180 @@
+/// Monomorphisation of parameterised sections.
+///
+/// A section declared `== haggle(@merchant: Merchant)` is a template. LINK
+/// resolves its body against `Merchant` and records every
+/// `-> haggle(@entity)` jump as an invocation. EMIT produces one concrete
+/// section per distinct argument, `tavern/haggle@harbor_master`, by cloning
+/// the template's content with the parameter replaced and building the
+/// clone like any other section. The template itself emits nothing.
+///
+/// Inside the template, a bare `-> haggle` or `? haggle.exhausted` refers
+/// to the current instance.
+
+use crate::ast::{Annotation, ConditionExpr, ContainerKind, ContentNode, DestinationKind, EffectType};
+use crate::symbol_table::SectionSymbol;
+
+/// One concrete instance of a template section.
+pub(super) struct Instance<'a> {
+    template_id: &'a str,
+    param: &'a str,
+    pub argument: &'a str,
+    pub id: String,
+}
+
+impl<'a> Instance<'a> {
+    /// Every instance of `section`, in order of first invocation. Empty for
+    /// unparameterised and uninvoked sections.
+    pub fn all(section: &'a SectionSymbol) -> Vec<Instance<'a>> {
+        let Some(param) = &section.param else {
+            return Vec::new();
+        };
+        section
+            .instance_arguments()
+            .into_iter()
+            .map(|argument| Instance {
+                template_id: &section.compiled_id,
+                param: &param.name,
+                argument,
+                id: section.instance_id(argument),
+            })
+            .collect()
+    }
+
+    /// Map an ID under the template (`tavern/haggle/ask`) to the same ID
+    /// under the instance (`tavern/haggle@harbor_master/ask`).
+    pub fn rebase(&self, id: &str) -> String {
+        match id.strip_prefix(self.template_id) {
+            Some(rest) if rest.is_empty() || rest.starts_with('/') => format!("{}{}", self.id, rest),
+            _ => id.to_string(),
+        }
+    }
+
+    /// The template's section symbol, renamed to the instance.
+    pub fn section_symbol(&self, template: &SectionSymbol) -> SectionSymbol {
+        let mut symbol = template.clone();
+        symbol.compiled_id = self.id.clone();
+        symbol.param = None;
+        symbol.invocations.clear();
+        for choice in &mut symbol.choices {
+            choice.compiled_id = self.rebase(&choice.compiled_id);
+        }
+        symbol
+    }
+
+    /// The argument in place of a reference to the parameter. Other
+    /// references are returned unchanged.
+    pub fn entity(&self, entity_ref: &str) -> String {
+        match entity_ref.strip_prefix('@') {
+            Some(name) if name == self.param => format!("@{}", self.argument),
+            None if entity_ref == self.param => self.argument.to_string(),
+            _ => entity_ref.to_string(),
+        }
+    }
+
+    /// Clone template content with the parameter substituted.
+    pub fn content(&self, nodes: &[&ContentNode]) -> Vec<ContentNode> {
+        nodes
+            .iter()
+            .map(|node| {
+                let mut node = (*node).clone();
+                self.substitute(&mut node);
+                node
+            })
+            .collect()
+    }
+
+    /// Clone one template node with the parameter substituted.
+    pub fn node(&self, node: &ContentNode) -> ContentNode {
+        let mut node = node.clone();
+        self.substitute(&mut node);
+        node
+    }
+
+    fn substitute(&self, node: &mut ContentNode) {
+        match node {
+            ContentNode::EntitySpeech(es) => {
+                es.entity_ref = self.entity(&es.entity_ref);
+                self.substitute_annotation(&mut es.annotation);
+            }
+            ContentNode::StageDirection(sd) => {
+                sd.entity_ref = self.entity(&sd.entity_ref);
+                self.substitute_annotation(&mut sd.annotation);
+            }
+            ContentNode::Choice(choice) => {
+                if let Some(target) = &mut choice.target {
+                    *target = self.entity(target);
+                }
+                self.substitute_annotation(&mut choice.annotation);
+                for child in &mut choice.content {
+                    self.substitute(child);
+                }
+            }
+            ContentNode::Condition(cond) => self.substitute_condition(&mut cond.expr),
+            ContentNode::OrConditionBlock(or) => {
+                for expr in &mut or.conditions {
+                    self.substitute_condition(expr);
+                }
+            }
+            ContentNode::Effect(effect) => {
+                match &mut effect.effect_type {
+                    EffectType::Set { target_prop, .. } | EffectType::Reveal { target_prop } => {
+                        if let Some((entity_ref, property)) = target_prop.split_once('.') {
+                            *target_prop = format!("{}.{}", self.entity(entity_ref), property);
+                        }
+                    }
+                    EffectType::Move { entity_ref, destination_ref } => {
+                        *entity_ref = self.entity(entity_ref);
+                        *destination_ref = self.entity(destination_ref);
+                    }
+                    EffectType::Destroy { entity_ref } => *entity_ref = self.entity(entity_ref),
+                    EffectType::End { .. } => {}
+                }
+                self.substitute_annotation(&mut effect.annotation);
+            }
+            ContentNode::Jump(jump) => {
+                // A bare jump to the template stays within this instance.
+                if let Some(ann) = &mut jump.annotation {
+                    if ann.resolved_section.as_deref() == Some(self.template_id) && ann.resolved_entity.is_none() {
+                        ann.resolved_entity = Some(self.argument.to_string());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn substitute_condition(&self, expr: &mut ConditionExpr) {
+        match expr {
+            ConditionExpr::PropertyComparison(pc) => {
+                pc.entity_ref = self.entity(&pc.entity_ref);
+                self.substitute_annotation(&mut pc.annotation);
+            }
+            ConditionExpr::ContainmentCheck(cc) => {
+                cc.entity_ref = self.entity(&cc.entity_ref);
+                cc.container_ref = self.entity(&cc.container_ref);
+                self.substitute_annotation(&mut cc.annotation);
+            }
+            ConditionExpr::ExhaustionCheck(ec) => {
+                if let Some(section) = ec.annotation.as_mut().and_then(|a| a.resolved_section.as_mut()) {
+                    *section = self.rebase(section);
+                }
+            }
+            ConditionExpr::VisitedCheck(_) | ConditionExpr::Once(_) => {}
+        }
+    }
+
+    fn substitute_annotation(&self, annotation: &mut Option<Annotation>) {
+        let Some(ann) = annotation else {
+            return;
+        };
+        if let Some(entity) = &mut ann.resolved_entity {
+            *entity = self.entity(entity);
+        }
+        if let Some(ContainerKind::EntityRef(id)) = &mut ann.container_kind {
+            *id = self.entity(id);
+        }
+        if let Some(DestinationKind::EntityRef(id)) = &mut ann.destination_kind {
+            *id = self.entity(id);
+        }
+    }
+}
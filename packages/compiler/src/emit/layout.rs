@@ -0,0 +1,233 @@
+/// Where EMIT places narrative text.
+///
+/// A file's top-level content divides into location descriptions and
+/// dialogue sections, and each section divides into three regions around
+/// its choices. EMIT builds its output from these partitions. VALIDATE
+/// walks the same partitions to find text that reaches no output field
+/// (URD443), so the warning and the emitted JSON cannot disagree.
+
+use crate::ast::{ContentNode, SectionLabel};
+use crate::span::Span;
+
+/// Whether top-level prose still belongs to the current location's
+/// description. The zone opens at a location heading and closes at the
+/// first exit, hook, entity list, section label, choice, or sequence or
+/// phase heading. LINK collects `LocationSymbol::description` with it.
+#[derive(Default)]
+pub(crate) struct DescriptionZone {
+    open: bool,
+}
+
+impl DescriptionZone {
+    /// Step past a top-level node. Returns `true` when the node is prose
+    /// that joins the location description.
+    pub fn visit(&mut self, node: &ContentNode) -> bool {
+        match node {
+            ContentNode::LocationHeading(_) => {
+                self.open = true;
+                false
+            }
+            ContentNode::Prose(_) => self.open,
+            ContentNode::RegionHeading(_)
+            | ContentNode::ExitDeclaration(_)
+            | ContentNode::LocationHook(_)
+            | ContentNode::EntityPresence(_)
+            | ContentNode::SectionLabel(_)
+            | ContentNode::Choice(_)
+            | ContentNode::SequenceHeading(_)
+            | ContentNode::PhaseHeading(_) => {
+                self.open = false;
+                false
+            }
+            _ => false,
+        }
+    }
+}
+
+/// A section label and the top-level nodes under it.
+pub(crate) struct SectionNodes<'a> {
+    pub label: &'a SectionLabel,
+    pub nodes: Vec<&'a ContentNode>,
+}
+
+/// Group a file's top-level content by section. A section runs from its
+/// label to the next section label, location heading, or region heading.
+pub(crate) fn sections(content: &[ContentNode]) -> Vec<SectionNodes<'_>> {
+    let mut sections: Vec<SectionNodes> = Vec::new();
+    let mut in_section = false;
+
+    for node in content {
+        match node {
+            ContentNode::SectionLabel(label) => {
+                sections.push(SectionNodes { label, nodes: Vec::new() });
+                in_section = true;
+            }
+            ContentNode::LocationHeading(_) | ContentNode::RegionHeading(_) => in_section = false,
+            _ => {
+                if in_section {
+                    if let Some(section) = sections.last_mut() {
+                        section.nodes.push(node);
+                    }
+                }
+            }
+        }
+    }
+    sections
+}
+
+/// A section's nodes split around its choices.
+pub(crate) struct Regions<'s, 'a> {
+    /// Region A, before the first choice: prompt, description, conditions.
+    pub before: &'s [&'a ContentNode],
+    /// Region B, from the first choice to the last. Only the choices are
+    /// emitted.
+    pub choices: &'s [&'a ContentNode],
+    /// Region C, after the last choice: `on_exhausted`.
+    pub after: &'s [&'a ContentNode],
+}
+
+impl<'s, 'a> Regions<'s, 'a> {
+    pub fn split(nodes: &'s [&'a ContentNode]) -> Self {
+        let is_choice = |n: &&&ContentNode| matches!(n, ContentNode::Choice(_));
+        let first = nodes.iter().position(|n| is_choice(&n));
+        let last = nodes.iter().rposition(|n| is_choice(&n));
+        match (first, last) {
+            (Some(first), Some(last)) => Regions {
+                before: &nodes[..first],
+                choices: &nodes[first..=last],
+                after: &nodes[last + 1..],
+            },
+            _ => Regions { before: nodes, choices: &[], after: &[] },
+        }
+    }
+}
+
+/// The Region C nodes that make up the `on_exhausted` text, in order.
+/// Prose and stage directions always join it; a speech line only when it
+/// opens the text, since the speaker applies to the whole field.
+pub(crate) fn exhausted_text<'a>(after: &[&'a ContentNode]) -> Vec<&'a ContentNode> {
+    let mut text = Vec::new();
+    for node in after {
+        let joins = match node {
+            ContentNode::EntitySpeech(_) => text.is_empty(),
+            ContentNode::Prose(p) => !p.text.trim().is_empty(),
+            ContentNode::StageDirection(_) => true,
+            _ => false,
+        };
+        if joins {
+            text.push(*node);
+        }
+    }
+    text
+}
+
+/// Why a piece of text reaches no output field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum DroppedPlace<'a> {
+    /// Before any location heading and outside any section.
+    Outside,
+    /// Prose in a location after its description has ended.
+    AfterDescription { location: &'a str },
+    /// Speech or a stage direction in a location, outside any section.
+    LocationSpeech { location: &'a str },
+    /// Between the first and last choice of a section.
+    BetweenChoices { section: &'a str },
+    /// A speech line after the last choice that does not open the
+    /// `on_exhausted` text.
+    LateExhaustedSpeech { section: &'a str },
+    /// Prose inside a choice.
+    ChoiceProse { label: &'a str },
+}
+
+/// A prose, speech, or stage direction line that EMIT will not output.
+pub(crate) struct DroppedText<'a> {
+    pub span: &'a Span,
+    pub place: DroppedPlace<'a>,
+}
+
+/// Find every text line in a file that EMIT will not place in the output.
+pub(crate) fn dropped_text(content: &[ContentNode]) -> Vec<DroppedText<'_>> {
+    let mut dropped = Vec::new();
+
+    // Top level: location descriptions.
+    let mut zone = DescriptionZone::default();
+    let mut location: Option<&str> = None;
+    let mut in_section = false;
+    for node in content {
+        let in_description = zone.visit(node);
+        match node {
+            ContentNode::LocationHeading(heading) => {
+                location = Some(&heading.display_name);
+                in_section = false;
+            }
+            ContentNode::RegionHeading(_) => {
+                location = None;
+                in_section = false;
+            }
+            ContentNode::SectionLabel(_) => in_section = true,
+            _ if in_section || in_description => {}
+            _ => {
+                let Some(span) = text_span(node) else {
+                    continue;
+                };
+                let place = match (location, node) {
+                    (None, _) => DroppedPlace::Outside,
+                    (Some(location), ContentNode::Prose(_)) => DroppedPlace::AfterDescription { location },
+                    (Some(location), _) => DroppedPlace::LocationSpeech { location },
+                };
+                dropped.push(DroppedText { span, place });
+            }
+        }
+    }
+
+    // Sections: the three regions and choice content.
+    for section in sections(content) {
+        let name = section.label.name.as_str();
+        let regions = Regions::split(&section.nodes);
+        for node in regions.choices {
+            match node {
+                ContentNode::Choice(choice) => dropped_in_choice(choice, &mut dropped),
+                _ => {
+                    if let Some(span) = text_span(node) {
+                        dropped.push(DroppedText { span, place: DroppedPlace::BetweenChoices { section: name } });
+                    }
+                }
+            }
+        }
+        let kept = exhausted_text(regions.after);
+        for node in regions.after {
+            if kept.iter().any(|k| std::ptr::eq(*k, *node)) {
+                continue;
+            }
+            if let Some(span) = text_span(node) {
+                dropped.push(DroppedText { span, place: DroppedPlace::LateExhaustedSpeech { section: name } });
+            }
+        }
+    }
+
+    dropped.sort_by_key(|d| (d.span.start_line, d.span.start_col));
+    dropped
+}
+
+fn dropped_in_choice<'a>(choice: &'a crate::ast::Choice, dropped: &mut Vec<DroppedText<'a>>) {
+    for child in &choice.content {
+        match child {
+            ContentNode::Prose(p) if !p.text.trim().is_empty() => {
+                dropped.push(DroppedText { span: &p.span, place: DroppedPlace::ChoiceProse { label: &choice.label } });
+            }
+            ContentNode::Choice(sub) => dropped_in_choice(sub, dropped),
+            _ => {}
+        }
+    }
+}
+
+/// The span of a prose, speech, or stage direction node. Blank prose is
+/// not text.
+fn text_span(node: &ContentNode) -> Option<&Span> {
+    match node {
+        ContentNode::Prose(p) if !p.text.trim().is_empty() => Some(&p.span),
+        ContentNode::EntitySpeech(es) => Some(&es.span),
+        ContentNode::StageDirection(sd) => Some(&sd.span),
+        _ => None,
+    }
+}
@@ -0,0 +1,134 @@
+/// Output map recording for EMIT (see [`crate::output_map`]).
+///
+/// Each finished top-level block is walked beside the symbols and AST
+/// nodes it was built from. Dialogue choices follow the AST in the order
+/// the section builders take them, so an entry's span is the node the
+/// emitted data came from.
+
+use indexmap::IndexMap;
+use serde_json::Value as Json;
+
+use crate::ast::{Choice, ContentNode, RuleBlock};
+use crate::graph::DependencyGraph;
+use crate::output_map::{JsonPath, OutputMap};
+use crate::symbol_table::{SectionSymbol, SymbolTable};
+
+use super::instantiate::Instance;
+use super::layout::Regions;
+use super::{collect_section_nodes, find_nested_choice_symbol};
+
+/// Record the entries for the finished top-level block `key`.
+pub(super) fn record_block(
+    map: &mut OutputMap,
+    key: &str,
+    block: &Json,
+    graph: &DependencyGraph,
+    symbol_table: &SymbolTable,
+) {
+    let root = JsonPath::default().key(key);
+    match key {
+        "types" => {
+            for (name, ts) in &symbol_table.types {
+                map.record(block, root.key(name), &ts.declared_in);
+                for (prop, ps) in &ts.properties {
+                    map.record(block, root.key(name).key("properties").key(prop), &ps.declared_in);
+                }
+            }
+        }
+        "entities" => symbol_table.entities.iter().for_each(|(id, s)| map.record(block, root.key(id), &s.declared_in)),
+        "regions" => symbol_table.regions.iter().for_each(|(id, s)| map.record(block, root.key(id), &s.declared_in)),
+        "actions" => symbol_table.actions.iter().for_each(|(id, s)| map.record(block, root.key(id), &s.declared_in)),
+        "endings" => symbol_table.endings.iter().for_each(|(id, s)| map.record(block, root.key(id), &s.declared_in)),
+        "locations" => {
+            for (id, ls) in &symbol_table.locations {
+                map.record(block, root.key(id), &ls.declared_in);
+                for (direction, es) in &ls.exits {
+                    map.record(block, root.key(id).key("exits").key(direction), &es.declared_in);
+                }
+            }
+        }
+        "rules" => {
+            let rule_blocks = rule_blocks(graph);
+            for (id, rs) in &symbol_table.rules {
+                map.record(block, root.key(id), &rs.declared_in);
+                let effects = rule_blocks.get(id.as_str()).map(|rb| rb.effects.as_slice()).unwrap_or_default();
+                for (i, effect) in effects.iter().enumerate() {
+                    map.record(block, root.key(id).key("effects").index(i), &effect.span);
+                }
+            }
+        }
+        "sequences" => {
+            for (id, seq) in &symbol_table.sequences {
+                map.record(block, root.key(id), &seq.declared_in);
+                for (i, phase) in seq.phases.iter().enumerate() {
+                    map.record(block, root.key(id).key("phases").index(i), &phase.declared_in);
+                }
+            }
+        }
+        "dialogue" => {
+            let paths: Vec<&str> = graph.nodes.keys().map(String::as_str).collect();
+            let section_nodes = collect_section_nodes(graph, &paths);
+            for ss in symbol_table.sections.values() {
+                let nodes = section_nodes.get(&ss.compiled_id).map(Vec::as_slice).unwrap_or_default();
+                let top_level: Vec<&Choice> = Regions::split(nodes)
+                    .choices
+                    .iter()
+                    .filter_map(|node| match node {
+                        ContentNode::Choice(choice) => Some(choice),
+                        _ => None,
+                    })
+                    .collect();
+                if ss.param.is_none() {
+                    record_section(map, block, &root, ss, &top_level);
+                }
+                // An instance is built from the template's nodes, spans and all.
+                for instance in Instance::all(ss) {
+                    record_section(map, block, &root, &instance.section_symbol(ss), &top_level);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn record_section(map: &mut OutputMap, block: &Json, root: &JsonPath, ss: &SectionSymbol, choices: &[&Choice]) {
+    let path = root.key(&ss.compiled_id);
+    map.record(block, path.clone(), &ss.declared_in);
+    record_choices(map, block, &path, choices, ss);
+}
+
+/// Record the `choices` under `parent`, each one's `effects`, and its
+/// nested choices in turn. `choices` are the AST nodes the emitted array
+/// was built from, in the same order; nested choices without a symbol
+/// are skipped, as EMIT skips them.
+fn record_choices(map: &mut OutputMap, block: &Json, parent: &JsonPath, choices: &[&Choice], ss: &SectionSymbol) {
+    for (i, choice) in choices.iter().enumerate() {
+        let path = parent.key("choices").index(i);
+        map.record(block, path.clone(), &choice.span);
+        let mut nested = Vec::new();
+        let mut effects = 0;
+        for child in &choice.content {
+            match child {
+                ContentNode::Effect(effect) => {
+                    map.record(block, path.key("effects").index(effects), &effect.span);
+                    effects += 1;
+                }
+                ContentNode::Choice(sub) if find_nested_choice_symbol(&sub.label, ss).is_some() => nested.push(sub),
+                _ => {}
+            }
+        }
+        record_choices(map, block, &path, &nested, ss);
+    }
+}
+
+fn rule_blocks(graph: &DependencyGraph) -> IndexMap<&str, &RuleBlock> {
+    let mut blocks = IndexMap::new();
+    for node in graph.nodes.values() {
+        for content in &node.ast.content {
+            if let ContentNode::RuleBlock(rb) = content {
+                blocks.insert(rb.name.as_str(), rb);
+            }
+        }
+    }
+    blocks
+}
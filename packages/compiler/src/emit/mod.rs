@@ -1,97 +1,475 @@
 /// Phase 5: EMIT — validated ASTs + symbol table to `.urd.json`.
 ///
 /// Input:  Validated ASTs + `SymbolTable` (with zero Error-severity diagnostics)
-/// Output: `.urd.json` string
+/// Output: [`CompiledWorld`] and its `.urd.json` string
 ///
 /// EMIT runs only when the diagnostic collector contains zero errors.
 /// It traverses pre-validated data structures in a fixed, deterministic order.
 ///
 /// Key guarantee: output conforms to JSON Schema, deterministic,
-/// `urd: "1"` injected, byte-identical across repeated compilations.
+/// `urd` set to the target [`Format`], byte-identical across repeated
+/// compilations.
 ///
 /// Diagnostic code range: URD500–URD599
 
+use std::convert::Infallible;
+use std::io::{self, Write};
+
 use indexmap::IndexMap;
 use serde_json::{Map, Value as Json, Number};
 
 use crate::ast::{
     ConditionExpr, ContainerKind, ContentNode, DestinationKind, EffectType,
-    FrontmatterValue, Scalar,
+    FrontmatterValue, HookKind, Scalar,
 };
 use crate::diagnostics::DiagnosticCollector;
 use crate::graph::{file_stem, DependencyGraph};
+use crate::output_map::OutputMap;
+use crate::property_groups;
 use crate::slugify::slugify;
+use crate::span::Span;
+use crate::strings::TextSink;
 use crate::symbol_table::{
-    PropertyType, SymbolTable, Value, Visibility,
+    EntitySymbol, LocationSymbol, PropertyType, SectionSymbol, SymbolTable, Value, Visibility, WorldMode,
 };
 
+mod compiled;
+mod instantiate;
+pub(crate) mod layout;
+mod mapping;
+pub(crate) mod numeric_ids;
+mod requires;
+mod split;
+
+use instantiate::Instance;
+use layout::Regions;
+
+pub use compiled::CompiledWorld;
+pub use split::emit_split;
+
+/// Options controlling EMIT output.
+#[derive(Debug, Clone, Default)]
+pub struct EmitOptions {
+    /// Replace player-visible text with its string table key (see
+    /// [`crate::strings`]). The world block carries `"strings": "external"`
+    /// so runtimes know to look text up in the table.
+    pub externalize_strings: bool,
+    /// Also produce one document per top-level block (see [`emit_split()`]).
+    /// Does not change the combined `.urd.json` output.
+    pub split: bool,
+    /// Add a `"doc"` field holding `///` doc comments to type, entity,
+    /// location, and section objects. Off by default so player-facing
+    /// exports carry no author notes.
+    pub include_docs: bool,
+    /// Add an `"idx"` integer to every emitted symbol and a top-level
+    /// `"index"` block mapping string IDs to them (see [`numeric_ids`]).
+    pub numeric_ids: bool,
+    /// Record the source span of each emitted object (see
+    /// [`crate::output_map`]).
+    /// Read it with [`emit_mapped()`] or from
+    /// `CompilationResult::output_map`. Does not change the output.
+    pub build_output_map: bool,
+    /// Name the compiler in the world block's `"generator"` field
+    /// (`"urd-compiler X.Y.Z"`, see [`crate::version`]). The CLI sets it;
+    /// the library and WASM leave it off, so a world compiles to the same
+    /// bytes under every compiler version that emits it the same way.
+    pub include_generator: bool,
+    /// The format version to write. Format 1, the default, has no text
+    /// variants: only the first line of each is emitted (URD501).
+    pub format: Format,
+}
+
+/// An `urd` format version EMIT can write (see
+/// [`crate::version::FORMAT_VERSIONS`]).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// Every text field is a string.
+    #[default]
+    V1,
+    /// A text field with `|` variants is `{"variants": [...]}`, the line
+    /// as written first.
+    V2,
+}
+
+impl Format {
+    /// The world block's `urd` value.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Format::V1 => "1",
+            Format::V2 => "2",
+        }
+    }
+}
+
+/// Report text EMIT cannot carry in `options.format`: under format 1,
+/// each speech line or prose with `|` variants loses all but its first
+/// line (URD501). Run once per compile, before emitting.
+pub fn check_format(graph: &DependencyGraph, options: &EmitOptions, diagnostics: &mut DiagnosticCollector) {
+    if options.format != Format::V1 {
+        return;
+    }
+    for path in graph.topological_order() {
+        if let Some(node) = graph.nodes.get(path.as_str()) {
+            report_dropped_variants(&node.ast.content, diagnostics);
+        }
+    }
+}
+
+fn report_dropped_variants(nodes: &[ContentNode], diagnostics: &mut DiagnosticCollector) {
+    for node in nodes {
+        let (count, span) = match node {
+            ContentNode::EntitySpeech(es) => (es.variants.len(), &es.span),
+            ContentNode::Prose(p) => (p.variants.len(), &p.span),
+            ContentNode::Choice(c) => {
+                report_dropped_variants(&c.content, diagnostics);
+                continue;
+            }
+            _ => continue,
+        };
+        if count > 0 {
+            diagnostics.warning(
+                "URD501",
+                format!(
+                    "Text variants need format 2. Only the first line is emitted; {} variant{} dropped.",
+                    count,
+                    if count == 1 { " is" } else { "s are" }
+                ),
+                span.clone(),
+            );
+        }
+    }
+}
+
 /// Emit the compiled `.urd.json` string from the validated world.
 ///
 /// Precondition: `diagnostics.has_errors()` is `false`.
 pub fn emit(
+    graph: &DependencyGraph,
+    symbol_table: &SymbolTable,
+    diagnostics: &mut DiagnosticCollector,
+) -> String {
+    emit_with_options(graph, symbol_table, diagnostics, &EmitOptions::default())
+}
+
+/// Emit the compiled `.urd.json` string with explicit options.
+///
+/// Precondition: `diagnostics.has_errors()` is `false`.
+pub fn emit_with_options(
     graph: &DependencyGraph,
     symbol_table: &SymbolTable,
     _diagnostics: &mut DiagnosticCollector,
+    options: &EmitOptions,
 ) -> String {
+    build_compiled(graph, symbol_table, options, None).to_document()
+}
+
+/// Emit the compiled world as a typed value, for hosts that read it in
+/// Rust. [`CompiledWorld::to_document()`] gives the string
+/// [`emit_with_options()`] returns.
+///
+/// Precondition: `diagnostics.has_errors()` is `false`.
+pub fn emit_value(
+    graph: &DependencyGraph,
+    symbol_table: &SymbolTable,
+    _diagnostics: &mut DiagnosticCollector,
+    options: &EmitOptions,
+) -> CompiledWorld {
+    build_compiled(graph, symbol_table, options, None)
+}
+
+/// Emit the compiled world and its output map, whatever
+/// `options.build_output_map` says.
+///
+/// Precondition: `diagnostics.has_errors()` is `false`.
+pub fn emit_mapped(
+    graph: &DependencyGraph,
+    symbol_table: &SymbolTable,
+    _diagnostics: &mut DiagnosticCollector,
+    options: &EmitOptions,
+) -> (CompiledWorld, OutputMap) {
+    let mut map = OutputMap::default();
+    let world = build_compiled(graph, symbol_table, options, Some(&mut map));
+    (world, map)
+}
+
+/// The object `entities[entity_id]` holds in the full output.
+///
+/// Like [`emit_location()`] and [`emit_dialogue_section()`], this builds
+/// one entry for previews without serialising the whole world. Output
+/// matches [`emit()`] with default options: no docs, no numeric IDs, text
+/// inline. `None` for an unknown ID.
+pub fn emit_entity(symbol_table: &SymbolTable, entity_id: &str) -> Option<Json> {
+    symbol_table.entities.get(entity_id).map(build_entity)
+}
+
+/// The object `locations[location_id]` holds in the full output. Only the
+/// file that declares the location is walked. `None` for an unknown ID.
+pub fn emit_location(
+    graph: &DependencyGraph,
+    symbol_table: &SymbolTable,
+    location_id: &str,
+) -> Option<Json> {
+    let ls = symbol_table.locations.get(location_id)?;
+    let content = collect_location_content(graph, &[ls.declared_in.file.as_str()], symbol_table);
+    let mut texts = TextSink::new(false, false);
+    Some(build_location_json(location_id, ls, content.get(location_id), &mut texts))
+}
+
+/// The object `dialogue[section_id]` holds in the full output. An instance
+/// ID (`tavern/haggle@harbor_master`) builds that instance; a template's
+/// own ID is `None`, as the template emits nothing. Only the file that
+/// declares the section is walked. `None` for an unknown ID.
+pub fn emit_dialogue_section(
+    graph: &DependencyGraph,
+    symbol_table: &SymbolTable,
+    section_id: &str,
+) -> Option<Json> {
+    let ss = match symbol_table.sections.get(section_id) {
+        Some(ss) => ss,
+        None => {
+            let (template_id, _) = section_id.rsplit_once('@')?;
+            symbol_table.sections.get(template_id).filter(|ss| ss.param.is_some())?
+        }
+    };
+    let section_nodes = collect_section_nodes(graph, &[ss.declared_in.file.as_str()]);
+    let mut texts = TextSink::new(false, false);
+    build_section_entries(ss, &section_nodes, symbol_table, &mut texts)
+        .into_iter()
+        .find_map(|(id, json)| (id == section_id).then_some(json))
+}
+
+/// Serialise a JSON document the way every EMIT output is written: pretty
+/// printed with a trailing newline.
+fn to_document(value: &Json) -> String {
+    let mut output = serde_json::to_string_pretty(value).unwrap();
+    output.push('\n');
+    output
+}
+
+/// Write the compiled `.urd.json` document to `writer`, block by block.
+///
+/// The output is byte-identical to [`emit_with_options()`], but the whole
+/// document is never held in memory: each top-level block is built,
+/// written, flushed, and dropped before the next one is built, so peak
+/// memory follows the largest block rather than the world. Hosts writing
+/// a large world to a file should prefer this.
+pub fn emit_to_writer(
+    graph: &DependencyGraph,
+    symbol_table: &SymbolTable,
+    options: &EmitOptions,
+    writer: &mut impl Write,
+) -> io::Result<()> {
+    writer.write_all(b"{")?;
+    let mut first = true;
+    build_blocks(graph, symbol_table, options, None, |key, block| {
+        write_block(writer, first, key, &block)?;
+        first = false;
+        writer.flush()
+    })?;
+    writer.write_all(b"\n}\n")?;
+    writer.flush()
+}
+
+/// Write one `"key": { ... }` member of the root object, preceded by a
+/// comma unless it is the `first`. Members written in turn between `{`
+/// and `\n}\n` match [`to_document()`] byte for byte.
+fn write_block(writer: &mut impl Write, first: bool, key: &str, block: &Map<String, Json>) -> io::Result<()> {
+    let separator: &[u8] = if first { b"\n  " } else { b",\n  " };
+    writer.write_all(separator)?;
+    serde_json::to_writer(&mut *writer, key)?;
+    writer.write_all(b": ")?;
+    serde_json::to_writer_pretty(Indented(&mut *writer), block)?;
+    Ok(())
+}
+
+/// Indents everything written through it by one level (two spaces), so a
+/// block serialised on its own lines up as a value of the root object.
+/// Pretty-printed JSON escapes newlines inside strings, so every `\n` it
+/// writes is a line break between tokens.
+struct Indented<W>(W);
+
+impl<W: Write> Write for Indented<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut lines = buf.split(|&b| b == b'\n');
+        if let Some(line) = lines.next() {
+            self.0.write_all(line)?;
+        }
+        for line in lines {
+            self.0.write_all(b"\n  ")?;
+            self.0.write_all(line)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+/// Build the top-level blocks of the compiled world, in emission order.
+fn build_root(
+    graph: &DependencyGraph,
+    symbol_table: &SymbolTable,
+    options: &EmitOptions,
+    output_map: Option<&mut OutputMap>,
+) -> Map<String, Json> {
+    let mut root = Map::new();
+    build_blocks(graph, symbol_table, options, output_map, |key, block| {
+        root.insert(key.to_string(), Json::Object(block));
+        Ok::<(), Infallible>(())
+    })
+    .unwrap_or_else(|never| match never {});
+    root
+}
+
+/// Build the compiled world, block by block.
+fn build_compiled(
+    graph: &DependencyGraph,
+    symbol_table: &SymbolTable,
+    options: &EmitOptions,
+    output_map: Option<&mut OutputMap>,
+) -> CompiledWorld {
+    let mut world = CompiledWorld::default();
+    build_blocks(graph, symbol_table, options, output_map, |key, block| {
+        world.insert(key, block);
+        Ok::<(), Infallible>(())
+    })
+    .unwrap_or_else(|never| match never {});
+    world
+}
+
+/// Build the top-level blocks in emission order, handing each finished
+/// block to `sink` before the next is built. Stops at the first error
+/// `sink` returns. Each block's entries are added to `output_map`, if
+/// given, before it is handed on.
+fn build_blocks<E>(
+    graph: &DependencyGraph,
+    symbol_table: &SymbolTable,
+    options: &EmitOptions,
+    mut output_map: Option<&mut OutputMap>,
+    mut sink: impl FnMut(&str, Map<String, Json>) -> Result<(), E>,
+) -> Result<(), E> {
     let ordered = graph.topological_order();
     let ordered_paths: Vec<&str> = ordered.iter().map(|s| s.as_str()).collect();
+    let mut texts = TextSink::new(options.externalize_strings, options.format == Format::V2);
+    let mut numbering = options.numeric_ids.then(numeric_ids::Numbering::default);
 
-    let mut root = Map::new();
+    let mut put = |key: &str, mut block: Json| {
+        if options.include_docs {
+            insert_docs(key, &mut block, symbol_table);
+        }
+        if let Some(numbering) = &mut numbering {
+            numbering.number(key, &mut block);
+        }
+        if let Some(map) = output_map.as_deref_mut() {
+            mapping::record_block(map, key, &block, graph, symbol_table);
+        }
+        let Json::Object(block) = block else {
+            unreachable!("every block is built as an object");
+        };
+        sink(key, block)
+    };
 
     // Step 1: world (always present)
-    root.insert("world".to_string(), build_world(graph, symbol_table));
+    let mut world = build_world(graph, symbol_table);
+    if let Json::Object(obj) = &mut world {
+        obj.insert("urd".to_string(), Json::String(options.format.as_str().to_string()));
+        if options.externalize_strings {
+            obj.insert("strings".to_string(), Json::String("external".to_string()));
+        }
+        if options.include_generator {
+            obj.insert("generator".to_string(), Json::String(crate::version::generator()));
+        }
+    }
+    put("world", world)?;
+
+    // Step 1a: requires (always present)
+    put("requires", requires::build_requires(graph, symbol_table))?;
 
     // Step 2: types
     if !symbol_table.types.is_empty() {
-        root.insert("types".to_string(), build_types(symbol_table));
+        put("types", build_types(symbol_table))?;
     }
 
     // Step 3: entities
     if !symbol_table.entities.is_empty() {
-        root.insert("entities".to_string(), build_entities(symbol_table));
+        put("entities", build_entities(symbol_table))?;
+    }
+
+    // Step 3a: display names
+    let display_names = build_display_names(symbol_table);
+    if !display_names.is_empty() {
+        put("display_names", Json::Object(display_names))?;
     }
 
     // Step 4: locations
     if !symbol_table.locations.is_empty() {
-        root.insert(
-            "locations".to_string(),
-            build_locations(graph, &ordered_paths, symbol_table),
-        );
+        put("locations", build_locations(graph, &ordered_paths, symbol_table, &mut texts))?;
+    }
+
+    // Step 4a: regions
+    if !symbol_table.regions.is_empty() {
+        put("regions", build_regions(symbol_table))?;
     }
 
     // Step 5: rules
     if !symbol_table.rules.is_empty() {
-        root.insert(
-            "rules".to_string(),
-            build_rules(graph, &ordered_paths, symbol_table),
-        );
+        put("rules", build_rules(graph, &ordered_paths, symbol_table))?;
     }
 
     // Step 6: actions
     if !symbol_table.actions.is_empty() {
-        root.insert(
-            "actions".to_string(),
-            build_actions(graph, &ordered_paths, symbol_table),
-        );
+        put("actions", build_actions(graph, &ordered_paths, symbol_table))?;
     }
 
     // Step 7: sequences
     if !symbol_table.sequences.is_empty() {
-        root.insert("sequences".to_string(), build_sequences(symbol_table));
+        put("sequences", build_sequences(symbol_table))?;
+    }
+
+    // Step 7a: endings
+    if !symbol_table.endings.is_empty() {
+        put("endings", build_endings(symbol_table, &mut texts))?;
     }
 
     // Step 8: dialogue
     if !symbol_table.sections.is_empty() {
-        root.insert(
-            "dialogue".to_string(),
-            build_dialogue(graph, &ordered_paths, symbol_table),
-        );
+        put("dialogue", build_dialogue(graph, &ordered_paths, symbol_table, &mut texts))?;
     }
 
-    let json_value = Json::Object(root);
-    let mut output = serde_json::to_string_pretty(&json_value).unwrap();
-    output.push('\n');
-    output
+    if let Some(numbering) = numbering {
+        sink("index", numbering.into_index())?;
+    }
+    Ok(())
+}
+
+/// Append `"doc"` to every documented type, entity, location, or section
+/// in `block`. Runs on each block once it is built, so the text builders
+/// shared with string extraction stay unaware of docs.
+fn insert_docs(block: &str, value: &mut Json, symbol_table: &SymbolTable) {
+    let Json::Object(objects) = value else {
+        return;
+    };
+    let mut attach = |id: &str, doc: &Option<String>| {
+        if let (Some(doc), Some(Json::Object(obj))) = (doc, objects.get_mut(id)) {
+            obj.insert("doc".to_string(), Json::String(doc.clone()));
+        }
+    };
+    match block {
+        "types" => symbol_table.types.iter().for_each(|(k, s)| attach(k, &s.doc)),
+        "entities" => symbol_table.entities.iter().for_each(|(k, s)| attach(k, &s.doc)),
+        "locations" => symbol_table.locations.iter().for_each(|(k, s)| attach(k, &s.doc)),
+        "dialogue" => {
+            for (k, s) in &symbol_table.sections {
+                attach(k, &s.doc);
+                // Instances of a parameterised section share its doc.
+                for instance in Instance::all(s) {
+                    attach(&instance.id, &s.doc);
+                }
+            }
+        }
+        _ => {}
+    }
 }
 
 // ── Step 1: World ──
@@ -151,12 +529,16 @@ fn build_world(graph: &DependencyGraph, symbol_table: &SymbolTable) -> Json {
         }
     }
 
-    // Re-insert in fixed key order: name, urd, version, description, author, start, entry, seed.
+    // Re-insert in fixed key order: name, urd, mode, version, description,
+    // author, start, entry, start_inventory, seed.
     let mut ordered = Map::new();
     if let Some(v) = world.remove("name") {
         ordered.insert("name".to_string(), v);
     }
-    ordered.insert("urd".to_string(), Json::String("1".to_string()));
+    ordered.insert("urd".to_string(), Json::String(Format::V1.as_str().to_string()));
+    if symbol_table.world_mode != WorldMode::World {
+        ordered.insert("mode".to_string(), Json::String(symbol_table.world_mode.name().to_string()));
+    }
     if let Some(v) = world.remove("version") {
         ordered.insert("version".to_string(), v);
     }
@@ -172,6 +554,18 @@ fn build_world(graph: &DependencyGraph, symbol_table: &SymbolTable) -> Json {
     if let Some(entry) = &symbol_table.world_entry {
         ordered.insert("entry".to_string(), Json::String(entry.clone()));
     }
+    if !symbol_table.world_start_inventory.is_empty() {
+        ordered.insert(
+            "start_inventory".to_string(),
+            Json::Array(
+                symbol_table
+                    .world_start_inventory
+                    .iter()
+                    .map(|id| Json::String(id.clone()))
+                    .collect(),
+            ),
+        );
+    }
     if let Some(v) = world.remove("seed") {
         ordered.insert("seed".to_string(), v);
     }
@@ -194,10 +588,10 @@ fn build_types(symbol_table: &SymbolTable) -> Json {
         }
 
         if !ts.properties.is_empty() {
-            let mut props = Map::new();
-            for (prop_name, ps) in &ts.properties {
-                props.insert(prop_name.clone(), build_property(ps));
-            }
+            // Dotted names nest into property groups.
+            let props = property_groups::nest(
+                ts.properties.iter().map(|(prop_name, ps)| (prop_name.clone(), build_property(ps))),
+            );
             type_obj.insert("properties".to_string(), Json::Object(props));
         }
 
@@ -235,12 +629,14 @@ fn build_property(ps: &crate::symbol_table::PropertySymbol) -> Json {
         );
     }
 
-    // min/max
-    if let Some(min) = ps.min {
-        prop.insert("min".to_string(), number_to_json(min));
-    }
-    if let Some(max) = ps.max {
-        prop.insert("max".to_string(), number_to_json(max));
+    // min/max, on numeric properties only (URD417 warns of the rest)
+    if matches!(ps.property_type, PropertyType::Integer | PropertyType::Number) {
+        if let Some(min) = ps.min {
+            prop.insert("min".to_string(), number_to_json(min));
+        }
+        if let Some(max) = ps.max {
+            prop.insert("max".to_string(), number_to_json(max));
+        }
     }
 
     // ref_type
@@ -256,162 +652,240 @@ fn build_property(ps: &crate::symbol_table::PropertySymbol) -> Json {
 fn build_entities(symbol_table: &SymbolTable) -> Json {
     let mut entities = Map::new();
     for (id, es) in &symbol_table.entities {
-        let mut entity_obj = Map::new();
-        entity_obj.insert("type".to_string(), Json::String(es.type_name.clone()));
+        entities.insert(id.clone(), build_entity(es));
+    }
+    Json::Object(entities)
+}
 
-        if !es.property_overrides.is_empty() {
-            let mut props = Map::new();
-            for (prop_name, val) in &es.property_overrides {
-                props.insert(prop_name.clone(), value_to_json(val));
-            }
-            entity_obj.insert("properties".to_string(), Json::Object(props));
-        }
+fn build_entity(es: &EntitySymbol) -> Json {
+    let mut entity_obj = Map::new();
+    entity_obj.insert("type".to_string(), Json::String(es.type_name.clone()));
 
-        entities.insert(id.clone(), Json::Object(entity_obj));
+    if !es.property_overrides.is_empty() {
+        let props = property_groups::nest(
+            es.property_overrides.iter().map(|(prop_name, val)| (prop_name.clone(), value_to_json(val))),
+        );
+        entity_obj.insert("properties".to_string(), Json::Object(props));
     }
-    Json::Object(entities)
+
+    Json::Object(entity_obj)
+}
+
+/// Entity ID to the name a runtime shows for it, in declaration order.
+/// Entities without a name are left out.
+fn build_display_names(symbol_table: &SymbolTable) -> Map<String, Json> {
+    symbol_table
+        .entities
+        .keys()
+        .filter_map(|id| {
+            let name = symbol_table.display_name(id)?;
+            Some((id.clone(), Json::String(name.to_string())))
+        })
+        .collect()
 }
 
 // ── Step 4: Locations ──
 
-fn build_locations(
+pub(crate) fn build_locations(
     graph: &DependencyGraph,
     ordered_paths: &[&str],
     symbol_table: &SymbolTable,
+    texts: &mut TextSink,
 ) -> Json {
-    // Collect location descriptions and exit content from AST.
-    let mut loc_descriptions: IndexMap<String, Vec<String>> = IndexMap::new();
-    let mut exit_content: IndexMap<(String, String), ExitContent> = IndexMap::new();
+    let content = collect_location_content(graph, ordered_paths, symbol_table);
+    let mut locations = Map::new();
+    for (id, ls) in &symbol_table.locations {
+        locations.insert(id.clone(), build_location_json(id, ls, content.get(id), texts));
+    }
+    Json::Object(locations)
+}
 
-    for file_path in ordered_paths {
+/// What a location's JSON takes from the AST rather than its symbol.
+#[derive(Default)]
+struct LocationContent {
+    exits: IndexMap<String, ExitContent>,
+    hooks: IndexMap<HookKind, Json>,
+}
+
+/// Collect exit content and hooks for every location declared in `paths`.
+/// Descriptions are on the symbol, collected in LINK.
+fn collect_location_content(
+    graph: &DependencyGraph,
+    paths: &[&str],
+    symbol_table: &SymbolTable,
+) -> IndexMap<String, LocationContent> {
+    let mut content_by_loc: IndexMap<String, LocationContent> = IndexMap::new();
+
+    for file_path in paths {
         let node = match graph.nodes.get(*file_path) {
             Some(n) => n,
             None => continue,
         };
 
         let mut current_loc_id: Option<String> = None;
-        let mut desc_buffer: Vec<String> = Vec::new();
-        let mut in_desc_zone = false;
 
         for content in &node.ast.content {
             match content {
-                ContentNode::LocationHeading(loc) => {
-                    // Flush previous location's description.
-                    if let Some(loc_id) = &current_loc_id {
-                        if !desc_buffer.is_empty() {
-                            loc_descriptions
-                                .entry(loc_id.clone())
-                                .or_default()
-                                .extend(desc_buffer.drain(..));
-                        }
-                    }
-                    let id = slugify(&loc.display_name);
-                    current_loc_id = Some(id);
-                    in_desc_zone = true;
-                    desc_buffer.clear();
-                }
-                ContentNode::Prose(prose) if in_desc_zone => {
-                    let trimmed = prose.text.trim().to_string();
-                    if !trimmed.is_empty() {
-                        desc_buffer.push(trimmed);
-                    }
+                ContentNode::LocationHeading(_) | ContentNode::RegionHeading(_) => {
+                    // Content after a region heading belongs to no location
+                    // until the next location heading.
+                    current_loc_id = match content {
+                        ContentNode::LocationHeading(loc) => Some(loc.id()),
+                        _ => None,
+                    };
                 }
                 ContentNode::ExitDeclaration(exit) => {
-                    in_desc_zone = false;
                     if let Some(loc_id) = &current_loc_id {
                         let ec = collect_exit_content(&exit.children, symbol_table);
-                        exit_content.insert(
-                            (loc_id.clone(), exit.direction.clone()),
-                            ec,
-                        );
+                        content_by_loc
+                            .entry(loc_id.clone())
+                            .or_default()
+                            .exits
+                            .insert(exit.direction.clone(), ec);
                     }
                 }
-                ContentNode::EntityPresence(_)
-                | ContentNode::SectionLabel(_)
-                | ContentNode::Choice(_)
-                | ContentNode::SequenceHeading(_)
-                | ContentNode::PhaseHeading(_) => {
-                    in_desc_zone = false;
+                ContentNode::LocationHook(hook) => {
+                    if let Some(loc_id) = &current_loc_id {
+                        // Duplicates were rejected in LINK; the first block wins.
+                        content_by_loc
+                            .entry(loc_id.clone())
+                            .or_default()
+                            .hooks
+                            .entry(hook.kind)
+                            .or_insert_with(|| build_hook_json(&hook.children, symbol_table));
+                    }
                 }
                 _ => {}
             }
         }
-        // Flush last location's description.
-        if let Some(loc_id) = &current_loc_id {
-            if !desc_buffer.is_empty() {
-                loc_descriptions
-                    .entry(loc_id.clone())
-                    .or_default()
-                    .extend(desc_buffer.drain(..));
-            }
-        }
     }
+    content_by_loc
+}
 
-    // Build location JSON objects.
-    let mut locations = Map::new();
-    for (id, ls) in &symbol_table.locations {
-        let mut loc_obj = Map::new();
+fn build_location_json(
+    id: &str,
+    ls: &LocationSymbol,
+    content: Option<&LocationContent>,
+    texts: &mut TextSink,
+) -> Json {
+    let mut loc_obj = Map::new();
 
-        // description
-        if let Some(prose_blocks) = loc_descriptions.get(id) {
-            if !prose_blocks.is_empty() {
-                loc_obj.insert(
-                    "description".to_string(),
-                    Json::String(prose_blocks.join("\n\n")),
-                );
-            }
-        }
+    // description
+    if let Some(first) = ls.description.first() {
+        let paragraphs: Vec<(&str, &[String])> =
+            ls.description.iter().map(|p| (p.text.as_str(), p.variants.as_slice())).collect();
+        loc_obj.insert(
+            "description".to_string(),
+            texts.varied(format!("locations/{}/description", id), &joined_variants(&paragraphs, "\n\n"), &first.span),
+        );
+    }
 
-        // contains
-        if !ls.contains.is_empty() {
-            loc_obj.insert(
-                "contains".to_string(),
-                Json::Array(
-                    ls.contains
-                        .iter()
-                        .map(|e| Json::String(strip_at(e)))
-                        .collect(),
-                ),
-            );
-        }
+    // region
+    if let Some(region) = &ls.region {
+        loc_obj.insert("region".to_string(), Json::String(region.clone()));
+    }
 
-        // exits
-        if !ls.exits.is_empty() {
-            let mut exits = Map::new();
-            for (direction, es) in &ls.exits {
-                let mut exit_obj = Map::new();
+    // contains
+    if !ls.contains.is_empty() {
+        loc_obj.insert(
+            "contains".to_string(),
+            Json::Array(
+                ls.contains
+                    .iter()
+                    .map(|e| Json::String(strip_at(e)))
+                    .collect(),
+            ),
+        );
+    }
 
-                // to
-                if let Some(dest) = &es.resolved_destination {
-                    exit_obj.insert("to".to_string(), Json::String(dest.clone()));
-                }
+    // exits
+    if !ls.exits.is_empty() {
+        let mut exits = Map::new();
+        for (direction, es) in &ls.exits {
+            let mut exit_obj = Map::new();
 
-                // condition, blocked_message, effects from AST
-                if let Some(ec) = exit_content.get(&(id.clone(), direction.clone())) {
-                    if let Some(cond_str) = &ec.condition {
-                        exit_obj.insert("condition".to_string(), Json::String(cond_str.clone()));
-                    }
-                    if let Some(msg) = &ec.blocked_message {
-                        exit_obj.insert("blocked_message".to_string(), Json::String(msg.clone()));
-                    }
-                    if !ec.effects.is_empty() {
-                        exit_obj.insert("effects".to_string(), Json::Array(ec.effects.clone()));
-                    }
-                }
+            // to
+            if let Some(dest) = &es.resolved_destination {
+                exit_obj.insert("to".to_string(), Json::String(dest.clone()));
+            }
 
-                exits.insert(direction.clone(), Json::Object(exit_obj));
+            // condition, blocked_message, effects from AST
+            if let Some(ec) = content.and_then(|c| c.exits.get(direction)) {
+                if let Some(cond_str) = &ec.condition {
+                    exit_obj.insert("condition".to_string(), Json::String(cond_str.clone()));
+                }
+                if let Some((msg, msg_span)) = &ec.blocked_message {
+                    exit_obj.insert(
+                        "blocked_message".to_string(),
+                        texts.text(
+                            format!("locations/{}/exits/{}/blocked_message", id, direction),
+                            msg,
+                            msg_span,
+                        ),
+                    );
+                }
+                // Effects run when the exit is traversed, never on a
+                // blocked attempt. `on_traverse` says so; `effects` is
+                // the old key, kept for one release.
+                if !ec.effects.is_empty() {
+                    exit_obj.insert("on_traverse".to_string(), Json::Array(ec.effects.clone()));
+                    exit_obj.insert("effects".to_string(), Json::Array(ec.effects.clone()));
+                }
             }
-            loc_obj.insert("exits".to_string(), Json::Object(exits));
+
+            exits.insert(direction.clone(), Json::Object(exit_obj));
         }
+        loc_obj.insert("exits".to_string(), Json::Object(exits));
+    }
 
-        locations.insert(id.clone(), Json::Object(loc_obj));
+    // on_enter, on_exit
+    for kind in [HookKind::Enter, HookKind::Exit] {
+        if let Some(hook_json) = content.and_then(|c| c.hooks.get(&kind)) {
+            loc_obj.insert(kind.json_key().to_string(), hook_json.clone());
+        }
     }
-    Json::Object(locations)
+
+    Json::Object(loc_obj)
+}
+
+/// Regions in declaration order, each with its display name and member
+/// locations. Members are listed in the order their headings were
+/// collected, so a region spread over several files lists them in
+/// topological file order.
+fn build_regions(symbol_table: &SymbolTable) -> Json {
+    let mut regions = Map::new();
+    for (id, rs) in &symbol_table.regions {
+        let mut region_obj = Map::new();
+        region_obj.insert("name".to_string(), Json::String(rs.display_name.clone()));
+        region_obj.insert(
+            "locations".to_string(),
+            Json::Array(rs.locations.iter().map(|l| Json::String(l.clone())).collect()),
+        );
+        regions.insert(id.clone(), Json::Object(region_obj));
+    }
+    Json::Object(regions)
+}
+
+/// Endings in declaration order. An ending without a description is an
+/// empty object.
+pub(crate) fn build_endings(symbol_table: &SymbolTable, texts: &mut TextSink) -> Json {
+    let mut endings = Map::new();
+    for (id, es) in &symbol_table.endings {
+        let mut ending_obj = Map::new();
+        if let Some(description) = &es.description {
+            ending_obj.insert(
+                "description".to_string(),
+                texts.text(format!("endings/{}/description", id), description, &es.declared_in),
+            );
+        }
+        endings.insert(id.clone(), Json::Object(ending_obj));
+    }
+    Json::Object(endings)
 }
 
 struct ExitContent {
     condition: Option<String>,
-    blocked_message: Option<String>,
+    blocked_message: Option<(String, Span)>,
     effects: Vec<Json>,
 }
 
@@ -426,7 +900,7 @@ fn collect_exit_content(children: &[ContentNode], symbol_table: &SymbolTable) ->
                 condition = Some(lower_condition(&cond.expr, symbol_table));
             }
             ContentNode::BlockedMessage(bm) => {
-                blocked_message = Some(bm.text.clone());
+                blocked_message = Some((bm.text.clone(), bm.span.clone()));
             }
             ContentNode::Effect(eff) => {
                 effects.push(lower_effect(&eff.effect_type, &eff.annotation, symbol_table));
@@ -442,6 +916,21 @@ fn collect_exit_content(children: &[ContentNode], symbol_table: &SymbolTable) ->
     }
 }
 
+/// Lower a location hook. Unguarded hooks emit a bare effect array; guarded
+/// hooks wrap the effects with their conditions.
+fn build_hook_json(children: &[ContentNode], symbol_table: &SymbolTable) -> Json {
+    let (conditions, effects) = collect_conditions_effects(children, symbol_table);
+    match conditions {
+        None => Json::Array(effects),
+        Some(conds) => {
+            let mut obj = Map::new();
+            obj.insert("conditions".to_string(), conds);
+            obj.insert("effects".to_string(), Json::Array(effects));
+            Json::Object(obj)
+        }
+    }
+}
+
 // ── Step 5: Rules ──
 
 fn build_rules(
@@ -478,30 +967,38 @@ fn build_rules(
         // trigger
         rule_obj.insert("trigger".to_string(), Json::String(rs.trigger.clone()));
 
+        // location: a scoped rule also gets the containment check its
+        // scope implies, first, for runtimes that do not read the field.
+        let mut conds: Vec<Json> = Vec::new();
+        if let Some(loc_id) = &rs.scope_location {
+            rule_obj.insert("location".to_string(), Json::String(loc_id.clone()));
+            conds.push(Json::String(format!("player.container == {}", loc_id)));
+        }
+
         // conditions from RuleBlock.where_clauses
         if let Some(rb) = rule_blocks.get(name) {
-            if !rb.where_clauses.is_empty() {
-                let conds: Vec<Json> = rb
-                    .where_clauses
-                    .iter()
-                    .map(|c| Json::String(lower_condition(c, symbol_table)))
-                    .collect();
-                rule_obj.insert("conditions".to_string(), Json::Array(conds));
-            }
+            conds.extend(rb.where_clauses.iter().map(|c| Json::String(lower_condition(c, symbol_table))));
+        }
+        if !conds.is_empty() {
+            rule_obj.insert("conditions".to_string(), Json::Array(conds));
         }
 
-        // select
+        // select: candidates as LINK resolved them
         if let Some(sel) = &rs.select {
-            let mut sel_obj = Map::new();
-            sel_obj.insert(
-                "from".to_string(),
-                Json::Array(
-                    sel.from
+            let from: Vec<Json> = rule_blocks
+                .get(name)
+                .and_then(|rb| rb.select.as_ref())
+                .map(|select| {
+                    select
+                        .annotations
                         .iter()
-                        .map(|e| Json::String(strip_at(e)))
-                        .collect(),
-                ),
-            );
+                        .filter_map(|a| a.as_ref()?.resolved_entity.clone())
+                        .map(Json::String)
+                        .collect()
+                })
+                .unwrap_or_default();
+            let mut sel_obj = Map::new();
+            sel_obj.insert("from".to_string(), Json::Array(from));
             sel_obj.insert("as".to_string(), Json::String(sel.variable.clone()));
             if !sel.where_clauses.is_empty() {
                 let where_conds: Vec<Json> = sel
@@ -546,55 +1043,82 @@ fn build_actions(
             None => continue,
         };
         let stem = file_stem(file_path);
-        let mut current_section_id: Option<String> = None;
-
-        for content in &node.ast.content {
-            match content {
-                ContentNode::SectionLabel(sl) => {
-                    current_section_id = Some(format!("{}/{}", stem, sl.name));
-                }
-                ContentNode::LocationHeading(_) => {
-                    current_section_id = None;
+        for section in layout::sections(&node.ast.content) {
+            let section_id = format!("{}/{}", stem, section.label.local_id());
+            for content in section.nodes {
+                if let ContentNode::Choice(choice) = content {
+                    collect_choice_nodes(choice, &section_id, &mut choice_nodes);
                 }
-                ContentNode::Choice(choice) => {
-                    if let Some(section_id) = &current_section_id {
-                        collect_choice_nodes(choice, section_id, &mut choice_nodes);
-                    }
-                }
-                _ => {}
             }
         }
     }
 
     let mut actions = Map::new();
     for (id, as_) in &symbol_table.actions {
-        let mut action_obj = Map::new();
-
-        // target
-        if let Some(target) = &as_.target {
-            action_obj.insert(
-                "target".to_string(),
-                Json::String(strip_at(target)),
-            );
+        // Choices in a parameterised section become one action per instance.
+        let template = id
+            .rsplit_once('/')
+            .and_then(|(section_id, _)| symbol_table.sections.get(section_id))
+            .filter(|ss| ss.param.is_some());
+        let choice = choice_nodes.get(id).copied();
+        match template {
+            Some(ss) => {
+                for instance in Instance::all(ss) {
+                    let target = as_.target.as_deref().map(|t| instance.entity(t));
+                    let choice = choice.map(|c| instance.node(&ContentNode::Choice(c.clone())));
+                    let content = match &choice {
+                        Some(ContentNode::Choice(c)) => Some(c.content.as_slice()),
+                        _ => None,
+                    };
+                    actions.insert(
+                        instance.rebase(id),
+                        build_action_json(target.as_deref(), as_.target_type.as_deref(), content, symbol_table),
+                    );
+                }
+            }
+            None => {
+                let content = choice.map(|c| c.content.as_slice());
+                actions.insert(
+                    id.clone(),
+                    build_action_json(as_.target.as_deref(), as_.target_type.as_deref(), content, symbol_table),
+                );
+            }
         }
+    }
+    Json::Object(actions)
+}
 
-        // target_type
-        if let Some(tt) = &as_.target_type {
-            action_obj.insert("target_type".to_string(), Json::String(tt.clone()));
-        }
+fn build_action_json(
+    target: Option<&str>,
+    target_type: Option<&str>,
+    choice_content: Option<&[ContentNode]>,
+    symbol_table: &SymbolTable,
+) -> Json {
+    let mut action_obj = Map::new();
 
-        // conditions and effects from the Choice AST node
-        if let Some(choice) = choice_nodes.get(id) {
-            let (conds, effects) = collect_choice_conditions_effects(choice, symbol_table);
-            if let Some(c) = conds {
-                action_obj.insert("conditions".to_string(), c);
-            }
-            action_obj.insert("effects".to_string(), Json::Array(effects));
-        }
+    // target
+    if let Some(target) = target {
+        action_obj.insert(
+            "target".to_string(),
+            Json::String(strip_at(target)),
+        );
+    }
 
-        actions.insert(id.clone(), Json::Object(action_obj));
+    // target_type
+    if let Some(tt) = target_type {
+        action_obj.insert("target_type".to_string(), Json::String(tt.to_string()));
     }
-    Json::Object(actions)
+
+    // conditions and effects from the Choice AST node
+    if let Some(content) = choice_content {
+        let (conds, effects) = collect_conditions_effects(content, symbol_table);
+        if let Some(c) = conds {
+            action_obj.insert("conditions".to_string(), c);
+        }
+        action_obj.insert("effects".to_string(), Json::Array(effects));
+    }
+
+    Json::Object(action_obj)
 }
 
 fn collect_choice_nodes<'a>(
@@ -652,6 +1176,11 @@ fn build_sequences(symbol_table: &SymbolTable) -> Json {
                 phase_obj.insert("rule".to_string(), Json::String(rule.clone()));
             }
 
+            // section
+            if let Some(section) = &ps.section {
+                phase_obj.insert("section".to_string(), Json::String(section.clone()));
+            }
+
             // advance
             let advance_str = format_advance(&ps.advance);
             phase_obj.insert("advance".to_string(), Json::String(advance_str));
@@ -670,15 +1199,10 @@ fn format_advance(advance: &str) -> String {
     // The advance string is already the correct value for most modes.
     // For on_condition, ensure the expression part is space-free.
     if let Some(expr) = advance.strip_prefix("on_condition ") {
-        // Make the expression space-free: remove spaces around operators.
-        let space_free = expr
-            .replace(" == ", "==")
-            .replace(" != ", "!=")
-            .replace(" < ", "<")
-            .replace(" > ", ">")
-            .replace(" <= ", "<=")
-            .replace(" >= ", ">=");
-        format!("on_condition {}", space_free)
+        let mut out = String::with_capacity(advance.len());
+        out.push_str("on_condition ");
+        push_space_free(expr, &mut out);
+        out
     } else {
         advance.to_string()
     }
@@ -686,123 +1210,135 @@ fn format_advance(advance: &str) -> String {
 
 // ── Step 8: Dialogue ──
 
-fn build_dialogue(
+pub(crate) fn build_dialogue(
     graph: &DependencyGraph,
     ordered_paths: &[&str],
     symbol_table: &SymbolTable,
+    texts: &mut TextSink,
 ) -> Json {
-    // Collect section content from AST.
-    let mut section_data: IndexMap<String, SectionData> = IndexMap::new();
+    let section_nodes = collect_section_nodes(graph, ordered_paths);
 
-    for file_path in ordered_paths {
+    let mut dialogue = Map::new();
+    for ss in symbol_table.sections.values() {
+        for (id, json) in build_section_entries(ss, &section_nodes, symbol_table, texts) {
+            dialogue.insert(id, json);
+        }
+    }
+    Json::Object(dialogue)
+}
+
+/// Section content from the AST, keyed by compiled section ID.
+fn collect_section_nodes<'a>(
+    graph: &'a DependencyGraph,
+    paths: &[&str],
+) -> IndexMap<String, Vec<&'a ContentNode>> {
+    let mut section_nodes: IndexMap<String, Vec<&ContentNode>> = IndexMap::new();
+    for file_path in paths {
         let node = match graph.nodes.get(*file_path) {
             Some(n) => n,
             None => continue,
         };
         let stem = file_stem(file_path);
-        let mut current_section_id: Option<String> = None;
-        let mut current_nodes: Vec<&ContentNode> = Vec::new();
+        for section in layout::sections(&node.ast.content) {
+            let sec_id = format!("{}/{}", stem, section.label.local_id());
+            section_nodes.insert(sec_id, section.nodes);
+        }
+    }
+    section_nodes
+}
 
-        for content in &node.ast.content {
-            match content {
-                ContentNode::SectionLabel(sl) => {
-                    // Flush previous section.
-                    if let Some(sec_id) = &current_section_id {
-                        section_data.insert(
-                            sec_id.clone(),
-                            build_section_data(&current_nodes, symbol_table),
-                        );
-                    }
-                    current_section_id = Some(format!("{}/{}", stem, sl.name));
-                    current_nodes.clear();
-                }
-                ContentNode::LocationHeading(_) => {
-                    // Flush previous section.
-                    if let Some(sec_id) = &current_section_id {
-                        section_data.insert(
-                            sec_id.clone(),
-                            build_section_data(&current_nodes, symbol_table),
-                        );
-                    }
-                    current_section_id = None;
-                    current_nodes.clear();
-                }
-                _ => {
-                    if current_section_id.is_some() {
-                        current_nodes.push(content);
-                    }
-                }
-            }
+/// The dialogue entries one section symbol produces: the section itself,
+/// or, for a parameterised section, one entry per instance.
+fn build_section_entries(
+    ss: &SectionSymbol,
+    section_nodes: &IndexMap<String, Vec<&ContentNode>>,
+    symbol_table: &SymbolTable,
+    texts: &mut TextSink,
+) -> Vec<(String, Json)> {
+    let id = &ss.compiled_id;
+    if ss.param.is_some() {
+        let nodes = section_nodes.get(id).map(Vec::as_slice).unwrap_or_default();
+        return Instance::all(ss)
+            .into_iter()
+            .map(|instance| {
+                let content = instance.content(nodes);
+                let refs: Vec<&ContentNode> = content.iter().collect();
+                let sd = build_section_data(&refs, symbol_table);
+                let symbol = instance.section_symbol(ss);
+                let json = build_section_json(&symbol, Some(&sd), texts);
+                (instance.id, json)
+            })
+            .collect();
+    }
+    let sd = section_nodes.get(id).map(|nodes| build_section_data(nodes, symbol_table));
+    vec![(id.clone(), build_section_json(ss, sd.as_ref(), texts))]
+}
+
+fn build_section_json(
+    ss: &SectionSymbol,
+    section_data: Option<&SectionData>,
+    texts: &mut TextSink,
+) -> Json {
+    let id = &ss.compiled_id;
+    let mut sec_obj = Map::new();
+
+    // id (required)
+    sec_obj.insert("id".to_string(), Json::String(id.clone()));
+
+    if let Some(sd) = section_data {
+        // prompt
+        if let Some(prompt) = build_segments_json(&sd.prompt, &format!("dialogue/{}/prompt", id), texts) {
+            sec_obj.insert("prompt".to_string(), prompt);
         }
-        // Flush last section in file.
-        if let Some(sec_id) = &current_section_id {
-            section_data.insert(
-                sec_id.clone(),
-                build_section_data(&current_nodes, symbol_table),
+
+        // description
+        if let Some((desc, desc_span)) = &sd.description {
+            sec_obj.insert(
+                "description".to_string(),
+                texts.varied(format!("dialogue/{}/description", id), desc, desc_span),
             );
         }
-    }
 
-    // Build dialogue JSON objects.
-    let mut dialogue = Map::new();
-    for (id, ss) in &symbol_table.sections {
-        let mut sec_obj = Map::new();
-
-        // id (required)
-        sec_obj.insert("id".to_string(), Json::String(id.clone()));
-
-        if let Some(sd) = section_data.get(id) {
-            // prompt
-            if let Some((speaker, text)) = &sd.prompt {
-                let mut speech = Map::new();
-                speech.insert("speaker".to_string(), Json::String(strip_at(speaker)));
-                speech.insert("text".to_string(), Json::String(text.clone()));
-                sec_obj.insert("prompt".to_string(), Json::Object(speech));
-            }
-
-            // description
-            if let Some(desc) = &sd.description {
-                sec_obj.insert("description".to_string(), Json::String(desc.clone()));
-            }
+        // conditions
+        if let Some(conds) = &sd.conditions {
+            sec_obj.insert("conditions".to_string(), conds.clone());
+        }
 
-            // conditions
-            if let Some(conds) = &sd.conditions {
-                sec_obj.insert("conditions".to_string(), conds.clone());
-            }
+        // choices
+        if !sd.choices.is_empty() {
+            let choices_json: Vec<Json> = sd
+                .choices
+                .iter()
+                .zip(ss.choices.iter())
+                .map(|(cd, cs)| build_choice_json(cd, cs, ss, texts))
+                .collect();
+            sec_obj.insert("choices".to_string(), Json::Array(choices_json));
+        }
 
-            // choices
-            if !sd.choices.is_empty() {
-                let choices_json: Vec<Json> = sd
-                    .choices
-                    .iter()
-                    .zip(ss.choices.iter())
-                    .map(|(cd, cs)| build_choice_json(cd, cs, symbol_table))
-                    .collect();
-                sec_obj.insert("choices".to_string(), Json::Array(choices_json));
+        // on_exhausted
+        if let Some(exhausted) = &sd.on_exhausted {
+            let mut ex_obj = Map::new();
+            if let Some(speaker) = &exhausted.speaker {
+                ex_obj.insert("speaker".to_string(), Json::String(strip_at(speaker)));
             }
-
-            // on_exhausted
-            if let Some(exhausted) = &sd.on_exhausted {
-                let mut ex_obj = Map::new();
-                if let Some(speaker) = &exhausted.speaker {
-                    ex_obj.insert("speaker".to_string(), Json::String(strip_at(speaker)));
-                }
-                ex_obj.insert("text".to_string(), Json::String(exhausted.text.clone()));
-                if let Some(goto) = &exhausted.goto {
-                    ex_obj.insert("goto".to_string(), Json::String(goto.clone()));
-                }
-                sec_obj.insert("on_exhausted".to_string(), Json::Object(ex_obj));
+            ex_obj.insert(
+                "text".to_string(),
+                texts.varied(format!("dialogue/{}/on_exhausted/text", id), &exhausted.text, &exhausted.span),
+            );
+            if let Some(jump) = &exhausted.jump {
+                jump.insert_into(&mut ex_obj);
             }
+            sec_obj.insert("on_exhausted".to_string(), Json::Object(ex_obj));
         }
-
-        dialogue.insert(id.clone(), Json::Object(sec_obj));
     }
-    Json::Object(dialogue)
+
+    Json::Object(sec_obj)
 }
 
 struct SectionData {
-    prompt: Option<(String, String)>, // (speaker, text)
-    description: Option<String>,
+    prompt: Vec<Segment>,
+    /// The description as written, then its variants.
+    description: Option<(Vec<String>, Span)>,
     conditions: Option<Json>,
     choices: Vec<ChoiceData>,
     on_exhausted: Option<ExhaustedData>,
@@ -811,97 +1347,117 @@ struct SectionData {
 struct ChoiceData {
     label: String,
     conditions: Option<Json>,
-    response: Option<(String, String)>, // (speaker, text)
+    response: Vec<Segment>,
     effects: Vec<Json>,
-    goto: Option<String>,
+    jump: Option<JumpData>,
     nested_choices: Vec<ChoiceData>,
 }
 
+/// Where a choice or `on_exhausted` block sends the player.
+enum JumpData {
+    /// `"goto"`: a dialogue section ID, or `"end"` to close the
+    /// conversation.
+    Goto(String),
+    /// `"exit"`: an exit direction of the enclosing location.
+    Exit(String),
+}
+
+impl JumpData {
+    fn insert_into(&self, obj: &mut Map<String, Json>) {
+        let (key, value) = match self {
+            JumpData::Goto(id) => ("goto", id),
+            JumpData::Exit(direction) => ("exit", direction),
+        };
+        obj.insert(key.to_string(), Json::String(value.clone()));
+    }
+}
+
+/// One part of a prompt or response, in authored order.
+enum Segment {
+    /// `text` holds the line as written, then its variants.
+    Speech { speaker: String, text: Vec<String>, span: Span },
+    Direction { text: String, span: Span },
+}
+
+impl Segment {
+    fn speech(es: &crate::ast::EntitySpeech) -> Self {
+        Segment::Speech {
+            speaker: es.speaker().to_string(),
+            text: std::iter::once(&es.text).chain(&es.variants).cloned().collect(),
+            span: es.span.clone(),
+        }
+    }
+
+    fn direction(sd: &crate::ast::StageDirection) -> Self {
+        Segment::Direction {
+            text: format!("{} {}", sd.entity_ref, sd.text).trim().to_string(),
+            span: sd.span.clone(),
+        }
+    }
+}
+
 struct ExhaustedData {
-    text: String,
+    /// The text as written, then its variants.
+    text: Vec<String>,
+    /// Span of the first text line.
+    span: Span,
     speaker: Option<String>,
-    goto: Option<String>,
+    jump: Option<JumpData>,
 }
 
 fn build_section_data(
     nodes: &[&ContentNode],
     symbol_table: &SymbolTable,
 ) -> SectionData {
-    // Partition into Region A, Region B, Region C.
-    let mut first_choice_idx = None;
-    let mut last_choice_idx = None;
-
-    for (i, node) in nodes.iter().enumerate() {
-        if matches!(node, ContentNode::Choice(_)) {
-            if first_choice_idx.is_none() {
-                first_choice_idx = Some(i);
-            }
-            last_choice_idx = Some(i);
-        }
-    }
-
-    let region_a = match first_choice_idx {
-        Some(idx) => &nodes[..idx],
-        None => nodes,
-    };
-
-    let region_c = match last_choice_idx {
-        Some(idx) if idx + 1 < nodes.len() => &nodes[idx + 1..],
-        _ => &[],
-    };
+    let regions = Regions::split(nodes);
 
     // Region A: extract prompt, description, conditions.
-    let mut prompt: Option<(String, String)> = None;
-    let mut prose_blocks: Vec<String> = Vec::new();
+    let mut prompt: Vec<Segment> = Vec::new();
+    let mut prose_blocks: Vec<(String, &[String], Span)> = Vec::new();
     let mut and_conditions: Vec<String> = Vec::new();
-    let mut or_conditions: Option<Vec<String>> = None;
+    let mut or_blocks: Vec<Vec<String>> = Vec::new();
 
-    for node in region_a {
+    for node in regions.before {
         match node {
             ContentNode::EntitySpeech(es) => {
-                if prompt.is_none() {
-                    prompt = Some((es.entity_ref.clone(), es.text.clone()));
-                } else {
-                    // Subsequent speech goes into description.
-                    let text = format!("{}: {}", es.entity_ref, es.text);
-                    prose_blocks.push(text.trim().to_string());
-                }
+                prompt.push(Segment::speech(es));
             }
             ContentNode::Prose(p) => {
                 let trimmed = p.text.trim().to_string();
                 if !trimmed.is_empty() {
-                    prose_blocks.push(trimmed);
+                    prose_blocks.push((trimmed, &p.variants, p.span.clone()));
                 }
             }
             ContentNode::StageDirection(sd) => {
-                let text = format!("{} {}", sd.entity_ref, sd.text);
-                prose_blocks.push(text.trim().to_string());
+                // Directions before the first speech set the scene; after
+                // it, they belong to the prompt.
+                if prompt.is_empty() {
+                    let text = format!("{} {}", sd.entity_ref, sd.text);
+                    prose_blocks.push((text.trim().to_string(), &[], sd.span.clone()));
+                } else {
+                    prompt.push(Segment::direction(sd));
+                }
             }
             ContentNode::Condition(cond) => {
                 and_conditions.push(lower_condition(&cond.expr, symbol_table));
             }
             ContentNode::OrConditionBlock(or) => {
-                let or_conds: Vec<String> = or
-                    .conditions
-                    .iter()
-                    .map(|c| lower_condition(c, symbol_table))
-                    .collect();
-                or_conditions = Some(or_conds);
+                or_blocks.push(or.conditions.iter().map(|c| lower_condition(c, symbol_table)).collect());
             }
             _ => {}
         }
     }
 
-    let description = if prose_blocks.is_empty() {
-        None
-    } else {
-        Some(prose_blocks.join("\n\n"))
-    };
+    let description = prose_blocks.first().map(|(_, _, first_span)| {
+        let blocks: Vec<(&str, &[String])> = prose_blocks.iter().map(|(t, v, _)| (t.as_str(), *v)).collect();
+        (joined_variants(&blocks, "\n\n"), first_span.clone())
+    });
 
-    let conditions = build_conditions_json(&and_conditions, &or_conditions);
+    let conditions = build_conditions_json(&and_conditions, &or_blocks);
 
     // Region B: extract choices.
-    let choices: Vec<ChoiceData> = nodes
+    let choices: Vec<ChoiceData> = regions
+        .choices
         .iter()
         .filter_map(|n| {
             if let ContentNode::Choice(c) = n {
@@ -913,7 +1469,7 @@ fn build_section_data(
         .collect();
 
     // Region C: extract on_exhausted.
-    let on_exhausted = build_exhausted_data(region_c, symbol_table);
+    let on_exhausted = build_exhausted_data(regions.after, symbol_table);
 
     SectionData {
         prompt,
@@ -929,42 +1485,36 @@ fn build_choice_data(
     symbol_table: &SymbolTable,
 ) -> ChoiceData {
     let mut and_conditions: Vec<String> = Vec::new();
-    let mut or_conditions: Option<Vec<String>> = None;
-    let mut response: Option<(String, String)> = None;
+    let mut or_blocks: Vec<Vec<String>> = Vec::new();
+    let mut response: Vec<Segment> = Vec::new();
     let mut effects: Vec<Json> = Vec::new();
-    let mut goto: Option<String> = None;
+    let mut jump_data: Option<JumpData> = None;
     let mut nested_choices: Vec<ChoiceData> = Vec::new();
 
     for child in &choice.content {
         match child {
+            // `once` is a marker on the choice, not a condition.
+            ContentNode::Condition(cond) if matches!(cond.expr, ConditionExpr::Once(_)) => {}
             ContentNode::Condition(cond) => {
                 and_conditions.push(lower_condition(&cond.expr, symbol_table));
             }
             ContentNode::OrConditionBlock(or) => {
-                let or_conds: Vec<String> = or
-                    .conditions
-                    .iter()
-                    .map(|c| lower_condition(c, symbol_table))
-                    .collect();
-                or_conditions = Some(or_conds);
+                or_blocks.push(or.conditions.iter().map(|c| lower_condition(c, symbol_table)).collect());
             }
             ContentNode::EntitySpeech(es) => {
-                if response.is_none() {
-                    response = Some((es.entity_ref.clone(), es.text.clone()));
-                }
+                response.push(Segment::speech(es));
+            }
+            ContentNode::StageDirection(sd) => {
+                response.push(Segment::direction(sd));
             }
             ContentNode::Effect(eff) => {
                 effects.push(lower_effect(&eff.effect_type, &eff.annotation, symbol_table));
             }
             ContentNode::Jump(jump) => {
-                // Only emit goto for section jumps (not "end", not target-directed).
+                // Target-directed choices carry no jump.
                 if choice.target.is_none() && choice.target_type.is_none() {
-                    if jump.target != "end" {
-                        if let Some(ann) = &jump.annotation {
-                            if let Some(sec_id) = &ann.resolved_section {
-                                goto = Some(sec_id.clone());
-                            }
-                        }
+                    if let Some(data) = lower_jump(jump, symbol_table) {
+                        jump_data = Some(data);
                     }
                 }
             }
@@ -975,14 +1525,14 @@ fn build_choice_data(
         }
     }
 
-    let conditions = build_conditions_json(&and_conditions, &or_conditions);
+    let conditions = build_conditions_json(&and_conditions, &or_blocks);
 
     ChoiceData {
         label: choice.label.clone(),
         conditions,
         response,
         effects,
-        goto,
+        jump: jump_data,
         nested_choices,
     }
 }
@@ -990,30 +1540,40 @@ fn build_choice_data(
 fn build_choice_json(
     cd: &ChoiceData,
     cs: &crate::symbol_table::ChoiceSymbol,
-    symbol_table: &SymbolTable,
+    section: &SectionSymbol,
+    texts: &mut TextSink,
 ) -> Json {
+    let section_id = &section.compiled_id;
     let mut choice_obj = Map::new();
+    // Nested choices share the section's ID space, so the key stays flat.
+    let choice_slug = cs.compiled_id.rsplit('/').next().unwrap_or(&cs.compiled_id);
+    let key_prefix = format!("dialogue/{}/choices/{}", section_id, choice_slug);
 
     // id
     choice_obj.insert("id".to_string(), Json::String(cs.compiled_id.clone()));
 
     // label
-    choice_obj.insert("label".to_string(), Json::String(cs.label.clone()));
+    choice_obj.insert(
+        "label".to_string(),
+        texts.text(format!("{}/label", key_prefix), &cs.label, &cs.declared_in),
+    );
 
     // sticky
     choice_obj.insert("sticky".to_string(), Json::Bool(cs.sticky));
 
+    // once
+    if cs.once {
+        choice_obj.insert("once".to_string(), Json::Bool(true));
+    }
+
     // conditions
     if let Some(conds) = &cd.conditions {
         choice_obj.insert("conditions".to_string(), conds.clone());
     }
 
     // response
-    if let Some((speaker, text)) = &cd.response {
-        let mut speech = Map::new();
-        speech.insert("speaker".to_string(), Json::String(strip_at(speaker)));
-        speech.insert("text".to_string(), Json::String(text.clone()));
-        choice_obj.insert("response".to_string(), Json::Object(speech));
+    if let Some(response) = build_segments_json(&cd.response, &format!("{}/response", key_prefix), texts) {
+        choice_obj.insert("response".to_string(), response);
     }
 
     // effects
@@ -1021,9 +1581,9 @@ fn build_choice_json(
         choice_obj.insert("effects".to_string(), Json::Array(cd.effects.clone()));
     }
 
-    // goto
-    if let Some(goto) = &cd.goto {
-        choice_obj.insert("goto".to_string(), Json::String(goto.clone()));
+    // goto or exit
+    if let Some(jump) = &cd.jump {
+        jump.insert_into(&mut choice_obj);
     }
 
     // nested choices
@@ -1036,8 +1596,8 @@ fn build_choice_json(
             .nested_choices
             .iter()
             .filter_map(|ncd| {
-                find_nested_choice_symbol(cs, &ncd.label, symbol_table)
-                    .map(|ncs| build_choice_json(ncd, ncs, symbol_table))
+                find_nested_choice_symbol(&ncd.label, section)
+                    .map(|ncs| build_choice_json(ncd, ncs, section, texts))
             })
             .collect();
         if !nested_json.is_empty() {
@@ -1048,86 +1608,169 @@ fn build_choice_json(
     Json::Object(choice_obj)
 }
 
+/// Build a prompt or response value. A lone speech line keeps the single
+/// speech object shape; anything else becomes an array of segments.
+fn build_segments_json(segments: &[Segment], key_prefix: &str, texts: &mut TextSink) -> Option<Json> {
+    if let [Segment::Speech { speaker, text, span }] = segments {
+        return Some(speech_json(speaker, texts.varied(format!("{}/text", key_prefix), text, span)));
+    }
+    if segments.is_empty() {
+        return None;
+    }
+
+    let items = segments
+        .iter()
+        .enumerate()
+        .map(|(i, segment)| match segment {
+            Segment::Speech { speaker, text, span } => {
+                speech_json(speaker, texts.varied(format!("{}/{}/text", key_prefix, i), text, span))
+            }
+            Segment::Direction { text, span } => {
+                let mut direction = Map::new();
+                direction.insert(
+                    "direction".to_string(),
+                    texts.text(format!("{}/{}/direction", key_prefix, i), text, span),
+                );
+                Json::Object(direction)
+            }
+        })
+        .collect();
+    Some(Json::Array(items))
+}
+
+fn speech_json(speaker: &str, text: Json) -> Json {
+    let mut speech = Map::new();
+    speech.insert("speaker".to_string(), Json::String(strip_at(speaker)));
+    speech.insert("text".to_string(), text);
+    Json::Object(speech)
+}
+
 fn find_nested_choice_symbol<'a>(
-    parent_cs: &crate::symbol_table::ChoiceSymbol,
     nested_label: &str,
-    symbol_table: &'a SymbolTable,
+    section: &'a SectionSymbol,
 ) -> Option<&'a crate::symbol_table::ChoiceSymbol> {
-    // Extract section_id from parent's compiled_id (everything before the last '/').
-    let section_id = parent_cs.compiled_id.rsplitn(2, '/').nth(1)?;
-    let expected_id = format!("{}/{}", section_id, slugify(nested_label));
-    symbol_table
-        .sections
-        .get(section_id)
-        .and_then(|ss| ss.choices.iter().find(|c| c.compiled_id == expected_id))
+    // Nested choices share the section's ID space.
+    let expected_id = format!("{}/{}", section.compiled_id, slugify(nested_label));
+    section.choices.iter().find(|c| c.compiled_id == expected_id)
+}
+
+/// Blocks joined with `sep`, first as written, then once per variant
+/// index: variant `k` takes each block's `k`th variant, or the block as
+/// written when it has fewer. A single text when no block varies.
+fn joined_variants(blocks: &[(&str, &[String])], sep: &str) -> Vec<String> {
+    let count = blocks.iter().map(|(_, v)| v.len()).max().unwrap_or(0);
+    (0..=count)
+        .map(|k| {
+            let parts: Vec<&str> = blocks
+                .iter()
+                .map(|(text, variants)| match k {
+                    0 => *text,
+                    _ => variants.get(k - 1).map_or(*text, |v| v.as_str()),
+                })
+                .collect();
+            parts.join(sep)
+        })
+        .collect()
 }
 
 fn build_exhausted_data(
     region_c: &[&ContentNode],
-    _symbol_table: &SymbolTable,
+    symbol_table: &SymbolTable,
 ) -> Option<ExhaustedData> {
     if region_c.is_empty() {
         return None;
     }
 
-    let mut text_parts: Vec<String> = Vec::new();
+    let mut text_parts: Vec<(String, &[String])> = Vec::new();
+    let mut first_span: Option<Span> = None;
     let mut speaker: Option<String> = None;
-    let mut goto: Option<String> = None;
+    let mut jump_data: Option<JumpData> = None;
 
-    for node in region_c {
+    for node in layout::exhausted_text(region_c) {
         match node {
             ContentNode::EntitySpeech(es) => {
-                if speaker.is_none() && text_parts.is_empty() {
-                    speaker = Some(es.entity_ref.clone());
-                    text_parts.push(es.text.clone());
-                }
+                speaker = Some(es.speaker().to_string());
+                text_parts.push((es.text.clone(), &es.variants));
+                first_span = Some(es.span.clone());
             }
             ContentNode::Prose(p) => {
-                let trimmed = p.text.trim().to_string();
-                if !trimmed.is_empty() {
-                    text_parts.push(trimmed);
-                }
+                text_parts.push((p.text.trim().to_string(), &p.variants));
+                first_span.get_or_insert_with(|| p.span.clone());
             }
             ContentNode::StageDirection(sd) => {
                 let text = format!("{} {}", sd.entity_ref, sd.text);
-                text_parts.push(text.trim().to_string());
-            }
-            ContentNode::Jump(jump) => {
-                if jump.target != "end" {
-                    if let Some(ann) = &jump.annotation {
-                        if let Some(sec_id) = &ann.resolved_section {
-                            goto = Some(sec_id.clone());
-                        }
-                    }
-                }
+                text_parts.push((text.trim().to_string(), &[]));
+                first_span.get_or_insert_with(|| sd.span.clone());
             }
             _ => {}
         }
     }
 
-    if text_parts.is_empty() {
-        return None;
+    for node in region_c {
+        if let ContentNode::Jump(jump) = node {
+            if let Some(data) = lower_jump(jump, symbol_table) {
+                jump_data = Some(data);
+            }
+        }
     }
 
+    let span = first_span?;
+    let parts: Vec<(&str, &[String])> = text_parts.iter().map(|(t, v)| (t.as_str(), *v)).collect();
+
     Some(ExhaustedData {
-        text: text_parts.join("\n\n"),
+        text: joined_variants(&parts, "\n\n"),
+        span,
         speaker,
-        goto,
+        jump: jump_data,
     })
 }
 
+/// Lower a jump LINK resolved: `-> end` and section jumps become a
+/// `goto`, and exit jumps (`-> exit:north`, or `-> north` with no section
+/// of that name) an `exit`. Unresolved jumps lower to nothing.
+fn lower_jump(jump: &crate::ast::Jump, symbol_table: &SymbolTable) -> Option<JumpData> {
+    if jump.target == "end" {
+        return Some(JumpData::Goto("end".to_string()));
+    }
+    if let Some(sec_id) = jump_goto(jump, symbol_table) {
+        return Some(JumpData::Goto(sec_id));
+    }
+    jump.annotation
+        .as_ref()?
+        .resolved_location
+        .as_ref()
+        .map(|_| JumpData::Exit(jump.target.clone()))
+}
+
+/// The dialogue ID a section jump lands on: the target section, or for a
+/// parameterised section the instance its argument selects.
+fn jump_goto(jump: &crate::ast::Jump, symbol_table: &SymbolTable) -> Option<String> {
+    let ann = jump.annotation.as_ref()?;
+    let sec_id = ann.resolved_section.as_ref()?;
+    match (symbol_table.sections.get(sec_id), &ann.resolved_entity) {
+        (Some(ss), Some(entity_id)) if ss.param.is_some() => Some(ss.instance_id(entity_id)),
+        _ => Some(sec_id.clone()),
+    }
+}
+
 // ── Condition Lowering ──
 
 fn lower_condition(expr: &ConditionExpr, symbol_table: &SymbolTable) -> String {
     match expr {
         ConditionExpr::PropertyComparison(pc) => {
-            format!(
-                "{}.{} {} {}",
-                strip_at(&pc.entity_ref),
-                pc.property,
-                pc.operator,
-                pc.value,
-            )
+            let (operator, value) = pc.lowered();
+            let entity = pc.entity_ref.strip_prefix('@').unwrap_or(&pc.entity_ref);
+            // A ref property is compared with an entity, which is written without its sigil.
+            let value = value.strip_prefix('@').unwrap_or(value);
+            let mut out = String::with_capacity(entity.len() + pc.property.len() + operator.len() + value.len() + 3);
+            out.push_str(entity);
+            out.push('.');
+            out.push_str(&pc.property);
+            out.push(' ');
+            out.push_str(operator);
+            out.push(' ');
+            out.push_str(value);
+            out
         }
         ConditionExpr::ContainmentCheck(cc) => {
             let entity = strip_at(&cc.entity_ref);
@@ -1144,17 +1787,50 @@ fn lower_condition(expr: &ConditionExpr, symbol_table: &SymbolTable) -> String {
                 .unwrap_or_else(|| ec.section_name.clone());
             format!("{}.exhausted", section_id)
         }
+        ConditionExpr::VisitedCheck(vc) => {
+            let location_id = vc
+                .annotation
+                .as_ref()
+                .and_then(|a| a.resolved_location.as_ref())
+                .cloned()
+                .unwrap_or_else(|| vc.location_ref.clone());
+            if vc.negated {
+                format!("{}.visited == false", location_id)
+            } else {
+                format!("{}.visited", location_id)
+            }
+        }
+        // VALIDATE rejects `once` outside a choice (URD445), and choices
+        // emit it as a marker, so it never reaches a condition string.
+        ConditionExpr::Once(_) => unreachable!("'once' lowered as a condition"),
     }
 }
 
-fn lower_condition_space_free(expr: &ConditionExpr, symbol_table: &SymbolTable) -> String {
-    let s = lower_condition(expr, symbol_table);
-    s.replace(" == ", "==")
-        .replace(" != ", "!=")
-        .replace(" < ", "<")
-        .replace(" > ", ">")
-        .replace(" <= ", "<=")
-        .replace(" >= ", ">=")
+/// Comparison operators written without their surrounding spaces.
+const SPACE_FREE_OPERATORS: &[&str] = &["==", "!=", "<=", ">=", "<", ">"];
+
+/// Append `expr` to `out` with the spaces around each comparison operator
+/// removed (`a == b` becomes `a==b`), in one pass over the input.
+fn push_space_free(expr: &str, out: &mut String) {
+    let mut rest = expr;
+    while let Some(pos) = rest.find(' ') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+        let operator = SPACE_FREE_OPERATORS
+            .iter()
+            .find(|op| after.strip_prefix(**op).is_some_and(|tail| tail.starts_with(' ')));
+        match operator {
+            Some(op) => {
+                out.push_str(op);
+                rest = &after[op.len() + 1..];
+            }
+            None => {
+                out.push(' ');
+                rest = after;
+            }
+        }
+    }
+    out.push_str(rest);
 }
 
 fn resolve_container(
@@ -1185,19 +1861,13 @@ fn lower_effect(
 ) -> Json {
     match effect_type {
         EffectType::Set {
-            target_prop: _,
+            target_prop,
             operator,
             value_expr,
+            ..
         } => {
             let ann = annotation.as_ref();
-            let entity_id = ann
-                .and_then(|a| a.resolved_entity.as_ref())
-                .map(|e| strip_at(e))
-                .unwrap_or_default();
-            let prop_name = ann
-                .and_then(|a| a.resolved_property.as_ref())
-                .cloned()
-                .unwrap_or_default();
+            let (entity_id, prop_name) = effect_target(ann, target_prop);
 
             let target = format!("{}.{}", entity_id, prop_name);
 
@@ -1217,28 +1887,18 @@ fn lower_effect(
         }
 
         EffectType::Move {
-            entity_ref: _,
-            destination_ref: _,
+            entity_ref,
+            destination_ref,
         } => {
             let ann = annotation.as_ref();
-            let entity_id = ann
-                .and_then(|a| a.resolved_entity.as_ref())
-                .map(|e| strip_at(e))
-                .unwrap_or_default();
-
-            let destination = if let Some(a) = ann {
-                if let Some(dk) = &a.destination_kind {
-                    match dk {
-                        DestinationKind::KeywordPlayer => "player".to_string(),
-                        DestinationKind::KeywordHere => "player.container".to_string(),
-                        DestinationKind::EntityRef(id) => strip_at(id),
-                        DestinationKind::LocationRef(id) => id.clone(),
-                    }
-                } else {
-                    String::new()
-                }
-            } else {
-                String::new()
+            let entity_id = effect_entity(ann, entity_ref);
+
+            let destination = match ann.and_then(|a| a.destination_kind.as_ref()) {
+                Some(DestinationKind::KeywordPlayer) => "player".to_string(),
+                Some(DestinationKind::KeywordHere) => "player.container".to_string(),
+                Some(DestinationKind::EntityRef(id)) => strip_at(id),
+                Some(DestinationKind::LocationRef(id)) => id.clone(),
+                None => strip_at(destination_ref),
             };
 
             let mut obj = Map::new();
@@ -1247,16 +1907,8 @@ fn lower_effect(
             Json::Object(obj)
         }
 
-        EffectType::Reveal { target_prop: _ } => {
-            let ann = annotation.as_ref();
-            let entity_id = ann
-                .and_then(|a| a.resolved_entity.as_ref())
-                .map(|e| strip_at(e))
-                .unwrap_or_default();
-            let prop_name = ann
-                .and_then(|a| a.resolved_property.as_ref())
-                .cloned()
-                .unwrap_or_default();
+        EffectType::Reveal { target_prop } => {
+            let (entity_id, prop_name) = effect_target(annotation.as_ref(), target_prop);
 
             let mut obj = Map::new();
             obj.insert(
@@ -1266,20 +1918,42 @@ fn lower_effect(
             Json::Object(obj)
         }
 
-        EffectType::Destroy { entity_ref: _ } => {
-            let ann = annotation.as_ref();
-            let entity_id = ann
-                .and_then(|a| a.resolved_entity.as_ref())
-                .map(|e| strip_at(e))
-                .unwrap_or_default();
+        EffectType::Destroy { entity_ref } => {
+            let entity_id = effect_entity(annotation.as_ref(), entity_ref);
 
             let mut obj = Map::new();
             obj.insert("destroy".to_string(), Json::String(entity_id));
             Json::Object(obj)
         }
+
+        EffectType::End { ending_ref } => {
+            let mut obj = Map::new();
+            obj.insert("end".to_string(), Json::String(ending_ref.clone()));
+            Json::Object(obj)
+        }
+    }
+}
+
+/// The entity an effect names: the resolved ID, or the name as written
+/// when LINK left it unresolved (a fragment's undeclared entity, see
+/// [`crate::fragment`]), as conditions lower it.
+fn effect_entity(ann: Option<&crate::ast::Annotation>, written: &str) -> String {
+    match ann.and_then(|a| a.resolved_entity.as_ref()) {
+        Some(id) => strip_at(id),
+        None => strip_at(written),
     }
 }
 
+/// The entity and property of an `@entity.property` effect target, each
+/// resolved or as written (see [`effect_entity()`]).
+fn effect_target(ann: Option<&crate::ast::Annotation>, target_prop: &str) -> (String, String) {
+    let (entity, property) = target_prop.split_once('.').unwrap_or((target_prop, ""));
+    let property = ann
+        .and_then(|a| a.resolved_property.clone())
+        .unwrap_or_else(|| property.to_string());
+    (effect_entity(ann, entity), property)
+}
+
 /// Convert a value expression string to a typed JSON value.
 fn typed_value(
     value_expr: &str,
@@ -1325,26 +1999,25 @@ fn typed_value(
 
 // ── Helpers ──
 
-fn collect_choice_conditions_effects(
-    choice: &crate::ast::Choice,
+/// Lower the direct Condition, OrConditionBlock, and Effect children of a
+/// choice or location hook.
+fn collect_conditions_effects(
+    children: &[ContentNode],
     symbol_table: &SymbolTable,
 ) -> (Option<Json>, Vec<Json>) {
     let mut and_conditions: Vec<String> = Vec::new();
-    let mut or_conditions: Option<Vec<String>> = None;
+    let mut or_blocks: Vec<Vec<String>> = Vec::new();
     let mut effects: Vec<Json> = Vec::new();
 
-    for child in &choice.content {
+    for child in children {
         match child {
+            // `once` is a marker on the choice, not a condition.
+            ContentNode::Condition(cond) if matches!(cond.expr, ConditionExpr::Once(_)) => {}
             ContentNode::Condition(cond) => {
                 and_conditions.push(lower_condition(&cond.expr, symbol_table));
             }
             ContentNode::OrConditionBlock(or) => {
-                let or_conds: Vec<String> = or
-                    .conditions
-                    .iter()
-                    .map(|c| lower_condition(c, symbol_table))
-                    .collect();
-                or_conditions = Some(or_conds);
+                or_blocks.push(or.conditions.iter().map(|c| lower_condition(c, symbol_table)).collect());
             }
             ContentNode::Effect(eff) => {
                 effects.push(lower_effect(&eff.effect_type, &eff.annotation, symbol_table));
@@ -1353,30 +2026,37 @@ fn collect_choice_conditions_effects(
         }
     }
 
-    let conditions = build_conditions_json(&and_conditions, &or_conditions);
+    let conditions = build_conditions_json(&and_conditions, &or_blocks);
     (conditions, effects)
 }
 
-fn build_conditions_json(
-    and_conditions: &[String],
-    or_conditions: &Option<Vec<String>>,
-) -> Option<Json> {
-    if let Some(or_conds) = or_conditions {
-        if !or_conds.is_empty() {
+/// The `conditionExpr` for a node's conditions, or `None` if it has none.
+///
+/// Pure AND is a flat array and a single `? any:` block with nothing
+/// beside it is `{ "any": [...] }`. Anything else is
+/// `{ "all": [...] }`: the AND strings first, then one `any` object per
+/// block, in source order.
+fn build_conditions_json(and_conditions: &[String], or_blocks: &[Vec<String>]) -> Option<Json> {
+    let strings = |conds: &[String]| conds.iter().map(|c| Json::String(c.clone())).collect::<Vec<_>>();
+    let any = |conds: &[String]| {
+        let mut obj = Map::new();
+        obj.insert("any".to_string(), Json::Array(strings(conds)));
+        Json::Object(obj)
+    };
+
+    let or_blocks: Vec<&Vec<String>> = or_blocks.iter().filter(|block| !block.is_empty()).collect();
+    match (and_conditions.is_empty(), or_blocks.as_slice()) {
+        (true, []) => None,
+        (false, []) => Some(Json::Array(strings(and_conditions))),
+        (true, [block]) => Some(any(block)),
+        _ => {
+            let mut all = strings(and_conditions);
+            all.extend(or_blocks.iter().map(|block| any(block)));
             let mut obj = Map::new();
-            obj.insert(
-                "any".to_string(),
-                Json::Array(or_conds.iter().map(|c| Json::String(c.clone())).collect()),
-            );
-            return Some(Json::Object(obj));
+            obj.insert("all".to_string(), Json::Array(all));
+            Some(Json::Object(obj))
         }
     }
-    if !and_conditions.is_empty() {
-        return Some(Json::Array(
-            and_conditions.iter().map(|c| Json::String(c.clone())).collect(),
-        ));
-    }
-    None
 }
 
 fn value_to_json(value: &Value) -> Json {
@@ -1390,14 +2070,16 @@ fn value_to_json(value: &Value) -> Json {
     }
 }
 
+/// Canonical JSON for a number: a whole value within `i64` range is an
+/// integer (`3`, never `3.0`; `-0.0` is `0`), and anything else is the
+/// shortest decimal that reads back as the same `f64` (`0.1`, `1e+21`), as
+/// serde_json's formatter writes it. VALIDATE rejects NaN and infinite
+/// values, so the `null` fallback is never reached for a valid world.
 fn number_to_json(n: f64) -> Json {
-    // If the number is a whole number, emit as integer.
-    if n.fract() == 0.0 && n.is_finite() {
-        if let Some(num) = Number::from_f64(n) {
-            return Json::Number(num);
-        }
+    // `i64::MAX as f64` rounds up to 2^63, which is out of range.
+    if n.fract() == 0.0 && n >= i64::MIN as f64 && n < i64::MAX as f64 {
+        return Json::Number(Number::from(n as i64));
     }
-    // Otherwise emit as float.
     Number::from_f64(n)
         .map(Json::Number)
         .unwrap_or(Json::Null)
@@ -0,0 +1,171 @@
+/// Numeric IDs: a small integer for every emitted symbol.
+///
+/// With `EmitOptions::numeric_ids`, each type, entity, location, rule,
+/// action, sequence, phase, section and choice object gains an `"idx"`
+/// field, and a top-level `"index"` block maps string IDs to them:
+///
+/// ```json
+/// "index": {
+///   "entities": { "warden": 0, "ghost": 1 },
+///   "phases": { "main-quest": { "intro": 0, "descent": 1 } },
+///   "choices": { "gatehouse/greet/ask-about-the-key": 0 }
+/// }
+/// ```
+///
+/// Integers count from zero within each kind, in emission order: block
+/// order, then key order within a block, with choices numbered depth
+/// first. Emission order is deterministic, so the same source always
+/// yields the same integers. Phase IDs are unique only within their
+/// sequence, so the index keys phases by sequence first; their integers
+/// still count across all sequences.
+///
+/// The pass runs over each block once it is built, so it numbers exactly
+/// what was emitted, including parameterised section instances.
+
+use serde_json::{Map, Value as Json};
+
+/// Blocks whose entries are numbered directly, with their index key.
+const BLOCKS: &[(&str, &str)] = &[
+    ("types", "types"),
+    ("entities", "entities"),
+    ("locations", "locations"),
+    ("rules", "rules"),
+    ("actions", "actions"),
+    ("sequences", "sequences"),
+    ("dialogue", "sections"),
+];
+
+/// Numbers blocks as they are built, in emission order, and collects the
+/// `"index"` block.
+#[derive(Default)]
+pub(super) struct Numbering {
+    index: Map<String, Json>,
+}
+
+impl Numbering {
+    /// Add `"idx"` to every numbered object in `block`. Blocks that are not
+    /// numbered are left alone.
+    pub(super) fn number(&mut self, block: &str, value: &mut Json) {
+        let Some((_, kind)) = BLOCKS.iter().find(|(name, _)| *name == block) else {
+            return;
+        };
+        let Json::Object(entries) = value else {
+            return;
+        };
+        let mut ids = Map::new();
+        for (id, entry) in entries.iter_mut() {
+            let idx = ids.len();
+            set_idx(entry, idx);
+            ids.insert(id.clone(), Json::from(idx));
+        }
+        self.index.insert(kind.to_string(), Json::Object(ids));
+
+        // Phases follow sequences, and choices follow sections.
+        match block {
+            "sequences" => {
+                self.index.insert("phases".to_string(), Json::Object(number_phases(entries)));
+            }
+            "dialogue" => {
+                let mut choices = Map::new();
+                for section in entries.values_mut() {
+                    number_choices(section, &mut choices);
+                }
+                if !choices.is_empty() {
+                    self.index.insert("choices".to_string(), Json::Object(choices));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The `"index"` block, appended after every other block.
+    pub(super) fn into_index(self) -> Map<String, Json> {
+        self.index
+    }
+}
+
+/// Number every sequence's phases, counting across sequences. Returns the
+/// index entries keyed by sequence ID, then phase ID.
+fn number_phases(sequences: &mut Map<String, Json>) -> Map<String, Json> {
+    let mut phases = Map::new();
+    let mut next = 0;
+    for (sequence_id, sequence) in sequences.iter_mut() {
+        let mut ids = Map::new();
+        if let Some(Json::Array(list)) = sequence.get_mut("phases") {
+            for phase in list {
+                if let Some(id) = phase.get("id").and_then(Json::as_str) {
+                    ids.insert(id.to_string(), Json::from(next));
+                }
+                set_idx(phase, next);
+                next += 1;
+            }
+        }
+        phases.insert(sequence_id.clone(), Json::Object(ids));
+    }
+    phases
+}
+
+/// Number the choices under `parent`, depth first. A nested choice is
+/// also listed at section level; both copies share the first integer.
+fn number_choices(parent: &mut Json, ids: &mut Map<String, Json>) {
+    let Some(Json::Array(choices)) = parent.get_mut("choices") else {
+        return;
+    };
+    for choice in choices {
+        let id = choice.get("id").and_then(Json::as_str).map(str::to_string);
+        let idx = match id.as_ref().and_then(|id| ids.get(id)).and_then(Json::as_u64) {
+            Some(existing) => existing as usize,
+            None => {
+                let idx = ids.len();
+                if let Some(id) = id {
+                    ids.insert(id, Json::from(idx));
+                }
+                idx
+            }
+        };
+        set_idx(choice, idx);
+        number_choices(choice, ids);
+    }
+}
+
+fn set_idx(object: &mut Json, idx: usize) {
+    if let Json::Object(obj) = object {
+        obj.insert("idx".to_string(), Json::from(idx));
+    }
+}
+
+/// Remove every `"idx"` field and the `"index"` block, so a world reads
+/// the same with and without numeric IDs.
+pub(crate) fn strip(world: &mut Json) {
+    let Json::Object(root) = world else {
+        return;
+    };
+    root.remove("index");
+    for (block, _) in BLOCKS {
+        let Some(Json::Object(entries)) = root.get_mut(*block) else {
+            continue;
+        };
+        for entry in entries.values_mut() {
+            strip_object(entry);
+            if let Some(Json::Array(phases)) = entry.get_mut("phases") {
+                phases.iter_mut().for_each(strip_object);
+            }
+            strip_choices(entry);
+        }
+    }
+}
+
+fn strip_choices(parent: &mut Json) {
+    if let Some(Json::Array(choices)) = parent.get_mut("choices") {
+        for choice in choices {
+            strip_object(choice);
+            strip_choices(choice);
+        }
+    }
+}
+
+fn strip_object(object: &mut Json) {
+    if let Json::Object(obj) = object {
+        obj.remove("idx");
+    }
+}
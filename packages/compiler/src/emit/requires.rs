@@ -0,0 +1,122 @@
+/// The `requires` block: what a runtime must support to run the world.
+///
+/// A runtime that implements traits, effects, or blocks incrementally can
+/// read this block on load and refuse a world it cannot run, instead of
+/// failing mid-game. Every key is always present, and arrays are sorted:
+///
+/// | Key | Value |
+/// |-----|-------|
+/// | `traits` | Traits declared by any type |
+/// | `effects` | Effect kinds used anywhere: `destroy`, `end`, `move`, `reveal`, `set` |
+/// | `conditions` | Condition kinds used anywhere: `any`, `containment`, `exhaustion`, `property_comparison`, `visited` |
+/// | `sequences`, `rules`, `dialogue` | Whether that block is emitted |
+/// | `max_choice_depth` | Deepest choice nesting; a top-level choice has depth 1 |
+///
+/// Kinds are gathered from the ASTs: choice, exit, and hook content, and
+/// rule `where` clauses and effects. `? once` is a marker on its choice,
+/// not a condition, so it is not listed.
+
+use std::collections::BTreeSet;
+
+use serde_json::{json, Value as Json};
+
+use crate::ast::{ConditionExpr, ContentNode, EffectType};
+use crate::graph::DependencyGraph;
+use crate::symbol_table::SymbolTable;
+
+/// Build the `requires` block.
+pub(super) fn build_requires(graph: &DependencyGraph, symbol_table: &SymbolTable) -> Json {
+    let traits: BTreeSet<&str> = symbol_table
+        .types
+        .values()
+        .flat_map(|ts| ts.traits.iter().map(String::as_str))
+        .collect();
+
+    let mut usage = Usage::default();
+    for node in graph.nodes.values() {
+        for child in &node.ast.content {
+            usage.visit(child, 0);
+        }
+    }
+
+    json!({
+        "traits": traits,
+        "effects": usage.effects,
+        "conditions": usage.conditions,
+        "sequences": !symbol_table.sequences.is_empty(),
+        "rules": !symbol_table.rules.is_empty(),
+        "dialogue": !symbol_table.sections.is_empty(),
+        "max_choice_depth": usage.max_choice_depth,
+    })
+}
+
+/// Kinds seen while walking content nodes.
+#[derive(Default)]
+struct Usage {
+    effects: BTreeSet<&'static str>,
+    conditions: BTreeSet<&'static str>,
+    max_choice_depth: usize,
+}
+
+impl Usage {
+    fn visit(&mut self, node: &ContentNode, choice_depth: usize) {
+        match node {
+            ContentNode::Condition(c) => self.condition(&c.expr),
+            ContentNode::OrConditionBlock(block) => {
+                self.conditions.insert("any");
+                for expr in &block.conditions {
+                    self.condition(expr);
+                }
+            }
+            ContentNode::Effect(e) => self.effect(&e.effect_type),
+            ContentNode::RuleBlock(rule) => {
+                for expr in &rule.where_clauses {
+                    self.condition(expr);
+                }
+                for e in &rule.effects {
+                    self.effect(&e.effect_type);
+                }
+            }
+            ContentNode::Choice(choice) => {
+                let depth = choice_depth + 1;
+                self.max_choice_depth = self.max_choice_depth.max(depth);
+                for child in &choice.content {
+                    self.visit(child, depth);
+                }
+            }
+            ContentNode::ExitDeclaration(exit) => {
+                for child in &exit.children {
+                    self.visit(child, choice_depth);
+                }
+            }
+            ContentNode::LocationHook(hook) => {
+                for child in &hook.children {
+                    self.visit(child, choice_depth);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn condition(&mut self, expr: &ConditionExpr) {
+        let kind = match expr {
+            ConditionExpr::PropertyComparison(_) => "property_comparison",
+            ConditionExpr::ContainmentCheck(_) => "containment",
+            ConditionExpr::ExhaustionCheck(_) => "exhaustion",
+            ConditionExpr::VisitedCheck(_) => "visited",
+            ConditionExpr::Once(_) => return,
+        };
+        self.conditions.insert(kind);
+    }
+
+    fn effect(&mut self, effect: &EffectType) {
+        let kind = match effect {
+            EffectType::Set { .. } => "set",
+            EffectType::Move { .. } => "move",
+            EffectType::Reveal { .. } => "reveal",
+            EffectType::Destroy { .. } => "destroy",
+            EffectType::End { .. } => "end",
+        };
+        self.effects.insert(kind);
+    }
+}
@@ -0,0 +1,93 @@
+/// Split output: one JSON document per group of top-level blocks.
+///
+/// Runtimes that load dialogue lazily, or stream the map separately from
+/// the rules, can consume these parts instead of the combined `.urd.json`:
+///
+/// | Part | Blocks |
+/// |------|--------|
+/// | `world.json` | `world`, `requires`, `types`, `entities`, `display_names`, `endings`, `index` |
+/// | `locations.json` | `locations`, `regions` |
+/// | `rules.json` | `rules` |
+/// | `actions.json` | `actions` |
+/// | `sequences.json` | `sequences` |
+/// | `dialogue.json` | `dialogue` |
+///
+/// Every part carries a `"world_name"` and `"urd"` header so a loader can
+/// check that the parts belong together. Blocks keep their combined-output
+/// shape and cross-reference each other by compiled ID, so the union of the
+/// parts' blocks is exactly the combined document. `manifest.json` lists the
+/// parts in the order above with their byte sizes and SHA-256 hashes.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value as Json};
+
+use super::{build_root, to_document, EmitOptions};
+use crate::diagnostics::DiagnosticCollector;
+use crate::hash::sha256_hex;
+use crate::graph::DependencyGraph;
+use crate::symbol_table::SymbolTable;
+
+/// Part file names and the top-level blocks each one holds.
+const PARTS: &[(&str, &[&str])] = &[
+    ("world.json", &["world", "requires", "types", "entities", "display_names", "endings", "index"]),
+    ("locations.json", &["locations", "regions"]),
+    ("rules.json", &["rules"]),
+    ("actions.json", &["actions"]),
+    ("sequences.json", &["sequences"]),
+    ("dialogue.json", &["dialogue"]),
+];
+
+/// Emit the split documents, keyed by file name, plus `manifest.json`.
+///
+/// All six parts are always present; a part whose blocks are absent from
+/// the world holds only its header. Precondition: `diagnostics.has_errors()`
+/// is `false`.
+pub fn emit_split(
+    graph: &DependencyGraph,
+    symbol_table: &SymbolTable,
+    _diagnostics: &mut DiagnosticCollector,
+    options: &EmitOptions,
+) -> BTreeMap<String, String> {
+    let mut root = build_root(graph, symbol_table, options, None);
+    let world_name = root
+        .get("world")
+        .and_then(|w| w.get("name"))
+        .cloned()
+        .unwrap_or(Json::Null);
+    let format = options.format.as_str();
+
+    let mut documents = BTreeMap::new();
+    let mut manifest_parts = Vec::new();
+
+    for (file, blocks) in PARTS {
+        let mut part = header(&world_name, format);
+        for block in *blocks {
+            if let Some(value) = root.shift_remove(*block) {
+                part.insert(block.to_string(), value);
+            }
+        }
+        let document = to_document(&Json::Object(part));
+
+        let mut entry = Map::new();
+        entry.insert("file".to_string(), Json::String(file.to_string()));
+        entry.insert("bytes".to_string(), Json::from(document.len()));
+        entry.insert("sha256".to_string(), Json::String(sha256_hex(document.as_bytes())));
+        manifest_parts.push(Json::Object(entry));
+
+        documents.insert(file.to_string(), document);
+    }
+
+    let mut manifest = header(&world_name, format);
+    manifest.insert("parts".to_string(), Json::Array(manifest_parts));
+    documents.insert("manifest.json".to_string(), to_document(&Json::Object(manifest)));
+
+    documents
+}
+
+fn header(world_name: &Json, format: &str) -> Map<String, Json> {
+    let mut map = Map::new();
+    map.insert("world_name".to_string(), world_name.clone());
+    map.insert("urd".to_string(), Json::String(format.to_string()));
+    map
+}
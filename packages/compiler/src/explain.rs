@@ -0,0 +1,423 @@
+/// Choice availability: why a choice is offered or withheld.
+///
+/// [`explain_choice()`] gathers everything that decides whether a choice
+/// appears: the conditions on the choice, how its section is entered,
+/// whether its location is reachable from `world.start`, and whether it is
+/// sticky or `? once`. Each condition lists the starting value of the
+/// property it reads and every effect that writes it, marking the writes
+/// that satisfy it.
+///
+/// The explanation is static. Guards on the write sites are not evaluated,
+/// and reads inside a `? any:` block are listed alongside the rest, so an
+/// explanation names what could enable a choice, not a route to it.
+
+use std::collections::VecDeque;
+
+use indexmap::IndexMap;
+
+use crate::analyze;
+use crate::facts::{
+    ChoiceId, CompareOp, EntityId, FactSet, FactSite, JumpTarget, LocationId, PropertyDependencyIndex,
+    PropertyId, SectionId, TypeId, WriteOp,
+};
+use crate::span::Span;
+
+/// Why a choice ID could not be explained.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExplainError {
+    UnknownChoice(String),
+}
+
+impl std::fmt::Display for ExplainError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExplainError::UnknownChoice(id) => write!(f, "Unknown choice '{}'.", id),
+        }
+    }
+}
+
+/// Whether the player can reach the location a choice's section is in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reachability {
+    /// The location is `world.start`.
+    Start,
+    /// Reachable from the start through unconditional exits.
+    Open,
+    /// Reachable only through at least one conditional exit.
+    Gated,
+    /// No exit path leads from the start.
+    Unreachable,
+    /// The world has no start, or the section is under no location.
+    Unknown,
+}
+
+impl Reachability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Reachability::Start => "start",
+            Reachability::Open => "open",
+            Reachability::Gated => "gated",
+            Reachability::Unreachable => "unreachable",
+            Reachability::Unknown => "unknown",
+        }
+    }
+}
+
+/// A jump into the choice's section from another section.
+#[derive(Debug, Clone)]
+pub struct SectionEntry {
+    pub from_section: SectionId,
+    /// The choice whose content holds the jump. `None` for a jump in the
+    /// section's own content.
+    pub from_choice: Option<ChoiceId>,
+    pub span: Span,
+}
+
+/// An effect that writes the property a condition reads.
+#[derive(Debug, Clone)]
+pub struct WriteSiteExplanation {
+    pub site: FactSite,
+    pub entity: Option<EntityId>,
+    pub operator: WriteOp,
+    pub value: String,
+    /// Whether the write satisfies the condition: `Some` for an assignment
+    /// of a literal, `None` for `+` and `-`, whose result depends on the
+    /// value they start from.
+    pub enables: Option<bool>,
+    /// Whether the write site has conditions of its own.
+    pub guarded: bool,
+    pub span: Span,
+}
+
+/// One property comparison on the choice.
+#[derive(Debug, Clone)]
+pub struct ConditionExplanation {
+    pub entity: Option<EntityId>,
+    pub entity_type: TypeId,
+    pub property: PropertyId,
+    pub operator: CompareOp,
+    pub value: String,
+    /// The entity's override, else the type's default. `None` when neither
+    /// is declared, and for the parameter of a parameterised section, whose
+    /// value depends on the argument.
+    pub starting_value: Option<String>,
+    /// Whether the starting value satisfies the comparison. `None` when
+    /// there is no starting value or the comparison cannot be decided.
+    pub holds_at_start: Option<bool>,
+    /// Writes to the property, restricted to the read entity when both
+    /// name one.
+    pub writes: Vec<WriteSiteExplanation>,
+    pub span: Span,
+}
+
+impl ConditionExplanation {
+    /// The writes that satisfy the condition or may, by arithmetic.
+    pub fn enabling_writes(&self) -> impl Iterator<Item = &WriteSiteExplanation> {
+        self.writes.iter().filter(|w| w.enables != Some(false))
+    }
+}
+
+/// The requirements for a choice to be offered.
+#[derive(Debug, Clone)]
+pub struct ChoiceExplanation {
+    pub choice_id: ChoiceId,
+    pub label: String,
+    pub section: SectionId,
+    pub sticky: bool,
+    pub once: bool,
+    pub location: Option<LocationId>,
+    pub reachability: Reachability,
+    pub entries: Vec<SectionEntry>,
+    pub conditions: Vec<ConditionExplanation>,
+    /// Containment, exhaustion, and visited conditions, which are not
+    /// property comparisons.
+    pub other_conditions: Vec<Span>,
+    pub span: Span,
+}
+
+/// Explain the availability of the choice with compiled ID `choice_id`.
+pub fn explain_choice(
+    fact_set: &FactSet,
+    index: &PropertyDependencyIndex,
+    choice_id: &str,
+) -> Result<ChoiceExplanation, ExplainError> {
+    let choice = fact_set
+        .choice_by_id(choice_id)
+        .ok_or_else(|| ExplainError::UnknownChoice(choice_id.to_string()))?;
+
+    let entries = fact_set
+        .jumps()
+        .iter()
+        .enumerate()
+        .filter(|(_, j)| j.from_section != choice.section)
+        .filter(|(_, j)| matches!(&j.target, JumpTarget::Section(id) if id == &choice.section))
+        .map(|(i, j)| SectionEntry {
+            from_section: j.from_section.clone(),
+            from_choice: fact_set
+                .choices()
+                .iter()
+                .find(|c| c.jump_indices.contains(&i))
+                .map(|c| c.choice_id.clone()),
+            span: j.span.clone(),
+        })
+        .collect();
+
+    let conditions = choice
+        .condition_reads
+        .iter()
+        .filter_map(|&i| fact_set.reads().get(i))
+        .map(|read| {
+            let starting_value = read
+                .entity
+                .as_ref()
+                .and_then(|id| fact_set.entity_by_id(id))
+                .and_then(|e| e.starting_values.get(&read.property))
+                .cloned();
+            let holds_at_start = starting_value
+                .as_ref()
+                .and_then(|v| analyze::literal_satisfies(v, &read.operator, &read.value_literal));
+            let writes = index
+                .writes_of(&read.key())
+                .iter()
+                .filter_map(|&i| fact_set.writes().get(i))
+                .filter(|w| match (&read.entity, &w.entity) {
+                    (Some(r), Some(w)) => r == w,
+                    _ => true,
+                })
+                .map(|w| WriteSiteExplanation {
+                    site: w.site.clone(),
+                    entity: w.entity.clone(),
+                    operator: w.operator.clone(),
+                    value: w.value_expr.clone(),
+                    enables: match w.operator {
+                        WriteOp::Set => analyze::literal_satisfies(&w.value_expr, &read.operator, &read.value_literal),
+                        WriteOp::Add | WriteOp::Sub => None,
+                    },
+                    guarded: !fact_set.read_indices_for_site(&w.site).is_empty(),
+                    span: w.span.clone(),
+                })
+                .collect();
+            ConditionExplanation {
+                entity: read.entity.clone(),
+                entity_type: read.entity_type.clone(),
+                property: read.property.clone(),
+                operator: read.operator.clone(),
+                value: read.value_literal.clone(),
+                starting_value,
+                holds_at_start,
+                writes,
+                span: read.span.clone(),
+            }
+        })
+        .collect();
+
+    Ok(ChoiceExplanation {
+        choice_id: choice.choice_id.clone(),
+        label: choice.label.clone(),
+        section: choice.section.clone(),
+        sticky: choice.sticky,
+        once: choice.once,
+        location: choice.location.clone(),
+        reachability: reachability(fact_set, choice.location.as_deref()),
+        entries,
+        conditions,
+        other_conditions: choice.other_conditions.clone(),
+        span: choice.span.clone(),
+    })
+}
+
+/// Breadth-first over exits from the start, preferring unconditional
+/// routes: a location first reached through a conditional exit may still
+/// turn out to be open.
+fn reachability(fact_set: &FactSet, location: Option<&str>) -> Reachability {
+    let (Some(start), Some(target)) = (fact_set.world_start(), location) else {
+        return Reachability::Unknown;
+    };
+    if start == target {
+        return Reachability::Start;
+    }
+
+    // Location → whether every exit on the route so far is unconditional.
+    let mut seen: IndexMap<&str, bool> = IndexMap::new();
+    let mut queue: VecDeque<(&str, bool)> = VecDeque::new();
+    seen.insert(start, true);
+    queue.push_back((start, true));
+    while let Some((from, open)) = queue.pop_front() {
+        for exit in fact_set.exits().iter().filter(|e| e.from_location == from) {
+            let open = open && !exit.is_conditional;
+            let to = exit.to_location.as_str();
+            match seen.get(to) {
+                Some(&known) if known || !open => continue,
+                _ => {}
+            }
+            seen.insert(to, open);
+            queue.push_back((to, open));
+        }
+    }
+    match seen.get(target) {
+        Some(true) => Reachability::Open,
+        Some(false) => Reachability::Gated,
+        None => Reachability::Unreachable,
+    }
+}
+
+fn compare_op_str(op: &CompareOp) -> &'static str {
+    match op {
+        CompareOp::Eq => "==",
+        CompareOp::Ne => "!=",
+        CompareOp::Lt => "<",
+        CompareOp::Gt => ">",
+        CompareOp::Le => "<=",
+        CompareOp::Ge => ">=",
+    }
+}
+
+fn write_op_str(op: &WriteOp) -> &'static str {
+    match op {
+        WriteOp::Set => "=",
+        WriteOp::Add => "+",
+        WriteOp::Sub => "-",
+    }
+}
+
+fn site_label(site: &FactSite) -> String {
+    match site {
+        FactSite::Choice(id) => format!("choice {}", id),
+        FactSite::Exit(id) => format!("exit {}", id),
+        FactSite::Rule(id) => format!("rule {}", id),
+        FactSite::LocationHook(id) => format!("hook {}", id),
+    }
+}
+
+fn site_to_json(site: &FactSite) -> serde_json::Value {
+    match site {
+        FactSite::Choice(id) => serde_json::json!({ "kind": "choice", "id": id }),
+        FactSite::Exit(id) => serde_json::json!({ "kind": "exit", "id": id }),
+        FactSite::Rule(id) => serde_json::json!({ "kind": "rule", "id": id }),
+        FactSite::LocationHook(id) => serde_json::json!({ "kind": "location_hook", "id": id }),
+    }
+}
+
+fn span_to_json(span: &Span) -> serde_json::Value {
+    serde_json::json!({
+        "file": span.file,
+        "start_line": span.start_line,
+        "start_col": span.start_col,
+        "end_line": span.end_line,
+        "end_col": span.end_col,
+    })
+}
+
+impl ConditionExplanation {
+    /// The condition as written: `@warden.trust >= 3`.
+    pub fn expression(&self) -> String {
+        let subject = match &self.entity {
+            Some(id) => format!("@{}", id),
+            None => self.entity_type.clone(),
+        };
+        format!("{}.{} {} {}", subject, self.property, compare_op_str(&self.operator), self.value)
+    }
+}
+
+impl ChoiceExplanation {
+    /// Whether nothing but its section being shown decides the choice.
+    pub fn is_unconditional(&self) -> bool {
+        self.conditions.is_empty() && self.other_conditions.is_empty()
+    }
+
+    /// One paragraph, in prose, for hovers and command-line output.
+    pub fn summary(&self) -> String {
+        let kind = match (self.sticky, self.once) {
+            (true, false) => "Sticky choice",
+            (true, true) => "Sticky choice, offered once,",
+            (false, false) => "One-shot choice",
+            (false, true) => "One-shot choice, offered once,",
+        };
+        let mut sentences = vec![format!("{} '{}' in section {}.", kind, self.label, self.section)];
+
+        if let Some(location) = &self.location {
+            sentences.push(match self.reachability {
+                Reachability::Start => format!("Location {} is the start.", location),
+                Reachability::Open => format!("Location {} is reachable from the start.", location),
+                Reachability::Gated => format!("Location {} is reachable only through a conditional exit.", location),
+                Reachability::Unreachable => format!("Location {} is not reachable from the start.", location),
+                Reachability::Unknown => format!("The world has no start to reach location {} from.", location),
+            });
+        }
+
+        if self.is_unconditional() {
+            sentences.push("It has no conditions: it is offered whenever its section is shown.".to_string());
+        }
+        for condition in &self.conditions {
+            let start = match (&condition.starting_value, condition.holds_at_start) {
+                (Some(v), Some(true)) => format!("it starts at {}, which satisfies it", v),
+                (Some(v), Some(false)) => format!("it starts at {}, which does not", v),
+                (Some(v), None) => format!("it starts at {}", v),
+                (None, _) => "it has no starting value".to_string(),
+            };
+            let writers: Vec<String> = condition.enabling_writes().map(|w| site_label(&w.site)).collect();
+            let writes = if writers.is_empty() {
+                "no effect enables it".to_string()
+            } else {
+                format!("enabled by {}", writers.join(", "))
+            };
+            sentences.push(format!("Needs {}: {}; {}.", condition.expression(), start, writes));
+        }
+        match self.other_conditions.len() {
+            0 => {}
+            1 => sentences.push("One other condition checks containment, exhaustion, or visits.".to_string()),
+            n => sentences.push(format!("{} other conditions check containment, exhaustion, or visits.", n)),
+        }
+        if !self.entries.is_empty() {
+            let mut from: Vec<&str> = Vec::new();
+            for entry in &self.entries {
+                if !from.contains(&entry.from_section.as_str()) {
+                    from.push(&entry.from_section);
+                }
+            }
+            sentences.push(format!("Its section is entered from {}.", from.join(", ")));
+        }
+        sentences.join(" ")
+    }
+
+    /// Serialise the explanation to a JSON value.
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::json!({
+            "choice_id": self.choice_id,
+            "label": self.label,
+            "section": self.section,
+            "sticky": self.sticky,
+            "once": self.once,
+            "location": self.location,
+            "reachability": self.reachability.as_str(),
+            "entries": self.entries.iter().map(|e| serde_json::json!({
+                "from_section": e.from_section,
+                "from_choice": e.from_choice,
+                "span": span_to_json(&e.span),
+            })).collect::<Vec<_>>(),
+            "conditions": self.conditions.iter().map(|c| serde_json::json!({
+                "entity": c.entity,
+                "entity_type": c.entity_type,
+                "property": c.property,
+                "operator": compare_op_str(&c.operator),
+                "value": c.value,
+                "starting_value": c.starting_value,
+                "holds_at_start": c.holds_at_start,
+                "writes": c.writes.iter().map(|w| serde_json::json!({
+                    "site": site_to_json(&w.site),
+                    "entity": w.entity,
+                    "operator": write_op_str(&w.operator),
+                    "value": w.value,
+                    "enables": w.enables,
+                    "guarded": w.guarded,
+                    "span": span_to_json(&w.span),
+                })).collect::<Vec<_>>(),
+                "span": span_to_json(&c.span),
+            })).collect::<Vec<_>>(),
+            "other_conditions": self.other_conditions.iter().map(span_to_json).collect::<Vec<_>>(),
+            "unconditional": self.is_unconditional(),
+            "summary": self.summary(),
+            "span": span_to_json(&self.span),
+        })
+    }
+}
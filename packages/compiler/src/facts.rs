@@ -11,11 +11,10 @@
 
 use indexmap::IndexMap;
 
-use crate::ast::{ContentNode, ConditionExpr, EffectType};
+use crate::ast::{ContentNode, ConditionExpr, Effect, EffectType, FrontmatterValue, HookKind, Scalar};
 use crate::graph::DependencyGraph;
-use crate::slugify::slugify;
 use crate::span::Span;
-use crate::symbol_table::{PropertyType, SymbolTable};
+use crate::symbol_table::{PropertyType, SymbolTable, Value, WorldMode};
 
 // ── Identity type aliases ──
 
@@ -23,6 +22,8 @@ use crate::symbol_table::{PropertyType, SymbolTable};
 pub type TypeId = String;
 /// Resolved property name (e.g., "trust").
 pub type PropertyId = String;
+/// Resolved entity ID, without the `@` (e.g., "harbor_master").
+pub type EntityId = String;
 /// Slugified location ID.
 pub type LocationId = String;
 /// Compiled section ID (file_stem/section_name).
@@ -33,6 +34,14 @@ pub type ChoiceId = String;
 pub type ExitId = String;
 /// Rule identifier.
 pub type RuleId = String;
+/// Composite hook ID: "location_id/on_enter" or "location_id/on_exit".
+pub type HookId = String;
+/// Slugified sequence ID.
+pub type SequenceId = String;
+/// Slugified phase ID, unique within its sequence.
+pub type PhaseId = String;
+/// Ending ID, as declared in the `endings:` block.
+pub type EndingId = String;
 
 // ── PropertyKey ──
 
@@ -114,6 +123,7 @@ pub enum FactSite {
     Choice(ChoiceId),
     Exit(ExitId),
     Rule(RuleId),
+    LocationHook(HookId),
 }
 
 /// The result of resolving a FactSite to its owning construct.
@@ -121,6 +131,7 @@ pub enum SiteOwner<'a> {
     Choice(&'a ChoiceFact),
     Exit(&'a ExitEdge),
     Rule(&'a RuleFact),
+    LocationHook(&'a HookFact),
 }
 
 // ── Fact structs ──
@@ -129,6 +140,10 @@ pub enum SiteOwner<'a> {
 #[derive(Debug, Clone)]
 pub struct PropertyRead {
     pub site: FactSite,
+    /// The entity read, when the reference names one. `None` for the
+    /// parameter of a parameterised section. A read through a rule's
+    /// select variable is recorded once per candidate entity.
+    pub entity: Option<EntityId>,
     pub entity_type: TypeId,
     pub property: PropertyId,
     pub operator: CompareOp,
@@ -150,6 +165,8 @@ impl PropertyRead {
 #[derive(Debug, Clone)]
 pub struct PropertyWrite {
     pub site: FactSite,
+    /// The entity written, as for [`PropertyRead::entity`].
+    pub entity: Option<EntityId>,
     pub entity_type: TypeId,
     pub property: PropertyId,
     pub operator: WriteOp,
@@ -175,6 +192,12 @@ pub struct ExitEdge {
     pub exit_name: String,
     pub is_conditional: bool,
     pub guard_reads: Vec<usize>,
+    /// Property writes among the exit's effects.
+    pub effect_writes: Vec<usize>,
+    /// Whether the exit declares any effect, including moves, reveals and
+    /// destroys, which are not property writes.
+    pub has_effects: bool,
+    pub has_blocked_message: bool,
     pub span: Span,
 }
 
@@ -190,6 +213,93 @@ impl ExitEdge {
 pub struct JumpEdge {
     pub from_section: SectionId,
     pub target: JumpTarget,
+    /// The entity passed to a parameterised section (`-> haggle(@entity)`).
+    pub argument: Option<EntityId>,
+    pub span: Span,
+}
+
+/// A parameterised section (`== haggle(@merchant: Merchant)`). Its choices
+/// and jumps are facts of the template; EMIT multiplies them per argument.
+#[derive(Debug, Clone)]
+pub struct SectionTemplateFact {
+    pub section: SectionId,
+    pub parameter: String,
+    pub parameter_type: TypeId,
+    pub span: Span,
+}
+
+/// A sequence phase. Phases of a sequence appear in declaration order.
+#[derive(Debug, Clone)]
+pub struct PhaseFact {
+    pub sequence: SequenceId,
+    pub phase: PhaseId,
+    pub advance: String,
+    /// Properties read by an `on_condition` advance expression. References
+    /// that do not resolve to a typed entity property are left out.
+    pub advance_reads: Vec<PropertyKey>,
+    pub span: Span,
+}
+
+/// Where the world starts an entity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Placement {
+    /// Listed in a location's entity presence line.
+    Location(LocationId),
+    /// Listed in `world.start_inventory`.
+    StartInventory,
+}
+
+/// An entity declared in frontmatter, with its starting places.
+#[derive(Debug, Clone)]
+pub struct EntityFact {
+    pub entity: EntityId,
+    /// `None` when the entity's type does not resolve.
+    pub entity_type: Option<TypeId>,
+    /// The traits of the entity's type.
+    pub traits: Vec<String>,
+    /// Locations in declaration order, then the start inventory. Empty
+    /// when the world starts the entity nowhere.
+    pub placements: Vec<Placement>,
+    /// The value each property of the entity's type starts at: the
+    /// entity's override, else the type's default, written as a condition
+    /// literal. Properties with neither are left out.
+    pub starting_values: IndexMap<PropertyId, String>,
+    pub span: Span,
+}
+
+/// A location, with what a player arriving there finds.
+#[derive(Debug, Clone)]
+pub struct LocationFact {
+    pub location: LocationId,
+    /// Whether the heading is followed by description prose.
+    pub has_description: bool,
+    /// Sections whose labels follow the location's heading, in source
+    /// order.
+    pub sections: Vec<SectionId>,
+    pub span: Span,
+}
+
+/// A `> move @entity -> destination` effect, in a choice, rule, or hook.
+#[derive(Debug, Clone)]
+pub struct MoveFact {
+    pub entity: EntityId,
+    pub span: Span,
+}
+
+/// An ending declared in the `endings:` block.
+#[derive(Debug, Clone)]
+pub struct EndingFact {
+    pub ending: EndingId,
+    pub span: Span,
+}
+
+/// A resolved `> end ending` effect, in a choice, exit, rule, or hook.
+#[derive(Debug, Clone)]
+pub struct EndFact {
+    pub ending: EndingId,
+    /// The location whose heading precedes the effect. `None` in a rule,
+    /// which is not tied to a location, and before any heading.
+    pub location: Option<LocationId>,
     pub span: Span,
 }
 
@@ -197,10 +307,18 @@ pub struct JumpEdge {
 #[derive(Debug, Clone)]
 pub struct ChoiceFact {
     pub section: SectionId,
+    /// The location whose heading precedes the choice's section, if any.
+    pub location: Option<LocationId>,
     pub choice_id: ChoiceId,
     pub label: String,
     pub sticky: bool,
+    /// The choice's `? once` backing flag: offered only on its section's
+    /// first presentation.
+    pub once: bool,
     pub condition_reads: Vec<usize>,
+    /// Spans of conditions that are not property reads: containment,
+    /// exhaustion, and visited checks. `? once` is `once`.
+    pub other_conditions: Vec<Span>,
     pub effect_writes: Vec<usize>,
     pub jump_indices: Vec<usize>,
     pub span: Span,
@@ -215,8 +333,31 @@ pub struct RuleFact {
     pub span: Span,
 }
 
+/// An `on enter:` / `on exit:` block, with its guards and effects indexed
+/// into the FactSet.
+#[derive(Debug, Clone)]
+pub struct HookFact {
+    pub location: LocationId,
+    pub kind: HookKind,
+    pub condition_reads: Vec<usize>,
+    pub effect_writes: Vec<usize>,
+    pub span: Span,
+}
+
+impl HookFact {
+    /// Derive the canonical HookId from components.
+    pub fn hook_id(&self) -> HookId {
+        make_hook_id(&self.location, self.kind)
+    }
+}
+
 // ── Helpers ──
 
+/// Compose a HookId from its components.
+pub fn make_hook_id(location_id: &str, kind: HookKind) -> HookId {
+    format!("{}/{}", location_id, kind.json_key())
+}
+
 /// Compose an ExitId from its components.
 pub fn make_exit_id(location_id: &str, exit_name: &str) -> ExitId {
     debug_assert!(
@@ -242,6 +383,18 @@ pub struct FactSet {
     jumps: Vec<JumpEdge>,
     choices: Vec<ChoiceFact>,
     rules: Vec<RuleFact>,
+    hooks: Vec<HookFact>,
+    section_templates: Vec<SectionTemplateFact>,
+    phases: Vec<PhaseFact>,
+    entities: Vec<EntityFact>,
+    locations: Vec<LocationFact>,
+    moves: Vec<MoveFact>,
+    endings: Vec<EndingFact>,
+    ends: Vec<EndFact>,
+    unplaced_traits: Vec<String>,
+    disabled_lints: Vec<String>,
+    world_start: Option<LocationId>,
+    world_mode: WorldMode,
 }
 
 impl FactSet {
@@ -271,8 +424,66 @@ impl FactSet {
         &self.rules
     }
 
+    pub fn hooks(&self) -> &[HookFact] {
+        &self.hooks
+    }
+
+    pub fn section_templates(&self) -> &[SectionTemplateFact] {
+        &self.section_templates
+    }
+
+    pub fn phases(&self) -> &[PhaseFact] {
+        &self.phases
+    }
+
+    pub fn entities(&self) -> &[EntityFact] {
+        &self.entities
+    }
+
+    pub fn locations(&self) -> &[LocationFact] {
+        &self.locations
+    }
+
+    pub fn moves(&self) -> &[MoveFact] {
+        &self.moves
+    }
+
+    pub fn endings(&self) -> &[EndingFact] {
+        &self.endings
+    }
+
+    pub fn ends(&self) -> &[EndFact] {
+        &self.ends
+    }
+
+    /// The world block's `unplaced_traits:` list: traits whose entities
+    /// need no starting place.
+    pub fn unplaced_traits(&self) -> &[String] {
+        &self.unplaced_traits
+    }
+
+    /// The world block's `lints:` list: ANALYZE codes the world turns off.
+    pub fn disabled_lints(&self) -> &[String] {
+        &self.disabled_lints
+    }
+
+    /// The resolved `world.start` location.
+    pub fn world_start(&self) -> Option<&str> {
+        self.world_start.as_deref()
+    }
+
+    /// Whether the world block sets `mode: dialogue`: a world with no
+    /// locations, where the checks that assume a map stay silent.
+    pub fn dialogue_mode(&self) -> bool {
+        self.world_mode == WorldMode::Dialogue
+    }
+
     // Lookup helpers.
 
+    pub fn entity_by_id(&self, id: &str) -> Option<&EntityFact> {
+        self.entities.iter().find(|e| e.entity == id)
+    }
+
     pub fn choice_by_id(&self, id: &str) -> Option<&ChoiceFact> {
         self.choices.iter().find(|c| c.choice_id == id)
     }
@@ -295,6 +506,10 @@ impl FactSet {
         self.rules.iter().find(|r| r.rule_id == id)
     }
 
+    pub fn hook_by_id(&self, hook_id: &str) -> Option<&HookFact> {
+        self.hooks.iter().find(|h| h.hook_id() == hook_id)
+    }
+
     // Property-level queries.
 
     pub fn reads_by_key<'a>(
@@ -345,6 +560,10 @@ impl FactSet {
                 .rule_by_id(id)
                 .map(|r| r.condition_reads.as_slice())
                 .unwrap_or(&[]),
+            FactSite::LocationHook(id) => self
+                .hook_by_id(id)
+                .map(|h| h.condition_reads.as_slice())
+                .unwrap_or(&[]),
         }
     }
 
@@ -358,7 +577,14 @@ impl FactSet {
                 .rule_by_id(id)
                 .map(|r| r.effect_writes.as_slice())
                 .unwrap_or(&[]),
-            FactSite::Exit(_) => &[],
+            FactSite::LocationHook(id) => self
+                .hook_by_id(id)
+                .map(|h| h.effect_writes.as_slice())
+                .unwrap_or(&[]),
+            FactSite::Exit(id) => self
+                .exit_by_id(id)
+                .map(|e| e.effect_writes.as_slice())
+                .unwrap_or(&[]),
         }
     }
 
@@ -367,6 +593,7 @@ impl FactSet {
             FactSite::Choice(id) => self.choice_by_id(id).map(SiteOwner::Choice),
             FactSite::Exit(id) => self.exit_by_id(id).map(SiteOwner::Exit),
             FactSite::Rule(id) => self.rule_by_id(id).map(SiteOwner::Rule),
+            FactSite::LocationHook(id) => self.hook_by_id(id).map(SiteOwner::LocationHook),
         }
     }
 }
@@ -382,6 +609,18 @@ struct FactSetBuilder {
     jumps: Vec<JumpEdge>,
     choices: Vec<ChoiceFact>,
     rules: Vec<RuleFact>,
+    hooks: Vec<HookFact>,
+    section_templates: Vec<SectionTemplateFact>,
+    phases: Vec<PhaseFact>,
+    entities: Vec<EntityFact>,
+    locations: Vec<LocationFact>,
+    moves: Vec<MoveFact>,
+    endings: Vec<EndingFact>,
+    ends: Vec<EndFact>,
+    unplaced_traits: Vec<String>,
+    disabled_lints: Vec<String>,
+    world_start: Option<LocationId>,
+    world_mode: WorldMode,
 }
 
 impl FactSetBuilder {
@@ -393,6 +632,18 @@ impl FactSetBuilder {
             jumps: Vec::new(),
             choices: Vec::new(),
             rules: Vec::new(),
+            hooks: Vec::new(),
+            section_templates: Vec::new(),
+            phases: Vec::new(),
+            entities: Vec::new(),
+            locations: Vec::new(),
+            moves: Vec::new(),
+            endings: Vec::new(),
+            ends: Vec::new(),
+            unplaced_traits: Vec::new(),
+            disabled_lints: Vec::new(),
+            world_start: None,
+            world_mode: WorldMode::World,
         }
     }
 
@@ -426,14 +677,27 @@ impl FactSetBuilder {
         self.rules.push(rule);
     }
 
+    fn push_hook(&mut self, hook: HookFact) {
+        self.hooks.push(hook);
+    }
+
     /// Set guard reads on an exit identified by location and exit name.
-    fn set_exit_guard_reads(&mut self, from_loc: &str, exit_name: &str, reads: Vec<usize>) {
+    fn set_exit_content(
+        &mut self,
+        from_loc: &str,
+        exit_name: &str,
+        reads: Vec<usize>,
+        writes: Vec<usize>,
+        has_effects: bool,
+    ) {
         if let Some(edge) = self
             .exits
             .iter_mut()
             .find(|e| e.from_location == from_loc && e.exit_name == exit_name)
         {
             edge.guard_reads = reads;
+            edge.effect_writes = writes;
+            edge.has_effects = has_effects;
         }
     }
 
@@ -445,6 +709,18 @@ impl FactSetBuilder {
             jumps: self.jumps,
             choices: self.choices,
             rules: self.rules,
+            hooks: self.hooks,
+            section_templates: self.section_templates,
+            phases: self.phases,
+            entities: self.entities,
+            locations: self.locations,
+            moves: self.moves,
+            endings: self.endings,
+            ends: self.ends,
+            unplaced_traits: self.unplaced_traits,
+            disabled_lints: self.disabled_lints,
+            world_start: self.world_start,
+            world_mode: self.world_mode,
         }
     }
 }
@@ -462,6 +738,22 @@ fn classify_literal(property_type: &PropertyType) -> LiteralKind {
     }
 }
 
+/// A declared value as a condition literal: strings and enum values bare,
+/// entity references with `@`.
+fn value_literal(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Integer(i) => i.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::Boolean(b) => b.to_string(),
+        Value::EntityRef(id) => format!("@{}", id),
+        Value::List(items) => {
+            let items: Vec<String> = items.iter().map(value_literal).collect();
+            format!("[{}]", items.join(", "))
+        }
+    }
+}
+
 /// Classify the value_kind for a PropertyWrite.
 fn classify_write_value_kind(
     operator: &WriteOp,
@@ -493,12 +785,39 @@ fn lookup_property_type<'a>(
         .map(|p| &p.property_type)
 }
 
+/// The entity properties named in an advance expression such as
+/// `on_condition @door.locked == false`. The expression is a plain string,
+/// so references are found lexically and kept only when the entity
+/// resolves to a type that declares the property.
+fn condition_property_keys(expr: &str, symbol_table: &SymbolTable) -> Vec<PropertyKey> {
+    let mut keys: Vec<PropertyKey> = Vec::new();
+    let words = expr.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '@' || c == '.'));
+    for word in words {
+        let Some((entity, property)) = word.trim_start_matches('@').split_once('.') else {
+            continue;
+        };
+        let Some(entity_type) = symbol_table.entities.get(entity).and_then(|e| e.type_symbol.as_ref()) else {
+            continue;
+        };
+        if lookup_property_type(entity_type, property, symbol_table).is_none() {
+            continue;
+        }
+        let key = PropertyKey { entity_type: entity_type.clone(), property: property.to_string() };
+        if !keys.contains(&key) {
+            keys.push(key);
+        }
+    }
+    keys
+}
+
 /// Extract normalized analysis facts from the resolved world.
 /// Called after LINK, before or during VALIDATE.
 /// Read-only — does not modify the graph or symbol table.
 /// Deterministic — same input always produces same output.
 pub fn extract_facts(graph: &DependencyGraph, symbol_table: &SymbolTable) -> FactSet {
     let mut builder = FactSetBuilder::new();
+    builder.world_start = symbol_table.world_start.clone();
+    builder.world_mode = symbol_table.world_mode;
     let ordered: Vec<String> = graph.topological_order().into_iter().cloned().collect();
 
     // Phase A: Extract exits from symbol table.
@@ -511,12 +830,103 @@ pub fn extract_facts(graph: &DependencyGraph, symbol_table: &SymbolTable) -> Fac
                     exit_name: exit_name.clone(),
                     is_conditional: exit_sym.condition_node.is_some(),
                     guard_reads: Vec::new(), // populated in Phase B
+                    effect_writes: Vec::new(), // populated in Phase B
+                    has_effects: false,        // populated in Phase B
+                    has_blocked_message: exit_sym.blocked_message_node.is_some(),
                     span: exit_sym.declared_in.clone(),
                 });
             }
         }
     }
 
+    // Phase A2: Parameterised sections from symbol table.
+    for (section_id, section_sym) in &symbol_table.sections {
+        if let Some(param) = &section_sym.param {
+            builder.section_templates.push(SectionTemplateFact {
+                section: section_id.clone(),
+                parameter: param.name.clone(),
+                parameter_type: param.type_name.clone(),
+                span: section_sym.declared_in.clone(),
+            });
+        }
+    }
+
+    // Phase A3: Sequence phases from symbol table.
+    for (sequence_id, sequence_sym) in &symbol_table.sequences {
+        for phase in &sequence_sym.phases {
+            let advance_reads = match phase.advance.strip_prefix("on_condition ") {
+                Some(expr) => condition_property_keys(expr, symbol_table),
+                None => Vec::new(),
+            };
+            builder.phases.push(PhaseFact {
+                sequence: sequence_id.clone(),
+                phase: phase.id.clone(),
+                advance: phase.advance.clone(),
+                advance_reads,
+                span: phase.declared_in.clone(),
+            });
+        }
+    }
+
+    // Phase A4: Entities and their starting places from symbol table.
+    for (entity_id, entity_sym) in &symbol_table.entities {
+        let mut placements: Vec<Placement> = symbol_table
+            .locations
+            .iter()
+            .filter(|(_, loc)| loc.contains.contains(entity_id))
+            .map(|(loc_id, _)| Placement::Location(loc_id.clone()))
+            .collect();
+        if symbol_table.world_start_inventory.contains(entity_id) {
+            placements.push(Placement::StartInventory);
+        }
+        let traits = entity_sym
+            .type_symbol
+            .as_ref()
+            .and_then(|t| symbol_table.types.get(t))
+            .map(|t| t.traits.clone())
+            .unwrap_or_default();
+        let starting_values = entity_sym
+            .type_symbol
+            .as_ref()
+            .and_then(|t| symbol_table.types.get(t))
+            .map(|t| {
+                t.properties
+                    .iter()
+                    .filter_map(|(name, prop)| {
+                        let value = entity_sym.property_overrides.get(name).or(prop.default.as_ref())?;
+                        Some((name.clone(), value_literal(value)))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        builder.entities.push(EntityFact {
+            entity: entity_id.clone(),
+            entity_type: entity_sym.type_symbol.clone(),
+            traits,
+            placements,
+            starting_values,
+            span: entity_sym.declared_in.clone(),
+        });
+    }
+
+    // Phase A5: Locations from symbol table. Sections are added in Phase B.
+    for (loc_id, loc_sym) in &symbol_table.locations {
+        builder.locations.push(LocationFact {
+            location: loc_id.clone(),
+            has_description: !loc_sym.description.is_empty(),
+            sections: Vec::new(),
+            span: loc_sym.declared_in.clone(),
+        });
+    }
+
+    // Phase A6: Endings from symbol table.
+    for (ending_id, ending_sym) in &symbol_table.endings {
+        builder.endings.push(EndingFact {
+            ending: ending_id.clone(),
+            span: ending_sym.declared_in.clone(),
+        });
+    }
+
     // Phase B: Walk AST content in topological file order.
     for file_path in &ordered {
         let file_node = match graph.nodes.get(file_path.as_str()) {
@@ -537,11 +947,179 @@ pub fn extract_facts(graph: &DependencyGraph, symbol_table: &SymbolTable) -> Fac
                 &mut current_section_id,
             );
         }
+
+        // Phase C: Move effects, wherever they appear.
+        extract_moves(&file_node.ast.content, symbol_table, &mut builder);
+
+        // Phase C2: End effects, with the location each one sits in.
+        extract_ends(&file_node.ast.content, symbol_table, &mut builder);
+
+        // Phase D: The world block's `unplaced_traits:` and `lints:` lists.
+        if let Some(fm) = &file_node.ast.frontmatter {
+            for entry in &fm.entries {
+                if let FrontmatterValue::WorldBlock(wb) = &entry.value {
+                    for (key, value) in &wb.fields {
+                        let list = match key.as_str() {
+                            "unplaced_traits" => &mut builder.unplaced_traits,
+                            "lints" => &mut builder.disabled_lints,
+                            _ => continue,
+                        };
+                        if let Scalar::List(items) = value {
+                            list.extend(items.iter().filter_map(|item| match item {
+                                Scalar::String(s) => Some(s.clone()),
+                                _ => None,
+                            }));
+                        }
+                    }
+                }
+            }
+        }
     }
 
     builder.finish()
 }
 
+/// Record every resolved `> move` effect in `nodes`, descending into
+/// choices, exits, hooks, and rules.
+fn extract_moves(nodes: &[ContentNode], symbol_table: &SymbolTable, builder: &mut FactSetBuilder) {
+    for node in nodes {
+        match node {
+            ContentNode::Effect(effect) => push_move(effect, &[], builder),
+            ContentNode::Choice(choice) => extract_moves(&choice.content, symbol_table, builder),
+            ContentNode::ExitDeclaration(exit) => extract_moves(&exit.children, symbol_table, builder),
+            ContentNode::LocationHook(hook) => extract_moves(&hook.children, symbol_table, builder),
+            ContentNode::RuleBlock(rule) => {
+                let candidates = select_candidates(&rule.name, symbol_table);
+                for effect in &rule.effects {
+                    push_move(effect, &candidates, builder);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// A move of a rule's select variable moves whichever candidate is
+/// selected, so it is recorded for each of `candidates`.
+fn push_move(effect: &Effect, candidates: &[EntityId], builder: &mut FactSetBuilder) {
+    if !matches!(effect.effect_type, EffectType::Move { .. }) {
+        return;
+    }
+    if let Some(entity) = effect.annotation.as_ref().and_then(|a| a.resolved_entity.as_ref()) {
+        let entities = if entity.starts_with('$') { candidates } else { std::slice::from_ref(entity) };
+        for entity in entities {
+            builder.moves.push(MoveFact {
+                entity: entity.clone(),
+                span: effect.span.clone(),
+            });
+        }
+    }
+}
+
+/// Record every resolved `> end` effect in a file's top-level `nodes`.
+/// Effects under a location heading sit in that location, except in a
+/// rule, which runs wherever the player is.
+fn extract_ends(nodes: &[ContentNode], symbol_table: &SymbolTable, builder: &mut FactSetBuilder) {
+    let mut location: Option<LocationId> = None;
+    for node in nodes {
+        match node {
+            ContentNode::LocationHeading(lh) => {
+                location = Some(lh.id()).filter(|id| symbol_table.locations.contains_key(id));
+            }
+            ContentNode::RegionHeading(_) => location = None,
+            ContentNode::RuleBlock(rule) => {
+                for effect in &rule.effects {
+                    push_end(effect, None, symbol_table, builder);
+                }
+            }
+            node => push_ends(node, location.as_ref(), symbol_table, builder),
+        }
+    }
+}
+
+/// Record the `> end` effects in `node`, descending into choices, exits,
+/// and hooks.
+fn push_ends(
+    node: &ContentNode,
+    location: Option<&LocationId>,
+    symbol_table: &SymbolTable,
+    builder: &mut FactSetBuilder,
+) {
+    let children = match node {
+        ContentNode::Effect(effect) => return push_end(effect, location, symbol_table, builder),
+        ContentNode::Choice(choice) => &choice.content,
+        ContentNode::ExitDeclaration(exit) => &exit.children,
+        ContentNode::LocationHook(hook) => &hook.children,
+        _ => return,
+    };
+    for child in children {
+        push_ends(child, location, symbol_table, builder);
+    }
+}
+
+fn push_end(
+    effect: &Effect,
+    location: Option<&LocationId>,
+    symbol_table: &SymbolTable,
+    builder: &mut FactSetBuilder,
+) {
+    if let EffectType::End { ending_ref } = &effect.effect_type {
+        if symbol_table.endings.contains_key(ending_ref) {
+            builder.ends.push(EndFact {
+                ending: ending_ref.clone(),
+                location: location.cloned(),
+                span: effect.span.clone(),
+            });
+        }
+    }
+}
+
+/// The declared entities a rule's select clause chooses from, in order.
+/// Empty for a rule without a select.
+fn select_candidates(rule_id: &str, symbol_table: &SymbolTable) -> Vec<EntityId> {
+    symbol_table
+        .rules
+        .get(rule_id)
+        .and_then(|rs| rs.select.as_ref())
+        .map(|select| {
+            select
+                .from
+                .iter()
+                .filter(|id| symbol_table.entities.contains_key(*id))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A read or write through a rule's select variable (`$door`) stands for
+/// every candidate the runtime may bind. The fact at `idx` is given the
+/// first candidate and copied for the rest; with no candidates it names
+/// no entity. Returns the indices of the facts it became.
+fn bind_candidates<T: Clone>(
+    facts: &mut Vec<T>,
+    idx: usize,
+    candidates: &[EntityId],
+    entity: fn(&mut T) -> &mut Option<EntityId>,
+) -> Vec<usize> {
+    if !entity(&mut facts[idx]).as_deref().is_some_and(|e| e.starts_with('$')) {
+        return vec![idx];
+    }
+    let Some((first, rest)) = candidates.split_first() else {
+        *entity(&mut facts[idx]) = None;
+        return vec![idx];
+    };
+    let mut indices = vec![idx];
+    for candidate in rest {
+        let mut fact = facts[idx].clone();
+        *entity(&mut fact) = Some(candidate.clone());
+        indices.push(facts.len());
+        facts.push(fact);
+    }
+    *entity(&mut facts[idx]) = Some(first.clone());
+    indices
+}
+
 /// Extract facts from a top-level content node (not inside a choice).
 fn extract_top_level_node(
     node: &ContentNode,
@@ -553,7 +1131,7 @@ fn extract_top_level_node(
 ) {
     match node {
         ContentNode::LocationHeading(lh) => {
-            let slug = slugify(&lh.display_name);
+            let slug = lh.id();
             if symbol_table.locations.contains_key(&slug) {
                 *current_location_id = Some(slug);
             } else {
@@ -561,11 +1139,19 @@ fn extract_top_level_node(
             }
         }
 
+        ContentNode::RegionHeading(_) => *current_location_id = None,
+
         ContentNode::SectionLabel(sl) => {
             // Use compiled_id from symbol table — never recompute from file_stem/name.
-            let lookup_key = format!("{}/{}", file_stem, sl.name);
+            let lookup_key = format!("{}/{}", file_stem, sl.local_id());
             if let Some(section_sym) = symbol_table.sections.get(&lookup_key) {
                 *current_section_id = Some(section_sym.compiled_id.clone());
+                if let Some(loc) = current_location_id
+                    .as_ref()
+                    .and_then(|id| builder.locations.iter_mut().find(|l| &l.location == id))
+                {
+                    loc.sections.push(section_sym.compiled_id.clone());
+                }
             } else {
                 *current_section_id = None;
             }
@@ -587,13 +1173,17 @@ fn extract_top_level_node(
         }
 
         ContentNode::ExitDeclaration(exit_decl) => {
-            extract_exit_guards(exit_decl, builder, symbol_table, current_location_id);
+            extract_exit_content(exit_decl, builder, symbol_table, current_location_id);
         }
 
         ContentNode::RuleBlock(rule_block) => {
             extract_rule(rule_block, builder, symbol_table);
         }
 
+        ContentNode::LocationHook(hook) => {
+            extract_location_hook(hook, builder, symbol_table, current_location_id);
+        }
+
         // Other top-level nodes (prose, speech, conditions outside choices) are not facts.
         _ => {}
     }
@@ -630,32 +1220,43 @@ fn extract_choice(
 
     let site = FactSite::Choice(choice_id.clone());
     let mut condition_reads: Vec<usize> = Vec::new();
+    let mut other_conditions: Vec<Span> = Vec::new();
     let mut effect_writes: Vec<usize> = Vec::new();
     let mut jump_indices: Vec<usize> = Vec::new();
 
     // Walk choice children.
     for child in &choice.content {
         match child {
-            ContentNode::Condition(cond) => {
-                if let ConditionExpr::PropertyComparison(pc) = &cond.expr {
+            ContentNode::Condition(cond) => match &cond.expr {
+                ConditionExpr::PropertyComparison(pc) => {
                     if let Some(idx) =
                         extract_property_read(pc, &site, symbol_table, builder)
                     {
                         condition_reads.push(idx);
                     }
                 }
-            }
+                ConditionExpr::Once(_) => {}
+                _ => other_conditions.push(cond.span.clone()),
+            },
 
             ContentNode::OrConditionBlock(or_block) => {
+                let mut has_other = false;
                 for expr in &or_block.conditions {
-                    if let ConditionExpr::PropertyComparison(pc) = expr {
-                        if let Some(idx) =
-                            extract_property_read(pc, &site, symbol_table, builder)
-                        {
-                            condition_reads.push(idx);
+                    match expr {
+                        ConditionExpr::PropertyComparison(pc) => {
+                            if let Some(idx) =
+                                extract_property_read(pc, &site, symbol_table, builder)
+                            {
+                                condition_reads.push(idx);
+                            }
                         }
+                        ConditionExpr::Once(_) => {}
+                        _ => has_other = true,
                     }
                 }
+                if has_other {
+                    other_conditions.push(or_block.span.clone());
+                }
             }
 
             ContentNode::Effect(effect) => {
@@ -690,10 +1291,13 @@ fn extract_choice(
 
     builder.push_choice(ChoiceFact {
         section: section_id,
+        location: current_location_id.clone(),
         choice_id,
         label: choice.label.clone(),
         sticky: choice.sticky,
+        once: choice.is_once(),
         condition_reads,
+        other_conditions,
         effect_writes,
         jump_indices,
         span: choice.span.clone(),
@@ -734,15 +1338,23 @@ fn extract_jump(
         return None; // Unresolvable
     };
 
+    // A section jump's resolved entity is the argument it passes.
+    let argument = match target {
+        JumpTarget::Section(_) => ann.resolved_entity.clone(),
+        _ => None,
+    };
+
     Some(builder.push_jump(JumpEdge {
         from_section: section_id,
         target,
+        argument,
         span: jump.span.clone(),
     }))
 }
 
-/// Extract guard condition reads from an ExitDeclaration's children.
-fn extract_exit_guards(
+/// Extract guard condition reads and effect writes from an
+/// ExitDeclaration's children.
+fn extract_exit_content(
     exit_decl: &crate::ast::ExitDeclaration,
     builder: &mut FactSetBuilder,
     symbol_table: &SymbolTable,
@@ -754,22 +1366,37 @@ fn extract_exit_guards(
     };
 
     let mut guard_read_indices: Vec<usize> = Vec::new();
+    let mut effect_write_indices: Vec<usize> = Vec::new();
+    let mut has_effects = false;
     let exit_id = make_exit_id(&loc_id, &exit_decl.direction);
+    let site = FactSite::Exit(exit_id);
 
     for child in &exit_decl.children {
-        if let ContentNode::Condition(cond) = child {
-            if let ConditionExpr::PropertyComparison(pc) = &cond.expr {
-                let site = FactSite::Exit(exit_id.clone());
-                if let Some(idx) = extract_property_read(pc, &site, symbol_table, builder) {
-                    guard_read_indices.push(idx);
+        match child {
+            ContentNode::Condition(cond) => {
+                if let ConditionExpr::PropertyComparison(pc) = &cond.expr {
+                    if let Some(idx) = extract_property_read(pc, &site, symbol_table, builder) {
+                        guard_read_indices.push(idx);
+                    }
                 }
             }
+            ContentNode::Effect(effect) => {
+                has_effects = true;
+                if let Some(idx) = extract_property_write(effect, &site, symbol_table, builder) {
+                    effect_write_indices.push(idx);
+                }
+            }
+            _ => {}
         }
     }
 
-    if !guard_read_indices.is_empty() {
-        builder.set_exit_guard_reads(&loc_id, &exit_decl.direction, guard_read_indices);
-    }
+    builder.set_exit_content(
+        &loc_id,
+        &exit_decl.direction,
+        guard_read_indices,
+        effect_write_indices,
+        has_effects,
+    );
 }
 
 /// Extract a RuleFact from a RuleBlock node.
@@ -780,25 +1407,16 @@ fn extract_rule(
 ) {
     let rule_id = rule_block.name.clone();
     let site = FactSite::Rule(rule_id.clone());
+    let candidates = select_candidates(&rule_id, symbol_table);
     let mut condition_reads: Vec<usize> = Vec::new();
     let mut effect_writes: Vec<usize> = Vec::new();
 
-    // Walk rule where_clauses for PropertyReads.
-    for expr in &rule_block.where_clauses {
+    // Walk rule where_clauses, then the select clause's, for PropertyReads.
+    let select_where = rule_block.select.iter().flat_map(|select| &select.where_clauses);
+    for expr in rule_block.where_clauses.iter().chain(select_where) {
         if let ConditionExpr::PropertyComparison(pc) = expr {
             if let Some(idx) = extract_property_read(pc, &site, symbol_table, builder) {
-                condition_reads.push(idx);
-            }
-        }
-    }
-
-    // Walk select clause where_clauses if present.
-    if let Some(ref select) = rule_block.select {
-        for expr in &select.where_clauses {
-            if let ConditionExpr::PropertyComparison(pc) = expr {
-                if let Some(idx) = extract_property_read(pc, &site, symbol_table, builder) {
-                    condition_reads.push(idx);
-                }
+                condition_reads.extend(bind_candidates(&mut builder.reads, idx, &candidates, |r| &mut r.entity));
             }
         }
     }
@@ -806,7 +1424,7 @@ fn extract_rule(
     // Walk rule effects for PropertyWrites.
     for effect in &rule_block.effects {
         if let Some(idx) = extract_property_write(effect, &site, symbol_table, builder) {
-            effect_writes.push(idx);
+            effect_writes.extend(bind_candidates(&mut builder.writes, idx, &candidates, |w| &mut w.entity));
         }
     }
 
@@ -818,6 +1436,71 @@ fn extract_rule(
     });
 }
 
+/// Extract a HookFact from an `on enter:` / `on exit:` block.
+fn extract_location_hook(
+    hook: &crate::ast::LocationHook,
+    builder: &mut FactSetBuilder,
+    symbol_table: &SymbolTable,
+    current_location_id: &mut Option<String>,
+) {
+    let loc_id = match current_location_id {
+        Some(ref id) => id.clone(),
+        None => return,
+    };
+
+    // Only the block registered in LINK is a fact; a rejected duplicate is not.
+    let registered = symbol_table.locations.get(&loc_id).and_then(|ls| match hook.kind {
+        HookKind::Enter => ls.on_enter.as_ref(),
+        HookKind::Exit => ls.on_exit.as_ref(),
+    });
+    match registered {
+        Some(hs) if hs.declared_in == hook.span => {}
+        _ => return,
+    }
+
+    let site = FactSite::LocationHook(make_hook_id(&loc_id, hook.kind));
+    let mut condition_reads: Vec<usize> = Vec::new();
+    let mut effect_writes: Vec<usize> = Vec::new();
+
+    for child in &hook.children {
+        match child {
+            ContentNode::Condition(cond) => {
+                if let ConditionExpr::PropertyComparison(pc) = &cond.expr {
+                    if let Some(idx) = extract_property_read(pc, &site, symbol_table, builder) {
+                        condition_reads.push(idx);
+                    }
+                }
+            }
+
+            ContentNode::OrConditionBlock(or_block) => {
+                for expr in &or_block.conditions {
+                    if let ConditionExpr::PropertyComparison(pc) = expr {
+                        if let Some(idx) = extract_property_read(pc, &site, symbol_table, builder) {
+                            condition_reads.push(idx);
+                        }
+                    }
+                }
+            }
+
+            ContentNode::Effect(effect) => {
+                if let Some(idx) = extract_property_write(effect, &site, symbol_table, builder) {
+                    effect_writes.push(idx);
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    builder.push_hook(HookFact {
+        location: loc_id,
+        kind: hook.kind,
+        condition_reads,
+        effect_writes,
+        span: hook.span.clone(),
+    });
+}
+
 /// Extract a PropertyRead from a PropertyComparison with a known FactSite.
 /// Returns the index into builder.reads if successful.
 fn extract_property_read(
@@ -830,7 +1513,8 @@ fn extract_property_read(
     let resolved_type = ann.resolved_type.as_ref()?;
     let resolved_property = ann.resolved_property.as_ref()?;
 
-    let compare_op = CompareOp::from_token(&pc.operator)?;
+    let (operator, value) = pc.lowered();
+    let compare_op = CompareOp::from_token(operator)?;
 
     let prop_type = lookup_property_type(resolved_type, resolved_property, symbol_table);
     let value_kind = prop_type
@@ -839,10 +1523,11 @@ fn extract_property_read(
 
     let idx = builder.push_read(PropertyRead {
         site: site.clone(),
+        entity: ann.resolved_entity.clone(),
         entity_type: resolved_type.clone(),
         property: resolved_property.clone(),
         operator: compare_op,
-        value_literal: pc.value.clone(),
+        value_literal: value.to_string(),
         value_kind,
         span: pc.span.clone(),
     });
@@ -863,6 +1548,7 @@ fn extract_property_write(
         target_prop: _,
         operator,
         value_expr,
+        ..
     } = &effect.effect_type
     {
         let ann = effect.annotation.as_ref()?;
@@ -878,6 +1564,7 @@ fn extract_property_write(
 
         let idx = builder.push_write(PropertyWrite {
             site: site.clone(),
+            entity: ann.resolved_entity.clone(),
             entity_type: resolved_type.clone(),
             property: resolved_property.clone(),
             operator: write_op,
@@ -1047,6 +1734,7 @@ fn site_to_json(site: &FactSite) -> serde_json::Value {
         FactSite::Choice(id) => serde_json::json!({ "kind": "choice", "id": id }),
         FactSite::Exit(id) => serde_json::json!({ "kind": "exit", "id": id }),
         FactSite::Rule(id) => serde_json::json!({ "kind": "rule", "id": id }),
+        FactSite::LocationHook(id) => serde_json::json!({ "kind": "location_hook", "id": id }),
     }
 }
 
@@ -1092,6 +1780,7 @@ impl FactSet {
         serde_json::json!({
             "reads": self.reads.iter().map(|r| serde_json::json!({
                 "site": site_to_json(&r.site),
+                "entity": r.entity,
                 "entity_type": r.entity_type,
                 "property": r.property,
                 "operator": compare_op_str(&r.operator),
@@ -1101,6 +1790,7 @@ impl FactSet {
             })).collect::<Vec<_>>(),
             "writes": self.writes.iter().map(|w| serde_json::json!({
                 "site": site_to_json(&w.site),
+                "entity": w.entity,
                 "entity_type": w.entity_type,
                 "property": w.property,
                 "operator": write_op_str(&w.operator),
@@ -1114,19 +1804,26 @@ impl FactSet {
                 "exit_name": e.exit_name,
                 "is_conditional": e.is_conditional,
                 "guard_reads": e.guard_reads,
+                "effect_writes": e.effect_writes,
+                "has_effects": e.has_effects,
+                "has_blocked_message": e.has_blocked_message,
                 "span": span_to_json(&e.span),
             })).collect::<Vec<_>>(),
             "jumps": self.jumps.iter().map(|j| serde_json::json!({
                 "from_section": j.from_section,
                 "target": jump_target_to_json(&j.target),
+                "argument": j.argument,
                 "span": span_to_json(&j.span),
             })).collect::<Vec<_>>(),
             "choices": self.choices.iter().map(|c| serde_json::json!({
                 "section": c.section,
+                "location": c.location,
                 "choice_id": c.choice_id,
                 "label": c.label,
                 "sticky": c.sticky,
+                "once": c.once,
                 "condition_reads": c.condition_reads,
+                "other_conditions": c.other_conditions.iter().map(span_to_json).collect::<Vec<_>>(),
                 "effect_writes": c.effect_writes,
                 "jump_indices": c.jump_indices,
                 "span": span_to_json(&c.span),
@@ -1137,6 +1834,64 @@ impl FactSet {
                 "effect_writes": r.effect_writes,
                 "span": span_to_json(&r.span),
             })).collect::<Vec<_>>(),
+            "hooks": self.hooks.iter().map(|h| serde_json::json!({
+                "hook_id": h.hook_id(),
+                "location": h.location,
+                "kind": h.kind.json_key(),
+                "condition_reads": h.condition_reads,
+                "effect_writes": h.effect_writes,
+                "span": span_to_json(&h.span),
+            })).collect::<Vec<_>>(),
+            "section_templates": self.section_templates.iter().map(|t| serde_json::json!({
+                "section": t.section,
+                "parameter": t.parameter,
+                "parameter_type": t.parameter_type,
+                "span": span_to_json(&t.span),
+            })).collect::<Vec<_>>(),
+            "phases": self.phases.iter().map(|p| serde_json::json!({
+                "sequence": p.sequence,
+                "phase": p.phase,
+                "advance": p.advance,
+                "advance_reads": p.advance_reads.iter().map(|k| serde_json::json!({
+                    "entity_type": k.entity_type,
+                    "property": k.property,
+                })).collect::<Vec<_>>(),
+                "span": span_to_json(&p.span),
+            })).collect::<Vec<_>>(),
+            "entities": self.entities.iter().map(|e| serde_json::json!({
+                "entity": e.entity,
+                "entity_type": e.entity_type,
+                "traits": e.traits,
+                "placements": e.placements.iter().map(|p| match p {
+                    Placement::Location(id) => serde_json::json!({ "kind": "location", "id": id }),
+                    Placement::StartInventory => serde_json::json!({ "kind": "start_inventory" }),
+                }).collect::<Vec<_>>(),
+                "starting_values": e.starting_values.iter().map(|(k, v)| (k.clone(), serde_json::json!(v))).collect::<serde_json::Map<_, _>>(),
+                "span": span_to_json(&e.span),
+            })).collect::<Vec<_>>(),
+            "locations": self.locations.iter().map(|l| serde_json::json!({
+                "location": l.location,
+                "has_description": l.has_description,
+                "sections": l.sections,
+                "span": span_to_json(&l.span),
+            })).collect::<Vec<_>>(),
+            "moves": self.moves.iter().map(|m| serde_json::json!({
+                "entity": m.entity,
+                "span": span_to_json(&m.span),
+            })).collect::<Vec<_>>(),
+            "endings": self.endings.iter().map(|e| serde_json::json!({
+                "ending": e.ending,
+                "span": span_to_json(&e.span),
+            })).collect::<Vec<_>>(),
+            "ends": self.ends.iter().map(|e| serde_json::json!({
+                "ending": e.ending,
+                "location": e.location,
+                "span": span_to_json(&e.span),
+            })).collect::<Vec<_>>(),
+            "unplaced_traits": self.unplaced_traits,
+            "disabled_lints": self.disabled_lints,
+            "world_start": self.world_start,
+            "world_mode": self.world_mode.name(),
         })
     }
 }
@@ -0,0 +1,226 @@
+/// Machine-applicable fixes: turn diagnostic suggestions into text edits.
+///
+/// Some diagnostics carry a [`Fix`]: a single token the compiler is sure
+/// should be replaced, and its replacement. URD301 sets one when exactly
+/// one entity or location is closest to the unresolved name; URD206 sets
+/// one for an import whose filename casing differs from the file on disk.
+///
+/// `urd fix` collects the fixes with [`fixes_by_file()`], turns each
+/// file's fixes into byte-range edits with [`plan_edits()`], and applies
+/// them with [`apply_edits()`] or prints [`unified_diff()`] for
+/// `--dry-run`. Like `watch`, this module does no file I/O: the CLI reads
+/// and writes the files.
+///
+/// A fix's span covers a whole node, so its token is found inside the
+/// span as a whole word. Edits are applied from the bottom of the file
+/// upward, so earlier offsets stay valid. Overlapping edits in one file
+/// are a [`FixConflict`], and that file is left alone.
+
+use std::collections::BTreeMap;
+
+use crate::diagnostics::{DiagnosticCollector, Fix};
+
+/// Replace `source[start..end]` with `replacement`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
+    /// 1-indexed line of `start`, for messages.
+    pub line: u32,
+}
+
+/// The edits for one file, and the fixes whose token was not found.
+#[derive(Debug, Default)]
+pub struct FilePlan {
+    /// Sorted by `start`, no two overlapping.
+    pub edits: Vec<TextEdit>,
+    pub unmatched: Vec<Fix>,
+}
+
+/// Two edits in one file touch the same text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixConflict {
+    pub line: u32,
+    pub first: String,
+    pub second: String,
+}
+
+impl std::fmt::Display for FixConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Overlapping fixes at line {}: '{}' and '{}'",
+            self.line, self.first, self.second
+        )
+    }
+}
+
+/// Every fix in `diagnostics`, grouped by the file its span is in.
+/// Duplicates, such as one typo reported twice from the same line, are
+/// dropped.
+pub fn fixes_by_file(diagnostics: &DiagnosticCollector) -> BTreeMap<String, Vec<Fix>> {
+    let mut by_file: BTreeMap<String, Vec<Fix>> = BTreeMap::new();
+    for fix in diagnostics.sorted().into_iter().filter_map(|d| d.fix.as_ref()) {
+        let fixes = by_file.entry(fix.span.file.clone()).or_default();
+        if !fixes.contains(fix) {
+            fixes.push(fix.clone());
+        }
+    }
+    by_file
+}
+
+/// Turn the fixes for one file into edits against `source`.
+///
+/// Each fix replaces every whole-word occurrence of its token within its
+/// span. A fix whose token does not occur there (the source changed since
+/// the compile, or the span is synthetic) goes to `unmatched`.
+pub fn plan_edits(source: &str, fixes: &[Fix]) -> Result<FilePlan, FixConflict> {
+    let line_starts = line_starts(source);
+    let mut plan = FilePlan::default();
+
+    for fix in fixes {
+        let found = span_range(source, &line_starts, fix)
+            .map(|(start, end)| token_occurrences(&source[start..end], &fix.original, start))
+            .unwrap_or_default();
+        if found.is_empty() {
+            plan.unmatched.push(fix.clone());
+        }
+        for start in found {
+            plan.edits.push(TextEdit {
+                start,
+                end: start + fix.original.len(),
+                replacement: fix.replacement.clone(),
+                line: line_of(&line_starts, start),
+            });
+        }
+    }
+
+    plan.edits.sort_by_key(|edit| (edit.start, edit.end));
+    plan.edits.dedup();
+    for pair in plan.edits.windows(2) {
+        if pair[1].start < pair[0].end {
+            return Err(FixConflict {
+                line: pair[1].line,
+                first: source[pair[0].start..pair[0].end].to_string(),
+                second: source[pair[1].start..pair[1].end].to_string(),
+            });
+        }
+    }
+    Ok(plan)
+}
+
+/// Apply non-overlapping edits to `source`, last first.
+pub fn apply_edits(source: &str, edits: &[TextEdit]) -> String {
+    let mut ordered: Vec<&TextEdit> = edits.iter().collect();
+    ordered.sort_by_key(|edit| std::cmp::Reverse(edit.start));
+    let mut out = source.to_string();
+    for edit in ordered {
+        out.replace_range(edit.start..edit.end, &edit.replacement);
+    }
+    out
+}
+
+/// A unified diff of `before` and `after`, with three lines of context.
+///
+/// Fixes replace tokens within a line and never add or remove lines, so
+/// the two texts are compared line by line. Empty when nothing differs.
+pub fn unified_diff(path: &str, before: &str, after: &str) -> String {
+    const CONTEXT: usize = 3;
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+    let len = old.len().max(new.len());
+    let changed: Vec<usize> = (0..len).filter(|&i| old.get(i) != new.get(i)).collect();
+    if changed.is_empty() {
+        return String::new();
+    }
+
+    // Group changed lines whose context would touch into one hunk.
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &i in &changed {
+        let start = i.saturating_sub(CONTEXT);
+        let end = (i + CONTEXT + 1).min(len);
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut out = format!("--- a/{}\n+++ b/{}\n", path, path);
+    for (start, end) in hunks {
+        let count = |lines: &[&str]| end.min(lines.len()).saturating_sub(start);
+        out.push_str(&format!(
+            "@@ -{},{} +{},{} @@\n",
+            start + 1,
+            count(&old),
+            start + 1,
+            count(&new)
+        ));
+        for i in start..end {
+            match (old.get(i), new.get(i)) {
+                (Some(a), Some(b)) if a == b => out.push_str(&format!(" {}\n", a)),
+                (a, b) => {
+                    if let Some(a) = a {
+                        out.push_str(&format!("-{}\n", a));
+                    }
+                    if let Some(b) = b {
+                        out.push_str(&format!("+{}\n", b));
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Byte offset of the start of each line.
+fn line_starts(source: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+fn line_of(line_starts: &[usize], offset: usize) -> u32 {
+    line_starts.partition_point(|&start| start <= offset) as u32
+}
+
+/// The byte range a fix's span covers, clamped to its lines. `None` for a
+/// span outside the source.
+fn span_range(source: &str, line_starts: &[usize], fix: &Fix) -> Option<(usize, usize)> {
+    let span = &fix.span;
+    let line_end = |line: usize| {
+        let end = line_starts.get(line + 1).map_or(source.len(), |next| next - 1);
+        // A CRLF line's text stops before the `\r`.
+        if source[..end].ends_with('\r') { end - 1 } else { end }
+    };
+    let start_line = (span.start_line as usize).checked_sub(1)?;
+    let end_line = (span.end_line as usize).checked_sub(1)?;
+    let start = *line_starts.get(start_line)?;
+    let end = *line_starts.get(end_line)?;
+    let start = (start + (span.start_col as usize).saturating_sub(1)).min(line_end(start_line));
+    let end = (end + (span.end_col as usize).saturating_sub(1)).min(line_end(end_line));
+    // Columns are bytes, but a bad one must not split a character.
+    let start = (0..=start).rev().find(|&i| source.is_char_boundary(i))?;
+    let end = (end..=source.len()).find(|&i| source.is_char_boundary(i))?;
+    (start < end).then_some((start, end))
+}
+
+/// Offsets (shifted by `base`) of each whole-word occurrence of `token`
+/// in `text`.
+fn token_occurrences(text: &str, token: &str, base: usize) -> Vec<usize> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_' || c == '-';
+    if token.is_empty() {
+        return Vec::new();
+    }
+    let first_is_word = token.starts_with(is_word);
+    let last_is_word = token.ends_with(is_word);
+    text.match_indices(token)
+        .filter(|&(i, _)| {
+            let before = text[..i].chars().next_back();
+            let after = text[i + token.len()..].chars().next();
+            (!first_is_word || !before.is_some_and(is_word))
+                && (!last_is_word || !after.is_some_and(is_word))
+        })
+        .map(|(i, _)| base + i)
+        .collect()
+}
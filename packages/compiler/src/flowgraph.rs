@@ -0,0 +1,315 @@
+/// Dialogue flow graphs: sections as nodes, the jumps between them as
+/// edges, rendered as Graphviz dot or Mermaid.
+///
+/// `build()` reads the FactSet's jumps and choices and the symbol table's
+/// sections and phases:
+///
+/// - **Choice edges** run from a section to where one of its choices
+///   jumps, labelled with the choice label. `*` marks a one-shot choice
+///   and `+` a sticky one; sticky edges are drawn bold (`==>` in Mermaid).
+/// - **Exhausted edges** are a section's own jump after its last choice,
+///   its `on_exhausted` goto. They are dashed.
+/// - **Phase edges** run from a sequence phase to the section it opens
+///   (`section: name`). They are dotted (dashed in Mermaid).
+///
+/// Jumps to `end` share one `end` node; exit jumps get a node per exit.
+/// Nodes are sections in symbol table order, then phases, then exit and
+/// end nodes as first reached. Edges follow the source order of the
+/// jumps, then phases. The same world always renders the same text, so
+/// diffs of generated graphs show only real changes.
+///
+/// Node identifiers are sanitised for dot and Mermaid (letters, digits,
+/// and `_`, with a kind prefix); labels keep the compiled IDs and choice
+/// labels as written. Like `stats`, this module does no file I/O.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::facts::{FactSet, JumpTarget};
+use crate::symbol_table::SymbolTable;
+use crate::CompilationResult;
+
+/// What a node stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    Section,
+    Phase,
+    Exit,
+    End,
+}
+
+/// How an edge is taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EdgeKind {
+    /// A `*` choice.
+    OneShot,
+    /// A `+` choice.
+    Sticky,
+    /// The section's `on_exhausted` goto.
+    Exhausted,
+    /// A phase opening its section.
+    Phase,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowNode {
+    /// Sanitised identifier, unique within the graph.
+    pub id: String,
+    /// Human-readable label: the compiled ID, `sequence / phase`,
+    /// `exit location/direction`, or `end`.
+    pub label: String,
+    pub kind: NodeKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FlowEdge {
+    /// Index of the source node in `FlowGraph::nodes`.
+    pub from: usize,
+    /// Index of the target node in `FlowGraph::nodes`.
+    pub to: usize,
+    pub kind: EdgeKind,
+    /// The choice label for a choice edge; empty otherwise.
+    pub label: String,
+}
+
+/// The dialogue flow of a world.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FlowGraph {
+    pub nodes: Vec<FlowNode>,
+    pub edges: Vec<FlowEdge>,
+}
+
+// ── Construction ──
+
+/// Build the flow graph of a world from its FactSet and symbol table.
+pub fn build(fact_set: &FactSet, symbol_table: &SymbolTable) -> FlowGraph {
+    let mut builder = Builder::default();
+    for id in symbol_table.sections.keys() {
+        builder.node(NodeKind::Section, id, id.clone());
+    }
+    for sequence in symbol_table.sequences.values() {
+        for phase in sequence.phases.iter().filter(|p| p.section.is_some()) {
+            let key = format!("{}/{}", sequence.id, phase.id);
+            builder.node(NodeKind::Phase, &key, format!("{} / {}", sequence.id, phase.id));
+        }
+    }
+
+    let owners: HashMap<usize, usize> = fact_set
+        .choices()
+        .iter()
+        .enumerate()
+        .flat_map(|(c, choice)| choice.jump_indices.iter().map(move |&j| (j, c)))
+        .collect();
+
+    for (j, jump) in fact_set.jumps().iter().enumerate() {
+        let Some(from) = builder.find(NodeKind::Section, &jump.from_section) else {
+            continue;
+        };
+        let to = match &jump.target {
+            JumpTarget::Section(id) => match builder.find(NodeKind::Section, id) {
+                Some(to) => to,
+                None => continue,
+            },
+            JumpTarget::Exit(exit_id) => builder.node(NodeKind::Exit, exit_id, format!("exit {}", exit_id)),
+            JumpTarget::End => builder.node(NodeKind::End, "end", "end".to_string()),
+        };
+        let (kind, label) = match owners.get(&j).map(|&c| &fact_set.choices()[c]) {
+            Some(choice) if choice.sticky => (EdgeKind::Sticky, choice.label.clone()),
+            Some(choice) => (EdgeKind::OneShot, choice.label.clone()),
+            None => (EdgeKind::Exhausted, String::new()),
+        };
+        builder.graph.edges.push(FlowEdge { from, to, kind, label });
+    }
+
+    for sequence in symbol_table.sequences.values() {
+        for phase in &sequence.phases {
+            let Some(section) = &phase.section else {
+                continue;
+            };
+            let from = builder.find(NodeKind::Phase, &format!("{}/{}", sequence.id, phase.id));
+            if let (Some(from), Some(to)) = (from, builder.find(NodeKind::Section, section)) {
+                builder.graph.edges.push(FlowEdge { from, to, kind: EdgeKind::Phase, label: String::new() });
+            }
+        }
+    }
+
+    builder.graph
+}
+
+/// Build the flow graph from a compilation result.
+///
+/// Returns `None` when LINK did not run (PARSE or IMPORT failure), since
+/// there is no FactSet to read jumps from.
+pub fn from_compilation(result: &CompilationResult) -> Option<FlowGraph> {
+    Some(build(result.fact_set.as_ref()?, result.symbol_table.as_ref()?))
+}
+
+/// Assigns node identifiers as nodes are added.
+#[derive(Default)]
+struct Builder {
+    graph: FlowGraph,
+    /// Node index by kind and unsanitised key.
+    index: HashMap<(NodeKind, String), usize>,
+    used: HashSet<String>,
+}
+
+impl Builder {
+    /// The node of `kind` keyed `key`, added with `label` if new.
+    fn node(&mut self, kind: NodeKind, key: &str, label: String) -> usize {
+        if let Some(&i) = self.index.get(&(kind, key.to_string())) {
+            return i;
+        }
+        let prefix = match kind {
+            NodeKind::Section => "section",
+            NodeKind::Phase => "phase",
+            NodeKind::Exit => "exit",
+            NodeKind::End => "end",
+        };
+        let base = format!("{}_{}", prefix, sanitise(key));
+        let mut id = base.clone();
+        let mut n = 2;
+        while !self.used.insert(id.clone()) {
+            id = format!("{}_{}", base, n);
+            n += 1;
+        }
+        self.graph.nodes.push(FlowNode { id, label, kind });
+        self.index.insert((kind, key.to_string()), self.graph.nodes.len() - 1);
+        self.graph.nodes.len() - 1
+    }
+
+    fn find(&self, kind: NodeKind, key: &str) -> Option<usize> {
+        self.index.get(&(kind, key.to_string())).copied()
+    }
+}
+
+/// `key` with every character outside ASCII letters, digits, and `_`
+/// replaced by `_`.
+fn sanitise(key: &str) -> String {
+    key.chars().map(|c| if c.is_ascii_alphanumeric() || c == '_' { c } else { '_' }).collect()
+}
+
+// ── Filtering ──
+
+impl FlowGraph {
+    /// The connected component holding section `section` (a compiled ID),
+    /// with edges followed in both directions. `None` if the world has no
+    /// such section. Nodes and edges keep their order.
+    pub fn component(&self, section: &str) -> Option<FlowGraph> {
+        let start = self.nodes.iter().position(|n| n.kind == NodeKind::Section && n.label == section)?;
+
+        let mut neighbours: Vec<Vec<usize>> = vec![Vec::new(); self.nodes.len()];
+        for edge in &self.edges {
+            neighbours[edge.from].push(edge.to);
+            neighbours[edge.to].push(edge.from);
+        }
+        let mut seen = vec![false; self.nodes.len()];
+        seen[start] = true;
+        let mut queue = VecDeque::from([start]);
+        while let Some(i) = queue.pop_front() {
+            for &next in &neighbours[i] {
+                if !seen[next] {
+                    seen[next] = true;
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let mut renumbered: HashMap<usize, usize> = HashMap::new();
+        let mut graph = FlowGraph::default();
+        for (i, node) in self.nodes.iter().enumerate().filter(|(i, _)| seen[*i]) {
+            renumbered.insert(i, graph.nodes.len());
+            graph.nodes.push(node.clone());
+        }
+        for edge in self.edges.iter().filter(|e| seen[e.from]) {
+            graph.edges.push(FlowEdge { from: renumbered[&edge.from], to: renumbered[&edge.to], ..edge.clone() });
+        }
+        Some(graph)
+    }
+}
+
+// ── Output ──
+
+impl FlowEdge {
+    /// The edge label as rendered: the choice label marked `*` or `+`,
+    /// `exhausted`, or `phase`.
+    fn text(&self) -> String {
+        match self.kind {
+            EdgeKind::OneShot => format!("* {}", self.label),
+            EdgeKind::Sticky => format!("+ {}", self.label),
+            EdgeKind::Exhausted => "exhausted".to_string(),
+            EdgeKind::Phase => "phase".to_string(),
+        }
+    }
+}
+
+impl FlowGraph {
+    /// Graphviz dot source.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph flow {\n  rankdir=LR;\n  node [shape=box];\n");
+        for node in &self.nodes {
+            let shape = match node.kind {
+                NodeKind::Section => "",
+                NodeKind::Phase => ", shape=ellipse",
+                NodeKind::Exit => ", shape=house",
+                NodeKind::End => ", shape=doublecircle",
+            };
+            out.push_str(&format!("  {} [label=\"{}\"{}];\n", node.id, dot_escape(&node.label), shape));
+        }
+        for edge in &self.edges {
+            let style = match edge.kind {
+                EdgeKind::OneShot => "",
+                EdgeKind::Sticky => ", style=bold",
+                EdgeKind::Exhausted => ", style=dashed",
+                EdgeKind::Phase => ", style=dotted",
+            };
+            out.push_str(&format!(
+                "  {} -> {} [label=\"{}\"{}];\n",
+                self.nodes[edge.from].id,
+                self.nodes[edge.to].id,
+                dot_escape(&edge.text()),
+                style,
+            ));
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// Mermaid flowchart source.
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart LR\n");
+        for node in &self.nodes {
+            let label = mermaid_escape(&node.label);
+            let shape = match node.kind {
+                NodeKind::Section => format!("[\"{}\"]", label),
+                NodeKind::Phase => format!("([\"{}\"])", label),
+                NodeKind::Exit => format!(">\"{}\"]", label),
+                NodeKind::End => format!("((\"{}\"))", label),
+            };
+            out.push_str(&format!("  {}{}\n", node.id, shape));
+        }
+        for edge in &self.edges {
+            let arrow = match edge.kind {
+                EdgeKind::OneShot => "-->",
+                EdgeKind::Sticky => "==>",
+                EdgeKind::Exhausted | EdgeKind::Phase => "-.->",
+            };
+            out.push_str(&format!(
+                "  {} {}|\"{}\"| {}\n",
+                self.nodes[edge.from].id,
+                arrow,
+                mermaid_escape(&edge.text()),
+                self.nodes[edge.to].id,
+            ));
+        }
+        out
+    }
+}
+
+fn dot_escape(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Mermaid has no backslash escapes inside quoted labels; `"` is written
+/// as the entity `#quot;`.
+fn mermaid_escape(text: &str) -> String {
+    text.replace('"', "#quot;")
+}
@@ -0,0 +1,208 @@
+/// Fragment compiles: one section or location without a whole world.
+///
+/// A snippet is compiled inside a synthetic world so it can be tested on
+/// its own. The wrapper adds a world block to the snippet's frontmatter
+/// (or a frontmatter holding only the world block, when the snippet has
+/// none) and, for a section, a `# Fragment` location to hold it. The
+/// snippet's frontmatter may declare types and entities but not a world
+/// block of its own.
+///
+/// The result is the emitted JSON of the snippet's first section or
+/// location, and the diagnostics with their lines counted from the
+/// snippet, not the wrapper. A message that would name the wrapper's file
+/// says "the snippet" instead. Unresolved `@entity` references are
+/// warnings (see [`CompileOptions::lenient_entity_refs`]), so a snippet
+/// can use characters it does not declare; those references emit with
+/// the names as written, without the `@`.
+///
+/// Compiled IDs carry the file stem, which is always `fragment`: the
+/// snippet is compiled as [`FILE_NAME`]. `player` is a keyword and needs
+/// no declaration.
+
+use serde_json::Value as Json;
+
+use crate::diagnostics::DiagnosticCollector;
+use crate::span::Span;
+use crate::CompileOptions;
+
+/// The file name a fragment compiles under.
+pub const FILE_NAME: &str = "fragment.urd.md";
+
+/// What a snippet holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FragmentKind {
+    /// `== section` dialogue. The result is its `dialogue` entry.
+    Section,
+    /// A `# Location` heading and its content. The result is its
+    /// `locations` entry.
+    Location,
+}
+
+impl FragmentKind {
+    /// Parse a kind name as given on the command line.
+    pub fn from_name(name: &str) -> Option<FragmentKind> {
+        match name {
+            "section" => Some(FragmentKind::Section),
+            "location" => Some(FragmentKind::Location),
+            _ => None,
+        }
+    }
+}
+
+/// The result of a fragment compile.
+#[derive(Debug)]
+pub struct FragmentResult {
+    /// `true` if the snippet compiled with zero errors.
+    pub success: bool,
+    /// The compiled ID of the snippet's first section or location.
+    pub id: Option<String>,
+    /// Its emitted JSON. `None` when compilation failed or the snippet
+    /// declares no section or location of its kind.
+    pub json: Option<Json>,
+    /// Diagnostics, with spans on the snippet's own lines. A diagnostic
+    /// on a synthetic line is moved to the snippet line before it.
+    pub diagnostics: DiagnosticCollector,
+}
+
+/// Compile a snippet of `kind` inside a synthetic world.
+pub fn compile_fragment(kind: FragmentKind, source: &str) -> FragmentResult {
+    let source = source.strip_prefix('\u{feff}').unwrap_or(source);
+    let wrapped = Wrapped::new(kind, source);
+    let options = CompileOptions { lenient_entity_refs: true, ..CompileOptions::default() };
+    let result = crate::compile_source_with_options(
+        FILE_NAME,
+        &wrapped.source,
+        &crate::import::StubFileReader,
+        &options,
+    );
+
+    let mut diagnostics = DiagnosticCollector::new();
+    for d in result.diagnostics.all() {
+        let mut d = d.clone();
+        d.message = snippet_message(&d.message);
+        wrapped.remap(&mut d.span);
+        for related in &mut d.related {
+            wrapped.remap(&mut related.span);
+        }
+        if let Some(fix) = &mut d.fix {
+            wrapped.remap(&mut fix.span);
+        }
+        diagnostics.emit(d);
+    }
+
+    let id = result.symbol_table.as_ref().and_then(|st| match kind {
+        FragmentKind::Section => st.sections.values().next().map(|s| s.compiled_id.clone()),
+        FragmentKind::Location => st.locations.keys().next().cloned(),
+    });
+    let block = match kind {
+        FragmentKind::Section => "dialogue",
+        FragmentKind::Location => "locations",
+    };
+    let json = result
+        .world
+        .as_deref()
+        .and_then(|world| serde_json::from_str::<Json>(world).ok())
+        .zip(id.as_deref())
+        .and_then(|(mut world, id)| world.get_mut(block)?.get_mut(id).map(Json::take));
+
+    FragmentResult { success: result.success, id, json, diagnostics }
+}
+
+/// `message` with the wrapper's file name replaced by "the snippet", as
+/// the author never wrote a file by that name.
+fn snippet_message(message: &str) -> String {
+    if !message.contains(FILE_NAME) {
+        return message.to_string();
+    }
+    message
+        .replace(&format!(" in file '{}'", FILE_NAME), " in the snippet")
+        .replace(&format!(" (file '{}')", FILE_NAME), " in the snippet")
+        .replace(&format!("'{}'", FILE_NAME), "the snippet")
+        .replace(FILE_NAME, "the snippet")
+}
+
+/// A snippet inside its synthetic world.
+struct Wrapped {
+    source: String,
+    /// The snippet line of each wrapped line, 1-based; 0 for a synthetic
+    /// line.
+    lines: Vec<u32>,
+}
+
+impl Wrapped {
+    fn new(kind: FragmentKind, snippet: &str) -> Self {
+        let mut wrapped = Wrapped { source: String::new(), lines: Vec::new() };
+        let snippet_lines: Vec<&str> = snippet.lines().collect();
+
+        // The closing `---` of the snippet's own frontmatter, if any.
+        let close = match snippet_lines.first() {
+            Some(first) if first.trim_end() == "---" => {
+                snippet_lines.iter().skip(1).position(|l| l.trim_end() == "---").map(|i| i + 1)
+            }
+            _ => None,
+        };
+
+        let mut next = 0;
+        match close {
+            Some(close) => {
+                wrapped.push(snippet_lines[0], 1);
+                wrapped.push_world_block();
+                for (i, line) in snippet_lines.iter().enumerate().take(close + 1).skip(1) {
+                    wrapped.push(line, i as u32 + 1);
+                }
+                next = close + 1;
+            }
+            None => {
+                wrapped.push("---", 0);
+                wrapped.push_world_block();
+                wrapped.push("---", 0);
+            }
+        }
+        if kind == FragmentKind::Section {
+            wrapped.push("# Fragment", 0);
+            wrapped.push("", 0);
+        }
+        for (i, line) in snippet_lines.iter().enumerate().skip(next) {
+            wrapped.push(line, i as u32 + 1);
+        }
+        wrapped
+    }
+
+    fn push_world_block(&mut self) {
+        self.push("world:", 0);
+        self.push("  name: fragment", 0);
+    }
+
+    fn push(&mut self, line: &str, snippet_line: u32) {
+        self.source.push_str(line);
+        self.source.push('\n');
+        self.lines.push(snippet_line);
+    }
+
+    /// Move `span` from wrapped lines to snippet lines. Synthetic spans
+    /// (line 0) and spans in other files are left alone.
+    fn remap(&self, span: &mut Span) {
+        if span.file != FILE_NAME || span.start_line == 0 {
+            return;
+        }
+        let (start_line, moved) = self.snippet_line(span.start_line);
+        let (end_line, _) = self.snippet_line(span.end_line);
+        span.start_line = start_line;
+        span.end_line = end_line.max(start_line);
+        if moved {
+            span.start_col = 1;
+            span.end_col = 1;
+        }
+    }
+
+    /// The snippet line for a wrapped line, and whether the wrapped line
+    /// was synthetic and so had to move.
+    fn snippet_line(&self, wrapped_line: u32) -> (u32, bool) {
+        let index = (wrapped_line as usize).min(self.lines.len());
+        match self.lines[..index].iter().rev().position(|&l| l != 0) {
+            Some(0) => (self.lines[index - 1], false),
+            Some(back) => (self.lines[index - 1 - back], true),
+            None => (1, true),
+        }
+    }
+}
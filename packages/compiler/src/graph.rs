@@ -6,7 +6,8 @@
 /// Properties (enforced by IMPORT):
 /// - Acyclic: cycles are rejected with a diagnostic.
 /// - Depth-limited: max 64 levels of import chaining.
-/// - Non-transitive: A imports B imports C does NOT give A access to C.
+/// - Non-transitive: A imports B imports C does NOT give A access to C,
+///   unless the project opts into `link::ScopePolicy::Transitive`.
 /// - Stable: same source files produce the same graph.
 
 use std::collections::BTreeSet;
@@ -146,5 +147,12 @@ pub const MAX_CHOICE_NESTING_DEPTH: usize = 4;
 /// Choice nesting depth that triggers a warning.
 pub const WARN_CHOICE_NESTING_DEPTH: usize = 3;
 
+/// Maximum conditions in one `? any:` block or one run of `?` lines
+/// (error at 256, warn at 32).
+pub const MAX_CONDITION_COUNT: usize = 256;
+
+/// Condition count that triggers a warning.
+pub const WARN_CONDITION_COUNT: usize = 32;
+
 /// Maximum frontmatter nesting depth.
 pub const MAX_FRONTMATTER_NESTING_DEPTH: usize = 8;
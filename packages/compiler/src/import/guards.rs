@@ -0,0 +1,100 @@
+/// Flag expansion: `?if` guards kept or dropped by the compile's flags.
+///
+/// Each file is expanded as soon as it is parsed and before its imports
+/// are read, so an `import:` inside a dropped guard is never followed. A
+/// kept guard's children take its place and the diagnostics PARSE held
+/// back for them are reported; a dropped guard leaves nothing behind.
+///
+/// The flags are `CompileOptions::defines` plus the entry file's
+/// `world.flags`. The world block is read before expansion, so its
+/// `flags:` only counts outside guards.
+
+use std::collections::BTreeSet;
+
+use crate::ast::{ConditionalBlock, ContentNode, FileAst, FrontmatterEntry, FrontmatterValue, Scalar};
+use crate::diagnostics::DiagnosticCollector;
+
+/// The flags set for a compile: `defines` and the entry file's
+/// `world.flags`.
+pub(crate) fn active_flags(entry: &FileAst, defines: &[String]) -> BTreeSet<String> {
+    let mut flags: BTreeSet<String> = defines.iter().cloned().collect();
+    let entries = entry.frontmatter.iter().flat_map(|fm| &fm.entries);
+    for entry in entries {
+        let FrontmatterValue::WorldBlock(wb) = &entry.value else {
+            continue;
+        };
+        for (key, value) in &wb.fields {
+            if let ("flags", Scalar::List(items)) = (key.as_str(), value) {
+                for item in items {
+                    if let Scalar::String(flag) = item {
+                        flags.insert(flag.clone());
+                    }
+                }
+            }
+        }
+    }
+    flags
+}
+
+/// Replace each guard in `ast` with its children if it is kept under
+/// `flags`, or with nothing if not.
+///
+/// A file with guards has the flags mixed into its source hash, so a LINK
+/// cache never reuses an expansion made under other flags.
+pub(crate) fn expand(ast: &mut FileAst, flags: &BTreeSet<String>, diagnostics: &mut DiagnosticCollector) {
+    let mut found = false;
+
+    let content = std::mem::take(&mut ast.content);
+    ast.content = splice(content, flags, diagnostics, &mut found, |node| match node {
+        ContentNode::ConditionalBlock(block) => Item::Guard(block),
+        node => Item::Other(node),
+    });
+
+    if let Some(fm) = &mut ast.frontmatter {
+        let entries = std::mem::take(&mut fm.entries);
+        fm.entries = splice(entries, flags, diagnostics, &mut found, |entry| match entry.value {
+            FrontmatterValue::Conditional(block) => Item::Guard(block),
+            value => Item::Other(FrontmatterEntry { key: entry.key, value, span: entry.span }),
+        });
+    }
+
+    if found {
+        if let Some(hash) = &ast.source_hash {
+            let keyed = format!("{}\n{}", hash, flags.iter().cloned().collect::<Vec<_>>().join(","));
+            ast.source_hash = Some(crate::hash::sha256_hex(keyed.as_bytes()));
+        }
+    }
+}
+
+/// A content node or frontmatter entry, taken apart if it is a guard.
+enum Item<T> {
+    Guard(ConditionalBlock<T>),
+    Other(T),
+}
+
+/// Splice the kept guards among `items`, recursively. `guard` takes an
+/// item apart if it is a guard and hands it back if not.
+fn splice<T>(
+    items: Vec<T>,
+    flags: &BTreeSet<String>,
+    diagnostics: &mut DiagnosticCollector,
+    found: &mut bool,
+    guard: fn(T) -> Item<T>,
+) -> Vec<T> {
+    let mut kept = Vec::with_capacity(items.len());
+    for item in items {
+        match guard(item) {
+            Item::Guard(block) => {
+                *found = true;
+                if block.is_active(flags) {
+                    for diagnostic in block.diagnostics {
+                        diagnostics.emit(diagnostic);
+                    }
+                    kept.extend(splice(block.children, flags, diagnostics, found, guard));
+                }
+            }
+            Item::Other(item) => kept.push(item),
+        }
+    }
+    kept
+}
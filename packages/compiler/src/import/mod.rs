@@ -8,13 +8,22 @@
 /// Diagnostic code range: URD200–URD299
 ///
 /// IMPORT is the only compiler phase that reads from the filesystem.
-/// All other phases operate on in-memory data structures.
+/// All other phases operate on in-memory data structures. It also expands
+/// `?if` guards (see [`guards`]), so later phases see only kept content.
+/// [`resolve_imports_dry_run()`] runs the same path checks without
+/// compiling, to show which files a compile would read.
 
-use std::collections::{BTreeMap, HashSet};
+mod guards;
+mod plan;
+
+pub use plan::{resolve_imports_dry_run, ImportPlan, ImportStatus, PlanMode, PlannedImport};
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 
 use crate::ast::{FileAst, FrontmatterValue, ImportDecl};
-use crate::diagnostics::DiagnosticCollector;
+use crate::diagnostics::{Diagnostic, DiagnosticCollector, Fix, Severity};
 use crate::graph::{file_stem, CompilationUnit, DependencyGraph, FileNode, MAX_FILE_COUNT, MAX_FILE_SIZE, MAX_IMPORT_DEPTH};
+use crate::observer::Relay;
 use crate::parse;
 use crate::span::Span;
 
@@ -124,6 +133,47 @@ impl FileReader for StubFileReader {
     }
 }
 
+// ── Missing imports ─────────────────────────────────────────────────
+
+/// How IMPORT treats an `import:` whose file cannot be found.
+///
+/// Only a missing file is affected. Other read failures (permission,
+/// encoding, size) remain errors under every policy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MissingImportPolicy {
+    /// URD201 is an error and compilation stops after IMPORT.
+    #[default]
+    Error,
+    /// URD201 is a warning and the file is left out of the graph.
+    /// References into it surface as ordinary LINK errors (URD301).
+    WarnAndSkip,
+    /// URD201 is a warning and an empty file is inserted at the
+    /// resolved path, so the import edge and scope bookkeeping match a
+    /// real import. Post-discovery checks ignore stub nodes.
+    Stub,
+}
+
+impl MissingImportPolicy {
+    /// Parse a policy name: `error`, `warn`, or `stub`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "error" => Some(MissingImportPolicy::Error),
+            "warn" => Some(MissingImportPolicy::WarnAndSkip),
+            "stub" => Some(MissingImportPolicy::Stub),
+            _ => None,
+        }
+    }
+
+    /// The policy's name, as [`Self::from_name`] reads it.
+    pub fn name(self) -> &'static str {
+        match self {
+            MissingImportPolicy::Error => "error",
+            MissingImportPolicy::WarnAndSkip => "warn",
+            MissingImportPolicy::Stub => "stub",
+        }
+    }
+}
+
 // ── Path utilities ──────────────────────────────────────────────────
 
 /// Extract the directory part of a path. Returns `""` if no directory.
@@ -159,25 +209,113 @@ fn collapse_dotdot(path: &str) -> Option<String> {
     Some(segments.join("/"))
 }
 
+/// Split a path into its root prefix (`/`, `C:/`, or `""`) and collapsed
+/// segments. Unlike `collapse_dotdot`, leading `..` segments of a relative
+/// path are kept, since the path has not yet been anchored to a root.
+fn lexical_segments(path: &str) -> (String, Vec<String>) {
+    let normalised = path.replace('\\', "/");
+    let (prefix, rest) = if let Some(rest) = normalised.strip_prefix('/') {
+        ("/".to_string(), rest.to_string())
+    } else if normalised.len() >= 2
+        && normalised.as_bytes()[0].is_ascii_alphabetic()
+        && normalised.as_bytes()[1] == b':'
+    {
+        let drive = normalised[..2].to_ascii_uppercase();
+        let rest = normalised[2..].trim_start_matches('/').to_string();
+        (format!("{}/", drive), rest)
+    } else {
+        (String::new(), normalised)
+    };
+
+    let mut segments: Vec<String> = Vec::new();
+    for segment in rest.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." if segments.last().is_some_and(|s| s != "..") => {
+                segments.pop();
+            }
+            ".." if !prefix.is_empty() => {} // `..` at a filesystem root stays there
+            _ => segments.push(segment.to_string()),
+        }
+    }
+    (prefix, segments)
+}
+
+/// Anchor an entry file to a project root.
+///
+/// Returns `(root_dir, entry_path)`: the root directory as a filesystem
+/// prefix (empty or ending in `/`), and the entry file's path relative to
+/// that root, which becomes its graph identity. Both inputs may use
+/// backslashes. Returns `None` if the entry file does not lie inside the
+/// root, or if one path is absolute and the other relative.
+pub fn anchor_to_project_root(project_root: &str, entry_file: &str) -> Option<(String, String)> {
+    let (root_prefix, root_segments) = lexical_segments(project_root);
+    let (entry_prefix, entry_segments) = lexical_segments(entry_file);
+
+    if root_prefix != entry_prefix
+        || entry_segments.len() <= root_segments.len()
+        || entry_segments[..root_segments.len()] != root_segments[..]
+    {
+        return None;
+    }
+
+    let root_dir = if root_segments.is_empty() {
+        root_prefix
+    } else {
+        format!("{}{}/", root_prefix, root_segments.join("/"))
+    };
+    let entry_path = entry_segments[root_segments.len()..].join("/");
+    Some((root_dir, entry_path))
+}
+
 // ── Path validation ─────────────────────────────────────────────────
 
+/// Why an import path was rejected before any filesystem access.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathRejection {
+    /// URD211: the path is empty after trimming.
+    Empty,
+    /// URD209: the path is absolute (Unix `/` or a Windows drive letter).
+    Absolute,
+    /// URD210: the path does not end in `.urd.md`.
+    Extension,
+    /// URD208: the path resolves outside the project root.
+    Escapes,
+}
+
+impl PathRejection {
+    /// The diagnostic code IMPORT reports for this rejection.
+    pub fn code(self) -> &'static str {
+        match self {
+            PathRejection::Empty => "URD211",
+            PathRejection::Absolute => "URD209",
+            PathRejection::Extension => "URD210",
+            PathRejection::Escapes => "URD208",
+        }
+    }
+
+    /// The diagnostic message for `written_path`, declared in `source_file`.
+    fn message(self, written_path: &str, source_file: &str, import_span: &Span) -> String {
+        match self {
+            PathRejection::Empty => format!("Empty import path at {}:{}.", source_file, import_span.start_line),
+            PathRejection::Absolute => format!("Absolute import paths are not supported: '{}'.", written_path),
+            PathRejection::Extension => {
+                format!("Import path '{}' does not have the .urd.md extension.", written_path)
+            }
+            PathRejection::Escapes => {
+                format!("Import path '{}' resolves outside the project root.", written_path)
+            }
+        }
+    }
+}
+
 /// Validate an import path after trimming and backslash normalisation.
-/// Checks run on `written_path`, before filesystem access.
-/// Returns `true` if the path passes all pre-validation checks.
-fn validate_import_path(
-    written_path: &str,
-    source_file: &str,
-    import_span: &Span,
-    diagnostics: &mut DiagnosticCollector,
-) -> bool {
+/// Checks run on `written_path`, before filesystem access, and the first
+/// one that fails is returned.
+fn validate_import_path(written_path: &str) -> Result<(), PathRejection> {
     // URD211: Empty path
     if written_path.is_empty() {
-        diagnostics.error(
-            "URD211",
-            format!("Empty import path at {}:{}.", source_file, import_span.start_line),
-            import_span.clone(),
-        );
-        return false;
+        return Err(PathRejection::Empty);
     }
 
     // URD209: Absolute path (Unix `/` or Windows drive letter `C:`)
@@ -186,46 +324,25 @@ fn validate_import_path(
             && written_path.as_bytes()[0].is_ascii_alphabetic()
             && written_path.as_bytes()[1] == b':')
     {
-        diagnostics.error(
-            "URD209",
-            format!(
-                "Absolute import paths are not supported: '{}'.",
-                written_path
-            ),
-            import_span.clone(),
-        );
-        return false;
+        return Err(PathRejection::Absolute);
     }
 
     // URD210: Missing .urd.md extension
     if !written_path.ends_with(".urd.md") {
-        diagnostics.error(
-            "URD210",
-            format!(
-                "Import path '{}' does not have the .urd.md extension.",
-                written_path
-            ),
-            import_span.clone(),
-        );
-        return false;
+        return Err(PathRejection::Extension);
     }
 
-    true
+    Ok(())
 }
 
 /// Resolve an import path relative to the importing file.
-/// Returns the normalised path (relative to entry directory).
-/// Emits URD208 and returns `None` if the path escapes the project root.
-fn resolve_import_path(
-    written_path: &str,
-    importer_path: &str,
-    import_span: &Span,
-    diagnostics: &mut DiagnosticCollector,
-) -> Option<String> {
+/// Returns the normalised path (relative to the project root), or
+/// [`PathRejection::Escapes`] if the path escapes the project root.
+fn resolve_import_path(written_path: &str, importer_path: &str) -> Result<String, PathRejection> {
     // Strip leading ./
     let stripped = written_path.strip_prefix("./").unwrap_or(written_path);
 
-    // Get importer's directory (relative to entry dir)
+    // Get importer's directory (relative to the project root)
     let dir = path_dir(importer_path);
 
     // Join with importer's directory
@@ -236,20 +353,81 @@ fn resolve_import_path(
     };
 
     // Collapse .. segments
-    match collapse_dotdot(&joined) {
-        Some(normalised) => Some(normalised),
-        None => {
-            diagnostics.error(
-                "URD208",
-                format!(
-                    "Import path '{}' resolves outside the project root.",
-                    written_path
-                ),
-                import_span.clone(),
-            );
-            None
-        }
+    collapse_dotdot(&joined).ok_or(PathRejection::Escapes)
+}
+
+/// What steps a–f of discovery decide for one declaration. None of them
+/// touches the filesystem.
+enum Resolution {
+    /// A file not yet in the graph, at this normalised path.
+    Load(String),
+    Rejected(PathRejection),
+    SelfImport,
+    /// The chain from the repeated file back to itself.
+    Cycle(Vec<String>),
+    DepthLimit,
+    /// A file already in the graph, at this normalised path.
+    AlreadyLoaded(String),
+}
+
+/// The path as the author wrote it: trimmed, with forward slashes.
+fn written_path(decl: &ImportDecl) -> String {
+    decl.path.trim().replace('\\', "/")
+}
+
+/// Steps a–f: validate and resolve `written_path`, then check it against
+/// the importer, the traversal stack, and the files already visited.
+fn resolve_declaration(
+    written_path: &str,
+    importer_path: &str,
+    visited: &HashSet<String>,
+    traversal_stack: &[String],
+) -> Resolution {
+    // Steps a and b: validate and resolve the path.
+    let normalised_path = match validate_import_path(written_path)
+        .and_then(|()| resolve_import_path(written_path, importer_path))
+    {
+        Ok(path) => path,
+        Err(rejection) => return Resolution::Rejected(rejection),
+    };
+
+    // Step c: Check for self-import.
+    if normalised_path == importer_path {
+        return Resolution::SelfImport;
+    }
+
+    // Step d: Check for cycles.
+    // The cycle path uses normalised_path values for consistency with graph identity.
+    if let Some(cycle_start) = traversal_stack.iter().position(|p| p == &normalised_path) {
+        let mut cycle = traversal_stack[cycle_start..].to_vec();
+        cycle.push(normalised_path);
+        return Resolution::Cycle(cycle);
+    }
+
+    // Step e: Check import depth.
+    if traversal_stack.len() >= MAX_IMPORT_DEPTH {
+        return Resolution::DepthLimit;
+    }
+
+    // Step f: Check for already-loaded file.
+    if visited.contains(&normalised_path) {
+        return Resolution::AlreadyLoaded(normalised_path);
     }
+
+    Resolution::Load(normalised_path)
+}
+
+/// The filesystem path of a normalised path under the project root.
+fn fs_path(entry_dir: &str, normalised_path: &str) -> String {
+    format!("{}{}", entry_dir, normalised_path)
+}
+
+/// The normalised path with the filename's casing as found on disk, if it
+/// differs from `normalised_path`.
+fn casing_correction(reader: &dyn FileReader, entry_dir: &str, normalised_path: &str) -> Option<String> {
+    let dir_part = path_dir(normalised_path);
+    let canonical = reader.canonical_filename(&fs_path(entry_dir, dir_part), path_filename(normalised_path))?;
+    Some(format!("{}{}", dir_part, canonical))
 }
 
 // ── Public entry points ─────────────────────────────────────────────
@@ -259,9 +437,12 @@ fn resolve_import_path(
 /// Discovers imported files recursively, parses each via PARSE, builds
 /// the dependency graph, and produces a topologically sorted file list.
 ///
-/// The `entry_dir` is the directory containing the entry file, used to
-/// construct filesystem paths for reading imported files. It may be empty
-/// if the entry file is in the current working directory.
+/// The `entry_dir` is the project root directory, used to construct
+/// filesystem paths for reading imported files. It defaults to the
+/// directory containing the entry file, and may be empty if that is the
+/// current working directory. Graph paths (including the entry AST's
+/// `path`) are relative to it; imports may traverse `..` freely as long as
+/// they stay inside it. See [`anchor_to_project_root()`].
 pub fn resolve_imports(
     entry_ast: FileAst,
     entry_dir: &str,
@@ -277,7 +458,47 @@ pub fn resolve_imports_with_reader(
     diagnostics: &mut DiagnosticCollector,
     reader: &dyn FileReader,
 ) -> CompilationUnit {
+    resolve_imports_with_policy(entry_ast, entry_dir, diagnostics, reader, MissingImportPolicy::Error)
+}
+
+/// Resolve all imports with an explicit file reader and missing-import policy.
+pub fn resolve_imports_with_policy(
+    entry_ast: FileAst,
+    entry_dir: &str,
+    diagnostics: &mut DiagnosticCollector,
+    reader: &dyn FileReader,
+    policy: MissingImportPolicy,
+) -> CompilationUnit {
+    resolve_imports_relayed(entry_ast, entry_dir, diagnostics, reader, policy, false, &[], &Relay::new(None))
+}
+
+/// [`resolve_imports_with_policy()`], reporting each imported file to the
+/// compile observer as it is parsed. `strict_case` makes URD215 an error;
+/// `defines` are flags set for `?if` guards besides `world.flags`.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn resolve_imports_relayed(
+    mut entry_ast: FileAst,
+    entry_dir: &str,
+    diagnostics: &mut DiagnosticCollector,
+    reader: &dyn FileReader,
+    policy: MissingImportPolicy,
+    strict_case: bool,
+    defines: &[String],
+    relay: &Relay,
+) -> CompilationUnit {
+    let flags = guards::active_flags(&entry_ast, defines);
+    guards::expand(&mut entry_ast, &flags, diagnostics);
+
     let mut graph = DependencyGraph::new();
+    let mut discovery = Discovery {
+        entry_dir,
+        reader,
+        policy,
+        relay,
+        flags,
+        stubs: HashSet::new(),
+        casing_fixes: Vec::new(),
+    };
 
     let entry_path = entry_ast.path.clone();
     let entry_imports = extract_import_decls(&entry_ast);
@@ -302,17 +523,18 @@ pub fn resolve_imports_with_reader(
     process_imports(
         &entry_path,
         &entry_imports,
-        entry_dir,
+        &mut discovery,
         &mut graph,
         &mut visited,
         &mut traversal_stack,
         diagnostics,
-        reader,
     );
 
     // Post-discovery checks.
-    check_file_count(&graph, diagnostics);
-    check_file_stems(&graph, diagnostics);
+    check_file_count(&graph, &discovery.stubs, diagnostics);
+    check_file_stems(&graph, &discovery.stubs, diagnostics);
+    check_case_collisions(&graph, &discovery.stubs, strict_case, diagnostics);
+    report_casing_fixes(&graph, &discovery.stubs, &discovery.casing_fixes, diagnostics);
 
     // Build ordered_asts from topological order.
     let ordered_asts = graph.topological_order().into_iter().cloned().collect();
@@ -342,16 +564,37 @@ fn extract_import_decls(ast: &FileAst) -> Vec<ImportDecl> {
 
 // ── Recursive discovery ─────────────────────────────────────────────
 
+/// Fixed inputs for one discovery run, plus the stub nodes it inserted.
+struct Discovery<'a> {
+    entry_dir: &'a str,
+    reader: &'a dyn FileReader,
+    policy: MissingImportPolicy,
+    relay: &'a Relay<'a>,
+    /// Flags that keep `?if` guards.
+    flags: BTreeSet<String>,
+    /// Paths of empty nodes inserted under [`MissingImportPolicy::Stub`].
+    stubs: HashSet<String>,
+    /// Imports whose casing was corrected, reported as URD206 once the
+    /// whole graph is known.
+    casing_fixes: Vec<CasingFix>,
+}
+
+/// An import redirected to the casing found on disk.
+struct CasingFix {
+    written_path: String,
+    corrected: String,
+    span: Span,
+}
+
 /// Process all import declarations for a single file.
 fn process_imports(
     importer_path: &str,
     import_decls: &[ImportDecl],
-    entry_dir: &str,
+    discovery: &mut Discovery,
     graph: &mut DependencyGraph,
     visited: &mut HashSet<String>,
     traversal_stack: &mut Vec<String>,
     diagnostics: &mut DiagnosticCollector,
-    reader: &dyn FileReader,
 ) {
     // Track edges from this file to prevent duplicate edges.
     let mut edges_from_this_file: HashSet<String> = HashSet::new();
@@ -360,13 +603,12 @@ fn process_imports(
         process_single_import(
             importer_path,
             decl,
-            entry_dir,
+            discovery,
             graph,
             visited,
             traversal_stack,
             &mut edges_from_this_file,
             diagnostics,
-            reader,
         );
     }
 }
@@ -378,105 +620,70 @@ fn process_imports(
 fn process_single_import(
     importer_path: &str,
     decl: &ImportDecl,
-    entry_dir: &str,
+    discovery: &mut Discovery,
     graph: &mut DependencyGraph,
     visited: &mut HashSet<String>,
     traversal_stack: &mut Vec<String>,
     edges_from_this_file: &mut HashSet<String>,
     diagnostics: &mut DiagnosticCollector,
-    reader: &dyn FileReader,
 ) {
-    // Step a: Trim and validate the path.
-    let trimmed = decl.path.trim();
-    let written_path = trimmed.replace('\\', "/");
-
-    if !validate_import_path(&written_path, importer_path, &decl.span, diagnostics) {
-        return;
-    }
-
-    // Step b: Resolve the path.
-    let mut normalised_path =
-        match resolve_import_path(&written_path, importer_path, &decl.span, diagnostics) {
-            Some(p) => p,
-            None => return,
-        };
-
-    // Step c: Check for self-import.
-    if normalised_path == importer_path {
-        diagnostics.error(
-            "URD207",
-            format!("File imports itself: '{}'.", written_path),
-            decl.span.clone(),
-        );
-        return;
-    }
-
-    // Step d: Check for cycles.
-    // The cycle path uses normalised_path values for consistency with graph identity.
-    if let Some(cycle_start) = traversal_stack.iter().position(|p| p == &normalised_path) {
-        let cycle_path: Vec<&str> = traversal_stack[cycle_start..]
-            .iter()
-            .map(|s| s.as_str())
-            .chain(std::iter::once(normalised_path.as_str()))
-            .collect();
-        let cycle_display = cycle_path.join(" \u{2192} ");
-        diagnostics.error(
-            "URD202",
-            format!("Circular import detected: {}.", cycle_display),
-            decl.span.clone(),
-        );
-        return;
-    }
-
-    // Step e: Check import depth.
-    if traversal_stack.len() >= MAX_IMPORT_DEPTH {
-        diagnostics.error(
-            "URD204",
-            "Import depth limit exceeded (64 files in chain).",
-            decl.span.clone(),
-        );
-        return;
-    }
-
-    // Step f: Check for already-loaded file.
-    if visited.contains(&normalised_path) {
-        add_edge(importer_path, &normalised_path, edges_from_this_file, graph);
-        return;
-    }
+    let entry_dir = discovery.entry_dir;
+    let reader = discovery.reader;
+
+    // Steps a–f: validate and resolve the path, then check it.
+    let written_path = written_path(decl);
+    let mut normalised_path = match resolve_declaration(&written_path, importer_path, visited, traversal_stack) {
+        Resolution::Load(path) => path,
+        Resolution::AlreadyLoaded(path) => {
+            add_edge(importer_path, &path, edges_from_this_file, graph);
+            return;
+        }
+        Resolution::Rejected(rejection) => {
+            diagnostics.error(
+                rejection.code(),
+                rejection.message(&written_path, importer_path, &decl.span),
+                decl.span.clone(),
+            );
+            return;
+        }
+        Resolution::SelfImport => {
+            diagnostics.error(
+                "URD207",
+                format!("File imports itself: '{}'.", written_path),
+                decl.span.clone(),
+            );
+            return;
+        }
+        Resolution::Cycle(cycle) => {
+            diagnostics.error(
+                "URD202",
+                format!("Circular import detected: {}.", cycle.join(" \u{2192} ")),
+                decl.span.clone(),
+            );
+            return;
+        }
+        Resolution::DepthLimit => {
+            diagnostics.error(
+                "URD204",
+                "Import depth limit exceeded (64 files in chain).",
+                decl.span.clone(),
+            );
+            return;
+        }
+    };
 
     // Step g: Load the new file.
-    let fs_path = if entry_dir.is_empty() {
-        normalised_path.clone()
-    } else {
-        format!("{}{}", entry_dir, normalised_path)
-    };
+    let fs_path = fs_path(entry_dir, &normalised_path);
 
     let source = match reader.read_file(&fs_path) {
         Ok(s) => {
             // Casing mismatch detection (step g, after locating file).
-            let filename = path_filename(&normalised_path);
-            let fs_dir = if entry_dir.is_empty() {
-                path_dir(&normalised_path).to_string()
-            } else {
-                format!("{}{}", entry_dir, path_dir(&normalised_path))
-            };
-
-            if let Some(canonical) = reader.canonical_filename(&fs_dir, filename) {
-                let dir_part = path_dir(&normalised_path);
-                let corrected = if dir_part.is_empty() {
-                    canonical.clone()
-                } else {
-                    format!("{}{}", dir_part, canonical)
-                };
-
-                diagnostics.warning(
-                    "URD206",
-                    format!(
-                        "Import path '{}' differs in filename casing from discovered file '{}'. Using discovered casing.",
-                        written_path, corrected
-                    ),
-                    decl.span.clone(),
-                );
+            if let Some(corrected) = casing_correction(reader, entry_dir, &normalised_path) {
+                discovery.casing_fixes.push(CasingFix {
+                    written_path: written_path.clone(),
+                    corrected: corrected.clone(),
+                    span: decl.span.clone(),
+                });
 
                 normalised_path = corrected;
 
@@ -490,14 +697,32 @@ fn process_single_import(
             s
         }
         Err(FileReadError::NotFound) => {
-            diagnostics.error(
-                "URD201",
-                format!(
-                    "Imported file not found: '{}' (imported from {}:{}).",
-                    written_path, importer_path, decl.span.start_line
-                ),
-                decl.span.clone(),
+            let message = format!(
+                "Imported file not found: '{}' (imported from {}:{}).",
+                written_path, importer_path, decl.span.start_line
             );
+            match discovery.policy {
+                MissingImportPolicy::Error => {
+                    diagnostics.error("URD201", message, decl.span.clone());
+                }
+                MissingImportPolicy::WarnAndSkip => {
+                    diagnostics.warning(
+                        "URD201",
+                        format!("{} Skipped; references into it will not resolve.", message),
+                        decl.span.clone(),
+                    );
+                }
+                MissingImportPolicy::Stub => {
+                    diagnostics.warning(
+                        "URD201",
+                        format!("{} Using an empty stub.", message),
+                        decl.span.clone(),
+                    );
+                    insert_stub(&normalised_path, graph, &mut discovery.stubs);
+                    visited.insert(normalised_path.clone());
+                    add_edge(importer_path, &normalised_path, edges_from_this_file, graph);
+                }
+            }
             return;
         }
         Err(FileReadError::PermissionDenied) => {
@@ -555,10 +780,12 @@ fn process_single_import(
     }
 
     // Step h: Parse the file.
-    let file_ast = match parse::parse(&normalised_path, &source, diagnostics) {
+    let mut file_ast = match parse::parse(&normalised_path, &source, diagnostics) {
         Some(ast) => ast,
         None => return, // Catastrophic parse failure
     };
+    guards::expand(&mut file_ast, &discovery.flags, diagnostics);
+    discovery.relay.file_parsed(&file_ast, diagnostics);
 
     // Step i: Add to graph.
     let new_imports = extract_import_decls(&file_ast);
@@ -578,16 +805,34 @@ fn process_single_import(
     process_imports(
         &normalised_path,
         &new_imports,
-        entry_dir,
+        discovery,
         graph,
         visited,
         traversal_stack,
         diagnostics,
-        reader,
     );
     traversal_stack.pop();
 }
 
+/// Insert an empty file node standing in for a missing import.
+fn insert_stub(path: &str, graph: &mut DependencyGraph, stubs: &mut HashSet<String>) {
+    graph.nodes.insert(
+        path.to_string(),
+        FileNode {
+            path: path.to_string(),
+            ast: FileAst {
+                path: path.to_string(),
+                frontmatter: None,
+                content: Vec::new(),
+                span: Span::new(path.to_string(), 1, 1, 1, 1),
+                source_hash: None,
+            },
+            imports: Vec::new(),
+        },
+    );
+    stubs.insert(path.to_string());
+}
+
 /// Add a dependency edge from `importer` to `target`, deduplicating.
 /// Also updates the importer's `imports` list.
 fn add_edge(
@@ -609,8 +854,9 @@ fn add_edge(
 // ── Post-discovery checks ───────────────────────────────────────────
 
 /// URD205: Check that the compilation unit does not exceed 256 files.
-fn check_file_count(graph: &DependencyGraph, diagnostics: &mut DiagnosticCollector) {
-    let count = graph.nodes.len();
+/// Stub nodes are not files and are not counted.
+fn check_file_count(graph: &DependencyGraph, stubs: &HashSet<String>, diagnostics: &mut DiagnosticCollector) {
+    let count = graph.nodes.len() - stubs.len();
     if count > MAX_FILE_COUNT {
         diagnostics.error(
             "URD205",
@@ -623,11 +869,111 @@ fn check_file_count(graph: &DependencyGraph, diagnostics: &mut DiagnosticCollect
     }
 }
 
-/// URD203: Check that all file stems are unique.
-fn check_file_stems(graph: &DependencyGraph, diagnostics: &mut DiagnosticCollector) {
+/// Unicode case folding, for comparing names the way a case-insensitive
+/// file system does. Upper-casing first folds characters such as `ß`
+/// (`SS`) that lower-casing alone leaves as they are.
+pub fn fold_case(name: &str) -> String {
+    name.to_uppercase().to_lowercase()
+}
+
+/// URD215: Check that no two file stems differ only in case. Such files
+/// coexist on Linux but not on the case-insensitive file systems macOS
+/// and Windows use by default, where one shadows the other. Identical
+/// stems are URD203's concern and are not reported again.
+fn check_case_collisions(
+    graph: &DependencyGraph,
+    stubs: &HashSet<String>,
+    strict: bool,
+    diagnostics: &mut DiagnosticCollector,
+) {
+    let mut folded: BTreeMap<String, Vec<(String, &str)>> = BTreeMap::new();
+    for path in graph.nodes.keys().filter(|p| !stubs.contains(*p)) {
+        let stem = file_stem(path);
+        folded.entry(fold_case(&stem)).or_default().push((stem, path));
+    }
+
+    for (_, mut files) in folded {
+        files.sort_by(|a, b| a.1.cmp(b.1));
+        for i in 0..files.len() {
+            for j in (i + 1)..files.len() {
+                let ((stem_a, path_a), (stem_b, path_b)) = (&files[i], &files[j]);
+                if stem_a == stem_b {
+                    continue;
+                }
+                let risk = if path_dir(path_a) == path_dir(path_b) {
+                    "On a case-insensitive file system, as macOS and Windows use by default, the two cannot coexist and one will shadow the other."
+                } else {
+                    "Their section IDs differ only in case, and moving both into one directory on a case-insensitive file system, as macOS and Windows use by default, would make one shadow the other."
+                };
+                let message = format!(
+                    "File stems '{}' ({}) and '{}' ({}) differ only in case. {} Rename one of them.",
+                    stem_a, path_a, stem_b, path_b, risk
+                );
+                if strict {
+                    diagnostics.error("URD215", message, Span::synthetic());
+                } else {
+                    diagnostics.warning("URD215", message, Span::synthetic());
+                }
+            }
+        }
+    }
+}
+
+/// URD206: Report imports whose casing was corrected. When another file
+/// in the graph differs from the corrected one only in case, the import
+/// could have loaded either, so the warning becomes an error naming it.
+fn report_casing_fixes(
+    graph: &DependencyGraph,
+    stubs: &HashSet<String>,
+    fixes: &[CasingFix],
+    diagnostics: &mut DiagnosticCollector,
+) {
+    for fix in fixes {
+        let message = format!(
+            "Import path '{}' differs in filename casing from discovered file '{}'. Using discovered casing.",
+            fix.written_path, fix.corrected
+        );
+        let folded = fold_case(&file_stem(&fix.corrected));
+        let colliding = graph
+            .nodes
+            .keys()
+            .filter(|p| !stubs.contains(*p) && **p != fix.corrected)
+            .filter(|p| path_dir(p) == path_dir(&fix.corrected))
+            .find(|p| fold_case(&file_stem(p)) == folded);
+        match colliding {
+            Some(other) => diagnostics.error(
+                "URD206",
+                format!(
+                    "{} '{}' is also in the compilation unit and differs from it only in case, so on a case-insensitive file system this import may load either file.",
+                    message, other
+                ),
+                fix.span.clone(),
+            ),
+            // The compiler already loaded the discovered file, so writing
+            // its casing into the import changes nothing else.
+            None => diagnostics.emit(Diagnostic {
+                severity: Severity::Warning,
+                code: "URD206".to_string(),
+                message,
+                span: fix.span.clone(),
+                suggestion: None,
+                fix: Some(Fix {
+                    span: fix.span.clone(),
+                    original: path_filename(&fix.written_path).to_string(),
+                    replacement: path_filename(&fix.corrected).to_string(),
+                }),
+                related: Vec::new(),
+            }),
+        }
+    }
+}
+
+/// URD203: Check that all file stems are unique. Stub nodes declare
+/// nothing, so they cannot produce conflicting section IDs and are skipped.
+fn check_file_stems(graph: &DependencyGraph, stubs: &HashSet<String>, diagnostics: &mut DiagnosticCollector) {
     // Build a map from stem to sorted list of paths.
     let mut stems: BTreeMap<String, Vec<&str>> = BTreeMap::new();
-    for path in graph.nodes.keys() {
+    for path in graph.nodes.keys().filter(|p| !stubs.contains(*p)) {
         let stem = file_stem(path);
         stems.entry(stem).or_default().push(path);
     }
@@ -0,0 +1,308 @@
+/// Dry-run import plans: which files a compile would read, found without
+/// compiling.
+///
+/// `resolve_imports_dry_run()` parses the entry file, then runs steps a–f
+/// of discovery exactly as IMPORT does, recording each outcome instead of
+/// emitting a diagnostic. Nothing is linked, no graph is built, and no
+/// diagnostic reaches any collector the caller holds.
+///
+/// - A **shallow** plan covers the entry file's own imports and reads
+///   nothing, so it is safe with a reader that only answers metadata.
+/// - A **full** plan reads each file it reaches, parses only its
+///   frontmatter, and follows its imports in source order, depth first.
+///
+/// `?if` guards are expanded with the entry file's `world.flags`, so an
+/// import inside a dropped guard is not planned.
+
+use std::collections::{BTreeSet, HashSet};
+
+use super::{
+    casing_correction, extract_import_decls, fs_path, guards, resolve_declaration, written_path, FileReadError,
+    FileReader, PathRejection, Resolution,
+};
+use crate::ast::FileAst;
+use crate::diagnostics::DiagnosticCollector;
+use crate::graph::MAX_FILE_SIZE;
+use crate::parse;
+use crate::span::Span;
+
+/// How far a plan follows imports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlanMode {
+    /// The entry file's direct imports, without reading any file.
+    #[default]
+    Shallow,
+    /// Every file reachable from the entry, reading each one's frontmatter.
+    Full,
+}
+
+/// The outcome of planning one import declaration.
+#[derive(Debug, Clone)]
+pub enum ImportStatus {
+    /// The path passed every check. A shallow plan would read `fs_path`;
+    /// a full plan read it and followed its imports. `path` is the graph
+    /// identity, after any casing correction.
+    Load { path: String, fs_path: String },
+    /// Full plans only: reading `fs_path` failed.
+    Unreadable { path: String, fs_path: String, error: FileReadError },
+    /// The written path failed validation; nothing would be read.
+    Rejected(PathRejection),
+    /// The file imports itself (URD207).
+    SelfImport,
+    /// The import closes a cycle (URD202): the chain from the repeated
+    /// file back to itself.
+    Cycle(Vec<String>),
+    /// The traversal stack is already at the depth limit (URD204).
+    DepthLimit,
+    /// The file is already planned at this graph path; it is not read again.
+    AlreadyLoaded(String),
+}
+
+impl ImportStatus {
+    /// The diagnostic code a compile would report for this import, or
+    /// `None` if it would be followed without complaint.
+    pub fn code(&self) -> Option<&'static str> {
+        match self {
+            ImportStatus::Load { .. } | ImportStatus::AlreadyLoaded(_) => None,
+            ImportStatus::Unreadable { error, .. } => Some(match error {
+                FileReadError::NotFound => "URD201",
+                FileReadError::PermissionDenied => "URD213",
+                FileReadError::InvalidUtf8 => "URD212",
+                FileReadError::IoError(_) => "URD214",
+                FileReadError::TooLarge(_) => "URD103",
+            }),
+            ImportStatus::Rejected(rejection) => Some(rejection.code()),
+            ImportStatus::SelfImport => Some("URD207"),
+            ImportStatus::Cycle(_) => Some("URD202"),
+            ImportStatus::DepthLimit => Some("URD204"),
+        }
+    }
+}
+
+/// One import declaration and what the plan decided for it.
+#[derive(Debug, Clone)]
+pub struct PlannedImport {
+    /// Graph path of the file declaring the import.
+    pub importer: String,
+    /// The path as written: trimmed, with forward slashes.
+    pub written_path: String,
+    /// Span of the `import:` declaration.
+    pub span: Span,
+    /// Files between the entry and the importer: 0 for the entry's own
+    /// imports.
+    pub depth: usize,
+    pub status: ImportStatus,
+}
+
+/// Every import declaration a dry run reached, in discovery order.
+#[derive(Debug, Clone)]
+pub struct ImportPlan {
+    pub mode: PlanMode,
+    /// Graph path of the entry file.
+    pub entry: String,
+    /// False if the entry file did not parse (URD101 or URD103), in
+    /// which case `imports` is empty.
+    pub entry_parsed: bool,
+    pub imports: Vec<PlannedImport>,
+}
+
+impl ImportPlan {
+    /// The filesystem paths the plan reads (full) or would read
+    /// (shallow), in order. The entry file itself is not listed.
+    pub fn reads(&self) -> impl Iterator<Item = &str> {
+        self.imports.iter().filter_map(|import| match &import.status {
+            ImportStatus::Load { fs_path, .. } | ImportStatus::Unreadable { fs_path, .. } => Some(fs_path.as_str()),
+            _ => None,
+        })
+    }
+
+    /// Whether a compile would report an error for any planned import.
+    pub fn has_errors(&self) -> bool {
+        self.imports.iter().any(|import| import.status.code().is_some())
+    }
+
+    /// One line per planned import, indented by depth, then a count of
+    /// the files read.
+    pub fn to_text(&self) -> String {
+        let mut out = format!("{} ({} plan)\n", self.entry, self.mode.name());
+        for import in &self.imports {
+            let outcome = match &import.status {
+                ImportStatus::Load { fs_path, .. } => match self.mode {
+                    PlanMode::Shallow => format!("would read {}", fs_path),
+                    PlanMode::Full => format!("read {}", fs_path),
+                },
+                ImportStatus::Unreadable { fs_path, error, .. } => {
+                    format!("cannot read {}: {}", fs_path, error.description())
+                }
+                ImportStatus::Rejected(rejection) => rejection.reason().to_string(),
+                ImportStatus::SelfImport => "imports itself".to_string(),
+                ImportStatus::Cycle(cycle) => format!("cycle {}", cycle.join(" \u{2192} ")),
+                ImportStatus::DepthLimit => "depth limit reached".to_string(),
+                ImportStatus::AlreadyLoaded(path) => format!("already planned as {}", path),
+            };
+            let code = import.status.code().map(|code| format!("{} ", code)).unwrap_or_default();
+            out.push_str(&format!(
+                "{}'{}': {}{}\n",
+                "  ".repeat(import.depth + 1),
+                import.written_path,
+                code,
+                outcome,
+            ));
+        }
+        let reads = self.reads().count();
+        let verb = if self.mode == PlanMode::Shallow { "would be read" } else { "read" };
+        out.push_str(&format!("{} {} {}\n", reads, if reads == 1 { "file" } else { "files" }, verb));
+        out
+    }
+}
+
+impl PlanMode {
+    /// `shallow` or `full`.
+    pub fn name(self) -> &'static str {
+        match self {
+            PlanMode::Shallow => "shallow",
+            PlanMode::Full => "full",
+        }
+    }
+}
+
+impl PathRejection {
+    /// A short account of the rejection, without the path.
+    fn reason(self) -> &'static str {
+        match self {
+            PathRejection::Empty => "empty path",
+            PathRejection::Absolute => "absolute path",
+            PathRejection::Extension => "no .urd.md extension",
+            PathRejection::Escapes => "resolves outside the project root",
+        }
+    }
+}
+
+impl FileReadError {
+    fn description(&self) -> String {
+        match self {
+            FileReadError::NotFound => "not found".to_string(),
+            FileReadError::PermissionDenied => "permission denied".to_string(),
+            FileReadError::InvalidUtf8 => "invalid UTF-8".to_string(),
+            FileReadError::IoError(message) => message.clone(),
+            FileReadError::TooLarge(size) => format!("{} bytes, over the 1 MB limit", size),
+        }
+    }
+}
+
+/// Plan the imports of `filename`, whose contents are `source`, without
+/// compiling it. `filename` is split into the project root (its directory)
+/// and graph path as `compile_source_with_options()` does with no
+/// `project_root`. Only a full plan calls `reader`.
+pub fn resolve_imports_dry_run(filename: &str, source: &str, reader: &dyn FileReader, mode: PlanMode) -> ImportPlan {
+    let normalised = filename.replace('\\', "/");
+    let (entry_dir, entry_path) = match normalised.rfind('/') {
+        Some(pos) => (normalised[..pos + 1].to_string(), normalised[pos + 1..].to_string()),
+        None => (String::new(), normalised),
+    };
+
+    let mut plan = ImportPlan { mode, entry: entry_path.clone(), entry_parsed: false, imports: Vec::new() };
+
+    // Diagnostics from parsing are never reported; the plan is the output.
+    let mut scratch = DiagnosticCollector::new();
+    let Some(mut entry_ast) = parse::parse(&entry_path, source, &mut scratch) else {
+        return plan;
+    };
+    plan.entry_parsed = true;
+    let flags = guards::active_flags(&entry_ast, &[]);
+    guards::expand(&mut entry_ast, &flags, &mut scratch);
+
+    let mut planner = Planner {
+        entry_dir: &entry_dir,
+        reader,
+        mode,
+        flags,
+        visited: HashSet::from([entry_path.clone()]),
+        traversal_stack: vec![entry_path.clone()],
+        scratch,
+    };
+    planner.plan_file(&entry_path, &entry_ast, &mut plan.imports);
+    plan
+}
+
+/// Discovery state for one dry run.
+struct Planner<'a> {
+    entry_dir: &'a str,
+    reader: &'a dyn FileReader,
+    mode: PlanMode,
+    flags: BTreeSet<String>,
+    visited: HashSet<String>,
+    traversal_stack: Vec<String>,
+    /// Collects and discards the diagnostics of frontmatter parsing.
+    scratch: DiagnosticCollector,
+}
+
+impl Planner<'_> {
+    /// Plan each import of `ast`, following loaded files in a full plan.
+    fn plan_file(&mut self, importer_path: &str, ast: &FileAst, out: &mut Vec<PlannedImport>) {
+        for decl in extract_import_decls(ast) {
+            let written_path = written_path(&decl);
+            let status = match resolve_declaration(&written_path, importer_path, &self.visited, &self.traversal_stack) {
+                Resolution::Load(path) => {
+                    let fs_path = fs_path(self.entry_dir, &path);
+                    self.visited.insert(path.clone());
+                    ImportStatus::Load { path, fs_path }
+                }
+                Resolution::Rejected(rejection) => ImportStatus::Rejected(rejection),
+                Resolution::SelfImport => ImportStatus::SelfImport,
+                Resolution::Cycle(cycle) => ImportStatus::Cycle(cycle),
+                Resolution::DepthLimit => ImportStatus::DepthLimit,
+                Resolution::AlreadyLoaded(path) => ImportStatus::AlreadyLoaded(path),
+            };
+            let index = out.len();
+            out.push(PlannedImport {
+                importer: importer_path.to_string(),
+                written_path,
+                span: decl.span.clone(),
+                depth: self.traversal_stack.len() - 1,
+                status,
+            });
+            if self.mode == PlanMode::Full {
+                if let ImportStatus::Load { path, fs_path } = out[index].status.clone() {
+                    self.follow(path, fs_path, index, out);
+                }
+            }
+        }
+    }
+
+    /// Step g, reading only: read the frontmatter of the file planned at
+    /// `out[index]` and plan its imports in turn.
+    fn follow(&mut self, mut path: String, fs_path: String, index: usize, out: &mut Vec<PlannedImport>) {
+        let source = match self.reader.read_file(&fs_path) {
+            Ok(source) if source.len() > MAX_FILE_SIZE => Err(FileReadError::TooLarge(source.len())),
+            other => other,
+        };
+        let source = match source {
+            Ok(source) => source,
+            Err(error) => {
+                self.visited.remove(&path);
+                out[index].status = ImportStatus::Unreadable { path, fs_path, error };
+                return;
+            }
+        };
+
+        if let Some(corrected) = casing_correction(self.reader, self.entry_dir, &path) {
+            self.visited.remove(&path);
+            path = corrected;
+            out[index].status = ImportStatus::Load { path: path.clone(), fs_path };
+            // Already planned under its canonical name: read, but not followed twice.
+            if !self.visited.insert(path.clone()) {
+                return;
+            }
+        }
+
+        let Some(mut ast) = parse::parse_frontmatter_only(&path, &source, &mut self.scratch) else {
+            return;
+        };
+        guards::expand(&mut ast, &self.flags, &mut self.scratch);
+
+        self.traversal_stack.push(path.clone());
+        self.plan_file(&path, &ast, out);
+        self.traversal_stack.pop();
+    }
+}
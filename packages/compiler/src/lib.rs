@@ -13,10 +13,37 @@ pub mod graph;
 pub mod span;
 pub mod facts;
 pub mod definition_index;
+pub mod references;
 pub mod diff;
+pub mod explain;
+pub mod fix;
+pub mod fragment;
+pub mod anonymize;
+pub mod docgen;
+pub mod flowgraph;
+pub mod hash;
+pub mod stats;
+pub mod strings;
+pub mod text_lint;
+pub mod size_lint;
 pub mod analyze;
+pub mod cache;
+pub mod cli;
+pub mod metadata;
+pub mod observer;
+pub mod output_map;
+pub mod payload;
+pub mod playable;
+pub mod project;
+pub mod property_groups;
+pub mod schema;
 pub mod slugify;
 pub mod symbol_table;
+pub mod test_support;
+pub mod traits;
+pub mod version;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod watch;
 
 // Phase modules
 pub mod parse;
@@ -25,12 +52,21 @@ pub mod link;
 pub mod validate;
 pub mod emit;
 
+// Hosts hold a LINK cache and read the emitted world without reaching
+// into the phase modules.
+pub use emit::CompiledWorld;
+pub use link::LinkCache;
+
 // WASM bindings (only compiled with the `wasm` feature)
 #[cfg(feature = "wasm")]
 pub mod wasm;
 
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
 use diagnostics::DiagnosticCollector;
 use import::FileReader;
+use observer::Phase;
 #[cfg(not(target_arch = "wasm32"))]
 use span::FilePath;
 
@@ -38,8 +74,13 @@ use span::FilePath;
 pub struct CompilationResult {
     /// `true` if compilation succeeded with zero errors.
     pub success: bool,
-    /// The compiled JSON string, or `None` if any errors occurred.
+    /// The compiled JSON string, or `None` if any errors occurred or
+    /// `CompileOptions::skip_world` was set.
     pub world: Option<String>,
+    /// The compiled world as typed blocks (see [`CompiledWorld`]), for
+    /// Rust hosts. `world` is its [`CompiledWorld::to_document()`], and
+    /// it is `Some` whenever `world` holds a compiled world.
+    pub world_value: Option<CompiledWorld>,
     /// All diagnostics (errors, warnings, info) from all phases.
     pub diagnostics: DiagnosticCollector,
     /// Normalized analysis IR. `Some` whenever LINK succeeds, even if
@@ -55,10 +96,26 @@ pub struct CompilationResult {
     /// Contains full type definitions with property constraints, entity
     /// overrides, rule definitions, sequence phases, and action metadata.
     pub symbol_table: Option<symbol_table::SymbolTable>,
+    /// The serialisable export of `symbol_table` (see
+    /// [`symbol_table::export`]). `Some` when LINK succeeds and
+    /// `CompileOptions::keep_symbols` is set.
+    pub symbols: Option<symbol_table::export::SymbolTableExport>,
     /// The file dependency graph with annotated ASTs. `Some` whenever
     /// LINK succeeds. Contains per-file ASTs (with annotation slots
     /// filled by LINK), import edges, and topological ordering.
     pub graph: Option<graph::DependencyGraph>,
+    /// Split output documents keyed by file name (see [`emit::emit_split()`]).
+    /// `Some` only when compilation succeeded with `EmitOptions::split` set.
+    pub parts: Option<std::collections::BTreeMap<String, String>>,
+    /// The source text of every file the compile read, keyed by the path
+    /// diagnostic spans use. `Some` when `CompileOptions::keep_sources` is
+    /// set, for rendering diagnostics with their source lines (see
+    /// [`diagnostics::render`]).
+    pub sources: Option<std::collections::BTreeMap<String, String>>,
+    /// Where each emitted object came from (see [`output_map`]).
+    /// `Some` only when compilation succeeded with
+    /// `EmitOptions::build_output_map` set, even if `skip_world` was too.
+    pub output_map: Option<output_map::OutputMap>,
 }
 
 /// Compile a single `.urd.md` source string (no import resolution).
@@ -70,120 +127,385 @@ pub fn compile_source(filename: &str, source: &str) -> CompilationResult {
     compile_source_with_reader(filename, source, &import::StubFileReader)
 }
 
+/// Options controlling a compilation.
+///
+/// `Default` reproduces the behaviour of [`compile_source_with_reader()`].
+#[derive(Debug, Clone, Default)]
+pub struct CompileOptions<'a> {
+    /// Project root directory. Imports may traverse `..` as long as the
+    /// resolved path stays inside this directory, and file paths in the
+    /// dependency graph and diagnostics are relative to it. `None` uses
+    /// the entry file's directory.
+    pub project_root: Option<String>,
+    /// Options passed through to EMIT.
+    pub emit: emit::EmitOptions,
+    /// How IMPORT treats imported files that cannot be found. Single-file
+    /// hosts with no real file system can downgrade URD201 to a warning.
+    pub missing_import_policy: import::MissingImportPolicy,
+    /// Make URD215 (file stems differing only in case) an error rather
+    /// than a warning.
+    pub strict_filename_case: bool,
+    /// Receives phase, file, and diagnostic events as the compile runs
+    /// (see [`observer`]). `None` reports nothing.
+    pub observer: Option<&'a dyn observer::CompileObserver>,
+    /// Fill `CompilationResult::symbols`. Off by default, since the export
+    /// copies the whole symbol table.
+    pub keep_symbols: bool,
+    /// Reuse LINK's per-file collection for files whose source is unchanged
+    /// since an earlier compile with the same cache (see
+    /// [`link::link_with_cache()`]). The output is the same either way.
+    pub link_cache: Option<&'a link::LinkCache>,
+    /// Run the [`text_lint`] built-in rules over player-visible text after
+    /// VALIDATE. Off by default; editors turn it on.
+    pub text_lints: bool,
+    /// Report unresolved `@entity` references (URD301) as warnings, so the
+    /// world still emits. Set by [`fragment::compile_fragment()`].
+    pub lenient_entity_refs: bool,
+    /// Flags set for `?if` guards, in addition to the entry file's
+    /// `world.flags`. IMPORT keeps the guards they select and drops the
+    /// rest.
+    pub defines: Vec<String>,
+    /// Skip serialising the combined world, leaving
+    /// `CompilationResult::world` as `None` even on success. For hosts that
+    /// write it themselves with [`emit::emit_to_writer()`] from the result's
+    /// graph and symbol table, so the document is never held in memory.
+    /// Split parts are still produced.
+    pub skip_world: bool,
+    /// Fill `CompilationResult::sources`. Off by default, since it keeps a
+    /// copy of every file read.
+    pub keep_sources: bool,
+    /// Thresholds for the ANALYZE size checks (URD615–URD617).
+    pub size_limits: size_lint::SizeLimits,
+    /// Which files a reference may resolve into: direct imports only
+    /// (the default), or everything reachable through imports. A world
+    /// block's `scope:` field wins over this.
+    pub scope_policy: link::ScopePolicy,
+}
+
 /// Compile a `.urd.md` source string with a custom file reader.
 ///
 /// The reader is used to resolve `import:` declarations. Native callers
 /// pass [`import::OsFileReader`]; WASM callers pass a stub or
 /// JavaScript-backed reader.
 ///
+/// Equivalent to [`compile_source_with_options()`] with default options.
+pub fn compile_source_with_reader(
+    filename: &str,
+    source: &str,
+    reader: &dyn FileReader,
+) -> CompilationResult {
+    compile_source_with_options(filename, source, reader, &CompileOptions::default())
+}
+
+/// Compile a `.urd.md` source string with a custom file reader and options.
+///
 /// Orchestrates the five phases in sequence:
 /// 1. PARSE    — source text → per-file AST
 /// 2. IMPORT   — entry AST → dependency graph + all ASTs
 /// 3. LINK     — graph + ASTs → symbol table + annotated ASTs
 /// 4. VALIDATE — annotated ASTs + symbol table → diagnostics
 /// 5. EMIT     — validated ASTs + symbol table → `.urd.json`
-pub fn compile_source_with_reader(
+///
+/// If `options.project_root` is set and the entry file lies outside it,
+/// compilation stops with URD208.
+pub fn compile_source_with_options(
     filename: &str,
     source: &str,
     reader: &dyn FileReader,
+    options: &CompileOptions,
 ) -> CompilationResult {
-    let mut diagnostics = DiagnosticCollector::new();
+    let relay = observer::Relay::new(options.observer);
+    let recorder = options
+        .keep_sources
+        .then(|| SourceRecorder { inner: reader, read: RefCell::new(BTreeMap::new()) });
+    let reader: &dyn FileReader = match &recorder {
+        Some(recorder) => recorder,
+        None => reader,
+    };
+    let mut result = compile_relayed(filename, source, reader, options, &relay);
+    if let Some(recorder) = recorder {
+        result.sources = Some(recorder.into_sources(filename, source, options, result.graph.as_ref()));
+    }
+    // Diagnostics raised outside any phase (URD208).
+    relay.flush(&result.diagnostics);
+    result
+}
 
-    // Normalise filename: split into directory + filename components.
+/// Split `filename` into the directory import paths resolve against and
+/// the entry file's graph path, or `None` if it lies outside
+/// `project_root`.
+fn split_entry(filename: &str, project_root: Option<&str>) -> Option<(String, String)> {
     let normalised = filename.replace('\\', "/");
-    let (entry_dir, entry_filename) = match normalised.rfind('/') {
-        Some(pos) => (
-            normalised[..pos + 1].to_string(),
-            normalised[pos + 1..].to_string(),
-        ),
-        None => (String::new(), normalised),
+    match project_root {
+        None => Some(match normalised.rfind('/') {
+            Some(pos) => (normalised[..pos + 1].to_string(), normalised[pos + 1..].to_string()),
+            None => (String::new(), normalised),
+        }),
+        Some(root) => import::anchor_to_project_root(root, &normalised),
+    }
+}
+
+/// Passes reads through to the wrapped reader, keeping each file's text
+/// for `CompilationResult::sources`.
+struct SourceRecorder<'a> {
+    inner: &'a dyn FileReader,
+    /// Contents by filesystem path.
+    read: RefCell<BTreeMap<String, String>>,
+}
+
+impl FileReader for SourceRecorder<'_> {
+    fn read_file(&self, fs_path: &str) -> Result<String, import::FileReadError> {
+        let contents = self.inner.read_file(fs_path)?;
+        self.read.borrow_mut().insert(fs_path.to_string(), contents.clone());
+        Ok(contents)
+    }
+
+    fn canonical_filename(&self, dir: &str, filename: &str) -> Option<String> {
+        self.inner.canonical_filename(dir, filename)
+    }
+}
+
+impl SourceRecorder<'_> {
+    /// The recorded files keyed by graph path, with the entry source. A
+    /// file whose casing IMPORT corrected is keyed by the corrected path.
+    fn into_sources(
+        self,
+        filename: &str,
+        source: &str,
+        options: &CompileOptions,
+        graph: Option<&graph::DependencyGraph>,
+    ) -> BTreeMap<String, String> {
+        let Some((entry_dir, entry_path)) = split_entry(filename, options.project_root.as_deref()) else {
+            return BTreeMap::new();
+        };
+        let mut sources: BTreeMap<String, String> = self
+            .read
+            .into_inner()
+            .into_iter()
+            .filter_map(|(fs_path, contents)| Some((fs_path.strip_prefix(&entry_dir)?.to_string(), contents)))
+            .collect();
+        for path in graph.into_iter().flat_map(|graph| graph.nodes.keys()) {
+            if sources.contains_key(path) {
+                continue;
+            }
+            if let Some(read_as) = sources.keys().find(|key| key.eq_ignore_ascii_case(path)).cloned() {
+                let contents = sources.remove(&read_as).unwrap_or_default();
+                sources.insert(path.clone(), contents);
+            }
+        }
+        sources.insert(entry_path, source.to_string());
+        sources
+    }
+}
+
+fn compile_relayed(
+    filename: &str,
+    source: &str,
+    reader: &dyn FileReader,
+    options: &CompileOptions,
+    relay: &observer::Relay,
+) -> CompilationResult {
+    let mut diagnostics = DiagnosticCollector::new();
+
+    // Normalise filename: split into root directory + root-relative path.
+    let (entry_dir, entry_filename) = match split_entry(filename, options.project_root.as_deref()) {
+        Some(split) => split,
+        None => {
+            let normalised = filename.replace('\\', "/");
+            let root = options.project_root.as_deref().unwrap_or_default();
+            let display = normalised.rsplit('/').next().unwrap_or(&normalised).to_string();
+            diagnostics.error(
+                "URD208",
+                format!(
+                    "Entry file '{}' is outside the project root '{}'.",
+                    normalised, root
+                ),
+                span::Span::new(display, 1, 1, 1, 1),
+            );
+            return CompilationResult {
+                success: false,
+                world: None,
+                world_value: None,
+                diagnostics,
+                fact_set: None,
+                property_index: None,
+                definition_index: None,
+                symbol_table: None,
+                symbols: None,
+                graph: None,
+                parts: None,
+                sources: None,
+                output_map: None,
+            };
+        }
     };
 
     // Phase 1: PARSE
-    let entry_ast = match parse::parse(&entry_filename, source, &mut diagnostics) {
+    let entry_ast = relay.phase(Phase::Parse, &mut diagnostics, |diagnostics| {
+        let ast = parse::parse(&entry_filename, source, diagnostics);
+        if let Some(ast) = &ast {
+            relay.file_parsed(ast, diagnostics);
+        }
+        ast
+    });
+    let entry_ast = match entry_ast {
         Some(ast) => ast,
         None => {
             return CompilationResult {
                 success: false,
                 world: None,
+                world_value: None,
                 diagnostics,
                 fact_set: None,
                 property_index: None,
                 definition_index: None,
                 symbol_table: None,
+                symbols: None,
                 graph: None,
+                parts: None,
+                sources: None,
+                output_map: None,
             };
         }
     };
 
     // Phase 2: IMPORT
-    let compilation_unit =
-        import::resolve_imports_with_reader(entry_ast, &entry_dir, &mut diagnostics, reader);
+    let compilation_unit = relay.phase(Phase::Import, &mut diagnostics, |diagnostics| {
+        import::resolve_imports_relayed(
+            entry_ast,
+            &entry_dir,
+            diagnostics,
+            reader,
+            options.missing_import_policy,
+            options.strict_filename_case,
+            &options.defines,
+            relay,
+        )
+    });
 
     // Fatal IMPORT errors (URD203, URD205) prevent LINK.
     if diagnostics.has_errors() {
         return CompilationResult {
             success: false,
             world: None,
+            world_value: None,
             diagnostics,
             fact_set: None,
             property_index: None,
             definition_index: None,
             symbol_table: None,
+            symbols: None,
             graph: None,
+            parts: None,
+            sources: None,
+            output_map: None,
         };
     }
 
     // Phase 3: LINK
-    let linked = link::link(compilation_unit, &mut diagnostics);
+    let linked = relay.phase(Phase::Link, &mut diagnostics, |diagnostics| {
+        let linked = link::link_with_policy(compilation_unit, options.scope_policy, options.link_cache, diagnostics);
+        if options.lenient_entity_refs {
+            diagnostics.downgrade_errors(|d| {
+                d.code == "URD301"
+                    && (d.message.starts_with("Unresolved entity reference")
+                        || d.message.starts_with("Unresolved reference '@"))
+            });
+        }
+        linked
+    });
     let link::LinkedWorld { graph, symbol_table } = linked;
 
-    // Phase 3a: Extract facts (always succeeds when LINK completes)
-    let fact_set = Some(facts::extract_facts(&graph, &symbol_table));
+    let (fact_set, property_index, definition_index) =
+        relay.phase(Phase::Analyze, &mut diagnostics, |diagnostics| {
+            // Phase 3a: Extract facts (always succeeds when LINK completes)
+            let fact_set = Some(facts::extract_facts(&graph, &symbol_table));
 
-    // Phase 3b: Build property dependency index
-    let property_index = fact_set
-        .as_ref()
-        .map(facts::PropertyDependencyIndex::build);
+            // Phase 3b: Build property dependency index
+            let property_index = fact_set
+                .as_ref()
+                .map(facts::PropertyDependencyIndex::build);
 
-    // Phase 3c: Build definition index
-    let definition_index = Some(definition_index::DefinitionIndex::build(&symbol_table));
+            // Phase 3c: Build definition index
+            let definition_index = Some(definition_index::DefinitionIndex::build(&symbol_table));
 
-    // Phase 3d: ANALYZE (FactSet-derived diagnostics, URD600–URD699)
-    if let (Some(ref fs), Some(ref idx)) = (&fact_set, &property_index) {
-        for diag in analyze::analyze(fs, idx) {
-            diagnostics.emit(diag);
-        }
-    }
+            // Phase 3d: ANALYZE (FactSet-derived diagnostics and size
+            // checks, URD600–URD699), less the codes in `world.lints`.
+            if let (Some(ref fs), Some(ref idx)) = (&fact_set, &property_index) {
+                let found = analyze::analyze(fs, idx)
+                    .into_iter()
+                    .chain(size_lint::lint(&graph, &symbol_table, &options.size_limits));
+                for diag in found.filter(|d| !fs.disabled_lints().contains(&d.code)) {
+                    diagnostics.emit(diag);
+                }
+            }
+            (fact_set, property_index, definition_index)
+        });
 
     // Phase 4: VALIDATE
-    validate::validate(&graph, &symbol_table, &mut diagnostics);
+    relay.phase(Phase::Validate, &mut diagnostics, |diagnostics| {
+        validate::validate(&graph, &symbol_table, diagnostics);
+
+        // Phase 4a: TEXT LINT (URD700–URD799). It reads the text EMIT
+        // would write, so it needs a world with no errors.
+        if options.text_lints && !diagnostics.has_errors() {
+            for diag in text_lint::lint(&graph, &symbol_table, text_lint::BUILTIN_RULES) {
+                diagnostics.emit(diag);
+            }
+        }
+    });
+    let symbols = options.keep_symbols.then(|| symbol_table.export());
 
     // Phase 5: EMIT
     if diagnostics.has_errors() {
         return CompilationResult {
             success: false,
             world: None,
+            world_value: None,
             diagnostics,
             fact_set,
             property_index,
             definition_index,
             symbol_table: Some(symbol_table),
+            symbols,
             graph: Some(graph),
+            parts: None,
+            sources: None,
+            output_map: None,
         };
     }
 
-    let json = emit::emit(&graph, &symbol_table, &mut diagnostics);
+    let (world_value, parts, output_map) = relay.phase(Phase::Emit, &mut diagnostics, |diagnostics| {
+        emit::check_format(&graph, &options.emit, diagnostics);
+        let (world_value, output_map) = if options.emit.build_output_map {
+            let (world, map) = emit::emit_mapped(&graph, &symbol_table, diagnostics, &options.emit);
+            ((!options.skip_world).then_some(world), Some(map))
+        } else {
+            let world = (!options.skip_world)
+                .then(|| emit::emit_value(&graph, &symbol_table, diagnostics, &options.emit));
+            (world, None)
+        };
+        let parts = options
+            .emit
+            .split
+            .then(|| emit::emit_split(&graph, &symbol_table, diagnostics, &options.emit));
+        (world_value, parts, output_map)
+    });
 
     CompilationResult {
         success: true,
-        world: Some(json),
+        world: world_value.as_ref().map(CompiledWorld::to_document),
+        world_value,
         diagnostics,
         fact_set,
         property_index,
         definition_index,
         symbol_table: Some(symbol_table),
+        symbols,
         graph: Some(graph),
+        parts,
+        sources: None,
+        output_map,
     }
 }
 
@@ -195,6 +517,14 @@ pub fn compile_source_with_reader(
 /// Not available on WASM targets.
 #[cfg(not(target_arch = "wasm32"))]
 pub fn compile(entry_file: &FilePath) -> CompilationResult {
+    compile_with_options(entry_file, &CompileOptions::default())
+}
+
+/// Compile from a file path with options (reads the file from disk).
+///
+/// Not available on WASM targets.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn compile_with_options(entry_file: &FilePath, options: &CompileOptions) -> CompilationResult {
     let mut diagnostics = DiagnosticCollector::new();
 
     let normalised = entry_file.replace('\\', "/");
@@ -214,15 +544,20 @@ pub fn compile(entry_file: &FilePath) -> CompilationResult {
             return CompilationResult {
                 success: false,
                 world: None,
+                world_value: None,
                 diagnostics,
                 fact_set: None,
                 property_index: None,
                 definition_index: None,
                 symbol_table: None,
+                symbols: None,
                 graph: None,
+                parts: None,
+                sources: None,
+                output_map: None,
             };
         }
     };
 
-    compile_source_with_reader(entry_file, &source, &import::OsFileReader)
+    compile_source_with_options(entry_file, &source, &import::OsFileReader, options)
 }
@@ -1,100 +1,179 @@
 /// Pass 1: Collection — walk every AST and register declarations.
 ///
-/// Processes files in topological order (`ordered_asts`). For each file:
-/// 1. Compute `FileContext` (file stem, visible scope, local sections).
-/// 2. Walk frontmatter: register types, entities; store world config.
-/// 3. Walk content: register locations, sections, choices, exits, sequences, rules.
+/// Pass 1 runs in two steps. [`collect_file()`] walks one file's AST and
+/// records what it declares, in source order, as a [`FileCollection`]: the
+/// symbols, the file's local sections, and the diagnostics that need no
+/// other file. It reads nothing but the AST, so its output can be reused
+/// for as long as the AST is unchanged (see [`super::LinkCache`]).
+///
+/// [`super::merge`] then replays each file's declarations into the global
+/// symbol table in topological order, where duplicates across files are
+/// detected.
 
 use indexmap::IndexMap;
 
 use crate::ast::{
-    Choice, ContentNode, FrontmatterValue, LocationHeading, PhaseHeading, RuleBlock, SectionLabel,
-    SequenceHeading,
+    Choice, ContentNode, FileAst, FrontmatterValue, LocationHeading, LocationHook, PhaseHeading,
+    RegionHeading, RuleBlock, SectionLabel, SequenceHeading,
 };
-use crate::diagnostics::DiagnosticCollector;
-use crate::graph::{file_stem, DependencyGraph};
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::emit::layout::DescriptionZone;
+use crate::graph::file_stem;
 use crate::slugify::slugify;
-use crate::span::FilePath;
+use crate::span::Span;
 use crate::symbol_table::{
-    ActionSymbol, AstNodeRef, ChoiceSymbol, Duplicate, ExitSymbol, LocationSymbol, PhaseSymbol,
-    PropertySymbol, SectionSymbol, SelectDef, SequenceSymbol, SymbolTable, TypeSymbol,
-    EntitySymbol, RuleSymbol,
+    ActionSymbol, AstNodeRef, ChoiceSymbol, DescriptionParagraph, Duplicate, ExitSymbol, LocationHookSymbol,
+    LocationSymbol, PhaseSymbol, RegionSymbol,
+    PropertySymbol, SectionSymbol, SelectDef, SequenceSymbol, TypeSymbol,
+    EntitySymbol, EndingSymbol, RuleSymbol, WorldMode,
 };
 
-use super::{parse_property_type, scalar_to_value, visible_scope, FileContext, WorldConfig};
+use super::{parse_property_type, scalar_to_value, ScopePolicy};
+
+/// Everything one file declares, in source order.
+#[derive(Debug)]
+pub(crate) struct FileCollection {
+    pub file_stem: String,
+    /// local section name → compiled section ID.
+    pub local_sections: IndexMap<String, String>,
+    /// label name → explicit local ID, for sections declared `== name {#id}`.
+    pub renamed_sections: IndexMap<String, String>,
+    pub declarations: Vec<Declaration>,
+}
 
-/// Run collection pass over all files.
-pub(crate) fn collect(
-    graph: &DependencyGraph,
-    ordered_asts: &[FilePath],
-    symbol_table: &mut SymbolTable,
-    world_config: &mut WorldConfig,
-    file_contexts: &mut IndexMap<String, FileContext>,
-    diagnostics: &mut DiagnosticCollector,
-) {
-    for file_path in ordered_asts {
-        let node = match graph.nodes.get(file_path.as_str()) {
-            Some(n) => n,
-            None => continue,
-        };
-
-        let stem = file_stem(file_path);
-        let scope = visible_scope(file_path, graph);
-
-        let mut ctx = FileContext {
-            file_stem: stem.clone(),
-            visible_scope: scope,
-            local_sections: IndexMap::new(),
-        };
-
-        // Tracking state for the content walk.
-        let mut current_location_id: Option<String> = None;
-        let mut current_section_id: Option<String> = None;
-        let mut current_sequence_id: Option<String> = None;
-
-        // Walk frontmatter.
-        if let Some(fm) = &node.ast.frontmatter {
-            for entry in &fm.entries {
-                collect_frontmatter_entry(
-                    &entry.value,
-                    &stem,
-                    file_path,
-                    symbol_table,
-                    world_config,
-                    diagnostics,
-                );
-            }
-        }
+/// One step of a file's collection. The merge applies them in order, so
+/// diagnostics keep the order a single walk would give them.
+#[derive(Debug)]
+pub(crate) enum Declaration {
+    Type(TypeSymbol),
+    Entity(EntitySymbol),
+    WorldStart(String, Span),
+    WorldEntry(String, Span),
+    WorldMode(WorldMode),
+    WorldScope(ScopePolicy),
+    StartInventory(Vec<String>, Span),
+    Region(RegionSymbol),
+    Location {
+        symbol: LocationSymbol,
+        /// Whether the heading gave an explicit `{#id}`.
+        explicit: bool,
+    },
+    Exit {
+        location: String,
+        symbol: ExitSymbol,
+    },
+    Hook {
+        location: String,
+        symbol: LocationHookSymbol,
+    },
+    /// A paragraph of a location's description.
+    Description {
+        location: String,
+        paragraph: DescriptionParagraph,
+    },
+    Section(SectionSymbol),
+    Sequence {
+        symbol: SequenceSymbol,
+        display_name: String,
+    },
+    Phase {
+        sequence: String,
+        symbol: PhaseSymbol,
+        display_name: String,
+    },
+    Choice {
+        section: String,
+        slug: String,
+        symbol: ChoiceSymbol,
+        action: ActionSymbol,
+        /// Nested choices, collected only if this one is registered.
+        nested: Vec<Declaration>,
+    },
+    Rule(RuleSymbol),
+    Ending(EndingSymbol),
+    /// A choice in a location's section, checked against the location's
+    /// exits once every file's exits are known (URD323).
+    ChoiceExitName {
+        location: String,
+        label: String,
+        slug: String,
+        span: Span,
+    },
+    Duplicate(Duplicate),
+    Diagnostic(Diagnostic),
+}
 
-        // Walk content.
-        for (node_index, content) in node.ast.content.iter().enumerate() {
-            collect_content_node(
-                content,
-                node_index,
-                file_path,
-                &stem,
-                &mut ctx,
-                &mut current_location_id,
-                &mut current_section_id,
-                &mut current_sequence_id,
-                symbol_table,
-                diagnostics,
-            );
+/// Records a file's declarations. Its diagnostic methods mirror
+/// `DiagnosticCollector`'s, so diagnostics stay in order with the rest.
+struct Recorder {
+    declarations: Vec<Declaration>,
+}
+
+impl Recorder {
+    fn push(&mut self, declaration: Declaration) {
+        self.declarations.push(declaration);
+    }
+
+    fn error(&mut self, code: &str, message: impl Into<String>, span: Span) {
+        self.emit(Diagnostic {
+            severity: Severity::Error,
+            code: code.to_string(),
+            message: message.into(),
+            span,
+            suggestion: None,
+            fix: None,
+            related: Vec::new(),
+        });
+    }
+
+    fn emit(&mut self, diagnostic: Diagnostic) {
+        self.declarations.push(Declaration::Diagnostic(diagnostic));
+    }
+}
+
+/// Tracking state for the content walk.
+#[derive(Default)]
+struct WalkContext {
+    location_id: Option<String>,
+    section_id: Option<String>,
+    sequence_id: Option<String>,
+    region_id: Option<String>,
+    description: DescriptionZone,
+}
+
+/// Collect one file's declarations from its AST.
+pub(crate) fn collect_file(file_path: &str, ast: &FileAst) -> FileCollection {
+    let mut collection = FileCollection {
+        file_stem: file_stem(file_path),
+        local_sections: IndexMap::new(),
+        renamed_sections: IndexMap::new(),
+        declarations: Vec::new(),
+    };
+    let mut recorder = Recorder { declarations: Vec::new() };
+
+    // Walk frontmatter.
+    if let Some(fm) = &ast.frontmatter {
+        for entry in &fm.entries {
+            collect_frontmatter_entry(&entry.value, &mut recorder);
         }
+    }
 
-        file_contexts.insert(file_path.clone(), ctx);
+    // Walk content.
+    let mut walk = WalkContext::default();
+    for content in &ast.content {
+        collect_content_node(content, file_path, &mut collection, &mut walk, &mut recorder);
     }
+
+    // Checked after every exit is known, including those declared after a
+    // choice in the same location.
+    record_choice_exit_names(&ast.content, &mut recorder);
+
+    collection.declarations = recorder.declarations;
+    collection
 }
 
 /// Process a single frontmatter entry during collection.
-fn collect_frontmatter_entry(
-    value: &FrontmatterValue,
-    _file_stem: &str,
-    _file_path: &str,
-    symbol_table: &mut SymbolTable,
-    world_config: &mut WorldConfig,
-    diagnostics: &mut DiagnosticCollector,
-) {
+fn collect_frontmatter_entry(value: &FrontmatterValue, recorder: &mut Recorder) {
     match value {
         FrontmatterValue::TypeDef(td) => {
             let mut properties = IndexMap::new();
@@ -129,35 +208,13 @@ fn collect_frontmatter_entry(
                 );
             }
 
-            let type_sym = TypeSymbol {
+            recorder.push(Declaration::Type(TypeSymbol {
                 name: td.name.clone(),
                 traits: td.traits.clone(),
                 properties,
+                doc: td.doc.clone(),
                 declared_in: td.span.clone(),
-            };
-
-            if symbol_table.types.contains_key(&td.name) {
-                let first = &symbol_table.types[&td.name];
-                diagnostics.error(
-                    "URD303",
-                    format!(
-                        "Duplicate type name '{}' declared in {}:{} and {}:{}.",
-                        td.name,
-                        first.declared_in.file,
-                        first.declared_in.start_line,
-                        td.span.file,
-                        td.span.start_line,
-                    ),
-                    td.span.clone(),
-                );
-                symbol_table.duplicates.push(Duplicate {
-                    namespace: "types",
-                    name: td.name.clone(),
-                    declared_in: td.span.clone(),
-                });
-            } else {
-                symbol_table.types.insert(td.name.clone(), type_sym);
-            }
+            }));
         }
 
         FrontmatterValue::EntityDecl(ed) => {
@@ -166,36 +223,15 @@ fn collect_frontmatter_entry(
                 overrides.insert(key.clone(), scalar_to_value(val));
             }
 
-            let entity_sym = EntitySymbol {
+            recorder.push(Declaration::Entity(EntitySymbol {
                 id: ed.id.clone(),
                 type_name: ed.type_name.clone(),
                 type_symbol: None,
                 property_overrides: overrides,
+                presence_overrides: IndexMap::new(),
+                doc: ed.doc.clone(),
                 declared_in: ed.span.clone(),
-            };
-
-            if symbol_table.entities.contains_key(&ed.id) {
-                let first = &symbol_table.entities[&ed.id];
-                diagnostics.error(
-                    "URD302",
-                    format!(
-                        "Duplicate entity ID '@{}' declared in {}:{} and {}:{}.",
-                        ed.id,
-                        first.declared_in.file,
-                        first.declared_in.start_line,
-                        ed.span.file,
-                        ed.span.start_line,
-                    ),
-                    ed.span.clone(),
-                );
-                symbol_table.duplicates.push(Duplicate {
-                    namespace: "entities",
-                    name: ed.id.clone(),
-                    declared_in: ed.span.clone(),
-                });
-            } else {
-                symbol_table.entities.insert(ed.id.clone(), entity_sym);
-            }
+            }));
         }
 
         FrontmatterValue::WorldBlock(wb) => {
@@ -203,15 +239,38 @@ fn collect_frontmatter_entry(
                 match key.as_str() {
                     "start" => {
                         if let crate::ast::Scalar::String(s) = val {
-                            *world_config = WorldConfig {
-                                start: Some((s.clone(), wb.span.clone())),
-                                ..std::mem::take(world_config)
-                            };
+                            recorder.push(Declaration::WorldStart(s.clone(), wb.span.clone()));
                         }
                     }
                     "entry" => {
                         if let crate::ast::Scalar::String(s) = val {
-                            world_config.entry = Some((s.clone(), wb.span.clone()));
+                            recorder.push(Declaration::WorldEntry(s.clone(), wb.span.clone()));
+                        }
+                    }
+                    "mode" => {
+                        if let crate::ast::Scalar::String(s) = val {
+                            if let Some(mode) = WorldMode::from_name(s) {
+                                recorder.push(Declaration::WorldMode(mode));
+                            }
+                        }
+                    }
+                    "scope" => {
+                        if let crate::ast::Scalar::String(s) = val {
+                            if let Some(policy) = ScopePolicy::from_name(s) {
+                                recorder.push(Declaration::WorldScope(policy));
+                            }
+                        }
+                    }
+                    "start_inventory" => {
+                        if let crate::ast::Scalar::List(items) = val {
+                            let refs = items
+                                .iter()
+                                .filter_map(|item| match item {
+                                    crate::ast::Scalar::EntityRef(id) => Some(id.clone()),
+                                    _ => None,
+                                })
+                                .collect();
+                            recorder.push(Declaration::StartInventory(refs, wb.span.clone()));
                         }
                     }
                     _ => {}
@@ -219,21 +278,22 @@ fn collect_frontmatter_entry(
             }
         }
 
+        FrontmatterValue::EndingDecl(ed) => {
+            recorder.push(Declaration::Ending(EndingSymbol {
+                id: ed.id.clone(),
+                description: ed.description.clone(),
+                declared_in: ed.span.clone(),
+            }));
+        }
+
         FrontmatterValue::ImportDecl(_) => {
             // Already processed by IMPORT.
         }
 
-        // Map values (from `types:` / `entities:` blocks) — recurse into entries.
+        // Map values (from `types:` / `entities:` / `endings:` blocks) — recurse into entries.
         FrontmatterValue::Map(entries) => {
             for entry in entries {
-                collect_frontmatter_entry(
-                    &entry.value,
-                    _file_stem,
-                    _file_path,
-                    symbol_table,
-                    world_config,
-                    diagnostics,
-                );
+                collect_frontmatter_entry(&entry.value, recorder);
             }
         }
 
@@ -245,82 +305,86 @@ fn collect_frontmatter_entry(
 /// Process a single content node during collection.
 fn collect_content_node(
     node: &ContentNode,
-    _node_index: usize,
     file_path: &str,
-    file_stem: &str,
-    ctx: &mut FileContext,
-    current_location_id: &mut Option<String>,
-    current_section_id: &mut Option<String>,
-    current_sequence_id: &mut Option<String>,
-    symbol_table: &mut SymbolTable,
-    diagnostics: &mut DiagnosticCollector,
+    collection: &mut FileCollection,
+    walk: &mut WalkContext,
+    recorder: &mut Recorder,
 ) {
+    let in_description = walk.description.visit(node);
     match node {
+        ContentNode::RegionHeading(region) => {
+            // Like the start of a file: no location, no section.
+            walk.location_id = None;
+            walk.section_id = None;
+            walk.region_id = collect_region(region, recorder);
+        }
+
         ContentNode::LocationHeading(loc) => {
-            collect_location(loc, file_path, current_location_id, current_section_id, symbol_table, diagnostics);
+            collect_location(loc, walk, recorder);
         }
 
         ContentNode::SectionLabel(sec) => {
-            collect_section(sec, file_path, file_stem, ctx, current_section_id, symbol_table, diagnostics);
+            collect_section(sec, file_path, collection, walk, recorder);
         }
 
         ContentNode::SequenceHeading(seq) => {
-            collect_sequence(seq, current_sequence_id, symbol_table, diagnostics);
+            collect_sequence(seq, walk, recorder);
         }
 
         ContentNode::PhaseHeading(phase) => {
-            collect_phase(phase, current_sequence_id, symbol_table, diagnostics);
+            collect_phase(phase, walk, recorder);
         }
 
         ContentNode::Choice(choice) => {
-            collect_choice(choice, file_path, current_section_id, symbol_table, diagnostics);
+            if let Some(section_id) = &walk.section_id {
+                collect_choice(choice, section_id, recorder);
+            }
         }
 
         ContentNode::ExitDeclaration(exit) => {
-            if current_location_id.is_none() {
-                diagnostics.error(
+            let Some(loc_id) = &walk.location_id else {
+                recorder.error(
                     "URD314",
                     "Exit construct outside of a location context.",
                     exit.span.clone(),
                 );
                 return;
-            }
-            let loc_id = current_location_id.as_ref().unwrap();
-            if let Some(loc_sym) = symbol_table.locations.get_mut(loc_id) {
-                let condition_node = exit
-                    .children
-                    .iter()
-                    .enumerate()
-                    .find(|(_, c)| matches!(c, ContentNode::Condition(_)))
-                    .map(|(i, _)| AstNodeRef {
-                        file: file_path.to_string(),
-                        node_index: i,
-                    });
-                let blocked_message_node = exit
-                    .children
-                    .iter()
-                    .enumerate()
-                    .find(|(_, c)| matches!(c, ContentNode::BlockedMessage(_)))
-                    .map(|(i, _)| AstNodeRef {
-                        file: file_path.to_string(),
-                        node_index: i,
-                    });
-
-                let exit_sym = ExitSymbol {
+            };
+            let condition_node = exit
+                .children
+                .iter()
+                .enumerate()
+                .find(|(_, c)| matches!(c, ContentNode::Condition(_)))
+                .map(|(i, _)| AstNodeRef {
+                    file: file_path.to_string(),
+                    node_index: i,
+                });
+            let blocked_message_node = exit
+                .children
+                .iter()
+                .enumerate()
+                .find(|(_, c)| matches!(c, ContentNode::BlockedMessage(_)))
+                .map(|(i, _)| AstNodeRef {
+                    file: file_path.to_string(),
+                    node_index: i,
+                });
+
+            recorder.push(Declaration::Exit {
+                location: loc_id.clone(),
+                symbol: ExitSymbol {
                     direction: exit.direction.clone(),
                     destination: exit.destination.clone(),
                     resolved_destination: None,
                     condition_node,
                     blocked_message_node,
                     declared_in: exit.span.clone(),
-                };
-                loc_sym.exits.insert(exit.direction.clone(), exit_sym);
-            }
+                },
+            });
         }
 
         ContentNode::EntityPresence(ep) => {
-            if current_location_id.is_none() {
-                diagnostics.error(
+            if walk.location_id.is_none() {
+                recorder.error(
                     "URD314",
                     "Entity presence construct outside of a location context.",
                     ep.span.clone(),
@@ -330,8 +394,26 @@ fn collect_content_node(
             // Raw refs stored; resolution in pass 2.
         }
 
+        ContentNode::LocationHook(hook) => {
+            collect_location_hook(hook, file_path, walk, recorder);
+        }
+
+        ContentNode::Prose(prose) if in_description => {
+            let text = prose.text.trim();
+            if let (Some(loc_id), false) = (&walk.location_id, text.is_empty()) {
+                recorder.push(Declaration::Description {
+                    location: loc_id.clone(),
+                    paragraph: DescriptionParagraph {
+                        text: text.to_string(),
+                        variants: prose.variants.clone(),
+                        span: prose.span.clone(),
+                    },
+                });
+            }
+        }
+
         ContentNode::RuleBlock(rule) => {
-            collect_rule(rule, symbol_table, diagnostics);
+            collect_rule(rule, walk, recorder);
         }
 
         ContentNode::ErrorNode(_) => {
@@ -344,116 +426,225 @@ fn collect_content_node(
     }
 }
 
-fn collect_location(
-    loc: &LocationHeading,
-    _file_path: &str,
-    current_location_id: &mut Option<String>,
-    current_section_id: &mut Option<String>,
-    symbol_table: &mut SymbolTable,
-    diagnostics: &mut DiagnosticCollector,
-) {
-    let id = slugify(&loc.display_name);
+/// Record a region heading, returning the region ID for the locations
+/// that follow it, or `None` if the name slugifies to nothing.
+fn collect_region(region: &RegionHeading, recorder: &mut Recorder) -> Option<String> {
+    let id = region.id();
     if id.is_empty() {
-        diagnostics.error(
+        recorder.error(
             "URD313",
             format!(
                 "Heading '{}' produces an empty ID after slugification.",
-                loc.display_name,
+                region.display_name,
             ),
-            loc.span.clone(),
+            region.span.clone(),
         );
-        return;
+        return None;
     }
+    recorder.push(Declaration::Region(RegionSymbol {
+        id: id.clone(),
+        display_name: region.display_name.clone(),
+        locations: Vec::new(),
+        declared_in: region.span.clone(),
+    }));
+    Some(id)
+}
 
-    if symbol_table.locations.contains_key(&id) {
-        let first = &symbol_table.locations[&id];
-        diagnostics.error(
-            "URD304",
+fn collect_location(loc: &LocationHeading, walk: &mut WalkContext, recorder: &mut Recorder) {
+    let id = loc.id();
+    if let Some(explicit) = &loc.explicit_id {
+        if !is_valid_location_id(explicit) {
+            emit_invalid_explicit_id("location", explicit, &loc.display_name, &slugify(explicit), &loc.span, recorder);
+            // Keep the (unregistered) ID as context so the location's exits
+            // and hooks do not attach to the previous location.
+            walk.location_id = Some(id);
+            walk.section_id = None;
+            return;
+        }
+    }
+    if id.is_empty() {
+        recorder.error(
+            "URD313",
             format!(
-                "Duplicate location ID '{}' — locations '{}' and '{}' both slugify to '{}'.",
-                id,
-                first.display_name,
+                "Heading '{}' produces an empty ID after slugification.",
                 loc.display_name,
-                id,
             ),
             loc.span.clone(),
         );
-        symbol_table.duplicates.push(Duplicate {
-            namespace: "locations",
-            name: id.clone(),
-            declared_in: loc.span.clone(),
-        });
-    } else {
-        symbol_table.locations.insert(
-            id.clone(),
-            LocationSymbol {
-                id: id.clone(),
-                display_name: loc.display_name.clone(),
-                exits: IndexMap::new(),
-                contains: Vec::new(),
-                declared_in: loc.span.clone(),
-            },
-        );
+        return;
     }
 
-    *current_location_id = Some(id);
+    recorder.push(Declaration::Location {
+        symbol: LocationSymbol {
+            id: id.clone(),
+            display_name: loc.display_name.clone(),
+            exits: IndexMap::new(),
+            contains: Vec::new(),
+            description: Vec::new(),
+            on_enter: None,
+            on_exit: None,
+            doc: loc.doc.clone(),
+            region: walk.region_id.clone(),
+            declared_in: loc.span.clone(),
+        },
+        explicit: loc.explicit_id.is_some(),
+    });
+
+    walk.location_id = Some(id);
     // Sections reset at location boundaries is not required by the brief,
     // but section context persists across locations within a file.
-    *current_section_id = None;
+    walk.section_id = None;
+}
+
+fn collect_location_hook(
+    hook: &LocationHook,
+    file_path: &str,
+    walk: &WalkContext,
+    recorder: &mut Recorder,
+) {
+    let Some(loc_id) = &walk.location_id else {
+        recorder.error(
+            "URD314",
+            "Location hook construct outside of a location context.",
+            hook.span.clone(),
+        );
+        return;
+    };
+
+    let node_ref = |i: usize| AstNodeRef {
+        file: file_path.to_string(),
+        node_index: i,
+    };
+    let condition_nodes = hook
+        .children
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| matches!(c, ContentNode::Condition(_) | ContentNode::OrConditionBlock(_)))
+        .map(|(i, _)| node_ref(i))
+        .collect();
+    let effect_nodes = hook
+        .children
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| matches!(c, ContentNode::Effect(_)))
+        .map(|(i, _)| node_ref(i))
+        .collect();
+
+    recorder.push(Declaration::Hook {
+        location: loc_id.clone(),
+        symbol: LocationHookSymbol {
+            kind: hook.kind,
+            condition_nodes,
+            effect_nodes,
+            declared_in: hook.span.clone(),
+        },
+    });
 }
 
 fn collect_section(
     sec: &SectionLabel,
     file_path: &str,
-    file_stem: &str,
-    ctx: &mut FileContext,
-    current_section_id: &mut Option<String>,
-    symbol_table: &mut SymbolTable,
-    diagnostics: &mut DiagnosticCollector,
+    collection: &mut FileCollection,
+    walk: &mut WalkContext,
+    recorder: &mut Recorder,
 ) {
-    let compiled_id = format!("{}/{}", file_stem, sec.name);
+    let local_id = sec.local_id();
+    if let Some(explicit) = &sec.explicit_id {
+        if !is_valid_section_id(explicit) {
+            let suggested = slugify(explicit).replace('-', "_");
+            emit_invalid_explicit_id("section", explicit, &sec.name, &suggested, &sec.span, recorder);
+            walk.section_id = None;
+            return;
+        }
+    }
+    let compiled_id = format!("{}/{}", collection.file_stem, local_id);
 
     // Check for duplicate local name within the file.
-    if ctx.local_sections.contains_key(&sec.name) {
-        diagnostics.error(
+    if collection.local_sections.contains_key(local_id) {
+        recorder.error(
             "URD305",
             format!(
                 "Duplicate section name '{}' in {}. Section names must be unique within a file.",
-                sec.name,
+                local_id,
                 file_path,
             ),
             sec.span.clone(),
         );
-        symbol_table.duplicates.push(Duplicate {
+        recorder.push(Declaration::Duplicate(Duplicate {
             namespace: "sections",
             name: compiled_id.clone(),
             declared_in: sec.span.clone(),
-        });
+        }));
     } else {
-        ctx.local_sections.insert(sec.name.clone(), compiled_id.clone());
+        collection.local_sections.insert(local_id.to_string(), compiled_id.clone());
+        if sec.explicit_id.is_some() {
+            collection.renamed_sections.insert(sec.name.clone(), local_id.to_string());
+        }
 
-        let section_sym = SectionSymbol {
-            local_name: sec.name.clone(),
+        recorder.push(Declaration::Section(SectionSymbol {
+            local_name: local_id.to_string(),
             compiled_id: compiled_id.clone(),
-            file_stem: file_stem.to_string(),
+            file_stem: collection.file_stem.clone(),
             choices: Vec::new(),
+            param: sec.param.clone(),
+            invocations: Vec::new(),
+            doc: sec.doc.clone(),
             declared_in: sec.span.clone(),
-        };
-        symbol_table.sections.insert(compiled_id.clone(), section_sym);
+        }));
     }
 
-    *current_section_id = Some(compiled_id);
+    walk.section_id = Some(compiled_id);
+}
+
+/// Location IDs follow slug rules: lowercase ASCII letters and digits
+/// joined by single hyphens — exactly the strings `slugify` leaves unchanged.
+fn is_valid_location_id(id: &str) -> bool {
+    !id.is_empty() && slugify(id) == id
 }
 
-fn collect_sequence(
-    seq: &SequenceHeading,
-    current_sequence_id: &mut Option<String>,
-    symbol_table: &mut SymbolTable,
-    diagnostics: &mut DiagnosticCollector,
+/// Section IDs follow section name rules: a lowercase letter, then
+/// lowercase letters, digits, or underscores.
+fn is_valid_section_id(id: &str) -> bool {
+    let mut chars = id.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+fn emit_invalid_explicit_id(
+    kind: &str,
+    explicit: &str,
+    declared_name: &str,
+    suggested: &str,
+    span: &Span,
+    recorder: &mut Recorder,
 ) {
+    let rules = if kind == "location" {
+        "Location IDs use lowercase letters, digits, and single hyphens."
+    } else {
+        "Section IDs start with a lowercase letter and use lowercase letters, digits, and underscores."
+    };
+    recorder.emit(Diagnostic {
+        severity: Severity::Error,
+        code: "URD316".to_string(),
+        message: format!(
+            "Invalid explicit {} ID '{{#{}}}' on '{}'. {}",
+            kind, explicit, declared_name, rules,
+        ),
+        span: span.clone(),
+        suggestion: if suggested.is_empty() {
+            None
+        } else {
+            Some(format!("Did you mean '{{#{}}}'?", suggested))
+        },
+        fix: None,
+        related: Vec::new(),
+    });
+}
+
+fn collect_sequence(seq: &SequenceHeading, walk: &mut WalkContext, recorder: &mut Recorder) {
     let id = slugify(&seq.display_name);
     if id.is_empty() {
-        diagnostics.error(
+        recorder.error(
             "URD313",
             format!(
                 "Heading '{}' produces an empty ID after slugification.",
@@ -464,44 +655,21 @@ fn collect_sequence(
         return;
     }
 
-    if symbol_table.sequences.contains_key(&id) {
-        let first = &symbol_table.sequences[&id];
-        diagnostics.error(
-            "URD313",
-            format!(
-                "Duplicate sequence ID '{}' — sequences '{}' and '{}' both slugify to '{}'.",
-                id,
-                first.id,
-                seq.display_name,
-                id,
-            ),
-            seq.span.clone(),
-        );
-        symbol_table.duplicates.push(Duplicate {
-            namespace: "sequences",
-            name: id.clone(),
-            declared_in: seq.span.clone(),
-        });
-    } else {
-        let seq_sym = SequenceSymbol {
+    recorder.push(Declaration::Sequence {
+        symbol: SequenceSymbol {
             id: id.clone(),
             phases: Vec::new(),
             declared_in: seq.span.clone(),
-        };
-        symbol_table.sequences.insert(id.clone(), seq_sym);
-    }
-    *current_sequence_id = Some(id);
+        },
+        display_name: seq.display_name.clone(),
+    });
+    walk.sequence_id = Some(id);
 }
 
-fn collect_phase(
-    phase: &PhaseHeading,
-    current_sequence_id: &mut Option<String>,
-    symbol_table: &mut SymbolTable,
-    diagnostics: &mut DiagnosticCollector,
-) {
+fn collect_phase(phase: &PhaseHeading, walk: &WalkContext, recorder: &mut Recorder) {
     let id = slugify(&phase.display_name);
     if id.is_empty() {
-        diagnostics.error(
+        recorder.error(
             "URD313",
             format!(
                 "Heading '{}' produces an empty ID after slugification.",
@@ -512,36 +680,27 @@ fn collect_phase(
         return;
     }
 
-    if let Some(seq_id) = current_sequence_id {
-        if let Some(seq_sym) = symbol_table.sequences.get_mut(seq_id) {
-            let phase_sym = PhaseSymbol {
+    if let Some(seq_id) = &walk.sequence_id {
+        recorder.push(Declaration::Phase {
+            sequence: seq_id.clone(),
+            symbol: PhaseSymbol {
                 id,
                 advance: if phase.auto { "auto".to_string() } else { "manual".to_string() },
                 action: None,
                 actions: None,
                 rule: None,
+                section: None,
                 declared_in: phase.span.clone(),
-            };
-            seq_sym.phases.push(phase_sym);
-        }
+            },
+            display_name: phase.display_name.clone(),
+        });
     }
 }
 
-fn collect_choice(
-    choice: &Choice,
-    file_path: &str,
-    current_section_id: &mut Option<String>,
-    symbol_table: &mut SymbolTable,
-    diagnostics: &mut DiagnosticCollector,
-) {
-    let section_id = match current_section_id {
-        Some(id) => id.clone(),
-        None => return, // No section context — skip.
-    };
-
+fn collect_choice(choice: &Choice, section_id: &str, recorder: &mut Recorder) {
     let slug = slugify(&choice.label);
     if slug.is_empty() {
-        diagnostics.error(
+        recorder.error(
             "URD313",
             format!(
                 "Heading '{}' produces an empty ID after slugification.",
@@ -554,74 +713,83 @@ fn collect_choice(
 
     let choice_id = format!("{}/{}", section_id, slug);
 
-    // Check for duplicate choice slug within the section.
-    if let Some(sec_sym) = symbol_table.sections.get(&section_id) {
-        if sec_sym.choices.iter().any(|c| c.compiled_id == choice_id) {
-            let first = sec_sym.choices.iter().find(|c| c.compiled_id == choice_id).unwrap();
-            diagnostics.error(
-                "URD306",
-                format!(
-                    "Duplicate choice ID '{}' in section '{}'. Choices '{}' and '{}' produce the same slugified ID.",
-                    choice_id,
-                    section_id,
-                    first.label,
-                    choice.label,
-                ),
-                choice.span.clone(),
-            );
-            symbol_table.duplicates.push(Duplicate {
-                namespace: "choices",
-                name: choice_id,
-                declared_in: choice.span.clone(),
-            });
-            return;
+    // Nested choices are recorded under their parent: a parent the merge
+    // rejects as a duplicate takes them with it.
+    let mut nested = Recorder { declarations: Vec::new() };
+    for child in &choice.content {
+        if let ContentNode::Choice(sub_choice) = child {
+            collect_choice(sub_choice, section_id, &mut nested);
         }
     }
 
-    let choice_sym = ChoiceSymbol {
-        label: choice.label.clone(),
-        compiled_id: choice_id.clone(),
-        sticky: choice.sticky,
-        declared_in: choice.span.clone(),
-    };
-
-    // Attach to the section.
-    if let Some(sec_sym) = symbol_table.sections.get_mut(&section_id) {
-        sec_sym.choices.push(choice_sym);
-    }
-
-    // Create corresponding ActionSymbol.
-    let action_sym = ActionSymbol {
-        id: choice_id.clone(),
-        target: choice.target.clone(),
-        target_type: choice.target_type.clone(),
-        declared_in: choice.span.clone(),
-    };
-
-    if symbol_table.actions.contains_key(&choice_id) {
-        // Duplicate action — standard first-wins rule.
-        symbol_table.duplicates.push(Duplicate {
-            namespace: "actions",
-            name: choice_id,
+    recorder.push(Declaration::Choice {
+        section: section_id.to_string(),
+        slug,
+        symbol: ChoiceSymbol {
+            label: choice.label.clone(),
+            compiled_id: choice_id.clone(),
+            sticky: choice.sticky,
+            once: choice.is_once(),
             declared_in: choice.span.clone(),
-        });
-    } else {
-        symbol_table.actions.insert(choice_id, action_sym);
+        },
+        action: ActionSymbol {
+            id: choice_id,
+            target: choice.target.clone(),
+            target_type: choice.target_type.clone(),
+            resolved_target: None,
+            resolved_target_type: None,
+            declared_in: choice.span.clone(),
+        },
+        nested: nested.declarations,
+    });
+}
+
+/// Record every choice in a location's section for the URD323 check
+/// against the location's exits, the choice counterpart of URD310. Tracks
+/// location and section context the same way the content walk does: a
+/// location heading ends the current section, and choices outside a
+/// section are never registered.
+fn record_choice_exit_names(content: &[ContentNode], recorder: &mut Recorder) {
+    let mut current_location_id: Option<String> = None;
+    let mut in_section = false;
+    for node in content {
+        match node {
+            ContentNode::LocationHeading(loc) => {
+                current_location_id = Some(loc.id());
+                in_section = false;
+            }
+            ContentNode::RegionHeading(_) => {
+                current_location_id = None;
+                in_section = false;
+            }
+            ContentNode::SectionLabel(_) => in_section = true,
+            ContentNode::Choice(choice) if in_section => {
+                if let Some(loc_id) = &current_location_id {
+                    record_choice_exit_name(choice, loc_id, recorder);
+                }
+            }
+            _ => {}
+        }
     }
+}
 
-    // Recurse into nested choices.
+fn record_choice_exit_name(choice: &Choice, loc_id: &str, recorder: &mut Recorder) {
+    recorder.push(Declaration::ChoiceExitName {
+        location: loc_id.to_string(),
+        label: choice.label.clone(),
+        slug: slugify(&choice.label),
+        span: choice.span.clone(),
+    });
     for child in &choice.content {
         if let ContentNode::Choice(sub_choice) = child {
-            collect_choice(sub_choice, file_path, current_section_id, symbol_table, diagnostics);
+            record_choice_exit_name(sub_choice, loc_id, recorder);
         }
     }
 }
 
-fn collect_rule(
-    rule: &RuleBlock,
-    symbol_table: &mut SymbolTable,
-    diagnostics: &mut DiagnosticCollector,
-) {
+/// Record a rule. An indented rule inside a location is scoped to it; one
+/// at column 0 is global wherever it sits.
+fn collect_rule(rule: &RuleBlock, walk: &WalkContext, recorder: &mut Recorder) {
     let select = rule.select.as_ref().map(|s| SelectDef {
         variable: s.variable.clone(),
         from: s.entity_refs.clone(),
@@ -629,34 +797,12 @@ fn collect_rule(
         span: s.span.clone(),
     });
 
-    let rule_sym = RuleSymbol {
+    recorder.push(Declaration::Rule(RuleSymbol {
         id: rule.name.clone(),
         actor: rule.actor.clone(),
         trigger: rule.trigger.clone(),
         select,
+        scope_location: walk.location_id.clone().filter(|_| rule.indent_level > 0),
         declared_in: rule.span.clone(),
-    };
-
-    if symbol_table.rules.contains_key(&rule.name) {
-        let first = &symbol_table.rules[&rule.name];
-        diagnostics.error(
-            "URD302",
-            format!(
-                "Duplicate rule name '{}' declared in {}:{} and {}:{}.",
-                rule.name,
-                first.declared_in.file,
-                first.declared_in.start_line,
-                rule.span.file,
-                rule.span.start_line,
-            ),
-            rule.span.clone(),
-        );
-        symbol_table.duplicates.push(Duplicate {
-            namespace: "rules",
-            name: rule.name.clone(),
-            declared_in: rule.span.clone(),
-        });
-    } else {
-        symbol_table.rules.insert(rule.name.clone(), rule_sym);
-    }
+    }));
 }
@@ -0,0 +1,384 @@
+/// Pass 1, second step: merge per-file collections into the symbol table.
+///
+/// Replays each file's [`Declaration`]s in order against the global
+/// `SymbolTable`. Everything that depends on other files happens here:
+/// duplicate IDs across files, exits and hooks attached to a location
+/// declared elsewhere, and the choice/section and choice/exit name checks.
+/// First declaration wins; later ones are reported and recorded in
+/// `SymbolTable::duplicates`.
+
+use indexmap::IndexMap;
+
+use crate::ast::HookKind;
+use crate::diagnostics::DiagnosticCollector;
+use crate::graph::DependencyGraph;
+use crate::slugify::slugify;
+use crate::symbol_table::{Duplicate, SymbolTable};
+
+use super::collect::{Declaration, FileCollection};
+use super::{visible_scope, FileContext, WorldConfig};
+
+/// Merge one file's collection and record its `FileContext`.
+pub(crate) fn merge(
+    file_path: &str,
+    collection: &FileCollection,
+    graph: &DependencyGraph,
+    symbol_table: &mut SymbolTable,
+    world_config: &mut WorldConfig,
+    file_contexts: &mut IndexMap<String, FileContext>,
+    diagnostics: &mut DiagnosticCollector,
+) {
+    for declaration in &collection.declarations {
+        apply(declaration, symbol_table, world_config, diagnostics);
+    }
+
+    file_contexts.insert(
+        file_path.to_string(),
+        FileContext {
+            file_stem: collection.file_stem.clone(),
+            visible_scope: visible_scope(file_path, graph),
+            local_sections: collection.local_sections.clone(),
+            renamed_sections: collection.renamed_sections.clone(),
+        },
+    );
+}
+
+fn apply(
+    declaration: &Declaration,
+    symbol_table: &mut SymbolTable,
+    world_config: &mut WorldConfig,
+    diagnostics: &mut DiagnosticCollector,
+) {
+    match declaration {
+        Declaration::Type(type_sym) => {
+            if let Some(first) = symbol_table.types.get(&type_sym.name) {
+                let span = &type_sym.declared_in;
+                diagnostics.error(
+                    "URD303",
+                    format!(
+                        "Duplicate type name '{}' declared in {}:{} and {}:{}.",
+                        type_sym.name,
+                        first.declared_in.file,
+                        first.declared_in.start_line,
+                        span.file,
+                        span.start_line,
+                    ),
+                    span.clone(),
+                );
+                symbol_table.duplicates.push(Duplicate {
+                    namespace: "types",
+                    name: type_sym.name.clone(),
+                    declared_in: span.clone(),
+                });
+            } else {
+                symbol_table.types.insert(type_sym.name.clone(), type_sym.clone());
+            }
+        }
+
+        Declaration::Entity(entity_sym) => {
+            if let Some(first) = symbol_table.entities.get(&entity_sym.id) {
+                let span = &entity_sym.declared_in;
+                diagnostics.error(
+                    "URD302",
+                    format!(
+                        "Duplicate entity ID '@{}' declared in {}:{} and {}:{}.",
+                        entity_sym.id,
+                        first.declared_in.file,
+                        first.declared_in.start_line,
+                        span.file,
+                        span.start_line,
+                    ),
+                    span.clone(),
+                );
+                symbol_table.duplicates.push(Duplicate {
+                    namespace: "entities",
+                    name: entity_sym.id.clone(),
+                    declared_in: span.clone(),
+                });
+            } else {
+                symbol_table.entities.insert(entity_sym.id.clone(), entity_sym.clone());
+            }
+        }
+
+        Declaration::WorldStart(start, span) => {
+            world_config.start = Some((start.clone(), span.clone()));
+        }
+        Declaration::WorldEntry(entry, span) => {
+            world_config.entry = Some((entry.clone(), span.clone()));
+        }
+        Declaration::WorldMode(mode) => {
+            symbol_table.world_mode = *mode;
+        }
+        Declaration::WorldScope(policy) => {
+            world_config.scope = Some(*policy);
+        }
+        Declaration::StartInventory(refs, span) => {
+            world_config.start_inventory = Some((refs.clone(), span.clone()));
+        }
+
+        Declaration::Region(region) => {
+            // A later heading for the same region, in this file or another,
+            // adds members to it.
+            symbol_table
+                .regions
+                .entry(region.id.clone())
+                .or_insert_with(|| region.clone());
+        }
+
+        Declaration::Location { symbol, explicit } => {
+            let id = &symbol.id;
+            if let Some(first) = symbol_table.locations.get(id) {
+                // An explicit ID on either heading means the IDs did not
+                // both come from slugification.
+                let both_derived = !explicit && slugify(&first.display_name) == *id;
+                diagnostics.error(
+                    "URD304",
+                    format!(
+                        "Duplicate location ID '{}' — locations '{}' and '{}' both {} '{}'.",
+                        id,
+                        first.display_name,
+                        symbol.display_name,
+                        if both_derived { "slugify to" } else { "resolve to" },
+                        id,
+                    ),
+                    symbol.declared_in.clone(),
+                );
+                symbol_table.duplicates.push(Duplicate {
+                    namespace: "locations",
+                    name: id.clone(),
+                    declared_in: symbol.declared_in.clone(),
+                });
+            } else {
+                symbol_table.locations.insert(id.clone(), symbol.clone());
+                if let Some(region) = symbol.region.as_ref().and_then(|r| symbol_table.regions.get_mut(r)) {
+                    region.locations.push(id.clone());
+                }
+            }
+        }
+
+        Declaration::Exit { location, symbol } => {
+            if let Some(loc_sym) = symbol_table.locations.get_mut(location) {
+                loc_sym.exits.insert(symbol.direction.clone(), symbol.clone());
+            }
+        }
+
+        Declaration::Description { location, paragraph } => {
+            if let Some(loc_sym) = symbol_table.locations.get_mut(location) {
+                loc_sym.description.push(paragraph.clone());
+            }
+        }
+
+        Declaration::Hook { location, symbol } => {
+            let Some(loc_sym) = symbol_table.locations.get_mut(location) else {
+                return;
+            };
+            let slot = match symbol.kind {
+                HookKind::Enter => &mut loc_sym.on_enter,
+                HookKind::Exit => &mut loc_sym.on_exit,
+            };
+            if let Some(first) = slot {
+                let span = &symbol.declared_in;
+                diagnostics.error(
+                    "URD315",
+                    format!(
+                        "Duplicate '{}' block in location '{}' declared at {}:{} and {}:{}.",
+                        symbol.kind.header(),
+                        location,
+                        first.declared_in.file,
+                        first.declared_in.start_line,
+                        span.file,
+                        span.start_line,
+                    ),
+                    span.clone(),
+                );
+                return;
+            }
+            *slot = Some(symbol.clone());
+        }
+
+        Declaration::Section(section) => {
+            symbol_table.sections.insert(section.compiled_id.clone(), section.clone());
+        }
+
+        Declaration::Sequence { symbol, display_name } => {
+            if let Some(first) = symbol_table.sequences.get(&symbol.id) {
+                diagnostics.error(
+                    "URD313",
+                    format!(
+                        "Duplicate sequence ID '{}' — sequences '{}' and '{}' both slugify to '{}'.",
+                        symbol.id,
+                        first.id,
+                        display_name,
+                        symbol.id,
+                    ),
+                    symbol.declared_in.clone(),
+                );
+                symbol_table.duplicates.push(Duplicate {
+                    namespace: "sequences",
+                    name: symbol.id.clone(),
+                    declared_in: symbol.declared_in.clone(),
+                });
+            } else {
+                symbol_table.sequences.insert(symbol.id.clone(), symbol.clone());
+            }
+        }
+
+        Declaration::Phase { sequence, symbol, display_name } => {
+            let Some(seq_sym) = symbol_table.sequences.get_mut(sequence) else {
+                return;
+            };
+            if let Some(first) = seq_sym.phases.iter().find(|p| p.id == symbol.id) {
+                diagnostics.error(
+                    "URD320",
+                    format!(
+                        "Duplicate phase ID '{}' in sequence '{}' — phase '{}' slugifies to the same ID as the phase at line {}.",
+                        symbol.id, sequence, display_name, first.declared_in.start_line,
+                    ),
+                    symbol.declared_in.clone(),
+                );
+                symbol_table.duplicates.push(Duplicate {
+                    namespace: "phases",
+                    name: format!("{}/{}", sequence, symbol.id),
+                    declared_in: symbol.declared_in.clone(),
+                });
+                return;
+            }
+            seq_sym.phases.push(symbol.clone());
+        }
+
+        Declaration::Choice { section, slug, symbol, action, nested } => {
+            let choice_id = &symbol.compiled_id;
+            let span = &symbol.declared_in;
+
+            // Check for duplicate choice slug within the section.
+            if let Some(sec_sym) = symbol_table.sections.get(section) {
+                if let Some(first) = sec_sym.choices.iter().find(|c| c.compiled_id == *choice_id) {
+                    diagnostics.error(
+                        "URD306",
+                        format!(
+                            "Duplicate choice ID '{}' in section '{}'. Choices '{}' and '{}' produce the same slugified ID.",
+                            choice_id,
+                            section,
+                            first.label,
+                            symbol.label,
+                        ),
+                        span.clone(),
+                    );
+                    symbol_table.duplicates.push(Duplicate {
+                        namespace: "choices",
+                        name: choice_id.clone(),
+                        declared_in: span.clone(),
+                    });
+                    return;
+                }
+
+                // A choice named like its own section yields `file/topics/topics`.
+                if *slug == sec_sym.local_name {
+                    diagnostics.warning(
+                        "URD322",
+                        format!(
+                            "Choice '{}' has the same name as its section '{}', giving the ID '{}'. Tools that read the last segment of an ID may take the choice for the section.",
+                            symbol.label, sec_sym.local_name, choice_id,
+                        ),
+                        span.clone(),
+                    );
+                }
+            }
+
+            // Attach to the section.
+            if let Some(sec_sym) = symbol_table.sections.get_mut(section) {
+                sec_sym.choices.push(symbol.clone());
+            }
+
+            if symbol_table.actions.contains_key(choice_id) {
+                // Duplicate action — standard first-wins rule.
+                symbol_table.duplicates.push(Duplicate {
+                    namespace: "actions",
+                    name: choice_id.clone(),
+                    declared_in: span.clone(),
+                });
+            } else {
+                symbol_table.actions.insert(choice_id.clone(), action.clone());
+            }
+
+            for child in nested {
+                apply(child, symbol_table, world_config, diagnostics);
+            }
+        }
+
+        Declaration::Rule(rule_sym) => {
+            if let Some(first) = symbol_table.rules.get(&rule_sym.id) {
+                let span = &rule_sym.declared_in;
+                diagnostics.error(
+                    "URD302",
+                    format!(
+                        "Duplicate rule name '{}' declared in {}:{} and {}:{}.",
+                        rule_sym.id,
+                        first.declared_in.file,
+                        first.declared_in.start_line,
+                        span.file,
+                        span.start_line,
+                    ),
+                    span.clone(),
+                );
+                symbol_table.duplicates.push(Duplicate {
+                    namespace: "rules",
+                    name: rule_sym.id.clone(),
+                    declared_in: span.clone(),
+                });
+            } else {
+                symbol_table.rules.insert(rule_sym.id.clone(), rule_sym.clone());
+            }
+        }
+
+        Declaration::Ending(ending_sym) => {
+            if let Some(first) = symbol_table.endings.get(&ending_sym.id) {
+                let span = &ending_sym.declared_in;
+                diagnostics.error(
+                    "URD302",
+                    format!(
+                        "Duplicate ending name '{}' declared in {}:{} and {}:{}.",
+                        ending_sym.id,
+                        first.declared_in.file,
+                        first.declared_in.start_line,
+                        span.file,
+                        span.start_line,
+                    ),
+                    span.clone(),
+                );
+                symbol_table.duplicates.push(Duplicate {
+                    namespace: "endings",
+                    name: ending_sym.id.clone(),
+                    declared_in: span.clone(),
+                });
+            } else {
+                symbol_table.endings.insert(ending_sym.id.clone(), ending_sym.clone());
+            }
+        }
+
+        // Warn on choices whose slug matches an exit direction of the
+        // enclosing location (URD323), the choice counterpart of URD310.
+        Declaration::ChoiceExitName { location, label, slug, span } => {
+            if let Some(loc_sym) = symbol_table.locations.get(location) {
+                if loc_sym.exits.contains_key(slug) {
+                    diagnostics.warning(
+                        "URD323",
+                        format!(
+                            "Choice '{}' has the same name as exit '{}' in location '{}'. Input matched against '{}' may pick the choice instead of the exit.",
+                            label, slug, loc_sym.id, slug,
+                        ),
+                        span.clone(),
+                    );
+                }
+            }
+        }
+
+        Declaration::Duplicate(duplicate) => {
+            symbol_table.duplicates.push(duplicate.clone());
+        }
+
+        Declaration::Diagnostic(diagnostic) => {
+            diagnostics.emit(diagnostic.clone());
+        }
+    }
+}
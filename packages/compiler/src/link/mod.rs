@@ -5,24 +5,34 @@
 ///
 /// Two sequential passes over all `FileAst`s in topological order:
 ///   Pass 1 (collection): register every declaration in the symbol table.
+///     Each file is collected on its own, then the results are merged in
+///     order; `link_with_cache()` reuses the collections of unchanged files.
+///     Names that collide with the grammar are reported last (`names`).
 ///   Pass 2 (resolution): resolve every reference, fill annotation slots.
 ///
 /// Key guarantees: every declared name registered, every resolvable
 /// reference annotated, duplicates flagged, visible scope enforced.
 ///
+/// Visible scope follows the project's [`ScopePolicy`]: a file's own
+/// declarations and its direct imports by default, everything reachable
+/// through its imports under `Transitive`.
+///
 /// Diagnostic code range: URD300–URD399
 
 pub mod collect;
+pub mod merge;
+pub mod names;
 pub mod resolve;
 
-use std::collections::BTreeSet;
+use std::cell::{Cell, RefCell};
+use std::collections::{BTreeSet, HashMap};
 
 use indexmap::IndexMap;
 
 use crate::ast::Scalar;
-use crate::diagnostics::DiagnosticCollector;
+use crate::diagnostics::{Diagnostic, DiagnosticCollector, Severity};
 use crate::graph::{CompilationUnit, DependencyGraph};
-use crate::span::Span;
+use crate::span::{FilePath, Span};
 use crate::symbol_table::{PropertyType, SymbolTable, Value};
 
 /// The output of the LINK phase: annotated ASTs + populated symbol table.
@@ -36,16 +46,63 @@ pub struct LinkedWorld {
 #[derive(Debug)]
 pub(crate) struct FileContext {
     pub file_stem: String,
-    pub visible_scope: BTreeSet<String>,
+    pub visible_scope: Scope,
     /// local section name → compiled section ID (for jump resolution within a file).
     pub local_sections: IndexMap<String, String>,
+    /// label name → explicit local ID, for sections declared `== name {#id}`.
+    /// Lets references to the label name suggest the explicit ID.
+    pub renamed_sections: IndexMap<String, String>,
 }
 
-/// Stored `world.start` / `world.entry` for resolution in pass 2.
+/// Stored `world.start` / `world.entry` / `world.start_inventory` for
+/// resolution in pass 2.
 #[derive(Debug, Default)]
 pub(crate) struct WorldConfig {
     pub start: Option<(String, Span)>,
     pub entry: Option<(String, Span)>,
+    /// Entity IDs written in `start_inventory`. Items that are not entity
+    /// references are left for VALIDATE to report.
+    pub start_inventory: Option<(Vec<String>, Span)>,
+    /// `world.scope`, which overrides the policy the host passed in.
+    pub scope: Option<ScopePolicy>,
+}
+
+/// Which files' declarations a file's references may resolve to.
+///
+/// Set by the host through `CompileOptions::scope_policy`; a world block's
+/// `scope:` field wins over it. The policy changes resolution only:
+/// duplicate detection is global either way, and the emitted world is the
+/// same whichever policy resolved it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ScopePolicy {
+    /// A file sees its own declarations and those of the files it imports
+    /// directly. A reference into any other file is URD301, with a hint
+    /// naming the missing import.
+    #[default]
+    Direct,
+    /// A file sees every file reachable through its imports. A reference
+    /// that resolves only this way is noted with URD334, naming the file
+    /// that declares it.
+    Transitive,
+}
+
+impl ScopePolicy {
+    /// Parse a policy name: `direct` or `transitive`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "direct" => Some(ScopePolicy::Direct),
+            "transitive" => Some(ScopePolicy::Transitive),
+            _ => None,
+        }
+    }
+
+    /// The policy's name, as [`Self::from_name`] reads it.
+    pub fn name(self) -> &'static str {
+        match self {
+            ScopePolicy::Direct => "direct",
+            ScopePolicy::Transitive => "transitive",
+        }
+    }
 }
 
 /// Result of a scope-checked symbol lookup.
@@ -59,9 +116,34 @@ pub(crate) enum ResolveResult<'a, V> {
 ///
 /// Consumes the `CompilationUnit` from IMPORT. Creates the symbol table
 /// internally. Returns `LinkedWorld` with populated symbol table and
-/// annotated ASTs.
+/// annotated ASTs. Scope follows [`ScopePolicy::Direct`] unless the world
+/// block sets `scope:`.
 pub fn link(
+    compilation_unit: CompilationUnit,
+    diagnostics: &mut DiagnosticCollector,
+) -> LinkedWorld {
+    link_with_policy(compilation_unit, ScopePolicy::Direct, None, diagnostics)
+}
+
+/// LINK, reusing pass 1 collections from `cache` for files whose source is
+/// unchanged since the cache last saw them.
+///
+/// The output and diagnostics are identical to [`link()`]'s. Pass 2 always
+/// runs in full.
+pub fn link_with_cache(
+    compilation_unit: CompilationUnit,
+    cache: &LinkCache,
+    diagnostics: &mut DiagnosticCollector,
+) -> LinkedWorld {
+    link_with_policy(compilation_unit, ScopePolicy::Direct, Some(cache), diagnostics)
+}
+
+/// LINK with `policy` as the scope policy when the world block does not
+/// set `scope:`, reusing collections from `cache` when one is given.
+pub fn link_with_policy(
     mut compilation_unit: CompilationUnit,
+    policy: ScopePolicy,
+    cache: Option<&LinkCache>,
     diagnostics: &mut DiagnosticCollector,
 ) -> LinkedWorld {
     let mut symbol_table = SymbolTable::default();
@@ -69,14 +151,50 @@ pub fn link(
     let mut file_contexts: IndexMap<String, FileContext> = IndexMap::new();
 
     // Pass 1: Collection
-    collect::collect(
-        &compilation_unit.graph,
-        &compilation_unit.ordered_asts,
-        &mut symbol_table,
-        &mut world_config,
-        &mut file_contexts,
-        diagnostics,
-    );
+    for file_path in &compilation_unit.ordered_asts {
+        let Some(node) = compilation_unit.graph.nodes.get(file_path.as_str()) else {
+            continue;
+        };
+        let fresh = || collect::collect_file(file_path, &node.ast);
+        let merge = |collection: &collect::FileCollection,
+                     symbol_table: &mut SymbolTable,
+                     world_config: &mut WorldConfig,
+                     file_contexts: &mut IndexMap<String, FileContext>,
+                     diagnostics: &mut DiagnosticCollector| {
+            merge::merge(
+                file_path,
+                collection,
+                &compilation_unit.graph,
+                symbol_table,
+                world_config,
+                file_contexts,
+                diagnostics,
+            )
+        };
+        match (cache, &node.ast.source_hash) {
+            (Some(cache), Some(hash)) => {
+                cache.with_collection(file_path, hash, fresh, |collection| {
+                    merge(collection, &mut symbol_table, &mut world_config, &mut file_contexts, diagnostics)
+                });
+            }
+            _ => {
+                merge(&fresh(), &mut symbol_table, &mut world_config, &mut file_contexts, diagnostics);
+            }
+        }
+    }
+    if let Some(cache) = cache {
+        cache.retain(&compilation_unit.ordered_asts);
+    }
+    names::check_names(&symbol_table, diagnostics);
+
+    // The world block may sit in any file, so scopes widen only once every
+    // file has been collected.
+    if world_config.scope.unwrap_or(policy) == ScopePolicy::Transitive {
+        let mut scopes = ScopeCache::new();
+        for (file_path, ctx) in &mut file_contexts {
+            ctx.visible_scope.widen(scopes.reachable(file_path, &compilation_unit.graph));
+        }
+    }
 
     // Pass 2: Resolution
     resolve::resolve(
@@ -94,18 +212,196 @@ pub fn link(
     }
 }
 
+/// Pass 1 collections kept between compiles, keyed by file path and the
+/// hash of the source each was collected from.
+///
+/// Hosts that recompile the same project repeatedly, such as the language
+/// server, keep one cache and pass it to [`link_with_cache()`] (or set
+/// `CompileOptions::link_cache`). Files absent from a compile are dropped.
+#[derive(Debug, Default)]
+pub struct LinkCache {
+    entries: RefCell<HashMap<FilePath, (String, collect::FileCollection)>>,
+    hits: Cell<usize>,
+    misses: Cell<usize>,
+}
+
+impl LinkCache {
+    /// An empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Files collected from the cache rather than from their AST, over
+    /// this cache's lifetime.
+    pub fn hits(&self) -> usize {
+        self.hits.get()
+    }
+
+    /// Files collected from their AST, over this cache's lifetime.
+    pub fn misses(&self) -> usize {
+        self.misses.get()
+    }
+
+    /// The number of files held.
+    pub fn len(&self) -> usize {
+        self.entries.borrow().len()
+    }
+
+    /// Returns `true` if no files are held.
+    pub fn is_empty(&self) -> bool {
+        self.entries.borrow().is_empty()
+    }
+
+    /// Pass the file's collection to `merge`, collecting it first unless
+    /// the cached one was collected from the same source.
+    fn with_collection(
+        &self,
+        file_path: &str,
+        hash: &str,
+        collect: impl FnOnce() -> collect::FileCollection,
+        merge: impl FnOnce(&collect::FileCollection),
+    ) {
+        let mut entries = self.entries.borrow_mut();
+        match entries.get(file_path) {
+            Some((cached_hash, _)) if cached_hash == hash => self.hits.set(self.hits.get() + 1),
+            _ => {
+                self.misses.set(self.misses.get() + 1);
+                entries.insert(file_path.to_string(), (hash.to_string(), collect()));
+            }
+        }
+        merge(&entries[file_path].1);
+    }
+
+    fn retain(&self, files: &[FilePath]) {
+        let files: BTreeSet<&str> = files.iter().map(String::as_str).collect();
+        self.entries.borrow_mut().retain(|path, _| files.contains(path.as_str()));
+    }
+}
+
+/// Reachable sets through the import graph, computed once per file.
+///
+/// A file's set is itself plus the sets of the files it imports, so each
+/// file is walked once however many importers reach it.
+#[derive(Debug, Default)]
+pub struct ScopeCache {
+    reachable: HashMap<FilePath, BTreeSet<FilePath>>,
+}
+
+impl ScopeCache {
+    /// An empty cache.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `file_path` and every file reachable from it through imports, as
+    /// [`reachable_files()`] computes it.
+    pub fn reachable(&mut self, file_path: &str, graph: &DependencyGraph) -> &BTreeSet<FilePath> {
+        if !self.reachable.contains_key(file_path) {
+            // IMPORT rejects cycles; the placeholder stops one regardless.
+            self.reachable.insert(file_path.to_string(), BTreeSet::new());
+            let mut files = BTreeSet::from([file_path.to_string()]);
+            for imp in graph.nodes.get(file_path).into_iter().flat_map(|n| &n.imports) {
+                if graph.nodes.contains_key(imp.as_str()) {
+                    files.extend(self.reachable(imp, graph).iter().cloned());
+                }
+            }
+            self.reachable.insert(file_path.to_string(), files);
+        }
+        &self.reachable[file_path]
+    }
+}
+
+/// `file_path` and every file reachable from it through imports, walked
+/// afresh. [`ScopeCache::reachable()`] gives the same set without
+/// rewalking shared imports.
+pub fn reachable_files(file_path: &str, graph: &DependencyGraph) -> BTreeSet<FilePath> {
+    let mut files = BTreeSet::from([file_path.to_string()]);
+    let mut pending = vec![file_path.to_string()];
+    while let Some(path) = pending.pop() {
+        for imp in graph.nodes.get(path.as_str()).into_iter().flat_map(|n| &n.imports) {
+            if graph.nodes.contains_key(imp.as_str()) && files.insert(imp.clone()) {
+                pending.push(imp.clone());
+            }
+        }
+    }
+    files
+}
+
+/// The files whose declarations one file's references may resolve to.
+#[derive(Debug)]
+pub(crate) struct Scope {
+    /// The file itself and its direct imports.
+    direct: BTreeSet<FilePath>,
+    /// Files reachable only through other imports. Empty unless the scope
+    /// was widened for [`ScopePolicy::Transitive`].
+    transitive: BTreeSet<FilePath>,
+    widened: bool,
+    /// Lookups that resolved into `transitive`, for URD334.
+    transitive_hits: RefCell<Vec<TransitiveHit>>,
+}
+
+#[derive(Debug, PartialEq)]
+struct TransitiveHit {
+    name: String,
+    declared_in_file: FilePath,
+    span: Span,
+}
+
+impl Scope {
+    /// Extend the scope to `reachable` for [`ScopePolicy::Transitive`].
+    fn widen(&mut self, reachable: &BTreeSet<FilePath>) {
+        self.transitive = reachable.difference(&self.direct).cloned().collect();
+        self.widened = true;
+    }
+
+    /// The URD301 hint for `shown`, a name declared in a file outside the
+    /// scope of `file_path`.
+    pub(crate) fn not_visible_hint(&self, shown: &str, declared_in_file: &str, file_path: &str) -> String {
+        if self.widened {
+            format!(
+                "'{}' is declared in {}, which no import chain from {} reaches.",
+                shown, declared_in_file, file_path,
+            )
+        } else {
+            format!(
+                "'{}' is declared in {} but {} is not imported by {}.",
+                shown, declared_in_file, declared_in_file, file_path,
+            )
+        }
+    }
+
+    /// Emit URD334 for each reference that resolved only through a
+    /// transitive import since the last call.
+    pub(crate) fn report_transitive_hits(&self, file_path: &str, diagnostics: &mut DiagnosticCollector) {
+        for hit in self.transitive_hits.take() {
+            diagnostics.emit(Diagnostic {
+                severity: Severity::Info,
+                code: "URD334".to_string(),
+                message: format!(
+                    "'{}' resolves only through a transitive import. It is declared in {}, which {} does not import directly.",
+                    hit.name, hit.declared_in_file, file_path,
+                ),
+                span: hit.span,
+                suggestion: Some(format!("Import {} to make the dependency explicit.", hit.declared_in_file)),
+                fix: None,
+                related: Vec::new(),
+            });
+        }
+    }
+}
+
 // ── Shared helpers ──
 
-/// Compute the visible scope for a file: {self} ∪ {direct imports}.
-pub(crate) fn visible_scope(file_path: &str, graph: &DependencyGraph) -> BTreeSet<String> {
-    let mut scope = BTreeSet::new();
-    scope.insert(file_path.to_string());
+/// Compute the direct visible scope for a file: {self} ∪ {direct imports}.
+pub(crate) fn visible_scope(file_path: &str, graph: &DependencyGraph) -> Scope {
+    let mut direct = BTreeSet::new();
+    direct.insert(file_path.to_string());
     if let Some(node) = graph.nodes.get(file_path) {
         for imp in &node.imports {
-            scope.insert(imp.clone());
+            direct.insert(imp.clone());
         }
     }
-    scope
+    Scope { direct, transitive: BTreeSet::new(), widened: false, transitive_hits: RefCell::new(Vec::new()) }
 }
 
 /// Scope-checked lookup in a symbol namespace.
@@ -113,16 +409,31 @@ pub(crate) fn visible_scope(file_path: &str, graph: &DependencyGraph) -> BTreeSe
 /// Returns `Found` if the name exists and its declaring file is in the visible scope.
 /// Returns `NotVisible` if the name exists but is out of scope.
 /// Returns `NotFound` if the name does not exist in the namespace.
+///
+/// A name found only through a transitive import is recorded against
+/// `span`, the referencing node, for URD334.
 pub(crate) fn resolve_in_scope<'a, V>(
     name: &str,
     namespace: &'a IndexMap<String, V>,
     declared_in_file: impl Fn(&V) -> &str,
-    visible_scope: &BTreeSet<String>,
+    visible_scope: &Scope,
+    span: &Span,
 ) -> ResolveResult<'a, V> {
     match namespace.get(name) {
         Some(symbol) => {
             let file = declared_in_file(symbol);
-            if visible_scope.contains(file) {
+            if visible_scope.transitive.contains(file) {
+                let hit = TransitiveHit {
+                    name: name.to_string(),
+                    declared_in_file: file.to_string(),
+                    span: span.clone(),
+                };
+                let mut hits = visible_scope.transitive_hits.borrow_mut();
+                if !hits.contains(&hit) {
+                    hits.push(hit);
+                }
+                ResolveResult::Found(symbol)
+            } else if visible_scope.direct.contains(file) {
                 ResolveResult::Found(symbol)
             } else {
                 ResolveResult::NotVisible {
@@ -163,10 +474,18 @@ pub fn edit_distance(a: &str, b: &str) -> usize {
     prev[b_len]
 }
 
+/// Namespaces larger than this get no suggestions: scanning every key for
+/// each unresolved name would cost more than the hint is worth.
+pub(crate) const MAX_SUGGESTION_CANDIDATES: usize = 10_000;
+
 /// Find the best suggestion from a namespace for a misspelled name.
 /// Returns the first candidate with edit distance ≤ 2, preferring smallest distance,
-/// then insertion order (deterministic via IndexMap).
+/// then insertion order (deterministic via IndexMap). `None` for a namespace
+/// of more than [`MAX_SUGGESTION_CANDIDATES`] keys.
 pub(crate) fn find_suggestion<V>(name: &str, namespace: &IndexMap<String, V>) -> Option<String> {
+    if namespace.len() > MAX_SUGGESTION_CANDIDATES {
+        return None;
+    }
     let mut best: Option<(String, usize)> = None;
     for key in namespace.keys() {
         let dist = edit_distance(name, key);
@@ -179,6 +498,15 @@ pub(crate) fn find_suggestion<V>(name: &str, namespace: &IndexMap<String, V>) ->
     best.map(|(name, _)| name)
 }
 
+/// Like [`find_suggestion()`], but `None` when another candidate is just as
+/// close, so the suggestion is safe to apply without asking.
+pub(crate) fn find_unique_suggestion<V>(name: &str, namespace: &IndexMap<String, V>) -> Option<String> {
+    let best = find_suggestion(name, namespace)?;
+    let dist = edit_distance(name, &best);
+    let ties = namespace.keys().filter(|key| edit_distance(name, key) == dist).count();
+    (ties == 1).then_some(best)
+}
+
 /// Parse a property type string into a `PropertyType` enum.
 pub(crate) fn parse_property_type(s: &str) -> PropertyType {
     match s {
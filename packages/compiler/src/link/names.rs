@@ -0,0 +1,122 @@
+/// Pass 1, last step: names that collide with the condition and effect
+/// grammar.
+///
+/// Runs once over the merged symbol table, before resolution. Lowered
+/// conditions and effects name entities, locations, and properties bare
+/// (`rusty_key.container == cell`), so a name that reads as part of the
+/// grammar makes the string ambiguous for runtimes:
+///
+/// | Code   | Severity | Collision |
+/// |--------|----------|-----------|
+/// | URD330 | Error    | A property named in [`RESERVED_PROPERTY_NAMES`]. |
+/// | URD331 | Error    | An entity ID holding `.`, `/`, or whitespace, or named in [`RESERVED_ENTITY_IDS`]. |
+/// | URD332 | Warning  | A type named like a property type in [`PROPERTY_TYPE_KEYWORDS`], ignoring case. |
+/// | URD333 | Warning  | A location ID equal to an entity ID. |
+///
+/// `player` is not reserved: `@player` is the player entity.
+
+use crate::diagnostics::{Diagnostic, DiagnosticCollector, RelatedInfo, Severity};
+use crate::symbol_table::SymbolTable;
+
+/// Property names the grammar gives a meaning of its own, with that
+/// meaning. Add new projections here.
+pub const RESERVED_PROPERTY_NAMES: &[(&str, &str)] = &[
+    ("container", "`@entity.container` is where the entity is"),
+    ("exhausted", "`name.exhausted` tests whether a section's choices are used up"),
+];
+
+/// Words an entity ID may not be, with what each means.
+pub const RESERVED_ENTITY_IDS: &[(&str, &str)] = &[
+    ("here", "the player's current location"),
+    ("end", "the end of a conversation, as a jump target"),
+    ("exit", "an exit of the current location, as in `-> exit:north`"),
+    ("world", "the world itself"),
+    ("not", "negation in conditions"),
+];
+
+/// The property type names a type definition accepts (`bool`, `boolean`,
+/// and so on).
+pub const PROPERTY_TYPE_KEYWORDS: &[&str] = &[
+    "bool", "boolean", "int", "integer", "num", "number", "str", "string", "enum", "ref", "list",
+];
+
+/// Report every reserved-name collision in declaration order: types and
+/// their properties, then entities, then locations.
+pub(crate) fn check_names(symbol_table: &SymbolTable, diagnostics: &mut DiagnosticCollector) {
+    for type_sym in symbol_table.types.values() {
+        let lower = type_sym.name.to_ascii_lowercase();
+        if PROPERTY_TYPE_KEYWORDS.contains(&lower.as_str()) {
+            diagnostics.warning(
+                "URD332",
+                format!(
+                    "Type '{}' has the name of the property type '{}'. A declaration such as `ref({})` or `list({})` reads as either.",
+                    type_sym.name, lower, type_sym.name, type_sym.name,
+                ),
+                type_sym.declared_in.clone(),
+            );
+        }
+        for prop in type_sym.properties.values() {
+            if let Some((_, meaning)) = RESERVED_PROPERTY_NAMES.iter().find(|(name, _)| *name == prop.name) {
+                diagnostics.emit(Diagnostic {
+                    severity: Severity::Error,
+                    code: "URD330".to_string(),
+                    message: format!(
+                        "Property '{}' on type '{}' uses a reserved name: {}, so conditions and effects could not tell the two apart.",
+                        prop.name, type_sym.name, meaning,
+                    ),
+                    span: prop.declared_in.clone(),
+                    suggestion: Some(format!("Rename the property. '{}' always has its built-in meaning.", prop.name)),
+                    fix: None,
+                    related: Vec::new(),
+                });
+            }
+        }
+    }
+
+    for entity in symbol_table.entities.values() {
+        if let Some(problem) = entity_id_problem(&entity.id) {
+            diagnostics.error(
+                "URD331",
+                format!("Entity ID '@{}' {}.", entity.id, problem),
+                entity.declared_in.clone(),
+            );
+        }
+    }
+
+    for location in symbol_table.locations.values() {
+        let Some(entity) = symbol_table.entities.get(&location.id) else {
+            continue;
+        };
+        diagnostics.emit(Diagnostic {
+            severity: Severity::Warning,
+            code: "URD333".to_string(),
+            message: format!(
+                "Location '{}' has the same ID as entity '@{}'. Lowered conditions and effects name both bare (`player.container == {}`), so a runtime cannot tell which is meant.",
+                location.id, entity.id, location.id,
+            ),
+            span: location.declared_in.clone(),
+            suggestion: Some("Give the location an explicit ID (`{#id}`) or rename the entity.".to_string()),
+            fix: None,
+            related: vec![RelatedInfo {
+                message: format!(
+                    "Entity '@{}' declared at {}:{}",
+                    entity.id, entity.declared_in.file, entity.declared_in.start_line,
+                ),
+                span: entity.declared_in.clone(),
+            }],
+        });
+    }
+}
+
+/// Why `id` cannot be an entity ID, completing "Entity ID '@id' ...".
+fn entity_id_problem(id: &str) -> Option<String> {
+    if let Some((_, meaning)) = RESERVED_ENTITY_IDS.iter().find(|(word, _)| *word == id) {
+        return Some(format!("is a reserved word: '{}' means {}", id, meaning));
+    }
+    let found = id.chars().find(|c| *c == '.' || *c == '/' || c.is_whitespace())?;
+    Some(match found {
+        '.' => "contains '.', which conditions and effects read as a property access".to_string(),
+        '/' => "contains '/', which compiled IDs use to join a file and a section".to_string(),
+        _ => "contains whitespace, which ends a reference in conditions and effects".to_string(),
+    })
+}
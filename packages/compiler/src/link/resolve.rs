@@ -5,21 +5,62 @@
 /// 2. Resolve frontmatter references (entity types, property overrides, world config).
 /// 3. Resolve content references (entity refs, property accesses, jumps, exits, etc.).
 
-use std::collections::BTreeSet;
-
 use indexmap::IndexMap;
 
 use crate::ast::{
-    Annotation, ConditionExpr, ContainerKind, ContentNode, DestinationKind, EffectType,
-    FrontmatterValue,
+    Annotation, Choice, ConditionExpr, ContainerKind, ContentNode, DestinationKind, EffectType,
+    EntitySpeech, FrontmatterValue, Scalar, SectionLabel,
 };
-use crate::diagnostics::{DiagnosticCollector, Diagnostic, Severity};
+use crate::diagnostics::{DiagnosticCollector, Diagnostic, Fix, RelatedInfo, Severity};
 use crate::graph::DependencyGraph;
 use crate::slugify::slugify;
 use crate::span::{FilePath, Span};
-use crate::symbol_table::SymbolTable;
+use crate::symbol_table::{SectionInvocation, SymbolTable, TypeSymbol};
+
+use super::{
+    edit_distance, find_suggestion, find_unique_suggestion, resolve_in_scope, scalar_to_value, FileContext, ResolveResult,
+    Scope, WorldConfig,
+};
+
+/// The name bound in the body being resolved: the parameter of a
+/// parameterised section, or the select variable of a rule (`$door`).
+/// References to it resolve against its type, not an entity.
+struct TemplateScope {
+    /// The parameterised section's compiled ID. `None` for a rule.
+    section_id: Option<String>,
+    param_name: String,
+    /// The parameter's type, or the type the select candidates share,
+    /// when it resolved.
+    param_type: Option<String>,
+}
+
+impl TemplateScope {
+    fn binds(&self, entity_ref: &str) -> bool {
+        self.param_name == entity_ref
+    }
+}
+
+/// The speaker a quoted line inherits, tracked through a file's content.
+enum PromptSpeaker {
+    /// Not in a section.
+    Outside,
+    /// In the named section, whose prompt has no speech line.
+    Missing(String),
+    /// The entity the prompt's first speech line resolved to. `None` when
+    /// it did not resolve, which LINK has already reported.
+    Speaker(Option<String>),
+}
 
-use super::{find_suggestion, resolve_in_scope, FileContext, ResolveResult, WorldConfig};
+/// The ID a `world.start` or `world.entry` value names: the value itself
+/// when it is an ID, otherwise its slug, so `start: cell` and `start: Cell`
+/// both name `# Cell`.
+fn resolve_world_id<V>(value: &str, namespace: &IndexMap<String, V>) -> Option<String> {
+    if namespace.contains_key(value) {
+        return Some(value.to_string());
+    }
+    let slug = slugify(value);
+    namespace.contains_key(&slug).then_some(slug)
+}
 
 /// Run resolution pass over all files.
 pub(crate) fn resolve(
@@ -32,14 +73,31 @@ pub(crate) fn resolve(
 ) {
     // Resolve world.start and world.entry — store results for VALIDATE.
     if let Some((start_val, _span)) = &world_config.start {
-        let slug = slugify(start_val);
-        if symbol_table.locations.contains_key(&slug) {
-            symbol_table.world_start = Some(slug);
-        }
+        symbol_table.world_start = resolve_world_id(start_val, &symbol_table.locations);
     }
     if let Some((entry_val, _span)) = &world_config.entry {
-        if symbol_table.sequences.contains_key(entry_val) {
-            symbol_table.world_entry = Some(entry_val.clone());
+        symbol_table.world_entry = resolve_world_id(entry_val, &symbol_table.sequences);
+    }
+    // Resolve world.start_inventory against the world block file's scope.
+    if let Some((refs, span)) = &world_config.start_inventory {
+        if let Some(ctx) = file_contexts.get(span.file.as_str()) {
+            for entity_ref in refs {
+                let resolved = resolve_entity_ref_value(
+                    entity_ref,
+                    span,
+                    &span.file,
+                    &ctx.visible_scope,
+                    None,
+                    symbol_table,
+                    diagnostics,
+                );
+                if let Some(entity_id) = resolved {
+                    if !symbol_table.world_start_inventory.contains(&entity_id) {
+                        symbol_table.world_start_inventory.push(entity_id);
+                    }
+                }
+            }
+            ctx.visible_scope.report_transitive_hits(&span.file, diagnostics);
         }
     }
 
@@ -67,19 +125,46 @@ pub(crate) fn resolve(
             }
         }
 
-        // Resolve content — track location context.
+        // Resolve content — track location, template and prompt context.
         let mut current_location_id: Option<String> = None;
+        let mut template: Option<TemplateScope> = None;
+        let mut prompt = PromptSpeaker::Outside;
+        let mut in_prompt = false;
 
         for content in &mut node.ast.content {
+            match content {
+                ContentNode::SectionLabel(sec) => {
+                    template = resolve_section_param(sec, file_path, ctx, symbol_table, diagnostics);
+                    prompt = PromptSpeaker::Missing(sec.local_id().to_string());
+                    in_prompt = true;
+                }
+                ContentNode::LocationHeading(_) | ContentNode::RegionHeading(_) => {
+                    template = None;
+                    prompt = PromptSpeaker::Outside;
+                }
+                ContentNode::SequenceHeading(_) | ContentNode::PhaseHeading(_) => prompt = PromptSpeaker::Outside,
+                ContentNode::Choice(_) => in_prompt = false,
+                _ => {}
+            }
             resolve_content_node(
                 content,
                 file_path,
                 ctx,
                 &mut current_location_id,
+                template.as_ref(),
+                &prompt,
                 symbol_table,
                 diagnostics,
             );
+            // The prompt's first speech line lends its speaker to quoted lines.
+            if let ContentNode::EntitySpeech(speech) = content {
+                if in_prompt && !speech.inherits_speaker() && matches!(prompt, PromptSpeaker::Missing(_)) {
+                    let resolved = speech.annotation.as_ref().and_then(|a| a.resolved_entity.clone());
+                    prompt = PromptSpeaker::Speaker(resolved);
+                }
+            }
         }
+        ctx.visible_scope.report_transitive_hits(file_path, diagnostics);
     }
 }
 
@@ -87,7 +172,7 @@ pub(crate) fn resolve(
 fn resolve_frontmatter_entry(
     value: &mut FrontmatterValue,
     file_path: &str,
-    visible_scope: &BTreeSet<String>,
+    visible_scope: &Scope,
     symbol_table: &mut SymbolTable,
     diagnostics: &mut DiagnosticCollector,
 ) {
@@ -99,6 +184,7 @@ fn resolve_frontmatter_entry(
                 &symbol_table.types,
                 |ts| ts.declared_in.file.as_str(),
                 visible_scope,
+                &ed.span,
             ) {
                 ResolveResult::Found(ts) => {
                     // Store the type name in the EntitySymbol.
@@ -122,10 +208,8 @@ fn resolve_frontmatter_entry(
                             ed.type_name,
                         ),
                         span: ed.span.clone(),
-                        suggestion: Some(format!(
-                            "'{}' is declared in {} but {} is not imported by {}.",
-                            ed.type_name, declared_in_file, declared_in_file, file_path,
-                        )),
+                        suggestion: Some(visible_scope.not_visible_hint(&ed.type_name, &declared_in_file, file_path)),
+                        fix: None,
                         related: Vec::new(),
                     });
                     false
@@ -140,6 +224,7 @@ fn resolve_frontmatter_entry(
                         ),
                         span: ed.span.clone(),
                         suggestion: None,
+                        fix: None,
                         related: Vec::new(),
                     };
                     if let Some(suggestion) = find_suggestion(&ed.type_name, &symbol_table.types) {
@@ -159,14 +244,7 @@ fn resolve_frontmatter_entry(
                         if !ts.properties.contains_key(prop_name)
                             && !IMPLICIT_PROPERTIES.contains(&prop_name.as_str())
                         {
-                            diagnostics.error(
-                                "URD308",
-                                format!(
-                                    "Property '{}' does not exist on type '{}'.",
-                                    prop_name, type_name,
-                                ),
-                                ed.span.clone(),
-                            );
+                            diagnostics.emit(unknown_property(prop_name, ts, &ed.span));
                         }
                     }
                 }
@@ -196,17 +274,25 @@ fn resolve_content_node(
     file_path: &str,
     ctx: &FileContext,
     current_location_id: &mut Option<String>,
+    template: Option<&TemplateScope>,
+    prompt: &PromptSpeaker,
     symbol_table: &mut SymbolTable,
     diagnostics: &mut DiagnosticCollector,
 ) {
     match node {
         ContentNode::LocationHeading(loc) => {
-            let id = slugify(&loc.display_name);
+            let id = loc.id();
             if !id.is_empty() {
                 *current_location_id = Some(id);
             }
         }
 
+        ContentNode::RegionHeading(_) => *current_location_id = None,
+
+        ContentNode::EntitySpeech(speech) if speech.inherits_speaker() => {
+            resolve_inherited_speaker(speech, prompt, diagnostics);
+        }
+
         ContentNode::EntitySpeech(speech) => {
             resolve_entity_ref(
                 &speech.entity_ref,
@@ -214,6 +300,7 @@ fn resolve_content_node(
                 &speech.span,
                 file_path,
                 &ctx.visible_scope,
+                template,
                 symbol_table,
                 diagnostics,
             );
@@ -226,6 +313,7 @@ fn resolve_content_node(
                 &sd.span,
                 file_path,
                 &ctx.visible_scope,
+                template,
                 symbol_table,
                 diagnostics,
             );
@@ -244,6 +332,7 @@ fn resolve_content_node(
                     &ep.span,
                     file_path,
                     &ctx.visible_scope,
+                    None,
                     symbol_table,
                     diagnostics,
                 );
@@ -258,6 +347,9 @@ fn resolve_content_node(
                             loc_sym.contains.push(entity_id.clone());
                         }
                     }
+                    if let Some(overrides) = ep.property_overrides.get(i) {
+                        merge_presence_overrides(entity_id, &loc_id, overrides, &ep.span, symbol_table, diagnostics);
+                    }
                 }
                 if i < ep.annotations.len() {
                     ep.annotations[i] = annotation;
@@ -267,20 +359,31 @@ fn resolve_content_node(
 
         ContentNode::Choice(choice) => {
             // Resolve entity target if present.
-            if let Some(target) = &choice.target {
-                let resolved = resolve_entity_ref_value(
+            let resolved_target = choice.target.as_ref().and_then(|target| {
+                resolve_entity_ref_value(
                     target,
                     &choice.span,
                     file_path,
                     &ctx.visible_scope,
+                    template,
                     symbol_table,
                     diagnostics,
-                );
-                if let Some(entity_id) = resolved {
-                    choice.annotation = Some(Annotation {
-                        resolved_entity: Some(entity_id),
-                        ..Default::default()
-                    });
+                )
+            });
+            let resolved_target_type = choice
+                .target_type
+                .as_ref()
+                .and_then(|type_name| resolve_target_type(type_name, choice, file_path, ctx, symbol_table, diagnostics));
+            if resolved_target.is_some() || resolved_target_type.is_some() {
+                choice.annotation = Some(Annotation {
+                    resolved_entity: resolved_target.clone(),
+                    resolved_type: resolved_target_type.clone(),
+                    ..Default::default()
+                });
+                // The choice's action shares its span.
+                if let Some(action) = symbol_table.actions.values_mut().find(|a| a.declared_in == choice.span) {
+                    action.resolved_target = resolved_target;
+                    action.resolved_target_type = resolved_target_type;
                 }
             }
 
@@ -291,6 +394,8 @@ fn resolve_content_node(
                     file_path,
                     ctx,
                     current_location_id,
+                    template,
+                    prompt,
                     symbol_table,
                     diagnostics,
                 );
@@ -302,6 +407,7 @@ fn resolve_content_node(
                 &mut cond.expr,
                 file_path,
                 ctx,
+                template,
                 symbol_table,
                 diagnostics,
             );
@@ -313,6 +419,7 @@ fn resolve_content_node(
                     expr,
                     file_path,
                     ctx,
+                    template,
                     symbol_table,
                     diagnostics,
                 );
@@ -326,6 +433,7 @@ fn resolve_content_node(
                 &effect.span,
                 file_path,
                 ctx,
+                template,
                 symbol_table,
                 diagnostics,
             );
@@ -337,6 +445,7 @@ fn resolve_content_node(
                 file_path,
                 ctx,
                 current_location_id,
+                template,
                 symbol_table,
                 diagnostics,
             );
@@ -355,6 +464,7 @@ fn resolve_content_node(
                     &symbol_table.locations,
                     |ls| ls.declared_in.file.as_str(),
                     &ctx.visible_scope,
+                    &exit.span,
                 ) {
                     ResolveResult::Found(_) => {
                         let loc_id = current_location_id.as_ref().unwrap();
@@ -377,22 +487,26 @@ fn resolve_content_node(
                                 exit.destination,
                             ),
                             span: exit.span.clone(),
-                            suggestion: Some(format!(
-                                "'{}' is declared in {} but {} is not imported by {}.",
-                                exit.destination, declared_in_file, declared_in_file, file_path,
-                            )),
+                            suggestion: Some(ctx.visible_scope.not_visible_hint(&exit.destination, &declared_in_file, file_path)),
+                            fix: None,
                             related: Vec::new(),
                         });
                     }
                     ResolveResult::NotFound => {
-                        diagnostics.error(
-                            "URD312",
-                            format!(
+                        diagnostics.emit(Diagnostic {
+                            severity: Severity::Error,
+                            code: "URD312".to_string(),
+                            message: format!(
                                 "Exit destination '{}' does not resolve to any known location.",
                                 exit.destination,
                             ),
-                            exit.span.clone(),
-                        );
+                            span: exit.span.clone(),
+                            suggestion: symbol_table
+                                .location_with_derived_id(&dest_slug)
+                                .map(|id| format!("Did you mean '{}'?", id)),
+                            fix: None,
+                            related: Vec::new(),
+                        });
                     }
                 }
             }
@@ -404,6 +518,28 @@ fn resolve_content_node(
                     file_path,
                     ctx,
                     current_location_id,
+                    template,
+                    prompt,
+                    symbol_table,
+                    diagnostics,
+                );
+            }
+        }
+
+        ContentNode::LocationHook(hook) => {
+            if current_location_id.is_none() {
+                // URD314 already emitted during collection.
+                return;
+            }
+            // Conditions and effects resolve exactly as they do under a choice.
+            for child in &mut hook.children {
+                resolve_content_node(
+                    child,
+                    file_path,
+                    ctx,
+                    current_location_id,
+                    template,
+                    prompt,
                     symbol_table,
                     diagnostics,
                 );
@@ -411,25 +547,43 @@ fn resolve_content_node(
         }
 
         ContentNode::RuleBlock(rule) => {
-            // Resolve entity refs in select clause.
-            if let Some(select) = &rule.select {
-                for entity_ref in &select.entity_refs {
-                    resolve_entity_ref_value(
+            // Resolve entity refs in select clause, then bind its variable.
+            let mut scope: Option<TemplateScope> = None;
+            if let Some(select) = rule.select.as_mut() {
+                for (entity_ref, annotation) in select.entity_refs.iter().zip(&mut select.annotations) {
+                    *annotation = resolve_entity_ref_value(
                         entity_ref,
                         &rule.span,
                         file_path,
                         &ctx.visible_scope,
+                        None,
                         symbol_table,
                         diagnostics,
-                    );
+                    )
+                    .map(|entity_id| Annotation {
+                        resolved_entity: Some(entity_id),
+                        ..Default::default()
+                    });
                 }
+            }
+            if let Some(select) = &rule.select {
+                let candidates: Vec<String> = select
+                    .annotations
+                    .iter()
+                    .filter_map(|annotation| annotation.as_ref()?.resolved_entity.clone())
+                    .collect();
+                scope = Some(TemplateScope {
+                    section_id: None,
+                    param_name: format!("${}", select.variable),
+                    param_type: candidate_type(rule, &candidates, symbol_table, diagnostics),
+                });
                 for expr in &mut rule.select.as_mut().unwrap().where_clauses {
-                    resolve_condition_expr(expr, file_path, ctx, symbol_table, diagnostics);
+                    resolve_condition_expr(expr, file_path, ctx, scope.as_ref(), symbol_table, diagnostics);
                 }
             }
             // Resolve entity refs in rule where_clauses.
             for expr in &mut rule.where_clauses {
-                resolve_condition_expr(expr, file_path, ctx, symbol_table, diagnostics);
+                resolve_condition_expr(expr, file_path, ctx, scope.as_ref(), symbol_table, diagnostics);
             }
             // Resolve effects.
             for effect in &mut rule.effects {
@@ -439,6 +593,7 @@ fn resolve_content_node(
                     &effect.span,
                     file_path,
                     ctx,
+                    scope.as_ref(),
                     symbol_table,
                     diagnostics,
                 );
@@ -449,8 +604,17 @@ fn resolve_content_node(
             // No references to resolve.
         }
 
-        ContentNode::SectionLabel(_) | ContentNode::SequenceHeading(_) | ContentNode::PhaseHeading(_) => {
-            // Already processed during collection.
+        ContentNode::ConditionalBlock(_) => {
+            // Expanded by IMPORT; LINK never sees one.
+        }
+
+        ContentNode::PhaseHeading(phase) => {
+            resolve_phase_section(phase, ctx, symbol_table, diagnostics);
+        }
+
+        ContentNode::SectionLabel(_) | ContentNode::SequenceHeading(_) => {
+            // Already processed during collection; a section parameter's
+            // type is resolved by the caller, which owns the template scope.
         }
 
         ContentNode::ErrorNode(_) => {
@@ -459,13 +623,42 @@ fn resolve_content_node(
     }
 }
 
+/// Give a quoted line the section prompt's speaker, or report that there
+/// is none to give (URD329).
+fn resolve_inherited_speaker(speech: &mut EntitySpeech, prompt: &PromptSpeaker, diagnostics: &mut DiagnosticCollector) {
+    let message = match prompt {
+        PromptSpeaker::Speaker(resolved) => {
+            speech.annotation = resolved.as_ref().map(|entity_id| Annotation {
+                resolved_entity: Some(entity_id.clone()),
+                ..Default::default()
+            });
+            return;
+        }
+        PromptSpeaker::Missing(section) => format!(
+            "Quoted line in section '{}' has no speaker to inherit: the section's prompt has no speech line.",
+            section,
+        ),
+        PromptSpeaker::Outside => "Quoted line has no speaker to inherit: it is not in a section.".to_string(),
+    };
+    diagnostics.emit(Diagnostic {
+        severity: Severity::Error,
+        code: "URD329".to_string(),
+        message,
+        span: speech.span.clone(),
+        suggestion: Some("Name the speaker instead: '@name: ...'.".to_string()),
+        fix: None,
+        related: Vec::new(),
+    });
+}
+
 /// Resolve an entity reference and populate the annotation slot.
 fn resolve_entity_ref(
     entity_ref: &str,
     annotation: &mut Option<Annotation>,
     span: &Span,
     file_path: &str,
-    visible_scope: &BTreeSet<String>,
+    visible_scope: &Scope,
+    template: Option<&TemplateScope>,
     symbol_table: &SymbolTable,
     diagnostics: &mut DiagnosticCollector,
 ) {
@@ -474,6 +667,7 @@ fn resolve_entity_ref(
         span,
         file_path,
         visible_scope,
+        template,
         symbol_table,
         diagnostics,
     ) {
@@ -485,19 +679,104 @@ fn resolve_entity_ref(
 }
 
 /// Resolve an entity reference string, returning the resolved entity ID or None.
+///
+/// Inside a parameterised section the parameter shadows any entity of the
+/// same name and resolves to itself.
+/// Merge a presence line's overrides into the entity's. An unknown
+/// property is URD308, as on a declaration. A property the declaration
+/// also sets takes the presence line's value (URD327, info); one another
+/// presence line already set is URD328, since the entity exists once.
+fn merge_presence_overrides(
+    entity_id: &str,
+    location_id: &str,
+    overrides: &[(String, Scalar)],
+    span: &Span,
+    symbol_table: &mut SymbolTable,
+    diagnostics: &mut DiagnosticCollector,
+) {
+    let type_symbol = symbol_table
+        .entities
+        .get(entity_id)
+        .and_then(|es| es.type_symbol.as_ref())
+        .and_then(|name| symbol_table.types.get(name));
+    let mut known = Vec::new();
+    for (property, value) in overrides {
+        match type_symbol {
+            Some(ts) if !ts.properties.contains_key(property) && !IMPLICIT_PROPERTIES.contains(&property.as_str()) => {
+                diagnostics.emit(unknown_property(property, ts, span));
+            }
+            // An unresolved type was reported as URD307; nothing to check against.
+            None => {}
+            Some(_) => known.push((property, value)),
+        }
+    }
+
+    let Some(entity) = symbol_table.entities.get_mut(entity_id) else {
+        return;
+    };
+    for (property, value) in known {
+        if let Some((first_location, first_span)) = entity.presence_overrides.get(property) {
+            let elsewhere = if first_location == location_id {
+                format!("twice in '{}'", location_id)
+            } else {
+                format!("in both '{}' and '{}'", first_location, location_id)
+            };
+            diagnostics.emit(Diagnostic {
+                severity: Severity::Error,
+                code: "URD328".to_string(),
+                message: format!(
+                    "Property '{}' of '@{}' is overridden on presence lines {}. The entity exists once, so only one line may set it.",
+                    property, entity_id, elsewhere,
+                ),
+                span: span.clone(),
+                suggestion: Some("Keep the override on one presence line.".to_string()),
+                fix: None,
+                related: vec![RelatedInfo { message: "First overridden here.".to_string(), span: first_span.clone() }],
+            });
+            continue;
+        }
+        if entity.property_overrides.contains_key(property) {
+            diagnostics.emit(Diagnostic {
+                severity: Severity::Info,
+                code: "URD327".to_string(),
+                message: format!(
+                    "Presence line in '{}' overrides '{}' of '@{}', which its declaration also sets. The presence line's value is used.",
+                    location_id, property, entity_id,
+                ),
+                span: span.clone(),
+                suggestion: None,
+                fix: None,
+                related: vec![RelatedInfo { message: "Declared here.".to_string(), span: entity.declared_in.clone() }],
+            });
+        }
+        entity.property_overrides.insert(property.clone(), scalar_to_value(value));
+        entity.presence_overrides.insert(property.clone(), (location_id.to_string(), span.clone()));
+    }
+}
+
 fn resolve_entity_ref_value(
     entity_ref: &str,
     span: &Span,
     file_path: &str,
-    visible_scope: &BTreeSet<String>,
+    visible_scope: &Scope,
+    template: Option<&TemplateScope>,
     symbol_table: &SymbolTable,
     diagnostics: &mut DiagnosticCollector,
 ) -> Option<String> {
+    if template.is_some_and(|t| t.binds(entity_ref)) {
+        return Some(entity_ref.to_string());
+    }
+    if entity_ref.starts_with('$') {
+        report_unbound_variable(entity_ref, span, template, diagnostics);
+        return None;
+    }
+
     match resolve_in_scope(
         entity_ref,
         &symbol_table.entities,
         |es| es.declared_in.file.as_str(),
         visible_scope,
+        span,
     ) {
         ResolveResult::Found(es) => Some(es.id.clone()),
         ResolveResult::NotVisible { declared_in_file } => {
@@ -506,10 +785,8 @@ fn resolve_entity_ref_value(
                 code: "URD301".to_string(),
                 message: format!("Unresolved entity reference '@{}'.", entity_ref),
                 span: span.clone(),
-                suggestion: Some(format!(
-                    "'@{}' is declared in {} but {} is not imported by {}.",
-                    entity_ref, declared_in_file, declared_in_file, file_path,
-                )),
+                suggestion: Some(visible_scope.not_visible_hint(&format!("@{}", entity_ref), &declared_in_file, file_path)),
+                fix: None,
                 related: Vec::new(),
             });
             None
@@ -521,17 +798,98 @@ fn resolve_entity_ref_value(
                 message: format!("Unresolved entity reference '@{}'.", entity_ref),
                 span: span.clone(),
                 suggestion: None,
+                fix: None,
                 related: Vec::new(),
             };
             if let Some(suggestion) = find_suggestion(entity_ref, &symbol_table.entities) {
                 diag.suggestion = Some(format!("Did you mean '@{}'?", suggestion));
             }
+            if let Some(unique) = find_unique_suggestion(entity_ref, &symbol_table.entities) {
+                diag.fix = Some(Fix {
+                    span: span.clone(),
+                    original: format!("@{}", entity_ref),
+                    replacement: format!("@{}", unique),
+                });
+            }
             diagnostics.emit(diag);
             None
         }
     }
 }
 
+/// Resolve a choice's `-> any Type` against the types in scope: URD301 when
+/// the type is declared in a file this one does not import, URD307 when
+/// no type has the name.
+fn resolve_target_type(
+    type_name: &str,
+    choice: &Choice,
+    file_path: &str,
+    ctx: &FileContext,
+    symbol_table: &SymbolTable,
+    diagnostics: &mut DiagnosticCollector,
+) -> Option<String> {
+    match resolve_in_scope(
+        type_name,
+        &symbol_table.types,
+        |ts| ts.declared_in.file.as_str(),
+        &ctx.visible_scope,
+        &choice.span,
+    ) {
+        ResolveResult::Found(ts) => Some(ts.name.clone()),
+        ResolveResult::NotVisible { declared_in_file } => {
+            diagnostics.emit(Diagnostic {
+                severity: Severity::Error,
+                code: "URD301".to_string(),
+                message: format!("Unresolved type reference '{}'.", type_name),
+                span: choice.span.clone(),
+                suggestion: Some(ctx.visible_scope.not_visible_hint(type_name, &declared_in_file, file_path)),
+                fix: None,
+                related: Vec::new(),
+            });
+            None
+        }
+        ResolveResult::NotFound => {
+            diagnostics.emit(Diagnostic {
+                severity: Severity::Error,
+                code: "URD307".to_string(),
+                message: format!("Unknown type '{}' for the target of choice '{}'.", type_name, choice.label),
+                span: choice.span.clone(),
+                suggestion: find_suggestion(type_name, &symbol_table.types).map(|s| format!("Did you mean '{}'?", s)),
+                fix: None,
+                related: Vec::new(),
+            });
+            None
+        }
+    }
+}
+
+/// Report a `$name` reference that no select clause binds (URD324): used
+/// outside a rule, in a rule without a select, or under the wrong name.
+fn report_unbound_variable(
+    variable: &str,
+    span: &Span,
+    template: Option<&TemplateScope>,
+    diagnostics: &mut DiagnosticCollector,
+) {
+    let suggestion = match template {
+        Some(t) if t.section_id.is_none() => format!("This rule's select variable is '{}'.", t.param_name),
+        _ => format!(
+            "Only a rule with 'selects {} from [...]' binds '{}'.",
+            &variable[1..],
+            variable,
+        ),
+    };
+    diagnostics.emit(Diagnostic {
+        severity: Severity::Error,
+        code: "URD324".to_string(),
+        message: format!("Unbound select variable '{}'.", variable),
+        span: span.clone(),
+        suggestion: Some(suggestion),
+        fix: None,
+        related: Vec::new(),
+    });
+}
+
 /// Internal enum used by resolve_container_or_destination before mapping to
 /// ContainerKind or DestinationKind.
 enum ContainerOrDest {
@@ -551,7 +909,8 @@ fn resolve_container_or_destination(
     ref_token: &str,
     span: &Span,
     file_path: &str,
-    visible_scope: &BTreeSet<String>,
+    visible_scope: &Scope,
+    template: Option<&TemplateScope>,
     symbol_table: &SymbolTable,
     diagnostics: &mut DiagnosticCollector,
 ) -> Option<ContainerOrDest> {
@@ -563,12 +922,23 @@ fn resolve_container_or_destination(
         return Some(ContainerOrDest::KeywordHere);
     }
 
+    // The enclosing template's parameter stands for an entity.
+    let param_ref = ref_token.strip_prefix('@').unwrap_or(ref_token);
+    if template.is_some_and(|t| t.binds(param_ref)) {
+        return Some(ContainerOrDest::EntityRef(param_ref.to_string()));
+    }
+    if ref_token.starts_with('$') {
+        report_unbound_variable(ref_token, span, template, diagnostics);
+        return None;
+    }
+
     // Try entity lookup (scope-checked).
     match resolve_in_scope(
         ref_token,
         &symbol_table.entities,
         |es| es.declared_in.file.as_str(),
         visible_scope,
+        span,
     ) {
         ResolveResult::Found(es) => {
             return Some(ContainerOrDest::EntityRef(es.id.clone()));
@@ -579,10 +949,8 @@ fn resolve_container_or_destination(
                 code: "URD301".to_string(),
                 message: format!("Unresolved reference '{}'.", ref_token),
                 span: span.clone(),
-                suggestion: Some(format!(
-                    "'{}' is declared in {} but {} is not imported by {}.",
-                    ref_token, declared_in_file, declared_in_file, file_path,
-                )),
+                suggestion: Some(visible_scope.not_visible_hint(ref_token, &declared_in_file, file_path)),
+                fix: None,
                 related: Vec::new(),
             });
             return None;
@@ -600,6 +968,7 @@ fn resolve_container_or_destination(
             &symbol_table.locations,
             |ls| ls.declared_in.file.as_str(),
             visible_scope,
+            span,
         ) {
             ResolveResult::Found(_) => {
                 return Some(ContainerOrDest::LocationRef(slug));
@@ -610,10 +979,8 @@ fn resolve_container_or_destination(
                     code: "URD301".to_string(),
                     message: format!("Unresolved reference '{}'.", ref_token),
                     span: span.clone(),
-                    suggestion: Some(format!(
-                        "'{}' is declared in {} but {} is not imported by {}.",
-                        ref_token, declared_in_file, declared_in_file, file_path,
-                    )),
+                    suggestion: Some(visible_scope.not_visible_hint(ref_token, &declared_in_file, file_path)),
+                    fix: None,
                     related: Vec::new(),
                 });
                 return None;
@@ -631,13 +998,31 @@ fn resolve_container_or_destination(
         message: format!("Unresolved reference '{}'.", ref_token),
         span: span.clone(),
         suggestion: None,
+        fix: None,
         related: Vec::new(),
     };
     // Try edit distance suggestion against both entities and locations.
-    if let Some(suggestion) = find_suggestion(ref_token, &symbol_table.entities) {
+    // Only a single-word reference with one closest entity or location is
+    // fixable: the replacement then resolves whether or not it keeps an `@`.
+    let unique = if let Some(suggestion) = find_suggestion(ref_token, &symbol_table.entities) {
         diag.suggestion = Some(format!("Did you mean '@{}'?", suggestion));
+        find_unique_suggestion(ref_token, &symbol_table.entities)
+    } else if let Some(id) = symbol_table.location_with_derived_id(&slug) {
+        diag.suggestion = Some(format!("Did you mean '{}'?", id));
+        Some(id.to_string())
     } else if let Some(suggestion) = find_suggestion(&slug, &symbol_table.locations) {
         diag.suggestion = Some(format!("Did you mean '{}'?", suggestion));
+        find_unique_suggestion(&slug, &symbol_table.locations)
+    } else {
+        None
+    };
+    let written = ref_token.strip_prefix('@').unwrap_or(ref_token);
+    if let Some(unique) = unique.filter(|_| !written.contains(char::is_whitespace)) {
+        diag.fix = Some(Fix {
+            span: span.clone(),
+            original: written.to_string(),
+            replacement: unique,
+        });
     }
     diagnostics.emit(diag);
     None
@@ -648,6 +1033,7 @@ fn resolve_condition_expr(
     expr: &mut ConditionExpr,
     file_path: &str,
     ctx: &FileContext,
+    template: Option<&TemplateScope>,
     symbol_table: &mut SymbolTable,
     diagnostics: &mut DiagnosticCollector,
 ) {
@@ -665,9 +1051,10 @@ fn resolve_condition_expr(
 
             let entity_resolved = resolve_entity_ref_value(
                 &pc.entity_ref,
-                &pc.span,
+                &pc.spans.entity_span(&pc.span),
                 file_path,
                 &ctx.visible_scope,
+                template,
                 symbol_table,
                 diagnostics,
             );
@@ -679,26 +1066,17 @@ fn resolve_condition_expr(
                 });
 
                 // Resolve property access — only if entity resolved.
-                if let Some(es) = symbol_table.entities.get(entity_id) {
-                    if let Some(type_name) = &es.type_symbol {
-                        if let Some(ts) = symbol_table.types.get(type_name) {
-                            if ts.properties.contains_key(&pc.property)
-                                || IMPLICIT_PROPERTIES.contains(&pc.property.as_str())
-                            {
-                                if let Some(ann) = &mut pc.annotation {
-                                    ann.resolved_property = Some(pc.property.clone());
-                                    ann.resolved_type = Some(type_name.clone());
-                                }
-                            } else {
-                                diagnostics.error(
-                                    "URD308",
-                                    format!(
-                                        "Property '{}' does not exist on type '{}'.",
-                                        pc.property, type_name,
-                                    ),
-                                    pc.span.clone(),
-                                );
+                if let Some(type_name) = entity_type_of(entity_id, template, symbol_table) {
+                    if let Some(ts) = symbol_table.types.get(&type_name) {
+                        if ts.properties.contains_key(&pc.property)
+                            || IMPLICIT_PROPERTIES.contains(&pc.property.as_str())
+                        {
+                            if let Some(ann) = &mut pc.annotation {
+                                ann.resolved_property = Some(pc.property.clone());
+                                ann.resolved_type = Some(type_name);
                             }
+                        } else {
+                            diagnostics.emit(unknown_property(&pc.property, ts, &pc.spans.property_span(&pc.span)));
                         }
                     }
                 }
@@ -711,6 +1089,7 @@ fn resolve_condition_expr(
                 &cc.span,
                 file_path,
                 &ctx.visible_scope,
+                template,
                 symbol_table,
                 diagnostics,
             );
@@ -721,6 +1100,7 @@ fn resolve_condition_expr(
                 &cc.span,
                 file_path,
                 &ctx.visible_scope,
+                template,
                 symbol_table,
                 diagnostics,
             );
@@ -739,24 +1119,111 @@ fn resolve_condition_expr(
             }
         }
 
+        ConditionExpr::ExhaustionCheck(ec) if ec.section_name.contains('/') => {
+            // File-qualified: a compiled section ID declared in a visible file.
+            match resolve_qualified_section(&ec.section_name, &ec.span, file_path, ctx, symbol_table) {
+                Ok(compiled_id) => {
+                    check_template_needs_argument(
+                        &compiled_id,
+                        &ec.section_name,
+                        template,
+                        &ec.span,
+                        symbol_table,
+                        diagnostics,
+                    );
+                    ec.annotation = Some(Annotation {
+                        resolved_section: Some(compiled_id),
+                        ..Default::default()
+                    });
+                }
+                Err(suggestion) => diagnostics.emit(Diagnostic {
+                    severity: Severity::Error,
+                    code: "URD309".to_string(),
+                    message: format!(
+                        "Unresolved section '{}' in exhaustion check. No section with this ID exists in scope.",
+                        ec.section_name,
+                    ),
+                    span: ec.span.clone(),
+                    suggestion,
+                    fix: None,
+                    related: Vec::new(),
+                }),
+            }
+        }
+
         ConditionExpr::ExhaustionCheck(ec) => {
             // Resolve section name to a section in the current file.
             if let Some(compiled_id) = ctx.local_sections.get(&ec.section_name) {
+                check_template_needs_argument(
+                    compiled_id,
+                    &ec.section_name,
+                    template,
+                    &ec.span,
+                    symbol_table,
+                    diagnostics,
+                );
                 ec.annotation = Some(Annotation {
                     resolved_section: Some(compiled_id.clone()),
                     ..Default::default()
                 });
             } else {
-                diagnostics.error(
-                    "URD309",
-                    format!(
+                diagnostics.emit(Diagnostic {
+                    severity: Severity::Error,
+                    code: "URD309".to_string(),
+                    message: format!(
                         "Unresolved section '{}' in exhaustion check. No section with this name exists in the current file.",
                         ec.section_name,
                     ),
-                    ec.span.clone(),
-                );
+                    span: ec.span.clone(),
+                    suggestion: renamed_section_suggestion(ctx, &ec.section_name).or_else(|| {
+                        find_suggestion(&ec.section_name, &ctx.local_sections)
+                            .map(|s| format!("Did you mean '{}'?", s))
+                    }),
+                    fix: None,
+                    related: Vec::new(),
+                });
             }
         }
+
+        ConditionExpr::VisitedCheck(vc) => {
+            let slug = slugify(&vc.location_ref);
+            let suggestion = match resolve_in_scope(
+                &slug,
+                &symbol_table.locations,
+                |ls| ls.declared_in.file.as_str(),
+                &ctx.visible_scope,
+                &vc.span,
+            ) {
+                ResolveResult::Found(_) => {
+                    vc.annotation = Some(Annotation {
+                        resolved_location: Some(slug),
+                        ..Default::default()
+                    });
+                    return;
+                }
+                ResolveResult::NotVisible { declared_in_file } => Some(ctx.visible_scope.not_visible_hint(&vc.location_ref, &declared_in_file, file_path)),
+                ResolveResult::NotFound => symbol_table
+                    .location_with_derived_id(&slug)
+                    .map(str::to_string)
+                    .or_else(|| find_suggestion(&slug, &symbol_table.locations))
+                    .map(|id| format!("Did you mean '{}'?", id)),
+            };
+            diagnostics.emit(Diagnostic {
+                severity: Severity::Error,
+                code: "URD321".to_string(),
+                message: format!(
+                    "Unresolved location '{}' in visited check.",
+                    vc.location_ref,
+                ),
+                span: vc.span.clone(),
+                suggestion,
+                fix: None,
+                related: Vec::new(),
+            });
+        }
+
+        // Nothing to resolve. The backing flag is the choice's own ID.
+        ConditionExpr::Once(_) => {}
     }
 }
 
@@ -767,22 +1234,35 @@ fn resolve_effect(
     span: &Span,
     file_path: &str,
     ctx: &FileContext,
+    template: Option<&TemplateScope>,
     symbol_table: &mut SymbolTable,
     diagnostics: &mut DiagnosticCollector,
 ) {
+    // Where a set effect's entity and property are written. A reveal does
+    // not record its parts.
+    let (entity_span, property_span) = match effect_type {
+        EffectType::Set { spans, .. } => (spans.entity_span(span), spans.property_span(span)),
+        _ => (span.clone(), span.clone()),
+    };
     match effect_type {
         EffectType::Set { target_prop, .. } | EffectType::Reveal { target_prop } => {
-            // target_prop format: "@entity.property"
-            if let Some(stripped) = target_prop.strip_prefix('@') {
+            // target_prop format: "@entity.property", or "$variable.property" in a rule
+            let stripped = if target_prop.starts_with('$') {
+                Some(target_prop.as_str())
+            } else {
+                target_prop.strip_prefix('@')
+            };
+            if let Some(stripped) = stripped {
                 if let Some(dot_pos) = stripped.find('.') {
                     let entity_ref = &stripped[..dot_pos];
                     let property = &stripped[dot_pos + 1..];
 
                     let entity_resolved = resolve_entity_ref_value(
                         entity_ref,
-                        span,
+                        &entity_span,
                         file_path,
                         &ctx.visible_scope,
+                        template,
                         symbol_table,
                         diagnostics,
                     );
@@ -794,24 +1274,15 @@ fn resolve_effect(
                         };
 
                         // Resolve property — no cascading if entity type unresolved.
-                        if let Some(es) = symbol_table.entities.get(entity_id) {
-                            if let Some(type_name) = &es.type_symbol {
-                                if let Some(ts) = symbol_table.types.get(type_name) {
-                                    if ts.properties.contains_key(property)
-                                        || IMPLICIT_PROPERTIES.contains(&property)
-                                    {
-                                        ann.resolved_property = Some(property.to_string());
-                                        ann.resolved_type = Some(type_name.clone());
-                                    } else {
-                                        diagnostics.error(
-                                            "URD308",
-                                            format!(
-                                                "Property '{}' does not exist on type '{}'.",
-                                                property, type_name,
-                                            ),
-                                            span.clone(),
-                                        );
-                                    }
+                        if let Some(type_name) = entity_type_of(entity_id, template, symbol_table) {
+                            if let Some(ts) = symbol_table.types.get(&type_name) {
+                                if ts.properties.contains_key(property)
+                                    || IMPLICIT_PROPERTIES.contains(&property)
+                                {
+                                    ann.resolved_property = Some(property.to_string());
+                                    ann.resolved_type = Some(type_name);
+                                } else {
+                                    diagnostics.emit(unknown_property(property, ts, &property_span));
                                 }
                             }
                         }
@@ -828,6 +1299,7 @@ fn resolve_effect(
                 span,
                 file_path,
                 &ctx.visible_scope,
+                template,
                 symbol_table,
                 diagnostics,
             );
@@ -838,6 +1310,7 @@ fn resolve_effect(
                 span,
                 file_path,
                 &ctx.visible_scope,
+                template,
                 symbol_table,
                 diagnostics,
             );
@@ -862,6 +1335,7 @@ fn resolve_effect(
                 span,
                 file_path,
                 &ctx.visible_scope,
+                template,
                 symbol_table,
                 diagnostics,
             );
@@ -873,7 +1347,62 @@ fn resolve_effect(
                 });
             }
         }
+
+        EffectType::End { ending_ref } => {
+            if let Some(diag) = unresolved_ending(ending_ref, span, file_path, ctx, symbol_table) {
+                diagnostics.emit(diag);
+            }
+        }
+    }
+}
+
+/// URD326 for an `> end` effect naming no ending in scope, or `None` if
+/// the ending resolves.
+fn unresolved_ending(
+    ending_ref: &str,
+    span: &Span,
+    file_path: &str,
+    ctx: &FileContext,
+    symbol_table: &SymbolTable,
+) -> Option<Diagnostic> {
+    let mut diag = Diagnostic {
+        severity: Severity::Error,
+        code: "URD326".to_string(),
+        message: format!("Unresolved ending '{}'.", ending_ref),
+        span: span.clone(),
+        suggestion: None,
+        fix: None,
+        related: Vec::new(),
+    };
+    match resolve_in_scope(
+        ending_ref,
+        &symbol_table.endings,
+        |e| e.declared_in.file.as_str(),
+        &ctx.visible_scope,
+        span,
+    ) {
+        ResolveResult::Found(_) => return None,
+        ResolveResult::NotVisible { declared_in_file } => {
+            diag.suggestion = Some(ctx.visible_scope.not_visible_hint(ending_ref, &declared_in_file, file_path));
+        }
+        ResolveResult::NotFound => {
+            diag.message = format!(
+                "Unresolved ending '{}'. No ending with this name is declared in an `endings:` block.",
+                ending_ref,
+            );
+            if let Some(suggestion) = find_suggestion(ending_ref, &symbol_table.endings) {
+                diag.suggestion = Some(format!("Did you mean '{}'?", suggestion));
+            }
+            if let Some(unique) = find_unique_suggestion(ending_ref, &symbol_table.endings) {
+                diag.fix = Some(Fix {
+                    span: span.clone(),
+                    original: ending_ref.to_string(),
+                    replacement: unique,
+                });
+            }
+        }
     }
+    Some(diag)
 }
 
 /// Implicit properties defined by the Urd runtime, not by user type definitions.
@@ -888,10 +1417,11 @@ const BUILTIN_JUMP_TARGETS: &[&str] = &["end"];
 /// Resolve a jump target using the normative priority rule.
 fn resolve_jump(
     jump: &mut crate::ast::Jump,
-    _file_path: &str,
+    file_path: &str,
     ctx: &FileContext,
     current_location_id: &Option<String>,
-    symbol_table: &SymbolTable,
+    template: Option<&TemplateScope>,
+    symbol_table: &mut SymbolTable,
     diagnostics: &mut DiagnosticCollector,
 ) {
     // Built-in terminals — check before section/exit lookup.
@@ -912,6 +1442,7 @@ fn resolve_jump(
         jump.annotation = Some(Annotation {
             ..Default::default()
         });
+        reject_jump_argument(jump, diagnostics);
         return;
     }
 
@@ -946,6 +1477,34 @@ fn resolve_jump(
         return;
     }
 
+    // File-qualified jump: -> file_stem/name. Only sections are named this
+    // way; an exit name never contains a slash.
+    if jump.target.contains('/') {
+        match resolve_qualified_section(&jump.target, &jump.span, file_path, ctx, symbol_table) {
+            Ok(compiled_id) => {
+                let argument = resolve_jump_argument(jump, &compiled_id, file_path, ctx, template, symbol_table, diagnostics);
+                jump.annotation = Some(Annotation {
+                    resolved_entity: argument,
+                    resolved_section: Some(compiled_id),
+                    ..Default::default()
+                });
+            }
+            Err(suggestion) => diagnostics.emit(Diagnostic {
+                severity: Severity::Error,
+                code: "URD309".to_string(),
+                message: format!(
+                    "Unresolved jump target '{}'. No section with this ID exists in scope.",
+                    jump.target,
+                ),
+                span: jump.span.clone(),
+                suggestion,
+                fix: None,
+                related: Vec::new(),
+            }),
+        }
+        return;
+    }
+
     // Standard jump: -> name
     // Priority: section first, exit second, error third.
     let section_match = ctx.local_sections.get(&jump.target);
@@ -956,11 +1515,14 @@ fn resolve_jump(
             .and_then(|loc| loc.exits.get(&jump.target))
     });
 
-    match (section_match, exit_match) {
-        (Some(compiled_id), Some(_)) => {
+    let exit_matched = exit_match.is_some();
+    match (section_match.cloned(), exit_matched) {
+        (Some(compiled_id), true) => {
             // Section wins, but warn about shadowing.
+            let argument = resolve_jump_argument(jump, &compiled_id, file_path, ctx, template, symbol_table, diagnostics);
             jump.annotation = Some(Annotation {
-                resolved_section: Some(compiled_id.clone()),
+                resolved_entity: argument,
+                resolved_section: Some(compiled_id),
                 ..Default::default()
             });
             diagnostics.warning(
@@ -972,28 +1534,394 @@ fn resolve_jump(
                 jump.span.clone(),
             );
         }
-        (Some(compiled_id), None) => {
+        (Some(compiled_id), false) => {
+            let argument = resolve_jump_argument(jump, &compiled_id, file_path, ctx, template, symbol_table, diagnostics);
             jump.annotation = Some(Annotation {
-                resolved_section: Some(compiled_id.clone()),
+                resolved_entity: argument,
+                resolved_section: Some(compiled_id),
                 ..Default::default()
             });
         }
-        (None, Some(_exit_sym)) => {
+        (None, true) => {
             let loc_id = current_location_id.as_ref().unwrap();
             jump.annotation = Some(Annotation {
                 resolved_location: Some(loc_id.clone()),
                 ..Default::default()
             });
+            reject_jump_argument(jump, diagnostics);
         }
-        (None, None) => {
-            diagnostics.error(
-                "URD309",
-                format!(
+        (None, false) => {
+            diagnostics.emit(Diagnostic {
+                severity: Severity::Error,
+                code: "URD309".to_string(),
+                message: format!(
                     "Unresolved jump target '{}'. No section or exit with this name exists in scope.",
                     jump.target,
                 ),
-                jump.span.clone(),
-            );
+                span: jump.span.clone(),
+                suggestion: renamed_section_suggestion(ctx, &jump.target),
+                fix: None,
+                related: Vec::new(),
+            });
         }
     }
 }
+
+/// Check a section jump against the target section's parameter and record
+/// the invocation. Returns the resolved argument entity, if any.
+fn resolve_jump_argument(
+    jump: &crate::ast::Jump,
+    compiled_id: &str,
+    file_path: &str,
+    ctx: &FileContext,
+    template: Option<&TemplateScope>,
+    symbol_table: &mut SymbolTable,
+    diagnostics: &mut DiagnosticCollector,
+) -> Option<String> {
+    let Some(argument) = &jump.argument else {
+        check_template_needs_argument(compiled_id, &jump.target, template, &jump.span, symbol_table, diagnostics);
+        return None;
+    };
+    if symbol_table.sections.get(compiled_id).is_some_and(|s| s.param.is_none()) {
+        reject_jump_argument(jump, diagnostics);
+        return None;
+    }
+    if let Some(t) = template {
+        diagnostics.error(
+            "URD319",
+            format!(
+                "Jump '-> {}(@{})' passes an argument from inside parameterised section '{}'. \
+                 Parameterised sections cannot invoke parameterised sections.",
+                jump.target, argument, t.section_id.as_deref().unwrap_or_default(),
+            ),
+            jump.span.clone(),
+        );
+        return None;
+    }
+
+    let entity_id = resolve_entity_ref_value(
+        argument,
+        &jump.span,
+        file_path,
+        &ctx.visible_scope,
+        None,
+        symbol_table,
+        diagnostics,
+    )?;
+    if let Some(section) = symbol_table.sections.get_mut(compiled_id) {
+        section.invocations.push(SectionInvocation {
+            argument: entity_id.clone(),
+            declared_in: jump.span.clone(),
+        });
+    }
+    Some(entity_id)
+}
+
+/// Report an argument on a jump whose target takes no parameter.
+fn reject_jump_argument(jump: &crate::ast::Jump, diagnostics: &mut DiagnosticCollector) {
+    if let Some(argument) = &jump.argument {
+        diagnostics.error(
+            "URD318",
+            format!(
+                "Jump target '{}' takes no parameter, but the jump passes '@{}'.",
+                jump.target, argument,
+            ),
+            jump.span.clone(),
+        );
+    }
+}
+
+/// Report a parameterised section used without an argument. Inside the
+/// section's own body the bare name is allowed and means the current
+/// instance.
+fn check_template_needs_argument(
+    compiled_id: &str,
+    name: &str,
+    template: Option<&TemplateScope>,
+    span: &Span,
+    symbol_table: &SymbolTable,
+    diagnostics: &mut DiagnosticCollector,
+) {
+    let Some(param) = symbol_table.sections.get(compiled_id).and_then(|s| s.param.as_ref()) else {
+        return;
+    };
+    if template.is_some_and(|t| t.section_id.as_deref() == Some(compiled_id)) {
+        return;
+    }
+    diagnostics.error(
+        "URD317",
+        format!(
+            "Section '{}' takes a parameter '@{}: {}' and can only be entered through a jump \
+             that passes an entity, such as '-> {}(@entity)'.",
+            name, param.name, param.type_name, name,
+        ),
+        span.clone(),
+    );
+}
+
+/// Resolve a section label's parameter type and open the template scope
+/// for the section's body. Returns `None` for unparameterised sections.
+fn resolve_section_param(
+    sec: &SectionLabel,
+    file_path: &str,
+    ctx: &FileContext,
+    symbol_table: &SymbolTable,
+    diagnostics: &mut DiagnosticCollector,
+) -> Option<TemplateScope> {
+    let param = sec.param.as_ref()?;
+    let section_id = ctx.local_sections.get(sec.local_id())?.clone();
+
+    let param_type = match resolve_in_scope(
+        &param.type_name,
+        &symbol_table.types,
+        |ts| ts.declared_in.file.as_str(),
+        &ctx.visible_scope,
+        &sec.span,
+    ) {
+        ResolveResult::Found(ts) => Some(ts.name.clone()),
+        ResolveResult::NotVisible { declared_in_file } => {
+            diagnostics.emit(Diagnostic {
+                severity: Severity::Error,
+                code: "URD301".to_string(),
+                message: format!("Unresolved type reference '{}'.", param.type_name),
+                span: sec.span.clone(),
+                suggestion: Some(ctx.visible_scope.not_visible_hint(&param.type_name, &declared_in_file, file_path)),
+                fix: None,
+                related: Vec::new(),
+            });
+            None
+        }
+        ResolveResult::NotFound => {
+            diagnostics.emit(Diagnostic {
+                severity: Severity::Error,
+                code: "URD307".to_string(),
+                message: format!(
+                    "Unknown type '{}' for section parameter '@{}'.",
+                    param.type_name, param.name,
+                ),
+                span: sec.span.clone(),
+                suggestion: find_suggestion(&param.type_name, &symbol_table.types)
+                    .map(|s| format!("Did you mean '{}'?", s)),
+                fix: None,
+                related: Vec::new(),
+            });
+            None
+        }
+    };
+
+    Some(TemplateScope {
+        section_id: Some(section_id),
+        param_name: param.name.clone(),
+        param_type,
+    })
+}
+
+/// The type a rule's select variable stands for: the type every resolved
+/// candidate shares. Candidates of different types leave the variable
+/// untyped. That is an error (URD325) once the rule reads or writes a
+/// declared property through it; `container`, moves and destroys need no
+/// type.
+fn candidate_type(
+    rule: &crate::ast::RuleBlock,
+    candidates: &[String],
+    symbol_table: &SymbolTable,
+    diagnostics: &mut DiagnosticCollector,
+) -> Option<String> {
+    let select = rule.select.as_ref()?;
+    let mut typed = candidates.iter().filter_map(|id| {
+        let type_name = symbol_table.entities.get(id)?.type_symbol.as_ref()?;
+        Some((id, type_name))
+    });
+    let (first_id, first_type) = typed.next()?;
+    let Some((other_id, other_type)) = typed.find(|(_, t)| *t != first_type) else {
+        return Some(first_type.clone());
+    };
+    if let Some(used) = declared_property_use(rule, &format!("${}", select.variable)) {
+        diagnostics.emit(Diagnostic {
+            severity: Severity::Error,
+            code: "URD325".to_string(),
+            message: format!(
+                "Rule '{}' uses '{}', but selects from entities of different types: '@{}' is '{}' and '@{}' is '{}'.",
+                rule.name, used, first_id, first_type, other_id, other_type,
+            ),
+            span: select.span.clone(),
+            suggestion: Some("Select only from entities of one type, or split the rule.".to_string()),
+            fix: None,
+            related: Vec::new(),
+        });
+    }
+    None
+}
+
+/// The first declared (not implicit) property a rule reads or writes
+/// through `variable`, as written: `$door.prize`.
+fn declared_property_use(rule: &crate::ast::RuleBlock, variable: &str) -> Option<String> {
+    let select_where = rule.select.iter().flat_map(|select| &select.where_clauses);
+    let reads = rule.where_clauses.iter().chain(select_where).filter_map(|expr| match expr {
+        ConditionExpr::PropertyComparison(pc) if pc.entity_ref == variable => Some(pc.property.as_str()),
+        _ => None,
+    });
+    let writes = rule.effects.iter().filter_map(|effect| match &effect.effect_type {
+        EffectType::Set { target_prop, .. } | EffectType::Reveal { target_prop } => {
+            target_prop.strip_prefix(variable)?.strip_prefix('.')
+        }
+        _ => None,
+    });
+    reads
+        .chain(writes)
+        .find(|property| !IMPLICIT_PROPERTIES.contains(property))
+        .map(|property| format!("{}.{}", variable, property))
+}
+
+/// The type of a resolved entity reference. Inside a template, the
+/// parameter has its declared type.
+fn entity_type_of(
+    entity_id: &str,
+    template: Option<&TemplateScope>,
+    symbol_table: &SymbolTable,
+) -> Option<String> {
+    match template {
+        Some(t) if t.binds(entity_id) => t.param_type.clone(),
+        _ => symbol_table.entities.get(entity_id).and_then(|es| es.type_symbol.clone()),
+    }
+}
+
+/// Resolve a file-qualified section name (`file_stem/name`) to its
+/// compiled ID among the sections declared in visible files.
+///
+/// On failure, returns the URD309 suggestion: the import hint when the
+/// section is declared in a file that is not imported, else the closest
+/// compiled section ID.
+fn resolve_qualified_section(
+    name: &str,
+    span: &Span,
+    file_path: &str,
+    ctx: &FileContext,
+    symbol_table: &SymbolTable,
+) -> Result<String, Option<String>> {
+    match resolve_in_scope(name, &symbol_table.sections, |s| s.declared_in.file.as_str(), &ctx.visible_scope, span) {
+        ResolveResult::Found(s) => Ok(s.compiled_id.clone()),
+        ResolveResult::NotVisible { declared_in_file } => Err(Some(ctx.visible_scope.not_visible_hint(name, &declared_in_file, file_path))),
+        ResolveResult::NotFound => {
+            Err(find_suggestion(name, &symbol_table.sections).map(|s| format!("Did you mean '{}'?", s)))
+        }
+    }
+}
+
+/// Resolve a phase's `section:` line and record the compiled section ID on
+/// the phase symbol.
+///
+/// Same rules as a jump: a local section name first, then a compiled
+/// section ID (`file_stem/name`) declared in a visible file.
+fn resolve_phase_section(
+    phase: &crate::ast::PhaseHeading,
+    ctx: &FileContext,
+    symbol_table: &mut SymbolTable,
+    diagnostics: &mut DiagnosticCollector,
+) {
+    let section = match &phase.section {
+        Some(s) => s,
+        None => return,
+    };
+
+    let resolved = match ctx.local_sections.get(&section.name) {
+        Some(compiled_id) => Ok(compiled_id.clone()),
+        None => match resolve_in_scope(
+            &section.name,
+            &symbol_table.sections,
+            |s| s.declared_in.file.as_str(),
+            &ctx.visible_scope,
+            &section.span,
+        ) {
+            ResolveResult::Found(s) => Ok(s.compiled_id.clone()),
+            ResolveResult::NotVisible { declared_in_file } => Err(Diagnostic {
+                severity: Severity::Error,
+                code: "URD309".to_string(),
+                message: format!(
+                    "Unresolved section '{}' in phase '{}'.",
+                    section.name, phase.display_name,
+                ),
+                span: section.span.clone(),
+                suggestion: Some(ctx.visible_scope.not_visible_hint(
+                    &section.name,
+                    &declared_in_file,
+                    &phase.span.file,
+                )),
+                fix: None,
+                related: Vec::new(),
+            }),
+            ResolveResult::NotFound => Err(Diagnostic {
+                severity: Severity::Error,
+                code: "URD309".to_string(),
+                message: format!(
+                    "Unresolved section '{}' in phase '{}'. No section with this name or ID exists in scope.",
+                    section.name, phase.display_name,
+                ),
+                span: section.span.clone(),
+                suggestion: renamed_section_suggestion(ctx, &section.name).or_else(|| {
+                    find_suggestion(&section.name, &ctx.local_sections)
+                        .or_else(|| find_suggestion(&section.name, &symbol_table.sections))
+                        .map(|s| format!("Did you mean '{}'?", s))
+                }),
+                fix: None,
+                related: Vec::new(),
+            }),
+        },
+    };
+
+    match resolved {
+        Ok(compiled_id) => {
+            check_template_needs_argument(&compiled_id, &section.name, None, &section.span, symbol_table, diagnostics);
+            let phase_sym = symbol_table
+                .sequences
+                .values_mut()
+                .flat_map(|seq| seq.phases.iter_mut())
+                .find(|p| p.declared_in == phase.span);
+            if let Some(phase_sym) = phase_sym {
+                phase_sym.section = Some(compiled_id);
+            }
+        }
+        Err(diagnostic) => diagnostics.emit(diagnostic),
+    }
+}
+
+/// Properties listed by name when an unknown one has no close match.
+const LISTED_PROPERTIES: usize = 5;
+
+/// URD308 for `property` on type `ts`. Suggests the type's closest
+/// property, else lists the first few it has.
+fn unknown_property(property: &str, ts: &TypeSymbol, span: &Span) -> Diagnostic {
+    let implicit = || {
+        IMPLICIT_PROPERTIES
+            .iter()
+            .find(|p| (1..=2).contains(&edit_distance(property, p)))
+            .map(|p| p.to_string())
+    };
+    let suggestion = match find_suggestion(property, &ts.properties).or_else(implicit) {
+        Some(closest) => format!("Did you mean '{}'?", closest),
+        None if ts.properties.is_empty() => format!("Type '{}' declares no properties.", ts.name),
+        None => {
+            let mut names: Vec<&str> = ts.properties.keys().take(LISTED_PROPERTIES).map(String::as_str).collect();
+            let more = ts.properties.len().saturating_sub(LISTED_PROPERTIES);
+            let more = (more > 0).then(|| format!("and {} more", more));
+            names.extend(more.as_deref());
+            format!("Type '{}' has: {}.", ts.name, names.join(", "))
+        }
+    };
+    Diagnostic {
+        severity: Severity::Error,
+        code: "URD308".to_string(),
+        message: format!("Property '{}' does not exist on type '{}'.", property, ts.name),
+        span: span.clone(),
+        suggestion: Some(suggestion),
+        fix: None,
+        related: Vec::new(),
+    }
+}
+
+/// Suggest the explicit ID of a section whose label is `name`.
+fn renamed_section_suggestion(ctx: &FileContext, name: &str) -> Option<String> {
+    ctx.renamed_sections
+        .get(name)
+        .map(|id| format!("Did you mean '{}'?", id))
+}
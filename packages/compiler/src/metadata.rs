@@ -0,0 +1,129 @@
+/// Build metadata: a sidecar record of how a world was compiled.
+///
+/// [`build_metadata()`] describes one compilation without touching the
+/// world itself, for build pipelines that archive it beside the
+/// `.urd.json` (`urd <file> -o world.urd.json --meta` writes
+/// `world.urd.meta.json`) and for Forge's export dialog:
+///
+/// ```json
+/// { "urd_meta": "1",
+///   "compiler": { "name": "urd-compiler", "version": "0.1.14", ... },
+///   "success": true,
+///   "summary": { "errors": 0, "warnings": 1, "info": 0 },
+///   "diagnostics": [...],
+///   "sources": [{ "path": "main.urd.md", "bytes": 412, "sha256": "..." }],
+///   "options": { "emit": {...}, "defines": [], ... },
+///   "stats": {...},
+///   "timings": { "parse": 0.41, ..., "total": 2.9 } }
+/// ```
+///
+/// `compiler` is [`crate::version::to_json()`], `diagnostics` is
+/// [`crate::payload::diagnostics_json()`], and `stats` is
+/// [`crate::stats::WorldStats::to_json()`] (`null` when LINK did not run).
+/// Sources are sorted by path. Keys are always in the order above, so the
+/// same compilation gives the same bytes, except for `timings`: phase wall
+/// times in milliseconds, present only when [`MetadataOptions::timings`]
+/// is set. Leaving it unset (`--reproducible`) makes the document a pure
+/// function of the sources and options.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde_json::{json, Value as Json};
+
+use crate::diagnostics::Severity;
+use crate::hash::sha256_hex;
+use crate::observer::Phase;
+use crate::{payload, stats, version, CompilationResult, CompileOptions};
+
+/// What [`build_metadata()`] records beyond the result itself.
+#[derive(Debug, Clone, Copy)]
+pub struct MetadataOptions<'a> {
+    /// The options the compilation ran with.
+    pub compile: &'a CompileOptions<'a>,
+    /// Each phase that ran, in order, with its wall time, as an observer
+    /// reported them. `None` omits `timings`.
+    pub timings: Option<&'a [(Phase, Duration)]>,
+}
+
+/// The metadata document for `result`, as pretty-printed JSON with a
+/// trailing newline. `sources` maps each file the compile read to its
+/// text, keyed as diagnostic spans name it (`CompilationResult::sources`
+/// under `CompileOptions::keep_sources`).
+pub fn build_metadata(
+    result: &CompilationResult,
+    sources: &BTreeMap<String, String>,
+    options: &MetadataOptions,
+) -> String {
+    let count = |severity| result.diagnostics.all().iter().filter(|d| d.severity == severity).count();
+    let sources: Vec<Json> = sources
+        .iter()
+        .map(|(path, text)| {
+            json!({
+                "path": path,
+                "bytes": text.len(),
+                "sha256": sha256_hex(text.as_bytes()),
+            })
+        })
+        .collect();
+
+    let mut document = json!({
+        "urd_meta": "1",
+        "compiler": version::to_json(),
+        "success": result.success,
+        "summary": {
+            "errors": count(Severity::Error),
+            "warnings": count(Severity::Warning),
+            "info": count(Severity::Info),
+        },
+        "diagnostics": payload::diagnostics_json(&result.diagnostics),
+        "sources": sources,
+        "options": options_json(options.compile),
+        "stats": stats::from_compilation(result).map(|s| s.to_json()),
+    });
+    if let Some(timings) = options.timings {
+        let mut phases = serde_json::Map::new();
+        for (phase, elapsed) in timings {
+            phases.insert(phase.name().to_string(), json!(milliseconds(*elapsed)));
+        }
+        let total: Duration = timings.iter().map(|(_, elapsed)| *elapsed).sum();
+        phases.insert("total".to_string(), json!(milliseconds(total)));
+        document["timings"] = Json::Object(phases);
+    }
+
+    let mut output = serde_json::to_string_pretty(&document).unwrap();
+    output.push('\n');
+    output
+}
+
+/// The options that can change a compile's output or diagnostics. Paths
+/// (the project root) are left out, since spans are already relative to
+/// it and an absolute path would differ between machines.
+fn options_json(options: &CompileOptions) -> Json {
+    let emit = &options.emit;
+    json!({
+        "emit": {
+            "format": emit.format.as_str(),
+            "split": emit.split,
+            "externalize_strings": emit.externalize_strings,
+            "include_docs": emit.include_docs,
+            "numeric_ids": emit.numeric_ids,
+            "include_generator": emit.include_generator,
+        },
+        "defines": options.defines,
+        "missing_import_policy": options.missing_import_policy.name(),
+        "strict_filename_case": options.strict_filename_case,
+        "text_lints": options.text_lints,
+        "lenient_entity_refs": options.lenient_entity_refs,
+        "scope_policy": options.scope_policy.name(),
+        "size_limits": {
+            "choices": options.size_limits.choices,
+            "prose_words": options.size_limits.prose_words,
+            "exits": options.size_limits.exits,
+        },
+    })
+}
+
+fn milliseconds(elapsed: Duration) -> f64 {
+    (elapsed.as_secs_f64() * 1_000_000.0).round() / 1000.0
+}
@@ -0,0 +1,158 @@
+/// Compile observer: structured progress events for embedding hosts.
+///
+/// Set `CompileOptions::observer` to receive a callback as each phase
+/// starts and finishes, as each file is parsed, and for each diagnostic.
+/// Forge reports per-phase timings from it and the CLI prints its
+/// `--timings` table from it, so there is one account of where a compile
+/// spends its time.
+///
+/// Every method has a no-op default and takes `&self`, so an observer
+/// implements only what it needs and keeps its state in cells. The trait
+/// is object-safe and does not require `Send`: single-threaded WASM hosts
+/// can implement it with `Rc` and `RefCell`.
+///
+/// Event order:
+///
+/// - `phase_started` and `phase_finished` bracket each phase that runs.
+///   A phase skipped because of earlier errors reports neither.
+/// - `file_parsed` fires for the entry file during PARSE and for each
+///   imported file during IMPORT, in discovery order.
+/// - `diagnostic_emitted` fires once per diagnostic, in the order the
+///   collector recorded them: before the `file_parsed` of the file whose
+///   parse raised it, and before the `phase_finished` of the phase that
+///   raised it. The collector is append-only, so diagnostics are relayed
+///   from it at those points rather than hooked into
+///   `DiagnosticCollector::emit`, which keeps `CompilationResult` free of
+///   borrowed state.
+///
+/// On WASM targets, where `std::time::Instant` is unavailable, durations
+/// are zero.
+
+use std::cell::Cell;
+use std::fmt;
+use std::time::Duration;
+
+use crate::ast::FileAst;
+use crate::diagnostics::{Diagnostic, DiagnosticCollector};
+
+/// A compiler phase, as reported to an observer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Phase {
+    Parse,
+    Import,
+    Link,
+    /// FactSet extraction, the property and definition indexes, and the
+    /// URD600 diagnostics.
+    Analyze,
+    Validate,
+    Emit,
+}
+
+impl Phase {
+    /// Lower-case name (`"parse"`, `"import"`, ...).
+    pub fn name(self) -> &'static str {
+        match self {
+            Phase::Parse => "parse",
+            Phase::Import => "import",
+            Phase::Link => "link",
+            Phase::Analyze => "analyze",
+            Phase::Validate => "validate",
+            Phase::Emit => "emit",
+        }
+    }
+}
+
+/// Receives compile events. See the module documentation for ordering.
+pub trait CompileObserver {
+    fn phase_started(&self, _phase: Phase) {}
+    fn phase_finished(&self, _phase: Phase, _elapsed: Duration) {}
+    /// `path` is the file's graph path; `node_count` its top-level
+    /// content nodes.
+    fn file_parsed(&self, _path: &str, _node_count: usize) {}
+    fn diagnostic_emitted(&self, _diagnostic: &Diagnostic) {}
+}
+
+impl fmt::Debug for dyn CompileObserver + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("CompileObserver")
+    }
+}
+
+/// Relays one compilation's events to an optional observer.
+pub(crate) struct Relay<'a> {
+    observer: Option<&'a dyn CompileObserver>,
+    /// Diagnostics relayed so far.
+    relayed: Cell<usize>,
+}
+
+impl<'a> Relay<'a> {
+    pub(crate) fn new(observer: Option<&'a dyn CompileObserver>) -> Self {
+        Relay { observer, relayed: Cell::new(0) }
+    }
+
+    /// Run `f` as `phase`, bracketed by its start and finish events.
+    pub(crate) fn phase<T>(
+        &self,
+        phase: Phase,
+        diagnostics: &mut DiagnosticCollector,
+        f: impl FnOnce(&mut DiagnosticCollector) -> T,
+    ) -> T {
+        let Some(observer) = self.observer else {
+            return f(diagnostics);
+        };
+        observer.phase_started(phase);
+        let stopwatch = Stopwatch::start();
+        let value = f(diagnostics);
+        let elapsed = stopwatch.elapsed();
+        self.flush(diagnostics);
+        observer.phase_finished(phase, elapsed);
+        value
+    }
+
+    pub(crate) fn file_parsed(&self, ast: &FileAst, diagnostics: &DiagnosticCollector) {
+        if let Some(observer) = self.observer {
+            self.flush(diagnostics);
+            observer.file_parsed(&ast.path, ast.content.len());
+        }
+    }
+
+    /// Relay the diagnostics recorded since the last flush.
+    pub(crate) fn flush(&self, diagnostics: &DiagnosticCollector) {
+        let Some(observer) = self.observer else {
+            return;
+        };
+        let all = diagnostics.all();
+        for diagnostic in &all[self.relayed.get().min(all.len())..] {
+            observer.diagnostic_emitted(diagnostic);
+        }
+        self.relayed.set(all.len());
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+struct Stopwatch(std::time::Instant);
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Stopwatch {
+    fn start() -> Self {
+        Stopwatch(std::time::Instant::now())
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.0.elapsed()
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+struct Stopwatch;
+
+#[cfg(target_arch = "wasm32")]
+impl Stopwatch {
+    fn start() -> Self {
+        Stopwatch
+    }
+
+    fn elapsed(&self) -> Duration {
+        Duration::ZERO
+    }
+}
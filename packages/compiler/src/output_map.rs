@@ -0,0 +1,168 @@
+/// Output maps: which emitted JSON a source line produced, and back.
+///
+/// With `EmitOptions::build_output_map` set, EMIT records a path and a
+/// span for each object or array element it builds from a declaration or
+/// a content node, and the result carries the map as
+/// `CompilationResult::output_map`. Recording happens as each top-level
+/// block is finished, like `"doc"` fields, and only for paths the block
+/// really holds:
+///
+/// - `types[T]` and its `properties[p]`
+/// - `entities[e]`, `regions[r]`, `actions[a]`, and `endings[e]`
+/// - `locations[l]` and its `exits[d]`
+/// - `rules[r]` and its `effects[i]`
+/// - `sequences[s]` and its `phases[i]`
+/// - `dialogue[s]`, its `choices[i]` at any depth, and their `effects[i]`
+///
+/// Text fields, conditions, and the world block are not recorded, so a
+/// prose line maps to nothing. With the option off no map is built.
+
+use std::fmt;
+
+use serde_json::Value as Json;
+
+use crate::span::Span;
+use crate::CompilationResult;
+
+/// One step of a [`JsonPath`]: an object key or an array index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+/// A position in the compiled world, from the root.
+///
+/// Displays as `dialogue["tavern/topics"].choices[2].effects[0]`; the
+/// [`pointer()`](JsonPath::pointer) form is
+/// `/dialogue/tavern~1topics/choices/2/effects/0`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct JsonPath {
+    segments: Vec<PathSegment>,
+}
+
+impl JsonPath {
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// The path as an RFC 6901 JSON pointer.
+    pub fn pointer(&self) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                PathSegment::Key(key) => format!("/{}", key.replace('~', "~0").replace('/', "~1")),
+                PathSegment::Index(index) => format!("/{}", index),
+            })
+            .collect()
+    }
+
+    pub(crate) fn key(&self, key: &str) -> JsonPath {
+        let mut path = self.clone();
+        path.segments.push(PathSegment::Key(key.to_string()));
+        path
+    }
+
+    pub(crate) fn index(&self, index: usize) -> JsonPath {
+        let mut path = self.clone();
+        path.segments.push(PathSegment::Index(index));
+        path
+    }
+
+    /// The value at this path in `value`, whose root is the world.
+    fn resolve<'a>(&self, value: &'a Json) -> Option<&'a Json> {
+        self.segments.iter().try_fold(value, |value, segment| match segment {
+            PathSegment::Key(key) => value.get(key),
+            PathSegment::Index(index) => value.get(index),
+        })
+    }
+}
+
+impl fmt::Display for JsonPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) if is_identifier(key) => {
+                    if i > 0 {
+                        write!(f, ".")?;
+                    }
+                    write!(f, "{}", key)?;
+                }
+                PathSegment::Key(key) => write!(f, "[{}]", Json::String(key.clone()))?,
+                PathSegment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// One recorded path and the span it was built from.
+#[derive(Debug, Clone)]
+pub struct OutputEntry {
+    pub path: JsonPath,
+    pub span: Span,
+}
+
+/// Every recorded path, in emission order.
+#[derive(Debug, Clone, Default)]
+pub struct OutputMap {
+    entries: Vec<OutputEntry>,
+}
+
+impl OutputMap {
+    pub fn entries(&self) -> &[OutputEntry] {
+        &self.entries
+    }
+
+    /// The paths whose spans cover `line` (1-based) of `file`, innermost
+    /// first: fewer lines before more, then deeper paths before shallower.
+    pub fn paths_at(&self, file: &str, line: u32) -> Vec<&JsonPath> {
+        let mut found: Vec<&OutputEntry> = self
+            .entries
+            .iter()
+            .filter(|e| e.span.file == file && e.span.start_line <= line && line <= e.span.end_line)
+            .collect();
+        found.sort_by_key(|e| (e.span.end_line - e.span.start_line, std::cmp::Reverse(e.path.segments.len())));
+        found.into_iter().map(|e| &e.path).collect()
+    }
+
+    /// The span `path` was built from. `path` is a JSON pointer or the
+    /// displayed form.
+    pub fn span_of(&self, path: &str) -> Option<&Span> {
+        let by_pointer = path.starts_with('/') || path.is_empty();
+        self.entries
+            .iter()
+            .find(|e| if by_pointer { e.path.pointer() == path } else { e.path.to_string() == path })
+            .map(|e| &e.span)
+    }
+
+    /// Add `path` if it names a value in `block`, the value of its first
+    /// segment, and `span` is a real one.
+    pub(crate) fn record(&mut self, block: &Json, path: JsonPath, span: &Span) {
+        let inner = JsonPath { segments: path.segments[1..].to_vec() };
+        if span.start_line > 0 && inner.resolve(block).is_some() {
+            self.entries.push(OutputEntry { path, span: span.clone() });
+        }
+    }
+}
+
+/// The innermost output `line` of `file` produced, or `None` when it
+/// produced none or the compile built no output map. `file` is the path
+/// spans use.
+pub fn output_path_for_span(compilation: &CompilationResult, file: &str, line: u32) -> Option<JsonPath> {
+    let map = compilation.output_map.as_ref()?;
+    map.paths_at(file, line).first().map(|path| (*path).clone())
+}
+
+/// The span the output at `json_path` was built from: a JSON pointer or
+/// the displayed form of a [`JsonPath`].
+pub fn span_for_output_path(compilation: &CompilationResult, json_path: &str) -> Option<Span> {
+    compilation.output_map.as_ref()?.span_of(json_path).cloned()
+}
+
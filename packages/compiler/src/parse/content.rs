@@ -5,14 +5,21 @@
 /// against the ordered rule list.
 
 use crate::ast::*;
+use crate::diagnostics::{Diagnostic, Severity};
 use crate::span::Span;
-use super::Parser;
+use super::frontmatter::{parse_inline_object, split_top_level};
+use super::{GuardLine, Parser};
 
 /// Parse narrative content lines starting from `parser.current_line`.
 /// `min_indent` is the minimum indent level for content to be parsed
 /// as children (used for choice nesting).
 pub(crate) fn parse_content(parser: &mut Parser, min_indent: usize) -> Vec<ContentNode> {
     let mut nodes = Vec::new();
+    // Indent level of the line above: the parent's for the first child.
+    let mut previous_indent = min_indent.checked_sub(1);
+    // A choice's content, or a section after its first choice, may hold
+    // quoted lines that inherit the prompt's speaker.
+    let mut after_choice = false;
 
     while !parser.at_end() {
         let line = match parser.peek_line() {
@@ -36,9 +43,47 @@ pub(crate) fn parse_content(parser: &mut Parser, min_indent: usize) -> Vec<Conte
             break;
         }
 
+        // Guards: `?if flag:` … `?endif`, at column 0 only
+        if let Some(guard) = Parser::guard_line(&processed) {
+            let open = parser.current_line;
+            match guard {
+                GuardLine::Open(condition) => {
+                    let (block, next) = parser.parse_guard(open, condition, parser.limit, |parser, start, _| {
+                        parser.current_line = start;
+                        parse_content(parser, 0)
+                    });
+                    nodes.push(ContentNode::ConditionalBlock(block));
+                    parser.current_line = next;
+                }
+                GuardLine::Close => {
+                    parser.unmatched_endif(open);
+                    parser.current_line += 1;
+                }
+            }
+            continue;
+        }
+
+        check_indent(parser, &processed, previous_indent);
+        previous_indent = Some(indent_level);
+
         // Dispatch to block parsers
         match parse_block(parser, &processed, indent_level) {
-            Some(node) => nodes.push(node),
+            Some(node) => {
+                match node {
+                    ContentNode::Choice(_) => after_choice = true,
+                    ContentNode::SectionLabel(_)
+                    | ContentNode::LocationHeading(_)
+                    | ContentNode::RegionHeading(_)
+                    | ContentNode::SequenceHeading(_)
+                    | ContentNode::PhaseHeading(_) => after_choice = false,
+                    _ => {}
+                }
+                if min_indent > 0 || after_choice {
+                    nodes.push(quoted_speech(node));
+                } else {
+                    nodes.push(node);
+                }
+            }
             None => {
                 // Should not happen — Prose is the fallback
                 parser.current_line += 1;
@@ -49,6 +94,76 @@ pub(crate) fn parse_content(parser: &mut Parser, min_indent: usize) -> Vec<Conte
     nodes
 }
 
+/// A prose node whose text is one quoted string, as speech with no
+/// speaker of its own. The quotes are dropped. Anything else is returned
+/// unchanged.
+fn quoted_speech(node: ContentNode) -> ContentNode {
+    match node {
+        ContentNode::Prose(p) if is_quoted(&p.text) => ContentNode::EntitySpeech(EntitySpeech {
+            entity_ref: String::new(),
+            text: unquote(&p.text),
+            variants: p.variants.iter().map(|v| if is_quoted(v) { unquote(v) } else { v.clone() }).collect(),
+            annotation: None,
+            span: p.span,
+        }),
+        other => other,
+    }
+}
+
+fn is_quoted(text: &str) -> bool {
+    text.len() >= 2 && text.starts_with('"') && text.ends_with('"')
+}
+
+fn unquote(text: &str) -> String {
+    text[1..text.len() - 1].trim().to_string()
+}
+
+/// Warn about indentation that `measure_indent` reads differently from
+/// how it looks: an odd number of spaces (URD114), or a line more than one
+/// level deeper than the line above it (URD115). The reading itself is
+/// unchanged.
+fn check_indent(parser: &mut Parser, processed_line: &str, previous_indent: Option<usize>) {
+    let (level, rest) = Parser::measure_indent(processed_line);
+    let spaces = (processed_line.len() - rest.len()) as u32;
+    let line = parser.current_line;
+    let line_number = parser.lines[line].line_number;
+
+    if spaces % 2 == 1 {
+        parser.diagnostics.warning(
+            "URD114",
+            format!(
+                "Indentation of {} spaces is not a multiple of 2; interpreted as level {}.",
+                spaces, level
+            ),
+            Span::new(parser.file_path.clone(), line_number, spaces, line_number, spaces + 1),
+        );
+    }
+
+    if let Some(previous) = previous_indent {
+        if level > previous + 1 {
+            parser.diagnostics.emit(Diagnostic {
+                severity: Severity::Warning,
+                code: "URD115".to_string(),
+                message: format!(
+                    "Line is indented {} levels deeper than the line above it (level {} after level {}). \
+                     It is read at level {}.",
+                    level - previous,
+                    level,
+                    previous,
+                    level
+                ),
+                span: parser.line_span(line),
+                suggestion: Some(format!(
+                    "Indent it by {} spaces to place it one level under the line above.",
+                    (previous + 1) * 2
+                )),
+                fix: None,
+                related: Vec::new(),
+            });
+        }
+    }
+}
+
 /// Block dispatch: try each alternative in order.
 /// Returns the parsed content node. Advances parser.current_line.
 fn parse_block(
@@ -69,6 +184,12 @@ fn parse_block(
         return Some(parse_rule_block(parser, indent_level));
     }
 
+    // 2b. LocationHook: `on enter:` / `on exit:`
+    let hook_header = Parser::strip_inline_comment(rest).trim_end();
+    if hook_header == "on enter:" || hook_header == "on exit:" {
+        return Some(parse_location_hook(parser, indent_level));
+    }
+
     // 3. Headings: ### before ## before #
     if rest.starts_with("### ") {
         return Some(parse_phase_heading(parser));
@@ -76,6 +197,9 @@ fn parse_block(
     if rest.starts_with("## ") {
         return Some(parse_sequence_heading(parser));
     }
+    if rest.starts_with(REGION_PREFIX) {
+        return Some(parse_region_heading(parser));
+    }
     if rest.starts_with("# ") {
         return Some(parse_location_heading(parser));
     }
@@ -130,8 +254,9 @@ fn parse_block(
         return Some(parse_entity_presence(parser));
     }
 
-    // 12. LineComment: // text
-    if rest.starts_with("// ") || rest == "//" {
+    // 12. LineComment: // text, or a /// doc comment (attached to the
+    // heading or label below it by that node's parser).
+    if rest.starts_with("// ") || rest == "//" || Parser::is_doc_comment(rest) {
         return Some(parse_line_comment(parser));
     }
 
@@ -140,22 +265,127 @@ fn parse_block(
         return Some(error);
     }
 
+    // 13b. A variant line that no speech line or prose took: kept as prose.
+    if is_variant_line(rest) {
+        let span = parser.content_line_span(line_idx);
+        parser.diagnostics.warning(
+            "URD116",
+            "Variant line has no line to vary. A '|' line must directly follow a speech line or a line of prose.",
+            span.clone(),
+        );
+        let text = Parser::strip_inline_comment(rest).trim().to_string();
+        parser.current_line += 1;
+        return Some(ContentNode::Prose(Prose { text, variants: Vec::new(), span }));
+    }
+
     // 14. Prose: fallback — any non-blank line
-    Some(parse_prose(parser))
+    Some(parse_prose(parser, indent_level))
+}
+
+/// Whether `rest` (a line with its indent removed) is plain text: a line
+/// that no block rule above Prose would claim.
+fn is_plain_text(rest: &str) -> bool {
+    const PREFIXES: &[&str] = &[
+        "#", "== ", "@", "-> ", "?", "> ", "* ", "+ ", "! ", "[@", "// ", "| ",
+    ];
+    let hook_header = Parser::strip_inline_comment(rest).trim_end();
+    !(PREFIXES.iter().any(|p| rest.starts_with(p))
+        || (rest.starts_with("rule ") && rest.ends_with(':'))
+        || hook_header == "on enter:"
+        || hook_header == "on exit:"
+        || rest == "//"
+        || Parser::is_doc_comment(rest))
+}
+
+/// Append indented continuation lines to a node's text, starting at
+/// `parser.current_line`, and return the node's span.
+///
+/// A continuation line sits exactly one indent level deeper than the node
+/// that starts on `line_idx`, with no blank line between, and holds plain
+/// text. It joins the text with a space. A continuation consisting of a
+/// lone `\` starts a new paragraph: the next one joins with `\n`.
+fn take_continuations(parser: &mut Parser, line_idx: usize, indent_level: usize, text: &mut String) -> Span {
+    let mut last_line = line_idx;
+    let mut separator = " ";
+    while let Some(line) = parser.peek_line() {
+        if line.trim().is_empty() || line.contains('\t') {
+            break;
+        }
+        let (indent, rest) = Parser::measure_indent(line);
+        if indent != indent_level + 1 || !is_plain_text(rest) {
+            break;
+        }
+        let continuation = Parser::strip_inline_comment(rest).trim();
+        if continuation == "\\" {
+            separator = "\n";
+        } else if !continuation.is_empty() {
+            if !text.is_empty() {
+                text.push_str(separator);
+            }
+            text.push_str(continuation);
+            separator = " ";
+        }
+        last_line = parser.current_line;
+        parser.current_line += 1;
+    }
+    parser.content_span_lines(line_idx, last_line)
+}
+
+/// Whether `rest` (a line with its indent removed) is a `| text` variant
+/// line.
+fn is_variant_line(rest: &str) -> bool {
+    rest.starts_with("| ") && !Parser::strip_inline_comment(&rest[2..]).trim().is_empty()
+}
+
+/// Take the `| text` lines directly below a speech line or prose starting
+/// at `parser.current_line`: at the node's indent level, with no blank
+/// line between. Each is one alternative to the node's text.
+fn take_variants(parser: &mut Parser, indent_level: usize) -> Vec<String> {
+    let mut variants = Vec::new();
+    while let Some(line) = parser.peek_line() {
+        if line.contains('\t') {
+            break;
+        }
+        let (indent, rest) = Parser::measure_indent(line);
+        if indent != indent_level || !is_variant_line(rest) {
+            break;
+        }
+        variants.push(Parser::strip_inline_comment(&rest[2..]).trim().to_string());
+        parser.current_line += 1;
+    }
+    variants
 }
 
 // ── Individual node parsers ──
 
+/// A level-1 heading naming a region rather than a location.
+const REGION_PREFIX: &str = "# Region: ";
+
+fn parse_region_heading(parser: &mut Parser) -> ContentNode {
+    let line_idx = parser.current_line;
+    let text = parser.check_tabs(line_idx);
+    let (_, rest) = Parser::measure_indent(&text);
+    let after_prefix = &rest[REGION_PREFIX.len()..];
+    let display_name = Parser::strip_inline_comment(after_prefix).trim().to_string();
+    let span = parser.content_line_span(line_idx);
+    parser.current_line += 1;
+
+    ContentNode::RegionHeading(RegionHeading { display_name, span })
+}
+
 fn parse_location_heading(parser: &mut Parser) -> ContentNode {
     let line_idx = parser.current_line;
     let text = parser.check_tabs(line_idx);
     let (_, rest) = Parser::measure_indent(&text);
     let after_sigil = &rest[2..]; // skip "# "
-    let display_name = Parser::strip_inline_comment(after_sigil).trim().to_string();
+    let (display_name, explicit_id) =
+        split_explicit_id(Parser::strip_inline_comment(after_sigil).trim());
+    let display_name = display_name.to_string();
+    let doc = parser.doc_comment_above(line_idx);
     let span = parser.content_line_span(line_idx);
     parser.current_line += 1;
 
-    ContentNode::LocationHeading(LocationHeading { display_name, span })
+    ContentNode::LocationHeading(LocationHeading { display_name, explicit_id, doc, span })
 }
 
 fn parse_sequence_heading(parser: &mut Parser) -> ContentNode {
@@ -186,21 +416,107 @@ fn parse_phase_heading(parser: &mut Parser) -> ContentNode {
     let span = parser.content_line_span(line_idx);
     parser.current_line += 1;
 
-    ContentNode::PhaseHeading(PhaseHeading { display_name, auto, span })
+    let section = parse_phase_section(parser);
+
+    ContentNode::PhaseHeading(PhaseHeading { display_name, auto, section, span })
+}
+
+/// `section: name` on the line directly after a phase heading. Any other
+/// line (including a blank one) leaves the phase without a section.
+fn parse_phase_section(parser: &mut Parser) -> Option<PhaseSection> {
+    let line_idx = parser.current_line;
+    let line = parser.peek_line()?;
+    let value = Parser::strip_inline_comment(line.trim_start())
+        .trim_end()
+        .strip_prefix("section:")?;
+    let name = value.trim();
+    if name.is_empty() {
+        return None;
+    }
+    let name = name.to_string();
+
+    parser.check_tabs(line_idx);
+    let span = parser.content_line_span(line_idx);
+    parser.current_line += 1;
+    Some(PhaseSection { name, span })
 }
 
 fn parse_section_label(parser: &mut Parser) -> ContentNode {
     let line_idx = parser.current_line;
     let text = parser.check_tabs(line_idx);
     let (_, rest) = Parser::measure_indent(&text);
-    let name = rest[3..].trim().to_string(); // skip "== "
+    let after_sigil = &rest[3..]; // skip "== "
+    let (name, explicit_id) = split_explicit_id(Parser::strip_inline_comment(after_sigil).trim());
+    let (name, param) = match split_section_param(name) {
+        Some(split) => split,
+        None => return make_error_node(parser, line_idx, Some("SectionLabel")),
+    };
+    let name = name.to_string();
+    let doc = parser.doc_comment_above(line_idx);
     let span = parser.content_line_span(line_idx);
     parser.current_line += 1;
 
-    ContentNode::SectionLabel(SectionLabel { name, span })
+    ContentNode::SectionLabel(SectionLabel { name, explicit_id, param, doc, span })
+}
+
+/// Split a trailing `(@param: Type)` off a section label name. Returns
+/// `None` when the parentheses are present but malformed.
+fn split_section_param(text: &str) -> Option<(&str, Option<SectionParam>)> {
+    let Some(open) = text.find('(') else {
+        return Some((text, None));
+    };
+    let inner = text[open + 1..].strip_suffix(')')?;
+    let (name, type_name) = inner.split_once(':')?;
+    let name = name.trim().strip_prefix('@')?;
+    let type_name = type_name.trim();
+    let type_ok = type_name.chars().next().is_some_and(|c| c.is_ascii_uppercase())
+        && type_name.chars().all(|c| c.is_ascii_alphanumeric());
+    let before = text[..open].trim_end();
+    if !is_entity_identifier(name) || !type_ok || before.is_empty() {
+        return None;
+    }
+    Some((
+        before,
+        Some(SectionParam { name: name.to_string(), type_name: type_name.to_string() }),
+    ))
+}
+
+/// Split a trailing `(@entity)` argument off a jump target. Returns `None`
+/// when the parentheses are present but malformed.
+fn split_jump_argument(text: &str) -> Option<(&str, Option<String>)> {
+    let Some(open) = text.find('(') else {
+        return Some((text, None));
+    };
+    let argument = text[open + 1..].strip_suffix(')')?.trim().strip_prefix('@')?;
+    let before = text[..open].trim_end();
+    if !is_entity_identifier(argument) || before.is_empty() {
+        return None;
+    }
+    Some((before, Some(argument.to_string())))
 }
 
-fn parse_entity_line(parser: &mut Parser, _indent_level: usize) -> Option<ContentNode> {
+/// A parameter or argument name: a lowercase letter, then lowercase
+/// letters, digits, or underscores.
+fn is_entity_identifier(text: &str) -> bool {
+    text.chars().next().is_some_and(|c| c.is_ascii_lowercase())
+        && text.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Split a trailing ` {#id}` override off heading or label text. The ID is
+/// returned as written; LINK validates it.
+fn split_explicit_id(text: &str) -> (&str, Option<String>) {
+    if let Some(body) = text.strip_suffix('}') {
+        if let Some(pos) = body.rfind("{#") {
+            let before = &body[..pos];
+            if before.ends_with(' ') && !before.trim().is_empty() {
+                return (before.trim_end(), Some(body[pos + 2..].to_string()));
+            }
+        }
+    }
+    (text, None)
+}
+
+fn parse_entity_line(parser: &mut Parser, indent_level: usize) -> Option<ContentNode> {
     let line_idx = parser.current_line;
     let text = parser.check_tabs(line_idx);
     let (_, rest) = Parser::measure_indent(&text);
@@ -229,16 +545,18 @@ fn parse_entity_line(parser: &mut Parser, _indent_level: usize) -> Option<Conten
 
     // EntitySpeech: @name: text
     if remaining.starts_with(": ") || remaining == ":" {
-        let speech_text = if remaining.len() > 2 {
+        let mut speech_text = if remaining.len() > 2 {
             Parser::strip_inline_comment(&remaining[2..]).trim().to_string()
         } else {
             String::new()
         };
-        let span = parser.content_line_span(line_idx);
         parser.current_line += 1;
+        let span = take_continuations(parser, line_idx, indent_level, &mut speech_text);
+        let variants = take_variants(parser, indent_level);
         return Some(ContentNode::EntitySpeech(EntitySpeech {
             entity_ref,
             text: speech_text,
+            variants,
             annotation: None,
             span,
         }));
@@ -290,7 +608,7 @@ fn parse_arrow_line(parser: &mut Parser, indent_level: usize) -> ContentNode {
             let span = parser.content_line_span(line_idx);
             parser.current_line += 1;
 
-            // Collect exit children (indented Condition and BlockedMessage)
+            // Collect exit children (indented Condition, BlockedMessage and Effect)
             let children = parse_exit_children(parser, indent_level);
 
             return ContentNode::ExitDeclaration(ExitDeclaration {
@@ -311,19 +629,25 @@ fn parse_arrow_line(parser: &mut Parser, indent_level: usize) -> ContentNode {
         return ContentNode::Jump(Jump {
             target,
             is_exit_qualified: true,
+            argument: None,
             indent_level,
             annotation: None,
             span,
         });
     }
 
-    // Simple jump: -> name
-    let target = after_arrow.trim().to_string();
+    // Simple jump: -> name, or -> name(@entity) for a parameterised section
+    let (target, argument) = match split_jump_argument(after_arrow.trim()) {
+        Some(split) => split,
+        None => return make_error_node(parser, line_idx, Some("Jump")),
+    };
+    let target = target.to_string();
     let span = parser.content_line_span(line_idx);
     parser.current_line += 1;
     ContentNode::Jump(Jump {
         target,
         is_exit_qualified: false,
+        argument,
         indent_level,
         annotation: None,
         span,
@@ -354,11 +678,13 @@ fn parse_exit_children(parser: &mut Parser, exit_indent: usize) -> Vec<ContentNo
             break;
         }
 
-        // Only Condition and BlockedMessage attach as exit children
+        // Only Condition, BlockedMessage and Effect attach as exit children
         if rest.starts_with("? ") {
             children.push(parse_condition_line(parser, indent_level));
         } else if rest.starts_with("! ") {
             children.push(parse_blocked_message(parser, indent_level));
+        } else if rest.starts_with("> ") {
+            children.push(parse_effect_line(parser, indent_level));
         } else {
             // Not a valid exit child — stop collecting children
             break;
@@ -368,15 +694,76 @@ fn parse_exit_children(parser: &mut Parser, exit_indent: usize) -> Vec<ContentNo
     children
 }
 
+/// Parse an `on enter:` / `on exit:` block. Children are condition,
+/// `? any:` and effect lines indented deeper than the header.
+fn parse_location_hook(parser: &mut Parser, hook_indent: usize) -> ContentNode {
+    let start_line = parser.current_line;
+    let text = parser.check_tabs(start_line);
+    let (_, rest) = Parser::measure_indent(&text);
+    let kind = if Parser::strip_inline_comment(rest).trim_end() == "on enter:" {
+        HookKind::Enter
+    } else {
+        HookKind::Exit
+    };
+    parser.current_line += 1;
+
+    let mut children = Vec::new();
+    let mut end_line = start_line;
+
+    while !parser.at_end() {
+        let line = match parser.peek_line() {
+            Some(l) => l,
+            None => break,
+        };
+
+        // Skip blank lines
+        if line.trim().is_empty() {
+            parser.current_line += 1;
+            continue;
+        }
+
+        let processed = parser.check_tabs(parser.current_line);
+        let (indent_level, child_rest) = Parser::measure_indent(&processed);
+
+        // Must be strictly deeper than the hook header
+        if indent_level <= hook_indent {
+            break;
+        }
+
+        if child_rest.starts_with("? any:") {
+            children.push(parse_or_condition_block(parser, indent_level));
+        } else if child_rest.starts_with("? ") {
+            children.push(parse_condition_line(parser, indent_level));
+        } else if child_rest.starts_with("> ") {
+            children.push(parse_effect_line(parser, indent_level));
+        } else if child_rest.starts_with("// ") || child_rest == "//" {
+            children.push(parse_line_comment(parser));
+        } else {
+            let line_idx = parser.current_line;
+            children.push(make_error_node(parser, line_idx, Some("LocationHook")));
+        }
+        end_line = parser.current_line - 1;
+    }
+
+    let span = parser.content_span_lines(start_line, end_line);
+
+    ContentNode::LocationHook(LocationHook {
+        kind,
+        children,
+        span,
+    })
+}
+
 fn parse_condition_line(parser: &mut Parser, indent_level: usize) -> ContentNode {
     let line_idx = parser.current_line;
     let text = parser.check_tabs(line_idx);
     let (_, rest) = Parser::measure_indent(&text);
     let after_sigil = &rest[2..]; // skip "? "
     let span = parser.content_line_span(line_idx);
+    let col = (text.len() - after_sigil.len()) as u32 + 1;
     parser.current_line += 1;
 
-    match parse_condition_expr(after_sigil, &span) {
+    match parse_condition_expr(after_sigil, &span, Some(col)) {
         Some(expr) => ContentNode::Condition(Condition {
             expr,
             indent_level,
@@ -401,31 +788,93 @@ fn parse_condition_line(parser: &mut Parser, indent_level: usize) -> ContentNode
 }
 
 /// Given an entity/binding name and the text after the dot (e.g. "state == closed"),
-/// attempt to parse as a PropertyComparison.
-fn parse_prop_comparison(entity_ref: String, after_dot: &str, span: &Span) -> Option<ConditionExpr> {
+/// attempt to parse as a PropertyComparison. A lone property name (e.g.
+/// "locked") is a bare boolean read, stored as `== true`. `col` is the
+/// column `after_dot` starts at, as for [`parse_condition_expr()`].
+fn parse_prop_comparison(
+    entity_ref: String,
+    entity_columns: (u32, u32),
+    after_dot: &str,
+    span: &Span,
+    col: Option<u32>,
+) -> Option<ConditionExpr> {
     let ops = ["==", "!=", ">=", "<=", ">", "<"];
     for op in &ops {
         if let Some(op_pos) = after_dot.find(op) {
-            let property = after_dot[..op_pos].trim().to_string();
-            let value = after_dot[op_pos + op.len()..].trim().to_string();
+            let (property, property_at) = trim_at(&after_dot[..op_pos], 0);
+            let (value, value_at) = trim_at(&after_dot[op_pos + op.len()..], op_pos + op.len());
             if !property.is_empty() {
                 return Some(ConditionExpr::PropertyComparison(PropertyComparison {
                     entity_ref,
-                    property,
+                    property: property.to_string(),
                     operator: op.to_string(),
-                    value,
+                    value: value.to_string(),
+                    negated: false,
+                    bare: false,
                     annotation: None,
+                    spans: OperandSpans {
+                        entity: entity_columns,
+                        property: part_columns(col, property_at, property.len()),
+                        operator: part_columns(col, op_pos, op.len()),
+                        value: part_columns(col, value_at, value.len()),
+                    },
                     span: span.clone(),
                 }));
             }
         }
     }
+
+    let (property, property_at) = trim_at(after_dot, 0);
+    if !property.is_empty() && property.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        let property_columns = part_columns(col, property_at, property.len());
+        return Some(ConditionExpr::PropertyComparison(PropertyComparison {
+            entity_ref,
+            property: property.to_string(),
+            operator: "==".to_string(),
+            value: "true".to_string(),
+            negated: false,
+            bare: true,
+            annotation: None,
+            spans: OperandSpans {
+                entity: entity_columns,
+                property: property_columns,
+                operator: property_columns,
+                value: property_columns,
+            },
+            span: span.clone(),
+        }));
+    }
     None
 }
 
+/// `text` without surrounding whitespace, and the byte offset it starts
+/// at, given that `text` itself starts at `offset`.
+fn trim_at(text: &str, offset: usize) -> (&str, usize) {
+    let start = text.len() - text.trim_start().len();
+    (text.trim(), offset + start)
+}
+
+/// The span of `len` bytes at byte `offset` into an expression starting
+/// at column `col` of `span`'s line. The whole `span` when `col` is
+/// unknown.
+fn part_columns(col: Option<u32>, offset: usize, len: usize) -> (u32, u32) {
+    match col {
+        Some(col) => {
+            let start_col = col + offset as u32;
+            (start_col, start_col + len as u32)
+        }
+        None => (0, 0),
+    }
+}
+
 /// Parse a condition expression string into a ConditionExpr.
-pub(crate) fn parse_condition_expr(expr: &str, span: &Span) -> Option<ConditionExpr> {
-    let expr = expr.trim();
+///
+/// `col` is the column `expr` starts at on `span`'s line, so comparisons
+/// can record where each part is written. `None` when `expr` is not the
+/// source text as written: its parts then all span the whole `span`.
+pub(crate) fn parse_condition_expr(expr: &str, span: &Span, col: Option<u32>) -> Option<ConditionExpr> {
+    let (expr, start) = trim_at(expr, 0);
+    let col = col.map(|c| c + start as u32);
 
     // ExhaustionCheck: identifier.exhausted
     if expr.ends_with(".exhausted") {
@@ -437,15 +886,60 @@ pub(crate) fn parse_condition_expr(expr: &str, span: &Span) -> Option<ConditionE
         }));
     }
 
-    // ContainmentCheck: @entity in container / @entity not in container
-    if expr.starts_with('@') {
+    // Once: the bare keyword.
+    if expr == "once" {
+        return Some(ConditionExpr::Once(OnceCondition { span: span.clone() }));
+    }
+
+    // Negated comparison: not @entity.property op value. Recorded on the
+    // comparison so VALIDATE still sees the written operator.
+    if let Some(rest) = expr.strip_prefix("not ") {
+        return match parse_condition_expr(rest, span, col.map(|c| c + 4))? {
+            ConditionExpr::PropertyComparison(pc) if !pc.negated => {
+                Some(ConditionExpr::PropertyComparison(PropertyComparison { negated: true, ..pc }))
+            }
+            _ => None,
+        };
+    }
+
+    // VisitedCheck: visited location / !visited location
+    let (negated, visited) = match expr.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, expr),
+    };
+    if let Some(location) = visited.strip_prefix("visited ") {
+        let location = location.trim();
+        if !location.is_empty() && !location.contains(char::is_whitespace) {
+            return Some(ConditionExpr::VisitedCheck(VisitedCheck {
+                location_ref: location.to_string(),
+                negated,
+                annotation: None,
+                span: span.clone(),
+            }));
+        }
+    }
+
+    // Negated bare boolean read: !@entity.property
+    if let Some(rest) = expr.strip_prefix('!') {
+        return match parse_condition_expr(rest, span, col.map(|c| c + 1))? {
+            ConditionExpr::PropertyComparison(pc) if pc.bare && !pc.negated => {
+                Some(ConditionExpr::PropertyComparison(PropertyComparison { negated: true, ..pc }))
+            }
+            _ => None,
+        };
+    }
+
+    // ContainmentCheck: @entity in container / @entity not in container.
+    // A rule's select variable is written `$name` and keeps its sigil.
+    if expr.starts_with('@') || expr.starts_with('$') {
+        let sigil = if expr.starts_with('$') { "$" } else { "" };
         let rest = &expr[1..];
 
         // Find end of entity identifier
         let id_end = rest
             .find(|c: char| !c.is_ascii_alphanumeric() && c != '_')
             .unwrap_or(rest.len());
-        let entity = &rest[..id_end];
+        let entity = format!("{}{}", sigil, &rest[..id_end]);
         let after_entity = rest[id_end..].trim();
 
         if after_entity.starts_with("not in ") {
@@ -479,20 +973,20 @@ pub(crate) fn parse_condition_expr(expr: &str, span: &Span) -> Option<ConditionE
                 None
             }
         }) {
-            let entity_ref;
-            let remaining;
-
-            if dot_pos < id_end {
+            let entity_end = if dot_pos < id_end {
                 // dot is within what we thought was the id
-                entity_ref = rest[..dot_pos].to_string();
-                remaining = &rest[dot_pos + 1..];
+                dot_pos
             } else {
                 // dot is right after the id
-                entity_ref = rest[..id_end].to_string();
-                remaining = &rest[id_end + 1..];
-            }
+                id_end
+            };
+            let entity_ref = format!("{}{}", sigil, &rest[..entity_end]);
+            let remaining = &rest[entity_end + 1..];
+            // The `@` or `$` is byte 0; the dot follows the identifier.
+            let entity_columns = part_columns(col, 0, entity_end + 1);
+            let remaining_col = col.map(|c| c + entity_end as u32 + 2);
 
-            if let Some(result) = parse_prop_comparison(entity_ref, remaining, span) {
+            if let Some(result) = parse_prop_comparison(entity_ref, entity_columns, remaining, span, remaining_col) {
                 return Some(result);
             }
         }
@@ -507,7 +1001,9 @@ pub(crate) fn parse_condition_expr(expr: &str, span: &Span) -> Option<ConditionE
         if let Some(dot_pos) = expr.find('.') {
             let entity_ref = expr[..dot_pos].to_string();
             let after_dot = &expr[dot_pos + 1..];
-            if let Some(result) = parse_prop_comparison(entity_ref, after_dot, span) {
+            let entity_columns = part_columns(col, 0, dot_pos);
+            let after_dot_col = col.map(|c| c + dot_pos as u32 + 1);
+            if let Some(result) = parse_prop_comparison(entity_ref, entity_columns, after_dot, span, after_dot_col) {
                 return Some(result);
             }
         }
@@ -544,7 +1040,8 @@ fn parse_or_condition_block(parser: &mut Parser, indent_level: usize) -> Content
 
         // Parse as a bare condition expression (without ? prefix)
         let line_span = parser.line_span(parser.current_line);
-        if let Some(expr) = parse_condition_expr(rest, &line_span) {
+        let col = (processed.len() - rest.len()) as u32 + 1;
+        if let Some(expr) = parse_condition_expr(rest, &line_span, Some(col)) {
             conditions.push(expr);
         }
         parser.current_line += 1;
@@ -569,9 +1066,10 @@ fn parse_effect_line(parser: &mut Parser, indent_level: usize) -> ContentNode {
     let (_, rest) = Parser::measure_indent(&text);
     let after_sigil = &rest[2..]; // skip "> "
     let span = parser.content_line_span(line_idx);
+    let col = (text.len() - after_sigil.len()) as u32 + 1;
     parser.current_line += 1;
 
-    let effect_type = parse_effect_type(after_sigil);
+    let effect_type = parse_effect_type(after_sigil, Some(col));
 
     ContentNode::Effect(Effect {
         effect_type,
@@ -581,15 +1079,17 @@ fn parse_effect_line(parser: &mut Parser, indent_level: usize) -> ContentNode {
     })
 }
 
-/// Parse the effect type from the text after `> `.
-fn parse_effect_type(text: &str) -> EffectType {
-    let text = text.trim();
+/// Parse the effect type from the text after `> `. `col` is as for
+/// [`parse_condition_expr()`].
+fn parse_effect_type(text: &str, col: Option<u32>) -> EffectType {
+    let (text, start) = trim_at(text, 0);
+    let col = col.map(|c| c + start as u32);
 
-    // Move effect: move @entity -> container
+    // Move effect: move @entity -> container, or move $variable -> container in a rule
     if text.starts_with("move ") {
         let rest = &text[5..];
-        if rest.starts_with('@') {
-            let after_at = &rest[1..];
+        if rest.starts_with('@') || rest.starts_with('$') {
+            let after_at = rest.strip_prefix('@').unwrap_or(rest);
             if let Some(arrow_pos) = after_at.find(" -> ") {
                 let entity = after_at[..arrow_pos].trim().to_string();
                 let dest_raw = after_at[arrow_pos + 4..].trim();
@@ -619,18 +1119,31 @@ fn parse_effect_type(text: &str) -> EffectType {
         return EffectType::Destroy { entity_ref: entity };
     }
 
+    // End effect: end ending_id
+    if let Some(rest) = text.strip_prefix("end ") {
+        return EffectType::End { ending_ref: rest.trim().to_string() };
+    }
+
     // Set effect: @entity.prop = value or @entity.prop + N or @entity.prop - N
     // Look for operator: =, +, -
     let ops = [" = ", " + ", " - "];
     for op_str in &ops {
         if let Some(op_pos) = text.find(op_str) {
-            let target_prop = text[..op_pos].trim().to_string();
-            let operator = op_str.trim().to_string();
-            let value_expr = text[op_pos + op_str.len()..].trim().to_string();
+            let (target_prop, target_at) = trim_at(&text[..op_pos], 0);
+            let operator = op_str.trim();
+            let (value_expr, value_at) = trim_at(&text[op_pos + op_str.len()..], op_pos + op_str.len());
+            let entity_len = target_prop.find('.').unwrap_or(target_prop.len());
+            let property_len = target_prop.len().saturating_sub(entity_len + 1);
             return EffectType::Set {
-                target_prop,
-                operator,
-                value_expr,
+                target_prop: target_prop.to_string(),
+                operator: operator.to_string(),
+                value_expr: value_expr.to_string(),
+                spans: OperandSpans {
+                    entity: part_columns(col, target_at, entity_len),
+                    property: part_columns(col, target_at + entity_len + 1, property_len),
+                    operator: part_columns(col, op_pos + 1, operator.len()),
+                    value: part_columns(col, value_at, value_expr.len()),
+                },
             };
         }
     }
@@ -640,6 +1153,7 @@ fn parse_effect_type(text: &str) -> EffectType {
         target_prop: text.to_string(),
         operator: "=".to_string(),
         value_expr: String::new(),
+        spans: OperandSpans::whole(),
     }
 }
 
@@ -695,9 +1209,9 @@ fn parse_blocked_message(parser: &mut Parser, indent_level: usize) -> ContentNod
     let line_idx = parser.current_line;
     let text = parser.check_tabs(line_idx);
     let (_, rest) = Parser::measure_indent(&text);
-    let msg_text = Parser::strip_inline_comment(&rest[2..]).trim().to_string(); // skip "! "
-    let span = parser.content_line_span(line_idx);
+    let mut msg_text = Parser::strip_inline_comment(&rest[2..]).trim().to_string(); // skip "! "
     parser.current_line += 1;
+    let span = take_continuations(parser, line_idx, indent_level, &mut msg_text);
 
     ContentNode::BlockedMessage(BlockedMessage {
         text: msg_text,
@@ -713,31 +1227,38 @@ fn parse_entity_presence(parser: &mut Parser) -> ContentNode {
     let span = parser.content_line_span(line_idx);
     parser.current_line += 1;
 
-    // Extract entities from [@entity1, @entity2]
+    // Extract entities from [@entity1, @entity2 (prop: value)]
     let inner = if rest.starts_with('[') && rest.ends_with(']') {
         &rest[1..rest.len() - 1]
     } else {
         rest
     };
 
-    let entity_refs: Vec<String> = inner
-        .split(',')
-        .map(|s| {
-            let s = s.trim();
-            if s.starts_with('@') {
-                s[1..].to_string()
-            } else {
-                s.to_string()
+    let mut entity_refs = Vec::new();
+    let mut property_overrides = Vec::new();
+    for entry in split_top_level(inner, ',') {
+        let entry = entry.trim();
+        let (name, overrides) = match entry.find('(') {
+            Some(open) => {
+                let inside = &entry[open + 1..];
+                let inside = inside.strip_suffix(')').unwrap_or(inside);
+                (entry[..open].trim(), parse_inline_object(inside))
             }
-        })
-        .filter(|s| !s.is_empty())
-        .collect();
+            None => (entry, Vec::new()),
+        };
+        let name = name.strip_prefix('@').unwrap_or(name);
+        if !name.is_empty() {
+            entity_refs.push(name.to_string());
+            property_overrides.push(overrides);
+        }
+    }
 
     let annotations = entity_refs.iter().map(|_| None).collect();
 
     ContentNode::EntityPresence(EntityPresence {
         entity_refs,
         annotations,
+        property_overrides,
         span,
     })
 }
@@ -746,7 +1267,10 @@ fn parse_line_comment(parser: &mut Parser) -> ContentNode {
     let line_idx = parser.current_line;
     let text = parser.check_tabs(line_idx);
     let (_, rest) = Parser::measure_indent(&text);
-    let comment_text = if rest.len() > 3 {
+    let comment_text = if Parser::is_doc_comment(rest) {
+        let text = &rest[3..]; // skip "///"
+        text.strip_prefix(' ').unwrap_or(text).to_string()
+    } else if rest.len() > 3 {
         rest[3..].to_string() // skip "// "
     } else {
         String::new() // bare "//"
@@ -760,21 +1284,29 @@ fn parse_line_comment(parser: &mut Parser) -> ContentNode {
     })
 }
 
-fn parse_prose(parser: &mut Parser) -> ContentNode {
+fn parse_prose(parser: &mut Parser, indent_level: usize) -> ContentNode {
     let line_idx = parser.current_line;
     let text = parser.check_tabs(line_idx);
     let (_, rest) = Parser::measure_indent(&text);
-    let prose_text = Parser::strip_inline_comment(rest).trim().to_string();
-    let span = parser.content_line_span(line_idx);
+    let mut prose_text = Parser::strip_inline_comment(rest).trim().to_string();
     parser.current_line += 1;
+    // Only a choice's response continues; top-level indented prose stays
+    // a node of its own.
+    let span = if indent_level > 0 {
+        take_continuations(parser, line_idx, indent_level, &mut prose_text)
+    } else {
+        parser.content_line_span(line_idx)
+    };
+    let variants = take_variants(parser, indent_level);
 
     ContentNode::Prose(Prose {
         text: prose_text,
+        variants,
         span,
     })
 }
 
-fn parse_rule_block(parser: &mut Parser, _indent_level: usize) -> ContentNode {
+fn parse_rule_block(parser: &mut Parser, indent_level: usize) -> ContentNode {
     let start_line = parser.current_line;
     let text = parser.check_tabs(start_line);
     let (rule_indent, rest) = Parser::measure_indent(&text);
@@ -828,7 +1360,8 @@ fn parse_rule_block(parser: &mut Parser, _indent_level: usize) -> ContentNode {
         // Select line: selects variable from [@a, @b]
         if body_rest.starts_with("selects ") {
             if let Some(from_pos) = body_rest.find(" from ") {
-                let variable = body_rest[8..from_pos].trim().to_string();
+                let variable = body_rest[8..from_pos].trim();
+                let variable = variable.strip_prefix('$').unwrap_or(variable).to_string();
                 let from_text = &body_rest[from_pos + 6..];
                 let entity_refs = parse_entity_ref_list(from_text);
 
@@ -851,7 +1384,7 @@ fn parse_rule_block(parser: &mut Parser, _indent_level: usize) -> ContentNode {
                     }
                     if wr.starts_with("where ") {
                         let ws = parser.line_span(parser.current_line);
-                        if let Some(expr) = parse_condition_expr(&wr[6..], &ws) {
+                        if let Some(expr) = parse_condition_expr(&bind_variable(&wr[6..], Some(&variable)), &ws, None) {
                             select_where.push(expr);
                         }
                         parser.current_line += 1;
@@ -860,9 +1393,11 @@ fn parse_rule_block(parser: &mut Parser, _indent_level: usize) -> ContentNode {
                     }
                 }
 
+                let annotations = entity_refs.iter().map(|_| None).collect();
                 select = Some(SelectClause {
                     variable,
                     entity_refs,
+                    annotations,
                     where_clauses: select_where,
                     span: body_span,
                 });
@@ -870,9 +1405,11 @@ fn parse_rule_block(parser: &mut Parser, _indent_level: usize) -> ContentNode {
             }
         }
 
+        let variable = select.as_ref().map(|s| s.variable.as_str());
+
         // Where line: where condition
         if body_rest.starts_with("where ") {
-            if let Some(expr) = parse_condition_expr(&body_rest[6..], &body_span) {
+            if let Some(expr) = parse_condition_expr(&bind_variable(&body_rest[6..], variable), &body_span, None) {
                 where_clauses.push(expr);
             }
             parser.current_line += 1;
@@ -881,7 +1418,7 @@ fn parse_rule_block(parser: &mut Parser, _indent_level: usize) -> ContentNode {
 
         // Effect line: > effect
         if body_rest.starts_with("> ") {
-            let effect_type = parse_effect_type(&body_rest[2..]);
+            let effect_type = parse_effect_type(&bind_variable(&body_rest[2..], variable), None);
             effects.push(Effect {
                 effect_type,
                 indent_level: body_indent,
@@ -905,10 +1442,35 @@ fn parse_rule_block(parser: &mut Parser, _indent_level: usize) -> ContentNode {
         select,
         where_clauses,
         effects,
+        indent_level,
         span,
     })
 }
 
+/// Rewrite a bare use of a rule's select variable (`door.prize`,
+/// `move door -> here`) to the `$door` form LINK resolves. Other text is
+/// unchanged.
+fn bind_variable(text: &str, variable: Option<&str>) -> String {
+    let text = text.trim();
+    let Some(variable) = variable else {
+        return text.to_string();
+    };
+    let keyword = ["reveal ", "move ", "destroy "]
+        .into_iter()
+        .find(|k| text.starts_with(k))
+        .unwrap_or("");
+    let rest = text[keyword.len()..].trim_start();
+    let bound = rest.strip_prefix(variable).is_some_and(|after| match keyword {
+        "move " | "destroy " => after.is_empty() || after.starts_with(' ') || after.starts_with('.'),
+        _ => after.starts_with('.'),
+    });
+    if bound {
+        format!("{}${}", keyword, rest)
+    } else {
+        text.to_string()
+    }
+}
+
 /// Parse an entity reference list like `[@a, @b, @c]`.
 fn parse_entity_ref_list(text: &str) -> Vec<String> {
     let text = text.trim();
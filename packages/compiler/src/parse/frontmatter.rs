@@ -5,7 +5,7 @@
 
 use crate::ast::*;
 use crate::graph::MAX_FRONTMATTER_NESTING_DEPTH;
-use super::Parser;
+use super::{GuardLine, Parser};
 
 /// Parse the frontmatter region between opening and closing `---` delimiters.
 /// `start_line` is the first line after the opening `---`.
@@ -21,6 +21,13 @@ pub(crate) fn parse_frontmatter(
         parser.line_span(start_line.min(parser.lines.len() - 1))
     };
 
+    let entries = parse_entries(parser, start_line, end_line);
+    Frontmatter { entries, span }
+}
+
+/// Parse the top-level entries from `start_line` up to `end_line`
+/// (exclusive). A guard holds the entries up to its `?endif`.
+fn parse_entries(parser: &mut Parser, start_line: usize, end_line: usize) -> Vec<FrontmatterEntry> {
     let mut entries = Vec::new();
     let mut i = start_line;
 
@@ -33,8 +40,29 @@ pub(crate) fn parse_frontmatter(
             continue;
         }
 
-        // Skip comment lines
-        if raw_text.trim_start().starts_with('#') {
+        // Guards: `?if flag:` … `?endif`, at column 0 only
+        if let Some(guard) = Parser::guard_line(raw_text) {
+            match guard {
+                GuardLine::Open(condition) => {
+                    let (block, next) = parser.parse_guard(i, condition, end_line, parse_entries);
+                    entries.push(FrontmatterEntry {
+                        key: "?if".to_string(),
+                        span: block.span.clone(),
+                        value: FrontmatterValue::Conditional(block),
+                    });
+                    i = next;
+                }
+                GuardLine::Close => {
+                    parser.unmatched_endif(i);
+                    i += 1;
+                }
+            }
+            continue;
+        }
+
+        // Skip comment lines (including `///` doc comments, which only
+        // attach to type and entity declarations)
+        if raw_text.trim_start().starts_with('#') || Parser::is_doc_comment(raw_text) {
             i += 1;
             continue;
         }
@@ -79,7 +107,7 @@ pub(crate) fn parse_frontmatter(
         }
     }
 
-    Frontmatter { entries, span }
+    entries
 }
 
 /// Check for rejected YAML constructs and emit educational errors.
@@ -186,7 +214,7 @@ fn parse_entry(
     }
 
     let after_colon = trimmed[colon_pos + 1..].trim();
-    let span = parser.line_span(line_idx);
+    let mut span = parser.line_span(line_idx);
 
     // Determine the value based on what follows the colon
     let value = if key == "import" {
@@ -220,6 +248,11 @@ fn parse_entry(
         *i += 1;
         let entity_entries = parse_entities_block(parser, i, end_line, indent_spaces + 2);
         FrontmatterValue::Map(entity_entries)
+    } else if key == "endings" {
+        // Endings block — one named ending per line
+        *i += 1;
+        let ending_entries = parse_endings_block(parser, i, end_line, indent_spaces + 2);
+        FrontmatterValue::Map(ending_entries)
     } else if after_colon.is_empty() {
         // Block value — nested entries
         *i += 1;
@@ -250,6 +283,14 @@ fn parse_entry(
         // Check if it's an entity declaration: @name: Type { overrides }
         // For simple entity ref in a value position, store as scalar
         FrontmatterValue::Scalar(Scalar::String(after_colon.to_string()))
+    } else if let Some(style) = BlockStyle::of(after_colon) {
+        // Block scalar — the indented lines below
+        *i += 1;
+        let (text, last_line) = parse_block_scalar(parser, style, i, end_line, indent_spaces);
+        if let Some(last_line) = last_line {
+            span = parser.span_lines(line_idx, last_line);
+        }
+        FrontmatterValue::Scalar(Scalar::String(text))
     } else {
         // Scalar value
         *i += 1;
@@ -287,15 +328,18 @@ fn parse_world_fields(
         }
 
         let clean = strip_frontmatter_comment(trimmed);
+        *i += 1;
         if let Some(colon_pos) = clean.find(':') {
             let key = clean[..colon_pos].trim().to_string();
             let val = clean[colon_pos + 1..].trim();
+            let value = match BlockStyle::of(val) {
+                Some(style) => Scalar::String(parse_block_scalar(parser, style, i, end_line, indent_spaces).0),
+                None => parse_scalar_value(val),
+            };
             if !key.is_empty() {
-                fields.push((key, parse_scalar_value(val)));
+                fields.push((key, value));
             }
         }
-
-        *i += 1;
     }
 
     fields
@@ -417,10 +461,14 @@ fn parse_type_definition(
             break;
         }
 
-        if let Some(prop) = parse_property_def(parser, *i) {
+        if Parser::is_doc_comment(ptrimmed) {
+            *i += 1;
+            continue;
+        }
+
+        if let Some(prop) = parse_property_def(parser, i, end_line) {
             properties.push(prop);
         }
-        *i += 1;
     }
 
     let span = if *i > line_idx + 1 {
@@ -433,15 +481,21 @@ fn parse_type_definition(
         name,
         traits,
         properties,
+        doc: parser.doc_comment_above(line_idx),
         span,
     })
 }
 
 /// Parse a property definition line: `name: type` or `~name: type = default`.
-fn parse_property_def(parser: &mut Parser, line_idx: usize) -> Option<PropertyDef> {
+/// A default of `|` or `>` is a block scalar on the lines below. Advances
+/// `i` past the consumed lines.
+fn parse_property_def(parser: &mut Parser, i: &mut usize, end_line: usize) -> Option<PropertyDef> {
+    let line_idx = *i;
+    *i += 1;
     let text = parser.check_tabs(line_idx);
+    let indent_spaces = text.len() - text.trim_start().len();
     let trimmed = strip_frontmatter_comment(text.trim());
-    let span = parser.line_span(line_idx);
+    let mut span = parser.line_span(line_idx);
 
     // Check for hidden prefix
     let (is_hidden, rest) = if trimmed.starts_with('~') {
@@ -459,7 +513,17 @@ fn parse_property_def(parser: &mut Parser, line_idx: usize) -> Option<PropertyDe
     let (type_str, default) = if let Some(eq_pos) = type_and_default.find(" = ") {
         let t = type_and_default[..eq_pos].trim();
         let d = type_and_default[eq_pos + 3..].trim();
-        (t, Some(parse_scalar_value(d)))
+        let default = match BlockStyle::of(d) {
+            Some(style) => {
+                let (text, last_line) = parse_block_scalar(parser, style, i, end_line, indent_spaces);
+                if let Some(last_line) = last_line {
+                    span = parser.span_lines(line_idx, last_line);
+                }
+                Scalar::String(text)
+            }
+            None => parse_scalar_value(d),
+        };
+        (t, Some(default))
     } else {
         (type_and_default, None)
     };
@@ -620,6 +684,66 @@ fn parse_entities_block(
     entries
 }
 
+/// Parse an endings: block. Each line is `id:` or `id: description`, and
+/// a description may be a block scalar.
+fn parse_endings_block(
+    parser: &mut Parser,
+    i: &mut usize,
+    end_line: usize,
+    child_indent: usize,
+) -> Vec<FrontmatterEntry> {
+    let mut entries = Vec::new();
+
+    while *i < end_line {
+        let text = parser.check_tabs(*i);
+        let trimmed = text.trim();
+
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            *i += 1;
+            continue;
+        }
+
+        let indent_spaces = text.len() - text.trim_start().len();
+        if indent_spaces < child_indent {
+            break;
+        }
+
+        let line_idx = *i;
+        let clean = strip_frontmatter_comment(trimmed);
+        *i += 1;
+        let Some(colon_pos) = clean.find(':') else {
+            continue;
+        };
+        let id = clean[..colon_pos].trim().to_string();
+        if id.is_empty() {
+            continue;
+        }
+        let val = clean[colon_pos + 1..].trim();
+        let mut span = parser.line_span(line_idx);
+        let description = match BlockStyle::of(val) {
+            Some(style) => {
+                let (text, last_line) = parse_block_scalar(parser, style, i, end_line, indent_spaces);
+                if let Some(last_line) = last_line {
+                    span = parser.span_lines(line_idx, last_line);
+                }
+                Some(text)
+            }
+            None if val.is_empty() => None,
+            None => match parse_scalar_value(val) {
+                Scalar::String(text) => Some(text),
+                _ => Some(val.to_string()),
+            },
+        };
+        entries.push(FrontmatterEntry {
+            key: id.clone(),
+            value: FrontmatterValue::EndingDecl(EndingDecl { id, description, span: span.clone() }),
+            span,
+        });
+    }
+
+    entries
+}
+
 /// Parse an entity declaration: `@name: Type { overrides }`.
 fn parse_entity_declaration(parser: &mut Parser, line_idx: usize) -> Option<EntityDecl> {
     let text = parser.check_tabs(line_idx);
@@ -654,6 +778,7 @@ fn parse_entity_declaration(parser: &mut Parser, line_idx: usize) -> Option<Enti
         type_name,
         property_overrides: overrides,
         annotation: None,
+        doc: parser.doc_comment_above(line_idx),
         span,
     })
 }
@@ -693,7 +818,7 @@ fn parse_nested_entries(
 }
 
 /// Parse an inline object: `{ key: value, key2: value2 }`.
-fn parse_inline_object(s: &str) -> Vec<(String, Scalar)> {
+pub(super) fn parse_inline_object(s: &str) -> Vec<(String, Scalar)> {
     let inner = s.trim();
     let inner = if inner.starts_with('{') && inner.ends_with('}') {
         &inner[1..inner.len() - 1]
@@ -732,7 +857,7 @@ fn parse_flow_list(s: &str) -> Vec<String> {
 }
 
 /// Split a string by a delimiter, respecting nested brackets and quotes.
-fn split_top_level(s: &str, delimiter: char) -> Vec<String> {
+pub(super) fn split_top_level(s: &str, delimiter: char) -> Vec<String> {
     let mut result = Vec::new();
     let mut current = String::new();
     let mut depth = 0;
@@ -779,6 +904,87 @@ fn split_top_level(s: &str, delimiter: char) -> Vec<String> {
     result
 }
 
+/// How a block scalar joins its lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BlockStyle {
+    /// `|`: line breaks are kept.
+    Literal,
+    /// `>`: lines are joined with spaces; a blank line is a line break.
+    Folded,
+}
+
+impl BlockStyle {
+    /// The style a value marks, if it is exactly `|` or `>`.
+    fn of(value: &str) -> Option<Self> {
+        match value {
+            "|" => Some(BlockStyle::Literal),
+            ">" => Some(BlockStyle::Folded),
+            _ => None,
+        }
+    }
+}
+
+/// Read a block scalar: the lines from `i` indented deeper than
+/// `key_indent`, ending at the first non-blank line that is not, or at
+/// `end_line`. The first content line sets the block's indentation, which
+/// is removed from every line; deeper indentation is kept. Trailing blank
+/// lines and the final line break are dropped, and `#` is text, not a
+/// comment. An indented `---` is content: only a `---` at the start of a
+/// line closes the frontmatter.
+///
+/// Advances `i` past the block. Returns the text and the index of its last
+/// non-blank line, or `None` for an empty block.
+fn parse_block_scalar(
+    parser: &mut Parser,
+    style: BlockStyle,
+    i: &mut usize,
+    end_line: usize,
+    key_indent: usize,
+) -> (String, Option<usize>) {
+    let mut lines: Vec<String> = Vec::new();
+    let mut block_indent = None;
+    let mut last_line = None;
+
+    while *i < end_line {
+        let text = parser.check_tabs(*i);
+        if text.trim().is_empty() {
+            lines.push(String::new());
+            *i += 1;
+            continue;
+        }
+        let indent = text.len() - text.trim_start().len();
+        if indent <= key_indent {
+            break;
+        }
+        let strip = indent.min(*block_indent.get_or_insert(indent));
+        lines.push(text[strip..].trim_end().to_string());
+        last_line = Some(*i);
+        *i += 1;
+    }
+
+    while lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+    }
+    let text = match style {
+        BlockStyle::Literal => lines.join("\n"),
+        BlockStyle::Folded => {
+            let mut folded = String::new();
+            for line in &lines {
+                if line.is_empty() {
+                    folded.push('\n');
+                } else {
+                    if !folded.is_empty() && !folded.ends_with('\n') {
+                        folded.push(' ');
+                    }
+                    folded.push_str(line.trim_start());
+                }
+            }
+            folded
+        }
+    };
+    (text, last_line)
+}
+
 /// Parse a scalar value from a string.
 pub(crate) fn parse_scalar_value(s: &str) -> Scalar {
     let s = s.trim();
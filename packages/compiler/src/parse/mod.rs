@@ -12,7 +12,7 @@ mod frontmatter;
 mod content;
 
 use crate::ast::*;
-use crate::diagnostics::DiagnosticCollector;
+use crate::diagnostics::{Diagnostic, DiagnosticCollector};
 use crate::graph::MAX_FILE_SIZE;
 use crate::span::{FilePath, Span};
 
@@ -24,6 +24,23 @@ use crate::span::{FilePath, Span};
 ///
 /// All other errors produce `ErrorNode` markers in the AST.
 pub fn parse(path: &FilePath, source: &str, diagnostics: &mut DiagnosticCollector) -> Option<FileAst> {
+    parse_with(path, source, diagnostics, true)
+}
+
+/// Parse only a file's frontmatter, leaving `content` empty.
+///
+/// Used by dry-run import plans, which need a file's `import:` lines but
+/// nothing below them. Returns `None` in the same cases as [`parse()`].
+pub fn parse_frontmatter_only(path: &FilePath, source: &str, diagnostics: &mut DiagnosticCollector) -> Option<FileAst> {
+    parse_with(path, source, diagnostics, false)
+}
+
+fn parse_with(
+    path: &FilePath,
+    source: &str,
+    diagnostics: &mut DiagnosticCollector,
+    with_content: bool,
+) -> Option<FileAst> {
     // URD103: File size check (before any parsing — first thing after receiving source)
     if source.len() > MAX_FILE_SIZE {
         diagnostics.error(
@@ -38,7 +55,13 @@ pub fn parse(path: &FilePath, source: &str, diagnostics: &mut DiagnosticCollecto
     let source = source.strip_prefix('\u{FEFF}').unwrap_or(source);
 
     let mut parser = Parser::new(path, source, diagnostics);
-    parser.parse_file()
+    let ast = parser.parse_file(with_content);
+    for diagnostic in std::mem::take(&mut parser.guard_errors) {
+        parser.diagnostics.emit(diagnostic);
+    }
+    let mut ast = ast?;
+    ast.source_hash = Some(crate::hash::sha256_hex(source.as_bytes()));
+    Some(ast)
 }
 
 /// Line-oriented parser state.
@@ -47,7 +70,22 @@ pub(crate) struct Parser<'a> {
     pub source: &'a str,
     pub lines: Vec<LineInfo<'a>>,
     pub current_line: usize,
+    /// One past the last line content parsing may read. A guard narrows
+    /// it to its `?endif` while its children are parsed.
+    pub limit: usize,
     pub diagnostics: &'a mut DiagnosticCollector,
+    /// Guards open around the line being parsed.
+    guard_depth: usize,
+    /// URD113 errors. They are reported whatever the flags, so they are
+    /// kept out of the diagnostics a guard holds back.
+    guard_errors: Vec<Diagnostic>,
+}
+
+/// A guard line: `?if flag:`, `?if !flag:`, or `?endif`, at column 0.
+pub(crate) enum GuardLine<'t> {
+    /// `?if` and the condition written after it, trimmed.
+    Open(&'t str),
+    Close,
 }
 
 /// Pre-computed information about a source line.
@@ -67,9 +105,12 @@ impl<'a> Parser<'a> {
         Parser {
             file_path: file_path.to_string(),
             source,
+            limit: lines.len(),
             lines,
             current_line: 0,
             diagnostics,
+            guard_depth: 0,
+            guard_errors: Vec::new(),
         }
     }
 
@@ -165,7 +206,7 @@ impl<'a> Parser<'a> {
 
     /// Check if we've consumed all lines.
     pub(crate) fn at_end(&self) -> bool {
-        self.current_line >= self.lines.len()
+        self.current_line >= self.limit
     }
 
     /// Peek at the current line text without advancing.
@@ -245,6 +286,140 @@ impl<'a> Parser<'a> {
         (indent_level, rest)
     }
 
+    /// Whether a line is a `///` doc comment. Doc comments are ordinary
+    /// comments to every rule except the declaration directly below them.
+    pub(crate) fn is_doc_comment(text: &str) -> bool {
+        text.trim_start().starts_with("///")
+    }
+
+    /// Collect the `///` doc comment block directly above `line_idx`:
+    /// consecutive doc lines with no blank line in between, marker and one
+    /// following space stripped, joined with newlines.
+    pub(crate) fn doc_comment_above(&self, line_idx: usize) -> Option<String> {
+        let mut start = line_idx;
+        while start > 0 && Self::is_doc_comment(self.lines[start - 1].text) {
+            start -= 1;
+        }
+        if start == line_idx {
+            return None;
+        }
+        let lines: Vec<&str> = self.lines[start..line_idx]
+            .iter()
+            .map(|line| {
+                let text = &line.text.trim()[3..];
+                text.strip_prefix(' ').unwrap_or(text)
+            })
+            .collect();
+        Some(lines.join("\n"))
+    }
+
+    /// Classify a line as a guard. A guard starts at column 0; an
+    /// indented `?if` is not one.
+    pub(crate) fn guard_line(text: &str) -> Option<GuardLine<'_>> {
+        let text = Self::strip_inline_comment(text.trim_end());
+        if text == "?endif" {
+            return Some(GuardLine::Close);
+        }
+        let rest = text.strip_prefix("?if")?;
+        if !rest.is_empty() && !rest.starts_with(' ') {
+            return None;
+        }
+        Some(GuardLine::Open(rest.trim()))
+    }
+
+    /// The `?endif` closing the guard opened on `open`, before `limit`.
+    fn matching_endif(&self, open: usize, limit: usize) -> Option<usize> {
+        let mut depth = 0;
+        for i in open + 1..limit {
+            match Self::guard_line(self.lines[i].text) {
+                Some(GuardLine::Open(_)) => depth += 1,
+                Some(GuardLine::Close) if depth == 0 => return Some(i),
+                Some(GuardLine::Close) => depth -= 1,
+                None => {}
+            }
+        }
+        None
+    }
+
+    /// Parse the guard opened on line `open` with `condition` written
+    /// after its `?if`. `children` parses the lines from its first to its
+    /// second argument, exclusive; while it runs, `limit` is the `?endif`
+    /// and diagnostics go to the block rather than the collector. An
+    /// unclosed guard runs to `limit`. Returns the block and the line
+    /// after it.
+    pub(crate) fn parse_guard<T>(
+        &mut self,
+        open: usize,
+        condition: &str,
+        limit: usize,
+        children: impl FnOnce(&mut Self, usize, usize) -> Vec<T>,
+    ) -> (ConditionalBlock<T>, usize) {
+        let written = self.lines[open].text.trim();
+        let (negated, flag) = match condition.strip_suffix(':').map(str::trim_end) {
+            Some(flag) => match flag.strip_prefix('!') {
+                Some(flag) => (true, flag),
+                None => (false, flag),
+            },
+            None => (false, ""),
+        };
+        let valid = !flag.is_empty()
+            && flag.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+        if !valid {
+            self.guard_error(
+                format!("Malformed guard '{}'. Expected '?if flag:' or '?if !flag:'.", written),
+                open,
+            );
+        } else if self.guard_depth >= 2 {
+            self.guard_error(
+                format!("Guard '{}' is nested two levels deep. Guards nest one level.", written),
+                open,
+            );
+        }
+
+        let end = self.matching_endif(open, limit);
+        if end.is_none() {
+            self.guard_error(
+                format!("Unclosed guard '{}'. Expected '?endif' at the start of a line.", written),
+                open,
+            );
+        }
+        let end = end.unwrap_or(limit);
+
+        let outer_limit = std::mem::replace(&mut self.limit, end);
+        let outer = std::mem::take(&mut *self.diagnostics);
+        self.guard_depth += 1;
+        let children = children(self, open + 1, end);
+        self.guard_depth -= 1;
+        let held = std::mem::replace(&mut *self.diagnostics, outer);
+        self.limit = outer_limit;
+
+        let block = ConditionalBlock {
+            flag: flag.to_string(),
+            negated,
+            children,
+            diagnostics: held.all().to_vec(),
+            span: self.span_lines(open, end.min(limit - 1)),
+        };
+        (block, (end + 1).min(limit))
+    }
+
+    /// Report an `?endif` with no guard open.
+    pub(crate) fn unmatched_endif(&mut self, line_idx: usize) {
+        self.guard_error("'?endif' without a matching '?if'.".to_string(), line_idx);
+    }
+
+    fn guard_error(&mut self, message: String, line_idx: usize) {
+        self.guard_errors.push(Diagnostic {
+            severity: crate::diagnostics::Severity::Error,
+            code: "URD113".to_string(),
+            message,
+            span: self.line_span(line_idx),
+            suggestion: None,
+            fix: None,
+            related: Vec::new(),
+        });
+    }
+
     /// Strip inline comments from text content.
     /// Returns the text with any trailing ` // comment` removed and trimmed.
     pub(crate) fn strip_inline_comment(text: &str) -> &str {
@@ -256,8 +431,9 @@ impl<'a> Parser<'a> {
         }
     }
 
-    /// Main entry point: parse the file into a FileAst.
-    fn parse_file(&mut self) -> Option<FileAst> {
+    /// Main entry point: parse the file into a FileAst. Without
+    /// `with_content`, parsing stops after the frontmatter.
+    fn parse_file(&mut self, with_content: bool) -> Option<FileAst> {
         let file_span_end = if self.lines.is_empty() {
             Span::new(self.file_path.clone(), 1, 1, 1, 1)
         } else {
@@ -286,12 +462,20 @@ impl<'a> Parser<'a> {
             let close_line = match close_line {
                 Some(cl) => cl,
                 None => {
-                    // URD101: Unclosed frontmatter
-                    self.diagnostics.error(
-                        "URD101",
-                        "Unclosed frontmatter block. Expected closing '---'.",
-                        self.line_span(open_line),
-                    );
+                    // URD101: Unclosed frontmatter. An indented '---' is
+                    // block content, so point at one if the author meant it
+                    // to close the frontmatter.
+                    let indented = (self.current_line..self.lines.len())
+                        .find(|&i| self.lines[i].text.trim() == "---");
+                    let message = match indented {
+                        Some(i) => format!(
+                            "Unclosed frontmatter block. Expected closing '---'. The '---' at line {} is indented, \
+                             so it is read as frontmatter content; the closing '---' must start its line.",
+                            self.lines[i].line_number,
+                        ),
+                        None => "Unclosed frontmatter block. Expected closing '---'.".to_string(),
+                    };
+                    self.diagnostics.error("URD101", message, self.line_span(open_line));
                     return None;
                 }
             };
@@ -303,23 +487,25 @@ impl<'a> Parser<'a> {
             self.current_line = close_line + 1;
 
             // Parse content
-            let content = content::parse_content(self, 0);
+            let content = if with_content { content::parse_content(self, 0) } else { Vec::new() };
 
             Some(FileAst {
                 path: self.file_path.clone(),
                 frontmatter: Some(fm),
                 content,
                 span: file_span_end,
+                source_hash: None,
             })
         } else {
             // No frontmatter — entire file is content
-            let content = content::parse_content(self, 0);
+            let content = if with_content { content::parse_content(self, 0) } else { Vec::new() };
 
             Some(FileAst {
                 path: self.file_path.clone(),
                 frontmatter: None,
                 content,
                 span: file_span_end,
+                source_hash: None,
             })
         }
     }
@@ -0,0 +1,93 @@
+/// JSON payloads for hosts that receive compiler output as text, chiefly
+/// the WASM bindings in `wasm.rs`.
+///
+/// The projection lives here rather than behind the `wasm` feature so that
+/// native tests cover the same shapes JavaScript sees. Every payload has
+/// `success` and `diagnostics`; the analysis fields (`facts`,
+/// `property_index`, `definition_index`) are added on request, and are
+/// `null` when LINK did not run.
+///
+/// A panic inside the compiler is never allowed to cross the FFI boundary
+/// as an unwind. [`guarded()`] turns one into a payload with
+/// `success: false` and an `error` object of kind `"panic"`.
+
+use std::panic::{self, AssertUnwindSafe};
+
+use serde_json::{json, Value};
+
+use crate::diagnostics::DiagnosticCollector;
+use crate::CompilationResult;
+
+/// Compile `source` as `filename` in single-file mode and project the
+/// result. `include_facts` adds the analysis fields.
+///
+/// Returns:
+/// ```json
+/// { "success": bool, "world": string|null, "diagnostics": [...],
+///   "facts": object|null, "property_index": object|null,
+///   "definition_index": object|null }
+/// ```
+/// An empty `filename` gives an `error` of kind `"invalid_argument"`, and a
+/// compiler panic one of kind `"panic"`; neither has a `world`.
+pub fn compile_full(filename: &str, source: &str, include_facts: bool) -> Value {
+    if filename.trim().is_empty() {
+        return error("invalid_argument", "The filename must not be empty.".to_string());
+    }
+    guarded(|| {
+        let result = crate::compile_source(filename, source);
+        result_json(&result, include_facts)
+    })
+}
+
+/// Project a compilation result. The analysis fields are present only when
+/// `include_analysis` is set.
+pub fn result_json(result: &CompilationResult, include_analysis: bool) -> Value {
+    let mut payload = json!({
+        "success": result.success,
+        "world": result.world,
+        "diagnostics": diagnostics_json(&result.diagnostics),
+    });
+    if include_analysis {
+        payload["facts"] = json!(result.fact_set.as_ref().map(|fs| fs.to_json()));
+        payload["property_index"] = json!(result.property_index.as_ref().map(|idx| idx.to_json()));
+        payload["definition_index"] = json!(result.definition_index.as_ref().map(|idx| idx.to_json()));
+    }
+    payload
+}
+
+/// Every diagnostic in the collector, sorted, as
+/// [`DiagnosticCollector::to_json()`] shapes it.
+pub fn diagnostics_json(collector: &DiagnosticCollector) -> Value {
+    collector.to_json()
+}
+
+/// Run `f`, turning a panic into an error payload:
+/// ```json
+/// { "success": false, "world": null, "diagnostics": [],
+///   "error": { "kind": "panic", "message": string } }
+/// ```
+///
+/// On `wasm32` this only helps when the module is built with unwinding;
+/// the default `panic = "abort"` traps before `f` returns.
+pub fn guarded(f: impl FnOnce() -> Value) -> Value {
+    match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(payload) => payload,
+        Err(cause) => {
+            let message = cause
+                .downcast_ref::<&str>()
+                .map(|s| s.to_string())
+                .or_else(|| cause.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            error("panic", format!("The compiler panicked: {}", message))
+        }
+    }
+}
+
+fn error(kind: &str, message: String) -> Value {
+    json!({
+        "success": false,
+        "world": null,
+        "diagnostics": [],
+        "error": { "kind": kind, "message": message },
+    })
+}
@@ -0,0 +1,187 @@
+/// Playable export: a compiled world as one self-contained HTML page.
+///
+/// `urd play <file> -o world.html` writes a page that embeds the emitted
+/// JSON and a small walker (`walker.js`, no framework) for clicking
+/// through a world with people who will never run a runtime. It is a
+/// stub, not a runtime. The walker shows the start location's description,
+/// contents, and exits, offers the dialogue sections under each location,
+/// and plays their prompts, choices, and responses. It applies `set` and
+/// `move` effects to an in-memory state, follows `goto` and exit jumps,
+/// and evaluates the lowered condition forms this module supports:
+///
+/// | Form | Example |
+/// |------|---------|
+/// | Property comparison | `arina.trust >= 3` |
+/// | Containment | `rusty_key.container == player` |
+/// | Exhaustion | `tavern/topics.exhausted` |
+///
+/// A world that needs anything else is refused with the list of what it
+/// uses, read from its `requires` block and its location hooks, rather
+/// than played wrongly. See [`unsupported_features()`].
+///
+/// The page holds the world JSON exactly as emitted, in a
+/// `<script type="application/json">` element, with `<`, `>`, and `&`
+/// written as JSON escapes so no string in the world can close the
+/// element. A second element holds the play index, which the JSON does
+/// not carry: the sections declared under each location, and location
+/// display names. The output depends only on the world, so regenerating
+/// an unchanged world changes no byte. Like `docgen`, this module does no
+/// file I/O.
+
+use serde_json::{json, Map, Value as Json};
+
+use crate::ast::ContentNode;
+use crate::graph::{file_stem, DependencyGraph};
+use crate::symbol_table::SymbolTable;
+use crate::CompilationResult;
+
+const WALKER_JS: &str = include_str!("walker.js");
+const WALKER_CSS: &str = include_str!("walker.css");
+
+/// Effect kinds the walker applies.
+const SUPPORTED_EFFECTS: &[&str] = &["move", "set"];
+
+/// Condition kinds the walker evaluates, as `requires.conditions` names
+/// them.
+const SUPPORTED_CONDITIONS: &[&str] = &["containment", "exhaustion", "property_comparison"];
+
+/// Why a world could not be exported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayError {
+    /// Compilation reported errors, so there is no world to play.
+    CompilationFailed,
+    /// The world uses features the walker cannot run, each described as
+    /// [`unsupported_features()`] lists them.
+    Unsupported(Vec<String>),
+}
+
+impl std::fmt::Display for PlayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlayError::CompilationFailed => write!(f, "Compilation failed; cannot export a playable world."),
+            PlayError::Unsupported(features) => write!(
+                f,
+                "The playable export cannot run this world. It uses {}, which only a full runtime supports.",
+                features.join(", ")
+            ),
+        }
+    }
+}
+
+/// The playable page for a compilation result.
+pub fn from_compilation(result: &CompilationResult) -> Result<String, PlayError> {
+    let (Some(world), Some(graph), Some(symbol_table)) =
+        (result.world.as_ref(), result.graph.as_ref(), result.symbol_table.as_ref())
+    else {
+        return Err(PlayError::CompilationFailed);
+    };
+    if !result.success {
+        return Err(PlayError::CompilationFailed);
+    }
+    let parsed: Json = serde_json::from_str(world).map_err(|_| PlayError::CompilationFailed)?;
+    let unsupported = unsupported_features(&parsed);
+    if !unsupported.is_empty() {
+        return Err(PlayError::Unsupported(unsupported));
+    }
+    Ok(render(world, &parsed, &play_index(graph, symbol_table)))
+}
+
+/// What the world uses that the walker cannot run, in a fixed order:
+/// effects, then conditions, then blocks, then location hooks. Empty when
+/// the world can be played.
+pub fn unsupported_features(world: &Json) -> Vec<String> {
+    let mut found = Vec::new();
+    let requires = &world["requires"];
+    for effect in names(&requires["effects"]) {
+        if !SUPPORTED_EFFECTS.contains(&effect) {
+            found.push(format!("`> {}` effects", effect));
+        }
+    }
+    for condition in names(&requires["conditions"]) {
+        if !SUPPORTED_CONDITIONS.contains(&condition) {
+            found.push(match condition {
+                "any" => "`? any:` blocks".to_string(),
+                "visited" => "`? visited` checks".to_string(),
+                other => format!("{} conditions", other),
+            });
+        }
+    }
+    for block in ["rules", "sequences"] {
+        if requires[block] == true {
+            found.push(block.to_string());
+        }
+    }
+    let hooks = world["locations"]
+        .as_object()
+        .is_some_and(|locations| locations.values().any(|l| l.get("on_enter").is_some() || l.get("on_exit").is_some()));
+    if hooks {
+        found.push("location hooks".to_string());
+    }
+    found
+}
+
+fn names(list: &Json) -> impl Iterator<Item = &str> {
+    list.as_array().into_iter().flatten().filter_map(Json::as_str)
+}
+
+/// The sections declared under each location, in source order, and each
+/// location's display name. Parameterised sections are left out: they are
+/// entered only through a jump that names their argument.
+fn play_index(graph: &DependencyGraph, symbol_table: &SymbolTable) -> Json {
+    let mut sections: Map<String, Json> = Map::new();
+    for path in graph.topological_order() {
+        let Some(node) = graph.nodes.get(path.as_str()) else {
+            continue;
+        };
+        let stem = file_stem(path);
+        let mut location: Option<String> = None;
+        for content in &node.ast.content {
+            match content {
+                ContentNode::LocationHeading(heading) => location = Some(heading.id()),
+                ContentNode::RegionHeading(_) => location = None,
+                ContentNode::SectionLabel(label) => {
+                    let Some(location) = &location else { continue };
+                    let id = format!("{}/{}", stem, label.local_id());
+                    if symbol_table.sections.get(&id).is_some_and(|s| s.param.is_none()) {
+                        let list = sections.entry(location.clone()).or_insert_with(|| json!([]));
+                        list.as_array_mut().unwrap().push(Json::String(id));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    let names: Map<String, Json> = symbol_table
+        .locations
+        .values()
+        .map(|l| (l.id.clone(), Json::String(l.display_name.clone())))
+        .collect();
+    json!({ "sections": sections, "location_names": names })
+}
+
+fn render(world_text: &str, world: &Json, index: &Json) -> String {
+    let name = world["world"]["name"].as_str().unwrap_or("Urd world");
+    let mut out = String::with_capacity(world_text.len() + WALKER_JS.len() + 2048);
+    out.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    out.push_str(&format!("<title>{}</title>\n<style>\n{}</style>\n</head>\n<body>\n", html_escape(name), WALKER_CSS));
+    out.push_str(&format!("<main>\n<h1>{}</h1>\n", html_escape(name)));
+    out.push_str("<div id=\"transcript\" aria-live=\"polite\"></div>\n<div id=\"actions\"></div>\n");
+    out.push_str("<noscript>This page needs JavaScript to play the world.</noscript>\n</main>\n");
+    out.push_str("<script type=\"application/json\" id=\"urd-world\">\n");
+    out.push_str(&script_escape(world_text.trim_end()));
+    out.push_str("\n</script>\n<script type=\"application/json\" id=\"urd-play-index\">\n");
+    out.push_str(&script_escape(&index.to_string()));
+    out.push_str(&format!("\n</script>\n<script>\n{}</script>\n</body>\n</html>\n", WALKER_JS));
+    out
+}
+
+/// JSON text made safe inside a `<script>` element. `<`, `>`, and `&` only
+/// occur inside JSON strings, where their `\u` escapes mean the same.
+pub fn script_escape(json: &str) -> String {
+    json.replace('<', "\\u003c").replace('>', "\\u003e").replace('&', "\\u0026")
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -0,0 +1,322 @@
+// Urd playable walker: clicks through an embedded compiled world.
+//
+// Not a runtime. It applies `set` and `move` effects, follows `goto` and
+// exit jumps, and evaluates property comparisons, containment checks,
+// and exhaustion checks. The compiler refuses worlds that need more.
+(function () {
+  "use strict";
+
+  var world = JSON.parse(document.getElementById("urd-world").textContent);
+  var index = JSON.parse(document.getElementById("urd-play-index").textContent);
+  var transcript = document.getElementById("transcript");
+  var actions = document.getElementById("actions");
+
+  var locations = world.locations || {};
+  var dialogue = world.dialogue || {};
+  var names = world.display_names || {};
+
+  // ── State ──
+
+  var props = {};
+  var containers = {};
+  var consumed = {};
+  var here = world.world.start;
+  var section = null;
+
+  Object.keys(world.entities || {}).forEach(function (id) {
+    var entity = world.entities[id];
+    var type = (world.types || {})[entity.type] || {};
+    props[id] = {};
+    flattenDefaults(type.properties || {}, "", props[id]);
+    flattenValues(entity.properties || {}, "", props[id]);
+  });
+  Object.keys(locations).forEach(function (id) {
+    (locations[id].contains || []).forEach(function (entity) {
+      containers[entity] = id;
+    });
+  });
+
+  function flattenDefaults(properties, prefix, into) {
+    Object.keys(properties).forEach(function (name) {
+      var entry = properties[name];
+      if (typeof entry.type === "string") {
+        if ("default" in entry) into[prefix + name] = entry["default"];
+      } else {
+        flattenDefaults(entry, prefix + name + ".", into);
+      }
+    });
+  }
+
+  function flattenValues(values, prefix, into) {
+    Object.keys(values).forEach(function (name) {
+      var value = values[name];
+      if (value !== null && typeof value === "object" && !Array.isArray(value)) {
+        flattenValues(value, prefix + name + ".", into);
+      } else {
+        into[prefix + name] = value;
+      }
+    });
+  }
+
+  // ── Conditions ──
+
+  function holds(conditions) {
+    if (conditions === undefined) return true;
+    if (typeof conditions === "string") return holdsOne(conditions);
+    if (Array.isArray(conditions)) return conditions.every(holds);
+    return (conditions.all || []).every(holds);
+  }
+
+  function holdsOne(condition) {
+    var exhaustion = /^(\S+)\.exhausted$/.exec(condition);
+    if (exhaustion) return exhausted(exhaustion[1]);
+    var comparison = /^(\S+) (==|!=|<=|>=|<|>) (.+)$/.exec(condition);
+    if (!comparison) return false;
+    var left = read(comparison[1]);
+    var right = literal(comparison[3]);
+    switch (comparison[2]) {
+      case "==": return left === right;
+      case "!=": return left !== right;
+      case "<": return left < right;
+      case ">": return left > right;
+      case "<=": return left <= right;
+      default: return left >= right;
+    }
+  }
+
+  function read(path) {
+    var dot = path.indexOf(".");
+    var entity = path.slice(0, dot);
+    var property = path.slice(dot + 1);
+    if (property === "container") return containerOf(entity);
+    return (props[entity] || {})[property];
+  }
+
+  function containerOf(entity) {
+    return entity === "player" ? here : containers[entity];
+  }
+
+  function literal(text) {
+    if (text === "player.container") return here;
+    if (text === "true") return true;
+    if (text === "false") return false;
+    if (/^-?\d+(\.\d+)?$/.test(text)) return Number(text);
+    if (/^".*"$/.test(text)) return JSON.parse(text);
+    return text;
+  }
+
+  // A section is exhausted when none of its choices is available. A
+  // choice that tests its own section's exhaustion counts as withheld
+  // while the question is being answered.
+  var exhausting = {};
+
+  function exhausted(id) {
+    var target = dialogue[id];
+    if (!target) return true;
+    if (exhausting[id]) return false;
+    exhausting[id] = true;
+    var result = available(target.choices || []).length === 0;
+    delete exhausting[id];
+    return result;
+  }
+
+  function available(choices) {
+    return choices.filter(function (choice) {
+      return !consumed[choice.id] && holds(choice.conditions);
+    });
+  }
+
+  // ── Effects ──
+
+  function apply(effects) {
+    (effects || []).forEach(function (effect) {
+      if ("set" in effect) {
+        var dot = effect.set.indexOf(".");
+        var entity = effect.set.slice(0, dot);
+        props[entity] = props[entity] || {};
+        props[entity][effect.set.slice(dot + 1)] = value(effect.set, effect.to);
+      } else if ("move" in effect) {
+        var to = effect.to === "player.container" ? here : effect.to;
+        if (effect.move === "player") {
+          here = to;
+        } else {
+          containers[effect.move] = to;
+        }
+      }
+    });
+  }
+
+  function value(target, to) {
+    if (typeof to !== "string") return to;
+    var arithmetic = /^(\S+) ([+-]) (\S+)$/.exec(to);
+    if (arithmetic && arithmetic[1] === target) {
+      var step = Number(arithmetic[3]);
+      return read(target) + (arithmetic[2] === "+" ? step : -step);
+    }
+    return literal(to);
+  }
+
+  // ── Rendering ──
+
+  function say(text, kind) {
+    var line = document.createElement("p");
+    line.className = kind || "prose";
+    line.textContent = text;
+    transcript.appendChild(line);
+    line.scrollIntoView({ block: "nearest" });
+  }
+
+  function text(value) {
+    return typeof value === "string" ? value : value.variants[0];
+  }
+
+  function speak(content) {
+    [].concat(content || []).forEach(function (line) {
+      if ("direction" in line) {
+        say(line.direction, "direction");
+      } else if (line.speaker) {
+        say(nameOf(line.speaker) + ": " + text(line.text), "speech");
+      } else {
+        say(text(line.text), "prose");
+      }
+    });
+  }
+
+  function nameOf(id) {
+    return names[id] || id;
+  }
+
+  function offer(buttons) {
+    actions.textContent = "";
+    buttons.forEach(function (button) {
+      var element = document.createElement("button");
+      element.type = "button";
+      element.textContent = button[0];
+      element.addEventListener("click", button[1]);
+      actions.appendChild(element);
+    });
+    if (buttons.length === 0) say("There is nothing more to do.", "end");
+  }
+
+  // ── Locations ──
+
+  function enterLocation(id) {
+    here = id;
+    section = null;
+    var location = locations[id] || {};
+    say(index.location_names[id] || id, "heading");
+    if (location.description) say(text(location.description), "prose");
+    var present = Object.keys(containers).filter(function (entity) {
+      return containers[entity] === id && entity !== "player";
+    });
+    if (present.length > 0) say("Here: " + present.map(nameOf).join(", ") + ".", "contents");
+    showLocation();
+  }
+
+  function showLocation() {
+    var location = locations[here] || {};
+    var buttons = (index.sections[here] || []).filter(function (id) {
+      var target = dialogue[id];
+      return target && holds(target.conditions) && (!exhausted(id) || target.on_exhausted);
+    }).map(function (id) {
+      return [talkLabel(id), function () { enterSection(id); }];
+    });
+    Object.keys(location.exits || {}).forEach(function (direction) {
+      buttons.push(["Go " + direction, function () { traverse(direction); }]);
+    });
+    offer(buttons);
+  }
+
+  function talkLabel(id) {
+    var prompt = [].concat(dialogue[id].prompt || []).filter(function (line) { return line.speaker; });
+    var local = id.slice(id.lastIndexOf("/") + 1);
+    return prompt.length > 0 ? "Talk to " + nameOf(prompt[0].speaker) + " (" + local + ")" : "Enter " + local;
+  }
+
+  function traverse(direction) {
+    var exit = locations[here].exits[direction];
+    say("> Go " + direction, "player");
+    if (!holds(exit.condition)) {
+      say(exit.blocked_message || "The way is blocked.", "blocked");
+      showLocation();
+      return;
+    }
+    apply(exit.on_traverse);
+    enterLocation(exit.to);
+  }
+
+  // ── Dialogue ──
+
+  function enterSection(id) {
+    section = id;
+    var target = dialogue[id];
+    if (target.description) say(text(target.description), "prose");
+    speak(target.prompt);
+    showChoices(target.choices || []);
+  }
+
+  function showChoices(choices) {
+    var open = available(choices);
+    if (open.length === 0) {
+      finishSection();
+      return;
+    }
+    var buttons = open.map(function (choice) {
+      return [choice.label, function () { choose(choice); }];
+    });
+    if (choices === dialogue[section].choices) {
+      buttons.push(["Leave the conversation", function () { say("> Leave", "player"); leave(); }]);
+    }
+    offer(buttons);
+  }
+
+  function choose(choice) {
+    if (!choice.sticky || choice.once) consumed[choice.id] = true;
+    say("> " + choice.label, "player");
+    speak(choice.response);
+    apply(choice.effects);
+    if (choice.choices && available(choice.choices).length > 0) {
+      showChoices(choice.choices);
+    } else {
+      jump(choice.goto, choice.exit);
+    }
+  }
+
+  function finishSection() {
+    var exhaustedContent = dialogue[section].on_exhausted;
+    if (!exhaustedContent) {
+      leave();
+      return;
+    }
+    if (exhaustedContent.speaker) {
+      say(nameOf(exhaustedContent.speaker) + ": " + text(exhaustedContent.text), "speech");
+    } else {
+      say(text(exhaustedContent.text), "prose");
+    }
+    if (exhaustedContent.goto || exhaustedContent.exit) {
+      jump(exhaustedContent.goto, exhaustedContent.exit);
+    } else {
+      leave();
+    }
+  }
+
+  function jump(target, exit) {
+    if (exit) {
+      section = null;
+      traverse(exit);
+    } else if (target === "end") {
+      leave();
+    } else if (target) {
+      enterSection(target);
+    } else {
+      showChoices(dialogue[section].choices || []);
+    }
+  }
+
+  function leave() {
+    section = null;
+    showLocation();
+  }
+
+  enterLocation(here);
+})();
@@ -0,0 +1,582 @@
+/// Project manifests: `urd.toml` at the project root.
+///
+/// A manifest names the entry file and output path and sets default
+/// compile options, so a bare `urd` in any directory of the project
+/// compiles it:
+///
+/// ```toml
+/// entry = "src/main.urd.md"
+/// out = "dist/world.urd.json"
+/// include-docs = true
+///
+/// [profile.release]
+/// include-docs = false
+/// numeric-ids = true
+/// ```
+///
+/// Option keys are the CLI flag names without the dashes. A named profile
+/// (`urd --profile release`) accepts the same keys and overrides the
+/// top-level values; command-line flags override both. Relative paths are
+/// relative to the manifest's directory.
+///
+/// The reader handles the subset of TOML a manifest needs: comments,
+/// `[table]` headers, and `key = value` pairs whose values are strings,
+/// integers, floats, booleans, or arrays of those. Unknown keys and
+/// tables produce warnings rather than errors, so a manifest written for
+/// a newer compiler still works. Malformed input stops at the first error.
+///
+/// This module does no file I/O: the CLI supplies the file system checks
+/// and the manifest text.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use indexmap::IndexMap;
+
+/// The manifest file name.
+pub const MANIFEST_NAME: &str = "urd.toml";
+
+/// A parsed manifest.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Manifest {
+    /// The entry `.urd.md` file.
+    pub entry: Option<String>,
+    /// Where the compiled `.urd.json` is written. `None` prints it.
+    pub out: Option<String>,
+    /// Top-level compile options.
+    pub options: ProjectOptions,
+    /// Named profiles from `[profile.<name>]` tables, in file order.
+    pub profiles: IndexMap<String, ProjectOptions>,
+}
+
+/// Compile options a manifest, a profile, or the command line can set.
+/// `None` leaves the value to the layer below.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProjectOptions {
+    /// `root`: the project root (`--root`).
+    pub root: Option<String>,
+    /// `emit-keys` (`--emit-keys`).
+    pub emit_keys: Option<bool>,
+    /// `include-docs` (`--include-docs`).
+    pub include_docs: Option<bool>,
+    /// `numeric-ids` (`--numeric-ids`).
+    pub numeric_ids: Option<bool>,
+    /// `split-output`: directory for per-block files (`--split-output`).
+    pub split_output: Option<String>,
+    /// `cache-dir` (`--cache-dir`).
+    pub cache_dir: Option<String>,
+    /// `timings` (`--timings`).
+    pub timings: Option<bool>,
+    /// `text-lint` (`--text-lint`).
+    pub text_lint: Option<bool>,
+    /// `generator`: name the compiler in the world block. Defaults to
+    /// `true` (`--no-generator` sets `false`).
+    pub generator: Option<bool>,
+    /// `define`: flags for `?if` guards (`--define`, repeatable). A
+    /// layer that sets it replaces the whole list.
+    pub define: Option<Vec<String>>,
+}
+
+impl ProjectOptions {
+    /// These options with every value set in `over` replacing ours.
+    pub fn overlay(&self, over: &ProjectOptions) -> ProjectOptions {
+        fn pick<T: Clone>(base: &Option<T>, over: &Option<T>) -> Option<T> {
+            over.clone().or_else(|| base.clone())
+        }
+        ProjectOptions {
+            root: pick(&self.root, &over.root),
+            emit_keys: pick(&self.emit_keys, &over.emit_keys),
+            include_docs: pick(&self.include_docs, &over.include_docs),
+            numeric_ids: pick(&self.numeric_ids, &over.numeric_ids),
+            split_output: pick(&self.split_output, &over.split_output),
+            cache_dir: pick(&self.cache_dir, &over.cache_dir),
+            timings: pick(&self.timings, &over.timings),
+            text_lint: pick(&self.text_lint, &over.text_lint),
+            generator: pick(&self.generator, &over.generator),
+            define: pick(&self.define, &over.define),
+        }
+    }
+
+    /// Join every relative path onto `dir`.
+    pub fn rebase(&mut self, dir: &Path) {
+        for path in [&mut self.root, &mut self.split_output, &mut self.cache_dir].into_iter().flatten() {
+            *path = join(dir, path);
+        }
+    }
+}
+
+impl Manifest {
+    /// The top-level options with `profile`'s values laid over them.
+    pub fn options_for(&self, profile: Option<&str>) -> Result<ProjectOptions, String> {
+        let Some(name) = profile else {
+            return Ok(self.options.clone());
+        };
+        match self.profiles.get(name) {
+            Some(profile) => Ok(self.options.overlay(profile)),
+            None if self.profiles.is_empty() => {
+                Err(format!("Profile '{}' is not defined. The manifest has no profiles.", name))
+            }
+            None => Err(format!(
+                "Profile '{}' is not defined. Available profiles: {}.",
+                name,
+                self.profiles.keys().map(String::as_str).collect::<Vec<_>>().join(", "),
+            )),
+        }
+    }
+
+    /// Join the entry, output, and every option path onto `dir`, the
+    /// manifest's directory.
+    pub fn rebase(&mut self, dir: &Path) {
+        for path in [&mut self.entry, &mut self.out].into_iter().flatten() {
+            *path = join(dir, path);
+        }
+        self.options.rebase(dir);
+        for profile in self.profiles.values_mut() {
+            profile.rebase(dir);
+        }
+    }
+}
+
+fn join(dir: &Path, path: &str) -> String {
+    dir.join(path).to_string_lossy().to_string()
+}
+
+/// The nearest `urd.toml`: in `start` or the closest ancestor that has
+/// one. `is_file` reports whether a path is an existing file.
+pub fn find_manifest(start: &Path, is_file: impl Fn(&Path) -> bool) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .map(|dir| dir.join(MANIFEST_NAME))
+        .find(|candidate| is_file(candidate))
+}
+
+/// A manifest that cannot be read. Parsing stops at the first problem.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestError {
+    /// 1-indexed line of the problem.
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ManifestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// A key or table the compiler does not know. Ignored, and reported so a
+/// misspelling does not go unnoticed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestWarning {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ManifestWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Parse manifest text. Returns the manifest and a warning for each
+/// unknown key or table.
+pub fn parse_manifest(source: &str) -> Result<(Manifest, Vec<ManifestWarning>), ManifestError> {
+    let tables = Reader::new(source).document()?;
+    let mut manifest = Manifest::default();
+    let mut warnings = Vec::new();
+
+    for table in tables {
+        match table.path.as_slice() {
+            [] => {
+                for entry in &table.entries {
+                    match entry.key.as_str() {
+                        "entry" => manifest.entry = Some(entry.string()?),
+                        "out" => manifest.out = Some(entry.string()?),
+                        _ => {
+                            if !set_option(&mut manifest.options, entry)? {
+                                warnings.push(entry.unknown("key"));
+                            }
+                        }
+                    }
+                }
+            }
+            [profile, name] if profile == "profile" => {
+                let mut options = ProjectOptions::default();
+                for entry in &table.entries {
+                    if !set_option(&mut options, entry)? {
+                        warnings.push(entry.unknown(&format!("key in profile '{}'", name)));
+                    }
+                }
+                manifest.profiles.insert(name.clone(), options);
+            }
+            path => {
+                // `[profile]` itself only holds subtables.
+                if path != ["profile"] || !table.entries.is_empty() {
+                    warnings.push(ManifestWarning {
+                        line: table.line,
+                        message: format!("Unknown table '[{}]' is ignored.", path.join(".")),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok((manifest, warnings))
+}
+
+/// Apply an option key. Returns `false` for an unknown key.
+fn set_option(options: &mut ProjectOptions, entry: &Entry) -> Result<bool, ManifestError> {
+    match entry.key.as_str() {
+        "root" => options.root = Some(entry.string()?),
+        "emit-keys" => options.emit_keys = Some(entry.boolean()?),
+        "include-docs" => options.include_docs = Some(entry.boolean()?),
+        "numeric-ids" => options.numeric_ids = Some(entry.boolean()?),
+        "split-output" => options.split_output = Some(entry.string()?),
+        "cache-dir" => options.cache_dir = Some(entry.string()?),
+        "timings" => options.timings = Some(entry.boolean()?),
+        "text-lint" => options.text_lint = Some(entry.boolean()?),
+        "generator" => options.generator = Some(entry.boolean()?),
+        "define" => options.define = Some(entry.strings()?),
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+// ── TOML subset ──
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    String(String),
+    Integer(i64),
+    Float(f64),
+    Boolean(bool),
+    Array(Vec<Value>),
+}
+
+impl Value {
+    fn kind(&self) -> &'static str {
+        match self {
+            Value::String(_) => "a string",
+            Value::Integer(_) => "an integer",
+            Value::Float(_) => "a float",
+            Value::Boolean(_) => "a boolean",
+            Value::Array(_) => "an array",
+        }
+    }
+}
+
+struct Entry {
+    key: String,
+    value: Value,
+    line: usize,
+}
+
+impl Entry {
+    fn string(&self) -> Result<String, ManifestError> {
+        match &self.value {
+            Value::String(s) => Ok(s.clone()),
+            other => Err(self.mismatch("a string", other)),
+        }
+    }
+
+    fn boolean(&self) -> Result<bool, ManifestError> {
+        match &self.value {
+            Value::Boolean(b) => Ok(*b),
+            other => Err(self.mismatch("true or false", other)),
+        }
+    }
+
+    fn strings(&self) -> Result<Vec<String>, ManifestError> {
+        match &self.value {
+            Value::Array(items) => items
+                .iter()
+                .map(|item| match item {
+                    Value::String(s) => Ok(s.clone()),
+                    other => Err(self.mismatch("an array of strings", other)),
+                })
+                .collect(),
+            other => Err(self.mismatch("an array of strings", other)),
+        }
+    }
+
+    fn mismatch(&self, expected: &str, found: &Value) -> ManifestError {
+        ManifestError {
+            line: self.line,
+            message: format!("'{}' must be {}, found {}.", self.key, expected, found.kind()),
+        }
+    }
+
+    fn unknown(&self, what: &str) -> ManifestWarning {
+        ManifestWarning {
+            line: self.line,
+            message: format!("Unknown {} '{}' is ignored.", what, self.key),
+        }
+    }
+}
+
+struct Table {
+    path: Vec<String>,
+    entries: Vec<Entry>,
+    line: usize,
+}
+
+struct Reader {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+}
+
+impl Reader {
+    fn new(source: &str) -> Self {
+        Reader { chars: source.chars().collect(), pos: 0, line: 1 }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+        }
+        Some(c)
+    }
+
+    fn error(&self, message: impl Into<String>) -> ManifestError {
+        ManifestError { line: self.line, message: message.into() }
+    }
+
+    fn skip_spaces(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t')) {
+            self.bump();
+        }
+    }
+
+    fn skip_comment(&mut self) {
+        if self.peek() == Some('#') {
+            while !matches!(self.peek(), None | Some('\n')) {
+                self.bump();
+            }
+        }
+    }
+
+    /// Skip spaces, comments, and newlines.
+    fn skip_blank(&mut self) {
+        loop {
+            self.skip_spaces();
+            self.skip_comment();
+            match self.peek() {
+                Some('\n') => {
+                    self.bump();
+                }
+                Some('\r') if self.chars.get(self.pos + 1) == Some(&'\n') => {
+                    self.bump();
+                }
+                _ => return,
+            }
+        }
+    }
+
+    /// After a header or value: spaces, an optional comment, then a line
+    /// break or the end of input.
+    fn end_of_line(&mut self) -> Result<(), ManifestError> {
+        self.skip_spaces();
+        self.skip_comment();
+        match self.peek() {
+            None | Some('\n') => Ok(()),
+            Some('\r') if self.chars.get(self.pos + 1) == Some(&'\n') => Ok(()),
+            Some(c) => Err(self.error(format!("Unexpected '{}' after the value. Each key needs its own line.", c))),
+        }
+    }
+
+    fn document(&mut self) -> Result<Vec<Table>, ManifestError> {
+        let mut tables = vec![Table { path: Vec::new(), entries: Vec::new(), line: 1 }];
+        loop {
+            self.skip_blank();
+            let Some(c) = self.peek() else {
+                return Ok(tables);
+            };
+            if c == '[' {
+                let line = self.line;
+                self.bump();
+                if self.peek() == Some('[') {
+                    return Err(self.error("Arrays of tables ('[[...]]') are not supported in urd.toml."));
+                }
+                self.skip_spaces();
+                let path = self.key()?;
+                self.skip_spaces();
+                if self.peek() != Some(']') {
+                    return Err(self.error("Expected ']' to close the table header."));
+                }
+                self.bump();
+                if tables.iter().any(|t| t.path == path) {
+                    return Err(self.error(format!("Table '[{}]' is defined twice.", path.join("."))));
+                }
+                self.end_of_line()?;
+                tables.push(Table { path, entries: Vec::new(), line });
+            } else {
+                let line = self.line;
+                let key = self.key()?.join(".");
+                self.skip_spaces();
+                if self.bump() != Some('=') {
+                    return Err(ManifestError { line, message: format!("Expected '=' after key '{}'.", key) });
+                }
+                self.skip_spaces();
+                let value = self.value()?;
+                self.end_of_line()?;
+                let table = tables.last_mut().unwrap();
+                if table.entries.iter().any(|e| e.key == key) {
+                    return Err(ManifestError { line, message: format!("Key '{}' is set twice.", key) });
+                }
+                table.entries.push(Entry { key, value, line });
+            }
+        }
+    }
+
+    /// A dotted key: bare or quoted segments joined by `.`.
+    fn key(&mut self) -> Result<Vec<String>, ManifestError> {
+        let mut segments = Vec::new();
+        loop {
+            self.skip_spaces();
+            let segment = match self.peek() {
+                Some('"') => self.basic_string()?,
+                Some('\'') => self.literal_string()?,
+                _ => {
+                    let start = self.pos;
+                    while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+                        self.bump();
+                    }
+                    if self.pos == start {
+                        return Err(match self.peek() {
+                            Some(c) => self.error(format!("Expected a key, found '{}'.", c)),
+                            None => self.error("Expected a key, found the end of the file."),
+                        });
+                    }
+                    self.chars[start..self.pos].iter().collect()
+                }
+            };
+            segments.push(segment);
+            self.skip_spaces();
+            if self.peek() != Some('.') {
+                return Ok(segments);
+            }
+            self.bump();
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, ManifestError> {
+        match self.peek() {
+            Some('"') => self.basic_string().map(Value::String),
+            Some('\'') => self.literal_string().map(Value::String),
+            Some('[') => self.array(),
+            Some('{') => Err(self.error("Inline tables are not supported in urd.toml.")),
+            Some(c) if c.is_ascii_alphanumeric() || matches!(c, '+' | '-') => self.scalar(),
+            Some(c) => Err(self.error(format!("Expected a value, found '{}'.", c))),
+            None => Err(self.error("Expected a value, found the end of the file.")),
+        }
+    }
+
+    fn scalar(&mut self) -> Result<Value, ManifestError> {
+        let start = self.pos;
+        while matches!(self.peek(), Some(c) if c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '_' | '.')) {
+            self.bump();
+        }
+        let word: String = self.chars[start..self.pos].iter().collect();
+        match word.as_str() {
+            "true" => return Ok(Value::Boolean(true)),
+            "false" => return Ok(Value::Boolean(false)),
+            _ => {}
+        }
+        let digits = word.replace('_', "");
+        if let Ok(n) = digits.parse::<i64>() {
+            return Ok(Value::Integer(n));
+        }
+        if digits.chars().any(|c| c.is_ascii_digit()) {
+            if let Ok(n) = digits.parse::<f64>() {
+                return Ok(Value::Float(n));
+            }
+        }
+        Err(self.error(format!("'{}' is not a valid value. Strings need quotes.", word)))
+    }
+
+    fn basic_string(&mut self) -> Result<String, ManifestError> {
+        self.bump();
+        if self.peek() == Some('"') && self.chars.get(self.pos + 1) == Some(&'"') {
+            return Err(self.error("Multi-line strings are not supported in urd.toml."));
+        }
+        let mut out = String::new();
+        loop {
+            let Some(c) = self.peek().filter(|c| *c != '\n') else {
+                return Err(self.error("Unterminated string."));
+            };
+            self.bump();
+            match c {
+                '"' => return Ok(out),
+                '\\' => {
+                    let escaped = match self.bump() {
+                        Some('"') => '"',
+                        Some('\\') => '\\',
+                        Some('n') => '\n',
+                        Some('t') => '\t',
+                        Some('r') => '\r',
+                        Some('u') => self.unicode_escape()?,
+                        Some(c) => return Err(self.error(format!("Unknown escape '\\{}' in string.", c))),
+                        None => return Err(self.error("Unterminated string.")),
+                    };
+                    out.push(escaped);
+                }
+                c => out.push(c),
+            }
+        }
+    }
+
+    fn unicode_escape(&mut self) -> Result<char, ManifestError> {
+        let hex: String = (0..4).filter_map(|_| self.bump()).collect();
+        u32::from_str_radix(&hex, 16)
+            .ok()
+            .and_then(char::from_u32)
+            .ok_or_else(|| self.error(format!("Invalid escape '\\u{}' in string.", hex)))
+    }
+
+    fn literal_string(&mut self) -> Result<String, ManifestError> {
+        self.bump();
+        let mut out = String::new();
+        loop {
+            let Some(c) = self.peek().filter(|c| *c != '\n') else {
+                return Err(self.error("Unterminated string."));
+            };
+            self.bump();
+            match c {
+                '\'' => return Ok(out),
+                c => out.push(c),
+            }
+        }
+    }
+
+    /// An array, possibly over several lines, with an optional trailing
+    /// comma.
+    fn array(&mut self) -> Result<Value, ManifestError> {
+        self.bump();
+        let mut items = Vec::new();
+        loop {
+            self.skip_blank();
+            if self.peek() == Some(']') {
+                self.bump();
+                return Ok(Value::Array(items));
+            }
+            items.push(self.value()?);
+            self.skip_blank();
+            match self.peek() {
+                Some(',') => {}
+                Some(']') => {
+                    self.bump();
+                    return Ok(Value::Array(items));
+                }
+                _ => return Err(self.error("Expected ',' or ']' in array.")),
+            }
+            self.bump();
+        }
+    }
+}
@@ -0,0 +1,105 @@
+/// Property groups: dotted property names emitted as nested objects.
+///
+/// A type may declare `appearance.height: integer`. Inside the compiler,
+/// and in condition and effect strings, the property is identified by its
+/// full dotted name (`guard.appearance.height > 180`). Only the JSON output
+/// nests it: a type's `properties` and an entity's overrides become
+/// `{ "appearance": { "height": ... } }`. VALIDATE rejects a name that is
+/// both a property and a group (URD446), so the nesting is unambiguous.
+///
+/// Tools that read a compiled world and want the compiler's view of it
+/// call [`flatten_world()`], or [`flatten_compiled()`] on the typed value,
+/// to restore the dotted keys.
+
+use serde_json::{Map, Value as Json};
+
+use crate::CompiledWorld;
+
+/// Nest dotted keys into group objects. Groups appear where their first
+/// member was; member order within a group follows `entries`. A key whose
+/// group path is already taken by a plain value stays flat.
+pub fn nest(entries: impl IntoIterator<Item = (String, Json)>) -> Map<String, Json> {
+    let mut root = Map::new();
+    for (key, value) in entries {
+        let segments: Vec<&str> = key.split('.').collect();
+        let (leaf, groups) = segments.split_last().expect("split yields one segment");
+        match group_for(&mut root, groups) {
+            Some(group) => {
+                group.insert(leaf.to_string(), value);
+            }
+            None => {
+                root.insert(key, value);
+            }
+        }
+    }
+    root
+}
+
+/// Walk to (creating as needed) the group object at `path`. `None` when a
+/// segment already holds something other than a group.
+fn group_for<'a>(root: &'a mut Map<String, Json>, path: &[&str]) -> Option<&'a mut Map<String, Json>> {
+    let mut current = root;
+    for segment in path {
+        let entry = current
+            .entry(segment.to_string())
+            .or_insert_with(|| Json::Object(Map::new()));
+        current = match entry {
+            Json::Object(map) if !is_property_schema(map) => map,
+            _ => return None,
+        };
+    }
+    Some(current)
+}
+
+/// Whether an object under a type's `properties` is a property schema
+/// rather than a group: schemas carry a string `type`.
+fn is_property_schema(map: &Map<String, Json>) -> bool {
+    map.get("type").is_some_and(Json::is_string)
+}
+
+/// Restore dotted keys in a compiled world: every type's `properties` and
+/// every entity's `properties`. The inverse of the nesting EMIT applies.
+pub fn flatten_world(world: &mut Json) {
+    for block in ["types", "entities"] {
+        if let Some(defs) = world.get_mut(block).and_then(Json::as_object_mut) {
+            flatten_block(defs, block == "types");
+        }
+    }
+}
+
+/// [`flatten_world()`] for a [`CompiledWorld`].
+pub fn flatten_compiled(world: &mut CompiledWorld) {
+    if let Some(types) = &mut world.types {
+        flatten_block(types, true);
+    }
+    if let Some(entities) = &mut world.entities {
+        flatten_block(entities, false);
+    }
+}
+
+fn flatten_block(defs: &mut Map<String, Json>, schemas: bool) {
+    for def in defs.values_mut() {
+        if let Some(Json::Object(props)) = def.get_mut("properties") {
+            let mut flat = Map::new();
+            flatten_into(&mut flat, "", std::mem::take(props), schemas);
+            *props = flat;
+        }
+    }
+}
+
+/// Move `map`'s entries into `out`, prefixing keys and descending into
+/// groups. In a type, a group is an object that is not a property schema;
+/// in entity overrides, every object is a group.
+fn flatten_into(out: &mut Map<String, Json>, prefix: &str, map: Map<String, Json>, schemas: bool) {
+    for (key, value) in map {
+        let key = if prefix.is_empty() { key } else { format!("{}.{}", prefix, key) };
+        match value {
+            Json::Object(group) if !(schemas && is_property_schema(&group)) => {
+                flatten_into(out, &key, group, schemas);
+            }
+            value => {
+                out.insert(key, value);
+            }
+        }
+    }
+}
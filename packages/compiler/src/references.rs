@@ -0,0 +1,254 @@
+/// Reference index: maps definition keys to the spans that refer to them.
+///
+/// Built on demand from the annotated ASTs LINK leaves in the dependency
+/// graph. Keys use the DefinitionIndex format (`entity:@warden`,
+/// `type:Guard`, `location:village-square`, `section:gatehouse/greet`), so
+/// a consumer can pair each definition with its uses. Only resolved
+/// references are recorded; an unresolved one has already produced a LINK
+/// diagnostic.
+///
+/// What counts as a reference:
+///
+/// - entity: presence lists, speech and stage directions, choice targets,
+///   conditions, effects, jump arguments, and rule `selects` lists
+/// - type: entity declarations and `ref(Type)` property types
+/// - location: exit destinations, containment and move targets, and
+///   `visited` checks
+/// - section: jumps, `exhausted` checks, and phase `section:` lines
+
+use indexmap::IndexMap;
+
+use crate::ast::{
+    Annotation, ConditionExpr, ContainerKind, ContentNode, DestinationKind, FrontmatterValue,
+};
+use crate::graph::DependencyGraph;
+use crate::span::Span;
+use crate::symbol_table::SymbolTable;
+
+/// Index mapping namespace-prefixed keys to the spans that reference them.
+#[derive(Debug, Clone, Default)]
+pub struct ReferenceIndex {
+    refs: IndexMap<String, Vec<Span>>,
+}
+
+impl ReferenceIndex {
+    /// Build the index from the annotated ASTs in `graph`. Files are
+    /// visited in graph order and each file's references in source order.
+    pub fn build(graph: &DependencyGraph, symbol_table: &SymbolTable) -> Self {
+        let mut builder = Builder { symbol_table, param: None, index: ReferenceIndex::default() };
+        for node in graph.nodes.values() {
+            if let Some(frontmatter) = &node.ast.frontmatter {
+                for entry in &frontmatter.entries {
+                    builder.frontmatter_value(&entry.value);
+                }
+            }
+            builder.param = None;
+            for content in &node.ast.content {
+                builder.content_node(content);
+            }
+        }
+        builder.index
+    }
+
+    /// The spans referring to `key`, in index order. Empty when nothing
+    /// refers to it.
+    pub fn get(&self, key: &str) -> &[Span] {
+        self.refs.get(key).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Iterate over all (key, spans) pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Vec<Span>)> {
+        self.refs.iter()
+    }
+
+    fn add(&mut self, key: String, span: &Span) {
+        self.refs.entry(key).or_default().push(span.clone());
+    }
+}
+
+struct Builder<'a> {
+    symbol_table: &'a SymbolTable,
+    /// The parameter of the enclosing parameterised section. References to
+    /// it bind to the argument, not to an entity of the same name.
+    param: Option<String>,
+    index: ReferenceIndex,
+}
+
+impl Builder<'_> {
+    fn frontmatter_value(&mut self, value: &FrontmatterValue) {
+        match value {
+            FrontmatterValue::EntityDecl(decl) => {
+                if let Some(type_name) = decl.annotation.as_ref().and_then(|a| a.resolved_type.as_ref()) {
+                    self.index.add(format!("type:{}", type_name), &decl.span);
+                }
+            }
+            FrontmatterValue::TypeDef(def) => {
+                for prop in &def.properties {
+                    for ref_type in [&prop.ref_type, &prop.element_ref_type].into_iter().flatten() {
+                        if self.symbol_table.types.contains_key(ref_type) {
+                            self.index.add(format!("type:{}", ref_type), &prop.span);
+                        }
+                    }
+                }
+            }
+            FrontmatterValue::Map(entries) | FrontmatterValue::InlineObject(entries) => {
+                for entry in entries {
+                    self.frontmatter_value(&entry.value);
+                }
+            }
+            FrontmatterValue::List(values) => {
+                for value in values {
+                    self.frontmatter_value(value);
+                }
+            }
+            FrontmatterValue::Scalar(_)
+            | FrontmatterValue::ImportDecl(_)
+            | FrontmatterValue::WorldBlock(_)
+            | FrontmatterValue::EndingDecl(_)
+            | FrontmatterValue::Conditional(_) => {}
+        }
+    }
+
+    fn content_node(&mut self, node: &ContentNode) {
+        match node {
+            ContentNode::LocationHeading(_) | ContentNode::RegionHeading(_) => self.param = None,
+            ContentNode::SectionLabel(label) => {
+                self.param = label.param.as_ref().map(|p| p.name.clone());
+            }
+            ContentNode::PhaseHeading(phase) => {
+                let Some(section) = &phase.section else {
+                    return;
+                };
+                let resolved = self
+                    .symbol_table
+                    .sequences
+                    .values()
+                    .flat_map(|seq| &seq.phases)
+                    .find(|p| p.declared_in == phase.span)
+                    .and_then(|p| p.section.as_ref());
+                if let Some(compiled_id) = resolved {
+                    self.index.add(format!("section:{}", compiled_id), &section.span);
+                }
+            }
+            ContentNode::EntityPresence(presence) => {
+                for annotation in presence.annotations.iter().flatten() {
+                    self.entity(annotation, &presence.span);
+                }
+            }
+            ContentNode::EntitySpeech(speech) => self.annotation(&speech.annotation, &speech.span),
+            ContentNode::StageDirection(sd) => self.annotation(&sd.annotation, &sd.span),
+            ContentNode::Choice(choice) => {
+                self.annotation(&choice.annotation, &choice.span);
+                for child in &choice.content {
+                    self.content_node(child);
+                }
+            }
+            ContentNode::Condition(condition) => self.condition(&condition.expr),
+            ContentNode::OrConditionBlock(block) => {
+                for expr in &block.conditions {
+                    self.condition(expr);
+                }
+            }
+            ContentNode::Effect(effect) => self.annotation(&effect.annotation, &effect.span),
+            ContentNode::Jump(jump) => {
+                // An exit jump's location is the one it leaves, not a use.
+                if let Some(annotation) = &jump.annotation {
+                    self.entity(annotation, &jump.span);
+                    if let Some(section) = &annotation.resolved_section {
+                        self.index.add(format!("section:{}", section), &jump.span);
+                    }
+                }
+            }
+            ContentNode::ExitDeclaration(exit) => {
+                if let Some(location) = exit.annotation.as_ref().and_then(|a| a.resolved_location.as_ref()) {
+                    self.index.add(format!("location:{}", location), &exit.span);
+                }
+                for child in &exit.children {
+                    self.content_node(child);
+                }
+            }
+            ContentNode::LocationHook(hook) => {
+                for child in &hook.children {
+                    self.content_node(child);
+                }
+            }
+            ContentNode::RuleBlock(rule) => {
+                if let Some(select) = &rule.select {
+                    for annotation in select.annotations.iter().flatten() {
+                        self.entity(annotation, &rule.span);
+                    }
+                    for expr in &select.where_clauses {
+                        self.condition(expr);
+                    }
+                }
+                for expr in &rule.where_clauses {
+                    self.condition(expr);
+                }
+                for effect in &rule.effects {
+                    self.annotation(&effect.annotation, &effect.span);
+                }
+            }
+            ContentNode::SequenceHeading(_)
+            | ContentNode::Prose(_)
+            | ContentNode::BlockedMessage(_)
+            | ContentNode::Comment(_)
+            | ContentNode::ErrorNode(_)
+            | ContentNode::ConditionalBlock(_) => {}
+        }
+    }
+
+    fn condition(&mut self, expr: &ConditionExpr) {
+        match expr {
+            ConditionExpr::PropertyComparison(pc) => self.annotation(&pc.annotation, &pc.span),
+            ConditionExpr::ContainmentCheck(cc) => self.annotation(&cc.annotation, &cc.span),
+            ConditionExpr::ExhaustionCheck(ec) => {
+                if let Some(section) = ec.annotation.as_ref().and_then(|a| a.resolved_section.as_ref()) {
+                    self.index.add(format!("section:{}", section), &ec.span);
+                }
+            }
+            ConditionExpr::VisitedCheck(vc) => {
+                if let Some(location) = vc.annotation.as_ref().and_then(|a| a.resolved_location.as_ref()) {
+                    self.index.add(format!("location:{}", location), &vc.span);
+                }
+            }
+            ConditionExpr::Once(_) => {}
+        }
+    }
+
+    /// Record the entity and the container or destination an annotation
+    /// resolved to.
+    fn annotation(&mut self, annotation: &Option<Annotation>, span: &Span) {
+        let Some(annotation) = annotation else {
+            return;
+        };
+        self.entity(annotation, span);
+        let target = match (&annotation.container_kind, &annotation.destination_kind) {
+            (Some(ContainerKind::EntityRef(id)), _) | (_, Some(DestinationKind::EntityRef(id))) => {
+                Some(("entity:@", id))
+            }
+            (Some(ContainerKind::LocationRef(id)), _) | (_, Some(DestinationKind::LocationRef(id))) => {
+                Some(("location:", id))
+            }
+            _ => None,
+        };
+        if let Some((prefix, id)) = target {
+            if !(prefix == "entity:@" && self.is_param(id)) {
+                self.index.add(format!("{}{}", prefix, id), span);
+            }
+        }
+    }
+
+    fn entity(&mut self, annotation: &Annotation, span: &Span) {
+        if let Some(id) = &annotation.resolved_entity {
+            if !self.is_param(id) {
+                self.index.add(format!("entity:@{}", id), span);
+            }
+        }
+    }
+
+    /// The section parameter or a rule's select variable (`$door`), which
+    /// stand for entities but are not one.
+    fn is_param(&self, id: &str) -> bool {
+        self.param.as_deref() == Some(id) || id.starts_with('$')
+    }
+}
@@ -0,0 +1,12 @@
+/// The JSON Schema for compiled `.urd.json` worlds.
+///
+/// The canonical schema lives in `packages/schema/urd-world-schema.json`
+/// (draft 2020-12) and is compiled into the crate, so the schema a build
+/// reports is always the one its tests validated EMIT output against.
+/// The schema is structural: cross-reference integrity is checked by the
+/// compiler, not by the schema.
+
+/// The world schema as JSON text.
+pub fn world_schema() -> &'static str {
+    include_str!("../../schema/urd-world-schema.json")
+}
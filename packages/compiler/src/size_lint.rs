@@ -0,0 +1,155 @@
+/// Size heuristics: sections and locations large enough to suggest a
+/// structural slip (ANALYZE phase).
+///
+/// Unlike [`crate::analyze`], these checks read the linked ASTs and the
+/// symbol table, since the FactSet records neither prose nor which choices
+/// sit directly under a section. They run alongside the FactSet checks and
+/// share the URD6xx range. All three are Info:
+///
+/// | Code   | Check |
+/// |--------|-------|
+/// | URD615 | A section with more top-level choices than [`SizeLimits::choices`]. |
+/// | URD616 | A section with more words of prose before its first choice than [`SizeLimits::prose_words`]. |
+/// | URD617 | A location with more exits than [`SizeLimits::exits`]. |
+///
+/// Sections are partitioned as EMIT partitions them (see
+/// [`crate::emit::layout`]), so the prose counted is the prose that ends
+/// up in the section's `description`.
+
+use crate::ast::ContentNode;
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::emit::layout::{self, Regions};
+use crate::graph::{file_stem, DependencyGraph};
+use crate::symbol_table::SymbolTable;
+
+/// The largest sizes that pass without a diagnostic. Each check fires
+/// only when its count exceeds the limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SizeLimits {
+    /// Choices directly under one section. Nested choices do not count.
+    pub choices: usize,
+    /// Words of prose in a section before its first choice.
+    pub prose_words: usize,
+    /// Exits from one location.
+    pub exits: usize,
+}
+
+impl Default for SizeLimits {
+    fn default() -> Self {
+        SizeLimits { choices: 20, prose_words: 500, exits: 10 }
+    }
+}
+
+/// Run the size checks over every file in topological order.
+pub fn lint(graph: &DependencyGraph, symbol_table: &SymbolTable, limits: &SizeLimits) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    for file_path in graph.topological_order() {
+        let node = match graph.nodes.get(file_path.as_str()) {
+            Some(n) => n,
+            None => continue,
+        };
+        let stem = file_stem(file_path);
+        for section in layout::sections(&node.ast.content) {
+            let id = format!("{}/{}", stem, section.label.local_id());
+            let regions = Regions::split(&section.nodes);
+            diagnostics.extend(check_choice_count(&id, &section, &regions, limits));
+            diagnostics.extend(check_prose_length(&id, &section, &regions, limits));
+        }
+    }
+    diagnostics.extend(check_exit_count(symbol_table, limits));
+
+    diagnostics
+}
+
+/// URD615: more top-level choices than `limits.choices`.
+fn check_choice_count(
+    id: &str,
+    section: &layout::SectionNodes,
+    regions: &Regions,
+    limits: &SizeLimits,
+) -> Option<Diagnostic> {
+    let count = regions.choices.iter().filter(|n| matches!(n, ContentNode::Choice(_))).count();
+    if count <= limits.choices {
+        return None;
+    }
+    Some(Diagnostic {
+        severity: Severity::Info,
+        code: "URD615".to_string(),
+        message: format!(
+            "Section '{}' offers {} choices at once (more than {}).",
+            id, count, limits.choices
+        ),
+        span: section.label.span.clone(),
+        suggestion: Some(
+            "Split the section, or group related choices under a few choices that lead to their own."
+                .to_string(),
+        ),
+        fix: None,
+        related: Vec::new(),
+    })
+}
+
+/// URD616: more than `limits.prose_words` words of prose before the first
+/// choice. Reported at the label, with the line the prose reaches.
+fn check_prose_length(
+    id: &str,
+    section: &layout::SectionNodes,
+    regions: &Regions,
+    limits: &SizeLimits,
+) -> Option<Diagnostic> {
+    let prose: Vec<_> = regions
+        .before
+        .iter()
+        .filter_map(|n| match n {
+            ContentNode::Prose(p) => Some(p),
+            _ => None,
+        })
+        .collect();
+    let words: usize = prose.iter().map(|p| p.text.split_whitespace().count()).sum();
+    if words <= limits.prose_words {
+        return None;
+    }
+    let end_line = prose.last().map_or(section.label.span.end_line, |p| p.span.end_line);
+    Some(Diagnostic {
+        severity: Severity::Info,
+        code: "URD616".to_string(),
+        message: format!(
+            "Section '{}' has {} words of prose before its first choice (more than {}), running to line {}.",
+            id, words, limits.prose_words, end_line
+        ),
+        span: section.label.span.clone(),
+        suggestion: Some(
+            "A run this long may be missing a section label. If it is one scene, it reads fine as is."
+                .to_string(),
+        ),
+        fix: None,
+        related: Vec::new(),
+    })
+}
+
+/// URD617: more exits from one location than `limits.exits`.
+fn check_exit_count(symbol_table: &SymbolTable, limits: &SizeLimits) -> Vec<Diagnostic> {
+    symbol_table
+        .locations
+        .values()
+        .filter(|loc| loc.exits.len() > limits.exits)
+        .map(|loc| Diagnostic {
+            severity: Severity::Info,
+            code: "URD617".to_string(),
+            message: format!(
+                "Location '{}' has {} exits (more than {}).",
+                loc.id,
+                loc.exits.len(),
+                limits.exits
+            ),
+            span: loc.declared_in.clone(),
+            suggestion: Some(
+                "Consider splitting the location, or moving some routes onto the locations they lead to."
+                    .to_string(),
+            ),
+            fix: None,
+            related: Vec::new(),
+        })
+        .collect()
+}
@@ -0,0 +1,322 @@
+/// World statistics: a content inventory computed from compilation artifacts.
+///
+/// `compute()` is a pure function over the dependency graph, symbol table,
+/// FactSet, and (optionally) the PropertyDependencyIndex. It never reads
+/// source text or the filesystem, so the CLI, Forge, and the LSP can all
+/// call it on a `CompilationResult` they already hold.
+///
+/// Counts come from three places:
+///
+/// - **Symbol table** — locations, exits, entities, sections, choices, rules.
+/// - **ASTs** — word counts, choice nesting depth, condition and effect lines.
+/// - **FactSet / index** — reachability and orphaned-property counts. These
+///   are reported under `analysis` and are absent when no index is supplied.
+
+use std::collections::{HashSet, VecDeque};
+
+use indexmap::IndexMap;
+use serde_json::{json, Value as Json};
+
+use crate::ast::ContentNode;
+use crate::facts::{FactSet, PropertyDependencyIndex};
+use crate::graph::DependencyGraph;
+use crate::symbol_table::SymbolTable;
+use crate::CompilationResult;
+
+/// Complexity metrics for a compiled world.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorldStats {
+    pub locations: usize,
+    pub exits: usize,
+    pub entities: usize,
+    /// Entity count per type name, sorted by type name.
+    pub entities_by_type: IndexMap<String, usize>,
+    pub sections: usize,
+    pub choices: usize,
+    pub sticky_choices: usize,
+    pub one_shot_choices: usize,
+    /// Words of narrative text: prose lines and blocked messages.
+    pub prose_words: usize,
+    /// Words spoken or performed by entities: speech and stage directions.
+    pub dialogue_words: usize,
+    /// Deepest choice nesting. A top-level choice has depth 1.
+    pub max_choice_depth: usize,
+    /// Condition lines (`?` and `? any:` blocks) plus rule `where` clauses.
+    pub conditions: usize,
+    /// Effect lines (`>`) plus rule effects.
+    pub effects: usize,
+    pub rules: usize,
+    /// Present when a PropertyDependencyIndex was available.
+    pub analysis: Option<AnalysisStats>,
+}
+
+/// Counts derived from the FactSet and PropertyDependencyIndex.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalysisStats {
+    /// Locations with no exit path from `world.start`. `None` when the
+    /// world declares no start location.
+    pub unreachable_locations: Option<usize>,
+    /// Properties read by conditions but never written by effects.
+    pub read_never_written: usize,
+    /// Properties written by effects but never read by conditions.
+    pub written_never_read: usize,
+}
+
+// ── Construction ──
+
+/// Compute statistics from compilation artifacts.
+pub fn compute(
+    graph: &DependencyGraph,
+    symbol_table: &SymbolTable,
+    fact_set: &FactSet,
+    index: Option<&PropertyDependencyIndex>,
+) -> WorldStats {
+    let mut entities_by_type: IndexMap<String, usize> = IndexMap::new();
+    for entity in symbol_table.entities.values() {
+        *entities_by_type.entry(entity.type_name.clone()).or_insert(0) += 1;
+    }
+    entities_by_type.sort_keys();
+
+    let choices: usize = symbol_table.sections.values().map(|s| s.choices.len()).sum();
+    let sticky_choices = symbol_table
+        .sections
+        .values()
+        .flat_map(|s| &s.choices)
+        .filter(|c| c.sticky)
+        .count();
+
+    let mut content = ContentCounts::default();
+    for node in graph.nodes.values() {
+        for child in &node.ast.content {
+            content.visit(child, 0);
+        }
+    }
+
+    let analysis = index.map(|idx| AnalysisStats {
+        unreachable_locations: count_unreachable(symbol_table, fact_set),
+        read_never_written: idx.read_but_never_written().len(),
+        written_never_read: idx.written_but_never_read().len(),
+    });
+
+    WorldStats {
+        locations: symbol_table.locations.len(),
+        exits: symbol_table.locations.values().map(|l| l.exits.len()).sum(),
+        entities: symbol_table.entities.len(),
+        entities_by_type,
+        sections: symbol_table.sections.len(),
+        choices,
+        sticky_choices,
+        one_shot_choices: choices - sticky_choices,
+        prose_words: content.prose_words,
+        dialogue_words: content.dialogue_words,
+        max_choice_depth: content.max_choice_depth,
+        conditions: content.conditions,
+        effects: content.effects,
+        rules: symbol_table.rules.len(),
+        analysis,
+    }
+}
+
+/// Compute statistics from a compilation result.
+///
+/// Returns `None` when LINK did not run (PARSE or IMPORT failure), since
+/// there is no symbol table to count from.
+pub fn from_compilation(result: &CompilationResult) -> Option<WorldStats> {
+    Some(compute(
+        result.graph.as_ref()?,
+        result.symbol_table.as_ref()?,
+        result.fact_set.as_ref()?,
+        result.property_index.as_ref(),
+    ))
+}
+
+/// Running totals gathered while walking content nodes.
+#[derive(Default)]
+struct ContentCounts {
+    prose_words: usize,
+    dialogue_words: usize,
+    max_choice_depth: usize,
+    conditions: usize,
+    effects: usize,
+}
+
+impl ContentCounts {
+    fn visit(&mut self, node: &ContentNode, choice_depth: usize) {
+        match node {
+            ContentNode::Prose(p) => self.prose_words += count_words(&p.text),
+            ContentNode::BlockedMessage(b) => self.prose_words += count_words(&b.text),
+            ContentNode::EntitySpeech(s) => self.dialogue_words += count_words(&s.text),
+            ContentNode::StageDirection(s) => self.dialogue_words += count_words(&s.text),
+            ContentNode::Condition(_) | ContentNode::OrConditionBlock(_) => self.conditions += 1,
+            ContentNode::Effect(_) => self.effects += 1,
+            ContentNode::RuleBlock(rule) => {
+                self.conditions += rule.where_clauses.len();
+                self.effects += rule.effects.len();
+            }
+            ContentNode::Choice(choice) => {
+                let depth = choice_depth + 1;
+                self.max_choice_depth = self.max_choice_depth.max(depth);
+                for child in &choice.content {
+                    self.visit(child, depth);
+                }
+            }
+            ContentNode::ExitDeclaration(exit) => {
+                for child in &exit.children {
+                    self.visit(child, choice_depth);
+                }
+            }
+            ContentNode::LocationHook(hook) => {
+                for child in &hook.children {
+                    self.visit(child, choice_depth);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Count whitespace-separated words. Inline comments are already stripped
+/// from node text by PARSE, so they never contribute.
+fn count_words(text: &str) -> usize {
+    text.split_whitespace().count()
+}
+
+/// Count locations not reachable from `world.start` through exit edges.
+fn count_unreachable(symbol_table: &SymbolTable, fact_set: &FactSet) -> Option<usize> {
+    let start = symbol_table.world_start.as_deref()?;
+    if !symbol_table.locations.contains_key(start) {
+        return None;
+    }
+
+    let mut visited: HashSet<&str> = HashSet::new();
+    let mut queue: VecDeque<&str> = VecDeque::new();
+    visited.insert(start);
+    queue.push_back(start);
+
+    while let Some(current) = queue.pop_front() {
+        for exit in fact_set.exits().iter().filter(|e| e.from_location == current) {
+            if visited.insert(exit.to_location.as_str()) {
+                queue.push_back(exit.to_location.as_str());
+            }
+        }
+    }
+
+    Some(
+        symbol_table
+            .locations
+            .keys()
+            .filter(|id| !visited.contains(id.as_str()))
+            .count(),
+    )
+}
+
+// ── Output ──
+
+impl WorldStats {
+    /// Mean choices per section, rounded to two decimal places.
+    /// Zero when the world has no sections.
+    pub fn avg_choices_per_section(&self) -> f64 {
+        if self.sections == 0 {
+            return 0.0;
+        }
+        let avg = self.choices as f64 / self.sections as f64;
+        (avg * 100.0).round() / 100.0
+    }
+
+    /// Serialise to JSON. Key order is fixed so output is suitable for
+    /// dashboards and diffing across builds.
+    pub fn to_json(&self) -> Json {
+        let by_type: serde_json::Map<String, Json> = self
+            .entities_by_type
+            .iter()
+            .map(|(k, v)| (k.clone(), json!(v)))
+            .collect();
+
+        let analysis = match &self.analysis {
+            Some(a) => json!({
+                "unreachable_locations": a.unreachable_locations,
+                "read_never_written": a.read_never_written,
+                "written_never_read": a.written_never_read,
+            }),
+            None => Json::Null,
+        };
+
+        json!({
+            "locations": self.locations,
+            "exits": self.exits,
+            "entities": {
+                "total": self.entities,
+                "by_type": by_type,
+            },
+            "sections": self.sections,
+            "choices": {
+                "total": self.choices,
+                "sticky": self.sticky_choices,
+                "one_shot": self.one_shot_choices,
+                "avg_per_section": self.avg_choices_per_section(),
+            },
+            "words": {
+                "prose": self.prose_words,
+                "dialogue": self.dialogue_words,
+                "total": self.prose_words + self.dialogue_words,
+            },
+            "max_choice_depth": self.max_choice_depth,
+            "conditions": self.conditions,
+            "effects": self.effects,
+            "rules": self.rules,
+            "analysis": analysis,
+        })
+    }
+
+    /// Render as an aligned, human-readable table.
+    pub fn table(&self) -> String {
+        let mut rows: Vec<(String, String)> = vec![
+            ("Locations".into(), self.locations.to_string()),
+            ("Exits".into(), self.exits.to_string()),
+            ("Entities".into(), self.entities.to_string()),
+        ];
+        for (type_name, count) in &self.entities_by_type {
+            rows.push((format!("  {}", type_name), count.to_string()));
+        }
+        rows.extend([
+            ("Sections".into(), self.sections.to_string()),
+            ("Choices".into(), self.choices.to_string()),
+            ("  Sticky".into(), self.sticky_choices.to_string()),
+            ("  One-shot".into(), self.one_shot_choices.to_string()),
+            ("  Avg per section".into(), format!("{:.2}", self.avg_choices_per_section())),
+            ("Max choice depth".into(), self.max_choice_depth.to_string()),
+            ("Words".into(), (self.prose_words + self.dialogue_words).to_string()),
+            ("  Prose".into(), self.prose_words.to_string()),
+            ("  Dialogue".into(), self.dialogue_words.to_string()),
+            ("Conditions".into(), self.conditions.to_string()),
+            ("Effects".into(), self.effects.to_string()),
+            ("Rules".into(), self.rules.to_string()),
+        ]);
+        if let Some(a) = &self.analysis {
+            let unreachable = a
+                .unreachable_locations
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| "-".to_string());
+            rows.extend([
+                ("Unreachable locations".into(), unreachable),
+                ("Read, never written".into(), a.read_never_written.to_string()),
+                ("Written, never read".into(), a.written_never_read.to_string()),
+            ]);
+        }
+
+        let label_width = rows.iter().map(|(l, _)| l.chars().count()).max().unwrap_or(0);
+        let value_width = rows.iter().map(|(_, v)| v.len()).max().unwrap_or(0);
+
+        let mut out = String::new();
+        for (label, value) in &rows {
+            out.push_str(&format!(
+                "{:<lw$}  {:>vw$}\n",
+                label,
+                value,
+                lw = label_width,
+                vw = value_width,
+            ));
+        }
+        out
+    }
+}
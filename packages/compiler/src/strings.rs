@@ -0,0 +1,242 @@
+/// String table extraction: every player-visible string in a compiled world,
+/// keyed for lookup-based localisation.
+///
+/// `extract_strings()` runs the same location, ending, and dialogue builders
+/// as EMIT, so the table covers exactly the text that lands in `.urd.json`.
+/// Keys are the compiled ID followed by the JSON field path:
+///
+/// - `locations/{location}/description`
+/// - `locations/{location}/exits/{direction}/blocked_message`
+/// - `endings/{ending}/description`
+/// - `dialogue/{section}/prompt/text`
+/// - `dialogue/{section}/description`
+/// - `dialogue/{section}/choices/{choice}/label`
+/// - `dialogue/{section}/choices/{choice}/response/text`
+/// - `dialogue/{section}/on_exhausted/text`
+///
+/// A prompt or response with several segments is an array, so its keys
+/// carry the segment index: `.../response/{i}/text` for speech and
+/// `.../response/{i}/direction` for stage directions.
+///
+/// Text with `|` variants gets one entry per variant: the line as written
+/// keeps the plain key, and variant `i` (counting from 1) adds
+/// `/variants/{i}`, as in `dialogue/{section}/prompt/text/variants/1`.
+///
+/// Nested choices share their section's ID space, so choice keys stay flat.
+/// With `EmitOptions::externalize_strings`, EMIT writes the key in place of
+/// the text and marks the world block with `"strings": "external"`.
+
+use serde_json::{json, Map, Value as Json};
+
+use crate::emit;
+use crate::graph::DependencyGraph;
+use crate::span::Span;
+use crate::symbol_table::SymbolTable;
+use crate::CompilationResult;
+
+/// One player-visible string and where it was written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StringEntry {
+    pub key: String,
+    pub text: String,
+    pub file: String,
+    /// Line of the first source line contributing to the text.
+    pub line: u32,
+}
+
+/// All player-visible strings of a world, in EMIT order.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StringTable {
+    pub entries: Vec<StringEntry>,
+}
+
+// ── Construction ──
+
+/// Extract the string table from LINK output.
+///
+/// Expects a world that passed VALIDATE: with duplicate IDs present, keys
+/// may collide just as emitted IDs would.
+pub fn extract_strings(graph: &DependencyGraph, symbol_table: &SymbolTable) -> StringTable {
+    collect(graph, symbol_table).table
+}
+
+/// Every player-visible string with the full span of the node it came
+/// from, in EMIT order. Used by [`crate::text_lint`].
+pub(crate) fn extract_spanned(graph: &DependencyGraph, symbol_table: &SymbolTable) -> Vec<(StringEntry, Span)> {
+    let texts = collect(graph, symbol_table);
+    texts.table.entries.into_iter().zip(texts.spans).collect()
+}
+
+fn collect(graph: &DependencyGraph, symbol_table: &SymbolTable) -> TextSink {
+    let ordered = graph.topological_order();
+    let ordered_paths: Vec<&str> = ordered.iter().map(|s| s.as_str()).collect();
+    let mut texts = TextSink::new(false, false);
+
+    if !symbol_table.locations.is_empty() {
+        emit::build_locations(graph, &ordered_paths, symbol_table, &mut texts);
+    }
+    if !symbol_table.endings.is_empty() {
+        emit::build_endings(symbol_table, &mut texts);
+    }
+    if !symbol_table.sections.is_empty() {
+        emit::build_dialogue(graph, &ordered_paths, symbol_table, &mut texts);
+    }
+
+    texts
+}
+
+/// Extract the string table from a compilation result.
+///
+/// Returns `None` when compilation reported errors, since EMIT would not
+/// have run and there is no emitted text to key.
+pub fn from_compilation(result: &CompilationResult) -> Option<StringTable> {
+    if !result.success {
+        return None;
+    }
+    Some(extract_strings(
+        result.graph.as_ref()?,
+        result.symbol_table.as_ref()?,
+    ))
+}
+
+/// Collects text as EMIT builds JSON. Returns either the text itself or its
+/// key, depending on whether strings are being externalised.
+pub(crate) struct TextSink {
+    externalize: bool,
+    /// Emit text variants as `{"variants": [...]}` (format 2). Otherwise
+    /// only the first is emitted, though all are recorded.
+    variants: bool,
+    table: StringTable,
+    /// The span of each entry in `table`, by index.
+    spans: Vec<Span>,
+}
+
+impl TextSink {
+    pub(crate) fn new(externalize: bool, variants: bool) -> Self {
+        TextSink {
+            externalize,
+            variants,
+            table: StringTable::default(),
+            spans: Vec::new(),
+        }
+    }
+
+    /// Record a string and return the JSON value to emit in its place.
+    pub(crate) fn text(&mut self, key: String, text: &str, span: &Span) -> Json {
+        let value = if self.externalize { key.clone() } else { text.to_string() };
+        self.table.entries.push(StringEntry {
+            key,
+            text: text.to_string(),
+            file: span.file.clone(),
+            line: span.start_line,
+        });
+        self.spans.push(span.clone());
+        Json::String(value)
+    }
+
+    /// Record a text and its variants, `texts[0]` being the text as
+    /// written. A text without variants is emitted as [`Self::text`] would.
+    pub(crate) fn varied(&mut self, key: String, texts: &[String], span: &Span) -> Json {
+        let mut values = Vec::with_capacity(texts.len());
+        for (i, text) in texts.iter().enumerate() {
+            let entry_key = if i == 0 { key.clone() } else { format!("{}/variants/{}", key, i) };
+            values.push(self.text(entry_key, text, span));
+        }
+        if values.len() > 1 && self.variants {
+            json!({ "variants": values })
+        } else {
+            values.into_iter().next().unwrap_or_else(|| Json::String(String::new()))
+        }
+    }
+}
+
+// ── Output ──
+
+impl StringTable {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Look up an entry by key.
+    pub fn get(&self, key: &str) -> Option<&StringEntry> {
+        self.entries.iter().find(|e| e.key == key)
+    }
+
+    /// Serialise to JSON: a version marker and a key-ordered map of entries.
+    pub fn to_json(&self) -> Json {
+        let mut strings = Map::new();
+        for entry in &self.entries {
+            strings.insert(
+                entry.key.clone(),
+                json!({
+                    "text": entry.text,
+                    "file": entry.file,
+                    "line": entry.line,
+                }),
+            );
+        }
+        json!({
+            "urd_strings": "1",
+            "strings": strings,
+        })
+    }
+
+    /// Render as CSV with a `key,text,file,line` header (RFC 4180 quoting).
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from("key,text,file,line\n");
+        for entry in &self.entries {
+            out.push_str(&format!(
+                "{},{},{},{}\n",
+                csv_field(&entry.key),
+                csv_field(&entry.text),
+                csv_field(&entry.file),
+                entry.line,
+            ));
+        }
+        out
+    }
+
+    /// Render as a gettext template. Keys become `msgctxt`, so identical
+    /// text at different sites stays separately translatable.
+    pub fn to_po(&self) -> String {
+        let mut out = String::from("msgid \"\"\nmsgstr \"\"\n\"Content-Type: text/plain; charset=UTF-8\\n\"\n");
+        for entry in &self.entries {
+            out.push_str(&format!(
+                "\n#: {}:{}\nmsgctxt {}\nmsgid {}\nmsgstr \"\"\n",
+                entry.file,
+                entry.line,
+                po_string(&entry.key),
+                po_string(&entry.text),
+            ));
+        }
+        out
+    }
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn po_string(value: &str) -> String {
+    let mut out = String::from("\"");
+    for c in value.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            _ => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,659 @@
+/// A serialisable projection of the symbol table for external tooling.
+///
+/// [`SymbolTable`] holds compiler-internal types (AST node references,
+/// resolution state) that change between versions. The export copies out
+/// what a tool can rely on: every declared name, its attributes, and its
+/// declaration span. Order matches the symbol table's insertion order, so
+/// two compiles of the same source export identical JSON.
+///
+/// Built by [`SymbolTable::export()`]. Compilations keep one in
+/// `CompilationResult::symbols` when `CompileOptions::keep_symbols` is
+/// set. `urd symbols` prints [`SymbolTableExport::to_json()`].
+///
+/// Every span is serialised as
+/// `{ "file", "start_line", "start_col", "end_line", "end_col" }`.
+
+use indexmap::IndexMap;
+use serde_json::{json, Value as Json};
+
+use super::{SymbolTable, Value, Visibility};
+use crate::span::Span;
+
+/// The exported symbol table.
+///
+/// JSON: `{ "types": [...], "entities": [...], "locations": [...],
+/// "regions": [...], "sections": [...], "actions": [...], "rules": [...],
+/// "sequences": [...], "endings": [...], "duplicates": [...], "world": { "start", "entry",
+/// "start_inventory" } }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolTableExport {
+    pub types: Vec<TypeExport>,
+    pub entities: Vec<EntityExport>,
+    pub locations: Vec<LocationExport>,
+    pub regions: Vec<RegionExport>,
+    pub sections: Vec<SectionExport>,
+    pub actions: Vec<ActionExport>,
+    pub rules: Vec<RuleExport>,
+    pub sequences: Vec<SequenceExport>,
+    pub endings: Vec<EndingExport>,
+    pub duplicates: Vec<DuplicateExport>,
+    /// Resolved `world.start` location ID.
+    pub world_start: Option<String>,
+    /// Resolved `world.entry` sequence ID.
+    pub world_entry: Option<String>,
+    /// Resolved `world.start_inventory` entity IDs.
+    pub start_inventory: Vec<String>,
+}
+
+/// A type definition.
+///
+/// JSON: `{ "name", "traits": [string], "properties": [...], "doc",
+/// "declared_in" }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeExport {
+    pub name: String,
+    pub traits: Vec<String>,
+    pub properties: Vec<PropertyExport>,
+    pub doc: Option<String>,
+    pub declared_in: Span,
+}
+
+/// A property of a type.
+///
+/// JSON: `{ "name", "type", "raw_type", "default", "visibility", "values",
+/// "min", "max", "ref_type", "element_type", "element_values",
+/// "element_ref_type", "description", "declared_in" }`. `type` and
+/// `element_type` are `"boolean"`, `"integer"`, `"number"`, `"string"`,
+/// `"enum"`, `"ref"`, or `"list"`; `visibility` is `"visible"` or
+/// `"hidden"`. Absent attributes are `null`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PropertyExport {
+    pub name: String,
+    pub property_type: &'static str,
+    /// The type as written, such as `int(0, 100)`.
+    pub raw_type: String,
+    pub default: Option<Value>,
+    pub hidden: bool,
+    pub values: Option<Vec<String>>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub ref_type: Option<String>,
+    pub element_type: Option<&'static str>,
+    pub element_values: Option<Vec<String>>,
+    pub element_ref_type: Option<String>,
+    pub description: Option<String>,
+    pub declared_in: Span,
+}
+
+/// An entity declaration.
+///
+/// JSON: `{ "id", "type", "type_resolved", "overrides": { name: value },
+/// "doc", "declared_in" }`. `type_resolved` is `false` when the type name
+/// did not resolve (URD307).
+#[derive(Debug, Clone, PartialEq)]
+pub struct EntityExport {
+    pub id: String,
+    pub type_name: String,
+    pub type_resolved: bool,
+    pub overrides: IndexMap<String, Value>,
+    pub doc: Option<String>,
+    pub declared_in: Span,
+}
+
+/// A location.
+///
+/// JSON: `{ "id", "display_name", "region", "exits": [...], "contains":
+/// [entity ID], "on_enter", "on_exit", "doc", "declared_in" }`. `on_enter`
+/// and `on_exit` are the hook's span, or `null` when there is none.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocationExport {
+    pub id: String,
+    pub display_name: String,
+    pub region: Option<String>,
+    pub exits: Vec<ExitExport>,
+    pub contains: Vec<String>,
+    pub on_enter: Option<Span>,
+    pub on_exit: Option<Span>,
+    pub doc: Option<String>,
+    pub declared_in: Span,
+}
+
+/// An exit of a location.
+///
+/// JSON: `{ "direction", "destination", "resolved_destination",
+/// "conditional", "blocked_message", "declared_in" }`.
+/// `resolved_destination` is `null` when the destination did not resolve.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExitExport {
+    pub direction: String,
+    /// The destination as written.
+    pub destination: String,
+    pub resolved_destination: Option<String>,
+    /// Whether the exit has a condition.
+    pub conditional: bool,
+    /// Whether the exit has a blocked message.
+    pub blocked_message: bool,
+    pub declared_in: Span,
+}
+
+/// A region.
+///
+/// JSON: `{ "id", "display_name", "locations": [location ID],
+/// "declared_in" }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionExport {
+    pub id: String,
+    pub display_name: String,
+    pub locations: Vec<String>,
+    pub declared_in: Span,
+}
+
+/// A dialogue section.
+///
+/// JSON: `{ "id", "local_name", "file_stem", "param", "instances":
+/// [entity ID], "choices": [...], "doc", "declared_in" }`. `param` is
+/// `{ "name", "type" }` for a parameterised section, else `null`.
+/// `instances` lists the distinct invocation arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SectionExport {
+    pub compiled_id: String,
+    pub local_name: String,
+    pub file_stem: String,
+    /// `(name, type)` of the entity parameter.
+    pub param: Option<(String, String)>,
+    pub instances: Vec<String>,
+    pub choices: Vec<ChoiceExport>,
+    pub doc: Option<String>,
+    pub declared_in: Span,
+}
+
+/// A choice within a section.
+///
+/// JSON: `{ "id", "label", "sticky", "once", "declared_in" }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChoiceExport {
+    pub compiled_id: String,
+    pub label: String,
+    pub sticky: bool,
+    pub once: bool,
+    pub declared_in: Span,
+}
+
+/// An action, declared in frontmatter or derived from a choice.
+///
+/// JSON: `{ "id", "target", "target_type", "declared_in" }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionExport {
+    pub id: String,
+    pub target: Option<String>,
+    pub target_type: Option<String>,
+    pub declared_in: Span,
+}
+
+/// A rule.
+///
+/// JSON: `{ "id", "actor", "trigger", "select", "location",
+/// "declared_in" }`. `select` is `{ "variable", "from": [entity ID],
+/// "where_count", "span" }` or `null`. The where clauses themselves are
+/// in the FactSet. `location` is the scope of a location rule, or `null`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleExport {
+    pub id: String,
+    pub actor: String,
+    pub trigger: String,
+    pub select: Option<SelectExport>,
+    pub location: Option<String>,
+    pub declared_in: Span,
+}
+
+/// The `selects ... from ... where` clause of a rule.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectExport {
+    pub variable: String,
+    pub from: Vec<String>,
+    pub where_count: usize,
+    pub span: Span,
+}
+
+/// A sequence and its phases.
+///
+/// JSON: `{ "id", "phases": [...], "declared_in" }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SequenceExport {
+    pub id: String,
+    pub phases: Vec<PhaseExport>,
+    pub declared_in: Span,
+}
+
+/// A phase of a sequence.
+///
+/// JSON: `{ "id", "advance", "action", "actions", "rule", "section",
+/// "declared_in" }`. `section` is the compiled ID of the section the phase
+/// opens, or `null`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PhaseExport {
+    pub id: String,
+    pub advance: String,
+    pub action: Option<String>,
+    pub actions: Option<Vec<String>>,
+    pub rule: Option<String>,
+    pub section: Option<String>,
+    pub declared_in: Span,
+}
+
+/// An ending.
+///
+/// JSON: `{ "id", "description", "declared_in" }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EndingExport {
+    pub id: String,
+    pub description: Option<String>,
+    pub declared_in: Span,
+}
+
+/// A second declaration of a name. The first stays in its namespace.
+///
+/// JSON: `{ "namespace", "name", "declared_in" }`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DuplicateExport {
+    pub namespace: &'static str,
+    pub name: String,
+    pub declared_in: Span,
+}
+
+impl SymbolTable {
+    /// Copy the table into its serialisable form.
+    pub fn export(&self) -> SymbolTableExport {
+        SymbolTableExport {
+            types: self
+                .types
+                .values()
+                .map(|t| TypeExport {
+                    name: t.name.clone(),
+                    traits: t.traits.clone(),
+                    properties: t
+                        .properties
+                        .values()
+                        .map(|p| PropertyExport {
+                            name: p.name.clone(),
+                            property_type: p.property_type.name(),
+                            raw_type: p.raw_type_string.clone(),
+                            default: p.default.clone(),
+                            hidden: p.visibility == Visibility::Hidden,
+                            values: p.values.clone(),
+                            min: p.min,
+                            max: p.max,
+                            ref_type: p.ref_type.clone(),
+                            element_type: p.element_type.as_ref().map(|t| t.name()),
+                            element_values: p.element_values.clone(),
+                            element_ref_type: p.element_ref_type.clone(),
+                            description: p.description.clone(),
+                            declared_in: p.declared_in.clone(),
+                        })
+                        .collect(),
+                    doc: t.doc.clone(),
+                    declared_in: t.declared_in.clone(),
+                })
+                .collect(),
+            entities: self
+                .entities
+                .values()
+                .map(|e| EntityExport {
+                    id: e.id.clone(),
+                    type_name: e.type_name.clone(),
+                    type_resolved: e.type_symbol.is_some(),
+                    overrides: e.property_overrides.clone(),
+                    doc: e.doc.clone(),
+                    declared_in: e.declared_in.clone(),
+                })
+                .collect(),
+            locations: self
+                .locations
+                .values()
+                .map(|l| LocationExport {
+                    id: l.id.clone(),
+                    display_name: l.display_name.clone(),
+                    region: l.region.clone(),
+                    exits: l
+                        .exits
+                        .values()
+                        .map(|x| ExitExport {
+                            direction: x.direction.clone(),
+                            destination: x.destination.clone(),
+                            resolved_destination: x.resolved_destination.clone(),
+                            conditional: x.condition_node.is_some(),
+                            blocked_message: x.blocked_message_node.is_some(),
+                            declared_in: x.declared_in.clone(),
+                        })
+                        .collect(),
+                    contains: l.contains.clone(),
+                    on_enter: l.on_enter.as_ref().map(|h| h.declared_in.clone()),
+                    on_exit: l.on_exit.as_ref().map(|h| h.declared_in.clone()),
+                    doc: l.doc.clone(),
+                    declared_in: l.declared_in.clone(),
+                })
+                .collect(),
+            regions: self
+                .regions
+                .values()
+                .map(|r| RegionExport {
+                    id: r.id.clone(),
+                    display_name: r.display_name.clone(),
+                    locations: r.locations.clone(),
+                    declared_in: r.declared_in.clone(),
+                })
+                .collect(),
+            sections: self
+                .sections
+                .values()
+                .map(|s| SectionExport {
+                    compiled_id: s.compiled_id.clone(),
+                    local_name: s.local_name.clone(),
+                    file_stem: s.file_stem.clone(),
+                    param: s.param.as_ref().map(|p| (p.name.clone(), p.type_name.clone())),
+                    instances: s.instance_arguments().into_iter().map(String::from).collect(),
+                    choices: s
+                        .choices
+                        .iter()
+                        .map(|c| ChoiceExport {
+                            compiled_id: c.compiled_id.clone(),
+                            label: c.label.clone(),
+                            sticky: c.sticky,
+                            once: c.once,
+                            declared_in: c.declared_in.clone(),
+                        })
+                        .collect(),
+                    doc: s.doc.clone(),
+                    declared_in: s.declared_in.clone(),
+                })
+                .collect(),
+            actions: self
+                .actions
+                .values()
+                .map(|a| ActionExport {
+                    id: a.id.clone(),
+                    target: a.target.clone(),
+                    target_type: a.target_type.clone(),
+                    declared_in: a.declared_in.clone(),
+                })
+                .collect(),
+            rules: self
+                .rules
+                .values()
+                .map(|r| RuleExport {
+                    id: r.id.clone(),
+                    actor: r.actor.clone(),
+                    trigger: r.trigger.clone(),
+                    select: r.select.as_ref().map(|s| SelectExport {
+                        variable: s.variable.clone(),
+                        from: s.from.clone(),
+                        where_count: s.where_clauses.len(),
+                        span: s.span.clone(),
+                    }),
+                    location: r.scope_location.clone(),
+                    declared_in: r.declared_in.clone(),
+                })
+                .collect(),
+            sequences: self
+                .sequences
+                .values()
+                .map(|s| SequenceExport {
+                    id: s.id.clone(),
+                    phases: s
+                        .phases
+                        .iter()
+                        .map(|p| PhaseExport {
+                            id: p.id.clone(),
+                            advance: p.advance.clone(),
+                            action: p.action.clone(),
+                            actions: p.actions.clone(),
+                            rule: p.rule.clone(),
+                            section: p.section.clone(),
+                            declared_in: p.declared_in.clone(),
+                        })
+                        .collect(),
+                    declared_in: s.declared_in.clone(),
+                })
+                .collect(),
+            endings: self
+                .endings
+                .values()
+                .map(|e| EndingExport {
+                    id: e.id.clone(),
+                    description: e.description.clone(),
+                    declared_in: e.declared_in.clone(),
+                })
+                .collect(),
+            duplicates: self
+                .duplicates
+                .iter()
+                .map(|d| DuplicateExport {
+                    namespace: d.namespace,
+                    name: d.name.clone(),
+                    declared_in: d.declared_in.clone(),
+                })
+                .collect(),
+            world_start: self.world_start.clone(),
+            world_entry: self.world_entry.clone(),
+            start_inventory: self.world_start_inventory.clone(),
+        }
+    }
+}
+
+impl SymbolTableExport {
+    /// Serialise the export to JSON, in the shape documented on each struct.
+    pub fn to_json(&self) -> Json {
+        json!({
+            "types": self.types.iter().map(TypeExport::to_json).collect::<Vec<_>>(),
+            "entities": self.entities.iter().map(EntityExport::to_json).collect::<Vec<_>>(),
+            "locations": self.locations.iter().map(LocationExport::to_json).collect::<Vec<_>>(),
+            "regions": self.regions.iter().map(|r| json!({
+                "id": r.id,
+                "display_name": r.display_name,
+                "locations": r.locations,
+                "declared_in": span_to_json(&r.declared_in),
+            })).collect::<Vec<_>>(),
+            "sections": self.sections.iter().map(SectionExport::to_json).collect::<Vec<_>>(),
+            "actions": self.actions.iter().map(|a| json!({
+                "id": a.id,
+                "target": a.target,
+                "target_type": a.target_type,
+                "declared_in": span_to_json(&a.declared_in),
+            })).collect::<Vec<_>>(),
+            "rules": self.rules.iter().map(RuleExport::to_json).collect::<Vec<_>>(),
+            "sequences": self.sequences.iter().map(SequenceExport::to_json).collect::<Vec<_>>(),
+            "endings": self.endings.iter().map(|e| json!({
+                "id": e.id,
+                "description": e.description,
+                "declared_in": span_to_json(&e.declared_in),
+            })).collect::<Vec<_>>(),
+            "duplicates": self.duplicates.iter().map(|d| json!({
+                "namespace": d.namespace,
+                "name": d.name,
+                "declared_in": span_to_json(&d.declared_in),
+            })).collect::<Vec<_>>(),
+            "world": {
+                "start": self.world_start,
+                "entry": self.world_entry,
+                "start_inventory": self.start_inventory,
+            },
+        })
+    }
+}
+
+impl TypeExport {
+    fn to_json(&self) -> Json {
+        json!({
+            "name": self.name,
+            "traits": self.traits,
+            "properties": self.properties.iter().map(PropertyExport::to_json).collect::<Vec<_>>(),
+            "doc": self.doc,
+            "declared_in": span_to_json(&self.declared_in),
+        })
+    }
+}
+
+impl PropertyExport {
+    fn to_json(&self) -> Json {
+        json!({
+            "name": self.name,
+            "type": self.property_type,
+            "raw_type": self.raw_type,
+            "default": self.default.as_ref().map(value_to_json),
+            "visibility": if self.hidden { "hidden" } else { "visible" },
+            "values": self.values,
+            "min": self.min,
+            "max": self.max,
+            "ref_type": self.ref_type,
+            "element_type": self.element_type,
+            "element_values": self.element_values,
+            "element_ref_type": self.element_ref_type,
+            "description": self.description,
+            "declared_in": span_to_json(&self.declared_in),
+        })
+    }
+}
+
+impl EntityExport {
+    fn to_json(&self) -> Json {
+        let overrides: serde_json::Map<String, Json> = self
+            .overrides
+            .iter()
+            .map(|(name, value)| (name.clone(), value_to_json(value)))
+            .collect();
+        json!({
+            "id": self.id,
+            "type": self.type_name,
+            "type_resolved": self.type_resolved,
+            "overrides": overrides,
+            "doc": self.doc,
+            "declared_in": span_to_json(&self.declared_in),
+        })
+    }
+}
+
+impl LocationExport {
+    fn to_json(&self) -> Json {
+        let exits: Vec<Json> = self
+            .exits
+            .iter()
+            .map(|x| {
+                json!({
+                    "direction": x.direction,
+                    "destination": x.destination,
+                    "resolved_destination": x.resolved_destination,
+                    "conditional": x.conditional,
+                    "blocked_message": x.blocked_message,
+                    "declared_in": span_to_json(&x.declared_in),
+                })
+            })
+            .collect();
+        json!({
+            "id": self.id,
+            "display_name": self.display_name,
+            "region": self.region,
+            "exits": exits,
+            "contains": self.contains,
+            "on_enter": self.on_enter.as_ref().map(span_to_json),
+            "on_exit": self.on_exit.as_ref().map(span_to_json),
+            "doc": self.doc,
+            "declared_in": span_to_json(&self.declared_in),
+        })
+    }
+}
+
+impl SectionExport {
+    fn to_json(&self) -> Json {
+        let choices: Vec<Json> = self
+            .choices
+            .iter()
+            .map(|c| {
+                json!({
+                    "id": c.compiled_id,
+                    "label": c.label,
+                    "sticky": c.sticky,
+                    "once": c.once,
+                    "declared_in": span_to_json(&c.declared_in),
+                })
+            })
+            .collect();
+        json!({
+            "id": self.compiled_id,
+            "local_name": self.local_name,
+            "file_stem": self.file_stem,
+            "param": self.param.as_ref().map(|(name, type_name)| json!({ "name": name, "type": type_name })),
+            "instances": self.instances,
+            "choices": choices,
+            "doc": self.doc,
+            "declared_in": span_to_json(&self.declared_in),
+        })
+    }
+}
+
+impl RuleExport {
+    fn to_json(&self) -> Json {
+        json!({
+            "id": self.id,
+            "actor": self.actor,
+            "trigger": self.trigger,
+            "select": self.select.as_ref().map(|s| json!({
+                "variable": s.variable,
+                "from": s.from,
+                "where_count": s.where_count,
+                "span": span_to_json(&s.span),
+            })),
+            "location": self.location,
+            "declared_in": span_to_json(&self.declared_in),
+        })
+    }
+}
+
+impl SequenceExport {
+    fn to_json(&self) -> Json {
+        let phases: Vec<Json> = self
+            .phases
+            .iter()
+            .map(|p| {
+                json!({
+                    "id": p.id,
+                    "advance": p.advance,
+                    "action": p.action,
+                    "actions": p.actions,
+                    "rule": p.rule,
+                    "section": p.section,
+                    "declared_in": span_to_json(&p.declared_in),
+                })
+            })
+            .collect();
+        json!({
+            "id": self.id,
+            "phases": phases,
+            "declared_in": span_to_json(&self.declared_in),
+        })
+    }
+}
+
+// ── JSON serialisation ──
+
+fn span_to_json(span: &Span) -> Json {
+    json!({
+        "file": span.file,
+        "start_line": span.start_line,
+        "start_col": span.start_col,
+        "end_line": span.end_line,
+        "end_col": span.end_col,
+    })
+}
+
+/// Values as authored. An entity reference is its ID without `@`, as in
+/// the compiled world.
+fn value_to_json(value: &Value) -> Json {
+    match value {
+        Value::String(s) => json!(s),
+        Value::Integer(i) => json!(i),
+        Value::Number(n) => json!(n),
+        Value::Boolean(b) => json!(b),
+        Value::EntityRef(id) => json!(id.strip_prefix('@').unwrap_or(id)),
+        Value::List(items) => Json::Array(items.iter().map(value_to_json).collect()),
+    }
+}
@@ -0,0 +1,440 @@
+/// The symbol table: global registry of declared names.
+///
+/// LINK populates it during the collection sub-pass and queries it during
+/// the resolution sub-pass. VALIDATE reads it for type information. EMIT
+/// reads it for ID generation.
+///
+/// All maps are `IndexMap` to preserve insertion order for deterministic output.
+/// [`export`] holds the serialisable copy handed to external tools.
+
+pub mod export;
+
+use indexmap::IndexMap;
+
+use crate::slugify::slugify;
+use crate::span::Span;
+
+/// A duplicate declaration recorded for diagnostic purposes.
+/// The canonical (first) declaration remains in the namespace map.
+#[derive(Debug, Clone)]
+pub struct Duplicate {
+    pub namespace: &'static str,
+    pub name: String,
+    pub declared_in: Span,
+}
+
+/// The compiler's global symbol table.
+///
+/// Nine ordered maps — types, entities, sections, locations, regions,
+/// actions, rules, sequences, endings — each preserving insertion order
+/// for deterministic output.
+/// Duplicates are tracked in a flat list for diagnostics only.
+#[derive(Debug, Default)]
+pub struct SymbolTable {
+    pub types: IndexMap<String, TypeSymbol>,
+    pub entities: IndexMap<String, EntitySymbol>,
+    pub sections: IndexMap<String, SectionSymbol>,
+    pub locations: IndexMap<String, LocationSymbol>,
+    /// Regions keyed by ID, in the order first declared.
+    pub regions: IndexMap<String, RegionSymbol>,
+    pub actions: IndexMap<String, ActionSymbol>,
+    pub rules: IndexMap<String, RuleSymbol>,
+    pub sequences: IndexMap<String, SequenceSymbol>,
+    pub endings: IndexMap<String, EndingSymbol>,
+    pub duplicates: Vec<Duplicate>,
+    /// Resolved `world.start` → location ID (set by LINK, consumed by VALIDATE).
+    pub world_start: Option<String>,
+    /// Resolved `world.entry` → sequence ID (set by LINK, consumed by VALIDATE).
+    pub world_entry: Option<String>,
+    /// Resolved `world.start_inventory` → entity IDs, in declared order
+    /// (set by LINK, consumed by VALIDATE and EMIT).
+    pub world_start_inventory: Vec<String>,
+    /// `world.mode` (set by LINK). An unknown or non-string value leaves
+    /// the default, and VALIDATE reports it (URD437).
+    pub world_mode: WorldMode,
+}
+
+impl SymbolTable {
+    /// The explicit ID of a location whose display name slugifies to
+    /// `derived_id`. References written against a heading's derived form
+    /// are pointed at the explicit ID that replaced it.
+    pub fn location_with_derived_id(&self, derived_id: &str) -> Option<&str> {
+        self.locations
+            .values()
+            .find(|loc| loc.id != derived_id && slugify(&loc.display_name) == derived_id)
+            .map(|loc| loc.id.as_str())
+    }
+
+    /// The name an entity is shown under: its `name` override, else the
+    /// default of its type's `name` property. Only a string-typed `name`
+    /// property counts, and an empty string is no name.
+    pub fn display_name(&self, entity_id: &str) -> Option<&str> {
+        let entity = self.entities.get(entity_id)?;
+        let prop = self
+            .types
+            .get(entity.type_symbol.as_ref()?)?
+            .properties
+            .get("name")
+            .filter(|p| p.property_type == PropertyType::String)?;
+        match entity.property_overrides.get("name").or(prop.default.as_ref()) {
+            Some(Value::String(s)) if !s.is_empty() => Some(s),
+            _ => None,
+        }
+    }
+}
+
+// ── Symbol types ──
+
+/// Property type discriminator (7 types per the spec).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PropertyType {
+    Boolean,
+    Integer,
+    Number,
+    String,
+    Enum,
+    Ref,
+    List,
+}
+
+impl PropertyType {
+    /// The spec name of the type: `"boolean"`, `"integer"`, and so on.
+    pub fn name(&self) -> &'static str {
+        match self {
+            PropertyType::Boolean => "boolean",
+            PropertyType::Integer => "integer",
+            PropertyType::Number => "number",
+            PropertyType::String => "string",
+            PropertyType::Enum => "enum",
+            PropertyType::Ref => "ref",
+            PropertyType::List => "list",
+        }
+    }
+}
+
+/// What a world is for, from `world.mode`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WorldMode {
+    /// A world the player moves through: locations, exits, `world.start`.
+    #[default]
+    World,
+    /// `mode: dialogue`: dialogue trees and systems for a host game, with
+    /// no locations. VALIDATE warns about any location heading (URD461),
+    /// and the location checks that assume a map stay silent.
+    Dialogue,
+}
+
+impl WorldMode {
+    /// The mode named by a `world.mode` value, if it is one.
+    pub fn from_name(name: &str) -> Option<WorldMode> {
+        match name {
+            "world" => Some(WorldMode::World),
+            "dialogue" => Some(WorldMode::Dialogue),
+            _ => None,
+        }
+    }
+
+    /// The `world.mode` value: `"world"` or `"dialogue"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            WorldMode::World => "world",
+            WorldMode::Dialogue => "dialogue",
+        }
+    }
+}
+
+/// Visibility discriminator for properties.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Visibility {
+    Visible,
+    Hidden,
+}
+
+impl Default for Visibility {
+    fn default() -> Self {
+        Self::Visible
+    }
+}
+
+/// A scalar value in the symbol table (property defaults, overrides).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    String(String),
+    Integer(i64),
+    Number(f64),
+    Boolean(bool),
+    List(Vec<Value>),
+    EntityRef(String),
+}
+
+impl Value {
+    /// Typed equality for comparing authored values. Like `==`, but an
+    /// integer and a number compare numerically, so `1` equals `1.0`.
+    /// Lists compare element-wise under the same rule.
+    pub fn same_value(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Integer(a), Value::Number(b)) | (Value::Number(b), Value::Integer(a)) => *a as f64 == *b,
+            (Value::List(a), Value::List(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.same_value(y))
+            }
+            _ => self == other,
+        }
+    }
+}
+
+/// A type definition symbol.
+#[derive(Debug, Clone)]
+pub struct TypeSymbol {
+    pub name: String,
+    pub traits: Vec<String>,
+    pub properties: IndexMap<String, PropertySymbol>,
+    /// Author documentation from `///` lines. Never shown to players.
+    pub doc: Option<String>,
+    pub declared_in: Span,
+}
+
+/// A property within a type.
+#[derive(Debug, Clone)]
+pub struct PropertySymbol {
+    pub name: String,
+    pub property_type: PropertyType,
+    pub raw_type_string: String,
+    pub default: Option<Value>,
+    pub visibility: Visibility,
+    pub values: Option<Vec<String>>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub ref_type: Option<String>,
+    pub element_type: Option<PropertyType>,
+    pub element_values: Option<Vec<String>>,
+    pub element_ref_type: Option<String>,
+    pub description: Option<String>,
+    pub declared_in: Span,
+}
+
+/// An entity declaration symbol.
+#[derive(Debug, Clone)]
+pub struct EntitySymbol {
+    pub id: String,
+    pub type_name: String,
+    pub type_symbol: Option<String>,
+    /// Declaration overrides, then presence line overrides merged in by
+    /// LINK. A presence line's value replaces the declaration's.
+    pub property_overrides: IndexMap<String, Value>,
+    /// The overrides set on presence lines, by property: the location
+    /// and the presence line. Diagnostics about them point there.
+    pub presence_overrides: IndexMap<String, (String, Span)>,
+    /// Author documentation from `///` lines. Never shown to players.
+    pub doc: Option<String>,
+    pub declared_in: Span,
+}
+
+impl EntitySymbol {
+    /// Where the override of `property` was written: its presence line,
+    /// or else the declaration.
+    pub fn override_span(&self, property: &str) -> &Span {
+        self.presence_overrides.get(property).map_or(&self.declared_in, |(_, span)| span)
+    }
+}
+
+/// A section symbol (`== name`).
+#[derive(Debug, Clone)]
+pub struct SectionSymbol {
+    pub local_name: String,
+    pub compiled_id: String,
+    pub file_stem: String,
+    pub choices: Vec<ChoiceSymbol>,
+    /// The entity parameter of a parameterised section
+    /// (`== haggle(@merchant: Merchant)`).
+    pub param: Option<crate::ast::SectionParam>,
+    /// Every `-> name(@entity)` jump that invokes this section, in source
+    /// order (set by LINK, consumed by VALIDATE and EMIT).
+    pub invocations: Vec<SectionInvocation>,
+    /// Author documentation from `///` lines. Never shown to players.
+    pub doc: Option<String>,
+    pub declared_in: Span,
+}
+
+impl SectionSymbol {
+    /// The compiled ID of the instance EMIT produces for `entity_id`:
+    /// `tavern/haggle@harbor_master`.
+    pub fn instance_id(&self, entity_id: &str) -> String {
+        format!("{}@{}", self.compiled_id, entity_id)
+    }
+
+    /// Distinct invocation arguments, in order of first invocation. Each
+    /// one becomes a concrete section in the emitted dialogue.
+    pub fn instance_arguments(&self) -> Vec<&str> {
+        let mut arguments: Vec<&str> = Vec::new();
+        for invocation in &self.invocations {
+            if !arguments.contains(&invocation.argument.as_str()) {
+                arguments.push(&invocation.argument);
+            }
+        }
+        arguments
+    }
+}
+
+/// One call site of a parameterised section.
+#[derive(Debug, Clone)]
+pub struct SectionInvocation {
+    /// The resolved entity ID passed as the argument.
+    pub argument: String,
+    pub declared_in: Span,
+}
+
+/// A choice within a section.
+#[derive(Debug, Clone)]
+pub struct ChoiceSymbol {
+    pub label: String,
+    pub compiled_id: String,
+    pub sticky: bool,
+    /// Set by a `? once` condition. Runtimes track a hidden per-choice
+    /// flag and withhold the choice after its section first presents it.
+    pub once: bool,
+    pub declared_in: Span,
+}
+
+/// A location symbol (`# Heading`).
+#[derive(Debug, Clone)]
+pub struct LocationSymbol {
+    pub id: String,
+    pub display_name: String,
+    pub exits: IndexMap<String, ExitSymbol>,
+    pub contains: Vec<String>,
+    /// Description paragraphs in source order: the heading's prose up to
+    /// its first exit, hook, entity list, or label. EMIT joins them with
+    /// blank lines.
+    pub description: Vec<DescriptionParagraph>,
+    /// The `on enter:` block, if declared.
+    pub on_enter: Option<LocationHookSymbol>,
+    /// The `on exit:` block, if declared.
+    pub on_exit: Option<LocationHookSymbol>,
+    /// Author documentation from `///` lines. Never shown to players.
+    pub doc: Option<String>,
+    /// ID of the region whose heading precedes this location in its file.
+    pub region: Option<String>,
+    pub declared_in: Span,
+}
+
+/// One paragraph of a location description, with the alternatives its
+/// `| ...` lines give.
+#[derive(Debug, Clone)]
+pub struct DescriptionParagraph {
+    pub text: String,
+    pub variants: Vec<String>,
+    pub span: Span,
+}
+
+/// A region (`# Region: Name`). Headings with the same ID in several
+/// files declare one region.
+#[derive(Debug, Clone)]
+pub struct RegionSymbol {
+    pub id: String,
+    /// From the first heading declaring the region.
+    pub display_name: String,
+    /// Member location IDs in declaration order: files in topological
+    /// order, then source order within each file.
+    pub locations: Vec<String>,
+    /// The first heading declaring the region.
+    pub declared_in: Span,
+}
+
+/// An `on enter:` / `on exit:` block within a location.
+#[derive(Debug, Clone)]
+pub struct LocationHookSymbol {
+    pub kind: crate::ast::HookKind,
+    /// Indices into the hook's children for Condition and OrConditionBlock
+    /// nodes, in source order.
+    pub condition_nodes: Vec<AstNodeRef>,
+    /// Indices into the hook's children for Effect nodes, in source order.
+    pub effect_nodes: Vec<AstNodeRef>,
+    pub declared_in: Span,
+}
+
+/// An exit within a location.
+#[derive(Debug, Clone)]
+pub struct ExitSymbol {
+    pub direction: String,
+    pub destination: String,
+    pub resolved_destination: Option<String>,
+    /// Index into the AST's content nodes for the Condition, if present.
+    pub condition_node: Option<AstNodeRef>,
+    /// Index into the AST's content nodes for the BlockedMessage, if present.
+    pub blocked_message_node: Option<AstNodeRef>,
+    pub declared_in: Span,
+}
+
+/// A lightweight reference to an AST node (file path + node index).
+#[derive(Debug, Clone)]
+pub struct AstNodeRef {
+    pub file: crate::span::FilePath,
+    pub node_index: usize,
+}
+
+/// An action symbol (either frontmatter-declared or choice-derived).
+#[derive(Debug, Clone)]
+pub struct ActionSymbol {
+    pub id: String,
+    pub target: Option<String>,
+    pub target_type: Option<String>,
+    /// The entity `target` resolved to in LINK, or the parameter name
+    /// when it names a template parameter. `None` until then, or when it
+    /// did not resolve.
+    pub resolved_target: Option<String>,
+    /// The type `target_type` resolved to in LINK.
+    pub resolved_target_type: Option<String>,
+    pub declared_in: Span,
+}
+
+/// A rule symbol.
+#[derive(Debug, Clone)]
+pub struct RuleSymbol {
+    pub id: String,
+    pub actor: String,
+    pub trigger: String,
+    pub select: Option<SelectDef>,
+    /// The location an indented rule under a location heading is scoped
+    /// to. `None` for a global rule.
+    pub scope_location: Option<String>,
+    pub declared_in: Span,
+}
+
+/// A sequence symbol (`## Heading`).
+#[derive(Debug, Clone)]
+pub struct SequenceSymbol {
+    pub id: String,
+    pub phases: Vec<PhaseSymbol>,
+    pub declared_in: Span,
+}
+
+/// A phase within a sequence (`### Heading`).
+#[derive(Debug, Clone)]
+pub struct PhaseSymbol {
+    pub id: String,
+    pub advance: String,
+    pub action: Option<String>,
+    pub actions: Option<Vec<String>>,
+    pub rule: Option<String>,
+    /// Compiled ID of the dialogue section this phase opens. Set by LINK
+    /// resolution; `None` when the phase names no section or it is unresolved.
+    pub section: Option<String>,
+    pub declared_in: Span,
+}
+
+/// An ending declared in the `endings:` block.
+#[derive(Debug, Clone)]
+pub struct EndingSymbol {
+    pub id: String,
+    pub description: Option<String>,
+    pub declared_in: Span,
+}
+
+/// The `selects...from...where` definition stored on a `RuleSymbol`.
+#[derive(Debug, Clone)]
+pub struct SelectDef {
+    pub variable: String,
+    pub from: Vec<String>,
+    pub where_clauses: Vec<crate::ast::ConditionExpr>,
+    pub span: Span,
+}
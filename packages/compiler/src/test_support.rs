@@ -0,0 +1,280 @@
+/// Test and benchmark support: synthetic worlds and an in-memory file
+/// reader.
+///
+/// [`synthetic_world()`] writes a valid multi-file world of any size, so
+/// benchmarks and integration tests can exercise the compiler at scale
+/// without checking large fixtures into the repository. Output depends
+/// only on the arguments and the seed: the same call always yields the
+/// same files, byte for byte.
+///
+/// [`MemoryFileReader`] serves those files to the IMPORT phase.
+
+use std::collections::HashMap;
+
+use crate::import::{FileReadError, FileReader};
+
+/// Name of the entry file in every generated world.
+pub const ENTRY_FILE: &str = "world.urd.md";
+
+/// Name of the shared types file in worlds of two or more files.
+pub const TYPES_FILE: &str = "types.urd.md";
+
+/// Seed used by [`synthetic_world()`].
+pub const DEFAULT_SEED: u64 = 0x5eed_0f0d;
+
+/// A [`FileReader`] over an in-memory map of path to contents.
+///
+/// Paths are looked up exactly as the IMPORT phase builds them, so a
+/// world compiled with an empty entry directory uses bare file names as
+/// keys. Casing is never reported as different.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryFileReader {
+    files: HashMap<String, String>,
+}
+
+impl MemoryFileReader {
+    pub fn new(files: HashMap<String, String>) -> Self {
+        Self { files }
+    }
+
+    /// The contents of a file, if present.
+    pub fn get(&self, path: &str) -> Option<&str> {
+        self.files.get(path).map(String::as_str)
+    }
+}
+
+impl FileReader for MemoryFileReader {
+    fn read_file(&self, fs_path: &str) -> Result<String, FileReadError> {
+        self.files.get(fs_path).cloned().ok_or(FileReadError::NotFound)
+    }
+
+    fn canonical_filename(&self, _dir: &str, _filename: &str) -> Option<String> {
+        None
+    }
+}
+
+/// Generate a synthetic world with [`DEFAULT_SEED`].
+///
+/// See [`synthetic_world_with_seed()`].
+pub fn synthetic_world(
+    locations: usize,
+    sections_per_location: usize,
+    choices_per_section: usize,
+    files: usize,
+) -> HashMap<String, String> {
+    synthetic_world_with_seed(
+        DEFAULT_SEED,
+        locations,
+        sections_per_location,
+        choices_per_section,
+        files,
+    )
+}
+
+/// Generate a synthetic world, keyed by file name.
+///
+/// The entry file is always [`ENTRY_FILE`]. With one file, everything
+/// lives in it. With more, [`TYPES_FILE`] holds the types and entities,
+/// the entry holds the world block and imports every other file, and
+/// the rest are `part-NNN.urd.md` files. Locations are shared out in
+/// contiguous runs between the entry and the parts.
+///
+/// Each location has a keeper and an item, a description, and
+/// `north`/`south` exits to its neighbours in the same file. The first
+/// location of the entry file also has a `wing-N` exit to the first
+/// location of each part, so every location is reachable from the start.
+/// Each section opens with the keeper's prompt, offers one-shot and
+/// sticky choices that test and set properties, jumps on to the next
+/// section from its last choice, and ends with a fallthrough line.
+///
+/// The seed only varies the prose: structure is fixed by the counts.
+///
+/// # Panics
+///
+/// Panics if `locations` or `files` is zero, or if there are more
+/// files holding locations than there are locations.
+pub fn synthetic_world_with_seed(
+    seed: u64,
+    locations: usize,
+    sections_per_location: usize,
+    choices_per_section: usize,
+    files: usize,
+) -> HashMap<String, String> {
+    assert!(locations > 0, "a synthetic world needs at least one location");
+    assert!(files > 0, "a synthetic world needs at least one file");
+    // Every file but the types file holds locations.
+    let holders = if files == 1 { 1 } else { files - 1 };
+    assert!(
+        holders <= locations,
+        "{} files need at least {} locations",
+        files,
+        holders
+    );
+
+    let mut gen = Generator {
+        rng: SplitMix64(seed),
+        sections: sections_per_location,
+        choices: choices_per_section,
+    };
+    let parts = holders - 1;
+    let runs: Vec<(usize, usize)> = (0..holders)
+        .map(|h| (h * locations / holders, (h + 1) * locations / holders))
+        .collect();
+
+    let mut world = String::new();
+    world.push_str("world:\n  name: synthetic\n  start: room-0\n");
+    if parts > 0 {
+        let wings: Vec<String> = (1..=parts).map(|p| format!("wing-{}", p)).collect();
+        world.push_str(&format!("  directions: [{}]\n", wings.join(", ")));
+    }
+    let declarations = declarations(locations);
+
+    let mut out = HashMap::new();
+    let mut entry = String::from("---\n");
+    entry.push_str(&world);
+    if files == 1 {
+        entry.push_str(&declarations);
+    } else {
+        entry.push_str(&format!("import: ./{}\n", TYPES_FILE));
+        for p in 1..=parts {
+            entry.push_str(&format!("import: ./{}\n", part_file(p)));
+        }
+        out.insert(TYPES_FILE.to_string(), format!("---\n{}---\n", declarations));
+    }
+    entry.push_str("---\n");
+    let wings: Vec<usize> = runs[1..].iter().map(|&(first, _)| first).collect();
+    gen.write_run(&mut entry, runs[0], &wings);
+    out.insert(ENTRY_FILE.to_string(), entry);
+
+    for (p, &run) in runs.iter().enumerate().skip(1) {
+        let mut part = format!("---\nimport: ./{}\n---\n", TYPES_FILE);
+        gen.write_run(&mut part, run, &[]);
+        out.insert(part_file(p), part);
+    }
+    out
+}
+
+fn part_file(index: usize) -> String {
+    format!("part-{:03}.urd.md", index)
+}
+
+/// The `types:` and `entities:` frontmatter blocks.
+fn declarations(locations: usize) -> String {
+    let mut out = String::from(
+        "types:\n  Keeper [interactable]:\n    mood: enum(calm, wary, warm) = calm\n  \
+         Item [portable]:\n    examined: bool = false\nentities:\n",
+    );
+    for i in 0..locations {
+        out.push_str(&format!("  @keeper_{}: Keeper\n  @item_{}: Item\n", i, i));
+    }
+    out
+}
+
+const ADJECTIVES: &[&str] = &[
+    "worn", "salt-stained", "quiet", "draughty", "gilded", "low", "narrow",
+    "smoky", "cold", "crowded", "sunlit", "damp",
+];
+
+const NOUNS: &[&str] = &[
+    "lantern", "ledger", "bell", "chart", "rope", "barrel", "clock",
+    "anchor", "mirror", "casket", "banner", "kettle",
+];
+
+const REMARKS: &[&str] = &[
+    "Nobody comes this way without a reason.",
+    "The tide turns soon, so be quick.",
+    "I have kept this place longer than I care to say.",
+    "Mind the floor, it was never level.",
+    "You look like you have walked a long way.",
+    "Ask what you came to ask.",
+    "There was a storm here once. There is always a storm here once.",
+    "I remember every face that passes. Yours is new.",
+];
+
+struct Generator {
+    rng: SplitMix64,
+    sections: usize,
+    choices: usize,
+}
+
+impl Generator {
+    fn pick<'a>(&mut self, words: &[&'a str]) -> &'a str {
+        words[(self.rng.next() % words.len() as u64) as usize]
+    }
+
+    /// Write locations `first..end`, chained north to south.
+    fn write_run(&mut self, out: &mut String, (first, end): (usize, usize), wings: &[usize]) {
+        for i in first..end {
+            let (a, b, noun) = (self.pick(ADJECTIVES), self.pick(ADJECTIVES), self.pick(NOUNS));
+            out.push_str(&format!(
+                "\n# Room {}\n\nA {} room with {} walls. A {} hangs by the door.\n\n",
+                i, a, b, noun
+            ));
+            out.push_str(&format!("[@keeper_{}, @item_{}]\n\n", i, i));
+            if i + 1 < end {
+                out.push_str(&format!("-> north: Room {}\n", i + 1));
+            }
+            if i > first {
+                out.push_str(&format!("-> south: Room {}\n", i - 1));
+            }
+            if i == first {
+                for (w, target) in wings.iter().enumerate() {
+                    out.push_str(&format!("-> wing-{}: Room {}\n", w + 1, target));
+                }
+            }
+            for s in 0..self.sections {
+                self.write_section(out, i, s);
+            }
+        }
+    }
+
+    fn write_section(&mut self, out: &mut String, location: usize, section: usize) {
+        let keeper = format!("@keeper_{}", location);
+        let item = format!("@item_{}", location);
+        out.push_str(&format!("\n== talk_{}_{}\n\n", location, section));
+        let prompt = self.pick(REMARKS);
+        out.push_str(&format!("{}: {}\n\n", keeper, prompt));
+
+        for k in 0..self.choices {
+            let marker = if k % 2 == 1 { '+' } else { '*' };
+            let noun = self.pick(NOUNS);
+            out.push_str(&format!("{} Ask about the {} {}\n", marker, noun, k + 1));
+            // The first choice sets what the second tests; the second sets
+            // what the rest test, but only when there is a rest to read it.
+            match k {
+                0 => {}
+                1 => out.push_str(&format!("  ? {}.examined == true\n", item)),
+                _ => out.push_str(&format!("  ? {}.mood == warm\n", keeper)),
+            }
+            let reply = self.pick(REMARKS);
+            out.push_str(&format!("  {}: {}\n", keeper, reply));
+            match k {
+                0 => out.push_str(&format!("  > {}.examined = true\n", item)),
+                1 if self.choices > 2 => out.push_str(&format!("  > {}.mood = warm\n", keeper)),
+                _ => {}
+            }
+            if k + 1 == self.choices && section + 1 < self.sections {
+                out.push_str(&format!("  -> talk_{}_{}\n", location, section + 1));
+            }
+            out.push('\n');
+        }
+
+        if self.choices > 0 {
+            let farewell = self.pick(REMARKS);
+            out.push_str(&format!("{}: {}\n", keeper, farewell));
+        }
+    }
+}
+
+/// SplitMix64: small, fast, and identical on every platform.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
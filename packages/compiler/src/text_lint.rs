@@ -0,0 +1,207 @@
+/// Text lint: typo and style checks over player-visible prose.
+///
+/// Runs only when `CompileOptions::text_lints` is set, after VALIDATE and
+/// only on a world with no errors. It walks the same strings as
+/// [`crate::strings::extract_strings()`]: descriptions, blocked messages,
+/// choice labels, speech, and stage directions. Frontmatter, IDs, and
+/// property values are never linted.
+///
+/// Each rule implements [`TextLintRule`] and reports its own code. The
+/// built-in rules are all Info:
+///
+/// | Code   | Rule |
+/// |--------|------|
+/// | URD701 | [`DoubledWord`]: the same word twice in a row (`the the`). |
+/// | URD702 | [`ConsecutiveSpaces`]: two or more spaces in a row. |
+/// | URD703 | [`UnmatchedDelimiter`]: a bracket or double quote with no partner. |
+///
+/// Spans cover the node the text came from (a choice line, a description's
+/// first paragraph), so messages quote the offending text.
+///
+/// ## Code Range
+///
+/// | Phase     | Range         |
+/// |-----------|---------------|
+/// | TEXT LINT | URD700–URD799 |
+
+use crate::diagnostics::{Diagnostic, Severity};
+use crate::graph::DependencyGraph;
+use crate::span::Span;
+use crate::strings;
+use crate::symbol_table::SymbolTable;
+
+/// What kind of player-visible text a string is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextContext {
+    /// A location or section description, or a section's exhausted text.
+    Description,
+    /// An exit's blocked message.
+    BlockedMessage,
+    ChoiceLabel,
+    Speech,
+    StageDirection,
+}
+
+impl TextContext {
+    /// Classify a string table key (see [`crate::strings`]).
+    pub fn of_key(key: &str) -> TextContext {
+        if key.ends_with("/label") {
+            TextContext::ChoiceLabel
+        } else if key.ends_with("/blocked_message") {
+            TextContext::BlockedMessage
+        } else if key.ends_with("/direction") {
+            TextContext::StageDirection
+        } else if key.contains("/prompt/") || key.contains("/response/") {
+            TextContext::Speech
+        } else {
+            TextContext::Description
+        }
+    }
+
+    /// Lower-case name for messages (`"choice label"`).
+    pub fn name(self) -> &'static str {
+        match self {
+            TextContext::Description => "description",
+            TextContext::BlockedMessage => "blocked message",
+            TextContext::ChoiceLabel => "choice label",
+            TextContext::Speech => "speech",
+            TextContext::StageDirection => "stage direction",
+        }
+    }
+}
+
+/// A check over one player-visible string.
+pub trait TextLintRule {
+    /// Diagnostics for `text`, written at `span`.
+    fn check(&self, text: &str, span: &Span, context: TextContext) -> Vec<Diagnostic>;
+}
+
+/// The rules a compile with `CompileOptions::text_lints` runs.
+pub const BUILTIN_RULES: &[&dyn TextLintRule] = &[&DoubledWord, &ConsecutiveSpaces, &UnmatchedDelimiter];
+
+/// Run `rules` over every player-visible string of a world that passed
+/// VALIDATE. Diagnostics come in text order, then rule order.
+pub fn lint(graph: &DependencyGraph, symbol_table: &SymbolTable, rules: &[&dyn TextLintRule]) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    for (entry, span) in strings::extract_spanned(graph, symbol_table) {
+        let context = TextContext::of_key(&entry.key);
+        for rule in rules {
+            diagnostics.extend(rule.check(&entry.text, &span, context));
+        }
+    }
+    diagnostics
+}
+
+fn info(code: &str, message: String, span: &Span) -> Diagnostic {
+    Diagnostic {
+        severity: Severity::Info,
+        code: code.to_string(),
+        message,
+        span: span.clone(),
+        suggestion: None,
+        fix: None,
+        related: Vec::new(),
+    }
+}
+
+// ── Built-in rules ──
+
+/// URD701: a word repeated with only whitespace between, ignoring case.
+/// Reported once per repetition, so `no no no` gives two.
+pub struct DoubledWord;
+
+impl TextLintRule for DoubledWord {
+    fn check(&self, text: &str, span: &Span, context: TextContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut previous: Option<&str> = None;
+        let mut rest = text;
+        while let Some(start) = rest.find(is_word_char) {
+            // Anything but whitespace between two words breaks the pair.
+            if !rest[..start].chars().all(char::is_whitespace) {
+                previous = None;
+            }
+            rest = &rest[start..];
+            let end = rest.find(|c: char| !is_word_char(c)).unwrap_or(rest.len());
+            let word = &rest[..end];
+            if let Some(first) = previous.filter(|p| p.to_lowercase() == word.to_lowercase()) {
+                diagnostics.push(info(
+                    "URD701",
+                    format!("Doubled word '{} {}' in {}.", first, word, context.name()),
+                    span,
+                ));
+            }
+            // Repeated numbers (`1 1`) are left alone.
+            previous = word.contains(char::is_alphabetic).then_some(word);
+            rest = &rest[end..];
+        }
+        diagnostics
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '\''
+}
+
+/// URD702: two or more spaces in a row. Reported once per run.
+pub struct ConsecutiveSpaces;
+
+impl TextLintRule for ConsecutiveSpaces {
+    fn check(&self, text: &str, span: &Span, context: TextContext) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        let mut rest = text;
+        while let Some(start) = rest.find("  ") {
+            let run = rest[start..].find(|c: char| c != ' ').unwrap_or(rest.len() - start);
+            let before = rest[..start].rsplit(' ').next().unwrap_or("");
+            diagnostics.push(info(
+                "URD702",
+                format!("{} consecutive spaces after '{}' in {}.", run, before, context.name()),
+                span,
+            ));
+            rest = &rest[start + run..];
+        }
+        diagnostics
+    }
+}
+
+/// URD703: a `(`, `[`, `{`, or curly quote with no partner, or an odd
+/// number of straight double quotes. Single quotes are not checked, since
+/// they double as apostrophes. Reports the first problem only.
+pub struct UnmatchedDelimiter;
+
+impl TextLintRule for UnmatchedDelimiter {
+    fn check(&self, text: &str, span: &Span, context: TextContext) -> Vec<Diagnostic> {
+        let Some(delimiter) = first_unmatched(text) else {
+            return Vec::new();
+        };
+        vec![info(
+            "URD703",
+            format!("Unmatched '{}' in {} '{}'.", delimiter, context.name(), text),
+            span,
+        )]
+    }
+}
+
+fn first_unmatched(text: &str) -> Option<char> {
+    let mut open: Vec<char> = Vec::new();
+    for c in text.chars() {
+        match c {
+            '(' | '[' | '{' | '“' => open.push(c),
+            ')' | ']' | '}' | '”' => {
+                let partner = match c {
+                    ')' => '(',
+                    ']' => '[',
+                    '}' => '{',
+                    _ => '“',
+                };
+                if open.pop() != Some(partner) {
+                    return Some(c);
+                }
+            }
+            _ => {}
+        }
+    }
+    if let Some(&c) = open.first() {
+        return Some(c);
+    }
+    (text.matches('"').count() % 2 == 1).then_some('"')
+}
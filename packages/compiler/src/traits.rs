@@ -0,0 +1,52 @@
+/// Trait registry: the built-in entity traits and what each one enables.
+///
+/// VALIDATE checks every trait a type declares against this registry
+/// (URD441), and the LSP uses it for trait completion and hover. A world
+/// that targets an engine with its own traits sets
+/// `allow_custom_traits: true` in the world block, which downgrades the
+/// unknown-trait warning to info.
+
+use crate::link::edit_distance;
+
+/// A built-in trait and its runtime meaning.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraitInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+/// Built-in traits, in the order the spec lists them.
+pub const BUILTIN_TRAITS: &[TraitInfo] = &[
+    TraitInfo {
+        name: "container",
+        description: "Can hold other entities. Required as the destination of `> move` and for `in`/`not in` checks against the entity. Locations have it implicitly.",
+    },
+    TraitInfo {
+        name: "portable",
+        description: "Can be moved into another container: picked up, carried, stored. Required for `> move` and for `world.start_inventory`.",
+    },
+    TraitInfo {
+        name: "mobile",
+        description: "Can move itself between containers. Required, with `container`, on the type of `@player`.",
+    },
+    TraitInfo {
+        name: "interactable",
+        description: "Can be the target of player actions and choices.",
+    },
+];
+
+/// Look up a built-in trait by name.
+pub fn lookup(name: &str) -> Option<&'static TraitInfo> {
+    BUILTIN_TRAITS.iter().find(|t| t.name == name)
+}
+
+/// The closest built-in trait within edit distance 2, for did-you-mean
+/// suggestions. Ties go to the earlier registry entry.
+pub fn suggest(name: &str) -> Option<&'static str> {
+    BUILTIN_TRAITS
+        .iter()
+        .map(|t| (t.name, edit_distance(name, t.name)))
+        .filter(|(_, d)| (1..=2).contains(d))
+        .min_by_key(|(_, d)| *d)
+        .map(|(name, _)| name)
+}
@@ -1,17 +1,24 @@
 /// Step 4: Condition Validation
 ///
 /// Walk all files in topological order, recurse into content nodes:
-/// - PropertyComparison: operator compatibility (URD420), value type match (URD401)
+/// - PropertyComparison: bare reads of non-boolean properties (URD453),
+///   operator compatibility (URD420), value type match (URD401), ref
+///   comparisons against declared entities (URD458, URD419),
+///   satisfiability against the declared range (URD435, URD436)
 /// - ContainmentCheck: container trait check via container_kind (URD422)
 /// - ExhaustionCheck: file-locality check (URD423)
+/// - Once: only on a one-shot choice (URD444), never outside one (URD445)
 
 use crate::ast::{ConditionExpr, ContainerKind, ContentNode};
-use crate::diagnostics::DiagnosticCollector;
+use crate::diagnostics::{Diagnostic, DiagnosticCollector, Severity};
 use crate::graph::DependencyGraph;
 use crate::span::Span;
 use crate::symbol_table::{PropertyType, SymbolTable};
 
-use super::helpers::{format_property_type, has_trait, parse_string_to_value};
+use super::helpers::{
+    check_ref_type, comparison_operators, entity_label, format_property_type, has_trait, literal_fits,
+    parse_string_to_value,
+};
 
 /// Validate all conditions in the compilation unit.
 pub fn validate_conditions(
@@ -59,6 +66,21 @@ fn validate_content_conditions(
         }
         ContentNode::Choice(choice) => {
             for child in &choice.content {
+                if let ContentNode::Condition(cond) = child {
+                    if let ConditionExpr::Once(once) = &cond.expr {
+                        if choice.sticky {
+                            diagnostics.error(
+                                "URD444",
+                                format!(
+                                    "Sticky choice '{}' cannot be 'once'. Use '*' for a one-shot choice.",
+                                    choice.label,
+                                ),
+                                once.span.clone(),
+                            );
+                        }
+                        continue;
+                    }
+                }
                 validate_content_conditions(child, file_path, local_section_ids, symbol_table, diagnostics);
             }
         }
@@ -67,6 +89,11 @@ fn validate_content_conditions(
                 validate_content_conditions(child, file_path, local_section_ids, symbol_table, diagnostics);
             }
         }
+        ContentNode::LocationHook(hook) => {
+            for child in &hook.children {
+                validate_content_conditions(child, file_path, local_section_ids, symbol_table, diagnostics);
+            }
+        }
         ContentNode::RuleBlock(rule) => {
             for expr in &rule.where_clauses {
                 validate_condition_expr(expr, file_path, local_section_ids, symbol_table, diagnostics);
@@ -114,12 +141,48 @@ fn validate_condition_expr(
                 None => return,
             };
 
-            // 1. Operator compatibility.
-            validate_operator(&pc.operator, prop, &pc.span, diagnostics);
+            // A bare read is only meaningful for a boolean.
+            if pc.bare && prop.property_type != PropertyType::Boolean {
+                let example = match prop.property_type {
+                    PropertyType::Enum => prop.values.as_ref().and_then(|v| v.first()).cloned(),
+                    PropertyType::Integer | PropertyType::Number => Some("0".to_string()),
+                    _ => None,
+                };
+                let subject = if pc.entity_ref.starts_with('$') {
+                    pc.entity_ref.clone()
+                } else {
+                    format!("@{}", pc.entity_ref)
+                };
+                diagnostics.emit(Diagnostic {
+                    severity: Severity::Error,
+                    code: "URD453".to_string(),
+                    message: format!(
+                        "Condition '{}{}.{}' tests property '{}' of type '{}' as a boolean. Only boolean properties can be tested without a comparison.",
+                        if pc.negated { "!" } else { "" },
+                        subject,
+                        pc.property,
+                        prop.name,
+                        format_property_type(&prop.property_type),
+                    ),
+                    span: pc.span.clone(),
+                    suggestion: example.map(|value| {
+                        format!("Write an explicit comparison, such as '{}.{} == {}'.", subject, pc.property, value)
+                    }),
+                    fix: None,
+                    related: Vec::new(),
+                });
+                return;
+            }
+
+            // 1. Operator compatibility, on the operator as written.
+            validate_operator(&pc.operator, prop, &pc.spans.operator_span(&pc.span), diagnostics);
 
             // 2. Value type check.
             let value = parse_string_to_value(&pc.value, &prop.property_type);
-            validate_comparison_value(&value, prop, entity_id, &pc.span, diagnostics);
+            validate_comparison_value(&pc.value, &value, prop, entity_id, symbol_table, &pc.spans.value_span(&pc.span), diagnostics);
+
+            // 3. Satisfiability against the declared range.
+            validate_comparison_range(pc, &value, prop, entity_id, diagnostics);
         }
 
         ConditionExpr::ContainmentCheck(cc) => {
@@ -175,7 +238,9 @@ fn validate_condition_expr(
             // from the current file's local_sections. If it resolved, it's file-local.
             // If the annotation has a resolved_section, LINK already confirmed file-locality.
             // VALIDATE confirms by checking the section's local_name is in this file's sections.
-            if ann.resolved_section.is_none() {
+            // A file-qualified name (`file_stem/name`) may name an imported
+            // file's section; LINK checked it is visible.
+            if ann.resolved_section.is_none() || ec.section_name.contains('/') {
                 return;
             }
             // Check file-locality: the section_name must be in local_section_ids.
@@ -190,98 +255,188 @@ fn validate_condition_expr(
                 );
             }
         }
+
+        // Unresolved locations were reported by LINK (URD321).
+        ConditionExpr::VisitedCheck(_) => {}
+
+        ConditionExpr::Once(once) => {
+            diagnostics.error(
+                "URD445",
+                "'once' is only valid as a condition on a choice.",
+                once.span.clone(),
+            );
+        }
     }
 }
 
+/// URD420 unless the property type allows `operator`.
 fn validate_operator(
     operator: &str,
     prop: &crate::symbol_table::PropertySymbol,
     span: &Span,
     diagnostics: &mut DiagnosticCollector,
 ) {
-    // == and != are valid for all types.
-    if operator == "==" || operator == "!=" {
-        return;
-    }
-    // <, >, <=, >= are valid only for integer and number.
-    if matches!(operator, "<" | ">" | "<=" | ">=") {
-        if !matches!(prop.property_type, PropertyType::Integer | PropertyType::Number) {
-            diagnostics.error(
-                "URD420",
-                format!(
-                    "Operator '{}' is not valid for property '{}' of type '{}'. Use == or != for non-numeric types.",
-                    operator, prop.name, format_property_type(&prop.property_type),
-                ),
-                span.clone(),
-            );
-        }
+    let allowed = comparison_operators(&prop.property_type);
+    if !allowed.contains(&operator) {
+        diagnostics.error(
+            "URD420",
+            format!(
+                "Operator '{}' is not valid for property '{}' of type '{}'. Allowed operators: {}.",
+                operator,
+                prop.name,
+                format_property_type(&prop.property_type),
+                allowed.join(", "),
+            ),
+            span.clone(),
+        );
     }
 }
 
+/// URD401 if the literal does not fit the property type or names no enum
+/// value; URD458 or URD419 if a ref property is compared with anything
+/// but an entity of its ref type.
 fn validate_comparison_value(
+    raw: &str,
     value: &crate::symbol_table::Value,
     prop: &crate::symbol_table::PropertySymbol,
     entity_id: &str,
+    symbol_table: &SymbolTable,
     span: &Span,
     diagnostics: &mut DiagnosticCollector,
 ) {
     use crate::symbol_table::Value;
-    match &prop.property_type {
-        PropertyType::Boolean => {
-            if !matches!(value, Value::Boolean(_)) {
-                diagnostics.error(
-                    "URD401",
-                    format!(
-                        "Type mismatch: property '{}' on entity '@{}' expects boolean but got '{}'.",
-                        prop.name, entity_id, super::helpers::format_value(value),
-                    ),
-                    span.clone(),
-                );
-            }
-        }
-        PropertyType::Integer => {
-            if !matches!(value, Value::Integer(_)) {
-                diagnostics.error(
-                    "URD401",
-                    format!(
-                        "Type mismatch: property '{}' on entity '@{}' expects integer but got '{}'.",
-                        prop.name, entity_id, super::helpers::format_value(value),
-                    ),
-                    span.clone(),
-                );
-            }
-        }
-        PropertyType::Number => {
-            if !matches!(value, Value::Number(_) | Value::Integer(_)) {
-                diagnostics.error(
-                    "URD401",
-                    format!(
-                        "Type mismatch: property '{}' on entity '@{}' expects number but got '{}'.",
-                        prop.name, entity_id, super::helpers::format_value(value),
-                    ),
-                    span.clone(),
-                );
-            }
+    let fits = match (&prop.property_type, value) {
+        (PropertyType::Enum, Value::String(s)) => prop.values.as_ref().is_none_or(|values| values.contains(s)),
+        (PropertyType::Ref, _) => {
+            validate_ref_comparison(raw, prop, entity_id, symbol_table, span, diagnostics);
+            true
         }
-        PropertyType::Enum => {
-            if let Value::String(s) = value {
-                if let Some(values) = &prop.values {
-                    if !values.contains(s) {
-                        diagnostics.error(
-                            "URD401",
-                            format!(
-                                "Type mismatch: property '{}' on entity '@{}' expects enum but got '{}'.",
-                                prop.name, entity_id, s,
-                            ),
-                            span.clone(),
-                        );
-                    }
-                }
-            }
-        }
-        PropertyType::String => {
-            // String comparisons always match.
-        }
-        _ => {}
+        (property_type, value) => literal_fits(value, property_type),
+    };
+    if !fits {
+        diagnostics.error(
+            "URD401",
+            format!(
+                "Type mismatch: property '{}' on entity '{}' expects {} but got '{}'.",
+                prop.name,
+                entity_label(entity_id),
+                format_property_type(&prop.property_type),
+                raw,
+            ),
+            span.clone(),
+        );
+    }
+}
+
+/// A ref property compares equal only to an entity, written `@id`.
+fn validate_ref_comparison(
+    raw: &str,
+    prop: &crate::symbol_table::PropertySymbol,
+    entity_id: &str,
+    symbol_table: &SymbolTable,
+    span: &Span,
+    diagnostics: &mut DiagnosticCollector,
+) {
+    let Some(ref_id) = raw.strip_prefix('@') else {
+        diagnostics.emit(Diagnostic {
+            severity: Severity::Error,
+            code: "URD458".to_string(),
+            message: format!(
+                "Property '{}' on entity '{}' is a ref and can only be compared with an entity reference, but got '{}'.",
+                prop.name, entity_label(entity_id), raw,
+            ),
+            span: span.clone(),
+            suggestion: symbol_table
+                .entities
+                .contains_key(raw)
+                .then(|| format!("Write the entity with its sigil: '@{}'.", raw)),
+            fix: None,
+            related: Vec::new(),
+        });
+        return;
+    };
+    if !symbol_table.entities.contains_key(ref_id) {
+        diagnostics.error(
+            "URD458",
+            format!(
+                "Property '{}' on entity '{}' is compared with '@{}', which is not a declared entity.",
+                prop.name, entity_label(entity_id), ref_id,
+            ),
+            span.clone(),
+        );
+        return;
+    }
+    check_ref_type(ref_id, prop, entity_id, symbol_table, span, diagnostics);
+}
+
+/// Check a numeric comparison against the property's declared `[min, max]`.
+///
+/// A comparison no value in the range can satisfy is a warning (URD435);
+/// one every value in the range satisfies is info (URD436). Skipped when
+/// the property declares neither bound or the literal is not numeric
+/// (URD401 covers the latter).
+fn validate_comparison_range(
+    pc: &crate::ast::PropertyComparison,
+    value: &crate::symbol_table::Value,
+    prop: &crate::symbol_table::PropertySymbol,
+    entity_id: &str,
+    diagnostics: &mut DiagnosticCollector,
+) {
+    use crate::symbol_table::Value;
+    if prop.min.is_none() && prop.max.is_none() {
+        return;
+    }
+    let v = match (&prop.property_type, value) {
+        (PropertyType::Integer, Value::Integer(i)) => *i as f64,
+        (PropertyType::Number, Value::Number(n)) => *n,
+        (PropertyType::Number, Value::Integer(i)) => *i as f64,
+        _ => return,
+    };
+
+    // An integer property can only hold whole numbers inside its bounds.
+    let (min, max) = match prop.property_type {
+        PropertyType::Integer => (
+            prop.min.map_or(f64::NEG_INFINITY, f64::ceil),
+            prop.max.map_or(f64::INFINITY, f64::floor),
+        ),
+        _ => (
+            prop.min.unwrap_or(f64::NEG_INFINITY),
+            prop.max.unwrap_or(f64::INFINITY),
+        ),
+    };
+
+    let (operator, literal) = pc.lowered();
+    let (never, always) = match operator {
+        ">" => (max <= v, min > v),
+        ">=" => (max < v, min >= v),
+        "<" => (min >= v, max < v),
+        "<=" => (min > v, max <= v),
+        "==" => (v < min || v > max, min == v && max == v),
+        "!=" => (min == v && max == v, v < min || v > max),
+        _ => return,
+    };
+
+    let min_str = prop.min.map(|m| m.to_string()).unwrap_or_else(|| "-∞".to_string());
+    let max_str = prop.max.map(|m| m.to_string()).unwrap_or_else(|| "∞".to_string());
+    let condition = format!("@{}.{} {} {}", pc.entity_ref, pc.property, operator, literal);
+
+    if never {
+        diagnostics.warning(
+            "URD435",
+            format!(
+                "Condition '{}' can never be satisfied: property '{}' on entity '{}' has declared range [{}, {}].",
+                condition, prop.name, entity_label(entity_id), min_str, max_str,
+            ),
+            pc.span.clone(),
+        );
+    } else if always {
+        diagnostics.info(
+            "URD436",
+            format!(
+                "Condition '{}' is always satisfied: property '{}' on entity '{}' has declared range [{}, {}].",
+                condition, prop.name, entity_label(entity_id), min_str, max_str,
+            ),
+            pc.span.clone(),
+        );
     }
 }
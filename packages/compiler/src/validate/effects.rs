@@ -4,14 +4,18 @@
 /// - Set: value type match (URD401), arithmetic operator check (URD424)
 /// - Move: portable trait (URD425), destination kind check (URD422)
 /// - Reveal: visibility check (URD426 warning)
-/// - Destroy: no additional checks
+/// - Destroy: not the player (URD456)
+///
+/// Each ordered effect list (a choice's, exit's, or hook's content, or a
+/// rule's effects) is also walked in order for effects on an entity an
+/// earlier effect in the same list destroyed (URD457 warning).
 
-use crate::ast::{ContentNode, DestinationKind, EffectType};
-use crate::diagnostics::DiagnosticCollector;
+use crate::ast::{ContentNode, DestinationKind, Effect, EffectType};
+use crate::diagnostics::{Diagnostic, DiagnosticCollector, RelatedInfo, Severity};
 use crate::graph::DependencyGraph;
 use crate::symbol_table::{PropertyType, SymbolTable, Visibility};
 
-use super::helpers::{format_property_type, has_trait, parse_string_to_value};
+use super::helpers::{entity_label, format_property_type, has_trait, literal_fits, parse_string_to_value};
 
 /// Validate all effects in the compilation unit.
 pub fn validate_effects(
@@ -45,21 +49,84 @@ fn validate_content_effects(
             for child in &choice.content {
                 validate_content_effects(child, symbol_table, diagnostics);
             }
+            check_destroyed_uses(child_effects(&choice.content), "choice", diagnostics);
         }
         ContentNode::ExitDeclaration(exit) => {
             for child in &exit.children {
                 validate_content_effects(child, symbol_table, diagnostics);
             }
+            check_destroyed_uses(child_effects(&exit.children), "exit", diagnostics);
+        }
+        ContentNode::LocationHook(hook) => {
+            for child in &hook.children {
+                validate_content_effects(child, symbol_table, diagnostics);
+            }
+            check_destroyed_uses(child_effects(&hook.children), "hook", diagnostics);
         }
         ContentNode::RuleBlock(rule) => {
             for effect in &rule.effects {
                 validate_effect(&effect.effect_type, &effect.annotation, &effect.span, symbol_table, diagnostics);
             }
+            check_destroyed_uses(rule.effects.iter(), "rule", diagnostics);
         }
         _ => {}
     }
 }
 
+/// The effects directly in a content list, in order.
+fn child_effects(children: &[ContentNode]) -> impl Iterator<Item = &Effect> {
+    children.iter().filter_map(|child| match child {
+        ContentNode::Effect(effect) => Some(effect),
+        _ => None,
+    })
+}
+
+/// Warn about each effect that refers to an entity an earlier effect in
+/// the same list destroyed (URD457). Unresolved effects take no part.
+/// Positions count effects only, from 1.
+fn check_destroyed_uses<'a>(
+    effects: impl Iterator<Item = &'a Effect>,
+    owner: &str,
+    diagnostics: &mut DiagnosticCollector,
+) {
+    // (entity, position, effect) for each destroy so far.
+    let mut destroyed: Vec<(&str, usize, &Effect)> = Vec::new();
+    for (i, effect) in effects.enumerate() {
+        let Some(ann) = &effect.annotation else {
+            continue;
+        };
+        let destination = match &ann.destination_kind {
+            Some(DestinationKind::EntityRef(id)) => Some(id.as_str()),
+            _ => None,
+        };
+        let referenced = ann.resolved_entity.as_deref().into_iter().chain(destination);
+        let earlier = referenced
+            .filter_map(|id| destroyed.iter().find(|(d, _, _)| *d == id))
+            .next();
+        if let Some(&(entity_id, position, destroy)) = earlier {
+            diagnostics.emit(Diagnostic {
+                severity: Severity::Warning,
+                code: "URD457".to_string(),
+                message: format!(
+                    "Effect {} in this {} refers to '{}', which effect {} already destroyed.",
+                    i + 1, owner, entity_label(entity_id), position,
+                ),
+                span: effect.span.clone(),
+                suggestion: Some(format!("Move the destroy of '{}' after this effect.", entity_label(entity_id))),
+                fix: None,
+                related: vec![RelatedInfo {
+                    message: format!("'{}' destroyed here", entity_label(entity_id)),
+                    span: destroy.span.clone(),
+                }],
+            });
+            continue;
+        }
+        if let (EffectType::Destroy { .. }, Some(entity_id)) = (&effect.effect_type, &ann.resolved_entity) {
+            destroyed.push((entity_id, i + 1, effect));
+        }
+    }
+}
+
 fn validate_effect(
     effect_type: &EffectType,
     annotation: &Option<crate::ast::Annotation>,
@@ -68,7 +135,7 @@ fn validate_effect(
     diagnostics: &mut DiagnosticCollector,
 ) {
     match effect_type {
-        EffectType::Set { target_prop: _, operator, value_expr } => {
+        EffectType::Set { target_prop: _, operator, value_expr, spans } => {
             // Skip if annotation unresolved.
             let ann = match annotation {
                 Some(a) => a,
@@ -105,25 +172,22 @@ fn validate_effect(
                             "Arithmetic operator '{}' is not valid for property '{}' of type '{}'. Arithmetic effects require integer or number properties.",
                             operator, prop.name, format_property_type(&prop.property_type),
                         ),
-                        span.clone(),
+                        spans.operator_span(span),
                     );
                     return;
                 }
 
                 // Check that value is numeric.
                 let value = parse_string_to_value(value_expr, &prop.property_type);
-                match &value {
-                    crate::symbol_table::Value::Integer(_) | crate::symbol_table::Value::Number(_) => {}
-                    _ => {
-                        diagnostics.error(
-                            "URD401",
-                            format!(
-                                "Type mismatch: property '{}' on entity '@{}' expects {} but got '{}'.",
-                                prop.name, entity_id, format_property_type(&prop.property_type), value_expr,
-                            ),
-                            span.clone(),
-                        );
-                    }
+                if !literal_fits(&value, &prop.property_type) {
+                    diagnostics.error(
+                        "URD401",
+                        format!(
+                            "Type mismatch: property '{}' on entity '{}' expects {} but got '{}'.",
+                            prop.name, entity_label(entity_id), format_property_type(&prop.property_type), value_expr,
+                        ),
+                        spans.value_span(span),
+                    );
                 }
             } else {
                 // Regular set: type-check the value.
@@ -135,7 +199,7 @@ fn validate_effect(
                     type_name,
                     super::helpers::CheckContext::ConditionOrEffect,
                     symbol_table,
-                    span,
+                    &spans.value_span(span),
                     diagnostics,
                 );
             }
@@ -159,8 +223,8 @@ fn validate_effect(
                         diagnostics.error(
                             "URD425",
                             format!(
-                                "Entity '@{}' cannot be moved because its type '{}' does not have the 'portable' trait.",
-                                entity_id, type_name,
+                                "Entity '{}' cannot be moved because its type '{}' does not have the 'portable' trait.",
+                                entity_label(entity_id), type_name,
                             ),
                             span.clone(),
                         );
@@ -234,8 +298,8 @@ fn validate_effect(
                 diagnostics.warning(
                     "URD426",
                     format!(
-                        "Property '{}' on entity '@{}' is not hidden. Reveal has no effect.",
-                        prop.name, entity_id,
+                        "Property '{}' on entity '{}' is not hidden. Reveal has no effect.",
+                        prop.name, entity_label(entity_id),
                     ),
                     span.clone(),
                 );
@@ -243,7 +307,24 @@ fn validate_effect(
         }
 
         EffectType::Destroy { entity_ref: _ } => {
-            // No additional type checks needed. Any entity can be destroyed.
+            // Any entity but the player can be destroyed.
+            let resolved = annotation.as_ref().and_then(|a| a.resolved_entity.as_deref());
+            if resolved == Some("player") {
+                diagnostics.emit(Diagnostic {
+                    severity: Severity::Error,
+                    code: "URD456".to_string(),
+                    message: "Entity '@player' cannot be destroyed. The player must exist for the world to run."
+                        .to_string(),
+                    span: span.clone(),
+                    suggestion: Some("To finish the game, use an ending instead: '> end <ending>'.".to_string()),
+                    fix: None,
+                    related: Vec::new(),
+                });
+            }
+        }
+
+        EffectType::End { .. } => {
+            // LINK has already checked the ending is declared.
         }
     }
 }
@@ -5,11 +5,17 @@
 /// - Check enum values (URD402)
 /// - Check range constraints (URD418)
 /// - Check ref type matches (URD419)
+/// - Flag overrides that equal the type's default (URD440, info)
+///
+/// Hidden properties get the same checks as visible ones: visibility only
+/// affects what the runtime exposes, not which values are valid. Overrides
+/// merged in from presence lines are checked the same way, and reported
+/// on their presence line.
 
 use crate::diagnostics::DiagnosticCollector;
 use crate::symbol_table::SymbolTable;
 
-use super::helpers::{check_value, CheckContext};
+use super::helpers::{check_value, format_value, CheckContext};
 
 /// Validate all entity property overrides.
 pub fn validate_entities(
@@ -34,16 +40,28 @@ pub fn validate_entities(
                 None => continue,
             };
 
-            check_value(
+            let valid = check_value(
                 value,
                 prop,
                 entity_id,
                 type_name,
                 CheckContext::Override,
                 symbol_table,
-                &entity_sym.declared_in,
+                entity_sym.override_span(prop_name),
                 diagnostics,
             );
+
+            // Redundant override: only meaningful for a well-typed value.
+            if valid && prop.default.as_ref().is_some_and(|d| d.same_value(value)) {
+                diagnostics.info(
+                    "URD440",
+                    format!(
+                        "Override '{}: {}' on entity '@{}' equals the default on type '{}' and can be removed.",
+                        prop_name, format_value(value), entity_id, type_name,
+                    ),
+                    entity_sym.override_span(prop_name).clone(),
+                );
+            }
         }
     }
 }
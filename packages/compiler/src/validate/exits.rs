@@ -0,0 +1,126 @@
+/// Exit direction validation.
+///
+/// Checks exit directions against the movement vocabulary (URD447) and
+/// flags pairs of exits that lead to each other in the same direction
+/// (URD448).
+///
+/// The default vocabulary is the eight compass directions, `up`/`down`,
+/// and `in`/`out`. A world with its own movement verbs lists them in the
+/// world block's `directions:` field, which adds to the default.
+
+use crate::diagnostics::{Diagnostic, DiagnosticCollector, RelatedInfo, Severity};
+use crate::link::edit_distance;
+use crate::symbol_table::SymbolTable;
+
+/// Default exit directions, each paired with its opposite.
+const DEFAULT_DIRECTIONS: &[(&str, &str)] = &[
+    ("north", "south"),
+    ("south", "north"),
+    ("east", "west"),
+    ("west", "east"),
+    ("northeast", "southwest"),
+    ("southwest", "northeast"),
+    ("northwest", "southeast"),
+    ("southeast", "northwest"),
+    ("up", "down"),
+    ("down", "up"),
+    ("in", "out"),
+    ("out", "in"),
+];
+
+/// The opposite of a default direction, if it has one.
+fn opposite(direction: &str) -> Option<&'static str> {
+    DEFAULT_DIRECTIONS
+        .iter()
+        .find(|(d, _)| *d == direction)
+        .map(|(_, o)| *o)
+}
+
+pub fn validate_exits(
+    symbol_table: &SymbolTable,
+    custom_directions: &[String],
+    diagnostics: &mut DiagnosticCollector,
+) {
+    let vocabulary: Vec<&str> = DEFAULT_DIRECTIONS
+        .iter()
+        .map(|(d, _)| *d)
+        .chain(custom_directions.iter().map(String::as_str))
+        .collect();
+
+    // a. Unknown direction
+    for loc_sym in symbol_table.locations.values() {
+        for exit in loc_sym.exits.values() {
+            if vocabulary.contains(&exit.direction.as_str()) {
+                continue;
+            }
+            let suggestion = vocabulary
+                .iter()
+                .map(|d| (*d, edit_distance(&exit.direction, d)))
+                .filter(|(_, dist)| (1..=2).contains(dist))
+                .min_by_key(|(_, dist)| *dist)
+                .map(|(d, _)| format!("Did you mean '{}'?", d));
+            diagnostics.emit(Diagnostic {
+                severity: Severity::Warning,
+                code: "URD447".to_string(),
+                message: format!(
+                    "Exit direction '{}' in location '{}' is not a known direction.",
+                    exit.direction, loc_sym.id,
+                ),
+                span: exit.declared_in.clone(),
+                suggestion: Some(suggestion.unwrap_or_else(|| {
+                    format!(
+                        "Add '{}' to the world block's 'directions' list if it is intentional.",
+                        exit.direction,
+                    )
+                })),
+                fix: None,
+                related: Vec::new(),
+            });
+        }
+    }
+
+    // b. Same-direction reciprocal pair. Only directions with an opposite
+    // are checked: a custom verb such as 'back' may well lead both ways.
+    for (loc_id, loc_sym) in &symbol_table.locations {
+        for exit in loc_sym.exits.values() {
+            if opposite(&exit.direction).is_none() {
+                continue;
+            }
+            let Some(dest_id) = &exit.resolved_destination else {
+                continue;
+            };
+            // Report each pair once, from the earlier-declared location.
+            let dest_index = symbol_table.locations.get_index_of(dest_id.as_str());
+            let loc_index = symbol_table.locations.get_index_of(loc_id.as_str());
+            if dest_index <= loc_index {
+                continue;
+            }
+            let Some(back) = symbol_table
+                .locations
+                .get(dest_id.as_str())
+                .and_then(|dest| dest.exits.get(&exit.direction))
+            else {
+                continue;
+            };
+            if back.resolved_destination.as_deref() != Some(loc_id.as_str()) {
+                continue;
+            }
+            diagnostics.emit(Diagnostic {
+                severity: Severity::Info,
+                code: "URD448".to_string(),
+                message: format!(
+                    "Exits between '{}' and '{}' both go '{}'. A return exit usually goes the opposite way.",
+                    loc_id, dest_id, exit.direction,
+                ),
+                span: exit.declared_in.clone(),
+                suggestion: opposite(&exit.direction)
+                    .map(|o| format!("Use '{}' for one of them.", o)),
+                fix: None,
+                related: vec![RelatedInfo {
+                    message: format!("Exit '{}' from '{}' declared here", back.direction, dest_id),
+                    span: back.declared_in.clone(),
+                }],
+            });
+        }
+    }
+}
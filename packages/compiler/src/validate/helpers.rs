@@ -10,6 +10,16 @@ use crate::diagnostics::DiagnosticCollector;
 use crate::span::Span;
 use crate::symbol_table::{PropertySymbol, PropertyType, SymbolTable, Value, Visibility};
 
+/// An entity reference as written in a message: `@id`, or a rule's select
+/// variable (`$door`) as it is.
+pub fn entity_label(entity_id: &str) -> String {
+    if entity_id.starts_with('$') {
+        entity_id.to_string()
+    } else {
+        format!("@{}", entity_id)
+    }
+}
+
 /// Check if a type has a given trait.
 pub fn has_trait(type_name: &str, trait_name: &str, symbol_table: &SymbolTable) -> bool {
     symbol_table
@@ -58,6 +68,33 @@ pub fn parse_string_to_value(raw: &str, expected_type: &PropertyType) -> Value {
     }
 }
 
+/// The comparison operators a property type allows: the full relational
+/// set for integers and numbers, `==` and `!=` for every other type.
+pub fn comparison_operators(property_type: &PropertyType) -> &'static [&'static str] {
+    match property_type {
+        PropertyType::Integer | PropertyType::Number => &["==", "!=", "<", ">", "<=", ">="],
+        _ => &["==", "!="],
+    }
+}
+
+/// Whether a condition or effect literal, parsed by
+/// `parse_string_to_value()`, has the shape its property type needs:
+/// `true` or `false` for a boolean, a whole number for an integer, any
+/// finite number for a number. Other types take any shape here; enum values and
+/// ref targets are checked by the caller.
+pub fn literal_fits(value: &Value, property_type: &PropertyType) -> bool {
+    match property_type {
+        PropertyType::Boolean => matches!(value, Value::Boolean(_)),
+        PropertyType::Integer => matches!(value, Value::Integer(_)),
+        PropertyType::Number => match value {
+            Value::Integer(_) => true,
+            Value::Number(n) => n.is_finite(),
+            _ => false,
+        },
+        _ => true,
+    }
+}
+
 /// Format a Value for diagnostic messages.
 pub fn format_value(value: &Value) -> String {
     match value {
@@ -127,6 +164,24 @@ pub fn check_value(
             }
         }
         PropertyType::Number => {
+            // JSON has no NaN or infinity, so a world cannot hold them.
+            if let Value::Number(n) = value {
+                if !n.is_finite() {
+                    let message = if is_default {
+                        format!(
+                            "Default value '{}' for property '{}' on type '{}' is not a finite number.",
+                            n, prop.name, type_name,
+                        )
+                    } else {
+                        format!(
+                            "Type mismatch: property '{}' on entity '{}' expects a finite number but got '{}'.",
+                            prop.name, entity_label(entity_id), n,
+                        )
+                    };
+                    diagnostics.error(if is_default { "URD413" } else { "URD401" }, message, span.clone());
+                    return false;
+                }
+            }
             let num = match value {
                 Value::Number(n) => Some(*n),
                 Value::Integer(i) => Some(*i as f64),
@@ -154,8 +209,8 @@ pub fn check_value(
                                 diagnostics.error(
                                     "URD402",
                                     format!(
-                                        "Enum value '{}' is not valid for property '{}' on entity '@{}'. Valid values: {}.",
-                                        s, prop.name, entity_id,
+                                        "Enum value '{}' is not valid for property '{}' on entity '{}'. Valid values: {}.",
+                                        s, prop.name, entity_label(entity_id),
                                         values.join(", "),
                                     ),
                                     span.clone(),
@@ -165,8 +220,8 @@ pub fn check_value(
                                 diagnostics.error(
                                     "URD413",
                                     format!(
-                                        "Default value '{}' for property '{}' on type '{}' does not match the declared type '{}'.",
-                                        s, prop.name, type_name, format_property_type(&prop.property_type),
+                                        "Default value '{}' for property '{}' on type '{}' is not one of its values: {}.",
+                                        s, prop.name, type_name, values.join(", "),
                                     ),
                                     span.clone(),
                                 );
@@ -175,8 +230,8 @@ pub fn check_value(
                                 diagnostics.error(
                                     "URD401",
                                     format!(
-                                        "Type mismatch: property '{}' on entity '@{}' expects {} but got '{}'.",
-                                        prop.name, entity_id, format_property_type(&prop.property_type), s,
+                                        "Type mismatch: property '{}' on entity '{}' expects {} but got '{}'.",
+                                        prop.name, entity_label(entity_id), format_property_type(&prop.property_type), s,
                                     ),
                                     span.clone(),
                                 );
@@ -256,8 +311,8 @@ fn emit_type_mismatch(
         diagnostics.error(
             "URD401",
             format!(
-                "Type mismatch: property '{}' on entity '@{}' expects {} but got '{}'.",
-                prop.name, entity_id, format_property_type(&prop.property_type), format_value(value),
+                "Type mismatch: property '{}' on entity '{}' expects {} but got '{}'.",
+                prop.name, entity_label(entity_id), format_property_type(&prop.property_type), format_value(value),
             ),
             span.clone(),
         );
@@ -280,8 +335,8 @@ fn check_range(
             diagnostics.error(
                 "URD418",
                 format!(
-                    "Value {} for property '{}' on entity '@{}' is outside the declared range [{}, {}].",
-                    value, prop.name, entity_id, min_str, max_str,
+                    "Value {} for property '{}' on entity '{}' is outside the declared range [{}, {}].",
+                    value, prop.name, entity_label(entity_id), min_str, max_str,
                 ),
                 span.clone(),
             );
@@ -291,7 +346,9 @@ fn check_range(
     true
 }
 
-fn check_ref_type(
+/// URD419 if `ref_id` names an entity whose type is not the property's
+/// `ref_type`. Unknown entities and untyped ones are skipped.
+pub fn check_ref_type(
     ref_id: &str,
     prop: &PropertySymbol,
     entity_id: &str,
@@ -306,8 +363,8 @@ fn check_ref_type(
                     diagnostics.error(
                         "URD419",
                         format!(
-                            "Property '{}' on entity '@{}' requires a reference to type '{}' but '@{}' has type '{}'.",
-                            prop.name, entity_id, expected_type, ref_id, actual_type,
+                            "Property '{}' on entity '{}' requires a reference to type '{}' but '@{}' has type '{}'.",
+                            prop.name, entity_label(entity_id), expected_type, ref_id, actual_type,
                         ),
                         span.clone(),
                     );
@@ -18,62 +18,195 @@ mod types;
 mod entities;
 mod conditions;
 mod effects;
+mod exits;
+mod speakers;
+mod placement;
+mod rules;
+mod sections;
 
 use std::collections::{HashSet, VecDeque};
 
 use crate::ast::{Choice, ConditionExpr, ContentNode, FrontmatterValue, PropertyComparison};
-use crate::diagnostics::DiagnosticCollector;
-use crate::graph::{DependencyGraph, WARN_CHOICE_NESTING_DEPTH, MAX_CHOICE_NESTING_DEPTH};
+use crate::diagnostics::{Diagnostic, DiagnosticCollector, RelatedInfo, Severity};
+use crate::emit::layout::{self, DroppedPlace};
+use crate::graph::{
+    DependencyGraph, MAX_CHOICE_NESTING_DEPTH, MAX_CONDITION_COUNT, WARN_CHOICE_NESTING_DEPTH, WARN_CONDITION_COUNT,
+};
+use crate::span::Span;
 use crate::slugify::slugify;
-use crate::symbol_table::{PropertyType, SymbolTable};
+
+use sections::SectionIndex;
+use crate::link::ScopePolicy;
+use crate::symbol_table::{PropertyType, SymbolTable, WorldMode};
 
 /// Valid advance modes for sequence phases.
 const VALID_ADVANCE_MODES: &[&str] = &["on_action", "on_rule", "on_condition", "end", "auto", "manual"];
 
 /// Validate the linked world: type-check properties, conditions, effects.
 /// Enforce all semantic constraints defined in the spec.
+///
+/// On native targets with more than one core, a world of at least
+/// [`PARALLEL_MIN_CONTENT_NODES`] top-level content nodes is validated by
+/// [`validate_parallel()`]; anything else by [`validate_sequential()`].
+/// Both give the same diagnostics in the same order.
 pub fn validate(
     graph: &DependencyGraph,
     symbol_table: &SymbolTable,
     diagnostics: &mut DiagnosticCollector,
 ) {
-    let ordered: Vec<String> = graph.topological_order().into_iter().cloned().collect();
-
-    // Step 1: Global configuration checks.
-    validate_global_config(graph, &ordered, symbol_table, diagnostics);
+    run_steps(graph, symbol_table, diagnostics, worth_threads(graph));
+}
 
-    // Step 2: Type definition validation.
-    types::validate_types(symbol_table, diagnostics);
+/// [`validate()`] with every step on the calling thread, as on WASM.
+pub fn validate_sequential(
+    graph: &DependencyGraph,
+    symbol_table: &SymbolTable,
+    diagnostics: &mut DiagnosticCollector,
+) {
+    run_steps(graph, symbol_table, diagnostics, false);
+}
 
-    // Step 3: Entity property override validation.
-    entities::validate_entities(symbol_table, diagnostics);
+/// [`validate()`] with the expensive steps on scoped threads of their own,
+/// however small the world. Each step records into a collector of its
+/// own, and the results are merged in step order, so the diagnostics and
+/// their order are exactly those of [`validate_sequential()`].
+#[cfg(not(target_arch = "wasm32"))]
+pub fn validate_parallel(
+    graph: &DependencyGraph,
+    symbol_table: &SymbolTable,
+    diagnostics: &mut DiagnosticCollector,
+) {
+    run_steps(graph, symbol_table, diagnostics, true);
+}
 
-    // Step 4: Condition validation.
-    conditions::validate_conditions(graph, &ordered, symbol_table, diagnostics);
+/// The fewest top-level content nodes, across all files, for which
+/// [`validate()`] starts threads. Below it, validation takes about a
+/// millisecond, which spawning the workers would mostly spend.
+pub const PARALLEL_MIN_CONTENT_NODES: usize = 2_000;
 
-    // Step 5: Effect validation.
-    effects::validate_effects(graph, &ordered, symbol_table, diagnostics);
+#[cfg(not(target_arch = "wasm32"))]
+fn worth_threads(graph: &DependencyGraph) -> bool {
+    static CORES: std::sync::OnceLock<usize> = std::sync::OnceLock::new();
+    let cores = *CORES.get_or_init(|| std::thread::available_parallelism().map_or(1, |n| n.get()));
+    cores > 1 && graph.nodes.values().map(|n| n.ast.content.len()).sum::<usize>() >= PARALLEL_MIN_CONTENT_NODES
+}
 
-    // Step 6: Action validation.
-    validate_actions(symbol_table, diagnostics);
+#[cfg(target_arch = "wasm32")]
+fn worth_threads(_graph: &DependencyGraph) -> bool {
+    false
+}
 
-    // Step 7: Sequence and phase validation.
-    validate_sequences(symbol_table, diagnostics);
+/// One VALIDATE step, recording into the collector it is given.
+type Step<'a> = Box<dyn FnOnce(&mut DiagnosticCollector) + Send + 'a>;
 
-    // Step 8: Nesting depth validation.
-    validate_nesting_depth(graph, &ordered, diagnostics);
+/// Where a step runs: inline on the calling thread, or on a worker.
+/// Steps that walk every condition, effect, or file's content go to a
+/// worker; the rest are cheap enough that a thread would cost more.
+#[derive(Clone, Copy, PartialEq)]
+enum On {
+    Caller,
+    Worker,
+}
 
-    // Step 9: Unreachable location (S3).
-    validate_location_reachability(symbol_table, diagnostics);
+fn run_steps(
+    graph: &DependencyGraph,
+    symbol_table: &SymbolTable,
+    diagnostics: &mut DiagnosticCollector,
+    parallel: bool,
+) {
+    let ordered: Vec<String> = graph.topological_order().into_iter().cloned().collect();
+    let ordered = ordered.as_slice();
+    let allow_custom_traits = world_allows_custom_traits(graph, ordered);
+    let directions = world_strings(graph, ordered, "directions");
+    let triggers = world_strings(graph, ordered, "triggers");
+    // Steps 10–12 share one pass over each file's sections.
+    let sections = SectionIndex::build(graph, ordered, symbol_table);
+    let sections = &sections;
+
+    let steps: Vec<(On, Step)> = vec![
+        // Step 1: Global configuration checks.
+        (On::Caller, Box::new(move |d| validate_global_config(graph, ordered, symbol_table, d))),
+        // Step 2: Type definition validation.
+        (On::Caller, Box::new(move |d| types::validate_types(symbol_table, allow_custom_traits, d))),
+        // Step 3: Entity property override validation.
+        (On::Caller, Box::new(move |d| entities::validate_entities(symbol_table, d))),
+        // Step 4: Condition validation.
+        (On::Worker, Box::new(move |d| conditions::validate_conditions(graph, ordered, symbol_table, d))),
+        // Step 5: Effect validation.
+        (On::Worker, Box::new(move |d| effects::validate_effects(graph, ordered, symbol_table, d))),
+        // Step 6: Action validation.
+        (On::Caller, Box::new(move |d| validate_actions(symbol_table, d))),
+        // Step 7: Sequence and phase validation.
+        (On::Caller, Box::new(move |d| validate_sequences(symbol_table, d))),
+        // Step 8: Nesting depth and condition count validation.
+        (
+            On::Worker,
+            Box::new(move |d| {
+                validate_nesting_depth(graph, ordered, d);
+                validate_condition_counts(graph, ordered, d);
+            }),
+        ),
+        // Step 9: Unreachable location (S3).
+        (On::Caller, Box::new(move |d| validate_location_reachability(symbol_table, d))),
+        // Step 10: Orphaned choice (S4).
+        (On::Worker, Box::new(move |d| validate_orphaned_choices(sections, symbol_table, d))),
+        // Step 11: Missing fallthrough (S6).
+        (On::Worker, Box::new(move |d| validate_section_fallthrough(sections, d))),
+        // Step 12: Section-exit shadowing (S8).
+        (On::Worker, Box::new(move |d| validate_section_exit_shadowing(sections, d))),
+        // Step 13: Section argument types.
+        (On::Caller, Box::new(move |d| validate_section_arguments(symbol_table, d))),
+        // Step 14: Dropped text.
+        (On::Worker, Box::new(move |d| validate_dropped_text(graph, ordered, d))),
+        // Step 15: Exit directions.
+        (On::Caller, Box::new(move |d| exits::validate_exits(symbol_table, &directions, d))),
+        // Step 16: Speaker display names.
+        (On::Worker, Box::new(move |d| speakers::validate_speakers(graph, ordered, symbol_table, d))),
+        // Step 17: Entity placement.
+        (On::Worker, Box::new(move |d| placement::validate_placement(graph, ordered, symbol_table, d))),
+        // Step 18: Rule triggers.
+        (On::Caller, Box::new(move |d| rules::validate_triggers(symbol_table, &triggers, d))),
+        // Step 19: Locations in a dialogue world.
+        (On::Caller, Box::new(move |d| validate_dialogue_mode(symbol_table, d))),
+    ];
+
+    if !parallel {
+        for (_, step) in steps {
+            step(diagnostics);
+        }
+        return;
+    }
 
-    // Step 10: Orphaned choice (S4).
-    validate_orphaned_choices(graph, &ordered, symbol_table, diagnostics);
+    // Start every worker step first, so they overlap the inline ones, then
+    // merge all results in step order. Completion order never shows.
+    std::thread::scope(|scope| {
+        let pending: Vec<Pending> = steps
+            .into_iter()
+            .map(|(on, step)| match on {
+                On::Caller => Pending::Inline(step),
+                On::Worker => Pending::Spawned(scope.spawn(move || run_step(step))),
+            })
+            .collect();
+        for step in pending {
+            let found = match step {
+                Pending::Inline(step) => run_step(step),
+                Pending::Spawned(handle) => handle.join().unwrap_or_else(|panic| std::panic::resume_unwind(panic)),
+            };
+            diagnostics.merge(found);
+        }
+    });
+}
 
-    // Step 11: Missing fallthrough (S6).
-    validate_section_fallthrough(graph, &ordered, symbol_table, diagnostics);
+/// A step that has either not run yet or is running on a worker.
+enum Pending<'scope, 'a> {
+    Inline(Step<'a>),
+    Spawned(std::thread::ScopedJoinHandle<'scope, Vec<Diagnostic>>),
+}
 
-    // Step 12: Section-exit shadowing (S8).
-    validate_section_exit_shadowing(graph, &ordered, symbol_table, diagnostics);
+fn run_step(step: Step) -> Vec<Diagnostic> {
+    let mut diagnostics = DiagnosticCollector::new();
+    step(&mut diagnostics);
+    diagnostics.into_vec()
 }
 
 // ── Step 1: Global Configuration ──
@@ -89,6 +222,7 @@ fn validate_global_config(
     let mut world_entry_raw: Option<String> = None;
     let mut world_span = None;
     let mut has_urd_field = false;
+    let mut typed_fields: Vec<(&str, &crate::ast::Scalar)> = Vec::new();
 
     for file_path in ordered_asts {
         let node = match graph.nodes.get(file_path.as_str()) {
@@ -101,19 +235,18 @@ fn validate_global_config(
                     world_span = Some(wb.span.clone());
                     for (key, scalar) in &wb.fields {
                         match key.as_str() {
-                            "start" => {
-                                if let crate::ast::Scalar::String(s) = scalar {
-                                    world_start_raw = Some(s.clone());
-                                }
-                            }
-                            "entry" => {
-                                if let crate::ast::Scalar::String(s) = scalar {
-                                    world_entry_raw = Some(s.clone());
-                                }
-                            }
+                            "start" | "entry" => match scalar {
+                                crate::ast::Scalar::String(s) if key == "start" => world_start_raw = Some(s.clone()),
+                                crate::ast::Scalar::String(s) => world_entry_raw = Some(s.clone()),
+                                _ => typed_fields.push((key.as_str(), scalar)),
+                            },
                             "urd" => {
                                 has_urd_field = true;
                             }
+                            "seed" | "version" | "start_inventory" | "allow_custom_traits" | "directions"
+                            | "unplaced_traits" | "triggers" | "flags" | "lints" | "mode" | "scope" => {
+                                typed_fields.push((key.as_str(), scalar));
+                            }
                             _ => {}
                         }
                     }
@@ -126,14 +259,24 @@ fn validate_global_config(
     if let Some(start_val) = &world_start_raw {
         if symbol_table.world_start.is_none() {
             if let Some(ref ws) = world_span {
-                diagnostics.error(
-                    "URD404",
-                    format!(
-                        "world.start references '{}' but no location with that ID exists.",
+                diagnostics.emit(Diagnostic {
+                    severity: Severity::Error,
+                    code: "URD404".to_string(),
+                    message: format!(
+                        "world.start references '{}'{} but no location with that ID exists.",
                         start_val,
+                        slug_note(start_val),
                     ),
-                    ws.clone(),
-                );
+                    span: ws.clone(),
+                    suggestion: symbol_table
+                        .location_with_derived_id(&slugify(start_val))
+                        .map(|id| format!("Did you mean '{}'?", id))
+                        .or_else(|| {
+                            world_id_suggestion(start_val, &symbol_table.locations, |loc| &loc.display_name)
+                        }),
+                    fix: None,
+                    related: Vec::new(),
+                });
             }
         }
     }
@@ -142,14 +285,19 @@ fn validate_global_config(
     if let Some(entry_val) = &world_entry_raw {
         if symbol_table.world_entry.is_none() {
             if let Some(ref ws) = world_span {
-                diagnostics.error(
-                    "URD405",
-                    format!(
-                        "world.entry references '{}' but no sequence with that ID exists.",
+                diagnostics.emit(Diagnostic {
+                    severity: Severity::Error,
+                    code: "URD405".to_string(),
+                    message: format!(
+                        "world.entry references '{}'{} but no sequence with that ID exists.",
                         entry_val,
+                        slug_note(entry_val),
                     ),
-                    ws.clone(),
-                );
+                    span: ws.clone(),
+                    suggestion: world_id_suggestion(entry_val, &symbol_table.sequences, |seq| &seq.id),
+                    fix: None,
+                    related: Vec::new(),
+                });
             }
         }
     }
@@ -165,7 +313,49 @@ fn validate_global_config(
         }
     }
 
-    // d. Player entity traits
+    // d. Field types: start, entry, seed, version, start_inventory,
+    //    allow_custom_traits, directions, unplaced_traits, triggers, flags,
+    //    lints, mode, scope
+    if let Some(ref ws) = world_span {
+        for (key, scalar) in &typed_fields {
+            let expected = match (*key, scalar) {
+                ("seed", crate::ast::Scalar::Integer(_)) => continue,
+                ("version", crate::ast::Scalar::String(_)) => continue,
+                ("allow_custom_traits", crate::ast::Scalar::Boolean(_)) => continue,
+                ("mode", crate::ast::Scalar::String(s)) if WorldMode::from_name(s).is_some() => continue,
+                ("scope", crate::ast::Scalar::String(s)) if ScopePolicy::from_name(s).is_some() => continue,
+                ("directions" | "unplaced_traits" | "triggers" | "flags" | "lints", crate::ast::Scalar::List(items))
+                    if items.iter().all(|i| matches!(i, crate::ast::Scalar::String(_))) =>
+                {
+                    continue
+                }
+                ("start_inventory", crate::ast::Scalar::List(items))
+                    if items.iter().all(|i| matches!(i, crate::ast::Scalar::EntityRef(_))) =>
+                {
+                    continue
+                }
+                ("seed", _) => "an integer",
+                ("start" | "entry" | "version", _) => "a string",
+                ("allow_custom_traits", _) => "a boolean",
+                ("mode", _) => "'world' or 'dialogue'",
+                ("scope", _) => "'direct' or 'transitive'",
+                ("directions" | "unplaced_traits" | "triggers" | "flags" | "lints", _) => "a list of strings",
+                _ => "a list of entity references",
+            };
+            diagnostics.error(
+                "URD437",
+                format!("world.{} must be {}.", key, expected),
+                ws.clone(),
+            );
+        }
+    }
+
+    // e. Start inventory: portable, and not also placed in a location
+    if let Some(ref ws) = world_span {
+        validate_start_inventory(graph, ordered_asts, symbol_table, ws, diagnostics);
+    }
+
+    // f. Player entity traits
     if let Some(player) = symbol_table.entities.get("player") {
         if let Some(type_name) = &player.type_symbol {
             if let Some(type_sym) = symbol_table.types.get(type_name) {
@@ -196,6 +386,143 @@ fn validate_global_config(
     }
 }
 
+/// Whether the world block sets `allow_custom_traits: true`, which turns
+/// unknown-trait warnings (URD441) into info.
+fn world_allows_custom_traits(graph: &DependencyGraph, ordered_asts: &[String]) -> bool {
+    ordered_asts
+        .iter()
+        .filter_map(|path| graph.nodes.get(path.as_str()))
+        .filter_map(|node| node.ast.frontmatter.as_ref())
+        .flat_map(|fm| fm.entries.iter())
+        .filter_map(|entry| match &entry.value {
+            FrontmatterValue::WorldBlock(wb) => Some(wb),
+            _ => None,
+        })
+        .flat_map(|wb| wb.fields.iter())
+        .any(|(key, value)| key == "allow_custom_traits" && matches!(value, crate::ast::Scalar::Boolean(true)))
+}
+
+/// `" (ID 'x')"` when a `world.start` or `world.entry` value is not
+/// already an ID, so the message shows the form that was looked up.
+fn slug_note(value: &str) -> String {
+    let slug = slugify(value);
+    if slug == value {
+        String::new()
+    } else {
+        format!(" (ID '{}')", slug)
+    }
+}
+
+/// A did-you-mean for an unresolved `world.start` or `world.entry`: the
+/// closest ID to the value's slug. When the value was written as a name,
+/// the suggestion also gives the name the ID comes from.
+fn world_id_suggestion<V>(
+    value: &str,
+    namespace: &indexmap::IndexMap<String, V>,
+    display_name: impl Fn(&V) -> &str,
+) -> Option<String> {
+    let slug = slugify(value);
+    let id = crate::link::find_suggestion(&slug, namespace)?;
+    let name = display_name(&namespace[&id]);
+    if slug != value && name != id {
+        Some(format!("Did you mean '{}' (from '{}')?", id, name))
+    } else {
+        Some(format!("Did you mean '{}'?", id))
+    }
+}
+
+/// The strings of a world block list field, from any file: `directions:`
+/// (URD447) and `triggers:` (URD454) add to a default vocabulary.
+fn world_strings(graph: &DependencyGraph, ordered_asts: &[String], field: &str) -> Vec<String> {
+    ordered_asts
+        .iter()
+        .filter_map(|path| graph.nodes.get(path.as_str()))
+        .filter_map(|node| node.ast.frontmatter.as_ref())
+        .flat_map(|fm| fm.entries.iter())
+        .filter_map(|entry| match &entry.value {
+            FrontmatterValue::WorldBlock(wb) => Some(wb),
+            _ => None,
+        })
+        .flat_map(|wb| wb.fields.iter())
+        .filter_map(|(key, value)| match value {
+            crate::ast::Scalar::List(items) if key == field => Some(items),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|item| match item {
+            crate::ast::Scalar::String(s) => Some(s.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Check each resolved `world.start_inventory` entity: its type should be
+/// portable (URD438), and it must not also be listed in a location (URD439).
+fn validate_start_inventory(
+    graph: &DependencyGraph,
+    ordered_asts: &[String],
+    symbol_table: &SymbolTable,
+    world_span: &crate::span::Span,
+    diagnostics: &mut DiagnosticCollector,
+) {
+    for entity_id in &symbol_table.world_start_inventory {
+        let Some(entity) = symbol_table.entities.get(entity_id) else {
+            continue;
+        };
+        if let Some(type_name) = &entity.type_symbol {
+            if !helpers::has_trait(type_name, "portable", symbol_table) {
+                diagnostics.warning(
+                    "URD438",
+                    format!(
+                        "Start inventory entity '@{}' has type '{}' which does not have the 'portable' trait.",
+                        entity_id, type_name,
+                    ),
+                    world_span.clone(),
+                );
+            }
+        }
+
+        for (loc_id, loc) in &symbol_table.locations {
+            if !loc.contains.contains(entity_id) {
+                continue;
+            }
+            let placed_at = presence_span(graph, ordered_asts, entity_id).unwrap_or_else(|| loc.declared_in.clone());
+            diagnostics.emit(Diagnostic {
+                severity: Severity::Error,
+                code: "URD439".to_string(),
+                message: format!(
+                    "Entity '@{}' is in world.start_inventory but is also placed in location '{}'. An entity starts in one place.",
+                    entity_id, loc_id,
+                ),
+                span: world_span.clone(),
+                suggestion: None,
+                fix: None,
+                related: vec![crate::diagnostics::RelatedInfo {
+                    message: format!("'@{}' placed here.", entity_id),
+                    span: placed_at,
+                }],
+            });
+        }
+    }
+}
+
+/// The span of the first `[...]` presence line that resolves to `entity_id`.
+fn presence_span(graph: &DependencyGraph, ordered_asts: &[String], entity_id: &str) -> Option<crate::span::Span> {
+    ordered_asts
+        .iter()
+        .filter_map(|path| graph.nodes.get(path.as_str()))
+        .flat_map(|node| node.ast.content.iter())
+        .find_map(|content| match content {
+            ContentNode::EntityPresence(ep) => ep
+                .annotations
+                .iter()
+                .flatten()
+                .any(|a| a.resolved_entity.as_deref() == Some(entity_id))
+                .then(|| ep.span.clone()),
+            _ => None,
+        })
+}
+
 // ── Step 6: Action Validation ──
 
 fn validate_actions(
@@ -205,14 +532,49 @@ fn validate_actions(
     for (action_id, action_sym) in &symbol_table.actions {
         // Mutual exclusion: both target + target_type.
         if action_sym.target.is_some() && action_sym.target_type.is_some() {
-            diagnostics.error(
-                "URD406",
-                format!(
+            let target = action_sym
+                .resolved_target
+                .as_ref()
+                .and_then(|id| symbol_table.entities.get(id))
+                .map(|es| RelatedInfo {
+                    message: format!("'@{}' declared here.", es.id),
+                    span: es.declared_in.clone(),
+                });
+            let target_type = action_sym
+                .resolved_target_type
+                .as_ref()
+                .and_then(|name| symbol_table.types.get(name))
+                .map(|ts| RelatedInfo {
+                    message: format!("Type '{}' declared here.", ts.name),
+                    span: ts.declared_in.clone(),
+                });
+            diagnostics.emit(Diagnostic {
+                severity: Severity::Error,
+                code: "URD406".to_string(),
+                message: format!(
                     "Action '{}' declares both 'target' and 'target_type'. Declare one or neither.",
                     action_id,
                 ),
-                action_sym.declared_in.clone(),
-            );
+                span: action_sym.declared_in.clone(),
+                suggestion: None,
+                fix: None,
+                related: target.into_iter().chain(target_type).collect(),
+            });
+            continue;
+        }
+
+        // A target type no entity has: the action can never bind.
+        if let Some(type_name) = &action_sym.resolved_target_type {
+            if !symbol_table.entities.values().any(|es| es.type_symbol.as_ref() == Some(type_name)) {
+                diagnostics.warning(
+                    "URD460",
+                    format!(
+                        "Action '{}' targets any '{}', but no entity has that type, so it can never be taken.",
+                        action_id, type_name,
+                    ),
+                    action_sym.declared_in.clone(),
+                );
+            }
         }
     }
 }
@@ -291,7 +653,18 @@ fn validate_sequences(
                 );
             }
 
-            // d. Auto phase with player actions.
+            // d. Phase section reference. LINK records only resolved IDs and
+            // reports URD309 otherwise, so an unresolved reference is skipped.
+            if let Some(section_id) = &phase.section {
+                debug_assert!(
+                    symbol_table.sections.contains_key(section_id),
+                    "phase '{}' resolved to unknown section '{}'",
+                    phase.id,
+                    section_id,
+                );
+            }
+
+            // e. Auto phase with player actions.
             // Check if advance is "auto" and phase has actions.
             if phase.advance == "auto" {
                 let has_actions = phase.action.is_some()
@@ -311,7 +684,7 @@ fn validate_sequences(
     }
 }
 
-// ── Step 8: Nesting Depth Validation ──
+// ── Step 8: Nesting Depth and Condition Count Validation ──
 
 fn validate_nesting_depth(
     graph: &DependencyGraph,
@@ -364,6 +737,84 @@ fn check_nesting(
     }
 }
 
+/// Conditions that apply together: each `? any:` block, each run of
+/// adjacent `?` lines, and each rule's `where` clauses (URD455).
+fn validate_condition_counts(
+    graph: &DependencyGraph,
+    ordered_asts: &[String],
+    diagnostics: &mut DiagnosticCollector,
+) {
+    for file_path in ordered_asts {
+        if let Some(node) = graph.nodes.get(file_path.as_str()) {
+            check_condition_counts(&node.ast.content, diagnostics);
+        }
+    }
+}
+
+fn check_condition_counts(nodes: &[ContentNode], diagnostics: &mut DiagnosticCollector) {
+    // The current run of `?` lines: its first span, last span, and length.
+    let mut run: Option<(&Span, &Span, usize)> = None;
+    for node in nodes {
+        match node {
+            ContentNode::Condition(cond) if !matches!(cond.expr, ConditionExpr::Once(_)) => {
+                run = Some(match run {
+                    Some((first, _, count)) => (first, &cond.span, count + 1),
+                    None => (&cond.span, &cond.span, 1),
+                });
+                continue;
+            }
+            // `once` and comments sit inside a run without ending it.
+            ContentNode::Condition(_) | ContentNode::Comment(_) => continue,
+            ContentNode::OrConditionBlock(or) => {
+                check_condition_count("`? any:` block", or.conditions.len(), &or.span, diagnostics);
+            }
+            ContentNode::RuleBlock(rule) => {
+                let what = format!("Where clause of rule '{}'", rule.name);
+                check_condition_count(&what, rule.where_clauses.len(), &rule.span, diagnostics);
+            }
+            ContentNode::Choice(choice) => check_condition_counts(&choice.content, diagnostics),
+            ContentNode::ExitDeclaration(exit) => check_condition_counts(&exit.children, diagnostics),
+            ContentNode::LocationHook(hook) => check_condition_counts(&hook.children, diagnostics),
+            _ => {}
+        }
+        if let Some((first, last, count)) = run.take() {
+            check_run_count(first, last, count, diagnostics);
+        }
+    }
+    if let Some((first, last, count)) = run {
+        check_run_count(first, last, count, diagnostics);
+    }
+}
+
+fn check_run_count(first: &Span, last: &Span, count: usize, diagnostics: &mut DiagnosticCollector) {
+    let span = Span::new(first.file.clone(), first.start_line, first.start_col, last.end_line, last.end_col);
+    check_condition_count("Condition list", count, &span, diagnostics);
+}
+
+fn check_condition_count(what: &str, count: usize, span: &Span, diagnostics: &mut DiagnosticCollector) {
+    let severity = if count >= MAX_CONDITION_COUNT {
+        Severity::Error
+    } else if count >= WARN_CONDITION_COUNT {
+        Severity::Warning
+    } else {
+        return;
+    };
+    diagnostics.emit(Diagnostic {
+        severity,
+        code: "URD455".to_string(),
+        message: format!(
+            "{} at line {} has {} conditions (error at {}).",
+            what, span.start_line, count, MAX_CONDITION_COUNT,
+        ),
+        span: span.clone(),
+        suggestion: Some(
+            "Track the outcome in a property that an effect sets, and test that property instead.".to_string(),
+        ),
+        fix: None,
+        related: Vec::new(),
+    });
+}
+
 // ── Step 9: Unreachable Location (S3) ──
 
 fn validate_location_reachability(
@@ -371,6 +822,10 @@ fn validate_location_reachability(
     diagnostics: &mut DiagnosticCollector,
 ) {
     // Skip if no world.start defined — unreachability is meaningless without a root.
+    // A dialogue world reports its locations as URD461 instead.
+    if symbol_table.world_mode == WorldMode::Dialogue {
+        return;
+    }
     let start_id = match &symbol_table.world_start {
         Some(id) => id,
         None => return,
@@ -416,35 +871,15 @@ fn validate_location_reachability(
 
 // ── Step 10: Orphaned Choice (S4) ──
 
-fn validate_orphaned_choices(
-    graph: &DependencyGraph,
-    ordered_asts: &[String],
-    symbol_table: &SymbolTable,
-    diagnostics: &mut DiagnosticCollector,
-) {
-    for file_path in ordered_asts {
-        let node = match graph.nodes.get(file_path.as_str()) {
-            Some(n) => n,
-            None => continue,
-        };
-
-        let mut current_section: Option<String> = None;
-
-        for content in &node.ast.content {
-            match content {
-                ContentNode::SectionLabel(sl) => {
-                    current_section = Some(sl.name.clone());
-                }
-                ContentNode::Choice(choice) => {
-                    check_choice_orphaned(
-                        choice,
-                        current_section.as_deref().unwrap_or("unnamed"),
-                        file_path,
-                        symbol_table,
-                        diagnostics,
-                    );
+fn validate_orphaned_choices(index: &SectionIndex, symbol_table: &SymbolTable, diagnostics: &mut DiagnosticCollector) {
+    for file in &index.files {
+        let spans = std::iter::once(("unnamed", &file.content[file.preamble.clone()]))
+            .chain(file.sections.iter().map(|s| (s.name, s.content(file))));
+        for (section_name, content) in spans {
+            for node in content {
+                if let ContentNode::Choice(choice) = node {
+                    check_choice_orphaned(choice, section_name, file.path, symbol_table, diagnostics);
                 }
-                _ => {}
             }
         }
     }
@@ -499,8 +934,9 @@ fn check_enum_condition(
     let resolved_type = ann.resolved_type.as_ref().unwrap();
     let resolved_property = ann.resolved_property.as_ref().unwrap();
 
-    // Only check == operator on enum properties.
-    if pc.operator != "==" {
+    // Only check == operator on enum properties. A bare read lowers to
+    // `== true`; URD453 already reports it.
+    if pc.bare || pc.lowered().0 != "==" {
         return;
     }
 
@@ -538,46 +974,10 @@ fn check_enum_condition(
 
 // ── Step 11: Missing Fallthrough (S6) ──
 
-fn validate_section_fallthrough(
-    graph: &DependencyGraph,
-    ordered_asts: &[String],
-    symbol_table: &SymbolTable,
-    diagnostics: &mut DiagnosticCollector,
-) {
-    for file_path in ordered_asts {
-        let node = match graph.nodes.get(file_path.as_str()) {
-            Some(n) => n,
-            None => continue,
-        };
-
-        let file_stem = crate::graph::file_stem(file_path);
-        let content = &node.ast.content;
-
-        // Collect section start positions: (name, span, index).
-        let section_starts: Vec<(String, crate::span::Span, usize)> = content
-            .iter()
-            .enumerate()
-            .filter_map(|(i, c)| {
-                if let ContentNode::SectionLabel(sl) = c {
-                    Some((sl.name.clone(), sl.span.clone(), i))
-                } else {
-                    None
-                }
-            })
-            .collect();
-
-        for (idx, (section_name, section_span, start)) in section_starts.iter().enumerate() {
-            let end = if idx + 1 < section_starts.len() {
-                section_starts[idx + 1].2
-            } else {
-                content.len()
-            };
-
-            let section_content = &content[*start..end];
-
-            // Look up section in symbol table.
-            let compiled_id = format!("{}/{}", file_stem, section_name);
-            let section_sym = match symbol_table.sections.get(&compiled_id) {
+fn validate_section_fallthrough(index: &SectionIndex, diagnostics: &mut DiagnosticCollector) {
+    for file in &index.files {
+        for section in &file.sections {
+            let section_sym = match section.symbol {
                 Some(s) => s,
                 None => continue,
             };
@@ -587,12 +987,14 @@ fn validate_section_fallthrough(
                 continue;
             }
 
-            // Condition 1: Has at least one sticky choice — safe.
-            if section_sym.choices.iter().any(|c| c.sticky) {
+            // Condition 1: Has at least one sticky choice — safe. A `once`
+            // choice is one-shot whatever its sigil.
+            if section_sym.choices.iter().any(|c| c.sticky && !c.once) {
                 continue;
             }
 
             // Find last Choice node in section content.
+            let section_content = section.content(file);
             let last_choice_idx = match section_content.iter().rposition(|c| matches!(c, ContentNode::Choice(_))) {
                 Some(idx) => idx,
                 None => continue,
@@ -614,9 +1016,9 @@ fn validate_section_fallthrough(
                     "URD433",
                     format!(
                         "Section '{}' in file '{}' has only one-shot choices and no terminal jump or fallthrough text. It will exhaust to an empty state.",
-                        section_name, file_path,
+                        section.name, file.path,
                     ),
-                    section_span.clone(),
+                    section.label.span.clone(),
                 );
             }
         }
@@ -625,10 +1027,70 @@ fn validate_section_fallthrough(
 
 // ── Step 12: Section-Exit Shadowing (S8) ──
 
-fn validate_section_exit_shadowing(
+fn validate_section_exit_shadowing(index: &SectionIndex, diagnostics: &mut DiagnosticCollector) {
+    for section in index.files.iter().flat_map(|f| &f.sections) {
+        let (loc_id, loc_sym) = match section.location {
+            Some(location) => location,
+            None => continue,
+        };
+        let name = section.name;
+        if loc_sym.exits.contains_key(name) {
+            diagnostics.warning(
+                "URD434",
+                format!(
+                    "Section '{}' in location '{}' shares a name with exit '{}'. Jumps to '{}' will target the section, not the exit. Use -> exit:{} to target the exit explicitly.",
+                    name, loc_id, name, name, name,
+                ),
+                section.label.span.clone(),
+            );
+        }
+    }
+}
+
+// ── Step 13: Section Argument Types ──
+
+fn validate_section_arguments(
+    symbol_table: &SymbolTable,
+    diagnostics: &mut DiagnosticCollector,
+) {
+    for (section_id, section_sym) in &symbol_table.sections {
+        let param = match &section_sym.param {
+            Some(p) => p,
+            None => continue,
+        };
+        // Skip if the parameter type is unresolved — LINK reported it.
+        if !symbol_table.types.contains_key(&param.type_name) {
+            continue;
+        }
+
+        for invocation in &section_sym.invocations {
+            let entity_type = match symbol_table
+                .entities
+                .get(&invocation.argument)
+                .and_then(|es| es.type_symbol.as_ref())
+            {
+                Some(t) => t,
+                None => continue,
+            };
+            if entity_type != &param.type_name {
+                diagnostics.error(
+                    "URD442",
+                    format!(
+                        "Section '{}' expects '@{}: {}', but '@{}' has type '{}'.",
+                        section_id, param.name, param.type_name, invocation.argument, entity_type,
+                    ),
+                    invocation.declared_in.clone(),
+                );
+            }
+        }
+    }
+}
+
+// ── Step 14: Dropped Text ──
+
+fn validate_dropped_text(
     graph: &DependencyGraph,
     ordered_asts: &[String],
-    symbol_table: &SymbolTable,
     diagnostics: &mut DiagnosticCollector,
 ) {
     for file_path in ordered_asts {
@@ -637,36 +1099,69 @@ fn validate_section_exit_shadowing(
             None => continue,
         };
 
-        let mut current_location_id: Option<String> = None;
-
-        for content in &node.ast.content {
-            match content {
-                ContentNode::LocationHeading(lh) => {
-                    let slug = slugify(&lh.display_name);
-                    if symbol_table.locations.contains_key(&slug) {
-                        current_location_id = Some(slug);
-                    } else {
-                        current_location_id = None;
-                    }
+        for dropped in layout::dropped_text(&node.ast.content) {
+            let hint = match dropped.place {
+                DroppedPlace::Outside => {
+                    "Place it under a location heading or a section label.".to_string()
                 }
-                ContentNode::SectionLabel(sl) => {
-                    if let Some(ref loc_id) = current_location_id {
-                        if let Some(loc_sym) = symbol_table.locations.get(loc_id) {
-                            if loc_sym.exits.contains_key(&sl.name) {
-                                diagnostics.warning(
-                                    "URD434",
-                                    format!(
-                                        "Section '{}' in location '{}' shares a name with exit '{}'. Jumps to '{}' will target the section, not the exit. Use -> exit:{} to target the exit explicitly.",
-                                        sl.name, loc_id, sl.name, sl.name, sl.name,
-                                    ),
-                                    sl.span.clone(),
-                                );
-                            }
-                        }
-                    }
-                }
-                _ => {}
-            }
+                DroppedPlace::AfterDescription { location } => format!(
+                    "A location description ends at the first exit, hook, entity list or section. Move it directly below '# {}'.",
+                    location,
+                ),
+                DroppedPlace::LocationSpeech { location } => format!(
+                    "Speech and stage directions in '{}' must belong to a dialogue section. Add a section label above it.",
+                    location,
+                ),
+                DroppedPlace::BetweenChoices { section } => format!(
+                    "Only choices are kept between the first and last choice of section '{}'. Move it before the first choice, into a choice, or after the last choice.",
+                    section,
+                ),
+                DroppedPlace::LateExhaustedSpeech { section } => format!(
+                    "Only a speech line that opens the text after the last choice of section '{}' is kept. Move it first or write it as a stage direction.",
+                    section,
+                ),
+                DroppedPlace::ChoiceProse { label } => format!(
+                    "The response to choice '{}' is built from speech and stage directions. Write it as '@entity: text'.",
+                    label,
+                ),
+            };
+            diagnostics.warning(
+                "URD443",
+                format!("This text will not appear in the compiled world. {}", hint),
+                dropped.span.clone(),
+            );
         }
     }
 }
+
+// ── Step 19: Locations in a Dialogue World ──
+
+/// URD461: a location heading in a world that declared `mode: dialogue`.
+/// Exits only exist under a heading, so this covers them too.
+fn validate_dialogue_mode(symbol_table: &SymbolTable, diagnostics: &mut DiagnosticCollector) {
+    if symbol_table.world_mode != WorldMode::Dialogue {
+        return;
+    }
+    for (loc_id, loc_sym) in &symbol_table.locations {
+        let exits = match loc_sym.exits.len() {
+            0 => String::new(),
+            1 => ", with 1 exit".to_string(),
+            n => format!(", with {} exits", n),
+        };
+        diagnostics.emit(Diagnostic {
+            severity: Severity::Warning,
+            code: "URD461".to_string(),
+            message: format!(
+                "Location '{}' is declared in a dialogue world{}. A world with `mode: dialogue` has no locations.",
+                loc_id, exits,
+            ),
+            span: loc_sym.declared_in.clone(),
+            suggestion: Some(
+                "Remove the heading and keep its sections, or drop `mode: dialogue` if the world has a map."
+                    .to_string(),
+            ),
+            fix: None,
+            related: Vec::new(),
+        });
+    }
+}
@@ -0,0 +1,135 @@
+/// Entity placement.
+///
+/// LINK builds each location's `contains` list from its `[...]` presence
+/// lines. An entity listed in two locations starts in both, and runtimes
+/// disagree about which wins (URD450, error). Every listing is reported as
+/// related information. An entity listed twice in the same location is
+/// kept once (URD451, info).
+///
+/// `@player` is exempt: its starting position is `world.start`, so a
+/// listing is only noise (URD452, info per listing). A location and
+/// `world.start_inventory` together are URD439.
+
+use indexmap::IndexMap;
+
+use crate::ast::ContentNode;
+use crate::diagnostics::{Diagnostic, DiagnosticCollector, RelatedInfo, Severity};
+use crate::graph::DependencyGraph;
+use crate::span::Span;
+use crate::symbol_table::SymbolTable;
+
+pub fn validate_placement(
+    graph: &DependencyGraph,
+    ordered_asts: &[String],
+    symbol_table: &SymbolTable,
+    diagnostics: &mut DiagnosticCollector,
+) {
+    // Entity ID → every listing, as (location ID, presence line span).
+    let mut listings: IndexMap<String, Vec<(String, Span)>> = IndexMap::new();
+
+    for file_path in ordered_asts {
+        let node = match graph.nodes.get(file_path.as_str()) {
+            Some(n) => n,
+            None => continue,
+        };
+
+        let mut current_location_id: Option<String> = None;
+        for content in &node.ast.content {
+            match content {
+                ContentNode::LocationHeading(lh) => {
+                    let id = lh.id();
+                    current_location_id = symbol_table.locations.contains_key(&id).then_some(id);
+                }
+                ContentNode::EntityPresence(ep) => {
+                    let Some(loc_id) = &current_location_id else {
+                        continue;
+                    };
+                    // Skip unresolved references — LINK already reported them.
+                    for entity_id in ep.annotations.iter().flatten().filter_map(|a| a.resolved_entity.as_ref()) {
+                        if entity_id == "player" {
+                            diagnostics.emit(Diagnostic {
+                                severity: Severity::Info,
+                                code: "URD452".to_string(),
+                                message: format!(
+                                    "'@player' is listed in location '{}'. The player starts at world.start, not in an entity list.",
+                                    loc_id,
+                                ),
+                                span: ep.span.clone(),
+                                suggestion: Some("Remove '@player' from the entity list.".to_string()),
+                                fix: None,
+                                related: Vec::new(),
+                            });
+                            continue;
+                        }
+                        listings
+                            .entry(entity_id.clone())
+                            .or_default()
+                            .push((loc_id.clone(), ep.span.clone()));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    for (entity_id, placed) in &listings {
+        let mut locations: Vec<&str> = Vec::new();
+        for (i, (loc_id, span)) in placed.iter().enumerate() {
+            if !locations.contains(&loc_id.as_str()) {
+                locations.push(loc_id);
+                continue;
+            }
+            let first = placed[..i].iter().find(|(id, _)| id == loc_id).map(|(_, s)| s.clone());
+            diagnostics.emit(Diagnostic {
+                severity: Severity::Info,
+                code: "URD451".to_string(),
+                message: format!(
+                    "Entity '@{}' is listed in location '{}' more than once. It is placed there once.",
+                    entity_id, loc_id,
+                ),
+                span: span.clone(),
+                suggestion: Some(format!("Remove the repeated '@{}'.", entity_id)),
+                fix: None,
+                related: first
+                    .into_iter()
+                    .map(|span| RelatedInfo {
+                        message: format!("'@{}' first listed here.", entity_id),
+                        span,
+                    })
+                    .collect(),
+            });
+        }
+
+        if locations.len() < 2 {
+            continue;
+        }
+        // Report at the first listing in a second location, with every
+        // other listing as related information.
+        let primary = placed.iter().position(|(id, _)| id != &placed[0].0).unwrap_or(0);
+        let quoted: Vec<String> = locations.iter().map(|id| format!("'{}'", id)).collect();
+        diagnostics.emit(Diagnostic {
+            severity: Severity::Error,
+            code: "URD450".to_string(),
+            message: format!(
+                "Entity '@{}' is placed in more than one location: {}. An entity starts in one place.",
+                entity_id,
+                quoted.join(", "),
+            ),
+            span: placed[primary].1.clone(),
+            suggestion: Some(format!(
+                "Keep '@{}' in one location's entity list and move it with '> move' if it should travel.",
+                entity_id,
+            )),
+            fix: None,
+            related: placed
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != primary)
+                .map(|(_, (loc_id, span))| RelatedInfo {
+                    message: format!("'@{}' placed in '{}' here.", entity_id, loc_id),
+                    span: span.clone(),
+                })
+                .collect(),
+        });
+    }
+}
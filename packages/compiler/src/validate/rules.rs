@@ -0,0 +1,87 @@
+/// Rule trigger validation.
+///
+/// Checks each rule's trigger against the trigger vocabulary (URD454). A
+/// trigger is a kind and, for every kind but `always`, one argument:
+/// `phase_is <phase>`, `action <action>`, `enter <location>`, or
+/// `state_change <entity.property>`. A runtime that fires triggers of its
+/// own lists their kinds in the world block's `triggers:` field, which adds
+/// to the default. A custom kind takes any argument, or none.
+
+use crate::diagnostics::{Diagnostic, DiagnosticCollector, Severity};
+use crate::link::edit_distance;
+use crate::symbol_table::SymbolTable;
+
+/// Default trigger kinds, each with what its argument names. `always`
+/// takes none.
+const DEFAULT_TRIGGERS: &[(&str, Option<&str>)] = &[
+    ("phase_is", Some("phase")),
+    ("action", Some("action")),
+    ("enter", Some("location")),
+    ("state_change", Some("entity.property")),
+    ("always", None),
+];
+
+pub fn validate_triggers(
+    symbol_table: &SymbolTable,
+    custom_triggers: &[String],
+    diagnostics: &mut DiagnosticCollector,
+) {
+    let vocabulary: Vec<&str> = DEFAULT_TRIGGERS
+        .iter()
+        .map(|(kind, _)| *kind)
+        .chain(custom_triggers.iter().map(String::as_str))
+        .collect();
+
+    for rule in symbol_table.rules.values() {
+        let (kind, argument) = match rule.trigger.split_once(' ') {
+            Some((kind, argument)) => (kind, argument.trim()),
+            None => (rule.trigger.as_str(), ""),
+        };
+        if custom_triggers.iter().any(|t| t == kind) {
+            continue;
+        }
+
+        let (message, suggestion) = match DEFAULT_TRIGGERS.iter().find(|(k, _)| *k == kind) {
+            Some((_, Some(names))) if argument.is_empty() => (
+                format!("Rule '{}' has trigger '{}' but does not name the {}.", rule.id, kind, names),
+                format!("Write '{} <{}>'.", kind, names),
+            ),
+            Some((_, None)) if !argument.is_empty() => (
+                format!("Rule '{}' has trigger '{} {}', but '{}' takes no argument.", rule.id, kind, argument, kind),
+                format!("Write '{}' on its own.", kind),
+            ),
+            Some(_) => continue,
+            None if kind.is_empty() => (
+                format!("Rule '{}' has no trigger.", rule.id),
+                "Add one after the actor, such as 'actor: @entity action <action>'.".to_string(),
+            ),
+            None => {
+                let closest = vocabulary
+                    .iter()
+                    .map(|k| (*k, edit_distance(kind, k)))
+                    .filter(|(_, dist)| (1..=2).contains(dist))
+                    .min_by_key(|(_, dist)| *dist)
+                    .map(|(k, _)| format!("Did you mean '{}'?", k));
+                (
+                    format!("Rule '{}' has unknown trigger '{}'.", rule.id, kind),
+                    closest.unwrap_or_else(|| {
+                        format!(
+                            "Known triggers: {}. Add '{}' to the world block's 'triggers' list if the runtime fires it.",
+                            vocabulary.join(", "),
+                            kind,
+                        )
+                    }),
+                )
+            }
+        };
+        diagnostics.emit(Diagnostic {
+            severity: Severity::Error,
+            code: "URD454".to_string(),
+            message,
+            span: rule.declared_in.clone(),
+            suggestion: Some(suggestion),
+            fix: None,
+            related: Vec::new(),
+        });
+    }
+}
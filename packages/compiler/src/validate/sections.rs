@@ -0,0 +1,106 @@
+/// The per-file section index shared by the section-scan checks.
+///
+/// Steps 10–12 (orphaned choices, missing fallthrough, section-exit
+/// shadowing) all need each file's sections in order, with the content
+/// each one spans. [`SectionIndex::build()`] walks every file once per
+/// VALIDATE run, derives each file stem and compiled section ID once, and
+/// looks up the section and enclosing location symbols up front, so the
+/// checks themselves do no string building or rescanning.
+///
+/// A section spans its label up to the next label in the same file, or
+/// the end of the file. Location headings do not end a section. Content
+/// before a file's first label is its preamble.
+
+use std::ops::Range;
+
+use crate::ast::{ContentNode, SectionLabel};
+use crate::graph::{file_stem, DependencyGraph};
+use crate::symbol_table::{LocationSymbol, SectionSymbol, SymbolTable};
+
+/// Every file's sections, in topological file order.
+pub(super) struct SectionIndex<'a> {
+    pub files: Vec<FileSections<'a>>,
+}
+
+/// The sections of one file, in declaration order.
+pub(super) struct FileSections<'a> {
+    pub path: &'a str,
+    pub content: &'a [ContentNode],
+    /// Content before the first section label.
+    pub preamble: Range<usize>,
+    pub sections: Vec<IndexedSection<'a>>,
+}
+
+/// One section label and the content it spans.
+pub(super) struct IndexedSection<'a> {
+    /// The local name, `explicit_id` when the label has one.
+    pub name: &'a str,
+    pub label: &'a SectionLabel,
+    /// Indices into the file's content, starting at the label.
+    pub range: Range<usize>,
+    /// The section's symbol, looked up by its compiled ID.
+    pub symbol: Option<&'a SectionSymbol>,
+    /// The location heading the label sits under, with its symbol. `None`
+    /// before any heading, after a region heading, or when the heading's
+    /// ID is not a known location.
+    pub location: Option<(&'a str, &'a LocationSymbol)>,
+}
+
+impl<'a> SectionIndex<'a> {
+    pub fn build(graph: &'a DependencyGraph, ordered_asts: &'a [String], symbol_table: &'a SymbolTable) -> Self {
+        let mut files = Vec::with_capacity(ordered_asts.len());
+        for file_path in ordered_asts {
+            let node = match graph.nodes.get(file_path.as_str()) {
+                Some(n) => n,
+                None => continue,
+            };
+            let content = node.ast.content.as_slice();
+            let stem = file_stem(file_path);
+
+            let mut sections: Vec<IndexedSection<'a>> = Vec::new();
+            let mut location = None;
+            for (i, c) in content.iter().enumerate() {
+                match c {
+                    ContentNode::LocationHeading(lh) => {
+                        location = symbol_table
+                            .locations
+                            .get_key_value(&lh.id())
+                            .map(|(id, sym)| (id.as_str(), sym));
+                    }
+                    ContentNode::RegionHeading(_) => location = None,
+                    ContentNode::SectionLabel(sl) => {
+                        if let Some(previous) = sections.last_mut() {
+                            previous.range.end = i;
+                        }
+                        let name = sl.local_id();
+                        let compiled_id = format!("{}/{}", stem, name);
+                        sections.push(IndexedSection {
+                            name,
+                            symbol: symbol_table.sections.get(&compiled_id),
+                            label: sl,
+                            range: i..content.len(),
+                            location,
+                        });
+                    }
+                    _ => {}
+                }
+            }
+
+            let preamble = 0..sections.first().map_or(content.len(), |s| s.range.start);
+            files.push(FileSections {
+                path: file_path,
+                content,
+                preamble,
+                sections,
+            });
+        }
+        SectionIndex { files }
+    }
+}
+
+impl<'a> IndexedSection<'a> {
+    /// The section's content, from its label to the next.
+    pub fn content(&self, file: &FileSections<'a>) -> &'a [ContentNode] {
+        &file.content[self.range.clone()]
+    }
+}
@@ -0,0 +1,98 @@
+/// Speaker display names.
+///
+/// A runtime shows a speaker under its display name: the entity's `name`
+/// override, else the default of its type's `name` property. An entity
+/// that speaks with neither is shown by its ID, which is rarely what the
+/// writer meant (URD449, info). Each speaker is reported once, at its
+/// first line.
+///
+/// A parameterised section's speaker is its parameter, not an entity, so
+/// template speech is skipped; the entities bound to it are reported if
+/// they speak elsewhere.
+
+use std::collections::HashSet;
+
+use crate::ast::ContentNode;
+use crate::diagnostics::{Diagnostic, DiagnosticCollector, Severity};
+use crate::graph::DependencyGraph;
+use crate::symbol_table::SymbolTable;
+
+pub fn validate_speakers(
+    graph: &DependencyGraph,
+    ordered_asts: &[String],
+    symbol_table: &SymbolTable,
+    diagnostics: &mut DiagnosticCollector,
+) {
+    let mut reported: HashSet<String> = HashSet::new();
+
+    for file_path in ordered_asts {
+        let node = match graph.nodes.get(file_path.as_str()) {
+            Some(n) => n,
+            None => continue,
+        };
+
+        let mut in_template = false;
+        for content in &node.ast.content {
+            match content {
+                ContentNode::SectionLabel(sl) => in_template = sl.param.is_some(),
+                ContentNode::LocationHeading(_)
+                | ContentNode::RegionHeading(_)
+                | ContentNode::SequenceHeading(_) => in_template = false,
+                _ => {}
+            }
+            if !in_template {
+                check_speech(content, symbol_table, &mut reported, diagnostics);
+            }
+        }
+    }
+}
+
+fn check_speech(
+    node: &ContentNode,
+    symbol_table: &SymbolTable,
+    reported: &mut HashSet<String>,
+    diagnostics: &mut DiagnosticCollector,
+) {
+    match node {
+        ContentNode::EntitySpeech(speech) => {
+            // Skip if unresolved — LINK already reported it.
+            let entity_id = match speech.annotation.as_ref().and_then(|a| a.resolved_entity.as_ref()) {
+                Some(id) => id,
+                None => return,
+            };
+            let entity = match symbol_table.entities.get(entity_id) {
+                Some(e) => e,
+                None => return,
+            };
+            if symbol_table.display_name(entity_id).is_some() || !reported.insert(entity_id.clone()) {
+                return;
+            }
+            diagnostics.emit(Diagnostic {
+                severity: Severity::Info,
+                code: "URD449".to_string(),
+                message: format!(
+                    "Entity '@{}' speaks but has no display name. Runtimes will show its ID.",
+                    entity_id,
+                ),
+                span: speech.span.clone(),
+                suggestion: Some(format!(
+                    "Add a 'name: string' property to type '{}', or set 'name' on '@{}'.",
+                    entity.type_name, entity_id,
+                )),
+                fix: None,
+                related: Vec::new(),
+            });
+        }
+        ContentNode::Choice(choice) => {
+            for child in &choice.content {
+                check_speech(child, symbol_table, reported, diagnostics);
+            }
+        }
+        ContentNode::LocationHook(hook) => {
+            for child in &hook.children {
+                check_speech(child, symbol_table, reported, diagnostics);
+            }
+        }
+        _ => {}
+    }
+}
@@ -3,24 +3,35 @@
 /// For each TypeSymbol in insertion order:
 /// - Validate property defaults against declared type (URD413)
 /// - Check for empty enum values lists (URD414)
+/// - Check for duplicate enum values (URD459)
 /// - Verify ref_type references exist (URD415)
-/// - Check range validity: min ≤ max (URD416)
-/// - Check range type compatibility (URD417)
+/// - Check range validity on numeric properties: min ≤ max (URD416)
+/// - Warn of ranges on other types, which are ignored (URD417)
+/// - Check declared traits against the trait registry (URD441)
+/// - Check property names against property group names (URD446)
 
-use crate::diagnostics::DiagnosticCollector;
+use crate::diagnostics::{Diagnostic, DiagnosticCollector, RelatedInfo, Severity};
+use crate::link::find_suggestion;
 use crate::symbol_table::{PropertyType, SymbolTable};
+use crate::traits;
 
 use super::helpers::{check_value, format_property_type, CheckContext};
 
 /// Validate all type definitions in the symbol table.
 pub fn validate_types(
     symbol_table: &SymbolTable,
+    allow_custom_traits: bool,
     diagnostics: &mut DiagnosticCollector,
 ) {
     for (type_name, type_sym) in &symbol_table.types {
         for (_prop_name, prop) in &type_sym.properties {
-            // a. Property defaults.
-            if let Some(default) = &prop.default {
+            let numeric = matches!(prop.property_type, PropertyType::Integer | PropertyType::Number);
+            let empty_enum = prop.property_type == PropertyType::Enum
+                && prop.values.as_ref().is_some_and(|values| values.is_empty());
+
+            // a. Property defaults. An empty enum has no value a default
+            // could match, so only URD414 is reported for it.
+            if let (Some(default), false) = (&prop.default, empty_enum) {
                 check_value(
                     default,
                     prop,
@@ -34,39 +45,65 @@ pub fn validate_types(
             }
 
             // b. Empty enum values list.
-            if prop.property_type == PropertyType::Enum {
-                if let Some(values) = &prop.values {
-                    if values.is_empty() {
-                        diagnostics.error(
-                            "URD414",
-                            format!(
-                                "Enum property '{}' on type '{}' declares an empty values list.",
-                                prop.name, type_name,
-                            ),
-                            type_sym.declared_in.clone(),
-                        );
+            if empty_enum {
+                diagnostics.error(
+                    "URD414",
+                    format!(
+                        "Enum property '{}' on type '{}' declares an empty values list.",
+                        prop.name, type_name,
+                    ),
+                    type_sym.declared_in.clone(),
+                );
+            }
+
+            // b2. Duplicate enum values, each listed once in the order of
+            // its second occurrence.
+            if let (PropertyType::Enum, Some(values)) = (&prop.property_type, &prop.values) {
+                let mut duplicates: Vec<&str> = Vec::new();
+                for (i, value) in values.iter().enumerate() {
+                    if values[..i].contains(value) && !duplicates.contains(&value.as_str()) {
+                        duplicates.push(value);
                     }
                 }
+                if !duplicates.is_empty() {
+                    diagnostics.error(
+                        "URD459",
+                        format!(
+                            "Enum property '{}' on type '{}' lists {} more than once: {}.",
+                            prop.name,
+                            type_name,
+                            if duplicates.len() == 1 { "a value" } else { "values" },
+                            duplicates.join(", "),
+                        ),
+                        type_sym.declared_in.clone(),
+                    );
+                }
             }
 
-            // c. Ref type existence.
+            // c. Ref type existence. LINK copies `ref_type` without
+            // resolving it, so this is the only check.
             if prop.property_type == PropertyType::Ref {
                 if let Some(ref_type) = &prop.ref_type {
                     if !symbol_table.types.contains_key(ref_type) {
-                        diagnostics.error(
-                            "URD415",
-                            format!(
+                        diagnostics.emit(Diagnostic {
+                            severity: Severity::Error,
+                            code: "URD415".to_string(),
+                            message: format!(
                                 "Property '{}' on type '{}' references unknown type '{}'.",
                                 prop.name, type_name, ref_type,
                             ),
-                            type_sym.declared_in.clone(),
-                        );
+                            span: type_sym.declared_in.clone(),
+                            suggestion: find_suggestion(ref_type, &symbol_table.types)
+                                .map(|t| format!("Did you mean '{}'?", t)),
+                            fix: None,
+                            related: Vec::new(),
+                        });
                     }
                 }
             }
 
             // d. Range validity.
-            if let (Some(min), Some(max)) = (prop.min, prop.max) {
+            if let (Some(min), Some(max), true) = (prop.min, prop.max, numeric) {
                 if min > max {
                     diagnostics.error(
                         "URD416",
@@ -79,18 +116,17 @@ pub fn validate_types(
                 }
             }
 
-            // e. Range type compatibility.
-            if prop.min.is_some() || prop.max.is_some() {
-                if !matches!(prop.property_type, PropertyType::Integer | PropertyType::Number) {
-                    diagnostics.error(
-                        "URD417",
-                        format!(
-                            "Range constraints (min/max) are only valid on integer and number properties, not '{}'.",
-                            format_property_type(&prop.property_type),
-                        ),
-                        type_sym.declared_in.clone(),
-                    );
-                }
+            // e. Range type compatibility. EMIT leaves the range out.
+            if (prop.min.is_some() || prop.max.is_some()) && !numeric {
+                diagnostics.warning(
+                    "URD417",
+                    format!(
+                        "Range constraints (min/max) are only valid on integer and number properties, not '{}'. \
+                         They are ignored.",
+                        format_property_type(&prop.property_type),
+                    ),
+                    type_sym.declared_in.clone(),
+                );
             }
 
             // f. Unrecognised type string.
@@ -107,6 +143,62 @@ pub fn validate_types(
                 );
             }
         }
+
+        // g. Unknown traits. Info rather than warning when the world opts
+        // in to engine-specific traits.
+        for trait_name in &type_sym.traits {
+            if traits::lookup(trait_name).is_some() {
+                continue;
+            }
+            let builtin: Vec<&str> = traits::BUILTIN_TRAITS.iter().map(|t| t.name).collect();
+            diagnostics.emit(Diagnostic {
+                severity: if allow_custom_traits { Severity::Info } else { Severity::Warning },
+                code: "URD441".to_string(),
+                message: format!(
+                    "Unknown trait '{}' on type '{}'. Built-in traits: {}.",
+                    trait_name,
+                    type_name,
+                    builtin.join(", "),
+                ),
+                span: type_sym.declared_in.clone(),
+                suggestion: traits::suggest(trait_name).map(|t| format!("Did you mean '{}'?", t)),
+                fix: None,
+                related: Vec::new(),
+            });
+        }
+
+        // h. Property group collisions. EMIT nests `appearance.height`
+        // under an `appearance` object, so no property may also be named
+        // `appearance`. Reported once per property, against the first
+        // member of the group it collides with.
+        for (prop_name, prop) in &type_sym.properties {
+            let group_prefix = format!("{}.", prop_name);
+            let Some(member) = type_sym
+                .properties
+                .values()
+                .find(|p| p.name.starts_with(&group_prefix))
+            else {
+                continue;
+            };
+            diagnostics.emit(Diagnostic {
+                severity: Severity::Error,
+                code: "URD446".to_string(),
+                message: format!(
+                    "Property '{}' on type '{}' collides with property group '{}' ('{}').",
+                    prop_name, type_name, prop_name, member.name,
+                ),
+                span: prop.declared_in.clone(),
+                suggestion: Some(format!(
+                    "Rename '{}' or move it into the group, e.g. '{}value'.",
+                    prop_name, group_prefix,
+                )),
+                fix: None,
+                related: vec![RelatedInfo {
+                    message: format!("Group member '{}' declared here", member.name),
+                    span: member.declared_in.clone(),
+                }],
+            });
+        }
     }
 }
 
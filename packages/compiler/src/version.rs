@@ -0,0 +1,59 @@
+/// The compiler's identity, for build systems that pin it.
+///
+/// `urd --version --json` prints [`to_json()`]:
+///
+/// ```json
+/// { "name": "urd-compiler", "version": "0.1.14",
+///   "formats": ["1", "2"], "features": [] }
+/// ```
+///
+/// The object's keys are stable: fields may be added, never renamed or
+/// removed. With `EmitOptions::include_generator` set, the world block
+/// also names the compiler in a `"generator"` field ([`generator()`]).
+
+use serde_json::{json, Value};
+
+/// The compiler's package name.
+pub const NAME: &str = "urd-compiler";
+
+/// The compiler's semver version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The `urd` format versions EMIT writes. A world names the one it uses
+/// in its `urd` field.
+pub const FORMAT_VERSIONS: &[&str] = &["1", "2"];
+
+/// The Cargo features this build was compiled with.
+pub fn features() -> Vec<&'static str> {
+    let mut features = Vec::new();
+    if cfg!(feature = "wasm") {
+        features.push("wasm");
+    }
+    features
+}
+
+/// `urd-compiler X.Y.Z`, the world block's `"generator"` value.
+pub fn generator() -> String {
+    format!("{} {}", NAME, VERSION)
+}
+
+/// The version report: `name`, `version`, `formats`, and `features`.
+pub fn to_json() -> Value {
+    json!({
+        "name": NAME,
+        "version": VERSION,
+        "formats": FORMAT_VERSIONS,
+        "features": features(),
+    })
+}
+
+/// The one-line human form: `urd 0.1.14 (formats: 1)`, plus any
+/// features.
+pub fn to_text() -> String {
+    let mut text = format!("urd {} (formats: {})", VERSION, FORMAT_VERSIONS.join(", "));
+    let features = features();
+    if !features.is_empty() {
+        text.push_str(&format!(" [features: {}]", features.join(", ")));
+    }
+    text
+}
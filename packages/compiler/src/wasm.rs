@@ -1,24 +1,83 @@
 /// WASM bindings for the Urd compiler.
 ///
-/// Provides three entry points for browser/playground use:
+/// Provides five entry points for browser/playground use:
 /// - `compile_source()` — full five-phase pipeline
+/// - `compile_full()` — the same, as a named file, with analysis opt-in
+/// - `compile_fragment()` — one section or location, without a world
 /// - `parse_only()` — phase 1 only (for live syntax checking)
 /// - `compiler_version()` — crate version string
 
 use wasm_bindgen::prelude::*;
 
-use crate::diagnostics::Severity;
+use crate::payload;
 
 /// Compile a `.urd.md` source string through the full pipeline.
 ///
+/// There is no file system in the browser, so every `import:` is missing.
+/// `missing_imports` selects the policy: `"error"` (default), `"warn"`
+/// to skip missing files, or `"stub"` to substitute empty ones.
+///
 /// Returns a JSON string with shape:
 /// ```json
-/// { "success": bool, "world": string|null, "diagnostics": [...] }
+/// { "success": bool, "world": string|null, "diagnostics": [...],
+///   "facts": object|null, "property_index": object|null,
+///   "definition_index": object|null }
 /// ```
 #[wasm_bindgen]
-pub fn compile_source(source: &str) -> String {
-    let result = crate::compile_source("playground.urd.md", source);
-    serialise_result(result)
+pub fn compile_source(source: &str, missing_imports: Option<String>) -> String {
+    let policy = missing_imports
+        .as_deref()
+        .and_then(crate::import::MissingImportPolicy::from_name)
+        .unwrap_or_default();
+    let options = crate::CompileOptions {
+        missing_import_policy: policy,
+        ..crate::CompileOptions::default()
+    };
+    let result = crate::compile_source_with_options(
+        "playground.urd.md",
+        source,
+        &crate::import::StubFileReader,
+        &options,
+    );
+    payload::result_json(&result, true).to_string()
+}
+
+/// Compile `source` as `filename` in single-file mode.
+///
+/// The FactSet, property index, and definition index are serialised only
+/// when `include_facts` is set, since they are larger than the world for
+/// most sources. A compiler panic is returned as an `error` object rather
+/// than thrown (see [`payload::compile_full()`] for the shape).
+#[wasm_bindgen]
+pub fn compile_full(filename: &str, source: &str, include_facts: bool) -> String {
+    payload::compile_full(filename, source, include_facts).to_string()
+}
+
+/// Compile a snippet holding one section or location (see
+/// [`crate::fragment`]). `kind` is `"section"` or `"location"`.
+///
+/// Returns a JSON string with shape:
+/// ```json
+/// { "success": bool, "id": string|null, "json": object|null, "diagnostics": [...] }
+/// ```
+/// An unknown `kind` gives `success: false` and an `error` message.
+#[wasm_bindgen]
+pub fn compile_fragment(source: &str, kind: &str) -> String {
+    let Some(kind) = crate::fragment::FragmentKind::from_name(kind) else {
+        return serde_json::json!({
+            "success": false,
+            "error": format!("Unknown fragment kind '{}'. Use 'section' or 'location'.", kind),
+        })
+        .to_string();
+    };
+    let result = crate::fragment::compile_fragment(kind, source);
+    serde_json::json!({
+        "success": result.success,
+        "id": result.id,
+        "json": result.json,
+        "diagnostics": payload::diagnostics_json(&result.diagnostics),
+    })
+    .to_string()
 }
 
 /// Run only the PARSE phase (for live syntax checking in the editor).
@@ -34,7 +93,7 @@ pub fn parse_only(source: &str) -> String {
     let parsed = crate::parse::parse(&filename, source, &mut diagnostics);
 
     let success = parsed.is_some() && !diagnostics.has_errors();
-    let diags = serialise_diagnostics(&diagnostics);
+    let diags = payload::diagnostics_json(&diagnostics);
 
     serde_json::json!({
         "success": success,
@@ -46,48 +105,5 @@ pub fn parse_only(source: &str) -> String {
 /// Return the compiler version (from Cargo.toml).
 #[wasm_bindgen]
 pub fn compiler_version() -> String {
-    env!("CARGO_PKG_VERSION").to_string()
-}
-
-fn serialise_result(result: crate::CompilationResult) -> String {
-    let diags = serialise_diagnostics(&result.diagnostics);
-
-    let facts_json = result.fact_set.as_ref().map(|fs| fs.to_json());
-    let index_json = result.property_index.as_ref().map(|idx| idx.to_json());
-    let definition_index_json = result.definition_index.as_ref().map(|idx| idx.to_json());
-
-    serde_json::json!({
-        "success": result.success,
-        "world": result.world,
-        "diagnostics": diags,
-        "facts": facts_json,
-        "property_index": index_json,
-        "definition_index": definition_index_json,
-    })
-    .to_string()
-}
-
-fn serialise_diagnostics(collector: &crate::diagnostics::DiagnosticCollector) -> Vec<serde_json::Value> {
-    collector
-        .sorted()
-        .iter()
-        .map(|d| {
-            serde_json::json!({
-                "severity": match d.severity {
-                    Severity::Error => "error",
-                    Severity::Warning => "warning",
-                    Severity::Info => "info",
-                },
-                "code": d.code,
-                "message": d.message,
-                "span": {
-                    "file": d.span.file,
-                    "start_line": d.span.start_line,
-                    "start_col": d.span.start_col,
-                    "end_line": d.span.end_line,
-                    "end_col": d.span.end_col,
-                },
-            })
-        })
-        .collect()
+    crate::version::VERSION.to_string()
 }
@@ -0,0 +1,169 @@
+/// Watch mode: recompile whenever the entry file or an imported file
+/// changes.
+///
+/// The CLI's `urd watch` drives [`watch()`] with a host that reads the
+/// file system. This module does no file I/O itself: the host reports
+/// modification times and runs each compile, so tests can drive the loop
+/// against a temporary directory.
+///
+/// Changes are found by polling modification times every
+/// [`POLL_INTERVAL`]. The watched set is every file in the last
+/// dependency graph, refreshed after each compile because imports can
+/// change. A burst of saves triggers one compile, [`DEBOUNCE`] after the
+/// last of them.
+///
+/// Not available on WASM targets.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::CompilationResult;
+
+/// Quiet period after the last change before recompiling.
+pub const DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// How often modification times are checked.
+pub const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// What the loop needs from its environment.
+pub trait WatchHost {
+    /// Modification time of `path`, or `None` if it cannot be read. A
+    /// file that appears or disappears counts as a change.
+    fn modified(&self, path: &str) -> Option<SystemTime>;
+
+    /// Compile the entry file from scratch (or through a cache).
+    fn compile(&mut self) -> CompilationResult;
+
+    /// Report a finished compile: write output, print diagnostics.
+    fn compiled(&mut self, run: WatchRun, result: &CompilationResult);
+}
+
+/// One compile of a watch session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WatchRun {
+    /// 1 for the initial compile, then one more per recompile.
+    pub count: usize,
+    pub elapsed: Duration,
+}
+
+/// Run the watch loop until `stop` is set: compile once, then again
+/// after each debounced change to a watched file.
+///
+/// `entry` is the entry file as the host opens it. `base_dir` is the
+/// directory the dependency graph's paths are relative to: the project
+/// root, or the entry file's directory.
+pub fn watch(host: &mut dyn WatchHost, entry: &str, base_dir: &str, stop: &AtomicBool) {
+    let mut watched = vec![entry.to_string()];
+    let mut stamps = BTreeMap::new();
+    let mut debounce = Debounce::new(DEBOUNCE);
+    let mut count = 0;
+    let mut due = true;
+
+    while !stop.load(Ordering::Relaxed) {
+        if due {
+            // Stamp before compiling, so a save during the compile still
+            // counts as a change.
+            stamps = stamp(host, &watched);
+            let started = Instant::now();
+            let result = host.compile();
+            let elapsed = started.elapsed();
+            count += 1;
+
+            watched = watched_files(&result, base_dir, entry, &watched);
+            stamps.retain(|path, _| watched.contains(path));
+            for path in &watched {
+                if !stamps.contains_key(path) {
+                    stamps.insert(path.clone(), host.modified(path));
+                }
+            }
+            // Report once the new watched set is stamped, so any save the
+            // host's output prompts is a change.
+            host.compiled(WatchRun { count, elapsed }, &result);
+        }
+
+        std::thread::sleep(POLL_INTERVAL);
+        let now = Instant::now();
+        let current = stamp(host, &watched);
+        if current != stamps {
+            debounce.changed(now);
+            stamps = current;
+        }
+        due = debounce.ready(now);
+    }
+}
+
+fn stamp(host: &dyn WatchHost, paths: &[String]) -> BTreeMap<String, Option<SystemTime>> {
+    paths.iter().map(|p| (p.clone(), host.modified(p))).collect()
+}
+
+/// The files to watch after a compile: the entry file, then every file
+/// in the dependency graph, joined onto `base_dir`.
+///
+/// A result without a graph (a PARSE or IMPORT failure, or a cache hit)
+/// says nothing new about the imports, so `previous` is kept, with the
+/// entry file added if missing.
+pub fn watched_files(
+    result: &CompilationResult,
+    base_dir: &str,
+    entry: &str,
+    previous: &[String],
+) -> Vec<String> {
+    let mut files = vec![entry.to_string()];
+    let graph_files: Vec<String> = match &result.graph {
+        Some(graph) => graph.nodes.keys().map(|path| join(base_dir, path)).collect(),
+        None => previous.to_vec(),
+    };
+    for file in graph_files {
+        if !files.contains(&file) {
+            files.push(file);
+        }
+    }
+    files
+}
+
+fn join(base_dir: &str, path: &str) -> String {
+    if base_dir.is_empty() || base_dir.ends_with('/') {
+        format!("{}{}", base_dir, path)
+    } else {
+        format!("{}/{}", base_dir, path)
+    }
+}
+
+/// Trailing-edge debounce: fires once a quiet period has passed since the
+/// last recorded change.
+#[derive(Debug, Clone)]
+pub struct Debounce {
+    quiet: Duration,
+    last_change: Option<Instant>,
+}
+
+impl Debounce {
+    pub fn new(quiet: Duration) -> Self {
+        Debounce { quiet, last_change: None }
+    }
+
+    /// Record a change seen at `now`. Restarts the quiet period.
+    pub fn changed(&mut self, now: Instant) {
+        self.last_change = Some(now);
+    }
+
+    /// Whether a change is pending and `now` is at least the quiet period
+    /// after it. Returns `true` once per burst of changes.
+    pub fn ready(&mut self, now: Instant) -> bool {
+        match self.last_change {
+            Some(last) if now.duration_since(last) >= self.quiet => {
+                self.last_change = None;
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+/// The default output path in watch mode: `world.urd.md` writes
+/// `world.urd.json`.
+pub fn default_output_path(source_path: &str) -> String {
+    let stem = source_path.strip_suffix(".urd.md").unwrap_or(source_path);
+    format!("{}.urd.json", stem)
+}
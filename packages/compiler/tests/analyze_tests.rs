@@ -1,12 +1,15 @@
 /// Tests for the ANALYZE phase (FactSet-derived diagnostics, URD600–URD699).
 ///
 /// Each test compiles a fixture, extracts the FactSet, runs `analyze()`,
-/// and asserts on the diagnostics produced.
+/// and asserts on the diagnostics produced. Value reachability tests call
+/// `classify_value_reachability()` directly.
 
-use urd_compiler::analyze;
-use urd_compiler::compile;
+use urd_compiler::analyze::{self, ValueReachability};
+use urd_compiler::import::StubFileReader;
+use urd_compiler::size_lint::SizeLimits;
+use urd_compiler::{compile, compile_source_with_options, CompileOptions};
 use urd_compiler::diagnostics::Diagnostic;
-use urd_compiler::facts::{FactSet, PropertyDependencyIndex};
+use urd_compiler::facts::{FactSet, PropertyDependencyIndex, PropertyKey};
 
 fn fixture_path(name: &str) -> String {
     let base = env!("CARGO_MANIFEST_DIR");
@@ -146,6 +149,511 @@ fn analyze_d5_circular_multi_write() {
     );
 }
 
+// ── D6: Parameterised section never invoked — URD606 ──
+
+#[test]
+fn analyze_d6_uninvoked_template() {
+    let diags = analyze_fixture("parameterised-sections.urd.md");
+    let d6 = diagnostics_with_code(&diags, "URD606");
+    assert_eq!(d6.len(), 1, "Only 'unused' is never invoked, got: {:?}", d6);
+    assert_eq!(
+        d6[0].message,
+        "Parameterised section 'parameterised-sections/unused' is never invoked. \
+         No jump passes it an '@patron: Patron' argument, so it emits nothing."
+    );
+}
+
+// ── D7/D8: Sequence phase ordering ──
+//
+// Schema Markdown writes only `auto` and `manual` advances, so these tests
+// set `end` and `on_condition` on the linked symbol table, as a host
+// building phases programmatically would.
+
+const SEQUENCE_TYPES: &str = "\
+---
+types:
+  Gate [interactable]:
+    open: bool = false
+    rusted: bool = true
+entities:
+  @gate: Gate
+---
+";
+
+const SEQUENCE_RULES: &str = "\
+---
+import: ./types.urd.md
+---
+
+rule open_gate:
+  actor: @gate action open
+  > @gate.open = true
+";
+
+const SEQUENCE_MAIN: &str = "\
+---
+import: ./rules.urd.md
+world:
+  name: ceremony
+  start: courtyard
+---
+
+# Courtyard
+
+[@gate]
+
+## Ceremony
+
+### Arrive
+
+### Wait
+
+### Leave
+";
+
+struct SequenceFs;
+
+impl urd_compiler::import::FileReader for SequenceFs {
+    fn read_file(&self, fs_path: &str) -> Result<String, urd_compiler::import::FileReadError> {
+        match fs_path {
+            "types.urd.md" => Ok(SEQUENCE_TYPES.to_string()),
+            "rules.urd.md" => Ok(SEQUENCE_RULES.to_string()),
+            _ => Err(urd_compiler::import::FileReadError::NotFound),
+        }
+    }
+
+    fn canonical_filename(&self, _dir: &str, _filename: &str) -> Option<String> {
+        None
+    }
+}
+
+/// Compile the ceremony world, set each phase's advance mode, and analyze.
+fn analyze_ceremony(advances: [&str; 3]) -> Vec<Diagnostic> {
+    let result = urd_compiler::compile_source_with_reader("main.urd.md", SEQUENCE_MAIN, &SequenceFs);
+    let mut symbol_table = result.symbol_table.expect("symbol table");
+    let graph = result.graph.expect("graph");
+    let sequence = symbol_table.sequences.get_mut("ceremony").expect("sequence");
+    for (phase, advance) in sequence.phases.iter_mut().zip(advances) {
+        phase.advance = advance.to_string();
+    }
+    let facts = urd_compiler::facts::extract_facts(&graph, &symbol_table);
+    let index = PropertyDependencyIndex::build(&facts);
+    analyze::analyze(&facts, &index)
+}
+
+#[test]
+fn analyze_d7_end_before_last_phase() {
+    let diags = analyze_ceremony(["end", "manual", "manual"]);
+    let d7 = diagnostics_with_code(&diags, "URD607");
+    assert_eq!(d7.len(), 1, "got: {:?}", d7);
+    assert_eq!(
+        d7[0].message,
+        "Phase 'arrive' ends sequence 'ceremony', but phases follow after it. \
+         Unreachable phases: wait, leave."
+    );
+    assert_eq!(d7[0].related.len(), 2);
+}
+
+#[test]
+fn analyze_d7_end_on_last_phase() {
+    let diags = analyze_ceremony(["manual", "manual", "end"]);
+    assert!(diagnostics_with_code(&diags, "URD607").is_empty(), "got: {:?}", diags);
+}
+
+#[test]
+fn analyze_d8_condition_never_written() {
+    let diags = analyze_ceremony(["manual", "on_condition @gate.rusted == false", "end"]);
+    let d8 = diagnostics_with_code(&diags, "URD608");
+    assert_eq!(d8.len(), 1, "got: {:?}", d8);
+    assert_eq!(
+        d8[0].message,
+        "Phase 'wait' in sequence 'ceremony' advances on a condition that reads 'Gate.rusted', \
+         but no effect writes that property. The sequence will stall in this phase."
+    );
+}
+
+#[test]
+fn analyze_d8_condition_written_by_imported_rule() {
+    // `Gate.open` is written only by a rule in rules.urd.md.
+    let diags = analyze_ceremony(["manual", "on_condition @gate.open == true", "end"]);
+    assert!(diagnostics_with_code(&diags, "URD608").is_empty(), "got: {:?}", diags);
+}
+
+// ── D9: Entity placed nowhere — URD609 ──
+
+/// Compile a one-file harbour world with the given world block lines,
+/// types, entities, and body, and analyze it.
+fn analyze_harbour(world_extra: &str, entities: &str, body: &str) -> Vec<Diagnostic> {
+    let source = format!(
+        "---\nworld:\n  name: harbour\n  start: quay\n{}\n\
+         types:\n  Person [interactable, mobile, container]:\n    name: string\n  \
+         Crate [portable]:\n    name: string\n  Faction [interactable, abstract]:\n    name: string\n  \
+         Rumour [interactable, hearsay]:\n    name: string\n\
+         entities:\n{}\n---\n\n# Quay\n\n{}\n",
+        world_extra, entities, body
+    );
+    let result = urd_compiler::compile_source("harbour.urd.md", &source);
+    let fs = result.fact_set.expect("FactSet");
+    let idx = PropertyDependencyIndex::build(&fs);
+    analyze::analyze(&fs, &idx)
+}
+
+#[test]
+fn analyze_d9_unplaced_entity_flagged() {
+    let diags = analyze_harbour("", "  @dockhand: Person\n  @spare_crate: Crate", "[@dockhand]");
+    let d9 = diagnostics_with_code(&diags, "URD609");
+    assert_eq!(d9.len(), 1, "got: {:?}", diags);
+    assert_eq!(d9[0].severity, urd_compiler::diagnostics::Severity::Info);
+    assert_eq!(
+        d9[0].message,
+        "Entity '@spare_crate' is not placed in any location or the start inventory, \
+         and no effect moves it, so it exists nowhere when the game begins."
+    );
+    let suggestion = d9[0].suggestion.as_deref().expect("suggestion");
+    assert!(suggestion.contains("world.start_inventory") && suggestion.contains("unplaced_traits"), "{}", suggestion);
+    assert_eq!(d9[0].span.start_line, 17, "Reported at the entity declaration");
+}
+
+#[test]
+fn analyze_d9_placed_entities_silent() {
+    let diags = analyze_harbour(
+        "  start_inventory: [@spare_crate]",
+        "  @dockhand: Person\n  @spare_crate: Crate",
+        "[@dockhand]",
+    );
+    assert!(diagnostics_with_code(&diags, "URD609").is_empty(), "got: {:?}", diags);
+}
+
+#[test]
+fn analyze_d9_moved_later_silent() {
+    let body = "[@dockhand]\n\n\
+                == unload\n\n\
+                * Open the hold\n  > move @spare_crate -> here\n";
+    let diags = analyze_harbour("", "  @dockhand: Person\n  @spare_crate: Crate", body);
+    assert!(diagnostics_with_code(&diags, "URD609").is_empty(), "got: {:?}", diags);
+}
+
+#[test]
+fn analyze_d9_player_exempt() {
+    let diags = analyze_harbour("", "  @player: Person\n  @dockhand: Person", "[@dockhand]");
+    assert!(diagnostics_with_code(&diags, "URD609").is_empty(), "got: {:?}", diags);
+}
+
+#[test]
+fn analyze_d9_exempt_traits_silent() {
+    let entities = "  @guild: Faction\n  @whisper: Rumour";
+    let diags = analyze_harbour("", entities, "");
+    let flagged: Vec<&str> = diagnostics_with_code(&diags, "URD609")
+        .iter()
+        .map(|d| d.message.as_str())
+        .collect();
+    assert_eq!(flagged.len(), 1, "Only the custom-trait entity: {:?}", flagged);
+    assert!(flagged[0].contains("@whisper"), "{:?}", flagged);
+
+    let diags = analyze_harbour("  unplaced_traits: [hearsay]", entities, "");
+    assert!(diagnostics_with_code(&diags, "URD609").is_empty(), "got: {:?}", diags);
+}
+
+#[test]
+fn analyze_d9_dialogue_mode_silent() {
+    let diags = analyze_harbour("  mode: dialogue", "  @dockhand: Person\n  @spare_crate: Crate", "");
+    assert!(diagnostics_with_code(&diags, "URD609").is_empty(), "got: {:?}", diags);
+}
+
+// ── D10: Exit effects with no gate — URD610 ──
+// ── D11: Exit effect flips its own condition — URD611 ──
+
+/// Analyze a gatehouse world with two doors, where `exits` is the exit
+/// block of the Gatehouse, leading to the Yard.
+fn analyze_gatehouse(exits: &str) -> Vec<Diagnostic> {
+    let source = format!(
+        "---\nworld:\n  name: gate\n  start: gatehouse\n\
+         types:\n  Door [interactable]:\n    locked: bool = true\n    wear: integer = 0\n\
+         entities:\n  @gate: Door\n  @postern: Door\n---\n\n\
+         # Gatehouse\n\n[@gate, @postern]\n\n{}\n\n# Yard\n\n-> south: Gatehouse\n",
+        exits
+    );
+    let result = urd_compiler::compile_source("gate.urd.md", &source);
+    let fs = result.fact_set.expect("FactSet");
+    let idx = PropertyDependencyIndex::build(&fs);
+    analyze::analyze(&fs, &idx)
+}
+
+#[test]
+fn analyze_d10_ungated_exit_effects_flagged() {
+    let diags = analyze_gatehouse("-> north: Yard\n  > @gate.wear + 1");
+    let d10 = diagnostics_with_code(&diags, "URD610");
+    assert_eq!(d10.len(), 1, "got: {:?}", diags);
+    assert_eq!(d10[0].severity, urd_compiler::diagnostics::Severity::Info);
+    assert!(d10[0].message.contains("Exit 'north' from 'gatehouse'"), "{}", d10[0].message);
+}
+
+#[test]
+fn analyze_d10_gated_exit_effects_silent() {
+    let conditional = analyze_gatehouse("-> north: Yard\n  ? @postern.locked == false\n  > @gate.wear + 1");
+    assert!(diagnostics_with_code(&conditional, "URD610").is_empty(), "got: {:?}", conditional);
+
+    let with_message = analyze_gatehouse("-> north: Yard\n  ! The hinges shriek.\n  > @gate.wear + 1");
+    assert!(diagnostics_with_code(&with_message, "URD610").is_empty(), "got: {:?}", with_message);
+
+    let no_effects = analyze_gatehouse("-> north: Yard");
+    assert!(diagnostics_with_code(&no_effects, "URD610").is_empty(), "got: {:?}", no_effects);
+}
+
+#[test]
+fn analyze_d11_exit_that_blocks_itself_flagged() {
+    let diags = analyze_gatehouse(
+        "-> north: Yard\n  ? @gate.locked == false\n  ! Locked.\n  > @gate.locked = true",
+    );
+    let d11 = diagnostics_with_code(&diags, "URD611");
+    assert_eq!(d11.len(), 1, "got: {:?}", diags);
+    assert_eq!(d11[0].severity, urd_compiler::diagnostics::Severity::Warning);
+    assert!(d11[0].message.contains("'@gate.locked' to true"), "{}", d11[0].message);
+    assert!(d11[0].message.contains("blocks the exit after its first traversal"), "{}", d11[0].message);
+    assert_eq!(d11[0].related.len(), 1);
+}
+
+#[test]
+fn analyze_d11_exit_setting_its_required_value_flagged() {
+    let diags = analyze_gatehouse("-> north: Yard\n  ? @gate.locked == false\n  > @gate.locked = false");
+    let d11 = diagnostics_with_code(&diags, "URD611");
+    assert_eq!(d11.len(), 1, "got: {:?}", diags);
+    assert!(d11[0].message.contains("changes nothing on traversal"), "{}", d11[0].message);
+}
+
+#[test]
+fn analyze_d11_numeric_threshold_flagged() {
+    let diags = analyze_gatehouse("-> north: Yard\n  ? @gate.wear < 3\n  > @gate.wear = 5");
+    assert_eq!(diagnostics_with_code(&diags, "URD611").len(), 1, "got: {:?}", diags);
+}
+
+#[test]
+fn analyze_d11_other_entity_or_arithmetic_silent() {
+    // Same type and property, but a different door.
+    let other = analyze_gatehouse("-> north: Yard\n  ? @gate.locked == false\n  > @postern.locked = true");
+    assert!(diagnostics_with_code(&other, "URD611").is_empty(), "got: {:?}", other);
+
+    // `+` and `-` are not judged.
+    let arithmetic = analyze_gatehouse("-> north: Yard\n  ? @gate.wear < 3\n  > @gate.wear + 1");
+    assert!(diagnostics_with_code(&arithmetic, "URD611").is_empty(), "got: {:?}", arithmetic);
+
+    // A value that keeps the condition true without being its exact value.
+    let kept = analyze_gatehouse("-> north: Yard\n  ? @gate.wear < 3\n  > @gate.wear = 1");
+    assert!(diagnostics_with_code(&kept, "URD611").is_empty(), "got: {:?}", kept);
+}
+
+// ── D12: Start location with nothing to do — URD612 ──
+
+/// Analyze a world starting in a Cell whose content is `cell`, followed
+/// by a Yard with an exit back.
+fn analyze_cell(cell: &str) -> Vec<Diagnostic> {
+    let source = format!(
+        "---\nworld:\n  name: cell\n  start: cell\n---\n\n# Cell\n\n{}\n\n# Yard\n\n-> south: Cell\n",
+        cell
+    );
+    let result = urd_compiler::compile_source("cell.urd.md", &source);
+    let fs = result.fact_set.expect("FactSet");
+    let idx = PropertyDependencyIndex::build(&fs);
+    analyze::analyze(&fs, &idx)
+}
+
+#[test]
+fn analyze_d12_empty_start_warns() {
+    let diags = analyze_cell("");
+    let d12 = diagnostics_with_code(&diags, "URD612");
+    assert_eq!(d12.len(), 1, "got: {:?}", diags);
+    assert_eq!(d12[0].severity, urd_compiler::diagnostics::Severity::Warning);
+    assert_eq!(
+        d12[0].message,
+        "The start location 'cell' has no description, exits, entities, or sections. \
+         The player will begin there with nothing to see or do."
+    );
+    let suggestion = d12[0].suggestion.as_deref().expect("suggestion");
+    assert!(suggestion.contains("exit") && suggestion.contains("section"), "{}", suggestion);
+    assert_eq!(d12[0].span.start_line, 7, "Reported at the location heading");
+}
+
+#[test]
+fn analyze_d12_description_only_start_is_info() {
+    let diags = analyze_cell("Bare stone walls, and a door with no handle.");
+    let d12 = diagnostics_with_code(&diags, "URD612");
+    assert_eq!(d12.len(), 1, "got: {:?}", diags);
+    assert_eq!(d12[0].severity, urd_compiler::diagnostics::Severity::Info);
+    assert!(d12[0].message.contains("has a description but no exits"), "{}", d12[0].message);
+}
+
+#[test]
+fn analyze_d12_start_with_exit_or_section_silent() {
+    let exit = analyze_cell("-> north: Yard");
+    assert!(diagnostics_with_code(&exit, "URD612").is_empty(), "got: {:?}", exit);
+
+    let section = analyze_cell("== wait\n\nTime passes.");
+    assert!(diagnostics_with_code(&section, "URD612").is_empty(), "got: {:?}", section);
+}
+
+// ── D13: Ending never reached — URD613 ──
+// ── D14: Ending reached only from unreachable locations — URD614 ──
+
+/// Analyze a world with endings `escaped` and `caught`, starting in a
+/// Cell whose content is `cell`, with a Vault no exit leads to whose
+/// content is `vault`.
+fn analyze_endings(cell: &str, vault: &str) -> Vec<Diagnostic> {
+    let source = format!(
+        "---\nworld:\n  name: cell\n  start: cell\nendings:\n  escaped: You slip away.\n  \
+         caught: The guards find you.\n---\n\n# Cell\n\n{}\n\n# Vault\n\n{}\n",
+        cell, vault
+    );
+    let result = urd_compiler::compile_source("cell.urd.md", &source);
+    let fs = result.fact_set.expect("FactSet");
+    let idx = PropertyDependencyIndex::build(&fs);
+    analyze::analyze(&fs, &idx)
+}
+
+#[test]
+fn analyze_d13_unreached_ending_flagged() {
+    let diags = analyze_endings("== run\n\n* Run\n  > end escaped", "");
+    let d13 = diagnostics_with_code(&diags, "URD613");
+    assert_eq!(d13.len(), 1, "got: {:?}", diags);
+    assert_eq!(d13[0].severity, urd_compiler::diagnostics::Severity::Warning);
+    assert_eq!(d13[0].message, "Ending 'caught' is declared but no `> end caught` effect reaches it.");
+    assert_eq!(d13[0].span.start_line, 7, "Reported at the ending declaration");
+}
+
+#[test]
+fn analyze_d13_every_ending_reached_silent() {
+    let diags = analyze_endings("== run\n\n* Run\n  > end escaped\n* Wait\n  > end caught", "");
+    assert!(diagnostics_with_code(&diags, "URD613").is_empty(), "got: {:?}", diags);
+}
+
+#[test]
+fn analyze_d14_ending_only_in_unreachable_location_is_info() {
+    let diags = analyze_endings("== run\n\n* Run\n  > end escaped", "== hide\n\n* Hide\n  > end caught");
+    let d14 = diagnostics_with_code(&diags, "URD614");
+    assert_eq!(d14.len(), 1, "got: {:?}", diags);
+    assert_eq!(d14[0].severity, urd_compiler::diagnostics::Severity::Info);
+    assert_eq!(
+        d14[0].message,
+        "Ending 'caught' is only reached from unreachable locations ('vault'), so no playthrough can end there."
+    );
+    assert_eq!(d14[0].related.len(), 1);
+    assert!(diagnostics_with_code(&diags, "URD613").is_empty(), "got: {:?}", diags);
+}
+
+#[test]
+fn analyze_d14_ending_also_reachable_silent() {
+    let diags = analyze_endings(
+        "== run\n\n* Run\n  > end escaped\n* Wait\n  > end caught",
+        "== hide\n\n* Hide\n  > end caught",
+    );
+    assert!(diagnostics_with_code(&diags, "URD614").is_empty(), "got: {:?}", diags);
+}
+
+// ── Size checks — URD615, URD616, URD617 ──
+
+/// Compile a one-file harbour world with the given world block lines and
+/// body, and return its diagnostics with size codes.
+fn size_diagnostics(world_extra: &str, body: &str, limits: SizeLimits) -> Vec<Diagnostic> {
+    let source = format!("---\nworld:\n  name: harbour\n  start: quay\n{}\n---\n\n# Quay\n\n{}\n", world_extra, body);
+    let options = CompileOptions { size_limits: limits, ..CompileOptions::default() };
+    let result = compile_source_with_options("harbour.urd.md", &source, &StubFileReader, &options);
+    result
+        .diagnostics
+        .all()
+        .iter()
+        .filter(|d| ["URD615", "URD616", "URD617"].contains(&d.code.as_str()))
+        .cloned()
+        .collect()
+}
+
+/// A `== market` section with `count` top-level choices, each with one
+/// nested choice.
+fn market(count: usize) -> String {
+    let choices: String = (1..=count).map(|i| format!("* Stall {}\n  * Haggle {}\n", i, i)).collect();
+    format!("== market\n\n{}", choices)
+}
+
+/// A `== tale` section whose prose has `words` words over four lines,
+/// then one choice.
+fn tale(words: usize) -> String {
+    let line = |n: usize| vec!["salt"; n].join(" ");
+    let quarter = words / 4;
+    format!(
+        "== tale\n\n{}\n\n{}\n\n{}\n\n{}\n\n* Listen",
+        line(quarter),
+        line(quarter),
+        line(quarter),
+        line(words - 3 * quarter)
+    )
+}
+
+/// Exits from the quay to `count` piers, and the piers themselves.
+fn piers(count: usize) -> String {
+    let exits: String = (1..=count).map(|i| format!("-> pier{}: Pier {}\n", i, i)).collect();
+    let rooms: String = (1..=count).map(|i| format!("\n# Pier {}\n\n-> back: Quay\n", i)).collect();
+    format!("{}{}", exits, rooms)
+}
+
+#[test]
+fn analyze_size_choices_fire_past_the_limit() {
+    assert!(size_diagnostics("", &market(20), SizeLimits::default()).is_empty());
+
+    let diags = size_diagnostics("", &market(21), SizeLimits::default());
+    assert_eq!(diags.len(), 1, "got: {:?}", diags);
+    assert_eq!(diags[0].code, "URD615");
+    assert_eq!(diags[0].severity, urd_compiler::diagnostics::Severity::Info);
+    assert_eq!(diags[0].message, "Section 'harbour/market' offers 21 choices at once (more than 20).");
+    assert_eq!(diags[0].span.start_line, 10, "Reported at the section label");
+}
+
+#[test]
+fn analyze_size_prose_fires_past_the_limit() {
+    assert!(size_diagnostics("", &tale(500), SizeLimits::default()).is_empty());
+
+    let diags = size_diagnostics("", &tale(501), SizeLimits::default());
+    assert_eq!(diags.len(), 1, "got: {:?}", diags);
+    assert_eq!(diags[0].code, "URD616");
+    assert_eq!(
+        diags[0].message,
+        "Section 'harbour/tale' has 501 words of prose before its first choice (more than 500), running to line 18."
+    );
+    assert_eq!(diags[0].span.start_line, 10, "Reported at the section label");
+}
+
+#[test]
+fn analyze_size_exits_fire_past_the_limit() {
+    assert!(size_diagnostics("", &piers(10), SizeLimits::default()).is_empty());
+
+    let diags = size_diagnostics("", &piers(11), SizeLimits::default());
+    assert_eq!(diags.len(), 1, "got: {:?}", diags);
+    assert_eq!(diags[0].code, "URD617");
+    assert_eq!(diags[0].message, "Location 'quay' has 11 exits (more than 10).");
+    assert_eq!(diags[0].span.start_line, 8, "Reported at the location heading");
+}
+
+#[test]
+fn analyze_size_limits_are_configurable() {
+    let limits = SizeLimits { choices: 2, prose_words: 8, exits: 1 };
+    let body = format!("{}\n{}\n{}", piers(2), tale(12), market(3));
+    let codes: Vec<_> = size_diagnostics("", &body, limits).into_iter().map(|d| d.code).collect();
+    assert_eq!(codes.len(), 3, "got: {:?}", codes);
+    for code in ["URD615", "URD616", "URD617"] {
+        assert!(codes.iter().any(|c| c == code), "missing {} in {:?}", code, codes);
+    }
+}
+
+#[test]
+fn analyze_size_checks_disabled_by_world_lints() {
+    let body = format!("{}\n{}\n{}", piers(11), tale(501), market(21));
+    let codes: Vec<_> = size_diagnostics("  lints: [URD615, URD617]", &body, SizeLimits::default())
+        .into_iter()
+        .map(|d| d.code)
+        .collect();
+    assert_eq!(codes, ["URD616"]);
+
+    let none = size_diagnostics("  lints: [URD615, URD616, URD617]", &body, SizeLimits::default());
+    assert!(none.is_empty(), "got: {:?}", none);
+}
+
 // ── Existing fixtures: no panics ──
 
 #[test]
@@ -214,6 +722,70 @@ fn analyze_existing_interrogation() {
     );
 }
 
+// ── Value reachability ──
+
+fn reachability(
+    fixture: &str,
+    entity_type: &str,
+    property: &str,
+    target: &str,
+) -> analyze::ValueReachabilityReport {
+    let facts = extract_fixture_facts(fixture);
+    let index = PropertyDependencyIndex::build(&facts);
+    let key = PropertyKey {
+        entity_type: entity_type.to_string(),
+        property: property.to_string(),
+    };
+    analyze::classify_value_reachability(&facts, &index, &key, target, None, None)
+}
+
+#[test]
+fn reachability_bool_directly_settable() {
+    let report = reachability("two-room-key-puzzle.urd.md", "Door", "locked", "false");
+    assert_eq!(report.verdict, ValueReachability::DirectlySettable);
+    assert_eq!(report.exact_writes.len(), 1);
+    assert!(report.arithmetic_writes.is_empty());
+}
+
+#[test]
+fn reachability_bool_never_assigned() {
+    // The door is only ever unlocked; nothing sets it back to true.
+    let report = reachability("two-room-key-puzzle.urd.md", "Door", "locked", "true");
+    assert_eq!(report.verdict, ValueReachability::Unreachable);
+    assert!(report.exact_writes.is_empty());
+    assert_eq!(report.other_writes.len(), 1);
+}
+
+#[test]
+fn reachability_enum_variant_never_assigned() {
+    let report = reachability("locked-garden.urd.md", "Character", "mood", "neutral");
+    assert_eq!(report.verdict, ValueReachability::Unreachable);
+    assert!(report.exact_writes.is_empty());
+    assert_eq!(report.other_writes.len(), 1); // mood = friendly
+}
+
+#[test]
+fn reachability_integer_via_increments() {
+    let report = reachability("locked-garden.urd.md", "Character", "trust", "10");
+    assert_eq!(report.verdict, ValueReachability::ViaArithmetic);
+    assert!(report.exact_writes.is_empty());
+    assert_eq!(report.arithmetic_writes.len(), 4); // +1, +5, -2, +2
+}
+
+#[test]
+fn reachability_integer_outside_declared_range() {
+    let facts = extract_fixture_facts("locked-garden.urd.md");
+    let index = PropertyDependencyIndex::build(&facts);
+    let key = PropertyKey {
+        entity_type: "Character".to_string(),
+        property: "trust".to_string(),
+    };
+    let report =
+        analyze::classify_value_reachability(&facts, &index, &key, "10", Some(0.0), Some(5.0));
+    assert_eq!(report.verdict, ValueReachability::Unreachable);
+    assert!(report.out_of_range);
+}
+
 // ── Empty world: no panic ──
 
 #[test]
@@ -0,0 +1,244 @@
+/// Tests for anonymised project copies.
+///
+/// Each fixture project is compiled, anonymised file by file, and compiled
+/// again from memory. The copy must keep every ID and diagnostic, differ
+/// from the original only in text, and come out the same on every run.
+
+use std::collections::HashMap;
+
+use urd_compiler::anonymize::{anonymize_source, placeholder};
+use urd_compiler::diff::{self, DiffSnapshot};
+use urd_compiler::test_support::MemoryFileReader;
+use urd_compiler::{compile_source_with_options, CompilationResult, CompileOptions};
+
+// ── Helpers ──
+
+/// Fixture projects as (directory under `tests/fixtures`, entry file).
+const PROJECTS: &[(&str, &str)] = &[
+    ("", "sunken-citadel.urd.md"),
+    ("", "tavern-scene.urd.md"),
+    ("", "location-hooks.urd.md"),
+    ("", "negative-type-mismatch.urd.md"),
+    ("interrogation", "main.urd.md"),
+    ("regions", "main.urd.md"),
+];
+
+fn fixture_dir(dir: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), dir)
+}
+
+fn compile_files(entry: &str, files: &HashMap<String, String>) -> CompilationResult {
+    let reader = MemoryFileReader::new(files.clone());
+    compile_source_with_options(entry, &files[entry], &reader, &CompileOptions::default())
+}
+
+/// Every file the project's dependency graph reaches, read from disk.
+fn project_files(dir: &str, entry: &str) -> HashMap<String, String> {
+    let base = fixture_dir(dir);
+    let graph = urd_compiler::compile(&format!("{}/{}", base, entry)).graph.expect("project should reach LINK");
+    graph
+        .nodes
+        .keys()
+        .map(|file| (file.clone(), std::fs::read_to_string(format!("{}/{}", base, file)).unwrap()))
+        .collect()
+}
+
+fn anonymize_files(entry: &str, files: &HashMap<String, String>) -> HashMap<String, String> {
+    let graph = compile_files(entry, files).graph.unwrap();
+    graph
+        .nodes
+        .iter()
+        .map(|(file, node)| (file.clone(), anonymize_source(&node.ast, &files[file])))
+        .collect()
+}
+
+/// `(code, line)` of every diagnostic, sorted.
+fn diagnostic_sites(result: &CompilationResult) -> Vec<(String, u32)> {
+    let mut sites: Vec<(String, u32)> =
+        result.diagnostics.all().iter().map(|d| (d.code.clone(), d.span.start_line)).collect();
+    sites.sort();
+    sites
+}
+
+// ── Placeholders ──
+
+#[test]
+fn placeholder_is_lorem_words_of_similar_length() {
+    let text = "The warden keeps the keys on a ring at her belt.";
+    let words = placeholder(text);
+    assert!(words.split(' ').all(|w| w.len() == 10 && w.starts_with("lorem-")), "{}", words);
+    assert!(words.len() >= text.len() && words.len() < text.len() + 11, "{}", words);
+    assert_eq!(placeholder("Hi."), placeholder("Hi."));
+    assert_ne!(placeholder("Hi."), placeholder("Ho."));
+    assert_eq!(placeholder("").split(' ').count(), 1);
+}
+
+#[test]
+fn placeholder_keeps_going_past_one_hash() {
+    let text = "word ".repeat(60);
+    let words = placeholder(&text);
+    assert!(words.len() >= text.len());
+    assert!(words.split(' ').count() > 16);
+}
+
+// ── Source rewriting ──
+
+#[test]
+fn only_text_is_rewritten() {
+    let source = "\
+---
+world:
+  name: yard
+  start: yard
+entities:
+  @warden: Person
+types:
+  Person [interactable]:
+    mood: enum(calm, angry) = calm
+---
+
+# Yard
+
+A cold yard. // Keep this comment.
+
+[@warden]
+
+-> north: Gate
+  ? @warden.mood == calm
+  ! The warden blocks the gate.
+
+== talk
+
+@warden: Who goes there?
+@warden folds her arms.
+
+* Ask about the gate
+  ? @warden.mood == calm
+  @warden: It stays shut
+    until dawn.
+  > @warden.mood = angry
+  -> talk
+
+# Gate
+
+The gate.
+";
+    let files = HashMap::from([("yard.urd.md".to_string(), source.to_string())]);
+    let anonymized = &anonymize_files("yard.urd.md", &files)["yard.urd.md"];
+    let expected = source
+        .replace("A cold yard.", &placeholder("A cold yard."))
+        .replace("The warden blocks the gate.", &placeholder("The warden blocks the gate."))
+        .replace("Who goes there?", &placeholder("Who goes there?"))
+        .replace("folds her arms.", &placeholder("folds her arms."))
+        .replace("It stays shut", &placeholder("It stays shut"))
+        .replace("    until dawn.", &format!("    {}", placeholder("until dawn.")))
+        .replace("The gate.\n", &format!("{}\n", placeholder("The gate.")));
+    assert_eq!(anonymized, &expected);
+}
+
+#[test]
+fn quoted_lines_keep_their_quotes() {
+    let source = "\
+---
+world:
+  name: yard
+  start: yard
+entities:
+  @warden: Person
+types:
+  Person [interactable]:
+    mood: enum(calm, angry) = calm
+---
+
+# Yard
+
+== talk
+
+@warden: Who goes there?
+
+* Ask about the gate
+  \"It stays shut
+    until dawn.\"
+
+\"Off with you.\"
+";
+    let files = HashMap::from([("yard.urd.md".to_string(), source.to_string())]);
+    let anonymized = &anonymize_files("yard.urd.md", &files)["yard.urd.md"];
+    let expected = source
+        .replace("Who goes there?", &placeholder("Who goes there?"))
+        .replace("It stays shut", &placeholder("It stays shut"))
+        .replace("until dawn.", &placeholder("until dawn."))
+        .replace("Off with you.", &placeholder("Off with you."));
+    assert_eq!(anonymized, &expected);
+}
+
+#[test]
+fn line_endings_and_byte_order_mark_are_kept() {
+    let source = "\u{feff}---\r\nworld:\r\n  name: yard\r\n  start: yard\r\n---\r\n\r\n# Yard\r\n\r\nA cold yard.\r\n";
+    let files = HashMap::from([("yard.urd.md".to_string(), source.to_string())]);
+    let anonymized = &anonymize_files("yard.urd.md", &files)["yard.urd.md"];
+    assert_eq!(anonymized, &source.replace("A cold yard.", &placeholder("A cold yard.")));
+}
+
+// ── Projects ──
+
+#[test]
+fn anonymized_projects_keep_ids_and_diagnostics() {
+    for &(dir, entry) in PROJECTS {
+        let files = project_files(dir, entry);
+        let original = compile_files(entry, &files);
+        let anonymized = compile_files(entry, &anonymize_files(entry, &files));
+        assert_eq!(diagnostic_sites(&anonymized), diagnostic_sites(&original), "{}/{}", dir, entry);
+        assert_eq!(anonymized.success, original.success, "{}/{}", dir, entry);
+
+        let (before, after) = (original.symbol_table.unwrap(), anonymized.symbol_table.unwrap());
+        assert!(before.entities.keys().eq(after.entities.keys()), "{}/{}", dir, entry);
+        assert!(before.locations.keys().eq(after.locations.keys()), "{}/{}", dir, entry);
+        assert!(before.sections.keys().eq(after.sections.keys()), "{}/{}", dir, entry);
+        assert!(before.actions.keys().eq(after.actions.keys()), "{}/{}", dir, entry);
+    }
+}
+
+#[test]
+fn anonymized_projects_differ_only_in_text() {
+    for &(dir, entry) in PROJECTS {
+        let files = project_files(dir, entry);
+        let original = compile_files(entry, &files);
+        let anonymized = compile_files(entry, &anonymize_files(entry, &files));
+        let report = diff::diff(&DiffSnapshot::from_compilation(&original), &DiffSnapshot::from_compilation(&anonymized));
+        assert!(report.changes.is_empty(), "{}/{}: {}", dir, entry, report.summary());
+
+        if let (Some(before), Some(after)) = (original.world, anonymized.world) {
+            assert_ne!(before, after, "{}/{}", dir, entry);
+            assert!(after.contains("lorem-"), "{}/{}", dir, entry);
+        }
+    }
+}
+
+#[test]
+fn anonymized_text_leaves_no_original_prose() {
+    let files = project_files("", "sunken-citadel.urd.md");
+    let original = compile_files("sunken-citadel.urd.md", &files);
+    let anonymized = compile_files("sunken-citadel.urd.md", &anonymize_files("sunken-citadel.urd.md", &files));
+    let before = urd_compiler::strings::from_compilation(&original).unwrap();
+    let after = urd_compiler::strings::from_compilation(&anonymized).unwrap();
+    assert_eq!(before.len(), after.len());
+    for (old, new) in before.entries.iter().zip(&after.entries) {
+        assert_eq!(old.key, new.key);
+        // Choice labels are kept, since choice IDs are slugified from them.
+        if old.key.ends_with("/label") {
+            assert_eq!(old.text, new.text);
+        } else {
+            // A stage direction's emitted text starts with the entity ID.
+            assert!(new.text.contains("lorem-") && !new.text.contains(&old.text), "{}: {}", new.key, new.text);
+        }
+    }
+}
+
+#[test]
+fn anonymizing_is_deterministic() {
+    for &(dir, entry) in PROJECTS {
+        let files = project_files(dir, entry);
+        assert_eq!(anonymize_files(entry, &files), anonymize_files(entry, &files), "{}/{}", dir, entry);
+    }
+}
@@ -0,0 +1,216 @@
+/// Tests for the compilation cache.
+///
+/// Sources live in an in-memory file system that counts reads, so a test
+/// can tell a cache hit (each manifest file read once, to hash it) from a
+/// compile (each import read again by IMPORT).
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use urd_compiler::cache::{compile_cached, CacheStatus, CacheStore, COMPILER_VERSION};
+use urd_compiler::import::{FileReadError, FileReader};
+use urd_compiler::CompileOptions;
+
+// ── Helpers ──
+
+/// In-memory file system that counts reads per path.
+#[derive(Default)]
+struct CountingFs {
+    files: RefCell<HashMap<String, String>>,
+    reads: RefCell<HashMap<String, usize>>,
+}
+
+impl CountingFs {
+    fn write(&self, path: &str, content: &str) {
+        self.files.borrow_mut().insert(path.to_string(), content.to_string());
+    }
+
+    fn reads(&self, path: &str) -> usize {
+        self.reads.borrow().get(path).copied().unwrap_or(0)
+    }
+
+    fn reset_reads(&self) {
+        self.reads.borrow_mut().clear();
+    }
+}
+
+impl FileReader for CountingFs {
+    fn read_file(&self, fs_path: &str) -> Result<String, FileReadError> {
+        *self.reads.borrow_mut().entry(fs_path.to_string()).or_default() += 1;
+        self.files.borrow().get(fs_path).cloned().ok_or(FileReadError::NotFound)
+    }
+
+    fn canonical_filename(&self, _dir: &str, _filename: &str) -> Option<String> {
+        None
+    }
+}
+
+#[derive(Default)]
+struct MemoryStore {
+    entries: RefCell<HashMap<String, String>>,
+}
+
+impl CacheStore for MemoryStore {
+    fn load(&self, key: &str) -> Option<String> {
+        self.entries.borrow().get(key).cloned()
+    }
+
+    fn store(&self, key: &str, contents: &str) -> Result<(), String> {
+        self.entries.borrow_mut().insert(key.to_string(), contents.to_string());
+        Ok(())
+    }
+}
+
+const MAIN: &str = "\
+---
+import: ./types.urd.md
+world:
+  name: cache-test
+  start: cellar
+entities:
+  @lamp: Lamp
+---
+
+# Cellar
+
+A damp cellar.
+
+[@lamp]
+
+== look
+
+* Look around
+  @lamp flickers.
+
+* Leave
+  -> end
+";
+
+const TYPES: &str = "\
+---
+types:
+  Lamp [portable]:
+    lit: bool = false
+---
+";
+
+fn project() -> CountingFs {
+    let fs = CountingFs::default();
+    fs.write("types.urd.md", TYPES);
+    fs
+}
+
+fn compile(fs: &CountingFs, store: &MemoryStore) -> (CacheStatus, String, Vec<String>) {
+    let cached = compile_cached("main.urd.md", MAIN, fs, &CompileOptions::default(), store);
+    assert!(cached.store_error.is_none());
+    let diagnostics = cached
+        .result
+        .diagnostics
+        .sorted()
+        .iter()
+        .map(|d| format!("{} {} {}", d.code, d.span, d.message))
+        .collect();
+    (cached.status, cached.result.world.expect("world"), diagnostics)
+}
+
+// ── Tests ──
+
+#[test]
+fn cold_compile_is_a_miss() {
+    let fs = project();
+    let store = MemoryStore::default();
+    let (status, _, _) = compile(&fs, &store);
+    assert_eq!(status, CacheStatus::Miss);
+    assert_eq!(store.entries.borrow().len(), 1);
+}
+
+#[test]
+fn warm_hit_is_byte_identical_and_skips_compile() {
+    let fs = project();
+    let store = MemoryStore::default();
+    let (_, cold_world, cold_diagnostics) = compile(&fs, &store);
+    assert_eq!(fs.reads("types.urd.md"), 1);
+
+    fs.reset_reads();
+    let (status, warm_world, warm_diagnostics) = compile(&fs, &store);
+    assert_eq!(status, CacheStatus::Hit);
+    assert_eq!(warm_world, cold_world);
+    assert_eq!(warm_diagnostics, cold_diagnostics);
+    // One read to hash the import; IMPORT would have read it again.
+    assert_eq!(fs.reads("types.urd.md"), 1);
+}
+
+#[test]
+fn warm_hit_carries_the_typed_world() {
+    let fs = project();
+    let store = MemoryStore::default();
+    let cold = compile_cached("main.urd.md", MAIN, &fs, &CompileOptions::default(), &store);
+    let warm = compile_cached("main.urd.md", MAIN, &fs, &CompileOptions::default(), &store);
+    assert_eq!(warm.status, CacheStatus::Hit);
+    assert!(warm.result.world_value.is_some());
+    assert_eq!(warm.result.world_value, cold.result.world_value);
+}
+
+#[test]
+fn warm_hit_replays_diagnostics() {
+    let fs = project();
+    let store = MemoryStore::default();
+    let (_, _, cold_diagnostics) = compile(&fs, &store);
+    assert!(!cold_diagnostics.is_empty(), "fixture should produce diagnostics");
+    let (_, _, warm_diagnostics) = compile(&fs, &store);
+    assert_eq!(warm_diagnostics, cold_diagnostics);
+}
+
+#[test]
+fn editing_imported_file_invalidates() {
+    let fs = project();
+    let store = MemoryStore::default();
+    let (_, cold_world, _) = compile(&fs, &store);
+
+    fs.write("types.urd.md", &TYPES.replace("lit: bool = false", "lit: bool = true"));
+    let (status, world, _) = compile(&fs, &store);
+    assert_eq!(status, CacheStatus::Stale);
+    assert_ne!(world, cold_world);
+
+    // The refreshed entry is a hit on the next run.
+    let (status, warm_world, _) = compile(&fs, &store);
+    assert_eq!(status, CacheStatus::Hit);
+    assert_eq!(warm_world, world);
+}
+
+#[test]
+fn version_change_invalidates() {
+    let fs = project();
+    let store = MemoryStore::default();
+    compile(&fs, &store);
+
+    for entry in store.entries.borrow_mut().values_mut() {
+        let version = format!("\"version\":\"{}\"", COMPILER_VERSION);
+        assert!(entry.contains(&version));
+        *entry = entry.replace(&version, "\"version\":\"0.0.0\"");
+    }
+    let (status, _, _) = compile(&fs, &store);
+    assert_eq!(status, CacheStatus::Stale);
+}
+
+#[test]
+fn corrupt_entry_is_a_miss() {
+    let fs = project();
+    let store = MemoryStore::default();
+    compile(&fs, &store);
+
+    for entry in store.entries.borrow_mut().values_mut() {
+        entry.truncate(entry.len() / 2);
+    }
+    let (status, _, _) = compile(&fs, &store);
+    assert_eq!(status, CacheStatus::Miss);
+}
+
+#[test]
+fn failed_compile_is_not_stored() {
+    let fs = CountingFs::default();
+    let store = MemoryStore::default();
+    let cached = compile_cached("main.urd.md", MAIN, &fs, &CompileOptions::default(), &store);
+    assert!(!cached.result.success);
+    assert!(store.entries.borrow().is_empty());
+}
@@ -0,0 +1,99 @@
+/// Tests for the diagnostic code catalog.
+///
+/// The catalog must cover every code the compiler can emit, so these tests
+/// scan the compiler source for `"URDnnn"` string literals rather than
+/// relying on the test suite to trigger each one. The reference document
+/// is checked the same way, so a new code cannot ship undocumented.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use urd_compiler::diagnostics::catalog::{self, CATALOG, DOCS_URL};
+
+// ── Helpers ──
+
+/// Every `"URDnnn"` string literal in the `.rs` files under `dir`.
+fn code_literals(dir: &Path, codes: &mut BTreeSet<String>) {
+    for entry in std::fs::read_dir(dir).expect("read source directory") {
+        let path = entry.expect("directory entry").path();
+        if path.is_dir() {
+            code_literals(&path, codes);
+            continue;
+        }
+        if path.extension().and_then(|e| e.to_str()) != Some("rs") {
+            continue;
+        }
+        let source = std::fs::read_to_string(&path).expect("read source file");
+        for (start, _) in source.match_indices("\"URD") {
+            let Some(literal) = source.get(start + 1..start + 7) else {
+                continue;
+            };
+            let closed = source[start + 7..].starts_with('"');
+            if closed && literal[3..].bytes().all(|b| b.is_ascii_digit()) {
+                codes.insert(literal.to_string());
+            }
+        }
+    }
+}
+
+/// The codes with a row in a diagnostic reference table.
+fn documented_codes(relative: &str) -> BTreeSet<String> {
+    let path = format!("{}/../../{}", env!("CARGO_MANIFEST_DIR"), relative);
+    let doc = std::fs::read_to_string(&path).expect("read diagnostic reference");
+    doc.lines()
+        .filter_map(|line| line.strip_prefix("| URD"))
+        .filter_map(|rest| rest.get(..3))
+        .filter(|digits| digits.bytes().all(|b| b.is_ascii_digit()))
+        .map(|digits| format!("URD{}", digits))
+        .collect()
+}
+
+fn catalog_codes() -> BTreeSet<String> {
+    CATALOG.iter().map(|c| c.code.to_string()).collect()
+}
+
+// ── Tests ──
+
+#[test]
+fn every_emitted_code_has_a_catalog_entry() {
+    let mut emitted = BTreeSet::new();
+    code_literals(&Path::new(env!("CARGO_MANIFEST_DIR")).join("src"), &mut emitted);
+    assert!(emitted.len() > 50, "source scan found too few codes: {:?}", emitted);
+
+    let missing: Vec<&String> = emitted.iter().filter(|c| catalog::lookup(c).is_none()).collect();
+    assert!(missing.is_empty(), "codes emitted without a catalog entry: {:?}", missing);
+}
+
+#[test]
+fn catalog_matches_reference_documents() {
+    for doc in ["docs/urd-diagnostic-codes.md", "content/documents/diagnostic-codes.md"] {
+        assert_eq!(documented_codes(doc), catalog_codes(), "catalog and {} disagree", doc);
+    }
+}
+
+#[test]
+fn catalog_is_ordered_and_unique() {
+    let codes: Vec<&str> = CATALOG.iter().map(|c| c.code).collect();
+    let mut sorted = codes.clone();
+    sorted.sort();
+    sorted.dedup();
+    assert_eq!(codes, sorted);
+}
+
+#[test]
+fn catalog_entries_are_complete() {
+    for info in CATALOG {
+        assert_eq!(info.slug, info.code.to_ascii_lowercase(), "slug for {}", info.code);
+        assert!(!info.title.is_empty(), "title for {}", info.code);
+        assert!(info.explanation.ends_with('.'), "explanation for {}", info.code);
+    }
+}
+
+#[test]
+fn lookup_finds_code_and_builds_docs_url() {
+    let info = catalog::lookup("URD433").expect("URD433 in catalog");
+    assert_eq!(info.code, "URD433");
+    assert_eq!(info.docs_url(), format!("{}#urd433", DOCS_URL));
+    assert!(catalog::lookup("URD999").is_none());
+    assert!(catalog::lookup("urd433").is_none());
+}
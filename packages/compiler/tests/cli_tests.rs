@@ -0,0 +1,527 @@
+/// Tests for the command-line contract: exit statuses, `--quiet` and
+/// `--verbose`, diagnostic styles, help text, and the exit code of each
+/// subcommand.
+
+use std::process::{Command, Output};
+
+use urd_compiler::cli::{self, CliError, DiagnosticStyle, ExitStatus, Verbosity, COMMANDS};
+use urd_compiler::diagnostics::{Diagnostic, DiagnosticCollector, Severity};
+use urd_compiler::span::Span;
+
+// ── Helpers ──
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn urd(list: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_urd")).args(list).output().unwrap()
+}
+
+fn code(output: &Output) -> i32 {
+    output.status.code().unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
+
+fn diagnostic(severity: Severity) -> Diagnostic {
+    Diagnostic {
+        severity,
+        code: "URD000".to_string(),
+        message: "Test.".to_string(),
+        span: Span::new("test.urd.md".to_string(), 1, 1, 1, 2),
+        suggestion: None,
+        fix: None,
+        related: Vec::new(),
+    }
+}
+
+// ── Exit status ──
+
+#[test]
+fn exit_status_codes() {
+    assert_eq!(ExitStatus::Success.code(), 0);
+    assert_eq!(ExitStatus::Failure.code(), 1);
+    assert_eq!(ExitStatus::Error.code(), 2);
+}
+
+#[test]
+fn exit_status_fails_only_on_errors() {
+    let mut diagnostics = DiagnosticCollector::new();
+    diagnostics.emit(diagnostic(Severity::Warning));
+    diagnostics.emit(diagnostic(Severity::Info));
+    assert_eq!(ExitStatus::from_diagnostics(&diagnostics), ExitStatus::Success);
+    diagnostics.emit(diagnostic(Severity::Error));
+    assert_eq!(ExitStatus::from_diagnostics(&diagnostics), ExitStatus::Failure);
+}
+
+#[test]
+fn usage_error_quotes_the_command_synopsis() {
+    assert_eq!(
+        CliError::usage("diff").to_string(),
+        "Usage: urd diff <file_a> <file_b> [--format json|summary]"
+    );
+}
+
+// ── Verbosity ──
+
+#[test]
+fn verbosity_flags_are_taken_from_anywhere() {
+    let (verbosity, rest) = cli::take_verbosity(&args(&["diff", "a", "--quiet", "b"])).unwrap();
+    assert_eq!(verbosity, Verbosity::Quiet);
+    assert_eq!(rest, args(&["diff", "a", "b"]));
+
+    let (verbosity, rest) = cli::take_verbosity(&args(&["--verbose", "world.urd.md"])).unwrap();
+    assert_eq!(verbosity, Verbosity::Verbose);
+    assert_eq!(rest, args(&["world.urd.md"]));
+
+    let (verbosity, _) = cli::take_verbosity(&args(&["world.urd.md"])).unwrap();
+    assert_eq!(verbosity, Verbosity::Normal);
+}
+
+#[test]
+fn quiet_and_verbose_together_is_a_usage_error() {
+    let error = cli::take_verbosity(&args(&["--quiet", "--verbose"])).unwrap_err();
+    assert_eq!(error.to_string(), "Use either --quiet or --verbose, not both.");
+}
+
+#[test]
+fn quiet_shows_only_errors() {
+    assert!(Verbosity::Quiet.shows(Severity::Error));
+    assert!(!Verbosity::Quiet.shows(Severity::Warning));
+    assert!(!Verbosity::Quiet.shows(Severity::Info));
+    for verbosity in [Verbosity::Normal, Verbosity::Verbose] {
+        assert!(verbosity.shows(Severity::Info));
+        assert!(verbosity.shows(Severity::Warning));
+    }
+    assert!(Verbosity::Verbose.progress());
+    assert!(!Verbosity::Normal.progress());
+}
+
+// ── Diagnostic style ──
+
+#[test]
+fn diagnostic_style_defaults_to_pretty_on_a_terminal() {
+    let (style, rest) = cli::take_diagnostic_style(&args(&["world.urd.md"]), true).unwrap();
+    assert_eq!((style, rest), (DiagnosticStyle::Pretty, args(&["world.urd.md"])));
+    let (style, _) = cli::take_diagnostic_style(&args(&["world.urd.md"]), false).unwrap();
+    assert_eq!(style, DiagnosticStyle::Plain);
+
+    let (style, rest) = cli::take_diagnostic_style(&args(&["--pretty-diagnostics", "stats", "w"]), false).unwrap();
+    assert_eq!((style, rest), (DiagnosticStyle::Pretty, args(&["stats", "w"])));
+    let (style, _) = cli::take_diagnostic_style(&args(&["w", "--plain-diagnostics"]), true).unwrap();
+    assert_eq!(style, DiagnosticStyle::Plain);
+
+    let error = cli::take_diagnostic_style(&args(&["--pretty-diagnostics", "--plain-diagnostics"]), true).unwrap_err();
+    assert_eq!(error.to_string(), "Use either --pretty-diagnostics or --plain-diagnostics, not both.");
+}
+
+#[test]
+fn diagnostics_format_selects_json() {
+    let (style, rest) = cli::take_diagnostic_style(&args(&["--diagnostics-format", "json", "w"]), true).unwrap();
+    assert_eq!((style, rest), (DiagnosticStyle::Json, args(&["w"])));
+    let (style, rest) = cli::take_diagnostic_style(&args(&["w", "--diagnostics-format", "text"]), true).unwrap();
+    assert_eq!((style, rest), (DiagnosticStyle::Pretty, args(&["w"])));
+
+    for list in [&["--diagnostics-format", "xml"][..], &["--diagnostics-format"]] {
+        let error = cli::take_diagnostic_style(&args(list), false).unwrap_err();
+        assert_eq!(error.to_string(), "--diagnostics-format takes 'text' or 'json'.");
+    }
+    let error = cli::take_diagnostic_style(&args(&["--diagnostics-format", "json", "--pretty-diagnostics"]), false)
+        .unwrap_err();
+    assert_eq!(error.to_string(), "--pretty-diagnostics and --plain-diagnostics do not apply to JSON diagnostics.");
+}
+
+#[test]
+fn json_diagnostics_are_one_array_on_stderr() {
+    let output = urd(&["--diagnostics-format", "json", "--quiet", &fixture("negative-unresolved-entity.urd.md")]);
+    assert_eq!(code(&output), 1);
+    let text = stderr(&output);
+    assert_eq!(text.lines().count(), 1, "{}", text);
+
+    // Every diagnostic, even with --quiet.
+    let diagnostics: serde_json::Value = serde_json::from_str(&text).unwrap();
+    let codes: Vec<&str> = diagnostics.as_array().unwrap().iter().map(|d| d["code"].as_str().unwrap()).collect();
+    assert_eq!(codes, ["URD612", "URD301"]);
+    assert!(diagnostics[0]["suggestion"].is_string());
+    assert!(diagnostics[1].get("suggestion").is_none());
+    let unresolved = &diagnostics[1];
+    assert_eq!(unresolved["severity"], "error");
+    assert_eq!(unresolved["message"], "Unresolved entity reference '@ghost'.");
+    assert_eq!(unresolved["span"]["start_line"], 8);
+    assert!(unresolved["span"]["file"].as_str().unwrap().ends_with("negative-unresolved-entity.urd.md"));
+}
+
+#[test]
+fn pretty_diagnostics_show_the_source_line() {
+    let output = urd(&[&fixture("negative-unresolved-entity.urd.md"), "--pretty-diagnostics"]);
+    assert_eq!(code(&output), 1);
+    let text = stderr(&output);
+    assert!(text.contains("error[URD301]: "), "{}", text);
+    assert!(text.contains(" --> negative-unresolved-entity.urd.md:"), "{}", text);
+    assert!(text.contains(" | ") && text.contains('^'), "{}", text);
+
+    // Output captured by a pipe is plain unless asked otherwise.
+    let plain = stderr(&urd(&[&fixture("negative-unresolved-entity.urd.md")]));
+    assert!(plain.contains("[error] ") && !plain.contains(" --> "), "{}", plain);
+}
+
+// ── Help ──
+
+#[test]
+fn full_help_lists_every_command() {
+    let text = cli::help(None).unwrap();
+    for command in COMMANDS {
+        assert!(text.contains(command.usage), "missing usage for {}", command.name);
+        assert!(text.contains(command.details), "missing details for {}", command.name);
+    }
+    assert!(text.contains("EXIT CODES:"));
+}
+
+#[test]
+fn command_help_shows_one_command() {
+    let text = cli::help(Some("snapshot")).unwrap();
+    assert!(text.starts_with("Usage: urd snapshot <file.urd.md>"), "{}", text);
+    assert!(!text.contains("diff <a> <b>"), "{}", text);
+    assert!(text.contains("--quiet"));
+}
+
+#[test]
+fn help_for_unknown_command_is_an_error() {
+    let error = cli::help(Some("frobnicate")).unwrap_err();
+    assert!(error.to_string().starts_with("Unknown command 'frobnicate'."), "{}", error);
+}
+
+// ── Version ──
+
+#[test]
+fn version_json_is_stable() {
+    let output = urd(&["--version", "--json"]);
+    assert_eq!(code(&output), 0);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "name": "urd-compiler",
+            "version": env!("CARGO_PKG_VERSION"),
+            "formats": ["1", "2"],
+            "features": [],
+        })
+    );
+
+    let text = String::from_utf8(urd(&["-V"]).stdout).unwrap();
+    assert_eq!(text, format!("urd {} (formats: 1, 2)\n", env!("CARGO_PKG_VERSION")));
+    assert_eq!(code(&urd(&["--version", "--yaml"])), 2);
+}
+
+#[test]
+fn compile_names_the_generator_unless_told_not_to() {
+    let generator = |extra: &[&str]| {
+        let world = fixture("locked-garden.urd.md");
+        let output = urd(&[&[world.as_str()], extra].concat());
+        assert_eq!(code(&output), 0, "{}", stderr(&output));
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["world"].get("generator").cloned()
+    };
+    assert_eq!(generator(&[]), Some(serde_json::json!(urd_compiler::version::generator())));
+    assert_eq!(generator(&["--no-generator"]), None);
+}
+
+#[test]
+fn compile_writes_metadata_beside_the_world() {
+    let dir = std::env::temp_dir().join(format!("urd-cli-meta-{}", std::process::id()));
+    let out = dir.join("world.urd.json");
+    let (out_str, main) = (out.to_string_lossy(), fixture("interrogation/main.urd.md"));
+    let output = urd(&[&main, "-o", &out_str, "--meta", "--reproducible"]);
+    assert_eq!(code(&output), 0, "{}", stderr(&output));
+    let first = std::fs::read_to_string(dir.join("world.urd.meta.json")).unwrap();
+    let meta: serde_json::Value = serde_json::from_str(&first).unwrap();
+    assert_eq!(meta["summary"]["warnings"], 1);
+    assert!(meta.get("timings").is_none());
+    assert!(out.exists());
+
+    // Reproducible metadata is the same on every run; without the flag it has timings.
+    assert_eq!(code(&urd(&[&main, "-o", &out_str, "--meta", "--reproducible"])), 0);
+    assert_eq!(std::fs::read_to_string(dir.join("world.urd.meta.json")).unwrap(), first);
+    let timed = dir.join("timed.json");
+    assert_eq!(code(&urd(&[&main, "--meta", &timed.to_string_lossy()])), 0);
+    let meta: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&timed).unwrap()).unwrap();
+    assert!(meta["timings"]["parse"].is_number());
+
+    // A bare --meta needs somewhere to put it.
+    assert_eq!(code(&urd(&[&main, "--meta"])), 2);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn compile_writes_the_world_to_output() {
+    let dir = std::env::temp_dir().join(format!("urd-cli-output-{}", std::process::id()));
+    let out = dir.join("build/nested/world.urd.json");
+    let out_str = out.to_string_lossy().to_string();
+    let output = urd(&[&fixture("monty-hall.urd.md"), "--output", &out_str]);
+    assert_eq!(code(&output), 0, "{}", stderr(&output));
+    assert!(output.stdout.is_empty());
+    let written = std::fs::read_to_string(&out).unwrap();
+    assert!(stderr(&output).ends_with(&format!("Wrote {} bytes to {}\n", written.len(), out_str)), "{}", stderr(&output));
+    serde_json::from_str::<serde_json::Value>(&written).unwrap();
+
+    // Bare, the world goes beside the source.
+    let source = dir.join("garden.urd.md");
+    std::fs::copy(fixture("locked-garden.urd.md"), &source).unwrap();
+    let source_str = source.to_string_lossy().to_string();
+    assert_eq!(code(&urd(&[&source_str, "-o", "--no-generator"])), 0);
+    assert!(dir.join("garden.urd.json").exists());
+
+    // Never over the input, however the path is spelled.
+    let original = std::fs::read_to_string(&source).unwrap();
+    let respelled = dir.join("build/../garden.urd.md").to_string_lossy().to_string();
+    let output = urd(&[&source_str, "-o", &respelled]);
+    assert_eq!(code(&output), 2);
+    assert!(stderr(&output).contains("it is the input file"), "{}", stderr(&output));
+    assert_eq!(std::fs::read_to_string(&source).unwrap(), original);
+
+    // A directory that cannot be created is an error, not a silent skip.
+    let blocked = dir.join("garden.urd.md/world.urd.json").to_string_lossy().to_string();
+    let output = urd(&[&source_str, "-o", &blocked]);
+    assert_eq!(code(&output), 2);
+    assert!(stderr(&output).contains("Cannot create"), "{}", stderr(&output));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// ── Subcommand exit codes ──
+
+#[test]
+fn compile_exit_codes() {
+    assert_eq!(code(&urd(&[&fixture("monty-hall.urd.md")])), 0);
+    assert_eq!(code(&urd(&[&fixture("negative-unresolved-entity.urd.md")])), 1);
+    assert_eq!(code(&urd(&[&fixture("no-such-file.urd.md")])), 2);
+    assert_eq!(code(&urd(&[&fixture("monty-hall.urd.md"), "--no-such-flag"])), 2);
+}
+
+#[test]
+fn diff_exit_codes() {
+    let a = fixture("diff/diff-a-minimal.urd.md");
+    let b = fixture("diff/diff-b-minimal.urd.md");
+    assert_eq!(code(&urd(&["diff", &a, &a])), 0);
+    assert_eq!(code(&urd(&["diff", &a, &b])), 1);
+    assert_eq!(code(&urd(&["diff", &a, &fixture("no-such-file.urd.md")])), 2);
+    assert_eq!(code(&urd(&["diff", &a])), 2);
+    assert_eq!(code(&urd(&["diff", &a, &b, "--format", "xml"])), 2);
+}
+
+#[test]
+fn snapshot_exit_codes() {
+    let out = std::env::temp_dir().join(format!("urd-cli-{}.urd.snapshot.json", std::process::id()));
+    let out = out.to_string_lossy();
+    assert_eq!(code(&urd(&["snapshot", &fixture("monty-hall.urd.md"), "-o", &out])), 0);
+    assert_eq!(code(&urd(&["snapshot", &fixture("no-such-file.urd.md"), "-o", &out])), 2);
+    assert_eq!(code(&urd(&["snapshot"])), 2);
+    let _ = std::fs::remove_file(&*out);
+}
+
+#[test]
+fn other_subcommand_usage_errors_exit_2() {
+    for list in [
+        &["stats"][..],
+        &["strings", "a.urd.md", "--format", "xml"],
+        &["fix"],
+        &["anonymize"],
+        &["doc"],
+        &["doc", "a.urd.md", "--format", "pdf"],
+        &["flow"],
+        &["flow", "a.urd.md", "--format", "svg"],
+        &["play"],
+        &["play", "a.urd.md", "--open"],
+        &["imports"],
+        &["imports", "a.urd.md", "--deep"],
+        &["locate"],
+        &["locate", "a.urd.md"],
+        &["locate", "a.urd.md", "--line", "0"],
+        &["fragment", "a.md"],
+        &["fragment", "--kind", "rule", "a.md"],
+        &["symbols"],
+        &["watch"],
+        &["explain"],
+        &["explain", "URD999"],
+        &["schema", "extra"],
+        &["--help", "frobnicate"],
+    ] {
+        let output = urd(list);
+        assert_eq!(code(&output), 2, "{:?}: {}", list, stderr(&output));
+    }
+    assert_eq!(code(&urd(&["explain", "URD301"])), 0);
+    assert_eq!(code(&urd(&["--help", "diff"])), 0);
+}
+
+#[test]
+fn symbols_exit_codes() {
+    let output = urd(&["symbols", &fixture("monty-hall.urd.md")]);
+    assert_eq!(code(&output), 0);
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(json["entities"].is_array());
+    assert_eq!(code(&urd(&["symbols", &fixture("negative-type-mismatch.urd.md")])), 1);
+    assert_eq!(code(&urd(&["symbols", &fixture("no-such-file.urd.md")])), 2);
+}
+
+#[test]
+fn anonymize_writes_every_project_file() {
+    let out = std::env::temp_dir().join(format!("urd-cli-anonymize-{}", std::process::id()));
+    let out_str = out.to_string_lossy();
+    let output = urd(&["anonymize", &fixture("interrogation/main.urd.md"), "-o", &out_str]);
+    assert_eq!(code(&output), 0, "{}", stderr(&output));
+    let main = std::fs::read_to_string(out.join("main.urd.md")).unwrap();
+    assert!(main.contains("lorem-"), "{}", main);
+    assert_eq!(code(&urd(&[&out.join("main.urd.md").to_string_lossy()])), 0);
+    assert_eq!(code(&urd(&["anonymize", &fixture("no-such-file.urd.md"), "-o", &out_str])), 2);
+    let _ = std::fs::remove_dir_all(&out);
+}
+
+#[test]
+fn doc_writes_one_page_per_category() {
+    let out = std::env::temp_dir().join(format!("urd-cli-doc-{}", std::process::id()));
+    let out_str = out.to_string_lossy();
+    let output = urd(&["doc", &fixture("two-room-key-puzzle.urd.md"), "-o", &out_str, "--format", "html"]);
+    assert_eq!(code(&output), 0, "{}", stderr(&output));
+    for page in ["index", "types", "entities", "locations", "dialogue"] {
+        assert!(out.join(format!("{}.html", page)).exists(), "{}", page);
+    }
+    assert_eq!(code(&urd(&["doc", &fixture("negative-type-mismatch.urd.md"), "-o", &out_str])), 1);
+    assert_eq!(code(&urd(&["doc", &fixture("no-such-file.urd.md"), "-o", &out_str])), 2);
+    let _ = std::fs::remove_dir_all(&out);
+}
+
+#[test]
+fn flow_prints_the_graph() {
+    let output = urd(&["flow", &fixture("dialogue-flow.urd.md"), "--format", "mermaid"]);
+    assert_eq!(code(&output), 0, "{}", stderr(&output));
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("flowchart LR\n"));
+    let output = urd(&["flow", &fixture("dialogue-flow.urd.md"), "--section", "ship"]);
+    assert_eq!(code(&output), 2);
+    assert!(stderr(&output).contains("Unknown section 'ship'."), "{}", stderr(&output));
+}
+
+#[test]
+fn play_writes_the_page() {
+    let out = std::env::temp_dir().join(format!("urd-play-{}.html", std::process::id()));
+    let out_str = out.to_string_lossy().to_string();
+    let output = urd(&["play", &fixture("tavern-scene.urd.md"), "-o", &out_str]);
+    assert_eq!(code(&output), 0, "{}", stderr(&output));
+    assert!(std::fs::read_to_string(&out).unwrap().contains("id=\"urd-world\""));
+    let _ = std::fs::remove_file(&out);
+
+    let output = urd(&["play", &fixture("monty-hall.urd.md")]);
+    assert_eq!(code(&output), 2);
+    assert!(stderr(&output).contains("It uses `> reveal` effects, rules, sequences"), "{}", stderr(&output));
+    assert_eq!(code(&urd(&["play", &fixture("negative-type-mismatch.urd.md")])), 1);
+}
+
+#[test]
+fn imports_prints_the_plan() {
+    let output = urd(&["imports", &fixture("qualified-sections/main.urd.md"), "--full"]);
+    assert_eq!(code(&output), 0, "{}", stderr(&output));
+    let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+    assert!(stdout.starts_with("main.urd.md (full plan)\n  './tavern.urd.md': read "), "{}", stdout);
+    assert!(stdout.ends_with("qualified-sections/tavern.urd.md\n1 file read\n"), "{}", stdout);
+
+    let path = std::env::temp_dir().join(format!("urd-cli-imports-{}.urd.md", std::process::id()));
+    std::fs::write(&path, "---\nimport: ../../outside.urd.md\n---\n").unwrap();
+    let output = urd(&["imports", &path.to_string_lossy()]);
+    assert_eq!(code(&output), 1);
+    assert!(
+        String::from_utf8_lossy(&output.stdout).contains("'../../outside.urd.md': URD208 resolves outside the project root\n"),
+        "{}",
+        String::from_utf8_lossy(&output.stdout),
+    );
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn locate_prints_json_pointers() {
+    let citadel = fixture("sunken-citadel.urd.md");
+    let output = urd(&["--quiet", "locate", &citadel, "--line", "226"]);
+    assert_eq!(code(&output), 0, "{}", stderr(&output));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "/dialogue/sunken-citadel~1square_talk/choices/1/effects/1\n");
+
+    // A prose line produces nothing.
+    let output = urd(&["--quiet", "locate", &citadel, "--line", "184"]);
+    assert_eq!(code(&output), 1);
+    assert!(output.stdout.is_empty());
+    assert!(stderr(&output).contains("sunken-citadel.urd.md:184 produces no output."), "{}", stderr(&output));
+
+    // An imported file, named as diagnostics name it.
+    let main = fixture("qualified-sections/main.urd.md");
+    let output = urd(&["--quiet", "locate", &main, "--line", "5", "--file", "tavern.urd.md"]);
+    assert_eq!(code(&output), 0, "{}", stderr(&output));
+    assert!(String::from_utf8_lossy(&output.stdout).starts_with("/dialogue/tavern~1topics/choices/0\n"));
+    assert_eq!(code(&urd(&["locate", &main, "--line", "5", "--file", "cellar.urd.md"])), 2);
+}
+
+#[test]
+fn fragment_prints_the_section_json() {
+    let path = std::env::temp_dir().join(format!("urd-cli-fragment-{}.md", std::process::id()));
+    std::fs::write(&path, "== talk\n\n@keeper: Mind the steps.\n").unwrap();
+    let path_str = path.to_string_lossy();
+    let output = urd(&["fragment", "--kind", "section", &path_str]);
+    assert_eq!(code(&output), 0, "{}", stderr(&output));
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(json["id"], "fragment/talk");
+    assert!(stderr(&output).contains(&format!("[warning] {}:3:1: Unresolved entity reference '@keeper'.", path_str)));
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn compile_define_sets_guard_flags() {
+    let path = std::env::temp_dir().join(format!("urd-cli-define-{}.urd.md", std::process::id()));
+    let source = "---\nworld:\n  name: w\n---\n# Hall\n\n?if demo:\n# Demo Room\n?endif\n?if beta:\n# Beta Room\n?endif\n";
+    std::fs::write(&path, source).unwrap();
+    let path_str = path.to_string_lossy();
+    let locations = |list: &[&str]| {
+        let output = urd(list);
+        assert_eq!(code(&output), 0, "{}", stderr(&output));
+        let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        json["locations"].as_object().unwrap().keys().cloned().collect::<Vec<_>>()
+    };
+    assert_eq!(locations(&[&path_str]), ["hall"]);
+    assert_eq!(locations(&[&path_str, "--define", "demo"]), ["hall", "demo-room"]);
+    assert_eq!(locations(&[&path_str, "--define", "demo", "--define", "beta"]), ["hall", "demo-room", "beta-room"]);
+    assert_eq!(code(&urd(&[&path_str, "--define"])), 2);
+    let _ = std::fs::remove_file(&path);
+}
+
+// ── Quiet and verbose output ──
+
+#[test]
+fn quiet_hides_warnings_and_info() {
+    let world = fixture("monty-hall.urd.md");
+    let normal = stderr(&urd(&[&world]));
+    assert!(normal.contains("[warning]"), "{}", normal);
+    assert!(normal.contains("[info]"), "{}", normal);
+
+    let quiet = urd(&[&world, "--quiet"]);
+    assert_eq!(code(&quiet), 0);
+    assert_eq!(stderr(&quiet), "");
+}
+
+#[test]
+fn quiet_keeps_errors() {
+    let output = urd(&["--quiet", &fixture("negative-unresolved-entity.urd.md")]);
+    assert_eq!(code(&output), 1);
+    assert!(stderr(&output).contains("[error]"), "{}", stderr(&output));
+    assert!(!stderr(&output).contains("[warning]"), "{}", stderr(&output));
+}
+
+#[test]
+fn verbose_prints_phase_progress() {
+    let output = urd(&["stats", &fixture("monty-hall.urd.md"), "--verbose"]);
+    assert_eq!(code(&output), 0);
+    let text = stderr(&output);
+    assert!(text.contains("PARSE...\n"), "{}", text);
+    assert!(text.contains("parsed monty-hall.urd.md"), "{}", text);
+    assert!(text.contains("EMIT done in"), "{}", text);
+}
@@ -126,14 +126,32 @@ fn definition_index_exit_kind_has_destination() {
         DefinitionKind::Exit {
             from_location,
             destination,
+            resolved,
         } => {
             assert_eq!(from_location, "gatehouse");
             assert_eq!(destination, "the-walled-garden");
+            assert!(resolved);
         }
         other => panic!("Expected Exit kind, got {:?}", other),
     }
 }
 
+#[test]
+fn definition_index_location_kind_has_description() {
+    let index = fixture_index("locked-garden.urd.md");
+    let entry = index.get("location:gatehouse").unwrap();
+    match &entry.kind {
+        DefinitionKind::Location {
+            display_name,
+            description,
+        } => {
+            assert_eq!(display_name, "Gatehouse");
+            assert_eq!(description, &["A stone archway choked with ivy. Lantern light flickers."]);
+        }
+        other => panic!("Expected Location kind, got {:?}", other),
+    }
+}
+
 // ── Span validity tests ──
 
 #[test]
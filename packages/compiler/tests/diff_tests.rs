@@ -3,7 +3,7 @@
 /// Each test compiles fixture pairs through the full pipeline, builds
 /// DiffSnapshots, and asserts expected change entries.
 
-use urd_compiler::compile;
+use urd_compiler::{compile, compile_source};
 use urd_compiler::diff::*;
 
 fn fixture_path(name: &str) -> String {
@@ -216,6 +216,42 @@ fn diff_section_added() {
     );
 }
 
+// ── Ending tests ──
+
+const ENDINGS_WORLD: &str = "---\nworld:\n  name: cell\n  start: cell\nendings:\n  escaped: You slip away.\n---\n\n\
+                             # Cell\n\n-> north: Yard\n  > end escaped\n\n# Yard\n";
+
+fn endings_snapshot(source: &str) -> DiffSnapshot {
+    DiffSnapshot::from_compilation(&compile_source("cell.urd.md", source))
+}
+
+#[test]
+fn diff_ending_added_and_removed() {
+    let after = ENDINGS_WORLD.replace("  escaped: You slip away.\n", "  escaped: You slip away.\n  caught:\n");
+    let report = diff(&endings_snapshot(ENDINGS_WORLD), &endings_snapshot(&after));
+    assert!(has_change(&report, "ending", "added", "caught"), "{:?}", report.changes);
+    let report = diff(&endings_snapshot(&after), &endings_snapshot(ENDINGS_WORLD));
+    assert!(has_change(&report, "ending", "removed", "caught"), "{:?}", report.changes);
+}
+
+#[test]
+fn diff_ending_description_and_reach_changed() {
+    let after = ENDINGS_WORLD
+        .replace("You slip away.", "You vanish.")
+        .replace("# Yard\n", "# Yard\n\n-> south: Cell\n  > end escaped\n");
+    let a = endings_snapshot(ENDINGS_WORLD);
+    let b = endings_snapshot(&after);
+    let report = diff(&a, &b);
+    let change = report.changes.iter().find(|c| c.kind == "description_changed").expect("description change");
+    assert_eq!(change.detail["after"], "You vanish.");
+    let change = report.changes.iter().find(|c| c.kind == "reach_changed").expect("reach change");
+    assert_eq!(change.detail["before"]["end_count"], 1);
+    assert_eq!(change.detail["after"]["end_count"], 2);
+
+    let restored = DiffSnapshot::from_json(&b.to_json("cell").to_string()).unwrap();
+    assert_eq!(restored, b);
+}
+
 // ── Property dependency tests ──
 
 #[test]
@@ -349,6 +385,21 @@ fn diff_snapshot_roundtrip() {
     assert_eq!(snap, restored, "Roundtrip snapshot must equal original");
 }
 
+#[test]
+fn diff_snapshot_records_compiler_version() {
+    let snap = compile_snapshot("locked-garden.urd.md");
+    assert_eq!(snap.compiler_version.as_deref(), Some(urd_compiler::version::VERSION));
+    let json = snap.to_json("the-locked-garden");
+    assert_eq!(json["compiler_version"], urd_compiler::version::VERSION);
+
+    // Older snapshots have no version; they still load and compare.
+    let mut old = json.clone();
+    old.as_object_mut().unwrap().remove("compiler_version");
+    let restored = DiffSnapshot::from_json(&old.to_string()).unwrap();
+    assert_eq!(restored.compiler_version, None);
+    assert!(diff(&restored, &snap).changes.is_empty());
+}
+
 #[test]
 fn diff_snapshot_version_mismatch() {
     let json = r#"{ "urd_snapshot": "2", "entities": {} }"#;
@@ -438,3 +489,120 @@ fn diff_report_summary_text() {
     let summary = report2.summary();
     assert!(summary.contains("changes:"), "Summary: {}", summary);
 }
+
+#[test]
+fn diff_report_by_category_groups_in_report_order() {
+    let report = diff_fixtures(
+        "diff/diff-a-minimal.urd.md",
+        "diff/diff-b-minimal.urd.md",
+    );
+    let groups = report.by_category();
+    let total: usize = groups.values().map(|g| g.len()).sum();
+    assert_eq!(total, report.changes.len());
+    for (category, entries) in &groups {
+        assert!(entries.iter().all(|c| &c.category == category));
+    }
+    // Categories appear in the same order as in the flat change list.
+    let mut seen: Vec<&str> = Vec::new();
+    for c in &report.changes {
+        if seen.last() != Some(&c.category.as_str()) {
+            seen.push(c.category.as_str());
+        }
+    }
+    let keys: Vec<&str> = groups.keys().map(|k| k.as_str()).collect();
+    assert_eq!(keys, seen);
+}
+
+#[test]
+fn diff_report_by_category_empty() {
+    let snap = compile_snapshot("locked-garden.urd.md");
+    assert!(diff(&snap, &snap).by_category().is_empty());
+}
+
+// ── Snapshot helpers ──
+
+#[test]
+fn default_snapshot_path_strips_urd_md() {
+    assert_eq!(default_snapshot_path("world/garden.urd.md"), "world/garden.urd.snapshot.json");
+    assert_eq!(default_snapshot_path("notes.txt"), "notes.txt.urd.snapshot.json");
+}
+
+#[test]
+fn world_name_from_compilation() {
+    let result = compile(&fixture_path("locked-garden.urd.md"));
+    assert_eq!(world_name(&result), "the-locked-garden");
+}
+
+// ── Explicit ID tests ──
+
+#[test]
+fn diff_heading_rename_with_explicit_id_is_empty() {
+    // Rename both location headings and a section label, keeping their
+    // {#id} overrides. Every compiled ID is stable, so nothing changes.
+    let before = std::fs::read_to_string(fixture_path("explicit-ids.urd.md")).unwrap();
+    let after = before
+        .replace("# Café & Bar!!! {#cafe}", "# The Corner Café {#cafe}")
+        .replace("# The Street {#street}", "# Wet Street {#street}")
+        .replace("== topics {#intro_topics}", "== chat {#intro_topics}");
+    assert_ne!(before, after);
+
+    let snap_a = DiffSnapshot::from_compilation(&compile_source("explicit-ids.urd.md", &before));
+    let snap_b = DiffSnapshot::from_compilation(&compile_source("explicit-ids.urd.md", &after));
+    let report = diff(&snap_a, &snap_b);
+    assert!(
+        report.changes.is_empty(),
+        "Expected no changes, got: {}",
+        report.summary()
+    );
+}
+
+#[test]
+fn diff_phase_section_reference_added() {
+    let before = std::fs::read_to_string(fixture_path("monty-hall.urd.md")).unwrap();
+    let after = before.replace("### Switch\n", "### Switch\nsection: switch\n");
+    assert_ne!(before, after);
+
+    let snap_a = DiffSnapshot::from_compilation(&compile_source("monty-hall.urd.md", &before));
+    let snap_b = DiffSnapshot::from_compilation(&compile_source("monty-hall.urd.md", &after));
+    assert_eq!(
+        snap_b.sections["monty-hall/switch"].phases,
+        vec!["the-game/switch".to_string()]
+    );
+
+    let report = diff(&snap_a, &snap_b);
+    assert!(has_change(&report, "section", "phases_changed", "monty-hall/switch"));
+
+    let restored = DiffSnapshot::from_json(&snap_b.to_json("monty-hall").to_string()).unwrap();
+    assert!(diff(&snap_b, &restored).changes.is_empty());
+}
+
+// ── Requires tests ──
+
+#[test]
+fn diff_requires_new_effect_kind() {
+    let before = std::fs::read_to_string(fixture_path("two-room-key-puzzle.urd.md")).unwrap();
+    let after = before.replace("  > destroy @rusty_key\n", "  > move @rusty_key -> here\n");
+    assert_ne!(before, after);
+
+    let snap_a = DiffSnapshot::from_compilation(&compile_source("two-room-key-puzzle.urd.md", &before));
+    let snap_b = DiffSnapshot::from_compilation(&compile_source("two-room-key-puzzle.urd.md", &after));
+    let report = diff(&snap_a, &snap_b);
+    let change = report.changes.iter().find(|c| c.category == "requires").expect("requires change");
+    assert_eq!((change.kind.as_str(), change.id.as_str()), ("changed", "effects"));
+    assert_eq!(change.detail["before"], serde_json::json!(["destroy", "set"]));
+    assert_eq!(change.detail["after"], serde_json::json!(["move", "set"]));
+    assert_eq!(report.changes.iter().filter(|c| c.category == "requires").count(), 1);
+
+    let restored = DiffSnapshot::from_json(&snap_b.to_json("key-puzzle").to_string()).unwrap();
+    assert_eq!(restored.requires, snap_b.requires);
+}
+
+#[test]
+fn diff_requires_absent_from_old_snapshot_is_not_a_change() {
+    let snap = compile_snapshot("two-room-key-puzzle.urd.md");
+    let mut old = snap.to_json("key-puzzle");
+    old.as_object_mut().unwrap().remove("requires");
+    let old = DiffSnapshot::from_json(&old.to_string()).unwrap();
+    assert!(old.requires.is_none());
+    assert!(diff(&old, &snap).changes.is_empty());
+}
@@ -0,0 +1,285 @@
+/// Tests for the generated reference pages.
+///
+/// The key puzzle's Markdown is compared page by page. Anchor and link
+/// tests run over every page of larger fixtures, in both formats.
+
+use std::collections::HashSet;
+
+use urd_compiler::docgen::{self, DocFormat, DocOptions, DocPage};
+use urd_compiler::emit::EmitOptions;
+use urd_compiler::import::OsFileReader;
+use urd_compiler::{compile_source_with_options, CompileOptions};
+
+// ── Helpers ──
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+/// The pages of a fixture, emitted with doc comments as `urd doc` does.
+fn fixture_pages(name: &str, options: &DocOptions) -> Vec<DocPage> {
+    let path = fixture_path(name);
+    let source = std::fs::read_to_string(&path).unwrap();
+    let compile = CompileOptions {
+        emit: EmitOptions { include_docs: true, ..EmitOptions::default() },
+        ..CompileOptions::default()
+    };
+    let result = compile_source_with_options(&path, &source, &OsFileReader, &compile);
+    docgen::from_compilation(&result, options).expect("fixture should compile")
+}
+
+fn page<'a>(pages: &'a [DocPage], file: &str) -> &'a str {
+    &pages.iter().find(|p| p.file == file).unwrap_or_else(|| panic!("no page {}", file)).contents
+}
+
+/// Every value of `attribute="..."` in `contents`, in order.
+fn attribute_values<'a>(contents: &'a str, attribute: &str) -> Vec<&'a str> {
+    let marker = format!("{}=\"", attribute);
+    contents
+        .match_indices(&marker)
+        .map(|(pos, _)| {
+            let rest = &contents[pos + marker.len()..];
+            &rest[..rest.find('"').unwrap()]
+        })
+        .collect()
+}
+
+/// Every Markdown link target `](...)` in `contents`.
+fn markdown_links(contents: &str) -> Vec<&str> {
+    contents
+        .match_indices("](")
+        .map(|(pos, _)| {
+            let rest = &contents[pos + 2..];
+            &rest[..rest.find(')').unwrap()]
+        })
+        .collect()
+}
+
+/// Check that each link names a generated page and, with a fragment, an
+/// anchor on that page.
+fn assert_links_resolve(pages: &[DocPage], links: impl Fn(&str) -> Vec<&str>) {
+    for source in pages {
+        for link in links(&source.contents) {
+            let (file, fragment) = link.split_once('#').unwrap_or((link, ""));
+            let target = page(pages, file);
+            if !fragment.is_empty() {
+                assert!(
+                    attribute_values(target, "id").contains(&fragment),
+                    "{}: link {} has no anchor",
+                    source.file,
+                    link,
+                );
+            }
+        }
+    }
+}
+
+const FIXTURES: &[&str] = &[
+    "two-room-key-puzzle.urd.md",
+    "sunken-citadel.urd.md",
+    "tavern-scene.urd.md",
+    "doc-comments.urd.md",
+    "parameterised-sections.urd.md",
+    "regions/main.urd.md",
+];
+
+// ── Snapshot ──
+
+#[test]
+fn key_puzzle_markdown() {
+    let pages = fixture_pages("two-room-key-puzzle.urd.md", &DocOptions::default());
+    let files: Vec<&str> = pages.iter().map(|p| p.file.as_str()).collect();
+    assert_eq!(files, ["index.md", "types.md", "entities.md", "locations.md", "dialogue.md"]);
+
+    assert_eq!(
+        page(&pages, "index.md"),
+        "\
+# key-puzzle
+
+The player starts in [Cell](locations.md#location-cell).
+
+- [Types](types.md) (2)
+- [Entities](entities.md) (2)
+- [Locations](locations.md) (2)
+- [Dialogue](dialogue.md) (1)
+",
+    );
+    assert_eq!(
+        page(&pages, "types.md"),
+        "\
+# Types
+
+Back to the [index](index.md).
+
+## <a id=\"type-Key\"></a>Key
+
+Traits: portable.
+
+| Property | Type | Default | Notes |
+|---|---|---|---|
+| `name` | `string` | | |
+
+Entities: [Rusty Key](entities.md#entity-rusty_key).
+
+## <a id=\"type-Door\"></a>Door
+
+Traits: interactable.
+
+| Property | Type | Default | Notes |
+|---|---|---|---|
+| `locked` | `boolean` | true | |
+
+Entities: [`@cell_door`](entities.md#entity-cell_door).
+",
+    );
+    assert_eq!(
+        page(&pages, "entities.md"),
+        "\
+# Entities
+
+Back to the [index](index.md).
+
+## <a id=\"entity-rusty_key\"></a>Rusty Key
+
+ID: `@rusty_key`. Type: [Key](types.md#type-Key). In: [Cell](locations.md#location-cell).
+
+| Property | Value |
+|---|---|
+| `name` | Rusty Key |
+
+## <a id=\"entity-cell_door\"></a>`@cell_door`
+
+ID: `@cell_door`. Type: [Door](types.md#type-Door). In: [Cell](locations.md#location-cell).
+",
+    );
+    assert_eq!(
+        page(&pages, "locations.md"),
+        "\
+# Locations
+
+Back to the [index](index.md).
+
+## <a id=\"location-cell\"></a>Cell
+
+ID: `cell`. The player starts here.
+
+> A dim stone cell.
+
+| Exit | To | Condition | Blocked message |
+|---|---|---|---|
+| `north` | [Corridor](locations.md#location-corridor) | `cell_door.locked == false` | The iron door is locked. |
+
+Contains: [Rusty Key](entities.md#entity-rusty_key), [`@cell_door`](entities.md#entity-cell_door).
+
+## <a id=\"location-corridor\"></a>Corridor
+
+ID: `corridor`.
+
+> You made it out.
+",
+    );
+    assert_eq!(
+        page(&pages, "dialogue.md"),
+        "\
+# Dialogue
+
+Back to the [index](index.md).
+
+## <a id=\"section-two-room-key-puzzle-actions\"></a>`two-room-key-puzzle/actions`
+
+- Use key
+",
+    );
+}
+
+#[test]
+fn doc_comments_are_included() {
+    let pages = fixture_pages("doc-comments.urd.md", &DocOptions::default());
+    assert!(page(&pages, "types.md").contains("A keeper of the light.\nSpeaks only when spoken to.\n"));
+    assert!(page(&pages, "entities.md").contains("The old keeper. Knows where the lens went.\n"));
+    assert!(page(&pages, "locations.md").contains("The lighthouse gallery, high above the rocks.\n"));
+    assert!(page(&pages, "dialogue.md").contains("Opening conversation with the keeper.\n\n- Ask about the lens (sticky) → end\n"));
+}
+
+#[test]
+fn nested_choices_are_outlined_under_their_parent() {
+    let pages = fixture_pages("sunken-citadel.urd.md", &DocOptions::default());
+    let dialogue = page(&pages, "dialogue.md");
+    let link = "[`sunken-citadel/square_talk`](dialogue.md#section-sunken-citadel-square_talk)";
+    assert!(dialogue.contains(&format!("- Talk to Pip (sticky)\n  - Tell the truth → {}\n", link)), "{}", dialogue);
+    assert_eq!(dialogue.matches("Tell the truth →").count(), 1);
+    assert!(dialogue.contains("- Pick up the cipher note → exit `upstairs`\n"));
+}
+
+// ── Anchors and links ──
+
+#[test]
+fn anchors_come_from_compiled_ids() {
+    assert_eq!(docgen::anchor("section", "tavern/topics"), "section-tavern-topics");
+    assert_eq!(docgen::anchor("section", "tavern/haggle@harbor_master"), "section-tavern-haggle-harbor_master");
+    assert_eq!(docgen::anchor("entity", "rusty_key"), "entity-rusty_key");
+}
+
+#[test]
+fn anchors_are_unique() {
+    for name in FIXTURES {
+        for format in [DocFormat::Markdown, DocFormat::Html] {
+            let pages = fixture_pages(name, &DocOptions { format, ..DocOptions::default() });
+            let mut seen = HashSet::new();
+            for page in &pages {
+                for id in attribute_values(&page.contents, "id") {
+                    assert!(seen.insert(id.to_string()), "{}: anchor {} repeats", name, id);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn links_resolve_in_markdown() {
+    for name in FIXTURES {
+        assert_links_resolve(&fixture_pages(name, &DocOptions::default()), markdown_links);
+    }
+}
+
+#[test]
+fn links_resolve_in_html() {
+    let options = DocOptions { format: DocFormat::Html, ..DocOptions::default() };
+    for name in FIXTURES {
+        let pages = fixture_pages(name, &options);
+        assert!(pages.iter().all(|p| p.file.ends_with(".html") && p.contents.starts_with("<!DOCTYPE html>")));
+        assert_links_resolve(&pages, |contents| attribute_values(contents, "href"));
+    }
+}
+
+#[test]
+fn entity_links_to_its_type() {
+    let pages = fixture_pages("sunken-citadel.urd.md", &DocOptions::default());
+    let entities = page(&pages, "entities.md");
+    let entry = &entities[entities.find("<a id=\"entity-elder_maren\"></a>").unwrap()..];
+    let type_link = "[Villager](types.md#type-Villager)";
+    assert!(entry[..entry.find("\n## ").unwrap()].contains(type_link), "{}", entry);
+    assert!(page(&pages, "types.md").contains("## <a id=\"type-Villager\"></a>Villager\n"));
+    assert!(page(&pages, "types.md").contains("[Elder Maren](entities.md#entity-elder_maren)"));
+}
+
+// ── Determinism ──
+
+#[test]
+fn pages_are_deterministic_and_undated_by_default() {
+    let options = DocOptions::default();
+    let first = fixture_pages("sunken-citadel.urd.md", &options);
+    assert_eq!(first, fixture_pages("sunken-citadel.urd.md", &options));
+    assert!(first.iter().all(|p| !p.contents.contains("Generated on")));
+
+    let dated = fixture_pages("sunken-citadel.urd.md", &DocOptions { date: Some("2026-10-14".to_string()), ..options });
+    assert!(page(&dated, "index.md").contains("\nGenerated on 2026-10-14.\n"));
+    assert_eq!(dated[1..], first[1..]);
+}
+
+#[test]
+fn failed_compilation_has_no_pages() {
+    let source = "---\nworld:\n  name: test\n  start: nowhere\n---\n\n# Hall\n";
+    let result = urd_compiler::compile_source("test.urd.md", source);
+    assert!(docgen::from_compilation(&result, &DocOptions::default()).is_none());
+}
@@ -4,8 +4,11 @@
 // the JSON output. Unlike unit tests that build ASTs programmatically,
 // these exercise the full pipeline: PARSE → IMPORT → LINK → VALIDATE → EMIT.
 
+use std::sync::OnceLock;
+
 use urd_compiler::compile;
 use urd_compiler::diagnostics::Severity;
+use urd_compiler::schema;
 
 // ── Helpers ──
 
@@ -27,7 +30,28 @@ fn compile_and_parse(name: &str) -> serde_json::Value {
         format_diagnostics(&result.diagnostics)
     );
     let json_str = result.world.expect("Expected world JSON when success is true");
-    serde_json::from_str(&json_str).expect("Expected valid JSON output")
+    let json = serde_json::from_str(&json_str).expect("Expected valid JSON output");
+    assert_schema_valid(name, &json);
+    json
+}
+
+fn schema_validator() -> &'static jsonschema::Validator {
+    static VALIDATOR: OnceLock<jsonschema::Validator> = OnceLock::new();
+    VALIDATOR.get_or_init(|| {
+        let schema: serde_json::Value =
+            serde_json::from_str(schema::world_schema()).expect("JSON Schema should be valid JSON");
+        jsonschema::validator_for(&schema).expect("JSON Schema should be a valid schema")
+    })
+}
+
+fn assert_schema_valid(fixture: &str, json: &serde_json::Value) {
+    let errors: Vec<String> = schema_validator().iter_errors(json).map(|e| format!("  - {}", e)).collect();
+    assert!(
+        errors.is_empty(),
+        "Fixture {} should validate against JSON Schema. Errors:\n{}",
+        fixture,
+        errors.join("\n")
+    );
 }
 
 fn format_diagnostics(diagnostics: &urd_compiler::diagnostics::DiagnosticCollector) -> String {
@@ -229,6 +253,51 @@ fn e2e_key_puzzle_action_effects_lowered() {
     assert_eq!(destroy_effect["destroy"], "rusty_key");
 }
 
+#[test]
+fn e2e_key_puzzle_requires_block() {
+    let json = compile_and_parse("two-room-key-puzzle.urd.md");
+    assert_eq!(json.as_object().unwrap().keys().nth(1).unwrap(), "requires");
+    assert_eq!(
+        json["requires"],
+        serde_json::json!({
+            "traits": ["interactable", "portable"],
+            "effects": ["destroy", "set"],
+            "conditions": ["containment", "property_comparison"],
+            "sequences": false,
+            "rules": false,
+            "dialogue": true,
+            "max_choice_depth": 1,
+        })
+    );
+}
+
+#[test]
+fn e2e_requires_reports_absent_dialogue() {
+    let json = compile_and_parse("location-hooks.urd.md");
+    assert!(json.get("dialogue").is_none());
+    let requires = &json["requires"];
+    assert_eq!(requires["dialogue"], false);
+    assert_eq!(requires["sequences"], false);
+    assert_eq!(requires["rules"], false);
+    assert_eq!(requires["max_choice_depth"], 0);
+}
+
+#[test]
+fn e2e_requires_block_is_sorted_and_deterministic() {
+    let first = compile_and_parse("sunken-citadel.urd.md");
+    let requires = &first["requires"];
+    assert_eq!(requires, &compile_and_parse("sunken-citadel.urd.md")["requires"]);
+    for key in ["traits", "effects", "conditions"] {
+        let values: Vec<&str> = requires[key].as_array().unwrap().iter().map(|v| v.as_str().unwrap()).collect();
+        let mut sorted = values.clone();
+        sorted.sort();
+        assert_eq!(values, sorted, "{} should be sorted", key);
+    }
+    assert_eq!(requires["traits"], serde_json::json!(["container", "interactable", "mobile", "portable"]));
+    assert_eq!(requires["conditions"], serde_json::json!(["any", "containment", "property_comparison"]));
+    assert_eq!(requires["max_choice_depth"], 2);
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // Tavern Scene — entity speech, enum/integer properties, arithmetic effects
 // ═══════════════════════════════════════════════════════════════════════════
@@ -430,6 +499,32 @@ fn e2e_monty_hall_has_sequence() {
     assert!(!sequences.is_empty(), "Should have at least one sequence");
 }
 
+#[test]
+fn e2e_monty_hall_phase_opens_section() {
+    let source = std::fs::read_to_string(fixture_path("monty-hall.urd.md"))
+        .unwrap()
+        .replace("### Switch\n", "### Switch\nsection: switch\n");
+    let result = urd_compiler::compile_source("monty-hall.urd.md", &source);
+    assert!(
+        result.success,
+        "Phase section should resolve. Diagnostics:\n{}",
+        format_diagnostics(&result.diagnostics)
+    );
+
+    // The switch section is only reachable through the phase. Nothing
+    // should flag it, and the phase carries the compiled section ID.
+    assert!(
+        !result.diagnostics.all().iter().any(|d| d.message.contains("switch")),
+        "Unexpected diagnostics:\n{}",
+        format_diagnostics(&result.diagnostics)
+    );
+    let json: serde_json::Value = serde_json::from_str(&result.world.unwrap()).unwrap();
+    let phases = json["sequences"]["the-game"]["phases"].as_array().unwrap();
+    assert_eq!(phases[2]["id"], "switch");
+    assert_eq!(phases[2]["section"], "monty-hall/switch");
+    assert!(phases[0].get("section").is_none());
+}
+
 #[test]
 fn e2e_monty_hall_has_rules() {
     let json = compile_and_parse("monty-hall.urd.md");
@@ -980,13 +1075,36 @@ fn e2e_locked_garden_assignment_effect() {
 
 #[test]
 fn e2e_locked_garden_deterministic_output() {
+    // The library leaves the generator out, so the bytes do not depend on
+    // the compiler version either.
     let result1 = compile_fixture("locked-garden.urd.md");
     let result2 = compile_fixture("locked-garden.urd.md");
-    assert_eq!(
-        result1.world.unwrap(),
-        result2.world.unwrap(),
-        "Two compilations must produce identical output"
-    );
+    let world = result1.world.unwrap();
+    assert!(!world.contains("\"generator\""));
+    assert_eq!(world, result2.world.unwrap(), "Two compilations must produce identical output");
+}
+
+#[test]
+fn e2e_generator_names_the_compiler_when_asked() {
+    let compile_with = |include_generator| {
+        let options = urd_compiler::CompileOptions {
+            emit: urd_compiler::emit::EmitOptions { include_generator, ..Default::default() },
+            ..Default::default()
+        };
+        let result = urd_compiler::compile_with_options(&fixture_path("locked-garden.urd.md"), &options);
+        let json: serde_json::Value = serde_json::from_str(&result.world.unwrap()).unwrap();
+        assert_schema_valid("locked-garden.urd.md", &json);
+        json
+    };
+    let plain = compile_with(false);
+    let mut named = compile_with(true);
+    assert_eq!(named["world"]["generator"], format!("urd-compiler {}", env!("CARGO_PKG_VERSION")));
+    assert!(plain["world"].get("generator").is_none());
+
+    // Nothing else changes, and the field is stable across compiles.
+    assert_eq!(named, compile_with(true));
+    named["world"].as_object_mut().unwrap().remove("generator");
+    assert_eq!(named, plain);
 }
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -1051,6 +1169,35 @@ fn e2e_type_aliases_defaults_preserved() {
     assert_eq!(props["label"]["default"], "default");
 }
 
+// ═══════════════════════════════════════════════════════════════════════════
+// Property Groups
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn e2e_property_groups_no_diagnostics() {
+    let result = compile_fixture("property-groups.urd.md");
+    assert!(
+        result.diagnostics.is_empty(),
+        "Expected no diagnostics:\n{}",
+        format_diagnostics(&result.diagnostics)
+    );
+}
+
+#[test]
+fn e2e_property_groups_nest_in_output() {
+    let json = compile_and_parse("property-groups.urd.md");
+    let props = &json["types"]["Guard"]["properties"];
+    assert_eq!(props["appearance"]["height"]["max"], 250.0);
+    assert_eq!(props["appearance"]["build"]["default"], "broad");
+    assert_eq!(props["stats"]["strength"]["type"], "integer");
+    assert_eq!(json["entities"]["guard"]["properties"]["appearance"]["height"], 195);
+
+    // Conditions and effects keep the dotted path.
+    let choice = &json["dialogue"]["property-groups/talk"]["choices"][0];
+    assert_eq!(choice["conditions"][0], "guard.appearance.height > 180");
+    assert_eq!(choice["effects"][0]["set"], "guard.stats.strength");
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // Cross-cutting: JSON structure
 // ═══════════════════════════════════════════════════════════════════════════
@@ -1236,6 +1383,13 @@ fn e2e_c9_nesting_error_blocks_compilation() {
 // Gate verification: canonical fixtures compile with zero warnings
 // ═══════════════════════════════════════════════════════════════════════════
 
+/// Lines of the sunken citadel whose prose responses URD443 reports.
+const SUNKEN_CITADEL_DROPPED_TEXT: &[u32] = &[
+    261, 329, 335, 340, 403, 411, 423, 429, 453, 564, 577, 584, 642, 649, 654, 678, 684, 689, 694, 724, 732, 741,
+    746, 754, 764, 771, 778, 810, 818, 823, 829, 833, 861, 869, 873, 907, 923, 929, 936, 944, 978, 986, 990, 995,
+    1051, 1058, 1066,
+];
+
 #[test]
 fn gate_canonical_fixtures_zero_warnings() {
     let fixtures = [
@@ -1255,8 +1409,17 @@ fn gate_canonical_fixtures_zero_warnings() {
         // Only check PARSE–EMIT warnings (URD100–URD599). ANALYZE warnings
         // (URD600+) are informational and expected on test worlds — they are
         // the output of SF-1A FactSet diagnostics, not authoring errors.
+        // The sunken citadel writes choice responses as prose, which the
+        // compiled world cannot carry (URD443); those lines are pinned so
+        // any other warning still fails the gate.
+        let dropped = if *fixture == "sunken-citadel.urd.md" { SUNKEN_CITADEL_DROPPED_TEXT } else { &[] };
+        let dropped_lines: Vec<u32> = result.diagnostics.sorted().iter()
+            .filter(|d| d.code == "URD443")
+            .map(|d| d.span.start_line)
+            .collect();
+        assert_eq!(dropped_lines, dropped, "URD443 lines in {}", fixture);
         let warnings: Vec<String> = result.diagnostics.all().iter()
-            .filter(|d| d.severity == Severity::Warning && !d.code.starts_with("URD6"))
+            .filter(|d| d.severity == Severity::Warning && !d.code.starts_with("URD6") && d.code != "URD443")
             .map(|d| d.code.clone())
             .collect();
         assert!(
@@ -1273,6 +1436,160 @@ fn gate_canonical_fixtures_zero_warnings() {
     }
 }
 
+// ═══════════════════════════════════════════════════════════════════════════
+// Explicit IDs — {#id} overrides on location headings and section labels
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn e2e_explicit_ids_locations() {
+    let json = compile_and_parse("explicit-ids.urd.md");
+    assert_eq!(json["world"]["start"], "cafe");
+    let locations = json["locations"].as_object().unwrap();
+    let keys: Vec<&str> = locations.keys().map(|k| k.as_str()).collect();
+    assert_eq!(keys, vec!["cafe", "street"]);
+    assert_eq!(json["locations"]["cafe"]["exits"]["out"]["to"], "street");
+    assert_eq!(json["locations"]["street"]["exits"]["in"]["to"], "cafe");
+}
+
+#[test]
+fn e2e_explicit_ids_dialogue() {
+    let json = compile_and_parse("explicit-ids.urd.md");
+    let topics = &json["dialogue"]["explicit-ids/intro_topics"];
+    assert_eq!(topics["choices"][0]["id"], "explicit-ids/intro_topics/ask-for-the-menu");
+    assert_eq!(topics["choices"][0]["goto"], "explicit-ids/intro_topics");
+    assert_eq!(
+        json["dialogue"]["explicit-ids/farewell"]["conditions"][0],
+        "explicit-ids/intro_topics.exhausted"
+    );
+}
+
+#[test]
+fn e2e_explicit_ids_world_start_derived_form_suggests_id() {
+    let source = "---\nworld:\n  name: test\n  start: Café & Bar\n---\n# Café & Bar {#cafe}\n\nQuiet.\n";
+    let result = urd_compiler::compile_source("test.urd.md", source);
+    let diag = result.diagnostics.all().iter().find(|d| d.code == "URD404").expect("URD404");
+    assert_eq!(diag.suggestion.as_deref(), Some("Did you mean 'cafe'?"));
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Doc comments — `///` on types, entities, locations, and sections
+// ═══════════════════════════════════════════════════════════════════════════
+
+fn compile_with_docs(name: &str) -> serde_json::Value {
+    let path = fixture_path(name);
+    let source = std::fs::read_to_string(&path).unwrap();
+    let options = urd_compiler::CompileOptions {
+        emit: urd_compiler::emit::EmitOptions { include_docs: true, ..Default::default() },
+        ..Default::default()
+    };
+    let result = urd_compiler::compile_source_with_options(&path, &source, &urd_compiler::import::OsFileReader, &options);
+    assert!(result.success, "{}", format_diagnostics(&result.diagnostics));
+    let json = serde_json::from_str(&result.world.unwrap()).unwrap();
+    assert_schema_valid(name, &json);
+    json
+}
+
+#[test]
+fn e2e_doc_comments_absent_by_default() {
+    let json = compile_and_parse("doc-comments.urd.md");
+    assert!(json["types"]["Keeper"].get("doc").is_none());
+    assert!(json["entities"]["keeper"].get("doc").is_none());
+    assert!(json["locations"]["lighthouse"].get("doc").is_none());
+    assert!(json["locations"]["lighthouse"].get("description").is_none(), "doc comments are not prose");
+    assert!(json["dialogue"]["doc-comments/talk"].get("doc").is_none());
+}
+
+#[test]
+fn e2e_doc_comments_emitted_with_include_docs() {
+    let json = compile_with_docs("doc-comments.urd.md");
+    assert_eq!(json["types"]["Keeper"]["doc"], "A keeper of the light.\nSpeaks only when spoken to.");
+    assert_eq!(json["entities"]["keeper"]["doc"], "The old keeper. Knows where the lens went.");
+    assert_eq!(
+        json["locations"]["lighthouse"]["doc"],
+        "The lighthouse gallery, high above the rocks.\nWind is constant here."
+    );
+    assert_eq!(json["dialogue"]["doc-comments/talk"]["doc"], "Opening conversation with the keeper.");
+
+    // Undocumented definitions gain no key.
+    let plain = compile_with_docs("two-room-key-puzzle.urd.md");
+    assert_eq!(plain, compile_and_parse("two-room-key-puzzle.urd.md"));
+}
+
+#[test]
+fn e2e_doc_comments_validate_against_schema() {
+    let json = compile_with_docs("doc-comments.urd.md");
+    let errors: Vec<String> = schema_validator().iter_errors(&json).map(|e| e.to_string()).collect();
+    assert!(errors.is_empty(), "{:?}", errors);
+}
+
+#[test]
+fn e2e_doc_comments_in_definition_index() {
+    let result = compile_fixture("doc-comments.urd.md");
+    let index = result.definition_index.expect("definition index");
+    let doc = |key: &str| index.get(key).and_then(|e| e.doc.clone());
+    assert_eq!(doc("type:Keeper").as_deref(), Some("A keeper of the light.\nSpeaks only when spoken to."));
+    assert_eq!(doc("entity:@keeper").as_deref(), Some("The old keeper. Knows where the lens went."));
+    assert_eq!(doc("section:doc-comments/talk").as_deref(), Some("Opening conversation with the keeper."));
+    assert!(doc("location:lighthouse").unwrap().starts_with("The lighthouse gallery"));
+    assert_eq!(doc("prop:Keeper.trust"), None);
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Parameterised sections — `== haggle(@merchant: Merchant)` monomorphised
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn e2e_parameterised_sections_emit_one_instance_per_argument() {
+    let json = compile_and_parse("parameterised-sections.urd.md");
+    let dialogue = json["dialogue"].as_object().unwrap();
+    let ids: Vec<&str> = dialogue.keys().map(|k| k.as_str()).collect();
+    assert_eq!(ids, vec![
+        "parameterised-sections/bar",
+        "parameterised-sections/haggle@harbor_master",
+        "parameterised-sections/haggle@fishmonger",
+    ]);
+    // The template and the uninvoked section emit nothing.
+    assert!(!dialogue.contains_key("parameterised-sections/haggle"));
+    assert!(!dialogue.contains_key("parameterised-sections/unused"));
+}
+
+#[test]
+fn e2e_parameterised_sections_goto_instances() {
+    let json = compile_and_parse("parameterised-sections.urd.md");
+    let bar = &json["dialogue"]["parameterised-sections/bar"];
+    assert_eq!(bar["choices"][0]["goto"], "parameterised-sections/haggle@harbor_master");
+    assert_eq!(bar["choices"][1]["goto"], "parameterised-sections/haggle@fishmonger");
+    // A bare `-> haggle` inside the template stays within the instance.
+    let haggle = &json["dialogue"]["parameterised-sections/haggle@fishmonger"];
+    assert_eq!(haggle["choices"][0]["goto"], "parameterised-sections/haggle@fishmonger");
+}
+
+#[test]
+fn e2e_parameterised_sections_substitute_argument() {
+    let json = compile_and_parse("parameterised-sections.urd.md");
+    let haggle = &json["dialogue"]["parameterised-sections/haggle@harbor_master"];
+    assert_eq!(haggle["prompt"]["speaker"], "harbor_master");
+    let offer = &haggle["choices"][0];
+    assert_eq!(offer["id"], "parameterised-sections/haggle@harbor_master/offer-less");
+    assert_eq!(offer["conditions"][0], "harbor_master.price > 10");
+    assert_eq!(offer["effects"][0]["set"], "harbor_master.price");
+    assert_eq!(offer["effects"][0]["to"], "harbor_master.price - 5");
+
+    let action = &json["actions"]["parameterised-sections/haggle@fishmonger/offer-less"];
+    assert_eq!(action["effects"][0]["set"], "fishmonger.price");
+    assert!(json["actions"].get("parameterised-sections/haggle/offer-less").is_none());
+}
+
+#[test]
+fn e2e_parameterised_sections_uninvoked_info() {
+    let result = compile_fixture("parameterised-sections.urd.md");
+    let infos: Vec<_> = result.diagnostics.all().iter()
+        .filter(|d| d.code == "URD606")
+        .map(|d| d.severity)
+        .collect();
+    assert_eq!(infos, vec![Severity::Info]);
+}
+
 // ═══════════════════════════════════════════════════════════════════════════
 // Gate verification: negative corpus rejected with correct codes
 // ═══════════════════════════════════════════════════════════════════════════
@@ -1334,20 +1651,22 @@ fn gate_negative_corpus_correct_codes() {
 
 #[test]
 fn gate_json_schema_validates_all_fixtures() {
-    let schema_path = format!("{}/../../packages/schema/urd-world-schema.json", env!("CARGO_MANIFEST_DIR"));
-    let schema_str = std::fs::read_to_string(&schema_path)
-        .unwrap_or_else(|e| panic!("Failed to read JSON Schema at {}: {}", schema_path, e));
-    let schema_value: serde_json::Value = serde_json::from_str(&schema_str)
-        .expect("JSON Schema should be valid JSON");
-    let validator = jsonschema::validator_for(&schema_value)
-        .expect("JSON Schema should be a valid schema");
-
     let fixtures = [
         "tavern-scene.urd.md",
         "monty-hall.urd.md",
         "two-room-key-puzzle.urd.md",
         "interrogation/main.urd.md",
         "sunken-citadel.urd.md",
+        "location-hooks.urd.md",
+        "explicit-ids.urd.md",
+        "parameterised-sections.urd.md",
+        "doc-comments.urd.md",
+        "inlay-hints.urd.md",
+        "locked-garden.urd.md",
+        "type-aliases.urd.md",
+        "positive-factset-diagnostics.urd.md",
+        "positive-factset-circular-deep.urd.md",
+        "property-groups.urd.md",
     ];
 
     for fixture in &fixtures {
@@ -1356,12 +1675,797 @@ fn gate_json_schema_validates_all_fixtures() {
         let json_str = result.world.expect("Expected world JSON");
         let json: serde_json::Value = serde_json::from_str(&json_str)
             .expect("Compiled output should be valid JSON");
-        let errors: Vec<_> = validator.iter_errors(&json).collect();
-        assert!(
-            errors.is_empty(),
-            "Fixture {} should validate against JSON Schema. Errors:\n{}",
-            fixture,
-            errors.iter().map(|e| format!("  - {}", e)).collect::<Vec<_>>().join("\n")
-        );
+        assert_schema_valid(fixture, &json);
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Continuation lines — indented text joins the node above it
+// ═══════════════════════════════════════════════════════════════════════════
+
+const CONTINUATION_WORLD: &str = "\
+---
+world:
+  name: lighthouse
+  start: gallery
+types:
+  Keeper [interactable]:
+    name: string
+  Hatch [interactable]:
+    open: bool = false
+entities:
+  @keeper: Keeper { name: \"Maren\" }
+  @hatch: Hatch
+---
+# Gallery
+
+[@keeper, @hatch]
+
+-> up: Lamp Room
+  ? @hatch.open == true
+  ! The hatch is bolted from above.
+    Someone has wedged it with driftwood.
+
+== talk
+
+* Ask about the hatch
+  @keeper: It sticks in wet weather.
+    Give it a shove.
+  > @hatch.open = true
+
+# Lamp Room
+";
+
+#[test]
+fn e2e_continuation_blocked_message_is_one_string() {
+    let result = urd_compiler::compile_source("lighthouse.urd.md", CONTINUATION_WORLD);
+    assert!(result.success, "{}", format_diagnostics(&result.diagnostics));
+    let json: serde_json::Value = serde_json::from_str(result.world.as_ref().unwrap()).unwrap();
+    assert_eq!(
+        json["locations"]["gallery"]["exits"]["up"]["blocked_message"],
+        "The hatch is bolted from above. Someone has wedged it with driftwood."
+    );
+    let response = &json["dialogue"]["lighthouse/talk"]["choices"][0]["response"];
+    assert_eq!(response["speaker"], "keeper");
+    assert_eq!(response["text"], "It sticks in wet weather. Give it a shove.");
+}
+
+#[test]
+fn e2e_continuation_leaves_fallthrough_check_unchanged() {
+    // The only text after the one-shot choice belongs to its response, so
+    // the section still has no fallthrough.
+    let result = urd_compiler::compile_source("lighthouse.urd.md", CONTINUATION_WORLD);
+    let urd433: Vec<_> = result.diagnostics.all().iter().filter(|d| d.code == "URD433").collect();
+    assert_eq!(urd433.len(), 1, "{}", format_diagnostics(&result.diagnostics));
+
+    let with_fallthrough = CONTINUATION_WORLD.replace(
+        "  > @hatch.open = true\n",
+        "  > @hatch.open = true\n\n@keeper: That is all\n  I know about it.\n",
+    );
+    let result = urd_compiler::compile_source("lighthouse.urd.md", &with_fallthrough);
+    assert!(
+        result.diagnostics.all().iter().all(|d| d.code != "URD433"),
+        "{}",
+        format_diagnostics(&result.diagnostics)
+    );
+}
+
+// ── Regions ──
+// The Keep is declared in keep.urd.md and again in main.urd.md, which
+// imports it. The Gate, in main.urd.md, comes before any region heading.
+
+#[test]
+fn e2e_regions_assign_locations() {
+    let json = compile_and_parse("regions/main.urd.md");
+    let locations = &json["locations"];
+    assert_eq!(locations["quay"]["region"], "the-docks");
+    assert_eq!(locations["fish-market"]["region"], "the-docks");
+    assert_eq!(locations["great-hall"]["region"], "the-keep");
+    assert_eq!(locations["gatehouse"]["region"], "the-keep");
+    assert!(
+        locations["gate"].get("region").is_none(),
+        "a region in an imported file must not carry into the importer"
+    );
+    // Exits keep using location IDs.
+    assert_eq!(locations["quay"]["exits"]["east"]["to"], "fish-market");
+}
+
+#[test]
+fn e2e_regions_block_merges_files() {
+    let json = compile_and_parse("regions/main.urd.md");
+    assert_eq!(
+        json["regions"],
+        serde_json::json!({
+            "the-keep": {
+                "name": "The Keep",
+                "locations": ["great-hall", "armoury", "gatehouse"]
+            },
+            "the-docks": {
+                "name": "The Docks",
+                "locations": ["quay", "fish-market"]
+            }
+        })
+    );
+    let keys: Vec<&String> = json["regions"].as_object().unwrap().keys().collect();
+    assert_eq!(keys, ["the-keep", "the-docks"], "imported file's regions come first");
+}
+
+#[test]
+fn e2e_regions_output_is_deterministic() {
+    let first = compile_fixture("regions/main.urd.md").world.unwrap();
+    let second = compile_fixture("regions/main.urd.md").world.unwrap();
+    assert_eq!(first, second);
+}
+
+#[test]
+fn e2e_world_without_regions_has_no_regions_block() {
+    let json = compile_and_parse("two-room-key-puzzle.urd.md");
+    assert!(json.get("regions").is_none());
+}
+
+// ── Exit effects ──
+
+#[test]
+fn e2e_exit_effects_emit_as_on_traverse() {
+    let source = "---\nworld:\n  name: vault\n  start: hall\ntypes:\n  Door [interactable]:\n    \
+                  locked: bool = true\n    opened: integer = 0\nentities:\n  @door: Door\n---\n\n\
+                  # Hall\n\n[@door]\n\n-> north: Vault\n  ? @door.locked == false\n  ! Locked.\n  \
+                  > @door.opened + 1\n\n# Vault\n\n-> south: Hall\n";
+    let result = urd_compiler::compile_source("vault.urd.md", source);
+    assert!(result.success, "{}", format_diagnostics(&result.diagnostics));
+    let json: serde_json::Value = serde_json::from_str(result.world.as_ref().unwrap()).unwrap();
+    assert_schema_valid("vault.urd.md", &json);
+
+    let exit = &json["locations"]["hall"]["exits"]["north"];
+    let expected = serde_json::json!([{ "set": "door.opened", "to": "door.opened + 1" }]);
+    assert_eq!(exit["on_traverse"], expected);
+    // The old key is kept alongside for one release.
+    assert_eq!(exit["effects"], exit["on_traverse"]);
+    assert!(json["locations"]["vault"]["exits"]["south"].get("on_traverse").is_none());
+}
+
+// ── Display names ──
+
+#[test]
+fn e2e_display_names_override_beats_type_default() {
+    let source = "---\nworld:\n  name: tavern\n  start: tavern\ntypes:\n  Person [interactable]:\n    \
+                  name: string = \"Stranger\"\n  Crate [portable]:\n    full: bool = true\nentities:\n  \
+                  @vell: Person { name: \"Captain Vell\" }\n  @drifter: Person\n  @crate: Crate\n---\n\n\
+                  # Tavern\n\n[@vell, @drifter, @crate]\n\n== greet\n\n@vell: Sit down.\n@drifter: Evening.\n";
+    let result = urd_compiler::compile_source("tavern.urd.md", source);
+    assert!(result.success, "{}", format_diagnostics(&result.diagnostics));
+    let json: serde_json::Value = serde_json::from_str(result.world.as_ref().unwrap()).unwrap();
+    assert_schema_valid("tavern.urd.md", &json);
+
+    let names = json["display_names"].as_object().unwrap();
+    let entries: Vec<(&str, &str)> = names.iter().map(|(k, v)| (k.as_str(), v.as_str().unwrap())).collect();
+    assert_eq!(entries, vec![("vell", "Captain Vell"), ("drifter", "Stranger")]);
+    // Speech still names the speaker by ID.
+    assert_eq!(json["dialogue"]["tavern/greet"]["prompt"][0]["speaker"], "vell");
+    assert!(!result.diagnostics.all().iter().any(|d| d.code == "URD449"));
+}
+
+#[test]
+fn e2e_world_without_names_has_no_display_names_block() {
+    let json = compile_and_parse("monty-hall.urd.md");
+    assert!(json.get("display_names").is_none());
+}
+
+// ── Single-symbol emission ──
+
+/// Every entity, location, and section emitted on its own, compared with
+/// its entry in the full output. Returns how many entries were compared.
+fn assert_single_symbols_match(name: &str) -> usize {
+    use urd_compiler::emit::{emit_dialogue_section, emit_entity, emit_location};
+
+    let result = compile_fixture(name);
+    let full = compile_and_parse(name);
+    let graph = result.graph.as_ref().unwrap();
+    let st = result.symbol_table.as_ref().unwrap();
+
+    let mut compared = 0;
+    let mut check = |block: &str, build: &dyn Fn(&str) -> Option<serde_json::Value>| {
+        for (id, expected) in full[block].as_object().into_iter().flatten() {
+            assert_eq!(build(id).as_ref(), Some(expected), "{} {}/{}", name, block, id);
+            compared += 1;
+        }
+    };
+    check("entities", &|id| emit_entity(st, id));
+    check("locations", &|id| emit_location(graph, st, id));
+    check("dialogue", &|id| emit_dialogue_section(graph, st, id));
+    compared
+}
+
+#[test]
+fn e2e_single_symbols_match_full_output_key_puzzle() {
+    assert!(assert_single_symbols_match("two-room-key-puzzle.urd.md") > 0);
+}
+
+#[test]
+fn e2e_single_symbols_match_full_output_nested_choices_and_imports() {
+    for name in ["locked-garden.urd.md", "interrogation/main.urd.md", "location-hooks.urd.md"] {
+        assert!(assert_single_symbols_match(name) > 0, "{}", name);
+    }
+}
+
+#[test]
+fn e2e_single_section_instances_match_full_output() {
+    assert_single_symbols_match("parameterised-sections.urd.md");
+
+    let result = compile_fixture("parameterised-sections.urd.md");
+    let graph = result.graph.as_ref().unwrap();
+    let st = result.symbol_table.as_ref().unwrap();
+    let template = st.sections.values().find(|s| s.param.is_some()).unwrap();
+    // The template itself emits nothing.
+    assert!(urd_compiler::emit::emit_dialogue_section(graph, st, &template.compiled_id).is_none());
+}
+
+#[test]
+fn e2e_single_symbol_unknown_id_is_none() {
+    use urd_compiler::emit::{emit_dialogue_section, emit_entity, emit_location};
+
+    let result = compile_fixture("two-room-key-puzzle.urd.md");
+    let graph = result.graph.as_ref().unwrap();
+    let st = result.symbol_table.as_ref().unwrap();
+    assert!(emit_entity(st, "nobody").is_none());
+    assert!(emit_location(graph, st, "nowhere").is_none());
+    assert!(emit_dialogue_section(graph, st, "nowhere/nothing").is_none());
+    assert!(emit_dialogue_section(graph, st, "nowhere/nothing@nobody").is_none());
+}
+
+// ── Rule select variables ──
+
+/// A world of three doors and a rule, `rule`, placed after the stage.
+fn doors_world(types: &str, entities: &str, rule: &str) -> urd_compiler::CompilationResult {
+    let source = format!(
+        "---\nworld:\n  name: doors\n  start: stage\ntypes:\n  Host [interactable]:\n    calm: bool = true\n{}\
+         entities:\n  @host: Host\n{}---\n\n# Stage\n\n[@host]\n\n{}",
+        types, entities, rule,
+    );
+    urd_compiler::compile_source("doors.urd.md", &source)
+}
+
+const DOOR_TYPES: &str = "  Door [interactable]:\n    ~prize: enum(goat, car)\n    revealed: bool = false\n";
+const DOOR_ENTITIES: &str = "  @door_1: Door { prize: \"goat\" }\n  @door_2: Door { prize: \"car\" }\n";
+
+fn codes(result: &urd_compiler::CompilationResult) -> Vec<&str> {
+    result.diagnostics.all().iter().map(|d| d.code.as_str()).collect()
+}
+
+#[test]
+fn e2e_rule_select_variable_lowers_with_sigil() {
+    let rule = "rule open_goat:\n  actor: @host action reveal\n  selects door from [@door_1, @door_2]\n    \
+                where door.prize == goat\n  where $door.revealed == false\n  > reveal door.prize\n  \
+                > $door.revealed = true\n";
+    let result = doors_world(DOOR_TYPES, DOOR_ENTITIES, rule);
+    assert!(result.success, "{}", format_diagnostics(&result.diagnostics));
+    let json: serde_json::Value = serde_json::from_str(result.world.as_ref().unwrap()).unwrap();
+    assert_schema_valid("doors.urd.md", &json);
+
+    let emitted = &json["rules"]["open_goat"];
+    assert_eq!(emitted["select"]["as"], "door");
+    let filters = serde_json::json!(["$door.prize == goat", "$door.revealed == false"]);
+    assert_eq!(emitted["select"]["where"], filters);
+    let expected = serde_json::json!([
+        { "reveal": "$door.prize" },
+        { "set": "$door.revealed", "to": true },
+    ]);
+    assert_eq!(emitted["effects"], expected);
+}
+
+#[test]
+fn e2e_rule_select_variable_property_is_type_checked() {
+    let rule = "rule open_goat:\n  actor: @host action reveal\n  selects door from [@door_1, @door_2]\n    \
+                where door.prize == lion\n  > reveal door.colour\n";
+    let result = doors_world(DOOR_TYPES, DOOR_ENTITIES, rule);
+    assert!(!result.success);
+    let messages: Vec<String> = result.diagnostics.all().iter().map(|d| format!("{} {}", d.code, d.message)).collect();
+    assert!(
+        messages.iter().any(|m| m.contains("'lion'") && m.contains("'$door'")),
+        "{:#?}",
+        messages
+    );
+    assert!(
+        messages.contains(&"URD308 Property 'colour' does not exist on type 'Door'.".to_string()),
+        "{:#?}",
+        messages
+    );
+}
+
+#[test]
+fn e2e_rule_without_select_reports_unbound_variable() {
+    let rule = "rule open_goat:\n  actor: @host action reveal\n  > reveal $door.prize\n";
+    let result = doors_world(DOOR_TYPES, DOOR_ENTITIES, rule);
+    assert!(codes(&result).contains(&"URD324"), "{}", format_diagnostics(&result.diagnostics));
+}
+
+#[test]
+fn e2e_rule_misspelt_variable_names_the_select_variable() {
+    let rule = "rule open_goat:\n  actor: @host action reveal\n  selects door from [@door_1, @door_2]\n  \
+                > $dor.revealed = true\n";
+    let result = doors_world(DOOR_TYPES, DOOR_ENTITIES, rule);
+    let diag = result.diagnostics.all().iter().find(|d| d.code == "URD324").unwrap();
+    assert_eq!(diag.message, "Unbound select variable '$dor'.");
+    assert_eq!(diag.suggestion.as_deref(), Some("This rule's select variable is '$door'."));
+}
+
+#[test]
+fn e2e_rule_select_over_mixed_types_reports_property_use() {
+    let types = format!("{}  Crate [portable]:\n    prize: string = \"\"\n", DOOR_TYPES);
+    let entities = format!("{}  @crate: Crate\n", DOOR_ENTITIES);
+    let rule = "rule open_any:\n  actor: @host action reveal\n  selects thing from [@door_1, @crate]\n  \
+                > reveal thing.prize\n";
+    let result = doors_world(&types, &entities, rule);
+    let diag = result.diagnostics.all().iter().find(|d| d.code == "URD325");
+    assert!(diag.is_some(), "{}", format_diagnostics(&result.diagnostics));
+    assert!(diag.unwrap().message.contains("'@door_1' is 'Door' and '@crate' is 'Crate'"));
+}
+
+#[test]
+fn e2e_rule_select_over_mixed_types_may_move_the_variable() {
+    let types = format!("{}  Crate [portable]:\n    full: bool = true\n", DOOR_TYPES);
+    let entities = format!("{}  @crate: Crate\n", DOOR_ENTITIES);
+    let rule = "rule tidy:\n  actor: @host action reveal\n  selects thing from [@door_1, @crate]\n  \
+                > move thing -> player\n";
+    let result = doors_world(&types, &entities, rule);
+    assert!(result.success, "{}", format_diagnostics(&result.diagnostics));
+    assert!(!codes(&result).contains(&"URD325"));
+    let json: serde_json::Value = serde_json::from_str(result.world.as_ref().unwrap()).unwrap();
+    assert_eq!(json["rules"]["tidy"]["effects"], serde_json::json!([{ "move": "$thing", "to": "player" }]));
+}
+
+#[test]
+fn e2e_rule_select_emits_resolved_candidates() {
+    let rule = "rule open_goat:\n  actor: @host action reveal\n  selects door from [@door_1, @door_2]\n  \
+                > reveal door.prize\n";
+    let result = doors_world(DOOR_TYPES, DOOR_ENTITIES, rule);
+    assert!(result.success, "{}", format_diagnostics(&result.diagnostics));
+    let json: serde_json::Value = serde_json::from_str(result.world.as_ref().unwrap()).unwrap();
+    assert_eq!(json["rules"]["open_goat"]["select"]["from"], serde_json::json!(["door_1", "door_2"]));
+}
+
+#[test]
+fn e2e_rule_select_from_unknown_entity_is_an_error() {
+    let rule = "rule open_goat:\n  actor: @host action reveal\n  selects door from [@door_1, @door_3]\n  \
+                > reveal door.prize\n";
+    let result = doors_world(DOOR_TYPES, DOOR_ENTITIES, rule);
+    assert!(!result.success);
+    let diag = result.diagnostics.all().iter().find(|d| d.code == "URD301").unwrap();
+    assert_eq!(diag.message, "Unresolved entity reference '@door_3'.");
+    assert_eq!(diag.suggestion.as_deref(), Some("Did you mean '@door_1'?"));
+}
+
+// ── Inherited speakers ──
+
+/// A harbour with Maren and a guard, and `section` after its heading.
+fn harbour_world(section: &str) -> urd_compiler::CompilationResult {
+    let source = format!(
+        "---\nworld:\n  name: harbour\n  start: harbour\ntypes:\n  Person [interactable]:\n    \
+         name: string = \"\"\nentities:\n  @maren: Person {{ name: \"Maren\" }}\n  \
+         @guard: Person {{ name: \"Guard\" }}\n---\n\n# Harbour\n\n[@maren, @guard]\n\n{}",
+        section,
+    );
+    urd_compiler::compile_source("harbour.urd.md", &source)
+}
+
+fn harbour_section(result: &urd_compiler::CompilationResult) -> serde_json::Value {
+    assert!(result.success, "{}", format_diagnostics(&result.diagnostics));
+    let json: serde_json::Value = serde_json::from_str(result.world.as_ref().unwrap()).unwrap();
+    assert_schema_valid("harbour.urd.md", &json);
+    json["dialogue"]["harbour/talk"].clone()
+}
+
+#[test]
+fn e2e_quoted_line_inherits_the_prompt_speaker() {
+    let section = "== talk\n\n@maren: Ask, then.\n\n* The ship\n  \"She came in on the tide.\"\n\n\
+                   * The guard\n  * Why is he here?\n    \"He watches the nets.\"\n\n\"That is all I know.\"\n";
+    let talk = harbour_section(&harbour_world(section));
+    let said = |text: &str| serde_json::json!({ "speaker": "maren", "text": text });
+    assert_eq!(talk["choices"][0]["response"], said("She came in on the tide."));
+    let nested = talk["choices"].as_array().unwrap().iter().find(|c| c["label"] == "The guard").unwrap();
+    assert_eq!(nested["choices"][0]["response"], said("He watches the nets."));
+    assert_eq!(talk["on_exhausted"], said("That is all I know."));
+}
+
+#[test]
+fn e2e_explicit_speaker_overrides_inheritance() {
+    let section = "== talk\n\n@maren: Ask, then.\n\n* The guard\n  @guard: Move along.\n  \"Do as he says.\"\n";
+    let talk = harbour_section(&harbour_world(section));
+    let expected = serde_json::json!([
+        { "speaker": "guard", "text": "Move along." },
+        { "speaker": "maren", "text": "Do as he says." },
+    ]);
+    assert_eq!(talk["choices"][0]["response"], expected);
+}
+
+#[test]
+fn e2e_stage_directions_are_not_inherited_speech() {
+    let section = "== talk\n\n@maren: Ask, then.\n\n* The ship\n  @maren looks away.\n  \"Gone.\"\n";
+    let talk = harbour_section(&harbour_world(section));
+    let expected = serde_json::json!([
+        { "direction": "maren looks away." },
+        { "speaker": "maren", "text": "Gone." },
+    ]);
+    assert_eq!(talk["choices"][0]["response"], expected);
+}
+
+#[test]
+fn e2e_quoted_line_without_a_prompt_speaker_is_an_error() {
+    let section = "== talk\n\nThe harbour is quiet.\n\n* The ship\n  \"She came in on the tide.\"\n";
+    let result = harbour_world(section);
+    assert!(!result.success);
+    let diag = result.diagnostics.all().iter().find(|d| d.code == "URD329").expect("URD329");
+    assert_eq!(
+        diag.message,
+        "Quoted line in section 'talk' has no speaker to inherit: the section's prompt has no speech line."
+    );
+    assert_eq!(diag.span.start_line, 22);
+
+    // A choice outside any section has no prompt at all.
+    let result = harbour_world("* Wave\n  \"Hello!\"\n");
+    let diag = result.diagnostics.all().iter().find(|d| d.code == "URD329").expect("URD329");
+    assert_eq!(diag.message, "Quoted line has no speaker to inherit: it is not in a section.");
+}
+
+// ── Location rules ──
+
+#[test]
+fn e2e_indented_rule_is_scoped_to_its_location() {
+    let rule = "  rule calm_host:\n    actor: @host action reveal\n    where @host.calm == false\n    \
+                > @host.calm = true\n\nrule any_room:\n  actor: @host action reveal\n  > @host.calm = false\n";
+    let result = doors_world(DOOR_TYPES, DOOR_ENTITIES, rule);
+    assert!(result.success, "{}", format_diagnostics(&result.diagnostics));
+    let json: serde_json::Value = serde_json::from_str(result.world.as_ref().unwrap()).unwrap();
+    assert_schema_valid("doors.urd.md", &json);
+
+    let scoped = &json["rules"]["calm_host"];
+    assert_eq!(scoped["location"], "stage");
+    let conditions = serde_json::json!(["player.container == stage", "host.calm == false"]);
+    assert_eq!(scoped["conditions"], conditions);
+
+    // At column 0 after the last heading, the rule stays global.
+    let global = &json["rules"]["any_room"];
+    assert!(global.get("location").is_none());
+    assert!(global.get("conditions").is_none());
+}
+
+// ── Rule triggers ──
+
+#[test]
+fn e2e_rule_trigger_typo_suggests_known_kind() {
+    let rule = "rule open_goat:\n  actor: @host acton reveal\n  > @host.calm = false\n";
+    let result = doors_world(DOOR_TYPES, DOOR_ENTITIES, rule);
+    assert!(!result.success);
+    let diag = result.diagnostics.all().iter().find(|d| d.code == "URD454").unwrap();
+    assert_eq!(diag.message, "Rule 'open_goat' has unknown trigger 'acton'.");
+    assert_eq!(diag.suggestion.as_deref(), Some("Did you mean 'action'?"));
+}
+
+#[test]
+fn e2e_rule_trigger_arguments_are_checked() {
+    for (trigger, message) in [
+        ("action", "Rule 'open_goat' has trigger 'action' but does not name the action."),
+        ("always now", "Rule 'open_goat' has trigger 'always now', but 'always' takes no argument."),
+    ] {
+        let rule = format!("rule open_goat:\n  actor: @host {}\n  > @host.calm = false\n", trigger);
+        let result = doors_world(DOOR_TYPES, DOOR_ENTITIES, &rule);
+        let diag = result.diagnostics.all().iter().find(|d| d.code == "URD454");
+        assert_eq!(diag.map(|d| d.message.as_str()), Some(message), "{}", trigger);
+    }
+    for trigger in ["always", "phase_is reveal", "enter stage", "state_change host.calm"] {
+        let rule = format!("rule open_goat:\n  actor: @host {}\n  > @host.calm = false\n", trigger);
+        let result = doors_world(DOOR_TYPES, DOOR_ENTITIES, &rule);
+        assert!(result.success, "{}: {}", trigger, format_diagnostics(&result.diagnostics));
     }
 }
+
+#[test]
+fn e2e_rule_custom_trigger_is_declared_in_world_block() {
+    let source = "---\nworld:\n  name: watch\n  start: wall\n  triggers: [on_turn]\ntypes:\n  Guard [interactable]:\n    \
+                  awake: bool = true\nentities:\n  @guard: Guard\n---\n\n# Wall\n\n[@guard]\n\n\
+                  rule doze:\n  actor: @guard on_turn\n  > @guard.awake = false\n";
+    let result = urd_compiler::compile_source("watch.urd.md", source);
+    assert!(result.success, "{}", format_diagnostics(&result.diagnostics));
+    let json: serde_json::Value = serde_json::from_str(result.world.as_ref().unwrap()).unwrap();
+    assert_eq!(json["rules"]["doze"]["trigger"], "on_turn");
+
+    let result = urd_compiler::compile_source("watch.urd.md", &source.replace("  triggers: [on_turn]\n", ""));
+    let diag = result.diagnostics.all().iter().find(|d| d.code == "URD454").unwrap();
+    assert!(diag.suggestion.as_deref().unwrap().contains("Add 'on_turn' to the world block's 'triggers' list"));
+}
+
+// ── Condition counts ──
+
+/// A world whose one choice is guarded by `conditions`, written under it.
+fn guarded_choice_world(conditions: &str) -> urd_compiler::CompilationResult {
+    let source = format!(
+        "---\nworld:\n  name: counts\n  start: yard\ntypes:\n  Guard [interactable]:\n    n: int = 0\n\
+         entities:\n  @guard: Guard\n---\n\n# Yard\n\n[@guard]\n\n== talk\n\n* Ask\n{}  @guard: No.\n\nThe guard waits.\n",
+        conditions,
+    );
+    urd_compiler::compile_source("counts.urd.md", &source)
+}
+
+fn or_block(count: usize) -> String {
+    let alternatives: String = (0..count).map(|i| format!("    @guard.n == {}\n", i)).collect();
+    format!("  ? any:\n{}", alternatives)
+}
+
+fn and_list(count: usize) -> String {
+    (0..count).map(|i| format!("  ? @guard.n != {}\n", i)).collect()
+}
+
+fn urd455(result: &urd_compiler::CompilationResult) -> Vec<(Severity, String, u32)> {
+    result
+        .diagnostics
+        .all()
+        .iter()
+        .filter(|d| d.code == "URD455")
+        .map(|d| (d.severity, d.message.clone(), d.span.start_line))
+        .collect()
+}
+
+#[test]
+fn e2e_or_block_condition_count_thresholds() {
+    assert!(urd455(&guarded_choice_world(&or_block(31))).is_empty());
+
+    let result = guarded_choice_world(&or_block(32));
+    assert!(result.success, "{}", format_diagnostics(&result.diagnostics));
+    assert_eq!(
+        urd455(&result),
+        vec![(Severity::Warning, "`? any:` block at line 19 has 32 conditions (error at 256).".to_string(), 19)],
+    );
+
+    let result = guarded_choice_world(&or_block(256));
+    assert!(!result.success);
+    assert_eq!(urd455(&result)[0].0, Severity::Error);
+}
+
+#[test]
+fn e2e_condition_list_count_thresholds() {
+    assert!(urd455(&guarded_choice_world(&and_list(31))).is_empty());
+
+    let result = guarded_choice_world(&and_list(32));
+    let diags = urd455(&result);
+    assert_eq!(diags, vec![(Severity::Warning, "Condition list at line 19 has 32 conditions (error at 256).".to_string(), 19)]);
+    let diag = result.diagnostics.all().iter().find(|d| d.code == "URD455").unwrap();
+    assert_eq!(diag.span.end_line, 50);
+
+    assert_eq!(urd455(&guarded_choice_world(&and_list(256)))[0].0, Severity::Error);
+    // An or-block between `?` lines splits them into two lists.
+    let split = format!("{}{}{}", and_list(20), or_block(2), and_list(20));
+    assert!(urd455(&guarded_choice_world(&split)).is_empty());
+}
+
+#[test]
+fn e2e_rule_where_clause_count_threshold() {
+    let wheres: String = (0..32).map(|_| "  where @host.calm == true\n").collect();
+    let rule = format!("rule settle:\n  actor: @host always\n{}  > @host.calm = false\n", wheres);
+    let result = doors_world("", "", &rule);
+    assert_eq!(
+        urd455(&result),
+        vec![(Severity::Warning, "Where clause of rule 'settle' at line 16 has 32 conditions (error at 256).".to_string(), 16)],
+    );
+}
+
+#[test]
+fn e2e_pathological_or_block_compiles_quickly() {
+    let start = std::time::Instant::now();
+    let result = guarded_choice_world(&or_block(4_000));
+    assert!(start.elapsed() < std::time::Duration::from_secs(10), "took {:?}", start.elapsed());
+    assert!(result.world.is_none());
+    assert_eq!(urd455(&result).len(), 1);
+}
+
+#[test]
+fn e2e_huge_namespace_skips_suggestions() {
+    let entities: String = (0..10_001).map(|i| format!("  @guard_{}: Guard\n", i)).collect();
+    let source = format!(
+        "---\nworld:\n  name: crowd\n  start: yard\ntypes:\n  Guard [interactable]:\n    n: int = 0\n\
+         entities:\n{}---\n\n# Yard\n\n[@guard_0]\n\n== talk\n\n* Ask\n  ? @guard_00.n == 1\n  @guard_0: No.\n",
+        entities,
+    );
+    let result = urd_compiler::compile_source("crowd.urd.md", &source);
+    let diag = result.diagnostics.all().iter().find(|d| d.code == "URD301").unwrap();
+    assert_eq!(diag.message, "Unresolved entity reference '@guard_00'.");
+    assert_eq!(diag.suggestion, None);
+}
+
+// ── Frontmatter block scalars ──
+
+#[test]
+fn e2e_block_scalars_reach_the_emitted_world() {
+    let source = "---\nworld:\n  name: cell\n  description: |\n    A cold cell.\n    Water drips.\n  start: cell\n\
+                  types:\n  Note [portable]:\n    text: string = >\n      Meet me\n      at dawn.\n\
+                  entities:\n  @note: Note\n---\n\n# Cell\n\nStone walls.\n\n[@note]\n";
+    let result = urd_compiler::compile_source("cell.urd.md", source);
+    assert!(result.success, "{}", format_diagnostics(&result.diagnostics));
+    let json: serde_json::Value = serde_json::from_str(result.world.as_ref().unwrap()).unwrap();
+    assert_schema_valid("cell.urd.md", &json);
+
+    assert_eq!(json["world"]["description"], "A cold cell.\nWater drips.");
+    assert_eq!(json["world"]["start"], "cell");
+    assert_eq!(json["types"]["Note"]["properties"]["text"]["default"], "Meet me at dawn.");
+}
+
+// ── Endings ──
+
+#[test]
+fn e2e_endings_reach_the_emitted_world() {
+    let source = "---\nworld:\n  name: cell\n  start: cell\nendings:\n  escaped: You slip away.\n  caught:\n---\n\n\
+                  # Cell\n\n-> north: Yard\n  > end escaped\n\n== wait\n\n* Wait\n  > end caught\n\n# Yard\n";
+    let result = urd_compiler::compile_source("cell.urd.md", source);
+    assert!(result.success, "{}", format_diagnostics(&result.diagnostics));
+    let json: serde_json::Value = serde_json::from_str(result.world.as_ref().unwrap()).unwrap();
+    assert_schema_valid("cell.urd.md", &json);
+
+    assert_eq!(
+        json["endings"],
+        serde_json::json!({ "escaped": { "description": "You slip away." }, "caught": {} })
+    );
+    let keys: Vec<&str> = json.as_object().unwrap().keys().map(String::as_str).collect();
+    assert_eq!(keys, ["world", "requires", "locations", "actions", "endings", "dialogue"]);
+    assert_eq!(
+        json["locations"]["cell"]["exits"]["north"]["on_traverse"],
+        serde_json::json!([{ "end": "escaped" }])
+    );
+    assert_eq!(json["requires"]["effects"], serde_json::json!(["end"]));
+    assert_eq!(result.world, urd_compiler::compile_source("cell.urd.md", source).world);
+}
+
+// ── Mixed conditions ──
+
+#[test]
+fn e2e_and_condition_beside_or_block_is_kept() {
+    // Until mixed forms were emitted as `all`, the or-block replaced the
+    // trust requirement and the choice was offered to any helpful guard.
+    let source = "---\nworld:\n  name: gate\n  start: gate\ntypes:\n  Guard [interactable]:\n    trust: int = 0\n\
+                  \x20   mood: enum(neutral, helpful, angry) = neutral\nentities:\n  @guard: Guard\n---\n\n# Gate\n\n\
+                  [@guard]\n\n== talk\n\n* Ask to pass\n  ? @guard.trust > 10\n  ? any:\n    @guard.mood == helpful\n\
+                  \x20   @guard.mood == neutral\n  @guard: Go on.\n\nThe guard waits.\n";
+    let result = urd_compiler::compile_source("gate.urd.md", source);
+    assert!(result.success, "{}", format_diagnostics(&result.diagnostics));
+    let json: serde_json::Value = serde_json::from_str(result.world.as_ref().unwrap()).unwrap();
+    assert_schema_valid("gate.urd.md", &json);
+
+    assert_eq!(
+        json["dialogue"]["gate/talk"]["choices"][0]["conditions"],
+        serde_json::json!({ "all": ["guard.trust > 10", { "any": ["guard.mood == helpful", "guard.mood == neutral"] }] })
+    );
+    assert_eq!(
+        json["requires"]["conditions"],
+        serde_json::json!(["any", "property_comparison"])
+    );
+
+    // The FactSet and the diff snapshot see all three reads.
+    let choice = &result.fact_set.as_ref().unwrap().choices()[0];
+    assert_eq!(choice.condition_reads.len(), 3);
+    let snapshot = urd_compiler::diff::DiffSnapshot::from_compilation(&result);
+    assert_eq!(snapshot.choices["gate/talk/ask-to-pass"].condition_count, 3);
+}
+
+// ── Text variants ──
+
+fn compile_variants(format: urd_compiler::emit::Format) -> urd_compiler::CompilationResult {
+    let options = urd_compiler::CompileOptions {
+        emit: urd_compiler::emit::EmitOptions { format, ..Default::default() },
+        ..Default::default()
+    };
+    let result = urd_compiler::compile_with_options(&fixture_path("text-variants.urd.md"), &options);
+    assert!(result.success, "{}", format_diagnostics(&result.diagnostics));
+    result
+}
+
+#[test]
+fn e2e_format_1_drops_variants_with_a_warning() {
+    let result = compile_variants(urd_compiler::emit::Format::V1);
+    let dropped: Vec<(u32, &str)> = result
+        .diagnostics
+        .all()
+        .iter()
+        .filter(|d| d.code == "URD501")
+        .map(|d| (d.span.start_line, d.message.as_str()))
+        .collect();
+    assert_eq!(dropped.len(), 4, "one per varied line: {:?}", dropped);
+    assert_eq!(
+        dropped[1],
+        (22, "Text variants need format 2. Only the first line is emitted; 2 variants are dropped.")
+    );
+
+    let json: serde_json::Value = serde_json::from_str(result.world.as_ref().unwrap()).unwrap();
+    assert_schema_valid("text-variants.urd.md", &json);
+    assert_eq!(json["world"]["urd"], "1");
+    assert_eq!(json["dialogue"]["text-variants/crossing"]["prompt"]["text"], "Going across?");
+    assert_eq!(
+        json["locations"]["dock"]["description"],
+        "Gulls wheel over the water.\n\nThe ferry rocks at its mooring."
+    );
+}
+
+#[test]
+fn e2e_format_2_emits_variants() {
+    let result = compile_variants(urd_compiler::emit::Format::V2);
+    assert!(result.diagnostics.all().iter().all(|d| d.code != "URD501"));
+
+    let json: serde_json::Value = serde_json::from_str(result.world.as_ref().unwrap()).unwrap();
+    assert_schema_valid("text-variants.urd.md", &json);
+    assert_eq!(json["world"]["urd"], "2");
+    let section = &json["dialogue"]["text-variants/crossing"];
+    assert_eq!(
+        section["prompt"],
+        serde_json::json!({
+            "speaker": "ferryman",
+            "text": { "variants": ["Going across?", "Another one for the far shore?", "Back again?"] }
+        })
+    );
+    assert_eq!(
+        section["choices"][0]["response"]["text"],
+        serde_json::json!({ "variants": ["One coin.", "Whatever you can spare."] })
+    );
+    // Only the varied paragraph changes between description variants.
+    assert_eq!(
+        json["locations"]["dock"]["description"],
+        serde_json::json!({
+            "variants": [
+                "Gulls wheel over the water.\n\nThe ferry rocks at its mooring.",
+                "Rain lashes the pier.\n\nThe ferry rocks at its mooring."
+            ]
+        })
+    );
+    assert_eq!(
+        section["on_exhausted"]["text"],
+        serde_json::json!({ "variants": ["The ferryman has nothing more to say.", "The ferryman turns back to his ropes."] })
+    );
+    // Choices without variants are unchanged.
+    assert_eq!(section["choices"][1]["label"], "Wave");
+}
+
+// ═══════════════════════════════════════════════════════════════════════════
+// Dialogue mode — a world with no locations
+// ═══════════════════════════════════════════════════════════════════════════
+
+#[test]
+fn e2e_dialogue_mode_compiles_without_warnings() {
+    let result = compile_fixture("dialogue-mode.urd.md");
+    assert!(
+        result.diagnostics.all().iter().all(|d| d.severity == Severity::Info && d.code != "URD609"),
+        "Diagnostics:\n{}",
+        format_diagnostics(&result.diagnostics)
+    );
+
+    let json = compile_and_parse("dialogue-mode.urd.md");
+    assert_eq!(json["world"]["mode"], "dialogue");
+    let keys: Vec<&str> = json.as_object().unwrap().keys().map(|k| k.as_str()).collect();
+    assert_eq!(keys, ["world", "requires", "types", "entities", "display_names", "actions", "dialogue"]);
+    assert_eq!(json["dialogue"]["dialogue-mode/challenge"]["prompt"]["speaker"], "guard");
+}
+
+#[test]
+fn e2e_default_mode_is_unchanged() {
+    let source = std::fs::read_to_string(fixture_path("dialogue-mode.urd.md")).unwrap();
+    let source = source.replace("  mode: dialogue\n", "");
+    let result = urd_compiler::compile_source("dialogue-mode.urd.md", &source);
+    assert!(result.success, "{}", format_diagnostics(&result.diagnostics));
+    assert!(result.diagnostics.all().iter().any(|d| d.code == "URD609"));
+
+    let json: serde_json::Value = serde_json::from_str(result.world.as_ref().unwrap()).unwrap();
+    assert!(json["world"].get("mode").is_none(), "{}", json["world"]);
+    let with_mode = compile_and_parse("dialogue-mode.urd.md");
+    assert_eq!(json["dialogue"], with_mode["dialogue"]);
+}
+
+#[test]
+fn e2e_dialogue_mode_with_a_location_warns() {
+    let source = std::fs::read_to_string(fixture_path("dialogue-mode.urd.md")).unwrap();
+    let source = format!("{}\n# Guardhouse\n\nA cramped stone room.\n", source);
+    let result = urd_compiler::compile_source("dialogue-mode.urd.md", &source);
+    assert!(result.success, "{}", format_diagnostics(&result.diagnostics));
+    let warnings: Vec<&str> = result
+        .diagnostics
+        .all()
+        .iter()
+        .filter(|d| d.severity == Severity::Warning)
+        .map(|d| d.code.as_str())
+        .collect();
+    assert_eq!(warnings, ["URD461"]);
+}
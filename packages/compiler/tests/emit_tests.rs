@@ -1,23 +1,26 @@
 // Tests for Phase 5: EMIT
 //
 // Test categories from the EMIT phase brief:
-// 1. World block (4)
-// 2. Type block (6)
-// 3. Entity block (5)
-// 4. Location block (6)
-// 5. Condition lowering (8)
+// 1. World block (5)
+// 2. Type block (7)
+// 3. Entity block (6)
+// 4. Location block (10)
+// 5. Condition lowering (10)
 // 6. Effect lowering (8)
-// 7. Sequence and advance (5)
-// 8. Dialogue block (14)
-// 9. Determinism (4)
+// 7. Sequence and advance (7)
+// 8. Dialogue block (19)
+// 9. Determinism (5)
 // 10. Integration (4)
 
+use std::sync::OnceLock;
+
 use indexmap::IndexMap;
 use urd_compiler::ast::*;
 use urd_compiler::diagnostics::DiagnosticCollector;
 use urd_compiler::graph::{CompilationUnit, DependencyGraph, FileNode};
 use urd_compiler::link;
 use urd_compiler::emit;
+use urd_compiler::schema;
 use urd_compiler::validate;
 use urd_compiler::span::Span;
 
@@ -33,6 +36,7 @@ fn make_file_ast(path: &str, frontmatter: Option<Frontmatter>, content: Vec<Cont
         frontmatter,
         content,
         span: span(path, 1),
+        source_hash: None,
     }
 }
 
@@ -56,6 +60,7 @@ fn make_type_def(name: &str, traits: Vec<&str>, properties: Vec<PropertyDef>) ->
         name: name.to_string(),
         traits: traits.into_iter().map(String::from).collect(),
         properties,
+        doc: None,
         span: span("test.urd.md", 2),
     })
 }
@@ -92,7 +97,6 @@ fn make_property_with_values(name: &str, prop_type: &str, values: Vec<&str>) ->
     }
 }
 
-#[allow(dead_code)]
 fn make_property_with_range(name: &str, prop_type: &str, min: Option<f64>, max: Option<f64>) -> PropertyDef {
     PropertyDef {
         min,
@@ -122,6 +126,7 @@ fn make_entity_decl(id: &str, type_name: &str, overrides: Vec<(&str, Scalar)>) -
         type_name: type_name.to_string(),
         property_overrides: overrides.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
         annotation: None,
+        doc: None,
         span: span("test.urd.md", 4),
     })
 }
@@ -136,6 +141,8 @@ fn make_world_block(fields: Vec<(&str, Scalar)>) -> FrontmatterValue {
 fn location(name: &str) -> ContentNode {
     ContentNode::LocationHeading(LocationHeading {
         display_name: name.to_string(),
+        explicit_id: None,
+        doc: None,
         span: span("test.urd.md", 10),
     })
 }
@@ -143,6 +150,9 @@ fn location(name: &str) -> ContentNode {
 fn section(name: &str) -> ContentNode {
     ContentNode::SectionLabel(SectionLabel {
         name: name.to_string(),
+        explicit_id: None,
+        param: None,
+        doc: None,
         span: span("test.urd.md", 15),
     })
 }
@@ -150,6 +160,7 @@ fn section(name: &str) -> ContentNode {
 fn prose(text: &str) -> ContentNode {
     ContentNode::Prose(Prose {
         text: text.to_string(),
+        variants: Vec::new(),
         span: span("test.urd.md", 11),
     })
 }
@@ -213,7 +224,10 @@ fn property_comparison(entity_ref: &str, property: &str, op: &str, value: &str)
             property: property.to_string(),
             operator: op.to_string(),
             value: value.to_string(),
+            negated: false,
+            bare: false,
             annotation: None,
+            spans: OperandSpans::whole(),
             span: span("test.urd.md", 40),
         }),
         indent_level: 0,
@@ -221,6 +235,23 @@ fn property_comparison(entity_ref: &str, property: &str, op: &str, value: &str)
     })
 }
 
+/// Mark a property comparison condition as written with `not` (or `!`).
+fn negated(mut node: ContentNode) -> ContentNode {
+    if let ContentNode::Condition(Condition { expr: ConditionExpr::PropertyComparison(pc), .. }) = &mut node {
+        pc.negated = true;
+    }
+    node
+}
+
+/// `? @entity.property` — a bare boolean read.
+fn bare_read(entity_ref: &str, property: &str) -> ContentNode {
+    let mut node = property_comparison(entity_ref, property, "==", "true");
+    if let ContentNode::Condition(Condition { expr: ConditionExpr::PropertyComparison(pc), .. }) = &mut node {
+        pc.bare = true;
+    }
+    node
+}
+
 fn containment_check(entity_ref: &str, container_ref: &str, negated: bool) -> ContentNode {
     ContentNode::Condition(Condition {
         expr: ConditionExpr::ContainmentCheck(ContainmentCheck {
@@ -247,12 +278,34 @@ fn exhaustion_check(section_name: &str) -> ContentNode {
     })
 }
 
+fn visited_check(location_ref: &str, negated: bool) -> ContentNode {
+    ContentNode::Condition(Condition {
+        expr: ConditionExpr::VisitedCheck(VisitedCheck {
+            location_ref: location_ref.to_string(),
+            negated,
+            annotation: None,
+            span: span("test.urd.md", 43),
+        }),
+        indent_level: 0,
+        span: span("test.urd.md", 43),
+    })
+}
+
+fn once_condition() -> ContentNode {
+    ContentNode::Condition(Condition {
+        expr: ConditionExpr::Once(OnceCondition { span: span("test.urd.md", 21) }),
+        indent_level: 1,
+        span: span("test.urd.md", 21),
+    })
+}
+
 fn set_effect(target_prop: &str, value: &str) -> ContentNode {
     ContentNode::Effect(Effect {
         effect_type: EffectType::Set {
             target_prop: target_prop.to_string(),
             operator: "=".to_string(),
             value_expr: value.to_string(),
+            spans: OperandSpans::whole(),
         },
         indent_level: 0,
         annotation: None,
@@ -266,6 +319,7 @@ fn set_effect_arithmetic(target_prop: &str, operator: &str, value: &str) -> Cont
             target_prop: target_prop.to_string(),
             operator: operator.to_string(),
             value_expr: value.to_string(),
+            spans: OperandSpans::whole(),
         },
         indent_level: 0,
         annotation: None,
@@ -311,16 +365,27 @@ fn entity_speech(entity_ref: &str, text: &str) -> ContentNode {
     ContentNode::EntitySpeech(EntitySpeech {
         entity_ref: entity_ref.to_string(),
         text: text.to_string(),
+        variants: Vec::new(),
         annotation: None,
         span: span("test.urd.md", 30),
     })
 }
 
+fn stage_direction(entity_ref: &str, text: &str) -> ContentNode {
+    ContentNode::StageDirection(StageDirection {
+        entity_ref: entity_ref.to_string(),
+        text: text.to_string(),
+        annotation: None,
+        span: span("test.urd.md", 31),
+    })
+}
+
 fn entity_presence(refs: Vec<&str>) -> ContentNode {
     let len = refs.len();
     ContentNode::EntityPresence(EntityPresence {
         entity_refs: refs.into_iter().map(String::from).collect(),
         annotations: vec![None; len],
+        property_overrides: vec![Vec::new(); len],
         span: span("test.urd.md", 12),
     })
 }
@@ -335,6 +400,14 @@ fn exit_decl(direction: &str, destination: &str) -> ContentNode {
     })
 }
 
+fn location_hook(kind: HookKind, children: Vec<ContentNode>) -> ContentNode {
+    ContentNode::LocationHook(LocationHook {
+        kind,
+        children,
+        span: span("test.urd.md", 25),
+    })
+}
+
 fn exit_decl_with_children(direction: &str, destination: &str, children: Vec<ContentNode>) -> ContentNode {
     ContentNode::ExitDeclaration(ExitDeclaration {
         direction: direction.to_string(),
@@ -357,6 +430,18 @@ fn jump(target: &str) -> ContentNode {
     ContentNode::Jump(Jump {
         target: target.to_string(),
         is_exit_qualified: false,
+        argument: None,
+        indent_level: 0,
+        annotation: None,
+        span: span("test.urd.md", 35),
+    })
+}
+
+fn exit_jump(direction: &str) -> ContentNode {
+    ContentNode::Jump(Jump {
+        target: direction.to_string(),
+        is_exit_qualified: true,
+        argument: None,
         indent_level: 0,
         annotation: None,
         span: span("test.urd.md", 35),
@@ -374,6 +459,7 @@ fn phase_heading(name: &str, auto: bool) -> ContentNode {
     ContentNode::PhaseHeading(PhaseHeading {
         display_name: name.to_string(),
         auto,
+        section: None,
         span: span("test.urd.md", 56),
     })
 }
@@ -440,9 +526,35 @@ fn link_and_emit(cu: CompilationUnit) -> (String, DiagnosticCollector) {
         diag.all().iter().map(|d| &d.message).collect::<Vec<_>>()
     );
     let json = emit::emit(&linked.graph, &linked.symbol_table, &mut diag);
+    assert_schema_valid(&json);
+    assert_streams_identically(&linked, &emit::EmitOptions::default(), &json);
     (json, diag)
 }
 
+/// Fail unless `emit_to_writer()` writes exactly `json`, so every test
+/// that emits also checks the streaming path.
+fn assert_streams_identically(linked: &link::LinkedWorld, options: &emit::EmitOptions, json: &str) {
+    let mut bytes = Vec::new();
+    emit::emit_to_writer(&linked.graph, &linked.symbol_table, options, &mut bytes).unwrap();
+    assert_eq!(String::from_utf8(bytes).unwrap(), json, "streamed output differs");
+}
+
+/// Fail unless the output conforms to the published world schema, so the
+/// schema and EMIT cannot drift apart.
+fn assert_schema_valid(json: &str) {
+    static VALIDATOR: OnceLock<jsonschema::Validator> = OnceLock::new();
+    let validator = VALIDATOR.get_or_init(|| {
+        let schema: serde_json::Value = serde_json::from_str(schema::world_schema()).expect("schema is JSON");
+        jsonschema::validator_for(&schema).expect("schema compiles")
+    });
+    let instance: serde_json::Value = serde_json::from_str(json).expect("EMIT output should be valid JSON");
+    let errors: Vec<String> = validator
+        .iter_errors(&instance)
+        .map(|e| format!("{} at {}", e, e.instance_path))
+        .collect();
+    assert!(errors.is_empty(), "Output does not match the world schema: {:?}\n{}", errors, json);
+}
+
 /// Link + validate + emit, returning parsed JSON.
 fn emit_json(cu: CompilationUnit) -> serde_json::Value {
     let (json, _) = link_and_emit(cu);
@@ -458,6 +570,7 @@ where
     let mut linked = link::link(cu, &mut diag);
     modify(&mut linked.symbol_table);
     let json = emit::emit(&linked.graph, &linked.symbol_table, &mut diag);
+    assert_schema_valid(&json);
     (json, diag)
 }
 
@@ -549,9 +662,33 @@ fn world_absent_optional_fields() {
     assert!(!obj.contains_key("description"));
     assert!(!obj.contains_key("author"));
     assert!(!obj.contains_key("entry"));
+    assert!(!obj.contains_key("start_inventory"));
     assert!(!obj.contains_key("seed"));
 }
 
+#[test]
+fn world_start_inventory_key_order() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
+            fm_entry("rusty_key", make_entity_decl("rusty_key", "Key", vec![])),
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("seed", Scalar::Integer(7)),
+                ("start_inventory", Scalar::List(vec![Scalar::EntityRef("rusty_key".to_string())])),
+                ("start", Scalar::String("Cell".to_string())),
+            ])),
+        ])),
+        vec![location("Cell")],
+    );
+    let json = emit_json(single_file_cu(ast));
+    let world = json["world"].as_object().unwrap();
+    assert_eq!(json["world"]["start_inventory"], serde_json::json!(["rusty_key"]));
+    let keys: Vec<&str> = world.keys().map(|k| k.as_str()).collect();
+    assert_eq!(keys, vec!["name", "urd", "start", "start_inventory", "seed"]);
+}
+
 // ── Type Block Tests ──
 
 #[test]
@@ -625,6 +762,66 @@ fn type_visible_property_default() {
     assert!(json["types"]["Item"]["properties"]["name"].get("visibility").is_none());
 }
 
+#[test]
+fn number_defaults_emit_canonically() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("start", Scalar::String("Cell".to_string())),
+            ])),
+            fm_entry("Gauge", make_type_def("Gauge", vec![], vec![
+                make_property_with_default("tenth", "number", Scalar::Number(0.1)),
+                make_property_with_default("huge", "number", Scalar::Number(1e21)),
+                make_property_with_default("zero", "number", Scalar::Number(-0.0)),
+                make_property_with_default("whole", "number", Scalar::Number(3.0)),
+                make_property_with_range("level", "number", Some(-2.0), Some(0.75)),
+                make_property_with_range("count", "integer", Some(0.0), Some(100.0)),
+            ])),
+        ])),
+        vec![location("Cell")],
+    );
+    let json = emit_json(single_file_cu(ast));
+    let props = &json["types"]["Gauge"]["properties"];
+    let emitted = |prop: &str, key: &str| serde_json::to_string(&props[prop][key]).unwrap();
+    assert_eq!(emitted("tenth", "default"), "0.1");
+    assert_eq!(emitted("huge", "default"), "1e+21");
+    assert_eq!(emitted("zero", "default"), "0");
+    assert_eq!(emitted("whole", "default"), "3");
+    assert_eq!(emitted("level", "min"), "-2");
+    assert_eq!(emitted("level", "max"), "0.75");
+    assert_eq!(emitted("count", "min"), "0");
+    assert_eq!(emitted("count", "max"), "100");
+}
+
+#[test]
+fn number_override_emits_shortest_round_trip() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("start", Scalar::String("Cell".to_string())),
+            ])),
+            fm_entry("Gauge", make_type_def("Gauge", vec![], vec![
+                make_property("level", "number"),
+            ])),
+            fm_entry("@dial", make_entity_decl("dial", "Gauge", vec![
+                ("level", Scalar::Number(0.1 + 0.2)),
+            ])),
+            fm_entry("@knob", make_entity_decl("knob", "Gauge", vec![
+                ("level", Scalar::Number(12.0)),
+            ])),
+        ])),
+        vec![location("Cell")],
+    );
+    let (raw, _) = link_and_emit(single_file_cu(ast));
+    assert!(raw.contains("\"level\": 0.30000000000000004"), "{}", raw);
+    assert!(raw.contains("\"level\": 12\n") || raw.contains("\"level\": 12,"), "{}", raw);
+    assert!(!raw.contains("12.0"), "{}", raw);
+}
+
 #[test]
 fn type_with_traits() {
     let ast = make_file_ast(
@@ -669,6 +866,33 @@ fn omit_empty_types_block() {
     assert!(json.get("types").is_none());
 }
 
+#[test]
+fn property_group_nests() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("start", Scalar::String("Cell".to_string())),
+            ])),
+            fm_entry("Guard", make_type_def("Guard", vec![], vec![
+                make_property("appearance.height", "integer"),
+                make_property("name", "string"),
+                make_property_with_values("appearance.eyes", "enum", vec!["grey", "brown"]),
+            ])),
+        ])),
+        vec![location("Cell")],
+    );
+    let json = emit_json(single_file_cu(ast));
+    let props = json["types"]["Guard"]["properties"].as_object().unwrap();
+    // The group sits where its first member was declared.
+    assert_eq!(props.keys().collect::<Vec<_>>(), vec!["appearance", "name"]);
+    let group = props["appearance"].as_object().unwrap();
+    assert_eq!(group.keys().collect::<Vec<_>>(), vec!["height", "eyes"]);
+    assert_eq!(group["height"]["type"], "integer");
+    assert_eq!(group["eyes"]["values"][1], "brown");
+}
+
 // ── Entity Block Tests ──
 
 #[test]
@@ -694,6 +918,35 @@ fn entity_with_overrides() {
     assert_eq!(json["entities"]["rusty_key"]["properties"]["name"], "Rusty Key");
 }
 
+#[test]
+fn entity_presence_override_is_emitted() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("start", Scalar::String("Cell".to_string())),
+            ])),
+            fm_entry("Door", make_type_def("Door", vec![], vec![
+                make_property("locked", "boolean"),
+            ])),
+            fm_entry("cell_door", make_entity_decl("cell_door", "Door", vec![("locked", Scalar::Boolean(true))])),
+        ])),
+        vec![
+            location("Cell"),
+            ContentNode::EntityPresence(EntityPresence {
+                entity_refs: vec!["cell_door".to_string()],
+                annotations: vec![None],
+                property_overrides: vec![vec![("locked".to_string(), Scalar::Boolean(false))]],
+                span: span("test.urd.md", 12),
+            }),
+        ],
+    );
+    let json = emit_json(single_file_cu(ast));
+    assert_eq!(json["entities"]["cell_door"]["properties"]["locked"], false);
+    assert_eq!(json["locations"]["cell"]["contains"], serde_json::json!(["cell_door"]));
+}
+
 #[test]
 fn entity_no_overrides() {
     let ast = make_file_ast(
@@ -770,6 +1023,39 @@ fn entity_ref_override() {
     assert_eq!(json["entities"]["door"]["properties"]["requires"], "rusty_key");
 }
 
+#[test]
+fn entity_override_group_nests() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("start", Scalar::String("Cell".to_string())),
+            ])),
+            fm_entry("Guard", make_type_def("Guard", vec![], vec![
+                make_property("appearance.height", "integer"),
+                make_property("appearance.scarred", "boolean"),
+            ])),
+            fm_entry("guard", make_entity_decl("guard", "Guard", vec![
+                ("appearance.height", Scalar::Integer(190)),
+                ("appearance.scarred", Scalar::Boolean(true)),
+            ])),
+        ])),
+        vec![location("Cell")],
+    );
+    let json = emit_json(single_file_cu(ast));
+    assert_eq!(
+        json["entities"]["guard"]["properties"],
+        serde_json::json!({ "appearance": { "height": 190, "scarred": true } })
+    );
+
+    // Flattening restores the compiler's dotted names.
+    let mut flat = json.clone();
+    urd_compiler::property_groups::flatten_world(&mut flat);
+    assert_eq!(flat["entities"]["guard"]["properties"]["appearance.height"], 190);
+    assert_eq!(flat["types"]["Guard"]["properties"]["appearance.scarred"]["type"], "boolean");
+}
+
 // ── Location Block Tests ──
 
 #[test]
@@ -870,6 +1156,67 @@ fn exit_with_blocked_message() {
     assert_eq!(json["locations"]["cell"]["exits"]["north"]["blocked_message"], "The door is locked.");
 }
 
+fn hook_world_cu() -> CompilationUnit {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("start", Scalar::String("Cell".to_string())),
+            ])),
+            fm_entry("Lamp", make_type_def("Lamp", vec![], vec![
+                make_property_with_default("lit", "boolean", Scalar::Boolean(false)),
+            ])),
+            fm_entry("lamp", make_entity_decl("lamp", "Lamp", vec![])),
+        ])),
+        vec![
+            location("Cell"),
+            prose("A dim stone cell."),
+            location_hook(HookKind::Enter, vec![
+                property_comparison("lamp", "lit", "==", "false"),
+                set_effect("@lamp.lit", "true"),
+            ]),
+            location_hook(HookKind::Exit, vec![
+                set_effect("@lamp.lit", "false"),
+            ]),
+        ],
+    );
+    single_file_cu(ast)
+}
+
+#[test]
+fn location_on_exit_unguarded_is_effect_array() {
+    let json = emit_json(hook_world_cu());
+    let on_exit = &json["locations"]["cell"]["on_exit"];
+    assert!(on_exit.is_array(), "Unguarded hook should be an array: {}", on_exit);
+    assert_eq!(on_exit[0]["set"], "lamp.lit");
+    assert_eq!(on_exit[0]["to"], false);
+}
+
+#[test]
+fn location_on_enter_guarded_wraps_conditions() {
+    let json = emit_json(hook_world_cu());
+    let on_enter = &json["locations"]["cell"]["on_enter"];
+    assert_eq!(on_enter["conditions"][0], "lamp.lit == false");
+    assert_eq!(on_enter["effects"][0]["set"], "lamp.lit");
+    assert_eq!(on_enter["effects"][0]["to"], true);
+}
+
+#[test]
+fn location_hook_does_not_end_description() {
+    let json = emit_json(hook_world_cu());
+    assert_eq!(json["locations"]["cell"]["description"], "A dim stone cell.");
+    let keys: Vec<&String> = json["locations"]["cell"].as_object().unwrap().keys().collect();
+    assert_eq!(keys, vec!["description", "on_enter", "on_exit"]);
+}
+
+#[test]
+fn location_without_hooks_omits_keys() {
+    let json = emit_json(minimal_world_cu());
+    assert!(json["locations"]["cell"].get("on_enter").is_none());
+    assert!(json["locations"]["cell"].get("on_exit").is_none());
+}
+
 #[test]
 fn omit_empty_locations_block() {
     let json = emit_json(minimal_world_cu());
@@ -903,6 +1250,31 @@ fn condition_property_comparison() {
     assert_eq!(json["dialogue"]["test/topics"]["conditions"][0], "guard.mood == neutral");
 }
 
+#[test]
+fn condition_property_group_keeps_dotted_name() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("start", Scalar::String("Cell".to_string())),
+            ])),
+            fm_entry("Guard", make_type_def("Guard", vec![], vec![
+                make_property("appearance.height", "integer"),
+            ])),
+            fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+        ])),
+        vec![
+            location("Cell"),
+            section("topics"),
+            property_comparison("guard", "appearance.height", ">", "180"),
+            choice("Ask", false),
+        ],
+    );
+    let json = emit_json(single_file_cu(ast));
+    assert_eq!(json["dialogue"]["test/topics"]["conditions"][0], "guard.appearance.height > 180");
+}
+
 #[test]
 fn condition_containment_entity() {
     let ast = make_file_ast(
@@ -1020,6 +1392,64 @@ fn condition_exhaustion() {
     assert_eq!(json["dialogue"]["test/farewell"]["conditions"][0], "test/topics.exhausted");
 }
 
+#[test]
+fn qualified_section_references_emit_compiled_id() {
+    let topics = ContentNode::SectionLabel(SectionLabel {
+        name: "topics".to_string(),
+        explicit_id: None,
+        param: None,
+        doc: None,
+        span: span("tavern.urd.md", 3),
+    });
+    let ast_b = make_file_ast("tavern.urd.md", None, vec![location("Tavern"), topics, choice("Ask", false)]);
+    let ast_a = make_file_ast(
+        "main.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("start", Scalar::String("Hall".to_string())),
+            ])),
+            fm_entry("import", FrontmatterValue::ImportDecl(ImportDecl {
+                path: "./tavern.urd.md".to_string(),
+                span: span("main.urd.md", 1),
+            })),
+        ])),
+        vec![
+            location("Hall"),
+            section("talk"),
+            choice_with_content("Go back", false, vec![exhaustion_check("tavern/topics"), jump("tavern/topics")]),
+        ],
+    );
+    let json = emit_json(two_file_cu(ast_b, ast_a));
+    let choice = &json["dialogue"]["main/talk"]["choices"][0];
+    assert_eq!(choice["conditions"][0], "tavern/topics.exhausted");
+    assert_eq!(choice["goto"], "tavern/topics");
+}
+
+#[test]
+fn condition_visited() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("start", Scalar::String("Cell".to_string())),
+            ])),
+        ])),
+        vec![
+            location("Cell"),
+            section("topics"),
+            choice_with_content("Ask about the docks", false, vec![visited_check("the-docks", false)]),
+            choice_with_content("Ask the way", false, vec![visited_check("the-docks", true)]),
+            location("The Docks"),
+        ],
+    );
+    let json = emit_json(single_file_cu(ast));
+    let choices = &json["dialogue"]["test/topics"]["choices"];
+    assert_eq!(choices[0]["conditions"][0], "the-docks.visited");
+    assert_eq!(choices[1]["conditions"][0], "the-docks.visited == false");
+}
+
 #[test]
 fn condition_and() {
     let ast = make_file_ast(
@@ -1074,7 +1504,10 @@ fn condition_or() {
                         property: "mood".to_string(),
                         operator: "==".to_string(),
                         value: "helpful".to_string(),
+                        negated: false,
+                        bare: false,
                         annotation: None,
+                        spans: OperandSpans::whole(),
                         span: span("test.urd.md", 40),
                     }),
                     ConditionExpr::PropertyComparison(PropertyComparison {
@@ -1082,7 +1515,10 @@ fn condition_or() {
                         property: "trust".to_string(),
                         operator: ">".to_string(),
                         value: "50".to_string(),
+                        negated: false,
+                        bare: false,
                         annotation: None,
+                        spans: OperandSpans::whole(),
                         span: span("test.urd.md", 41),
                     }),
                 ],
@@ -1098,6 +1534,194 @@ fn condition_or() {
     assert_eq!(conds["any"].as_array().unwrap().len(), 2);
 }
 
+#[test]
+fn condition_bare_and_negated_lowering() {
+    let or_block = ContentNode::OrConditionBlock(OrConditionBlock {
+        conditions: [negated(bare_read("guard", "alert")), negated(property_comparison("guard", "mood", "==", "helpful"))]
+            .into_iter()
+            .map(|node| match node {
+                ContentNode::Condition(c) => c.expr,
+                _ => unreachable!(),
+            })
+            .collect(),
+        indent_level: 2,
+        span: span("test.urd.md", 39),
+    });
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("start", Scalar::String("Cell".to_string())),
+            ])),
+            fm_entry("Guard", make_type_def("Guard", vec![], vec![
+                make_property_with_values("mood", "enum", vec!["neutral", "helpful"]),
+                make_property("trust", "integer"),
+                make_property("alert", "boolean"),
+            ])),
+            fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+        ])),
+        vec![
+            location("Cell"),
+            section("topics"),
+            choice_with_content("Sneak", false, vec![negated(bare_read("guard", "alert"))]),
+            choice_with_content("Salute", false, vec![bare_read("guard", "alert")]),
+            choice_with_content("Plead", false, vec![negated(property_comparison("guard", "trust", "<", "3"))]),
+            choice_with_content("Wave", false, vec![or_block]),
+        ],
+    );
+    let json = emit_json(single_file_cu(ast));
+    let choices = &json["dialogue"]["test/topics"]["choices"];
+    assert_eq!(choices[0]["conditions"][0], "guard.alert == false");
+    assert_eq!(choices[1]["conditions"][0], "guard.alert == true");
+    assert_eq!(choices[2]["conditions"][0], "guard.trust >= 3");
+    assert_eq!(
+        choices[3]["conditions"]["any"],
+        serde_json::json!(["guard.alert == false", "guard.mood != helpful"])
+    );
+}
+
+#[test]
+fn condition_ref_value_drops_sigil() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("start", Scalar::String("Cell".to_string())),
+            ])),
+            fm_entry("Guard", make_type_def("Guard", vec![], vec![
+                PropertyDef { ref_type: Some("Guard".to_string()), ..make_property("friend", "ref") },
+            ])),
+            fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+            fm_entry("warden", make_entity_decl("warden", "Guard", vec![])),
+        ])),
+        vec![
+            location("Cell"),
+            section("topics"),
+            choice_with_content("Ask", false, vec![property_comparison("guard", "friend", "==", "@warden")]),
+        ],
+    );
+    let json = emit_json(single_file_cu(ast));
+    assert_eq!(json["dialogue"]["test/topics"]["choices"][0]["conditions"], serde_json::json!(["guard.friend == warden"]));
+}
+
+/// An `? any:` block of the given condition nodes.
+fn or_block(conditions: Vec<ContentNode>) -> ContentNode {
+    ContentNode::OrConditionBlock(OrConditionBlock {
+        conditions: conditions
+            .into_iter()
+            .map(|node| match node {
+                ContentNode::Condition(c) => c.expr,
+                _ => unreachable!(),
+            })
+            .collect(),
+        indent_level: 0,
+        span: span("test.urd.md", 39),
+    })
+}
+
+/// A guard world whose `topics` section, `Ask` choice, and `on enter:`
+/// hook each hold `content`.
+fn mixed_conditions_world(content: Vec<ContentNode>) -> serde_json::Value {
+    let mut hook = content.clone();
+    hook.push(set_effect("@guard.trust", "1"));
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("start", Scalar::String("Cell".to_string())),
+            ])),
+            fm_entry("Guard", make_type_def("Guard", vec![], vec![
+                make_property_with_values("mood", "enum", vec!["neutral", "helpful", "angry"]),
+                make_property("trust", "integer"),
+            ])),
+            fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+        ])),
+        [
+            vec![location("Cell"), location_hook(HookKind::Enter, hook), section("topics")],
+            content.clone(),
+            vec![choice_with_content("Ask", false, content)],
+        ]
+        .concat(),
+    );
+    emit_json(single_file_cu(ast))
+}
+
+#[test]
+fn condition_and_with_or_emits_all() {
+    let json = mixed_conditions_world(vec![
+        property_comparison("guard", "trust", ">", "10"),
+        or_block(vec![
+            property_comparison("guard", "mood", "==", "helpful"),
+            property_comparison("guard", "mood", "==", "neutral"),
+        ]),
+    ]);
+    let expected = serde_json::json!({
+        "all": ["guard.trust > 10", { "any": ["guard.mood == helpful", "guard.mood == neutral"] }]
+    });
+    let section = &json["dialogue"]["test/topics"];
+    assert_eq!(section["conditions"], expected);
+    assert_eq!(section["choices"][0]["conditions"], expected);
+    assert_eq!(json["locations"]["cell"]["on_enter"]["conditions"], expected);
+}
+
+#[test]
+fn condition_or_blocks_keep_source_order_after_and() {
+    // AND strings come first wherever they were written; each block stays whole.
+    let json = mixed_conditions_world(vec![
+        or_block(vec![property_comparison("guard", "mood", "==", "helpful")]),
+        property_comparison("guard", "trust", ">", "10"),
+        or_block(vec![
+            property_comparison("guard", "trust", ">", "50"),
+            property_comparison("guard", "mood", "!=", "angry"),
+        ]),
+    ]);
+    assert_eq!(
+        json["dialogue"]["test/topics"]["choices"][0]["conditions"],
+        serde_json::json!({
+            "all": [
+                "guard.trust > 10",
+                { "any": ["guard.mood == helpful"] },
+                { "any": ["guard.trust > 50", "guard.mood != angry"] },
+            ]
+        })
+    );
+}
+
+#[test]
+fn condition_two_or_blocks_emit_all() {
+    let json = mixed_conditions_world(vec![
+        or_block(vec![property_comparison("guard", "mood", "==", "helpful")]),
+        or_block(vec![property_comparison("guard", "trust", ">", "50")]),
+    ]);
+    assert_eq!(
+        json["dialogue"]["test/topics"]["conditions"],
+        serde_json::json!({ "all": [{ "any": ["guard.mood == helpful"] }, { "any": ["guard.trust > 50"] }] })
+    );
+}
+
+#[test]
+fn condition_pure_forms_are_unchanged_by_all() {
+    let and_only = mixed_conditions_world(vec![
+        property_comparison("guard", "trust", ">", "10"),
+        property_comparison("guard", "mood", "==", "helpful"),
+    ]);
+    assert_eq!(
+        and_only["dialogue"]["test/topics"]["choices"][0]["conditions"],
+        serde_json::json!(["guard.trust > 10", "guard.mood == helpful"])
+    );
+    let or_only = mixed_conditions_world(vec![or_block(vec![
+        property_comparison("guard", "mood", "==", "helpful"),
+        property_comparison("guard", "trust", ">", "50"),
+    ])]);
+    assert_eq!(
+        or_only["locations"]["cell"]["on_enter"]["conditions"],
+        serde_json::json!({ "any": ["guard.mood == helpful", "guard.trust > 50"] })
+    );
+}
+
 // ── Effect Lowering Tests ──
 
 #[test]
@@ -1323,6 +1947,52 @@ fn sequence_auto_phase() {
     assert_eq!(phase["advance"], "auto");
 }
 
+#[test]
+fn phase_section_emitted() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("start", Scalar::String("Cell".to_string())),
+            ])),
+        ])),
+        vec![
+            location("Cell"),
+            section("confession"),
+            sequence_heading("Interrogation"),
+            ContentNode::PhaseHeading(PhaseHeading {
+                display_name: "Opening".to_string(),
+                auto: false,
+                section: Some(PhaseSection {
+                    name: "confession".to_string(),
+                    span: span("test.urd.md", 57),
+                }),
+                span: span("test.urd.md", 56),
+            }),
+        ],
+    );
+    let json = emit_json(single_file_cu(ast));
+    let phase = &json["sequences"]["interrogation"]["phases"][0];
+    assert_eq!(phase["section"], "test/confession");
+}
+
+#[test]
+fn phase_without_section_omits_key() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("start", Scalar::String("Cell".to_string())),
+            ])),
+        ])),
+        vec![location("Cell"), sequence_heading("Quest"), phase_heading("Act", false)],
+    );
+    let json = emit_json(single_file_cu(ast));
+    assert!(json["sequences"]["quest"]["phases"][0].get("section").is_none());
+}
+
 #[test]
 fn advance_on_action() {
     let ast = make_file_ast(
@@ -1370,6 +2040,34 @@ fn advance_on_condition_space_free() {
     assert_eq!(json["sequences"]["quest"]["phases"][0]["advance"], "on_condition guard.mood==helpful");
 }
 
+#[test]
+fn advance_on_condition_space_free_every_operator() {
+    for (written, emitted) in [
+        ("gate.count != 3", "gate.count!=3"),
+        ("gate.count <= 3", "gate.count<=3"),
+        ("gate.count >= 3", "gate.count>=3"),
+        ("gate.count < 3", "gate.count<3"),
+        ("gate.count > 3", "gate.count>3"),
+        ("guard.name == \"Old  Tom\"", "guard.name==\"Old  Tom\""),
+    ] {
+        let ast = make_file_ast(
+            "test.urd.md",
+            Some(make_frontmatter(vec![
+                fm_entry("world", make_world_block(vec![
+                    ("name", Scalar::String("test".to_string())),
+                    ("start", Scalar::String("Cell".to_string())),
+                ])),
+            ])),
+            vec![location("Cell"), sequence_heading("Quest"), phase_heading("Wait", false)],
+        );
+        let (json_str, _) = link_modify_and_emit(single_file_cu(ast), |st| {
+            st.sequences.get_mut("quest").unwrap().phases[0].advance = format!("on_condition {}", written);
+        });
+        let json: serde_json::Value = serde_json::from_str(&json_str).unwrap();
+        assert_eq!(json["sequences"]["quest"]["phases"][0]["advance"], format!("on_condition {}", emitted));
+    }
+}
+
 #[test]
 fn advance_end() {
     let ast = make_file_ast(
@@ -1461,6 +2159,35 @@ fn dialogue_sticky_choice() {
     assert_eq!(json["dialogue"]["test/topics"]["choices"][0]["sticky"], true);
 }
 
+#[test]
+fn dialogue_once_choice() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("start", Scalar::String("Cell".to_string())),
+            ])),
+        ])),
+        vec![
+            location("Cell"),
+            section("topics"),
+            choice_with_content("Introduce yourself", false, vec![once_condition()]),
+            choice("Ask about the crew", false),
+            prose("The guard looks away."),
+        ],
+    );
+    let json = emit_json(single_file_cu(ast));
+    let choices = &json["dialogue"]["test/topics"]["choices"];
+    assert_eq!(choices[0]["once"], true);
+    assert_eq!(choices[0]["sticky"], false);
+    assert!(choices[0].get("conditions").is_none(), "once is not a condition: {}", choices[0]);
+    assert!(choices[1].get("once").is_none());
+    let action = &json["actions"]["test/topics/introduce-yourself"];
+    assert!(action.is_object(), "expected action: {}", json["actions"]);
+    assert!(action.get("conditions").is_none(), "once is not an action condition: {}", action);
+}
+
 #[test]
 fn dialogue_choice_with_goto() {
     let ast = make_file_ast(
@@ -1485,9 +2212,27 @@ fn dialogue_choice_with_goto() {
 
 #[test]
 fn dialogue_choice_with_goto_end() {
-    // A choice with no Jump produces no "goto" field.
-    // (In real parsing, `-> end` would be a Jump with target "end",
-    //  but LINK doesn't yet special-case "end" — so we test the no-jump path.)
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("start", Scalar::String("Cell".to_string())),
+            ])),
+        ])),
+        vec![
+            location("Cell"),
+            section("topics"),
+            choice_with_content("Done", false, vec![jump("end")]),
+        ],
+    );
+    let json = emit_json(single_file_cu(ast));
+    assert_eq!(json["dialogue"]["test/topics"]["choices"][0]["goto"], "end");
+}
+
+#[test]
+fn dialogue_choice_without_jump_has_no_goto() {
+    // No jump: the conversation stays in the section.
     let ast = make_file_ast(
         "test.urd.md",
         Some(make_frontmatter(vec![
@@ -1499,7 +2244,63 @@ fn dialogue_choice_with_goto_end() {
         vec![location("Cell"), section("topics"), choice("Done", false)],
     );
     let json = emit_json(single_file_cu(ast));
-    assert!(json["dialogue"]["test/topics"]["choices"][0].get("goto").is_none());
+    let choice = &json["dialogue"]["test/topics"]["choices"][0];
+    assert!(choice.get("goto").is_none());
+    assert!(choice.get("exit").is_none());
+}
+
+#[test]
+fn dialogue_choice_with_exit_jump() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("start", Scalar::String("Cell".to_string())),
+            ])),
+        ])),
+        vec![
+            location("Cell"),
+            exit_decl("north", "Harbor"),
+            section("topics"),
+            choice_with_content("Slip out", false, vec![exit_jump("north")]),
+            choice_with_content("Walk away", false, vec![jump("north")]),
+            location("Harbor"),
+        ],
+    );
+    let json = emit_json(single_file_cu(ast));
+    let choices = &json["dialogue"]["test/topics"]["choices"];
+    for choice in choices.as_array().unwrap() {
+        assert_eq!(choice["exit"], "north", "{}", choice);
+        assert!(choice.get("goto").is_none(), "{}", choice);
+    }
+}
+
+#[test]
+fn dialogue_section_jump_beats_exit_of_same_name() {
+    // `-> north` with a section named north stays a section goto (URD310).
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("start", Scalar::String("Cell".to_string())),
+            ])),
+        ])),
+        vec![
+            location("Cell"),
+            exit_decl("north", "Harbor"),
+            section("topics"),
+            choice_with_content("Go on", false, vec![jump("north")]),
+            section("north"),
+            choice("Bye", false),
+            location("Harbor"),
+        ],
+    );
+    let json = emit_json(single_file_cu(ast));
+    let choice = &json["dialogue"]["test/topics"]["choices"][0];
+    assert_eq!(choice["goto"], "test/north");
+    assert!(choice.get("exit").is_none());
 }
 
 #[test]
@@ -1712,6 +2513,39 @@ fn dialogue_on_exhausted_with_goto() {
     assert_eq!(exhausted["goto"], "test/farewell");
 }
 
+#[test]
+fn dialogue_on_exhausted_with_end_and_exit() {
+    let world = || {
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("start", Scalar::String("Cell".to_string())),
+            ])),
+        ]))
+    };
+    let tail = |jump_node: ContentNode| {
+        vec![
+            location("Cell"),
+            exit_decl("north", "Harbor"),
+            section("topics"),
+            choice("Ask", false),
+            prose("Nothing more to discuss."),
+            jump_node,
+            location("Harbor"),
+        ]
+    };
+
+    let json = emit_json(single_file_cu(make_file_ast("test.urd.md", world(), tail(jump("end")))));
+    let exhausted = &json["dialogue"]["test/topics"]["on_exhausted"];
+    assert_eq!(exhausted["goto"], "end");
+    assert!(exhausted.get("exit").is_none());
+
+    let json = emit_json(single_file_cu(make_file_ast("test.urd.md", world(), tail(exit_jump("north")))));
+    let exhausted = &json["dialogue"]["test/topics"]["on_exhausted"];
+    assert_eq!(exhausted["exit"], "north");
+    assert!(exhausted.get("goto").is_none());
+}
+
 #[test]
 fn dialogue_no_on_exhausted() {
     let ast = make_file_ast(
@@ -1777,6 +2611,80 @@ fn dialogue_section_with_prompt_and_description() {
     assert_eq!(sec["prompt"]["text"], "What'll it be?");
 }
 
+fn guard_world(content: Vec<ContentNode>) -> serde_json::Value {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("name", Scalar::String("test".to_string())),
+                ("start", Scalar::String("Gate".to_string())),
+            ])),
+            fm_entry("NPC", make_type_def("NPC", vec![], vec![])),
+            fm_entry("guard", make_entity_decl("guard", "NPC", vec![])),
+        ])),
+        [vec![location("Gate"), section("talk")], content].concat(),
+    );
+    emit_json(single_file_cu(ast))
+}
+
+#[test]
+fn dialogue_response_preserves_speech_direction_order() {
+    let json = guard_world(vec![choice_with_content("Insult him", false, vec![
+        entity_speech("guard", "Watch your tongue."),
+        stage_direction("guard", "draws his sword."),
+        entity_speech("guard", "Last warning."),
+    ])]);
+    let response = &json["dialogue"]["test/talk"]["choices"][0]["response"];
+    assert_eq!(
+        *response,
+        serde_json::json!([
+            { "speaker": "guard", "text": "Watch your tongue." },
+            { "direction": "guard draws his sword." },
+            { "speaker": "guard", "text": "Last warning." },
+        ])
+    );
+}
+
+#[test]
+fn dialogue_single_speech_response_keeps_object_shape() {
+    let json = guard_world(vec![choice_with_content("Greet", false, vec![
+        entity_speech("guard", "Move along."),
+    ])]);
+    let response = &json["dialogue"]["test/talk"]["choices"][0]["response"];
+    assert_eq!(*response, serde_json::json!({ "speaker": "guard", "text": "Move along." }));
+}
+
+#[test]
+fn dialogue_direction_only_response_is_array() {
+    let json = guard_world(vec![choice_with_content("Wave", false, vec![
+        stage_direction("guard", "ignores you."),
+    ])]);
+    let response = &json["dialogue"]["test/talk"]["choices"][0]["response"];
+    assert_eq!(*response, serde_json::json!([{ "direction": "guard ignores you." }]));
+}
+
+#[test]
+fn dialogue_multi_part_prompt() {
+    let json = guard_world(vec![
+        stage_direction("guard", "blocks the gate."),
+        entity_speech("guard", "Halt."),
+        stage_direction("guard", "squints."),
+        entity_speech("guard", "State your business."),
+        choice("Trade", false),
+    ]);
+    let sec = &json["dialogue"]["test/talk"];
+    // Directions before the first speech stay in the description.
+    assert_eq!(sec["description"], "guard blocks the gate.");
+    assert_eq!(
+        sec["prompt"],
+        serde_json::json!([
+            { "speaker": "guard", "text": "Halt." },
+            { "direction": "guard squints." },
+            { "speaker": "guard", "text": "State your business." },
+        ])
+    );
+}
+
 #[test]
 fn dialogue_nested_choice_generates_action() {
     let ast = make_file_ast(
@@ -1833,6 +2741,13 @@ fn determinism_byte_identical() {
     assert_eq!(json1, json2);
 }
 
+#[test]
+fn determinism_location_hooks() {
+    let (json1, _) = link_and_emit(hook_world_cu());
+    let (json2, _) = link_and_emit(hook_world_cu());
+    assert_eq!(json1, json2);
+}
+
 #[test]
 fn determinism_cross_file_ordering() {
     let ast_b = make_file_ast(
@@ -1996,6 +2911,7 @@ fn integration_multi_file() {
             description: None,
             span: items_span(3),
         }],
+        doc: None,
         span: items_span(2),
     });
     let key_entity = FrontmatterValue::EntityDecl(EntityDecl {
@@ -2003,6 +2919,7 @@ fn integration_multi_file() {
         type_name: "Key".to_string(),
         property_overrides: vec![],
         annotation: None,
+        doc: None,
         span: items_span(4),
     });
     let ast_b = make_file_ast(
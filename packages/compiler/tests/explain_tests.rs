@@ -0,0 +1,169 @@
+/// Tests for choice availability explanations.
+///
+/// Each test compiles a world and calls `explain_choice()` on one choice,
+/// checking the conditions, write sites, and reachability it reports.
+
+use urd_compiler::explain::{self, ChoiceExplanation, ExplainError, Reachability};
+use urd_compiler::facts::FactSite;
+use urd_compiler::{compile, compile_source, CompilationResult};
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn explain(result: &CompilationResult, choice_id: &str) -> Result<ChoiceExplanation, ExplainError> {
+    explain::explain_choice(
+        result.fact_set.as_ref().expect("LINK ran"),
+        result.property_index.as_ref().expect("LINK ran"),
+        choice_id,
+    )
+}
+
+const VAULT: &str = "---
+world:
+  name: vault
+  start: hall
+types:
+  Door [interactable]:
+    state: enum(shut, ajar, open) = shut
+entities:
+  @door: Door
+---
+
+# Hall
+
+[@door]
+
+-> east: Vault
+  ? @door.state == open
+
+== talk
+
+* Pull the lever
+  > @door.state = open
+
+* Knock
+  > @door.state = ajar
+
+* Walk through
+  ? @door.state == open
+  The way is clear.
+
+* Leave
+  Nothing else to do.
+
+# Vault
+
+== inside
+
+* Look around
+  Dust.
+";
+
+// ── Conditions ──
+
+#[test]
+fn enum_condition_names_the_enabling_choice() {
+    let result = compile_source("vault.urd.md", VAULT);
+    let explanation = explain(&result, "vault/talk/walk-through").unwrap();
+
+    assert_eq!(explanation.conditions.len(), 1);
+    let condition = &explanation.conditions[0];
+    assert_eq!(condition.expression(), "@door.state == open");
+    assert_eq!(condition.starting_value, Some("shut".to_string()));
+    assert_eq!(condition.holds_at_start, Some(false));
+
+    let enabling: Vec<&FactSite> = condition.enabling_writes().map(|w| &w.site).collect();
+    assert_eq!(enabling, vec![&FactSite::Choice("vault/talk/pull-the-lever".to_string())]);
+    let knock = condition
+        .writes
+        .iter()
+        .find(|w| w.site == FactSite::Choice("vault/talk/knock".to_string()))
+        .unwrap();
+    assert_eq!(knock.enables, Some(false));
+
+    let summary = explanation.summary();
+    assert!(summary.contains("Needs @door.state == open"), "{}", summary);
+    assert!(summary.contains("enabled by choice vault/talk/pull-the-lever"), "{}", summary);
+}
+
+#[test]
+fn arithmetic_writes_may_enable() {
+    let result = compile(&fixture_path("locked-garden.urd.md"));
+    let explanation = explain(&result, "locked-garden/greet/ask-about-the-garden").unwrap();
+
+    let condition = &explanation.conditions[0];
+    assert_eq!(condition.starting_value, Some("0".to_string()));
+    assert_eq!(condition.holds_at_start, Some(false));
+    let enabling: Vec<String> = condition
+        .enabling_writes()
+        .map(|w| format!("{:?} {}", w.operator, w.value))
+        .collect();
+    assert_eq!(enabling, vec!["Add 1", "Add 5", "Sub 2"]);
+    assert!(!explanation.sticky);
+    assert_eq!(explanation.reachability, Reachability::Start);
+}
+
+#[test]
+fn entity_override_is_the_starting_value() {
+    let result = compile(&fixture_path("locked-garden.urd.md"));
+    let explanation = explain(&result, "locked-garden/greet/force-the-gate").unwrap();
+    let condition = &explanation.conditions[0];
+    assert_eq!(condition.starting_value, Some("neutral".to_string()));
+    assert_eq!(condition.holds_at_start, Some(true));
+}
+
+#[test]
+fn unconditioned_choice_is_trivially_available() {
+    let result = compile_source("vault.urd.md", VAULT);
+    let explanation = explain(&result, "vault/talk/leave").unwrap();
+    assert!(explanation.is_unconditional());
+    assert!(explanation.conditions.is_empty());
+    assert_eq!(explanation.reachability, Reachability::Start);
+    assert!(explanation.summary().contains("It has no conditions"), "{}", explanation.summary());
+}
+
+#[test]
+fn containment_is_another_condition() {
+    let result = compile(&fixture_path("locked-garden.urd.md"));
+    let explanation = explain(&result, "locked-garden/greet/offer-the-journal").unwrap();
+    assert!(explanation.conditions.is_empty());
+    assert_eq!(explanation.other_conditions.len(), 1);
+    assert!(!explanation.is_unconditional());
+}
+
+// ── Reachability ──
+
+#[test]
+fn location_behind_a_conditional_exit_is_gated() {
+    let result = compile_source("vault.urd.md", VAULT);
+    let explanation = explain(&result, "vault/inside/look-around").unwrap();
+    assert_eq!(explanation.location.as_deref(), Some("vault"));
+    assert_eq!(explanation.reachability, Reachability::Gated);
+}
+
+// ── Errors and JSON ──
+
+#[test]
+fn unknown_choice_is_an_error() {
+    let result = compile_source("vault.urd.md", VAULT);
+    let error = explain(&result, "vault/talk/dance").unwrap_err();
+    assert_eq!(error, ExplainError::UnknownChoice("vault/talk/dance".to_string()));
+    assert_eq!(error.to_string(), "Unknown choice 'vault/talk/dance'.");
+}
+
+#[test]
+fn explanation_json_has_documented_shape() {
+    let result = compile_source("vault.urd.md", VAULT);
+    let json = explain(&result, "vault/talk/walk-through").unwrap().to_json();
+    assert_eq!(json["choice_id"], "vault/talk/walk-through");
+    assert_eq!(json["reachability"], "start");
+    assert_eq!(json["unconditional"], false);
+    let condition = &json["conditions"][0];
+    assert_eq!(condition["operator"], "==");
+    assert_eq!(condition["starting_value"], "shut");
+    assert_eq!(condition["holds_at_start"], false);
+    assert_eq!(condition["writes"][0]["site"]["kind"], "choice");
+    assert_eq!(condition["writes"][0]["enables"], true);
+    assert!(json["summary"].is_string());
+}
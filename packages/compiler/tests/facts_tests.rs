@@ -69,6 +69,35 @@ fn facts_key_puzzle_effect_writes() {
     assert_eq!(write.value_expr, "false");
 }
 
+#[test]
+fn facts_key_puzzle_write_names_entity() {
+    let facts = extract_fixture_facts("two-room-key-puzzle.urd.md");
+    assert_eq!(facts.writes()[0].entity.as_deref(), Some("cell_door"));
+    let exit = &facts.exits()[0];
+    assert_eq!(facts.reads()[exit.guard_reads[0]].entity.as_deref(), Some("cell_door"));
+}
+
+#[test]
+fn facts_exit_effects_recorded() {
+    let source = "---\nworld:\n  name: vault\n  start: hall\ntypes:\n  Door [interactable]:\n    \
+                  locked: bool = true\nentities:\n  @door: Door\n---\n\n# Hall\n\n[@door]\n\n\
+                  -> north: Vault\n  ? @door.locked == false\n  ! Locked.\n  > @door.locked = true\n\n\
+                  # Vault\n\n-> south: Hall\n  > @door.locked = false\n";
+    let facts = urd_compiler::compile_source("vault.urd.md", source).fact_set.unwrap();
+
+    let north = facts.exit_by_location_and_name("hall", "north").unwrap();
+    assert!(north.has_effects && north.has_blocked_message && north.is_conditional);
+    assert_eq!(north.effect_writes.len(), 1);
+    let write = &facts.writes()[north.effect_writes[0]];
+    assert_eq!(write.site, FactSite::Exit(north.exit_id()));
+    assert_eq!(write.value_expr, "true");
+    assert_eq!(facts.write_indices_for_site(&write.site), north.effect_writes.as_slice());
+
+    let south = facts.exit_by_location_and_name("vault", "south").unwrap();
+    assert!(south.has_effects && !south.has_blocked_message && !south.is_conditional);
+    assert_eq!(south.effect_writes.len(), 1);
+}
+
 #[test]
 fn facts_key_puzzle_choice_count() {
     let facts = extract_fixture_facts("two-room-key-puzzle.urd.md");
@@ -182,6 +211,20 @@ fn facts_monty_hall_choice_condition() {
     assert_eq!(choice_reads[0].value_literal, "false");
 }
 
+#[test]
+fn facts_monty_hall_select_variable_reads_each_candidate() {
+    let facts = extract_fixture_facts("monty-hall.urd.md");
+    // `where door.prize == goat` is read once per door in the from list.
+    let rule = &facts.rules()[0];
+    let entities: Vec<Option<&str>> = rule
+        .condition_reads
+        .iter()
+        .map(|&i| facts.reads()[i].entity.as_deref())
+        .collect();
+    assert_eq!(entities, vec![Some("door_1"), Some("door_2"), Some("door_3")]);
+    assert!(rule.condition_reads.iter().all(|&i| facts.reads()[i].property == "prize"));
+}
+
 // ── Interrogation ──
 
 #[test]
@@ -523,6 +566,120 @@ fn facts_site_rule_resolution() {
     }
 }
 
+// ── Location hooks ──
+
+#[test]
+fn facts_location_hooks_recorded() {
+    let facts = extract_fixture_facts("location-hooks.urd.md");
+    let ids: Vec<String> = facts.hooks().iter().map(|h| h.hook_id()).collect();
+    assert_eq!(ids, vec!["cellar/on_enter", "cellar/on_exit"]);
+
+    let on_enter = facts.hook_by_id("cellar/on_enter").expect("on_enter hook");
+    assert_eq!(on_enter.condition_reads.len(), 2);
+    assert_eq!(on_enter.effect_writes.len(), 1);
+    let write = &facts.writes()[on_enter.effect_writes[0]];
+    assert_eq!(write.property, "lit");
+    assert_eq!(write.site, FactSite::LocationHook("cellar/on_enter".to_string()));
+
+    let on_exit = facts.hook_by_id("cellar/on_exit").expect("on_exit hook");
+    assert!(on_exit.condition_reads.is_empty());
+    assert_eq!(on_exit.effect_writes.len(), 2);
+}
+
+#[test]
+fn facts_site_location_hook_resolution() {
+    let facts = extract_fixture_facts("location-hooks.urd.md");
+    for hook in facts.hooks() {
+        let site = FactSite::LocationHook(hook.hook_id());
+        assert!(matches!(facts.resolve_site(&site), Some(SiteOwner::LocationHook(_))));
+        assert_eq!(facts.write_indices_for_site(&site), hook.effect_writes.as_slice());
+        assert_eq!(facts.read_indices_for_site(&site), hook.condition_reads.as_slice());
+    }
+}
+
+#[test]
+fn index_sees_location_hook_writes() {
+    // Lamp.fuel is only written by `on exit:` and Lamp.lit is only read by
+    // `on enter:`. Neither should be flagged.
+    let facts = extract_fixture_facts("location-hooks.urd.md");
+    let index = PropertyDependencyIndex::build(&facts);
+    assert!(index.read_but_never_written().is_empty(), "{:?}", index.read_but_never_written());
+    assert!(index.written_but_never_read().is_empty(), "{:?}", index.written_but_never_read());
+}
+
+// ── Parameterised sections ──
+
+#[test]
+fn facts_section_templates_recorded() {
+    let facts = extract_fixture_facts("parameterised-sections.urd.md");
+    let templates: Vec<(&str, &str, &str)> = facts
+        .section_templates()
+        .iter()
+        .map(|t| (t.section.as_str(), t.parameter.as_str(), t.parameter_type.as_str()))
+        .collect();
+    assert_eq!(templates, vec![
+        ("parameterised-sections/haggle", "merchant", "Merchant"),
+        ("parameterised-sections/unused", "patron", "Patron"),
+    ]);
+}
+
+#[test]
+fn facts_jump_arguments_recorded() {
+    let facts = extract_fixture_facts("parameterised-sections.urd.md");
+    let arguments: Vec<&str> = facts
+        .jumps()
+        .iter()
+        .filter(|j| j.target == JumpTarget::Section("parameterised-sections/haggle".to_string()))
+        .filter_map(|j| j.argument.as_deref())
+        .collect();
+    assert_eq!(arguments, vec!["harbor_master", "fishmonger"]);
+}
+
+// ── Entity placement ──
+
+#[test]
+fn facts_entity_placements() {
+    let facts = extract_fixture_facts("two-room-key-puzzle.urd.md");
+    let placements: Vec<(&str, &[Placement])> = facts
+        .entities()
+        .iter()
+        .map(|e| (e.entity.as_str(), e.placements.as_slice()))
+        .collect();
+    let cell = [Placement::Location("cell".to_string())];
+    assert_eq!(placements, vec![
+        ("rusty_key", &cell[..]),
+        ("cell_door", &cell[..]),
+    ]);
+    assert!(facts.moves().is_empty(), "The key is destroyed, not moved");
+}
+
+#[test]
+fn facts_entity_starting_values() {
+    let facts = extract_fixture_facts("locked-garden.urd.md");
+    let warden = facts.entity_by_id("warden").unwrap();
+    let values: Vec<(&str, &str)> = warden
+        .starting_values
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_str()))
+        .collect();
+    // `mood` is overridden, `trust` takes the type default, `role` is overridden.
+    assert_eq!(values, vec![("mood", "neutral"), ("trust", "0"), ("role", "Gatekeeper")]);
+    let gate = facts.entity_by_id("garden_gate").unwrap();
+    assert_eq!(gate.starting_values.get("locked").map(String::as_str), Some("true"));
+}
+
+#[test]
+fn facts_moves_include_nested_choices() {
+    let facts = extract_fixture_facts("sunken-citadel.urd.md");
+    // 23 effects name an entity; `merchant_restocks` moves its select
+    // variable, one fact for each of its three candidates.
+    assert_eq!(facts.moves().len(), 26, "One fact per `> move` effect: {:?}", facts.moves());
+    let moved: Vec<&str> = facts.moves().iter().map(|m| m.entity.as_str()).collect();
+    // `@bronze_key` is moved in a subchoice at line 315.
+    assert!(moved.contains(&"bronze_key"), "{:?}", moved);
+    assert!(facts.moves().iter().any(|m| m.span.start_line == 315), "{:?}", moved);
+}
+
 // ── Jump target resolution ──
 
 #[test]
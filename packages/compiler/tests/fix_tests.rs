@@ -0,0 +1,242 @@
+/// Tests for machine-applicable fixes: which diagnostics carry a fix, how
+/// fixes become edits, and the `urd fix` command on disk.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use urd_compiler::diagnostics::{Fix, Severity};
+use urd_compiler::fix::{apply_edits, fixes_by_file, plan_edits, unified_diff};
+use urd_compiler::import::{FileReadError, FileReader};
+use urd_compiler::span::Span;
+use urd_compiler::test_support::MemoryFileReader;
+use urd_compiler::{compile_source_with_reader, CompilationResult};
+
+// ── Helpers ──
+
+const TYPES: &str = "---\nworld:\n  name: fixes\n  start: hall\ntypes:\n  Guard [interactable]:\n    mood: enum(calm, cross) = calm\nentities:\n  @guard: Guard\n  @cat: Guard\n  @bat: Guard\n---\n\n# Hall\n\nA hall.\n\n[@guard, @cat, @bat]\n\n== talk\n\n";
+
+fn compile(files: &HashMap<String, String>, entry: &str) -> CompilationResult {
+    let reader = MemoryFileReader::new(files.clone());
+    compile_source_with_reader(entry, &files[entry], &reader)
+}
+
+fn errors(result: &CompilationResult) -> Vec<String> {
+    result
+        .diagnostics
+        .all()
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .map(|d| format!("{} {}", d.code, d.message))
+        .collect()
+}
+
+/// Apply every fix in `result` to `files`, as `urd fix` does.
+fn fix_all(files: &HashMap<String, String>, result: &CompilationResult) -> HashMap<String, String> {
+    let mut fixed = files.clone();
+    for (file, fixes) in fixes_by_file(&result.diagnostics) {
+        let plan = plan_edits(&files[&file], &fixes).unwrap();
+        assert!(plan.unmatched.is_empty(), "unmatched fixes: {:?}", plan.unmatched);
+        fixed.insert(file.clone(), apply_edits(&files[&file], &plan.edits));
+    }
+    fixed
+}
+
+fn one_file(content: &str) -> HashMap<String, String> {
+    HashMap::from([("world.urd.md".to_string(), format!("{}{}", TYPES, content))])
+}
+
+/// A fresh, empty directory under the system temp directory.
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("urd-fix-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+// ── Fixes from diagnostics ──
+
+#[test]
+fn two_typos_in_one_file_are_fixed() {
+    let files = one_file("@gaurd: Halt.\n\n* Smile\n  ? @gurd.mood == calm\n  > @guard.mood = cross\n  -> talk\n");
+    let before = compile(&files, "world.urd.md");
+    assert_eq!(errors(&before).len(), 2, "{:#?}", errors(&before));
+
+    let fixed = fix_all(&files, &before);
+    let text = &fixed["world.urd.md"];
+    assert!(text.contains("@guard: Halt."), "{}", text);
+    assert!(text.contains("? @guard.mood == calm"), "{}", text);
+
+    let after = compile(&fixed, "world.urd.md");
+    assert!(errors(&after).is_empty(), "{:#?}", errors(&after));
+}
+
+#[test]
+fn ambiguous_suggestion_has_no_fix() {
+    // '@rat' is one edit from both '@cat' and '@bat'.
+    let files = one_file("@rat: Meow.\n");
+    let before = compile(&files, "world.urd.md");
+    let diag = before.diagnostics.all().iter().find(|d| d.code == "URD301").unwrap();
+    assert!(diag.suggestion.is_some());
+    assert!(diag.fix.is_none());
+}
+
+#[test]
+fn recompile_shows_fewer_errors() {
+    let files = one_file("@gaurd: Halt.\n@rat: Meow.\n\n* Smile\n  > @gurd.mood = cross\n  -> talk\n");
+    let before = compile(&files, "world.urd.md");
+    assert_eq!(errors(&before).len(), 3, "{:#?}", errors(&before));
+
+    let after = compile(&fix_all(&files, &before), "world.urd.md");
+    assert_eq!(
+        errors(&after),
+        vec!["URD301 Unresolved entity reference '@rat'.".to_string()]
+    );
+}
+
+#[test]
+fn repeated_typo_on_one_line_is_fixed_everywhere() {
+    let files = one_file("* Smile\n  ? @gurd.mood == calm\n  > @gurd.mood = cross\n  -> talk\n");
+    let before = compile(&files, "world.urd.md");
+    let fixes = &fixes_by_file(&before.diagnostics)["world.urd.md"];
+    assert_eq!(fixes.len(), 2, "one fix per line: {:#?}", fixes);
+
+    let fixed = fix_all(&files, &before);
+    assert!(!fixed["world.urd.md"].contains("gurd"));
+}
+
+/// A case-insensitive file system: reads ignore case and report the
+/// casing on disk.
+struct CaseInsensitiveFs {
+    files: HashMap<String, String>,
+}
+
+impl FileReader for CaseInsensitiveFs {
+    fn read_file(&self, fs_path: &str) -> Result<String, FileReadError> {
+        self.files
+            .iter()
+            .find(|(path, _)| path.to_lowercase() == fs_path.to_lowercase())
+            .map(|(_, source)| source.clone())
+            .ok_or(FileReadError::NotFound)
+    }
+
+    fn canonical_filename(&self, dir: &str, filename: &str) -> Option<String> {
+        let wanted = format!("{}{}", dir, filename);
+        let (found, _) = self.files.iter().find(|(path, _)| path.to_lowercase() == wanted.to_lowercase())?;
+        let canonical = &found[dir.len()..];
+        (canonical != filename).then(|| canonical.to_string())
+    }
+}
+
+#[test]
+fn import_casing_fix_rewrites_only_the_filename() {
+    let entry = "---\nimport: ./rooms/Annex.urd.md\nworld:\n  name: fixes\n  start: hall\n---\n\n# Hall\n\nA hall.\n\n-> east: Annex\n";
+    let files = HashMap::from([
+        ("world.urd.md".to_string(), entry.to_string()),
+        ("rooms/annex.urd.md".to_string(), "# Annex\n\nAn annex.\n\n-> west: Hall\n".to_string()),
+    ]);
+    let fs = CaseInsensitiveFs { files: files.clone() };
+    let before = compile_source_with_reader("world.urd.md", entry, &fs);
+    let diag = before.diagnostics.all().iter().find(|d| d.code == "URD206").unwrap();
+    assert_eq!(diag.fix.as_ref().unwrap().replacement, "annex.urd.md");
+
+    let fixed = fix_all(&files, &before);
+    assert!(fixed["world.urd.md"].contains("import: ./rooms/annex.urd.md\n"));
+
+    let after = compile_source_with_reader("world.urd.md", &fixed["world.urd.md"], &fs);
+    assert!(after.diagnostics.all().iter().all(|d| d.code != "URD206"));
+}
+
+// ── Edits ──
+
+fn fix(line: u32, original: &str, replacement: &str) -> Fix {
+    Fix {
+        span: Span::new("world.urd.md".to_string(), line, 1, line, 40),
+        original: original.to_string(),
+        replacement: replacement.to_string(),
+    }
+}
+
+#[test]
+fn overlapping_edits_abort_the_file() {
+    let source = "first\n  > @gurd.mood = cross\n";
+    let fixes = [fix(2, "@gurd", "@guard"), fix(2, "gurd.mood", "guard.trust")];
+    let conflict = plan_edits(source, &fixes).unwrap_err();
+    assert_eq!(conflict.line, 2);
+    assert_eq!(
+        conflict.to_string(),
+        "Overlapping fixes at line 2: '@gurd' and 'gurd.mood'"
+    );
+}
+
+#[test]
+fn tokens_match_whole_words_only() {
+    let source = "  > @gurd.mood = @gurdian\n";
+    let plan = plan_edits(source, &[fix(1, "@gurd", "@guard")]).unwrap();
+    assert_eq!(plan.edits.len(), 1);
+    assert_eq!(apply_edits(source, &plan.edits), "  > @guard.mood = @gurdian\n");
+}
+
+#[test]
+fn missing_token_is_unmatched() {
+    let plan = plan_edits("a\nb\n", &[fix(1, "@gurd", "@guard"), fix(9, "b", "c")]).unwrap();
+    assert!(plan.edits.is_empty());
+    assert_eq!(plan.unmatched.len(), 2);
+}
+
+#[test]
+fn edits_apply_bottom_up() {
+    let source = "@ab @ab\n@cd\n";
+    let plan = plan_edits(source, &[fix(1, "@ab", "@abcdef"), fix(2, "@cd", "@c")]).unwrap();
+    assert_eq!(apply_edits(source, &plan.edits), "@abcdef @abcdef\n@c\n");
+}
+
+#[test]
+fn unified_diff_groups_nearby_changes() {
+    let before: String = (1..=12).map(|i| format!("line {}\n", i)).collect();
+    let after = before.replace("line 2\n", "LINE 2\n").replace("line 4\n", "LINE 4\n");
+    let diff = unified_diff("world.urd.md", &before, &after);
+    assert!(diff.starts_with("--- a/world.urd.md\n+++ b/world.urd.md\n@@ -1,7 +1,7 @@\n line 1\n-line 2\n+LINE 2\n"));
+    assert_eq!(diff.matches("@@ -").count(), 1);
+    assert!(unified_diff("world.urd.md", &before, &before).is_empty());
+}
+
+// ── CLI ──
+
+fn urd_fix(dir: &Path, extra: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_urd"))
+        .arg("fix")
+        .arg(dir.join("world.urd.md"))
+        .args(extra)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn cli_dry_run_leaves_files_untouched() {
+    let dir = scratch_dir("dry-run");
+    let source = one_file("@gaurd: Halt.\n").remove("world.urd.md").unwrap();
+    std::fs::write(dir.join("world.urd.md"), &source).unwrap();
+
+    let output = urd_fix(&dir, &["--dry-run"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stdout.contains("-@gaurd: Halt.\n+@guard: Halt.\n"), "{}", stdout);
+    assert!(stderr.contains("Would apply 1 fix in 1 file. Errors: 1 before, 0 after."), "{}", stderr);
+    assert!(output.status.success());
+    assert_eq!(std::fs::read_to_string(dir.join("world.urd.md")).unwrap(), source);
+}
+
+#[test]
+fn cli_writes_fixed_files() {
+    let dir = scratch_dir("write");
+    let source = one_file("@gaurd: Halt.\n@rat: Meow.\n").remove("world.urd.md").unwrap();
+    std::fs::write(dir.join("world.urd.md"), &source).unwrap();
+
+    let output = urd_fix(&dir, &[]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Applied 1 fix in 1 file. Errors: 2 before, 1 after."), "{}", stderr);
+    assert!(!output.status.success(), "an error remains");
+    let written = std::fs::read_to_string(dir.join("world.urd.md")).unwrap();
+    assert!(written.contains("@guard: Halt.\n@rat: Meow.\n"), "{}", written);
+}
@@ -0,0 +1,147 @@
+/// Tests for dialogue flow graphs.
+///
+/// Snapshot tests render the dialogue-flow fixture: three sections joined
+/// by choice jumps and an `on_exhausted` jump, plus an exit and `end`.
+
+use urd_compiler::flowgraph::{self, EdgeKind, FlowGraph, NodeKind};
+use urd_compiler::{compile, compile_source};
+
+fn fixture_path(name: &str) -> String {
+    let base = env!("CARGO_MANIFEST_DIR");
+    format!("{}/tests/fixtures/{}", base, name)
+}
+
+fn fixture_graph(name: &str) -> FlowGraph {
+    let result = compile(&fixture_path(name));
+    assert!(result.success, "{} should compile", name);
+    flowgraph::from_compilation(&result).expect("flow graph")
+}
+
+fn source_graph(source: &str) -> FlowGraph {
+    let result = compile_source("chat.urd.md", source);
+    flowgraph::from_compilation(&result).expect("flow graph")
+}
+
+fn labels(graph: &FlowGraph) -> Vec<&str> {
+    graph.nodes.iter().map(|n| n.label.as_str()).collect()
+}
+
+// ── Output ──
+
+#[test]
+fn dot_output_snapshot() {
+    let dot = fixture_graph("dialogue-flow.urd.md").to_dot();
+    assert_eq!(
+        dot,
+        r#"digraph flow {
+  rankdir=LR;
+  node [shape=box];
+  section_dialogue_flow_topics [label="dialogue-flow/topics"];
+  section_dialogue_flow_ship [label="dialogue-flow/ship"];
+  section_dialogue_flow_farewell [label="dialogue-flow/farewell"];
+  end_end [label="end", shape=doublecircle];
+  exit_tavern_out [label="exit tavern/out", shape=house];
+  section_dialogue_flow_topics -> section_dialogue_flow_ship [label="* Ask about the ship"];
+  section_dialogue_flow_topics -> section_dialogue_flow_farewell [label="+ Say goodbye", style=bold];
+  section_dialogue_flow_topics -> section_dialogue_flow_farewell [label="exhausted", style=dashed];
+  section_dialogue_flow_ship -> section_dialogue_flow_topics [label="+ Ask about the \"Gull\"", style=bold];
+  section_dialogue_flow_farewell -> end_end [label="* Leave"];
+  section_dialogue_flow_farewell -> exit_tavern_out [label="* Step outside"];
+}
+"#
+    );
+}
+
+#[test]
+fn mermaid_output_snapshot() {
+    let mermaid = fixture_graph("dialogue-flow.urd.md").to_mermaid();
+    assert_eq!(
+        mermaid,
+        r#"flowchart LR
+  section_dialogue_flow_topics["dialogue-flow/topics"]
+  section_dialogue_flow_ship["dialogue-flow/ship"]
+  section_dialogue_flow_farewell["dialogue-flow/farewell"]
+  end_end(("end"))
+  exit_tavern_out>"exit tavern/out"]
+  section_dialogue_flow_topics -->|"* Ask about the ship"| section_dialogue_flow_ship
+  section_dialogue_flow_topics ==>|"+ Say goodbye"| section_dialogue_flow_farewell
+  section_dialogue_flow_topics -.->|"exhausted"| section_dialogue_flow_farewell
+  section_dialogue_flow_ship ==>|"+ Ask about the #quot;Gull#quot;"| section_dialogue_flow_topics
+  section_dialogue_flow_farewell -->|"* Leave"| end_end
+  section_dialogue_flow_farewell -->|"* Step outside"| exit_tavern_out
+"#
+    );
+}
+
+#[test]
+fn output_is_deterministic() {
+    let first = fixture_graph("sunken-citadel.urd.md");
+    let second = fixture_graph("sunken-citadel.urd.md");
+    assert_eq!(first.to_dot(), second.to_dot());
+    assert_eq!(first.to_mermaid(), second.to_mermaid());
+}
+
+#[test]
+fn phase_section_is_a_dotted_edge() {
+    let graph = source_graph(
+        "---\nworld:\n  name: chat\n  start: stage\n---\n\n# Stage\n\n## Game\n\n### Interview\nsection: questions\n\n\
+         == questions\n\n* Ask\n  -> end\n",
+    );
+    assert_eq!(labels(&graph), ["chat/questions", "game / interview", "end"]);
+    assert_eq!(graph.nodes[1].kind, NodeKind::Phase);
+    let phase = graph.edges.iter().find(|e| e.kind == EdgeKind::Phase).expect("phase edge");
+    assert_eq!((phase.from, phase.to), (1, 0));
+    assert!(graph.to_dot().contains("  phase_game_interview -> section_chat_questions [label=\"phase\", style=dotted];\n"));
+}
+
+// ── Filtering ──
+
+#[test]
+fn section_filter_keeps_its_component() {
+    let source = "---\nworld:\n  name: chat\n  start: hall\n---\n\n# Hall\n\n\
+                  == greet\n\n* Hello\n  -> news\n\n== news\n\n* Back\n  -> greet\n\n\
+                  == shop\n\n+ Browse\n  -> end\n";
+    let graph = source_graph(source);
+    assert_eq!(labels(&graph), ["chat/greet", "chat/news", "chat/shop", "end"]);
+
+    let component = graph.component("chat/news").expect("section exists");
+    assert_eq!(labels(&component), ["chat/greet", "chat/news"]);
+    assert_eq!(component.edges.len(), 2);
+    assert_eq!((component.edges[1].from, component.edges[1].to), (1, 0));
+
+    let shop = graph.component("chat/shop").expect("section exists");
+    assert_eq!(labels(&shop), ["chat/shop", "end"]);
+    assert!(graph.component("news").is_none(), "filter takes a compiled ID");
+}
+
+// ── Sanitisation ──
+
+#[test]
+fn node_ids_are_sanitised_and_unique() {
+    let graph = fixture_graph("dialogue-flow.urd.md");
+    for node in &graph.nodes {
+        assert!(node.id.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'), "{}", node.id);
+    }
+    assert_eq!(graph.nodes[0].id, "section_dialogue_flow_topics");
+    assert_eq!(graph.nodes[0].label, "dialogue-flow/topics");
+
+    // `a-b/c` and `a_b/c` sanitise alike; the later one gets a suffix.
+    let mut files = std::collections::HashMap::new();
+    files.insert(
+        "main.urd.md".to_string(),
+        "---\nworld:\n  name: w\n  start: hall\nimport: ./a_b.urd.md\nimport: ./a-b.urd.md\n---\n\n# Hall\n".to_string(),
+    );
+    files.insert("a-b.urd.md".to_string(), "== c\n\n* Go\n  -> end\n".to_string());
+    files.insert("a_b.urd.md".to_string(), "== c\n\n* Go\n  -> end\n".to_string());
+    let reader = urd_compiler::test_support::MemoryFileReader::new(files.clone());
+    let result = urd_compiler::compile_source_with_options(
+        "main.urd.md",
+        &files["main.urd.md"],
+        &reader,
+        &urd_compiler::CompileOptions::default(),
+    );
+    let graph = flowgraph::from_compilation(&result).expect("flow graph");
+    let ids: Vec<&str> = graph.nodes.iter().map(|n| n.id.as_str()).collect();
+    assert_eq!(ids, ["section_a_b_c", "section_a_b_c_2", "end_end"]);
+    assert_eq!(labels(&graph), ["a-b/c", "a_b/c", "end"]);
+}
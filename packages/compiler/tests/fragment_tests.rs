@@ -0,0 +1,158 @@
+/// Tests for fragment compiles.
+///
+/// A fragment must emit what the same section or location emits in a
+/// full world compiled under the fragment's file name, report diagnostics
+/// on the snippet's own lines, and let undeclared entities through as
+/// warnings.
+
+use urd_compiler::diagnostics::Severity;
+use urd_compiler::fragment::{self, compile_fragment, FragmentKind, FragmentResult};
+
+// ── Helpers ──
+
+const FRONTMATTER: &str = "\
+---
+types:
+  Character [interactable]:
+    mood: enum(hostile, neutral, friendly) = neutral
+    trust: integer = 0
+entities:
+  @arina: Character { mood: \"friendly\" }
+---
+";
+
+const TOPICS: &str = "\
+== topics
+
+@arina: What'll it be?
+
+* Ask about the ship
+  @arina: She's seen better days.
+  > @arina.trust + 1
++ Order a drink
+  ? @arina.mood == friendly
+  @arina: Coming right up.
+";
+
+/// The emitted world of `body` with a world block and `FRONTMATTER`'s
+/// declarations, compiled under the fragment's file name.
+fn full_world(body: &str) -> serde_json::Value {
+    let declarations = FRONTMATTER.strip_prefix("---\n").unwrap();
+    let source = format!("---\nworld:\n  name: fragment\n{}{}", declarations, body);
+    let result = urd_compiler::compile_source(fragment::FILE_NAME, &source);
+    assert!(result.success, "{:?}", result.diagnostics.all());
+    serde_json::from_str(result.world.as_deref().unwrap()).unwrap()
+}
+
+/// `(line, column, code, severity)` of every URD3xx diagnostic, sorted.
+fn positions(result: &FragmentResult) -> Vec<(u32, u32, &str, Severity)> {
+    result
+        .diagnostics
+        .sorted()
+        .iter()
+        .filter(|d| d.code.starts_with("URD3"))
+        .map(|d| (d.span.start_line, d.span.start_col, d.code.as_str(), d.severity))
+        .collect()
+}
+
+// ── Output ──
+
+#[test]
+fn section_matches_full_world() {
+    let result = compile_fragment(FragmentKind::Section, &format!("{}{}", FRONTMATTER, TOPICS));
+    assert!(result.success, "{:?}", result.diagnostics.all());
+    assert_eq!(result.id.as_deref(), Some("fragment/topics"));
+
+    let world = full_world(&format!("# Fragment\n\n{}", TOPICS));
+    assert_eq!(result.json.as_ref(), Some(&world["dialogue"]["fragment/topics"]));
+}
+
+#[test]
+fn location_matches_full_world() {
+    let cell = "# Cell\n\nA dim stone cell.\n\n[@arina]\n\n-> north: Corridor\n\n# Corridor\n\nYou made it out.\n";
+    let result = compile_fragment(FragmentKind::Location, &format!("{}{}", FRONTMATTER, cell));
+    assert!(result.success, "{:?}", result.diagnostics.all());
+    assert_eq!(result.id.as_deref(), Some("cell"));
+
+    let world = full_world(cell);
+    assert_eq!(result.json.as_ref(), Some(&world["locations"]["cell"]));
+}
+
+#[test]
+fn snippet_without_its_kind_has_no_output() {
+    let result = compile_fragment(FragmentKind::Section, "Just some prose.\n");
+    assert!(result.success);
+    assert_eq!(result.id, None);
+    assert_eq!(result.json, None);
+
+    assert_eq!(FragmentKind::from_name("location"), Some(FragmentKind::Location));
+    assert_eq!(FragmentKind::from_name("rule"), None);
+}
+
+// ── Line remapping ──
+
+#[test]
+fn diagnostics_use_snippet_lines_without_frontmatter() {
+    // Undeclared @arina on lines 3, 6, 7, 9, and 10 of the snippet.
+    let result = compile_fragment(FragmentKind::Section, TOPICS);
+    let lines: Vec<u32> = positions(&result).iter().map(|p| p.0).collect();
+    assert_eq!(lines, [3, 6, 7, 9, 10]);
+    assert_eq!(positions(&result)[1], (6, 3, "URD301", Severity::Warning));
+}
+
+#[test]
+fn messages_name_the_snippet_not_the_wrapper() {
+    let result = compile_fragment(FragmentKind::Section, "== chat\n\n* Wave\n  Nobody waves back.\n");
+    let messages: Vec<&str> = result.diagnostics.all().iter().map(|d| d.message.as_str()).collect();
+    assert!(
+        messages.contains(
+            &"Section 'chat' in the snippet has only one-shot choices and no terminal jump or fallthrough text. \
+              It will exhaust to an empty state."
+        ),
+        "{:?}",
+        messages
+    );
+    assert!(messages.iter().all(|m| !m.contains(fragment::FILE_NAME)));
+}
+
+#[test]
+fn diagnostics_use_snippet_lines_with_frontmatter() {
+    let snippet = format!("{}{}", FRONTMATTER, TOPICS.replace("@arina.trust + 1", "@arina.trsut + 1"));
+    let result = compile_fragment(FragmentKind::Section, &snippet);
+    // Frontmatter is 8 lines; the effect is on line 7 of TOPICS, and the
+    // property starts in column 12.
+    assert_eq!(positions(&result), [(15, 12, "URD308", Severity::Error)]);
+    assert!(!result.success);
+    assert_eq!(result.json, None);
+}
+
+// ── Undeclared entities ──
+
+#[test]
+fn undeclared_entities_are_warnings() {
+    let result = compile_fragment(FragmentKind::Section, TOPICS);
+    assert!(result.success);
+    assert!(result.diagnostics.all().iter().all(|d| d.severity != Severity::Error));
+    let json = result.json.expect("fragment should emit");
+    assert_eq!(json["choices"][1]["conditions"][0], "arina.mood == friendly");
+    assert_eq!(json["choices"][0]["effects"][0]["set"], "arina.trust");
+
+    // Effects name undeclared entities as written, like conditions.
+    let snippet = "== chat\n\n* Calm him\n  > @ghost.mood = calm\n  > move @ghost -> player\n  > destroy @ghost\n";
+    let effects = compile_fragment(FragmentKind::Section, snippet).json.unwrap()["choices"][0]["effects"].take();
+    assert_eq!(
+        effects,
+        serde_json::json!([{"set": "ghost.mood", "to": "calm"}, {"move": "ghost", "to": "player"}, {"destroy": "ghost"}])
+    );
+
+    // A full world with the same gap does not compile.
+    let full = urd_compiler::compile_source("world.urd.md", &format!("---\nworld:\n  name: w\n---\n{}", TOPICS));
+    assert!(!full.success);
+}
+
+#[test]
+fn other_unresolved_references_stay_errors() {
+    let result = compile_fragment(FragmentKind::Location, "# Cell\n\n-> north: Corridor\n");
+    assert_eq!(positions(&result), [(3, 1, "URD312", Severity::Error)]);
+    assert!(!result.success);
+}
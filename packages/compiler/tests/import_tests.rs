@@ -7,12 +7,19 @@
 // - Error recovery (5 tests)
 // - Span reference (4 tests)
 // - Missing tests from audit (6 tests)
+// - Project root (8 tests)
+// - Missing-import policy (4 tests)
+// - Case-insensitive stem collisions (7 tests)
+// - Guards (5 tests)
+// - Dry-run plans (4 tests)
 
 use std::collections::HashMap;
-use urd_compiler::diagnostics::DiagnosticCollector;
-use urd_compiler::import::{FileReadError, FileReader};
-use urd_compiler::import::resolve_imports_with_reader;
+use urd_compiler::diagnostics::{DiagnosticCollector, Severity};
+use urd_compiler::import::{FileReadError, FileReader, MissingImportPolicy, StubFileReader};
+use urd_compiler::import::{anchor_to_project_root, resolve_imports_dry_run, resolve_imports_with_reader};
+use urd_compiler::import::{ImportStatus, PathRejection, PlanMode};
 use urd_compiler::parse;
+use urd_compiler::{compile_source_with_options, CompileOptions};
 
 // ── Test helpers ────────────────────────────────────────────────────
 
@@ -827,6 +834,7 @@ fn casing_mismatch_warning() {
     let cu = resolve_imports_with_reader(ast, "", &mut diag, &fs);
 
     let d = find_diagnostic(&diag, "URD206").expect("Expected URD206");
+    assert_eq!(d.severity, Severity::Warning);
     assert!(d.message.contains("Types.urd.md"));
     assert!(d.message.contains("types.urd.md"));
     // File stored under canonical casing.
@@ -883,6 +891,107 @@ fn casing_mismatch_canonical_already_visited() {
     assert_eq!(c_edges[0].1, "types.urd.md");
 }
 
+// ── Case-insensitive stem collisions ────────────────────────────────
+
+/// Resolve an entry file importing each of `paths`, all present and empty.
+fn resolve_all(paths: &[&str]) -> DiagnosticCollector {
+    let imports: Vec<String> = paths.iter().map(|p| format!("./{}", p)).collect();
+    let imports: Vec<&str> = imports.iter().map(String::as_str).collect();
+    let ast = parse_source("world.urd.md", &make_source(&imports, ""));
+    let leaf = make_source(&[], "");
+    let fs = paths.iter().fold(MockFs::new(), |fs, p| fs.add(p, &leaf));
+    let mut diag = DiagnosticCollector::new();
+    resolve_imports_with_reader(ast, "", &mut diag, &fs);
+    diag
+}
+
+#[test]
+fn case_collision_warns_naming_both_paths() {
+    let diag = resolve_all(&["Café.urd.md", "café.urd.md"]);
+
+    assert!(!diag.has_errors());
+    assert_eq!(count_diagnostics(&diag, "URD215"), 1);
+    let d = find_diagnostic(&diag, "URD215").unwrap();
+    assert_eq!(d.severity, Severity::Warning);
+    assert!(d.message.contains("(Café.urd.md)"), "URD215 message: {}", d.message);
+    assert!(d.message.contains("(café.urd.md)"), "URD215 message: {}", d.message);
+    assert!(d.message.contains("shadow"));
+}
+
+#[test]
+fn case_collision_folds_non_ascii_letters() {
+    // Only the accented letters differ in case.
+    let diag = resolve_all(&["ÉTÉ.urd.md", "été.urd.md"]);
+    assert_eq!(count_diagnostics(&diag, "URD215"), 1);
+}
+
+#[test]
+fn case_collision_uses_full_case_folding() {
+    // `ß` folds to `ss`, so these are one name to a case-insensitive system.
+    let diag = resolve_all(&["Straße.urd.md", "STRASSE.urd.md"]);
+    assert_eq!(count_diagnostics(&diag, "URD215"), 1);
+}
+
+#[test]
+fn case_collision_across_directories() {
+    let diag = resolve_all(&["north/Café.urd.md", "south/café.urd.md"]);
+
+    let d = find_diagnostic(&diag, "URD215").expect("Expected URD215");
+    assert!(d.message.contains("section IDs"), "URD215 message: {}", d.message);
+    // Distinct stems: not a URD203 collision.
+    assert_eq!(count_diagnostics(&diag, "URD203"), 0);
+}
+
+#[test]
+fn identical_stems_are_not_case_collisions() {
+    let diag = resolve_all(&["north/café.urd.md", "south/café.urd.md"]);
+    assert_eq!(count_diagnostics(&diag, "URD203"), 1);
+    assert_eq!(count_diagnostics(&diag, "URD215"), 0);
+}
+
+#[test]
+fn case_collision_error_when_strict() {
+    let source = make_source(&["./Café.urd.md", "./café.urd.md"], "");
+    let leaf = make_source(&[], "");
+    let fs = MockFs::new().add("Café.urd.md", &leaf).add("café.urd.md", &leaf);
+    let options = CompileOptions {
+        strict_filename_case: true,
+        ..CompileOptions::default()
+    };
+
+    let result = compile_source_with_options("world.urd.md", &source, &fs, &options);
+
+    let d = find_diagnostic(&result.diagnostics, "URD215").expect("Expected URD215");
+    assert_eq!(d.severity, Severity::Error);
+    assert!(!result.success);
+}
+
+#[test]
+fn casing_mismatch_escalates_when_graph_has_case_collision() {
+    // The entry imports Café directly; b's import of CAFÉ is corrected to
+    // café, which differs from Café only in case.
+    let entry_source = make_source(&["./Café.urd.md", "./b.urd.md"], "");
+    let b_source = make_source(&["./CAFÉ.urd.md"], "");
+    let leaf = make_source(&[], "");
+    let ast = parse_source("world.urd.md", &entry_source);
+    let inner_fs = MockFs::new()
+        .add("Café.urd.md", &leaf)
+        .add("café.urd.md", &leaf)
+        .add("CAFÉ.urd.md", &leaf)
+        .add("b.urd.md", &b_source);
+    let fs = CasingMockFs::new(inner_fs).add_casing("", "CAFÉ.urd.md", "café.urd.md");
+    let mut diag = DiagnosticCollector::new();
+
+    let cu = resolve_imports_with_reader(ast, "", &mut diag, &fs);
+
+    assert!(cu.graph.nodes.contains_key("café.urd.md"));
+    let d = find_diagnostic(&diag, "URD206").expect("Expected URD206");
+    assert_eq!(d.severity, Severity::Error);
+    assert!(d.message.contains("'Café.urd.md' is also in the compilation unit"), "URD206 message: {}", d.message);
+    assert_eq!(d.span.file, "b.urd.md");
+    assert_eq!(count_diagnostics(&diag, "URD215"), 1);
+}
+
 // ── Additional edge cases ───────────────────────────────────────────
 
 #[test]
@@ -1241,6 +1350,440 @@ entities:
     );
 }
 
+// ── Project root ────────────────────────────────────────────────────
+
+fn with_root(root: &str) -> CompileOptions<'_> {
+    CompileOptions {
+        project_root: Some(root.to_string()),
+        ..CompileOptions::default()
+    }
+}
+
+const ACT1_SOURCE: &str = "---\nimport: ../shared/npcs.urd.md\n---\n# Gate\n\nA gate.\n";
+
+#[test]
+fn root_allows_sibling_import() {
+    let fs = MockFs::new().add("worlds/shared/npcs.urd.md", &make_source(&[], ""));
+
+    let result = compile_source_with_options(
+        "worlds/act1/main.urd.md",
+        ACT1_SOURCE,
+        &fs,
+        &with_root("worlds"),
+    );
+
+    assert_eq!(count_diagnostics(&result.diagnostics, "URD208"), 0);
+    let graph = result.graph.expect("graph should be built");
+    // Graph identity is root-relative.
+    assert_eq!(graph.entry_path.as_deref(), Some("act1/main.urd.md"));
+    assert!(graph.nodes.contains_key("shared/npcs.urd.md"));
+}
+
+#[test]
+fn root_defaults_to_entry_dir() {
+    let fs = MockFs::new().add("worlds/shared/npcs.urd.md", &make_source(&[], ""));
+
+    let result = compile_source_with_options(
+        "worlds/act1/main.urd.md",
+        ACT1_SOURCE,
+        &fs,
+        &CompileOptions::default(),
+    );
+
+    assert_eq!(count_diagnostics(&result.diagnostics, "URD208"), 1);
+}
+
+#[test]
+fn root_still_rejects_escape_above_root() {
+    let source = "---\nimport: ../../outside.urd.md\n---\n";
+    let fs = MockFs::new().add("outside.urd.md", &make_source(&[], ""));
+
+    let result =
+        compile_source_with_options("worlds/act1/main.urd.md", source, &fs, &with_root("worlds"));
+
+    let d = find_diagnostic(&result.diagnostics, "URD208").expect("URD208 expected");
+    assert_eq!(d.span.file, "act1/main.urd.md");
+}
+
+#[test]
+fn root_entry_outside_root_is_rejected() {
+    let fs = MockFs::new();
+
+    let result =
+        compile_source_with_options("worlds/act1/main.urd.md", ACT1_SOURCE, &fs, &with_root("lib"));
+
+    assert!(!result.success);
+    assert!(result.graph.is_none());
+    let d = find_diagnostic(&result.diagnostics, "URD208").expect("URD208 expected");
+    assert!(d.message.contains("outside the project root"), "{}", d.message);
+}
+
+#[test]
+fn root_stem_collision_uses_root_relative_paths() {
+    let source = "---\nimport: ../shared/npcs.urd.md\nimport: ./npcs.urd.md\n---\n";
+    let fs = MockFs::new()
+        .add("worlds/shared/npcs.urd.md", &make_source(&[], ""))
+        .add("worlds/act1/npcs.urd.md", &make_source(&[], ""));
+
+    let result =
+        compile_source_with_options("worlds/act1/main.urd.md", source, &fs, &with_root("worlds"));
+
+    let d = find_diagnostic(&result.diagnostics, "URD203").expect("URD203 expected");
+    assert!(
+        d.message.contains("act1/npcs.urd.md and shared/npcs.urd.md"),
+        "{}",
+        d.message
+    );
+}
+
+#[test]
+fn root_windows_backslash_paths_normalised() {
+    let fs = MockFs::new().add("C:/proj/worlds/shared/npcs.urd.md", &make_source(&[], ""));
+
+    let result = compile_source_with_options(
+        "C:\\proj\\worlds\\act1\\main.urd.md",
+        ACT1_SOURCE,
+        &fs,
+        &with_root("C:\\proj\\worlds\\"),
+    );
+
+    assert!(!result.diagnostics.has_errors(), "{:?}", result.diagnostics.all());
+    let graph = result.graph.expect("graph should be built");
+    assert_eq!(graph.entry_path.as_deref(), Some("act1/main.urd.md"));
+    assert!(graph.nodes.contains_key("shared/npcs.urd.md"));
+}
+
+#[test]
+fn anchor_relative_root() {
+    assert_eq!(
+        anchor_to_project_root("./worlds/", "worlds/act1/main.urd.md"),
+        Some(("worlds/".to_string(), "act1/main.urd.md".to_string()))
+    );
+    assert_eq!(
+        anchor_to_project_root(".", "main.urd.md"),
+        Some((String::new(), "main.urd.md".to_string()))
+    );
+    assert_eq!(
+        anchor_to_project_root("../lib", "../lib/a/../b.urd.md"),
+        Some(("../lib/".to_string(), "b.urd.md".to_string()))
+    );
+}
+
+#[test]
+fn anchor_rejects_mismatched_roots() {
+    // Sibling directory sharing a name prefix.
+    assert_eq!(anchor_to_project_root("world", "worlds/main.urd.md"), None);
+    // Absolute root, relative entry.
+    assert_eq!(anchor_to_project_root("/proj", "proj/main.urd.md"), None);
+    // Entry equal to the root itself.
+    assert_eq!(anchor_to_project_root("worlds", "worlds"), None);
+}
+
+// ── Missing-import policy ───────────────────────────────────────────
+
+const MISSING_IMPORT_SOURCE: &str =
+    "---\nimport: ./world.urd.md\nworld:\n  name: test\n  start: hall\n---\n# Hall\n\n[@guard]\n";
+
+fn with_policy(policy: MissingImportPolicy) -> CompileOptions<'static> {
+    CompileOptions {
+        missing_import_policy: policy,
+        ..CompileOptions::default()
+    }
+}
+
+#[test]
+fn missing_import_policy_error_stops_after_import() {
+    let result = compile_source_with_options(
+        "main.urd.md",
+        MISSING_IMPORT_SOURCE,
+        &StubFileReader,
+        &with_policy(MissingImportPolicy::Error),
+    );
+
+    let d = find_diagnostic(&result.diagnostics, "URD201").expect("URD201 expected");
+    assert_eq!(d.severity, Severity::Error);
+    assert!(result.symbol_table.is_none(), "LINK should not run");
+    assert_eq!(count_diagnostics(&result.diagnostics, "URD301"), 0);
+}
+
+#[test]
+fn missing_import_policy_warn_skips_file() {
+    let result = compile_source_with_options(
+        "main.urd.md",
+        MISSING_IMPORT_SOURCE,
+        &StubFileReader,
+        &with_policy(MissingImportPolicy::WarnAndSkip),
+    );
+
+    let d = find_diagnostic(&result.diagnostics, "URD201").expect("URD201 expected");
+    assert_eq!(d.severity, Severity::Warning);
+    assert!(d.message.contains("Skipped"));
+    // The reference into the missing file surfaces from LINK.
+    let unresolved = find_diagnostic(&result.diagnostics, "URD301").expect("URD301 expected");
+    assert!(unresolved.message.contains("@guard"));
+    assert!(!result.success);
+
+    let graph = result.graph.expect("graph should be built");
+    assert_eq!(graph.nodes.len(), 1);
+    assert!(graph.edges.is_empty());
+}
+
+#[test]
+fn missing_import_policy_stub_inserts_empty_file() {
+    let result = compile_source_with_options(
+        "main.urd.md",
+        MISSING_IMPORT_SOURCE,
+        &StubFileReader,
+        &with_policy(MissingImportPolicy::Stub),
+    );
+
+    let d = find_diagnostic(&result.diagnostics, "URD201").expect("URD201 expected");
+    assert_eq!(d.severity, Severity::Warning);
+    assert!(d.message.contains("stub"));
+    assert!(find_diagnostic(&result.diagnostics, "URD301").is_some());
+
+    let graph = result.graph.expect("graph should be built");
+    let stub = graph.nodes.get("world.urd.md").expect("stub node expected");
+    assert!(stub.ast.content.is_empty());
+    assert!(stub.ast.frontmatter.is_none());
+    assert!(graph.edges.contains(&("main.urd.md".to_string(), "world.urd.md".to_string())));
+    assert!(graph_is_valid(&graph));
+}
+
+#[test]
+fn missing_import_policy_stub_skips_stem_check() {
+    // A real stem collision would be URD203; a stub declares nothing.
+    let source = MISSING_IMPORT_SOURCE.replace("./world.urd.md", "lib/main.urd.md");
+    let result = compile_source_with_options(
+        "main.urd.md",
+        &source,
+        &StubFileReader,
+        &with_policy(MissingImportPolicy::Stub),
+    );
+
+    assert_eq!(count_diagnostics(&result.diagnostics, "URD203"), 0);
+    assert_eq!(count_diagnostics(&result.diagnostics, "URD201"), 1);
+    assert!(result.graph.unwrap().nodes.contains_key("lib/main.urd.md"));
+}
+
+// ── Guards ──────────────────────────────────────────────────────────
+
+const GUARDED_SOURCE: &str = "\
+---
+world:
+  name: guarded
+  start: hall
+?if demo:
+import: ./demo.urd.md
+?endif
+---
+
+# Hall
+
+-> east: Demo Room
+
+?if demo:
+# Demo Room
+
+Only in the demo.
+?endif
+";
+
+const DEMO_SOURCE: &str = "---\ntypes:\n  Sign:\n    text: string\n---\n";
+
+fn compile_with_flags(source: &str, fs: &MockFs, defines: &[&str]) -> urd_compiler::CompilationResult {
+    let options = CompileOptions {
+        defines: defines.iter().map(|d| d.to_string()).collect(),
+        ..CompileOptions::default()
+    };
+    compile_source_with_options("main.urd.md", source, fs, &options)
+}
+
+#[test]
+fn guarded_location_follows_flags() {
+    let fs = MockFs::new().add("demo.urd.md", DEMO_SOURCE);
+    let with = compile_with_flags(GUARDED_SOURCE, &fs, &["demo"]);
+    assert!(with.success, "{:?}", with.diagnostics.all());
+    let world = with.world.unwrap();
+    assert!(world.contains("\"demo-room\""));
+    assert!(world.contains("\"Sign\""));
+
+    // Without the flag, the exit to the dropped room no longer resolves.
+    let without = compile_with_flags(GUARDED_SOURCE, &fs, &[]);
+    assert!(find_diagnostic(&without.diagnostics, "URD312").is_some());
+    let guardless = GUARDED_SOURCE.replace("-> east: Demo Room\n", "");
+    let without = compile_with_flags(&guardless, &fs, &[]);
+    assert!(without.success, "{:?}", without.diagnostics.all());
+    let world = without.world.unwrap();
+    assert!(!world.contains("demo") && !world.contains("Demo"), "{}", world);
+}
+
+#[test]
+fn guarded_import_is_skipped() {
+    let source = GUARDED_SOURCE.replace("-> east: Demo Room\n", "");
+    let result = compile_with_flags(&source, &MockFs::new(), &[]);
+    assert_eq!(count_diagnostics(&result.diagnostics, "URD201"), 0);
+    assert_eq!(result.graph.unwrap().nodes.len(), 1);
+
+    let result = compile_with_flags(&source, &MockFs::new(), &["demo"]);
+    assert_eq!(count_diagnostics(&result.diagnostics, "URD201"), 1);
+}
+
+#[test]
+fn world_flags_set_guards() {
+    let fs = MockFs::new().add("demo.urd.md", DEMO_SOURCE);
+    let source = GUARDED_SOURCE.replace("  start: hall\n", "  start: hall\n  flags: [demo]\n");
+    let result = compile_with_flags(&source, &fs, &[]);
+    assert!(result.success, "{:?}", result.diagnostics.all());
+    assert!(result.world.unwrap().contains("\"demo-room\""));
+
+    let source = "---\nworld:\n  name: w\n  flags: demo\n---\n# Hall\n";
+    let result = compile_with_flags(source, &fs, &[]);
+    let d = find_diagnostic(&result.diagnostics, "URD437").expect("URD437 expected");
+    assert_eq!(d.message, "world.flags must be a list of strings.");
+}
+
+#[test]
+fn dropped_guard_reports_nothing() {
+    let source = "---\nworld:\n  name: w\n---\n# Hall\n\n?if !demo:\n\t* Wave at @nobody\n  @nobody: Hi.\n?endif\n";
+    let dropped = compile_with_flags(source, &MockFs::new(), &["demo"]);
+    assert!(dropped.success);
+    assert!(dropped.diagnostics.is_empty(), "{:?}", dropped.diagnostics.all());
+
+    let kept = compile_with_flags(source, &MockFs::new(), &[]);
+    assert!(find_diagnostic(&kept.diagnostics, "URD102").is_some());
+    let kept = compile_with_flags(&source.replace('\t', ""), &MockFs::new(), &[]);
+    assert!(find_diagnostic(&kept.diagnostics, "URD301").is_some());
+
+    // Structural guard errors are reported whatever the flags.
+    let unclosed = compile_with_flags("# Hall\n?if demo:\n?if beta:\n?endif\n", &MockFs::new(), &[]);
+    assert_eq!(count_diagnostics(&unclosed.diagnostics, "URD113"), 1);
+    assert!(!unclosed.success);
+}
+
+#[test]
+fn guarded_output_is_deterministic_per_flag_set() {
+    let fs = MockFs::new().add("demo.urd.md", DEMO_SOURCE);
+    let source = GUARDED_SOURCE.replace("-> east: Demo Room\n", "");
+    for defines in [&[][..], &["demo"][..], &["demo", "beta"][..]] {
+        let first = compile_with_flags(&source, &fs, defines).world.unwrap();
+        let second = compile_with_flags(&source, &fs, defines).world.unwrap();
+        assert_eq!(first, second, "flags {:?}", defines);
+    }
+    assert_ne!(
+        compile_with_flags(&source, &fs, &[]).world,
+        compile_with_flags(&source, &fs, &["demo"]).world,
+    );
+}
+
+// ── Dry-run plans ───────────────────────────────────────────────────
+
+/// A reader for shallow plans, which must not read anything.
+struct NoReads;
+
+impl FileReader for NoReads {
+    fn read_file(&self, fs_path: &str) -> Result<String, FileReadError> {
+        panic!("shallow plan read {}", fs_path);
+    }
+
+    fn canonical_filename(&self, _dir: &str, _filename: &str) -> Option<String> {
+        None
+    }
+}
+
+fn plan_codes(plan: &urd_compiler::import::ImportPlan) -> Vec<(&str, Option<&str>)> {
+    plan.imports.iter().map(|i| (i.written_path.as_str(), i.status.code())).collect()
+}
+
+#[test]
+fn dry_run_shallow_lists_direct_imports_with_statuses() {
+    let source = make_source(
+        &["./rooms/hall.urd.md", "/etc/world.urd.md", "./notes.md", "rooms\\hall.urd.md", "./main.urd.md"],
+        "",
+    );
+    let plan = resolve_imports_dry_run("project/main.urd.md", &source, &NoReads, PlanMode::Shallow);
+
+    assert!(plan.entry_parsed);
+    assert_eq!(plan.entry, "main.urd.md");
+    assert_eq!(
+        plan_codes(&plan),
+        [
+            ("./rooms/hall.urd.md", None),
+            ("/etc/world.urd.md", Some("URD209")),
+            ("./notes.md", Some("URD210")),
+            ("rooms/hall.urd.md", None),
+            ("./main.urd.md", Some("URD207")),
+        ]
+    );
+    assert!(matches!(&plan.imports[3].status, ImportStatus::AlreadyLoaded(p) if p == "rooms/hall.urd.md"));
+    assert_eq!(plan.reads().collect::<Vec<_>>(), ["project/rooms/hall.urd.md"]);
+    assert!(plan.has_errors());
+}
+
+#[test]
+fn dry_run_full_discovers_transitive_files() {
+    let fs = MockFs::new()
+        .add("hall.urd.md", &make_source(&["./rooms/cellar.urd.md", "./missing.urd.md"], "# Hall\n"))
+        .add("rooms/cellar.urd.md", &make_source(&["../hall.urd.md", "../main.urd.md"], "# Cellar\n"));
+    let source = make_source(&["./hall.urd.md", "./rooms/cellar.urd.md"], "");
+    let plan = resolve_imports_dry_run("main.urd.md", &source, &fs, PlanMode::Full);
+
+    let steps: Vec<(&str, usize, Option<&str>)> =
+        plan.imports.iter().map(|i| (i.importer.as_str(), i.depth, i.status.code())).collect();
+    assert_eq!(
+        steps,
+        [
+            ("main.urd.md", 0, None),
+            ("hall.urd.md", 1, None),
+            ("rooms/cellar.urd.md", 2, Some("URD202")),
+            ("rooms/cellar.urd.md", 2, Some("URD202")),
+            ("hall.urd.md", 1, Some("URD201")),
+            ("main.urd.md", 0, None),
+        ]
+    );
+    let ImportStatus::Cycle(cycle) = &plan.imports[3].status else { panic!("expected a cycle") };
+    assert_eq!(cycle, &["main.urd.md", "hall.urd.md", "rooms/cellar.urd.md", "main.urd.md"]);
+    assert!(matches!(&plan.imports[5].status, ImportStatus::AlreadyLoaded(_)));
+    assert_eq!(
+        plan.reads().collect::<Vec<_>>(),
+        ["hall.urd.md", "rooms/cellar.urd.md", "missing.urd.md"]
+    );
+}
+
+#[test]
+fn dry_run_escape_reports_urd208_without_diagnostics() {
+    let source = make_source(&["../../secrets/keys.urd.md"], "");
+
+    // A compile reports the escape as a diagnostic...
+    let mut diag = DiagnosticCollector::new();
+    resolve_imports_with_reader(parse_source("main.urd.md", &source), "", &mut diag, &MockFs::new());
+    assert_eq!(count_diagnostics(&diag, "URD208"), 1);
+
+    // ...the plan records it, and reads nothing.
+    let plan = resolve_imports_dry_run("main.urd.md", &source, &NoReads, PlanMode::Full);
+    assert!(matches!(plan.imports[0].status, ImportStatus::Rejected(PathRejection::Escapes)));
+    assert_eq!(plan.imports[0].status.code(), Some("URD208"));
+    assert_eq!(plan.reads().count(), 0);
+}
+
+#[test]
+fn dry_run_full_stops_at_depth_limit() {
+    // The same chain as graph_deep_chain_65_levels.
+    let mut fs = MockFs::new();
+    for i in 1..=64 {
+        let imports = if i < 64 { vec![format!("./file_{:02}.urd.md", i + 1)] } else { Vec::new() };
+        let imports: Vec<&str> = imports.iter().map(String::as_str).collect();
+        fs = fs.add(&format!("file_{:02}.urd.md", i), &make_source(&imports, ""));
+    }
+    let source = make_source(&["./file_01.urd.md"], "");
+    let plan = resolve_imports_dry_run("entry.urd.md", &source, &fs, PlanMode::Full);
+
+    assert_eq!(plan.reads().count(), 63);
+    let last = plan.imports.last().unwrap();
+    assert_eq!((last.importer.as_str(), last.depth), ("file_63.urd.md", 63));
+    assert!(matches!(last.status, ImportStatus::DepthLimit));
+}
+
 // ── Helper ──────────────────────────────────────────────────────────
 
 fn graph_is_valid(graph: &urd_compiler::graph::DependencyGraph) -> bool {
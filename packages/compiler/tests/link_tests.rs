@@ -1,17 +1,18 @@
 // Tests for Phase 3: LINK
 //
 // Test categories from the LINK phase brief:
-// 1. Collection (10)
+// 1. Collection (11)
 // 2. Choice-to-action (7)
-// 3. Resolution (14)
-// 4. ID derivation (6)
+// 3. Resolution (23)
+// 4. ID derivation (18)
 // 5. Integration (3)
 // 6. Error recovery (4)
 // 7. Scope & location context (6)
+// 8. Parameterised sections (11)
 
 use indexmap::IndexMap;
 use urd_compiler::ast::*;
-use urd_compiler::diagnostics::DiagnosticCollector;
+use urd_compiler::diagnostics::{DiagnosticCollector, Severity};
 use urd_compiler::graph::{CompilationUnit, DependencyGraph, FileNode};
 use urd_compiler::link;
 use urd_compiler::span::Span;
@@ -28,6 +29,7 @@ fn make_file_ast(path: &str, frontmatter: Option<Frontmatter>, content: Vec<Cont
         frontmatter,
         content,
         span: span(path, 1),
+        source_hash: None,
     }
 }
 
@@ -51,6 +53,7 @@ fn make_type_def(name: &str, traits: Vec<&str>, properties: Vec<PropertyDef>) ->
         name: name.to_string(),
         traits: traits.into_iter().map(String::from).collect(),
         properties,
+        doc: None,
         span: span("test.urd.md", 2),
     })
 }
@@ -60,6 +63,7 @@ fn make_type_def_in(name: &str, file: &str, line: u32, traits: Vec<&str>, proper
         name: name.to_string(),
         traits: traits.into_iter().map(String::from).collect(),
         properties,
+        doc: None,
         span: span(file, line),
     })
 }
@@ -106,6 +110,7 @@ fn make_entity_decl(id: &str, type_name: &str, overrides: Vec<(&str, Scalar)>) -
         type_name: type_name.to_string(),
         property_overrides: overrides.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
         annotation: None,
+        doc: None,
         span: span("test.urd.md", 4),
     })
 }
@@ -116,6 +121,7 @@ fn make_entity_decl_in(id: &str, type_name: &str, file: &str, line: u32, overrid
         type_name: type_name.to_string(),
         property_overrides: overrides.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
         annotation: None,
+        doc: None,
         span: span(file, line),
     })
 }
@@ -130,6 +136,8 @@ fn make_world_block(fields: Vec<(&str, Scalar)>) -> FrontmatterValue {
 fn location(name: &str) -> ContentNode {
     ContentNode::LocationHeading(LocationHeading {
         display_name: name.to_string(),
+        explicit_id: None,
+        doc: None,
         span: span("test.urd.md", 10),
     })
 }
@@ -137,13 +145,57 @@ fn location(name: &str) -> ContentNode {
 fn location_in(name: &str, file: &str, line: u32) -> ContentNode {
     ContentNode::LocationHeading(LocationHeading {
         display_name: name.to_string(),
+        explicit_id: None,
+        doc: None,
         span: span(file, line),
     })
 }
 
+fn region(name: &str) -> ContentNode {
+    ContentNode::RegionHeading(RegionHeading {
+        display_name: name.to_string(),
+        span: span("test.urd.md", 5),
+    })
+}
+
 fn section(name: &str) -> ContentNode {
     ContentNode::SectionLabel(SectionLabel {
         name: name.to_string(),
+        explicit_id: None,
+        param: None,
+        doc: None,
+        span: span("test.urd.md", 15),
+    })
+}
+
+fn param_section(name: &str, param: &str, type_name: &str) -> ContentNode {
+    ContentNode::SectionLabel(SectionLabel {
+        name: name.to_string(),
+        explicit_id: None,
+        param: Some(SectionParam {
+            name: param.to_string(),
+            type_name: type_name.to_string(),
+        }),
+        doc: None,
+        span: span("test.urd.md", 15),
+    })
+}
+
+fn location_with_id(name: &str, id: &str) -> ContentNode {
+    ContentNode::LocationHeading(LocationHeading {
+        display_name: name.to_string(),
+        explicit_id: Some(id.to_string()),
+        doc: None,
+        span: span("test.urd.md", 10),
+    })
+}
+
+fn section_with_id(name: &str, id: &str) -> ContentNode {
+    ContentNode::SectionLabel(SectionLabel {
+        name: name.to_string(),
+        explicit_id: Some(id.to_string()),
+        param: None,
+        doc: None,
         span: span("test.urd.md", 15),
     })
 }
@@ -151,6 +203,9 @@ fn section(name: &str) -> ContentNode {
 fn section_in(name: &str, file: &str, line: u32) -> ContentNode {
     ContentNode::SectionLabel(SectionLabel {
         name: name.to_string(),
+        explicit_id: None,
+        param: None,
+        doc: None,
         span: span(file, line),
     })
 }
@@ -194,6 +249,13 @@ fn choice_with_type_target(label: &str, target_type: &str) -> ContentNode {
     })
 }
 
+fn choice_with_type_target_at(label: &str, target_type: &str, line: u32) -> ContentNode {
+    match choice_with_type_target(label, target_type) {
+        ContentNode::Choice(choice) => ContentNode::Choice(Choice { span: span("test.urd.md", line), ..choice }),
+        _ => unreachable!(),
+    }
+}
+
 fn choice_with_children(label: &str, children: Vec<ContentNode>) -> ContentNode {
     ContentNode::Choice(Choice {
         sticky: false,
@@ -222,6 +284,7 @@ fn entity_presence(refs: Vec<&str>) -> ContentNode {
     ContentNode::EntityPresence(EntityPresence {
         entity_refs: refs.into_iter().map(String::from).collect(),
         annotations: vec![None; len],
+        property_overrides: vec![Vec::new(); len],
         span: span("test.urd.md", 12),
     })
 }
@@ -230,6 +293,7 @@ fn entity_speech(entity_ref: &str, text: &str) -> ContentNode {
     ContentNode::EntitySpeech(EntitySpeech {
         entity_ref: entity_ref.to_string(),
         text: text.to_string(),
+        variants: Vec::new(),
         annotation: None,
         span: span("test.urd.md", 30),
     })
@@ -248,6 +312,7 @@ fn jump(target: &str) -> ContentNode {
     ContentNode::Jump(Jump {
         target: target.to_string(),
         is_exit_qualified: false,
+        argument: None,
         indent_level: 0,
         annotation: None,
         span: span("test.urd.md", 35),
@@ -258,12 +323,24 @@ fn exit_jump(target: &str) -> ContentNode {
     ContentNode::Jump(Jump {
         target: target.to_string(),
         is_exit_qualified: true,
+        argument: None,
         indent_level: 0,
         annotation: None,
         span: span("test.urd.md", 36),
     })
 }
 
+fn jump_with_argument(target: &str, argument: &str) -> ContentNode {
+    ContentNode::Jump(Jump {
+        target: target.to_string(),
+        is_exit_qualified: false,
+        argument: Some(argument.to_string()),
+        indent_level: 0,
+        annotation: None,
+        span: span("test.urd.md", 35),
+    })
+}
+
 fn property_comparison(entity_ref: &str, property: &str, op: &str, value: &str) -> ContentNode {
     ContentNode::Condition(Condition {
         expr: ConditionExpr::PropertyComparison(PropertyComparison {
@@ -271,7 +348,10 @@ fn property_comparison(entity_ref: &str, property: &str, op: &str, value: &str)
             property: property.to_string(),
             operator: op.to_string(),
             value: value.to_string(),
+            negated: false,
+            bare: false,
             annotation: None,
+            spans: OperandSpans::whole(),
             span: span("test.urd.md", 40),
         }),
         indent_level: 0,
@@ -305,12 +385,34 @@ fn exhaustion_check(section_name: &str) -> ContentNode {
     })
 }
 
+fn visited_check(location_ref: &str, negated: bool) -> ContentNode {
+    ContentNode::Condition(Condition {
+        expr: ConditionExpr::VisitedCheck(VisitedCheck {
+            location_ref: location_ref.to_string(),
+            negated,
+            annotation: None,
+            span: span("test.urd.md", 43),
+        }),
+        indent_level: 0,
+        span: span("test.urd.md", 43),
+    })
+}
+
+fn once_condition() -> ContentNode {
+    ContentNode::Condition(Condition {
+        expr: ConditionExpr::Once(OnceCondition { span: span("test.urd.md", 21) }),
+        indent_level: 1,
+        span: span("test.urd.md", 21),
+    })
+}
+
 fn set_effect(target_prop: &str, value: &str) -> ContentNode {
     ContentNode::Effect(Effect {
         effect_type: EffectType::Set {
             target_prop: target_prop.to_string(),
             operator: "=".to_string(),
             value_expr: value.to_string(),
+            spans: OperandSpans::whole(),
         },
         indent_level: 0,
         annotation: None,
@@ -341,6 +443,14 @@ fn destroy_effect(entity_ref: &str) -> ContentNode {
     })
 }
 
+fn location_hook(kind: HookKind, children: Vec<ContentNode>) -> ContentNode {
+    ContentNode::LocationHook(LocationHook {
+        kind,
+        children,
+        span: span("test.urd.md", 46),
+    })
+}
+
 fn error_node(text: &str) -> ContentNode {
     ContentNode::ErrorNode(ErrorNode {
         raw_text: text.to_string(),
@@ -360,10 +470,23 @@ fn phase_heading(name: &str, auto: bool) -> ContentNode {
     ContentNode::PhaseHeading(PhaseHeading {
         display_name: name.to_string(),
         auto,
+        section: None,
         span: span("test.urd.md", 56),
     })
 }
 
+fn phase_heading_with_section(name: &str, section: &str, file: &str, line: u32) -> ContentNode {
+    ContentNode::PhaseHeading(PhaseHeading {
+        display_name: name.to_string(),
+        auto: false,
+        section: Some(PhaseSection {
+            name: section.to_string(),
+            span: span(file, line + 1),
+        }),
+        span: span(file, line),
+    })
+}
+
 /// Build a single-file CompilationUnit.
 fn single_file_cu(ast: FileAst) -> CompilationUnit {
     let path = ast.path.clone();
@@ -563,6 +686,27 @@ fn collect_section_with_choices() {
     assert!(sec.choices[1].sticky);
 }
 
+#[test]
+fn collect_once_choice() {
+    let ast = make_file_ast(
+        "tavern.urd.md",
+        None,
+        vec![
+            section("topics"),
+            choice_with_children("Introduce yourself", vec![once_condition()]),
+            choice("Order a drink", false),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(!diag.has_errors());
+    let sec = &linked.symbol_table.sections["tavern/topics"];
+    assert!(sec.choices[0].once);
+    assert!(!sec.choices[1].once);
+}
+
 #[test]
 fn collect_duplicate_entity() {
     let ast_a = make_file_ast(
@@ -749,6 +893,99 @@ fn choice_with_type_target_action() {
     assert_eq!(action.target_type, Some("Door".to_string()));
 }
 
+#[test]
+fn choice_target_resolutions_stored_on_action() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Door", make_type_def("Door", vec![], vec![])),
+            fm_entry("cell_door", make_entity_decl("cell_door", "Door", vec![])),
+        ])),
+        vec![
+            section("actions"),
+            choice_with_target("Use key", "cell_door"),
+            choice_with_type_target_at("Pick a door", "Door", 21),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(!diag.has_errors(), "{:?}", diag.all());
+    let use_key = &linked.symbol_table.actions["test/actions/use-key"];
+    assert_eq!(use_key.resolved_target.as_deref(), Some("cell_door"));
+    assert_eq!(use_key.resolved_target_type, None);
+    let pick = &linked.symbol_table.actions["test/actions/pick-a-door"];
+    assert_eq!(pick.resolved_target, None);
+    assert_eq!(pick.resolved_target_type.as_deref(), Some("Door"));
+}
+
+#[test]
+fn choice_unknown_target_entity() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Door", make_type_def("Door", vec![], vec![])),
+            fm_entry("cell_door", make_entity_decl("cell_door", "Door", vec![])),
+        ])),
+        vec![section("actions"), choice_with_target("Use key", "cell_dor")],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    let err = diag.all().iter().find(|d| d.code == "URD301").expect("URD301");
+    assert_eq!(err.suggestion.as_deref(), Some("Did you mean '@cell_door'?"));
+    assert_eq!(linked.symbol_table.actions["test/actions/use-key"].resolved_target, None);
+}
+
+#[test]
+fn choice_unknown_target_type() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![fm_entry("Door", make_type_def("Door", vec![], vec![]))])),
+        vec![section("actions"), choice_with_type_target("Pick a door", "Dor")],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    let err = diag.all().iter().find(|d| d.code == "URD307").expect("URD307");
+    assert_eq!(err.message, "Unknown type 'Dor' for the target of choice 'Pick a door'.");
+    assert_eq!(err.suggestion.as_deref(), Some("Did you mean 'Door'?"));
+    assert_eq!(linked.symbol_table.actions["test/actions/pick-a-door"].resolved_target_type, None);
+}
+
+#[test]
+fn choice_target_scope_enforcement() {
+    // Door and cell_door declared in B, which A does NOT import.
+    let ast_b = make_file_ast(
+        "doors.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Door", make_type_def_in("Door", "doors.urd.md", 2, vec![], vec![])),
+            fm_entry("cell_door", make_entity_decl_in("cell_door", "Door", "doors.urd.md", 3, vec![])),
+        ])),
+        Vec::new(),
+    );
+    let ast_a = make_file_ast(
+        "main.urd.md",
+        None,
+        vec![
+            section("actions"),
+            choice_with_target("Use key", "cell_door"),
+            choice_with_type_target_at("Pick a door", "Door", 21),
+        ],
+    );
+    let cu = two_file_cu_no_import(ast_b, ast_a);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    let errors: Vec<_> = diag.all().iter().filter(|d| d.code == "URD301").collect();
+    assert_eq!(errors.len(), 2, "{:?}", diag.all());
+    assert!(errors.iter().all(|d| d.suggestion.as_ref().unwrap().contains("not imported")));
+    assert!(errors.iter().any(|d| d.message == "Unresolved type reference 'Door'."));
+}
+
 #[test]
 fn choice_with_no_target() {
     let ast = make_file_ast(
@@ -951,6 +1188,56 @@ fn resolve_unresolved_entity_with_suggestion() {
     assert!(has_suggestion(&diag, "URD301"));
 }
 
+#[test]
+fn resolve_start_inventory() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
+            fm_entry("rusty_key", make_entity_decl("rusty_key", "Key", vec![])),
+            fm_entry("map", make_entity_decl("map", "Key", vec![])),
+            fm_entry("world", make_world_block(vec![(
+                "start_inventory",
+                Scalar::List(vec![
+                    Scalar::EntityRef("rusty_key".to_string()),
+                    Scalar::EntityRef("map".to_string()),
+                    Scalar::EntityRef("rusty_key".to_string()),
+                ]),
+            )])),
+        ])),
+        Vec::new(),
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(!diag.has_errors(), "{:?}", diag.all());
+    assert_eq!(linked.symbol_table.world_start_inventory, vec!["rusty_key", "map"]);
+}
+
+#[test]
+fn resolve_start_inventory_unresolved_with_suggestion() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
+            fm_entry("rusty_key", make_entity_decl("rusty_key", "Key", vec![])),
+            fm_entry("world", make_world_block(vec![(
+                "start_inventory",
+                Scalar::List(vec![Scalar::EntityRef("rusty_kee".to_string())]),
+            )])),
+        ])),
+        Vec::new(),
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD301"));
+    assert!(has_suggestion(&diag, "URD301"));
+    assert!(linked.symbol_table.world_start_inventory.is_empty());
+}
+
 #[test]
 fn resolve_scope_enforcement() {
     // guard declared in B, file A does NOT import B.
@@ -1100,6 +1387,21 @@ fn resolve_unresolved_jump() {
     assert!(has_error(&diag, "URD309"));
 }
 
+#[test]
+fn exhaustion_check_typo_suggests_local_section() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![section("topics"), section("farewell"), exhaustion_check("topcs")],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert_eq!(error_count(&diag, "URD309"), 1);
+    assert_eq!(suggestion_for(&diag, "URD309").as_deref(), Some("Did you mean 'topics'?"));
+}
+
 #[test]
 fn resolve_exit_destination() {
     let ast = make_file_ast(
@@ -1139,500 +1441,455 @@ fn resolve_unresolved_exit_destination() {
 }
 
 #[test]
-fn resolve_entity_presence() {
+fn resolve_visited_check() {
     let ast = make_file_ast(
         "test.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("Key", make_type_def("Key", vec![], vec![])),
-            fm_entry("rusty_key", make_entity_decl("rusty_key", "Key", vec![])),
-            fm_entry("Door", make_type_def("Door", vec![], vec![])),
-            fm_entry("cell_door", make_entity_decl("cell_door", "Door", vec![])),
-        ])),
+        None,
         vec![
-            location("Cell"),
-            entity_presence(vec!["rusty_key", "cell_door"]),
+            location("Tavern"),
+            section("topics"),
+            choice_with_children("Ask about the harbour", vec![visited_check("the-docks", false)]),
+            location("The Docks"),
         ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert!(!diag.has_errors());
-    let cell = &linked.symbol_table.locations["cell"];
-    assert_eq!(cell.contains.len(), 2);
-    assert!(cell.contains.contains(&"rusty_key".to_string()));
-    assert!(cell.contains.contains(&"cell_door".to_string()));
+    assert!(!diag.has_errors(), "{:?}", diag.all());
+    let ast = &linked.graph.nodes["test.urd.md"].ast;
+    let ContentNode::Choice(choice) = &ast.content[2] else {
+        panic!("expected Choice");
+    };
+    let ContentNode::Condition(cond) = &choice.content[0] else {
+        panic!("expected Condition");
+    };
+    let ConditionExpr::VisitedCheck(vc) = &cond.expr else {
+        panic!("expected VisitedCheck");
+    };
+    let ann = vc.annotation.as_ref().expect("visited check annotated");
+    assert_eq!(ann.resolved_location.as_deref(), Some("the-docks"));
 }
 
-// ═══════════════════════════════════════════════════════════════════
-// 4. ID Derivation Tests
-// ═══════════════════════════════════════════════════════════════════
-
 #[test]
-fn id_section() {
+fn resolve_unresolved_visited_check_with_suggestion() {
     let ast = make_file_ast(
-        "tavern.urd.md",
+        "test.urd.md",
         None,
-        vec![section("topics")],
+        vec![
+            location("Tavern"),
+            section("topics"),
+            choice_with_children("Ask about the harbour", vec![visited_check("the-dock", true)]),
+            location("The Docks"),
+        ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
-    let linked = link::link(cu, &mut diag);
+    let _linked = link::link(cu, &mut diag);
 
-    assert!(linked.symbol_table.sections.contains_key("tavern/topics"));
+    assert_eq!(error_count(&diag, "URD321"), 1);
+    assert_eq!(suggestion_for(&diag, "URD321").as_deref(), Some("Did you mean 'the-docks'?"));
 }
 
 #[test]
-fn id_choice() {
+fn resolve_visited_check_by_derived_id_suggests_explicit_id() {
     let ast = make_file_ast(
-        "tavern.urd.md",
+        "test.urd.md",
         None,
         vec![
+            location("Tavern"),
             section("topics"),
-            choice("Ask about the harbor", false),
+            choice_with_children("Ask about the harbour", vec![visited_check("the-docks", false)]),
+            location_with_id("The Docks", "docks"),
         ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
-    let linked = link::link(cu, &mut diag);
+    let _linked = link::link(cu, &mut diag);
 
-    let sec = &linked.symbol_table.sections["tavern/topics"];
-    assert_eq!(sec.choices[0].compiled_id, "tavern/topics/ask-about-the-harbor");
+    assert!(has_error(&diag, "URD321"));
+    assert_eq!(suggestion_for(&diag, "URD321").as_deref(), Some("Did you mean 'docks'?"));
 }
 
 #[test]
-fn id_location() {
+fn resolve_entity_presence() {
     let ast = make_file_ast(
         "test.urd.md",
-        None,
-        vec![location("The Rusty Anchor")],
+        Some(make_frontmatter(vec![
+            fm_entry("Key", make_type_def("Key", vec![], vec![])),
+            fm_entry("rusty_key", make_entity_decl("rusty_key", "Key", vec![])),
+            fm_entry("Door", make_type_def("Door", vec![], vec![])),
+            fm_entry("cell_door", make_entity_decl("cell_door", "Door", vec![])),
+        ])),
+        vec![
+            location("Cell"),
+            entity_presence(vec!["rusty_key", "cell_door"]),
+        ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert!(linked.symbol_table.locations.contains_key("the-rusty-anchor"));
+    assert!(!diag.has_errors());
+    let cell = &linked.symbol_table.locations["cell"];
+    assert_eq!(cell.contains.len(), 2);
+    assert!(cell.contains.contains(&"rusty_key".to_string()));
+    assert!(cell.contains.contains(&"cell_door".to_string()));
 }
 
 #[test]
-fn id_slugify_special_chars() {
+fn resolve_phase_section_local() {
     let ast = make_file_ast(
         "test.urd.md",
         None,
-        vec![location("Café & Bar!!!")],
+        vec![
+            location("Tavern"),
+            section("confession"),
+            sequence_heading("Interrogation"),
+            phase_heading_with_section("Opening", "confession", "test.urd.md", 30),
+        ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert!(linked.symbol_table.locations.contains_key("caf-bar"));
+    assert!(!diag.has_errors());
+    let seq = &linked.symbol_table.sequences["interrogation"];
+    assert_eq!(seq.phases[0].section, Some("test/confession".to_string()));
 }
 
 #[test]
-fn id_slugify_collapse() {
-    let ast = make_file_ast(
-        "test.urd.md",
+fn resolve_phase_section_cross_file() {
+    let ast_b = make_file_ast("b.urd.md", None, vec![section_in("confession", "b.urd.md", 5)]);
+    let ast_a = make_file_ast(
+        "a.urd.md",
         None,
-        vec![location("-- hello -- world --")],
+        vec![
+            sequence_heading("Interrogation"),
+            phase_heading_with_section("Opening", "b/confession", "a.urd.md", 12),
+        ],
     );
-    let cu = single_file_cu(ast);
+    let cu = two_file_cu(ast_b, ast_a);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert!(linked.symbol_table.locations.contains_key("hello-world"));
+    assert!(!diag.has_errors());
+    let seq = &linked.symbol_table.sequences["interrogation"];
+    assert_eq!(seq.phases[0].section, Some("b/confession".to_string()));
 }
 
 #[test]
-fn id_empty_slug() {
+fn unresolved_phase_section() {
     let ast = make_file_ast(
         "test.urd.md",
         None,
-        vec![location("!!!")],
+        vec![
+            location("Tavern"),
+            section("confession"),
+            sequence_heading("Interrogation"),
+            phase_heading("Warm Up", false),
+            phase_heading_with_section("Opening", "confesion", "test.urd.md", 30),
+        ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
-    let _linked = link::link(cu, &mut diag);
+    let linked = link::link(cu, &mut diag);
 
-    assert!(has_error(&diag, "URD313"));
+    assert_eq!(error_count(&diag, "URD309"), 1);
+    assert!(has_suggestion(&diag, "URD309"));
+    let seq = &linked.symbol_table.sequences["interrogation"];
+    assert_eq!(seq.phases[0].section, None);
+    assert_eq!(seq.phases[1].section, None);
 }
 
-// ═══════════════════════════════════════════════════════════════════
-// 5. Integration Tests
-// ═══════════════════════════════════════════════════════════════════
-
 #[test]
-fn integration_two_room_key_puzzle() {
-    // Single file with: types, entities, two locations, exits, entity presence, conditions.
-    let file = "dungeon.urd.md";
-    let ast = make_file_ast(
-        file,
-        Some(Frontmatter {
-            entries: vec![
-                fm_entry("Container", make_type_def_in("Container", file, 2, vec![], vec![
-                    make_property_with_default("locked", "boolean", Scalar::Boolean(false)),
-                ])),
-                fm_entry("Item", make_type_def_in("Item", file, 3, vec!["portable"], vec![])),
-                fm_entry("cell_door", make_entity_decl_in("cell_door", "Container", file, 4, vec![
-                    ("locked", Scalar::Boolean(true)),
-                ])),
-                fm_entry("rusty_key", make_entity_decl_in("rusty_key", "Item", file, 5, vec![])),
-            ],
-            span: span(file, 1),
-        }),
+fn phase_section_not_imported() {
+    let ast_b = make_file_ast("b.urd.md", None, vec![section_in("confession", "b.urd.md", 5)]);
+    let ast_a = make_file_ast(
+        "a.urd.md",
+        None,
         vec![
-            location_in("Cell", file, 10),
-            entity_presence(vec!["rusty_key", "cell_door"]),
-            exit_decl("north", "Corridor"),
-            section_in("examine", file, 14),
-            entity_speech("cell_door", "A heavy iron door."),
-            property_comparison("cell_door", "locked", "==", "true"),
-            location_in("Corridor", file, 20),
+            sequence_heading("Interrogation"),
+            phase_heading_with_section("Opening", "b/confession", "a.urd.md", 12),
         ],
     );
-    let cu = single_file_cu(ast);
+    let cu = two_file_cu_no_import(ast_b, ast_a);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert!(!diag.has_errors());
-    assert_eq!(linked.symbol_table.types.len(), 2);
-    assert_eq!(linked.symbol_table.entities.len(), 2);
-    assert_eq!(linked.symbol_table.locations.len(), 2);
-    assert_eq!(linked.symbol_table.sections.len(), 1);
+    assert!(has_error(&diag, "URD309"));
+    assert!(diag.all().iter().any(|d| d.code == "URD309"
+        && d.suggestion.as_deref().is_some_and(|s| s.contains("not imported"))));
+    assert_eq!(linked.symbol_table.sequences["interrogation"].phases[0].section, None);
+}
 
-    let cell = &linked.symbol_table.locations["cell"];
-    assert_eq!(cell.contains.len(), 2);
-    assert!(cell.exits.contains_key("north"));
-    assert_eq!(cell.exits["north"].resolved_destination, Some("corridor".to_string()));
+/// The resolved section of the exhaustion check or jump at `index` in
+/// `file`'s content.
+fn resolved_section_at(linked: &link::LinkedWorld, file: &str, index: usize) -> Option<String> {
+    let annotation = match &linked.graph.nodes[file].ast.content[index] {
+        ContentNode::Condition(Condition { expr: ConditionExpr::ExhaustionCheck(ec), .. }) => &ec.annotation,
+        ContentNode::Jump(jump) => &jump.annotation,
+        other => panic!("expected an exhaustion check or jump, got {:?}", other),
+    };
+    annotation.as_ref().and_then(|a| a.resolved_section.clone())
 }
 
 #[test]
-fn integration_multi_file_project() {
-    // File B: types and entities. File A: imports B, references entities.
-    let ast_b = make_file_ast(
-        "types.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("Guard", make_type_def_in("Guard", "types.urd.md", 2, vec![], vec![
-                make_property("mood", "string"),
-            ])),
-            fm_entry("guard", make_entity_decl_in("guard", "Guard", "types.urd.md", 3, vec![])),
-        ])),
-        Vec::new(),
-    );
+fn qualified_exhaustion_check_and_jump_cross_file() {
+    let ast_b = make_file_ast("b.urd.md", None, vec![section_in("topics", "b.urd.md", 5)]);
     let ast_a = make_file_ast(
-        "main.urd.md",
+        "a.urd.md",
         None,
-        vec![
-            entity_speech("guard", "Halt!"),
-            stage_direction("guard", "draws sword"),
-        ],
+        vec![section("talk"), exhaustion_check("b/topics"), jump("b/topics")],
     );
     let cu = two_file_cu(ast_b, ast_a);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert!(!diag.has_errors());
-    // Entity should be resolved in main.urd.md content.
-    if let ContentNode::EntitySpeech(speech) = &linked.graph.nodes["main.urd.md"].ast.content[0] {
-        assert_eq!(speech.annotation.as_ref().unwrap().resolved_entity, Some("guard".to_string()));
-    }
+    assert!(!diag.has_errors(), "{:?}", diag.all());
+    assert_eq!(resolved_section_at(&linked, "a.urd.md", 1), Some("b/topics".to_string()));
+    assert_eq!(resolved_section_at(&linked, "a.urd.md", 2), Some("b/topics".to_string()));
 }
 
 #[test]
-fn integration_jump_disambiguation() {
-    // File has section and exit sharing a name. Section should win with warning.
-    let ast = make_file_ast(
-        "test.urd.md",
+fn qualified_section_not_imported() {
+    let ast_b = make_file_ast("b.urd.md", None, vec![section_in("topics", "b.urd.md", 5)]);
+    let ast_a = make_file_ast(
+        "a.urd.md",
         None,
-        vec![
-            location("Room"),
-            exit_decl("north", "Room"),
-            section("north"),
-            jump("north"),
-            exit_jump("north"),
-        ],
+        vec![section("talk"), exhaustion_check("b/topics"), jump("b/topics")],
     );
-    let cu = single_file_cu(ast);
+    let cu = two_file_cu_no_import(ast_b, ast_a);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert!(has_warning(&diag, "URD310"));
-    // Standard jump resolves to section.
-    if let ContentNode::Jump(j) = &linked.graph.nodes["test.urd.md"].ast.content[3] {
-        assert_eq!(j.annotation.as_ref().unwrap().resolved_section, Some("test/north".to_string()));
-    }
-    // Explicit exit jump resolves to exit.
-    if let ContentNode::Jump(j) = &linked.graph.nodes["test.urd.md"].ast.content[4] {
-        assert!(j.annotation.is_some());
-    }
+    assert_eq!(error_count(&diag, "URD309"), 2);
+    assert!(diag.all().iter().filter(|d| d.code == "URD309").all(|d| d.suggestion.as_deref()
+        == Some("'b/topics' is declared in b.urd.md but b.urd.md is not imported by a.urd.md.")));
+    assert_eq!(resolved_section_at(&linked, "a.urd.md", 1), None);
+    assert_eq!(resolved_section_at(&linked, "a.urd.md", 2), None);
+}
+
+#[test]
+fn qualified_section_typo_suggests_compiled_id() {
+    let ast_b = make_file_ast("b.urd.md", None, vec![section_in("topics", "b.urd.md", 5)]);
+    let ast_a = make_file_ast("a.urd.md", None, vec![section("talk"), jump("b/topcs")]);
+    let cu = two_file_cu(ast_b, ast_a);
+    let mut diag = DiagnosticCollector::new();
+    link::link(cu, &mut diag);
+
+    let err = diag.all().iter().find(|d| d.code == "URD309").expect("URD309");
+    assert_eq!(err.message, "Unresolved jump target 'b/topcs'. No section with this ID exists in scope.");
+    assert_eq!(err.suggestion.as_deref(), Some("Did you mean 'b/topics'?"));
+}
+
+#[test]
+fn unqualified_section_prefers_local_over_imported() {
+    let ast_b = make_file_ast("b.urd.md", None, vec![section_in("topics", "b.urd.md", 5)]);
+    let ast_a = make_file_ast(
+        "a.urd.md",
+        None,
+        vec![section("topics"), exhaustion_check("topics"), jump("topics"), jump("b/topics")],
+    );
+    let cu = two_file_cu(ast_b, ast_a);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(!diag.has_errors(), "{:?}", diag.all());
+    assert_eq!(resolved_section_at(&linked, "a.urd.md", 1), Some("a/topics".to_string()));
+    assert_eq!(resolved_section_at(&linked, "a.urd.md", 2), Some("a/topics".to_string()));
+    assert_eq!(resolved_section_at(&linked, "a.urd.md", 3), Some("b/topics".to_string()));
 }
 
 // ═══════════════════════════════════════════════════════════════════
-// 6. Error Recovery Tests
+// 4. ID Derivation Tests
 // ═══════════════════════════════════════════════════════════════════
 
 #[test]
-fn recovery_one_bad_entity_among_good() {
+fn id_section() {
     let ast = make_file_ast(
-        "test.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("Guard", make_type_def("Guard", vec![], vec![
-                make_property("mood", "string"),
-            ])),
-            fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
-            fm_entry("innkeeper", make_entity_decl("innkeeper", "MissingType", vec![])),
-        ])),
-        Vec::new(),
+        "tavern.urd.md",
+        None,
+        vec![section("topics")],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert!(has_error(&diag, "URD307"));
-    assert_eq!(error_count(&diag, "URD307"), 1);
-    // Guard entity should still be fully resolved.
-    assert_eq!(linked.symbol_table.entities["guard"].type_symbol, Some("Guard".to_string()));
-    // Innkeeper entity's type should be unresolved.
-    assert_eq!(linked.symbol_table.entities["innkeeper"].type_symbol, None);
+    assert!(linked.symbol_table.sections.contains_key("tavern/topics"));
 }
 
 #[test]
-fn recovery_cascading_suppressed() {
-    // Entity references a missing type. Three property accesses on that entity.
-    // Should get URD307 once, zero URD308.
+fn id_choice() {
     let ast = make_file_ast(
-        "test.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("guard", make_entity_decl("guard", "MissingType", vec![])),
-        ])),
+        "tavern.urd.md",
+        None,
         vec![
-            property_comparison("guard", "mood", "==", "happy"),
-            property_comparison("guard", "health", ">=", "50"),
-            property_comparison("guard", "strength", "<", "10"),
+            section("topics"),
+            choice("Ask about the harbor", false),
         ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
-    let _linked = link::link(cu, &mut diag);
+    let linked = link::link(cu, &mut diag);
 
-    assert_eq!(error_count(&diag, "URD307"), 1);
-    assert_eq!(error_count(&diag, "URD308"), 0);
+    let sec = &linked.symbol_table.sections["tavern/topics"];
+    assert_eq!(sec.choices[0].compiled_id, "tavern/topics/ask-about-the-harbor");
 }
 
 #[test]
-fn recovery_error_node_among_valid() {
+fn id_location() {
     let ast = make_file_ast(
-        "tavern.urd.md",
+        "test.urd.md",
         None,
-        vec![
-            section("greetings"),
-            error_node("broken stuff"),
-            section("farewell"),
-        ],
+        vec![location("The Rusty Anchor")],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert!(!diag.has_errors());
-    assert!(linked.symbol_table.sections.contains_key("tavern/greetings"));
-    assert!(linked.symbol_table.sections.contains_key("tavern/farewell"));
+    assert!(linked.symbol_table.locations.contains_key("the-rusty-anchor"));
 }
 
 #[test]
-fn recovery_duplicate_then_reference() {
-    // Two files declare @guard, then it's referenced. First should win, reference resolves.
-    let ast_b = make_file_ast(
-        "b.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("Guard", make_type_def_in("Guard", "b.urd.md", 2, vec![], vec![])),
-            fm_entry("guard", make_entity_decl_in("guard", "Guard", "b.urd.md", 3, vec![])),
-        ])),
-        Vec::new(),
-    );
-    let ast_a = make_file_ast(
-        "a.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("guard", make_entity_decl_in("guard", "Guard", "a.urd.md", 2, vec![])),
-        ])),
-        vec![entity_speech("guard", "Halt!")],
+fn id_slugify_special_chars() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![location("Café & Bar!!!")],
     );
-    let cu = two_file_cu(ast_b, ast_a);
+    let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    // URD302 for duplicate.
-    assert!(has_error(&diag, "URD302"));
-    // Reference should still resolve to first declaration (from b.urd.md).
-    if let ContentNode::EntitySpeech(speech) = &linked.graph.nodes["a.urd.md"].ast.content[0] {
-        assert!(speech.annotation.is_some());
-        assert_eq!(speech.annotation.as_ref().unwrap().resolved_entity, Some("guard".to_string()));
-    }
-    // No URD301.
-    assert!(!has_error(&diag, "URD301"));
+    assert!(linked.symbol_table.locations.contains_key("caf-bar"));
 }
 
-// ═══════════════════════════════════════════════════════════════════
-// 7. Scope & Location Context Tests
-// ═══════════════════════════════════════════════════════════════════
+#[test]
+fn id_slugify_collapse() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![location("-- hello -- world --")],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(linked.symbol_table.locations.contains_key("hello-world"));
+}
 
 #[test]
-fn scope_exit_outside_location_context() {
+fn id_empty_slug() {
     let ast = make_file_ast(
         "test.urd.md",
         None,
-        vec![
-            exit_decl("north", "Harbor"),
-        ],
+        vec![location("!!!")],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
     let _linked = link::link(cu, &mut diag);
 
-    assert!(has_error(&diag, "URD314"));
+    assert!(has_error(&diag, "URD313"));
 }
 
 #[test]
-fn scope_entity_presence_outside_location() {
+fn region_heading_assigns_following_locations() {
     let ast = make_file_ast(
         "test.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("Key", make_type_def("Key", vec![], vec![])),
-            fm_entry("rusty_key", make_entity_decl("rusty_key", "Key", vec![])),
-        ])),
+        None,
         vec![
-            entity_presence(vec!["rusty_key"]),
+            location("Gate"),
+            region("The Docks"),
+            location("Quay"),
+            location("Fish Market"),
+            region("The Keep"),
+            location("Great Hall"),
+            region("The Docks"),
+            location("Boathouse"),
         ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
-    let _linked = link::link(cu, &mut diag);
+    let linked = link::link(cu, &mut diag);
+    let st = &linked.symbol_table;
 
-    assert!(has_error(&diag, "URD314"));
+    assert_eq!(st.locations["gate"].region, None);
+    assert_eq!(st.locations["quay"].region.as_deref(), Some("the-docks"));
+    assert_eq!(st.locations["great-hall"].region.as_deref(), Some("the-keep"));
+    assert_eq!(st.locations["boathouse"].region.as_deref(), Some("the-docks"));
+
+    let regions: Vec<&str> = st.regions.keys().map(String::as_str).collect();
+    assert_eq!(regions, ["the-docks", "the-keep"]);
+    assert_eq!(st.regions["the-docks"].display_name, "The Docks");
+    assert_eq!(st.regions["the-docks"].locations, ["quay", "fish-market", "boathouse"]);
 }
 
 #[test]
-fn scope_explicit_exit_jump_outside_location() {
+fn region_empty_slug() {
     let ast = make_file_ast(
         "test.urd.md",
         None,
-        vec![exit_jump("north")],
+        vec![region("!!!"), location("Quay")],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
-    let _linked = link::link(cu, &mut diag);
+    let linked = link::link(cu, &mut diag);
 
-    assert!(has_error(&diag, "URD314"));
+    assert!(has_error(&diag, "URD313"));
+    assert!(linked.symbol_table.regions.is_empty());
+    assert_eq!(linked.symbol_table.locations["quay"].region, None);
+}
+
+fn suggestion_for(diag: &DiagnosticCollector, code: &str) -> Option<String> {
+    diag.all().iter().find(|d| d.code == code).and_then(|d| d.suggestion.clone())
 }
 
 #[test]
-fn scope_cross_file_with_import() {
-    let ast_b = make_file_ast(
-        "types.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("Guard", make_type_def_in("Guard", "types.urd.md", 2, vec![], vec![])),
-            fm_entry("guard", make_entity_decl_in("guard", "Guard", "types.urd.md", 3, vec![])),
-        ])),
-        Vec::new(),
-    );
-    let ast_a = make_file_ast(
-        "main.urd.md",
-        None,
-        vec![entity_speech("guard", "Hello")],
-    );
-    let cu = two_file_cu(ast_b, ast_a);
-    let mut diag = DiagnosticCollector::new();
-    let linked = link::link(cu, &mut diag);
-
-    assert!(!diag.has_errors());
-    if let ContentNode::EntitySpeech(speech) = &linked.graph.nodes["main.urd.md"].ast.content[0] {
-        assert_eq!(speech.annotation.as_ref().unwrap().resolved_entity, Some("guard".to_string()));
-    }
-}
-
-#[test]
-fn scope_cross_file_without_import() {
-    let ast_b = make_file_ast(
-        "npcs.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("Guard", make_type_def_in("Guard", "npcs.urd.md", 2, vec![], vec![])),
-            fm_entry("guard", make_entity_decl_in("guard", "Guard", "npcs.urd.md", 3, vec![])),
-        ])),
-        Vec::new(),
-    );
-    let ast_a = make_file_ast(
-        "main.urd.md",
-        None,
-        vec![entity_speech("guard", "Hello")],
-    );
-    let cu = two_file_cu_no_import(ast_b, ast_a);
-    let mut diag = DiagnosticCollector::new();
-    let _linked = link::link(cu, &mut diag);
-
-    assert!(has_error(&diag, "URD301"));
-}
-
-#[test]
-fn scope_forward_reference_within_file() {
-    // Entity referenced before declaration in same file. Should resolve.
+fn id_explicit_location() {
     let ast = make_file_ast(
         "test.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("Guard", make_type_def("Guard", vec![], vec![])),
-            fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
-        ])),
-        vec![
-            entity_speech("guard", "Halt!"),
-        ],
+        None,
+        vec![location_with_id("Café & Bar!!!", "cafe")],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
     assert!(!diag.has_errors());
-    if let ContentNode::EntitySpeech(speech) = &linked.graph.nodes["test.urd.md"].ast.content[0] {
-        assert!(speech.annotation.is_some());
-    }
+    assert!(!linked.symbol_table.locations.contains_key("caf-bar"));
+    assert_eq!(linked.symbol_table.locations["cafe"].display_name, "Café & Bar!!!");
 }
 
-// ═══════════════════════════════════════════════════════════════════
-// Additional Tests
-// ═══════════════════════════════════════════════════════════════════
-
 #[test]
-fn resolve_exhaustion_check() {
+fn id_explicit_section_and_choice() {
     let ast = make_file_ast(
         "tavern.urd.md",
         None,
         vec![
-            section("topics"),
-            exhaustion_check("topics"),
+            section_with_id("topics", "intro_topics"),
+            choice("Ask about the harbor", false),
         ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert!(!diag.has_errors());
-    if let ContentNode::Condition(cond) = &linked.graph.nodes["tavern.urd.md"].ast.content[1] {
-        if let ConditionExpr::ExhaustionCheck(ec) = &cond.expr {
-            assert!(ec.annotation.is_some());
-            assert_eq!(ec.annotation.as_ref().unwrap().resolved_section, Some("tavern/topics".to_string()));
-        }
-    }
+    assert!(!linked.symbol_table.sections.contains_key("tavern/topics"));
+    let sec = &linked.symbol_table.sections["tavern/intro_topics"];
+    assert_eq!(sec.local_name, "intro_topics");
+    assert_eq!(sec.choices[0].compiled_id, "tavern/intro_topics/ask-about-the-harbor");
 }
 
 #[test]
-fn resolve_set_effect() {
+fn id_explicit_location_exit_destination() {
     let ast = make_file_ast(
         "test.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("Guard", make_type_def("Guard", vec![], vec![
-                make_property("mood", "string"),
-            ])),
-            fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
-        ])),
+        None,
         vec![
-            set_effect("@guard.mood", "happy"),
+            location("Street"),
+            exit_decl("in", "cafe"),
+            location_with_id("Café & Bar", "cafe"),
         ],
     );
     let cu = single_file_cu(ast);
@@ -1640,197 +1897,164 @@ fn resolve_set_effect() {
     let linked = link::link(cu, &mut diag);
 
     assert!(!diag.has_errors());
-    if let ContentNode::Effect(eff) = &linked.graph.nodes["test.urd.md"].ast.content[0] {
-        assert!(eff.annotation.is_some());
-        let ann = eff.annotation.as_ref().unwrap();
-        assert_eq!(ann.resolved_entity, Some("guard".to_string()));
-        assert_eq!(ann.resolved_property, Some("mood".to_string()));
-    }
+    let exit = &linked.symbol_table.locations["street"].exits["in"];
+    assert_eq!(exit.resolved_destination.as_deref(), Some("cafe"));
 }
 
 #[test]
-fn resolve_move_effect() {
+fn id_explicit_location_derived_form_suggests_id() {
     let ast = make_file_ast(
         "test.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("Item", make_type_def("Item", vec![], vec![])),
-            fm_entry("Container", make_type_def("Container", vec![], vec![])),
-            fm_entry("key", make_entity_decl("key", "Item", vec![])),
-            fm_entry("chest", make_entity_decl("chest", "Container", vec![])),
-        ])),
+        None,
         vec![
-            move_effect("key", "chest"),
+            location("Street"),
+            exit_decl("in", "Café & Bar"),
+            location_with_id("Café & Bar", "cafe"),
         ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
-    let linked = link::link(cu, &mut diag);
+    let _linked = link::link(cu, &mut diag);
 
-    assert!(!diag.has_errors());
-    if let ContentNode::Effect(eff) = &linked.graph.nodes["test.urd.md"].ast.content[0] {
-        assert!(eff.annotation.is_some());
-        assert_eq!(eff.annotation.as_ref().unwrap().resolved_entity, Some("key".to_string()));
-    }
+    assert!(has_error(&diag, "URD312"));
+    assert_eq!(suggestion_for(&diag, "URD312").as_deref(), Some("Did you mean 'cafe'?"));
 }
 
 #[test]
-fn resolve_destroy_effect() {
+fn id_explicit_section_jump_and_exhaustion() {
     let ast = make_file_ast(
         "test.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("Item", make_type_def("Item", vec![], vec![])),
-            fm_entry("key", make_entity_decl("key", "Item", vec![])),
-        ])),
+        None,
         vec![
-            destroy_effect("key"),
+            location("Tavern"),
+            section_with_id("topics", "intro_topics"),
+            jump("intro_topics"),
+            exhaustion_check("intro_topics"),
+            jump("topics"),
         ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
-    let linked = link::link(cu, &mut diag);
+    let _linked = link::link(cu, &mut diag);
 
-    assert!(!diag.has_errors());
-    if let ContentNode::Effect(eff) = &linked.graph.nodes["test.urd.md"].ast.content[0] {
-        assert!(eff.annotation.is_some());
-        assert_eq!(eff.annotation.as_ref().unwrap().resolved_entity, Some("key".to_string()));
-    }
+    // Only the jump using the label name fails, and it suggests the ID.
+    assert_eq!(error_count(&diag, "URD309"), 1);
+    assert_eq!(suggestion_for(&diag, "URD309").as_deref(), Some("Did you mean 'intro_topics'?"));
 }
 
 #[test]
-fn resolve_containment_check() {
+fn id_explicit_location_invalid() {
     let ast = make_file_ast(
         "test.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("Item", make_type_def("Item", vec![], vec![])),
-            fm_entry("Container", make_type_def("Container", vec![], vec![])),
-            fm_entry("key", make_entity_decl("key", "Item", vec![])),
-            fm_entry("chest", make_entity_decl("chest", "Container", vec![])),
-        ])),
-        vec![
-            containment_check("key", "chest", false),
-        ],
+        None,
+        vec![location_with_id("Café & Bar", "Cafe Bar")],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert!(!diag.has_errors());
-    if let ContentNode::Condition(cond) = &linked.graph.nodes["test.urd.md"].ast.content[0] {
-        if let ConditionExpr::ContainmentCheck(cc) = &cond.expr {
-            assert!(cc.annotation.is_some());
-            assert_eq!(cc.annotation.as_ref().unwrap().resolved_entity, Some("key".to_string()));
-        }
-    }
+    assert!(has_error(&diag, "URD316"));
+    assert_eq!(suggestion_for(&diag, "URD316").as_deref(), Some("Did you mean '{#cafe-bar}'?"));
+    assert!(linked.symbol_table.locations.is_empty());
 }
 
 #[test]
-fn resolve_unknown_type_with_suggestion() {
-    let ast = make_file_ast(
-        "test.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("Guard", make_type_def("Guard", vec![], vec![])),
-            fm_entry("guard", make_entity_decl("guard", "Gurad", vec![])),
-        ])),
-        Vec::new(),
-    );
-    let cu = single_file_cu(ast);
-    let mut diag = DiagnosticCollector::new();
-    let _linked = link::link(cu, &mut diag);
-
-    assert!(has_error(&diag, "URD307"));
-    assert!(has_suggestion(&diag, "URD307"));
+fn id_explicit_location_invalid_hyphens() {
+    for id in ["", "-cafe", "cafe-", "ca--fe", "café"] {
+        let ast = make_file_ast("test.urd.md", None, vec![location_with_id("Café", id)]);
+        let cu = single_file_cu(ast);
+        let mut diag = DiagnosticCollector::new();
+        let _linked = link::link(cu, &mut diag);
+        assert!(has_error(&diag, "URD316"), "expected URD316 for '{}'", id);
+    }
 }
 
 #[test]
-fn resolve_unknown_property() {
+fn id_explicit_section_invalid() {
     let ast = make_file_ast(
-        "test.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("Guard", make_type_def("Guard", vec![], vec![
-                make_property("mood", "string"),
-            ])),
-            fm_entry("guard", make_entity_decl("guard", "Guard", vec![
-                ("nonexistent", Scalar::String("value".to_string())),
-            ])),
-        ])),
-        Vec::new(),
+        "tavern.urd.md",
+        None,
+        vec![
+            section_with_id("topics", "intro-topics"),
+            choice("Ask", false),
+        ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
-    let _linked = link::link(cu, &mut diag);
+    let linked = link::link(cu, &mut diag);
 
-    assert!(has_error(&diag, "URD308"));
+    assert!(has_error(&diag, "URD316"));
+    assert_eq!(suggestion_for(&diag, "URD316").as_deref(), Some("Did you mean '{#intro_topics}'?"));
+    assert!(linked.symbol_table.sections.is_empty());
 }
 
 #[test]
-fn resolve_unresolved_explicit_exit() {
+fn id_explicit_location_collides_with_slug() {
     let ast = make_file_ast(
         "test.urd.md",
         None,
         vec![
-            location("Cell"),
-            exit_jump("west"),
+            location("Cafe"),
+            location_with_id("Café & Bar", "cafe"),
         ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
     let _linked = link::link(cu, &mut diag);
 
-    assert!(has_error(&diag, "URD311"));
+    assert!(has_error(&diag, "URD304"));
+    let msg = &diag.all().iter().find(|d| d.code == "URD304").unwrap().message;
+    assert!(msg.contains("both resolve to 'cafe'"), "message: {}", msg);
 }
 
 #[test]
-fn collect_sequence_and_phase() {
+fn id_explicit_section_collides_with_name() {
     let ast = make_file_ast(
-        "test.urd.md",
+        "tavern.urd.md",
         None,
         vec![
-            sequence_heading("Morning Routine"),
-            phase_heading("Wake Up", false),
-            phase_heading("Breakfast", true),
+            section("intro"),
+            section_with_id("topics", "intro"),
         ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
-    let linked = link::link(cu, &mut diag);
-
-    assert!(!diag.has_errors());
-    assert!(linked.symbol_table.sequences.contains_key("morning-routine"));
-    let seq = &linked.symbol_table.sequences["morning-routine"];
-    assert_eq!(seq.phases.len(), 2);
-    assert_eq!(seq.phases[0].id, "wake-up");
-    assert_eq!(seq.phases[0].advance, "manual");
-    assert_eq!(seq.phases[1].id, "breakfast");
-    assert_eq!(seq.phases[1].advance, "auto");
-}
+    let _linked = link::link(cu, &mut diag);
 
-#[test]
-fn edit_distance_basic() {
-    // Verify the edit distance function directly.
-    assert_eq!(urd_compiler::link::edit_distance("guard", "gaurd"), 2);
-    assert_eq!(urd_compiler::link::edit_distance("guard", "guard"), 0);
-    assert_eq!(urd_compiler::link::edit_distance("guard", "gurd"), 1);
-    assert_eq!(urd_compiler::link::edit_distance("", "abc"), 3);
-    assert_eq!(urd_compiler::link::edit_distance("abc", ""), 3);
+    assert!(has_error(&diag, "URD305"));
 }
 
 // ═══════════════════════════════════════════════════════════════════
-// 8. Compliance Fixes – Regression Tests
+// 5. Integration Tests
 // ═══════════════════════════════════════════════════════════════════
 
 #[test]
-fn world_config_start_and_entry_stored() {
+fn integration_two_room_key_puzzle() {
+    // Single file with: types, entities, two locations, exits, entity presence, conditions.
+    let file = "dungeon.urd.md";
     let ast = make_file_ast(
-        "test.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("world", make_world_block(vec![
-                ("start", Scalar::String("Grand Hall".to_string())),
-                ("entry", Scalar::String("morning-routine".to_string())),
-            ])),
-        ])),
+        file,
+        Some(Frontmatter {
+            entries: vec![
+                fm_entry("Container", make_type_def_in("Container", file, 2, vec![], vec![
+                    make_property_with_default("locked", "boolean", Scalar::Boolean(false)),
+                ])),
+                fm_entry("Item", make_type_def_in("Item", file, 3, vec!["portable"], vec![])),
+                fm_entry("cell_door", make_entity_decl_in("cell_door", "Container", file, 4, vec![
+                    ("locked", Scalar::Boolean(true)),
+                ])),
+                fm_entry("rusty_key", make_entity_decl_in("rusty_key", "Item", file, 5, vec![])),
+            ],
+            span: span(file, 1),
+        }),
         vec![
-            location("Grand Hall"),
-            sequence_heading("Morning Routine"),
+            location_in("Cell", file, 10),
+            entity_presence(vec!["rusty_key", "cell_door"]),
+            exit_decl("north", "Corridor"),
+            section_in("examine", file, 14),
+            entity_speech("cell_door", "A heavy iron door."),
+            property_comparison("cell_door", "locked", "==", "true"),
+            location_in("Corridor", file, 20),
         ],
     );
     let cu = single_file_cu(ast);
@@ -1838,533 +2062,2268 @@ fn world_config_start_and_entry_stored() {
     let linked = link::link(cu, &mut diag);
 
     assert!(!diag.has_errors());
-    assert_eq!(linked.symbol_table.world_start, Some("grand-hall".to_string()));
-    assert_eq!(linked.symbol_table.world_entry, Some("morning-routine".to_string()));
+    assert_eq!(linked.symbol_table.types.len(), 2);
+    assert_eq!(linked.symbol_table.entities.len(), 2);
+    assert_eq!(linked.symbol_table.locations.len(), 2);
+    assert_eq!(linked.symbol_table.sections.len(), 1);
+
+    let cell = &linked.symbol_table.locations["cell"];
+    assert_eq!(cell.contains.len(), 2);
+    assert!(cell.exits.contains_key("north"));
+    assert_eq!(cell.exits["north"].resolved_destination, Some("corridor".to_string()));
 }
 
 #[test]
-fn world_config_unresolved_not_stored() {
-    let ast = make_file_ast(
-        "test.urd.md",
+fn integration_multi_file_project() {
+    // File B: types and entities. File A: imports B, references entities.
+    let ast_b = make_file_ast(
+        "types.urd.md",
         Some(make_frontmatter(vec![
-            fm_entry("world", make_world_block(vec![
-                ("start", Scalar::String("Nonexistent".to_string())),
+            fm_entry("Guard", make_type_def_in("Guard", "types.urd.md", 2, vec![], vec![
+                make_property("mood", "string"),
             ])),
+            fm_entry("guard", make_entity_decl_in("guard", "Guard", "types.urd.md", 3, vec![])),
         ])),
         Vec::new(),
     );
-    let cu = single_file_cu(ast);
+    let ast_a = make_file_ast(
+        "main.urd.md",
+        None,
+        vec![
+            entity_speech("guard", "Halt!"),
+            stage_direction("guard", "draws sword"),
+        ],
+    );
+    let cu = two_file_cu(ast_b, ast_a);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert_eq!(linked.symbol_table.world_start, None);
+    assert!(!diag.has_errors());
+    // Entity should be resolved in main.urd.md content.
+    if let ContentNode::EntitySpeech(speech) = &linked.graph.nodes["main.urd.md"].ast.content[0] {
+        assert_eq!(speech.annotation.as_ref().unwrap().resolved_entity, Some("guard".to_string()));
+    }
 }
 
 #[test]
-fn duplicate_sequence_detected() {
+fn integration_jump_disambiguation() {
+    // File has section and exit sharing a name. Section should win with warning.
     let ast = make_file_ast(
         "test.urd.md",
         None,
         vec![
-            sequence_heading("Morning Routine"),
-            phase_heading("Phase A", false),
-            sequence_heading("Morning Routine"),
-            phase_heading("Phase B", false),
+            location("Room"),
+            exit_decl("north", "Room"),
+            section("north"),
+            jump("north"),
+            exit_jump("north"),
         ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert!(has_error(&diag, "URD313")); // Duplicate sequence
-    assert_eq!(linked.symbol_table.duplicates.iter().filter(|d| d.namespace == "sequences").count(), 1);
-    // First declaration wins — only one entry in the sequences map.
-    assert_eq!(linked.symbol_table.sequences.len(), 1);
-    let seq = &linked.symbol_table.sequences["morning-routine"];
-    // Both phases attach to the original sequence since current_sequence_id is set
-    // to the same ID after the duplicate heading.
-    assert_eq!(seq.phases.len(), 2);
-    assert_eq!(seq.phases[0].id, "phase-a");
-    assert_eq!(seq.phases[1].id, "phase-b");
+    assert!(has_warning(&diag, "URD310"));
+    // Standard jump resolves to section.
+    if let ContentNode::Jump(j) = &linked.graph.nodes["test.urd.md"].ast.content[3] {
+        assert_eq!(j.annotation.as_ref().unwrap().resolved_section, Some("test/north".to_string()));
+    }
+    // Explicit exit jump resolves to exit.
+    if let ContentNode::Jump(j) = &linked.graph.nodes["test.urd.md"].ast.content[4] {
+        assert!(j.annotation.is_some());
+    }
 }
 
+// ═══════════════════════════════════════════════════════════════════
+// 6. Error Recovery Tests
+// ═══════════════════════════════════════════════════════════════════
+
 #[test]
-fn duplicate_rule_detected() {
+fn recovery_one_bad_entity_among_good() {
     let ast = make_file_ast(
         "test.urd.md",
-        None,
-        vec![
-            ContentNode::RuleBlock(RuleBlock {
-                name: "patrol".to_string(),
-                actor: "guard".to_string(),
-                trigger: "idle".to_string(),
-                select: None,
-                where_clauses: Vec::new(),
-                effects: Vec::new(),
-                span: span("test.urd.md", 10),
-            }),
-            ContentNode::RuleBlock(RuleBlock {
-                name: "patrol".to_string(),
-                actor: "knight".to_string(),
-                trigger: "idle".to_string(),
-                select: None,
-                where_clauses: Vec::new(),
-                effects: Vec::new(),
-                span: span("test.urd.md", 20),
-            }),
-        ],
+        Some(make_frontmatter(vec![
+            fm_entry("Guard", make_type_def("Guard", vec![], vec![
+                make_property("mood", "string"),
+            ])),
+            fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+            fm_entry("innkeeper", make_entity_decl("innkeeper", "MissingType", vec![])),
+        ])),
+        Vec::new(),
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert!(has_error(&diag, "URD302")); // Duplicate rule
-    assert_eq!(linked.symbol_table.duplicates.iter().filter(|d| d.namespace == "rules").count(), 1);
-    // First declaration wins.
-    let rule = &linked.symbol_table.rules["patrol"];
-    assert_eq!(rule.actor, "guard");
+    assert!(has_error(&diag, "URD307"));
+    assert_eq!(error_count(&diag, "URD307"), 1);
+    // Guard entity should still be fully resolved.
+    assert_eq!(linked.symbol_table.entities["guard"].type_symbol, Some("Guard".to_string()));
+    // Innkeeper entity's type should be unresolved.
+    assert_eq!(linked.symbol_table.entities["innkeeper"].type_symbol, None);
 }
 
 #[test]
-fn entity_presence_outside_location_returns_early() {
+fn recovery_cascading_suppressed() {
+    // Entity references a missing type. Three property accesses on that entity.
+    // Should get URD307 once, zero URD308.
     let ast = make_file_ast(
         "test.urd.md",
         Some(make_frontmatter(vec![
-            fm_entry("Item", make_type_def("Item", vec![], vec![])),
-            fm_entry("key", make_entity_decl("key", "Item", vec![])),
+            fm_entry("guard", make_entity_decl("guard", "MissingType", vec![])),
         ])),
         vec![
-            // EntityPresence before any location heading.
-            entity_presence(vec!["key"]),
+            property_comparison("guard", "mood", "==", "happy"),
+            property_comparison("guard", "health", ">=", "50"),
+            property_comparison("guard", "strength", "<", "10"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert_eq!(error_count(&diag, "URD307"), 1);
+    assert_eq!(error_count(&diag, "URD308"), 0);
+}
+
+#[test]
+fn recovery_error_node_among_valid() {
+    let ast = make_file_ast(
+        "tavern.urd.md",
+        None,
+        vec![
+            section("greetings"),
+            error_node("broken stuff"),
+            section("farewell"),
         ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert!(has_error(&diag, "URD314"));
-    // The entity should NOT be added to any location's contains.
-    for (_id, loc) in &linked.symbol_table.locations {
-        assert!(loc.contains.is_empty());
-    }
+    assert!(!diag.has_errors());
+    assert!(linked.symbol_table.sections.contains_key("tavern/greetings"));
+    assert!(linked.symbol_table.sections.contains_key("tavern/farewell"));
 }
 
 #[test]
-fn type_not_visible_uses_suggestion_field() {
-    let ast_types = make_file_ast(
-        "types.urd.md",
-        Some(Frontmatter {
-            entries: vec![FrontmatterEntry {
-                key: "Guard".to_string(),
-                value: make_type_def_in("Guard", "types.urd.md", 2, vec![], vec![]),
-                span: span("types.urd.md", 1),
-            }],
-            span: span("types.urd.md", 1),
-        }),
+fn recovery_duplicate_then_reference() {
+    // Two files declare @guard, then it's referenced. First should win, reference resolves.
+    let ast_b = make_file_ast(
+        "b.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Guard", make_type_def_in("Guard", "b.urd.md", 2, vec![], vec![])),
+            fm_entry("guard", make_entity_decl_in("guard", "Guard", "b.urd.md", 3, vec![])),
+        ])),
         Vec::new(),
     );
-    let ast_main = make_file_ast(
-        "main.urd.md",
-        Some(Frontmatter {
-            entries: vec![FrontmatterEntry {
-                key: "guard".to_string(),
-                value: make_entity_decl_in("guard", "Guard", "main.urd.md", 4, vec![]),
-                span: span("main.urd.md", 1),
-            }],
-            span: span("main.urd.md", 1),
-        }),
-        Vec::new(),
+    let ast_a = make_file_ast(
+        "a.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("guard", make_entity_decl_in("guard", "Guard", "a.urd.md", 2, vec![])),
+        ])),
+        vec![entity_speech("guard", "Halt!")],
     );
-    // No import edge: types.urd.md is NOT imported by main.urd.md.
-    let cu = two_file_cu_no_import(ast_types, ast_main);
+    let cu = two_file_cu(ast_b, ast_a);
     let mut diag = DiagnosticCollector::new();
-    let _linked = link::link(cu, &mut diag);
+    let linked = link::link(cu, &mut diag);
 
-    assert!(has_error(&diag, "URD301"));
-    // The not-visible hint should be in the suggestion field, not the primary message.
-    let diag_301 = diag.all().iter().find(|d| d.code == "URD301").unwrap();
-    assert!(diag_301.suggestion.is_some());
-    assert!(diag_301.suggestion.as_ref().unwrap().contains("not imported"));
-    // Primary message should be concise.
-    assert!(diag_301.message.contains("Unresolved type reference"));
-    assert!(!diag_301.message.contains("not imported"));
+    // URD302 for duplicate.
+    assert!(has_error(&diag, "URD302"));
+    // Reference should still resolve to first declaration (from b.urd.md).
+    if let ContentNode::EntitySpeech(speech) = &linked.graph.nodes["a.urd.md"].ast.content[0] {
+        assert!(speech.annotation.is_some());
+        assert_eq!(speech.annotation.as_ref().unwrap().resolved_entity, Some("guard".to_string()));
+    }
+    // No URD301.
+    assert!(!has_error(&diag, "URD301"));
 }
 
-// ── Container / Destination Resolution Tests ──
+// ═══════════════════════════════════════════════════════════════════
+// 7. Scope & Location Context Tests
+// ═══════════════════════════════════════════════════════════════════
 
 #[test]
-fn containment_check_player_keyword() {
-    // ? @key in player → ContainerKind::KeywordPlayer, no URD301
+fn scope_exit_outside_location_context() {
     let ast = make_file_ast(
         "test.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
-            fm_entry("key", make_entity_decl("key", "Key", vec![])),
-        ])),
+        None,
         vec![
-            containment_check("key", "player", false),
+            exit_decl("north", "Harbor"),
         ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
-    let linked = link::link(cu, &mut diag);
+    let _linked = link::link(cu, &mut diag);
 
-    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
-    let node = linked.graph.nodes.get("test.urd.md").unwrap();
-    if let ContentNode::Condition(cond) = &node.ast.content[0] {
-        if let ConditionExpr::ContainmentCheck(cc) = &cond.expr {
-            let ann = cc.annotation.as_ref().expect("annotation should be set");
-            assert_eq!(ann.container_kind, Some(ContainerKind::KeywordPlayer));
-            assert_eq!(ann.resolved_entity, Some("key".to_string()));
-        } else { panic!("expected ContainmentCheck"); }
-    } else { panic!("expected Condition"); }
+    assert!(has_error(&diag, "URD314"));
 }
 
 #[test]
-fn containment_check_here_keyword() {
-    // ? @key in here → ContainerKind::KeywordHere, no URD301
+fn scope_entity_presence_outside_location() {
     let ast = make_file_ast(
         "test.urd.md",
         Some(make_frontmatter(vec![
-            fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
-            fm_entry("key", make_entity_decl("key", "Key", vec![])),
+            fm_entry("Key", make_type_def("Key", vec![], vec![])),
+            fm_entry("rusty_key", make_entity_decl("rusty_key", "Key", vec![])),
         ])),
         vec![
-            containment_check("key", "here", false),
+            entity_presence(vec!["rusty_key"]),
         ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
-    let linked = link::link(cu, &mut diag);
+    let _linked = link::link(cu, &mut diag);
 
-    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
-    let node = linked.graph.nodes.get("test.urd.md").unwrap();
-    if let ContentNode::Condition(cond) = &node.ast.content[0] {
-        if let ConditionExpr::ContainmentCheck(cc) = &cond.expr {
-            let ann = cc.annotation.as_ref().expect("annotation should be set");
-            assert_eq!(ann.container_kind, Some(ContainerKind::KeywordHere));
-        } else { panic!("expected ContainmentCheck"); }
-    } else { panic!("expected Condition"); }
+    assert!(has_error(&diag, "URD314"));
 }
 
 #[test]
-fn containment_check_entity_container() {
-    // ? @key in @chest → ContainerKind::EntityRef("chest")
+fn scope_explicit_exit_jump_outside_location() {
     let ast = make_file_ast(
         "test.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
-            fm_entry("Container", make_type_def("Container", vec!["container"], vec![])),
-            fm_entry("key", make_entity_decl("key", "Key", vec![])),
-            fm_entry("chest", make_entity_decl("chest", "Container", vec![])),
-        ])),
-        vec![
-            containment_check("key", "chest", false),
-        ],
+        None,
+        vec![exit_jump("north")],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
-    let linked = link::link(cu, &mut diag);
+    let _linked = link::link(cu, &mut diag);
 
-    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
-    let node = linked.graph.nodes.get("test.urd.md").unwrap();
-    if let ContentNode::Condition(cond) = &node.ast.content[0] {
-        if let ConditionExpr::ContainmentCheck(cc) = &cond.expr {
-            let ann = cc.annotation.as_ref().expect("annotation should be set");
-            assert_eq!(ann.container_kind, Some(ContainerKind::EntityRef("chest".to_string())));
-        } else { panic!("expected ContainmentCheck"); }
-    } else { panic!("expected Condition"); }
+    assert!(has_error(&diag, "URD314"));
 }
 
 #[test]
-fn containment_check_location_container() {
-    // ? @key in cellar → ContainerKind::LocationRef("cellar")
+fn scope_location_hook_outside_location() {
     let ast = make_file_ast(
         "test.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
-            fm_entry("key", make_entity_decl("key", "Key", vec![])),
-        ])),
-        vec![
-            location("Cellar"),
-            containment_check("key", "cellar", false),
-        ],
+        None,
+        vec![location_hook(HookKind::Enter, vec![])],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
-    let linked = link::link(cu, &mut diag);
+    let _linked = link::link(cu, &mut diag);
 
-    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
-    let node = linked.graph.nodes.get("test.urd.md").unwrap();
-    // Content: [location, containment_check]
-    if let ContentNode::Condition(cond) = &node.ast.content[1] {
-        if let ConditionExpr::ContainmentCheck(cc) = &cond.expr {
-            let ann = cc.annotation.as_ref().expect("annotation should be set");
-            assert_eq!(ann.container_kind, Some(ContainerKind::LocationRef("cellar".to_string())));
-        } else { panic!("expected ContainmentCheck"); }
-    } else { panic!("expected Condition"); }
+    assert!(has_error(&diag, "URD314"));
 }
 
 #[test]
-fn move_player_destination() {
-    // > move @key -> player → DestinationKind::KeywordPlayer
+fn collect_location_hooks() {
     let ast = make_file_ast(
         "test.urd.md",
         Some(make_frontmatter(vec![
-            fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
-            fm_entry("key", make_entity_decl("key", "Key", vec![])),
+            fm_entry("Lamp", make_type_def("Lamp", vec![], vec![
+                make_property("lit", "boolean"),
+            ])),
+            fm_entry("lamp", make_entity_decl("lamp", "Lamp", vec![])),
         ])),
         vec![
-            move_effect("key", "player"),
+            location("Cellar"),
+            location_hook(HookKind::Enter, vec![
+                property_comparison("lamp", "lit", "==", "false"),
+                set_effect("@lamp.lit", "true"),
+            ]),
+            location_hook(HookKind::Exit, vec![
+                set_effect("@lamp.lit", "false"),
+            ]),
         ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
-    let node = linked.graph.nodes.get("test.urd.md").unwrap();
-    if let ContentNode::Effect(eff) = &node.ast.content[0] {
-        let ann = eff.annotation.as_ref().expect("annotation should be set");
-        assert_eq!(ann.destination_kind, Some(DestinationKind::KeywordPlayer));
-        assert_eq!(ann.resolved_entity, Some("key".to_string()));
-    } else { panic!("expected Effect"); }
+    assert!(!diag.has_errors(), "{:?}", diag.all());
+    let ls = &linked.symbol_table.locations["cellar"];
+    let on_enter = ls.on_enter.as_ref().expect("on_enter should be collected");
+    assert_eq!(on_enter.kind, HookKind::Enter);
+    assert_eq!(on_enter.condition_nodes.len(), 1);
+    assert_eq!(on_enter.condition_nodes[0].node_index, 0);
+    assert_eq!(on_enter.effect_nodes.len(), 1);
+    assert_eq!(on_enter.effect_nodes[0].node_index, 1);
+    let on_exit = ls.on_exit.as_ref().expect("on_exit should be collected");
+    assert!(on_exit.condition_nodes.is_empty());
+    assert_eq!(on_exit.effect_nodes.len(), 1);
 }
 
 #[test]
-fn move_location_destination() {
-    // > move @key -> cellar → DestinationKind::LocationRef("cellar")
+fn resolve_location_hook_effect() {
     let ast = make_file_ast(
         "test.urd.md",
         Some(make_frontmatter(vec![
-            fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
-            fm_entry("key", make_entity_decl("key", "Key", vec![])),
+            fm_entry("Lamp", make_type_def("Lamp", vec![], vec![
+                make_property("lit", "boolean"),
+            ])),
+            fm_entry("lamp", make_entity_decl("lamp", "Lamp", vec![])),
         ])),
         vec![
             location("Cellar"),
-            move_effect("key", "cellar"),
+            location_hook(HookKind::Enter, vec![set_effect("@lamp.lit", "true")]),
         ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
-    let node = linked.graph.nodes.get("test.urd.md").unwrap();
-    // Content: [location, move_effect]
-    if let ContentNode::Effect(eff) = &node.ast.content[1] {
-        let ann = eff.annotation.as_ref().expect("annotation should be set");
-        assert_eq!(ann.destination_kind, Some(DestinationKind::LocationRef("cellar".to_string())));
-    } else { panic!("expected Effect"); }
+    assert!(!diag.has_errors(), "{:?}", diag.all());
+    match &linked.graph.nodes["test.urd.md"].ast.content[1] {
+        ContentNode::LocationHook(hook) => match &hook.children[0] {
+            ContentNode::Effect(eff) => {
+                let ann = eff.annotation.as_ref().expect("effect should be annotated");
+                assert_eq!(ann.resolved_entity, Some("lamp".to_string()));
+                assert_eq!(ann.resolved_property, Some("lit".to_string()));
+            }
+            other => panic!("expected Effect, got {:?}", other),
+        },
+        other => panic!("expected LocationHook, got {:?}", other),
+    }
 }
 
-// ═══════════════════════════════════════════════════════════════════
-// 9. Built-in Jump Targets (-> end)
-// ═══════════════════════════════════════════════════════════════════
-
 #[test]
-fn resolve_builtin_end_jump() {
-    // -> end should resolve without error. Annotation has no resolved_section or resolved_location.
+fn resolve_location_hook_unknown_entity() {
     let ast = make_file_ast(
         "test.urd.md",
         None,
-        vec![jump("end")],
+        vec![
+            location("Cellar"),
+            location_hook(HookKind::Exit, vec![set_effect("@lantern.lit", "false")]),
+        ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
-    let linked = link::link(cu, &mut diag);
+    let _linked = link::link(cu, &mut diag);
 
-    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
-    if let ContentNode::Jump(j) = &linked.graph.nodes["test.urd.md"].ast.content[0] {
-        let ann = j.annotation.as_ref().expect("annotation should be set for -> end");
-        assert_eq!(ann.resolved_section, None);
-        assert_eq!(ann.resolved_location, None);
-    } else {
-        panic!("expected Jump");
-    }
+    assert!(has_error(&diag, "URD301"));
 }
 
 #[test]
-fn resolve_non_builtin_still_errors() {
-    // -> ending should NOT be treated as a built-in (exact match only).
+fn duplicate_location_hook() {
     let ast = make_file_ast(
         "test.urd.md",
         None,
-        vec![jump("ending")],
+        vec![
+            location("Cellar"),
+            location_hook(HookKind::Enter, vec![]),
+            location_hook(HookKind::Exit, vec![]),
+            location_hook(HookKind::Enter, vec![]),
+        ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
-    let _linked = link::link(cu, &mut diag);
+    let linked = link::link(cu, &mut diag);
 
-    assert!(has_error(&diag, "URD309"));
+    assert_eq!(error_count(&diag, "URD315"), 1);
+    assert!(linked.symbol_table.locations["cellar"].on_enter.is_some());
 }
 
 #[test]
-fn resolve_builtin_end_shadows_section() {
-    // If a section named "end" exists, -> end still resolves as built-in terminal
-    // but emits URD431 shadowing warning.
-    let ast = make_file_ast(
-        "test.urd.md",
+fn scope_cross_file_with_import() {
+    let ast_b = make_file_ast(
+        "types.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Guard", make_type_def_in("Guard", "types.urd.md", 2, vec![], vec![])),
+            fm_entry("guard", make_entity_decl_in("guard", "Guard", "types.urd.md", 3, vec![])),
+        ])),
+        Vec::new(),
+    );
+    let ast_a = make_file_ast(
+        "main.urd.md",
         None,
-        vec![
-            section("end"),
-            jump("end"),
-        ],
+        vec![entity_speech("guard", "Hello")],
     );
-    let cu = single_file_cu(ast);
+    let cu = two_file_cu(ast_b, ast_a);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
-    assert!(has_warning(&diag, "URD431"));
-    // Built-in wins — annotation has no resolved_section.
-    if let ContentNode::Jump(j) = &linked.graph.nodes["test.urd.md"].ast.content[1] {
-        let ann = j.annotation.as_ref().expect("annotation should be set");
-        assert_eq!(ann.resolved_section, None, "built-in should win, not section");
-        assert_eq!(ann.resolved_location, None);
-    } else {
-        panic!("expected Jump");
+    assert!(!diag.has_errors());
+    if let ContentNode::EntitySpeech(speech) = &linked.graph.nodes["main.urd.md"].ast.content[0] {
+        assert_eq!(speech.annotation.as_ref().unwrap().resolved_entity, Some("guard".to_string()));
     }
 }
 
 #[test]
-fn resolve_builtin_end_inside_location() {
-    // -> end inside a location context should still resolve as built-in.
+fn scope_cross_file_without_import() {
+    let ast_b = make_file_ast(
+        "npcs.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Guard", make_type_def_in("Guard", "npcs.urd.md", 2, vec![], vec![])),
+            fm_entry("guard", make_entity_decl_in("guard", "Guard", "npcs.urd.md", 3, vec![])),
+        ])),
+        Vec::new(),
+    );
+    let ast_a = make_file_ast(
+        "main.urd.md",
+        None,
+        vec![entity_speech("guard", "Hello")],
+    );
+    let cu = two_file_cu_no_import(ast_b, ast_a);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD301"));
+}
+
+#[test]
+fn scope_forward_reference_within_file() {
+    // Entity referenced before declaration in same file. Should resolve.
     let ast = make_file_ast(
         "test.urd.md",
-        None,
+        Some(make_frontmatter(vec![
+            fm_entry("Guard", make_type_def("Guard", vec![], vec![])),
+            fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+        ])),
         vec![
-            location("Cell"),
-            jump("end"),
+            entity_speech("guard", "Halt!"),
         ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
-    if let ContentNode::Jump(j) = &linked.graph.nodes["test.urd.md"].ast.content[1] {
-        let ann = j.annotation.as_ref().expect("annotation should be set");
-        assert_eq!(ann.resolved_section, None);
-        assert_eq!(ann.resolved_location, None);
-    } else {
-        panic!("expected Jump");
+    assert!(!diag.has_errors());
+    if let ContentNode::EntitySpeech(speech) = &linked.graph.nodes["test.urd.md"].ast.content[0] {
+        assert!(speech.annotation.is_some());
     }
 }
 
 // ═══════════════════════════════════════════════════════════════════
-// 10. Implicit Properties (.container)
+// Additional Tests
 // ═══════════════════════════════════════════════════════════════════
 
 #[test]
-fn implicit_container_in_condition() {
-    // ? @key.container != player — should resolve without URD308.
+fn resolve_exhaustion_check() {
     let ast = make_file_ast(
-        "test.urd.md",
-        Some(make_frontmatter(vec![
-            fm_entry("Item", make_type_def("Item", vec!["portable"], vec![])),
-            fm_entry("key", make_entity_decl("key", "Item", vec![])),
-        ])),
+        "tavern.urd.md",
+        None,
         vec![
-            property_comparison("key", "container", "!=", "player"),
+            section("topics"),
+            exhaustion_check("topics"),
         ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
-    if let ContentNode::Condition(cond) = &linked.graph.nodes["test.urd.md"].ast.content[0] {
-        if let ConditionExpr::PropertyComparison(pc) = &cond.expr {
-            let ann = pc.annotation.as_ref().expect("annotation should be set");
-            assert_eq!(ann.resolved_entity, Some("key".to_string()));
-            assert_eq!(ann.resolved_property, Some("container".to_string()));
-            assert_eq!(ann.resolved_type, Some("Item".to_string()));
-        } else { panic!("expected PropertyComparison"); }
-    } else { panic!("expected Condition"); }
+    assert!(!diag.has_errors());
+    if let ContentNode::Condition(cond) = &linked.graph.nodes["tavern.urd.md"].ast.content[1] {
+        if let ConditionExpr::ExhaustionCheck(ec) = &cond.expr {
+            assert!(ec.annotation.is_some());
+            assert_eq!(ec.annotation.as_ref().unwrap().resolved_section, Some("tavern/topics".to_string()));
+        }
+    }
 }
 
 #[test]
-fn implicit_container_in_effect() {
-    // > @key.container = player — should resolve without URD308.
+fn resolve_set_effect() {
     let ast = make_file_ast(
         "test.urd.md",
         Some(make_frontmatter(vec![
-            fm_entry("Item", make_type_def("Item", vec!["portable"], vec![])),
-            fm_entry("key", make_entity_decl("key", "Item", vec![])),
+            fm_entry("Guard", make_type_def("Guard", vec![], vec![
+                make_property("mood", "string"),
+            ])),
+            fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
         ])),
         vec![
-            set_effect("@key.container", "player"),
+            set_effect("@guard.mood", "happy"),
         ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
     let linked = link::link(cu, &mut diag);
 
-    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
+    assert!(!diag.has_errors());
     if let ContentNode::Effect(eff) = &linked.graph.nodes["test.urd.md"].ast.content[0] {
-        let ann = eff.annotation.as_ref().expect("annotation should be set");
-        assert_eq!(ann.resolved_entity, Some("key".to_string()));
-        assert_eq!(ann.resolved_property, Some("container".to_string()));
-    } else { panic!("expected Effect"); }
+        assert!(eff.annotation.is_some());
+        let ann = eff.annotation.as_ref().unwrap();
+        assert_eq!(ann.resolved_entity, Some("guard".to_string()));
+        assert_eq!(ann.resolved_property, Some("mood".to_string()));
+    }
 }
 
 #[test]
-fn implicit_container_in_entity_override() {
-    // Entity with container override in frontmatter — should not URD308.
+fn resolve_move_effect() {
     let ast = make_file_ast(
         "test.urd.md",
         Some(make_frontmatter(vec![
-            fm_entry("Item", make_type_def("Item", vec!["portable"], vec![])),
-            fm_entry("key", make_entity_decl("key", "Item", vec![
-                ("container", Scalar::String("player".to_string())),
-            ])),
+            fm_entry("Item", make_type_def("Item", vec![], vec![])),
+            fm_entry("Container", make_type_def("Container", vec![], vec![])),
+            fm_entry("key", make_entity_decl("key", "Item", vec![])),
+            fm_entry("chest", make_entity_decl("chest", "Container", vec![])),
         ])),
-        Vec::new(),
+        vec![
+            move_effect("key", "chest"),
+        ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
-    let _linked = link::link(cu, &mut diag);
+    let linked = link::link(cu, &mut diag);
 
-    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
+    assert!(!diag.has_errors());
+    if let ContentNode::Effect(eff) = &linked.graph.nodes["test.urd.md"].ast.content[0] {
+        assert!(eff.annotation.is_some());
+        assert_eq!(eff.annotation.as_ref().unwrap().resolved_entity, Some("key".to_string()));
+    }
 }
 
 #[test]
-fn non_implicit_property_still_errors() {
-    // ? @key.nonexistent != player — should still produce URD308.
+fn resolve_destroy_effect() {
     let ast = make_file_ast(
         "test.urd.md",
         Some(make_frontmatter(vec![
-            fm_entry("Item", make_type_def("Item", vec!["portable"], vec![])),
+            fm_entry("Item", make_type_def("Item", vec![], vec![])),
             fm_entry("key", make_entity_decl("key", "Item", vec![])),
         ])),
         vec![
-            property_comparison("key", "nonexistent", "!=", "player"),
+            destroy_effect("key"),
         ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
-    let _linked = link::link(cu, &mut diag);
+    let linked = link::link(cu, &mut diag);
 
-    assert!(has_error(&diag, "URD308"));
+    assert!(!diag.has_errors());
+    if let ContentNode::Effect(eff) = &linked.graph.nodes["test.urd.md"].ast.content[0] {
+        assert!(eff.annotation.is_some());
+        assert_eq!(eff.annotation.as_ref().unwrap().resolved_entity, Some("key".to_string()));
+    }
 }
 
 #[test]
-fn near_miss_implicit_property_still_errors() {
-    // ? @key.containers != player — "containers" is not "container", should URD308.
+fn resolve_containment_check() {
     let ast = make_file_ast(
         "test.urd.md",
         Some(make_frontmatter(vec![
-            fm_entry("Item", make_type_def("Item", vec!["portable"], vec![])),
+            fm_entry("Item", make_type_def("Item", vec![], vec![])),
+            fm_entry("Container", make_type_def("Container", vec![], vec![])),
             fm_entry("key", make_entity_decl("key", "Item", vec![])),
+            fm_entry("chest", make_entity_decl("chest", "Container", vec![])),
         ])),
         vec![
-            property_comparison("key", "containers", "!=", "player"),
+            containment_check("key", "chest", false),
         ],
     );
     let cu = single_file_cu(ast);
     let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(!diag.has_errors());
+    if let ContentNode::Condition(cond) = &linked.graph.nodes["test.urd.md"].ast.content[0] {
+        if let ConditionExpr::ContainmentCheck(cc) = &cond.expr {
+            assert!(cc.annotation.is_some());
+            assert_eq!(cc.annotation.as_ref().unwrap().resolved_entity, Some("key".to_string()));
+        }
+    }
+}
+
+#[test]
+fn resolve_unknown_type_with_suggestion() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Guard", make_type_def("Guard", vec![], vec![])),
+            fm_entry("guard", make_entity_decl("guard", "Gurad", vec![])),
+        ])),
+        Vec::new(),
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD307"));
+    assert!(has_suggestion(&diag, "URD307"));
+}
+
+#[test]
+fn resolve_unknown_property() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Guard", make_type_def("Guard", vec![], vec![
+                make_property("mood", "string"),
+            ])),
+            fm_entry("guard", make_entity_decl("guard", "Guard", vec![
+                ("nonexistent", Scalar::String("value".to_string())),
+            ])),
+        ])),
+        Vec::new(),
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
     let _linked = link::link(cu, &mut diag);
 
     assert!(has_error(&diag, "URD308"));
 }
+
+#[test]
+fn resolve_unresolved_explicit_exit() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![
+            location("Cell"),
+            exit_jump("west"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD311"));
+}
+
+#[test]
+fn collect_sequence_and_phase() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![
+            sequence_heading("Morning Routine"),
+            phase_heading("Wake Up", false),
+            phase_heading("Breakfast", true),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(!diag.has_errors());
+    assert!(linked.symbol_table.sequences.contains_key("morning-routine"));
+    let seq = &linked.symbol_table.sequences["morning-routine"];
+    assert_eq!(seq.phases.len(), 2);
+    assert_eq!(seq.phases[0].id, "wake-up");
+    assert_eq!(seq.phases[0].advance, "manual");
+    assert_eq!(seq.phases[1].id, "breakfast");
+    assert_eq!(seq.phases[1].advance, "auto");
+}
+
+#[test]
+fn edit_distance_basic() {
+    // Verify the edit distance function directly.
+    assert_eq!(urd_compiler::link::edit_distance("guard", "gaurd"), 2);
+    assert_eq!(urd_compiler::link::edit_distance("guard", "guard"), 0);
+    assert_eq!(urd_compiler::link::edit_distance("guard", "gurd"), 1);
+    assert_eq!(urd_compiler::link::edit_distance("", "abc"), 3);
+    assert_eq!(urd_compiler::link::edit_distance("abc", ""), 3);
+}
+
+// ═══════════════════════════════════════════════════════════════════
+// 8. Compliance Fixes – Regression Tests
+// ═══════════════════════════════════════════════════════════════════
+
+#[test]
+fn world_config_start_and_entry_stored() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("start", Scalar::String("Grand Hall".to_string())),
+                ("entry", Scalar::String("morning-routine".to_string())),
+            ])),
+        ])),
+        vec![
+            location("Grand Hall"),
+            sequence_heading("Morning Routine"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(!diag.has_errors());
+    assert_eq!(linked.symbol_table.world_start, Some("grand-hall".to_string()));
+    assert_eq!(linked.symbol_table.world_entry, Some("morning-routine".to_string()));
+}
+
+#[test]
+fn world_config_unresolved_not_stored() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("world", make_world_block(vec![
+                ("start", Scalar::String("Nonexistent".to_string())),
+            ])),
+        ])),
+        Vec::new(),
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert_eq!(linked.symbol_table.world_start, None);
+}
+
+#[test]
+fn duplicate_sequence_detected() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![
+            sequence_heading("Morning Routine"),
+            phase_heading("Phase A", false),
+            sequence_heading("Morning Routine"),
+            phase_heading("Phase B", false),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD313")); // Duplicate sequence
+    assert_eq!(linked.symbol_table.duplicates.iter().filter(|d| d.namespace == "sequences").count(), 1);
+    // First declaration wins — only one entry in the sequences map.
+    assert_eq!(linked.symbol_table.sequences.len(), 1);
+    let seq = &linked.symbol_table.sequences["morning-routine"];
+    // Both phases attach to the original sequence since current_sequence_id is set
+    // to the same ID after the duplicate heading.
+    assert_eq!(seq.phases.len(), 2);
+    assert_eq!(seq.phases[0].id, "phase-a");
+    assert_eq!(seq.phases[1].id, "phase-b");
+}
+
+#[test]
+fn duplicate_phase_id_detected() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![
+            sequence_heading("Morning Routine"),
+            phase_heading("Wake Up", false),
+            ContentNode::PhaseHeading(PhaseHeading {
+                display_name: "Wake up!".to_string(),
+                auto: true,
+                section: None,
+                span: span("test.urd.md", 60),
+            }),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    let d = diag.all().iter().find(|d| d.code == "URD320").expect("Expected URD320");
+    assert_eq!(d.span.start_line, 60);
+    assert_eq!(
+        d.message,
+        "Duplicate phase ID 'wake-up' in sequence 'morning-routine' — phase 'Wake up!' slugifies to the same ID as the phase at line 56."
+    );
+    assert_eq!(linked.symbol_table.duplicates.iter().filter(|d| d.namespace == "phases").count(), 1);
+    // First declaration wins.
+    let seq = &linked.symbol_table.sequences["morning-routine"];
+    assert_eq!(seq.phases.len(), 1);
+    assert_eq!(seq.phases[0].advance, "manual");
+}
+
+#[test]
+fn same_phase_name_in_different_sequences() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![
+            sequence_heading("Morning"),
+            phase_heading("Wake Up", false),
+            sequence_heading("Evening"),
+            phase_heading("Wake Up", false),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    link::link(cu, &mut diag);
+
+    assert!(!has_error(&diag, "URD320"), "Phase IDs are per sequence: {:?}", diag.all());
+}
+
+#[test]
+fn duplicate_rule_detected() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![
+            ContentNode::RuleBlock(RuleBlock {
+                name: "patrol".to_string(),
+                actor: "guard".to_string(),
+                trigger: "idle".to_string(),
+                select: None,
+                where_clauses: Vec::new(),
+                effects: Vec::new(),
+                indent_level: 0,
+                span: span("test.urd.md", 10),
+            }),
+            ContentNode::RuleBlock(RuleBlock {
+                name: "patrol".to_string(),
+                actor: "knight".to_string(),
+                trigger: "idle".to_string(),
+                select: None,
+                where_clauses: Vec::new(),
+                effects: Vec::new(),
+                indent_level: 0,
+                span: span("test.urd.md", 20),
+            }),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD302")); // Duplicate rule
+    assert_eq!(linked.symbol_table.duplicates.iter().filter(|d| d.namespace == "rules").count(), 1);
+    // First declaration wins.
+    let rule = &linked.symbol_table.rules["patrol"];
+    assert_eq!(rule.actor, "guard");
+}
+
+fn rule_at(name: &str, indent_level: usize, line: u32) -> ContentNode {
+    ContentNode::RuleBlock(RuleBlock {
+        name: name.to_string(),
+        actor: "guard".to_string(),
+        trigger: "always".to_string(),
+        select: None,
+        where_clauses: Vec::new(),
+        effects: Vec::new(),
+        indent_level,
+        span: span("test.urd.md", line),
+    })
+}
+
+#[test]
+fn indented_rule_under_location_is_scoped() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![location("Tavern"), rule_at("last_orders", 1, 12), rule_at("weather", 0, 20)],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    let rules = &linked.symbol_table.rules;
+    assert_eq!(rules["last_orders"].scope_location.as_deref(), Some("tavern"));
+    // Column 0 after the last heading is still global.
+    assert_eq!(rules["weather"].scope_location, None);
+}
+
+#[test]
+fn indented_rule_before_any_location_is_global() {
+    let ast = make_file_ast("test.urd.md", None, vec![rule_at("patrol", 1, 5)]);
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+    assert_eq!(linked.symbol_table.rules["patrol"].scope_location, None);
+}
+
+#[test]
+fn scoped_rule_name_collides_with_global_rule() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![rule_at("patrol", 0, 5), location("Tavern"), rule_at("patrol", 1, 12)],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD302"));
+    assert_eq!(linked.symbol_table.rules["patrol"].scope_location, None);
+}
+
+#[test]
+fn entity_presence_outside_location_returns_early() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Item", make_type_def("Item", vec![], vec![])),
+            fm_entry("key", make_entity_decl("key", "Item", vec![])),
+        ])),
+        vec![
+            // EntityPresence before any location heading.
+            entity_presence(vec!["key"]),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD314"));
+    // The entity should NOT be added to any location's contains.
+    for (_id, loc) in &linked.symbol_table.locations {
+        assert!(loc.contains.is_empty());
+    }
+}
+
+#[test]
+fn type_not_visible_uses_suggestion_field() {
+    let ast_types = make_file_ast(
+        "types.urd.md",
+        Some(Frontmatter {
+            entries: vec![FrontmatterEntry {
+                key: "Guard".to_string(),
+                value: make_type_def_in("Guard", "types.urd.md", 2, vec![], vec![]),
+                span: span("types.urd.md", 1),
+            }],
+            span: span("types.urd.md", 1),
+        }),
+        Vec::new(),
+    );
+    let ast_main = make_file_ast(
+        "main.urd.md",
+        Some(Frontmatter {
+            entries: vec![FrontmatterEntry {
+                key: "guard".to_string(),
+                value: make_entity_decl_in("guard", "Guard", "main.urd.md", 4, vec![]),
+                span: span("main.urd.md", 1),
+            }],
+            span: span("main.urd.md", 1),
+        }),
+        Vec::new(),
+    );
+    // No import edge: types.urd.md is NOT imported by main.urd.md.
+    let cu = two_file_cu_no_import(ast_types, ast_main);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD301"));
+    // The not-visible hint should be in the suggestion field, not the primary message.
+    let diag_301 = diag.all().iter().find(|d| d.code == "URD301").unwrap();
+    assert!(diag_301.suggestion.is_some());
+    assert!(diag_301.suggestion.as_ref().unwrap().contains("not imported"));
+    // Primary message should be concise.
+    assert!(diag_301.message.contains("Unresolved type reference"));
+    assert!(!diag_301.message.contains("not imported"));
+}
+
+// ── Container / Destination Resolution Tests ──
+
+#[test]
+fn containment_check_player_keyword() {
+    // ? @key in player → ContainerKind::KeywordPlayer, no URD301
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
+            fm_entry("key", make_entity_decl("key", "Key", vec![])),
+        ])),
+        vec![
+            containment_check("key", "player", false),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
+    let node = linked.graph.nodes.get("test.urd.md").unwrap();
+    if let ContentNode::Condition(cond) = &node.ast.content[0] {
+        if let ConditionExpr::ContainmentCheck(cc) = &cond.expr {
+            let ann = cc.annotation.as_ref().expect("annotation should be set");
+            assert_eq!(ann.container_kind, Some(ContainerKind::KeywordPlayer));
+            assert_eq!(ann.resolved_entity, Some("key".to_string()));
+        } else { panic!("expected ContainmentCheck"); }
+    } else { panic!("expected Condition"); }
+}
+
+#[test]
+fn containment_check_here_keyword() {
+    // ? @key in here → ContainerKind::KeywordHere, no URD301
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
+            fm_entry("key", make_entity_decl("key", "Key", vec![])),
+        ])),
+        vec![
+            containment_check("key", "here", false),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
+    let node = linked.graph.nodes.get("test.urd.md").unwrap();
+    if let ContentNode::Condition(cond) = &node.ast.content[0] {
+        if let ConditionExpr::ContainmentCheck(cc) = &cond.expr {
+            let ann = cc.annotation.as_ref().expect("annotation should be set");
+            assert_eq!(ann.container_kind, Some(ContainerKind::KeywordHere));
+        } else { panic!("expected ContainmentCheck"); }
+    } else { panic!("expected Condition"); }
+}
+
+#[test]
+fn containment_check_entity_container() {
+    // ? @key in @chest → ContainerKind::EntityRef("chest")
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
+            fm_entry("Container", make_type_def("Container", vec!["container"], vec![])),
+            fm_entry("key", make_entity_decl("key", "Key", vec![])),
+            fm_entry("chest", make_entity_decl("chest", "Container", vec![])),
+        ])),
+        vec![
+            containment_check("key", "chest", false),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
+    let node = linked.graph.nodes.get("test.urd.md").unwrap();
+    if let ContentNode::Condition(cond) = &node.ast.content[0] {
+        if let ConditionExpr::ContainmentCheck(cc) = &cond.expr {
+            let ann = cc.annotation.as_ref().expect("annotation should be set");
+            assert_eq!(ann.container_kind, Some(ContainerKind::EntityRef("chest".to_string())));
+        } else { panic!("expected ContainmentCheck"); }
+    } else { panic!("expected Condition"); }
+}
+
+#[test]
+fn containment_check_location_container() {
+    // ? @key in cellar → ContainerKind::LocationRef("cellar")
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
+            fm_entry("key", make_entity_decl("key", "Key", vec![])),
+        ])),
+        vec![
+            location("Cellar"),
+            containment_check("key", "cellar", false),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
+    let node = linked.graph.nodes.get("test.urd.md").unwrap();
+    // Content: [location, containment_check]
+    if let ContentNode::Condition(cond) = &node.ast.content[1] {
+        if let ConditionExpr::ContainmentCheck(cc) = &cond.expr {
+            let ann = cc.annotation.as_ref().expect("annotation should be set");
+            assert_eq!(ann.container_kind, Some(ContainerKind::LocationRef("cellar".to_string())));
+        } else { panic!("expected ContainmentCheck"); }
+    } else { panic!("expected Condition"); }
+}
+
+#[test]
+fn move_player_destination() {
+    // > move @key -> player → DestinationKind::KeywordPlayer
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
+            fm_entry("key", make_entity_decl("key", "Key", vec![])),
+        ])),
+        vec![
+            move_effect("key", "player"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
+    let node = linked.graph.nodes.get("test.urd.md").unwrap();
+    if let ContentNode::Effect(eff) = &node.ast.content[0] {
+        let ann = eff.annotation.as_ref().expect("annotation should be set");
+        assert_eq!(ann.destination_kind, Some(DestinationKind::KeywordPlayer));
+        assert_eq!(ann.resolved_entity, Some("key".to_string()));
+    } else { panic!("expected Effect"); }
+}
+
+#[test]
+fn move_location_destination() {
+    // > move @key -> cellar → DestinationKind::LocationRef("cellar")
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
+            fm_entry("key", make_entity_decl("key", "Key", vec![])),
+        ])),
+        vec![
+            location("Cellar"),
+            move_effect("key", "cellar"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
+    let node = linked.graph.nodes.get("test.urd.md").unwrap();
+    // Content: [location, move_effect]
+    if let ContentNode::Effect(eff) = &node.ast.content[1] {
+        let ann = eff.annotation.as_ref().expect("annotation should be set");
+        assert_eq!(ann.destination_kind, Some(DestinationKind::LocationRef("cellar".to_string())));
+    } else { panic!("expected Effect"); }
+}
+
+// ═══════════════════════════════════════════════════════════════════
+// 9. Built-in Jump Targets (-> end)
+// ═══════════════════════════════════════════════════════════════════
+
+#[test]
+fn resolve_builtin_end_jump() {
+    // -> end should resolve without error. Annotation has no resolved_section or resolved_location.
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![jump("end")],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
+    if let ContentNode::Jump(j) = &linked.graph.nodes["test.urd.md"].ast.content[0] {
+        let ann = j.annotation.as_ref().expect("annotation should be set for -> end");
+        assert_eq!(ann.resolved_section, None);
+        assert_eq!(ann.resolved_location, None);
+    } else {
+        panic!("expected Jump");
+    }
+}
+
+#[test]
+fn resolve_non_builtin_still_errors() {
+    // -> ending should NOT be treated as a built-in (exact match only).
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![jump("ending")],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD309"));
+}
+
+#[test]
+fn resolve_builtin_end_shadows_section() {
+    // If a section named "end" exists, -> end still resolves as built-in terminal
+    // but emits URD431 shadowing warning.
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![
+            section("end"),
+            jump("end"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
+    assert!(has_warning(&diag, "URD431"));
+    // Built-in wins — annotation has no resolved_section.
+    if let ContentNode::Jump(j) = &linked.graph.nodes["test.urd.md"].ast.content[1] {
+        let ann = j.annotation.as_ref().expect("annotation should be set");
+        assert_eq!(ann.resolved_section, None, "built-in should win, not section");
+        assert_eq!(ann.resolved_location, None);
+    } else {
+        panic!("expected Jump");
+    }
+}
+
+#[test]
+fn resolve_builtin_end_inside_location() {
+    // -> end inside a location context should still resolve as built-in.
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![
+            location("Cell"),
+            jump("end"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
+    if let ContentNode::Jump(j) = &linked.graph.nodes["test.urd.md"].ast.content[1] {
+        let ann = j.annotation.as_ref().expect("annotation should be set");
+        assert_eq!(ann.resolved_section, None);
+        assert_eq!(ann.resolved_location, None);
+    } else {
+        panic!("expected Jump");
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════
+// 10. Implicit Properties (.container)
+// ═══════════════════════════════════════════════════════════════════
+
+#[test]
+fn implicit_container_in_condition() {
+    // ? @key.container != player — should resolve without URD308.
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Item", make_type_def("Item", vec!["portable"], vec![])),
+            fm_entry("key", make_entity_decl("key", "Item", vec![])),
+        ])),
+        vec![
+            property_comparison("key", "container", "!=", "player"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
+    if let ContentNode::Condition(cond) = &linked.graph.nodes["test.urd.md"].ast.content[0] {
+        if let ConditionExpr::PropertyComparison(pc) = &cond.expr {
+            let ann = pc.annotation.as_ref().expect("annotation should be set");
+            assert_eq!(ann.resolved_entity, Some("key".to_string()));
+            assert_eq!(ann.resolved_property, Some("container".to_string()));
+            assert_eq!(ann.resolved_type, Some("Item".to_string()));
+        } else { panic!("expected PropertyComparison"); }
+    } else { panic!("expected Condition"); }
+}
+
+#[test]
+fn implicit_container_in_effect() {
+    // > @key.container = player — should resolve without URD308.
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Item", make_type_def("Item", vec!["portable"], vec![])),
+            fm_entry("key", make_entity_decl("key", "Item", vec![])),
+        ])),
+        vec![
+            set_effect("@key.container", "player"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
+    if let ContentNode::Effect(eff) = &linked.graph.nodes["test.urd.md"].ast.content[0] {
+        let ann = eff.annotation.as_ref().expect("annotation should be set");
+        assert_eq!(ann.resolved_entity, Some("key".to_string()));
+        assert_eq!(ann.resolved_property, Some("container".to_string()));
+    } else { panic!("expected Effect"); }
+}
+
+#[test]
+fn implicit_container_in_entity_override() {
+    // Entity with container override in frontmatter — should not URD308.
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Item", make_type_def("Item", vec!["portable"], vec![])),
+            fm_entry("key", make_entity_decl("key", "Item", vec![
+                ("container", Scalar::String("player".to_string())),
+            ])),
+        ])),
+        Vec::new(),
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
+}
+
+#[test]
+fn non_implicit_property_still_errors() {
+    // ? @key.nonexistent != player — should still produce URD308.
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Item", make_type_def("Item", vec!["portable"], vec![])),
+            fm_entry("key", make_entity_decl("key", "Item", vec![])),
+        ])),
+        vec![
+            property_comparison("key", "nonexistent", "!=", "player"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD308"));
+}
+
+#[test]
+fn near_miss_implicit_property_still_errors() {
+    // ? @key.containers != player — "containers" is not "container", should URD308.
+    let ast = make_file_ast(
+        "test.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Item", make_type_def("Item", vec!["portable"], vec![])),
+            fm_entry("key", make_entity_decl("key", "Item", vec![])),
+        ])),
+        vec![
+            property_comparison("key", "containers", "!=", "player"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD308"));
+}
+
+fn guard_frontmatter(properties: &[&str]) -> Frontmatter {
+    let properties = properties.iter().map(|p| make_property(p, "integer")).collect();
+    make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], properties)),
+        fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+    ])
+}
+
+fn link_content(frontmatter: Frontmatter, content: Vec<ContentNode>) -> DiagnosticCollector {
+    let cu = single_file_cu(make_file_ast("test.urd.md", Some(frontmatter), content));
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+    diag
+}
+
+#[test]
+fn property_typo_suggests_property_of_entity_type() {
+    // A `mood` entity must not be offered: properties come from the type.
+    let mut frontmatter = guard_frontmatter(&["mood", "trust", "health"]);
+    frontmatter.entries.push(fm_entry("mood", make_entity_decl("mood", "Guard", vec![])));
+    let diag = link_content(frontmatter, vec![property_comparison("guard", "moood", "==", "3")]);
+
+    assert_eq!(error_count(&diag, "URD308"), 1);
+    let d = diag.all().iter().find(|d| d.code == "URD308").unwrap();
+    assert_eq!(d.message, "Property 'moood' does not exist on type 'Guard'.");
+    assert_eq!(d.suggestion.as_deref(), Some("Did you mean 'mood'?"));
+}
+
+#[test]
+fn unknown_property_lists_properties_of_type() {
+    let diag = link_content(
+        guard_frontmatter(&["mood", "trust", "health"]),
+        vec![property_comparison("guard", "rank", "==", "3")],
+    );
+    assert_eq!(suggestion_for(&diag, "URD308").as_deref(), Some("Type 'Guard' has: mood, trust, health."));
+
+    let diag = link_content(
+        guard_frontmatter(&["mood", "trust", "health", "rank", "post", "shift", "wage"]),
+        vec![property_comparison("guard", "alertness", "==", "3")],
+    );
+    assert_eq!(
+        suggestion_for(&diag, "URD308").as_deref(),
+        Some("Type 'Guard' has: mood, trust, health, rank, post, and 2 more."),
+    );
+}
+
+#[test]
+fn unknown_property_near_implicit_property_suggests_it() {
+    let diag = link_content(guard_frontmatter(&["mood"]), vec![property_comparison("guard", "containers", "!=", "player")]);
+    assert_eq!(suggestion_for(&diag, "URD308").as_deref(), Some("Did you mean 'container'?"));
+}
+
+#[test]
+fn effect_property_typo_suggests_property_of_entity_type() {
+    let diag = link_content(guard_frontmatter(&["mood", "trust"]), vec![set_effect("@guard.trsut", "1")]);
+    assert_eq!(error_count(&diag, "URD308"), 1);
+    assert_eq!(suggestion_for(&diag, "URD308").as_deref(), Some("Did you mean 'trust'?"));
+}
+
+#[test]
+fn override_property_typo_suggests_property_of_type() {
+    let frontmatter = make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![make_property("mood", "integer")])),
+        fm_entry("guard", make_entity_decl("guard", "Guard", vec![("mod", Scalar::Integer(2))])),
+    ]);
+    let diag = link_content(frontmatter, vec![]);
+    assert_eq!(suggestion_for(&diag, "URD308").as_deref(), Some("Did you mean 'mood'?"));
+}
+
+// ═══════════════════════════════════════════════════════════════════
+// 11. Parameterised Sections
+// ═══════════════════════════════════════════════════════════════════
+
+fn merchant_frontmatter() -> Option<Frontmatter> {
+    Some(make_frontmatter(vec![
+        fm_entry("Merchant", make_type_def("Merchant", vec![], vec![make_property("price", "integer")])),
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![])),
+        fm_entry("harbor_master", make_entity_decl("harbor_master", "Merchant", vec![])),
+        fm_entry("captain", make_entity_decl("captain", "Guard", vec![])),
+    ]))
+}
+
+#[test]
+fn template_param_resolves_against_declared_type() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        merchant_frontmatter(),
+        vec![
+            param_section("haggle", "merchant", "Merchant"),
+            property_comparison("merchant", "price", ">", "10"),
+            set_effect("@merchant.price", "5"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(!has_error(&diag, "URD301"), "Parameter should not need an entity: {:?}", diag.all());
+    assert!(!has_error(&diag, "URD308"), "Property should resolve on Merchant: {:?}", diag.all());
+    let content = &linked.graph.nodes["test.urd.md"].ast.content;
+    if let ContentNode::Condition(cond) = &content[1] {
+        if let ConditionExpr::PropertyComparison(pc) = &cond.expr {
+            let ann = pc.annotation.as_ref().expect("annotation should be set");
+            assert_eq!(ann.resolved_entity, Some("merchant".to_string()));
+            assert_eq!(ann.resolved_type, Some("Merchant".to_string()));
+            assert_eq!(ann.resolved_property, Some("price".to_string()));
+        } else { panic!("expected PropertyComparison"); }
+    } else { panic!("expected Condition"); }
+    let sec = &linked.symbol_table.sections["test/haggle"];
+    assert_eq!(sec.param.as_ref().map(|p| p.type_name.as_str()), Some("Merchant"));
+}
+
+#[test]
+fn template_param_unknown_property() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        merchant_frontmatter(),
+        vec![
+            param_section("haggle", "merchant", "Merchant"),
+            property_comparison("merchant", "mood", "==", "happy"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD308"));
+}
+
+#[test]
+fn template_param_unknown_type() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        merchant_frontmatter(),
+        vec![
+            param_section("haggle", "merchant", "Merchent"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD307"));
+    assert!(has_suggestion(&diag, "URD307"));
+}
+
+#[test]
+fn template_param_scope_ends_at_next_section() {
+    // `merchant` is only bound inside haggle.
+    let ast = make_file_ast(
+        "test.urd.md",
+        merchant_frontmatter(),
+        vec![
+            param_section("haggle", "merchant", "Merchant"),
+            section("farewell"),
+            property_comparison("merchant", "price", ">", "10"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD301"));
+}
+
+#[test]
+fn jump_with_argument_records_invocation() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        merchant_frontmatter(),
+        vec![
+            section("start"),
+            jump_with_argument("haggle", "harbor_master"),
+            jump_with_argument("haggle", "harbor_master"),
+            param_section("haggle", "merchant", "Merchant"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(!diag.has_errors(), "Expected no errors, got: {:?}", diag.all());
+    if let ContentNode::Jump(j) = &linked.graph.nodes["test.urd.md"].ast.content[1] {
+        let ann = j.annotation.as_ref().expect("annotation should be set");
+        assert_eq!(ann.resolved_section, Some("test/haggle".to_string()));
+        assert_eq!(ann.resolved_entity, Some("harbor_master".to_string()));
+    } else { panic!("expected Jump"); }
+    let sec = &linked.symbol_table.sections["test/haggle"];
+    assert_eq!(sec.invocations.len(), 2);
+    assert_eq!(sec.instance_arguments(), vec!["harbor_master"]);
+    assert_eq!(sec.instance_id("harbor_master"), "test/haggle@harbor_master");
+}
+
+#[test]
+fn jump_argument_unresolved_entity() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        merchant_frontmatter(),
+        vec![
+            section("start"),
+            jump_with_argument("haggle", "harbour_master"),
+            param_section("haggle", "merchant", "Merchant"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD301"));
+    assert!(linked.symbol_table.sections["test/haggle"].invocations.is_empty());
+}
+
+#[test]
+fn template_jump_without_argument() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        merchant_frontmatter(),
+        vec![
+            section("start"),
+            jump("haggle"),
+            param_section("haggle", "merchant", "Merchant"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD317"));
+}
+
+#[test]
+fn template_self_jump_without_argument_allowed() {
+    // Inside its own body, `-> haggle` loops within the current instance.
+    let ast = make_file_ast(
+        "test.urd.md",
+        merchant_frontmatter(),
+        vec![
+            param_section("haggle", "merchant", "Merchant"),
+            choice_with_children("Try again", vec![jump("haggle")]),
+            exhaustion_check("haggle"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert!(!has_error(&diag, "URD317"), "Self-reference should be allowed: {:?}", diag.all());
+}
+
+#[test]
+fn argument_to_unparameterised_section() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        merchant_frontmatter(),
+        vec![
+            section("start"),
+            jump_with_argument("farewell", "harbor_master"),
+            section("farewell"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD318"));
+}
+
+#[test]
+fn argument_to_end_rejected() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        merchant_frontmatter(),
+        vec![
+            section("start"),
+            jump_with_argument("end", "harbor_master"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD318"));
+}
+
+#[test]
+fn template_invoking_template_rejected() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        merchant_frontmatter(),
+        vec![
+            param_section("haggle", "merchant", "Merchant"),
+            jump_with_argument("inspect", "captain"),
+            param_section("inspect", "guard", "Guard"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD319"));
+    assert!(linked.symbol_table.sections["test/inspect"].invocations.is_empty());
+}
+
+// ── Choice Name Collision Tests (URD322, URD323) ──
+
+#[test]
+fn choice_named_like_its_section_warns() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![section("topics"), choice("Topics", false)],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(cu, &mut diag);
+
+    assert!(has_warning(&diag, "URD322"));
+    assert!(!diag.has_errors());
+    let warning = diag.all().iter().find(|d| d.code == "URD322").unwrap();
+    assert!(warning.message.contains("'test/topics/topics'"), "{}", warning.message);
+    assert!(linked.symbol_table.actions.contains_key("test/topics/topics"));
+}
+
+#[test]
+fn nested_choice_named_like_its_section_warns() {
+    let mut outer = choice("Ask around", false);
+    if let ContentNode::Choice(c) = &mut outer {
+        c.content.push(choice("Topics", false));
+    }
+    let ast = make_file_ast("test.urd.md", None, vec![section("topics"), outer]);
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert_eq!(diag.all().iter().filter(|d| d.code == "URD322").count(), 1);
+}
+
+#[test]
+fn choice_named_like_another_section_is_silent() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![section("topics"), choice("Greet", false), section("greet"), choice("Topics", false)],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert!(!has_warning(&diag, "URD322"));
+}
+
+#[test]
+fn choice_named_like_an_exit_warns() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![
+            location("Cell"),
+            exit_decl("north", "Harbor"),
+            section("talk"),
+            choice("North", false),
+            location("Harbor"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert!(has_warning(&diag, "URD323"));
+    let warning = diag.all().iter().find(|d| d.code == "URD323").unwrap();
+    assert!(warning.message.contains("location 'cell'"), "{}", warning.message);
+}
+
+#[test]
+fn choice_named_like_a_later_exit_warns() {
+    // The exit is declared after the section, still in the same location.
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![
+            location("Cell"),
+            section("talk"),
+            choice("North", false),
+            exit_decl("north", "Harbor"),
+            location("Harbor"),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert!(has_warning(&diag, "URD323"));
+}
+
+#[test]
+fn choice_named_like_an_exit_elsewhere_is_silent() {
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![
+            location("Cell"),
+            exit_decl("north", "Harbor"),
+            location("Harbor"),
+            exit_decl("south", "Cell"),
+            section("talk"),
+            choice("North", false),
+        ],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert!(!has_warning(&diag, "URD323"));
+}
+
+// ── Choice Jump Tests ──
+
+#[test]
+fn choice_exit_jump_to_missing_exit_is_error() {
+    let mut leave = choice("Leave", false);
+    if let ContentNode::Choice(c) = &mut leave {
+        c.content.push(exit_jump("south"));
+    }
+    let ast = make_file_ast(
+        "test.urd.md",
+        None,
+        vec![location("Cell"), exit_decl("north", "Harbor"), section("talk"), leave, location("Harbor")],
+    );
+    let cu = single_file_cu(ast);
+    let mut diag = DiagnosticCollector::new();
+    let _linked = link::link(cu, &mut diag);
+
+    assert!(has_error(&diag, "URD311"));
+}
+
+// ── Operand Spans ──
+
+/// `(code, line, start_col, end_col)` of each URD3xx diagnostic from a
+/// cell whose one choice holds `lines`.
+fn reference_columns(lines: &str) -> Vec<(String, u32, u32, u32)> {
+    let source = format!(
+        "---\nworld:\n  name: cell\n  start: cell\ntypes:\n  Guard [interactable]:\n    \
+         trust: integer = 0\nentities:\n  @guard: Guard\n---\n\n# Cell\n\n[@guard]\n\n\
+         == talk\n\n+ Wave\n{}\n",
+        lines
+    );
+    let result = urd_compiler::compile_source("cell.urd.md", &source);
+    result
+        .diagnostics
+        .all()
+        .iter()
+        .filter(|d| d.code.starts_with("URD3"))
+        .map(|d| (d.code.clone(), d.span.start_line, d.span.start_col, d.span.end_col))
+        .collect()
+}
+
+#[test]
+fn unknown_property_spans_only_the_property() {
+    let found = reference_columns("  ? @guard.trsut > 1\n  > @guard.trsut + 1");
+    assert_eq!(
+        found,
+        [("URD308".to_string(), 19, 12, 17), ("URD308".to_string(), 20, 12, 17)]
+    );
+}
+
+#[test]
+fn unknown_entity_spans_only_the_entity() {
+    let found = reference_columns("  ? @gaurd.trust > 1\n  > @gaurd.trust + 1");
+    assert_eq!(
+        found,
+        [("URD301".to_string(), 19, 5, 11), ("URD301".to_string(), 20, 5, 11)]
+    );
+}
+
+// ── Ending Tests ──
+
+/// Compile a one-location world with an `endings:` block and `body`
+/// under its `# Cell` heading.
+fn compile_endings(endings: &str, body: &str) -> urd_compiler::CompilationResult {
+    let source = format!(
+        "---\nworld:\n  name: cell\n  start: cell\nendings:\n{}---\n\n# Cell\n\n{}\n",
+        endings, body
+    );
+    urd_compiler::compile_source("cell.urd.md", &source)
+}
+
+#[test]
+fn end_effect_resolves_declared_ending() {
+    let result = compile_endings(
+        "  escaped: You slip away.\n  caught:\n",
+        "== talk\n\n* Run\n  > end escaped\n* Wait\n  > end caught",
+    );
+    assert!(result.success, "{:?}", result.diagnostics.all());
+    let st = result.symbol_table.unwrap();
+    assert_eq!(st.endings.keys().collect::<Vec<_>>(), ["escaped", "caught"]);
+    assert_eq!(st.endings["escaped"].description.as_deref(), Some("You slip away."));
+    assert_eq!(st.endings["caught"].description, None);
+}
+
+#[test]
+fn unknown_ending_suggests_declared_one() {
+    let result = compile_endings("  escaped: You slip away.\n", "== talk\n\n* Run\n  > end escpaed");
+    assert!(!result.success);
+    let d = result.diagnostics.all().iter().find(|d| d.code == "URD326").expect("URD326");
+    assert_eq!(d.message, "Unresolved ending 'escpaed'. No ending with this name is declared in an `endings:` block.");
+    assert_eq!(d.suggestion.as_deref(), Some("Did you mean 'escaped'?"));
+    let fix = d.fix.as_ref().expect("fix");
+    assert_eq!((fix.original.as_str(), fix.replacement.as_str()), ("escpaed", "escaped"));
+}
+
+#[test]
+fn duplicate_ending_is_reported() {
+    let result = compile_endings(
+        "  escaped: You slip away.\n  escaped: Again.\n",
+        "== talk\n\n* Run\n  > end escaped",
+    );
+    let d = result.diagnostics.all().iter().find(|d| d.code == "URD302").expect("URD302");
+    assert!(d.message.starts_with("Duplicate ending name 'escaped'"), "{}", d.message);
+    let st = result.symbol_table.unwrap();
+    assert_eq!(st.duplicates.len(), 1);
+    assert_eq!(st.duplicates[0].namespace, "endings");
+}
+
+#[test]
+fn ending_in_unimported_file_is_not_visible() {
+    let mut files = std::collections::HashMap::new();
+    files.insert(
+        "main.urd.md".to_string(),
+        "---\nworld:\n  name: w\n  start: cell\nimport: ./a.urd.md\n---\n\n# Cell\n\n== talk\n\n* Run\n  > end escaped\n".to_string(),
+    );
+    files.insert("a.urd.md".to_string(), "---\nimport: ./b.urd.md\n---\n".to_string());
+    files.insert("b.urd.md".to_string(), "---\nendings:\n  escaped: You slip away.\n---\n".to_string());
+    let reader = urd_compiler::test_support::MemoryFileReader::new(files.clone());
+    let result = urd_compiler::compile_source_with_options(
+        "main.urd.md",
+        &files["main.urd.md"],
+        &reader,
+        &urd_compiler::CompileOptions::default(),
+    );
+    let d = result.diagnostics.all().iter().find(|d| d.code == "URD326").expect("URD326");
+    assert_eq!(d.message, "Unresolved ending 'escaped'.");
+    assert!(d.suggestion.as_deref().unwrap_or("").contains("b.urd.md"), "{:?}", d.suggestion);
+}
+
+// ── Link Cache Tests ──
+
+const CACHE_MAIN: &str = "---
+import: ./people.urd.md
+import: ./places.urd.md
+world:
+  name: cache-test
+  start: hall
+---
+
+# Hall
+
+[@guard]
+
+-> north: Yard
+
+== talk
+
+* Greet the guard
+  @guard: Halt.
+  -> talk
+";
+
+const CACHE_PEOPLE: &str = "---
+types:
+  Guard [interactable]:
+    mood: enum(calm, angry) = calm
+entities:
+  @guard: Guard
+---
+";
+
+const CACHE_PLACES: &str = "---
+types:
+  Crate [portable]:
+    weight: integer = 1
+entities:
+  @crate: Crate
+---
+
+# Yard
+
+[@crate]
+";
+
+fn cache_project(places: &str) -> std::collections::HashMap<String, String> {
+    [("main.urd.md", CACHE_MAIN), ("people.urd.md", CACHE_PEOPLE), ("places.urd.md", places)]
+        .into_iter()
+        .map(|(path, source)| (path.to_string(), source.to_string()))
+        .collect()
+}
+
+/// The world JSON and every diagnostic, as compared between cached and
+/// uncached compiles.
+fn cache_output(
+    files: &std::collections::HashMap<String, String>,
+    cache: Option<&link::LinkCache>,
+) -> (Option<String>, Vec<String>) {
+    let reader = urd_compiler::test_support::MemoryFileReader::new(files.clone());
+    let options = urd_compiler::CompileOptions { link_cache: cache, ..Default::default() };
+    let result = urd_compiler::compile_source_with_options("main.urd.md", &files["main.urd.md"], &reader, &options);
+    let diagnostics = result.diagnostics.all().iter().map(|d| format!("{:?}", d)).collect();
+    (result.world, diagnostics)
+}
+
+#[test]
+fn link_cache_matches_uncached_compile_after_edit() {
+    let cache = link::LinkCache::new();
+    let before = cache_project(CACHE_PLACES);
+    let cached = cache_output(&before, Some(&cache));
+    assert!(cached.0.is_some(), "{:#?}", cached.1);
+    assert_eq!(cached, cache_output(&before, None));
+    assert_eq!((cache.hits(), cache.misses()), (0, 3));
+
+    // Only places.urd.md changes, and it now clashes with people.urd.md.
+    let after = cache_project(&CACHE_PLACES.replace("@crate: Crate", "@crate: Crate\n  @guard: Crate"));
+    let cached = cache_output(&after, Some(&cache));
+    assert_eq!(cached, cache_output(&after, None));
+    assert_eq!((cache.hits(), cache.misses()), (2, 4));
+    assert!(cached.1.iter().any(|d| d.contains("URD302")), "{:#?}", cached.1);
+}
+
+#[test]
+fn link_cache_hit_reports_file_local_diagnostics_again() {
+    let cache = link::LinkCache::new();
+    let files = cache_project(&CACHE_PLACES.replace("# Yard", "# Yard\n\n== dup\n\n== dup"));
+    let first = cache_output(&files, Some(&cache));
+    let second = cache_output(&files, Some(&cache));
+    assert_eq!(cache.hits(), 3);
+    assert_eq!(first, second);
+    assert!(second.1.iter().any(|d| d.contains("URD305")), "{:#?}", second.1);
+}
+
+#[test]
+fn link_cache_drops_files_no_longer_imported() {
+    let cache = link::LinkCache::new();
+    cache_output(&cache_project(CACHE_PLACES), Some(&cache));
+    assert_eq!(cache.len(), 3);
+
+    let mut files = cache_project(CACHE_PLACES);
+    files.insert("main.urd.md".to_string(), CACHE_MAIN.replace("import: ./places.urd.md\n", ""));
+    cache_output(&files, Some(&cache));
+    assert_eq!(cache.len(), 2);
+}
+
+// ── Presence Overrides ──
+
+/// Compile a two-location world whose `@cell_door` is declared with
+/// `declared` overrides, with `cell` and `yard` as each location's body.
+fn compile_presence(declared: &str, cell: &str, yard: &str) -> urd_compiler::CompilationResult {
+    let source = format!(
+        "---\nworld:\n  name: cell\n  start: cell\ntypes:\n  Door [interactable]:\n    locked: boolean = true\n\
+         \x20   label: string = \"Door\"\nentities:\n  @cell_door: Door{}\n---\n\n# Cell\n\n{}\n\n-> out: Yard\n\n\
+         # Yard\n\n{}\n\n-> in: Cell\n",
+        declared, cell, yard
+    );
+    urd_compiler::compile_source("cell.urd.md", &source)
+}
+
+#[test]
+fn presence_override_wins_over_declaration() {
+    let result = compile_presence(" { locked: true, label: \"Iron door\" }", "[@cell_door (locked: false)]", "");
+    assert!(result.success, "{:?}", result.diagnostics.all());
+    let st = result.symbol_table.unwrap();
+    let door = &st.entities["cell_door"];
+    assert_eq!(door.property_overrides["locked"], urd_compiler::symbol_table::Value::Boolean(false));
+    assert_eq!(door.property_overrides["label"], urd_compiler::symbol_table::Value::String("Iron door".to_string()));
+    assert_eq!(door.presence_overrides["locked"].0, "cell");
+    assert_eq!(door.override_span("locked").start_line, 15);
+    assert_eq!(door.override_span("label").start_line, 10);
+
+    let info: Vec<_> = result.diagnostics.all().iter().filter(|d| d.code == "URD327").collect();
+    assert_eq!(info.len(), 1);
+    assert_eq!(info[0].severity, Severity::Info);
+    assert_eq!(
+        info[0].message,
+        "Presence line in 'cell' overrides 'locked' of '@cell_door', which its declaration also sets. The presence line's value is used."
+    );
+    assert_eq!(info[0].related[0].span.start_line, 10);
+}
+
+#[test]
+fn presence_override_without_declaration_override_is_silent() {
+    let result = compile_presence("", "[@cell_door (locked: false)]", "");
+    assert!(result.success);
+    assert!(!result.diagnostics.all().iter().any(|d| d.code == "URD327"));
+}
+
+#[test]
+fn presence_override_unknown_property() {
+    let result = compile_presence("", "[@cell_door (lokced: false)]", "");
+    let d = result.diagnostics.all().iter().find(|d| d.code == "URD308").expect("URD308");
+    assert_eq!(d.span.start_line, 15);
+    assert_eq!(d.suggestion.as_deref(), Some("Did you mean 'locked'?"));
+    assert!(!result.symbol_table.unwrap().entities["cell_door"].property_overrides.contains_key("lokced"));
+}
+
+#[test]
+fn presence_overrides_in_two_locations_conflict() {
+    let result = compile_presence("", "[@cell_door (locked: false)]", "[@cell_door (locked: true, label: \"Gate\")]");
+    let errors: Vec<_> = result.diagnostics.all().iter().filter(|d| d.code == "URD328").collect();
+    assert_eq!(errors.len(), 1, "{:?}", result.diagnostics.all());
+    assert_eq!(errors[0].severity, Severity::Error);
+    assert_eq!(
+        errors[0].message,
+        "Property 'locked' of '@cell_door' is overridden on presence lines in both 'cell' and 'yard'. \
+         The entity exists once, so only one line may set it."
+    );
+    assert_eq!(errors[0].span.start_line, 21);
+    assert_eq!(errors[0].related[0].span.start_line, 15);
+
+    // The first value is kept; a property only the second line sets merges.
+    let st = result.symbol_table.unwrap();
+    assert_eq!(st.entities["cell_door"].property_overrides["locked"], urd_compiler::symbol_table::Value::Boolean(false));
+    assert_eq!(st.entities["cell_door"].presence_overrides["label"].0, "yard");
+}
+
+#[test]
+fn presence_overrides_repeated_in_one_location_conflict() {
+    let result = compile_presence("", "[@cell_door (locked: false)]\n[@cell_door (locked: false)]", "");
+    let d = result.diagnostics.all().iter().find(|d| d.code == "URD328").expect("URD328");
+    assert!(d.message.contains("overridden on presence lines twice in 'cell'."), "{}", d.message);
+}
+
+// ── Reserved names (URD330–URD333) ──
+
+fn compile_names(types: &str, entities: &str) -> urd_compiler::CompilationResult {
+    let source = format!(
+        "---\nworld:\n  name: names\n  start: hall\ntypes:\n  Box [mobile, container]:\n    label: string\n{}entities:\n  @player: Box\n{}---\n\n# Hall\n\nA bare hall.\n",
+        types, entities
+    );
+    urd_compiler::compile_source("names.urd.md", &source)
+}
+
+fn name_diagnostics(result: &urd_compiler::CompilationResult) -> Vec<&urd_compiler::diagnostics::Diagnostic> {
+    let codes = ["URD330", "URD331", "URD332", "URD333"];
+    result.diagnostics.all().iter().filter(|d| codes.contains(&d.code.as_str())).collect()
+}
+
+#[test]
+fn reserved_property_names_are_errors() {
+    let result = compile_names("  Crate:\n    container: boolean = false\n    exhausted: integer = 0\n", "");
+    let errors = name_diagnostics(&result);
+    assert_eq!(errors.len(), 2, "{:?}", errors);
+    assert!(errors.iter().all(|d| d.code == "URD330" && d.severity == Severity::Error));
+    assert_eq!(
+        errors[0].message,
+        "Property 'container' on type 'Crate' uses a reserved name: `@entity.container` is where the entity is, \
+         so conditions and effects could not tell the two apart."
+    );
+    assert_eq!(errors[0].span.start_line, 9);
+    assert!(errors[1].message.starts_with("Property 'exhausted' on type 'Crate'"));
+    assert!(!result.success);
+}
+
+#[test]
+fn reserved_and_malformed_entity_ids_are_errors() {
+    let result = compile_names("", "  @here: Box\n  @exit: Box\n  @lamp.oil: Box\n  @box/lid: Box\n");
+    let messages: Vec<&str> = name_diagnostics(&result)
+        .iter()
+        .inspect(|d| assert_eq!((d.code.as_str(), d.severity), ("URD331", Severity::Error)))
+        .map(|d| d.message.as_str())
+        .collect();
+    assert_eq!(
+        messages,
+        [
+            "Entity ID '@here' is a reserved word: 'here' means the player's current location.",
+            "Entity ID '@exit' is a reserved word: 'exit' means an exit of the current location, as in `-> exit:north`.",
+            "Entity ID '@lamp.oil' contains '.', which conditions and effects read as a property access.",
+            "Entity ID '@box/lid' contains '/', which compiled IDs use to join a file and a section.",
+        ]
+    );
+}
+
+#[test]
+fn type_named_like_a_property_type_warns() {
+    let result = compile_names("  String:\n    text: string\n", "");
+    let warnings = name_diagnostics(&result);
+    assert_eq!(warnings.len(), 1, "{:?}", warnings);
+    assert_eq!(warnings[0].code, "URD332");
+    assert_eq!(warnings[0].severity, Severity::Warning);
+    assert_eq!(
+        warnings[0].message,
+        "Type 'String' has the name of the property type 'string'. A declaration such as `ref(String)` or `list(String)` reads as either."
+    );
+    assert!(result.success);
+}
+
+#[test]
+fn location_matching_an_entity_id_warns() {
+    let result = compile_names("", "  @hall: Box\n");
+    let warnings = name_diagnostics(&result);
+    assert_eq!(warnings.len(), 1, "{:?}", warnings);
+    assert_eq!(warnings[0].code, "URD333");
+    assert_eq!(warnings[0].severity, Severity::Warning);
+    assert!(warnings[0].message.starts_with("Location 'hall' has the same ID as entity '@hall'."));
+    assert_eq!(warnings[0].span.start_line, 13);
+    assert_eq!(warnings[0].related[0].span.start_line, 10);
+    assert!(result.success);
+}
+
+#[test]
+fn player_entity_is_not_reserved() {
+    let result = compile_names("", "");
+    assert!(result.success, "{:?}", result.diagnostics.all());
+    assert!(name_diagnostics(&result).is_empty());
+}
+
+#[test]
+fn fixture_worlds_have_no_name_collisions() {
+    for fixture in ["two-room-key-puzzle.urd.md", "tavern-scene.urd.md", "monty-hall.urd.md", "sunken-citadel.urd.md"] {
+        let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), fixture);
+        let result = urd_compiler::compile(&path);
+        assert!(name_diagnostics(&result).is_empty(), "{}: {:?}", fixture, name_diagnostics(&result));
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════
+// Scope policy
+// ═══════════════════════════════════════════════════════════════════
+
+/// A CompilationUnit in which each file imports the files listed with it.
+/// `files` is in topological order, entry file last.
+fn import_graph_cu(files: Vec<(FileAst, Vec<&str>)>) -> CompilationUnit {
+    let mut nodes = IndexMap::new();
+    let mut edges = Vec::new();
+    let mut ordered_asts = Vec::new();
+    for (ast, imports) in files {
+        let path = ast.path.clone();
+        let imports: Vec<String> = imports.into_iter().map(str::to_string).collect();
+        edges.extend(imports.iter().map(|imp| (path.clone(), imp.clone())));
+        ordered_asts.push(path.clone());
+        nodes.insert(path.clone(), FileNode { path, ast, imports });
+    }
+    let entry_path = ordered_asts.last().cloned();
+    CompilationUnit { graph: DependencyGraph { nodes, edges, entry_path }, ordered_asts }
+}
+
+/// `@guard` and its type declared in c, spoken in a. A imports B, B imports C.
+fn guard_two_imports_away(world: Option<&str>) -> CompilationUnit {
+    let ast_c = make_file_ast(
+        "c.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Guard", make_type_def_in("Guard", "c.urd.md", 2, vec![], vec![])),
+            fm_entry("@guard", make_entity_decl_in("guard", "Guard", "c.urd.md", 4, vec![])),
+        ])),
+        vec![],
+    );
+    let ast_b = make_file_ast("b.urd.md", None, vec![]);
+    let frontmatter = world.map(|scope| {
+        make_frontmatter(vec![fm_entry("world", make_world_block(vec![("scope", Scalar::String(scope.to_string()))]))])
+    });
+    let ast_a = make_file_ast("a.urd.md", frontmatter, vec![location("Gate"), entity_speech("guard", "Halt.")]);
+    import_graph_cu(vec![(ast_c, vec![]), (ast_b, vec!["c.urd.md"]), (ast_a, vec!["b.urd.md"])])
+}
+
+fn speaker_at_gate(linked: &link::LinkedWorld) -> Option<String> {
+    match &linked.graph.nodes["a.urd.md"].ast.content[1] {
+        ContentNode::EntitySpeech(speech) => speech.annotation.as_ref().and_then(|a| a.resolved_entity.clone()),
+        other => panic!("expected entity speech, got {:?}", other),
+    }
+}
+
+#[test]
+fn transitive_reference_needs_the_transitive_policy() {
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(guard_two_imports_away(None), &mut diag);
+    assert_eq!(speaker_at_gate(&linked), None);
+    let unresolved: Vec<_> = diag.all().iter().filter(|d| d.code == "URD301").collect();
+    assert_eq!(unresolved.len(), 1);
+    assert_eq!(
+        unresolved[0].suggestion.as_deref(),
+        Some("'@guard' is declared in c.urd.md but c.urd.md is not imported by a.urd.md.")
+    );
+    assert!(!has_error(&diag, "URD334"));
+
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link_with_policy(guard_two_imports_away(None), link::ScopePolicy::Transitive, None, &mut diag);
+    assert_eq!(speaker_at_gate(&linked).as_deref(), Some("guard"));
+    assert!(!diag.has_errors(), "{:?}", diag.all());
+}
+
+#[test]
+fn transitive_reference_names_the_declaring_file() {
+    let mut diag = DiagnosticCollector::new();
+    link::link_with_policy(guard_two_imports_away(None), link::ScopePolicy::Transitive, None, &mut diag);
+    let notes: Vec<_> = diag.all().iter().filter(|d| d.code == "URD334").collect();
+    assert_eq!(notes.len(), 1, "{:?}", diag.all());
+    assert_eq!(notes[0].severity, Severity::Info);
+    assert_eq!(
+        notes[0].message,
+        "'guard' resolves only through a transitive import. It is declared in c.urd.md, which a.urd.md does not import directly."
+    );
+    assert_eq!(notes[0].suggestion.as_deref(), Some("Import c.urd.md to make the dependency explicit."));
+    assert_eq!(notes[0].span.start_line, 30);
+}
+
+#[test]
+fn unreachable_reference_has_no_import_hint_under_transitive() {
+    let ast_b = make_file_ast(
+        "b.urd.md",
+        Some(make_frontmatter(vec![
+            fm_entry("Guard", make_type_def_in("Guard", "b.urd.md", 2, vec![], vec![])),
+            fm_entry("@guard", make_entity_decl_in("guard", "Guard", "b.urd.md", 4, vec![])),
+        ])),
+        vec![],
+    );
+    let ast_a = make_file_ast("a.urd.md", None, vec![location("Gate"), entity_speech("guard", "Halt.")]);
+    let mut diag = DiagnosticCollector::new();
+    link::link_with_policy(two_file_cu_no_import(ast_b, ast_a), link::ScopePolicy::Transitive, None, &mut diag);
+    let unresolved: Vec<_> = diag.all().iter().filter(|d| d.code == "URD301").collect();
+    assert_eq!(unresolved.len(), 1);
+    assert_eq!(
+        unresolved[0].suggestion.as_deref(),
+        Some("'@guard' is declared in b.urd.md, which no import chain from a.urd.md reaches.")
+    );
+}
+
+#[test]
+fn world_block_scope_wins_over_the_option() {
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(guard_two_imports_away(Some("transitive")), &mut diag);
+    assert_eq!(speaker_at_gate(&linked).as_deref(), Some("guard"));
+    assert_eq!(error_count(&diag, "URD334"), 1);
+    assert!(!diag.has_errors(), "{:?}", diag.all());
+
+    let mut diag = DiagnosticCollector::new();
+    let linked =
+        link::link_with_policy(guard_two_imports_away(Some("direct")), link::ScopePolicy::Transitive, None, &mut diag);
+    assert_eq!(speaker_at_gate(&linked), None);
+    assert!(has_error(&diag, "URD301"));
+    assert!(!has_error(&diag, "URD334"));
+}
+
+#[test]
+fn scope_cache_matches_recomputation() {
+    // a → b → d → e and a → c → d: d and e are reached twice.
+    let empty = |path: &str| make_file_ast(path, None, vec![]);
+    let cu = import_graph_cu(vec![
+        (empty("e.urd.md"), vec![]),
+        (empty("d.urd.md"), vec!["e.urd.md"]),
+        (empty("b.urd.md"), vec!["d.urd.md"]),
+        (empty("c.urd.md"), vec!["d.urd.md"]),
+        (empty("a.urd.md"), vec!["b.urd.md", "c.urd.md"]),
+    ]);
+    let graph = &cu.graph;
+    let mut cache = link::ScopeCache::new();
+    // Ask for the entry first so the shared imports are filled on the way.
+    for path in cu.ordered_asts.iter().rev().chain(&cu.ordered_asts) {
+        assert_eq!(cache.reachable(path, graph), &link::reachable_files(path, graph), "{}", path);
+    }
+    let all: Vec<&str> = cache.reachable("a.urd.md", graph).iter().map(String::as_str).collect();
+    assert_eq!(all, ["a.urd.md", "b.urd.md", "c.urd.md", "d.urd.md", "e.urd.md"]);
+    let from_b: Vec<String> = link::reachable_files("b.urd.md", graph).into_iter().collect();
+    assert_eq!(from_b, ["b.urd.md", "d.urd.md", "e.urd.md"]);
+}
@@ -0,0 +1,131 @@
+/// Tests for build metadata: the sidecar document `urd --meta` writes.
+
+use std::time::Duration;
+
+use serde_json::Value as Json;
+use urd_compiler::hash::sha256_hex;
+use urd_compiler::metadata::{build_metadata, MetadataOptions};
+use urd_compiler::observer::Phase;
+use urd_compiler::{compile_with_options, CompilationResult, CompileOptions};
+
+const INTERROGATION: &str = "interrogation/main.urd.md";
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn compile_kept(name: &str, options: &CompileOptions) -> CompilationResult {
+    let options = CompileOptions { keep_sources: true, ..options.clone() };
+    compile_with_options(&fixture_path(name), &options)
+}
+
+fn metadata(result: &CompilationResult, options: &CompileOptions, timings: Option<&[(Phase, Duration)]>) -> Json {
+    let metadata_options = MetadataOptions { compile: options, timings };
+    let text = build_metadata(result, result.sources.as_ref().unwrap(), &metadata_options);
+    serde_json::from_str(&text).unwrap()
+}
+
+#[test]
+fn two_file_world_with_one_warning() {
+    let options = CompileOptions::default();
+    let result = compile_kept(INTERROGATION, &options);
+    let meta = metadata(&result, &options, None);
+
+    assert_eq!(meta["urd_meta"], "1");
+    assert_eq!(meta["compiler"], urd_compiler::version::to_json());
+    assert_eq!(meta["success"], true);
+    assert_eq!(meta["summary"], serde_json::json!({ "errors": 0, "warnings": 1, "info": 2 }));
+    let warnings: Vec<&Json> = meta["diagnostics"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|d| d["severity"] == "warning")
+        .collect();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0]["code"], "URD601");
+
+    let sources = meta["sources"].as_array().unwrap();
+    let paths: Vec<&str> = sources.iter().map(|s| s["path"].as_str().unwrap()).collect();
+    assert_eq!(paths, ["main.urd.md", "world.urd.md"]);
+    let world = std::fs::read_to_string(fixture_path("interrogation/world.urd.md")).unwrap();
+    assert_eq!(sources[1]["sha256"], sha256_hex(world.as_bytes()));
+    assert_eq!(sources[1]["bytes"], world.len());
+
+    assert_eq!(meta["options"]["emit"]["format"], "1");
+    assert_eq!(meta["options"]["missing_import_policy"], "error");
+    assert_eq!(meta["stats"]["locations"], urd_compiler::stats::from_compilation(&result).unwrap().locations);
+    assert!(meta.get("timings").is_none());
+}
+
+#[test]
+fn hashes_are_stable_and_follow_the_source() {
+    let options = CompileOptions::default();
+    let first = metadata(&compile_kept(INTERROGATION, &options), &options, None);
+    let second = metadata(&compile_kept(INTERROGATION, &options), &options, None);
+    assert_eq!(first["sources"], second["sources"]);
+
+    // Editing a file changes its hash and only its hash.
+    let result = compile_kept(INTERROGATION, &options);
+    let mut sources = result.sources.clone().unwrap();
+    sources.get_mut("main.urd.md").unwrap().push('\n');
+    let edited = build_metadata(&result, &sources, &MetadataOptions { compile: &options, timings: None });
+    let edited: Json = serde_json::from_str(&edited).unwrap();
+    assert_ne!(edited["sources"][0]["sha256"], first["sources"][0]["sha256"]);
+    assert_eq!(edited["sources"][1], first["sources"][1]);
+}
+
+#[test]
+fn timings_are_present_only_when_given() {
+    let options = CompileOptions::default();
+    let result = compile_kept(INTERROGATION, &options);
+    let timings = [(Phase::Parse, Duration::from_micros(1500)), (Phase::Emit, Duration::from_micros(250))];
+    let timed = metadata(&result, &options, Some(&timings));
+    assert_eq!(timed["timings"], serde_json::json!({ "parse": 1.5, "emit": 0.25, "total": 1.75 }));
+
+    // Without timings the document is byte-identical across compiles.
+    let sources = result.sources.as_ref().unwrap();
+    let reproducible = MetadataOptions { compile: &options, timings: None };
+    let again = compile_kept(INTERROGATION, &options);
+    assert_eq!(
+        build_metadata(&result, sources, &reproducible),
+        build_metadata(&again, again.sources.as_ref().unwrap(), &reproducible)
+    );
+}
+
+#[test]
+fn keys_keep_their_order() {
+    let options = CompileOptions { defines: vec!["demo".to_string()], ..CompileOptions::default() };
+    let result = compile_kept(INTERROGATION, &options);
+    let meta = metadata(&result, &options, Some(&[]));
+    let keys: Vec<&str> = meta.as_object().unwrap().keys().map(|k| k.as_str()).collect();
+    assert_eq!(
+        keys,
+        ["urd_meta", "compiler", "success", "summary", "diagnostics", "sources", "options", "stats", "timings"]
+    );
+    let option_keys: Vec<&str> = meta["options"].as_object().unwrap().keys().map(|k| k.as_str()).collect();
+    assert_eq!(
+        option_keys,
+        [
+            "emit",
+            "defines",
+            "missing_import_policy",
+            "strict_filename_case",
+            "text_lints",
+            "lenient_entity_refs",
+            "scope_policy",
+            "size_limits"
+        ]
+    );
+    assert_eq!(meta["options"]["defines"], serde_json::json!(["demo"]));
+}
+
+#[test]
+fn failed_compile_still_has_metadata() {
+    let options = CompileOptions::default();
+    let result = compile_kept("negative-type-mismatch.urd.md", &options);
+    assert!(!result.success);
+    let meta = metadata(&result, &options, None);
+    assert_eq!(meta["success"], false);
+    assert!(meta["summary"]["errors"].as_u64().unwrap() > 0);
+    assert_eq!(meta["sources"].as_array().unwrap().len(), 1);
+}
@@ -0,0 +1,226 @@
+/// Tests for numeric IDs: `idx` fields and the top-level `index` block.
+///
+/// Completeness tests collect every string ID the world emits, per kind,
+/// and check that the index holds each exactly once and agrees with the
+/// `idx` on the object.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde_json::Value as Json;
+use urd_compiler::diff::{diff, DiffSnapshot};
+use urd_compiler::emit::EmitOptions;
+use urd_compiler::import::OsFileReader;
+use urd_compiler::{schema, CompilationResult, CompileOptions};
+
+const FIXTURES: &[&str] = &[
+    "two-room-key-puzzle.urd.md",
+    "tavern-scene.urd.md",
+    "monty-hall.urd.md",
+    "sunken-citadel.urd.md",
+    "interrogation/main.urd.md",
+];
+
+// ── Helpers ──
+
+fn fixture_path(name: &str) -> String {
+    let base = env!("CARGO_MANIFEST_DIR");
+    format!("{}/tests/fixtures/{}", base, name)
+}
+
+fn compile_fixture(name: &str, emit: EmitOptions) -> CompilationResult {
+    let path = fixture_path(name);
+    let source = std::fs::read_to_string(&path).unwrap();
+    let options = CompileOptions { emit, ..CompileOptions::default() };
+    let result = urd_compiler::compile_source_with_options(&path, &source, &OsFileReader, &options);
+    assert!(result.success, "{} should compile", name);
+    result
+}
+
+fn numeric() -> EmitOptions {
+    EmitOptions { numeric_ids: true, ..EmitOptions::default() }
+}
+
+fn world(result: &CompilationResult) -> Json {
+    serde_json::from_str(result.world.as_ref().unwrap()).unwrap()
+}
+
+/// `(index kind, string ID, idx)` for every numbered object, in emission
+/// order. Phases are named `sequence/phase`. A nested choice appears twice:
+/// under its parent and at section level.
+fn emitted_ids(world: &Json) -> Vec<(&'static str, String, Option<u64>)> {
+    fn idx(object: &Json) -> Option<u64> {
+        object.get("idx").and_then(Json::as_u64)
+    }
+    fn choices(parent: &Json, out: &mut Vec<(&'static str, String, Option<u64>)>) {
+        for choice in parent.get("choices").and_then(Json::as_array).into_iter().flatten() {
+            out.push(("choices", choice["id"].as_str().unwrap().to_string(), idx(choice)));
+            choices(choice, out);
+        }
+    }
+
+    let mut out = Vec::new();
+    let blocks = [
+        ("types", "types"),
+        ("entities", "entities"),
+        ("locations", "locations"),
+        ("rules", "rules"),
+        ("actions", "actions"),
+        ("sequences", "sequences"),
+        ("dialogue", "sections"),
+    ];
+    for (block, kind) in blocks {
+        for (id, entry) in world.get(block).and_then(Json::as_object).into_iter().flatten() {
+            out.push((kind, id.clone(), idx(entry)));
+        }
+    }
+    for (sequence_id, sequence) in world.get("sequences").and_then(Json::as_object).into_iter().flatten() {
+        for phase in sequence["phases"].as_array().unwrap() {
+            let id = format!("{}/{}", sequence_id, phase["id"].as_str().unwrap());
+            out.push(("phases", id, idx(phase)));
+        }
+    }
+    for section in world.get("dialogue").and_then(Json::as_object).into_iter().flatten().map(|(_, s)| s) {
+        choices(section, &mut out);
+    }
+    out
+}
+
+/// The index block flattened to `(kind, string ID) → integer`.
+fn index_entries(world: &Json) -> BTreeMap<(String, String), u64> {
+    let mut entries = BTreeMap::new();
+    for (kind, ids) in world["index"].as_object().unwrap() {
+        for (id, value) in ids.as_object().unwrap() {
+            if kind == "phases" {
+                for (phase, value) in value.as_object().unwrap() {
+                    entries.insert((kind.clone(), format!("{}/{}", id, phase)), value.as_u64().unwrap());
+                }
+            } else {
+                entries.insert((kind.clone(), id.clone()), value.as_u64().unwrap());
+            }
+        }
+    }
+    entries
+}
+
+// ── Determinism ──
+
+#[test]
+fn numeric_ids_are_stable_across_compiles() {
+    for fixture in FIXTURES {
+        let a = compile_fixture(fixture, numeric());
+        let b = compile_fixture(fixture, numeric());
+        assert_eq!(a.world, b.world, "{}", fixture);
+    }
+}
+
+#[test]
+fn numeric_ids_count_from_zero_in_emission_order() {
+    let world = world(&compile_fixture("sunken-citadel.urd.md", numeric()));
+    let mut next: BTreeMap<&str, u64> = BTreeMap::new();
+    let mut seen: BTreeMap<(&str, String), u64> = BTreeMap::new();
+    for (kind, id, idx) in emitted_ids(&world) {
+        if let Some(first) = seen.get(&(kind, id.clone())) {
+            assert_eq!(idx, Some(*first), "{} '{}' repeated with another idx", kind, id);
+            continue;
+        }
+        let expected = next.entry(kind).or_insert(0);
+        assert_eq!(idx, Some(*expected), "{} '{}'", kind, id);
+        seen.insert((kind, id), *expected);
+        *expected += 1;
+    }
+    assert!(next.get("choices").copied().unwrap_or(0) > 0);
+    assert!(next.get("phases").copied().unwrap_or(0) > 0);
+}
+
+// ── Index block ──
+
+#[test]
+fn index_lists_every_emitted_id_exactly_once() {
+    for fixture in FIXTURES {
+        let world = world(&compile_fixture(fixture, numeric()));
+        let emitted = emitted_ids(&world);
+        let index = index_entries(&world);
+
+        let distinct: BTreeSet<(&str, &String)> = emitted.iter().map(|(k, id, _)| (*k, id)).collect();
+        assert_eq!(index.len(), distinct.len(), "{}: index size", fixture);
+        for (kind, id, idx) in &emitted {
+            assert_eq!(
+                index.get(&(kind.to_string(), id.clone())).copied(),
+                *idx,
+                "{}: {} '{}'",
+                fixture,
+                kind,
+                id
+            );
+        }
+    }
+}
+
+#[test]
+fn index_is_the_last_block() {
+    let world = world(&compile_fixture("monty-hall.urd.md", numeric()));
+    assert_eq!(world.as_object().unwrap().keys().next_back().unwrap(), "index");
+}
+
+// ── Option off ──
+
+#[test]
+fn numeric_ids_off_by_default() {
+    for fixture in FIXTURES {
+        let plain = world(&compile_fixture(fixture, EmitOptions::default()));
+        assert!(plain.get("index").is_none(), "{}", fixture);
+        assert!(emitted_ids(&plain).iter().all(|(_, _, idx)| idx.is_none()), "{}", fixture);
+    }
+}
+
+#[test]
+fn numeric_ids_only_add_fields() {
+    fn strip(value: &mut Json) {
+        match value {
+            Json::Object(map) => {
+                map.remove("idx");
+                map.values_mut().for_each(strip);
+            }
+            Json::Array(items) => items.iter_mut().for_each(strip),
+            _ => {}
+        }
+    }
+    for fixture in FIXTURES {
+        let plain = world(&compile_fixture(fixture, EmitOptions::default()));
+        let mut numbered = world(&compile_fixture(fixture, numeric()));
+        numbered.as_object_mut().unwrap().remove("index");
+        strip(&mut numbered);
+        assert_eq!(numbered, plain, "{}", fixture);
+    }
+}
+
+// ── Consumers ──
+
+#[test]
+fn diff_ignores_numeric_ids() {
+    for fixture in FIXTURES {
+        let plain = DiffSnapshot::from_compilation(&compile_fixture(fixture, EmitOptions::default()));
+        let numbered = DiffSnapshot::from_compilation(&compile_fixture(fixture, numeric()));
+        let report = diff(&plain, &numbered);
+        assert!(report.changes.is_empty(), "{}: {:?}", fixture, report.changes);
+    }
+}
+
+#[test]
+fn numeric_output_validates_against_schema() {
+    let schema: Json = serde_json::from_str(schema::world_schema()).unwrap();
+    let validator = jsonschema::validator_for(&schema).unwrap();
+    for fixture in FIXTURES {
+        let world = world(&compile_fixture(fixture, numeric()));
+        let errors: Vec<String> = validator.iter_errors(&world).map(|e| e.to_string()).collect();
+        assert!(errors.is_empty(), "{}: {:?}", fixture, errors);
+    }
+}
+
+#[test]
+fn split_output_carries_index_in_world_part() {
+    let result = compile_fixture("sunken-citadel.urd.md", EmitOptions { split: true, ..numeric() });
+    let parts = result.parts.as_ref().unwrap();
+    let world_part: Json = serde_json::from_str(&parts["world.json"]).unwrap();
+    assert_eq!(world_part["index"], world(&result)["index"]);
+}
@@ -0,0 +1,140 @@
+/// Tests for the compile observer.
+///
+/// A recording observer logs every event as a line of text; each test
+/// compiles a small in-memory world and checks the log.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use urd_compiler::cache::cache_key;
+use urd_compiler::diagnostics::Diagnostic;
+use urd_compiler::import::{FileReadError, FileReader};
+use urd_compiler::observer::{CompileObserver, Phase};
+use urd_compiler::{compile_source_with_options, CompilationResult, CompileOptions};
+
+// ── Helpers ──
+
+#[derive(Default)]
+struct Recorder {
+    events: RefCell<Vec<String>>,
+}
+
+impl CompileObserver for Recorder {
+    fn phase_started(&self, phase: Phase) {
+        self.events.borrow_mut().push(format!("start {}", phase.name()));
+    }
+
+    fn phase_finished(&self, phase: Phase, _elapsed: Duration) {
+        self.events.borrow_mut().push(format!("finish {}", phase.name()));
+    }
+
+    fn file_parsed(&self, path: &str, node_count: usize) {
+        self.events.borrow_mut().push(format!("file {} ({})", path, node_count));
+    }
+
+    fn diagnostic_emitted(&self, diagnostic: &Diagnostic) {
+        self.events.borrow_mut().push(format!("diagnostic {}", diagnostic.code));
+    }
+}
+
+struct MapReader(HashMap<String, String>);
+
+impl FileReader for MapReader {
+    fn read_file(&self, fs_path: &str) -> Result<String, FileReadError> {
+        self.0.get(fs_path).cloned().ok_or(FileReadError::NotFound)
+    }
+
+    fn canonical_filename(&self, _dir: &str, _filename: &str) -> Option<String> {
+        None
+    }
+}
+
+const CAST: &str = "---\ntypes:\n  Keeper [interactable]:\n    name: string\nentities:\n  @keeper: Keeper\n---\n";
+
+/// Compile `main` (importing the cast file) with a recording observer.
+fn observe(main: &str) -> (Vec<String>, CompilationResult) {
+    let reader = MapReader(HashMap::from([("cast.urd.md".to_string(), CAST.to_string())]));
+    let recorder = Recorder::default();
+    let options = CompileOptions { observer: Some(&recorder), ..CompileOptions::default() };
+    let result = compile_source_with_options("main.urd.md", main, &reader, &options);
+    (recorder.events.into_inner(), result)
+}
+
+// ── Tests ──
+
+#[test]
+fn two_file_compile_event_sequence() {
+    // `@nobody` is an intentional LINK error.
+    let main = "---\nimport: ./cast.urd.md\nworld:\n  name: observed\n  start: hall\n---\n\n# Hall\n\n[@keeper, @nobody]\n";
+    let (events, result) = observe(main);
+
+    assert!(!result.success);
+    assert_eq!(
+        events,
+        vec![
+            "start parse",
+            "file main.urd.md (2)",
+            "finish parse",
+            "start import",
+            "file cast.urd.md (0)",
+            "finish import",
+            "start link",
+            "diagnostic URD301",
+            "finish link",
+            "start analyze",
+            "finish analyze",
+            "start validate",
+            "finish validate",
+        ]
+    );
+}
+
+#[test]
+fn successful_compile_reports_emit() {
+    let main = "---\nimport: ./cast.urd.md\nworld:\n  name: observed\n  start: hall\n---\n\n# Hall\n\n[@keeper]\n";
+    let (events, result) = observe(main);
+
+    assert!(result.success);
+    assert_eq!(&events[events.len() - 2..], ["start emit", "finish emit"]);
+    assert!(!events.iter().any(|e| e.starts_with("diagnostic")));
+}
+
+#[test]
+fn every_diagnostic_is_relayed_once() {
+    let main = "---\nimport: ./cast.urd.md\n---\n\n# Hall\n\n[@keeper, @ghost]\n\n@nobody: Hello.\n\n-> nowhere\n";
+    let (events, result) = observe(main);
+
+    let relayed: Vec<&str> = events.iter().filter_map(|e| e.strip_prefix("diagnostic ")).collect();
+    let recorded: Vec<&str> = result.diagnostics.all().iter().map(|d| d.code.as_str()).collect();
+    assert!(recorded.len() >= 3, "Expected several diagnostics: {:?}", recorded);
+    assert_eq!(relayed, recorded);
+}
+
+#[test]
+fn diagnostic_outside_any_phase_is_relayed() {
+    let recorder = Recorder::default();
+    let options = CompileOptions {
+        project_root: Some("/project".to_string()),
+        observer: Some(&recorder),
+        ..CompileOptions::default()
+    };
+    let reader = MapReader(HashMap::new());
+    let result = compile_source_with_options("/elsewhere/main.urd.md", "# Hall\n", &reader, &options);
+
+    assert_eq!(result.diagnostics.all()[0].code, "URD208");
+    assert_eq!(recorder.events.into_inner(), vec!["diagnostic URD208"]);
+}
+
+#[test]
+fn observer_does_not_change_results_or_cache_key() {
+    let main = "---\nimport: ./cast.urd.md\nworld:\n  name: observed\n  start: hall\n---\n\n# Hall\n\n[@keeper]\n";
+    let (_, observed) = observe(main);
+    let reader = MapReader(HashMap::from([("cast.urd.md".to_string(), CAST.to_string())]));
+    let plain = compile_source_with_options("main.urd.md", main, &reader, &CompileOptions::default());
+    assert_eq!(observed.world, plain.world);
+
+    let recorder = Recorder::default();
+    let options = CompileOptions { observer: Some(&recorder), ..CompileOptions::default() };
+    assert_eq!(cache_key("main.urd.md", &options), cache_key("main.urd.md", &CompileOptions::default()));
+}
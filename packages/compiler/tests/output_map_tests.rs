@@ -0,0 +1,86 @@
+/// Tests for output maps: source lines to emitted JSON paths and back.
+
+use serde_json::Value as Json;
+use urd_compiler::emit::EmitOptions;
+use urd_compiler::output_map::{output_path_for_span, span_for_output_path};
+use urd_compiler::{compile_with_options, CompilationResult, CompileOptions};
+
+const CITADEL: &str = "sunken-citadel.urd.md";
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn compile_mapped(name: &str, emit: EmitOptions) -> CompilationResult {
+    let emit = EmitOptions { build_output_map: true, ..emit };
+    let result = compile_with_options(&fixture_path(name), &CompileOptions { emit, ..CompileOptions::default() });
+    assert!(result.success, "{} should compile", name);
+    result
+}
+
+#[test]
+fn choice_effect_maps_to_its_pointer() {
+    // Line 226: `> @elder_maren.secret = "revealed"`, the second effect of
+    // the second choice in square_talk.
+    let result = compile_mapped(CITADEL, EmitOptions::default());
+    let path = output_path_for_span(&result, CITADEL, 226).expect("line 226 emits an effect");
+    assert_eq!(path.pointer(), "/dialogue/sunken-citadel~1square_talk/choices/1/effects/1");
+    assert_eq!(path.to_string(), r#"dialogue["sunken-citadel/square_talk"].choices[1].effects[1]"#);
+
+    let world: Json = serde_json::from_str(result.world.as_ref().unwrap()).unwrap();
+    assert_eq!(world.pointer(&path.pointer()).unwrap()["set"], "elder_maren.secret");
+
+    // The choice line maps to the choice, not the action it also declares.
+    let choice = output_path_for_span(&result, CITADEL, 221).unwrap();
+    assert_eq!(choice.pointer(), "/dialogue/sunken-citadel~1square_talk/choices/1");
+}
+
+#[test]
+fn output_path_maps_back_to_its_span() {
+    let result = compile_mapped(CITADEL, EmitOptions::default());
+    for path in [
+        "/dialogue/sunken-citadel~1square_talk/choices/1/effects/1",
+        r#"dialogue["sunken-citadel/square_talk"].choices[1].effects[1]"#,
+    ] {
+        let span = span_for_output_path(&result, path).expect(path);
+        assert_eq!((span.file.as_str(), span.start_line, span.end_line), (CITADEL, 226, 226));
+    }
+    let nested = span_for_output_path(&result, "/dialogue/sunken-citadel~1square_talk/choices/3/choices/0").unwrap();
+    assert_eq!(nested.start_line, 241);
+    assert!(span_for_output_path(&result, "/dialogue/sunken-citadel~1square_talk/choices/99").is_none());
+}
+
+#[test]
+fn lines_without_output_map_to_nothing() {
+    let result = compile_mapped(CITADEL, EmitOptions::default());
+    // Location prose, a comment, and a blank line.
+    for line in [184, 188, 183] {
+        assert_eq!(output_path_for_span(&result, CITADEL, line), None, "line {}", line);
+    }
+    assert_eq!(output_path_for_span(&result, "elsewhere.urd.md", 226), None);
+}
+
+#[test]
+fn every_entry_resolves_in_the_world() {
+    let emit = EmitOptions { include_docs: true, numeric_ids: true, ..EmitOptions::default() };
+    for name in [CITADEL, "parameterised-sections.urd.md", "interrogation/main.urd.md", "location-hooks.urd.md"] {
+        let result = compile_mapped(name, emit.clone());
+        let world: Json = serde_json::from_str(result.world.as_ref().unwrap()).unwrap();
+        let map = result.output_map.as_ref().unwrap();
+        assert!(!map.entries().is_empty(), "{}", name);
+        for entry in map.entries() {
+            assert!(world.pointer(&entry.path.pointer()).is_some(), "{}: {}", name, entry.path);
+        }
+    }
+}
+
+#[test]
+fn map_is_absent_when_the_option_is_off() {
+    let plain = compile_with_options(&fixture_path(CITADEL), &CompileOptions::default());
+    assert!(plain.output_map.is_none());
+    assert_eq!(output_path_for_span(&plain, CITADEL, 226), None);
+
+    // The option changes nothing about the world itself.
+    let mapped = compile_mapped(CITADEL, EmitOptions::default());
+    assert_eq!(plain.world, mapped.world);
+}
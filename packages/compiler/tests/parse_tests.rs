@@ -73,6 +73,25 @@ fn location_heading() {
     }
 }
 
+#[test]
+fn region_heading() {
+    match first_node("# Region: The Docks") {
+        ContentNode::RegionHeading(h) => {
+            assert_eq!(h.display_name, "The Docks");
+            assert_eq!(h.id(), "the-docks");
+        }
+        other => panic!("expected RegionHeading, got {:?}", other),
+    }
+}
+
+#[test]
+fn region_word_alone_is_a_location_heading() {
+    match first_node("# Regional Office") {
+        ContentNode::LocationHeading(h) => assert_eq!(h.display_name, "Regional Office"),
+        other => panic!("expected LocationHeading, got {:?}", other),
+    }
+}
+
 #[test]
 fn sequence_heading() {
     match first_node("## The Game") {
@@ -105,6 +124,32 @@ fn phase_heading_auto() {
     }
 }
 
+#[test]
+fn phase_heading_section() {
+    let nodes = parse_content_only("### Opening\nsection: confession // who talks first\nThe room goes quiet.");
+    match &nodes[0] {
+        ContentNode::PhaseHeading(h) => {
+            assert_eq!(h.display_name, "Opening");
+            assert!(!h.auto);
+            let section = h.section.as_ref().expect("missing phase section");
+            assert_eq!(section.name, "confession");
+            assert_eq!(section.span.start_line, 2);
+        }
+        other => panic!("expected PhaseHeading, got {:?}", other),
+    }
+    assert!(matches!(nodes[1], ContentNode::Prose(_)));
+}
+
+#[test]
+fn phase_heading_section_must_follow_immediately() {
+    let nodes = parse_content_only("### Opening\n\nsection: confession");
+    match &nodes[0] {
+        ContentNode::PhaseHeading(h) => assert!(h.section.is_none()),
+        other => panic!("expected PhaseHeading, got {:?}", other),
+    }
+    assert!(matches!(nodes[1], ContentNode::Prose(_)));
+}
+
 #[test]
 fn section_label() {
     match first_node("== topics") {
@@ -115,6 +160,73 @@ fn section_label() {
     }
 }
 
+#[test]
+fn location_heading_explicit_id() {
+    match first_node("# Café & Bar {#cafe}") {
+        ContentNode::LocationHeading(h) => {
+            assert_eq!(h.display_name, "Café & Bar");
+            assert_eq!(h.explicit_id.as_deref(), Some("cafe"));
+        }
+        other => panic!("expected LocationHeading, got {:?}", other),
+    }
+}
+
+#[test]
+fn location_heading_explicit_id_with_comment() {
+    match first_node("# The Hall {#hall} // renamed from Great Hall") {
+        ContentNode::LocationHeading(h) => {
+            assert_eq!(h.display_name, "The Hall");
+            assert_eq!(h.explicit_id.as_deref(), Some("hall"));
+        }
+        other => panic!("expected LocationHeading, got {:?}", other),
+    }
+}
+
+#[test]
+fn location_heading_brace_without_space_is_text() {
+    match first_node("# Room{#a}") {
+        ContentNode::LocationHeading(h) => {
+            assert_eq!(h.display_name, "Room{#a}");
+            assert!(h.explicit_id.is_none());
+        }
+        other => panic!("expected LocationHeading, got {:?}", other),
+    }
+}
+
+#[test]
+fn section_label_explicit_id() {
+    match first_node("== topics {#intro_topics}") {
+        ContentNode::SectionLabel(s) => {
+            assert_eq!(s.name, "topics");
+            assert_eq!(s.explicit_id.as_deref(), Some("intro_topics"));
+            assert_eq!(s.local_id(), "intro_topics");
+        }
+        other => panic!("expected SectionLabel, got {:?}", other),
+    }
+}
+
+#[test]
+fn section_label_with_param() {
+    match first_node("== haggle(@merchant: Merchant) {#barter}") {
+        ContentNode::SectionLabel(s) => {
+            assert_eq!(s.name, "haggle");
+            assert_eq!(s.explicit_id.as_deref(), Some("barter"));
+            let param = s.param.expect("expected a section parameter");
+            assert_eq!(param.name, "merchant");
+            assert_eq!(param.type_name, "Merchant");
+        }
+        other => panic!("expected SectionLabel, got {:?}", other),
+    }
+}
+
+#[test]
+fn section_label_malformed_param() {
+    for source in ["== haggle(merchant: Merchant)", "== haggle(@merchant)", "== haggle(@merchant: merchant)"] {
+        let (_, diag) = parse_source(source);
+        assert!(diag.all().iter().any(|d| d.code == "URD112"), "{} should be rejected", source);
+    }
+}
+
 #[test]
 fn entity_speech() {
     match first_node("@arina: What'll it be?") {
@@ -147,6 +259,28 @@ fn entity_presence() {
     }
 }
 
+#[test]
+fn entity_presence_with_overrides() {
+    match first_node("[@cell_door (locked: false, label: \"Gate, rusted\"), @key, @lamp ()]") {
+        ContentNode::EntityPresence(p) => {
+            assert_eq!(p.entity_refs, vec!["cell_door", "key", "lamp"]);
+            assert_eq!(
+                p.property_overrides,
+                vec![
+                    vec![
+                        ("locked".to_string(), Scalar::Boolean(false)),
+                        ("label".to_string(), Scalar::String("Gate, rusted".to_string())),
+                    ],
+                    Vec::new(),
+                    Vec::new(),
+                ]
+            );
+            assert_eq!(p.annotations.len(), 3);
+        }
+        other => panic!("expected EntityPresence, got {:?}", other),
+    }
+}
+
 #[test]
 fn one_shot_choice() {
     match first_node("* Ask about the ship") {
@@ -271,6 +405,117 @@ fn exhaustion_condition() {
     }
 }
 
+#[test]
+fn once_condition() {
+    let source = "* Introduce yourself\n  ? once";
+    match first_node(source) {
+        ContentNode::Choice(c) => {
+            assert!(c.is_once());
+            assert!(matches!(&c.content[0], ContentNode::Condition(cond) if matches!(cond.expr, ConditionExpr::Once(_))));
+        }
+        other => panic!("expected Choice, got {:?}", other),
+    }
+}
+
+#[test]
+fn visited_condition() {
+    for (source, negated) in [("? visited the-docks", false), ("? !visited the-docks", true)] {
+        match first_node(source) {
+            ContentNode::Condition(c) => match &c.expr {
+                ConditionExpr::VisitedCheck(vc) => {
+                    assert_eq!(vc.location_ref, "the-docks");
+                    assert_eq!(vc.negated, negated);
+                }
+                other => panic!("expected VisitedCheck, got {:?}", other),
+            },
+            other => panic!("expected Condition, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn visited_condition_malformed() {
+    for source in ["? visited", "? visited the docks", "? ! visited docks", "? once more"] {
+        let (_, diag) = parse_source(source);
+        assert!(diag.all().iter().any(|d| d.code == "URD112"), "{} should be rejected", source);
+    }
+}
+
+fn property_comparison_of(source: &str) -> PropertyComparison {
+    match first_node(source) {
+        ContentNode::Condition(Condition { expr: ConditionExpr::PropertyComparison(pc), .. }) => pc,
+        other => panic!("expected PropertyComparison for {}, got {:?}", source, other),
+    }
+}
+
+#[test]
+fn bare_and_negated_property_conditions() {
+    for (source, operator, value, negated, bare) in [
+        ("? @door.locked", "==", "true", false, true),
+        ("? !@door.locked", "==", "true", true, true),
+        ("? not @door.locked", "==", "true", true, true),
+        ("? not @guard.trust < 3", "<", "3", true, false),
+        ("? not $door.state == open", "==", "open", true, false),
+        ("? player.awake", "==", "true", false, true),
+    ] {
+        let pc = property_comparison_of(source);
+        assert_eq!(
+            (pc.operator.as_str(), pc.value.as_str(), pc.negated, pc.bare),
+            (operator, value, negated, bare),
+            "{}",
+            source,
+        );
+    }
+}
+
+#[test]
+fn negated_property_conditions_lower_by_flipping() {
+    for (source, lowered) in [
+        ("? @door.locked", ("==", "true")),
+        ("? !@door.locked", ("==", "false")),
+        ("? not @guard.trust < 3", (">=", "3")),
+        ("? not @guard.trust >= 3", ("<", "3")),
+        ("? not @guard.trust > 3", ("<=", "3")),
+        ("? not @guard.mood == angry", ("!=", "angry")),
+        ("? not @guard.mood != angry", ("==", "angry")),
+    ] {
+        assert_eq!(property_comparison_of(source).lowered(), lowered, "{}", source);
+    }
+}
+
+#[test]
+fn negated_property_conditions_malformed() {
+    for source in [
+        "? !@guard.trust > 3",
+        "? not not @door.locked",
+        "? not !@door.locked",
+        "? not @key in here",
+        "? @door.is locked",
+    ] {
+        let (_, diag) = parse_source(source);
+        assert!(diag.all().iter().any(|d| d.code == "URD112"), "{} should be rejected", source);
+    }
+}
+
+#[test]
+fn or_condition_block_with_negations() {
+    let source = "? any:\n  !@door.locked\n  not @guard.mood == angry";
+    match &parse_content_only(source)[0] {
+        ContentNode::OrConditionBlock(oc) => {
+            let lowered: Vec<(&str, &str)> = oc
+                .conditions
+                .iter()
+                .map(|expr| match expr {
+                    ConditionExpr::PropertyComparison(pc) => pc.lowered(),
+                    other => panic!("expected PropertyComparison, got {:?}", other),
+                })
+                .collect();
+            assert_eq!(lowered, vec![("==", "false"), ("!=", "angry")]);
+        }
+        other => panic!("expected OrConditionBlock, got {:?}", other),
+    }
+}
+
 #[test]
 fn or_condition_block() {
     let source = "? any:\n  @guard.mood == neutral\n  @key in here";
@@ -288,7 +533,7 @@ fn set_effect() {
     match first_node("> @guard.mood = neutral") {
         ContentNode::Effect(e) => {
             match &e.effect_type {
-                EffectType::Set { target_prop, operator, value_expr } => {
+                EffectType::Set { target_prop, operator, value_expr, .. } => {
                     assert_eq!(target_prop, "@guard.mood");
                     assert_eq!(operator, "=");
                     assert_eq!(value_expr, "neutral");
@@ -305,7 +550,7 @@ fn increment_effect() {
     match first_node("> @arina.trust + 5") {
         ContentNode::Effect(e) => {
             match &e.effect_type {
-                EffectType::Set { target_prop, operator, value_expr } => {
+                EffectType::Set { target_prop, operator, value_expr, .. } => {
                     assert_eq!(target_prop, "@arina.trust");
                     assert_eq!(operator, "+");
                     assert_eq!(value_expr, "5");
@@ -379,6 +624,17 @@ fn destroy_effect() {
     }
 }
 
+#[test]
+fn end_effect() {
+    match first_node("> end escaped") {
+        ContentNode::Effect(e) => match &e.effect_type {
+            EffectType::End { ending_ref } => assert_eq!(ending_ref, "escaped"),
+            other => panic!("expected End, got {:?}", other),
+        },
+        other => panic!("expected Effect, got {:?}", other),
+    }
+}
+
 #[test]
 fn section_jump() {
     match first_node("-> topics") {
@@ -390,6 +646,23 @@ fn section_jump() {
     }
 }
 
+#[test]
+fn section_jump_with_argument() {
+    match first_node("-> haggle(@harbor_master)") {
+        ContentNode::Jump(j) => {
+            assert_eq!(j.target, "haggle");
+            assert_eq!(j.argument.as_deref(), Some("harbor_master"));
+        }
+        other => panic!("expected Jump, got {:?}", other),
+    }
+}
+
+#[test]
+fn section_jump_malformed_argument() {
+    let (_, diag) = parse_source("-> haggle(harbor_master)");
+    assert!(diag.all().iter().any(|d| d.code == "URD112"));
+}
+
 #[test]
 fn exit_qualified_jump() {
     match first_node("-> exit:harbor") {
@@ -442,6 +715,21 @@ fn exit_with_deep_children() {
     }
 }
 
+#[test]
+fn exit_with_effect_children() {
+    let source = "-> north: Corridor\n  ? @door.locked == false\n  > @door.locked = true\n  > move @key -> player";
+    let nodes = parse_content_only(source);
+    assert_eq!(nodes.len(), 1);
+    match &nodes[0] {
+        ContentNode::ExitDeclaration(e) => {
+            assert_eq!(e.children.len(), 3);
+            assert!(matches!(&e.children[1], ContentNode::Effect(_)));
+            assert!(matches!(&e.children[2], ContentNode::Effect(_)));
+        }
+        other => panic!("expected ExitDeclaration, got {:?}", other),
+    }
+}
+
 #[test]
 fn exit_non_child_content() {
     let source = "-> north: Corridor\n  @arina: Hello";
@@ -469,6 +757,61 @@ fn exit_children_with_blank_lines() {
     }
 }
 
+#[test]
+fn location_hook_on_enter() {
+    let source = "on enter:\n  ? @lamp.lit == false\n  > @lamp.lit = true";
+    let nodes = parse_content_only(source);
+    assert_eq!(nodes.len(), 1);
+    match &nodes[0] {
+        ContentNode::LocationHook(h) => {
+            assert_eq!(h.kind, HookKind::Enter);
+            assert_eq!(h.children.len(), 2);
+            assert!(matches!(&h.children[0], ContentNode::Condition(_)));
+            assert!(matches!(&h.children[1], ContentNode::Effect(_)));
+            assert_eq!(h.span.start_line, 1);
+            assert_eq!(h.span.end_line, 3);
+        }
+        other => panic!("expected LocationHook, got {:?}", other),
+    }
+}
+
+#[test]
+fn location_hook_on_exit_with_or_block() {
+    let source = "on exit: // dim the lamp\n  ? any:\n    @lamp.lit == true\n    @lamp.fuel > 0\n  > @lamp.lit = false\nBack to prose";
+    let nodes = parse_content_only(source);
+    assert_eq!(nodes.len(), 2);
+    match &nodes[0] {
+        ContentNode::LocationHook(h) => {
+            assert_eq!(h.kind, HookKind::Exit);
+            assert_eq!(h.children.len(), 2);
+            assert!(matches!(&h.children[0], ContentNode::OrConditionBlock(_)));
+            assert!(matches!(&h.children[1], ContentNode::Effect(_)));
+        }
+        other => panic!("expected LocationHook, got {:?}", other),
+    }
+    assert!(matches!(&nodes[1], ContentNode::Prose(_)));
+}
+
+#[test]
+fn location_hook_rejects_non_hook_child() {
+    let (ast, diag) = parse_source("on enter:\n  The lamp flickers.\n  > @lamp.lit = true");
+    let nodes = ast.expect("parse returned None").content;
+    assert_eq!(nodes.len(), 1);
+    match &nodes[0] {
+        ContentNode::LocationHook(h) => {
+            assert_eq!(h.children.len(), 2);
+            assert!(matches!(&h.children[0], ContentNode::ErrorNode(_)));
+        }
+        other => panic!("expected LocationHook, got {:?}", other),
+    }
+    assert!(diag.all().iter().any(|d| d.code == "URD112"));
+}
+
+#[test]
+fn on_enter_without_colon_is_prose() {
+    assert!(matches!(first_node("on entering the hall, you shiver."), ContentNode::Prose(_)));
+}
+
 #[test]
 fn choice_body_with_blank_lines() {
     let source = "* Ask\n\n  ? topics.exhausted\n\n  > reveal @x.y\nBack to prose";
@@ -571,6 +914,166 @@ fn rule_block() {
     }
 }
 
+#[test]
+fn quoted_line_in_choice_is_speech_without_a_speaker() {
+    let source = "== talk\n\n\"Choose.\"\n\n* Ask\n  \"It stays shut.\"\n  \"Half\" a door.\n\n\"Off with you.\"";
+    let nodes = parse_content_only(source);
+    // In the prompt, a quoted line is prose.
+    assert!(matches!(&nodes[1], ContentNode::Prose(p) if p.text == "\"Choose.\""));
+    let choice = match &nodes[2] {
+        ContentNode::Choice(c) => c,
+        other => panic!("expected Choice, got {:?}", other),
+    };
+    match &choice.content[0] {
+        ContentNode::EntitySpeech(es) => {
+            assert!(es.inherits_speaker());
+            assert_eq!(es.text, "It stays shut.");
+        }
+        other => panic!("expected EntitySpeech, got {:?}", other),
+    }
+    // Only a line quoted end to end.
+    assert!(matches!(&choice.content[1], ContentNode::Prose(_)));
+    assert!(matches!(&nodes[3], ContentNode::EntitySpeech(es) if es.text == "Off with you." && es.entity_ref.is_empty()));
+}
+
+#[test]
+fn indented_rule_block_records_its_indent() {
+    let source = "# Tavern\n\n  rule last_orders:\n    actor: @barkeep action close\n    > @barkeep.open = false";
+    let nodes = parse_content_only(source);
+    let rule = nodes.iter().find_map(|n| match n {
+        ContentNode::RuleBlock(r) => Some(r),
+        _ => None,
+    });
+    let rule = rule.expect("missing RuleBlock");
+    assert_eq!(rule.indent_level, 1);
+    assert_eq!(rule.effects.len(), 1);
+}
+
+#[test]
+fn doc_comment_attaches_to_location_heading() {
+    let source = "/// A dim stone cell.\n///\n/// Damp walls.\n# Cell";
+    let nodes = parse_content_only(source);
+    match nodes.last().unwrap() {
+        ContentNode::LocationHeading(h) => {
+            assert_eq!(h.doc.as_deref(), Some("A dim stone cell.\n\nDamp walls."));
+        }
+        other => panic!("expected LocationHeading, got {:?}", other),
+    }
+}
+
+#[test]
+fn doc_comment_attaches_to_section_label() {
+    let source = "# Cell\n\n/// The guard's greeting.\n== greet";
+    let nodes = parse_content_only(source);
+    match nodes.last().unwrap() {
+        ContentNode::SectionLabel(l) => {
+            assert_eq!(l.doc.as_deref(), Some("The guard's greeting."));
+        }
+        other => panic!("expected SectionLabel, got {:?}", other),
+    }
+}
+
+#[test]
+fn doc_comment_is_comment_not_prose() {
+    match first_node("/// author note") {
+        ContentNode::Comment(c) => assert_eq!(c.text, "author note"),
+        other => panic!("expected Comment, got {:?}", other),
+    }
+}
+
+#[test]
+fn doc_comment_separated_by_blank_line_does_not_attach() {
+    let source = "/// Stray note.\n\n# Cell\n\n// plain comment\n== greet";
+    let nodes = parse_content_only(source);
+    for node in &nodes {
+        match node {
+            ContentNode::LocationHeading(h) => assert!(h.doc.is_none()),
+            ContentNode::SectionLabel(l) => assert!(l.doc.is_none()),
+            _ => {}
+        }
+    }
+}
+
+// ── Continuation Lines ──
+
+#[test]
+fn blocked_message_continuation_joins_with_space() {
+    let source = "-> north: Corridor\n  ! The iron door is locked.\n    Rust has sealed it for years.\n";
+    match first_node(source) {
+        ContentNode::ExitDeclaration(e) => match &e.children[0] {
+            ContentNode::BlockedMessage(b) => {
+                assert_eq!(b.text, "The iron door is locked. Rust has sealed it for years.");
+                assert_eq!((b.span.start_line, b.span.end_line), (2, 3));
+                assert_eq!(b.span.end_col, 34);
+            }
+            other => panic!("expected BlockedMessage, got {:?}", other),
+        },
+        other => panic!("expected ExitDeclaration, got {:?}", other),
+    }
+}
+
+#[test]
+fn speech_continuation_with_paragraph_marker() {
+    let source = "@keeper: The lamp has burned\n  for a hundred years.\n  \\\n  It will burn for a hundred more.\n@keeper: Go.\n";
+    let nodes = parse_content_only(source);
+    assert_eq!(nodes.len(), 2, "{:?}", nodes);
+    match &nodes[0] {
+        ContentNode::EntitySpeech(s) => {
+            assert_eq!(s.text, "The lamp has burned for a hundred years.\nIt will burn for a hundred more.");
+            assert_eq!((s.span.start_line, s.span.end_line), (1, 4));
+        }
+        other => panic!("expected EntitySpeech, got {:?}", other),
+    }
+}
+
+#[test]
+fn choice_response_continuation() {
+    let source = "* Ask about the lamp\n  The keeper smiles.\n    She trims the wick. // aside\n  > @lamp.lit = true\n";
+    match first_node(source) {
+        ContentNode::Choice(c) => {
+            assert_eq!(c.content.len(), 2, "{:?}", c.content);
+            match &c.content[0] {
+                ContentNode::Prose(p) => {
+                    assert_eq!(p.text, "The keeper smiles. She trims the wick.");
+                    assert_eq!(p.span.end_line, 3);
+                }
+                other => panic!("expected Prose, got {:?}", other),
+            }
+            assert!(matches!(&c.content[1], ContentNode::Effect(_)));
+        }
+        other => panic!("expected Choice, got {:?}", other),
+    }
+}
+
+#[test]
+fn continuation_stops_at_constructs_and_blank_lines() {
+    // An indented `+` line is a nested choice, not more speech.
+    let source = "* Ask\n  @keeper: Which one?\n    + The lamp\n    + The stair\n";
+    match first_node(source) {
+        ContentNode::Choice(c) => {
+            assert_eq!(c.content.len(), 3, "{:?}", c.content);
+            match &c.content[0] {
+                ContentNode::EntitySpeech(s) => assert_eq!(s.text, "Which one?"),
+                other => panic!("expected EntitySpeech, got {:?}", other),
+            }
+            assert!(matches!(&c.content[1], ContentNode::Choice(_)));
+        }
+        other => panic!("expected Choice, got {:?}", other),
+    }
+
+    // A blank line ends the node; two levels deeper is not a continuation.
+    let nodes = parse_content_only("! Locked.\n\n  Still locked.\n");
+    assert_eq!(nodes.len(), 2, "{:?}", nodes);
+    let nodes = parse_content_only("! Locked.\n    Far too deep.\n");
+    assert_eq!(nodes.len(), 2, "{:?}", nodes);
+}
+
+#[test]
+fn top_level_prose_does_not_continue() {
+    let nodes = parse_content_only("The hall is quiet.\n  Dust hangs in the air.\n");
+    assert_eq!(nodes.len(), 2, "{:?}", nodes);
+}
+
 // ── Frontmatter Tests ──
 
 #[test]
@@ -602,6 +1105,91 @@ fn world_block() {
     }
 }
 
+fn world_field(source: &str, key: &str) -> Scalar {
+    let (ast, diag) = parse_source(source);
+    assert!(!diag.has_errors(), "{:?}", diag.all());
+    let fm = ast.unwrap().frontmatter.unwrap();
+    let FrontmatterValue::WorldBlock(wb) = &fm.entries[0].value else {
+        panic!("expected WorldBlock");
+    };
+    wb.fields.iter().find(|(k, _)| k == key).map(|(_, v)| v.clone()).expect("no such field")
+}
+
+#[test]
+fn literal_block_keeps_line_breaks() {
+    let source = "---\nworld:\n  name: test\n  description: |\n    A cold cell.\n      # Not a comment.\n\n    Water drips.\n  start: cell\n---\n";
+    assert_eq!(
+        world_field(source, "description"),
+        Scalar::String("A cold cell.\n  # Not a comment.\n\nWater drips.".to_string())
+    );
+    assert_eq!(world_field(source, "start"), Scalar::String("cell".to_string()));
+}
+
+#[test]
+fn folded_block_joins_lines() {
+    let source = "---\nworld:\n  name: test\n  description: >\n    A cold\n    cell.\n\n    Water drips.\n---\n";
+    assert_eq!(
+        world_field(source, "description"),
+        Scalar::String("A cold cell.\nWater drips.".to_string())
+    );
+}
+
+#[test]
+fn block_ends_at_dedent() {
+    // The block ends at 'title:', which is no deeper than its key.
+    let (ast, diag) = parse_source("---\nintro: |\n  First.\n  Second.\n\ntitle: Cell\n---\n");
+    assert!(!diag.has_errors(), "{:?}", diag.all());
+    let fm = ast.unwrap().frontmatter.unwrap();
+    assert_eq!(fm.entries.len(), 2);
+    assert!(matches!(&fm.entries[0].value, FrontmatterValue::Scalar(Scalar::String(s)) if s == "First.\nSecond."));
+    // The span covers the key line and the block.
+    assert_eq!((fm.entries[0].span.start_line, fm.entries[0].span.end_line), (2, 4));
+    assert_eq!(fm.entries[1].key, "title");
+}
+
+#[test]
+fn indented_dashes_in_block_are_content() {
+    let source = "---\nworld:\n  name: test\n  description: |\n    Above.\n    ---\n    Below.\n---\n# Cell\n";
+    let (ast, diag) = parse_source(source);
+    assert!(!diag.has_errors(), "{:?}", diag.all());
+    let ast = ast.unwrap();
+    let FrontmatterValue::WorldBlock(wb) = &ast.frontmatter.unwrap().entries[0].value else {
+        panic!("expected WorldBlock");
+    };
+    assert_eq!(wb.fields[1].1, Scalar::String("Above.\n---\nBelow.".to_string()));
+    assert!(matches!(&ast.content[0], ContentNode::LocationHeading(_)));
+}
+
+#[test]
+fn block_as_property_default() {
+    let source = "---\ntypes:\n  Note [portable]:\n    text: string = >\n      Meet me\n      at dawn.\n    read: bool = false\n---\n";
+    let (ast, diag) = parse_source(source);
+    assert!(!diag.has_errors(), "{:?}", diag.all());
+    let note = get_type_def(&ast, "Note");
+    assert_eq!(note.properties.len(), 2);
+    assert_eq!(note.properties[0].default, Some(Scalar::String("Meet me at dawn.".to_string())));
+    assert_eq!((note.properties[0].span.start_line, note.properties[0].span.end_line), (4, 6));
+    assert_eq!(note.properties[1].name, "read");
+}
+
+#[test]
+fn tab_in_block_is_rejected() {
+    let (_, diag) = parse_source("---\nworld:\n  name: test\n  description: |\n    A\tcell.\n---\n");
+    assert_eq!(diag.all().iter().filter(|d| d.code == "URD102").count(), 1);
+}
+
+#[test]
+fn block_left_open_by_indented_close_names_the_line() {
+    // The closing '---' is indented, so it belongs to the block and the
+    // frontmatter never closes.
+    let (ast, diag) = parse_source("---\nworld:\n  description: |\n    A cell.\n    ---\n# Cell\n\nProse.\n");
+    assert!(ast.is_none());
+    let errors = diag.all();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].code, "URD101");
+    assert!(errors[0].message.contains("The '---' at line 5 is indented"), "{}", errors[0].message);
+}
+
 #[test]
 fn type_definition() {
     let source = "---\ntypes:\n  Guard [interactable, mobile]:\n    mood: enum(hostile, neutral)\n    ~prize: string\n---\n";
@@ -657,6 +1245,35 @@ fn entity_declaration() {
     }
 }
 
+#[test]
+fn endings_block() {
+    let source = "---\nendings:\n  escaped: You slip away.\n  caught:\n  # A comment.\n  drowned: \"The river takes you.\"\n  lost: >\n    You wander\n    forever.\n---\n";
+    let (ast, diag) = parse_source(source);
+    assert!(!diag.has_errors());
+    let fm = ast.unwrap().frontmatter.unwrap();
+    let FrontmatterValue::Map(entries) = &fm.entries[0].value else {
+        panic!("expected Map, got {:?}", fm.entries[0].value);
+    };
+    let endings: Vec<(&str, Option<&str>, u32, u32)> = entries
+        .iter()
+        .map(|e| match &e.value {
+            FrontmatterValue::EndingDecl(ed) => {
+                (ed.id.as_str(), ed.description.as_deref(), ed.span.start_line, ed.span.end_line)
+            }
+            other => panic!("expected EndingDecl, got {:?}", other),
+        })
+        .collect();
+    assert_eq!(
+        endings,
+        [
+            ("escaped", Some("You slip away."), 3, 3),
+            ("caught", None, 4, 4),
+            ("drowned", Some("The river takes you."), 6, 6),
+            ("lost", Some("You wander forever."), 7, 9),
+        ]
+    );
+}
+
 #[test]
 fn import_declaration() {
     let source = "---\nimport: ./world.urd.md\n---\n";
@@ -673,6 +1290,21 @@ fn import_declaration() {
     }
 }
 
+#[test]
+fn doc_comments_attach_to_type_and_entity() {
+    let source = "---\ntypes:\n  /// A lockable door.\n  /// Opens with its key.\n  Door [interactable]:\n    /// Whether it is locked.\n    locked: bool = true\n  Key:\n    name: string\nentities:\n  /// The cell door.\n  @cell_door: Door\n  @key: Key\n---\n";
+    let (ast, diag) = parse_source(source);
+    assert!(!diag.has_errors());
+
+    let door = get_type_def(&ast, "Door");
+    assert_eq!(door.doc.as_deref(), Some("A lockable door.\nOpens with its key."));
+    assert_eq!(door.properties.len(), 1, "property doc lines are not properties");
+    assert!(get_type_def(&ast, "Key").doc.is_none());
+
+    assert_eq!(get_entity_decl(&ast, "cell_door").doc.as_deref(), Some("The cell door."));
+    assert!(get_entity_decl(&ast, "key").doc.is_none());
+}
+
 #[test]
 fn no_frontmatter() {
     let source = "# Heading\nSome prose.";
@@ -683,6 +1315,207 @@ fn no_frontmatter() {
     assert_eq!(ast.content.len(), 2);
 }
 
+// ── Guards ──
+
+fn guard(node: &ContentNode) -> &ConditionalBlock<ContentNode> {
+    match node {
+        ContentNode::ConditionalBlock(block) => block,
+        other => panic!("expected a guard, got {:?}", other),
+    }
+}
+
+fn guard_errors(diag: &DiagnosticCollector) -> Vec<(u32, &str)> {
+    diag.all().iter().filter(|d| d.code == "URD113").map(|d| (d.span.start_line, d.message.as_str())).collect()
+}
+
+#[test]
+fn guard_holds_its_content() {
+    let nodes = parse_content_only("# Hall\n\n?if demo:\n# Demo Room\n\nOnly in the demo.\n?endif\n\n# Cellar\n");
+    assert_eq!(nodes.len(), 3);
+    let block = guard(&nodes[1]);
+    assert_eq!((block.flag.as_str(), block.negated), ("demo", false));
+    assert!(matches!(&block.children[0], ContentNode::LocationHeading(h) if h.display_name == "Demo Room"));
+    assert!(matches!(&block.children[1], ContentNode::Prose(_)));
+    assert_eq!((block.span.start_line, block.span.end_line), (3, 7));
+    assert!(matches!(&nodes[2], ContentNode::LocationHeading(h) if h.display_name == "Cellar"));
+}
+
+#[test]
+fn guard_closes_open_choice() {
+    let nodes = parse_content_only("* Look around\n  Dust.\n?if !demo:\n* Buy the full game\n?endif\n");
+    let ContentNode::Choice(choice) = &nodes[0] else { panic!("expected a choice") };
+    assert_eq!(choice.content.len(), 1);
+    let block = guard(&nodes[1]);
+    assert_eq!((block.flag.as_str(), block.negated), ("demo", true));
+    assert!(matches!(&block.children[0], ContentNode::Choice(_)));
+}
+
+#[test]
+fn guard_nests_one_level() {
+    let source = "?if demo:\nA.\n?if beta:\nB.\n?endif\nC.\n?endif\n";
+    let (ast, diag) = parse_source(source);
+    assert!(!diag.has_errors());
+    let outer = guard(&ast.as_ref().unwrap().content[0]);
+    assert_eq!(outer.children.len(), 3);
+    assert_eq!(guard(&outer.children[1]).flag, "beta");
+
+    let (_, diag) = parse_source("?if a:\n?if b:\n?if c:\nX.\n?endif\n?endif\n?endif\n");
+    assert_eq!(guard_errors(&diag), [(3, "Guard '?if c:' is nested two levels deep. Guards nest one level.")]);
+}
+
+#[test]
+fn guard_unclosed_or_unmatched() {
+    let (ast, diag) = parse_source("# Hall\n?if demo:\n# Demo Room\n");
+    assert_eq!(guard_errors(&diag), [(2, "Unclosed guard '?if demo:'. Expected '?endif' at the start of a line.")]);
+    assert_eq!(guard(&ast.unwrap().content[1]).children.len(), 1);
+
+    let (_, diag) = parse_source("# Hall\n?endif\n");
+    assert_eq!(guard_errors(&diag), [(2, "'?endif' without a matching '?if'.")]);
+
+    let (_, diag) = parse_source("?if demo\n?endif\n");
+    assert_eq!(guard_errors(&diag), [(1, "Malformed guard '?if demo'. Expected '?if flag:' or '?if !flag:'.")]);
+}
+
+#[test]
+fn guard_only_at_column_zero() {
+    let (_, diag) = parse_source("* Look\n  ?if demo:\n");
+    assert!(guard_errors(&diag).is_empty());
+}
+
+#[test]
+fn guard_holds_back_its_diagnostics() {
+    let (ast, diag) = parse_source("?if demo:\n\t* Choice\n?endif\n");
+    assert!(diag.is_empty());
+    let block = guard(&ast.as_ref().unwrap().content[0]);
+    assert!(!block.diagnostics.is_empty());
+    assert!(block.diagnostics.iter().all(|d| d.code == "URD102" && d.span.start_line == 2));
+}
+
+#[test]
+fn guard_in_frontmatter() {
+    let source = "---\nworld:\n  name: test\n?if demo:\nimport: ./demo.urd.md\nentities:\n  @guide: Guide\n?endif\n---\n";
+    let (ast, diag) = parse_source(source);
+    assert!(!diag.has_errors());
+    let fm = ast.unwrap().frontmatter.unwrap();
+    assert_eq!(fm.entries.len(), 2);
+    assert_eq!(fm.entries[1].key, "?if");
+    let FrontmatterValue::Conditional(block) = &fm.entries[1].value else { panic!("expected a guard") };
+    assert!(matches!(&block.children[0].value, FrontmatterValue::ImportDecl(d) if d.path == "./demo.urd.md"));
+    assert!(matches!(&block.children[1].value, FrontmatterValue::Map(entities) if entities.len() == 1));
+
+    let (_, diag) = parse_source("---\n?if demo:\nimport: ./demo.urd.md\n---\n?endif\n");
+    assert_eq!(guard_errors(&diag)[0].0, 2, "a frontmatter guard closes inside the frontmatter");
+}
+
+// ── Indentation ──
+
+fn indent_warnings(diag: &DiagnosticCollector) -> Vec<(&str, u32, u32, &str)> {
+    diag.all()
+        .iter()
+        .filter(|d| d.code == "URD114" || d.code == "URD115")
+        .map(|d| (d.code.as_str(), d.span.start_line, d.span.start_col, d.message.as_str()))
+        .collect()
+}
+
+#[test]
+fn odd_indent_warns_at_the_stray_space() {
+    let (ast, diag) = parse_source("* Ask\n   @arina: She's seen better days.\n");
+    assert_eq!(
+        indent_warnings(&diag),
+        [("URD114", 2, 3, "Indentation of 3 spaces is not a multiple of 2; interpreted as level 1.")]
+    );
+    assert_eq!(diag.all()[0].span.end_col, 4);
+    assert!(!diag.has_errors());
+    let ContentNode::Choice(choice) = &ast.unwrap().content[0] else { panic!("expected a choice") };
+    assert_eq!(choice.content.len(), 1, "reading is unchanged");
+}
+
+#[test]
+fn over_indented_choice_warns() {
+    let (ast, diag) = parse_source("* Ask\n  * About the ship\n      * About the crew\n");
+    assert_eq!(
+        indent_warnings(&diag),
+        [(
+            "URD115",
+            3,
+            1,
+            "Line is indented 2 levels deeper than the line above it (level 3 after level 1). It is read at level 3."
+        )]
+    );
+    assert_eq!(
+        diag.all()[0].suggestion.as_deref(),
+        Some("Indent it by 4 spaces to place it one level under the line above.")
+    );
+    let ContentNode::Choice(top) = &ast.unwrap().content[0] else { panic!("expected a choice") };
+    let ContentNode::Choice(inner) = &top.content[0] else { panic!("expected a nested choice") };
+    assert!(matches!(&inner.content[0], ContentNode::Choice(c) if c.indent_level == 3));
+
+    // A choice's first child is measured against the choice itself.
+    let (_, diag) = parse_source("* Leave\n    -> end\n");
+    assert_eq!(indent_warnings(&diag).len(), 1);
+}
+
+#[test]
+fn correctly_indented_fixtures_have_no_indent_warnings() {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+    let mut paths: Vec<_> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.to_string_lossy().ends_with(".urd.md"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty());
+    for path in paths {
+        let (_, diag) = parse_source(&std::fs::read_to_string(&path).unwrap());
+        assert!(indent_warnings(&diag).is_empty(), "{}: {:?}", path.display(), indent_warnings(&diag));
+    }
+}
+
+// ── Text Variants ──
+
+#[test]
+fn variant_lines_attach_to_the_line_above() {
+    let source = "# Dock\n\nGulls wheel overhead.\n| Rain lashes the pier.\n| Fog hides the water.\n\n== talk\n\n@arina: Fair winds.\n| Calm seas.\n\n* Ask\n  @arina: Maybe.\n  | Who knows?\n";
+    let (ast, diag) = parse_source(source);
+    assert!(diag.all().is_empty(), "{:?}", diag.all());
+    let content = ast.unwrap().content;
+
+    let ContentNode::Prose(prose) = &content[1] else { panic!("expected prose") };
+    assert_eq!(prose.text, "Gulls wheel overhead.");
+    assert_eq!(prose.variants, ["Rain lashes the pier.", "Fog hides the water."]);
+    assert_eq!(prose.span.start_line, 3, "the span stays on the line as written");
+
+    let ContentNode::EntitySpeech(speech) = &content[3] else { panic!("expected speech") };
+    assert_eq!(speech.text, "Fair winds.");
+    assert_eq!(speech.variants, ["Calm seas."]);
+
+    let ContentNode::Choice(choice) = &content[4] else { panic!("expected a choice") };
+    let ContentNode::EntitySpeech(response) = &choice.content[0] else { panic!("expected speech") };
+    assert_eq!(response.variants, ["Who knows?"]);
+    assert_eq!(choice.content.len(), 1);
+}
+
+#[test]
+fn orphan_variant_warns_and_stays_prose() {
+    // After a blank line, at a different indent, or under a choice.
+    for source in [
+        "@arina: Hello.\n\n| Hi.\n",
+        "@arina: Hello.\n  | Hi.\n",
+        "* Ask\n| Hi.\n",
+    ] {
+        let (ast, diag) = parse_source(source);
+        let warnings: Vec<_> = diag.all().iter().filter(|d| d.code == "URD116").collect();
+        assert_eq!(warnings.len(), 1, "{:?}", source);
+        assert_eq!(
+            warnings[0].message,
+            "Variant line has no line to vary. A '|' line must directly follow a speech line or a line of prose."
+        );
+        assert!(!diag.has_errors());
+        let ContentNode::Prose(kept) = ast.unwrap().content.last().unwrap().clone() else { panic!("{:?}", source) };
+        assert_eq!(kept.text, "| Hi.");
+    }
+}
+
 // ── Error Recovery Tests ──
 
 #[test]
@@ -816,6 +1649,51 @@ fn span_after_frontmatter() {
     }
 }
 
+/// `(start_col, end_col)` of each of a comparison's or set effect's parts.
+fn operand_columns(spans: &OperandSpans) -> [(u32, u32); 4] {
+    [spans.entity, spans.property, spans.operator, spans.value]
+}
+
+#[test]
+fn span_condition_operands() {
+    let pc = property_comparison_of("? not  @guard.mood   ==  hostile");
+    assert_eq!(operand_columns(&pc.spans), [(8, 14), (15, 19), (22, 24), (26, 33)]);
+
+    // A bare read has its property for operator and value.
+    let pc = property_comparison_of("? !player.awake");
+    assert_eq!(operand_columns(&pc.spans), [(4, 10), (11, 16), (11, 16), (11, 16)]);
+
+    let source = "* Choice\n  ? any:\n    @guard.trust > 50";
+    let ContentNode::Choice(c) = &parse_content_only(source)[0] else { panic!("expected Choice") };
+    let ContentNode::OrConditionBlock(oc) = &c.content[0] else { panic!("expected OrConditionBlock") };
+    let ConditionExpr::PropertyComparison(pc) = &oc.conditions[0] else { panic!("expected PropertyComparison") };
+    assert_eq!(pc.spans.value_span(&pc.span).start_line, 3);
+    assert_eq!(operand_columns(&pc.spans), [(5, 11), (12, 17), (18, 19), (20, 22)]);
+}
+
+#[test]
+fn span_set_effect_operands() {
+    let ContentNode::Choice(c) = &parse_content_only("* Choice\n  > @guard.trust + 5")[0] else {
+        panic!("expected Choice")
+    };
+    let ContentNode::Effect(e) = &c.content[0] else { panic!("expected Effect") };
+    let EffectType::Set { spans, .. } = &e.effect_type else { panic!("expected Set") };
+    assert_eq!(spans.value_span(&e.span).start_line, 2);
+    assert_eq!(operand_columns(spans), [(5, 11), (12, 17), (18, 19), (20, 21)]);
+}
+
+#[test]
+fn span_operands_count_bytes() {
+    // `é` is two bytes, so the value starts two columns later than its
+    // character count suggests.
+    let pc = property_comparison_of("? @guard.humeur_été == calme");
+    assert_eq!(operand_columns(&pc.spans), [(3, 9), (10, 22), (23, 25), (26, 31)]);
+
+    let ContentNode::Effect(e) = first_node("> @guard.humeur_été = calme") else { panic!("expected Effect") };
+    let EffectType::Set { spans, .. } = &e.effect_type else { panic!("expected Set") };
+    assert_eq!(operand_columns(spans), [(3, 9), (10, 22), (23, 24), (25, 30)]);
+}
+
 // ── Negative Tests: Grammar Rejections ──
 
 #[test]
@@ -1637,7 +2515,8 @@ rule test_rule:
     assert_eq!(select.where_clauses.len(), 1, "select should have one where clause");
     match &select.where_clauses[0] {
         ConditionExpr::PropertyComparison(pc) => {
-            assert_eq!(pc.entity_ref, "target");
+            // The bare select variable is read as `$target`.
+            assert_eq!(pc.entity_ref, "$target");
             assert_eq!(pc.property, "prize");
             assert_eq!(pc.operator, "!=");
             assert_eq!(pc.value, "car");
@@ -1651,7 +2530,7 @@ fn target_effect_parses_as_set() {
     match first_node("> target.state = open") {
         ContentNode::Effect(e) => {
             match &e.effect_type {
-                EffectType::Set { target_prop, operator, value_expr } => {
+                EffectType::Set { target_prop, operator, value_expr, .. } => {
                     assert_eq!(target_prop, "target.state");
                     assert_eq!(operator, "=");
                     assert_eq!(value_expr, "open");
@@ -0,0 +1,82 @@
+/// Tests for the JSON payloads returned to JavaScript hosts.
+///
+/// The lamp world has one dependency across constructs: rule
+/// `keeper_lights` writes `Lamp.lit`, and the `chat` choice reads it. The
+/// property index in the payload must show both sides.
+
+use serde_json::Value;
+use urd_compiler::payload::{self, compile_full};
+
+const LAMP_WORLD: &str = "---\nworld:\n  name: lamp-room\n  start: hall\ntypes:\n  Lamp [interactable]:\n    \
+                          lit: bool = false\nentities:\n  @lamp: Lamp\n  @keeper: Lamp\n---\n\n# Hall\n\n\
+                          [@lamp, @keeper]\n\nrule keeper_lights:\n  actor: @keeper action light\n  \
+                          selects target from [@lamp]\n  > target.lit = true\n\n== chat\n\n\
+                          + Read by the lamp\n  ? @lamp.lit == true\n  -> end\n";
+
+fn lamp_property(payload: &Value) -> &Value {
+    payload["property_index"]["properties"]
+        .as_array()
+        .expect("properties array")
+        .iter()
+        .find(|p| p["entity_type"] == "Lamp" && p["property"] == "lit")
+        .expect("Lamp.lit indexed")
+}
+
+// ── Analysis payload ──
+
+#[test]
+fn choice_condition_reads_what_a_rule_writes() {
+    let payload = compile_full("lamp.urd.md", LAMP_WORLD, true);
+    assert_eq!(payload["success"], true, "{}", payload["diagnostics"]);
+    assert!(payload["world"].is_string());
+
+    let lit = lamp_property(&payload);
+    assert_eq!((lit["read_count"].as_u64(), lit["write_count"].as_u64()), (Some(1), Some(1)));
+    assert_eq!(lit["orphaned"], Value::Null);
+
+    let facts = &payload["facts"];
+    let read = lit["read_indices"][0].as_u64().unwrap() as usize;
+    let write = lit["write_indices"][0].as_u64().unwrap() as usize;
+    assert_eq!(facts["reads"][read]["site"]["kind"], "choice");
+    assert_eq!(facts["writes"][write]["site"]["kind"], "rule");
+    assert_eq!(facts["choices"][0]["condition_reads"], serde_json::json!([read]));
+    assert_eq!(facts["rules"][0]["effect_writes"], serde_json::json!([write]));
+}
+
+#[test]
+fn analysis_fields_are_opt_in() {
+    let payload = compile_full("lamp.urd.md", LAMP_WORLD, false);
+    assert_eq!(payload["success"], true);
+    let keys: Vec<&str> = payload.as_object().unwrap().keys().map(String::as_str).collect();
+    assert_eq!(keys, ["success", "world", "diagnostics"]);
+}
+
+#[test]
+fn analysis_fields_are_null_before_link() {
+    let payload = compile_full("broken.urd.md", "---\nworld:\n  name: broken\n", true);
+    assert_eq!(payload["success"], false);
+    assert_eq!(payload["world"], Value::Null);
+    assert_eq!(payload["facts"], Value::Null);
+    assert_eq!(payload["property_index"], Value::Null);
+    assert_eq!(payload["diagnostics"][0]["code"], "URD101");
+    assert_eq!(payload["diagnostics"][0]["span"]["file"], "broken.urd.md");
+}
+
+// ── Errors ──
+
+#[test]
+fn empty_filename_is_an_error_object() {
+    let payload = compile_full("  ", LAMP_WORLD, true);
+    assert_eq!(payload["success"], false);
+    assert_eq!(payload["error"]["kind"], "invalid_argument");
+    assert_eq!(payload["diagnostics"], serde_json::json!([]));
+}
+
+#[test]
+fn panic_becomes_an_error_object() {
+    let payload = payload::guarded(|| panic!("index out of range"));
+    assert_eq!(payload["success"], false);
+    assert_eq!(payload["world"], Value::Null);
+    assert_eq!(payload["error"]["kind"], "panic");
+    assert_eq!(payload["error"]["message"], "The compiler panicked: index out of range");
+}
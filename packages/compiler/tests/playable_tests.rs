@@ -0,0 +1,110 @@
+/// Tests for the playable HTML export.
+///
+/// The page is checked as text: the embedded world must parse back to the
+/// emitted JSON, no world string may close its `<script>` element, and
+/// worlds the walker cannot run are refused with what they use.
+
+use serde_json::Value as Json;
+use urd_compiler::playable::{self, PlayError};
+use urd_compiler::{compile, compile_source, CompilationResult};
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+/// The text of the `<script type="application/json">` element with `id`.
+fn embedded<'a>(page: &'a str, id: &str) -> &'a str {
+    let open = format!("<script type=\"application/json\" id=\"{}\">\n", id);
+    let start = page.find(&open).unwrap_or_else(|| panic!("no {} element", id)) + open.len();
+    let end = start + page[start..].find("\n</script>").unwrap();
+    &page[start..end]
+}
+
+fn world_json(result: &CompilationResult) -> Json {
+    serde_json::from_str(result.world.as_ref().unwrap()).unwrap()
+}
+
+const CELL: &str = "---\nworld:\n  name: cell\n  start: cell\ntypes:\n  Guard [interactable]:\n    mood: enum(wary, calm) = wary\n\
+    entities:\n  @guard: Guard\n---\n# Cell\n\n{description}\n\n[@guard]\n\n== talk\n\n@guard: What?\n\n\
+    * Calm down\n  > @guard.mood = calm\n+ Wait\n\n-> out: Yard\n\n# Yard\n\nOpen sky.\n\n-> in: Cell\n";
+
+fn cell(description: &str) -> CompilationResult {
+    compile_source("cell.urd.md", &CELL.replace("{description}", description))
+}
+
+#[test]
+fn page_embeds_the_compiled_world() {
+    let result = compile(&fixture_path("tavern-scene.urd.md"));
+    let page = playable::from_compilation(&result).unwrap();
+    assert!(page.starts_with("<!DOCTYPE html>\n"));
+    assert!(page.contains("<title>the-rusty-anchor</title>"));
+
+    let world: Json = serde_json::from_str(embedded(&page, "urd-world")).unwrap();
+    assert_eq!(world, world_json(&result));
+    let index: Json = serde_json::from_str(embedded(&page, "urd-play-index")).unwrap();
+    assert_eq!(index["sections"]["the-rusty-anchor"], serde_json::json!(["tavern-scene/topics"]));
+    assert_eq!(index["location_names"]["the-harbor"], "The Harbor");
+}
+
+#[test]
+fn script_closers_in_the_world_are_escaped() {
+    let result = cell("A note reads </script><script>alert(1)</script> & <b>more</b>.");
+    assert!(result.success, "{:?}", result.diagnostics.all());
+    let page = playable::from_compilation(&result).unwrap();
+
+    // Only the page's own three script elements close.
+    assert_eq!(page.matches("</script>").count(), 3);
+    assert!(!page.contains("alert(1)</script>"));
+    let text = embedded(&page, "urd-world");
+    assert!(text.contains("\\u003c/script\\u003e"));
+    assert!(text.contains("\\u0026 \\u003cb\\u003e"));
+
+    let world: Json = serde_json::from_str(text).unwrap();
+    assert_eq!(
+        world["locations"]["cell"]["description"],
+        "A note reads </script><script>alert(1)</script> & <b>more</b>."
+    );
+}
+
+#[test]
+fn world_with_rules_is_refused() {
+    let result = compile(&fixture_path("monty-hall.urd.md"));
+    assert!(result.success);
+    let error = playable::from_compilation(&result).unwrap_err();
+    assert_eq!(
+        error,
+        PlayError::Unsupported(vec!["`> reveal` effects".to_string(), "rules".to_string(), "sequences".to_string()])
+    );
+    assert_eq!(
+        error.to_string(),
+        "The playable export cannot run this world. It uses `> reveal` effects, rules, sequences, \
+         which only a full runtime supports."
+    );
+}
+
+#[test]
+fn unsupported_features_come_from_requires_and_hooks() {
+    let world = world_json(&compile(&fixture_path("sunken-citadel.urd.md")));
+    assert_eq!(
+        playable::unsupported_features(&world),
+        ["`> destroy` effects", "`> reveal` effects", "`? any:` blocks", "rules", "sequences"]
+    );
+    let hooks = world_json(&compile(&fixture_path("location-hooks.urd.md")));
+    assert!(playable::unsupported_features(&hooks).contains(&"location hooks".to_string()));
+    assert!(playable::unsupported_features(&world_json(&cell("A damp cell."))).is_empty());
+}
+
+#[test]
+fn failed_compile_has_no_page() {
+    let result = compile(&fixture_path("negative-type-mismatch.urd.md"));
+    assert_eq!(playable::from_compilation(&result), Err(PlayError::CompilationFailed));
+}
+
+#[test]
+fn page_is_the_same_on_every_export() {
+    let first = playable::from_compilation(&cell("A damp cell.")).unwrap();
+    let second = playable::from_compilation(&cell("A damp cell.")).unwrap();
+    assert_eq!(first, second);
+    let edited = playable::from_compilation(&cell("A dry cell.")).unwrap();
+    assert_ne!(first, edited);
+}
@@ -0,0 +1,183 @@
+/// Tests for `urd.toml` project manifests: discovery, parsing, profiles,
+/// and the precedence of command-line flags.
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use urd_compiler::project::{find_manifest, parse_manifest, Manifest, ProjectOptions};
+
+// ── Helpers ──
+
+const MANIFEST: &str = r#"
+# The harbour project.
+entry = "src/main.urd.md"
+out = "dist/world.urd.json"
+include-docs = true
+cache-dir = ".urd-cache"
+
+[profile.release]
+include-docs = false   # no author notes in shipped builds
+numeric-ids = true
+
+[profile.debug]
+timings = true
+"#;
+
+fn parse(source: &str) -> Manifest {
+    let (manifest, warnings) = parse_manifest(source).unwrap();
+    assert!(warnings.is_empty(), "{:?}", warnings);
+    manifest
+}
+
+// ── Discovery ──
+
+#[test]
+fn manifest_found_from_subdirectory() {
+    let files: BTreeSet<PathBuf> = [PathBuf::from("/work/harbour/urd.toml")].into();
+    let is_file = |p: &Path| files.contains(p);
+
+    let found = find_manifest(Path::new("/work/harbour/src/scenes"), is_file);
+    assert_eq!(found, Some(PathBuf::from("/work/harbour/urd.toml")));
+    assert_eq!(find_manifest(Path::new("/work/harbour"), is_file), found);
+    assert_eq!(find_manifest(Path::new("/work/other"), is_file), None);
+}
+
+#[test]
+fn nearest_manifest_wins() {
+    let files: BTreeSet<PathBuf> =
+        [PathBuf::from("/work/urd.toml"), PathBuf::from("/work/harbour/urd.toml")].into();
+    let found = find_manifest(Path::new("/work/harbour/src"), |p| files.contains(p));
+    assert_eq!(found, Some(PathBuf::from("/work/harbour/urd.toml")));
+}
+
+#[test]
+fn manifest_found_on_disk() {
+    let dir = std::env::temp_dir().join(format!("urd-project-{}", std::process::id()));
+    let nested = dir.join("src").join("scenes");
+    std::fs::create_dir_all(&nested).unwrap();
+    std::fs::write(dir.join("urd.toml"), MANIFEST).unwrap();
+
+    let found = find_manifest(&nested, |p| p.is_file());
+    assert_eq!(found, Some(dir.join("urd.toml")));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+// ── Parsing ──
+
+#[test]
+fn manifest_fields_parsed() {
+    let manifest = parse(MANIFEST);
+    assert_eq!(manifest.entry.as_deref(), Some("src/main.urd.md"));
+    assert_eq!(manifest.out.as_deref(), Some("dist/world.urd.json"));
+    assert_eq!(manifest.options.include_docs, Some(true));
+    assert_eq!(manifest.options.cache_dir.as_deref(), Some(".urd-cache"));
+    assert_eq!(manifest.options.numeric_ids, None);
+    assert_eq!(manifest.profiles.keys().collect::<Vec<_>>(), ["release", "debug"]);
+}
+
+#[test]
+fn define_is_a_list_of_flags() {
+    let manifest = parse("define = [\"demo\", \"beta\"]\n\n[profile.full]\ndefine = []\n");
+    assert_eq!(manifest.options.define, Some(vec!["demo".to_string(), "beta".to_string()]));
+    assert_eq!(manifest.options_for(Some("full")).unwrap().define, Some(Vec::new()));
+
+    let error = parse_manifest("define = \"demo\"\n").unwrap_err();
+    assert!(error.message.contains("'define' must be an array of strings, found a string"), "{}", error);
+    let error = parse_manifest("define = [\"demo\", true]\n").unwrap_err();
+    assert!(error.message.contains("found a boolean"), "{}", error);
+}
+
+#[test]
+fn string_forms_and_escapes() {
+    let manifest = parse("entry = 'C:\\stories\\main.urd.md'\nout = \"dist/\\u00e9t\\u00e9.json\"\n");
+    assert_eq!(manifest.entry.as_deref(), Some("C:\\stories\\main.urd.md"));
+    assert_eq!(manifest.out.as_deref(), Some("dist/été.json"));
+}
+
+#[test]
+fn unknown_keys_and_tables_warn() {
+    let source = "entry = \"main.urd.md\"\nsort = \"alpha\"\nlimits = [\n  1,\n  2.5,\n]\n\n[lsp]\nport = 9000\n\n[profile.release]\nstrict = true\n";
+    let (manifest, warnings) = parse_manifest(source).unwrap();
+    assert_eq!(manifest.entry.as_deref(), Some("main.urd.md"));
+
+    let lines: Vec<usize> = warnings.iter().map(|w| w.line).collect();
+    assert_eq!(lines, [2, 3, 8, 12]);
+    assert!(warnings[0].message.contains("'sort'"), "{}", warnings[0]);
+    assert!(warnings[2].message.contains("[lsp]"), "{}", warnings[2]);
+    assert!(warnings[3].message.contains("profile 'release'"), "{}", warnings[3]);
+}
+
+#[test]
+fn malformed_manifest_is_one_clear_error() {
+    let cases = [
+        ("entry = \"main.urd.md\"\nout = dist/world.json\n", 2, "Strings need quotes"),
+        ("entry = \"main.urd.md\n", 1, "Unterminated string"),
+        ("[profile.release\nnumeric-ids = true\n", 1, "']'"),
+        ("entry = \"a\"\nentry = \"b\"\n", 2, "set twice"),
+        ("include-docs = \"yes\"\n", 1, "'include-docs' must be true or false, found a string"),
+        ("entry \"main.urd.md\"\n", 1, "Expected '='"),
+        ("numeric-ids = true false\n", 1, "own line"),
+        ("[profile.a]\n[profile.a]\n", 2, "defined twice"),
+    ];
+    for (source, line, fragment) in cases {
+        let error = parse_manifest(source).unwrap_err();
+        assert_eq!(error.line, line, "{:?}: {}", source, error);
+        assert!(error.message.contains(fragment), "{:?}: {}", source, error);
+        assert!(error.to_string().starts_with(&format!("line {}: ", line)));
+    }
+}
+
+// ── Profiles and precedence ──
+
+#[test]
+fn profile_overrides_top_level() {
+    let manifest = parse(MANIFEST);
+
+    let base = manifest.options_for(None).unwrap();
+    assert_eq!(base.include_docs, Some(true));
+    assert_eq!(base.numeric_ids, None);
+
+    let release = manifest.options_for(Some("release")).unwrap();
+    assert_eq!(release.include_docs, Some(false));
+    assert_eq!(release.numeric_ids, Some(true));
+    // Values the profile leaves alone come from the top level.
+    assert_eq!(release.cache_dir.as_deref(), Some(".urd-cache"));
+    assert_eq!(release.timings, None);
+}
+
+#[test]
+fn unknown_profile_lists_defined_ones() {
+    let error = parse(MANIFEST).options_for(Some("staging")).unwrap_err();
+    assert!(error.contains("'staging'") && error.contains("release, debug"), "{}", error);
+
+    let error = parse("entry = \"main.urd.md\"\n").options_for(Some("release")).unwrap_err();
+    assert!(error.contains("no profiles"), "{}", error);
+}
+
+#[test]
+fn flags_override_manifest_and_profile() {
+    let manifest = parse(MANIFEST);
+    let flags = ProjectOptions {
+        include_docs: Some(true),
+        cache_dir: Some("/tmp/cache".into()),
+        ..ProjectOptions::default()
+    };
+
+    let options = manifest.options_for(Some("release")).unwrap().overlay(&flags);
+    assert_eq!(options.include_docs, Some(true), "Flag beats the profile");
+    assert_eq!(options.cache_dir.as_deref(), Some("/tmp/cache"), "Flag beats the top level");
+    assert_eq!(options.numeric_ids, Some(true), "Profile kept where no flag is given");
+}
+
+#[test]
+fn paths_are_relative_to_the_manifest() {
+    let mut manifest = parse(MANIFEST);
+    manifest.rebase(Path::new("/work/harbour"));
+    assert_eq!(manifest.entry.as_deref(), Some("/work/harbour/src/main.urd.md"));
+    assert_eq!(manifest.out.as_deref(), Some("/work/harbour/dist/world.urd.json"));
+    assert_eq!(manifest.options.cache_dir.as_deref(), Some("/work/harbour/.urd-cache"));
+
+    let mut absolute = parse("entry = \"/elsewhere/main.urd.md\"\n");
+    absolute.rebase(Path::new("/work/harbour"));
+    assert_eq!(absolute.entry.as_deref(), Some("/elsewhere/main.urd.md"));
+}
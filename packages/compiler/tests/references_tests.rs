@@ -0,0 +1,79 @@
+/// Tests for the ReferenceIndex.
+///
+/// Each test compiles a fixture through the full pipeline and builds the
+/// index from the annotated graph, then checks the references recorded
+/// against DefinitionIndex keys.
+
+use urd_compiler::compile;
+use urd_compiler::references::ReferenceIndex;
+
+fn fixture_path(name: &str) -> String {
+    let base = env!("CARGO_MANIFEST_DIR");
+    format!("{}/tests/fixtures/{}", base, name)
+}
+
+fn fixture_refs(name: &str) -> ReferenceIndex {
+    let result = compile(&fixture_path(name));
+    let graph = result.graph.expect("graph should be present after LINK");
+    let symbol_table = result.symbol_table.expect("symbol table should be present after LINK");
+    ReferenceIndex::build(&graph, &symbol_table)
+}
+
+/// (file, line) for each reference to `key`.
+fn sites(index: &ReferenceIndex, key: &str) -> Vec<(String, u32)> {
+    index.get(key).iter().map(|s| (s.file.clone(), s.start_line)).collect()
+}
+
+// ── Tests ──
+
+#[test]
+fn entity_references_span_files() {
+    let index = fixture_refs("code-lens/main.urd.md");
+    let mut found = sites(&index, "entity:@lantern");
+    found.sort();
+    assert_eq!(
+        found,
+        vec![
+            ("cast.urd.md".to_string(), 15),
+            ("main.urd.md".to_string(), 13),
+            ("main.urd.md".to_string(), 21),
+        ]
+    );
+}
+
+#[test]
+fn unreferenced_entity_has_no_references() {
+    let index = fixture_refs("code-lens/main.urd.md");
+    assert!(index.get("entity:@spare_wick").is_empty());
+    assert!(index.get("entity:@nobody").is_empty());
+}
+
+#[test]
+fn type_references_are_entity_declarations() {
+    let index = fixture_refs("code-lens/main.urd.md");
+    assert_eq!(index.get("type:Lamp").len(), 2);
+    assert_eq!(sites(&index, "type:Keeper"), vec![("cast.urd.md".to_string(), 10)]);
+}
+
+#[test]
+fn location_references_are_exits() {
+    let index = fixture_refs("code-lens/main.urd.md");
+    assert_eq!(sites(&index, "location:store-room"), vec![("main.urd.md".to_string(), 12)]);
+    assert!(index.get("location:gallery").is_empty());
+}
+
+#[test]
+fn section_references_are_jumps() {
+    let index = fixture_refs("interrogation/main.urd.md");
+    assert_eq!(index.get("section:main/confession").len(), 1);
+    assert!(index.get("section:main/approach").is_empty());
+}
+
+#[test]
+fn exit_jump_is_not_a_location_reference() {
+    let index = fixture_refs("inlay-hints.urd.md");
+    // `-> exit:north` on line 33 leaves the gate; only the return exit uses it.
+    assert_eq!(sites(&index, "location:gate"), vec![("inlay-hints.urd.md".to_string(), 39)]);
+    assert_eq!(sites(&index, "location:courtyard"), vec![("inlay-hints.urd.md".to_string(), 15)]);
+    assert_eq!(sites(&index, "section:inlay-hints/north"), vec![("inlay-hints.urd.md".to_string(), 23)]);
+}
@@ -0,0 +1,133 @@
+/// Tests for diagnostic rendering (`diagnostics::render`), the JSON form
+/// (`DiagnosticCollector::to_json`), and the `keep_sources` option that
+/// supplies source text.
+
+use std::collections::HashMap;
+
+use urd_compiler::diagnostics::render;
+use urd_compiler::diagnostics::{Diagnostic, DiagnosticCollector, Severity};
+use urd_compiler::span::Span;
+use urd_compiler::test_support::MemoryFileReader;
+use urd_compiler::{compile_source_with_options, CompileOptions};
+
+fn diagnostic(start: (u32, u32), end: (u32, u32), suggestion: Option<&str>) -> Diagnostic {
+    Diagnostic {
+        severity: Severity::Error,
+        code: "URD301".to_string(),
+        message: "Unresolved entity reference '@ghost'.".to_string(),
+        span: Span::new("tavern.urd.md".to_string(), start.0, start.1, end.0, end.1),
+        suggestion: suggestion.map(str::to_string),
+        fix: None,
+        related: Vec::new(),
+    }
+}
+
+// ── Pretty ──
+
+#[test]
+fn underline_spans_the_columns() {
+    let source = "# Tavern\n\n  @ghost: Hello.\n";
+    assert_eq!(
+        render::pretty(&diagnostic((3, 3), (3, 9), None), Some(source)),
+        "error[URD301]: Unresolved entity reference '@ghost'.\n\
+         \x20--> tavern.urd.md:3:3\n\
+         \x20 |\n\
+         3 |   @ghost: Hello.\n\
+         \x20 |   ^^^^^^"
+    );
+}
+
+#[test]
+fn tabs_are_expanded_before_the_caret() {
+    // A tab and two characters: `@ghost` starts at display column 6.
+    let source = "\tab@ghost\n";
+    let rendered = render::pretty(&diagnostic((1, 4), (1, 10), None), Some(source));
+    let lines: Vec<&str> = rendered.lines().collect();
+    assert_eq!(lines[3], "1 |     ab@ghost");
+    assert_eq!(lines[4], "  |       ^^^^^^");
+}
+
+#[test]
+fn multi_line_span_shows_first_and_last_lines() {
+    let source = "== talk\n\n* Ask\n  ? @ghost.here\n  -> end\n";
+    let rendered = render::pretty(&diagnostic((3, 1), (5, 9), None), Some(source));
+    assert_eq!(
+        rendered.lines().skip(3).collect::<Vec<_>>(),
+        ["3 | * Ask", "  | ^^^^^", "...", "5 |   -> end", "  |   ^^^^^^"]
+    );
+}
+
+#[test]
+fn suggestion_follows_on_a_help_line() {
+    let source = "  @ghost: Hello.\n";
+    let rendered = render::pretty(&diagnostic((1, 3), (1, 9), Some("Did you mean '@host'?")), Some(source));
+    assert_eq!(rendered.lines().last(), Some("  = help: Did you mean '@host'?"));
+}
+
+#[test]
+fn gutter_widens_for_the_last_line_number() {
+    let source = "line\n".repeat(12);
+    let rendered = render::pretty(&diagnostic((9, 1), (10, 5), None), Some(&source));
+    let lines: Vec<&str> = rendered.lines().collect();
+    assert_eq!(lines[1], "  --> tavern.urd.md:9:1");
+    assert_eq!(lines[3], " 9 | line");
+    assert_eq!(lines[5], "10 | line");
+}
+
+// ── Fallback ──
+
+#[test]
+fn without_source_pretty_is_plain() {
+    let d = diagnostic((3, 3), (3, 9), Some("Did you mean '@host'?"));
+    let plain = "[error] tavern.urd.md:3:3: Unresolved entity reference '@ghost'. (URD301)";
+    assert_eq!(render::plain(&d), plain);
+    assert_eq!(render::pretty(&d, None), plain);
+    // A span past the end of the source, or a synthetic one, also falls back.
+    assert_eq!(render::pretty(&d, Some("# Tavern\n")), plain);
+    let synthetic = diagnostic((0, 0), (0, 0), None);
+    assert_eq!(render::pretty(&synthetic, Some("# Tavern\n")), render::plain(&synthetic));
+}
+
+// ── JSON ──
+
+#[test]
+fn json_lists_every_diagnostic_in_sorted_order() {
+    let mut collector = DiagnosticCollector::new();
+    collector.emit(diagnostic((3, 3), (3, 9), Some("Did you mean '@guard'?")));
+    collector.warning("URD430", "Location 'vault' is unreachable.", Span::new("tavern.urd.md".to_string(), 1, 1, 1, 8));
+    assert_eq!(
+        collector.to_json().to_string(),
+        concat!(
+            r#"[{"severity":"warning","code":"URD430","message":"Location 'vault' is unreachable.","#,
+            r#""span":{"file":"tavern.urd.md","start_line":1,"start_col":1,"end_line":1,"end_col":8}},"#,
+            r#"{"severity":"error","code":"URD301","message":"Unresolved entity reference '@ghost'.","#,
+            r#""span":{"file":"tavern.urd.md","start_line":3,"start_col":3,"end_line":3,"end_col":9},"#,
+            r#""suggestion":"Did you mean '@guard'?"}]"#,
+        )
+    );
+    assert_eq!(DiagnosticCollector::new().to_json().to_string(), "[]");
+}
+
+// ── Kept sources ──
+
+#[test]
+fn keep_sources_keys_every_file_by_span_path() {
+    let mut files = HashMap::new();
+    files.insert(
+        "main.urd.md".to_string(),
+        "---\nimport: ./rooms/cellar.urd.md\nworld:\n  name: w\n  start: hall\n---\n\n# Hall\n".to_string(),
+    );
+    files.insert("rooms/cellar.urd.md".to_string(), "# Cellar\n\n[@ghost]\n".to_string());
+    let reader = MemoryFileReader::new(files.clone());
+    let options = CompileOptions { keep_sources: true, ..CompileOptions::default() };
+    let result = compile_source_with_options("main.urd.md", &files["main.urd.md"], &reader, &options);
+
+    let sources = result.sources.as_ref().expect("sources kept");
+    assert_eq!(sources.keys().collect::<Vec<_>>(), ["main.urd.md", "rooms/cellar.urd.md"]);
+    let d = result.diagnostics.sorted().into_iter().find(|d| d.code == "URD301").expect("URD301");
+    let rendered = render::pretty(d, sources.get(&d.span.file).map(String::as_str));
+    assert!(rendered.contains("3 | [@ghost]"), "{}", rendered);
+
+    let default = compile_source_with_options("main.urd.md", &files["main.urd.md"], &reader, &CompileOptions::default());
+    assert!(default.sources.is_none());
+}
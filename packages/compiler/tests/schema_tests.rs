@@ -0,0 +1,190 @@
+/// Tests for the exported world JSON Schema.
+///
+/// Compiled output is validated against the schema throughout the emit and
+/// end-to-end suites. These tests check the schema itself: that it is the
+/// published file, and that it accepts and rejects hand-written worlds for
+/// the shapes no fixture may happen to exercise.
+
+use serde_json::{json, Value as Json};
+use urd_compiler::schema;
+
+// ── Helpers ──
+
+fn validator() -> jsonschema::Validator {
+    let schema: Json = serde_json::from_str(schema::world_schema()).expect("schema is JSON");
+    jsonschema::validator_for(&schema).expect("schema compiles")
+}
+
+fn errors(instance: &Json) -> Vec<String> {
+    validator().iter_errors(instance).map(|e| e.to_string()).collect()
+}
+
+/// A world using every optional block.
+fn full_world() -> Json {
+    json!({
+        "world": { "name": "schema-test", "urd": "1", "start": "hall", "entry": "game" },
+        "types": {
+            "Door": {
+                "traits": ["interactable"],
+                "properties": {
+                    "state": { "type": "enum", "values": ["open", "closed"], "default": "closed" }
+                }
+            }
+        },
+        "entities": {
+            "door": { "type": "Door" },
+            "lamp": { "type": "Door" }
+        },
+        "locations": {
+            "hall": {
+                "description": "A long hall.",
+                "contains": ["door"],
+                "exits": { "north": { "to": "vault", "condition": "door.state == open" } }
+            },
+            "vault": { "exits": { "south": { "to": "hall" } } }
+        },
+        "actions": {
+            "open_door": {
+                "target": "door",
+                "conditions": { "any": ["door.state == closed", "hall.visited"] },
+                "effects": [
+                    { "set": "door.state", "to": "open" },
+                    { "move": "lamp", "to": "vault" },
+                    { "reveal": "door.state" },
+                    { "destroy": "lamp" },
+                    { "spawn": { "id": "key", "type": "Door", "in": "vault" } },
+                    { "end": "escaped" }
+                ]
+            }
+        },
+        "sequences": {
+            "game": {
+                "phases": [
+                    { "id": "explore", "action": "open_door", "advance": "on_action" },
+                    { "id": "wait", "auto": true, "advance": "on_condition door.state == open" },
+                    { "id": "done", "advance": "end" }
+                ]
+            }
+        },
+        "endings": {
+            "escaped": { "description": "You leave the hall behind." },
+            "caught": {}
+        },
+        "dialogue": {
+            "hall/talk": {
+                "id": "hall/talk",
+                "prompt": { "speaker": "door", "text": "Well?" },
+                "conditions": ["door.state == closed"],
+                "choices": [
+                    { "id": "hall/talk/knock", "label": "Knock", "sticky": false, "once": true },
+                    {
+                        "id": "hall/talk/ask",
+                        "label": "Ask",
+                        "sticky": true,
+                        "conditions": { "any": ["door.state == open"] },
+                        "response": { "speaker": "door", "text": "No." },
+                        "goto": "hall/talk"
+                    }
+                ],
+                "on_exhausted": { "text": "Nothing more to say.", "goto": "hall/talk" }
+            }
+        }
+    })
+}
+
+// ── Tests ──
+
+#[test]
+fn schema_is_draft_2020_12() {
+    let schema: Json = serde_json::from_str(schema::world_schema()).unwrap();
+    assert_eq!(schema["$schema"], "https://json-schema.org/draft/2020-12/schema");
+    assert_eq!(schema["required"], json!(["world"]));
+    validator();
+}
+
+#[test]
+fn schema_matches_published_file() {
+    let path = format!("{}/../schema/urd-world-schema.json", env!("CARGO_MANIFEST_DIR"));
+    assert_eq!(schema::world_schema(), std::fs::read_to_string(path).unwrap());
+}
+
+#[test]
+fn full_world_validates() {
+    assert_eq!(errors(&full_world()), Vec::<String>::new());
+}
+
+#[test]
+fn header_only_world_validates() {
+    assert!(errors(&json!({ "world": { "name": "bare", "urd": "1" } })).is_empty());
+}
+
+#[test]
+fn unknown_effect_is_rejected() {
+    let mut world = full_world();
+    world["actions"]["open_door"]["effects"] = json!([{ "toggle": "door.state" }]);
+    assert!(!errors(&world).is_empty());
+}
+
+#[test]
+fn unknown_ending_field_is_rejected() {
+    let mut world = full_world();
+    world["endings"]["caught"] = json!({ "title": "Caught" });
+    assert!(!errors(&world).is_empty());
+}
+
+#[test]
+fn empty_any_is_rejected() {
+    let mut world = full_world();
+    world["actions"]["open_door"]["conditions"] = json!({ "any": [] });
+    assert!(!errors(&world).is_empty());
+}
+
+#[test]
+fn all_with_nested_any_validates() {
+    let mut world = full_world();
+    world["actions"]["open_door"]["conditions"] = json!({ "all": ["hall.visited", { "any": ["door.state == closed"] }] });
+    assert_eq!(errors(&world), Vec::<String>::new());
+}
+
+#[test]
+fn all_nesting_all_or_mixed_any_is_rejected() {
+    let mut world = full_world();
+    world["actions"]["open_door"]["conditions"] = json!({ "all": [{ "all": ["hall.visited"] }] });
+    assert!(!errors(&world).is_empty());
+    world["actions"]["open_door"]["conditions"] = json!({ "any": ["hall.visited", { "any": ["door.state == closed"] }] });
+    assert!(!errors(&world).is_empty());
+    world["actions"]["open_door"]["conditions"] = json!({ "all": [] });
+    assert!(!errors(&world).is_empty());
+}
+
+#[test]
+fn unknown_advance_mode_is_rejected() {
+    let mut world = full_world();
+    world["sequences"]["game"]["phases"][0]["advance"] = json!("eventually");
+    assert!(!errors(&world).is_empty());
+}
+
+#[test]
+fn once_false_is_rejected() {
+    let mut world = full_world();
+    world["dialogue"]["hall/talk"]["choices"][0]["once"] = json!(false);
+    assert!(!errors(&world).is_empty());
+}
+
+#[test]
+fn property_groups_validate() {
+    let mut world = full_world();
+    world["types"]["Door"]["properties"]["lock"] = json!({
+        "pins": { "type": "integer", "min": 0 },
+        "picked": { "type": "boolean", "default": false }
+    });
+    world["entities"]["door"]["properties"] = json!({ "lock": { "pins": 5 } });
+    assert_eq!(errors(&world), Vec::<String>::new());
+}
+
+#[test]
+fn malformed_property_schema_is_not_a_group() {
+    let mut world = full_world();
+    world["types"]["Door"]["properties"]["lock"] = json!({ "type": "integr" });
+    assert!(!errors(&world).is_empty());
+}
@@ -0,0 +1,142 @@
+/// Tests for split output: one JSON document per block group plus a
+/// manifest.
+///
+/// Merge tests check that the union of the parts' blocks equals the
+/// combined `.urd.json`. Manifest tests recompute sizes and hashes.
+
+use std::collections::BTreeMap;
+
+use serde_json::{Map, Value as Json};
+use urd_compiler::emit::EmitOptions;
+use urd_compiler::hash::sha256_hex;
+use urd_compiler::import::OsFileReader;
+use urd_compiler::CompileOptions;
+
+const FIXTURES: &[&str] = &[
+    "two-room-key-puzzle.urd.md",
+    "tavern-scene.urd.md",
+    "monty-hall.urd.md",
+    "sunken-citadel.urd.md",
+    "interrogation/main.urd.md",
+];
+
+const PART_FILES: &[&str] = &[
+    "world.json",
+    "locations.json",
+    "rules.json",
+    "actions.json",
+    "sequences.json",
+    "dialogue.json",
+];
+
+fn fixture_path(name: &str) -> String {
+    let base = env!("CARGO_MANIFEST_DIR");
+    format!("{}/tests/fixtures/{}", base, name)
+}
+
+/// Compile a fixture with the given split flag, returning the combined
+/// output and the parts.
+fn compile_fixture(name: &str, split: bool) -> (String, Option<BTreeMap<String, String>>) {
+    let path = fixture_path(name);
+    let source = std::fs::read_to_string(&path).unwrap();
+    let options = CompileOptions {
+        emit: EmitOptions { split, ..EmitOptions::default() },
+        ..CompileOptions::default()
+    };
+    let result = urd_compiler::compile_source_with_options(&path, &source, &OsFileReader, &options);
+    assert!(result.success, "{} should compile", name);
+    (result.world.unwrap(), result.parts)
+}
+
+fn parse(document: &str) -> Map<String, Json> {
+    match serde_json::from_str(document).unwrap() {
+        Json::Object(map) => map,
+        other => panic!("expected an object, got {}", other),
+    }
+}
+
+// ── Merge ──
+
+#[test]
+fn parts_merge_to_combined_output() {
+    for fixture in FIXTURES {
+        let (combined, parts) = compile_fixture(fixture, true);
+        let parts = parts.expect("split parts");
+
+        let mut merged = Map::new();
+        for file in PART_FILES {
+            for (key, value) in parse(&parts[*file]) {
+                if key == "world_name" || key == "urd" {
+                    continue;
+                }
+                assert!(merged.insert(key.clone(), value).is_none(), "{}: block '{}' in two parts", fixture, key);
+            }
+        }
+        assert_eq!(Json::Object(merged), Json::Object(parse(&combined)), "{}", fixture);
+    }
+}
+
+#[test]
+fn every_part_carries_header() {
+    let (combined, parts) = compile_fixture("monty-hall.urd.md", true);
+    let world_name = parse(&combined)["world"]["name"].clone();
+    let parts = parts.unwrap();
+
+    let mut files: Vec<&str> = PART_FILES.to_vec();
+    files.push("manifest.json");
+    files.sort();
+    assert_eq!(parts.keys().map(|k| k.as_str()).collect::<Vec<_>>(), files);
+
+    for (file, document) in &parts {
+        let json = parse(document);
+        assert_eq!(json["world_name"], world_name, "{}", file);
+        assert_eq!(json["urd"], "1", "{}", file);
+    }
+}
+
+#[test]
+fn absent_blocks_leave_header_only_part() {
+    let (_, parts) = compile_fixture("two-room-key-puzzle.urd.md", true);
+    let sequences = parse(&parts.unwrap()["sequences.json"]);
+    assert_eq!(sequences.keys().collect::<Vec<_>>(), vec!["world_name", "urd"]);
+}
+
+#[test]
+fn split_off_leaves_combined_output_unchanged() {
+    for fixture in FIXTURES {
+        let (plain, no_parts) = compile_fixture(fixture, false);
+        let (combined, _) = compile_fixture(fixture, true);
+        assert!(no_parts.is_none());
+        assert_eq!(plain, combined, "{}", fixture);
+    }
+}
+
+// ── Manifest ──
+
+#[test]
+fn manifest_sizes_and_hashes_verify() {
+    let (_, parts) = compile_fixture("sunken-citadel.urd.md", true);
+    let parts = parts.unwrap();
+    let manifest = parse(&parts["manifest.json"]);
+
+    let entries = manifest["parts"].as_array().unwrap();
+    let listed: Vec<&str> = entries.iter().map(|e| e["file"].as_str().unwrap()).collect();
+    assert_eq!(listed, PART_FILES);
+
+    for entry in entries {
+        let document = &parts[entry["file"].as_str().unwrap()];
+        assert_eq!(entry["bytes"], document.len());
+        assert_eq!(entry["sha256"], sha256_hex(document.as_bytes()));
+    }
+}
+
+// ── Determinism ──
+
+#[test]
+fn split_is_deterministic() {
+    for fixture in FIXTURES {
+        let (_, a) = compile_fixture(fixture, true);
+        let (_, b) = compile_fixture(fixture, true);
+        assert_eq!(a, b, "{}", fixture);
+    }
+}
@@ -0,0 +1,139 @@
+/// Tests for world statistics.
+///
+/// Fixture tests compile through the full pipeline and assert exact counts.
+/// Source-string tests exercise individual metrics in isolation.
+
+use urd_compiler::{compile, compile_source};
+use urd_compiler::stats::{self, WorldStats};
+
+fn fixture_path(name: &str) -> String {
+    let base = env!("CARGO_MANIFEST_DIR");
+    format!("{}/tests/fixtures/{}", base, name)
+}
+
+fn fixture_stats(name: &str) -> WorldStats {
+    let result = compile(&fixture_path(name));
+    stats::from_compilation(&result).expect("stats should be computed")
+}
+
+fn source_stats(source: &str) -> WorldStats {
+    let result = compile_source("test.urd.md", source);
+    stats::from_compilation(&result).expect("stats should be computed")
+}
+
+// ── Two-room key puzzle ──
+
+#[test]
+fn key_puzzle_inventory() {
+    let s = fixture_stats("two-room-key-puzzle.urd.md");
+    assert_eq!(s.locations, 2);
+    assert_eq!(s.exits, 1);
+    assert_eq!(s.entities, 2);
+    assert_eq!(s.entities_by_type.get("Door"), Some(&1));
+    assert_eq!(s.entities_by_type.get("Key"), Some(&1));
+    assert_eq!(s.sections, 1);
+    assert_eq!(s.rules, 0);
+}
+
+#[test]
+fn key_puzzle_choices() {
+    let s = fixture_stats("two-room-key-puzzle.urd.md");
+    assert_eq!(s.choices, 1);
+    assert_eq!(s.sticky_choices, 0);
+    assert_eq!(s.one_shot_choices, 1);
+    assert_eq!(s.avg_choices_per_section(), 1.0);
+    assert_eq!(s.max_choice_depth, 1);
+}
+
+#[test]
+fn key_puzzle_words_conditions_effects() {
+    let s = fixture_stats("two-room-key-puzzle.urd.md");
+    // "A dim stone cell." + "The iron door is locked." + "The cell falls silent."
+    // + "You made it out."
+    assert_eq!(s.prose_words, 17);
+    assert_eq!(s.dialogue_words, 0);
+    // Exit guard + choice guard.
+    assert_eq!(s.conditions, 2);
+    // Set locked + destroy key.
+    assert_eq!(s.effects, 2);
+}
+
+#[test]
+fn key_puzzle_analysis() {
+    let s = fixture_stats("two-room-key-puzzle.urd.md");
+    let a = s.analysis.expect("analysis should be present");
+    assert_eq!(a.unreachable_locations, Some(0));
+    assert_eq!(a.read_never_written, 0);
+    assert_eq!(a.written_never_read, 0);
+}
+
+#[test]
+fn key_puzzle_json_is_stable_keyed() {
+    let s = fixture_stats("two-room-key-puzzle.urd.md");
+    let json = s.to_json();
+    let keys: Vec<&str> = json.as_object().unwrap().keys().map(|k| k.as_str()).collect();
+    assert_eq!(
+        keys,
+        vec![
+            "locations", "exits", "entities", "sections", "choices", "words",
+            "max_choice_depth", "conditions", "effects", "rules", "analysis",
+        ]
+    );
+    assert_eq!(json["entities"]["by_type"]["Door"], 1);
+    assert_eq!(json["choices"]["avg_per_section"], 1.0);
+    assert_eq!(json["words"]["total"], 17);
+    assert_eq!(json["analysis"]["unreachable_locations"], 0);
+}
+
+#[test]
+fn key_puzzle_table_is_aligned() {
+    let table = fixture_stats("two-room-key-puzzle.urd.md").table();
+    let widths: Vec<usize> = table.lines().map(|l| l.chars().count()).collect();
+    assert!(!widths.is_empty());
+    assert!(widths.iter().all(|w| *w == widths[0]), "rows differ in width:\n{}", table);
+    assert!(table.contains("Locations"));
+    assert!(table.contains("Avg per section"));
+}
+
+// ── Word counting ──
+
+#[test]
+fn word_count_ignores_inline_comments() {
+    let s = source_stats(
+        "---\nworld:\n  name: test\n  start: room\ntypes:\n  Npc [interactable]:\n    mood: string\nentities:\n  @guide: Npc\n---\n# Room\n\n[@guide]\n\nThree plain words. // not counted at all\n\n@guide: Two words. // also ignored\n",
+    );
+    assert_eq!(s.prose_words, 3);
+    assert_eq!(s.dialogue_words, 2);
+}
+
+#[test]
+fn word_count_ignores_comment_lines() {
+    let s = source_stats(
+        "---\nworld:\n  name: test\n  start: room\n---\n# Room\n\n// A whole line of commentary here.\nOne two.\n",
+    );
+    assert_eq!(s.prose_words, 2);
+}
+
+// ── Edge cases ──
+
+#[test]
+fn nested_choice_depth() {
+    let s = source_stats(
+        "---\nworld:\n  name: test\n  start: room\n---\n# Room\n\n== talk\n\n* Outer\n  * Inner\n    Deep text.\n",
+    );
+    assert_eq!(s.choices, 2);
+    assert_eq!(s.max_choice_depth, 2);
+}
+
+#[test]
+fn no_sections_average_is_zero() {
+    let s = source_stats("---\nworld:\n  name: test\n  start: room\n---\n# Room\n\nQuiet.\n");
+    assert_eq!(s.sections, 0);
+    assert_eq!(s.avg_choices_per_section(), 0.0);
+}
+
+#[test]
+fn parse_failure_yields_no_stats() {
+    let result = compile_source("test.urd.md", "---\nworld: test\n");
+    assert!(stats::from_compilation(&result).is_none());
+}
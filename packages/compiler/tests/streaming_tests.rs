@@ -0,0 +1,175 @@
+/// Tests for streamed emission through `emit::emit_to_writer()` and the
+/// typed `CompiledWorld` both outputs are written from.
+///
+/// The streamed document must match the in-memory one byte for byte under
+/// every option, and must never hold the whole world: a counting allocator
+/// tracks live bytes on the test's own thread. Both must match the document
+/// pretty printed in one piece, as EMIT has always written it.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use urd_compiler::emit::{self, EmitOptions, Format};
+use urd_compiler::test_support::{synthetic_world, MemoryFileReader, ENTRY_FILE};
+use urd_compiler::{compile_source_with_options, compile_with_options, CompilationResult, CompileOptions, CompiledWorld};
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn streamed(result: &CompilationResult, options: &EmitOptions) -> String {
+    let (graph, symbol_table) = (result.graph.as_ref().unwrap(), result.symbol_table.as_ref().unwrap());
+    let mut bytes = Vec::new();
+    emit::emit_to_writer(graph, symbol_table, options, &mut bytes).unwrap();
+    String::from_utf8(bytes).unwrap()
+}
+
+fn all_options() -> Vec<EmitOptions> {
+    let mut all = Vec::new();
+    for bits in 0..32 {
+        all.push(EmitOptions {
+            externalize_strings: bits & 1 != 0,
+            include_docs: bits & 2 != 0,
+            numeric_ids: bits & 4 != 0,
+            split: false,
+            build_output_map: false,
+            include_generator: bits & 8 != 0,
+            format: if bits & 16 != 0 { Format::V2 } else { Format::V1 },
+        });
+    }
+    all
+}
+
+// ── Byte identity ──
+
+#[test]
+fn streamed_fixtures_match_in_memory_output() {
+    let fixtures = ["sunken-citadel.urd.md", "doc-comments.urd.md", "parameterised-sections.urd.md"];
+    for fixture in fixtures {
+        for emit in all_options() {
+            let options = CompileOptions { emit: emit.clone(), ..CompileOptions::default() };
+            let result = compile_with_options(&fixture_path(fixture), &options);
+            assert!(result.success, "{} should compile", fixture);
+            assert_eq!(&streamed(&result, &emit), result.world.as_ref().unwrap(), "{} with {:?}", fixture, emit);
+        }
+    }
+}
+
+#[test]
+fn typed_world_serialises_as_one_pretty_printed_document() {
+    let fixtures = ["sunken-citadel.urd.md", "doc-comments.urd.md", "parameterised-sections.urd.md"];
+    for fixture in fixtures {
+        for emit in all_options() {
+            let options = CompileOptions { emit: emit.clone(), ..CompileOptions::default() };
+            let result = compile_with_options(&fixture_path(fixture), &options);
+            let (world, value) = (result.world.as_ref().unwrap(), result.world_value.as_ref().unwrap());
+            let whole = serde_json::to_string_pretty(&value.to_json()).unwrap() + "\n";
+            assert_eq!(world, &whole, "{} with {:?}", fixture, emit);
+            assert_eq!(CompiledWorld::parse(world).as_ref(), Some(value), "{} with {:?}", fixture, emit);
+            assert_eq!(value.index.is_some(), emit.numeric_ids);
+        }
+    }
+}
+
+#[test]
+fn typed_world_holds_each_block() {
+    let result = compile_with_options(&fixture_path("sunken-citadel.urd.md"), &CompileOptions::default());
+    let value = result.world_value.unwrap();
+    let keys: Vec<&str> = value.blocks().map(|(key, _)| key).collect();
+    let document: serde_json::Value = serde_json::from_str(result.world.as_ref().unwrap()).unwrap();
+    assert_eq!(keys, document.as_object().unwrap().keys().map(String::as_str).collect::<Vec<_>>());
+    assert_eq!(value.world["start"], document["world"]["start"]);
+    assert_eq!(value.entities.as_ref().map(|e| e.len()), document["entities"].as_object().map(|e| e.len()));
+    assert!(value.regions.is_none() && value.index.is_none());
+
+    assert_eq!(CompiledWorld::parse("[]"), None);
+    assert_eq!(CompiledWorld::parse(r#"{"world": {}}"#), None);
+    assert_eq!(CompiledWorld::parse(r#"{"world": {}, "requires": {}, "extra": {}}"#), None);
+}
+
+#[test]
+fn skip_world_leaves_the_world_to_the_writer() {
+    let path = fixture_path("sunken-citadel.urd.md");
+    let full = compile_with_options(&path, &CompileOptions::default());
+    let skipped = compile_with_options(&path, &CompileOptions { skip_world: true, ..CompileOptions::default() });
+    assert!(skipped.success);
+    assert!(skipped.world.is_none());
+    assert!(skipped.world_value.is_none());
+    assert_eq!(&streamed(&skipped, &EmitOptions::default()), full.world.as_ref().unwrap());
+}
+
+// ── Memory ──
+
+/// Counts allocations and live bytes per thread, so tests running in
+/// parallel do not disturb each other's figures.
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+    static LIVE: Cell<usize> = const { Cell::new(0) };
+    static PEAK: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+            let _ = LIVE.try_with(|live| {
+                live.set(live.get() + layout.size());
+                let _ = PEAK.try_with(|peak| peak.set(peak.get().max(live.get())));
+            });
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) };
+        let _ = LIVE.try_with(|live| live.set(live.get().saturating_sub(layout.size())));
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Run `f`, returning its allocation count and the most bytes it held
+/// live at once beyond what was live when it started.
+fn measure(f: impl FnOnce()) -> (usize, usize) {
+    let start_count = ALLOCATIONS.with(Cell::get);
+    let start_live = LIVE.with(Cell::get);
+    PEAK.with(|peak| peak.set(start_live));
+    f();
+    (ALLOCATIONS.with(Cell::get) - start_count, PEAK.with(Cell::get) - start_live)
+}
+
+#[test]
+fn streaming_holds_less_than_the_whole_document() {
+    let reader = MemoryFileReader::new(synthetic_world(60, 3, 4, 1));
+    let entry = reader.get(ENTRY_FILE).unwrap().to_string();
+    let options = CompileOptions { skip_world: true, ..CompileOptions::default() };
+    let result = compile_source_with_options(ENTRY_FILE, &entry, &reader, &options);
+    assert!(result.success);
+    let (graph, symbol_table) = (result.graph.as_ref().unwrap(), result.symbol_table.as_ref().unwrap());
+    let emit = EmitOptions::default();
+
+    let mut document_len = 0;
+    let (in_memory_allocations, in_memory_peak) = measure(|| {
+        let mut diagnostics = urd_compiler::diagnostics::DiagnosticCollector::new();
+        document_len = emit::emit_with_options(graph, symbol_table, &mut diagnostics, &emit).len();
+    });
+    let (streamed_allocations, streamed_peak) = measure(|| {
+        emit::emit_to_writer(graph, symbol_table, &emit, &mut std::io::sink()).unwrap();
+    });
+
+    // The in-memory path holds the whole tree and the whole string; the
+    // streamed path at most one block's tree and no string at all.
+    assert!(in_memory_peak > document_len, "{} <= {}", in_memory_peak, document_len);
+    assert!(
+        streamed_peak < in_memory_peak - document_len,
+        "streamed peak {} vs in-memory peak {} with a {}-byte document",
+        streamed_peak,
+        in_memory_peak,
+        document_len,
+    );
+    assert!(streamed_allocations <= in_memory_allocations, "{} > {}", streamed_allocations, in_memory_allocations);
+}
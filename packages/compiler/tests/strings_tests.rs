@@ -0,0 +1,295 @@
+/// Tests for string table extraction and externalised emission.
+///
+/// Coverage tests compare the table against the player-visible fields of
+/// the emitted JSON. Format tests exercise CSV and gettext escaping.
+
+use serde_json::Value as Json;
+use urd_compiler::emit::EmitOptions;
+use urd_compiler::import::OsFileReader;
+use urd_compiler::strings::{self, StringTable};
+use urd_compiler::{compile, compile_source, CompileOptions};
+
+fn fixture_path(name: &str) -> String {
+    let base = env!("CARGO_MANIFEST_DIR");
+    format!("{}/tests/fixtures/{}", base, name)
+}
+
+fn fixture_table(name: &str) -> StringTable {
+    let result = compile(&fixture_path(name));
+    strings::from_compilation(&result).expect("strings should be extracted")
+}
+
+fn fixture_world(name: &str, externalize: bool) -> Json {
+    let path = fixture_path(name);
+    let source = std::fs::read_to_string(&path).unwrap();
+    let options = CompileOptions {
+        emit: EmitOptions { externalize_strings: externalize, ..EmitOptions::default() },
+        ..CompileOptions::default()
+    };
+    let result = urd_compiler::compile_source_with_options(&path, &source, &OsFileReader, &options);
+    assert!(result.success, "{} should compile", name);
+    serde_json::from_str(&result.world.unwrap()).unwrap()
+}
+
+/// Collect `(key, value)` for every player-visible field in world JSON,
+/// deriving each key from the field's JSON path.
+fn visible_fields(world: &Json) -> Vec<(String, String)> {
+    let mut out = Vec::new();
+    let text = |v: &Json| v.as_str().unwrap().to_string();
+
+    if let Some(locations) = world["locations"].as_object() {
+        for (id, loc) in locations {
+            if let Some(desc) = loc.get("description") {
+                out.push((format!("locations/{}/description", id), text(desc)));
+            }
+            if let Some(exits) = loc.get("exits").and_then(|e| e.as_object()) {
+                for (dir, exit) in exits {
+                    if let Some(msg) = exit.get("blocked_message") {
+                        out.push((format!("locations/{}/exits/{}/blocked_message", id, dir), text(msg)));
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(endings) = world["endings"].as_object() {
+        for (id, ending) in endings {
+            if let Some(desc) = ending.get("description") {
+                out.push((format!("endings/{}/description", id), text(desc)));
+            }
+        }
+    }
+
+    // A prompt or response is one speech object or an array of segments.
+    fn spoken(prefix: &str, value: &Json, out: &mut Vec<(String, String)>) {
+        match value {
+            Json::Object(_) => out.push((format!("{}/text", prefix), value["text"].as_str().unwrap().to_string())),
+            Json::Array(segments) => {
+                for (i, segment) in segments.iter().enumerate() {
+                    let (field, text) = segment.as_object().unwrap().iter().find(|(k, _)| *k != "speaker").unwrap();
+                    out.push((format!("{}/{}/{}", prefix, i, field), text.as_str().unwrap().to_string()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn choices(section: &str, list: &Json, out: &mut Vec<(String, String)>) {
+        for choice in list.as_array().into_iter().flatten() {
+            let slug = choice["id"].as_str().unwrap().rsplit('/').next().unwrap();
+            let prefix = format!("dialogue/{}/choices/{}", section, slug);
+            out.push((format!("{}/label", prefix), choice["label"].as_str().unwrap().to_string()));
+            spoken(&format!("{}/response", prefix), &choice["response"], out);
+            choices(section, &choice["choices"], out);
+        }
+    }
+
+    if let Some(dialogue) = world["dialogue"].as_object() {
+        for (id, sec) in dialogue {
+            spoken(&format!("dialogue/{}/prompt", id), &sec["prompt"], &mut out);
+            if let Some(desc) = sec.get("description") {
+                out.push((format!("dialogue/{}/description", id), text(desc)));
+            }
+            choices(id, &sec["choices"], &mut out);
+            if let Some(t) = sec["on_exhausted"]["text"].as_str() {
+                out.push((format!("dialogue/{}/on_exhausted/text", id), t.to_string()));
+            }
+        }
+    }
+
+    out
+}
+
+// ── Coverage ──
+
+#[test]
+fn key_puzzle_every_visible_string_appears_once() {
+    let table = fixture_table("two-room-key-puzzle.urd.md");
+    let fields = visible_fields(&fixture_world("two-room-key-puzzle.urd.md", false));
+
+    assert_eq!(fields.len(), 5);
+    for (key, text) in &fields {
+        let matches: Vec<_> = table.entries.iter().filter(|e| &e.text == text).collect();
+        assert_eq!(matches.len(), 1, "'{}' should appear exactly once in the table", text);
+        assert_eq!(&matches[0].key, key);
+    }
+    assert_eq!(table.len(), fields.len(), "table has entries not present in the world");
+}
+
+#[test]
+fn all_fixtures_keys_match_json_paths() {
+    for fixture in [
+        "tavern-scene.urd.md",
+        "monty-hall.urd.md",
+        "sunken-citadel.urd.md",
+        "interrogation/main.urd.md",
+        "location-hooks.urd.md",
+        "explicit-ids.urd.md",
+    ] {
+        let table = fixture_table(fixture);
+        let fields = visible_fields(&fixture_world(fixture, false));
+        let table_pairs: Vec<(String, String)> =
+            table.entries.iter().map(|e| (e.key.clone(), e.text.clone())).collect();
+        assert_eq!(table_pairs, fields, "{}: table and emitted text differ", fixture);
+    }
+}
+
+#[test]
+fn key_puzzle_entries_carry_source_lines() {
+    let table = fixture_table("two-room-key-puzzle.urd.md");
+    let desc = table.get("locations/cell/description").unwrap();
+    assert_eq!(desc.text, "A dim stone cell.");
+    assert_eq!(desc.file, "two-room-key-puzzle.urd.md");
+    assert_eq!(desc.line, 16);
+
+    let label = table.get("dialogue/two-room-key-puzzle/actions/choices/use-key/label").unwrap();
+    assert_eq!(label.text, "Use key");
+    assert_eq!(label.line, 26);
+}
+
+#[test]
+fn tavern_prompt_and_responses() {
+    let table = fixture_table("tavern-scene.urd.md");
+    assert_eq!(table.get("dialogue/tavern-scene/topics/prompt/text").unwrap().text, "What'll it be?");
+    assert_eq!(
+        table.get("dialogue/tavern-scene/topics/choices/ask-about-the-ship/response/text").unwrap().text,
+        "She's seen better days."
+    );
+    assert_eq!(
+        table.get("dialogue/tavern-scene/topics/choices/order-a-drink/label").unwrap().line,
+        26
+    );
+}
+
+#[test]
+fn extraction_is_deterministic() {
+    let a = fixture_table("sunken-citadel.urd.md");
+    let b = fixture_table("sunken-citadel.urd.md");
+    assert_eq!(a, b);
+    assert_eq!(a.to_json().to_string(), b.to_json().to_string());
+    assert_eq!(a.to_po(), b.to_po());
+}
+
+// ── Externalised emission ──
+
+#[test]
+fn emit_keys_replaces_text_with_keys() {
+    let plain = fixture_world("tavern-scene.urd.md", false);
+    let keyed = fixture_world("tavern-scene.urd.md", true);
+    let table = fixture_table("tavern-scene.urd.md");
+
+    assert_eq!(keyed["world"]["strings"], "external");
+    assert!(plain["world"].get("strings").is_none());
+
+    let keyed_fields = visible_fields(&keyed);
+    assert_eq!(keyed_fields.len(), table.len());
+    for ((key, value), (plain_key, plain_text)) in keyed_fields.iter().zip(visible_fields(&plain)) {
+        assert_eq!(value, key, "externalised field should hold its own key");
+        assert_eq!(key, &plain_key);
+        assert_eq!(table.get(key).unwrap().text, plain_text);
+    }
+
+    // Non-text blocks are untouched.
+    for block in ["types", "entities", "actions"] {
+        assert_eq!(keyed[block], plain[block], "{} should not change", block);
+    }
+    assert_eq!(
+        keyed["locations"]["the-rusty-anchor"]["exits"],
+        plain["locations"]["the-rusty-anchor"]["exits"]
+    );
+    assert_eq!(
+        keyed["dialogue"]["tavern-scene/topics"]["choices"][0]["id"],
+        plain["dialogue"]["tavern-scene/topics"]["choices"][0]["id"]
+    );
+}
+
+#[test]
+fn emit_keys_output_validates_against_schema() {
+    let schema: Json = serde_json::from_str(urd_compiler::schema::world_schema()).unwrap();
+    let validator = jsonschema::validator_for(&schema).unwrap();
+    let keyed = fixture_world("two-room-key-puzzle.urd.md", true);
+    assert!(validator.is_valid(&keyed));
+}
+
+// ── Formats ──
+
+const QUOTED: &str = "---\nworld:\n  name: test\n  start: room\n---\n# Room\n\nA \"quiet\" room, mostly.\n";
+
+#[test]
+fn csv_quotes_commas_and_quotes() {
+    let table = strings::from_compilation(&compile_source("test.urd.md", QUOTED)).unwrap();
+    assert_eq!(
+        table.to_csv(),
+        "key,text,file,line\nlocations/room/description,\"A \"\"quiet\"\" room, mostly.\",test.urd.md,8\n"
+    );
+}
+
+#[test]
+fn po_escapes_and_uses_key_as_context() {
+    let table = strings::from_compilation(&compile_source("test.urd.md", QUOTED)).unwrap();
+    let po = table.to_po();
+    assert!(po.starts_with("msgid \"\"\nmsgstr \"\"\n"));
+    assert!(po.contains(
+        "#: test.urd.md:8\nmsgctxt \"locations/room/description\"\nmsgid \"A \\\"quiet\\\" room, mostly.\"\nmsgstr \"\"\n"
+    ));
+}
+
+#[test]
+fn json_is_keyed_by_string_id() {
+    let table = strings::from_compilation(&compile_source("test.urd.md", QUOTED)).unwrap();
+    let json = table.to_json();
+    assert_eq!(json["urd_strings"], "1");
+    assert_eq!(json["strings"]["locations/room/description"]["text"], "A \"quiet\" room, mostly.");
+    assert_eq!(json["strings"]["locations/room/description"]["line"], 8);
+}
+
+#[test]
+fn compile_errors_yield_no_table() {
+    let result = compile_source("test.urd.md", "---\nworld:\n  name: test\n  start: nowhere\n---\n# Room\n");
+    assert!(!result.success);
+    assert!(strings::from_compilation(&result).is_none());
+}
+
+#[test]
+fn variants_get_indexed_keys() {
+    let table = fixture_table("text-variants.urd.md");
+    let text = |key: &str| table.get(key).map(|e| e.text.as_str());
+
+    assert_eq!(text("dialogue/text-variants/crossing/prompt/text"), Some("Going across?"));
+    assert_eq!(text("dialogue/text-variants/crossing/prompt/text/variants/1"), Some("Another one for the far shore?"));
+    assert_eq!(text("dialogue/text-variants/crossing/prompt/text/variants/2"), Some("Back again?"));
+    assert_eq!(
+        text("dialogue/text-variants/crossing/choices/ask-about-the-fare/response/text/variants/1"),
+        Some("Whatever you can spare.")
+    );
+    assert_eq!(
+        text("locations/dock/description/variants/1"),
+        Some("Rain lashes the pier.\n\nThe ferry rocks at its mooring.")
+    );
+    assert_eq!(
+        text("dialogue/text-variants/crossing/on_exhausted/text/variants/1"),
+        Some("The ferryman turns back to his ropes.")
+    );
+    // Every variant is listed in format 1 too, though only the first is emitted.
+    assert_eq!(table.len(), 11);
+}
+
+#[test]
+fn ending_descriptions_are_keyed_and_externalised() {
+    let source = "---\nworld:\n  name: cell\n  start: cell\nendings:\n  escaped: You slip away.\n  caught:\n---\n\n\
+                  # Cell\n\n-> north: Yard\n  > end escaped\n\n== wait\n\n* Wait\n  > end caught\n\n# Yard\n";
+    let result = compile_source("cell.urd.md", source);
+    let table = strings::from_compilation(&result).expect("strings should be extracted");
+    let entry = table.get("endings/escaped/description").expect("ending description should be keyed");
+    assert_eq!(entry.text, "You slip away.");
+    assert_eq!((entry.file.as_str(), entry.line), ("cell.urd.md", 6));
+    assert!(table.entries.iter().all(|e| !e.key.starts_with("endings/caught")));
+
+    let options = CompileOptions {
+        emit: EmitOptions { externalize_strings: true, ..EmitOptions::default() },
+        ..CompileOptions::default()
+    };
+    let result = urd_compiler::compile_source_with_options("cell.urd.md", source, &OsFileReader, &options);
+    let world: Json = serde_json::from_str(&result.world.unwrap()).unwrap();
+    assert_eq!(world["endings"]["escaped"]["description"], "endings/escaped/description");
+}
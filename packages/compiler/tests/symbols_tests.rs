@@ -0,0 +1,158 @@
+/// Tests for the symbol table export.
+///
+/// Each test compiles a fixture and checks the export against what the
+/// source declares: attributes copied through, spans kept, and the same
+/// JSON from every compile of the same source.
+
+use serde_json::Value as Json;
+use urd_compiler::import::OsFileReader;
+use urd_compiler::symbol_table::export::SymbolTableExport;
+use urd_compiler::symbol_table::Value;
+use urd_compiler::{compile, compile_source, compile_source_with_options, CompileOptions};
+
+fn fixture_path(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn fixture_symbols(name: &str) -> SymbolTableExport {
+    let path = fixture_path(name);
+    let source = std::fs::read_to_string(&path).unwrap();
+    let options = CompileOptions { keep_symbols: true, ..CompileOptions::default() };
+    compile_source_with_options(&path, &source, &OsFileReader, &options)
+        .symbols
+        .expect("symbols should be kept after LINK")
+}
+
+fn is_span(value: &Json) -> bool {
+    ["file", "start_line", "start_col", "end_line", "end_col"]
+        .iter()
+        .all(|key| value.get(key).is_some())
+}
+
+// ── Options ──
+
+#[test]
+fn symbols_are_kept_only_when_asked() {
+    assert!(compile(&fixture_path("locked-garden.urd.md")).symbols.is_none());
+    let symbols = fixture_symbols("locked-garden.urd.md");
+    let table = compile(&fixture_path("locked-garden.urd.md")).symbol_table.unwrap();
+    assert_eq!(symbols, table.export());
+}
+
+#[test]
+fn symbols_are_kept_when_validate_fails() {
+    let source = "---\ntypes:\n  Door [interactable]:\n    open: bool = 3\nentities:\n  @door: Door\n---\n\n# Hall\n\n[@door]\n";
+    let options = CompileOptions { keep_symbols: true, ..CompileOptions::default() };
+    let result = compile_source_with_options("hall.urd.md", source, &urd_compiler::import::StubFileReader, &options);
+    assert!(!result.success);
+    let symbols = result.symbols.expect("LINK ran");
+    assert_eq!(symbols.entities[0].id, "door");
+}
+
+#[test]
+fn no_symbols_without_link() {
+    let options = CompileOptions { keep_symbols: true, ..CompileOptions::default() };
+    let result = compile_source_with_options("bad.urd.md", "---\nworld: x\n", &urd_compiler::import::StubFileReader, &options);
+    assert!(result.symbols.is_none());
+}
+
+// ── Determinism ──
+
+#[test]
+fn export_json_is_identical_across_compiles() {
+    for name in ["locked-garden.urd.md", "monty-hall.urd.md", "sunken-citadel.urd.md"] {
+        let first = fixture_symbols(name).to_json();
+        let second = fixture_symbols(name).to_json();
+        assert_eq!(first, second, "{} exported differently", name);
+        assert_eq!(
+            serde_json::to_string(&first).unwrap(),
+            serde_json::to_string(&second).unwrap(),
+            "{} key order differs",
+            name
+        );
+    }
+}
+
+// ── Contents ──
+
+#[test]
+fn export_copies_property_attributes() {
+    let symbols = fixture_symbols("locked-garden.urd.md");
+    let character = symbols.types.iter().find(|t| t.name == "Character").unwrap();
+    assert_eq!(character.traits, vec!["interactable"]);
+
+    let mood = character.properties.iter().find(|p| p.name == "mood").unwrap();
+    assert_eq!(mood.property_type, "enum");
+    assert_eq!(mood.values.as_deref(), Some(&["wary".to_string(), "neutral".to_string(), "friendly".to_string()][..]));
+    assert_eq!(mood.default, Some(Value::String("wary".to_string())));
+
+    let role = character.properties.iter().find(|p| p.name == "role").unwrap();
+    assert!(role.hidden, "~role is hidden");
+    assert_eq!(role.declared_in.start_line, 10);
+}
+
+#[test]
+fn export_copies_entities_and_locations() {
+    let symbols = fixture_symbols("locked-garden.urd.md");
+    let warden = symbols.entities.iter().find(|e| e.id == "warden").unwrap();
+    assert_eq!(warden.type_name, "Character");
+    assert!(warden.type_resolved);
+    assert_eq!(warden.overrides.get("mood"), Some(&Value::String("neutral".to_string())));
+
+    let gatehouse = symbols.locations.iter().find(|l| l.id == "gatehouse").unwrap();
+    assert_eq!(gatehouse.contains, vec!["warden", "iron_key"]);
+    let exit = &gatehouse.exits[0];
+    assert_eq!(exit.direction, "garden");
+    assert_eq!(exit.resolved_destination.as_deref(), Some("the-walled-garden"));
+    assert!(exit.conditional);
+    assert!(exit.blocked_message);
+}
+
+#[test]
+fn export_copies_sections_and_choices() {
+    let symbols = fixture_symbols("locked-garden.urd.md");
+    let greet = symbols.sections.iter().find(|s| s.compiled_id.ends_with("/greet")).unwrap();
+    assert_eq!(greet.local_name, "greet");
+    assert_eq!(greet.file_stem, "locked-garden");
+    let purpose = &greet.choices[0];
+    assert_eq!(purpose.label, "State your purpose");
+    assert!(purpose.sticky);
+    assert!(purpose.compiled_id.starts_with("locked-garden/greet/"), "{}", purpose.compiled_id);
+    assert!(!greet.choices[1].sticky);
+}
+
+#[test]
+fn export_records_duplicates() {
+    let source = "---\ntypes:\n  Key [portable]:\n    name: string\nentities:\n  @key: Key\n  @key: Key\n---\n\n# Hall\n";
+    let table = compile_source("hall.urd.md", source).symbol_table.unwrap();
+    let symbols = table.export();
+    assert_eq!(symbols.duplicates.len(), 1);
+    assert_eq!(symbols.duplicates[0].namespace, "entities");
+    assert_eq!(symbols.duplicates[0].name, "key");
+    assert_eq!(symbols.duplicates[0].declared_in.start_line, 7);
+}
+
+// ── JSON shape ──
+
+#[test]
+fn export_json_has_documented_shape() {
+    let json = fixture_symbols("locked-garden.urd.md").to_json();
+    for block in ["types", "entities", "locations", "regions", "sections", "actions", "rules", "sequences", "endings", "duplicates"] {
+        assert!(json[block].is_array(), "missing {}", block);
+    }
+    assert_eq!(json["world"]["start"], "gatehouse");
+
+    let property = &json["types"][0]["properties"][0];
+    for key in ["name", "type", "raw_type", "default", "visibility", "values", "min", "max", "ref_type", "description"] {
+        assert!(property.get(key).is_some(), "property missing {}", key);
+    }
+    assert!(is_span(&property["declared_in"]));
+
+    let entity = json["entities"].as_array().unwrap().iter().find(|e| e["id"] == "warden").unwrap();
+    assert_eq!(entity["overrides"]["role"], "Gatekeeper");
+    assert!(is_span(&entity["declared_in"]));
+
+    let location = &json["locations"][0];
+    assert!(is_span(&location["exits"][0]["declared_in"]));
+    assert!(is_span(&json["sections"][0]["choices"][0]["declared_in"]));
+}
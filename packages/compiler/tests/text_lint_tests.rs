@@ -0,0 +1,176 @@
+/// Tests for text lint over player-visible prose.
+///
+/// Rule tests call each built-in rule on a string directly. Compile tests
+/// check the `CompileOptions::text_lints` gate and that only the text EMIT
+/// writes is linted.
+
+use urd_compiler::diagnostics::{Diagnostic, Severity};
+use urd_compiler::import::StubFileReader;
+use urd_compiler::span::Span;
+use urd_compiler::text_lint::{
+    ConsecutiveSpaces, DoubledWord, TextContext, TextLintRule, UnmatchedDelimiter,
+};
+use urd_compiler::{compile_source_with_options, CompileOptions};
+
+// ── Helpers ──
+
+fn span() -> Span {
+    Span::new("test.urd.md".to_string(), 3, 1, 3, 20)
+}
+
+fn check(rule: &dyn TextLintRule, text: &str) -> Vec<Diagnostic> {
+    rule.check(text, &span(), TextContext::Speech)
+}
+
+fn messages(rule: &dyn TextLintRule, text: &str) -> Vec<String> {
+    check(rule, text).into_iter().map(|d| d.message).collect()
+}
+
+/// Text lint diagnostics from compiling `source`, as `(code, line)`.
+fn lint_source(source: &str, text_lints: bool) -> Vec<(String, u32)> {
+    let options = CompileOptions { text_lints, ..CompileOptions::default() };
+    let result = compile_source_with_options("test.urd.md", source, &StubFileReader, &options);
+    assert!(result.success, "source should compile: {:?}", result.diagnostics.all());
+    result
+        .diagnostics
+        .all()
+        .iter()
+        .filter(|d| d.code.starts_with("URD7"))
+        .map(|d| (d.code.clone(), d.span.start_line))
+        .collect()
+}
+
+// Frontmatter strings, the world name, and IDs carry every problem the
+// rules look for; none of them is player-visible text.
+const WORLD: &str = "\
+---
+world:
+  name: hall-hall
+  start: hall-hall
+types:
+  Guard [interactable]:
+    note: string = \"the the  (open\"
+entities:
+  @guard_guard: Guard
+---
+
+# Hall Hall
+
+A quiet hall. The guard is is asleep.
+
+[@guard_guard]
+
+-> north: Yard
+  ! The gate is  shut.
+
+# Yard
+
+An empty yard.
+
+== talk
+
+@guard_guard: Who goes there?
+
+* Ask (about the key
+  @guard_guard: \"Go away.
+";
+
+// ── Rules ──
+
+#[test]
+fn doubled_word_is_reported_ignoring_case() {
+    let diagnostics = check(&DoubledWord, "The the door is shut.");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "URD701");
+    assert_eq!(diagnostics[0].severity, Severity::Info);
+    assert_eq!(diagnostics[0].span, span());
+    assert_eq!(diagnostics[0].message, "Doubled word 'The the' in speech.");
+}
+
+#[test]
+fn doubled_word_needs_only_whitespace_between() {
+    assert!(check(&DoubledWord, "No, no. Bye. Bye.").is_empty());
+    assert!(check(&DoubledWord, "Room 1 1 floor.").is_empty());
+    assert!(check(&DoubledWord, "It was the theory.").is_empty());
+    assert_eq!(messages(&DoubledWord, "no no no").len(), 2);
+    assert_eq!(messages(&DoubledWord, "don't\ndon't"), vec!["Doubled word 'don't don't' in speech."]);
+}
+
+#[test]
+fn consecutive_spaces_are_reported_per_run() {
+    assert_eq!(
+        messages(&ConsecutiveSpaces, "Hello  there,   friend."),
+        vec![
+            "2 consecutive spaces after 'Hello' in speech.",
+            "3 consecutive spaces after 'there,' in speech.",
+        ],
+    );
+    assert!(check(&ConsecutiveSpaces, "Hello there.\n\nFriend.").is_empty());
+    assert_eq!(check(&ConsecutiveSpaces, "a  b")[0].code, "URD702");
+}
+
+#[test]
+fn unmatched_delimiters_are_reported_once() {
+    let diagnostics = check(&UnmatchedDelimiter, "Ask (about the key");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, "URD703");
+    assert_eq!(diagnostics[0].message, "Unmatched '(' in speech 'Ask (about the key'.");
+
+    assert_eq!(messages(&UnmatchedDelimiter, "A [note) here").len(), 1);
+    assert_eq!(messages(&UnmatchedDelimiter, "done]")[0], "Unmatched ']' in speech 'done]'.");
+    assert_eq!(messages(&UnmatchedDelimiter, "“Stop, he said.")[0], "Unmatched '“' in speech '“Stop, he said.'.");
+    assert_eq!(messages(&UnmatchedDelimiter, "\"Go away.")[0], "Unmatched '\"' in speech '\"Go away.'.");
+}
+
+#[test]
+fn matched_delimiters_and_apostrophes_pass() {
+    for text in ["(a [b] {c})", "“Yes,” she said.", "\"Go\", \"now\".", "It's the guard's 'key'."] {
+        assert!(check(&UnmatchedDelimiter, text).is_empty(), "{}", text);
+    }
+}
+
+#[test]
+fn context_is_read_from_string_keys() {
+    let cases = [
+        ("locations/hall/description", TextContext::Description),
+        ("locations/hall/exits/north/blocked_message", TextContext::BlockedMessage),
+        ("dialogue/test/talk/description", TextContext::Description),
+        ("dialogue/test/talk/on_exhausted/text", TextContext::Description),
+        ("dialogue/test/talk/prompt/text", TextContext::Speech),
+        ("dialogue/test/talk/choices/ask/label", TextContext::ChoiceLabel),
+        ("dialogue/test/talk/choices/ask/response/1/text", TextContext::Speech),
+        ("dialogue/test/talk/choices/ask/response/0/direction", TextContext::StageDirection),
+    ];
+    for (key, context) in cases {
+        assert_eq!(TextContext::of_key(key), context, "{}", key);
+    }
+}
+
+// ── Compile ──
+
+#[test]
+fn text_lint_is_off_by_default() {
+    assert!(lint_source(WORLD, false).is_empty());
+}
+
+#[test]
+fn only_player_visible_text_is_linted() {
+    assert_eq!(
+        lint_source(WORLD, true),
+        vec![
+            ("URD701".to_string(), 14),
+            ("URD702".to_string(), 19),
+            ("URD703".to_string(), 29),
+            ("URD703".to_string(), 30),
+        ],
+    );
+}
+
+#[test]
+fn text_lint_is_skipped_when_compilation_fails() {
+    let source = "---\nworld:\n  name: test\n  start: nowhere\n---\n\n# Hall\n\nThe the hall.\n";
+    let options = CompileOptions { text_lints: true, ..CompileOptions::default() };
+    let result = compile_source_with_options("test.urd.md", source, &StubFileReader, &options);
+    assert!(!result.success);
+    assert!(result.diagnostics.all().iter().all(|d| !d.code.starts_with("URD7")));
+}
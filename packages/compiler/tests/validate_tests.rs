@@ -1,10 +1,10 @@
 // Tests for Phase 4: VALIDATE
 //
 // Test categories from the VALIDATE phase brief:
-// 1. Property type checking (14)
-// 2. Condition validation (10)
+// 1. Property type checking (22)
+// 2. Condition validation (23)
 // 3. Effect validation (13)
-// 4. Structural constraints (16)
+// 4. Structural constraints (38)
 // 5. Skip rule (4)
 // 6. Integration tests (4)
 
@@ -28,6 +28,7 @@ fn make_file_ast(path: &str, frontmatter: Option<Frontmatter>, content: Vec<Cont
         frontmatter,
         content,
         span: span(path, 1),
+        source_hash: None,
     }
 }
 
@@ -51,6 +52,7 @@ fn make_type_def(name: &str, traits: Vec<&str>, properties: Vec<PropertyDef>) ->
         name: name.to_string(),
         traits: traits.into_iter().map(String::from).collect(),
         properties,
+        doc: None,
         span: span("test.urd.md", 2),
     })
 }
@@ -116,6 +118,7 @@ fn make_entity_decl(id: &str, type_name: &str, overrides: Vec<(&str, Scalar)>) -
         type_name: type_name.to_string(),
         property_overrides: overrides.into_iter().map(|(k, v)| (k.to_string(), v)).collect(),
         annotation: None,
+        doc: None,
         span: span("test.urd.md", 4),
     })
 }
@@ -130,6 +133,8 @@ fn make_world_block(fields: Vec<(&str, Scalar)>) -> FrontmatterValue {
 fn location(name: &str) -> ContentNode {
     ContentNode::LocationHeading(LocationHeading {
         display_name: name.to_string(),
+        explicit_id: None,
+        doc: None,
         span: span("test.urd.md", 10),
     })
 }
@@ -137,6 +142,22 @@ fn location(name: &str) -> ContentNode {
 fn section(name: &str) -> ContentNode {
     ContentNode::SectionLabel(SectionLabel {
         name: name.to_string(),
+        explicit_id: None,
+        param: None,
+        doc: None,
+        span: span("test.urd.md", 15),
+    })
+}
+
+fn param_section(name: &str, param: &str, type_name: &str) -> ContentNode {
+    ContentNode::SectionLabel(SectionLabel {
+        name: name.to_string(),
+        explicit_id: None,
+        param: Some(SectionParam {
+            name: param.to_string(),
+            type_name: type_name.to_string(),
+        }),
+        doc: None,
         span: span("test.urd.md", 15),
     })
 }
@@ -154,6 +175,27 @@ fn choice(label: &str, sticky: bool) -> ContentNode {
     })
 }
 
+fn once_condition() -> ContentNode {
+    ContentNode::Condition(Condition {
+        expr: ConditionExpr::Once(OnceCondition { span: span("test.urd.md", 21) }),
+        indent_level: 1,
+        span: span("test.urd.md", 21),
+    })
+}
+
+fn once_choice(label: &str, sticky: bool) -> ContentNode {
+    ContentNode::Choice(Choice {
+        sticky,
+        label: label.to_string(),
+        target: None,
+        target_type: None,
+        content: vec![once_condition()],
+        indent_level: 1,
+        annotation: None,
+        span: span("test.urd.md", 20),
+    })
+}
+
 fn choice_at_depth(label: &str, depth: usize) -> ContentNode {
     ContentNode::Choice(Choice {
         sticky: false,
@@ -174,7 +216,10 @@ fn property_comparison(entity_ref: &str, property: &str, op: &str, value: &str)
             property: property.to_string(),
             operator: op.to_string(),
             value: value.to_string(),
+            negated: false,
+            bare: false,
             annotation: None,
+            spans: OperandSpans::whole(),
             span: span("test.urd.md", 40),
         }),
         indent_level: 0,
@@ -182,6 +227,23 @@ fn property_comparison(entity_ref: &str, property: &str, op: &str, value: &str)
     })
 }
 
+/// Mark a property comparison condition as written with `not` (or `!`).
+fn negated(mut node: ContentNode) -> ContentNode {
+    if let ContentNode::Condition(Condition { expr: ConditionExpr::PropertyComparison(pc), .. }) = &mut node {
+        pc.negated = true;
+    }
+    node
+}
+
+/// `? @entity.property` — a bare boolean read.
+fn bare_read(entity_ref: &str, property: &str) -> ContentNode {
+    let mut node = property_comparison(entity_ref, property, "==", "true");
+    if let ContentNode::Condition(Condition { expr: ConditionExpr::PropertyComparison(pc), .. }) = &mut node {
+        pc.bare = true;
+    }
+    node
+}
+
 fn containment_check(entity_ref: &str, container_ref: &str, negated: bool) -> ContentNode {
     ContentNode::Condition(Condition {
         expr: ConditionExpr::ContainmentCheck(ContainmentCheck {
@@ -214,6 +276,7 @@ fn set_effect(target_prop: &str, value: &str) -> ContentNode {
             target_prop: target_prop.to_string(),
             operator: "=".to_string(),
             value_expr: value.to_string(),
+            spans: OperandSpans::whole(),
         },
         indent_level: 0,
         annotation: None,
@@ -227,6 +290,7 @@ fn set_effect_arithmetic(target_prop: &str, operator: &str, value: &str) -> Cont
             target_prop: target_prop.to_string(),
             operator: operator.to_string(),
             value_expr: value.to_string(),
+            spans: OperandSpans::whole(),
         },
         indent_level: 0,
         annotation: None,
@@ -272,6 +336,7 @@ fn entity_speech(entity_ref: &str, text: &str) -> ContentNode {
     ContentNode::EntitySpeech(EntitySpeech {
         entity_ref: entity_ref.to_string(),
         text: text.to_string(),
+        variants: Vec::new(),
         annotation: None,
         span: span("test.urd.md", 30),
     })
@@ -288,10 +353,19 @@ fn phase_heading(name: &str, auto: bool) -> ContentNode {
     ContentNode::PhaseHeading(PhaseHeading {
         display_name: name.to_string(),
         auto,
+        section: None,
         span: span("test.urd.md", 56),
     })
 }
 
+fn location_hook(kind: HookKind, children: Vec<ContentNode>) -> ContentNode {
+    ContentNode::LocationHook(LocationHook {
+        kind,
+        children,
+        span: span("test.urd.md", 46),
+    })
+}
+
 fn error_node(text: &str) -> ContentNode {
     ContentNode::ErrorNode(ErrorNode {
         raw_text: text.to_string(),
@@ -315,6 +389,7 @@ fn entity_presence(refs: Vec<&str>) -> ContentNode {
     ContentNode::EntityPresence(EntityPresence {
         entity_refs: refs.into_iter().map(String::from).collect(),
         annotations: vec![None; len],
+        property_overrides: vec![Vec::new(); len],
         span: span("test.urd.md", 12),
     })
 }
@@ -400,6 +475,7 @@ fn rule_block(name: &str, actor: &str, trigger: &str) -> ContentNode {
         select: None,
         where_clauses: Vec::new(),
         effects: Vec::new(),
+        indent_level: 0,
         span: span("test.urd.md", 60),
     })
 }
@@ -414,6 +490,11 @@ fn has_warning(diag: &DiagnosticCollector, code: &str) -> bool {
     diag.all().iter().any(|d| d.code == code && d.severity == Severity::Warning)
 }
 
+/// Check if diagnostics contain an info with the given code.
+fn has_info(diag: &DiagnosticCollector, code: &str) -> bool {
+    diag.all().iter().any(|d| d.code == code && d.severity == Severity::Info)
+}
+
 /// Count only VALIDATE-range diagnostics (URD4xx).
 fn count_validate_errors(diag: &DiagnosticCollector) -> usize {
     diag.all().iter().filter(|d| d.code.starts_with("URD4") && d.severity == Severity::Error).count()
@@ -584,6 +665,31 @@ fn default_invalid() {
     assert!(has_error(&diag, "URD413"), "Expected URD413, got: {:?}", diag.all());
 }
 
+#[test]
+fn default_not_finite() {
+    // PARSE reads `inf` and `NaN` as numbers; JSON has neither.
+    let result = urd_compiler::compile_source(
+        "test.urd.md",
+        "---\nworld:\n  name: test\ntypes:\n  Gauge:\n    level: number = inf\n    drift: number = NaN\n---\n# Cell\n",
+    );
+    assert!(!result.success);
+    assert!(result.world.is_none());
+    let messages: Vec<&str> = result
+        .diagnostics
+        .all()
+        .iter()
+        .filter(|d| d.code == "URD413")
+        .map(|d| d.message.as_str())
+        .collect();
+    assert_eq!(
+        messages,
+        [
+            "Default value 'inf' for property 'level' on type 'Gauge' is not a finite number.",
+            "Default value 'NaN' for property 'drift' on type 'Gauge' is not a finite number.",
+        ]
+    );
+}
+
 #[test]
 fn empty_enum() {
     let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
@@ -615,245 +721,811 @@ fn range_on_string() {
             make_property_with_range("name", "string", Some(0.0), None),
         ])),
     ])), Vec::new());
-    let diag = link_and_validate(single_file_cu(ast));
-    assert!(has_error(&diag, "URD417"), "Expected URD417, got: {:?}", diag.all());
+    let mut diag = DiagnosticCollector::new();
+    let linked = link::link(single_file_cu(ast), &mut diag);
+    validate::validate(&linked.graph, &linked.symbol_table, &mut diag);
+    assert!(has_warning(&diag, "URD417"), "Expected URD417 warning, got: {:?}", diag.all());
+    assert!(!diag.has_errors(), "{:?}", diag.all());
+
+    // The ignored range is left out of the world.
+    let world = urd_compiler::emit::emit(&linked.graph, &linked.symbol_table, &mut diag);
+    let world: serde_json::Value = serde_json::from_str(&world).unwrap();
+    assert_eq!(world["types"]["Guard"]["properties"]["name"], serde_json::json!({"type": "string"}));
 }
 
 #[test]
-fn ref_type_unknown() {
+fn enum_duplicate_values() {
     let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
-        fm_entry("LockedDoor", make_type_def("LockedDoor", vec![], vec![
-            make_property_with_ref_type("requires", "UnknownType"),
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![
+            make_property_with_values("mood", "enum", vec!["calm", "angry", "calm", "wary", "angry", "calm"]),
         ])),
     ])), Vec::new());
     let diag = link_and_validate(single_file_cu(ast));
-    assert!(has_error(&diag, "URD415"), "Expected URD415, got: {:?}", diag.all());
+    let messages: Vec<&str> =
+        diag.all().iter().filter(|d| d.code == "URD459").map(|d| d.message.as_str()).collect();
+    assert_eq!(messages, ["Enum property 'mood' on type 'Guard' lists values more than once: calm, angry."]);
+    assert!(has_error(&diag, "URD459"));
 }
 
-// ═══════════════════════════════════════════════════════════
-// Condition Validation Tests
-// ═══════════════════════════════════════════════════════════
+#[test]
+fn enum_default_not_in_values() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![PropertyDef {
+            default: Some(Scalar::String("asleep".to_string())),
+            ..make_property_with_values("mood", "enum", vec!["calm", "angry"])
+        }])),
+    ])), Vec::new());
+    let diag = link_and_validate(single_file_cu(ast));
+    let messages: Vec<&str> =
+        diag.all().iter().filter(|d| d.code == "URD413").map(|d| d.message.as_str()).collect();
+    assert_eq!(
+        messages,
+        ["Default value 'asleep' for property 'mood' on type 'Guard' is not one of its values: calm, angry."]
+    );
+}
 
 #[test]
-fn property_comparison_valid() {
+fn empty_enum_with_default_reports_once() {
     let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
-        fm_entry("Guard", make_type_def("Guard", vec![], vec![
-            make_property_with_values("mood", "enum", vec!["friendly", "neutral", "hostile"]),
-        ])),
-        fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
-    ])), vec![
-        property_comparison("guard", "mood", "==", "neutral"),
-    ]);
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![PropertyDef {
+            default: Some(Scalar::String("calm".to_string())),
+            ..make_property_with_values("mood", "enum", vec![])
+        }])),
+    ])), Vec::new());
     let diag = link_and_validate(single_file_cu(ast));
-    assert_eq!(count_validate_errors(&diag), 0);
+    let codes: Vec<&str> = diag.all().iter().map(|d| d.code.as_str()).collect();
+    assert_eq!(codes, ["URD414"]);
 }
 
 #[test]
-fn ordering_on_enum() {
+fn ref_type_unknown_suggests_a_type() {
     let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
-        fm_entry("Guard", make_type_def("Guard", vec![], vec![
-            make_property_with_values("mood", "enum", vec!["friendly", "neutral", "hostile"]),
+        fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
+        fm_entry("LockedDoor", make_type_def("LockedDoor", vec![], vec![
+            make_property_with_ref_type("requires", "Kay"),
         ])),
-        fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
-    ])), vec![
-        property_comparison("guard", "mood", ">", "neutral"),
-    ]);
+    ])), Vec::new());
     let diag = link_and_validate(single_file_cu(ast));
-    assert!(has_error(&diag, "URD420"), "Expected URD420, got: {:?}", diag.all());
+    let found: Vec<_> = diag.all().iter().filter(|d| d.code == "URD415").collect();
+    assert_eq!(found.len(), 1, "{:?}", diag.all());
+    assert_eq!(found[0].suggestion.as_deref(), Some("Did you mean 'Key'?"));
 }
 
 #[test]
-fn value_type_mismatch_in_condition() {
+fn well_formed_property_declarations() {
     let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
         fm_entry("Guard", make_type_def("Guard", vec![], vec![
+            PropertyDef {
+                default: Some(Scalar::String("calm".to_string())),
+                ..make_property_with_values("mood", "enum", vec!["calm", "angry"])
+            },
             make_property_with_range("trust", "integer", Some(0.0), Some(100.0)),
+            make_property_with_range("fear", "number", Some(0.5), Some(0.5)),
+            make_property_with_ref_type("carries", "Key"),
         ])),
-        fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
-    ])), vec![
-        property_comparison("guard", "trust", "==", "high"),
-    ]);
+    ])), Vec::new());
     let diag = link_and_validate(single_file_cu(ast));
-    assert!(has_error(&diag, "URD401"), "Expected URD401, got: {:?}", diag.all());
+    let codes: Vec<&str> = diag.all().iter().map(|d| d.code.as_str()).collect();
+    assert!(codes.is_empty(), "{:?}", diag.all());
 }
 
 #[test]
-fn containment_player_keyword_valid() {
+fn ref_type_unknown() {
     let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
-        fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
-        fm_entry("key", make_entity_decl("key", "Key", vec![])),
-    ])), vec![
-        containment_check("key", "player", false),
-    ]);
+        fm_entry("LockedDoor", make_type_def("LockedDoor", vec![], vec![
+            make_property_with_ref_type("requires", "UnknownType"),
+        ])),
+    ])), Vec::new());
     let diag = link_and_validate(single_file_cu(ast));
-    assert_eq!(count_validate_errors(&diag), 0);
+    assert!(has_error(&diag, "URD415"), "Expected URD415, got: {:?}", diag.all());
 }
 
 #[test]
-fn containment_here_keyword_valid() {
+fn redundant_boolean_override_info() {
     let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
-        fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
-        fm_entry("key", make_entity_decl("key", "Key", vec![])),
-    ])), vec![
-        containment_check("key", "here", false),
-    ]);
+        fm_entry("LockedDoor", make_type_def("LockedDoor", vec![], vec![
+            make_property_with_default("locked", "boolean", Scalar::Boolean(true)),
+        ])),
+        fm_entry("door", make_entity_decl("door", "LockedDoor", vec![
+            ("locked", Scalar::Boolean(true)),
+        ])),
+    ])), Vec::new());
     let diag = link_and_validate(single_file_cu(ast));
+    assert!(has_info(&diag, "URD440"), "Expected URD440 info, got: {:?}", diag.all());
     assert_eq!(count_validate_errors(&diag), 0);
+    assert_eq!(count_validate_warnings(&diag), 0);
 }
 
 #[test]
-fn container_entity_without_trait() {
+fn differing_override_not_flagged() {
     let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
-        fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
-        fm_entry("Door", make_type_def("Door", vec![], vec![])),  // no container trait
-        fm_entry("key", make_entity_decl("key", "Key", vec![])),
-        fm_entry("door", make_entity_decl("door", "Door", vec![])),
-    ])), vec![
-        containment_check("key", "door", false),
-    ]);
+        fm_entry("LockedDoor", make_type_def("LockedDoor", vec![], vec![
+            make_property_with_default("locked", "boolean", Scalar::Boolean(true)),
+        ])),
+        fm_entry("door", make_entity_decl("door", "LockedDoor", vec![
+            ("locked", Scalar::Boolean(false)),
+        ])),
+    ])), Vec::new());
     let diag = link_and_validate(single_file_cu(ast));
-    assert!(has_error(&diag, "URD422"), "Expected URD422, got: {:?}", diag.all());
+    assert!(!has_info(&diag, "URD440"), "Unexpected URD440: {:?}", diag.all());
 }
 
 #[test]
-fn container_kind_null_skip() {
-    // If container_ref is not resolvable, LINK emits URD301 and VALIDATE skips.
+fn redundant_number_override_coerces_integer() {
+    // `1` and `1.0` are the same number.
     let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
-        fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
-        fm_entry("key", make_entity_decl("key", "Key", vec![])),
-    ])), vec![
-        containment_check("key", "nowhere", false),
-    ]);
+        fm_entry("Item", make_type_def("Item", vec![], vec![
+            make_property_with_default("weight", "number", Scalar::Integer(1)),
+        ])),
+        fm_entry("item", make_entity_decl("item", "Item", vec![
+            ("weight", Scalar::Number(1.0)),
+        ])),
+    ])), Vec::new());
     let diag = link_and_validate(single_file_cu(ast));
-    // LINK emits URD301 for "nowhere", VALIDATE emits zero URD4xx errors.
-    assert!(has_error(&diag, "URD301"));
-    assert_eq!(count_validate_errors(&diag), 0);
+    assert!(has_info(&diag, "URD440"), "Expected URD440 info, got: {:?}", diag.all());
 }
 
 #[test]
-fn keyword_shadows_location() {
-    // Location with ID "player" exists. `? @key in player` resolves to keyword.
+fn mistyped_override_not_flagged_redundant() {
+    // An integer property overridden with 1.0 is a type error, not a no-op.
     let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
-        fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
-        fm_entry("key", make_entity_decl("key", "Key", vec![])),
-    ])), vec![
-        location("player"),
-        containment_check("key", "player", false),
-    ]);
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![
+            make_property_with_default("trust", "integer", Scalar::Integer(1)),
+        ])),
+        fm_entry("guard", make_entity_decl("guard", "Guard", vec![
+            ("trust", Scalar::Number(1.0)),
+        ])),
+    ])), Vec::new());
     let diag = link_and_validate(single_file_cu(ast));
-    // Keyword wins — no errors from either LINK or VALIDATE.
-    assert_eq!(count_validate_errors(&diag), 0);
+    assert!(has_error(&diag, "URD401"), "Expected URD401, got: {:?}", diag.all());
+    assert!(!has_info(&diag, "URD440"), "Unexpected URD440: {:?}", diag.all());
 }
 
 #[test]
-fn exhaustion_valid() {
+fn hidden_enum_override_invalid() {
     let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
-        fm_entry("Guard", make_type_def("Guard", vec![], vec![])),
-        fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
-    ])), vec![
-        location("Tavern"),
-        section("topics"),
-        choice("Ask about weather", false),
-        exhaustion_check("topics"),
-    ]);
+        fm_entry("Door", make_type_def("Door", vec![], vec![
+            PropertyDef {
+                visibility: Some("hidden".to_string()),
+                ..make_property_with_values("prize", "enum", vec!["goat", "car"])
+            },
+        ])),
+        fm_entry("door", make_entity_decl("door", "Door", vec![
+            ("prize", Scalar::String("banana".to_string())),
+        ])),
+    ])), Vec::new());
     let diag = link_and_validate(single_file_cu(ast));
-    assert_eq!(count_validate_errors(&diag), 0);
+    assert!(has_error(&diag, "URD402"), "Expected URD402, got: {:?}", diag.all());
 }
 
 #[test]
-fn exhaustion_cross_file() {
-    // ExhaustionCheck references a section in a different file.
-    let ast_types = make_file_ast("types.urd.md", Some(make_frontmatter(vec![
+fn hidden_range_override_invalid() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![
+            PropertyDef {
+                visibility: Some("hidden".to_string()),
+                ..make_property_with_range("trust", "integer", Some(0.0), Some(100.0))
+            },
+        ])),
+        fm_entry("guard", make_entity_decl("guard", "Guard", vec![
+            ("trust", Scalar::Integer(150)),
+        ])),
+    ])), Vec::new());
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(has_error(&diag, "URD418"), "Expected URD418, got: {:?}", diag.all());
+}
+
+#[test]
+fn hidden_ref_override_invalid() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
         fm_entry("Guard", make_type_def("Guard", vec![], vec![])),
-    ])), vec![
-        location("Tavern"),
-        section("topics"),
-        choice("Ask about weather", false),
-    ]);
-    let ast_main = make_file_ast("main.urd.md", Some(Frontmatter {
-        entries: vec![
-            FrontmatterEntry {
-                key: "import".to_string(),
-                value: FrontmatterValue::ImportDecl(ImportDecl {
-                    path: "types.urd.md".to_string(),
-                    span: span("main.urd.md", 1),
-                }),
-                span: span("main.urd.md", 1),
+        fm_entry("LockedDoor", make_type_def("LockedDoor", vec![], vec![
+            PropertyDef {
+                visibility: Some("hidden".to_string()),
+                ..make_property_with_ref_type("requires", "Key")
             },
-            fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
-        ],
-        span: span("main.urd.md", 1),
-    }), vec![
-        // Exhaustion check referencing section in types.urd.md — should fail LINK resolution.
-        exhaustion_check("topics"),
-    ]);
-    let cu = two_file_cu(ast_types, ast_main);
-    let diag = link_and_validate(cu);
-    // LINK should emit URD309 for unresolved section (not in current file's local_sections).
-    assert!(has_error(&diag, "URD309"), "Expected URD309, got: {:?}", diag.all());
+        ])),
+        fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+        fm_entry("door", make_entity_decl("door", "LockedDoor", vec![
+            ("requires", Scalar::String("guard".to_string())),
+        ])),
+    ])), Vec::new());
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(has_error(&diag, "URD419"), "Expected URD419, got: {:?}", diag.all());
 }
 
 // ═══════════════════════════════════════════════════════════
-// Effect Validation Tests
+// Condition Validation Tests
 // ═══════════════════════════════════════════════════════════
 
 #[test]
-fn set_valid() {
+fn property_comparison_valid() {
     let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
         fm_entry("Guard", make_type_def("Guard", vec![], vec![
             make_property_with_values("mood", "enum", vec!["friendly", "neutral", "hostile"]),
         ])),
         fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
     ])), vec![
-        set_effect("@guard.mood", "neutral"),
+        property_comparison("guard", "mood", "==", "neutral"),
     ]);
     let diag = link_and_validate(single_file_cu(ast));
     assert_eq!(count_validate_errors(&diag), 0);
 }
 
 #[test]
-fn set_type_mismatch() {
+fn ordering_on_enum() {
     let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
         fm_entry("Guard", make_type_def("Guard", vec![], vec![
             make_property_with_values("mood", "enum", vec!["friendly", "neutral", "hostile"]),
         ])),
         fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
     ])), vec![
-        set_effect("@guard.mood", "42"),
+        property_comparison("guard", "mood", ">", "neutral"),
     ]);
     let diag = link_and_validate(single_file_cu(ast));
-    assert!(has_error(&diag, "URD401"), "Expected URD401, got: {:?}", diag.all());
+    assert!(has_error(&diag, "URD420"), "Expected URD420, got: {:?}", diag.all());
 }
 
 #[test]
-fn arithmetic_on_integer() {
+fn value_type_mismatch_in_condition() {
     let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
         fm_entry("Guard", make_type_def("Guard", vec![], vec![
             make_property_with_range("trust", "integer", Some(0.0), Some(100.0)),
         ])),
         fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
     ])), vec![
-        set_effect_arithmetic("@guard.trust", "+", "10"),
+        property_comparison("guard", "trust", "==", "high"),
     ]);
     let diag = link_and_validate(single_file_cu(ast));
-    assert_eq!(count_validate_errors(&diag), 0);
+    assert!(has_error(&diag, "URD401"), "Expected URD401, got: {:?}", diag.all());
 }
 
-#[test]
-fn arithmetic_on_enum() {
+/// Validate `? @guard.trust <op> <value>` against a `trust` property with the given range.
+fn validate_range_condition(
+    prop_type: &str,
+    min: Option<f64>,
+    max: Option<f64>,
+    op: &str,
+    value: &str,
+) -> DiagnosticCollector {
     let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
         fm_entry("Guard", make_type_def("Guard", vec![], vec![
-            make_property_with_values("mood", "enum", vec!["friendly", "neutral", "hostile"]),
+            make_property_with_range("trust", prop_type, min, max),
         ])),
         fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
     ])), vec![
-        set_effect_arithmetic("@guard.mood", "+", "1"),
+        property_comparison("guard", "trust", op, value),
     ]);
-    let diag = link_and_validate(single_file_cu(ast));
-    assert!(has_error(&diag, "URD424"), "Expected URD424, got: {:?}", diag.all());
+    link_and_validate(single_file_cu(ast))
 }
 
 #[test]
-fn move_valid_portable_to_player() {
+fn condition_greater_than_above_max() {
+    let diag = validate_range_condition("integer", Some(0.0), Some(100.0), ">", "150");
+    assert!(has_warning(&diag, "URD435"), "Expected URD435, got: {:?}", diag.all());
+    let d = diag.all().iter().find(|d| d.code == "URD435").unwrap();
+    assert!(d.message.contains("[0, 100]"), "Range missing from: {}", d.message);
+}
+
+#[test]
+fn condition_greater_than_at_max_integer() {
+    // No integer in [0, 100] exceeds 100; 99 is satisfiable.
+    let diag = validate_range_condition("integer", Some(0.0), Some(100.0), ">", "100");
+    assert!(has_warning(&diag, "URD435"), "Expected URD435, got: {:?}", diag.all());
+    let diag = validate_range_condition("integer", Some(0.0), Some(100.0), ">", "99");
+    assert!(!has_warning(&diag, "URD435"), "Unexpected URD435: {:?}", diag.all());
+}
+
+#[test]
+fn condition_less_than_below_min() {
+    let diag = validate_range_condition("integer", Some(0.0), Some(100.0), "<", "-5");
+    assert!(has_warning(&diag, "URD435"), "Expected URD435, got: {:?}", diag.all());
+    let diag = validate_range_condition("integer", Some(0.0), Some(100.0), "<=", "-1");
+    assert!(has_warning(&diag, "URD435"), "Expected URD435, got: {:?}", diag.all());
+}
+
+#[test]
+fn condition_greater_equal_above_max() {
+    let diag = validate_range_condition("number", Some(0.0), Some(1.0), ">=", "1.5");
+    assert!(has_warning(&diag, "URD435"), "Expected URD435, got: {:?}", diag.all());
+}
+
+#[test]
+fn condition_equality_outside_range() {
+    let diag = validate_range_condition("integer", Some(0.0), Some(100.0), "==", "200");
+    assert!(has_warning(&diag, "URD435"), "Expected URD435, got: {:?}", diag.all());
+}
+
+#[test]
+fn condition_inequality_outside_range_always_true() {
+    let diag = validate_range_condition("integer", Some(0.0), Some(100.0), "!=", "200");
+    assert!(has_info(&diag, "URD436"), "Expected URD436, got: {:?}", diag.all());
+}
+
+#[test]
+fn condition_greater_equal_min_always_true() {
+    let diag = validate_range_condition("integer", Some(0.0), Some(100.0), ">=", "0");
+    assert!(has_info(&diag, "URD436"), "Expected URD436, got: {:?}", diag.all());
+    assert!(!has_warning(&diag, "URD435"));
+    let diag = validate_range_condition("integer", Some(0.0), Some(100.0), "<", "101");
+    assert!(has_info(&diag, "URD436"), "Expected URD436, got: {:?}", diag.all());
+}
+
+#[test]
+fn condition_within_range_no_diagnostic() {
+    for (op, value) in [(">", "50"), (">=", "100"), ("<", "1"), ("<=", "0"), ("==", "50"), ("!=", "50")] {
+        let diag = validate_range_condition("integer", Some(0.0), Some(100.0), op, value);
+        assert!(
+            !diag.all().iter().any(|d| d.code == "URD435" || d.code == "URD436"),
+            "Unexpected range diagnostic for {} {}: {:?}", op, value, diag.all(),
+        );
+    }
+}
+
+#[test]
+fn condition_without_range_no_diagnostic() {
+    let diag = validate_range_condition("integer", None, None, ">", "1000000");
+    assert!(!diag.all().iter().any(|d| d.code == "URD435" || d.code == "URD436"), "{:?}", diag.all());
+}
+
+#[test]
+fn condition_single_bound() {
+    // Only a minimum: `< 0` is unsatisfiable, `> 1000` is fine.
+    let diag = validate_range_condition("integer", Some(0.0), None, "<", "0");
+    assert!(has_warning(&diag, "URD435"), "Expected URD435, got: {:?}", diag.all());
+    let diag = validate_range_condition("integer", Some(0.0), None, ">", "1000");
+    assert!(!diag.all().iter().any(|d| d.code == "URD435" || d.code == "URD436"), "{:?}", diag.all());
+}
+
+#[test]
+fn condition_range_skips_non_numeric_literal() {
+    let diag = validate_range_condition("integer", Some(0.0), Some(100.0), ">", "high");
+    assert!(has_error(&diag, "URD401"), "Expected URD401, got: {:?}", diag.all());
+    assert!(!has_warning(&diag, "URD435"));
+}
+
+fn validate_guard_condition(condition: ContentNode) -> DiagnosticCollector {
+    validate_guard_content(vec![condition])
+}
+
+fn validate_guard_content(content: Vec<ContentNode>) -> DiagnosticCollector {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![
+            make_property_with_values("mood", "enum", vec!["friendly", "neutral", "hostile"]),
+            make_property_with_range("trust", "integer", Some(0.0), Some(100.0)),
+            make_property("alert", "boolean"),
+        ])),
+        fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+    ])), content);
+    link_and_validate(single_file_cu(ast))
+}
+
+#[test]
+fn bare_condition_on_boolean_is_valid() {
+    for condition in [bare_read("guard", "alert"), negated(bare_read("guard", "alert"))] {
+        let diag = validate_guard_condition(condition);
+        assert_eq!(count_validate_errors(&diag), 0, "{:?}", diag.all());
+    }
+}
+
+#[test]
+fn bare_condition_on_enum_is_error() {
+    // On a sticky choice, so the enum-value check (URD432) sees it too
+    // and the section needs no fallthrough.
+    let diag = validate_guard_content(vec![
+        section("watch"),
+        choice_with_content("Ask", true, vec![bare_read("guard", "mood")]),
+    ]);
+    let codes: Vec<&str> = diag.all().iter().map(|d| d.code.as_str()).collect();
+    assert_eq!(codes, ["URD453"], "URD453 replaces the type mismatch and enum-value checks");
+    let d = diag.all().iter().find(|d| d.code == "URD453").unwrap();
+    assert!(d.message.contains("'@guard.mood'"), "{}", d.message);
+    assert_eq!(d.suggestion.as_deref(), Some("Write an explicit comparison, such as '@guard.mood == friendly'."));
+}
+
+#[test]
+fn negated_bare_condition_on_integer_is_error() {
+    let diag = validate_guard_condition(negated(bare_read("guard", "trust")));
+    assert!(has_error(&diag, "URD453"), "Expected URD453, got: {:?}", diag.all());
+    let d = diag.all().iter().find(|d| d.code == "URD453").unwrap();
+    assert!(d.message.contains("'!@guard.trust'"), "{}", d.message);
+}
+
+#[test]
+fn negated_comparison_checks_written_operator() {
+    // `not @guard.mood < neutral` still orders an enum.
+    let diag = validate_guard_condition(negated(property_comparison("guard", "mood", "<", "neutral")));
+    assert!(has_error(&diag, "URD420"), "Expected URD420, got: {:?}", diag.all());
+    let diag = validate_guard_condition(negated(property_comparison("guard", "mood", "==", "sleepy")));
+    assert!(has_error(&diag, "URD401"), "Expected URD401, got: {:?}", diag.all());
+}
+
+#[test]
+fn negated_comparison_range_uses_flipped_operator() {
+    // `not @guard.trust < 0` tests `>= 0`, which every value satisfies.
+    let diag = validate_guard_condition(negated(property_comparison("guard", "trust", "<", "0")));
+    assert!(has_info(&diag, "URD436"), "Expected URD436, got: {:?}", diag.all());
+    assert!(!has_warning(&diag, "URD435"), "{:?}", diag.all());
+    let d = diag.all().iter().find(|d| d.code == "URD436").unwrap();
+    assert!(d.message.contains("'@guard.trust >= 0'"), "{}", d.message);
+}
+
+/// Validate `condition` against a Guard with one property of each scalar
+/// type, beside a second Guard and a Lamp.
+fn validate_typed_condition(condition: ContentNode) -> DiagnosticCollector {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![
+            make_property("alert", "boolean"),
+            make_property_with_values("mood", "enum", vec!["friendly", "neutral", "hostile"]),
+            make_property("name", "string"),
+            make_property("trust", "integer"),
+            make_property("weight", "number"),
+            make_property_with_ref_type("friend", "Guard"),
+        ])),
+        fm_entry("Lamp", make_type_def("Lamp", vec![], vec![])),
+        fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+        fm_entry("other", make_entity_decl("other", "Guard", vec![])),
+        fm_entry("lamp", make_entity_decl("lamp", "Lamp", vec![])),
+    ])), vec![condition]);
+    link_and_validate(single_file_cu(ast))
+}
+
+#[test]
+fn comparison_operators_allowed_per_type() {
+    let cases = [
+        ("alert", "true", &["==", "!="][..]),
+        ("mood", "neutral", &["==", "!="]),
+        ("name", "Bob", &["==", "!="]),
+        ("friend", "@other", &["==", "!="]),
+        ("trust", "3", &["==", "!=", "<", ">", "<=", ">="]),
+        ("weight", "2.5", &["==", "!=", "<", ">", "<=", ">="]),
+    ];
+    for (property, value, operators) in cases {
+        for op in operators {
+            let diag = validate_typed_condition(property_comparison("guard", property, op, value));
+            assert_eq!(count_validate_errors(&diag), 0, "{} {} {}: {:?}", property, op, value, diag.all());
+        }
+    }
+}
+
+#[test]
+fn comparison_operators_rejected_per_type() {
+    for (property, value, type_name) in [
+        ("alert", "true", "boolean"),
+        ("mood", "helpful", "enum"),
+        ("name", "Bob", "string"),
+        ("friend", "@other", "ref"),
+    ] {
+        for op in ["<", ">", "<=", ">="] {
+            let diag = validate_typed_condition(property_comparison("guard", property, op, value));
+            let d = diag.all().iter().find(|d| d.code == "URD420").expect("URD420");
+            assert_eq!(
+                d.message,
+                format!(
+                    "Operator '{}' is not valid for property '{}' of type '{}'. Allowed operators: ==, !=.",
+                    op, property, type_name,
+                ),
+            );
+        }
+    }
+}
+
+#[test]
+fn comparison_literal_shape_per_type() {
+    for (property, value) in [
+        ("alert", "yes"),
+        ("alert", "1"),
+        ("trust", "2.5"),
+        ("trust", "many"),
+        ("weight", "2e"),
+        ("weight", "inf"),
+        ("mood", "sleepy"),
+    ] {
+        let diag = validate_typed_condition(property_comparison("guard", property, "==", value));
+        assert!(has_error(&diag, "URD401"), "{} == {}: {:?}", property, value, diag.all());
+    }
+    for (property, value) in [("alert", "false"), ("trust", "-4"), ("weight", "3"), ("weight", "-0.5")] {
+        let diag = validate_typed_condition(property_comparison("guard", property, "==", value));
+        assert_eq!(count_validate_errors(&diag), 0, "{} == {}: {:?}", property, value, diag.all());
+    }
+}
+
+#[test]
+fn ref_comparison_needs_a_declared_entity() {
+    let diag = validate_typed_condition(property_comparison("guard", "friend", "==", "other"));
+    let d = diag.all().iter().find(|d| d.code == "URD458").expect("URD458");
+    assert_eq!(
+        d.message,
+        "Property 'friend' on entity '@guard' is a ref and can only be compared with an entity reference, but got 'other'."
+    );
+    assert_eq!(d.suggestion.as_deref(), Some("Write the entity with its sigil: '@other'."));
+
+    let diag = validate_typed_condition(property_comparison("guard", "friend", "!=", "none"));
+    let d = diag.all().iter().find(|d| d.code == "URD458").expect("URD458");
+    assert_eq!(d.suggestion, None);
+
+    let diag = validate_typed_condition(property_comparison("guard", "friend", "==", "@nobody"));
+    let d = diag.all().iter().find(|d| d.code == "URD458").expect("URD458");
+    assert_eq!(d.message, "Property 'friend' on entity '@guard' is compared with '@nobody', which is not a declared entity.");
+
+    let diag = validate_typed_condition(property_comparison("guard", "friend", "==", "@lamp"));
+    assert!(has_error(&diag, "URD419"), "{:?}", diag.all());
+    assert!(!has_error(&diag, "URD458"), "{:?}", diag.all());
+}
+
+#[test]
+fn operator_checks_leave_containment_and_exhaustion_alone() {
+    let diag = validate_typed_condition(containment_check("lamp", "player", true));
+    assert_eq!(count_validate_errors(&diag), 0, "{:?}", diag.all());
+    let diag = validate_typed_condition(containment_check("guard", "other", false));
+    assert!(has_error(&diag, "URD422"), "{:?}", diag.all());
+    assert!(!diag.all().iter().any(|d| d.code == "URD420" || d.code == "URD458"), "{:?}", diag.all());
+    let diag = validate_typed_condition(exhaustion_check("topics"));
+    assert!(!diag.all().iter().any(|d| d.code.starts_with("URD4")), "{:?}", diag.all());
+}
+
+#[test]
+fn containment_player_keyword_valid() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
+        fm_entry("key", make_entity_decl("key", "Key", vec![])),
+    ])), vec![
+        containment_check("key", "player", false),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert_eq!(count_validate_errors(&diag), 0);
+}
+
+#[test]
+fn containment_here_keyword_valid() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
+        fm_entry("key", make_entity_decl("key", "Key", vec![])),
+    ])), vec![
+        containment_check("key", "here", false),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert_eq!(count_validate_errors(&diag), 0);
+}
+
+#[test]
+fn container_entity_without_trait() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
+        fm_entry("Door", make_type_def("Door", vec![], vec![])),  // no container trait
+        fm_entry("key", make_entity_decl("key", "Key", vec![])),
+        fm_entry("door", make_entity_decl("door", "Door", vec![])),
+    ])), vec![
+        containment_check("key", "door", false),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(has_error(&diag, "URD422"), "Expected URD422, got: {:?}", diag.all());
+}
+
+#[test]
+fn container_kind_null_skip() {
+    // If container_ref is not resolvable, LINK emits URD301 and VALIDATE skips.
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
+        fm_entry("key", make_entity_decl("key", "Key", vec![])),
+    ])), vec![
+        containment_check("key", "nowhere", false),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    // LINK emits URD301 for "nowhere", VALIDATE emits zero URD4xx errors.
+    assert!(has_error(&diag, "URD301"));
+    assert_eq!(count_validate_errors(&diag), 0);
+}
+
+#[test]
+fn keyword_shadows_location() {
+    // Location with ID "player" exists. `? @key in player` resolves to keyword.
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
+        fm_entry("key", make_entity_decl("key", "Key", vec![])),
+    ])), vec![
+        location("player"),
+        containment_check("key", "player", false),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    // Keyword wins — no errors from either LINK or VALIDATE.
+    assert_eq!(count_validate_errors(&diag), 0);
+}
+
+#[test]
+fn exhaustion_valid() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![])),
+        fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+    ])), vec![
+        location("Tavern"),
+        section("topics"),
+        choice("Ask about weather", false),
+        exhaustion_check("topics"),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert_eq!(count_validate_errors(&diag), 0);
+}
+
+#[test]
+fn exhaustion_cross_file() {
+    // ExhaustionCheck references a section in a different file.
+    let ast_types = make_file_ast("types.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![])),
+    ])), vec![
+        location("Tavern"),
+        section("topics"),
+        choice("Ask about weather", false),
+    ]);
+    let ast_main = make_file_ast("main.urd.md", Some(Frontmatter {
+        entries: vec![
+            FrontmatterEntry {
+                key: "import".to_string(),
+                value: FrontmatterValue::ImportDecl(ImportDecl {
+                    path: "types.urd.md".to_string(),
+                    span: span("main.urd.md", 1),
+                }),
+                span: span("main.urd.md", 1),
+            },
+            fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+        ],
+        span: span("main.urd.md", 1),
+    }), vec![
+        // Exhaustion check referencing section in types.urd.md — should fail LINK resolution.
+        exhaustion_check("topics"),
+    ]);
+    let cu = two_file_cu(ast_types, ast_main);
+    let diag = link_and_validate(cu);
+    // LINK should emit URD309 for unresolved section (not in current file's local_sections).
+    assert!(has_error(&diag, "URD309"), "Expected URD309, got: {:?}", diag.all());
+}
+
+#[test]
+fn exhaustion_cross_file_qualified() {
+    // A file-qualified check may name a section in an imported file.
+    let ast_tavern = make_file_ast("tavern.urd.md", None, vec![
+        location("Tavern"),
+        ContentNode::SectionLabel(SectionLabel {
+            name: "topics".to_string(),
+            explicit_id: None,
+            param: None,
+            doc: None,
+            span: span("tavern.urd.md", 3),
+        }),
+        choice("Ask about weather", false),
+    ]);
+    let ast_main = make_file_ast("main.urd.md", Some(make_frontmatter(vec![
+        fm_entry("import", FrontmatterValue::ImportDecl(ImportDecl {
+            path: "tavern.urd.md".to_string(),
+            span: span("main.urd.md", 1),
+        })),
+    ])), vec![
+        location("Hall"),
+        section("talk"),
+        choice_with_content("Ask again", false, vec![exhaustion_check("tavern/topics")]),
+    ]);
+    let diag = link_and_validate(two_file_cu(ast_tavern, ast_main));
+    assert!(!has_error(&diag, "URD309"), "{:?}", diag.all());
+    assert!(!has_error(&diag, "URD423"), "{:?}", diag.all());
+}
+
+// ═══════════════════════════════════════════════════════════
+// Effect Validation Tests
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn set_valid() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![
+            make_property_with_values("mood", "enum", vec!["friendly", "neutral", "hostile"]),
+        ])),
+        fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+    ])), vec![
+        set_effect("@guard.mood", "neutral"),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert_eq!(count_validate_errors(&diag), 0);
+}
+
+#[test]
+fn set_type_mismatch() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![
+            make_property_with_values("mood", "enum", vec!["friendly", "neutral", "hostile"]),
+        ])),
+        fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+    ])), vec![
+        set_effect("@guard.mood", "42"),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(has_error(&diag, "URD401"), "Expected URD401, got: {:?}", diag.all());
+}
+
+#[test]
+fn location_hook_valid() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![
+            make_property_with_values("mood", "enum", vec!["friendly", "neutral", "hostile"]),
+        ])),
+        fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+    ])), vec![
+        location("Gatehouse"),
+        location_hook(HookKind::Enter, vec![
+            property_comparison("guard", "mood", "!=", "hostile"),
+            set_effect("@guard.mood", "neutral"),
+        ]),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert_eq!(count_validate_errors(&diag), 0, "{:?}", diag.all());
+}
+
+#[test]
+fn location_hook_invalid_enum_value() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![
+            make_property_with_values("mood", "enum", vec!["friendly", "neutral", "hostile"]),
+        ])),
+        fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+    ])), vec![
+        location("Gatehouse"),
+        location_hook(HookKind::Enter, vec![
+            set_effect("@guard.mood", "furious"),
+        ]),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(has_error(&diag, "URD401"), "Expected URD401, got: {:?}", diag.all());
+}
+
+#[test]
+fn location_hook_condition_invalid_operator() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![
+            make_property_with_values("mood", "enum", vec!["friendly", "neutral", "hostile"]),
+        ])),
+        fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+    ])), vec![
+        location("Gatehouse"),
+        location_hook(HookKind::Exit, vec![
+            property_comparison("guard", "mood", ">", "neutral"),
+            set_effect("@guard.mood", "hostile"),
+        ]),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(has_error(&diag, "URD420"), "Expected URD420, got: {:?}", diag.all());
+}
+
+#[test]
+fn arithmetic_on_integer() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![
+            make_property_with_range("trust", "integer", Some(0.0), Some(100.0)),
+        ])),
+        fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+    ])), vec![
+        set_effect_arithmetic("@guard.trust", "+", "10"),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert_eq!(count_validate_errors(&diag), 0);
+}
+
+#[test]
+fn arithmetic_on_enum() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![
+            make_property_with_values("mood", "enum", vec!["friendly", "neutral", "hostile"]),
+        ])),
+        fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+    ])), vec![
+        set_effect_arithmetic("@guard.mood", "+", "1"),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(has_error(&diag, "URD424"), "Expected URD424, got: {:?}", diag.all());
+}
+
+#[test]
+fn move_valid_portable_to_player() {
     let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
         fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
         fm_entry("key", make_entity_decl("key", "Key", vec![])),
@@ -1021,6 +1693,138 @@ fn world_entry_invalid() {
     assert!(has_error(&diag, "URD405"), "Expected URD405, got: {:?}", diag.all());
 }
 
+/// Link and validate a one-file world with the given world block fields
+/// and content.
+fn validate_world(fields: Vec<(&str, Scalar)>, content: Vec<ContentNode>) -> DiagnosticCollector {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("world", make_world_block(fields)),
+    ])), content);
+    link_and_validate(single_file_cu(ast))
+}
+
+#[test]
+fn world_start_accepts_display_name_or_id() {
+    for start in ["Harbour Gate", "harbour-gate"] {
+        let diag = validate_world(
+            vec![("start", Scalar::String(start.to_string()))],
+            vec![location("Harbour Gate")],
+        );
+        assert!(!has_error(&diag, "URD404"), "{}: {:?}", start, diag.all());
+    }
+}
+
+#[test]
+fn world_start_near_miss_suggests_id_and_name() {
+    let diag = validate_world(
+        vec![("start", Scalar::String("Harbor Gate".to_string()))],
+        vec![location("Harbour Gate")],
+    );
+    let d = diag.all().iter().find(|d| d.code == "URD404").expect("Expected URD404");
+    assert_eq!(
+        d.message,
+        "world.start references 'Harbor Gate' (ID 'harbor-gate') but no location with that ID exists."
+    );
+    assert_eq!(d.suggestion.as_deref(), Some("Did you mean 'harbour-gate' (from 'Harbour Gate')?"));
+
+    let diag = validate_world(
+        vec![("start", Scalar::String("harbor-gate".to_string()))],
+        vec![location("Harbour Gate")],
+    );
+    let d = diag.all().iter().find(|d| d.code == "URD404").expect("Expected URD404");
+    assert_eq!(d.message, "world.start references 'harbor-gate' but no location with that ID exists.");
+    assert_eq!(d.suggestion.as_deref(), Some("Did you mean 'harbour-gate'?"));
+}
+
+#[test]
+fn world_entry_accepts_display_name_or_id() {
+    for entry in ["The Heist", "the-heist"] {
+        let diag = validate_world(
+            vec![("entry", Scalar::String(entry.to_string()))],
+            vec![sequence_heading("The Heist"), phase_heading("Setup", false)],
+        );
+        assert!(!has_error(&diag, "URD405"), "{}: {:?}", entry, diag.all());
+    }
+}
+
+#[test]
+fn world_entry_near_miss_suggests_id() {
+    let diag = validate_world(
+        vec![("entry", Scalar::String("The Hiest".to_string()))],
+        vec![sequence_heading("The Heist"), phase_heading("Setup", false)],
+    );
+    let d = diag.all().iter().find(|d| d.code == "URD405").expect("Expected URD405");
+    assert_eq!(
+        d.message,
+        "world.entry references 'The Hiest' (ID 'the-hiest') but no sequence with that ID exists."
+    );
+    assert_eq!(d.suggestion.as_deref(), Some("Did you mean 'the-heist'?"));
+}
+
+#[test]
+fn world_start_and_entry_must_be_strings() {
+    let diag = validate_world(
+        vec![("start", Scalar::Integer(3)), ("entry", Scalar::Boolean(true))],
+        vec![location("Harbour Gate")],
+    );
+    let messages: Vec<&str> =
+        diag.all().iter().filter(|d| d.code == "URD437").map(|d| d.message.as_str()).collect();
+    assert_eq!(messages, ["world.start must be a string.", "world.entry must be a string."]);
+    assert!(!has_error(&diag, "URD404") && !has_error(&diag, "URD405"), "{:?}", diag.all());
+}
+
+#[test]
+fn world_mode_must_be_known() {
+    let diag = validate_world(vec![("mode", Scalar::String("dialog".to_string()))], vec![]);
+    let messages: Vec<&str> =
+        diag.all().iter().filter(|d| d.code == "URD437").map(|d| d.message.as_str()).collect();
+    assert_eq!(messages, ["world.mode must be 'world' or 'dialogue'."]);
+
+    for mode in ["world", "dialogue"] {
+        let diag = validate_world(vec![("mode", Scalar::String(mode.to_string()))], vec![]);
+        assert!(!has_error(&diag, "URD437"), "{}: {:?}", mode, diag.all());
+    }
+}
+
+#[test]
+fn world_scope_must_be_known() {
+    let diag = validate_world(vec![("scope", Scalar::String("global".to_string()))], vec![]);
+    let messages: Vec<&str> =
+        diag.all().iter().filter(|d| d.code == "URD437").map(|d| d.message.as_str()).collect();
+    assert_eq!(messages, ["world.scope must be 'direct' or 'transitive'."]);
+
+    for scope in ["direct", "transitive"] {
+        let diag = validate_world(vec![("scope", Scalar::String(scope.to_string()))], vec![]);
+        assert!(!has_error(&diag, "URD437"), "{}: {:?}", scope, diag.all());
+    }
+}
+
+#[test]
+fn dialogue_mode_warns_about_locations() {
+    let diag = validate_world(
+        vec![("mode", Scalar::String("dialogue".to_string())), ("start", Scalar::String("gate".to_string()))],
+        vec![location("Gate"), exit_decl("north", "Gate")],
+    );
+    let d = diag.all().iter().find(|d| d.code == "URD461").expect("URD461");
+    assert_eq!(d.severity, Severity::Warning);
+    assert_eq!(
+        d.message,
+        "Location 'gate' is declared in a dialogue world, with 1 exit. A world with `mode: dialogue` has no locations."
+    );
+    assert_eq!(d.span.start_line, 10, "Reported at the heading");
+}
+
+#[test]
+fn dialogue_mode_skips_reachability() {
+    let content = vec![location("Gate"), location("Yard")];
+    let start = ("start", Scalar::String("gate".to_string()));
+    let diag = validate_world(vec![start.clone()], content.clone());
+    assert!(has_warning(&diag, "URD430") && !has_warning(&diag, "URD461"), "{:?}", diag.all());
+
+    let diag = validate_world(vec![start, ("mode", Scalar::String("dialogue".to_string()))], content);
+    assert!(!has_warning(&diag, "URD430"), "{:?}", diag.all());
+    assert_eq!(diag.all().iter().filter(|d| d.code == "URD461").count(), 2);
+}
+
 #[test]
 fn no_world_entry_freeform() {
     // No entry in world block → freeform, no error.
@@ -1073,18 +1877,41 @@ fn player_missing_trait() {
         fm_entry("player", make_entity_decl("player", "Guard", vec![])),
     ])), Vec::new());
     let diag = link_and_validate(single_file_cu(ast));
-    assert!(has_error(&diag, "URD412"), "Expected URD412, got: {:?}", diag.all());
+    assert!(has_error(&diag, "URD412"), "Expected URD412, got: {:?}", diag.all());
+}
+
+#[test]
+fn action_mutual_exclusion() {
+    // Create a choice-derived action (has target from choice).
+    // For action mutual exclusion, we need an ActionSymbol with both target and target_type.
+    // Choice-derived actions don't set both. We need to test frontmatter-declared actions.
+    // Since we don't have frontmatter action declarations yet, test via choice with both.
+    // Actually — actions come from choices. Choices have either target or target_type.
+    // A choice cannot have both target and target_type (PARSE doesn't produce this).
+    // But we can test it by constructing an AST with both.
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![])),
+        fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+    ])), vec![
+        location("Tavern"),
+        section("greet"),
+        ContentNode::Choice(Choice {
+            sticky: false,
+            label: "Attack".to_string(),
+            target: Some("guard".to_string()),
+            target_type: Some("Guard".to_string()),
+            content: Vec::new(),
+            indent_level: 1,
+            annotation: None,
+            span: span("test.urd.md", 20),
+        }),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(has_error(&diag, "URD406"), "Expected URD406, got: {:?}", diag.all());
 }
 
 #[test]
-fn action_mutual_exclusion() {
-    // Create a choice-derived action (has target from choice).
-    // For action mutual exclusion, we need an ActionSymbol with both target and target_type.
-    // Choice-derived actions don't set both. We need to test frontmatter-declared actions.
-    // Since we don't have frontmatter action declarations yet, test via choice with both.
-    // Actually — actions come from choices. Choices have either target or target_type.
-    // A choice cannot have both target and target_type (PARSE doesn't produce this).
-    // But we can test it by constructing an AST with both.
+fn action_mutual_exclusion_relates_declarations() {
     let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
         fm_entry("Guard", make_type_def("Guard", vec![], vec![])),
         fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
@@ -1103,7 +1930,67 @@ fn action_mutual_exclusion() {
         }),
     ]);
     let diag = link_and_validate(single_file_cu(ast));
-    assert!(has_error(&diag, "URD406"), "Expected URD406, got: {:?}", diag.all());
+    let d = diag.all().iter().find(|d| d.code == "URD406").expect("URD406");
+    let messages: Vec<_> = d.related.iter().map(|r| r.message.as_str()).collect();
+    assert_eq!(messages, ["'@guard' declared here.", "Type 'Guard' declared here."]);
+}
+
+fn type_target_choice(label: &str, target_type: &str) -> ContentNode {
+    ContentNode::Choice(Choice {
+        sticky: false,
+        label: label.to_string(),
+        target: None,
+        target_type: Some(target_type.to_string()),
+        content: Vec::new(),
+        indent_level: 1,
+        annotation: None,
+        span: span("test.urd.md", 20),
+    })
+}
+
+#[test]
+fn target_type_without_entities() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Door", make_type_def("Door", vec![], vec![])),
+    ])), vec![
+        location("Hall"),
+        section("doors"),
+        type_target_choice("Pick a door", "Door"),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    let d = diag.all().iter().find(|d| d.code == "URD460").expect("URD460");
+    assert_eq!(d.severity, Severity::Warning);
+    assert_eq!(
+        d.message,
+        "Action 'test/doors/pick-a-door' targets any 'Door', but no entity has that type, so it can never be taken."
+    );
+}
+
+#[test]
+fn target_type_with_entities() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Door", make_type_def("Door", vec![], vec![])),
+        fm_entry("red_door", make_entity_decl("red_door", "Door", vec![])),
+    ])), vec![
+        location("Hall"),
+        section("doors"),
+        type_target_choice("Pick a door", "Door"),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(!has_warning(&diag, "URD460"));
+    assert!(!diag.has_errors(), "{:?}", diag.all());
+}
+
+#[test]
+fn unknown_target_type_no_cascade() {
+    let ast = make_file_ast("test.urd.md", None, vec![
+        location("Hall"),
+        section("doors"),
+        type_target_choice("Pick a door", "Door"),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(has_error(&diag, "URD307"));
+    assert!(!has_warning(&diag, "URD460"));
 }
 
 #[test]
@@ -1229,6 +2116,111 @@ fn c9_nesting_depth_mixed() {
     assert_eq!(urd410_count, 1, "Only the depth-4 branch should trigger URD410: {:?}", diag.all());
 }
 
+#[test]
+fn world_seed_integer_valid() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("world", make_world_block(vec![
+            ("seed", Scalar::Integer(42)),
+            ("version", Scalar::String("1.0".to_string())),
+        ])),
+    ])), Vec::new());
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(!has_error(&diag, "URD437"), "Unexpected URD437: {:?}", diag.all());
+}
+
+#[test]
+fn world_seed_string_is_error() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("world", make_world_block(vec![
+            ("seed", Scalar::String("abc".to_string())),
+        ])),
+    ])), Vec::new());
+    let diag = link_and_validate(single_file_cu(ast));
+    let d = diag.all().iter().find(|d| d.code == "URD437").expect("Expected URD437");
+    assert_eq!(d.message, "world.seed must be an integer.");
+}
+
+#[test]
+fn world_version_number_is_error() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("world", make_world_block(vec![
+            ("version", Scalar::Number(1.5)),
+        ])),
+    ])), Vec::new());
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(has_error(&diag, "URD437"), "Expected URD437, got: {:?}", diag.all());
+}
+
+#[test]
+fn start_inventory_portable_valid() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
+        fm_entry("rusty_key", make_entity_decl("rusty_key", "Key", vec![])),
+        fm_entry("world", make_world_block(vec![
+            ("start_inventory", Scalar::List(vec![Scalar::EntityRef("rusty_key".to_string())])),
+        ])),
+    ])), Vec::new());
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(!diag.has_errors(), "Unexpected errors: {:?}", diag.all());
+    assert!(!has_warning(&diag, "URD438"), "Unexpected URD438: {:?}", diag.all());
+}
+
+#[test]
+fn start_inventory_not_a_list_is_error() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("world", make_world_block(vec![
+            ("start_inventory", Scalar::String("rusty_key".to_string())),
+        ])),
+    ])), Vec::new());
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(has_error(&diag, "URD437"), "Expected URD437, got: {:?}", diag.all());
+}
+
+#[test]
+fn unplaced_traits_not_a_list_is_error() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("world", make_world_block(vec![
+            ("unplaced_traits", Scalar::String("faction".to_string())),
+        ])),
+    ])), Vec::new());
+    let diag = link_and_validate(single_file_cu(ast));
+    let d = diag.all().iter().find(|d| d.code == "URD437").expect("Expected URD437");
+    assert_eq!(d.message, "world.unplaced_traits must be a list of strings.");
+}
+
+#[test]
+fn start_inventory_non_portable_warning() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Statue", make_type_def("Statue", vec![], vec![])),
+        fm_entry("statue", make_entity_decl("statue", "Statue", vec![])),
+        fm_entry("world", make_world_block(vec![
+            ("start_inventory", Scalar::List(vec![Scalar::EntityRef("statue".to_string())])),
+        ])),
+    ])), Vec::new());
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(has_warning(&diag, "URD438"), "Expected URD438 warning, got: {:?}", diag.all());
+    assert!(!diag.has_errors(), "Non-portable start items are a warning only: {:?}", diag.all());
+}
+
+#[test]
+fn start_inventory_also_placed_is_error() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
+        fm_entry("rusty_key", make_entity_decl("rusty_key", "Key", vec![])),
+        fm_entry("world", make_world_block(vec![
+            ("start_inventory", Scalar::List(vec![Scalar::EntityRef("rusty_key".to_string())])),
+        ])),
+    ])), vec![
+        location("Cell"),
+        entity_presence(vec!["rusty_key"]),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    let d = diag.all().iter().find(|d| d.code == "URD439").expect("Expected URD439");
+    assert_eq!(d.span.start_line, 5, "Primary span should be the world block");
+    assert_eq!(d.related.len(), 1);
+    assert_eq!(d.related[0].span.start_line, 12, "Related span should be the presence line");
+}
+
 // ═══════════════════════════════════════════════════════════
 // Skip Rule Tests
 // ═══════════════════════════════════════════════════════════
@@ -1298,47 +2290,289 @@ fn unrecognised_type_warns() {
         ])),
     ])), Vec::new());
     let diag = link_and_validate(single_file_cu(ast));
-    assert!(has_warning(&diag, "URD429"), "Expected URD429 for unrecognised type 'integr': {:?}", diag.all());
-    assert_eq!(count_validate_errors(&diag), 0, "URD429 is a warning, not an error");
+    assert!(has_warning(&diag, "URD429"), "Expected URD429 for unrecognised type 'integr': {:?}", diag.all());
+    assert_eq!(count_validate_errors(&diag), 0, "URD429 is a warning, not an error");
+}
+
+#[test]
+fn recognised_alias_no_warning() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![
+            make_property("trust", "int"),
+            make_property("flag", "bool"),
+            make_property("weight", "num"),
+            make_property("label", "str"),
+        ])),
+    ])), Vec::new());
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(!has_warning(&diag, "URD429"), "Recognised aliases should not trigger URD429: {:?}", diag.all());
+}
+
+#[test]
+fn recognised_canonical_no_warning() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![
+            make_property("trust", "integer"),
+            make_property("flag", "boolean"),
+            make_property("weight", "number"),
+            make_property("label", "string"),
+        ])),
+    ])), Vec::new());
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(!has_warning(&diag, "URD429"), "Canonical types should not trigger URD429: {:?}", diag.all());
+}
+
+#[test]
+fn uppercase_type_warns() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![
+            make_property("trust", "Int"),
+        ])),
+    ])), Vec::new());
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(has_warning(&diag, "URD429"), "Case-sensitive: 'Int' should trigger URD429: {:?}", diag.all());
+}
+
+// ═══════════════════════════════════════════════════════════
+// Unknown Trait Tests (URD441)
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn trait_typo_warns_with_suggestion() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Key", make_type_def("Key", vec!["portible", "interactable"], vec![])),
+    ])), Vec::new());
+    let diag = link_and_validate(single_file_cu(ast));
+    let d = diag.all().iter().find(|d| d.code == "URD441").expect("Expected URD441");
+    assert_eq!(d.severity, Severity::Warning);
+    assert_eq!(
+        d.message,
+        "Unknown trait 'portible' on type 'Key'. Built-in traits: container, portable, mobile, interactable."
+    );
+    assert_eq!(d.suggestion.as_deref(), Some("Did you mean 'portable'?"));
+    assert_eq!(diag.all().iter().filter(|d| d.code == "URD441").count(), 1, "Only the typo is reported");
+}
+
+#[test]
+fn builtin_traits_no_warning() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec!["container", "portable", "mobile", "interactable"], vec![])),
+    ])), Vec::new());
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(!diag.all().iter().any(|d| d.code == "URD441"), "Unexpected URD441: {:?}", diag.all());
+}
+
+#[test]
+fn unrelated_trait_has_no_suggestion() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec!["flammable"], vec![])),
+    ])), Vec::new());
+    let diag = link_and_validate(single_file_cu(ast));
+    let d = diag.all().iter().find(|d| d.code == "URD441").expect("Expected URD441");
+    assert!(d.suggestion.is_none());
+}
+
+#[test]
+fn custom_traits_allowed_downgrade_to_info() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("world", make_world_block(vec![
+            ("allow_custom_traits", Scalar::Boolean(true)),
+        ])),
+        fm_entry("Torch", make_type_def("Torch", vec!["portable", "flammable"], vec![])),
+    ])), Vec::new());
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(has_info(&diag, "URD441"), "Expected URD441 info, got: {:?}", diag.all());
+    assert!(!has_warning(&diag, "URD441"), "Opt-out should suppress the warning: {:?}", diag.all());
+}
+
+#[test]
+fn allow_custom_traits_must_be_boolean() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("world", make_world_block(vec![
+            ("allow_custom_traits", Scalar::String("yes".to_string())),
+        ])),
+        fm_entry("Torch", make_type_def("Torch", vec!["flammable"], vec![])),
+    ])), Vec::new());
+    let diag = link_and_validate(single_file_cu(ast));
+    let d = diag.all().iter().find(|d| d.code == "URD437").expect("Expected URD437");
+    assert_eq!(d.message, "world.allow_custom_traits must be a boolean.");
+    assert!(has_warning(&diag, "URD441"), "A non-boolean does not opt out: {:?}", diag.all());
+}
+
+// ═══════════════════════════════════════════════════════════
+// Property Group Tests (URD446)
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn property_group_collision_errors() {
+    let mut height = make_property("appearance.height", "integer");
+    height.span = span("test.urd.md", 4);
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![
+            make_property("appearance", "string"),
+            height,
+            make_property("appearance.eyes", "string"),
+        ])),
+    ])), Vec::new());
+    let diag = link_and_validate(single_file_cu(ast));
+    let found: Vec<_> = diag.all().iter().filter(|d| d.code == "URD446").collect();
+    assert_eq!(found.len(), 1, "One error per colliding property: {:?}", diag.all());
+    assert_eq!(found[0].severity, Severity::Error);
+    assert_eq!(
+        found[0].message,
+        "Property 'appearance' on type 'Guard' collides with property group 'appearance' ('appearance.height')."
+    );
+    assert_eq!(found[0].span.start_line, 3);
+    assert_eq!(found[0].related[0].span.start_line, 4);
+}
+
+#[test]
+fn property_group_without_scalar_is_valid() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![
+            make_property("appearance.height", "integer"),
+            make_property("appearance.eyes", "string"),
+            // A shared leading word is not a group prefix.
+            make_property("appearances", "integer"),
+        ])),
+    ])), Vec::new());
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(!has_error(&diag, "URD446"), "Unexpected URD446: {:?}", diag.all());
+}
+
+// ═══════════════════════════════════════════════════════════
+// Section Argument Tests (URD442)
+// ═══════════════════════════════════════════════════════════
+
+fn haggle_ast(argument: &str) -> FileAst {
+    make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Merchant", make_type_def("Merchant", vec![], vec![make_property("price", "integer")])),
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![])),
+        fm_entry("harbor_master", make_entity_decl("harbor_master", "Merchant", vec![])),
+        fm_entry("captain", make_entity_decl("captain", "Guard", vec![])),
+    ])), vec![
+        section("start"),
+        jump_with_argument("haggle", argument),
+        param_section("haggle", "merchant", "Merchant"),
+        property_comparison("merchant", "price", ">", "10"),
+    ])
+}
+
+#[test]
+fn section_argument_type_mismatch() {
+    let diag = link_and_validate(single_file_cu(haggle_ast("captain")));
+    let d = diag.all().iter().find(|d| d.code == "URD442").expect("Expected URD442");
+    assert_eq!(d.severity, Severity::Error);
+    assert_eq!(
+        d.message,
+        "Section 'test/haggle' expects '@merchant: Merchant', but '@captain' has type 'Guard'."
+    );
+}
+
+#[test]
+fn section_argument_type_match() {
+    let diag = link_and_validate(single_file_cu(haggle_ast("harbor_master")));
+    assert!(!has_error(&diag, "URD442"), "Unexpected URD442: {:?}", diag.all());
+}
+
+#[test]
+fn template_body_checked_against_param_type() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Merchant", make_type_def("Merchant", vec![], vec![make_property("price", "integer")])),
+    ])), vec![
+        param_section("haggle", "merchant", "Merchant"),
+        property_comparison("merchant", "price", "==", "cheap"),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(has_error(&diag, "URD401"), "Expected URD401, got: {:?}", diag.all());
+}
+
+// ═══════════════════════════════════════════════════════════
+// Dropped Text Tests (URD443)
+// ═══════════════════════════════════════════════════════════
+
+fn guard_frontmatter() -> Option<Frontmatter> {
+    Some(make_frontmatter(vec![
+        fm_entry("Guard", make_type_def("Guard", vec![], vec![])),
+        fm_entry("guard", make_entity_decl("guard", "Guard", vec![])),
+    ]))
+}
+
+#[test]
+fn dropped_prose_after_exit() {
+    let ast = make_file_ast("test.urd.md", None, vec![
+        location("Tavern"),
+        exit_decl("north", "Harbor"),
+        prose("The door creaks."),
+        location("Harbor"),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    let d = diag.all().iter().find(|d| d.code == "URD443").expect("Expected URD443");
+    assert_eq!(d.severity, Severity::Warning);
+    assert_eq!(d.span.start_line, 35);
+    assert_eq!(
+        d.message,
+        "This text will not appear in the compiled world. A location description ends at the first exit, hook, entity list or section. Move it directly below '# Tavern'."
+    );
+}
+
+#[test]
+fn description_prose_not_dropped() {
+    let ast = make_file_ast("test.urd.md", None, vec![
+        location("Tavern"),
+        prose("A low room that smells of smoke."),
+        exit_decl("north", "Harbor"),
+        location("Harbor"),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(!has_warning(&diag, "URD443"), "Description prose is emitted: {:?}", diag.all());
 }
 
 #[test]
-fn recognised_alias_no_warning() {
-    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
-        fm_entry("Guard", make_type_def("Guard", vec![], vec![
-            make_property("trust", "int"),
-            make_property("flag", "bool"),
-            make_property("weight", "num"),
-            make_property("label", "str"),
-        ])),
-    ])), Vec::new());
+fn on_exhausted_prose_not_dropped() {
+    let ast = make_file_ast("test.urd.md", None, vec![
+        location("Tavern"),
+        section("greet"),
+        choice("Hello", false),
+        choice("Goodbye", false),
+        prose("The tavern falls quiet."),
+    ]);
     let diag = link_and_validate(single_file_cu(ast));
-    assert!(!has_warning(&diag, "URD429"), "Recognised aliases should not trigger URD429: {:?}", diag.all());
+    assert!(!has_warning(&diag, "URD443"), "on_exhausted prose is emitted: {:?}", diag.all());
 }
 
 #[test]
-fn recognised_canonical_no_warning() {
-    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
-        fm_entry("Guard", make_type_def("Guard", vec![], vec![
-            make_property("trust", "integer"),
-            make_property("flag", "boolean"),
-            make_property("weight", "number"),
-            make_property("label", "string"),
-        ])),
-    ])), Vec::new());
+fn dropped_prose_in_choice_after_speech() {
+    let mut ask = choice("Ask", true);
+    if let ContentNode::Choice(c) = &mut ask {
+        c.content = vec![entity_speech("guard", "Move along."), prose("He turns away.")];
+    }
+    let ast = make_file_ast("test.urd.md", guard_frontmatter(), vec![
+        location("Tavern"),
+        section("greet"),
+        ask,
+    ]);
     let diag = link_and_validate(single_file_cu(ast));
-    assert!(!has_warning(&diag, "URD429"), "Canonical types should not trigger URD429: {:?}", diag.all());
+    let d = diag.all().iter().find(|d| d.code == "URD443").expect("Expected URD443");
+    assert_eq!(
+        d.message,
+        "This text will not appear in the compiled world. The response to choice 'Ask' is built from speech and stage directions. Write it as '@entity: text'."
+    );
 }
 
 #[test]
-fn uppercase_type_warns() {
-    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
-        fm_entry("Guard", make_type_def("Guard", vec![], vec![
-            make_property("trust", "Int"),
-        ])),
-    ])), Vec::new());
+fn dropped_speech_between_choices() {
+    let ast = make_file_ast("test.urd.md", guard_frontmatter(), vec![
+        location("Tavern"),
+        section("greet"),
+        choice("Hello", true),
+        entity_speech("guard", "Anything else?"),
+        choice("Goodbye", true),
+    ]);
     let diag = link_and_validate(single_file_cu(ast));
-    assert!(has_warning(&diag, "URD429"), "Case-sensitive: 'Int' should trigger URD429: {:?}", diag.all());
+    let d = diag.all().iter().find(|d| d.code == "URD443").expect("Expected URD443");
+    assert_eq!(d.span.start_line, 30);
+    assert!(d.message.contains("section 'greet'"), "Names the section: {}", d.message);
 }
 
 // ═══════════════════════════════════════════════════════════
@@ -1428,6 +2662,7 @@ fn integration_multi_file() {
                     ("label".to_string(), Scalar::String("Golden Key".to_string())),
                 ],
                 annotation: None,
+                doc: None,
                 span: span("main.urd.md", 3),
             })),
             fm_entry("chest", FrontmatterValue::EntityDecl(EntityDecl {
@@ -1435,6 +2670,7 @@ fn integration_multi_file() {
                 type_name: "Container".to_string(),
                 property_overrides: vec![],
                 annotation: None,
+                doc: None,
                 span: span("main.urd.md", 4),
             })),
         ],
@@ -1485,7 +2721,7 @@ fn integration_maximum_errors() {
     assert!(has_error(&diag, "URD405"), "Expected URD405 (world.entry)");
     assert!(has_warning(&diag, "URD411"), "Expected URD411 (urd override)");
     assert!(has_error(&diag, "URD416"), "Expected URD416 (inverted range)");
-    assert!(has_error(&diag, "URD417"), "Expected URD417 (range on string)");
+    assert!(has_warning(&diag, "URD417"), "Expected URD417 (range on string)");
     assert!(has_error(&diag, "URD414"), "Expected URD414 (empty enum)");
     assert!(has_error(&diag, "URD415"), "Expected URD415 (unknown ref type)");
     assert!(has_error(&diag, "URD402"), "Expected URD402 (invalid enum value)");
@@ -1691,7 +2927,7 @@ fn integration_monty_hall() {
         property_comparison("door1", "has_prize", "==", "true"),
         reveal_effect("@door1.has_prize"),
         set_effect("@player.status", "won"),
-        rule_block("host_reveal", "system", "after_choose"),
+        rule_block("host_reveal", "system", "action reveal"),
         sequence_heading("Game"),
         phase_heading("Setup", false),
     ]);
@@ -1704,14 +2940,27 @@ fn integration_monty_hall() {
 fn prose(text: &str) -> ContentNode {
     ContentNode::Prose(Prose {
         text: text.to_string(),
+        variants: Vec::new(),
         span: span("test.urd.md", 35),
     })
 }
 
+fn jump_with_argument(target: &str, argument: &str) -> ContentNode {
+    ContentNode::Jump(Jump {
+        target: target.to_string(),
+        is_exit_qualified: false,
+        argument: Some(argument.to_string()),
+        indent_level: 0,
+        annotation: None,
+        span: span("test.urd.md", 36),
+    })
+}
+
 fn jump(target: &str) -> ContentNode {
     ContentNode::Jump(Jump {
         target: target.to_string(),
         is_exit_qualified: false,
+        argument: None,
         indent_level: 0,
         annotation: None,
         span: span("test.urd.md", 36),
@@ -1746,6 +2995,7 @@ fn effect_node(target_prop: &str, value: &str) -> ContentNode {
             target_prop: target_prop.to_string(),
             operator: "=".to_string(),
             value_expr: value.to_string(),
+            spans: OperandSpans::whole(),
         },
         indent_level: 0,
         annotation: None,
@@ -2283,6 +3533,78 @@ fn s6_on_exhausted_content() {
     assert!(!has_warning(&diag, "URD433"), "on_exhausted content prevents URD433: {:?}", diag.all());
 }
 
+// ═══════════════════════════════════════════════════════════
+// Once conditions (URD444, URD445)
+// ═══════════════════════════════════════════════════════════
+
+#[test]
+fn once_on_sticky_choice() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("world", make_world_block(vec![
+            ("start", Scalar::String("tavern".to_string())),
+        ])),
+    ])), vec![
+        location("Tavern"),
+        section("greet"),
+        once_choice("Hello", true),
+        prose("The tavern falls quiet."),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(has_error(&diag, "URD444"), "Expected URD444: {:?}", diag.all());
+}
+
+#[test]
+fn once_outside_choice() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("world", make_world_block(vec![
+            ("start", Scalar::String("tavern".to_string())),
+        ])),
+    ])), vec![
+        location("Tavern"),
+        section("greet"),
+        once_condition(),
+        choice("Hello", false),
+        prose("The tavern falls quiet."),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(has_error(&diag, "URD445"), "Expected URD445: {:?}", diag.all());
+    assert!(!has_error(&diag, "URD444"));
+}
+
+#[test]
+fn s6_once_choice_is_one_shot() {
+    // A sticky `once` choice does not make the section safe.
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("world", make_world_block(vec![
+            ("start", Scalar::String("tavern".to_string())),
+        ])),
+    ])), vec![
+        location("Tavern"),
+        section("greet"),
+        choice("Hello", false),
+        once_choice("Order", true),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(has_warning(&diag, "URD433"), "Expected URD433: {:?}", diag.all());
+}
+
+#[test]
+fn s6_once_choice_with_sticky_sibling() {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("world", make_world_block(vec![
+            ("start", Scalar::String("tavern".to_string())),
+        ])),
+    ])), vec![
+        location("Tavern"),
+        section("greet"),
+        once_choice("Hello", false),
+        choice("Order", true),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(!has_warning(&diag, "URD433"), "Sticky choice: {:?}", diag.all());
+    assert!(!diag.has_errors(), "{:?}", diag.all());
+}
+
 // ═══════════════════════════════════════════════════════════
 // S8: Section-Exit Shadowing (URD434)
 // ═══════════════════════════════════════════════════════════
@@ -2385,3 +3707,487 @@ fn s8_exit_direction_matches_section() {
     let diag = link_and_validate(single_file_cu(ast));
     assert!(has_warning(&diag, "URD434"), "Exit key matches section: {:?}", diag.all());
 }
+
+// ═══════════════════════════════════════════════════════════
+// Exit Direction Tests (URD447, URD448)
+// ═══════════════════════════════════════════════════════════
+
+/// Deck and Galley, connected by `-> {there}: Galley` and `-> {back}: Deck`.
+fn deck_and_galley(world: Vec<(&str, Scalar)>, there: &str, back: &str) -> DiagnosticCollector {
+    let mut fields = vec![("start", Scalar::String("deck".to_string()))];
+    fields.extend(world);
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("world", make_world_block(fields)),
+    ])), vec![
+        location("Deck"),
+        exit_decl(there, "Galley"),
+        location("Galley"),
+        exit_decl(back, "Deck"),
+    ]);
+    link_and_validate(single_file_cu(ast))
+}
+
+#[test]
+fn exit_direction_typo_warns_with_suggestion() {
+    let diag = deck_and_galley(Vec::new(), "nroth", "south");
+    let found: Vec<_> = diag.all().iter().filter(|d| d.code == "URD447").collect();
+    assert_eq!(found.len(), 1, "Expected one URD447: {:?}", diag.all());
+    assert_eq!(found[0].severity, Severity::Warning);
+    assert_eq!(
+        found[0].message,
+        "Exit direction 'nroth' in location 'deck' is not a known direction."
+    );
+    assert_eq!(found[0].suggestion.as_deref(), Some("Did you mean 'north'?"));
+}
+
+#[test]
+fn exit_direction_custom_vocabulary() {
+    let directions = Scalar::List(vec![
+        Scalar::String("port".to_string()),
+        Scalar::String("starboard".to_string()),
+    ]);
+    let diag = deck_and_galley(vec![("directions", directions)], "port", "starboard");
+    assert!(!has_warning(&diag, "URD447"), "Custom directions accepted: {:?}", diag.all());
+
+    let diag = deck_and_galley(Vec::new(), "port", "starboard");
+    assert_eq!(diag.all().iter().filter(|d| d.code == "URD447").count(), 2);
+}
+
+#[test]
+fn exit_directions_must_be_a_list_of_strings() {
+    let diag = deck_and_galley(vec![("directions", Scalar::String("port".to_string()))], "north", "south");
+    assert!(has_error(&diag, "URD437"), "Expected URD437: {:?}", diag.all());
+}
+
+#[test]
+fn exit_reciprocal_same_direction_info() {
+    let diag = deck_and_galley(Vec::new(), "north", "north");
+    let found: Vec<_> = diag.all().iter().filter(|d| d.code == "URD448").collect();
+    assert_eq!(found.len(), 1, "One info per pair: {:?}", diag.all());
+    assert_eq!(found[0].severity, Severity::Info);
+    assert_eq!(
+        found[0].message,
+        "Exits between 'deck' and 'galley' both go 'north'. A return exit usually goes the opposite way."
+    );
+    assert_eq!(found[0].related.len(), 1);
+}
+
+#[test]
+fn exit_reciprocal_opposite_or_different_directions_are_silent() {
+    for (there, back) in [("north", "south"), ("north", "east"), ("up", "in")] {
+        let diag = deck_and_galley(Vec::new(), there, back);
+        assert!(
+            !diag.all().iter().any(|d| d.code == "URD447" || d.code == "URD448"),
+            "{} / {}: {:?}", there, back, diag.all()
+        );
+    }
+}
+
+#[test]
+fn exit_one_way_same_direction_is_silent() {
+    // Deck north to Galley, Galley north to Hold: a chain, not a pair.
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("world", make_world_block(vec![
+            ("start", Scalar::String("deck".to_string())),
+        ])),
+    ])), vec![
+        location("Deck"),
+        exit_decl("north", "Galley"),
+        location("Galley"),
+        exit_decl("north", "Hold"),
+        location("Hold"),
+    ]);
+    let diag = link_and_validate(single_file_cu(ast));
+    assert!(!has_info(&diag, "URD448"), "One-way exits: {:?}", diag.all());
+}
+
+// ═══════════════════════════════════════════════════════════
+// Speaker Display Name Tests (URD449)
+// ═══════════════════════════════════════════════════════════
+
+/// A Tavern with `@guard` and `@barkeep`, speaking in a choice and after
+/// it. `name` is the Person type's `name` property, if any.
+fn tavern_speakers(name: Option<PropertyDef>, guard_overrides: Vec<(&str, Scalar)>) -> DiagnosticCollector {
+    let mut ask = choice("Ask", true);
+    if let ContentNode::Choice(c) = &mut ask {
+        c.content = vec![entity_speech("guard", "Move along.")];
+    }
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Person", make_type_def("Person", vec!["interactable"], name.into_iter().collect())),
+        fm_entry("guard", make_entity_decl("guard", "Person", guard_overrides)),
+        fm_entry("barkeep", make_entity_decl("barkeep", "Person", vec![])),
+    ])), vec![
+        location("Tavern"),
+        section("greet"),
+        entity_speech("guard", "Halt."),
+        ask,
+        entity_speech("barkeep", "Closing time."),
+    ]);
+    link_and_validate(single_file_cu(ast))
+}
+
+#[test]
+fn speaker_without_display_name_info_once_per_entity() {
+    let diag = tavern_speakers(None, vec![]);
+    let found: Vec<_> = diag.all().iter().filter(|d| d.code == "URD449").collect();
+    assert_eq!(found.len(), 2, "One info per speaker: {:?}", diag.all());
+    assert_eq!(found[0].severity, Severity::Info);
+    assert_eq!(
+        found[0].message,
+        "Entity '@guard' speaks but has no display name. Runtimes will show its ID."
+    );
+    assert_eq!(
+        found[0].suggestion.as_deref(),
+        Some("Add a 'name: string' property to type 'Person', or set 'name' on '@guard'.")
+    );
+}
+
+#[test]
+fn speaker_with_type_name_default_is_silent() {
+    let name = make_property_with_default("name", "string", Scalar::String("Stranger".to_string()));
+    let diag = tavern_speakers(Some(name), vec![]);
+    assert!(!has_info(&diag, "URD449"), "Type default names every speaker: {:?}", diag.all());
+}
+
+#[test]
+fn speaker_name_override_covers_only_that_entity() {
+    let diag = tavern_speakers(
+        Some(make_property("name", "string")),
+        vec![("name", Scalar::String("Captain Vell".to_string()))],
+    );
+    let found: Vec<_> = diag.all().iter().filter(|d| d.code == "URD449").collect();
+    assert_eq!(found.len(), 1, "Only the barkeep is unnamed: {:?}", diag.all());
+    assert!(found[0].message.contains("'@barkeep'"), "{}", found[0].message);
+}
+
+// ═══════════════════════════════════════════════════════════
+// Entity Placement Tests (URD450–URD452)
+// ═══════════════════════════════════════════════════════════
+
+fn entity_presence_at(refs: Vec<&str>, line: u32) -> ContentNode {
+    let mut node = entity_presence(refs);
+    if let ContentNode::EntityPresence(ep) = &mut node {
+        ep.span = span("test.urd.md", line);
+    }
+    node
+}
+
+/// `@rusty_key` and `@player` declared, with `content` as the file body.
+fn cell_placement(content: Vec<ContentNode>) -> DiagnosticCollector {
+    let ast = make_file_ast("test.urd.md", Some(make_frontmatter(vec![
+        fm_entry("Key", make_type_def("Key", vec!["portable"], vec![])),
+        fm_entry("Hero", make_type_def("Hero", vec!["mobile", "container"], vec![])),
+        fm_entry("rusty_key", make_entity_decl("rusty_key", "Key", vec![])),
+        fm_entry("player", make_entity_decl("player", "Hero", vec![])),
+    ])), content);
+    link_and_validate(single_file_cu(ast))
+}
+
+#[test]
+fn entity_in_two_locations_is_error() {
+    let diag = cell_placement(vec![
+        location("Cell"),
+        entity_presence_at(vec!["rusty_key"], 12),
+        location("Corridor"),
+        entity_presence_at(vec!["rusty_key"], 20),
+    ]);
+    let found: Vec<_> = diag.all().iter().filter(|d| d.code == "URD450").collect();
+    assert_eq!(found.len(), 1, "{:?}", diag.all());
+    assert_eq!(found[0].severity, Severity::Error);
+    assert_eq!(
+        found[0].message,
+        "Entity '@rusty_key' is placed in more than one location: 'cell', 'corridor'. An entity starts in one place."
+    );
+    assert_eq!(found[0].span.start_line, 20, "Reported at the second location's listing");
+    assert_eq!(found[0].related.len(), 1);
+    assert_eq!(found[0].related[0].span.start_line, 12);
+    assert_eq!(found[0].related[0].message, "'@rusty_key' placed in 'cell' here.");
+}
+
+#[test]
+fn entity_repeated_in_one_location_is_info() {
+    let diag = cell_placement(vec![
+        location("Cell"),
+        entity_presence_at(vec!["rusty_key"], 12),
+        entity_presence_at(vec!["rusty_key"], 13),
+    ]);
+    assert!(!diag.has_errors(), "A repeat is not an error: {:?}", diag.all());
+    let d = diag.all().iter().find(|d| d.code == "URD451").expect("Expected URD451");
+    assert_eq!(d.severity, Severity::Info);
+    assert_eq!(d.span.start_line, 13);
+    assert_eq!(d.related[0].span.start_line, 12);
+}
+
+#[test]
+fn player_in_location_is_info() {
+    let diag = cell_placement(vec![
+        location("Cell"),
+        entity_presence_at(vec!["player", "rusty_key"], 12),
+        location("Corridor"),
+        entity_presence_at(vec!["player"], 20),
+    ]);
+    assert!(!has_error(&diag, "URD450"), "The player is exempt: {:?}", diag.all());
+    let found: Vec<_> = diag.all().iter().filter(|d| d.code == "URD452").collect();
+    assert_eq!(found.len(), 2, "One info per listing: {:?}", diag.all());
+    assert_eq!(found[0].severity, Severity::Info);
+    assert_eq!(found[0].suggestion.as_deref(), Some("Remove '@player' from the entity list."));
+}
+
+#[test]
+fn single_placements_are_silent() {
+    let diag = cell_placement(vec![
+        location("Cell"),
+        entity_presence_at(vec!["rusty_key"], 12),
+        location("Corridor"),
+    ]);
+    assert!(
+        !diag.all().iter().any(|d| ["URD450", "URD451", "URD452"].contains(&d.code.as_str())),
+        "{:?}",
+        diag.all()
+    );
+}
+
+// ── Operand spans ──
+
+/// `(line, start_col, end_col)` of each `code` diagnostic from compiling a
+/// guard's cell whose one choice holds `lines`.
+fn guard_diagnostic_columns(code: &str, lines: &str) -> Vec<(u32, u32, u32)> {
+    let source = format!(
+        "---\nworld:\n  name: cell\n  start: cell\ntypes:\n  Guard [interactable]:\n    \
+         mood: enum(calm, angry) = calm\n    trust: integer = 0\nentities:\n  @guard: Guard\n---\n\n\
+         # Cell\n\n[@guard]\n\n== talk\n\n+ Wave\n{}\n",
+        lines
+    );
+    let result = urd_compiler::compile_source("cell.urd.md", &source);
+    result
+        .diagnostics
+        .all()
+        .iter()
+        .filter(|d| d.code == code)
+        .map(|d| (d.span.start_line, d.span.start_col, d.span.end_col))
+        .collect()
+}
+
+#[test]
+fn enum_violation_spans_only_the_value() {
+    let found = guard_diagnostic_columns("URD401", "  ? @guard.mood == furious\n  > @guard.mood = livid");
+    assert_eq!(found, [(20, 20, 27), (21, 19, 24)]);
+}
+
+#[test]
+fn invalid_operator_spans_only_the_operator() {
+    let found = guard_diagnostic_columns("URD420", "  ? @guard.mood > calm");
+    assert_eq!(found, [(20, 17, 18)]);
+    let found = guard_diagnostic_columns("URD424", "  > @guard.mood + 1");
+    assert_eq!(found, [(20, 17, 18)]);
+}
+
+// ── Entity lifecycle ──
+
+/// Diagnostics with `code` from compiling a cell whose one choice holds
+/// `lines`. The world has a player, a portable lantern, and a chest.
+fn lifecycle_diagnostics(code: &str, lines: &str) -> Vec<urd_compiler::diagnostics::Diagnostic> {
+    let source = format!(
+        "---\nworld:\n  name: cell\n  start: cell\ntypes:\n  Hero [mobile, container]:\n    name: string = \"You\"\n  \
+         Lamp [portable]:\n    lit: boolean = false\n    ~maker: string = \"unknown\"\n  Chest [container]:\n    open: boolean = false\n\
+         entities:\n  @player: Hero\n  @lantern: Lamp\n  @chest: Chest\n---\n\n\
+         # Cell\n\n[@lantern, @chest]\n\n== talk\n\n+ Act\n{}\n",
+        lines
+    );
+    let result = urd_compiler::compile_source("cell.urd.md", &source);
+    result.diagnostics.all().iter().filter(|d| d.code == code).cloned().collect()
+}
+
+#[test]
+fn destroying_the_player_is_an_error() {
+    let found = lifecycle_diagnostics("URD456", "  > destroy @player");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].severity, Severity::Error);
+    assert_eq!(found[0].span.start_line, 26);
+    assert!(lifecycle_diagnostics("URD456", "  > destroy @lantern").is_empty());
+}
+
+#[test]
+fn reveal_of_a_visible_property_warns() {
+    assert_eq!(lifecycle_diagnostics("URD426", "  > reveal @lantern.lit").len(), 1);
+    assert!(lifecycle_diagnostics("URD426", "  > reveal @lantern.maker").is_empty());
+}
+
+#[test]
+fn effect_after_destroy_warns_with_both_positions() {
+    let found = lifecycle_diagnostics(
+        "URD457",
+        "  > destroy @lantern\n  > @chest.open = true\n  > @lantern.lit = true\n  > move @lantern -> @chest",
+    );
+    assert_eq!(found.len(), 2, "{:?}", found);
+    assert_eq!(found[0].message, "Effect 3 in this choice refers to '@lantern', which effect 1 already destroyed.");
+    assert_eq!(found[0].span.start_line, 28);
+    assert_eq!(found[0].related[0].span.start_line, 26);
+    assert_eq!(found[1].message, "Effect 4 in this choice refers to '@lantern', which effect 1 already destroyed.");
+
+    // A destroyed container is also a use.
+    let found = lifecycle_diagnostics("URD457", "  > destroy @chest\n  > move @lantern -> @chest");
+    assert_eq!(found.len(), 1);
+}
+
+#[test]
+fn destroy_as_the_last_effect_is_silent() {
+    let found = lifecycle_diagnostics("URD457", "  > @lantern.lit = true\n  > move @lantern -> @chest\n  > destroy @lantern");
+    assert!(found.is_empty(), "{:?}", found);
+}
+
+#[test]
+fn unresolved_effects_take_no_part_in_lifecycle_checks() {
+    assert!(lifecycle_diagnostics("URD456", "  > destroy @ghost").is_empty());
+    let found = lifecycle_diagnostics("URD457", "  > destroy @ghost\n  > @ghost.lit = true\n  > destroy @lantern\n  > @lamp.lit = true");
+    assert!(found.is_empty(), "{:?}", found);
+}
+
+// ── Presence overrides ──
+
+#[test]
+fn presence_override_is_validated_on_its_line() {
+    let source = "---\nworld:\n  name: cell\n  start: cell\ntypes:\n  Door [interactable]:\n    locked: boolean = true\n    \
+                  state: enum(open, shut) = shut\n    weight: integer(0, 10) = 5\nentities:\n  @cell_door: Door\n---\n\n\
+                  # Cell\n\n[@cell_door (locked: 3, state: ajar, weight: 11)]\n";
+    let result = urd_compiler::compile_source("cell.urd.md", source);
+    let found: Vec<(&str, u32)> = result
+        .diagnostics
+        .all()
+        .iter()
+        .filter(|d| d.code.starts_with("URD4"))
+        .map(|d| (d.code.as_str(), d.span.start_line))
+        .collect();
+    assert_eq!(found, [("URD401", 16), ("URD402", 16), ("URD418", 16)]);
+    assert!(!result.success);
+}
+
+// ── Section-scan checks at scale ──
+
+/// A 500-section synthetic world across four files, edited so that every
+/// section-scan check fires somewhere: rooms divisible by 4 lose their
+/// sticky choices and fallthrough line (URD433), rooms divisible by 5 test
+/// an impossible mood (URD432), the first room of each file has a section
+/// named like its north exit (URD434), and some rooms have a choice ahead
+/// of their first section, which belongs to the section before it.
+fn section_scan_world() -> (String, urd_compiler::test_support::MemoryFileReader) {
+    use urd_compiler::test_support::{synthetic_world, MemoryFileReader, ENTRY_FILE};
+
+    let files = synthetic_world(125, 4, 3, 5)
+        .into_iter()
+        .map(|(name, source)| {
+            let lines: Vec<&str> = source.lines().collect();
+            let mut out = String::new();
+            let (mut room, mut first_room) = (None::<usize>, None::<usize>);
+            for (i, line) in lines.iter().enumerate() {
+                if let Some(n) = line.strip_prefix("# Room ") {
+                    let n: usize = n.parse().unwrap();
+                    room = Some(n);
+                    first_room.get_or_insert(n);
+                }
+                let Some(n) = room else {
+                    out.push_str(line);
+                    out.push('\n');
+                    continue;
+                };
+                if n % 4 == 0 && line.starts_with("@keeper_") {
+                    let next = lines[i + 1..].iter().find(|l| !l.is_empty());
+                    if next.is_none_or(|l| l.starts_with("==") || l.starts_with('#')) {
+                        continue;
+                    }
+                }
+                let mut line = line.to_string();
+                if n % 4 == 0 {
+                    line = line.replace("+ Ask", "* Ask");
+                }
+                if n % 5 == 0 {
+                    line = line.replace("mood == warm", "mood == hot");
+                }
+                if (n % 7 == 3 || first_room == Some(n)) && line == format!("== talk_{}_0", n) {
+                    out.push_str(&format!("* Poke the fire\n  ? @keeper_{}.mood == hot\n\n", n));
+                }
+                if first_room == Some(n) && line == format!("== talk_{}_0", n) {
+                    line = "== north".to_string();
+                }
+                out.push_str(&line);
+                out.push('\n');
+            }
+            (name, out)
+        })
+        .collect();
+    let reader = MemoryFileReader::new(files);
+    (reader.get(ENTRY_FILE).unwrap().to_string(), reader)
+}
+
+/// The full diagnostic list, in order, against one recorded before the
+/// section scans shared an index. Run with `URD_WRITE_GOLDEN=1` to
+/// rewrite the fixture after an intended change.
+#[test]
+fn section_scan_diagnostics_are_unchanged_at_scale() {
+    use urd_compiler::test_support::ENTRY_FILE;
+
+    let (entry, reader) = section_scan_world();
+    let result = urd_compiler::compile_source_with_reader(ENTRY_FILE, &entry, &reader);
+    let sections = result.symbol_table.as_ref().map_or(0, |st| st.sections.len());
+    assert_eq!(sections, 500);
+
+    let found: String = result
+        .diagnostics
+        .all()
+        .iter()
+        .map(|d| format!("{:?} {} {}: {}\n", d.severity, d.code, d.span, d.message))
+        .collect();
+    if std::env::var_os("URD_WRITE_GOLDEN").is_some() {
+        std::fs::write(format!("{}/tests/fixtures/section-scan-500.diagnostics", env!("CARGO_MANIFEST_DIR")), &found).unwrap();
+    }
+    for code in ["URD432", "URD433", "URD434"] {
+        assert!(found.contains(code), "no {} in:\n{}", code, found);
+    }
+    assert_eq!(found, include_str!("fixtures/section-scan-500.diagnostics"));
+}
+
+/// Both VALIDATE paths over a compiled world: (parallel, sequential).
+fn both_paths(result: &urd_compiler::CompilationResult) -> (DiagnosticCollector, DiagnosticCollector) {
+    let graph = result.graph.as_ref().unwrap();
+    let symbol_table = result.symbol_table.as_ref().unwrap();
+    let mut parallel = DiagnosticCollector::new();
+    validate::validate_parallel(graph, symbol_table, &mut parallel);
+    let mut sequential = DiagnosticCollector::new();
+    validate::validate_sequential(graph, symbol_table, &mut sequential);
+    (parallel, sequential)
+}
+
+/// Worker steps finish in any order; the merged list must not show it.
+#[test]
+fn parallel_validate_matches_sequential_at_scale() {
+    use urd_compiler::test_support::ENTRY_FILE;
+
+    let (entry, reader) = section_scan_world();
+    let result = urd_compiler::compile_source_with_reader(ENTRY_FILE, &entry, &reader);
+    for _ in 0..8 {
+        let (parallel, sequential) = both_paths(&result);
+        assert!(sequential.len() > 400, "{}", sequential.len());
+        assert_eq!(format!("{:?}", parallel.all()), format!("{:?}", sequential.all()));
+        assert_eq!(format!("{:?}", parallel.sorted()), format!("{:?}", sequential.sorted()));
+    }
+}
+
+#[test]
+fn parallel_validate_matches_sequential_on_fixtures() {
+    let dir = format!("{}/tests/fixtures", env!("CARGO_MANIFEST_DIR"));
+    let mut compared = 0;
+    for entry in std::fs::read_dir(&dir).unwrap() {
+        let path = entry.unwrap().path();
+        if !path.to_string_lossy().ends_with(".urd.md") {
+            continue;
+        }
+        let result = urd_compiler::compile(&path.to_string_lossy().to_string());
+        if result.graph.is_none() || result.symbol_table.is_none() {
+            continue;
+        }
+        let (parallel, sequential) = both_paths(&result);
+        assert_eq!(format!("{:?}", parallel.all()), format!("{:?}", sequential.all()), "{}", path.display());
+        compared += 1;
+    }
+    assert!(compared > 20, "{}", compared);
+}
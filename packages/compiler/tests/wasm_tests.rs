@@ -0,0 +1,41 @@
+#![cfg(feature = "wasm")]
+
+/// Tests for the `#[wasm_bindgen]` exports, called natively.
+///
+/// Only built with `--features wasm`. The exports return JSON text; these
+/// tests check that it parses and keeps the documented fields.
+
+use serde_json::Value;
+use urd_compiler::wasm;
+
+const SOURCE: &str = "---\nworld:\n  name: hall\n  start: hall\n---\n\n# Hall\n";
+
+fn parse(text: String) -> Value {
+    serde_json::from_str(&text).expect("export returns JSON")
+}
+
+#[test]
+fn compile_full_returns_analysis_on_request() {
+    let with = parse(wasm::compile_full("hall.urd.md", SOURCE, true));
+    assert_eq!(with["success"], true);
+    assert!(with["facts"].is_object() && with["property_index"].is_object());
+
+    let without = parse(wasm::compile_full("hall.urd.md", SOURCE, false));
+    assert!(without.get("facts").is_none());
+    assert_eq!(without["world"], with["world"]);
+}
+
+#[test]
+fn compile_full_reports_errors_as_objects() {
+    let payload = parse(wasm::compile_full("", SOURCE, true));
+    assert_eq!(payload["error"]["kind"], "invalid_argument");
+}
+
+#[test]
+fn compile_source_keeps_its_analysis_fields() {
+    let payload = parse(wasm::compile_source(SOURCE, None));
+    assert_eq!(payload["success"], true);
+    for field in ["facts", "property_index", "definition_index"] {
+        assert!(payload[field].is_object(), "{} missing", field);
+    }
+}
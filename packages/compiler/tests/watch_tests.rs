@@ -0,0 +1,193 @@
+/// Tests for watch mode: the watched set, the debounce, and a smoke test
+/// of the loop against a temporary directory.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+use urd_compiler::import::{FileReadError, FileReader, OsFileReader};
+use urd_compiler::watch::{default_output_path, watch, watched_files, Debounce, WatchHost, WatchRun};
+use urd_compiler::{compile_source_with_reader, CompilationResult};
+
+// ── Helpers ──
+
+struct MapReader(Vec<(&'static str, &'static str)>);
+
+impl FileReader for MapReader {
+    fn read_file(&self, fs_path: &str) -> Result<String, FileReadError> {
+        self.0
+            .iter()
+            .find(|(path, _)| *path == fs_path)
+            .map(|(_, source)| source.to_string())
+            .ok_or(FileReadError::NotFound)
+    }
+
+    fn canonical_filename(&self, _dir: &str, _filename: &str) -> Option<String> {
+        None
+    }
+}
+
+const CAST: &str = "---\ntypes:\n  Keeper [interactable]:\n    name: string\nentities:\n  @keeper: Keeper\n---\n";
+const MAIN: &str = "---\nimport: ./cast.urd.md\nworld:\n  name: watched\n  start: hall\n---\n\n# Hall\n\n[@keeper]\n";
+
+/// A fresh, empty directory under the system temp directory.
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("urd-watch-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Write `contents` and move the modification time forward, so the change
+/// is seen even on file systems with coarse timestamps.
+fn touch(path: &Path, contents: &str, bump: u64) {
+    std::fs::write(path, contents).unwrap();
+    let file = std::fs::File::options().write(true).open(path).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(bump)).unwrap();
+}
+
+/// Compiles from disk and sends each run's success flag to the test.
+struct DiskHost {
+    entry: String,
+    runs: mpsc::Sender<(WatchRun, bool)>,
+}
+
+impl WatchHost for DiskHost {
+    fn modified(&self, path: &str) -> Option<SystemTime> {
+        std::fs::metadata(path).and_then(|m| m.modified()).ok()
+    }
+
+    fn compile(&mut self) -> CompilationResult {
+        match std::fs::read_to_string(&self.entry) {
+            Ok(source) => compile_source_with_reader(&self.entry, &source, &OsFileReader),
+            Err(_) => urd_compiler::compile(&self.entry),
+        }
+    }
+
+    fn compiled(&mut self, run: WatchRun, result: &CompilationResult) {
+        let _ = self.runs.send((run, result.success));
+    }
+}
+
+// ── Watched set ──
+
+#[test]
+fn watched_set_is_entry_and_imports() {
+    let reader = MapReader(vec![("stories/cast.urd.md", CAST)]);
+    let result = compile_source_with_reader("stories/main.urd.md", MAIN, &reader);
+    assert!(result.success);
+
+    let files = watched_files(&result, "stories/", "stories/main.urd.md", &[]);
+    assert_eq!(files, vec!["stories/main.urd.md", "stories/cast.urd.md"]);
+
+    let files = watched_files(&result, "/project/stories", "/project/stories/main.urd.md", &[]);
+    assert_eq!(files, vec!["/project/stories/main.urd.md", "/project/stories/cast.urd.md"]);
+}
+
+#[test]
+fn watched_set_follows_changed_imports() {
+    let reader = MapReader(vec![("cast.urd.md", CAST)]);
+    let with_import = compile_source_with_reader("main.urd.md", MAIN, &reader);
+    let previous = watched_files(&with_import, "", "main.urd.md", &[]);
+    assert_eq!(previous.len(), 2);
+
+    let without_import = compile_source_with_reader("main.urd.md", "# Hall\n", &reader);
+    assert_eq!(watched_files(&without_import, "", "main.urd.md", &previous), vec!["main.urd.md"]);
+}
+
+#[test]
+fn watched_set_kept_without_a_graph() {
+    // An import cycle stops before LINK, so there is no graph.
+    let reader = MapReader(vec![("cast.urd.md", "---\nimport: ./main.urd.md\n---\n")]);
+    let result = compile_source_with_reader("main.urd.md", "---\nimport: ./cast.urd.md\n---\n", &reader);
+    assert!(result.graph.is_none());
+
+    let previous = vec!["main.urd.md".to_string(), "cast.urd.md".to_string()];
+    assert_eq!(watched_files(&result, "", "main.urd.md", &previous), previous);
+    assert_eq!(watched_files(&result, "", "main.urd.md", &[]), vec!["main.urd.md"]);
+}
+
+// ── Debounce ──
+
+#[test]
+fn debounce_waits_for_quiet_period() {
+    let start = Instant::now();
+    let ms = |n: u64| start + Duration::from_millis(n);
+    let mut debounce = Debounce::new(Duration::from_millis(250));
+
+    assert!(!debounce.ready(ms(1000)), "Nothing changed");
+    debounce.changed(ms(0));
+    assert!(!debounce.ready(ms(100)));
+    assert!(debounce.ready(ms(250)));
+    assert!(!debounce.ready(ms(600)), "Fires once per burst");
+}
+
+#[test]
+fn debounce_restarts_on_each_change() {
+    let start = Instant::now();
+    let ms = |n: u64| start + Duration::from_millis(n);
+    let mut debounce = Debounce::new(Duration::from_millis(250));
+
+    // Saves at 0, 200 and 400 ms: one compile, 250 ms after the last.
+    for t in [0, 200, 400] {
+        debounce.changed(ms(t));
+        assert!(!debounce.ready(ms(t + 100)));
+    }
+    assert!(!debounce.ready(ms(600)));
+    assert!(debounce.ready(ms(650)));
+}
+
+#[test]
+fn default_output_path_replaces_extension() {
+    assert_eq!(default_output_path("stories/world.urd.md"), "stories/world.urd.json");
+    assert_eq!(default_output_path("world.md"), "world.md.urd.json");
+}
+
+// ── Loop ──
+
+#[test]
+fn watch_recompiles_on_change_and_stops() {
+    let dir = scratch_dir("loop");
+    let main = dir.join("main.urd.md");
+    let cast = dir.join("cast.urd.md");
+    touch(&cast, CAST, 0);
+    touch(&main, MAIN, 0);
+
+    let entry = main.to_string_lossy().to_string();
+    let base_dir = format!("{}/", dir.to_string_lossy());
+    let (runs, received) = mpsc::channel();
+    let stop = Arc::new(AtomicBool::new(false));
+    let handle = {
+        let stop = Arc::clone(&stop);
+        let entry = entry.clone();
+        std::thread::spawn(move || {
+            let mut host = DiskHost { entry: entry.clone(), runs };
+            watch(&mut host, &entry, &base_dir, &stop);
+        })
+    };
+    let next = || received.recv_timeout(Duration::from_secs(10)).expect("Expected a compile");
+
+    let (run, success) = next();
+    assert_eq!(run.count, 1);
+    assert!(success);
+
+    // Breaking the imported file triggers a recompile.
+    touch(&cast, "---\nentities:\n  @keeper: Nobody\n---\n", 10);
+    let (run, success) = next();
+    assert_eq!(run.count, 2);
+    assert!(!success);
+
+    // A burst of saves is one recompile.
+    touch(&cast, CAST, 20);
+    touch(&cast, CAST, 30);
+    let (run, success) = next();
+    assert_eq!(run.count, 3);
+    assert!(success);
+    assert!(received.recv_timeout(Duration::from_millis(600)).is_err(), "Burst compiled more than once");
+
+    stop.store(true, Ordering::Relaxed);
+    handle.join().unwrap();
+    let _ = std::fs::remove_dir_all(&dir);
+}
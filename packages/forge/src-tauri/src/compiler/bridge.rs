@@ -7,7 +7,10 @@
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::cell::RefCell;
+use std::time::Duration;
 use urd_compiler::import::{FileReadError, FileReader};
+use urd_compiler::observer::{CompileObserver, Phase};
 
 // ===== In-memory FileReader =====
 
@@ -41,6 +44,20 @@ impl FileReader for BufferMapReader {
     }
 }
 
+// ===== Phase timing observer =====
+
+/// Records each phase's duration as the compiler reports it.
+#[derive(Default)]
+struct PhaseRecorder {
+    phases: RefCell<Vec<(Phase, Duration)>>,
+}
+
+impl CompileObserver for PhaseRecorder {
+    fn phase_finished(&self, phase: Phase, elapsed: Duration) {
+        self.phases.borrow_mut().push((phase, elapsed));
+    }
+}
+
 // ===== Output types (serialised to frontend) =====
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -113,6 +130,32 @@ fn sha256_hash(data: &[u8]) -> String {
     format!("{:x}", hasher.finalize())
 }
 
+/// Convert a compiler diagnostic to its frontend shape. Diagnostics with
+/// no source file carry no span.
+pub fn diagnostic_output(d: &urd_compiler::diagnostics::Diagnostic) -> DiagnosticOutput {
+    let severity = match d.severity {
+        urd_compiler::diagnostics::Severity::Error => "error",
+        urd_compiler::diagnostics::Severity::Warning => "warning",
+        urd_compiler::diagnostics::Severity::Info => "info",
+    };
+    DiagnosticOutput {
+        severity: severity.to_string(),
+        message: d.message.clone(),
+        code: d.code.clone(),
+        span: if d.span.file.is_empty() {
+            None
+        } else {
+            Some(SpanOutput {
+                file: d.span.file.clone(),
+                start_line: d.span.start_line,
+                start_col: d.span.start_col,
+                end_line: d.span.end_line,
+                end_col: d.span.end_col,
+            })
+        },
+    }
+}
+
 fn make_chunk(name: &str, data: serde_json::Value) -> Chunk {
     let serialised = serde_json::to_string(&data).unwrap_or_default();
     let hash = sha256_hash(serialised.as_bytes());
@@ -189,8 +232,21 @@ pub fn compile_buffers(
 
     let input_file_count = buffers.len();
 
-    let result =
-        urd_compiler::compile_source_with_reader(&entry_filename, &entry_source, &reader);
+    let recorder = PhaseRecorder::default();
+    // Text lint results join the diagnostics list as info.
+    let options = urd_compiler::CompileOptions {
+        observer: Some(&recorder),
+        text_lints: true,
+        // For the build metadata's source hashes.
+        keep_sources: true,
+        ..urd_compiler::CompileOptions::default()
+    };
+    let result = urd_compiler::compile_source_with_options(
+        &entry_filename,
+        &entry_source,
+        &reader,
+        &options,
+    );
 
     let duration = start.elapsed();
     let duration_ms = duration.as_millis() as u64;
@@ -200,39 +256,18 @@ pub fn compile_buffers(
         .diagnostics
         .all()
         .iter()
-        .map(|d| {
-            let severity = match d.severity {
-                urd_compiler::diagnostics::Severity::Error => "error",
-                urd_compiler::diagnostics::Severity::Warning => "warning",
-                urd_compiler::diagnostics::Severity::Info => "info",
-            };
-            DiagnosticOutput {
-                severity: severity.to_string(),
-                message: d.message.clone(),
-                code: d.code.clone(),
-                span: if d.span.file.is_empty() {
-                    None
-                } else {
-                    Some(SpanOutput {
-                        file: d.span.file.clone(),
-                        start_line: d.span.start_line,
-                        start_col: d.span.start_col,
-                        end_line: d.span.end_line,
-                        end_col: d.span.end_col,
-                    })
-                },
-            }
-        })
+        .map(diagnostic_output)
         .collect();
 
-    // Build urdJson chunk from world output.
-    // Only Some when EMIT succeeds (zero errors). When None, the chunk is
-    // omitted so the frontend cache retains the last successful world data
-    // (stale-retention pattern, matching the playground).
+    // Build urdJson chunk from the typed world, so the emitted text is
+    // never parsed back. Only Some when EMIT succeeds (zero errors). When
+    // None, the chunk is omitted so the frontend cache retains the last
+    // successful world data (stale-retention pattern, matching the
+    // playground).
     let urd_json: Option<serde_json::Value> = result
-        .world
+        .world_value
         .as_ref()
-        .and_then(|w| serde_json::from_str(w).ok());
+        .map(urd_compiler::CompiledWorld::to_json);
 
     // Build symbol table chunk — flat entries array for the frontend
     let symbol_table = serde_json::json!({
@@ -303,6 +338,21 @@ pub fn compile_buffers(
         .as_ref()
         .map(|fs| fs.to_json());
 
+    // Build metadata chunk — the document `urd --meta` writes, for the
+    // export dialog.
+    let phases = recorder.phases.borrow().clone();
+    let metadata_options = urd_compiler::metadata::MetadataOptions {
+        compile: &options,
+        timings: Some(&phases),
+    };
+    let build_metadata = urd_compiler::metadata::build_metadata(
+        &result,
+        result.sources.as_ref().unwrap_or(&Default::default()),
+        &metadata_options,
+    );
+    let build_metadata: serde_json::Value =
+        serde_json::from_str(&build_metadata).unwrap_or_default();
+
     // Build AST chunk (placeholder — full AST serialisation deferred)
     let ast = serde_json::json!({ "nodes": [] });
 
@@ -362,6 +412,7 @@ pub fn compile_buffers(
             "diagnostics",
             serde_json::to_value(&diagnostics).unwrap_or_default(),
         ),
+        make_chunk("buildMetadata", build_metadata),
     ];
     if let Some(fs) = fact_set {
         chunks.push(make_chunk("factSet", fs));
@@ -383,12 +434,18 @@ pub fn compile_buffers(
             .map(|d| d.as_millis() as u64)
             .unwrap_or(0),
         duration_ms,
-        phase_timings: vec![
-            PhaseTiming {
+        // One entry per phase that ran, then the whole call.
+        phase_timings: phases
+            .iter()
+            .map(|(phase, elapsed)| PhaseTiming {
+                phase: phase.name().to_string(),
+                duration_ms: elapsed.as_millis() as u64,
+            })
+            .chain(std::iter::once(PhaseTiming {
                 phase: "total".to_string(),
                 duration_ms,
-            },
-        ],
+            }))
+            .collect(),
         world_counts: WorldCounts {
             entities: entity_count,
             locations: location_count,
@@ -459,6 +516,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn phase_timings_come_from_the_observer() {
+        let mut buffers = HashMap::new();
+        buffers.insert(
+            "world.urd.md".to_string(),
+            "---\nworld:\n  name: timed\n  start: hall\n---\n\n# Hall\n".to_string(),
+        );
+
+        let output = compile_buffers(buffers, "test-timings", None);
+        let phases: Vec<&str> = output.header.phase_timings.iter().map(|p| p.phase.as_str()).collect();
+        assert_eq!(phases, vec!["parse", "import", "link", "analyze", "validate", "emit", "total"]);
+    }
+
     #[test]
     fn compile_rich_world_chunks() {
         let mut buffers = HashMap::new();
@@ -554,9 +624,25 @@ mod tests {
         let buffers = HashMap::new();
         let output = compile_buffers(buffers, "test-002", None);
         assert_eq!(output.header.input_file_count, 0);
-        // Empty buffers: ast, symbolTable, diagnostics always present.
-        // Optional chunks may or may not be produced.
-        assert!(output.chunks.len() >= 3, "expected at least 3 chunks, got {}", output.chunks.len());
+        // Empty buffers: ast, symbolTable, diagnostics, buildMetadata always
+        // present. Optional chunks may or may not be produced.
+        assert!(output.chunks.len() >= 4, "expected at least 4 chunks, got {}", output.chunks.len());
+    }
+
+    #[test]
+    fn build_metadata_matches_the_compiler() {
+        let mut buffers = HashMap::new();
+        buffers.insert(
+            "world.urd.md".to_string(),
+            "---\nworld:\n  name: meta\n  start: hall\n---\n\n# Hall\n".to_string(),
+        );
+
+        let output = compile_buffers(buffers, "test-meta", None);
+        let meta = &output.chunks.iter().find(|c| c.name == "buildMetadata").unwrap().data;
+        assert_eq!(meta["urd_meta"], "1");
+        assert_eq!(meta["success"], true);
+        assert_eq!(meta["sources"][0]["path"], "world.urd.md");
+        assert!(meta["timings"]["total"].is_number());
     }
 
     #[test]
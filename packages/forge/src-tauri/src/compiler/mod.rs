@@ -1 +1,3 @@
 pub mod bridge;
+pub mod preview;
+pub mod snapshot;
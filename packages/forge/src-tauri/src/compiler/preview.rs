@@ -0,0 +1,109 @@
+//! Symbol preview — the compiled JSON for one location, section, or
+//! entity, for the Forge preview panel.
+//!
+//! `preview_symbol` compiles an entry file from disk and builds only the
+//! requested entry with `urd_compiler::emit`'s single-symbol helpers, so
+//! the panel gets exactly what the full `.urd.json` would hold under that
+//! key without serialising the whole world.
+
+use serde::{Deserialize, Serialize};
+use urd_compiler::emit::{emit_dialogue_section, emit_entity, emit_location};
+use urd_compiler::CompilationResult;
+
+// ===== Output types (serialised to frontend) =====
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolPreview {
+    /// `"section"`, `"location"`, or `"entity"`.
+    pub kind: String,
+    pub id: String,
+    /// The object under `dialogue`, `locations`, or `entities` in the full
+    /// output.
+    pub json: serde_json::Value,
+}
+
+// ===== Helpers =====
+
+/// Build the preview for `compiled_id`. An ID with a `/` is a dialogue
+/// section; otherwise locations are tried before entities. `None` when
+/// nothing has that ID, or when the world did not compile: EMIT only runs
+/// on a world without errors.
+pub fn preview_for(result: &CompilationResult, compiled_id: &str) -> Option<SymbolPreview> {
+    if !result.success {
+        return None;
+    }
+    let graph = result.graph.as_ref()?;
+    let symbol_table = result.symbol_table.as_ref()?;
+
+    let (kind, json) = if compiled_id.contains('/') {
+        ("section", emit_dialogue_section(graph, symbol_table, compiled_id)?)
+    } else if let Some(json) = emit_location(graph, symbol_table, compiled_id) {
+        ("location", json)
+    } else {
+        ("entity", emit_entity(symbol_table, compiled_id)?)
+    };
+
+    Some(SymbolPreview {
+        kind: kind.to_string(),
+        id: compiled_id.to_string(),
+        json,
+    })
+}
+
+// ===== Tauri command =====
+
+#[tauri::command]
+pub fn preview_symbol(entry: String, compiled_id: String) -> Result<Option<SymbolPreview>, String> {
+    std::fs::metadata(&entry).map_err(|e| format!("{}: {}", entry, e))?;
+    let result = urd_compiler::compile(&entry);
+    if !result.success {
+        return Err(format!(
+            "{} has errors. Fix them to preview compiled output.",
+            entry
+        ));
+    }
+    Ok(preview_for(&result, &compiled_id))
+}
+
+// ===== Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORLD: &str = "---\nworld:\n  name: test\n  start: hall\ntypes:\n  Guard [interactable]:\n    mood: enum(calm, cross) = calm\nentities:\n  @guard: Guard\n---\n# Hall\n\nA hall.\n\n[@guard]\n\n-> north: Yard\n\n== talk\n\n@guard: Halt.\n\n* Smile\n  > @guard.mood = cross\n\n# Yard\n\nGrass.\n";
+
+    fn full_output(result: &CompilationResult) -> serde_json::Value {
+        result.world_value.as_ref().unwrap().to_json()
+    }
+
+    #[test]
+    fn previews_match_the_full_output() {
+        let result = urd_compiler::compile_source("test.urd.md", WORLD);
+        let full = full_output(&result);
+
+        let hall = preview_for(&result, "hall").unwrap();
+        assert_eq!(hall.kind, "location");
+        assert_eq!(hall.json, full["locations"]["hall"]);
+
+        let talk = preview_for(&result, "test/talk").unwrap();
+        assert_eq!(talk.kind, "section");
+        assert_eq!(talk.json, full["dialogue"]["test/talk"]);
+
+        let guard = preview_for(&result, "guard").unwrap();
+        assert_eq!(guard.kind, "entity");
+        assert_eq!(guard.json, full["entities"]["guard"]);
+
+        let json = serde_json::to_value(&guard).unwrap();
+        assert_eq!(json["kind"], "entity");
+        assert_eq!(json["json"]["type"], "Guard");
+    }
+
+    #[test]
+    fn unknown_id_has_no_preview() {
+        let result = urd_compiler::compile_source("test.urd.md", WORLD);
+        assert!(preview_for(&result, "cellar").is_none());
+        assert!(preview_for(&result, "test/missing").is_none());
+    }
+}
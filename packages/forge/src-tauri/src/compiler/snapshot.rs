@@ -0,0 +1,247 @@
+//! Snapshot and diff commands — the `urd snapshot` / `urd diff` workflow
+//! for the Forge UI.
+//!
+//! `create_snapshot` compiles an entry file from disk and writes a
+//! `.urd.snapshot.json` next to it. `diff_against_snapshot` compiles the
+//! current state and compares it with a stored snapshot. Snapshot building,
+//! comparison, and grouping all live in `urd_compiler::diff`; this module
+//! only does file I/O and converts results into frontend DTOs.
+
+use serde::{Deserialize, Serialize};
+use urd_compiler::diff::{self, DiffError, DiffReport, DiffSnapshot};
+use urd_compiler::CompilationResult;
+
+use super::bridge::{diagnostic_output, DiagnosticOutput};
+
+// ===== Output types (serialised to frontend) =====
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotInfo {
+    pub path: String,
+    pub world_name: String,
+    pub counts: SnapshotCounts,
+}
+
+#[derive(Serialize, Deserialize, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotCounts {
+    pub entities: usize,
+    pub locations: usize,
+    pub exits: usize,
+    pub sections: usize,
+    pub choices: usize,
+    pub rules: usize,
+    pub properties: usize,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffResultDto {
+    pub total_changes: usize,
+    /// Same text as `urd diff` prints, e.g. "3 changes: 1 entity, 2 exit".
+    pub summary: String,
+    /// One group per category with changes, in the compiler's category order.
+    pub groups: Vec<DiffCategoryGroup>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffCategoryGroup {
+    pub category: String,
+    /// Change entries in the compiler's `{category, kind, id, detail}` shape.
+    pub changes: Vec<serde_json::Value>,
+}
+
+/// Errors surfaced to the frontend, tagged by `kind`.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum SnapshotCommandError {
+    /// Compilation stopped before LINK, so there is nothing to snapshot.
+    CompileFailed { diagnostics: Vec<DiagnosticOutput> },
+    /// The snapshot was written by an incompatible compiler version.
+    UnsupportedSnapshotVersion { path: String },
+    /// The snapshot file is not valid snapshot JSON.
+    InvalidSnapshot { path: String, message: String },
+    /// Reading the source or reading/writing the snapshot failed.
+    Io { path: String, message: String },
+}
+
+// ===== Conversions =====
+
+pub fn snapshot_info(path: &str, world_name: &str, snapshot: &DiffSnapshot) -> SnapshotInfo {
+    SnapshotInfo {
+        path: path.to_string(),
+        world_name: world_name.to_string(),
+        counts: SnapshotCounts {
+            entities: snapshot.entities.len(),
+            locations: snapshot.locations.len(),
+            exits: snapshot.exits.len(),
+            sections: snapshot.sections.len(),
+            choices: snapshot.choices.len(),
+            rules: snapshot.rules.len(),
+            properties: snapshot.properties.len(),
+        },
+    }
+}
+
+pub fn diff_result(report: &DiffReport) -> DiffResultDto {
+    let groups = report
+        .by_category()
+        .into_iter()
+        .map(|(category, entries)| DiffCategoryGroup {
+            category,
+            changes: entries.iter().map(|c| c.to_json()).collect(),
+        })
+        .collect();
+
+    DiffResultDto {
+        total_changes: report.changes.len(),
+        summary: report.summary(),
+        groups,
+    }
+}
+
+fn snapshot_error(path: &str, err: DiffError) -> SnapshotCommandError {
+    match err {
+        DiffError::UnsupportedSnapshotVersion => SnapshotCommandError::UnsupportedSnapshotVersion {
+            path: path.to_string(),
+        },
+        DiffError::ParseError(message) => SnapshotCommandError::InvalidSnapshot {
+            path: path.to_string(),
+            message,
+        },
+    }
+}
+
+fn io_error(path: &str, err: std::io::Error) -> SnapshotCommandError {
+    SnapshotCommandError::Io {
+        path: path.to_string(),
+        message: err.to_string(),
+    }
+}
+
+/// Compile from disk, failing when there is nothing to snapshot. Matches the
+/// CLI: a world with errors can still be snapshotted once LINK has run.
+fn compile_for_snapshot(entry_path: &str) -> Result<CompilationResult, SnapshotCommandError> {
+    std::fs::metadata(entry_path).map_err(|e| io_error(entry_path, e))?;
+
+    let result = urd_compiler::compile(&entry_path.to_string());
+    if result.diagnostics.has_errors() && result.fact_set.is_none() {
+        return Err(SnapshotCommandError::CompileFailed {
+            diagnostics: result.diagnostics.all().iter().map(diagnostic_output).collect(),
+        });
+    }
+    Ok(result)
+}
+
+// ===== Tauri commands =====
+
+#[tauri::command]
+pub fn create_snapshot(entry_path: String) -> Result<SnapshotInfo, SnapshotCommandError> {
+    let result = compile_for_snapshot(&entry_path)?;
+
+    let world_name = diff::world_name(&result);
+    let snapshot = DiffSnapshot::from_compilation(&result);
+    let json = serde_json::to_string_pretty(&snapshot.to_json(&world_name)).unwrap_or_default();
+
+    let output = diff::default_snapshot_path(&entry_path);
+    std::fs::write(&output, format!("{}\n", json)).map_err(|e| io_error(&output, e))?;
+
+    Ok(snapshot_info(&output, &world_name, &snapshot))
+}
+
+#[tauri::command]
+pub fn diff_against_snapshot(
+    entry_path: String,
+    snapshot_path: String,
+) -> Result<DiffResultDto, SnapshotCommandError> {
+    let stored = std::fs::read_to_string(&snapshot_path).map_err(|e| io_error(&snapshot_path, e))?;
+    let before = DiffSnapshot::from_json(&stored).map_err(|e| snapshot_error(&snapshot_path, e))?;
+
+    let result = compile_for_snapshot(&entry_path)?;
+    let after = DiffSnapshot::from_compilation(&result);
+
+    Ok(diff_result(&diff::diff(&before, &after)))
+}
+
+// ===== Tests =====
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BEFORE: &str = "---\nworld:\n  name: test\n  start: hall\ntypes:\n  Key [portable]:\n    shiny: bool = true\nentities:\n  @key: Key\n---\n# Hall\n\n[@key]\n\n-> north: Yard\n\n# Yard\n\nGrass.\n";
+    const AFTER: &str = "---\nworld:\n  name: test\n  start: hall\ntypes:\n  Key [portable]:\n    shiny: bool = true\nentities:\n  @key: Key\n  @spare: Key\n---\n# Hall\n\n[@key]\n\n# Yard\n\nGrass.\n";
+
+    fn snapshot_of(source: &str) -> DiffSnapshot {
+        DiffSnapshot::from_compilation(&urd_compiler::compile_source("test.urd.md", source))
+    }
+
+    #[test]
+    fn empty_diff_has_no_groups() {
+        let snap = snapshot_of(BEFORE);
+        let dto = diff_result(&diff::diff(&snap, &snap));
+        assert_eq!(dto.total_changes, 0);
+        assert_eq!(dto.summary, "No changes detected.");
+        assert!(dto.groups.is_empty());
+
+        let json = serde_json::to_value(&dto).unwrap();
+        assert_eq!(json["totalChanges"], 0);
+        assert_eq!(json["groups"], serde_json::json!([]));
+    }
+
+    #[test]
+    fn diff_groups_by_category_in_compiler_order() {
+        let report = diff::diff(&snapshot_of(BEFORE), &snapshot_of(AFTER));
+        let dto = diff_result(&report);
+
+        assert_eq!(dto.total_changes, report.changes.len());
+        assert_eq!(dto.summary, report.summary());
+        let categories: Vec<&str> = dto.groups.iter().map(|g| g.category.as_str()).collect();
+        let expected: Vec<String> = report.by_category().keys().cloned().collect();
+        assert_eq!(categories, expected);
+        assert!(categories.contains(&"entity"));
+        assert!(categories.contains(&"exit"));
+
+        let entity = dto.groups.iter().find(|g| g.category == "entity").unwrap();
+        assert!(entity
+            .changes
+            .iter()
+            .any(|c| c["kind"] == "added" && c["id"] == "@spare"));
+        let total: usize = dto.groups.iter().map(|g| g.changes.len()).sum();
+        assert_eq!(total, dto.total_changes);
+    }
+
+    #[test]
+    fn snapshot_info_counts() {
+        let snap = snapshot_of(BEFORE);
+        let info = snapshot_info("test.urd.snapshot.json", "test", &snap);
+        assert_eq!(info.counts.entities, 1);
+        assert_eq!(info.counts.locations, 2);
+        assert_eq!(info.counts.exits, 1);
+
+        let json = serde_json::to_value(&info).unwrap();
+        assert_eq!(json["worldName"], "test");
+        assert_eq!(json["counts"]["locations"], 2);
+    }
+
+    #[test]
+    fn errors_are_tagged_by_kind() {
+        let err = snapshot_error("a.json", DiffError::UnsupportedSnapshotVersion);
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["kind"], "unsupportedSnapshotVersion");
+        assert_eq!(json["path"], "a.json");
+
+        let err = snapshot_error("a.json", DiffError::ParseError("bad".to_string()));
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["kind"], "invalidSnapshot");
+        assert_eq!(json["message"], "bad");
+    }
+
+    #[test]
+    fn missing_entry_is_io_error() {
+        let result = compile_for_snapshot("/nonexistent/world.urd.md");
+        assert!(matches!(result, Err(SnapshotCommandError::Io { .. })));
+    }
+}
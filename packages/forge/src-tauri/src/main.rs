@@ -11,6 +11,9 @@ fn main() {
         .plugin(tauri_plugin_fs::init())
         .invoke_handler(tauri::generate_handler![
             compiler::bridge::compile_project,
+            compiler::preview::preview_symbol,
+            compiler::snapshot::create_snapshot,
+            compiler::snapshot::diff_against_snapshot,
             shell::reveal_in_explorer,
         ])
         .run(tauri::generate_context!())
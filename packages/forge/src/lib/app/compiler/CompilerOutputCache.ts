@@ -27,6 +27,7 @@ import type {
   UrdSequence,
   UrdRule,
   Diagnostic,
+  BuildMetadata,
 } from './types';
 
 interface CacheEntry {
@@ -56,9 +57,15 @@ function normaliseUrdJson(raw: unknown): UrdWorld {
     result.world = data.world as UrdWorldMeta;
   }
 
-  // Preserve type definitions (object-keyed, no normalisation needed)
+  // Type definitions stay object-keyed; only property groups are flattened
   if (data.types && typeof data.types === 'object') {
-    result.types = data.types as Record<string, UrdTypeDef>;
+    const types: Record<string, UrdTypeDef> = {};
+    for (const [name, val] of Object.entries(data.types as Record<string, UrdTypeDef>)) {
+      types[name] = val.properties
+        ? { ...val, properties: flattenPropertyGroups(val.properties, true) as UrdTypeDef['properties'] }
+        : val;
+    }
+    result.types = types;
   }
 
   // Preserve dialogue tree (passthrough — consumed by the runtime)
@@ -80,6 +87,33 @@ function normaliseUrdJson(raw: unknown): UrdWorld {
   return result;
 }
 
+/**
+ * Restore dotted property names from the compiler's property groups.
+ *
+ * The emitter nests `appearance.height` as `{ "appearance": { "height": ... } }`
+ * in type properties and entity overrides. Projections key properties by the
+ * dotted name, as conditions and effects do. In type properties a group is an
+ * object without a string `type`; in entity overrides every object is a group.
+ */
+function flattenPropertyGroups(
+  props: Record<string, unknown>,
+  schemas: boolean,
+  prefix = '',
+  out: Record<string, unknown> = {},
+): Record<string, unknown> {
+  for (const [key, val] of Object.entries(props)) {
+    const name = prefix ? `${prefix}.${key}` : key;
+    const isObject = val !== null && typeof val === 'object' && !Array.isArray(val);
+    const isGroup = isObject && !(schemas && typeof (val as Record<string, unknown>).type === 'string');
+    if (isGroup) {
+      flattenPropertyGroups(val as Record<string, unknown>, schemas, name, out);
+    } else {
+      out[name] = val;
+    }
+  }
+  return out;
+}
+
 function normaliseEntities(raw: unknown): UrdEntity[] {
   if (!raw) return [];
   // Already an array (fixture/mock format)
@@ -91,7 +125,7 @@ function normaliseEntities(raw: unknown): UrdEntity[] {
       const entity: UrdEntity = {
         id,
         name: (obj.name as string) ?? id,
-        properties: (obj.properties as Record<string, unknown>) ?? {},
+        properties: flattenPropertyGroups((obj.properties as Record<string, unknown>) ?? {}, false),
       };
       if (obj.type) entity.type = obj.type as string;
       if (obj.contains) entity.contains = obj.contains as string[];
@@ -136,7 +170,9 @@ function normaliseExits(raw: unknown): UrdExit[] {
       };
       if (obj.condition) exit.condition = obj.condition as string;
       if (obj.blocked_message) exit.blocked_message = obj.blocked_message as string;
-      if (obj.effects) exit.effects = obj.effects as UrdEffect[];
+      // `on_traverse` replaces `effects`; older output has only `effects`.
+      const effects = obj.on_traverse ?? obj.effects;
+      if (effects) exit.effects = effects as UrdEffect[];
       return exit;
     });
   }
@@ -190,6 +226,7 @@ export class CompilerOutputCache {
       urdJson: (resolved.urdJson ?? this.staleOrDefault('urdJson', { entities: [], locations: [] })) as UrdWorld,
       rawUrdJson: (resolved.rawUrdJson ?? this.staleRaw('urdJson')) as Record<string, unknown> | null,
       diagnostics: (resolved.diagnostics ?? this.staleOrDefault('diagnostics', [])) as Diagnostic[],
+      buildMetadata: (resolved.buildMetadata ?? this.staleRaw('buildMetadata')) as BuildMetadata | null,
     };
   }
 
@@ -247,6 +284,9 @@ export class CompilerOutputCache {
       case 'diagnostics':
         target.diagnostics = value as Diagnostic[];
         break;
+      case 'buildMetadata':
+        target.buildMetadata = value as BuildMetadata;
+        break;
     }
   }
 }
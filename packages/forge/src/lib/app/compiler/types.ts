@@ -14,7 +14,8 @@ export type ChunkName =
   | 'propertyDependencyIndex'
   | 'definitionIndex'
   | 'urdJson'
-  | 'diagnostics';
+  | 'diagnostics'
+  | 'buildMetadata';
 
 export interface Chunk<T = unknown> {
   name: ChunkName;
@@ -64,6 +65,8 @@ export interface ResolvedCompilerOutput {
   /** Raw urdJson before normalisation — object-keyed, matching the compiler's output exactly. */
   rawUrdJson: Record<string, unknown> | null;
   diagnostics: Diagnostic[];
+  /** The compiler's build metadata (`build_metadata`), kept for export. Null until the first compile. */
+  buildMetadata: BuildMetadata | null;
 }
 
 // ===== Inner types =====
@@ -298,12 +301,23 @@ export interface UrdWorldMeta {
   author?: string;
 }
 
+/** A `? any:` block: holds when any one condition does. */
+export interface UrdAnyConditions {
+  any: string[];
+}
+
+/**
+ * A `conditions` field: an AND list, one OR block, or, when both appear,
+ * an `all` list holding the AND strings and then each OR block.
+ */
+export type UrdConditionExpr = string[] | UrdAnyConditions | { all: (string | UrdAnyConditions)[] };
+
 export interface UrdAction {
   description?: string;
   actor?: string;
   target?: string;
   target_type?: string;
-  conditions?: string[] | { any: string[] };
+  conditions?: UrdConditionExpr;
   effects: UrdEffect[];
 }
 
@@ -373,8 +387,74 @@ export interface Diagnostic {
   span: DiagnosticSpan | null;
 }
 
+/**
+ * Build metadata, matching `urd --meta` (snake_case, as the compiler writes
+ * it). Diagnostics here keep the compiler's own JSON shape.
+ */
+export interface BuildMetadata {
+  urd_meta: string;
+  compiler: Record<string, unknown>;
+  success: boolean;
+  summary: { errors: number; warnings: number; info: number };
+  diagnostics: unknown[];
+  sources: { path: string; bytes: number; sha256: string }[];
+  options: Record<string, unknown>;
+  stats: Record<string, unknown> | null;
+  timings?: Record<string, number>;
+}
+
 export type AnalysisType = 'full' | 'incremental';
 
+// ===== Snapshot / diff (create_snapshot, diff_against_snapshot) =====
+
+export interface SnapshotInfo {
+  path: string;
+  worldName: string;
+  counts: {
+    entities: number;
+    locations: number;
+    exits: number;
+    sections: number;
+    choices: number;
+    rules: number;
+    properties: number;
+  };
+}
+
+/** A change entry in the compiler's diff report shape. */
+export interface DiffChange {
+  category: string;
+  kind: string;
+  id: string;
+  detail: Record<string, unknown>;
+}
+
+export interface DiffCategoryGroup {
+  category: string;
+  changes: DiffChange[];
+}
+
+export interface DiffResult {
+  totalChanges: number;
+  summary: string;
+  groups: DiffCategoryGroup[];
+}
+
+export type SnapshotCommandError =
+  | { kind: 'compileFailed'; diagnostics: Diagnostic[] }
+  | { kind: 'unsupportedSnapshotVersion'; path: string }
+  | { kind: 'invalidSnapshot'; path: string; message: string }
+  | { kind: 'io'; path: string; message: string };
+
+// ===== Symbol preview (preview_symbol) =====
+
+/** One compiled entry, exactly as the full `.urd.json` holds it. */
+export interface SymbolPreview {
+  kind: 'section' | 'location' | 'entity';
+  id: string;
+  json: Record<string, unknown>;
+}
+
 // ===== Compiler service interface =====
 
 export interface CompilerService {
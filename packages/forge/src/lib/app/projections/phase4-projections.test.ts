@@ -106,6 +106,7 @@ function makeFixtureSource(): ResolvedCompilerOutput {
       { severity: 'warning', message: "Entity 'Shield' has no location assigned", code: 'W001', span: { file: 'main.urd.md', startLine: 11, startCol: 1, endLine: 11, endCol: 20 } },
       { severity: 'info', message: 'Compilation successful with 3 entities and 2 locations', code: 'I001', span: null },
     ],
+    buildMetadata: null,
   };
 }
 
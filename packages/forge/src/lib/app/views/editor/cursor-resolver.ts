@@ -688,7 +688,7 @@ function findRuleKeyword(trimmed: string, col: number, trimOffset: number): Refe
 // --- World sub-key detection (F9) ---
 
 const WORLD_SUB_KEYS = new Set([
-  'name', 'version', 'start', 'entry', 'seed', 'description', 'author',
+  'name', 'version', 'start', 'entry', 'start_inventory', 'seed', 'description', 'author',
 ]);
 
 function findWorldSubKey(trimmed: string, col: number, trimOffset: number): Reference | null {
@@ -864,7 +864,8 @@ const WORLD_KEY_DOCS: Record<string, string> = {
   version: 'Schema version string — included in compiled output for compatibility checking',
   start: 'Starting location — where the player begins',
   entry: 'Entry sequence — the initial quest/progression arc',
-  seed: 'Random seed — used by the runtime for deterministic randomisation',
+  start_inventory: 'Starting inventory — entities the player holds on load',
+  seed: 'Random seed (integer) — used by the runtime for deterministic randomisation',
   description: 'Human-readable description of the world',
   author: 'Author attribution',
 };
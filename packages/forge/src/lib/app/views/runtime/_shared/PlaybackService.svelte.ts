@@ -21,6 +21,7 @@ import type {
   UrdSequence,
   UrdSequencePhase,
   UrdRule,
+  UrdAnyConditions,
   FactSet,
 } from '$lib/app/compiler/types';
 import {
@@ -40,8 +41,11 @@ import {
   type DialogueChoice,
   type DialogueEffect,
   type DialogueChoiceView,
+  type DialogueSpoken,
   createInitialPlaybackState,
   createInitialCoverage,
+  spokenSegments,
+  spokenSpeaker,
 } from './playback-types';
 
 // ===== Helpers =====
@@ -257,9 +261,7 @@ class PlaybackServiceImpl {
       this.pushNarrative('narration', dlg.description);
     }
 
-    if (dlg.prompt) {
-      this.pushNarrative('dlg_prompt', dlg.prompt.text, this.eName(dlg.prompt.speaker));
-    }
+    this.pushSpoken('dlg_prompt', dlg.prompt);
 
     // Check if section is already exhausted
     if (this.isSectionExhausted(dialogueId)) {
@@ -291,6 +293,7 @@ class PlaybackServiceImpl {
     if (!choice.sticky) {
       this.usedChoices.add(choice.id);
     }
+    this.consumeOnceChoices(choices);
 
     // Track coverage
     if (!this.coverage.choices.visited.includes(choice.id)) {
@@ -306,10 +309,7 @@ class PlaybackServiceImpl {
     }
 
     // Show response
-    if (choice.response) {
-      this.pushNarrative('dlg_response', choice.response.text, this.eName(choice.response.speaker));
-      this.emitEvent('dialogue', `${this.eName(choice.response.speaker)}: "${choice.response.text}"`);
-    }
+    this.pushSpoken('dlg_response', choice.response, true);
 
     // Navigate: goto > subchoices > sticky loop > end
     if (choice.goto) {
@@ -318,6 +318,9 @@ class PlaybackServiceImpl {
         return;
       }
       this.navigateToSection(choice.goto);
+    } else if (choice.exit) {
+      this.dialogueState = null;
+      this.takeExit(choice.exit);
     } else if (choice.choices && choice.choices.length > 0) {
       this.dialogueState = { dialogueId: this.dialogueState.dialogueId, subChoices: choice.choices };
     } else if (choice.sticky && this.dialogueState.dialogueId) {
@@ -346,6 +349,7 @@ class PlaybackServiceImpl {
   endDialogue(): void {
     if (!this.dialogueState) return;
 
+    this.consumeOnceChoices(this.getAvailableChoices());
     this.dialogueState = null;
     this.pushNarrative('system', 'You step back.');
     this.emitEvent('dialogue', 'Ended dialogue');
@@ -381,6 +385,14 @@ class PlaybackServiceImpl {
     this.publishState();
   }
 
+  /** Leave the current location through the exit in `direction`. */
+  private takeExit(direction: string): void {
+    if (!this.state.currentLocation) return;
+    const loc = this.locationMap.get(this.state.currentLocation.id);
+    const exit = loc?.exits.find((e) => e.direction === direction);
+    if (exit) this.moveTo(exit.target);
+  }
+
   /** Try to use a blocked exit — shows the blocked message. */
   tryBlockedExit(direction: string): void {
     if (this.state.status !== 'playing' || !this.state.currentLocation) return;
@@ -495,8 +507,9 @@ class PlaybackServiceImpl {
     if (Object.keys(this.dialogue).length === 0) return;
 
     for (const [did, dlg] of Object.entries(this.dialogue)) {
-      if (!dlg.prompt?.speaker) continue;
-      const speaker = this.entityState.get(dlg.prompt.speaker);
+      const speakerId = spokenSpeaker(dlg.prompt);
+      if (!speakerId) continue;
+      const speaker = this.entityState.get(speakerId);
       if (!speaker || speaker.container !== locationId) continue;
       // Only auto-enter dialogues with NO entry conditions
       if (dlg.conditions) continue;
@@ -516,7 +529,7 @@ class PlaybackServiceImpl {
         this.pushNarrative('narration', dlg.description);
       }
 
-      this.pushNarrative('dlg_prompt', dlg.prompt.text, this.eName(dlg.prompt.speaker));
+      this.pushSpoken('dlg_prompt', dlg.prompt);
 
       // Check if exhausted immediately
       if (this.isSectionExhausted(did)) {
@@ -614,10 +627,11 @@ class PlaybackServiceImpl {
     if (!this.dialogueState) {
       const shown = new Set<string>();
       for (const [did, dlg] of Object.entries(this.dialogue)) {
-        if (!dlg.prompt?.speaker) continue;
-        const speaker = this.entityState.get(dlg.prompt.speaker);
+        const speakerId = spokenSpeaker(dlg.prompt);
+        if (!speakerId) continue;
+        const speaker = this.entityState.get(speakerId);
         if (!speaker || speaker.container !== locationId) continue;
-        if (this.destroyedEntities.has(dlg.prompt.speaker)) continue;
+        if (this.destroyedEntities.has(speakerId)) continue;
         if (shown.has(did)) continue;
         shown.add(did);
 
@@ -628,8 +642,8 @@ class PlaybackServiceImpl {
         }
 
         interactions.push({
-          entityId: dlg.prompt.speaker,
-          entityName: this.eName(dlg.prompt.speaker),
+          entityId: speakerId,
+          entityName: this.eName(speakerId),
           dialogueId: did,
           available,
           conditionText,
@@ -677,6 +691,13 @@ class PlaybackServiceImpl {
     return raw.filter((ch) => ch.sticky || !this.usedChoices.has(ch.id));
   }
 
+  /** Once choices are used up by being presented, whether or not they were taken. */
+  private consumeOnceChoices(presented: DialogueChoice[]): void {
+    for (const ch of presented) {
+      if (ch.once) this.usedChoices.add(ch.id);
+    }
+  }
+
   /** Check if a section is exhausted (all choices used or unavailable). */
   private isSectionExhausted(sectionId: string): boolean {
     const dlg = this.dialogue[sectionId];
@@ -718,6 +739,9 @@ class PlaybackServiceImpl {
           return;
         }
         this.navigateToSection(oe.goto);
+      } else if (oe.exit) {
+        this.dialogueState = null;
+        this.takeExit(oe.exit);
       } else {
         this.dialogueState = null;
       }
@@ -753,9 +777,7 @@ class PlaybackServiceImpl {
       this.pushNarrative('narration', next.description);
     }
 
-    if (next.prompt) {
-      this.pushNarrative('dlg_prompt', next.prompt.text, this.eName(next.prompt.speaker));
-    }
+    this.pushSpoken('dlg_prompt', next.prompt);
 
     // Check if this section is exhausted
     if (this.isSectionExhausted(sectionId)) {
@@ -775,6 +797,13 @@ class PlaybackServiceImpl {
         return this.exhaustedSections.has(exhaustedMatch[1]);
       }
 
+      // Visited condition: "location_id.visited" / "location_id.visited == false"
+      const visitedMatch = c.match(/^(\S+)\.visited(?:\s*==\s*(true|false))?$/);
+      if (visitedMatch) {
+        const visited = this.visitedLocations.has(visitedMatch[1]);
+        return visitedMatch[2] === 'false' ? !visited : visited;
+      }
+
       // Container condition: "@entity in location" / "entity in player" / "entity in here"
       const inMatch = c.match(/^@?(\w+)\s+in\s+(\w+)$/);
       if (inMatch) {
@@ -848,15 +877,18 @@ class PlaybackServiceImpl {
     }
   }
 
-  private evalConds(conditions: { any?: string[]; all?: string[] } | string[] | unknown): boolean {
+  private evalConds(conditions: unknown): boolean {
     if (!conditions) return true;
     if (Array.isArray(conditions)) {
       return (conditions as string[]).every((c) => this.evalCond(c));
     }
     if (typeof conditions === 'object') {
-      const obj = conditions as { any?: string[]; all?: string[] };
+      const obj = conditions as { any?: string[]; all?: (string | UrdAnyConditions)[] };
       if (obj.any) return obj.any.some((c) => this.evalCond(c));
-      if (obj.all) return obj.all.every((c) => this.evalCond(c));
+      // An `all` list mixes AND strings with nested `any` blocks.
+      if (obj.all) {
+        return obj.all.every((c) => (typeof c === 'string' ? this.evalCond(c) : this.evalConds(c)));
+      }
     }
     return true;
   }
@@ -872,9 +904,13 @@ class PlaybackServiceImpl {
       return (conditions as string[]).join(', ');
     }
     if (typeof conditions === 'object' && conditions !== null) {
-      const obj = conditions as { any?: string[]; all?: string[] };
+      const obj = conditions as { any?: string[]; all?: (string | UrdAnyConditions)[] };
       if (obj.any) return obj.any.join(' OR ');
-      if (obj.all) return obj.all.join(' AND ');
+      if (obj.all) {
+        return obj.all
+          .map((c) => (typeof c === 'string' ? c : `(${this.formatConditions(c)})`))
+          .join(' AND ');
+      }
     }
     return String(conditions);
   }
@@ -1155,6 +1191,8 @@ class PlaybackServiceImpl {
       if (rule.select) {
         // Select-based rule: iterate over entities
         const { from, as: varName, where } = rule.select;
+        // Compiled strings refer to the variable as `$name`.
+        const varRef = new RegExp(`\\$?\\b${varName}\\b`, 'g');
         for (const entityId of from) {
           const ent = this.entityState.get(entityId);
           if (!ent || this.destroyedEntities.has(entityId)) continue;
@@ -1162,7 +1200,7 @@ class PlaybackServiceImpl {
           // Check where conditions with variable substitution
           if (where) {
             const allMet = where.every((cond) => {
-              const substituted = cond.replace(new RegExp(`\\b${varName}\\b`, 'g'), entityId);
+              const substituted = cond.replace(varRef, entityId);
               return this.evalCond(substituted);
             });
             if (!allMet) continue;
@@ -1173,12 +1211,12 @@ class PlaybackServiceImpl {
           const substitutedEffects = rule.effects.map((eff) => {
             const effCopy = { ...eff };
             // Substitute variable name in effect targets
-            if (effCopy.set) effCopy.set = effCopy.set.replace(new RegExp(`\\b${varName}\\b`, 'g'), entityId);
-            if (effCopy.move) effCopy.move = effCopy.move.replace(new RegExp(`\\b${varName}\\b`, 'g'), entityId);
-            if (effCopy.destroy) effCopy.destroy = effCopy.destroy.replace(new RegExp(`\\b${varName}\\b`, 'g'), entityId);
-            if (effCopy.reveal) effCopy.reveal = effCopy.reveal.replace(new RegExp(`\\b${varName}\\b`, 'g'), entityId);
+            if (effCopy.set) effCopy.set = effCopy.set.replace(varRef, entityId);
+            if (effCopy.move) effCopy.move = effCopy.move.replace(varRef, entityId);
+            if (effCopy.destroy) effCopy.destroy = effCopy.destroy.replace(varRef, entityId);
+            if (effCopy.reveal) effCopy.reveal = effCopy.reveal.replace(varRef, entityId);
             if (typeof effCopy.to === 'string') {
-              effCopy.to = effCopy.to.replace(new RegExp(`\\b${varName}\\b`, 'g'), entityId);
+              effCopy.to = effCopy.to.replace(varRef, entityId);
             }
             return effCopy;
           });
@@ -1224,6 +1262,20 @@ class PlaybackServiceImpl {
     return count;
   }
 
+  /** Push each segment of a prompt or response; directions read as narration. */
+  private pushSpoken(kind: NarrativeLineKind, spoken: DialogueSpoken | undefined, logSpeech = false): void {
+    for (const segment of spokenSegments(spoken)) {
+      if ('direction' in segment) {
+        this.pushNarrative('narration', segment.direction);
+        continue;
+      }
+      this.pushNarrative(kind, segment.text, this.eName(segment.speaker));
+      if (logSpeech) {
+        this.emitEvent('dialogue', `${this.eName(segment.speaker)}: "${segment.text}"`);
+      }
+    }
+  }
+
   private pushNarrative(kind: NarrativeLineKind, text: string, speaker?: string): void {
     const line: NarrativeLine = {
       id: this.narrativeCounter++,
@@ -6,6 +6,8 @@
  * BreadcrumbTrail, CoverageOverlay).
  */
 
+import type { UrdConditionExpr } from '$lib/app/compiler/types';
+
 // ===== Dialogue tree types (consumed by the runtime from urdJson.dialogue) =====
 
 export interface DialoguePrompt {
@@ -13,6 +15,15 @@ export interface DialoguePrompt {
   text: string;
 }
 
+export interface DialogueDirection {
+  direction: string;
+}
+
+export type DialogueSegment = DialoguePrompt | DialogueDirection;
+
+/** A prompt or response: one speech object, or segments in authored order. */
+export type DialogueSpoken = DialoguePrompt | DialogueSegment[];
+
 export interface EffectSet { set: string; to: string | number | boolean; }
 export interface EffectMove { move: string; to: string; }
 export interface EffectDestroy { destroy: string; }
@@ -23,10 +34,15 @@ export interface DialogueChoice {
   id: string;
   label: string;
   sticky?: boolean;
-  conditions?: string[];
-  response?: DialoguePrompt;
+  /** Offered only on the section's first presentation. */
+  once?: boolean;
+  conditions?: UrdConditionExpr;
+  response?: DialogueSpoken;
   effects?: DialogueEffect[];
+  /** Section ID, or 'end' to end the conversation. */
   goto?: string;
+  /** Exit direction of the current location to leave through. */
+  exit?: string;
   choices?: DialogueChoice[];
 }
 
@@ -34,13 +50,14 @@ export interface DialogueOnExhausted {
   speaker?: string;
   text: string;
   goto?: string;
+  exit?: string;
 }
 
 export interface DialogueNode {
   id: string;
-  prompt?: DialoguePrompt;
+  prompt?: DialogueSpoken;
   description?: string;
-  conditions?: { any?: string[]; all?: string[] } | string[];
+  conditions?: UrdConditionExpr;
   choices: DialogueChoice[];
   on_exhausted?: DialogueOnExhausted;
 }
@@ -208,6 +225,22 @@ export interface CoverageData {
   exits: CoverageCategory;
 }
 
+// ===== Spoken content =====
+
+/** Normalise a prompt or response to its segment list. */
+export function spokenSegments(spoken: DialogueSpoken | undefined): DialogueSegment[] {
+  if (!spoken) return [];
+  return Array.isArray(spoken) ? spoken : [spoken];
+}
+
+/** The first speaker in a prompt or response, if any. */
+export function spokenSpeaker(spoken: DialogueSpoken | undefined): string | undefined {
+  for (const segment of spokenSegments(spoken)) {
+    if ('speaker' in segment) return segment.speaker;
+  }
+  return undefined;
+}
+
 // ===== Initial state factory =====
 
 export function createInitialPlaybackState(): PlaybackState {
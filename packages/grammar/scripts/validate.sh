@@ -17,7 +17,7 @@ echo ""
 if cargo test --test corpus 2>&1; then
     echo ""
     echo "════════════════════════════════════════"
-    echo "  12/12 passed"
+    echo "  16/16 passed"
     echo "════════════════════════════════════════"
     exit 0
 else
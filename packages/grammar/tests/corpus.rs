@@ -1,4 +1,4 @@
-use urd_grammar::parse;
+use urd_grammar::{parse, Rule};
 
 // ═══════════════════════════════════════════════════════════════
 // POSITIVE CORPUS — these files must parse successfully
@@ -48,6 +48,178 @@ fn valid_edge_cases_structure() {
     assert_valid("tests/valid/edge-cases-structure.urd.md");
 }
 
+#[test]
+fn valid_location_hooks() {
+    let path = "tests/valid/location-hooks.urd.md";
+    assert_valid(path);
+    let input = std::fs::read_to_string(path).unwrap();
+    let hooks = parse(&input)
+        .unwrap()
+        .flatten()
+        .filter(|p| p.as_rule() == Rule::LocationHook)
+        .count();
+    assert_eq!(hooks, 2, "Expected both hooks to parse as LocationHook, not Prose");
+}
+
+#[test]
+fn valid_regions() {
+    let path = "tests/valid/regions.urd.md";
+    assert_valid(path);
+    let input = std::fs::read_to_string(path).unwrap();
+    let pairs: Vec<_> = parse(&input).unwrap().flatten().collect();
+    let regions = pairs.iter().filter(|p| p.as_rule() == Rule::RegionHeading).count();
+    let locations = pairs.iter().filter(|p| p.as_rule() == Rule::LocationHeading).count();
+    assert_eq!(regions, 2, "Expected both region headings to parse as RegionHeading");
+    assert_eq!(locations, 3);
+}
+
+#[test]
+fn valid_explicit_ids() {
+    let path = "tests/valid/explicit-ids.urd.md";
+    assert_valid(path);
+    let input = std::fs::read_to_string(path).unwrap();
+    let ids: Vec<String> = parse(&input)
+        .unwrap()
+        .flatten()
+        .filter(|p| p.as_rule() == Rule::ExplicitIdValue)
+        .map(|p| p.as_str().to_string())
+        .collect();
+    assert_eq!(ids, vec!["cafe", "intro_topics", "street"]);
+    let names: Vec<String> = parse(&input)
+        .unwrap()
+        .flatten()
+        .filter(|p| p.as_rule() == Rule::HeadingName)
+        .map(|p| p.as_str().to_string())
+        .collect();
+    assert_eq!(names, vec!["Café & Bar!!!", "The Street"]);
+}
+
+#[test]
+fn valid_phase_sections() {
+    let path = "tests/valid/phase-sections.urd.md";
+    assert_valid(path);
+    let input = std::fs::read_to_string(path).unwrap();
+    let refs: Vec<String> = parse(&input)
+        .unwrap()
+        .flatten()
+        .filter(|p| p.as_rule() == Rule::PhaseSectionRef)
+        .map(|p| p.as_str().to_string())
+        .collect();
+    assert_eq!(refs, vec!["confession", "phase-sections/confession"]);
+}
+
+#[test]
+fn valid_qualified_sections() {
+    let path = "tests/valid/qualified-sections.urd.md";
+    assert_valid(path);
+    let input = std::fs::read_to_string(path).unwrap();
+    let refs: Vec<String> = parse(&input)
+        .unwrap()
+        .flatten()
+        .filter(|p| p.as_rule() == Rule::SectionRef)
+        .map(|p| p.as_str().to_string())
+        .collect();
+    assert_eq!(
+        refs,
+        vec![
+            "tavern/topics",
+            "tavern/topics",
+            "harbour-side/harbour_master",
+            "harbour-side/harbour_master",
+            "talk",
+            "talk",
+        ]
+    );
+}
+
+#[test]
+fn valid_presence_overrides() {
+    let path = "tests/valid/presence-overrides.urd.md";
+    assert_valid(path);
+    let input = std::fs::read_to_string(path).unwrap();
+    let overrides: Vec<String> = parse(&input)
+        .unwrap()
+        .flatten()
+        .filter(|p| p.as_rule() == Rule::PresenceOverride)
+        .map(|p| p.as_str().to_string())
+        .collect();
+    assert_eq!(
+        overrides,
+        vec!["locked: false", "opens: @cell_door", "label: \"Gate, rusted\"", "locked: true"]
+    );
+}
+
+#[test]
+fn valid_parameterised_sections() {
+    let path = "tests/valid/parameterised-sections.urd.md";
+    assert_valid(path);
+    let input = std::fs::read_to_string(path).unwrap();
+    let params = parse(&input)
+        .unwrap()
+        .flatten()
+        .filter(|p| p.as_rule() == Rule::SectionParam)
+        .count();
+    assert_eq!(params, 2, "Expected both section parameters to parse");
+    let arguments: Vec<String> = parse(&input)
+        .unwrap()
+        .flatten()
+        .filter(|p| p.as_rule() == Rule::JumpArgument)
+        .map(|p| p.as_str().to_string())
+        .collect();
+    assert_eq!(arguments, vec!["(@harbor_master)", "(@fishmonger)"]);
+}
+
+#[test]
+fn valid_choice_conditions() {
+    let path = "tests/valid/choice-conditions.urd.md";
+    assert_valid(path);
+    let input = std::fs::read_to_string(path).unwrap();
+    let conditions: Vec<String> = parse(&input)
+        .unwrap()
+        .flatten()
+        .filter(|p| p.as_rule() == Rule::ConditionExpr)
+        .map(|p| p.as_str().to_string())
+        .collect();
+    assert_eq!(conditions, vec!["once", "visited the-docks", "!visited the-docks"]);
+}
+
+#[test]
+fn valid_negated_conditions() {
+    let path = "tests/valid/negated-conditions.urd.md";
+    assert_valid(path);
+    let input = std::fs::read_to_string(path).unwrap();
+    let conditions: Vec<String> = parse(&input)
+        .unwrap()
+        .flatten()
+        .filter(|p| p.as_rule() == Rule::ConditionExpr)
+        .map(|p| p.as_str().to_string())
+        .collect();
+    assert_eq!(
+        conditions,
+        vec![
+            "!@warden.alert",
+            "@warden.alert",
+            "not @warden.trust < 5",
+            "not @warden.mood == calm",
+            "!@warden.alert",
+        ]
+    );
+}
+
+#[test]
+fn valid_endings() {
+    let path = "tests/valid/endings.urd.md";
+    assert_valid(path);
+    let input = std::fs::read_to_string(path).unwrap();
+    let ends: Vec<String> = parse(&input)
+        .unwrap()
+        .flatten()
+        .filter(|p| p.as_rule() == Rule::EndEffect)
+        .map(|p| p.as_str().to_string())
+        .collect();
+    assert_eq!(ends, vec!["end escaped", "end caught", "end caught"]);
+}
+
 // ═══════════════════════════════════════════════════════════════
 // NEGATIVE CORPUS — these files must fail to parse
 // ═══════════════════════════════════════════════════════════════
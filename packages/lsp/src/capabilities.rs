@@ -2,6 +2,16 @@
 
 use lsp_types::*;
 
+/// The `serverInfo` sent beside the capabilities: the server's name, and
+/// its version followed by the embedded compiler's,
+/// `0.1.0 (urd-compiler 0.1.14)`.
+pub fn server_info() -> ServerInfo {
+    ServerInfo {
+        name: "urd-lsp".to_string(),
+        version: Some(format!("{} ({})", env!("CARGO_PKG_VERSION"), urd_compiler::version::generator())),
+    }
+}
+
 pub fn server_capabilities() -> ServerCapabilities {
     ServerCapabilities {
         text_document_sync: Some(TextDocumentSyncCapability::Options(
@@ -21,9 +31,35 @@ pub fn server_capabilities() -> ServerCapabilities {
                 "@".to_string(),
                 ".".to_string(),
                 ">".to_string(),
+                "[".to_string(),
+                ",".to_string(),
+            ]),
+            ..Default::default()
+        }),
+        signature_help_provider: Some(SignatureHelpOptions {
+            trigger_characters: Some(vec![
+                ".".to_string(),
+                "=".to_string(),
+                " ".to_string(),
             ]),
             ..Default::default()
         }),
+        inlay_hint_provider: Some(OneOf::Left(true)),
+        code_lens_provider: Some(CodeLensOptions {
+            resolve_provider: Some(true),
+        }),
+        execute_command_provider: Some(ExecuteCommandOptions {
+            commands: vec![crate::code_lens::COMPILE_WORLD.to_string()],
+            ..Default::default()
+        }),
+        // A file's diagnostics depend on the files it imports and on the
+        // entry file that imports it.
+        diagnostic_provider: Some(DiagnosticServerCapabilities::Options(DiagnosticOptions {
+            identifier: Some("urd".to_string()),
+            inter_file_dependencies: true,
+            workspace_diagnostics: true,
+            ..Default::default()
+        })),
         ..Default::default()
     }
 }
@@ -0,0 +1,189 @@
+/// Code lens handler — reference counts and a compile action.
+///
+/// Every type, entity, location, and section declared in the file gets a
+/// lens on its declaration line. `textDocument/codeLens` returns these
+/// unresolved, carrying the definition key; `codeLens/resolve` counts the
+/// references from a ReferenceIndex built on demand, so no counting happens
+/// for lenses the editor never shows. Clicking a count opens the client's
+/// reference peek (`editor.action.showReferences`).
+///
+/// The frontmatter `world:` line gets a "Compile world" lens that runs
+/// `urd.compileWorld` on the server: a recompile and diagnostics push
+/// without saving. The server has no unsaved-buffer overlay, so the
+/// compile reads the files on disk.
+
+use lsp_server::Connection;
+use lsp_types::{CodeLens, CodeLensParams, Command, ExecuteCommandParams, Position, Range};
+use serde_json::json;
+
+use crate::diagnostics;
+use crate::world_state::{self, WorldState};
+use urd_compiler::definition_index::DefinitionKind;
+use urd_compiler::references::ReferenceIndex;
+
+/// The server command behind the "Compile world" lens.
+pub const COMPILE_WORLD: &str = "urd.compileWorld";
+
+/// The client command a reference count invokes.
+const SHOW_REFERENCES: &str = "editor.action.showReferences";
+
+pub fn handle(connection: &Connection, state: &WorldState, req: lsp_server::Request) {
+    let params: CodeLensParams = serde_json::from_value(req.params.clone()).unwrap();
+
+    let result = build_lenses(state, &params).unwrap_or_default();
+
+    let response = lsp_server::Response::new_ok(req.id, result);
+    connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+        .ok();
+}
+
+pub fn handle_resolve(connection: &Connection, state: &WorldState, req: lsp_server::Request) {
+    let lens: CodeLens = serde_json::from_value(req.params.clone()).unwrap();
+
+    let result = resolve_lens(state, lens);
+
+    let response = lsp_server::Response::new_ok(req.id, result);
+    connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+        .ok();
+}
+
+pub fn handle_execute_command(
+    connection: &Connection,
+    state: &mut WorldState,
+    req: lsp_server::Request,
+) {
+    let params: ExecuteCommandParams = serde_json::from_value(req.params.clone()).unwrap();
+
+    let response = if params.command == COMPILE_WORLD {
+        if state.entry_path.is_none() {
+            state.entry_path = params
+                .arguments
+                .first()
+                .and_then(|uri| serde_json::from_value(uri.clone()).ok())
+                .map(|uri| world_state::uri_to_path(&uri));
+        }
+        state.recompile();
+        diagnostics::push_diagnostics(connection, state);
+        lsp_server::Response::new_ok(req.id, serde_json::Value::Null)
+    } else {
+        lsp_server::Response::new_err(
+            req.id,
+            lsp_server::ErrorCode::InvalidParams as i32,
+            format!("Unknown command '{}'", params.command),
+        )
+    };
+    connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+        .ok();
+}
+
+fn build_lenses(state: &WorldState, params: &CodeLensParams) -> Option<Vec<CodeLens>> {
+    let root_dir = state.root_dir()?;
+    let uri = &params.text_document.uri;
+    let path = world_state::uri_to_path(uri);
+    let source = std::fs::read_to_string(&path).ok()?;
+
+    let mut lenses = Vec::new();
+
+    if let Some(line) = world_block_line(&source) {
+        let position = Position { line, character: 0 };
+        lenses.push(CodeLens {
+            range: Range { start: position, end: position },
+            command: Some(Command {
+                title: "Compile world".to_string(),
+                command: COMPILE_WORLD.to_string(),
+                arguments: Some(vec![json!(uri)]),
+            }),
+            data: None,
+        });
+    }
+
+    // The current compile's index, not the stale-retained one: resolve
+    // counts from the same compile's graph.
+    let index = state.result.as_ref().and_then(|r| r.definition_index.as_ref());
+    let file = world_state::span_file_for_path(&path, &root_dir);
+    for (key, entry) in index.into_iter().flat_map(|i| i.iter()) {
+        let counted = matches!(
+            entry.kind,
+            DefinitionKind::Type
+                | DefinitionKind::Entity { .. }
+                | DefinitionKind::Location { .. }
+                | DefinitionKind::Section { .. }
+        );
+        if !counted || Some(&entry.span.file) != file.as_ref() {
+            continue;
+        }
+        let start = world_state::span_to_range(&entry.span).start;
+        lenses.push(CodeLens {
+            range: Range { start, end: start },
+            command: None,
+            data: Some(json!({ "uri": uri, "key": key })),
+        });
+    }
+
+    lenses.sort_by_key(|l| l.range.start.line);
+    Some(lenses)
+}
+
+/// Fill in a reference-count lens. Lenses that already carry a command,
+/// or that arrive before any compile has linked, come back unchanged.
+fn resolve_lens(state: &WorldState, mut lens: CodeLens) -> CodeLens {
+    if lens.command.is_some() {
+        return lens;
+    }
+    let Some(data) = lens.data.as_ref() else {
+        return lens;
+    };
+    let key = data.get("key").and_then(|k| k.as_str());
+    let uri = data.get("uri").and_then(|u| serde_json::from_value::<lsp_types::Uri>(u.clone()).ok());
+    let (Some(key), Some(uri)) = (key, uri) else {
+        return lens;
+    };
+    let result = state.result.as_ref();
+    let (Some(graph), Some(symbol_table), Some(root_dir)) = (
+        result.and_then(|r| r.graph.as_ref()),
+        result.and_then(|r| r.symbol_table.as_ref()),
+        state.root_dir(),
+    ) else {
+        return lens;
+    };
+
+    let refs = ReferenceIndex::build(graph, symbol_table);
+    let locations: Vec<lsp_types::Location> = refs
+        .get(key)
+        .iter()
+        .map(|span| world_state::span_to_location(span, &root_dir))
+        .collect();
+    let title = match locations.len() {
+        1 => "1 reference".to_string(),
+        n => format!("{} references", n),
+    };
+    lens.command = Some(Command {
+        title,
+        command: SHOW_REFERENCES.to_string(),
+        arguments: Some(vec![json!(uri), json!(lens.range.start), json!(locations)]),
+    });
+    lens
+}
+
+/// The 0-indexed line of the top-level `world:` key in the frontmatter.
+fn world_block_line(source: &str) -> Option<u32> {
+    let mut lines = source.lines().enumerate();
+    if lines.next()?.1.trim_end() != "---" {
+        return None;
+    }
+    for (i, line) in lines {
+        if line.trim_end() == "---" {
+            return None;
+        }
+        if line.starts_with("world:") {
+            return Some(i as u32);
+        }
+    }
+    None
+}
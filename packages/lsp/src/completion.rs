@@ -3,8 +3,10 @@
 use lsp_server::Connection;
 use lsp_types::*;
 
+use crate::cursor;
 use crate::world_state::{self, WorldState};
 use urd_compiler::definition_index::DefinitionKind;
+use urd_compiler::traits;
 
 pub fn handle(connection: &Connection, state: &WorldState, req: lsp_server::Request) {
     let params: CompletionParams = serde_json::from_value(req.params.clone()).unwrap();
@@ -40,6 +42,14 @@ fn build_completions(state: &WorldState, params: &CompletionParams) -> Vec<Compl
     // Determine trigger context
     let trimmed = before_cursor.trim_end();
 
+    // Inside "Type [" — complete built-in trait names
+    if let Some(open) = cursor::trait_list_start(before_cursor) {
+        let listed = &before_cursor[open + 1..];
+        if !listed.contains(']') {
+            return complete_traits(listed);
+        }
+    }
+
     // After "@entity." — complete properties for the entity's type
     if let Some(entity_prop_prefix) = extract_entity_dot_prefix(trimmed) {
         return complete_entity_properties(state, &entity_prop_prefix);
@@ -50,16 +60,25 @@ fn build_completions(state: &WorldState, params: &CompletionParams) -> Vec<Compl
         return complete_entities(state);
     }
 
-    // After "-> " — complete section names
+    // After "-> " — complete jump targets
+    let line_number = position.line + 1;
     if trimmed.ends_with("-> ") || trimmed.ends_with("->") {
-        return complete_sections(state);
+        return complete_jump_targets(state, &path, line_number);
     }
 
     // After "->" followed by partial text
     if let Some(arrow_pos) = trimmed.rfind("-> ") {
         let partial = &trimmed[arrow_pos + 3..];
         if !partial.contains(' ') {
-            return complete_sections(state);
+            return complete_jump_targets(state, &path, line_number);
+        }
+    }
+
+    // After "? " — the section of an exhaustion check
+    let condition = before_cursor.trim_start();
+    if let Some(partial) = condition.strip_prefix("? ") {
+        if !partial.contains(' ') && !partial.contains('.') {
+            return complete_sections(state, &path);
         }
     }
 
@@ -142,34 +161,115 @@ fn complete_entity_properties(state: &WorldState, entity_id: &str) -> Vec<Comple
         .collect()
 }
 
-/// Complete section names from the DefinitionIndex.
-fn complete_sections(state: &WorldState) -> Vec<CompletionItem> {
+/// Complete built-in trait names from the compiler's trait registry,
+/// leaving out traits already in the list.
+fn complete_traits(listed: &str) -> Vec<CompletionItem> {
+    let already: Vec<&str> = listed.split(',').map(|t| t.trim()).collect();
+    traits::BUILTIN_TRAITS
+        .iter()
+        .filter(|t| !already.contains(&t.name))
+        .map(|t| CompletionItem {
+            label: t.name.to_string(),
+            kind: Some(CompletionItemKind::ENUM_MEMBER),
+            detail: Some("built-in trait".to_string()),
+            documentation: Some(Documentation::String(t.description.to_string())),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Complete the sections visible from `path`: its own by local name, then
+/// those of the files it imports directly by compiled ID (`file_stem/name`).
+/// Sections elsewhere in the world are left out, since LINK would not
+/// resolve them from here.
+fn complete_sections(state: &WorldState, path: &std::path::Path) -> Vec<CompletionItem> {
     let index = match &state.definition_index {
         Some(i) => i,
         None => return vec![],
     };
+    let Some(file) = state.root_dir().and_then(|root| world_state::span_file_for_path(path, &root)) else {
+        return vec![];
+    };
+    let imports: Vec<&String> = state
+        .result
+        .as_ref()
+        .and_then(|result| result.graph.as_ref()?.nodes.get(&file))
+        .map(|node| node.imports.iter().collect())
+        .unwrap_or_default();
 
-    // Collect unique local_names from sections
-    let mut seen = std::collections::HashSet::new();
-    index
-        .iter()
-        .filter_map(|(_, entry)| match &entry.kind {
-            DefinitionKind::Section {
-                local_name,
-                file_stem,
-            } => {
-                if seen.insert(local_name.clone()) {
-                    Some(CompletionItem {
-                        label: local_name.clone(),
-                        kind: Some(CompletionItemKind::REFERENCE),
-                        detail: Some(format!("in {}", file_stem)),
-                        ..Default::default()
-                    })
-                } else {
-                    None
-                }
-            }
-            _ => None,
+    let mut local = Vec::new();
+    let mut qualified = Vec::new();
+    for (key, entry) in index.iter() {
+        let DefinitionKind::Section { local_name, .. } = &entry.kind else {
+            continue;
+        };
+        let compiled_id = key.strip_prefix("section:").unwrap_or(key);
+        if entry.span.file == file {
+            local.push(section_item(state, local_name, compiled_id));
+        } else if imports.contains(&&entry.span.file) {
+            qualified.push(section_item(state, compiled_id, compiled_id));
+        }
+    }
+    local.extend(qualified);
+    local
+}
+
+/// Complete a jump target: the visible sections, `end`, and an `exit:`
+/// jump for each exit of the location enclosing `line` (1-based).
+fn complete_jump_targets(state: &WorldState, path: &std::path::Path, line: u32) -> Vec<CompletionItem> {
+    let Some(index) = &state.definition_index else {
+        return vec![];
+    };
+    let mut items = complete_sections(state, path);
+    items.push(CompletionItem {
+        label: "end".to_string(),
+        kind: Some(CompletionItemKind::KEYWORD),
+        detail: Some("end the conversation".to_string()),
+        ..Default::default()
+    });
+
+    let location = state
+        .root_dir()
+        .and_then(|root| world_state::span_file_for_path(path, &root))
+        .and_then(|file| state.enclosing_location(&file, line).map(str::to_string));
+    let Some(location) = location else {
+        return items;
+    };
+    let prefix = format!("exit:{}/", location);
+    items.extend(index.iter().filter_map(|(key, entry)| {
+        let direction = key.strip_prefix(&prefix)?;
+        let DefinitionKind::Exit { destination, .. } = &entry.kind else {
+            return None;
+        };
+        Some(CompletionItem {
+            label: format!("exit:{}", direction),
+            kind: Some(CompletionItemKind::REFERENCE),
+            detail: Some(destination.clone()),
+            ..Default::default()
         })
-        .collect()
+    }));
+    items
+}
+
+/// A section completion: `label` as typed, the compiled ID it resolves to
+/// as detail, and the first line of its prompt or description from the
+/// last world that emitted.
+fn section_item(state: &WorldState, label: &str, compiled_id: &str) -> CompletionItem {
+    let summary = state.world_json.as_ref().and_then(|world| {
+        let section = &world["dialogue"][compiled_id];
+        let prompt = &section["prompt"];
+        let text = prompt["text"]
+            .as_str()
+            .or_else(|| prompt[0]["text"].as_str())
+            .or_else(|| prompt[0]["direction"].as_str())
+            .or_else(|| section["description"].as_str())?;
+        text.lines().next().map(str::to_string)
+    });
+    CompletionItem {
+        label: label.to_string(),
+        kind: Some(CompletionItemKind::REFERENCE),
+        detail: Some(compiled_id.to_string()),
+        documentation: summary.map(Documentation::String),
+        ..Default::default()
+    }
 }
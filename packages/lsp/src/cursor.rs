@@ -13,12 +13,19 @@ pub enum Reference {
     EntityProperty(String, String),
     /// `Type.property` in a condition or effect
     TypeProperty(String, String),
-    /// `-> section_name` (a jump target)
+    /// `-> section_name` (a jump target) or `? section_name.exhausted`.
+    /// A file-qualified target (`file_stem/name`) is a compiled section ID.
     SectionJump(String),
+    /// `-> direction: Destination` (an exit declaration), anywhere on the line
+    ExitDeclaration(String),
+    /// `-> exit:direction` (an exit-qualified jump)
+    ExitJump(String),
     /// `== section_name` (a section label declaration)
     SectionLabel(String),
     /// `# Location Name` (a location heading)
     LocationHeading(String),
+    /// A trait in a type declaration's list: `Key [portable]:`
+    Trait(String),
 }
 
 /// Identify the reference under the cursor at (line_text, col).
@@ -26,34 +33,51 @@ pub enum Reference {
 /// `col` is 0-indexed (LSP convention). Returns `None` if the cursor is
 /// not on a recognisable identifier.
 ///
-/// Precedence: `@entity.property` > `@entity` > `-> section` >
-/// `== label` > `# heading` > `Type.property`.
+/// Precedence: `== label` > `# heading` > `-> direction: Destination` >
+/// `-> exit:name` > `-> section` > `? section.exhausted` >
+/// `@entity.property` > `@entity` > `Type [trait]` > `Type.property`.
 pub fn identify_reference(line: &str, col: usize) -> Option<Reference> {
     let trimmed = line.trim_start();
 
-    // 1. Check for section label: == name
-    if trimmed.starts_with("== ") {
-        let name = trimmed[3..].trim();
+    // 1. Check for section label: == name, or == name {#id}
+    if let Some(rest) = trimmed.strip_prefix("== ") {
+        let (name, explicit_id) = split_explicit_id(rest.trim());
+        let name = explicit_id.unwrap_or(name);
         if !name.is_empty() {
             return Some(Reference::SectionLabel(name.to_string()));
         }
     }
-    if trimmed.starts_with("==") && trimmed.len() > 2 {
-        let name = trimmed[2..].trim();
+    if let Some(rest) = trimmed.strip_prefix("==").filter(|r| !r.is_empty()) {
+        let (name, explicit_id) = split_explicit_id(rest.trim());
+        let name = explicit_id.unwrap_or(name);
         if !name.is_empty() {
             return Some(Reference::SectionLabel(name.to_string()));
         }
     }
 
-    // 2. Check for location heading: # Name (single #)
-    if trimmed.starts_with("# ") && !trimmed.starts_with("## ") {
-        let name = trimmed[2..].trim();
+    // 2. Check for location heading: # Name (single #), ignoring any {#id}.
+    // A region heading (# Region: Name) names no location.
+    if trimmed.starts_with("# Region: ") {
+        return None;
+    }
+    if let Some(rest) = trimmed.strip_prefix("# ") {
+        let (name, _) = split_explicit_id(rest.trim());
         if !name.is_empty() {
             return Some(Reference::LocationHeading(name.to_string()));
         }
     }
 
-    // 3. Check for jump arrow: -> target
+    // 3. Check for an exit declaration: -> direction: Destination. The same
+    // test as PARSE: a `: ` with no other colon before it.
+    if let Some(after_arrow) = trimmed.strip_prefix("-> ") {
+        if let Some((direction, _)) = after_arrow.split_once(": ") {
+            if direction != "exit" && !direction.contains(':') && !direction.trim().is_empty() {
+                return Some(Reference::ExitDeclaration(direction.trim().to_string()));
+            }
+        }
+    }
+
+    // 4. Check for jump arrow: -> target, or -> exit:name
     if let Some(arrow_pos) = line.find("-> ") {
         let after_arrow = &line[arrow_pos + 3..];
         let target = after_arrow
@@ -64,6 +88,9 @@ pub fn identify_reference(line: &str, col: usize) -> Option<Reference> {
             let arrow_start = arrow_pos;
             let target_end = arrow_pos + 3 + target.len();
             if col >= arrow_start && col < target_end {
+                if let Some(direction) = target.strip_prefix("exit:") {
+                    return Some(Reference::ExitJump(direction.to_string()));
+                }
                 // Strip @ prefix if jumping to an entity (-> @entity syntax)
                 let clean = target.trim_start_matches('@');
                 return Some(Reference::SectionJump(clean.to_string()));
@@ -71,12 +98,27 @@ pub fn identify_reference(line: &str, col: usize) -> Option<Reference> {
         }
     }
 
-    // 4. Check for @entity or @entity.property
+    // 5. Check for an exhaustion check: ? section.exhausted
+    if let Some(condition) = trimmed.strip_prefix("? ") {
+        if let Some(section) = condition.trim_end().strip_suffix(".exhausted") {
+            let start = line.len() - trimmed.len() + 2;
+            if !section.is_empty() && col >= start && col < start + section.len() {
+                return Some(Reference::SectionJump(section.to_string()));
+            }
+        }
+    }
+
+    // 6. Check for @entity or @entity.property
     if let Some(result) = find_entity_reference(line, col) {
         return Some(result);
     }
 
-    // 5. Check for Type.property (uppercase start, dot, lowercase property)
+    // 7. Check for a trait inside a type declaration: Type [a, b]:
+    if let Some(result) = find_trait(line, col) {
+        return Some(result);
+    }
+
+    // 8. Check for Type.property (uppercase start, dot, lowercase property)
     if let Some(result) = find_type_property(line, col) {
         return Some(result);
     }
@@ -201,6 +243,68 @@ fn find_type_property(line: &str, col: usize) -> Option<Reference> {
     None
 }
 
+/// Find the trait under the cursor in a `Type [trait, ...]:` declaration.
+fn find_trait(line: &str, col: usize) -> Option<Reference> {
+    let col = byte_offset(line, col);
+    let open = trait_list_start(line)?;
+    let close = open + line[open..].find(']')?;
+    if col <= open || col > close {
+        return None;
+    }
+
+    let start = line[..col]
+        .rfind(|c: char| !c.is_alphanumeric() && c != '_')
+        .map(|pos| pos + 1)
+        .unwrap_or(0);
+    let end = line[col..]
+        .find(|c: char| !c.is_alphanumeric() && c != '_')
+        .map(|pos| col + pos)
+        .unwrap_or(line.len());
+    if start >= end {
+        return None;
+    }
+    Some(Reference::Trait(line[start..end].to_string()))
+}
+
+/// The byte offset of UTF-16 column `col` in `line`, rounded to a
+/// character boundary. LSP columns count UTF-16 code units, so a column
+/// is only a byte index on an ASCII line.
+fn byte_offset(line: &str, col: usize) -> usize {
+    let mut units = 0;
+    for (i, c) in line.char_indices() {
+        if units >= col {
+            return i;
+        }
+        units += c.len_utf16();
+    }
+    line.len()
+}
+
+/// Byte offset of the `[` opening a type declaration's trait list, if
+/// `line` starts like one: an indented capitalised name followed by `[`.
+pub fn trait_list_start(line: &str) -> Option<usize> {
+    let indent = line.len() - line.trim_start().len();
+    let open = line.find('[')?;
+    let name = line[indent..open].trim_end();
+    let is_type_name = name.chars().next().is_some_and(|c| c.is_uppercase())
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    (indent > 0 && is_type_name).then_some(open)
+}
+
+/// Split a trailing ` {#id}` override off a heading or section label,
+/// matching the compiler's parser.
+fn split_explicit_id(text: &str) -> (&str, Option<&str>) {
+    if let Some(body) = text.strip_suffix('}') {
+        if let Some(pos) = body.rfind("{#") {
+            let before = &body[..pos];
+            if before.ends_with(' ') && !before.trim().is_empty() {
+                return (before.trim_end(), Some(&body[pos + 2..]));
+            }
+        }
+    }
+    (text, None)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -243,6 +347,41 @@ mod tests {
         );
     }
 
+    #[test]
+    fn qualified_section_jump() {
+        assert_eq!(
+            identify_reference("  -> tavern/topics", 10),
+            Some(Reference::SectionJump("tavern/topics".to_string()))
+        );
+    }
+
+    #[test]
+    fn exhaustion_check() {
+        assert_eq!(
+            identify_reference("  ? tavern/topics.exhausted", 6),
+            Some(Reference::SectionJump("tavern/topics".to_string()))
+        );
+        assert_eq!(identify_reference("  ? tavern/topics.exhausted", 20), None);
+    }
+
+    #[test]
+    fn exit_declaration_anywhere_on_line() {
+        for col in [0, 4, 12] {
+            assert_eq!(
+                identify_reference("-> north: Corridor", col),
+                Some(Reference::ExitDeclaration("north".to_string()))
+            );
+        }
+    }
+
+    #[test]
+    fn exit_qualified_jump() {
+        assert_eq!(
+            identify_reference("  -> exit:north", 8),
+            Some(Reference::ExitJump("north".to_string()))
+        );
+    }
+
     #[test]
     fn section_label() {
         assert_eq!(
@@ -259,6 +398,56 @@ mod tests {
         );
     }
 
+    #[test]
+    fn section_label_with_explicit_id() {
+        assert_eq!(
+            identify_reference("== topics {#intro_topics}", 5),
+            Some(Reference::SectionLabel("intro_topics".to_string()))
+        );
+    }
+
+    #[test]
+    fn region_heading_is_not_a_reference() {
+        assert_eq!(identify_reference("# Region: The Docks", 12), None);
+    }
+
+    #[test]
+    fn location_heading_with_explicit_id() {
+        assert_eq!(
+            identify_reference("# Café & Bar {#cafe}", 3),
+            Some(Reference::LocationHeading("Café & Bar".to_string()))
+        );
+    }
+
+    #[test]
+    fn trait_in_type_declaration() {
+        assert_eq!(
+            identify_reference("  Key [portable, interactable]:", 18),
+            Some(Reference::Trait("interactable".to_string()))
+        );
+        assert_eq!(
+            identify_reference("  Key [portable, interactable]:", 7),
+            Some(Reference::Trait("portable".to_string()))
+        );
+        assert_eq!(identify_reference("  Key [portable]:", 3), None);
+    }
+
+    #[test]
+    fn trait_on_a_non_ascii_line() {
+        assert_eq!(
+            identify_reference("  Crate [portáble, x]:", 14),
+            Some(Reference::Trait("portáble".to_string()))
+        );
+        assert_eq!(
+            identify_reference("  Crate [portáble, x]:", 19),
+            Some(Reference::Trait("x".to_string()))
+        );
+        assert_eq!(
+            identify_reference("  Charactér [interactable]:", 18),
+            Some(Reference::Trait("interactable".to_string()))
+        );
+    }
+
     #[test]
     fn type_property() {
         assert_eq!(
@@ -27,7 +27,7 @@ fn find_definition(
     params: &lsp_types::GotoDefinitionParams,
 ) -> Option<lsp_types::GotoDefinitionResponse> {
     let index = state.definition_index.as_ref()?;
-    let entry_dir = state.entry_dir()?;
+    let root_dir = state.root_dir()?;
     let uri = &params.text_document_position_params.text_document.uri;
     let position = &params.text_document_position_params.position;
 
@@ -43,7 +43,7 @@ fn find_definition(
         Reference::Entity(id) => {
             let key = format!("entity:@{}", id);
             let entry = index.get(&key)?;
-            let location = world_state::span_to_location(&entry.span, &entry_dir);
+            let location = world_state::span_to_location(&entry.span, &root_dir);
             Some(lsp_types::GotoDefinitionResponse::Scalar(location))
         }
         Reference::EntityProperty(entity_id, property) => {
@@ -51,13 +51,19 @@ fn find_definition(
             let type_name = resolve_entity_type(state, &entity_id)?;
             let key = format!("prop:{}.{}", type_name, property);
             let entry = index.get(&key)?;
-            let location = world_state::span_to_location(&entry.span, &entry_dir);
+            let location = world_state::span_to_location(&entry.span, &root_dir);
             Some(lsp_types::GotoDefinitionResponse::Scalar(location))
         }
         Reference::TypeProperty(type_name, property) => {
             let key = format!("prop:{}.{}", type_name, property);
             let entry = index.get(&key)?;
-            let location = world_state::span_to_location(&entry.span, &entry_dir);
+            let location = world_state::span_to_location(&entry.span, &root_dir);
+            Some(lsp_types::GotoDefinitionResponse::Scalar(location))
+        }
+        Reference::SectionJump(name) if name.contains('/') => {
+            // A file-qualified target is the compiled section ID.
+            let entry = index.get(&format!("section:{}", name))?;
+            let location = world_state::span_to_location(&entry.span, &root_dir);
             Some(lsp_types::GotoDefinitionResponse::Scalar(location))
         }
         Reference::SectionJump(name) => {
@@ -66,7 +72,7 @@ fn find_definition(
                 .iter()
                 .filter_map(|(_, entry)| match &entry.kind {
                     DefinitionKind::Section { local_name, .. } if local_name == &name => {
-                        Some(world_state::span_to_location(&entry.span, &entry_dir))
+                        Some(world_state::span_to_location(&entry.span, &root_dir))
                     }
                     _ => None,
                 })
@@ -86,7 +92,7 @@ fn find_definition(
                 .iter()
                 .filter_map(|(_, entry)| match &entry.kind {
                     DefinitionKind::Section { local_name, .. } if local_name == &name => {
-                        Some(world_state::span_to_location(&entry.span, &entry_dir))
+                        Some(world_state::span_to_location(&entry.span, &root_dir))
                     }
                     _ => None,
                 })
@@ -105,8 +111,8 @@ fn find_definition(
             let locations: Vec<lsp_types::Location> = index
                 .iter()
                 .filter_map(|(_, entry)| match &entry.kind {
-                    DefinitionKind::Location { display_name } if display_name == &name => {
-                        Some(world_state::span_to_location(&entry.span, &entry_dir))
+                    DefinitionKind::Location { display_name, .. } if display_name == &name => {
+                        Some(world_state::span_to_location(&entry.span, &root_dir))
                     }
                     _ => None,
                 })
@@ -120,6 +126,9 @@ fn find_definition(
                 _ => Some(lsp_types::GotoDefinitionResponse::Array(locations)),
             }
         }
+        // Traits are built in; there is no declaration to jump to.
+        Reference::Trait(_) => None,
+        Reference::ExitDeclaration(_) | Reference::ExitJump(_) => None,
     }
 }
 
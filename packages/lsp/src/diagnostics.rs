@@ -1,55 +1,182 @@
-/// Compiler diagnostics → LSP diagnostics mapping and push.
+/// Compiler diagnostics → LSP diagnostics mapping, push and pull.
+///
+/// Every recompile pushes `publishDiagnostics`, unless the client turned
+/// that off with `{ "pushDiagnostics": false }` in `initializationOptions`.
+/// Clients that pull (LSP 3.17) send `textDocument/diagnostic` or
+/// `workspace/diagnostic` and get the same diagnostics from the last
+/// compile. Reports carry the compile generation as their result ID; a
+/// pull whose previous result ID is still current is answered "unchanged".
 
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 use lsp_server::Connection;
 use lsp_types::notification::Notification;
-use lsp_types::Uri;
+use lsp_types::{
+    DocumentDiagnosticParams, DocumentDiagnosticReport, DocumentDiagnosticReportResult,
+    FullDocumentDiagnosticReport, RelatedFullDocumentDiagnosticReport,
+    RelatedUnchangedDocumentDiagnosticReport, UnchangedDocumentDiagnosticReport, Uri,
+    WorkspaceDiagnosticParams, WorkspaceDiagnosticReport, WorkspaceDiagnosticReportResult,
+    WorkspaceDocumentDiagnosticReport, WorkspaceFullDocumentDiagnosticReport,
+    WorkspaceUnchangedDocumentDiagnosticReport,
+};
+use urd_compiler::diagnostics::catalog;
 
 use crate::world_state::{self, WorldState};
 
+/// Whether to push diagnostics, from the client's `initializationOptions`.
+/// On unless `pushDiagnostics` is `false`.
+pub fn push_enabled(options: Option<&serde_json::Value>) -> bool {
+    options
+        .and_then(|o| o.get("pushDiagnostics"))
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true)
+}
+
 /// Push diagnostics for all tracked files to the editor.
 ///
 /// Groups diagnostics by file, sends a `publishDiagnostics` notification
 /// per file, and clears diagnostics for files that no longer have errors.
+/// Does nothing when the client turned pushing off.
 pub fn push_diagnostics(connection: &Connection, state: &WorldState) {
-    let result = match &state.result {
-        Some(r) => r,
-        None => return,
-    };
-
-    let entry_dir = match state.entry_dir() {
-        Some(d) => d,
-        None => return,
-    };
-
-    // Group compiler diagnostics by file
-    let mut by_file: HashMap<String, Vec<lsp_types::Diagnostic>> = HashMap::new();
-    for d in result.diagnostics.sorted() {
-        let lsp_diag = to_lsp_diagnostic(d);
-        by_file.entry(d.span.file.clone()).or_default().push(lsp_diag);
+    if !state.push_diagnostics {
+        return;
     }
+    let Some((root_dir, by_file)) = diagnostics_by_file(state) else {
+        return;
+    };
 
     // Push per-file diagnostics
     for (file, diags) in &by_file {
-        let uri = world_state::span_file_to_uri(file, &entry_dir);
+        let uri = world_state::span_file_to_uri(file, &root_dir);
         send_diagnostics(connection, uri, diags.clone());
     }
 
     // Clear diagnostics for previously tracked files that no longer have errors
     for tracked in &state.tracked_files {
-        let filename = tracked
-            .file_name()
-            .map(|f| f.to_string_lossy().to_string())
-            .unwrap_or_default();
-        let tracked_str = tracked.to_string_lossy().replace('\\', "/");
-        if !by_file.contains_key(&filename) && !by_file.contains_key(&tracked_str) {
+        let span_file = world_state::span_file_for_path(tracked, &root_dir);
+        if !span_file.is_some_and(|f| by_file.contains_key(&f)) {
             let uri = world_state::path_to_uri(tracked);
             send_diagnostics(connection, uri, vec![]);
         }
     }
 }
 
+/// The latest compile's diagnostics as LSP diagnostics, grouped by span
+/// file, with the root directory the span files are relative to.
+fn diagnostics_by_file(state: &WorldState) -> Option<(PathBuf, BTreeMap<String, Vec<lsp_types::Diagnostic>>)> {
+    let result = state.result.as_ref()?;
+    let root_dir = state.root_dir()?;
+    let mut by_file: BTreeMap<String, Vec<lsp_types::Diagnostic>> = BTreeMap::new();
+    for d in result.diagnostics.sorted() {
+        let lsp_diag = to_lsp_diagnostic(d, &root_dir);
+        by_file.entry(d.span.file.clone()).or_default().push(lsp_diag);
+    }
+    Some((root_dir, by_file))
+}
+
+/// `textDocument/diagnostic`: the diagnostics the last compile reported
+/// for one file. Compiles first when no file has been opened yet.
+pub fn handle_document(connection: &Connection, state: &mut WorldState, req: lsp_server::Request) {
+    let params: DocumentDiagnosticParams = serde_json::from_value(req.params.clone()).unwrap();
+    if state.entry_path.is_none() {
+        state.entry_path = Some(world_state::uri_to_path(&params.text_document.uri));
+        state.recompile();
+    }
+
+    let result_id = state.generation.to_string();
+    let report = if params.previous_result_id.as_deref() == Some(result_id.as_str()) {
+        DocumentDiagnosticReport::Unchanged(RelatedUnchangedDocumentDiagnosticReport {
+            related_documents: None,
+            unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport { result_id },
+        })
+    } else {
+        let path = world_state::uri_to_path(&params.text_document.uri);
+        let items = diagnostics_by_file(state)
+            .and_then(|(root_dir, mut by_file)| {
+                by_file.remove(&world_state::span_file_for_path(&path, &root_dir)?)
+            })
+            .unwrap_or_default();
+        DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport {
+            related_documents: None,
+            full_document_diagnostic_report: FullDocumentDiagnosticReport {
+                result_id: Some(result_id),
+                items,
+            },
+        })
+    };
+
+    let response = lsp_server::Response::new_ok(req.id, DocumentDiagnosticReportResult::Report(report));
+    connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+        .ok();
+}
+
+/// `workspace/diagnostic`: a report for every file the last compile read,
+/// plus any file that had diagnostics before. A file whose previous result
+/// ID is current is reported unchanged.
+pub fn handle_workspace(connection: &Connection, state: &WorldState, req: lsp_server::Request) {
+    let params: WorkspaceDiagnosticParams = serde_json::from_value(req.params.clone()).unwrap();
+
+    let items = match diagnostics_by_file(state) {
+        Some((root_dir, mut by_file)) => {
+            let result_id = state.generation.to_string();
+            let compiled = state.result.as_ref().and_then(|r| r.graph.as_ref());
+            let mut paths: Vec<PathBuf> = compiled
+                .into_iter()
+                .flat_map(|graph| graph.nodes.keys())
+                .chain(by_file.keys())
+                .map(|file| world_state::span_file_to_path(file, &root_dir))
+                .chain(state.tracked_files.iter().cloned())
+                .collect();
+            paths.sort();
+            paths.dedup();
+
+            paths
+                .into_iter()
+                .map(|path| {
+                    let uri = world_state::path_to_uri(&path);
+                    let unchanged = params
+                        .previous_result_ids
+                        .iter()
+                        .any(|previous| previous.uri == uri && previous.value == result_id);
+                    if unchanged {
+                        return WorkspaceDocumentDiagnosticReport::Unchanged(
+                            WorkspaceUnchangedDocumentDiagnosticReport {
+                                uri,
+                                version: None,
+                                unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport {
+                                    result_id: result_id.clone(),
+                                },
+                            },
+                        );
+                    }
+                    let items = world_state::span_file_for_path(&path, &root_dir)
+                        .and_then(|file| by_file.remove(&file))
+                        .unwrap_or_default();
+                    WorkspaceDocumentDiagnosticReport::Full(WorkspaceFullDocumentDiagnosticReport {
+                        uri,
+                        version: None,
+                        full_document_diagnostic_report: FullDocumentDiagnosticReport {
+                            result_id: Some(result_id.clone()),
+                            items,
+                        },
+                    })
+                })
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    let report = WorkspaceDiagnosticReportResult::Report(WorkspaceDiagnosticReport { items });
+    let response = lsp_server::Response::new_ok(req.id, report);
+    connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+        .ok();
+}
+
 fn send_diagnostics(connection: &Connection, uri: Uri, diagnostics: Vec<lsp_types::Diagnostic>) {
     let params = lsp_types::PublishDiagnosticsParams {
         uri,
@@ -66,7 +193,10 @@ fn send_diagnostics(connection: &Connection, uri: Uri, diagnostics: Vec<lsp_type
         .ok();
 }
 
-fn to_lsp_diagnostic(d: &urd_compiler::diagnostics::Diagnostic) -> lsp_types::Diagnostic {
+fn to_lsp_diagnostic(
+    d: &urd_compiler::diagnostics::Diagnostic,
+    root_dir: &Path,
+) -> lsp_types::Diagnostic {
     let severity = match d.severity {
         urd_compiler::diagnostics::Severity::Error => lsp_types::DiagnosticSeverity::ERROR,
         urd_compiler::diagnostics::Severity::Warning => lsp_types::DiagnosticSeverity::WARNING,
@@ -79,25 +209,24 @@ fn to_lsp_diagnostic(d: &urd_compiler::diagnostics::Diagnostic) -> lsp_types::Di
         Some(
             d.related
                 .iter()
-                .filter_map(|r| {
-                    let uri_str = format!("file:///{}", r.span.file);
-                    let uri: Uri = uri_str.parse().ok()?;
-                    Some(lsp_types::DiagnosticRelatedInformation {
-                        location: lsp_types::Location {
-                            uri,
-                            range: world_state::span_to_range(&r.span),
-                        },
-                        message: r.message.clone(),
-                    })
+                .map(|r| lsp_types::DiagnosticRelatedInformation {
+                    location: world_state::span_to_location(&r.span, root_dir),
+                    message: r.message.clone(),
                 })
                 .collect(),
         )
     };
 
+    // Clients that support it render the code as a link to its reference entry.
+    let code_description = catalog::lookup(&d.code)
+        .and_then(|info| info.docs_url().parse::<Uri>().ok())
+        .map(|href| lsp_types::CodeDescription { href });
+
     lsp_types::Diagnostic {
         range: world_state::span_to_range(&d.span),
         severity: Some(severity),
         code: Some(lsp_types::NumberOrString::String(d.code.clone())),
+        code_description,
         source: Some("urd".to_string()),
         message: d.message.clone(),
         related_information,
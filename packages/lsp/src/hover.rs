@@ -1,12 +1,18 @@
-/// Hover handler — provides Markdown tooltips for Urd constructs.
+/// Hover handler — provides Markdown tooltips for Urd constructs, and the
+/// title and documentation link of any diagnostic under the cursor.
+
+use std::path::Path;
 
 use lsp_server::Connection;
-use lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind};
+use lsp_types::{Hover, HoverContents, MarkupContent, MarkupKind, Position};
 
 use crate::cursor::{self, Reference};
 use crate::world_state::{self, WorldState};
 use urd_compiler::definition_index::DefinitionKind;
+use urd_compiler::diagnostics::catalog;
+use urd_compiler::explain;
 use urd_compiler::facts::PropertyKey;
+use urd_compiler::traits;
 
 pub fn handle(connection: &Connection, state: &WorldState, req: lsp_server::Request) {
     let params: lsp_types::HoverParams =
@@ -29,8 +35,28 @@ fn build_hover(state: &WorldState, params: &lsp_types::HoverParams) -> Option<Ho
     let source = std::fs::read_to_string(&path).ok()?;
     let line = source.lines().nth(position.line as usize)?;
 
-    let reference = cursor::identify_reference(line, position.character as usize)?;
+    let content = cursor::identify_reference(line, position.character as usize)
+        .and_then(|reference| hover_reference(state, &path, position, reference))
+        .or_else(|| hover_choice(state, &path, position));
+    let notes = diagnostic_lines(state, &path, position);
+
+    let value = match content {
+        Some(content) if notes.is_empty() => content,
+        Some(content) => format!("{}\n\n---\n\n{}", content, notes.join("\n\n")),
+        None if notes.is_empty() => return None,
+        None => notes.join("\n\n"),
+    };
 
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value,
+        }),
+        range: None,
+    })
+}
+
+fn hover_reference(state: &WorldState, path: &Path, position: &Position, reference: Reference) -> Option<String> {
     let content = match reference {
         Reference::Entity(id) => hover_entity(state, &id)?,
         Reference::EntityProperty(entity_id, property) => {
@@ -44,15 +70,40 @@ fn build_hover(state: &WorldState, params: &lsp_types::HoverParams) -> Option<Ho
             hover_section(state, &name)?
         }
         Reference::LocationHeading(name) => hover_location(state, &name)?,
+        Reference::ExitDeclaration(direction) | Reference::ExitJump(direction) => {
+            hover_exit(state, path, position, &direction)?
+        }
+        Reference::Trait(name) => hover_trait(&name),
+    };
+    Some(content)
+}
+
+/// One line per diagnostic code reported at the cursor: the code, its
+/// catalog title, and a link to its reference entry.
+fn diagnostic_lines(state: &WorldState, path: &Path, position: &Position) -> Vec<String> {
+    let (Some(result), Some(root_dir)) = (&state.result, state.root_dir()) else {
+        return Vec::new();
+    };
+    let Some(file) = world_state::span_file_for_path(path, &root_dir) else {
+        return Vec::new();
     };
 
-    Some(Hover {
-        contents: HoverContents::Markup(MarkupContent {
-            kind: MarkupKind::Markdown,
-            value: content,
-        }),
-        range: None,
-    })
+    let mut lines: Vec<String> = Vec::new();
+    for d in result.diagnostics.sorted() {
+        let range = world_state::span_to_range(&d.span);
+        let at_cursor = (range.start.line, range.start.character) <= (position.line, position.character)
+            && (position.line, position.character) <= (range.end.line, range.end.character);
+        if d.span.file != file || !at_cursor {
+            continue;
+        }
+        if let Some(info) = catalog::lookup(&d.code) {
+            let line = format!("**{}**: {} ([reference]({}))", info.code, info.title, info.docs_url());
+            if !lines.contains(&line) {
+                lines.push(line);
+            }
+        }
+    }
+    lines
 }
 
 fn hover_entity(state: &WorldState, entity_id: &str) -> Option<String> {
@@ -63,7 +114,8 @@ fn hover_entity(state: &WorldState, entity_id: &str) -> Option<String> {
     }
 
     let type_name = entity["type"].as_str().unwrap_or("unknown");
-    let mut lines = vec![format!("**@{}**: {}", entity_id, type_name)];
+    let mut lines = doc_lines(state, &format!("entity:@{}", entity_id));
+    lines.push(format!("**@{}**: {}", entity_id, type_name));
 
     // Container (which location holds this entity)
     if let Some(locations) = world["locations"].as_object() {
@@ -96,7 +148,7 @@ fn hover_property(state: &WorldState, type_name: &str, property: &str) -> Option
     let key = format!("prop:{}.{}", type_name, property);
     let entry = index.get(&key)?;
 
-    let mut lines = Vec::new();
+    let mut lines = doc_lines(state, &format!("type:{}", type_name));
 
     // Type and default from DefinitionIndex
     if let DefinitionKind::Property {
@@ -157,7 +209,8 @@ fn hover_section(state: &WorldState, local_name: &str) -> Option<String> {
     // Strip "section:" prefix to get compiled_id
     let compiled_id = section_key.strip_prefix("section:")?;
 
-    let mut lines = vec![format!("**Section**: {}", compiled_id)];
+    let mut lines = doc_lines(state, section_key);
+    lines.push(format!("**Section**: {}", compiled_id));
 
     // Count incoming/outgoing jumps and choices from FactSet
     if let Some(fact_set) = state.fact_set() {
@@ -188,12 +241,13 @@ fn hover_location(state: &WorldState, display_name: &str) -> Option<String> {
 
     // Find location by display_name
     let (location_key, _entry) = index.iter().find(|(_, entry)| {
-        matches!(&entry.kind, DefinitionKind::Location { display_name: dn } if dn == display_name)
+        matches!(&entry.kind, DefinitionKind::Location { display_name: dn, .. } if dn == display_name)
     })?;
 
     let slug = location_key.strip_prefix("location:")?;
 
-    let mut lines = vec![format!("**Location**: {}", slug)];
+    let mut lines = doc_lines(state, location_key);
+    lines.push(format!("**Location**: {}", slug));
 
     // Count exits and entities from world JSON
     if let Some(world) = &state.world_json {
@@ -210,6 +264,100 @@ fn hover_location(state: &WorldState, display_name: &str) -> Option<String> {
     Some(lines.join("\n\n"))
 }
 
+/// On an exit declaration or an `-> exit:name` jump, where the exit leads:
+/// the destination's name and first description paragraph, then the
+/// exit's condition and blocked message.
+fn hover_exit(state: &WorldState, path: &Path, position: &Position, direction: &str) -> Option<String> {
+    let index = state.definition_index.as_ref()?;
+    let file = world_state::span_file_for_path(path, &state.root_dir()?)?;
+    let line = position.line + 1;
+
+    // A declaration is on the cursor line; a jump takes the exit of the
+    // last location heading above it.
+    let (_, exit) = index
+        .iter()
+        .find(|(key, entry)| {
+            key.starts_with("exit:") && entry.span.file == file && entry.span.start_line == line
+        })
+        .or_else(|| {
+            let from = state.enclosing_location(&file, line)?;
+            index.entries().get_key_value(&format!("exit:{}/{}", from, direction))
+        })?;
+    let DefinitionKind::Exit { from_location, destination, resolved } = &exit.kind else {
+        return None;
+    };
+
+    if !resolved {
+        let mut lines = vec![format!("**Exit**: {} → {} (unresolved)", direction, destination)];
+        // The URD312 hint, or its message when it has none.
+        let unresolved = state.result.as_ref().and_then(|result| {
+            result.diagnostics.all().iter().find(|d| d.code == "URD312" && d.span == exit.span)
+        });
+        if let Some(d) = unresolved {
+            lines.push(d.suggestion.clone().unwrap_or_else(|| d.message.clone()));
+        }
+        return Some(lines.join("\n\n"));
+    }
+
+    let location = index.get(&format!("location:{}", destination));
+    let (display_name, description) = match location.map(|entry| &entry.kind) {
+        Some(DefinitionKind::Location { display_name, description }) => (display_name.as_str(), description.first()),
+        _ => (destination.as_str(), None),
+    };
+    let mut lines = vec![format!("**Exit**: {} → {}", direction, display_name)];
+    lines.extend(description.cloned());
+
+    // Condition and blocked message from world JSON
+    if let Some(world) = &state.world_json {
+        let exit_json = &world["locations"][from_location]["exits"][direction];
+        if let Some(condition) = exit_json["condition"].as_str() {
+            lines.push(format!("Condition: `{}`", condition));
+        }
+        if let Some(message) = exit_json["blocked_message"].as_str() {
+            lines.push(format!("Blocked message: {}", message));
+        }
+    }
+
+    Some(lines.join("\n\n"))
+}
+
+/// On a choice line, why the choice is offered or withheld.
+fn hover_choice(state: &WorldState, path: &Path, position: &Position) -> Option<String> {
+    let fact_set = state.fact_set()?;
+    let file = world_state::span_file_for_path(path, &state.root_dir()?)?;
+    let choice = fact_set
+        .choices()
+        .iter()
+        .find(|c| c.span.file == file && c.span.start_line == position.line + 1)?;
+    let explanation = explain::explain_choice(fact_set, state.property_index()?, &choice.choice_id).ok()?;
+    Some(format!("**Choice**: {}\n\n{}", explanation.choice_id, explanation.summary()))
+}
+
+/// The `///` documentation for a DefinitionIndex key, as the opening hover
+/// paragraph. Empty when the definition has none.
+fn doc_lines(state: &WorldState, key: &str) -> Vec<String> {
+    state
+        .definition_index
+        .as_ref()
+        .and_then(|index| index.get(key))
+        .and_then(|entry| entry.doc.clone())
+        .into_iter()
+        .collect()
+}
+
+fn hover_trait(name: &str) -> String {
+    match traits::lookup(name) {
+        Some(info) => format!("**{}** (built-in trait)\n\n{}", info.name, info.description),
+        None => {
+            let mut text = format!("**{}**: not a built-in trait", name);
+            if let Some(suggestion) = traits::suggest(name) {
+                text.push_str(&format!("\n\nDid you mean `{}`?", suggestion));
+            }
+            text
+        }
+    }
+}
+
 fn resolve_entity_type(state: &WorldState, entity_id: &str) -> Option<String> {
     let world = state.world_json.as_ref()?;
     world["entities"][entity_id]["type"]
@@ -0,0 +1,168 @@
+/// Inlay hint handler — shows resolved compiled IDs and destinations.
+///
+/// Hints come from the latest compilation's DefinitionIndex and FactSet and
+/// sit at the end of the line they describe:
+///
+/// - section label → compiled section ID (`tavern/topics`)
+/// - exit declaration → resolved destination (`→ cellar`)
+/// - jump → what it resolved to (`section tavern/topics`, `exit → cellar`)
+/// - choice → its slug (`ask-about-the-ship`)
+///
+/// Each category can be switched off through `initializationOptions`:
+/// `{ "inlayHints": { "sections": false, "exits": true, ... } }`.
+
+use lsp_server::Connection;
+use lsp_types::{InlayHint, InlayHintLabel, InlayHintParams, InlayHintTooltip, Position};
+
+use crate::world_state::{self, WorldState};
+use urd_compiler::definition_index::DefinitionKind;
+use urd_compiler::facts::JumpTarget;
+use urd_compiler::span::Span;
+
+/// Which hint categories to show. All are on by default.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlayHintSettings {
+    pub sections: bool,
+    pub exits: bool,
+    pub jumps: bool,
+    pub choices: bool,
+}
+
+impl Default for InlayHintSettings {
+    fn default() -> Self {
+        Self {
+            sections: true,
+            exits: true,
+            jumps: true,
+            choices: true,
+        }
+    }
+}
+
+impl InlayHintSettings {
+    /// Read settings from the client's `initializationOptions`. Missing or
+    /// non-boolean fields keep their defaults.
+    pub fn from_init_options(options: Option<&serde_json::Value>) -> Self {
+        let mut settings = Self::default();
+        let Some(hints) = options.and_then(|o| o.get("inlayHints")) else {
+            return settings;
+        };
+        let flag = |name: &str, default: bool| hints.get(name).and_then(|v| v.as_bool()).unwrap_or(default);
+        settings.sections = flag("sections", settings.sections);
+        settings.exits = flag("exits", settings.exits);
+        settings.jumps = flag("jumps", settings.jumps);
+        settings.choices = flag("choices", settings.choices);
+        settings
+    }
+}
+
+pub fn handle(connection: &Connection, state: &WorldState, req: lsp_server::Request) {
+    let params: InlayHintParams = serde_json::from_value(req.params.clone()).unwrap();
+
+    let result = build_hints(state, &params).unwrap_or_default();
+
+    let response = lsp_server::Response::new_ok(req.id, result);
+    connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+        .ok();
+}
+
+fn build_hints(state: &WorldState, params: &InlayHintParams) -> Option<Vec<InlayHint>> {
+    let result = state.result.as_ref()?;
+    let root_dir = state.root_dir()?;
+    let path = world_state::uri_to_path(&params.text_document.uri);
+    let file = world_state::span_file_for_path(&path, &root_dir)?;
+    let source = std::fs::read_to_string(&path).ok()?;
+    let lines: Vec<&str> = source.lines().collect();
+    let settings = &state.inlay_hints;
+
+    // (span, label, tooltip) for every hint in the compilation unit.
+    let mut found: Vec<(&Span, String, String)> = Vec::new();
+
+    if let Some(index) = result.definition_index.as_ref() {
+        for (key, entry) in index.iter() {
+            match &entry.kind {
+                DefinitionKind::Section { .. } if settings.sections => {
+                    let id = key.strip_prefix("section:").unwrap_or(key);
+                    found.push((&entry.span, id.to_string(), "Compiled section ID".to_string()));
+                }
+                DefinitionKind::Choice { .. } if settings.choices => {
+                    let id = key.strip_prefix("choice:").unwrap_or(key);
+                    let slug = id.rsplit('/').next().unwrap_or(id);
+                    found.push((&entry.span, slug.to_string(), format!("Compiled choice ID: {}", id)));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if let Some(facts) = result.fact_set.as_ref() {
+        if settings.exits {
+            for exit in facts.exits() {
+                found.push((
+                    &exit.span,
+                    format!("→ {}", exit.to_location),
+                    format!("Exit {} resolves to location {}", exit.exit_id(), exit.to_location),
+                ));
+            }
+        }
+        if settings.jumps {
+            for jump in facts.jumps() {
+                let (label, tooltip) = match &jump.target {
+                    JumpTarget::Section(id) => {
+                        let mut tooltip = format!("Jump resolves to section {}", id);
+                        // Mirror URD310: the section wins over an exit of the same name.
+                        if result.diagnostics.all().iter().any(|d| d.code == "URD310" && d.span == jump.span) {
+                            tooltip.push_str(", shadowing an exit of the same name");
+                        }
+                        (format!("section {}", id), tooltip)
+                    }
+                    JumpTarget::Exit(exit_id) => {
+                        let destination = facts
+                            .exits()
+                            .iter()
+                            .find(|e| &e.exit_id() == exit_id)
+                            .map(|e| e.to_location.as_str())
+                            .unwrap_or("?");
+                        (
+                            format!("exit → {}", destination),
+                            format!("Jump resolves to exit {}", exit_id),
+                        )
+                    }
+                    JumpTarget::End => continue,
+                };
+                found.push((&jump.span, label, tooltip));
+            }
+        }
+    }
+
+    let range = &params.range;
+    let mut hints: Vec<InlayHint> = found
+        .into_iter()
+        .filter(|(span, _, _)| span.file == file)
+        .filter_map(|(span, label, tooltip)| {
+            let line = span.start_line.checked_sub(1)?;
+            if line < range.start.line || line > range.end.line {
+                return None;
+            }
+            let text = lines.get(line as usize)?;
+            Some(InlayHint {
+                position: Position {
+                    line,
+                    character: text.encode_utf16().count() as u32,
+                },
+                label: InlayHintLabel::String(label),
+                kind: None,
+                text_edits: None,
+                tooltip: Some(InlayHintTooltip::String(tooltip)),
+                padding_left: Some(true),
+                padding_right: None,
+                data: None,
+            })
+        })
+        .collect();
+
+    hints.sort_by_key(|h| h.position.line);
+    Some(hints)
+}
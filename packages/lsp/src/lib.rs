@@ -1,15 +1,23 @@
-/// Urd Language Server — embeds the compiler with real-time diagnostics,
-/// go-to-definition, hover, and autocomplete.
+/// Urd Language Server — embeds the compiler with real-time diagnostics
+/// (pushed, or pulled by clients that ask), go-to-definition, hover,
+/// autocomplete, signature help, inlay hints, code lenses, and symbol
+/// previews (`urd/previewSymbol`), and output locations
+/// (`urd/outputLocation`).
 ///
 /// Communicates via stdin/stdout using the Language Server Protocol.
 /// Synchronous, single-threaded, recompile-on-save.
 
 pub mod capabilities;
+pub mod code_lens;
 pub mod completion;
 pub mod cursor;
 pub mod definition;
 pub mod diagnostics;
 pub mod hover;
+pub mod inlay_hints;
+pub mod output_location;
+pub mod preview;
+pub mod signature_help;
 pub mod world_state;
 
 use lsp_server::{Connection, Message};
@@ -22,16 +30,38 @@ use lsp_types::InitializeParams;
 ///
 /// Exposed as a public function for mock-client testing via `Connection::memory()`.
 pub fn run_server(connection: Connection) {
-    let server_capabilities = capabilities::server_capabilities();
-    let init_params = connection
-        .initialize(serde_json::to_value(server_capabilities).unwrap())
+    let (init_id, init_params) = connection.initialize_start().unwrap();
+    let init_result = lsp_types::InitializeResult {
+        capabilities: capabilities::server_capabilities(),
+        server_info: Some(capabilities::server_info()),
+    };
+    connection
+        .initialize_finish(init_id, serde_json::to_value(init_result).unwrap())
         .unwrap();
-    let _params: InitializeParams = serde_json::from_value(init_params).unwrap();
+    let params: InitializeParams = serde_json::from_value(init_params).unwrap();
 
     let mut state = world_state::WorldState::new();
+    state.workspace_roots = workspace_roots(&params);
+    state.inlay_hints =
+        inlay_hints::InlayHintSettings::from_init_options(params.initialization_options.as_ref());
+    state.push_diagnostics = diagnostics::push_enabled(params.initialization_options.as_ref());
     main_loop(&connection, &mut state);
 }
 
+/// Workspace folders from the initialize request, falling back to the
+/// deprecated `rootUri` for clients that only send that.
+#[allow(deprecated)]
+fn workspace_roots(params: &InitializeParams) -> Vec<std::path::PathBuf> {
+    match (&params.workspace_folders, &params.root_uri) {
+        (Some(folders), _) if !folders.is_empty() => folders
+            .iter()
+            .map(|f| world_state::uri_to_path(&f.uri))
+            .collect(),
+        (_, Some(uri)) => vec![world_state::uri_to_path(uri)],
+        _ => Vec::new(),
+    }
+}
+
 fn main_loop(connection: &Connection, state: &mut world_state::WorldState) {
     for msg in &connection.receiver {
         match msg {
@@ -51,13 +81,22 @@ fn main_loop(connection: &Connection, state: &mut world_state::WorldState) {
 
 fn handle_request(
     connection: &Connection,
-    state: &world_state::WorldState,
+    state: &mut world_state::WorldState,
     req: lsp_server::Request,
 ) {
     match req.method.as_str() {
         "textDocument/definition" => definition::handle(connection, state, req),
         "textDocument/hover" => hover::handle(connection, state, req),
         "textDocument/completion" => completion::handle(connection, state, req),
+        "textDocument/signatureHelp" => signature_help::handle(connection, state, req),
+        "textDocument/inlayHint" => inlay_hints::handle(connection, state, req),
+        "textDocument/codeLens" => code_lens::handle(connection, state, req),
+        "textDocument/diagnostic" => diagnostics::handle_document(connection, state, req),
+        "workspace/diagnostic" => diagnostics::handle_workspace(connection, state, req),
+        "codeLens/resolve" => code_lens::handle_resolve(connection, state, req),
+        "workspace/executeCommand" => code_lens::handle_execute_command(connection, state, req),
+        preview::METHOD => preview::handle(connection, state, req),
+        output_location::METHOD => output_location::handle(connection, state, req),
         _ => {
             let resp = lsp_server::Response::new_err(
                 req.id,
@@ -0,0 +1,66 @@
+/// `urd/outputLocation` — between source lines and compiled JSON paths.
+///
+/// A custom request for editors that show the compiled world beside the
+/// source. It goes either way:
+///
+/// - `{ "textDocument": { "uri" }, "position": { "line", "character" } }`
+///   gets `{ "pointer", "path", "pointers" }` for the innermost object the
+///   line produces: `pointer` is an RFC 6901 JSON pointer, `path` the same
+///   path written as `dialogue["tavern/topics"].choices[2]`, and `pointers`
+///   every match, innermost first. `null` when the line produces nothing.
+/// - `{ "pointer": "/dialogue/tavern~1topics/choices/2" }` gets the
+///   `Location` of the source that produced it, or `null`.
+///
+/// Reads the output map kept from the last successful compile, so the
+/// answer lags behind a world that currently has errors.
+
+use std::path::Path;
+
+use lsp_server::Connection;
+use serde_json::{json, Value};
+
+use crate::world_state::{self, WorldState};
+
+/// The custom request method.
+pub const METHOD: &str = "urd/outputLocation";
+
+pub fn handle(connection: &Connection, state: &WorldState, req: lsp_server::Request) {
+    let params = &req.params;
+    let response = if let Some(pointer) = params["pointer"].as_str() {
+        lsp_server::Response::new_ok(req.id, source_location(state, pointer))
+    } else if let (Some(uri), Some(line)) =
+        (params["textDocument"]["uri"].as_str(), params["position"]["line"].as_u64())
+    {
+        lsp_server::Response::new_ok(req.id, output_location(state, uri, line as u32 + 1))
+    } else {
+        lsp_server::Response::new_err(
+            req.id,
+            lsp_server::ErrorCode::InvalidParams as i32,
+            "Expected a 'pointer' string, or a 'textDocument' and 'position'".to_string(),
+        )
+    };
+    connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+        .ok();
+}
+
+/// The paths `line` (1-based) of the document at `uri` produces.
+fn output_location(state: &WorldState, uri: &str, line: u32) -> Option<Value> {
+    let map = state.output_map.as_ref()?;
+    let path = world_state::uri_str_to_path(uri);
+    let file = world_state::span_file_for_path(Path::new(&path), &state.root_dir()?)?;
+    let paths = map.paths_at(&file, line);
+    let innermost = paths.first()?;
+    Some(json!({
+        "pointer": innermost.pointer(),
+        "path": innermost.to_string(),
+        "pointers": paths.iter().map(|p| p.pointer()).collect::<Vec<_>>(),
+    }))
+}
+
+/// Where the object at `pointer` was declared.
+fn source_location(state: &WorldState, pointer: &str) -> Option<lsp_types::Location> {
+    let span = state.output_map.as_ref()?.span_of(pointer)?;
+    Some(world_state::span_to_location(span, &state.root_dir()?))
+}
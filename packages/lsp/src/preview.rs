@@ -0,0 +1,53 @@
+/// `urd/previewSymbol` — the compiled JSON for one symbol.
+///
+/// A custom request for preview panels. The client sends
+/// `{ "compiledId": "tavern/greet" }` and gets back
+/// `{ "kind", "id", "json" }`, where `json` is the object the compiled world
+/// holds under that ID, or `null` when nothing has it. An ID with a `/` is
+/// a dialogue section; otherwise locations are tried before entities.
+///
+/// Slices the world JSON kept from the last successful compile, so the
+/// preview lags behind a world that currently has errors.
+
+use lsp_server::Connection;
+use serde_json::{json, Value};
+
+use crate::world_state::WorldState;
+
+/// The custom request method.
+pub const METHOD: &str = "urd/previewSymbol";
+
+pub fn handle(connection: &Connection, state: &WorldState, req: lsp_server::Request) {
+    let response = match req.params["compiledId"].as_str() {
+        Some(compiled_id) => {
+            let preview = state
+                .world_json
+                .as_ref()
+                .and_then(|world| preview_symbol(world, compiled_id));
+            lsp_server::Response::new_ok(req.id, preview)
+        }
+        None => lsp_server::Response::new_err(
+            req.id,
+            lsp_server::ErrorCode::InvalidParams as i32,
+            "Expected a 'compiledId' string".to_string(),
+        ),
+    };
+    connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+        .ok();
+}
+
+/// The preview for `compiled_id` in `world`, if it names a section,
+/// location, or entity.
+pub fn preview_symbol(world: &Value, compiled_id: &str) -> Option<Value> {
+    let blocks: &[(&str, &str)] = if compiled_id.contains('/') {
+        &[("dialogue", "section")]
+    } else {
+        &[("locations", "location"), ("entities", "entity")]
+    };
+    blocks.iter().find_map(|(block, kind)| {
+        let object = world[*block].get(compiled_id)?;
+        Some(json!({ "kind": kind, "id": compiled_id, "json": object }))
+    })
+}
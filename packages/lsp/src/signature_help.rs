@@ -0,0 +1,276 @@
+/// Signature help for effect and condition lines.
+///
+/// While an author types `> @guard.trust = ` or `? @guard.mood == `, shows
+/// the full form of the line with the slot under the cursor highlighted:
+/// the property, then the value shape it accepts (enum values, numeric
+/// range, boolean). Before a property is chosen, there is one signature
+/// per matching property. `> move @entity -> ` lists valid destinations.
+///
+/// Works on the line prefix up to the cursor and the compiled world JSON,
+/// like completion. Anything that does not resolve returns no signature.
+
+use lsp_server::Connection;
+use lsp_types::*;
+use serde_json::Value;
+
+use crate::world_state::{self, WorldState};
+
+/// Operators an effect may use on a property, longest first.
+const EFFECT_OPERATORS: &[&str] = &["=", "+", "-"];
+
+/// Comparison operators a condition may use, longest first.
+const CONDITION_OPERATORS: &[&str] = &["==", "!=", ">=", "<=", ">", "<"];
+
+pub fn handle(connection: &Connection, state: &WorldState, req: lsp_server::Request) {
+    let params: SignatureHelpParams = serde_json::from_value(req.params.clone()).unwrap();
+
+    let help = build_signature_help(state, &params);
+
+    let response = lsp_server::Response::new_ok(req.id, help);
+    connection
+        .sender
+        .send(lsp_server::Message::Response(response))
+        .ok();
+}
+
+fn build_signature_help(state: &WorldState, params: &SignatureHelpParams) -> Option<SignatureHelp> {
+    let uri = &params.text_document_position_params.text_document.uri;
+    let position = &params.text_document_position_params.position;
+
+    let path = world_state::uri_to_path(uri);
+    let source = std::fs::read_to_string(&path).ok()?;
+    let line = source.lines().nth(position.line as usize)?;
+    let world = state.world_json.as_ref()?;
+
+    signature_for_prefix(world, line_prefix(line, position.character))
+}
+
+/// The part of `line` before the cursor. LSP columns count UTF-16 code
+/// units, so the cut is made on a character boundary.
+fn line_prefix(line: &str, character: u32) -> &str {
+    let mut units = 0;
+    for (i, c) in line.char_indices() {
+        if units >= character as usize {
+            return &line[..i];
+        }
+        units += c.len_utf16();
+    }
+    line
+}
+
+/// Signature help for a line typed up to `prefix`.
+pub fn signature_for_prefix(world: &Value, prefix: &str) -> Option<SignatureHelp> {
+    let text = prefix.trim_start();
+    let (operators, rest) = if let Some(rest) = text.strip_prefix('>') {
+        (EFFECT_OPERATORS, rest.trim_start())
+    } else if let Some(rest) = text.strip_prefix('?') {
+        (CONDITION_OPERATORS, rest.trim_start())
+    } else {
+        return None;
+    };
+
+    if operators == EFFECT_OPERATORS {
+        if let Some(rest) = rest.strip_prefix("move ") {
+            return move_signature(world, rest.trim_start());
+        }
+    }
+
+    let rest = rest.strip_prefix('@')?;
+    let id_end = identifier_end(rest, false);
+    let entity_id = &rest[..id_end];
+    let type_name = world["entities"][entity_id]["type"].as_str()?;
+    let properties = world["types"][type_name]["properties"].as_object()?;
+
+    let after_dot = rest[id_end..].strip_prefix('.')?;
+    let prop_end = identifier_end(after_dot, true);
+    let (typed, tail) = after_dot.split_at(prop_end);
+
+    // Still typing the property: offer every property it could become.
+    if tail.is_empty() {
+        let signatures: Vec<SignatureInformation> = properties
+            .iter()
+            .filter(|(name, _)| name.starts_with(typed))
+            .map(|(name, prop)| property_signature(entity_id, name, prop, operators[0]))
+            .collect();
+        if signatures.is_empty() {
+            return None;
+        }
+        return Some(SignatureHelp {
+            signatures,
+            active_signature: Some(0),
+            active_parameter: Some(0),
+        });
+    }
+
+    let prop = properties.get(typed)?;
+    let tail = tail.trim_start();
+    let operator = operators.iter().find(|op| tail.starts_with(*op));
+    let active_parameter = match operator {
+        Some(_) => 1,
+        None if tail.is_empty() => 0,
+        None => return None,
+    };
+    let operator = operator.copied().unwrap_or(operators[0]);
+
+    Some(SignatureHelp {
+        signatures: vec![property_signature(entity_id, typed, prop, operator)],
+        active_signature: Some(0),
+        active_parameter: Some(active_parameter),
+    })
+}
+
+/// `@entity.property <op> <value shape>`.
+fn property_signature(entity_id: &str, name: &str, prop: &Value, operator: &str) -> SignatureInformation {
+    let target = format!("@{}.{}", entity_id, name);
+    // `+` and `-` take an amount, which the property's range does not bound.
+    let shape = if operator == "+" || operator == "-" {
+        format!("<{} amount>", prop["type"].as_str().unwrap_or("number"))
+    } else {
+        value_shape(prop)
+    };
+
+    let mut doc = Vec::new();
+    if let Some(description) = prop["description"].as_str() {
+        doc.push(description.to_string());
+    }
+    if !prop["default"].is_null() {
+        doc.push(format!("Default: `{}`", prop["default"]));
+    }
+
+    SignatureInformation {
+        label: format!("{} {} {}", target, operator, shape),
+        documentation: (!doc.is_empty()).then(|| markdown(doc.join("\n\n"))),
+        parameters: Some(vec![
+            ParameterInformation {
+                label: ParameterLabel::Simple(target),
+                documentation: Some(Documentation::String(
+                    prop["type"].as_str().unwrap_or("").to_string(),
+                )),
+            },
+            ParameterInformation {
+                label: ParameterLabel::Simple(shape),
+                documentation: None,
+            },
+        ]),
+        active_parameter: None,
+    }
+}
+
+/// The values a property accepts, written as a placeholder:
+/// `<wary | friendly>`, `<integer 0..100>`, `<true | false>`.
+fn value_shape(prop: &Value) -> String {
+    let type_name = prop["type"].as_str().unwrap_or("value");
+    if let Some(values) = prop["values"].as_array() {
+        let values: Vec<&str> = values.iter().filter_map(Value::as_str).collect();
+        return format!("<{}>", values.join(" | "));
+    }
+    match type_name {
+        "boolean" | "bool" => "<true | false>".to_string(),
+        "integer" | "number" => match (bound(&prop["min"]), bound(&prop["max"])) {
+            (None, None) => format!("<{}>", type_name),
+            (Some(min), None) => format!("<{} {}..>", type_name, min),
+            (None, Some(max)) => format!("<{} ..{}>", type_name, max),
+            (Some(min), Some(max)) => format!("<{} {}..{}>", type_name, min, max),
+        },
+        "ref" => match prop["ref_type"].as_str() {
+            Some(ref_type) => format!("<@{}>", ref_type),
+            None => "<@entity>".to_string(),
+        },
+        other => format!("<{}>", other),
+    }
+}
+
+/// A range bound as written: the JSON stores bounds as floats, so whole
+/// numbers drop the fraction.
+fn bound(value: &Value) -> Option<String> {
+    let n = value.as_f64()?;
+    Some(if n.fract() == 0.0 { format!("{}", n as i64) } else { n.to_string() })
+}
+
+/// `move @entity -> <destination>`, listing the destinations.
+fn move_signature(world: &Value, rest: &str) -> Option<SignatureHelp> {
+    let (entity_part, active_parameter) = match rest.find("->") {
+        Some(arrow) => (rest[..arrow].trim_end(), 1),
+        None => (rest, 0),
+    };
+
+    let entity = match entity_part.strip_prefix('@') {
+        Some(id) if !id.is_empty() && active_parameter == 1 => {
+            // A named entity must exist; one still being typed need not.
+            world["entities"].get(id)?;
+            format!("@{}", id)
+        }
+        None if !entity_part.is_empty() => return None,
+        _ => "@entity".to_string(),
+    };
+
+    let mut locations: Vec<&String> = world["locations"]
+        .as_object()
+        .map(|l| l.keys().collect())
+        .unwrap_or_default();
+    locations.sort();
+    let mut containers: Vec<String> = world["entities"]
+        .as_object()
+        .map(|entities| {
+            entities
+                .iter()
+                .filter(|(_, e)| {
+                    let type_name = e["type"].as_str().unwrap_or("");
+                    world["types"][type_name]["traits"]
+                        .as_array()
+                        .is_some_and(|t| t.iter().any(|t| t == "container"))
+                })
+                .map(|(id, _)| format!("`@{}`", id))
+                .collect()
+        })
+        .unwrap_or_default();
+    containers.sort();
+
+    let mut doc = vec!["`player` — the player's inventory".to_string(), "`here` — the player's current location".to_string()];
+    if !containers.is_empty() {
+        doc.push(format!("Containers: {}", containers.join(", ")));
+    }
+    if !locations.is_empty() {
+        let locations: Vec<String> = locations.iter().map(|l| format!("`{}`", l)).collect();
+        doc.push(format!("Locations: {}", locations.join(", ")));
+    }
+
+    let destination = "<destination>".to_string();
+    let signature = SignatureInformation {
+        label: format!("move {} -> {}", entity, destination),
+        documentation: None,
+        parameters: Some(vec![
+            ParameterInformation {
+                label: ParameterLabel::Simple(entity),
+                documentation: Some(Documentation::String(
+                    "The entity to move. Its type needs the `portable` trait.".to_string(),
+                )),
+            },
+            ParameterInformation {
+                label: ParameterLabel::Simple(destination),
+                documentation: Some(markdown(doc.join("\n\n"))),
+            },
+        ]),
+        active_parameter: None,
+    };
+
+    Some(SignatureHelp {
+        signatures: vec![signature],
+        active_signature: Some(0),
+        active_parameter: Some(active_parameter),
+    })
+}
+
+/// Byte length of the identifier at the start of `text`. Property names
+/// may contain dots (`appearance.height`).
+fn identifier_end(text: &str, allow_dots: bool) -> usize {
+    text.find(|c: char| !(c.is_alphanumeric() || c == '_' || (allow_dots && c == '.')))
+        .unwrap_or(text.len())
+}
+
+fn markdown(value: String) -> Documentation {
+    Documentation::MarkupContent(MarkupContent {
+        kind: MarkupKind::Markdown,
+        value,
+    })
+}
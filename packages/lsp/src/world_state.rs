@@ -1,7 +1,7 @@
 /// Compilation state management for the LSP server.
 ///
-/// Tracks the entry file, latest compilation result, and stale-retained
-/// indices that survive failed recompilations.
+/// Tracks the entry file, workspace folders, latest compilation result, and
+/// stale-retained indices that survive failed recompilations.
 
 use std::collections::HashSet;
 use std::path::{Path, PathBuf};
@@ -9,29 +9,55 @@ use std::path::{Path, PathBuf};
 use lsp_types::Uri;
 use urd_compiler::definition_index::DefinitionIndex;
 use urd_compiler::facts::FactSet;
-use urd_compiler::CompilationResult;
+use urd_compiler::output_map::OutputMap;
+use urd_compiler::property_groups;
+use urd_compiler::{CompilationResult, CompileOptions, LinkCache};
+
+use crate::inlay_hints::InlayHintSettings;
 
 pub struct WorldState {
     /// Path to the entry file (first .urd.md opened).
     pub entry_path: Option<PathBuf>,
+    /// Workspace folders sent at initialize. The first one containing the
+    /// entry file becomes the compiler's project root.
+    pub workspace_roots: Vec<PathBuf>,
     /// Latest compilation result — always updated for diagnostics.
     pub result: Option<CompilationResult>,
     /// Latest DefinitionIndex — stale-retained when LINK fails.
     pub definition_index: Option<DefinitionIndex>,
     /// Parsed world JSON — stale-retained when EMIT fails.
     pub world_json: Option<serde_json::Value>,
-    /// Files in the compilation unit (entry + imports).
+    /// Output map of `world_json` — stale-retained with it.
+    pub output_map: Option<OutputMap>,
+    /// Files that have had diagnostics pushed, so a later push can clear
+    /// the ones that no longer have any.
     pub tracked_files: HashSet<PathBuf>,
+    /// Inlay hint categories, from `initializationOptions`.
+    pub inlay_hints: InlayHintSettings,
+    /// LINK collections reused across recompiles for unchanged files.
+    pub link_cache: LinkCache,
+    /// Bumped on every recompile. Pulled diagnostics carry it as their
+    /// result ID, so a pull with the current ID is answered "unchanged".
+    pub generation: u64,
+    /// Whether recompiles push `publishDiagnostics`, from
+    /// `initializationOptions`. Clients that pull can turn it off.
+    pub push_diagnostics: bool,
 }
 
 impl WorldState {
     pub fn new() -> Self {
         Self {
             entry_path: None,
+            workspace_roots: Vec::new(),
             result: None,
             definition_index: None,
             world_json: None,
+            output_map: None,
             tracked_files: HashSet::new(),
+            inlay_hints: InlayHintSettings::default(),
+            link_cache: LinkCache::new(),
+            generation: 0,
+            push_diagnostics: true,
         }
     }
 
@@ -39,7 +65,7 @@ impl WorldState {
     ///
     /// - `result` is always replaced (for diagnostics).
     /// - `definition_index` is only replaced when LINK succeeds.
-    /// - `world_json` is only replaced when EMIT succeeds.
+    /// - `world_json` and `output_map` are only replaced when EMIT succeeds.
     /// - `property_index` and `fact_set` are accessed via `result` directly.
     pub fn recompile(&mut self) {
         let entry = match &self.entry_path {
@@ -47,7 +73,15 @@ impl WorldState {
             None => return,
         };
 
-        let result = urd_compiler::compile(&entry);
+        let mut options = CompileOptions {
+            project_root: self
+                .project_root()
+                .map(|p| p.to_string_lossy().to_string()),
+            link_cache: Some(&self.link_cache),
+            ..CompileOptions::default()
+        };
+        options.emit.build_output_map = true;
+        let mut result = urd_compiler::compile_with_options(&entry, &options);
 
         // Update stale-retained definition_index only when new data is available
         if let Some(ref idx) = result.definition_index {
@@ -56,13 +90,23 @@ impl WorldState {
 
         // Update world_json only when EMIT succeeds
         if let Some(ref world_str) = result.world {
-            if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(world_str) {
+            if let Ok(mut parsed) = serde_json::from_str::<serde_json::Value>(world_str) {
+                // Hover and completion look properties up by dotted name.
+                property_groups::flatten_world(&mut parsed);
                 self.world_json = Some(parsed);
+                self.output_map = result.output_map.take();
+            }
+        }
+
+        if let Some(root) = self.root_dir() {
+            for d in result.diagnostics.all() {
+                self.tracked_files.insert(span_file_to_path(&d.span.file, &root));
             }
         }
 
         // Always replace result (for diagnostics; also gives access to fact_set, property_index)
         self.result = Some(result);
+        self.generation += 1;
     }
 
     /// Access the FactSet from the latest compilation result.
@@ -79,52 +123,155 @@ impl WorldState {
             .and_then(|r| r.property_index.as_ref())
     }
 
-    /// The entry file's parent directory, for resolving relative span paths.
+    /// The entry file's parent directory.
     pub fn entry_dir(&self) -> Option<PathBuf> {
         self.entry_path.as_ref().and_then(|p| p.parent()).map(|p| p.to_path_buf())
     }
+
+    /// The workspace folder that contains the entry file, if any.
+    ///
+    /// An entry file opened from outside every workspace folder falls back
+    /// to the compiler's default root (its own directory) rather than
+    /// failing with URD208.
+    pub fn project_root(&self) -> Option<PathBuf> {
+        let entry = self.entry_path.as_ref()?;
+        self.workspace_roots
+            .iter()
+            .find(|root| entry.starts_with(root))
+            .cloned()
+    }
+
+    /// The directory compiler span paths are relative to: the project root
+    /// when one applies, otherwise the entry file's directory.
+    pub fn root_dir(&self) -> Option<PathBuf> {
+        self.project_root().or_else(|| self.entry_dir())
+    }
+
+    /// The compiled ID of the location whose heading is the last one in
+    /// `file` at or above `line` (1-based), as LINK assigns content to
+    /// locations.
+    pub fn enclosing_location(&self, file: &str, line: u32) -> Option<&str> {
+        let (key, _) = self
+            .definition_index
+            .as_ref()?
+            .iter()
+            .filter(|(key, entry)| key.starts_with("location:") && entry.span.file == file && entry.span.start_line <= line)
+            .max_by_key(|(_, entry)| entry.span.start_line)?;
+        key.strip_prefix("location:")
+    }
 }
 
 // ── URI / path conversion ──
+//
+// All path ↔ URI conversion goes through this section. Paths handed to the
+// compiler (and compared against its spans) use forward slashes, with an
+// upper-case drive letter (`C:/Users/...`) and UNC shares as `//server/share`.
+// URIs we send back use the form VS Code itself produces: percent-encoded,
+// lower-case drive letter with an encoded colon (`file:///c%3A/Users/...`),
+// and UNC shares as the authority (`file://server/share/...`).
+//
+// The string functions are pure and platform-independent so they can be
+// tested everywhere; the `Path`/`Uri` wrappers are thin.
 
-/// Convert an LSP URI to a filesystem path.
+/// Convert an LSP URI to a compiler-facing filesystem path.
 pub fn uri_to_path(uri: &Uri) -> PathBuf {
-    let s = uri.as_str();
-    // Strip file:/// prefix and decode
-    if let Some(path_str) = s.strip_prefix("file:///") {
-        // On Windows, file:///C:/path → C:/path
-        // On Unix, file:///path → /path
-        let decoded = percent_decode(path_str);
-        if cfg!(windows) {
-            PathBuf::from(decoded)
-        } else {
-            PathBuf::from(format!("/{}", decoded))
-        }
-    } else if let Some(path_str) = s.strip_prefix("file://") {
-        PathBuf::from(percent_decode(path_str))
-    } else {
-        PathBuf::from(s)
-    }
+    PathBuf::from(uri_str_to_path(uri.as_str()))
 }
 
 /// Convert a filesystem path to an LSP URI.
 pub fn path_to_uri(path: &Path) -> Uri {
-    let normalised = path.to_string_lossy().replace('\\', "/");
-    let uri_str = if normalised.starts_with('/') {
-        format!("file://{}", normalised)
-    } else {
-        format!("file:///{}", normalised)
+    path_str_to_uri(&path.to_string_lossy())
+        .parse::<Uri>()
+        .expect("percent-encoded file URI is always valid")
+}
+
+/// Convert a URI string to a normalised, forward-slash path string.
+///
+/// Non-`file:` strings are treated as paths and only normalised.
+pub fn uri_str_to_path(uri: &str) -> String {
+    let rest = match strip_file_scheme(uri) {
+        Some(rest) => rest,
+        None => return normalise_path(uri),
     };
-    uri_str.parse::<Uri>().unwrap()
+
+    // `file:///path` has an empty authority; `file://server/share` is UNC.
+    let (authority, path) = match rest.strip_prefix("//") {
+        Some(after) => match after.find('/') {
+            Some(i) => (&after[..i], &after[i..]),
+            None => (after, ""),
+        },
+        None => ("", rest),
+    };
+    let path = percent_decode(path);
+
+    if authority.is_empty() || authority.eq_ignore_ascii_case("localhost") {
+        // `/c:/Users` → `c:/Users`; a bare Unix path keeps its leading slash.
+        let path = match path.strip_prefix('/') {
+            Some(after) if has_drive_letter(after) => after.to_string(),
+            _ => path,
+        };
+        normalise_path(&path)
+    } else {
+        normalise_path(&format!("//{}{}", percent_decode(authority), path))
+    }
 }
 
-/// Resolve a compiler span file path to an absolute path, then to a URI.
+/// Convert a path string (either separator style) to a file URI string.
+pub fn path_str_to_uri(path: &str) -> String {
+    let normalised = normalise_path(path);
+
+    if let Some(unc) = normalised.strip_prefix("//") {
+        let (server, rest) = match unc.find('/') {
+            Some(i) => (&unc[..i], &unc[i..]),
+            None => (unc, ""),
+        };
+        return format!("file://{}{}", percent_encode(server), percent_encode(rest));
+    }
+
+    if has_drive_letter(&normalised) {
+        let drive = normalised[..1].to_ascii_lowercase();
+        return format!("file:///{}%3A{}", drive, percent_encode(&normalised[2..]));
+    }
+
+    match normalised.strip_prefix('/') {
+        Some(rest) => format!("file:///{}", percent_encode(rest)),
+        None => format!("file:///{}", percent_encode(&normalised)),
+    }
+}
+
+/// Normalise a path string for the compiler: `\` → `/`, upper-case drive
+/// letter, and UNC prefixes (`\\server`) kept as a leading `//`.
+pub fn normalise_path(path: &str) -> String {
+    let mut normalised = path.replace('\\', "/");
+    if has_drive_letter(&normalised) {
+        normalised[..1].make_ascii_uppercase();
+    }
+    normalised
+}
+
+/// Resolve a compiler span file path to an absolute path.
 ///
-/// Compiler spans use forward-slash relative paths (e.g. `locked-garden.urd.md`).
-/// We join with the entry file's parent directory to get an absolute path.
-pub fn span_file_to_uri(span_file: &str, entry_dir: &Path) -> Uri {
-    let absolute = entry_dir.join(span_file);
-    path_to_uri(&absolute)
+/// Compiler spans use forward-slash paths relative to the project root
+/// (e.g. `locked-garden.urd.md`). We join with the root directory using
+/// `/` so Windows roots don't end up with mixed separators.
+pub fn span_file_to_path(span_file: &str, root_dir: &Path) -> PathBuf {
+    let root = normalise_path(&root_dir.to_string_lossy());
+    PathBuf::from(format!("{}/{}", root.trim_end_matches('/'), span_file))
+}
+
+/// Resolve a compiler span file path to a URI.
+pub fn span_file_to_uri(span_file: &str, root_dir: &Path) -> Uri {
+    path_to_uri(&span_file_to_path(span_file, root_dir))
+}
+
+/// The inverse of [`span_file_to_uri`]: the compiler span path for a file,
+/// or `None` when the file is not under the root directory.
+pub fn span_file_for_path(path: &Path, root_dir: &Path) -> Option<String> {
+    let root = normalise_path(&root_dir.to_string_lossy());
+    let path = normalise_path(&path.to_string_lossy());
+    path.strip_prefix(root.trim_end_matches('/'))?
+        .strip_prefix('/')
+        .map(|rest| rest.to_string())
 }
 
 /// Convert a compiler Span (1-indexed) to an LSP Range (0-indexed).
@@ -144,30 +291,165 @@ pub fn span_to_range(span: &urd_compiler::span::Span) -> lsp_types::Range {
 /// Convert a compiler Span to an LSP Location (with URI).
 pub fn span_to_location(
     span: &urd_compiler::span::Span,
-    entry_dir: &Path,
+    root_dir: &Path,
 ) -> lsp_types::Location {
     lsp_types::Location {
-        uri: span_file_to_uri(&span.file, entry_dir),
+        uri: span_file_to_uri(&span.file, root_dir),
         range: span_to_range(span),
     }
 }
 
-/// Simple percent-decoding for URI path segments.
+fn strip_file_scheme(uri: &str) -> Option<&str> {
+    let scheme = uri.get(..5)?;
+    if scheme.eq_ignore_ascii_case("file:") {
+        Some(&uri[5..])
+    } else {
+        None
+    }
+}
+
+/// True when `path` starts with a drive letter, e.g. `C:` or `c:/`.
+fn has_drive_letter(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    bytes.len() >= 2
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && (bytes.len() == 2 || bytes[2] == b'/')
+}
+
+/// Percent-decode a URI component. Decodes to bytes first so multi-byte
+/// UTF-8 sequences (`%C3%A9`) come back as one character.
 fn percent_decode(s: &str) -> String {
-    let mut result = String::with_capacity(s.len());
-    let mut chars = s.chars();
-    while let Some(c) = chars.next() {
-        if c == '%' {
-            let hex: String = chars.by_ref().take(2).collect();
-            if let Ok(byte) = u8::from_str_radix(&hex, 16) {
-                result.push(byte as char);
-            } else {
-                result.push('%');
-                result.push_str(&hex);
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(byte) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Percent-encode a path for a URI, keeping unreserved characters and `/`.
+fn percent_encode(s: &str) -> String {
+    let mut encoded = String::with_capacity(s.len());
+    for byte in s.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                encoded.push(byte as char)
             }
-        } else {
-            result.push(c);
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
         }
     }
-    result
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unix_uri_round_trip() {
+        assert_eq!(uri_str_to_path("file:///home/ana/world.urd.md"), "/home/ana/world.urd.md");
+        assert_eq!(
+            path_str_to_uri("/home/ana/world.urd.md"),
+            "file:///home/ana/world.urd.md"
+        );
+    }
+
+    #[test]
+    fn windows_uri_encoded_colon() {
+        assert_eq!(
+            uri_str_to_path("file:///c%3A/Users/ana/world.urd.md"),
+            "C:/Users/ana/world.urd.md"
+        );
+        assert_eq!(
+            uri_str_to_path("file:///C:/Users/ana/world.urd.md"),
+            "C:/Users/ana/world.urd.md"
+        );
+    }
+
+    #[test]
+    fn windows_path_to_uri_matches_client_form() {
+        let expected = "file:///c%3A/Users/ana/world.urd.md";
+        assert_eq!(path_str_to_uri("C:\\Users\\ana\\world.urd.md"), expected);
+        assert_eq!(path_str_to_uri("c:/Users/ana/world.urd.md"), expected);
+        assert_eq!(path_str_to_uri(&uri_str_to_path(expected)), expected);
+    }
+
+    #[test]
+    fn drive_letter_casing_is_normalised() {
+        assert_eq!(normalise_path("c:\\Users\\ana"), "C:/Users/ana");
+        assert_eq!(normalise_path("C:/Users/ana"), "C:/Users/ana");
+        // Only a leading drive letter is touched.
+        assert_eq!(normalise_path("/srv/c:/x"), "/srv/c:/x");
+    }
+
+    #[test]
+    fn unc_uri_round_trip() {
+        assert_eq!(
+            uri_str_to_path("file://fileserver/share/worlds/main.urd.md"),
+            "//fileserver/share/worlds/main.urd.md"
+        );
+        assert_eq!(
+            path_str_to_uri("\\\\fileserver\\share\\worlds\\main.urd.md"),
+            "file://fileserver/share/worlds/main.urd.md"
+        );
+    }
+
+    #[test]
+    fn localhost_authority_is_local() {
+        assert_eq!(uri_str_to_path("file://localhost/home/ana/a.urd.md"), "/home/ana/a.urd.md");
+    }
+
+    #[test]
+    fn spaces_and_non_ascii_round_trip() {
+        let uri = "file:///c%3A/Users/Ana%20Mar%C3%ADa/Caf%C3%A9%20world/main.urd.md";
+        let path = uri_str_to_path(uri);
+        assert_eq!(path, "C:/Users/Ana María/Café world/main.urd.md");
+        assert_eq!(path_str_to_uri(&path), uri);
+
+        assert_eq!(
+            path_str_to_uri("/home/ana/my worlds/ø.urd.md"),
+            "file:///home/ana/my%20worlds/%C3%B8.urd.md"
+        );
+    }
+
+    #[test]
+    fn malformed_percent_escapes_are_kept() {
+        assert_eq!(uri_str_to_path("file:///tmp/100%zz"), "/tmp/100%zz");
+        assert_eq!(uri_str_to_path("file:///tmp/50%"), "/tmp/50%");
+    }
+
+    #[test]
+    fn span_file_joins_with_forward_slash() {
+        let uri = span_file_to_uri("rooms/hall.urd.md", Path::new("C:\\worlds\\demo\\"));
+        assert_eq!(uri.as_str(), "file:///c%3A/worlds/demo/rooms/hall.urd.md");
+    }
+
+    #[test]
+    fn span_file_for_path_is_root_relative() {
+        let root = Path::new("C:\\worlds\\demo\\");
+        assert_eq!(
+            span_file_for_path(Path::new("C:/worlds/demo/rooms/hall.urd.md"), root).as_deref(),
+            Some("rooms/hall.urd.md")
+        );
+        assert_eq!(span_file_for_path(Path::new("C:/worlds/demo2/a.urd.md"), root), None);
+        assert_eq!(span_file_for_path(Path::new("/elsewhere/a.urd.md"), root), None);
+    }
+
+    #[test]
+    fn path_to_uri_parses_with_spaces() {
+        let uri = path_to_uri(Path::new("/tmp/a b/c.urd.md"));
+        assert_eq!(uri.as_str(), "file:///tmp/a%20b/c.urd.md");
+        assert_eq!(uri_to_path(&uri), PathBuf::from("/tmp/a b/c.urd.md"));
+    }
 }
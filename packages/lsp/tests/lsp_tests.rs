@@ -45,7 +45,30 @@ fn initialize(client: &Connection) -> InitializeResult {
         capabilities: ClientCapabilities::default(),
         ..Default::default()
     };
+    initialize_with(client, init_params)
+}
+
+/// Initialize with a single workspace folder, given relative to `compiler/tests/`.
+fn initialize_with_workspace(client: &Connection, dir: &str) -> InitializeResult {
+    let base = env!("CARGO_MANIFEST_DIR").replace('\\', "/");
+    let path = format!("{}/../compiler/tests/{}", base, dir);
+    let uri_str = if path.starts_with('/') {
+        format!("file://{}", path)
+    } else {
+        format!("file:///{}", path)
+    };
+    let init_params = InitializeParams {
+        capabilities: ClientCapabilities::default(),
+        workspace_folders: Some(vec![WorkspaceFolder {
+            uri: uri_str.parse::<Uri>().unwrap(),
+            name: dir.to_string(),
+        }]),
+        ..Default::default()
+    };
+    initialize_with(client, init_params)
+}
 
+fn initialize_with(client: &Connection, init_params: InitializeParams) -> InitializeResult {
     // Send initialize request
     let req = Request {
         id: 1.into(),
@@ -222,6 +245,118 @@ fn send_completion(client: &Connection, fixture: &str, line: u32, character: u32
     }
 }
 
+/// Send a textDocument/signatureHelp request and return the response.
+fn send_signature_help(client: &Connection, fixture: &str, line: u32, character: u32) -> Response {
+    let uri = fixture_uri(fixture);
+    let params = SignatureHelpParams {
+        context: None,
+        text_document_position_params: TextDocumentPositionParams {
+            text_document: TextDocumentIdentifier { uri },
+            position: Position { line, character },
+        },
+        work_done_progress_params: Default::default(),
+    };
+    let req = Request {
+        id: 32.into(),
+        method: "textDocument/signatureHelp".to_string(),
+        params: serde_json::to_value(params).unwrap(),
+    };
+    client.sender.send(Message::Request(req)).unwrap();
+
+    match client.receiver.recv_timeout(Duration::from_secs(5)).unwrap() {
+        Message::Response(r) => r,
+        other => panic!("Expected Response, got {:?}", other),
+    }
+}
+
+/// Send a signature help request and parse the result, `None` for null.
+fn signature_help(client: &Connection, fixture: &str, line: u32, character: u32) -> Option<SignatureHelp> {
+    let resp = send_signature_help(client, fixture, line, character);
+    serde_json::from_value(resp.result.expect("signature help response has no result")).unwrap()
+}
+
+/// Send a textDocument/inlayHint request for a line range and return the hints.
+fn send_inlay_hints(client: &Connection, fixture: &str, start_line: u32, end_line: u32) -> Vec<InlayHint> {
+    let uri = fixture_uri(fixture);
+    let params = InlayHintParams {
+        text_document: TextDocumentIdentifier { uri },
+        range: Range {
+            start: Position { line: start_line, character: 0 },
+            end: Position { line: end_line, character: 0 },
+        },
+        work_done_progress_params: Default::default(),
+    };
+    let req = Request {
+        id: 40.into(),
+        method: "textDocument/inlayHint".to_string(),
+        params: serde_json::to_value(params).unwrap(),
+    };
+    client.sender.send(Message::Request(req)).unwrap();
+
+    match client.receiver.recv_timeout(Duration::from_secs(5)).unwrap() {
+        Message::Response(r) => serde_json::from_value(r.result.unwrap()).unwrap(),
+        other => panic!("Expected Response, got {:?}", other),
+    }
+}
+
+/// (line, label) pairs for comparing hint sets.
+fn hint_labels(hints: &[InlayHint]) -> Vec<(u32, String)> {
+    hints
+        .iter()
+        .map(|h| match &h.label {
+            InlayHintLabel::String(s) => (h.position.line, s.clone()),
+            InlayHintLabel::LabelParts(_) => panic!("Expected a plain label"),
+        })
+        .collect()
+}
+
+/// Send a request and return its response, collecting any notifications
+/// that arrive first.
+fn send_request(client: &Connection, id: i32, method: &str, params: serde_json::Value) -> (Response, Vec<Notification>) {
+    let req = Request { id: id.into(), method: method.to_string(), params };
+    client.sender.send(Message::Request(req)).unwrap();
+
+    let mut notifications = Vec::new();
+    loop {
+        match client.receiver.recv_timeout(Duration::from_secs(5)).unwrap() {
+            Message::Response(r) => return (r, notifications),
+            Message::Notification(n) => notifications.push(n),
+            other => panic!("Expected Response, got {:?}", other),
+        }
+    }
+}
+
+/// Send a textDocument/codeLens request and return the unresolved lenses.
+fn send_code_lens(client: &Connection, fixture: &str) -> Vec<CodeLens> {
+    let params = CodeLensParams {
+        text_document: TextDocumentIdentifier { uri: fixture_uri(fixture) },
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+    let (resp, _) = send_request(client, 50, "textDocument/codeLens", serde_json::to_value(params).unwrap());
+    serde_json::from_value(resp.result.unwrap()).unwrap()
+}
+
+/// Send a codeLens/resolve request and return the resolved lens.
+fn resolve_code_lens(client: &Connection, lens: &CodeLens) -> CodeLens {
+    let (resp, _) = send_request(client, 51, "codeLens/resolve", serde_json::to_value(lens).unwrap());
+    serde_json::from_value(resp.result.unwrap()).unwrap()
+}
+
+/// The resolved lens on a 0-indexed line: (title, reference locations).
+fn lens_on_line(client: &Connection, fixture: &str, line: u32) -> (String, Vec<Location>) {
+    let lenses = send_code_lens(client, fixture);
+    let lens = lenses
+        .iter()
+        .find(|l| l.range.start.line == line && l.command.is_none())
+        .unwrap_or_else(|| panic!("No reference lens on line {}", line));
+    let command = resolve_code_lens(client, lens).command.expect("resolved command");
+    assert_eq!(command.command, "editor.action.showReferences");
+    let arguments = command.arguments.unwrap();
+    let locations: Vec<Location> = serde_json::from_value(arguments[2].clone()).unwrap();
+    (command.title, locations)
+}
+
 // ── Tests ──
 
 #[test]
@@ -233,6 +368,23 @@ fn lsp_initialize() {
     assert!(result.capabilities.definition_provider.is_some());
     assert!(result.capabilities.hover_provider.is_some());
     assert!(result.capabilities.completion_provider.is_some());
+    assert!(result.capabilities.inlay_hint_provider.is_some());
+    assert!(result.capabilities.signature_help_provider.is_some());
+    assert_eq!(result.capabilities.code_lens_provider.unwrap().resolve_provider, Some(true));
+    match result.capabilities.diagnostic_provider {
+        Some(DiagnosticServerCapabilities::Options(options)) => {
+            assert!(options.inter_file_dependencies);
+            assert!(options.workspace_diagnostics);
+        }
+        other => panic!("Expected diagnostic options, got {:?}", other),
+    }
+    assert_eq!(
+        result.capabilities.execute_command_provider.unwrap().commands,
+        vec!["urd.compileWorld".to_string()]
+    );
+    let info = result.server_info.expect("serverInfo");
+    assert_eq!(info.name, "urd-lsp");
+    assert!(info.version.unwrap().ends_with(&format!("({})", urd_compiler::version::generator())));
 
     shutdown(&client);
     thread.join().unwrap();
@@ -310,6 +462,51 @@ fn lsp_diagnostics_error_world() {
     thread.join().unwrap();
 }
 
+#[test]
+fn lsp_diagnostics_link_to_reference() {
+    let (client, thread) = setup();
+    initialize(&client);
+
+    send_did_open(&client, "negative-unreachable-location.urd.md");
+    let diags = recv_diagnostics(&client);
+
+    let unreachable = diags
+        .diagnostics
+        .iter()
+        .find(|d| d.code == Some(NumberOrString::String("URD430".to_string())))
+        .expect("URD430 diagnostic");
+    let href = &unreachable.code_description.as_ref().expect("code description").href;
+    assert_eq!(href.as_str(), "https://urd.dev/documents/diagnostic-codes#urd430");
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_hover_on_diagnostic_shows_title() {
+    let (client, thread) = setup();
+    initialize(&client);
+
+    send_did_open(&client, "negative-unreachable-location.urd.md");
+    let diags = recv_diagnostics(&client);
+    let unreachable = diags
+        .diagnostics
+        .iter()
+        .find(|d| d.code == Some(NumberOrString::String("URD430".to_string())))
+        .expect("URD430 diagnostic");
+    let start = unreachable.range.start;
+
+    let markup = hover_markup(&client, "negative-unreachable-location.urd.md", start.line, start.character);
+    assert!(
+        markup.contains("**URD430**: Unreachable location ([reference](https://urd.dev/documents/diagnostic-codes#urd430))"),
+        "Hover should carry the diagnostic title, got: {}",
+        markup
+    );
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
 #[test]
 fn lsp_goto_entity() {
     let (client, thread) = setup();
@@ -359,6 +556,25 @@ fn lsp_goto_section() {
     thread.join().unwrap();
 }
 
+#[test]
+fn lsp_goto_qualified_section() {
+    let (client, thread) = setup();
+    initialize(&client);
+
+    send_did_open(&client, "qualified-sections/main.urd.md");
+
+    // Line 14: "  ? tavern/topics.exhausted"; line 15: "  -> tavern/topics"
+    for (line, character) in [(14, 8), (15, 8)] {
+        let resp = send_definition(&client, "qualified-sections/main.urd.md", line, character);
+        let location: Location = serde_json::from_value(resp.result.expect("definition result")).unwrap();
+        assert!(location.uri.as_str().ends_with("qualified-sections/tavern.urd.md"), "{:?}", location.uri);
+        assert_eq!(location.range.start.line, 2);
+    }
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
 #[test]
 fn lsp_goto_not_found() {
     let (client, thread) = setup();
@@ -483,6 +699,152 @@ fn lsp_hover_section() {
     thread.join().unwrap();
 }
 
+/// Hover markup at a position, or panic if there is none.
+fn hover_markup(client: &Connection, fixture: &str, line: u32, character: u32) -> String {
+    let resp = send_hover(client, fixture, line, character);
+    let hover: Hover = serde_json::from_value(resp.result.expect("hover result")).unwrap();
+    match hover.contents {
+        HoverContents::Markup(markup) => markup.value,
+        other => panic!("Expected Markup hover, got {:?}", other),
+    }
+}
+
+#[test]
+fn lsp_hover_prepends_doc_comments() {
+    let (client, thread) = setup();
+    initialize(&client);
+
+    // The fixture compiles cleanly, so no diagnostics are published.
+    send_did_open(&client, "doc-comments.urd.md");
+    let _diags = recv_diagnostics(&client);
+
+    // Line 18: "[@keeper]"
+    let entity = hover_markup(&client, "doc-comments.urd.md", 18, 3);
+    assert!(
+        entity.starts_with("The old keeper. Knows where the lens went.\n\n**@keeper**: Keeper"),
+        "Entity doc should open the hover, got: {}",
+        entity
+    );
+
+    // Line 21: "== talk"
+    let section = hover_markup(&client, "doc-comments.urd.md", 21, 4);
+    assert!(
+        section.starts_with("Opening conversation with the keeper.\n\n**Section**: doc-comments/talk"),
+        "Section doc should open the hover, got: {}",
+        section
+    );
+
+    // Line 16: "# Lighthouse"
+    let location = hover_markup(&client, "doc-comments.urd.md", 16, 4);
+    assert!(
+        location.starts_with("The lighthouse gallery, high above the rocks.\nWind is constant here.\n\n**Location**"),
+        "Multi-line location doc should open the hover, got: {}",
+        location
+    );
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_hover_without_doc_comments_is_unchanged() {
+    let (client, thread) = setup();
+    initialize(&client);
+
+    send_did_open(&client, "locked-garden.urd.md");
+    let _diags = recv_diagnostics(&client);
+
+    // Line 28: "[@warden, @iron_key]"
+    let entity = hover_markup(&client, "locked-garden.urd.md", 28, 3);
+    assert!(entity.starts_with("**@warden**"), "got: {}", entity);
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_hover_choice_explains_availability() {
+    let (client, thread) = setup();
+    initialize(&client);
+
+    send_did_open(&client, "locked-garden.urd.md");
+    let _diags = recv_diagnostics(&client);
+
+    // Line 49: "* Ask about the garden"
+    let gated = hover_markup(&client, "locked-garden.urd.md", 49, 8);
+    assert!(gated.starts_with("**Choice**: locked-garden/greet/ask-about-the-garden"), "got: {}", gated);
+    assert!(gated.contains("Needs @warden.trust >= 3: it starts at 0, which does not"), "got: {}", gated);
+
+    // Line 88: "* Leave quietly"
+    let open = hover_markup(&client, "locked-garden.urd.md", 88, 4);
+    assert!(open.contains("It has no conditions"), "got: {}", open);
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_hover_exit_shows_destination_and_guard() {
+    let (client, thread) = setup();
+    initialize(&client);
+
+    send_did_open(&client, "locked-garden.urd.md");
+    let _diags = recv_diagnostics(&client);
+
+    // Line 30: "-> garden: The Walled Garden"
+    let exit = hover_markup(&client, "locked-garden.urd.md", 30, 20);
+    assert_eq!(
+        exit,
+        "**Exit**: garden → The Walled Garden\n\n\
+         Overgrown paths wind between crumbling statues.\n\n\
+         Condition: `garden_gate.locked == false`\n\n\
+         Blocked message: The gate is sealed with old iron."
+    );
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_hover_exit_without_destination_description() {
+    let (client, thread) = setup();
+    initialize(&client);
+
+    send_did_open(&client, "negative-unresolved-exit.urd.md");
+    let _diags = recv_diagnostics(&client);
+
+    // Line 10: "-> up: Kitchen"
+    let exit = hover_markup(&client, "negative-unresolved-exit.urd.md", 10, 3);
+    assert_eq!(exit, "**Exit**: up → Kitchen");
+
+    // Line 16: "  -> exit:up", in a section under the Cellar heading
+    let jump = hover_markup(&client, "negative-unresolved-exit.urd.md", 16, 8);
+    assert_eq!(jump, "**Exit**: up → Kitchen");
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_hover_unresolved_exit_shows_hint() {
+    let (client, thread) = setup();
+    initialize(&client);
+
+    send_did_open(&client, "negative-unresolved-exit.urd.md");
+    let _diags = recv_diagnostics(&client);
+
+    // Line 11: "-> down: Crypt", where Crypt's ID is vault
+    let exit = hover_markup(&client, "negative-unresolved-exit.urd.md", 11, 5);
+    assert!(
+        exit.starts_with("**Exit**: down → Crypt (unresolved)\n\nDid you mean 'vault'?\n\n---\n\n**URD312**"),
+        "got: {}",
+        exit
+    );
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
 #[test]
 fn lsp_autocomplete_section() {
     let (client, thread) = setup();
@@ -523,22 +885,699 @@ fn lsp_autocomplete_section() {
     thread.join().unwrap();
 }
 
+fn completion_labels(client: &Connection, fixture: &str, line: u32, character: u32) -> Vec<String> {
+    let resp = send_completion(client, fixture, line, character);
+    match serde_json::from_value(resp.result.expect("completion result")).unwrap() {
+        CompletionResponse::Array(items) => items.into_iter().map(|i| i.label).collect(),
+        _ => panic!("Expected Array completion response"),
+    }
+}
+
 #[test]
-fn lsp_latency_sunken_citadel() {
+fn lsp_autocomplete_qualified_sections() {
     let (client, thread) = setup();
     initialize(&client);
 
-    let start = std::time::Instant::now();
-    send_did_open(&client, "sunken-citadel.urd.md");
+    send_did_open(&client, "qualified-sections/main.urd.md");
+
+    // After "-> " and after "? ": the imported section by compiled ID.
+    for (line, character) in [(15, 5), (14, 4)] {
+        let labels = completion_labels(&client, "qualified-sections/main.urd.md", line, character);
+        assert!(labels.contains(&"tavern/topics".to_string()), "{:?}", labels);
+        assert!(!labels.contains(&"main/talk".to_string()), "{:?}", labels);
+    }
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+fn completion_items(client: &Connection, fixture: &str, line: u32, character: u32) -> Vec<CompletionItem> {
+    let resp = send_completion(client, fixture, line, character);
+    match serde_json::from_value(resp.result.expect("completion result")).unwrap() {
+        CompletionResponse::Array(items) => items,
+        _ => panic!("Expected Array completion response"),
+    }
+}
+
+#[test]
+fn lsp_autocomplete_jump_targets_in_scope() {
+    let (client, thread) = setup();
+    initialize(&client);
+
+    send_did_open(&client, "qualified-sections/main.urd.md");
+
+    // Line 15: "  -> tavern/topics" inside Hall. The tavern's own `topics`
+    // resolves only from the tavern file, so it is not offered bare.
+    assert_eq!(
+        completion_labels(&client, "qualified-sections/main.urd.md", 15, 5),
+        vec!["talk", "tavern/topics", "end", "exit:in"]
+    );
+    // Line 14: "  ? tavern/topics.exhausted" — sections only.
+    assert_eq!(
+        completion_labels(&client, "qualified-sections/main.urd.md", 14, 4),
+        vec!["talk", "tavern/topics"]
+    );
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_autocomplete_jump_target_details() {
+    let (client, thread) = setup();
+    initialize(&client);
+
+    send_did_open(&client, "locked-garden.urd.md");
     let _diags = recv_diagnostics(&client);
-    let elapsed = start.elapsed();
 
-    assert!(
-        elapsed < Duration::from_millis(200),
-        "Sunken Citadel recompile + diagnostic push took {:?}, expected < 200ms",
-        elapsed
+    // Line 41: "  -> greet" in the Gatehouse, which has one exit.
+    let items = completion_items(&client, "locked-garden.urd.md", 41, 5);
+    let greet = items.iter().find(|i| i.label == "greet").expect("greet offered");
+    assert_eq!(greet.detail.as_deref(), Some("locked-garden/greet"));
+    assert_eq!(
+        greet.documentation,
+        Some(Documentation::String("Nobody passes without reason.".to_string()))
+    );
+    let exit = items.iter().find(|i| i.label == "exit:garden").expect("exit offered");
+    assert_eq!(exit.detail.as_deref(), Some("the-walled-garden"));
+
+    // Line 26: a description paragraph is prose; nothing is offered.
+    assert!(completion_labels(&client, "locked-garden.urd.md", 26, 10).is_empty());
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_autocomplete_traits() {
+    let (client, thread) = setup();
+    initialize(&client);
+
+    // The fixture compiles cleanly, so no diagnostics are published.
+    send_did_open(&client, "doc-comments.urd.md");
+    let _diags = recv_diagnostics(&client);
+
+    // Line 7: "  Keeper [interactable]:" — cursor just after "["
+    assert_eq!(
+        completion_labels(&client, "doc-comments.urd.md", 7, 10),
+        vec!["container", "portable", "mobile", "interactable"]
     );
 
+    // After "interactable" the listed trait is left out.
+    assert_eq!(
+        completion_labels(&client, "doc-comments.urd.md", 7, 22),
+        vec!["container", "portable", "mobile"]
+    );
+
+    // Past "]" there is nothing to complete.
+    assert!(completion_labels(&client, "doc-comments.urd.md", 7, 24).is_empty());
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_hover_trait() {
+    let (client, thread) = setup();
+    initialize(&client);
+
+    send_did_open(&client, "doc-comments.urd.md");
+    let _diags = recv_diagnostics(&client);
+
+    // Line 7: "  Keeper [interactable]:"
+    let markup = hover_markup(&client, "doc-comments.urd.md", 7, 14);
+    assert!(
+        markup.starts_with("**interactable** (built-in trait)\n\n"),
+        "Trait hover should explain the built-in trait, got: {}",
+        markup
+    );
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_latency_sunken_citadel() {
+    let (client, thread) = setup();
+    initialize(&client);
+
+    let start = std::time::Instant::now();
+    send_did_open(&client, "sunken-citadel.urd.md");
+    let _diags = recv_diagnostics(&client);
+    let elapsed = start.elapsed();
+
+    assert!(
+        elapsed < Duration::from_millis(200),
+        "Sunken Citadel recompile + diagnostic push took {:?}, expected < 200ms",
+        elapsed
+    );
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+// ── Inlay hint tests ──
+
+#[test]
+fn lsp_inlay_hints_fixture() {
+    let (client, thread) = setup();
+    initialize(&client);
+    send_did_open(&client, "inlay-hints.urd.md");
+    let _diags = recv_diagnostics(&client);
+
+    let hints = send_inlay_hints(&client, "inlay-hints.urd.md", 0, 100);
+    let expected: Vec<(u32, String)> = [
+        (14, "→ courtyard"),
+        (16, "inlay-hints/talk"),
+        (20, "ask-to-pass"),
+        (22, "section inlay-hints/north"),
+        (24, "turn-back"),
+        (27, "inlay-hints/north"),
+        (31, "leave"),
+        (32, "exit → courtyard"),
+        (38, "→ gate"),
+    ]
+    .iter()
+    .map(|(line, label)| (*line, label.to_string()))
+    .collect();
+    assert_eq!(hint_labels(&hints), expected);
+
+    // The shadowed jump reveals that the section won, as URD310 reports.
+    let shadowed = hints.iter().find(|h| h.position.line == 22).unwrap();
+    match &shadowed.tooltip {
+        Some(InlayHintTooltip::String(t)) => assert!(t.contains("shadowing an exit"), "{}", t),
+        other => panic!("Expected a tooltip, got {:?}", other),
+    }
+
+    // Hints sit at the end of the line and carry no edits.
+    assert_eq!(shadowed.position.character, "  -> north".len() as u32);
+    assert!(hints.iter().all(|h| h.text_edits.is_none()));
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_inlay_hints_respect_range() {
+    let (client, thread) = setup();
+    initialize(&client);
+    send_did_open(&client, "inlay-hints.urd.md");
+    let _diags = recv_diagnostics(&client);
+
+    let hints = send_inlay_hints(&client, "inlay-hints.urd.md", 20, 24);
+    let lines: Vec<u32> = hints.iter().map(|h| h.position.line).collect();
+    assert_eq!(lines, vec![20, 22, 24]);
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_inlay_hints_categories_can_be_disabled() {
+    let (client, thread) = setup();
+    let init_params = InitializeParams {
+        capabilities: ClientCapabilities::default(),
+        initialization_options: Some(json!({ "inlayHints": { "choices": false, "exits": false } })),
+        ..Default::default()
+    };
+    initialize_with(&client, init_params);
+    send_did_open(&client, "inlay-hints.urd.md");
+    let _diags = recv_diagnostics(&client);
+
+    let labels: Vec<String> = hint_labels(&send_inlay_hints(&client, "inlay-hints.urd.md", 0, 100))
+        .into_iter()
+        .map(|(_, label)| label)
+        .collect();
+    assert_eq!(
+        labels,
+        vec![
+            "inlay-hints/talk",
+            "section inlay-hints/north",
+            "inlay-hints/north",
+            "exit → courtyard",
+        ]
+    );
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+// ── Code lens tests ──
+
+#[test]
+fn lsp_code_lens_positions() {
+    let (client, thread) = setup();
+    initialize(&client);
+    send_did_open(&client, "code-lens/main.urd.md");
+
+    let lines = |fixture: &str| -> Vec<(u32, bool)> {
+        send_code_lens(&client, fixture)
+            .iter()
+            .map(|l| (l.range.start.line, l.command.is_some()))
+            .collect()
+    };
+    // `world:`, then the Gallery heading and the talk section.
+    assert_eq!(lines("code-lens/main.urd.md"), vec![(2, true), (7, false), (15, false)]);
+    // Two types, three entities, and the Store Room heading; no world block.
+    assert_eq!(
+        lines("code-lens/cast.urd.md"),
+        vec![(2, false), (4, false), (7, false), (8, false), (9, false), (12, false)]
+    );
+
+    let compile = send_code_lens(&client, "code-lens/main.urd.md").remove(0).command.unwrap();
+    assert_eq!(compile.title, "Compile world");
+    assert_eq!(compile.command, "urd.compileWorld");
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_code_lens_counts_references_across_files() {
+    let (client, thread) = setup();
+    initialize(&client);
+    send_did_open(&client, "code-lens/main.urd.md");
+
+    // @lantern: presence in cast.urd.md, an exit condition and an effect in main.urd.md.
+    let (title, locations) = lens_on_line(&client, "code-lens/cast.urd.md", 7);
+    assert_eq!(title, "3 references");
+    let mut sites: Vec<(String, u32)> = locations
+        .iter()
+        .map(|l| (l.uri.as_str().rsplit('/').next().unwrap().to_string(), l.range.start.line))
+        .collect();
+    sites.sort();
+    assert_eq!(
+        sites,
+        vec![
+            ("cast.urd.md".to_string(), 14),
+            ("main.urd.md".to_string(), 12),
+            ("main.urd.md".to_string(), 20),
+        ]
+    );
+
+    // The Store Room heading: the Gallery's exit down.
+    let (title, _) = lens_on_line(&client, "code-lens/cast.urd.md", 12);
+    assert_eq!(title, "1 reference");
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_code_lens_unreferenced_entity() {
+    let (client, thread) = setup();
+    initialize(&client);
+    send_did_open(&client, "code-lens/main.urd.md");
+
+    let (title, locations) = lens_on_line(&client, "code-lens/cast.urd.md", 8);
+    assert_eq!(title, "0 references");
+    assert!(locations.is_empty());
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_compile_world_command_pushes_diagnostics() {
+    let (client, thread) = setup();
+    initialize(&client);
+    send_did_open(&client, "negative-unreachable-location.urd.md");
+    let opened = recv_diagnostics(&client);
+
+    let params = ExecuteCommandParams {
+        command: "urd.compileWorld".to_string(),
+        arguments: vec![json!(fixture_uri("negative-unreachable-location.urd.md"))],
+        work_done_progress_params: Default::default(),
+    };
+    let (resp, notifications) =
+        send_request(&client, 52, "workspace/executeCommand", serde_json::to_value(params).unwrap());
+    assert!(resp.error.is_none(), "{:?}", resp.error);
+
+    let pushed: Vec<PublishDiagnosticsParams> = notifications
+        .into_iter()
+        .filter(|n| n.method == "textDocument/publishDiagnostics")
+        .map(|n| serde_json::from_value(n.params).unwrap())
+        .collect();
+    assert_eq!(pushed.len(), 1);
+    assert_eq!(pushed[0], opened);
+
+    let params = ExecuteCommandParams {
+        command: "urd.unknown".to_string(),
+        arguments: vec![],
+        work_done_progress_params: Default::default(),
+    };
+    let (resp, _) = send_request(&client, 53, "workspace/executeCommand", serde_json::to_value(params).unwrap());
+    assert!(resp.error.is_some());
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+// ── Pull diagnostics tests ──
+
+/// Send a textDocument/diagnostic request and return the report.
+fn pull_diagnostics(client: &Connection, fixture: &str, previous_result_id: Option<String>) -> DocumentDiagnosticReport {
+    let params = DocumentDiagnosticParams {
+        text_document: TextDocumentIdentifier { uri: fixture_uri(fixture) },
+        identifier: None,
+        previous_result_id,
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+    let (resp, _) = send_request(client, 70, "textDocument/diagnostic", serde_json::to_value(params).unwrap());
+    match serde_json::from_value(resp.result.unwrap()).unwrap() {
+        DocumentDiagnosticReportResult::Report(report) => report,
+        other => panic!("Expected a report, got {:?}", other),
+    }
+}
+
+fn full_report(report: DocumentDiagnosticReport) -> FullDocumentDiagnosticReport {
+    match report {
+        DocumentDiagnosticReport::Full(full) => full.full_document_diagnostic_report,
+        other => panic!("Expected a full report, got {:?}", other),
+    }
+}
+
+#[test]
+fn lsp_pull_diagnostics_match_push() {
+    let (client, thread) = setup();
+    initialize(&client);
+    send_did_open(&client, "negative-unreachable-location.urd.md");
+    let pushed = recv_diagnostics(&client);
+
+    let pulled = full_report(pull_diagnostics(&client, "negative-unreachable-location.urd.md", None));
+    assert!(!pulled.items.is_empty());
+    assert_eq!(pulled.items, pushed.diagnostics);
+    assert!(pulled.result_id.is_some());
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_pull_diagnostics_unchanged_until_recompile() {
+    let (client, thread) = setup();
+    initialize(&client);
+    send_did_open(&client, "negative-unreachable-location.urd.md");
+    let _diags = recv_diagnostics(&client);
+
+    let first = full_report(pull_diagnostics(&client, "negative-unreachable-location.urd.md", None));
+    let again = pull_diagnostics(&client, "negative-unreachable-location.urd.md", first.result_id.clone());
+    match again {
+        DocumentDiagnosticReport::Unchanged(unchanged) => {
+            assert_eq!(Some(unchanged.unchanged_document_diagnostic_report.result_id), first.result_id);
+        }
+        other => panic!("Expected an unchanged report, got {:?}", other),
+    }
+
+    send_did_save(&client, "negative-unreachable-location.urd.md");
+    let _diags = recv_diagnostics(&client);
+    let after_save = full_report(pull_diagnostics(&client, "negative-unreachable-location.urd.md", first.result_id.clone()));
+    assert_ne!(after_save.result_id, first.result_id);
+    assert_eq!(after_save.items, first.items);
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_workspace_pull_covers_imported_files() {
+    let (client, thread) = setup();
+    initialize(&client);
+    send_did_open(&client, "code-lens/main.urd.md");
+
+    let params = WorkspaceDiagnosticParams {
+        identifier: None,
+        previous_result_ids: vec![],
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+    let (resp, _) = send_request(&client, 71, "workspace/diagnostic", serde_json::to_value(params).unwrap());
+    let items = match serde_json::from_value(resp.result.unwrap()).unwrap() {
+        WorkspaceDiagnosticReportResult::Report(report) => report.items,
+        other => panic!("Expected a report, got {:?}", other),
+    };
+
+    let full = |fixture: &str| {
+        let uri = fixture_uri(fixture);
+        items
+            .iter()
+            .find_map(|item| match item {
+                WorkspaceDocumentDiagnosticReport::Full(f) if f.uri == uri => {
+                    Some(f.full_document_diagnostic_report.items.clone())
+                }
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("No report for {}", fixture))
+    };
+    let codes = |diags: Vec<Diagnostic>| -> Vec<Option<NumberOrString>> { diags.into_iter().map(|d| d.code).collect() };
+    assert!(codes(full("code-lens/cast.urd.md")).contains(&Some(NumberOrString::String("URD609".into()))));
+    assert!(codes(full("code-lens/main.urd.md")).contains(&Some(NumberOrString::String("URD449".into()))));
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_push_can_be_turned_off() {
+    let (client, thread) = setup();
+    initialize_with(
+        &client,
+        InitializeParams {
+            capabilities: ClientCapabilities::default(),
+            initialization_options: Some(json!({ "pushDiagnostics": false })),
+            ..Default::default()
+        },
+    );
+    send_did_open(&client, "negative-unreachable-location.urd.md");
+
+    let params = DocumentDiagnosticParams {
+        text_document: TextDocumentIdentifier { uri: fixture_uri("negative-unreachable-location.urd.md") },
+        identifier: None,
+        previous_result_id: None,
+        work_done_progress_params: Default::default(),
+        partial_result_params: Default::default(),
+    };
+    let (resp, notifications) = send_request(&client, 72, "textDocument/diagnostic", serde_json::to_value(params).unwrap());
+    assert!(notifications.iter().all(|n| n.method != "textDocument/publishDiagnostics"), "{:?}", notifications);
+    match serde_json::from_value(resp.result.unwrap()).unwrap() {
+        DocumentDiagnosticReportResult::Report(report) => assert!(!full_report(report).items.is_empty()),
+        other => panic!("Expected a report, got {:?}", other),
+    }
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+// ── Workspace root tests ──
+
+#[test]
+fn lsp_workspace_folder_is_project_root() {
+    let (client, thread) = setup();
+    // Root one level above the fixture, so spans read `fixtures/...`.
+    initialize_with_workspace(&client, "");
+
+    send_did_open(&client, "negative-unreachable-location.urd.md");
+    let diags = recv_diagnostics(&client);
+
+    assert_eq!(diags.uri, fixture_uri("negative-unreachable-location.urd.md"));
+    assert!(!diags.diagnostics.is_empty());
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_entry_outside_workspace_uses_entry_dir() {
+    let (client, thread) = setup();
+    // The fixture is not inside this folder, so the root falls back.
+    initialize_with_workspace(&client, "fixtures/diff");
+
+    send_did_open(&client, "negative-unreachable-location.urd.md");
+    let diags = recv_diagnostics(&client);
+
+    assert_eq!(diags.uri, fixture_uri("negative-unreachable-location.urd.md"));
+    assert!(
+        diags.diagnostics.iter().all(|d| d.code != Some(NumberOrString::String("URD208".into()))),
+        "Entry outside the workspace must not be rejected: {:?}",
+        diags.diagnostics
+    );
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+// ── Signature help tests ──
+
+#[test]
+fn lsp_signature_help_enum_effect() {
+    let (client, thread) = setup();
+    initialize(&client);
+
+    send_did_open(&client, "locked-garden.urd.md");
+    let _diags = recv_diagnostics(&client);
+
+    // Line 47: "  > @warden.mood = friendly" — cursor after "= "
+    let help = signature_help(&client, "locked-garden.urd.md", 47, 19).expect("Expected signature help");
+    assert_eq!(help.signatures.len(), 1);
+    assert_eq!(help.signatures[0].label, "@warden.mood = <wary | neutral | friendly>");
+    assert_eq!(help.active_parameter, Some(1));
+
+    // Cursor after "@warden." — one signature per property, property slot active.
+    let help = signature_help(&client, "locked-garden.urd.md", 47, 12).expect("Expected signature help");
+    let labels: Vec<&str> = help.signatures.iter().map(|s| s.label.as_str()).collect();
+    assert!(labels.contains(&"@warden.mood = <wary | neutral | friendly>"), "got: {:?}", labels);
+    assert!(labels.iter().any(|l| l.starts_with("@warden.trust = <integer")), "got: {:?}", labels);
+    assert_eq!(help.active_parameter, Some(0));
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_signature_help_integer_range() {
+    let (client, thread) = setup();
+    initialize(&client);
+
+    send_did_open(&client, "property-groups.urd.md");
+
+    // Line 30: "  > @guard.appearance.height = 200" — cursor after "= "
+    let help = signature_help(&client, "property-groups.urd.md", 30, 31).expect("Expected signature help");
+    assert_eq!(help.signatures[0].label, "@guard.appearance.height = <integer 100..250>");
+    assert_eq!(help.active_parameter, Some(1));
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_signature_help_move_destinations() {
+    let (client, thread) = setup();
+    initialize(&client);
+
+    send_did_open(&client, "sunken-citadel.urd.md");
+    let _diags = recv_diagnostics(&client);
+
+    // Line 261: "  > move @ancient_coin -> player" — cursor after "-> "
+    let help = signature_help(&client, "sunken-citadel.urd.md", 261, 26).expect("Expected signature help");
+    let signature = &help.signatures[0];
+    assert_eq!(signature.label, "move @ancient_coin -> <destination>");
+    assert_eq!(help.active_parameter, Some(1));
+
+    let docs = match &signature.parameters.as_ref().unwrap()[1].documentation {
+        Some(Documentation::MarkupContent(m)) => m.value.clone(),
+        other => panic!("Expected markdown destination docs, got {:?}", other),
+    };
+    assert!(docs.contains("`player`"), "got: {}", docs);
+    assert!(docs.contains("`here`"), "got: {}", docs);
+    assert!(docs.contains("Locations:"), "got: {}", docs);
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_signature_help_outside_effects_is_null() {
+    let (client, thread) = setup();
+    initialize(&client);
+
+    send_did_open(&client, "locked-garden.urd.md");
+    let _diags = recv_diagnostics(&client);
+
+    // Line 44: "  ? @journal in player" — a containment check, not a property.
+    assert!(signature_help(&client, "locked-garden.urd.md", 44, 22).is_none());
+    // Line 47 before the ">" marker.
+    assert!(signature_help(&client, "locked-garden.urd.md", 47, 1).is_none());
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn signature_help_ignores_malformed_lines() {
+    let world = json!({
+        "types": { "Guard": { "properties": { "mood": { "type": "enum", "values": ["calm"] } } } },
+        "entities": { "guard": { "type": "Guard" } },
+        "locations": { "gate": {} }
+    });
+    for prefix in [
+        "", ">", "> @", "> @.", "? @.", "> @nobody.mood = ", "> @guard", "> @guard.x",
+        "> @guard.mood ~ ", "> move ", "> move ->", "> move guard -> ", "> move @nobody -> ",
+        "? @gärd.mööd == ", "> @guard.mood = ✓",
+    ] {
+        let help = urd_lsp::signature_help::signature_for_prefix(&world, prefix);
+        match prefix {
+            "> move " | "> move ->" => assert!(help.is_some(), "{:?} should offer the move form", prefix),
+            "> @guard.mood = ✓" => assert!(help.is_some(), "{:?} is still a value slot", prefix),
+            _ => assert!(help.is_none(), "{:?} should give no signature", prefix),
+        }
+    }
+    assert!(urd_lsp::signature_help::signature_for_prefix(&json!({}), "> @guard.mood = ").is_none());
+}
+
+// ── Symbol preview ──
+
+#[test]
+fn lsp_preview_symbol_slices_the_compiled_world() {
+    let (client, thread) = setup();
+    initialize(&client);
+    send_did_open(&client, "locked-garden.urd.md");
+    let _diags = recv_diagnostics(&client);
+
+    let compiled = urd_compiler::compile(&fixture_path("locked-garden.urd.md"));
+    let world: serde_json::Value = serde_json::from_str(compiled.world.as_ref().unwrap()).unwrap();
+
+    let preview = |id: &str| {
+        let (resp, _) = send_request(&client, 60, "urd/previewSymbol", json!({ "compiledId": id }));
+        resp.result.unwrap()
+    };
+    let section = preview("locked-garden/greet");
+    assert_eq!(section["kind"], "section");
+    assert_eq!(section["json"], world["dialogue"]["locked-garden/greet"]);
+    let location = preview("gatehouse");
+    assert_eq!(location["kind"], "location");
+    assert_eq!(location["json"], world["locations"]["gatehouse"]);
+    assert_eq!(preview("warden")["json"], world["entities"]["warden"]);
+    assert!(preview("nowhere").is_null());
+
+    shutdown(&client);
+    thread.join().unwrap();
+}
+
+#[test]
+fn lsp_output_location_maps_lines_and_pointers() {
+    let (client, thread) = setup();
+    initialize(&client);
+    send_did_open(&client, "sunken-citadel.urd.md");
+    let _diags = recv_diagnostics(&client);
+
+    let uri = fixture_uri("sunken-citadel.urd.md");
+    let at_line = |line: u32| {
+        let params = json!({ "textDocument": { "uri": uri.as_str() }, "position": { "line": line, "character": 0 } });
+        let (resp, _) = send_request(&client, 61, "urd/outputLocation", params);
+        resp.result.unwrap()
+    };
+    // Line 226 (0-based 225) is the second effect of the second choice.
+    let effect = at_line(225);
+    assert_eq!(effect["pointer"], "/dialogue/sunken-citadel~1square_talk/choices/1/effects/1");
+    assert_eq!(effect["path"], r#"dialogue["sunken-citadel/square_talk"].choices[1].effects[1]"#);
+    assert_eq!(effect["pointers"][0], effect["pointer"]);
+    // Location prose produces nothing.
+    assert!(at_line(183).is_null());
+
+    let (resp, _) = send_request(
+        &client,
+        62,
+        "urd/outputLocation",
+        json!({ "pointer": "/dialogue/sunken-citadel~1square_talk/choices/1/effects/1" }),
+    );
+    let location = resp.result.unwrap();
+    assert_eq!(location["uri"], uri.as_str());
+    assert_eq!(location["range"]["start"]["line"], 225);
+
+    let (resp, _) = send_request(&client, 63, "urd/outputLocation", json!({}));
+    assert!(resp.error.is_some());
+
     shutdown(&client);
     thread.join().unwrap();
 }
@@ -1,6 +1,6 @@
 /// Urd MCP Server — read-only semantic query interface for compiled worlds.
 ///
-/// Exposes eight tools via the Model Context Protocol, backed by FactSet,
+/// Exposes ten tools via the Model Context Protocol, backed by FactSet,
 /// PropertyDependencyIndex, and compiled world JSON. Read-only, no mutation.
 
 pub mod queries;
@@ -5,9 +5,13 @@
 
 use std::collections::{HashMap, HashSet, VecDeque};
 
-use serde_json::{json, Value};
+use serde_json::{json, Map, Value};
 
-use urd_compiler::facts::{CompareOp, FactSite, JumpTarget, WriteOp};
+use urd_compiler::analyze::{self, ValueReachability};
+use urd_compiler::diff::{self, DiffSnapshot};
+use urd_compiler::explain;
+use urd_compiler::facts::{CompareOp, FactSet, FactSite, JumpTarget, SiteOwner, WriteOp};
+use urd_compiler::CompiledWorld;
 
 use crate::world_data::WorldData;
 
@@ -37,13 +41,15 @@ fn format_site(site: &FactSite) -> String {
         FactSite::Choice(id) => format!("choice:{}", id),
         FactSite::Exit(id) => format!("exit:{}", id),
         FactSite::Rule(id) => format!("rule:{}", id),
+        FactSite::LocationHook(id) => format!("hook:{}", id),
         _ => format!("unknown"),
     }
 }
 
-fn object_keys_sorted(value: &Value, key: &str) -> Vec<String> {
-    value[key]
-        .as_object()
+static NULL: Value = Value::Null;
+
+fn object_keys_sorted(block: Option<&Map<String, Value>>) -> Vec<String> {
+    block
         .map(|obj| {
             let mut keys: Vec<String> = obj.keys().cloned().collect();
             keys.sort();
@@ -52,47 +58,39 @@ fn object_keys_sorted(value: &Value, key: &str) -> Vec<String> {
         .unwrap_or_default()
 }
 
+/// The entry `id` in `block`, or null when either is missing.
+fn entry<'a>(block: &'a Option<Map<String, Value>>, id: &str) -> &'a Value {
+    block.as_ref().and_then(|b| b.get(id)).unwrap_or(&NULL)
+}
+
+/// The number of entries in a world block, 0 without a world.
+fn block_len(data: &WorldData, block: impl Fn(&CompiledWorld) -> &Option<Map<String, Value>>) -> usize {
+    data.world.as_ref().and_then(|w| block(w).as_ref()).map_or(0, Map::len)
+}
+
+/// Docs whose key starts with `prefix`, keyed by the rest of the key.
+fn docs_with_prefix(data: &WorldData, prefix: &str) -> Map<String, Value> {
+    data.docs
+        .iter()
+        .filter_map(|(key, doc)| Some((key.strip_prefix(prefix)?.to_string(), json!(doc))))
+        .collect()
+}
+
 // ── Tool 1: get_world_metadata ──
 
 pub fn get_world_metadata(data: &WorldData) -> Value {
-    let (world_name, start_location) = match &data.world_json {
-        Some(w) => (
-            w["world"]["name"].as_str().unwrap_or("unknown").to_string(),
-            w["world"]["start"]
-                .as_str()
-                .unwrap_or("unknown")
-                .to_string(),
-        ),
+    let (world_name, start_location) = match &data.world {
+        Some(w) => {
+            let field = |key: &str| w.world.get(key).and_then(Value::as_str).unwrap_or("unknown").to_string();
+            (field("name"), field("start"))
+        }
         None => ("unknown".to_string(), "unknown".to_string()),
     };
 
-    let entity_count = data
-        .world_json
-        .as_ref()
-        .and_then(|w| w["entities"].as_object())
-        .map(|o| o.len())
-        .unwrap_or(0);
-
-    let location_count = data
-        .world_json
-        .as_ref()
-        .and_then(|w| w["locations"].as_object())
-        .map(|o| o.len())
-        .unwrap_or(0);
-
-    let type_count = data
-        .world_json
-        .as_ref()
-        .and_then(|w| w["types"].as_object())
-        .map(|o| o.len())
-        .unwrap_or(0);
-
-    let section_count = data
-        .world_json
-        .as_ref()
-        .and_then(|w| w["dialogue"].as_object())
-        .map(|o| o.len())
-        .unwrap_or(0);
+    let entity_count = block_len(data, |w| &w.entities);
+    let location_count = block_len(data, |w| &w.locations);
+    let type_count = block_len(data, |w| &w.types);
+    let section_count = block_len(data, |w| &w.dialogue);
 
     let exit_count = data
         .fact_set
@@ -125,9 +123,9 @@ pub fn get_world_metadata(data: &WorldData) -> Value {
 
 pub fn get_exit_graph(data: &WorldData) -> Value {
     let nodes = data
-        .world_json
+        .world
         .as_ref()
-        .map(|w| object_keys_sorted(w, "locations"))
+        .map(|w| object_keys_sorted(w.locations.as_ref()))
         .unwrap_or_default();
 
     let edges: Vec<Value> = data
@@ -152,7 +150,8 @@ pub fn get_exit_graph(data: &WorldData) -> Value {
     json!({
         "schema_version": "1",
         "nodes": nodes,
-        "edges": edges
+        "edges": edges,
+        "location_docs": docs_with_prefix(data, "location:")
     })
 }
 
@@ -160,9 +159,9 @@ pub fn get_exit_graph(data: &WorldData) -> Value {
 
 pub fn get_dialogue_graph(data: &WorldData) -> Value {
     let sections = data
-        .world_json
+        .world
         .as_ref()
-        .map(|w| object_keys_sorted(w, "dialogue"))
+        .map(|w| object_keys_sorted(w.dialogue.as_ref()))
         .unwrap_or_default();
 
     let jumps: Vec<Value> = data
@@ -211,14 +210,15 @@ pub fn get_dialogue_graph(data: &WorldData) -> Value {
         "schema_version": "1",
         "sections": sections,
         "jumps": jumps,
-        "choices": choices
+        "choices": choices,
+        "section_docs": docs_with_prefix(data, "section:")
     })
 }
 
 // ── Tool 4: get_entity_details ──
 
 pub fn get_entity_details(data: &WorldData, entity_id: &str) -> Value {
-    let world = match &data.world_json {
+    let world = match &data.world {
         Some(w) => w,
         None => {
             return json!({
@@ -232,7 +232,7 @@ pub fn get_entity_details(data: &WorldData, entity_id: &str) -> Value {
     // Strip leading @ if present
     let clean_id = entity_id.strip_prefix('@').unwrap_or(entity_id);
 
-    let entity = &world["entities"][clean_id];
+    let entity = entry(&world.entities, clean_id);
     if entity.is_null() {
         return json!({
             "schema_version": "1",
@@ -244,8 +244,9 @@ pub fn get_entity_details(data: &WorldData, entity_id: &str) -> Value {
     let type_name = entity["type"].as_str().unwrap_or("unknown");
 
     // Find container location
-    let container = world["locations"]
-        .as_object()
+    let container = world
+        .locations
+        .as_ref()
         .and_then(|locations| {
             locations.iter().find_map(|(loc_id, loc)| {
                 loc["contains"].as_array().and_then(|contains| {
@@ -260,7 +261,7 @@ pub fn get_entity_details(data: &WorldData, entity_id: &str) -> Value {
         .unwrap_or_default();
 
     // Build property list from type definitions + entity overrides
-    let type_props = &world["types"][type_name]["properties"];
+    let type_props = &entry(&world.types, type_name)["properties"];
     let entity_props = &entity["properties"];
 
     let mut properties: Vec<Value> = Vec::new();
@@ -302,13 +303,20 @@ pub fn get_entity_details(data: &WorldData, entity_id: &str) -> Value {
         }
     }
 
-    json!({
+    let mut details = json!({
         "schema_version": "1",
         "entity_id": format!("@{}", clean_id),
         "type": type_name,
         "container": container,
         "properties": properties
-    })
+    });
+    if let Some(doc) = data.docs.get(&format!("entity:@{}", clean_id)) {
+        details["doc"] = json!(doc);
+    }
+    if let Some(doc) = data.docs.get(&format!("type:{}", type_name)) {
+        details["type_doc"] = json!(doc);
+    }
+    details
 }
 
 // ── Tool 5: get_property_dependencies ──
@@ -392,7 +400,7 @@ pub fn get_property_dependencies(data: &WorldData, entity_type: &str, property:
 // ── Tool 6: get_reachable_locations ──
 
 pub fn get_reachable_locations(data: &WorldData, from: &str) -> Value {
-    let world = match &data.world_json {
+    let world = match &data.world {
         Some(w) => w,
         None => {
             return json!({
@@ -403,7 +411,7 @@ pub fn get_reachable_locations(data: &WorldData, from: &str) -> Value {
         }
     };
 
-    let all_locations = object_keys_sorted(world, "locations");
+    let all_locations = object_keys_sorted(world.locations.as_ref());
     if !all_locations.contains(&from.to_string()) {
         return json!({
             "schema_version": "1",
@@ -510,9 +518,9 @@ pub fn get_choice_conditions(data: &WorldData, section: &str) -> Value {
 
     // Check if section exists in world JSON
     let section_exists = data
-        .world_json
+        .world
         .as_ref()
-        .and_then(|w| w["dialogue"].as_object())
+        .and_then(|w| w.dialogue.as_ref())
         .map(|d| d.contains_key(section))
         .unwrap_or(false);
 
@@ -624,3 +632,249 @@ pub fn get_diagnostics(data: &WorldData, severity: Option<&str>, file: Option<&s
         "diagnostics": diagnostics
     })
 }
+
+// ── Tool 9: can_reach_value ──
+
+pub fn can_reach_value(data: &WorldData, entity_id: &str, property: &str, target: &str) -> Value {
+    let clean_id = entity_id.strip_prefix('@').unwrap_or(entity_id);
+    let error = |message: &str| {
+        json!({
+            "schema_version": "1",
+            "error": message,
+            "entity_id": format!("@{}", clean_id),
+            "property": property
+        })
+    };
+
+    let world = match &data.world {
+        Some(w) => w,
+        None => return error("No compiled world available"),
+    };
+    let (fact_set, prop_index) = match (&data.fact_set, &data.property_index) {
+        (Some(fs), Some(pi)) => (fs, pi),
+        _ => return error("No FactSet available"),
+    };
+
+    let entity = entry(&world.entities, clean_id);
+    if entity.is_null() {
+        return error("Entity not found");
+    }
+    let type_name = entity["type"].as_str().unwrap_or("unknown");
+    let prop_def = &entry(&world.types, type_name)["properties"][property];
+    if prop_def.is_null() {
+        return error("Property not found");
+    }
+
+    let property_type = prop_def["type"].as_str().unwrap_or("unknown");
+    let target = target.trim().trim_matches('"');
+    let initial = entity["properties"]
+        .get(property)
+        .unwrap_or(&prop_def["default"])
+        .clone();
+
+    let key = urd_compiler::facts::PropertyKey {
+        entity_type: type_name.to_string(),
+        property: property.to_string(),
+    };
+    let report = analyze::classify_value_reachability(
+        fact_set,
+        prop_index,
+        &key,
+        target,
+        prop_def["min"].as_f64(),
+        prop_def["max"].as_f64(),
+    );
+
+    // Values the declaration rules out can never be held, whatever writes it.
+    let undeclared = match property_type {
+        "boolean" => target != "true" && target != "false",
+        "enum" => !prop_def["values"]
+            .as_array()
+            .is_some_and(|values| values.iter().any(|v| v.as_str() == Some(target))),
+        "integer" | "number" => target.parse::<f64>().is_err(),
+        _ => false,
+    };
+
+    let assignment = format!("{}.{} = {}", type_name, property, target);
+    let (classification, reason) = if undeclared {
+        (
+            "unreachable",
+            format!("'{}' is not a valid {} value for {}.{}.", target, property_type, type_name, property),
+        )
+    } else {
+        match report.verdict {
+            ValueReachability::DirectlySettable => (
+                "directly_settable",
+                format!("{} effect(s) assign {}.", report.exact_writes.len(), assignment),
+            ),
+            ValueReachability::ViaArithmetic => (
+                "possibly_reachable_via_arithmetic",
+                format!(
+                    "No effect assigns {} directly, but {} +/- effect(s) could accumulate to it.",
+                    assignment,
+                    report.arithmetic_writes.len()
+                ),
+            ),
+            ValueReachability::Unreachable if report.out_of_range => (
+                "unreachable",
+                format!("{} is outside the declared range of {}.{}.", target, type_name, property),
+            ),
+            ValueReachability::Unreachable => (
+                "unreachable",
+                format!("No effect can produce {}.", assignment),
+            ),
+        }
+    };
+
+    let evidence: Vec<Value> = [
+        ("sets_target", &report.exact_writes),
+        ("arithmetic", &report.arithmetic_writes),
+        ("sets_other", &report.other_writes),
+    ]
+    .iter()
+    .flat_map(|(kind, indices)| {
+        indices
+            .iter()
+            .filter_map(|&idx| fact_set.writes().get(idx))
+            .map(move |w| {
+                json!({
+                    "kind": kind,
+                    "site": format_site(&w.site),
+                    "owner": site_owner_json(fact_set, &w.site),
+                    "operation": format!("{} {} {}", w.property, write_op_symbol(&w.operator), w.value_expr),
+                    "guards": site_guards_json(fact_set, &w.site),
+                    "file": w.span.file,
+                    "line": w.span.start_line
+                })
+            })
+    })
+    .collect();
+
+    json!({
+        "schema_version": "1",
+        "entity_id": format!("@{}", clean_id),
+        "entity_type": type_name,
+        "property": property,
+        "property_type": property_type,
+        "target": target,
+        "classification": classification,
+        "reason": reason,
+        "initial_value": initial,
+        "initially_holds": value_matches(&initial, target),
+        "evidence": evidence,
+        "note": "Static over-approximation: writes are matched by entity type, not by \
+                 individual entity, and guards are listed but not evaluated. \
+                 'unreachable' is definite; the other classifications mean 'possibly'."
+    })
+}
+
+// ── Tool 10: diff_world ──
+
+/// Diff a saved snapshot against the loaded world. The snapshot comes
+/// from `snapshot_path` or, failing that, inline `snapshot_json`; exactly
+/// one must be given. Changes read from the snapshot to the loaded world.
+pub fn diff_world(data: &WorldData, snapshot_path: Option<&str>, snapshot_json: Option<&str>) -> Value {
+    let error = |message: String| {
+        json!({
+            "schema_version": "1",
+            "error": message,
+            "snapshot_path": snapshot_path
+        })
+    };
+
+    let source = match (snapshot_path, snapshot_json) {
+        (Some(path), None) => match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) => return error(format!("Cannot read snapshot '{}': {}", path, e)),
+        },
+        (None, Some(inline)) => inline.to_string(),
+        (Some(_), Some(_)) => {
+            return error("Give either snapshot_path or snapshot_json, not both".to_string())
+        }
+        (None, None) => return error("Give snapshot_path or snapshot_json".to_string()),
+    };
+
+    let snapshot = match DiffSnapshot::from_json(&source) {
+        Ok(snapshot) => snapshot,
+        Err(e) => return error(e.to_string()),
+    };
+
+    let report = diff::diff(&snapshot, &data.snapshot);
+    json!({
+        "schema_version": "1",
+        "summary": report.summary(),
+        "report": report.to_json()
+    })
+}
+
+// ── Tool 11: explain_choice ──
+
+/// Why a choice is offered or withheld: its conditions with starting values
+/// and write sites, how its section is entered, and whether its location
+/// is reachable.
+pub fn explain_choice(data: &WorldData, choice_id: &str) -> Value {
+    let error = |message: String| {
+        json!({
+            "schema_version": "1",
+            "error": message,
+            "choice_id": choice_id
+        })
+    };
+
+    let (fact_set, prop_index) = match (&data.fact_set, &data.property_index) {
+        (Some(fs), Some(pi)) => (fs, pi),
+        _ => return error("No FactSet available".to_string()),
+    };
+
+    match explain::explain_choice(fact_set, prop_index, choice_id) {
+        Ok(explanation) => json!({
+            "schema_version": "1",
+            "explanation": explanation.to_json(),
+            "note": "Static: guards on the write sites are not evaluated, and reads \
+                     inside a '? any:' block are listed with the rest."
+        }),
+        Err(e) => error(e.to_string()),
+    }
+}
+
+fn site_owner_json(fact_set: &FactSet, site: &FactSite) -> Value {
+    match fact_set.resolve_site(site) {
+        Some(SiteOwner::Choice(c)) => json!({
+            "kind": "choice",
+            "id": c.choice_id,
+            "section": c.section,
+            "label": c.label,
+            "sticky": c.sticky
+        }),
+        Some(SiteOwner::Rule(r)) => json!({ "kind": "rule", "id": r.rule_id }),
+        Some(SiteOwner::LocationHook(h)) => json!({ "kind": "hook", "id": h.hook_id() }),
+        Some(SiteOwner::Exit(e)) => json!({ "kind": "exit", "id": e.exit_id() }),
+        None => Value::Null,
+    }
+}
+
+fn site_guards_json(fact_set: &FactSet, site: &FactSite) -> Vec<Value> {
+    fact_set
+        .read_indices_for_site(site)
+        .iter()
+        .filter_map(|&idx| fact_set.reads().get(idx))
+        .map(|r| {
+            json!({
+                "property": format!("{}.{}", r.entity_type, r.property),
+                "comparison": format!("{} {}", compare_op_symbol(&r.operator), r.value_literal)
+            })
+        })
+        .collect()
+}
+
+fn value_matches(value: &Value, target: &str) -> bool {
+    match value {
+        Value::Bool(b) => b.to_string() == target,
+        Value::Number(n) => match (n.as_f64(), target.parse::<f64>()) {
+            (Some(a), Ok(b)) => a == b,
+            _ => false,
+        },
+        Value::String(s) => s == target,
+        _ => false,
+    }
+}
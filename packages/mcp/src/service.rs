@@ -40,6 +40,12 @@ pub struct SectionParams {
     pub section: String,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ChoiceParams {
+    #[schemars(description = "Choice compiled ID (e.g., 'gatehouse/greet/ask-about-the-garden')")]
+    pub choice_id: String,
+}
+
 #[derive(Debug, Deserialize, JsonSchema)]
 pub struct DiagnosticParams {
     #[schemars(description = "Filter by severity: 'error', 'warning', or 'info'")]
@@ -48,6 +54,24 @@ pub struct DiagnosticParams {
     pub file: Option<String>,
 }
 
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct ReachValueParams {
+    #[schemars(description = "Entity ID (e.g., '@cell_door')")]
+    pub entity_id: String,
+    #[schemars(description = "Property name (e.g., 'locked')")]
+    pub property: String,
+    #[schemars(description = "Target value (e.g., 'false', 'friendly', '10')")]
+    pub value: String,
+}
+
+#[derive(Debug, Deserialize, JsonSchema)]
+pub struct DiffParams {
+    #[schemars(description = "Path to a .urd.snapshot.json file")]
+    pub snapshot_path: Option<String>,
+    #[schemars(description = "Snapshot JSON, inline, instead of a path")]
+    pub snapshot_json: Option<String>,
+}
+
 // ── Service struct ──
 
 #[derive(Clone)]
@@ -79,7 +103,7 @@ impl UrdMcpService {
 
     #[tool(
         name = "get_exit_graph",
-        description = "Returns the complete location exit graph as nodes (locations) and edges (exits with direction, destination, and condition information). Use this to understand spatial navigation between locations."
+        description = "Returns the complete location exit graph as nodes (locations) and edges (exits with direction, destination, and condition information), plus author documentation per location where written. Use this to understand spatial navigation between locations."
     )]
     fn get_exit_graph(&self) -> String {
         queries::get_exit_graph(&self.data).to_string()
@@ -87,7 +111,7 @@ impl UrdMcpService {
 
     #[tool(
         name = "get_dialogue_graph",
-        description = "Returns the dialogue structure graph: sections (dialogue nodes), jumps between sections, and choices within sections including their labels, conditions, and effects."
+        description = "Returns the dialogue structure graph: sections (dialogue nodes), jumps between sections, and choices within sections including their labels, conditions, and effects, plus author documentation per section where written."
     )]
     fn get_dialogue_graph(&self) -> String {
         queries::get_dialogue_graph(&self.data).to_string()
@@ -95,7 +119,7 @@ impl UrdMcpService {
 
     #[tool(
         name = "get_entity_details",
-        description = "Returns detailed information about a specific entity: its type, container location, and all properties with types, defaults, and constraints. Entity IDs start with '@'."
+        description = "Returns detailed information about a specific entity: its type, container location, and all properties with types, defaults, and constraints, and author documentation for the entity and its type where written. Entity IDs start with '@'."
     )]
     fn get_entity_details(
         &self,
@@ -153,6 +177,45 @@ impl UrdMcpService {
         )
         .to_string()
     }
+
+    #[tool(
+        name = "can_reach_value",
+        description = "Answers whether an entity property can ever hold a target value. Classifies the value as directly settable, possibly reachable via arithmetic, or unreachable, with the effect sites, their owning choices or rules, and their guarding conditions as evidence. A static over-approximation: guards are not evaluated."
+    )]
+    fn can_reach_value(
+        &self,
+        Parameters(params): Parameters<ReachValueParams>,
+    ) -> String {
+        queries::can_reach_value(&self.data, &params.entity_id, &params.property, &params.value)
+            .to_string()
+    }
+
+    #[tool(
+        name = "diff_world",
+        description = "Compares a saved .urd.snapshot.json (by path, or inline JSON) with the loaded world and returns the semantic change report: entities, locations, exits, sections, choices, rules, property dependencies and reachability added, removed or changed since the snapshot, plus a one-line summary. Never writes snapshots."
+    )]
+    fn diff_world(
+        &self,
+        Parameters(params): Parameters<DiffParams>,
+    ) -> String {
+        queries::diff_world(
+            &self.data,
+            params.snapshot_path.as_deref(),
+            params.snapshot_json.as_deref(),
+        )
+        .to_string()
+    }
+
+    #[tool(
+        name = "explain_choice",
+        description = "Explains why a dialogue choice is offered or hidden: each condition with the property's starting value and the effects that write it (marking those that satisfy it), the jumps into its section, whether its location is reachable from the start, and whether it is sticky or offered once. Includes a one-paragraph summary. Static: guards on the writes are not evaluated."
+    )]
+    fn explain_choice(
+        &self,
+        Parameters(params): Parameters<ChoiceParams>,
+    ) -> String {
+        queries::explain_choice(&self.data, &params.choice_id).to_string()
+    }
 }
 
 // ── ServerHandler ──
@@ -164,7 +227,8 @@ impl ServerHandler for UrdMcpService {
             instructions: Some(
                 "Read-only query interface for a compiled Urd world. \
                  Provides structural analysis tools for locations, entities, \
-                 dialogue, properties, and diagnostics."
+                 dialogue, properties, and diagnostics, and a semantic \
+                 diff against saved snapshots."
                     .to_string(),
             ),
             capabilities: ServerCapabilities::builder().enable_tools().build(),
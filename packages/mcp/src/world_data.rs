@@ -3,9 +3,13 @@
 /// Built once from a CompilationResult at startup. Immutable for the
 /// server's lifetime. All query functions operate against this struct.
 
+use std::collections::BTreeMap;
+
 use urd_compiler::diagnostics::Severity;
+use urd_compiler::diff::DiffSnapshot;
 use urd_compiler::facts::{FactSet, PropertyDependencyIndex};
-use urd_compiler::CompilationResult;
+use urd_compiler::property_groups;
+use urd_compiler::{CompilationResult, CompiledWorld};
 
 /// Serialisable diagnostic entry, flattened from the compiler's Diagnostic.
 #[derive(Debug, Clone, serde::Serialize)]
@@ -22,8 +26,9 @@ pub struct DiagnosticEntry {
 
 /// Immutable compiled world data for MCP queries.
 pub struct WorldData {
-    /// Parsed world JSON from EMIT. `None` if compilation had errors.
-    pub world_json: Option<serde_json::Value>,
+    /// The compiled world from EMIT, with property groups flattened.
+    /// `None` if compilation had errors.
+    pub world: Option<CompiledWorld>,
     /// FactSet from LINK. `None` only on PARSE or IMPORT failure.
     pub fact_set: Option<FactSet>,
     /// Property dependency analysis. `Some` whenever `fact_set` is `Some`.
@@ -32,6 +37,12 @@ pub struct WorldData {
     pub diagnostics: Vec<DiagnosticEntry>,
     /// Whether compilation produced any errors.
     pub has_errors: bool,
+    /// Author `///` documentation keyed by DefinitionIndex key
+    /// (`type:Name`, `entity:@id`, `section:id`, `location:slug`).
+    pub docs: BTreeMap<String, String>,
+    /// Diff snapshot of this compilation, compared against saved
+    /// snapshots by `diff_world`.
+    pub snapshot: DiffSnapshot,
 }
 
 impl WorldData {
@@ -39,8 +50,15 @@ impl WorldData {
     ///
     /// Moves owned data out of the result. The CompilationResult is consumed.
     pub fn from_result(result: CompilationResult) -> Self {
-        let world_json = result.world.as_ref().and_then(|json_str| {
-            serde_json::from_str::<serde_json::Value>(json_str).ok()
+        // Taken first: the snapshot reads the world, FactSet and
+        // diagnostics before they are moved out below.
+        let snapshot = DiffSnapshot::from_compilation(&result);
+
+        // Queries look properties up by their dotted names, so property
+        // groups are flattened back out of the emitted nesting.
+        let world = result.world_value.map(|mut world| {
+            property_groups::flatten_compiled(&mut world);
+            world
         });
 
         let diagnostics: Vec<DiagnosticEntry> = result
@@ -65,12 +83,25 @@ impl WorldData {
 
         let has_errors = result.diagnostics.has_errors();
 
+        let docs = result
+            .definition_index
+            .as_ref()
+            .map(|index| {
+                index
+                    .iter()
+                    .filter_map(|(key, entry)| Some((key.clone(), entry.doc.clone()?)))
+                    .collect()
+            })
+            .unwrap_or_default();
+
         Self {
-            world_json,
+            world,
             fact_set: result.fact_set,
             property_index: result.property_index,
             diagnostics,
             has_errors,
+            docs,
+            snapshot,
         }
     }
 }
@@ -8,6 +8,7 @@ use std::sync::OnceLock;
 
 use serde_json::Value;
 
+use urd_compiler::diff::{self, DiffSnapshot};
 use urd_mcp::queries;
 use urd_mcp::world_data::WorldData;
 
@@ -36,6 +37,33 @@ fn unreachable_fixture() -> &'static WorldData {
     })
 }
 
+fn key_puzzle() -> &'static WorldData {
+    static DATA: OnceLock<WorldData> = OnceLock::new();
+    DATA.get_or_init(|| {
+        let path = fixture_path("two-room-key-puzzle.urd.md");
+        let result = urd_compiler::compile(&path);
+        WorldData::from_result(result)
+    })
+}
+
+fn doc_comments() -> &'static WorldData {
+    static DATA: OnceLock<WorldData> = OnceLock::new();
+    DATA.get_or_init(|| {
+        let path = fixture_path("doc-comments.urd.md");
+        let result = urd_compiler::compile(&path);
+        WorldData::from_result(result)
+    })
+}
+
+fn property_groups() -> &'static WorldData {
+    static DATA: OnceLock<WorldData> = OnceLock::new();
+    DATA.get_or_init(|| {
+        let path = fixture_path("property-groups.urd.md");
+        let result = urd_compiler::compile(&path);
+        WorldData::from_result(result)
+    })
+}
+
 // ── Tool 1: get_world_metadata ──
 
 #[test]
@@ -139,6 +167,21 @@ fn query_entity_details_found() {
     assert!(prop_names.contains(&"mood"), "Expected mood property");
 }
 
+#[test]
+fn query_entity_details_grouped_properties() {
+    let data = property_groups();
+    let result = queries::get_entity_details(data, "@guard");
+
+    let props = result["properties"].as_array().unwrap();
+    let height = props
+        .iter()
+        .find(|p| p["name"] == "appearance.height")
+        .expect("Expected the dotted property name");
+    assert_eq!(height["type"], "integer");
+    assert_eq!(height["default"], 195, "Entity override wins over the type default");
+    assert!(props.iter().all(|p| p["name"] != "appearance"), "Groups are not properties");
+}
+
 #[test]
 fn query_entity_details_not_found() {
     let data = locked_garden();
@@ -148,6 +191,35 @@ fn query_entity_details_not_found() {
     assert_eq!(result["error"], "Entity not found");
 }
 
+#[test]
+fn query_entity_details_include_docs() {
+    let data = doc_comments();
+    let result = queries::get_entity_details(data, "@keeper");
+
+    assert_eq!(result["doc"], "The old keeper. Knows where the lens went.");
+    assert_eq!(result["type_doc"], "A keeper of the light.\nSpeaks only when spoken to.");
+
+    // Worlds without `///` comments carry no doc fields.
+    let plain = queries::get_entity_details(locked_garden(), "@warden");
+    assert!(plain.get("doc").is_none());
+    assert!(plain.get("type_doc").is_none());
+}
+
+#[test]
+fn query_graphs_include_docs() {
+    let data = doc_comments();
+    let dialogue = queries::get_dialogue_graph(data);
+    assert_eq!(dialogue["section_docs"]["doc-comments/talk"], "Opening conversation with the keeper.");
+
+    let exits = queries::get_exit_graph(data);
+    assert_eq!(
+        exits["location_docs"]["lighthouse"],
+        "The lighthouse gallery, high above the rocks.\nWind is constant here."
+    );
+
+    assert_eq!(queries::get_dialogue_graph(locked_garden())["section_docs"], serde_json::json!({}));
+}
+
 // ── Tool 5: get_property_dependencies ──
 
 #[test]
@@ -166,6 +238,16 @@ fn query_property_deps_trust() {
     assert_eq!(result["write_only"], false);
 }
 
+#[test]
+fn query_property_deps_grouped_property() {
+    let data = property_groups();
+    let result = queries::get_property_dependencies(data, "Guard", "stats.strength");
+
+    assert_eq!(result["property_key"], "Guard.stats.strength");
+    assert_eq!(result["read_count"], 1);
+    assert_eq!(result["write_count"], 1);
+}
+
 #[test]
 fn query_property_deps_not_found() {
     let data = locked_garden();
@@ -299,13 +381,180 @@ fn query_diagnostics_filter_file() {
     }
 }
 
+// ── Tool 9: can_reach_value ──
+
+#[test]
+fn query_can_reach_value_unlockable_door() {
+    let data = key_puzzle();
+    let result = queries::can_reach_value(data, "@cell_door", "locked", "false");
+
+    assert_eq!(result["schema_version"], "1");
+    assert_eq!(result["entity_type"], "Door");
+    assert_eq!(result["classification"], "directly_settable");
+    assert_eq!(result["initially_holds"], false);
+
+    let evidence = result["evidence"].as_array().unwrap();
+    assert_eq!(evidence.len(), 1);
+    assert_eq!(evidence[0]["kind"], "sets_target");
+    assert_eq!(evidence[0]["operation"], "locked = false");
+    assert_eq!(evidence[0]["owner"]["kind"], "choice");
+    assert_eq!(evidence[0]["owner"]["label"], "Use key");
+    assert!(result["note"].as_str().unwrap().contains("over-approximation"));
+}
+
+#[test]
+fn query_can_reach_value_enum_never_assigned() {
+    let data = locked_garden();
+    let result = queries::can_reach_value(data, "@ghost", "mood", "neutral");
+
+    assert_eq!(result["classification"], "unreachable");
+    assert_eq!(result["initial_value"], "wary");
+    assert_eq!(result["initially_holds"], false);
+
+    // The only mood write sets 'friendly', listed as evidence.
+    let evidence = result["evidence"].as_array().unwrap();
+    assert_eq!(evidence.len(), 1);
+    assert_eq!(evidence[0]["kind"], "sets_other");
+    assert_eq!(evidence[0]["operation"], "mood = friendly");
+}
+
+#[test]
+fn query_can_reach_value_integer_via_increments() {
+    let data = locked_garden();
+    let result = queries::can_reach_value(data, "@warden", "trust", "10");
+
+    assert_eq!(result["classification"], "possibly_reachable_via_arithmetic");
+    let evidence = result["evidence"].as_array().unwrap();
+    assert!(!evidence.is_empty());
+    assert!(evidence.iter().all(|e| e["kind"] == "arithmetic"));
+
+    // Every trust write in the locked garden sits on a choice.
+    assert!(evidence.iter().all(|e| e["owner"]["kind"] == "choice"));
+}
+
+#[test]
+fn query_can_reach_value_undeclared_variant() {
+    let data = locked_garden();
+    let result = queries::can_reach_value(data, "@warden", "mood", "furious");
+
+    assert_eq!(result["classification"], "unreachable");
+    assert!(result["reason"].as_str().unwrap().contains("not a valid enum value"));
+}
+
+#[test]
+fn query_can_reach_value_not_found() {
+    let data = locked_garden();
+    assert_eq!(
+        queries::can_reach_value(data, "@nobody", "mood", "wary")["error"],
+        "Entity not found"
+    );
+    assert_eq!(
+        queries::can_reach_value(data, "@warden", "nonexistent", "1")["error"],
+        "Property not found"
+    );
+}
+
+// ── Tool 10: diff_world ──
+
+/// Snapshot JSON of a fresh locked-garden compile.
+fn locked_garden_snapshot() -> String {
+    let result = urd_compiler::compile(&fixture_path("locked-garden.urd.md"));
+    let name = diff::world_name(&result);
+    DiffSnapshot::from_compilation(&result).to_json(&name).to_string()
+}
+
+#[test]
+fn query_diff_world_unchanged() {
+    let result = queries::diff_world(locked_garden(), None, Some(&locked_garden_snapshot()));
+    assert_eq!(result["summary"], "No changes detected.");
+    assert_eq!(result["report"]["summary"]["total_changes"], 0);
+}
+
+#[test]
+fn query_diff_world_location_removed_from_snapshot() {
+    let mut snapshot: Value = serde_json::from_str(&locked_garden_snapshot()).unwrap();
+    let removed = snapshot["locations"]
+        .as_object_mut()
+        .unwrap()
+        .remove("the-walled-garden");
+    assert!(removed.is_some());
+
+    let result = queries::diff_world(locked_garden(), None, Some(&snapshot.to_string()));
+    assert!(result["error"].is_null(), "unexpected error: {}", result["error"]);
+    let changes = result["report"]["changes"].as_array().unwrap();
+    assert_eq!(changes.len(), 1, "changes: {:#?}", changes);
+    assert_eq!(changes[0]["category"], "location");
+    assert_eq!(changes[0]["kind"], "added");
+    assert_eq!(changes[0]["id"], "the-walled-garden");
+    assert_eq!(result["summary"], "1 changes: 1 location");
+}
+
+#[test]
+fn query_diff_world_reads_snapshot_path() {
+    let dir = std::env::temp_dir().join(format!("urd-mcp-diff-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("locked-garden.urd.snapshot.json");
+    std::fs::write(&path, locked_garden_snapshot()).unwrap();
+
+    let result = queries::diff_world(locked_garden(), Some(path.to_str().unwrap()), None);
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(result["summary"], "No changes detected.");
+}
+
+#[test]
+fn query_diff_world_unsupported_version() {
+    let mut snapshot: Value = serde_json::from_str(&locked_garden_snapshot()).unwrap();
+    snapshot["urd_snapshot"] = Value::from("2");
+
+    let result = queries::diff_world(locked_garden(), None, Some(&snapshot.to_string()));
+    assert_eq!(result["schema_version"], "1");
+    assert!(result["error"].as_str().unwrap().contains("Unsupported snapshot version"));
+    assert!(result["report"].is_null());
+}
+
+#[test]
+fn query_diff_world_unreadable_path() {
+    let result = queries::diff_world(locked_garden(), Some("/nonexistent/world.urd.snapshot.json"), None);
+    assert!(result["error"].as_str().unwrap().starts_with("Cannot read snapshot"));
+    assert_eq!(result["snapshot_path"], "/nonexistent/world.urd.snapshot.json");
+}
+
+#[test]
+fn query_diff_world_needs_one_source() {
+    let neither = queries::diff_world(locked_garden(), None, None);
+    assert!(neither["error"].is_string());
+    let both = queries::diff_world(locked_garden(), Some("a.json"), Some("{}"));
+    assert!(both["error"].is_string());
+}
+
+// ── Tool 11: explain_choice ──
+
+#[test]
+fn query_explain_choice_lists_write_sites() {
+    let result = queries::explain_choice(locked_garden(), "locked-garden/greet/ask-about-the-garden");
+    let explanation = &result["explanation"];
+    assert_eq!(explanation["reachability"], "start");
+    let condition = &explanation["conditions"][0];
+    assert_eq!(condition["property"], "trust");
+    assert_eq!(condition["starting_value"], "0");
+    assert_eq!(condition["writes"].as_array().unwrap().len(), 3);
+    assert!(explanation["summary"].as_str().unwrap().contains("Needs @warden.trust >= 3"));
+}
+
+#[test]
+fn query_explain_choice_not_found() {
+    let result = queries::explain_choice(locked_garden(), "locked-garden/greet/sing");
+    assert_eq!(result["error"], "Unknown choice 'locked-garden/greet/sing'.");
+    assert_eq!(result["choice_id"], "locked-garden/greet/sing");
+}
+
 // ── Cross-cutting: schema_version present ──
 
 #[test]
 fn query_schema_version_present() {
     let data = locked_garden();
 
-    // All 8 tool responses must include schema_version
+    // All 11 tool responses must include schema_version
     let results: Vec<Value> = vec![
         queries::get_world_metadata(data),
         queries::get_exit_graph(data),
@@ -315,6 +564,9 @@ fn query_schema_version_present() {
         queries::get_reachable_locations(data, "gatehouse"),
         queries::get_choice_conditions(data, "locked-garden/greet"),
         queries::get_diagnostics(data, None, None),
+        queries::can_reach_value(data, "@warden", "trust", "3"),
+        queries::diff_world(data, None, Some(&locked_garden_snapshot())),
+        queries::explain_choice(data, "locked-garden/greet/ask-about-the-garden"),
     ];
 
     for (i, result) in results.iter().enumerate() {
@@ -332,7 +584,7 @@ fn query_schema_version_present() {
 fn mcp_import_boundary() {
     // The MCP crate should not import compiler internals.
     // Only urd_compiler::{compile, CompilationResult}, urd_compiler::facts::*,
-    // and urd_compiler::diagnostics::Severity are allowed.
+    // urd_compiler::analyze, and urd_compiler::diagnostics::Severity are allowed.
     let src_dir = format!("{}/../mcp/src", env!("CARGO_MANIFEST_DIR"));
     let prohibited = [
         "urd_compiler::ast",
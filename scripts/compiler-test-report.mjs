@@ -32,20 +32,42 @@ const BINARY_PHASE_MAP = {
   'link_tests': 'link',
   'validate_tests': 'validate',
   'emit_tests': 'emit',
+  'streaming_tests': 'emit',
   'facts_tests': 'facts',
   'analyze_tests': 'analyze',
   'e2e_tests': 'e2e',
   'diff_tests': 'diff',
   'definition_index_tests': 'definition_index',
+  'references_tests': 'references',
   'integration_tests': 'integration',
   'compilation_result_tests': 'compilation_result',
+  'stats_tests': 'stats',
+  'strings_tests': 'strings',
+  'split_tests': 'split',
+  'cache_tests': 'cache',
+  'catalog_tests': 'catalog',
+  'schema_tests': 'schema',
+  'observer_tests': 'observer',
+  'watch_tests': 'watch',
+  'numeric_ids_tests': 'numeric_ids',
+  'project_tests': 'project',
+  'test_support_tests': 'test_support',
+  'fix_tests': 'fix',
+  'cli_tests': 'cli',
+  'symbols_tests': 'symbols',
+  'explain_tests': 'explain',
+  'text_lint_tests': 'text_lint',
+  'anonymize_tests': 'anonymize',
+  'docgen_tests': 'docgen',
+  'fragment_tests': 'fragment',
+  'flowgraph_tests': 'flowgraph',
 };
 
 /** Diagnostic codes owned by each phase (static metadata). */
 const DIAGNOSTIC_CODES = {
-  parse: expandRange('URD', 100, 112),
+  parse: expandRange('URD', 100, 115),
   import: expandRange('URD', 201, 211),
-  link: expandRange('URD', 301, 314),
+  link: expandRange('URD', 301, 328),
   validate: [
     ...expandRange('URD', 401, 402),
     ...expandRange('URD', 404, 420),
@@ -53,12 +75,33 @@ const DIAGNOSTIC_CODES = {
   ],
   emit: [],
   facts: [],
-  analyze: expandRange('URD', 601, 605),
+  analyze: expandRange('URD', 601, 614),
   e2e: [],
   diff: [],
   definition_index: [],
+  references: [],
   integration: [],
   compilation_result: [],
+  stats: [],
+  strings: [],
+  split: [],
+  cache: [],
+  catalog: [],
+  schema: [],
+  observer: [],
+  watch: [],
+  numeric_ids: [],
+  project: [],
+  test_support: [],
+  fix: [],
+  cli: [],
+  symbols: [],
+  explain: [],
+  text_lint: expandRange('URD', 701, 703),
+  anonymize: [],
+  docgen: [],
+  fragment: [],
+  flowgraph: [],
   scaffolding: [],
 };
 
@@ -74,8 +117,29 @@ const DIAGNOSTIC_RANGES = {
   e2e: null,
   diff: null,
   definition_index: null,
+  references: null,
   integration: null,
   compilation_result: null,
+  stats: null,
+  strings: null,
+  split: null,
+  cache: null,
+  catalog: null,
+  schema: null,
+  observer: null,
+  watch: null,
+  numeric_ids: null,
+  project: null,
+  test_support: null,
+  fix: null,
+  cli: null,
+  symbols: null,
+  explain: null,
+  text_lint: '700-799',
+  anonymize: null,
+  docgen: null,
+  fragment: null,
+  flowgraph: null,
   scaffolding: null,
 };
 
@@ -90,7 +154,7 @@ const COMPLIANCE = [
 ];
 
 /** Ordered list of phases for output. */
-const PHASE_ORDER = ['parse', 'import', 'link', 'validate', 'emit', 'facts', 'analyze', 'e2e', 'diff', 'definition_index', 'integration', 'compilation_result', 'scaffolding'];
+const PHASE_ORDER = ['parse', 'import', 'link', 'validate', 'emit', 'facts', 'analyze', 'e2e', 'diff', 'definition_index', 'references', 'integration', 'compilation_result', 'stats', 'strings', 'split', 'cache', 'catalog', 'schema', 'observer', 'watch', 'numeric_ids', 'project', 'test_support', 'fix', 'cli', 'symbols', 'explain', 'text_lint', 'anonymize', 'docgen', 'fragment', 'flowgraph', 'scaffolding'];
 
 // ---------------------------------------------------------------------------
 // Helpers
@@ -6,6 +6,7 @@ import svelte from "@astrojs/svelte";
 import sitemap from "@astrojs/sitemap";
 import tailwindcss from "@tailwindcss/vite";
 import rehypeSlug from "rehype-slug";
+import rehypeDiagnosticAnchors from "./src/lib/rehype-diagnostic-anchors.mjs";
 
 // Read compiler version from Cargo.toml for WASM cache-busting.
 const cargoToml = readFileSync("../../packages/compiler/Cargo.toml", "utf-8");
@@ -15,7 +16,7 @@ export default defineConfig({
   site: "https://urd.dev",
   integrations: [svelte(), sitemap()],
   markdown: {
-    rehypePlugins: [rehypeSlug],
+    rehypePlugins: [rehypeSlug, rehypeDiagnosticAnchors],
   },
   vite: {
     plugins: [tailwindcss()],
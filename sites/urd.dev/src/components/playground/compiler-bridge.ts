@@ -160,6 +160,7 @@ export interface Diagnostic {
     end_line: number;
     end_col: number;
   };
+  suggestion?: string;
 }
 
 // --- Module state ---
@@ -0,0 +1,26 @@
+/**
+ * Give each diagnostic code cell in a reference table an anchor, so
+ * `diagnostic-codes#urd433` lands on the URD433 row. The compiler's code
+ * catalog links to these anchors; the slug is the code in lower case.
+ * A code listed twice on one page is anchored at its first row.
+ */
+const CODE = /^URD\d{3}$/;
+
+export default function rehypeDiagnosticAnchors() {
+  return (tree) => visit(tree, new Set());
+}
+
+function visit(node, seen) {
+  if (node.type === "element" && node.tagName === "tr") {
+    const cell = node.children.find((c) => c.type === "element" && c.tagName === "td");
+    const text = cell?.children.length === 1 && cell.children[0].type === "text" ? cell.children[0].value.trim() : "";
+    if (CODE.test(text) && !seen.has(text)) {
+      seen.add(text);
+      cell.properties = { ...cell.properties, id: text.toLowerCase() };
+    }
+    return;
+  }
+  for (const child of node.children ?? []) {
+    visit(child, seen);
+  }
+}